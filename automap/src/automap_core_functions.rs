@@ -87,6 +87,11 @@ pub fn change_handler(change: AutomapChange) {
     match change {
         AutomapChange::NewIp(ip_addr) => info!("Notified of public-IP change to {:?}", ip_addr),
         AutomapChange::Error(e) => error!("Notified of error: {:?}", e),
+        AutomapChange::LifetimeReduced { old, new } => warn!(
+            "Notified of mapping lifetime reduction from {}s to {}s",
+            old.as_secs(),
+            new.as_secs()
+        ),
     }
 }
 
@@ -0,0 +1,500 @@
+// Copyright (c) 2019-2021, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::automap_core_functions::change_handler;
+use crate::comm_layer::igdp::IgdpTransactor;
+use crate::comm_layer::pcp::PcpTransactor;
+use crate::comm_layer::pcp_pmp_common::RouterTiming;
+use crate::comm_layer::pmp::PmpTransactor;
+use crate::comm_layer::{AutomapError, Transactor};
+use masq_lib::utils::AutomapProtocol;
+use std::fmt::{Display, Formatter};
+use std::net::IpAddr;
+
+pub const DIAGNOSTIC_MAPPING_LIFETIME_SECONDS: u32 = 5;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiagnosticStep {
+    pub description: String,
+    pub outcome: Result<String, AutomapError>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProtocolReport {
+    pub protocol: AutomapProtocol,
+    pub steps: Vec<DiagnosticStep>,
+}
+
+impl ProtocolReport {
+    pub fn is_operational(&self) -> bool {
+        !self.steps.is_empty() && self.steps.iter().all(|step| step.outcome.is_ok())
+    }
+
+    fn record<T>(
+        &mut self,
+        description: String,
+        result: Result<T, AutomapError>,
+        summarize: impl FnOnce(&T) -> String,
+    ) -> Option<T> {
+        let (outcome, value_opt) = match result {
+            Ok(value) => (Ok(summarize(&value)), Some(value)),
+            Err(e) => (Err(e), None),
+        };
+        self.steps.push(DiagnosticStep {
+            description,
+            outcome,
+        });
+        value_opt
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiagnosticsReport {
+    pub hole_port: u16,
+    pub protocol_reports: Vec<ProtocolReport>,
+}
+
+impl DiagnosticsReport {
+    pub fn is_operational(&self) -> bool {
+        self.protocol_reports
+            .iter()
+            .any(|report| report.is_operational())
+    }
+}
+
+impl Display for DiagnosticsReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Automap diagnostics for port {}", self.hole_port)?;
+        for report in &self.protocol_reports {
+            writeln!(f)?;
+            writeln!(f, "=============={}===============", report.protocol)?;
+            for (index, step) in report.steps.iter().enumerate() {
+                let (verdict, detail) = match &step.outcome {
+                    Ok(summary) => ("OK", summary.clone()),
+                    Err(e) => ("FAILED", format!("{:?}", e)),
+                };
+                writeln!(
+                    f,
+                    "{}. {}: {} ({})",
+                    index + 1,
+                    step.description,
+                    verdict,
+                    detail
+                )?;
+            }
+            writeln!(
+                f,
+                "Verdict: {}",
+                if report.is_operational() {
+                    "operational"
+                } else {
+                    "not operational"
+                }
+            )?;
+        }
+        writeln!(f)?;
+        write!(
+            f,
+            "Overall: {}",
+            match self
+                .protocol_reports
+                .iter()
+                .find(|report| report.is_operational())
+            {
+                Some(report) => format!("port mapping works with {}", report.protocol),
+                None => "no protocol could map a port through this router".to_string(),
+            }
+        )
+    }
+}
+
+pub fn transactor_for(protocol: AutomapProtocol) -> Box<dyn Transactor> {
+    match protocol {
+        AutomapProtocol::Pcp => Box::new(PcpTransactor::default()),
+        AutomapProtocol::Pmp => Box::new(PmpTransactor::default()),
+        AutomapProtocol::Igdp => Box::new(IgdpTransactor::default()),
+    }
+}
+
+pub fn diagnose(transactors: Vec<Box<dyn Transactor>>, hole_port: u16) -> DiagnosticsReport {
+    DiagnosticsReport {
+        hole_port,
+        protocol_reports: transactors
+            .into_iter()
+            .map(|mut transactor| diagnose_protocol(transactor.as_mut(), hole_port))
+            .collect(),
+    }
+}
+
+// Walks one protocol through what AutomapControl would do with it: discovery, housekeeping,
+// public IP, a short-lived mapping that's removed right away, and the read timeout the protocol
+// has settled on for the router. Later steps are skipped once there's no router to talk to or the
+// housekeeping thread won't start.
+fn diagnose_protocol(transactor: &mut dyn Transactor, hole_port: u16) -> ProtocolReport {
+    let mut report = ProtocolReport {
+        protocol: transactor.protocol(),
+        steps: vec![],
+    };
+    let router_ip = match report.record(
+        "Looking for routers on the subnet".to_string(),
+        find_router(transactor),
+        |router_ip| format!("found router at {}", router_ip),
+    ) {
+        Some(router_ip) => router_ip,
+        None => return report,
+    };
+    if report
+        .record(
+            format!("Starting housekeeping thread for router at {}", router_ip),
+            transactor.start_housekeeping_thread(Box::new(change_handler), router_ip),
+            |_| "started".to_string(),
+        )
+        .is_none()
+    {
+        return report;
+    }
+    report.record(
+        format!("Seeking public IP address from router at {}", router_ip),
+        transactor.get_public_ip(router_ip),
+        |public_ip| format!("public IP is {}", public_ip),
+    );
+    let mapped_opt = report.record(
+        format!("Adding a mapping for port {}", hole_port),
+        add_mapping(transactor, router_ip, hole_port),
+        |lifetime| match lifetime {
+            0 => "router accepts only permanent mappings; added one".to_string(),
+            lifetime => format!("router granted a {}-second lease", lifetime),
+        },
+    );
+    if mapped_opt.is_some() {
+        report.record(
+            format!("Deleting the mapping for port {}", hole_port),
+            transactor.delete_mapping(router_ip, hole_port),
+            |_| "deleted".to_string(),
+        );
+    }
+    if let Some(router_timing) = transactor.router_timing_opt(router_ip) {
+        report.record(
+            format!("Calibrating read timeout for router at {}", router_ip),
+            Ok(router_timing),
+            describe_router_timing,
+        );
+    }
+    report.record(
+        "Stopping housekeeping thread".to_string(),
+        transactor.stop_housekeeping_thread(),
+        |_| "stopped".to_string(),
+    );
+    report
+}
+
+fn find_router(transactor: &dyn Transactor) -> Result<IpAddr, AutomapError> {
+    match transactor.find_routers()?.first() {
+        Some(router_ip) => Ok(*router_ip),
+        None => Err(AutomapError::FindRouterError(
+            "No routers found on the subnet".to_string(),
+        )),
+    }
+}
+
+fn describe_router_timing(router_timing: &RouterTiming) -> String {
+    match router_timing.smoothed_rtt_opt {
+        Some(smoothed_rtt) => format!(
+            "waits {}ms; router answers in {}ms on average",
+            router_timing.read_timeout.as_millis(),
+            smoothed_rtt.as_millis()
+        ),
+        None => format!(
+            "waits {}ms; router hasn't answered yet",
+            router_timing.read_timeout.as_millis()
+        ),
+    }
+}
+
+fn add_mapping(
+    transactor: &dyn Transactor,
+    router_ip: IpAddr,
+    hole_port: u16,
+) -> Result<u32, AutomapError> {
+    match transactor.add_mapping(router_ip, hole_port, DIAGNOSTIC_MAPPING_LIFETIME_SECONDS) {
+        Err(AutomapError::PermanentLeasesOnly) => transactor
+            .add_permanent_mapping(router_ip, hole_port)
+            .map(|_| 0),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks::{TransactorMock, PUBLIC_IP, ROUTER_IP};
+    use crossbeam_channel::unbounded;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    fn healthy_transactor(protocol: AutomapProtocol) -> TransactorMock {
+        TransactorMock::new(protocol)
+            .find_routers_result(Ok(vec![*ROUTER_IP]))
+            .start_housekeeping_thread_result(Ok(unbounded().0))
+            .get_public_ip_result(Ok(*PUBLIC_IP))
+            .add_mapping_result(Ok(300))
+            .delete_mapping_result(Ok(()))
+            .router_timing_opt_result(Some(RouterTiming {
+                smoothed_rtt_opt: Some(Duration::from_millis(3)),
+                read_timeout: Duration::from_millis(50),
+            }))
+            .stop_housekeeping_thread_result(Ok(Box::new(|_| ())))
+    }
+
+    fn step(description: &str, outcome: Result<&str, AutomapError>) -> DiagnosticStep {
+        DiagnosticStep {
+            description: description.to_string(),
+            outcome: outcome.map(|summary| summary.to_string()),
+        }
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(DIAGNOSTIC_MAPPING_LIFETIME_SECONDS, 5);
+    }
+
+    #[test]
+    fn transactor_for_makes_the_right_transactors() {
+        assert_eq!(
+            transactor_for(AutomapProtocol::Pcp).protocol(),
+            AutomapProtocol::Pcp
+        );
+        assert_eq!(
+            transactor_for(AutomapProtocol::Pmp).protocol(),
+            AutomapProtocol::Pmp
+        );
+        assert_eq!(
+            transactor_for(AutomapProtocol::Igdp).protocol(),
+            AutomapProtocol::Igdp
+        );
+    }
+
+    #[test]
+    fn diagnose_walks_a_healthy_protocol_through_every_step() {
+        let add_mapping_params_arc = Arc::new(Mutex::new(vec![]));
+        let delete_mapping_params_arc = Arc::new(Mutex::new(vec![]));
+        let router_timing_opt_params_arc = Arc::new(Mutex::new(vec![]));
+        let stop_housekeeping_thread_params_arc = Arc::new(Mutex::new(vec![]));
+        let transactor = healthy_transactor(AutomapProtocol::Pcp)
+            .add_mapping_params(&add_mapping_params_arc)
+            .delete_mapping_params(&delete_mapping_params_arc)
+            .router_timing_opt_params(&router_timing_opt_params_arc)
+            .stop_housekeeping_thread_params(&stop_housekeeping_thread_params_arc);
+
+        let result = diagnose(vec![Box::new(transactor)], 1234);
+
+        assert_eq!(
+            result,
+            DiagnosticsReport {
+                hole_port: 1234,
+                protocol_reports: vec![ProtocolReport {
+                    protocol: AutomapProtocol::Pcp,
+                    steps: vec![
+                        step(
+                            "Looking for routers on the subnet",
+                            Ok("found router at 1.2.3.4")
+                        ),
+                        step(
+                            "Starting housekeeping thread for router at 1.2.3.4",
+                            Ok("started")
+                        ),
+                        step(
+                            "Seeking public IP address from router at 1.2.3.4",
+                            Ok("public IP is 2.3.4.5")
+                        ),
+                        step(
+                            "Adding a mapping for port 1234",
+                            Ok("router granted a 300-second lease")
+                        ),
+                        step("Deleting the mapping for port 1234", Ok("deleted")),
+                        step(
+                            "Calibrating read timeout for router at 1.2.3.4",
+                            Ok("waits 50ms; router answers in 3ms on average")
+                        ),
+                        step("Stopping housekeeping thread", Ok("stopped")),
+                    ]
+                }]
+            }
+        );
+        assert_eq!(result.is_operational(), true);
+        assert_eq!(
+            *add_mapping_params_arc.lock().unwrap(),
+            vec![(*ROUTER_IP, 1234, DIAGNOSTIC_MAPPING_LIFETIME_SECONDS)]
+        );
+        assert_eq!(
+            *delete_mapping_params_arc.lock().unwrap(),
+            vec![(*ROUTER_IP, 1234)]
+        );
+        assert_eq!(
+            *router_timing_opt_params_arc.lock().unwrap(),
+            vec![*ROUTER_IP]
+        );
+        assert_eq!(stop_housekeeping_thread_params_arc.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn diagnose_falls_back_to_a_permanent_mapping_when_the_router_insists() {
+        let add_permanent_mapping_params_arc = Arc::new(Mutex::new(vec![]));
+        let transactor = TransactorMock::new(AutomapProtocol::Igdp)
+            .find_routers_result(Ok(vec![*ROUTER_IP]))
+            .start_housekeeping_thread_result(Ok(unbounded().0))
+            .get_public_ip_result(Ok(*PUBLIC_IP))
+            .add_mapping_result(Err(AutomapError::PermanentLeasesOnly))
+            .add_permanent_mapping_params(&add_permanent_mapping_params_arc)
+            .add_permanent_mapping_result(Ok(0))
+            .delete_mapping_result(Ok(()))
+            .router_timing_opt_result(None)
+            .stop_housekeeping_thread_result(Ok(Box::new(|_| ())));
+
+        let result = diagnose(vec![Box::new(transactor)], 1234);
+
+        assert_eq!(
+            result.protocol_reports[0].steps[3],
+            step(
+                "Adding a mapping for port 1234",
+                Ok("router accepts only permanent mappings; added one")
+            )
+        );
+        assert_eq!(result.is_operational(), true);
+        assert_eq!(
+            *add_permanent_mapping_params_arc.lock().unwrap(),
+            vec![(*ROUTER_IP, 1234)]
+        );
+    }
+
+    #[test]
+    fn diagnose_stops_early_when_no_router_is_found() {
+        let transactor = TransactorMock::new(AutomapProtocol::Pmp).find_routers_result(Ok(vec![]));
+
+        let result = diagnose(vec![Box::new(transactor)], 1234);
+
+        assert_eq!(
+            result.protocol_reports,
+            vec![ProtocolReport {
+                protocol: AutomapProtocol::Pmp,
+                steps: vec![step(
+                    "Looking for routers on the subnet",
+                    Err(AutomapError::FindRouterError(
+                        "No routers found on the subnet".to_string()
+                    ))
+                )]
+            }]
+        );
+        assert_eq!(result.is_operational(), false);
+    }
+
+    #[test]
+    fn diagnose_stops_early_when_housekeeping_wont_start() {
+        let transactor = TransactorMock::new(AutomapProtocol::Pmp)
+            .find_routers_result(Ok(vec![*ROUTER_IP]))
+            .start_housekeeping_thread_result(Err(AutomapError::HousekeeperAlreadyRunning));
+
+        let result = diagnose(vec![Box::new(transactor)], 1234);
+
+        assert_eq!(
+            result.protocol_reports[0].steps,
+            vec![
+                step(
+                    "Looking for routers on the subnet",
+                    Ok("found router at 1.2.3.4")
+                ),
+                step(
+                    "Starting housekeeping thread for router at 1.2.3.4",
+                    Err(AutomapError::HousekeeperAlreadyRunning)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn diagnose_skips_deletion_but_still_stops_housekeeping_when_mapping_fails() {
+        let delete_mapping_params_arc = Arc::new(Mutex::new(vec![]));
+        let transactor = TransactorMock::new(AutomapProtocol::Pcp)
+            .find_routers_result(Ok(vec![*ROUTER_IP]))
+            .start_housekeeping_thread_result(Ok(unbounded().0))
+            .get_public_ip_result(Err(AutomapError::GetPublicIpError("booga".to_string())))
+            .add_mapping_result(Err(AutomapError::TemporaryMappingError(
+                "NotAuthorized".to_string(),
+            )))
+            .delete_mapping_params(&delete_mapping_params_arc)
+            .router_timing_opt_result(Some(RouterTiming::default()))
+            .stop_housekeeping_thread_result(Ok(Box::new(|_| ())));
+
+        let result = diagnose(vec![Box::new(transactor)], 1234);
+
+        assert_eq!(
+            result.protocol_reports[0].steps[2..],
+            vec![
+                step(
+                    "Seeking public IP address from router at 1.2.3.4",
+                    Err(AutomapError::GetPublicIpError("booga".to_string()))
+                ),
+                step(
+                    "Adding a mapping for port 1234",
+                    Err(AutomapError::TemporaryMappingError(
+                        "NotAuthorized".to_string()
+                    ))
+                ),
+                step(
+                    "Calibrating read timeout for router at 1.2.3.4",
+                    Ok("waits 250ms; router hasn't answered yet")
+                ),
+                step("Stopping housekeeping thread", Ok("stopped")),
+            ]
+        );
+        assert_eq!(result.is_operational(), false);
+        assert_eq!(delete_mapping_params_arc.lock().unwrap().is_empty(), true);
+    }
+
+    #[test]
+    fn report_displays_every_protocol_and_an_overall_verdict() {
+        let broken = TransactorMock::new(AutomapProtocol::Pcp)
+            .find_routers_result(Err(AutomapError::FindRouterError("no gateway".to_string())));
+        let healthy = healthy_transactor(AutomapProtocol::Pmp);
+        let report = diagnose(vec![Box::new(broken), Box::new(healthy)], 1234);
+
+        let result = report.to_string();
+
+        assert_eq!(
+            result,
+            "Automap diagnostics for port 1234\n\
+             \n\
+             ==============PCP===============\n\
+             1. Looking for routers on the subnet: FAILED (FindRouterError(\"no gateway\"))\n\
+             Verdict: not operational\n\
+             \n\
+             ==============PMP===============\n\
+             1. Looking for routers on the subnet: OK (found router at 1.2.3.4)\n\
+             2. Starting housekeeping thread for router at 1.2.3.4: OK (started)\n\
+             3. Seeking public IP address from router at 1.2.3.4: OK (public IP is 2.3.4.5)\n\
+             4. Adding a mapping for port 1234: OK (router granted a 300-second lease)\n\
+             5. Deleting the mapping for port 1234: OK (deleted)\n\
+             6. Calibrating read timeout for router at 1.2.3.4: OK (waits 50ms; router answers in 3ms on average)\n\
+             7. Stopping housekeeping thread: OK (stopped)\n\
+             Verdict: operational\n\
+             \n\
+             Overall: port mapping works with PMP"
+        );
+    }
+
+    #[test]
+    fn report_admits_when_nothing_works() {
+        let report = DiagnosticsReport {
+            hole_port: 1234,
+            protocol_reports: vec![],
+        };
+
+        let result = report.to_string();
+
+        assert_eq!(
+            result,
+            "Automap diagnostics for port 1234\n\
+             \n\
+             Overall: no protocol could map a port through this router"
+        );
+    }
+}
@@ -1,6 +1,8 @@
 // Copyright (c) 2019-2021, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
-use crate::comm_layer::pcp_pmp_common::MappingConfig;
+use crate::comm_layer::pcp_pmp_common::{
+    HousekeepingStats, MappingConfig, MappingConfigs, RouterTiming,
+};
 use crate::comm_layer::{
     AutomapError, HousekeepingThreadCommand, LocalIpFinder, LocalIpFinderReal, Transactor,
 };
@@ -22,7 +24,7 @@ use std::ops::Add;
 use std::sync::{Arc, Mutex, MutexGuard};
 use std::thread;
 use std::thread::JoinHandle;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 pub const HOUSEKEEPING_THREAD_LOOP_DELAY_MS: u64 = 100;
 pub const PUBLIC_IP_POLL_DELAY_SECONDS: u64 = 60;
@@ -114,6 +116,7 @@ struct IgdpTransactorInner {
     housekeeping_commander_opt: Option<Sender<HousekeepingThreadCommand>>,
     public_ip_opt: Option<Ipv4Addr>,
     mapping_adder: Box<dyn MappingAdder>,
+    housekeeping_stats: HousekeepingStats,
     logger: Logger,
 }
 
@@ -197,9 +200,7 @@ impl Transactor for IgdpTransactor {
                 };
                 if let Some(commander) = inner.housekeeping_commander_opt.as_ref() {
                     commander
-                        .try_send(HousekeepingThreadCommand::InitializeMappingConfig(
-                            mapping_config,
-                        ))
+                        .try_send(HousekeepingThreadCommand::AddMappingConfig(mapping_config))
                         .expect("Housekeeping thread died");
                 } else {
                     panic!("Start housekeeping thread before calling add_mapping()");
@@ -230,7 +231,14 @@ impl Transactor for IgdpTransactor {
             .as_ref()
             .remove_port(PortMappingProtocol::TCP, hole_port)
         {
-            Ok(_) => Ok(()),
+            Ok(_) => {
+                if let Some(commander) = inner.housekeeping_commander_opt.as_ref() {
+                    commander
+                        .try_send(HousekeepingThreadCommand::RemoveMappingConfig(hole_port))
+                        .expect("Housekeeping thread died");
+                }
+                Ok(())
+            }
             Err(e) => {
                 warning!(
                     inner.logger,
@@ -248,6 +256,15 @@ impl Transactor for IgdpTransactor {
         AutomapProtocol::Igdp
     }
 
+    // The IGD library manages its own timeouts
+    fn router_timing_opt(&self, _router_ip: IpAddr) -> Option<RouterTiming> {
+        None
+    }
+
+    fn housekeeping_stats(&self) -> Option<HousekeepingStats> {
+        Some(self.inner().housekeeping_stats)
+    }
+
     fn start_housekeeping_thread(
         &mut self,
         change_handler: ChangeHandler,
@@ -264,6 +281,7 @@ impl Transactor for IgdpTransactor {
                 return Err(AutomapError::HousekeeperAlreadyRunning);
             }
             inner.housekeeping_commander_opt = Some(tx.clone());
+            inner.housekeeping_stats = HousekeepingStats::default();
             debug!(
                 inner.logger,
                 "Starting housekeeping thread for router at {}", router_ip
@@ -339,6 +357,7 @@ impl IgdpTransactor {
             housekeeping_commander_opt: None,
             public_ip_opt: None,
             mapping_adder: Box::new(MappingAdderReal::new()),
+            housekeeping_stats: HousekeepingStats::default(),
             logger: Logger::new("IgdpTransactor"),
         }));
         Self {
@@ -352,6 +371,13 @@ impl IgdpTransactor {
         }
     }
 
+    // How often the housekeeping thread asks the router for the public IP and checks whether any
+    // mapping is due to be renewed
+    pub fn with_public_ip_poll_delay(mut self, public_ip_poll_delay: Duration) -> Self {
+        self.public_ip_poll_delay = public_ip_poll_delay;
+        self
+    }
+
     fn ensure_gateway(&self) -> Result<(), AutomapError> {
         let inner_arc = &self.inner_arc;
         let gateway_factory = &self.gateway_factory.as_ref();
@@ -385,38 +411,28 @@ impl IgdpTransactor {
         inner_arc: Arc<Mutex<IgdpTransactorInner>>,
         rx: Receiver<HousekeepingThreadCommand>,
     ) -> ChangeHandler {
-        let mut last_remapped = Instant::now();
-        let mut last_announcement_check = Instant::now();
-        let mut mapping_config_opt = None;
+        let mut last_poll = Instant::now();
+        let mut mapping_configs = MappingConfigs::new();
         loop {
             thread::sleep(housekeeping_thread_loop_delay);
-            if last_announcement_check
-                .add(public_ip_poll_delay)
-                .lt(&Instant::now())
-            {
-                last_announcement_check = Instant::now();
-                if !Self::thread_guts_iteration(
-                    &change_handler,
-                    &inner_arc,
-                    &mut last_remapped,
-                    &mapping_config_opt,
-                ) {
+            if last_poll.add(public_ip_poll_delay).lt(&Instant::now()) {
+                last_poll = Instant::now();
+                if !Self::thread_guts_iteration(&change_handler, &inner_arc, &mut mapping_configs) {
                     break;
                 }
             }
             match rx.try_recv() {
-                Ok(HousekeepingThreadCommand::InitializeMappingConfig(mapping_config)) => {
-                    mapping_config_opt = Some(mapping_config);
+                Ok(HousekeepingThreadCommand::AddMappingConfig(mapping_config)) => {
+                    mapping_configs.add(mapping_config);
+                }
+                Ok(HousekeepingThreadCommand::RemoveMappingConfig(hole_port)) => {
+                    mapping_configs.remove(hole_port);
                 }
                 Ok(HousekeepingThreadCommand::SetRemapIntervalMs(remap_after)) => {
-                    match mapping_config_opt.as_mut() {
-                        Some(mapping_config) => {
-                            mapping_config.remap_interval = Duration::from_millis(remap_after)
-                        }
-                        None => {
-                            panic!("Must InitializeMappingConfig before you can SetRemapIntervalMs")
-                        }
+                    if mapping_configs.is_empty() {
+                        panic!("Must AddMappingConfig before you can SetRemapIntervalMs")
                     }
+                    mapping_configs.set_remap_interval(Duration::from_millis(remap_after))
                 }
                 Ok(HousekeepingThreadCommand::Stop) => break,
                 Err(_) => continue,
@@ -428,45 +444,69 @@ impl IgdpTransactor {
     fn thread_guts_iteration(
         change_handler: &ChangeHandler,
         inner_arc: &Arc<Mutex<IgdpTransactorInner>>,
-        last_remapped: &mut Instant,
-        mapping_config_opt: &Option<MappingConfig>,
+        mapping_configs: &mut MappingConfigs,
     ) -> bool {
-        let inner = inner_arc.lock().expect("IgdpTransactor died");
-        Self::remap_if_necessary(change_handler, &*inner, last_remapped, mapping_config_opt);
+        let mut inner = inner_arc.lock().expect("IgdpTransactor died");
+        Self::check_public_ip(change_handler, &mut inner);
+        Self::remap_if_necessary(change_handler, &mut inner, mapping_configs);
         true
     }
 
+    // IGDP routers don't announce a new public IP the way PCP and PMP routers may, so we ask
+    fn check_public_ip(change_handler: &ChangeHandler, inner: &mut IgdpTransactorInner) {
+        let result = match inner.gateway_opt.as_ref() {
+            Some(gateway) => gateway.get_external_ip(),
+            None => return,
+        };
+        match result {
+            Ok(public_ip) => match inner.public_ip_opt.replace(public_ip) {
+                Some(old_public_ip) if old_public_ip != public_ip => {
+                    info!(
+                        inner.logger,
+                        "Public IP changed from {} to {}", old_public_ip, public_ip
+                    );
+                    change_handler(AutomapChange::NewIp(IpAddr::V4(public_ip)))
+                }
+                _ => (),
+            },
+            Err(e) => {
+                error!(inner.logger, "Public IP check failure: {:?}", e);
+                inner.housekeeping_stats.record_error();
+                change_handler(AutomapChange::Error(AutomapError::GetPublicIpError(
+                    format!("{:?}", e),
+                )))
+            }
+        }
+    }
+
     fn remap_if_necessary(
         change_handler: &ChangeHandler,
-        inner: &IgdpTransactorInner,
-        last_remapped: &mut Instant,
-        mapping_config_opt: &Option<MappingConfig>,
+        inner: &mut IgdpTransactorInner,
+        mapping_configs: &mut MappingConfigs,
     ) {
-        if let Some(mapping_config) = mapping_config_opt {
-            let since_last_remapped = last_remapped.elapsed();
-            if since_last_remapped.gt(&mapping_config.remap_interval) {
-                Self::remap_if_possible(change_handler, inner, mapping_config_opt);
-                *last_remapped = Instant::now();
-            }
-        }
+        mapping_configs.remap_due(|mapping_config| {
+            Self::remap_if_possible(change_handler, inner, mapping_config)
+        })
     }
 
     fn remap_if_possible(
         change_handler: &ChangeHandler,
-        inner: &IgdpTransactorInner,
-        mapping_config_opt: &Option<MappingConfig>,
+        inner: &mut IgdpTransactorInner,
+        mapping_config: &MappingConfig,
     ) {
-        if let Some(mapping_config) = mapping_config_opt {
-            if mapping_config.next_lifetime.as_secs() > 0 {
-                // if the mapping isn't permanent
-                if let Err(e) = Self::remap_port(
-                    inner.mapping_adder.as_ref(),
-                    inner.gateway_opt.as_ref().expectv("gateway_opt").as_ref(),
-                    mapping_config.hole_port,
-                    mapping_config.remap_interval,
-                    &inner.logger,
-                ) {
+        if mapping_config.next_lifetime.as_secs() > 0 {
+            // if the mapping isn't permanent
+            match Self::remap_port(
+                inner.mapping_adder.as_ref(),
+                inner.gateway_opt.as_ref().expectv("gateway_opt").as_ref(),
+                mapping_config.hole_port,
+                mapping_config.remap_interval,
+                &inner.logger,
+            ) {
+                Ok(_) => inner.housekeeping_stats.record_remap(SystemTime::now()),
+                Err(e) => {
                     error!(inner.logger, "Remapping failure: {:?}", e);
+                    inner.housekeeping_stats.record_error();
                     change_handler(AutomapChange::Error(e));
                 }
             }
@@ -590,6 +630,12 @@ mod tests {
     use std::str::FromStr;
     use std::sync::{Arc, Mutex};
 
+    fn remapped_as_of(mapping_config: MappingConfig, last_remapped: Instant) -> MappingConfigs {
+        let mut mapping_configs = MappingConfigs::new();
+        mapping_configs.add_as_of(mapping_config, last_remapped);
+        mapping_configs
+    }
+
     fn clone_get_external_ip_error(error: &GetExternalIpError) -> GetExternalIpError {
         match error {
             GetExternalIpError::ActionNotAuthorized => GetExternalIpError::ActionNotAuthorized,
@@ -794,6 +840,26 @@ mod tests {
         assert_eq!(method, AutomapProtocol::Igdp);
     }
 
+    #[test]
+    fn starts_with_empty_housekeeping_stats() {
+        let subject = IgdpTransactor::new();
+
+        let result = subject.housekeeping_stats();
+
+        assert_eq!(result, Some(HousekeepingStats::default()));
+    }
+
+    #[test]
+    fn public_ip_poll_delay_can_be_configured() {
+        let subject = IgdpTransactor::new().with_public_ip_poll_delay(Duration::from_secs(5));
+
+        assert_eq!(subject.public_ip_poll_delay, Duration::from_secs(5));
+        assert_eq!(
+            IgdpTransactor::new().public_ip_poll_delay,
+            Duration::from_secs(PUBLIC_IP_POLL_DELAY_SECONDS)
+        );
+    }
+
     #[test]
     fn find_routers_works() {
         let make_params_arc = Arc::new(Mutex::new(vec![]));
@@ -1082,8 +1148,10 @@ mod tests {
             .remove_port_params(&remove_port_params_arc)
             .remove_port_result(Ok(()));
         let gateway_factory = GatewayFactoryMock::new().make_result(Ok(gateway));
+        let (tx, rx) = unbounded();
         let mut subject = IgdpTransactor::new();
         subject.gateway_factory = Box::new(gateway_factory);
+        subject.inner_arc.lock().unwrap().housekeeping_commander_opt = Some(tx);
 
         let _ = subject
             .delete_mapping(IpAddr::from_str("192.168.0.1").unwrap(), 7777)
@@ -1091,6 +1159,10 @@ mod tests {
 
         let remove_port_params = remove_port_params_arc.lock().unwrap();
         assert_eq!(*remove_port_params, vec![(PortMappingProtocol::TCP, 7777,)]);
+        assert_eq!(
+            rx.try_recv(),
+            Ok(HousekeepingThreadCommand::RemoveMappingConfig(7777))
+        );
     }
 
     #[test]
@@ -1144,11 +1216,17 @@ mod tests {
         });
         let mut subject = IgdpTransactor::new();
         subject.public_ip_poll_delay = Duration::from_millis(10);
+        subject.inner().housekeeping_stats.record_error();
         let _ =
             subject.start_housekeeping_thread(change_handler, IpAddr::from_str("1.2.3.4").unwrap());
 
         let change_handler = subject.stop_housekeeping_thread().unwrap();
 
+        assert_eq!(
+            subject.housekeeping_stats(),
+            Some(HousekeepingStats::default())
+        );
+
         let change = AutomapChange::NewIp(IpAddr::from_str("4.3.2.1").unwrap());
         change_handler(change.clone());
         let change_log = change_log_arc.lock().unwrap();
@@ -1218,15 +1296,14 @@ mod tests {
             housekeeping_commander_opt: Some(tx.clone()),
             public_ip_opt: None,
             mapping_adder: Box::new(MappingAdderMock::new()), // no provision for add_mapping()
+            housekeeping_stats: HousekeepingStats::default(),
             logger: Logger::new("no_remap_test"),
         }));
-        tx.send(HousekeepingThreadCommand::InitializeMappingConfig(
-            MappingConfig {
-                hole_port: 6666,
-                next_lifetime: Duration::from_secs(0),
-                remap_interval: Duration::from_secs(1),
-            },
-        ))
+        tx.send(HousekeepingThreadCommand::AddMappingConfig(MappingConfig {
+            hole_port: 6666,
+            next_lifetime: Duration::from_secs(0),
+            remap_interval: Duration::from_secs(1),
+        }))
         .unwrap();
         tx.send(HousekeepingThreadCommand::Stop).unwrap();
 
@@ -1259,6 +1336,7 @@ mod tests {
             housekeeping_commander_opt: None,
             public_ip_opt: None,
             mapping_adder,
+            housekeeping_stats: HousekeepingStats::default(),
             logger: Logger::new("timed_remap_test"),
         }));
         let mapping_config = MappingConfig {
@@ -1270,19 +1348,28 @@ mod tests {
         IgdpTransactor::thread_guts_iteration(
             &change_handler,
             &inner_arc,
-            &mut Instant::now().sub(Duration::from_millis(1000)),
-            &Some(mapping_config),
+            &mut remapped_as_of(
+                mapping_config,
+                Instant::now().sub(Duration::from_millis(1000)),
+            ),
         );
 
         let (_, hole_port, lifetime) = add_mapping_params_arc.lock().unwrap().remove(0);
         assert_eq!(hole_port, 6689);
         assert_eq!(lifetime, 1);
+        let inner = inner_arc.lock().unwrap();
+        assert_eq!(inner.housekeeping_stats.remaps, 1);
+        assert_eq!(inner.housekeeping_stats.errors, 0);
+        assert_eq!(
+            inner.public_ip_opt,
+            Some(Ipv4Addr::from_str("192.168.0.1").unwrap())
+        );
         TestLogHandler::new()
             .exists_log_containing("INFO: timed_remap_test: Remapping port 6689 for 0 seconds");
     }
 
     #[test]
-    #[should_panic(expected = "Must InitializeMappingConfig before you can SetRemapIntervalMs")]
+    #[should_panic(expected = "Must AddMappingConfig before you can SetRemapIntervalMs")]
     fn thread_guts_panics_if_remap_interval_is_set_in_absence_of_mapping_config() {
         let (tx, rx) = unbounded();
         let change_handler: ChangeHandler = Box::new(move |_| {});
@@ -1293,6 +1380,7 @@ mod tests {
             housekeeping_commander_opt: Some(tx.clone()),
             public_ip_opt: Some(public_ip),
             mapping_adder: Box::new(MappingAdderMock::new()),
+            housekeeping_stats: HousekeepingStats::default(),
             logger: Logger::new("test"),
         }));
         tx.send(HousekeepingThreadCommand::SetRemapIntervalMs(1234))
@@ -1328,6 +1416,7 @@ mod tests {
             housekeeping_commander_opt: None,
             public_ip_opt: Some(new_public_ip),
             mapping_adder: Box::new(MappingAdderMock::new()),
+            housekeeping_stats: HousekeepingStats::default(),
             logger: Logger::new("thread_guts_iteration_handles_missing_mapping_config"),
         }));
         let change_handler: ChangeHandler = Box::new(move |_| panic!("Shouldn't be called"));
@@ -1335,8 +1424,7 @@ mod tests {
         let result = IgdpTransactor::thread_guts_iteration(
             &change_handler,
             &inner_arc,
-            &mut Instant::now().sub(Duration::from_secs(1)),
-            &None,
+            &mut MappingConfigs::new(),
         );
 
         assert!(result);
@@ -1355,6 +1443,7 @@ mod tests {
             mapping_adder: Box::new(MappingAdderMock::new().add_mapping_result(Err(
                 AutomapError::PermanentMappingError("Booga".to_string()),
             ))),
+            housekeeping_stats: HousekeepingStats::default(),
             logger: Logger::new("test"),
         }));
         let change_log_arc = Arc::new(Mutex::new(vec![]));
@@ -1365,12 +1454,14 @@ mod tests {
         let result = IgdpTransactor::thread_guts_iteration(
             &change_handler,
             &inner_arc,
-            &mut Instant::now().sub(Duration::from_secs(1)),
-            &Some(MappingConfig {
-                hole_port: 6689,
-                next_lifetime: Duration::from_secs(600),
-                remap_interval: Duration::from_secs(0),
-            }),
+            &mut remapped_as_of(
+                MappingConfig {
+                    hole_port: 6689,
+                    next_lifetime: Duration::from_secs(600),
+                    remap_interval: Duration::from_secs(0),
+                },
+                Instant::now().sub(Duration::from_secs(1)),
+            ),
         );
 
         assert!(result);
@@ -1402,6 +1493,7 @@ mod tests {
             housekeeping_commander_opt: None,
             public_ip_opt: Some(Ipv4Addr::from_str("1.2.3.4").unwrap()),
             mapping_adder: Box::new(mapping_adder),
+            housekeeping_stats: HousekeepingStats::default(),
             logger: Logger::new("thread_guts_iteration_reports_router_error_to_change_handler"),
         }));
         let change_log_arc = Arc::new(Mutex::new(vec![]));
@@ -1412,33 +1504,75 @@ mod tests {
         let result = IgdpTransactor::thread_guts_iteration(
             &change_handler,
             &inner_arc,
-            &mut Instant::now().sub(Duration::from_secs(2000)),
-            &Some(MappingConfig {
-                hole_port: 7777,
-                next_lifetime: Duration::from_secs(1000),
-                remap_interval: Duration::from_secs(1000),
-            }),
+            &mut remapped_as_of(
+                MappingConfig {
+                    hole_port: 7777,
+                    next_lifetime: Duration::from_secs(1000),
+                    remap_interval: Duration::from_secs(1000),
+                },
+                Instant::now().sub(Duration::from_secs(2000)),
+            ),
         );
 
         assert!(result);
         let change_log = change_log_arc.lock().unwrap();
         assert_eq!(
             *change_log,
-            vec![AutomapChange::Error(AutomapError::TemporaryMappingError(
-                "Booga".to_string()
-            ))]
+            vec![
+                AutomapChange::Error(AutomapError::GetPublicIpError(
+                    "ActionNotAuthorized".to_string()
+                )),
+                AutomapChange::Error(AutomapError::TemporaryMappingError("Booga".to_string()))
+            ]
         );
+        assert_eq!(inner_arc.lock().unwrap().housekeeping_stats.errors, 2);
         let add_mapping_params = add_mapping_params_arc.lock().unwrap();
         let add_mapping_params_call = (*add_mapping_params)[0];
         assert_eq!(add_mapping_params_call.1, 7777);
         assert_eq!(add_mapping_params_call.2, 1000);
         let tlh = TestLogHandler::new();
+        tlh.exists_log_containing(
+            "ERROR: thread_guts_iteration_reports_router_error_to_change_handler: Public IP check failure: ActionNotAuthorized",
+        );
         tlh.exists_log_containing(&format!(
             "ERROR: thread_guts_iteration_reports_router_error_to_change_handler: Remapping failure: TemporaryMappingError(\"Booga\")",
         ));
-        tlh.exists_log_containing(&format!(
-            "ERROR: thread_guts_iteration_reports_router_error_to_change_handler: Remapping failure: TemporaryMappingError(\"Booga\")",
-        ));
+    }
+
+    #[test]
+    fn thread_guts_iteration_reports_new_public_ip_to_change_handler() {
+        init_test_logging();
+        let new_public_ip = Ipv4Addr::from_str("4.3.2.1").unwrap();
+        let gateway = GatewayWrapperMock::new().get_external_ip_result(Ok(new_public_ip));
+        let inner_arc = Arc::new(Mutex::new(IgdpTransactorInner {
+            gateway_opt: Some(Box::new(gateway)),
+            housekeeping_commander_opt: None,
+            public_ip_opt: Some(Ipv4Addr::from_str("1.2.3.4").unwrap()),
+            mapping_adder: Box::new(MappingAdderMock::new()),
+            housekeeping_stats: HousekeepingStats::default(),
+            logger: Logger::new("thread_guts_iteration_reports_new_public_ip_to_change_handler"),
+        }));
+        let change_log_arc = Arc::new(Mutex::new(vec![]));
+        let change_log_inner = change_log_arc.clone();
+        let change_handler: ChangeHandler =
+            Box::new(move |change| change_log_inner.lock().unwrap().push(change));
+
+        let result = IgdpTransactor::thread_guts_iteration(
+            &change_handler,
+            &inner_arc,
+            &mut MappingConfigs::new(),
+        );
+
+        assert!(result);
+        let change_log = change_log_arc.lock().unwrap();
+        assert_eq!(
+            *change_log,
+            vec![AutomapChange::NewIp(IpAddr::V4(new_public_ip))]
+        );
+        assert_eq!(inner_arc.lock().unwrap().public_ip_opt, Some(new_public_ip));
+        TestLogHandler::new().exists_log_containing(
+            "INFO: thread_guts_iteration_reports_new_public_ip_to_change_handler: Public IP changed from 1.2.3.4 to 4.3.2.1",
+        );
     }
 
     #[test]
@@ -1449,6 +1583,7 @@ mod tests {
             housekeeping_commander_opt: None,
             public_ip_opt: None,
             mapping_adder: Box::new(MappingAdderMock::new()),
+            housekeeping_stats: HousekeepingStats::default(),
             logger: Logger::new("test"),
         };
         let mapping_config = MappingConfig {
@@ -1460,8 +1595,7 @@ mod tests {
         IgdpTransactor::remap_if_necessary(
             &change_handler,
             &inner,
-            &mut Instant::now().sub(Duration::from_secs(300)),
-            &Some(mapping_config),
+            &mut remapped_as_of(mapping_config, Instant::now().sub(Duration::from_secs(300))),
         );
 
         // No exception; test passes
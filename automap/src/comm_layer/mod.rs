@@ -9,7 +9,7 @@ use crossbeam_channel::Sender;
 
 use masq_lib::utils::AutomapProtocol;
 
-use crate::comm_layer::pcp_pmp_common::MappingConfig;
+use crate::comm_layer::pcp_pmp_common::{HousekeepingStats, MappingConfig, RouterTiming};
 use crate::control_layer::automap_control::ChangeHandler;
 use crate::protocols::utils::ParseError;
 
@@ -107,6 +107,10 @@ pub trait Transactor {
         -> Result<u32, AutomapError>;
     fn delete_mapping(&self, router_ip: IpAddr, hole_port: u16) -> Result<(), AutomapError>;
     fn protocol(&self) -> AutomapProtocol;
+    // None if the protocol doesn't calibrate its read timeouts
+    fn router_timing_opt(&self, router_ip: IpAddr) -> Option<RouterTiming>;
+    // None if the protocol has no keep-alive loop to report on
+    fn housekeeping_stats(&self) -> Option<HousekeepingStats>;
     fn start_housekeeping_thread(
         &mut self,
         change_handler: ChangeHandler,
@@ -126,7 +130,8 @@ impl Debug for dyn Transactor {
 pub enum HousekeepingThreadCommand {
     Stop,
     SetRemapIntervalMs(u64),
-    InitializeMappingConfig(MappingConfig),
+    AddMappingConfig(MappingConfig),
+    RemoveMappingConfig(u16),
 }
 
 pub trait LocalIpFinder: Send {
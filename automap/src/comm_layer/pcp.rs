@@ -2,11 +2,10 @@
 
 use std::any::Any;
 use std::convert::TryFrom;
-use std::io::ErrorKind;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::sync::{Arc, Mutex, MutexGuard};
 use std::thread::JoinHandle;
-use std::time::{Duration, Instant};
+use std::time::{Duration, SystemTime};
 use std::{io, thread};
 
 use crossbeam_channel::{unbounded, Receiver, Sender};
@@ -18,10 +17,12 @@ use masq_lib::logger::Logger;
 use masq_lib::utils::AutomapProtocol;
 use masq_lib::{debug, warning};
 
+use crate::comm_layer::pcp_pmp_common::router_discovery::{RouterDiscoverer, RouterDiscovererReal};
 use crate::comm_layer::pcp_pmp_common::{
-    find_routers, make_local_socket_address, FreePortFactory, FreePortFactoryReal, MappingConfig,
-    UdpSocketFactoryReal, UdpSocketWrapper, UdpSocketWrapperFactory,
-    HOUSEKEEPING_THREAD_LOOP_DELAY_MILLIS, ROUTER_PORT,
+    exchange_with_router, lifetime_reduction, make_local_socket_address, ExchangeError,
+    FreePortFactory, FreePortFactoryReal, HousekeepingStats, MappingConfig, MappingConfigs,
+    ReadTimeoutCalibrator, RouterTiming, UdpSocketFactoryReal, UdpSocketWrapper,
+    UdpSocketWrapperFactory, HOUSEKEEPING_THREAD_LOOP_DELAY_MILLIS, ROUTER_PORT,
 };
 use crate::comm_layer::{
     AutomapError, AutomapErrorCause, HousekeepingThreadCommand, LocalIpFinder, LocalIpFinderReal,
@@ -57,6 +58,7 @@ struct Factories {
     local_ip_finder: Box<dyn LocalIpFinder>,
     mapping_nonce_factory: Box<dyn MappingNonceFactory>,
     free_port_factory: Box<dyn FreePortFactory>,
+    read_timeout_calibrator: Arc<ReadTimeoutCalibrator>,
 }
 
 impl Default for Factories {
@@ -66,6 +68,7 @@ impl Default for Factories {
             local_ip_finder: Box::new(LocalIpFinderReal::new()),
             mapping_nonce_factory: Box::new(MappingNonceFactoryReal::new()),
             free_port_factory: Box::new(FreePortFactoryReal::new()),
+            read_timeout_calibrator: Arc::new(ReadTimeoutCalibrator::new()),
         }
     }
 }
@@ -78,8 +81,10 @@ struct PcpTransactorInner {
 pub struct PcpTransactor {
     inner_arc: Arc<Mutex<PcpTransactorInner>>,
     router_port: u16,
+    router_discoverer: Box<dyn RouterDiscoverer>,
     housekeeper_commander_opt: Option<Sender<HousekeepingThreadCommand>>,
     join_handle_opt: Option<JoinHandle<ChangeHandler>>,
+    housekeeping_stats_arc: Arc<Mutex<HousekeepingStats>>,
     read_timeout_millis: u64,
     logger: Logger,
 }
@@ -87,7 +92,7 @@ pub struct PcpTransactor {
 impl Transactor for PcpTransactor {
     fn find_routers(&self) -> Result<Vec<IpAddr>, AutomapError> {
         debug!(self.logger, "Seeking routers on LAN");
-        find_routers()
+        self.router_discoverer.discover()
     }
 
     fn get_public_ip(&self, router_ip: IpAddr) -> Result<IpAddr, AutomapError> {
@@ -144,9 +149,7 @@ impl Transactor for PcpTransactor {
         self.housekeeper_commander_opt
             .as_ref()
             .expect("Start housekeeping thread before adding a mapping")
-            .try_send(HousekeepingThreadCommand::InitializeMappingConfig(
-                mapping_config,
-            ))
+            .try_send(HousekeepingThreadCommand::AddMappingConfig(mapping_config))
             .expect("Housekeepig thread panicked");
         Ok(approved_lifetime / 2)
     }
@@ -176,13 +179,33 @@ impl Transactor for PcpTransactor {
                     remap_interval: Duration::from_secs(0),
                 },
             )
-            .map(|_| ())
+            .map(|_| {
+                if let Some(commander) = self.housekeeper_commander_opt.as_ref() {
+                    commander
+                        .try_send(HousekeepingThreadCommand::RemoveMappingConfig(hole_port))
+                        .expect("Housekeeping thread panicked");
+                }
+            })
     }
 
     fn protocol(&self) -> AutomapProtocol {
         AutomapProtocol::Pcp
     }
 
+    fn router_timing_opt(&self, router_ip: IpAddr) -> Option<RouterTiming> {
+        let inner = self.inner_arc.lock().expect("PcpTransactor is dead");
+        Some(inner.factories.read_timeout_calibrator.timing(router_ip))
+    }
+
+    fn housekeeping_stats(&self) -> Option<HousekeepingStats> {
+        Some(
+            *self
+                .housekeeping_stats_arc
+                .lock()
+                .expect("PcpTransactor is dead"),
+        )
+    }
+
     fn start_housekeeping_thread(
         &mut self,
         change_handler: ChangeHandler,
@@ -199,6 +222,11 @@ impl Transactor for PcpTransactor {
         self.housekeeper_commander_opt = Some(tx.clone());
         let inner_arc = self.inner_arc.clone();
         let router_addr = SocketAddr::new(router_ip, self.router_port);
+        *self
+            .housekeeping_stats_arc
+            .lock()
+            .expect("PcpTransactor is dead") = HousekeepingStats::default();
+        let housekeeping_stats_arc = self.housekeeping_stats_arc.clone();
         let read_timeout_millis = self.read_timeout_millis;
         let logger = self.logger.clone();
         self.join_handle_opt = Some(thread::spawn(move || {
@@ -207,6 +235,7 @@ impl Transactor for PcpTransactor {
                 inner_arc,
                 router_addr,
                 change_handler,
+                housekeeping_stats_arc,
                 read_timeout_millis,
                 logger,
             )
@@ -258,8 +287,10 @@ impl Default for PcpTransactor {
                 factories: Factories::default(),
             })),
             router_port: ROUTER_PORT,
+            router_discoverer: Box::new(RouterDiscovererReal::new(AutomapProtocol::Pcp)),
             housekeeper_commander_opt: None,
             join_handle_opt: None,
+            housekeeping_stats_arc: Arc::new(Mutex::new(HousekeepingStats::default())),
             read_timeout_millis: HOUSEKEEPING_THREAD_LOOP_DELAY_MILLIS,
             logger: Logger::new("PcpTransactor"),
         }
@@ -268,9 +299,7 @@ impl Default for PcpTransactor {
 
 impl PcpTransactor {
     fn inner(&self) -> MutexGuard<PcpTransactorInner> {
-        self.inner_arc
-            .lock()
-            .expect("PCP Housekeeping Thread is dead")
+        self.inner_arc.lock().expect("PcpTransactor is dead")
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -279,62 +308,80 @@ impl PcpTransactor {
         inner_arc: Arc<Mutex<PcpTransactorInner>>,
         router_addr: SocketAddr,
         change_handler: ChangeHandler,
+        housekeeping_stats_arc: Arc<Mutex<HousekeepingStats>>,
         read_timeout_millis: u64,
         logger: Logger,
     ) -> ChangeHandler {
-        let mut last_remapped = Instant::now();
-        let mut mapping_config_opt: Option<MappingConfig> = None;
+        let mut mapping_configs = MappingConfigs::new();
         loop {
             match rx.try_recv() {
                 Ok(HousekeepingThreadCommand::Stop) => {
                     break;
                 }
                 Ok(HousekeepingThreadCommand::SetRemapIntervalMs(remap_after)) => {
-                    match &mut mapping_config_opt {
-                        None => {
-                            error!(
-                                logger,
-                                "Can't set remap interval until after first mapping request"
-                            );
-                        }
-                        Some(mapping_config) => {
-                            debug!(
-                                logger,
-                                "Changing remap interval from {}ms to {}ms",
-                                mapping_config.remap_interval.as_millis(),
-                                remap_after
-                            );
-                            mapping_config.remap_interval = Duration::from_millis(remap_after)
-                        }
+                    if mapping_configs.is_empty() {
+                        error!(
+                            logger,
+                            "Can't set remap interval until after first mapping request"
+                        );
+                    } else {
+                        debug!(
+                            logger,
+                            "Changing remap interval for ports {:?} to {}ms",
+                            mapping_configs.hole_ports(),
+                            remap_after
+                        );
+                        mapping_configs.set_remap_interval(Duration::from_millis(remap_after))
                     }
                 }
-                Ok(HousekeepingThreadCommand::InitializeMappingConfig(mapping_config)) => {
-                    mapping_config_opt.replace(mapping_config);
+                Ok(HousekeepingThreadCommand::AddMappingConfig(mapping_config)) => {
+                    mapping_configs.add(mapping_config);
+                }
+                Ok(HousekeepingThreadCommand::RemoveMappingConfig(hole_port)) => {
+                    mapping_configs.remove(hole_port);
                 }
                 Err(_) => (),
             }
             thread::sleep(Duration::from_millis(read_timeout_millis)); // replaces IP-change check
-            let since_last_remapped = last_remapped.elapsed();
-            match &mut mapping_config_opt {
-                None => (),
-                Some(mapping_config) => {
-                    if since_last_remapped.gt(&mapping_config.remap_interval) {
-                        let inner = inner_arc.lock().expect("PcpTransactor is dead");
-                        let requested_lifetime = mapping_config.next_lifetime;
-                        if let Err(e) = Self::remap_port(
-                            &inner,
-                            router_addr,
-                            mapping_config,
-                            requested_lifetime,
-                            &logger,
-                        ) {
-                            error!(logger, "Remapping failure: {:?}", e);
-                            change_handler(AutomapChange::Error(e));
+            mapping_configs.remap_due(|mapping_config| {
+                let inner = inner_arc.lock().expect("PcpTransactor is dead");
+                let requested_lifetime = mapping_config.next_lifetime;
+                match Self::remap_port(
+                    &inner,
+                    router_addr,
+                    mapping_config,
+                    requested_lifetime,
+                    &logger,
+                ) {
+                    Ok(approved_lifetime) => {
+                        housekeeping_stats_arc
+                            .lock()
+                            .expect("PcpTransactor is dead")
+                            .record_remap(SystemTime::now());
+                        let approved_lifetime = Duration::from_secs(approved_lifetime as u64);
+                        if let Some(change) =
+                            lifetime_reduction(requested_lifetime, approved_lifetime)
+                        {
+                            warning!(
+                                logger,
+                                "Router cut the lifetime of port {} from {}s to {}s",
+                                mapping_config.hole_port,
+                                requested_lifetime.as_secs(),
+                                approved_lifetime.as_secs()
+                            );
+                            change_handler(change);
                         }
-                        last_remapped = Instant::now();
+                    }
+                    Err(e) => {
+                        housekeeping_stats_arc
+                            .lock()
+                            .expect("PcpTransactor is dead")
+                            .record_error();
+                        error!(logger, "Remapping failure: {:?}", e);
+                        change_handler(AutomapChange::Error(e));
                     }
                 }
-            }
+            });
         }
         change_handler
     }
@@ -423,25 +470,15 @@ impl MappingTransactor for MappingTransactorReal {
                 ));
             }
         };
-        socket
-            .set_read_timeout(Some(Duration::from_secs(3)))
-            .expect("set_read_timeout failed");
-        match socket.send_to(&buffer[0..request_len], router_addr) {
-            Ok(_) => (),
-            Err(e) => {
-                warning!(
-                    self.logger,
-                    "Error while transmitting to router at {}: \"{:?}\"",
-                    router_addr,
-                    e
-                );
-                return Err(AutomapError::SocketSendError(AutomapErrorCause::Unknown(
-                    format!("{:?}", e),
-                )));
-            }
-        };
-        let response = match socket.recv_from(&mut buffer) {
-            Ok((len, _peer_addr)) => match PcpPacket::try_from(&buffer[0..len]) {
+        let request = buffer[0..request_len].to_vec();
+        let response = match exchange_with_router(
+            socket.as_ref(),
+            router_addr,
+            &request,
+            &mut buffer,
+            &factories.read_timeout_calibrator,
+        ) {
+            Ok(len) => match PcpPacket::try_from(&buffer[0..len]) {
                 Ok(pkt) => pkt,
                 Err(e) => {
                     warning!(
@@ -453,12 +490,24 @@ impl MappingTransactor for MappingTransactorReal {
                     return Err(AutomapError::PacketParseError(e));
                 }
             },
-            Err(e) if (e.kind() == ErrorKind::WouldBlock) || (e.kind() == ErrorKind::TimedOut) => {
-                return Err(AutomapError::ProtocolError(
-                    "Timed out after 3 seconds".to_string(),
-                ))
+            Err(ExchangeError::Send(e)) => {
+                warning!(
+                    self.logger,
+                    "Error while transmitting to router at {}: \"{:?}\"",
+                    router_addr,
+                    e
+                );
+                return Err(AutomapError::SocketSendError(AutomapErrorCause::Unknown(
+                    format!("{:?}", e),
+                )));
             }
-            Err(e) => {
+            Err(ExchangeError::TimedOut(elapsed)) => {
+                return Err(AutomapError::ProtocolError(format!(
+                    "Timed out after {}ms",
+                    elapsed.as_millis()
+                )))
+            }
+            Err(ExchangeError::Receive(e)) => {
                 warning!(
                     self.logger,
                     "Error while receiving from router at {}: \"{:?}\"",
@@ -577,10 +626,11 @@ mod tests {
     use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
     use masq_lib::utils::localhost;
 
-    use crate::comm_layer::pcp_pmp_common::ROUTER_PORT;
+    use crate::comm_layer::pcp_pmp_common::{INITIAL_READ_TIMEOUT_MILLIS, ROUTER_PORT};
     use crate::comm_layer::{AutomapErrorCause, LocalIpFinder};
     use crate::mocks::{
-        FreePortFactoryMock, LocalIpFinderMock, UdpSocketWrapperFactoryMock, UdpSocketWrapperMock,
+        FreePortFactoryMock, LocalIpFinderMock, RouterDiscovererMock, UdpSocketWrapperFactoryMock,
+        UdpSocketWrapperMock,
     };
     use crate::protocols::pcp::map_packet::{MapOpcodeData, Protocol};
     use crate::protocols::pcp::pcp_packet::{Opcode, PcpPacket};
@@ -937,6 +987,21 @@ mod tests {
         assert!(result.len() > 0)
     }
 
+    #[test]
+    fn find_routers_asks_the_router_discoverer() {
+        let router_ips = vec![
+            IpAddr::from_str("192.168.1.1").unwrap(),
+            IpAddr::from_str("192.168.0.1").unwrap(),
+        ];
+        let mut subject = PcpTransactor::default();
+        subject.router_discoverer =
+            Box::new(RouterDiscovererMock::new().discover_result(Ok(router_ips.clone())));
+
+        let result = subject.find_routers();
+
+        assert_eq!(result, Ok(router_ips));
+    }
+
     #[test]
     fn get_public_ip_works() {
         let send_to_params_arc = Arc::new(Mutex::new(vec![]));
@@ -1116,7 +1181,7 @@ mod tests {
 
         assert_eq!(result, Ok(4000));
         let mapping_config = match rx.try_recv().unwrap() {
-            HousekeepingThreadCommand::InitializeMappingConfig(mc) => mc,
+            HousekeepingThreadCommand::AddMappingConfig(mc) => mc,
             x => panic!("Expecting AddMappingConfig, got {:?}", x),
         };
         assert_eq!(
@@ -1136,7 +1201,10 @@ mod tests {
             ))]
         );
         let set_read_timeout_params = set_read_timeout_params_arc.lock().unwrap();
-        assert_eq!(*set_read_timeout_params, vec![Some(Duration::from_secs(3))]);
+        assert_eq!(
+            *set_read_timeout_params,
+            vec![Some(Duration::from_millis(INITIAL_READ_TIMEOUT_MILLIS))]
+        );
         let mut send_to_params = send_to_params_arc.lock().unwrap();
         let (actual_buf, actual_addr) = send_to_params.remove(0);
         assert_eq!(
@@ -1152,6 +1220,19 @@ mod tests {
         );
         let recv_from_params = recv_from_params_arc.lock().unwrap();
         assert_eq!(*recv_from_params, vec![()]);
+        let router_timing = subject
+            .router_timing_opt(IpAddr::from_str("1.2.3.4").unwrap())
+            .unwrap();
+        assert_eq!(router_timing.smoothed_rtt_opt.is_some(), true);
+    }
+
+    #[test]
+    fn router_timing_opt_reports_initial_timing_for_unknown_router() {
+        let subject = PcpTransactor::default();
+
+        let result = subject.router_timing_opt(IpAddr::from_str("1.2.3.4").unwrap());
+
+        assert_eq!(result, Some(RouterTiming::default()));
     }
 
     #[test]
@@ -1234,7 +1315,9 @@ mod tests {
         let socket_factory = UdpSocketWrapperFactoryMock::new().make_result(Ok(socket));
         let nonce_factory =
             MappingNonceFactoryMock::new().make_result([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
-        let subject = PcpTransactor::default();
+        let (tx, rx) = unbounded();
+        let mut subject = PcpTransactor::default();
+        subject.housekeeper_commander_opt = Some(tx);
         {
             let factories = &mut subject.inner_arc.lock().unwrap().factories;
             factories.socket_factory = Box::new(socket_factory);
@@ -1244,8 +1327,15 @@ mod tests {
         let result = subject.delete_mapping(IpAddr::from_str("1.2.3.4").unwrap(), 6666);
 
         assert_eq!(result, Ok(()));
+        assert_eq!(
+            rx.try_recv(),
+            Ok(HousekeepingThreadCommand::RemoveMappingConfig(6666))
+        );
         let set_read_timeout_params = set_read_timeout_params_arc.lock().unwrap();
-        assert_eq!(*set_read_timeout_params, vec![Some(Duration::from_secs(3))]);
+        assert_eq!(
+            *set_read_timeout_params,
+            vec![Some(Duration::from_millis(INITIAL_READ_TIMEOUT_MILLIS))]
+        );
         let send_to_params = send_to_params_arc.lock().unwrap();
         assert_eq!(
             *send_to_params,
@@ -1331,6 +1421,30 @@ mod tests {
         assert_eq!(change_log.last().unwrap(), &change)
     }
 
+    #[test]
+    fn start_housekeeping_thread_starts_housekeeping_stats_afresh() {
+        let mut subject = PcpTransactor::default();
+        subject
+            .housekeeping_stats_arc
+            .lock()
+            .unwrap()
+            .record_remap(SystemTime::now());
+        subject
+            .housekeeping_stats_arc
+            .lock()
+            .unwrap()
+            .record_error();
+
+        let _ = subject
+            .start_housekeeping_thread(Box::new(|_| ()), IpAddr::from_str("1.2.3.4").unwrap());
+
+        assert_eq!(
+            subject.housekeeping_stats(),
+            Some(HousekeepingStats::default())
+        );
+        let _ = subject.stop_housekeeping_thread();
+    }
+
     #[test]
     #[should_panic(expected = "No HousekeepingCommander: can't stop housekeeping thread")]
     fn stop_housekeeping_thread_handles_missing_housekeeper_commander() {
@@ -1400,10 +1514,8 @@ mod tests {
             next_lifetime: Duration::from_secs(1),
             remap_interval: Duration::from_millis(500),
         };
-        tx.send(HousekeepingThreadCommand::InitializeMappingConfig(
-            mapping_config,
-        ))
-        .unwrap();
+        tx.send(HousekeepingThreadCommand::AddMappingConfig(mapping_config))
+            .unwrap();
         tx.send(HousekeepingThreadCommand::SetRemapIntervalMs(1000))
             .unwrap();
         tx.send(HousekeepingThreadCommand::Stop).unwrap();
@@ -1413,6 +1525,7 @@ mod tests {
             inner_arc,
             SocketAddr::new(localhost(), 0),
             change_handler,
+            Arc::new(Mutex::new(HousekeepingStats::default())),
             10,
             Logger::new("no_remap_test"),
         );
@@ -1495,12 +1608,13 @@ mod tests {
             next_lifetime: Duration::from_secs(1000),
             remap_interval: Duration::from_secs(500),
         };
-        tx.send(HousekeepingThreadCommand::InitializeMappingConfig(
-            mapping_config,
-        ))
-        .unwrap();
+        tx.send(HousekeepingThreadCommand::AddMappingConfig(mapping_config))
+            .unwrap();
         tx.send(HousekeepingThreadCommand::SetRemapIntervalMs(80))
             .unwrap();
+        let housekeeping_stats_arc = Arc::new(Mutex::new(HousekeepingStats::default()));
+        let housekeeping_stats_arc_inner = housekeeping_stats_arc.clone();
+        let before = SystemTime::now();
 
         let handle = thread::spawn(move || {
             let _ = PcpTransactor::thread_guts(
@@ -1508,6 +1622,7 @@ mod tests {
                 inner_arc,
                 SocketAddr::new(localhost(), 0),
                 change_handler,
+                housekeeping_stats_arc_inner,
                 10,
                 Logger::new("timed_remap_test"),
             );
@@ -1516,6 +1631,12 @@ mod tests {
         thread::sleep(Duration::from_millis(100));
         tx.send(HousekeepingThreadCommand::Stop).unwrap();
         handle.join().unwrap();
+        let after = SystemTime::now();
+        let housekeeping_stats = *housekeeping_stats_arc.lock().unwrap();
+        assert_eq!(housekeeping_stats.remaps, 1);
+        let last_remap = housekeeping_stats.last_successful_remap_opt.unwrap();
+        assert!(before <= last_remap && last_remap <= after);
+        assert_eq!(housekeeping_stats.errors, 0);
         let mut mapping_socket_send_to_params = mapping_socket_send_to_params_arc.lock().unwrap();
         let (actual_outgoing_packet_bytes, _) = mapping_socket_send_to_params.remove(0);
         assert_eq!(
@@ -1529,6 +1650,65 @@ mod tests {
         TestLogHandler::new().exists_log_containing("INFO: timed_remap_test: Remapping port 6689");
     }
 
+    #[test]
+    fn thread_guts_remaps_nearly_due_mappings_together_and_forgets_removed_ones() {
+        init_test_logging();
+        let (tx, rx) = unbounded();
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mapping_transactor = Box::new(
+            MappingTransactorMock::new()
+                .transact_params(&transact_params_arc)
+                .transact_result(Ok((1000, MapOpcodeData::default()))),
+        );
+        let make_mapping_config = |hole_port: u16, remap_interval_ms: u64| MappingConfig {
+            hole_port,
+            next_lifetime: Duration::from_secs(1000),
+            remap_interval: Duration::from_millis(remap_interval_ms),
+        };
+        tx.send(HousekeepingThreadCommand::AddMappingConfig(
+            make_mapping_config(6001, 60),
+        ))
+        .unwrap();
+        tx.send(HousekeepingThreadCommand::AddMappingConfig(
+            make_mapping_config(6002, 100),
+        ))
+        .unwrap();
+        tx.send(HousekeepingThreadCommand::AddMappingConfig(
+            make_mapping_config(6003, 40),
+        ))
+        .unwrap();
+        tx.send(HousekeepingThreadCommand::RemoveMappingConfig(6003))
+            .unwrap();
+
+        let handle = thread::spawn(move || {
+            let _ = PcpTransactor::thread_guts(
+                &rx,
+                Arc::new(Mutex::new(PcpTransactorInner {
+                    mapping_transactor,
+                    factories: Factories::default(),
+                })),
+                SocketAddr::new(IpAddr::from_str("1.1.1.1").unwrap(), 0),
+                Box::new(|_| ()),
+                Arc::new(Mutex::new(HousekeepingStats::default())),
+                10,
+                Logger::new("batched_remap_test"),
+            );
+        });
+
+        thread::sleep(Duration::from_millis(100));
+        tx.send(HousekeepingThreadCommand::Stop).unwrap();
+        handle.join().unwrap();
+        let remapped_ports = transact_params_arc
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, _, mapping_config)| mapping_config.hole_port)
+            .collect::<Vec<u16>>();
+        assert_eq!(remapped_ports, vec![6001, 6002]);
+        TestLogHandler::new()
+            .exists_no_log_containing("INFO: batched_remap_test: Remapping port 6003");
+    }
+
     #[test]
     fn thread_guts_logs_and_continues_if_remap_interval_is_set_before_mapping_config() {
         init_test_logging();
@@ -1552,6 +1732,7 @@ mod tests {
                 })),
                 SocketAddr::new(IpAddr::from_str("1.1.1.1").unwrap(), 0),
                 Box::new(|_| ()),
+                Arc::new(Mutex::new(HousekeepingStats::default())),
                 10,
                 logger,
             );
@@ -1572,7 +1753,7 @@ mod tests {
                 .transact_result(Err(AutomapError::TemporaryMappingError(
                     "NoResources".to_string(),
                 )))
-                .transact_result(Ok((0, MapOpcodeData::default()))), // extra fodder for macOS in Actions
+                .transact_result(Ok((u32::MAX, MapOpcodeData::default()))), // extra fodder for macOS in Actions
         );
         let change_opt_arc = Arc::new(Mutex::new(None));
         let change_opt_arc_inner = change_opt_arc.clone();
@@ -1580,16 +1761,16 @@ mod tests {
             change_opt_arc_inner.lock().unwrap().replace(change);
         });
         let logger = Logger::new("thread_guts_complains_if_remapping_fails");
-        tx.send(HousekeepingThreadCommand::InitializeMappingConfig(
-            MappingConfig {
-                hole_port: 0,
-                next_lifetime: Duration::from_secs(u32::MAX as u64),
-                remap_interval: Duration::from_secs((u32::MAX / 2) as u64),
-            },
-        ))
+        tx.send(HousekeepingThreadCommand::AddMappingConfig(MappingConfig {
+            hole_port: 0,
+            next_lifetime: Duration::from_secs(u32::MAX as u64),
+            remap_interval: Duration::from_secs((u32::MAX / 2) as u64),
+        }))
         .unwrap();
         tx.send(HousekeepingThreadCommand::SetRemapIntervalMs(80))
             .unwrap();
+        let housekeeping_stats_arc = Arc::new(Mutex::new(HousekeepingStats::default()));
+        let housekeeping_stats_arc_inner = housekeeping_stats_arc.clone();
 
         let handle = thread::spawn(move || {
             let _ = PcpTransactor::thread_guts(
@@ -1600,6 +1781,7 @@ mod tests {
                 })),
                 SocketAddr::new(IpAddr::from_str("1.1.1.1").unwrap(), 0),
                 change_handler,
+                housekeeping_stats_arc_inner,
                 10,
                 logger,
             );
@@ -1608,6 +1790,7 @@ mod tests {
         thread::sleep(Duration::from_millis(100));
         tx.send(HousekeepingThreadCommand::Stop).unwrap();
         handle.join().unwrap();
+        assert_eq!(housekeeping_stats_arc.lock().unwrap().errors, 1);
         let change_opt = change_opt_arc.lock().unwrap();
         assert_eq!(
             *change_opt,
@@ -1620,6 +1803,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn thread_guts_reports_materially_reduced_lifetime() {
+        init_test_logging();
+        let (tx, rx) = unbounded();
+        let mapping_transactor = Box::new(
+            MappingTransactorMock::new().transact_result(Ok((600, MapOpcodeData::default()))),
+        );
+        let changes_arc = Arc::new(Mutex::new(vec![]));
+        let changes_arc_inner = changes_arc.clone();
+        let change_handler: ChangeHandler = Box::new(move |change| {
+            changes_arc_inner.lock().unwrap().push(change);
+        });
+        let logger = Logger::new("thread_guts_reports_materially_reduced_lifetime");
+        tx.send(HousekeepingThreadCommand::AddMappingConfig(MappingConfig {
+            hole_port: 6689,
+            next_lifetime: Duration::from_secs(3600),
+            remap_interval: Duration::from_secs(1800),
+        }))
+        .unwrap();
+        tx.send(HousekeepingThreadCommand::SetRemapIntervalMs(80))
+            .unwrap();
+
+        let handle = thread::spawn(move || {
+            let _ = PcpTransactor::thread_guts(
+                &rx,
+                Arc::new(Mutex::new(PcpTransactorInner {
+                    mapping_transactor,
+                    factories: Factories::default(),
+                })),
+                SocketAddr::new(IpAddr::from_str("1.1.1.1").unwrap(), 0),
+                change_handler,
+                Arc::new(Mutex::new(HousekeepingStats::default())),
+                10,
+                logger,
+            );
+        });
+
+        thread::sleep(Duration::from_millis(100));
+        tx.send(HousekeepingThreadCommand::Stop).unwrap();
+        handle.join().unwrap();
+        let changes = changes_arc.lock().unwrap();
+        assert_eq!(
+            changes.first(),
+            Some(&AutomapChange::LifetimeReduced {
+                old: Duration::from_secs(3600),
+                new: Duration::from_secs(600),
+            })
+        );
+        TestLogHandler::new().exists_log_containing(
+            "WARN: thread_guts_reports_materially_reduced_lifetime: Router cut the lifetime of port 6689 from 3600s to 600s",
+        );
+    }
+
     #[test]
     fn remap_port_correctly_converts_lifetime_greater_than_one_second() {
         let mapping_transactor_params_arc = Arc::new(Mutex::new(vec![]));
@@ -2,6 +2,7 @@
 
 pub mod linux_specific;
 mod macos_specific;
+pub mod router_discovery;
 mod windows_specific;
 
 #[cfg(target_os = "linux")]
@@ -17,15 +18,36 @@ use crate::comm_layer::pcp_pmp_common::windows_specific::{
     windows_find_routers, WindowsFindRoutersCommand,
 };
 use crate::comm_layer::AutomapError;
+use crate::control_layer::automap_control::AutomapChange;
 use masq_lib::utils::find_free_port;
+use std::collections::HashMap;
 use std::io;
+use std::io::ErrorKind;
 pub use std::net::UdpSocket;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::process::Command;
-use std::time::Duration;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime};
 
 pub const ROUTER_PORT: u16 = 5351; // from the PCP and PMP RFCs
 pub const HOUSEKEEPING_THREAD_LOOP_DELAY_MILLIS: u64 = 1000;
+// When one mapping comes due for remapping, every other mapping that has used up at least this
+// much of its own remap interval is remapped in the same housekeeping iteration. Mappings with
+// similar lifetimes thereby fall into step, and the router hears from us in occasional bursts
+// rather than in a steady trickle.
+pub const REMAP_BATCHING_THRESHOLD_PERCENT: u32 = 50;
+// A remap granted a lifetime shorter than the previous one by at least this much is reported as
+// AutomapChange::LifetimeReduced; smaller wobbles are ignored.
+pub const MATERIAL_LIFETIME_REDUCTION_PERCENT: u32 = 25;
+// A router nobody has heard from yet gets this long to answer, as RFC 6886 suggests; routers on
+// the LAN usually answer in a few milliseconds.
+pub const INITIAL_READ_TIMEOUT_MILLIS: u64 = 250;
+// Each timeout doubles the wait for the retransmitted request, up to this. Some ISP-supplied
+// routers take several seconds to answer.
+pub const MAX_READ_TIMEOUT_MILLIS: u64 = 6000;
+pub const MIN_READ_TIMEOUT_MILLIS: u64 = 50;
+// Once a router has answered, its read timeout is this many times its smoothed round-trip time
+pub const RTT_READ_TIMEOUT_MULTIPLIER: u32 = 4;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct MappingConfig {
@@ -40,6 +62,252 @@ impl MappingConfig {
     }
 }
 
+pub fn lifetime_reduction(old: Duration, new: Duration) -> Option<AutomapChange> {
+    if new <= old * (100 - MATERIAL_LIFETIME_REDUCTION_PERCENT) / 100 {
+        Some(AutomapChange::LifetimeReduced { old, new })
+    } else {
+        None
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct ManagedMapping {
+    config: MappingConfig,
+    last_remapped: Instant,
+}
+
+impl ManagedMapping {
+    fn is_due(&self, now: Instant) -> bool {
+        now.duration_since(self.last_remapped) > self.config.remap_interval
+    }
+
+    fn is_nearly_due(&self, now: Instant) -> bool {
+        now.duration_since(self.last_remapped)
+            >= self.config.remap_interval * REMAP_BATCHING_THRESHOLD_PERCENT / 100
+    }
+}
+
+// The set of port mappings a housekeeping thread keeps alive. Each mapping has its own lifetime
+// and remap schedule; there is never more than one mapping per hole port.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MappingConfigs {
+    mappings: Vec<ManagedMapping>,
+}
+
+impl MappingConfigs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mappings.is_empty()
+    }
+
+    pub fn hole_ports(&self) -> Vec<u16> {
+        self.mappings
+            .iter()
+            .map(|mapping| mapping.config.hole_port)
+            .collect()
+    }
+
+    pub fn get(&self, hole_port: u16) -> Option<&MappingConfig> {
+        self.mappings
+            .iter()
+            .find(|mapping| mapping.config.hole_port == hole_port)
+            .map(|mapping| &mapping.config)
+    }
+
+    pub fn add(&mut self, mapping_config: MappingConfig) {
+        self.add_as_of(mapping_config, Instant::now())
+    }
+
+    // Replaces any mapping already present for the same hole port.
+    pub fn add_as_of(&mut self, mapping_config: MappingConfig, last_remapped: Instant) {
+        let mapping = ManagedMapping {
+            config: mapping_config,
+            last_remapped,
+        };
+        match self
+            .mappings
+            .iter_mut()
+            .find(|existing| existing.config.hole_port == mapping_config.hole_port)
+        {
+            Some(existing) => *existing = mapping,
+            None => self.mappings.push(mapping),
+        }
+    }
+
+    pub fn remove(&mut self, hole_port: u16) -> Option<MappingConfig> {
+        let index = self
+            .mappings
+            .iter()
+            .position(|mapping| mapping.config.hole_port == hole_port)?;
+        Some(self.mappings.remove(index).config)
+    }
+
+    pub fn set_remap_interval(&mut self, remap_interval: Duration) {
+        self.mappings
+            .iter_mut()
+            .for_each(|mapping| mapping.config.remap_interval = remap_interval)
+    }
+
+    // If any mapping is due for remapping, hands it--along with every other mapping that is
+    // nearly due--to remap, in the order the mappings were added.
+    pub fn remap_due<F>(&mut self, mut remap: F)
+    where
+        F: FnMut(&mut MappingConfig),
+    {
+        let now = Instant::now();
+        if !self.mappings.iter().any(|mapping| mapping.is_due(now)) {
+            return;
+        }
+        self.mappings
+            .iter_mut()
+            .filter(|mapping| mapping.is_nearly_due(now))
+            .for_each(|mapping| {
+                remap(&mut mapping.config);
+                mapping.last_remapped = Instant::now();
+            })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RouterTiming {
+    pub smoothed_rtt_opt: Option<Duration>,
+    pub read_timeout: Duration,
+}
+
+impl Default for RouterTiming {
+    fn default() -> Self {
+        Self {
+            smoothed_rtt_opt: None,
+            read_timeout: Duration::from_millis(INITIAL_READ_TIMEOUT_MILLIS),
+        }
+    }
+}
+
+// Remembers how quickly each router answers, so that the PCP and PMP transactors wait long enough
+// for a slow router but don't sit out a long timeout for a fast one that dropped a packet.
+#[derive(Debug, Default)]
+pub struct ReadTimeoutCalibrator {
+    timings: Mutex<HashMap<IpAddr, RouterTiming>>,
+}
+
+impl ReadTimeoutCalibrator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn timing(&self, router_ip: IpAddr) -> RouterTiming {
+        self.timings().get(&router_ip).copied().unwrap_or_default()
+    }
+
+    // Smoothed the way TCP smooths its round-trip time (RFC 6298), so one slow answer doesn't
+    // throw the timeout off
+    pub fn record_answer(&self, router_ip: IpAddr, rtt: Duration) {
+        let mut timings = self.timings();
+        let timing = timings.entry(router_ip).or_default();
+        let smoothed_rtt = match timing.smoothed_rtt_opt {
+            Some(smoothed_rtt) => (smoothed_rtt * 7 + rtt) / 8,
+            None => rtt,
+        };
+        timing.smoothed_rtt_opt = Some(smoothed_rtt);
+        timing.read_timeout = Self::clamp(smoothed_rtt * RTT_READ_TIMEOUT_MULTIPLIER);
+    }
+
+    // Doubles the router's read timeout, unless it was already as long as it's allowed to be: in
+    // that case it's left alone and false is returned.
+    pub fn record_timeout(&self, router_ip: IpAddr) -> bool {
+        let mut timings = self.timings();
+        let timing = timings.entry(router_ip).or_default();
+        if timing.read_timeout >= Duration::from_millis(MAX_READ_TIMEOUT_MILLIS) {
+            return false;
+        }
+        timing.read_timeout = Self::clamp(timing.read_timeout * 2);
+        true
+    }
+
+    fn clamp(read_timeout: Duration) -> Duration {
+        read_timeout.clamp(
+            Duration::from_millis(MIN_READ_TIMEOUT_MILLIS),
+            Duration::from_millis(MAX_READ_TIMEOUT_MILLIS),
+        )
+    }
+
+    fn timings(&self) -> MutexGuard<HashMap<IpAddr, RouterTiming>> {
+        self.timings
+            .lock()
+            .expect("ReadTimeoutCalibrator is poisoned")
+    }
+}
+
+// What a housekeeping thread has done since it started, so that an operator can see whether the
+// keep-alive loop is really holding the hole open. None of the housekeeping loops listens for router
+// announcements at the moment, so announcements_received stays at zero until one does.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HousekeepingStats {
+    pub remaps: u64,
+    pub last_successful_remap_opt: Option<SystemTime>,
+    pub announcements_received: u64,
+    pub errors: u64,
+}
+
+impl HousekeepingStats {
+    pub fn record_remap(&mut self, now: SystemTime) {
+        self.remaps += 1;
+        self.last_successful_remap_opt = Some(now);
+    }
+
+    pub fn record_announcement(&mut self) {
+        self.announcements_received += 1;
+    }
+
+    pub fn record_error(&mut self) {
+        self.errors += 1;
+    }
+}
+
+#[derive(Debug)]
+pub enum ExchangeError {
+    Send(io::Error),
+    Receive(io::Error),
+    TimedOut(Duration),
+}
+
+// Sends a request to the router and waits for its answer, retransmitting with a longer read
+// timeout each time one expires, until a wait of MAX_READ_TIMEOUT_MILLIS has expired too. Returns
+// the length of the answer, which is left in the buffer.
+pub fn exchange_with_router(
+    socket: &dyn UdpSocketWrapper,
+    router_addr: SocketAddr,
+    request: &[u8],
+    buffer: &mut [u8],
+    calibrator: &ReadTimeoutCalibrator,
+) -> Result<usize, ExchangeError> {
+    let started = Instant::now();
+    loop {
+        socket
+            .set_read_timeout(Some(calibrator.timing(router_addr.ip()).read_timeout))
+            .expect("set_read_timeout failed");
+        let sent_at = Instant::now();
+        socket
+            .send_to(request, router_addr)
+            .map_err(ExchangeError::Send)?;
+        match socket.recv_from(buffer) {
+            Ok((len, _)) => {
+                calibrator.record_answer(router_addr.ip(), sent_at.elapsed());
+                return Ok(len);
+            }
+            Err(e) if (e.kind() == ErrorKind::WouldBlock) || (e.kind() == ErrorKind::TimedOut) => {
+                if !calibrator.record_timeout(router_addr.ip()) {
+                    return Err(ExchangeError::TimedOut(started.elapsed()));
+                }
+            }
+            Err(e) => return Err(ExchangeError::Receive(e)),
+        }
+    }
+}
+
 pub trait UdpSocketWrapper: Send {
     fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)>;
     fn send_to(&self, buf: &[u8], addr: SocketAddr) -> io::Result<usize>;
@@ -174,7 +442,10 @@ pub fn make_local_socket_address(is_ipv4: bool, free_port: u16) -> SocketAddr {
 #[cfg(test)]
 pub mod tests {
     use super::*;
+    use crate::mocks::UdpSocketWrapperMock;
     use masq_lib::utils::localhost;
+    use std::str::FromStr;
+    use std::sync::Arc;
 
     #[test]
     fn change_handler_config_next_lifetime_secs_handles_greater_than_one_second() {
@@ -202,6 +473,318 @@ pub mod tests {
         assert_eq!(result, 0);
     }
 
+    #[test]
+    fn lifetime_reduction_reports_only_material_reductions() {
+        let old = Duration::from_secs(3600);
+
+        assert_eq!(lifetime_reduction(old, Duration::from_secs(3600)), None);
+        assert_eq!(lifetime_reduction(old, Duration::from_secs(7200)), None);
+        assert_eq!(lifetime_reduction(old, Duration::from_secs(2701)), None);
+        assert_eq!(
+            lifetime_reduction(old, Duration::from_secs(2700)),
+            Some(AutomapChange::LifetimeReduced {
+                old,
+                new: Duration::from_secs(2700)
+            })
+        );
+        assert_eq!(
+            lifetime_reduction(old, Duration::from_secs(60)),
+            Some(AutomapChange::LifetimeReduced {
+                old,
+                new: Duration::from_secs(60)
+            })
+        );
+    }
+
+    #[test]
+    fn read_timeout_constants_have_correct_values() {
+        assert_eq!(INITIAL_READ_TIMEOUT_MILLIS, 250);
+        assert_eq!(MAX_READ_TIMEOUT_MILLIS, 6000);
+        assert_eq!(MIN_READ_TIMEOUT_MILLIS, 50);
+        assert_eq!(RTT_READ_TIMEOUT_MULTIPLIER, 4);
+    }
+
+    #[test]
+    fn read_timeout_calibrator_starts_low_and_backs_off_to_the_maximum() {
+        let router_ip = IpAddr::from_str("1.2.3.4").unwrap();
+        let subject = ReadTimeoutCalibrator::new();
+
+        let mut read_timeouts = vec![subject.timing(router_ip).read_timeout];
+        while subject.record_timeout(router_ip) {
+            read_timeouts.push(subject.timing(router_ip).read_timeout);
+        }
+
+        assert_eq!(
+            read_timeouts,
+            vec![250, 500, 1000, 2000, 4000, 6000]
+                .into_iter()
+                .map(Duration::from_millis)
+                .collect::<Vec<Duration>>()
+        );
+        assert_eq!(subject.timing(router_ip).smoothed_rtt_opt, None);
+    }
+
+    #[test]
+    fn read_timeout_calibrator_follows_each_routers_smoothed_round_trip_time() {
+        let fast_router_ip = IpAddr::from_str("1.2.3.4").unwrap();
+        let slow_router_ip = IpAddr::from_str("5.6.7.8").unwrap();
+        let subject = ReadTimeoutCalibrator::new();
+
+        subject.record_answer(fast_router_ip, Duration::from_millis(2));
+        subject.record_answer(slow_router_ip, Duration::from_millis(800));
+        subject.record_answer(slow_router_ip, Duration::from_millis(1600));
+
+        assert_eq!(
+            subject.timing(fast_router_ip),
+            RouterTiming {
+                smoothed_rtt_opt: Some(Duration::from_millis(2)),
+                read_timeout: Duration::from_millis(MIN_READ_TIMEOUT_MILLIS),
+            }
+        );
+        assert_eq!(
+            subject.timing(slow_router_ip),
+            RouterTiming {
+                smoothed_rtt_opt: Some(Duration::from_millis(900)),
+                read_timeout: Duration::from_millis(3600),
+            }
+        );
+        assert_eq!(
+            subject.timing(IpAddr::from_str("9.9.9.9").unwrap()),
+            RouterTiming::default()
+        );
+    }
+
+    #[test]
+    fn housekeeping_stats_accumulate() {
+        let mut subject = HousekeepingStats::default();
+        let first_remap = SystemTime::now();
+        let second_remap = first_remap + Duration::from_secs(60);
+
+        subject.record_remap(first_remap);
+        subject.record_error();
+        subject.record_announcement();
+        subject.record_remap(second_remap);
+        subject.record_error();
+
+        assert_eq!(
+            subject,
+            HousekeepingStats {
+                remaps: 2,
+                last_successful_remap_opt: Some(second_remap),
+                announcements_received: 1,
+                errors: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn exchange_with_router_retransmits_with_longer_timeouts_until_the_router_answers() {
+        let router_addr = SocketAddr::from_str("1.2.3.4:5351").unwrap();
+        let set_read_timeout_params_arc = Arc::new(Mutex::new(vec![]));
+        let send_to_params_arc = Arc::new(Mutex::new(vec![]));
+        let socket = UdpSocketWrapperMock::new()
+            .set_read_timeout_params(&set_read_timeout_params_arc)
+            .set_read_timeout_result(Ok(()))
+            .set_read_timeout_result(Ok(()))
+            .set_read_timeout_result(Ok(()))
+            .send_to_params(&send_to_params_arc)
+            .send_to_result(Ok(3))
+            .send_to_result(Ok(3))
+            .send_to_result(Ok(3))
+            .recv_from_result(Err(io::Error::from(ErrorKind::WouldBlock)), vec![])
+            .recv_from_result(Err(io::Error::from(ErrorKind::TimedOut)), vec![])
+            .recv_from_result(Ok((2, router_addr)), vec![4, 5]);
+        let calibrator = ReadTimeoutCalibrator::new();
+        let mut buffer = [0u8; 10];
+
+        let result =
+            exchange_with_router(&socket, router_addr, &[1, 2, 3], &mut buffer, &calibrator);
+
+        assert_eq!(result.unwrap(), 2);
+        assert_eq!(&buffer[0..2], &[4, 5]);
+        assert_eq!(
+            *set_read_timeout_params_arc.lock().unwrap(),
+            vec![
+                Some(Duration::from_millis(250)),
+                Some(Duration::from_millis(500)),
+                Some(Duration::from_millis(1000))
+            ]
+        );
+        assert_eq!(
+            *send_to_params_arc.lock().unwrap(),
+            vec![(vec![1, 2, 3], router_addr); 3]
+        );
+        assert_eq!(
+            calibrator
+                .timing(router_addr.ip())
+                .smoothed_rtt_opt
+                .is_some(),
+            true
+        );
+    }
+
+    #[test]
+    fn exchange_with_router_gives_up_after_a_timeout_at_the_maximum() {
+        let router_addr = SocketAddr::from_str("1.2.3.4:5351").unwrap();
+        let calibrator = ReadTimeoutCalibrator::new();
+        while calibrator.record_timeout(router_addr.ip()) {}
+        let socket = UdpSocketWrapperMock::new()
+            .set_read_timeout_result(Ok(()))
+            .send_to_result(Ok(3))
+            .recv_from_result(Err(io::Error::from(ErrorKind::WouldBlock)), vec![]);
+
+        let result = exchange_with_router(
+            &socket,
+            router_addr,
+            &[1, 2, 3],
+            &mut [0u8; 10],
+            &calibrator,
+        );
+
+        match result {
+            Err(ExchangeError::TimedOut(_)) => (),
+            x => panic!("Expected TimedOut; got {:?}", x),
+        }
+    }
+
+    #[test]
+    fn exchange_with_router_reports_send_and_receive_errors_without_retrying() {
+        let router_addr = SocketAddr::from_str("1.2.3.4:5351").unwrap();
+        let calibrator = ReadTimeoutCalibrator::new();
+        let send_failure = UdpSocketWrapperMock::new()
+            .set_read_timeout_result(Ok(()))
+            .send_to_result(Err(io::Error::from(ErrorKind::PermissionDenied)));
+        let receive_failure = UdpSocketWrapperMock::new()
+            .set_read_timeout_result(Ok(()))
+            .send_to_result(Ok(3))
+            .recv_from_result(Err(io::Error::from(ErrorKind::ConnectionReset)), vec![]);
+
+        let send_result = exchange_with_router(
+            &send_failure,
+            router_addr,
+            &[1, 2, 3],
+            &mut [0u8; 10],
+            &calibrator,
+        );
+        let receive_result = exchange_with_router(
+            &receive_failure,
+            router_addr,
+            &[1, 2, 3],
+            &mut [0u8; 10],
+            &calibrator,
+        );
+
+        match send_result {
+            Err(ExchangeError::Send(e)) => assert_eq!(e.kind(), ErrorKind::PermissionDenied),
+            x => panic!("Expected Send error; got {:?}", x),
+        }
+        match receive_result {
+            Err(ExchangeError::Receive(e)) => assert_eq!(e.kind(), ErrorKind::ConnectionReset),
+            x => panic!("Expected Receive error; got {:?}", x),
+        }
+        assert_eq!(calibrator.timing(router_addr.ip()), RouterTiming::default());
+    }
+
+    fn make_mapping_config(hole_port: u16, remap_interval_secs: u64) -> MappingConfig {
+        MappingConfig {
+            hole_port,
+            next_lifetime: Duration::from_secs(remap_interval_secs * 2),
+            remap_interval: Duration::from_secs(remap_interval_secs),
+        }
+    }
+
+    #[test]
+    fn mapping_configs_keep_one_mapping_per_hole_port() {
+        let mut subject = MappingConfigs::new();
+        assert!(subject.is_empty());
+
+        subject.add(make_mapping_config(1111, 10));
+        subject.add(make_mapping_config(2222, 20));
+        subject.add(make_mapping_config(1111, 30));
+
+        assert_eq!(subject.hole_ports(), vec![1111, 2222]);
+        assert_eq!(subject.get(1111), Some(&make_mapping_config(1111, 30)));
+        assert_eq!(subject.get(3333), None);
+        assert_eq!(subject.remove(1111), Some(make_mapping_config(1111, 30)));
+        assert_eq!(subject.remove(1111), None);
+        assert_eq!(subject.hole_ports(), vec![2222]);
+    }
+
+    #[test]
+    fn mapping_configs_set_remap_interval_applies_to_every_mapping() {
+        let mut subject = MappingConfigs::new();
+        subject.add(make_mapping_config(1111, 10));
+        subject.add(make_mapping_config(2222, 20));
+
+        subject.set_remap_interval(Duration::from_millis(1234));
+
+        assert_eq!(
+            subject.get(1111).unwrap().remap_interval,
+            Duration::from_millis(1234)
+        );
+        assert_eq!(
+            subject.get(2222).unwrap().remap_interval,
+            Duration::from_millis(1234)
+        );
+    }
+
+    #[test]
+    fn mapping_configs_remap_nothing_until_some_mapping_is_due() {
+        let mut subject = MappingConfigs::new();
+        let now = Instant::now();
+        subject.add_as_of(
+            make_mapping_config(1111, 100),
+            now - Duration::from_secs(90),
+        );
+        subject.add_as_of(
+            make_mapping_config(2222, 100),
+            now - Duration::from_secs(80),
+        );
+        let mut remapped = vec![];
+
+        subject.remap_due(|mapping_config| remapped.push(mapping_config.hole_port));
+
+        assert!(remapped.is_empty());
+    }
+
+    #[test]
+    fn mapping_configs_remap_nearly_due_mappings_along_with_due_ones() {
+        let mut subject = MappingConfigs::new();
+        let now = Instant::now();
+        subject.add_as_of(
+            make_mapping_config(1111, 100),
+            now - Duration::from_secs(60),
+        );
+        subject.add_as_of(
+            make_mapping_config(2222, 100),
+            now - Duration::from_secs(101),
+        );
+        subject.add_as_of(
+            make_mapping_config(3333, 100),
+            now - Duration::from_secs(40),
+        );
+        subject.add_as_of(make_mapping_config(4444, 10), now - Duration::from_secs(5));
+        let mut remapped = vec![];
+
+        subject.remap_due(|mapping_config| {
+            remapped.push(mapping_config.hole_port);
+            mapping_config.remap_interval = Duration::from_secs(50);
+        });
+
+        assert_eq!(remapped, vec![1111, 2222, 4444]);
+        assert_eq!(
+            subject.get(2222).unwrap().remap_interval,
+            Duration::from_secs(50)
+        );
+        assert_eq!(
+            subject.get(3333).unwrap().remap_interval,
+            Duration::from_secs(100)
+        );
+        let mut remapped_again = vec![];
+        subject.remap_due(|mapping_config| remapped_again.push(mapping_config.hole_port));
+        assert!(remapped_again.is_empty());
+    }
+
     #[test]
     fn free_port_factory_works() {
         let subject = FreePortFactoryReal::new();
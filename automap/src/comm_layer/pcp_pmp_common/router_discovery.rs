@@ -0,0 +1,673 @@
+// Copyright (c) 2019-2021, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::comm_layer::pcp_pmp_common::{find_routers, make_local_socket_address, ROUTER_PORT};
+use crate::comm_layer::{AutomapError, AutomapErrorCause, LocalIpFinder, LocalIpFinderReal};
+use crate::protocols::pcp::pcp_packet::{Opcode as PcpOpcode, PcpPacket};
+use crate::protocols::pmp::get_packet::GetOpcodeData;
+use crate::protocols::pmp::pmp_packet::{Opcode as PmpOpcode, PmpPacket};
+use crate::protocols::utils::{Direction, Packet};
+use masq_lib::debug;
+use masq_lib::logger::Logger;
+use masq_lib::utils::AutomapProtocol;
+use std::convert::TryFrom;
+use std::fs;
+use std::io::ErrorKind;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Candidates are probed all at once, so this is about how long discovery takes when some of them
+// never answer.
+pub const PROBE_TIMEOUT_MILLIS: u64 = 1000;
+
+#[cfg(target_os = "linux")]
+const DHCP_LEASE_DIRECTORIES: &[&str] = &[
+    "/var/lib/dhcp",
+    "/var/lib/dhclient",
+    "/run/systemd/netif/leases",
+];
+#[cfg(not(target_os = "linux"))]
+const DHCP_LEASE_DIRECTORIES: &[&str] = &[];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RouterCandidateSource {
+    DefaultGateway,
+    DhcpLease,
+    SubnetGuess,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RouterCandidate {
+    pub router_ip: IpAddr,
+    pub source: RouterCandidateSource,
+}
+
+pub trait RouterDiscoverer: Send {
+    fn discover(&self) -> Result<Vec<IpAddr>, AutomapError>;
+}
+
+pub trait DefaultGatewayFinder: Send {
+    fn find(&self) -> Result<Vec<IpAddr>, AutomapError>;
+}
+
+pub struct DefaultGatewayFinderReal {}
+
+impl DefaultGatewayFinder for DefaultGatewayFinderReal {
+    fn find(&self) -> Result<Vec<IpAddr>, AutomapError> {
+        find_routers()
+    }
+}
+
+impl DefaultGatewayFinderReal {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Default for DefaultGatewayFinderReal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub trait DhcpLeaseReader: Send {
+    fn read_leases(&self) -> Vec<String>;
+}
+
+pub struct DhcpLeaseReaderReal {}
+
+impl DhcpLeaseReader for DhcpLeaseReaderReal {
+    fn read_leases(&self) -> Vec<String> {
+        DHCP_LEASE_DIRECTORIES
+            .iter()
+            .filter_map(|directory| fs::read_dir(directory).ok())
+            .flatten()
+            .flatten()
+            .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+            .collect()
+    }
+}
+
+impl DhcpLeaseReaderReal {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Default for DhcpLeaseReaderReal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Must be shareable among threads, because every candidate is probed on a thread of its own
+pub trait RouterProber: Send + Sync {
+    fn probe(
+        &self,
+        router_ip: IpAddr,
+        client_ip_opt: Option<IpAddr>,
+    ) -> Result<Duration, AutomapError>;
+}
+
+pub struct RouterProberReal {
+    protocol: AutomapProtocol,
+    router_port: u16,
+    timeout: Duration,
+}
+
+impl RouterProber for RouterProberReal {
+    fn probe(
+        &self,
+        router_ip: IpAddr,
+        client_ip_opt: Option<IpAddr>,
+    ) -> Result<Duration, AutomapError> {
+        let mut buffer = [0u8; 1100];
+        let len = self.make_request(client_ip_opt, &mut buffer);
+        // Port 0, because a free port found ahead of time might be taken by a sibling probe
+        let local_address = make_local_socket_address(router_ip.is_ipv4(), 0);
+        let socket = UdpSocket::bind(local_address)
+            .map_err(|e| AutomapError::SocketBindingError(format!("{:?}", e), local_address))?;
+        let router_addr = SocketAddr::new(router_ip, self.router_port);
+        let started = Instant::now();
+        socket.send_to(&buffer[0..len], router_addr).map_err(|e| {
+            AutomapError::SocketSendError(AutomapErrorCause::Unknown(format!("{:?}", e)))
+        })?;
+        loop {
+            let remaining = match self.timeout.checked_sub(started.elapsed()) {
+                Some(remaining) if remaining > Duration::from_millis(0) => remaining,
+                _ => return Err(self.no_answer(router_ip)),
+            };
+            socket
+                .set_read_timeout(Some(remaining))
+                .expect("set_read_timeout failed");
+            match socket.recv_from(&mut buffer) {
+                Ok((len, from)) if from.ip() == router_ip && self.is_answer(&buffer[0..len]) => {
+                    return Ok(started.elapsed())
+                }
+                Ok(_) => continue,
+                Err(e)
+                    if (e.kind() == ErrorKind::WouldBlock) || (e.kind() == ErrorKind::TimedOut) =>
+                {
+                    return Err(self.no_answer(router_ip))
+                }
+                Err(e) => {
+                    return Err(AutomapError::SocketReceiveError(
+                        AutomapErrorCause::Unknown(format!("{:?}", e)),
+                    ))
+                }
+            }
+        }
+    }
+}
+
+impl RouterProberReal {
+    pub fn new(protocol: AutomapProtocol) -> Self {
+        if protocol == AutomapProtocol::Igdp {
+            panic!("IGDP finds its routers by itself; there's nothing to probe")
+        }
+        Self {
+            protocol,
+            router_port: ROUTER_PORT,
+            timeout: Duration::from_millis(PROBE_TIMEOUT_MILLIS),
+        }
+    }
+
+    // An ANNOUNCE for PCP and a request for the public IP for PMP: neither changes anything on
+    // the router, and any router that speaks the protocol has to answer them.
+    fn make_request(&self, client_ip_opt: Option<IpAddr>, buffer: &mut [u8]) -> usize {
+        let marshal_result = match self.protocol {
+            AutomapProtocol::Pcp => PcpPacket {
+                direction: Direction::Request,
+                opcode: PcpOpcode::Announce,
+                client_ip_opt,
+                ..Default::default()
+            }
+            .marshal(buffer),
+            AutomapProtocol::Pmp => PmpPacket {
+                direction: Direction::Request,
+                opcode: PmpOpcode::Get,
+                result_code_opt: None,
+                opcode_data: Box::new(GetOpcodeData {
+                    epoch_opt: None,
+                    external_ip_address_opt: None,
+                }),
+            }
+            .marshal(buffer),
+            AutomapProtocol::Igdp => unreachable!("IGDP prober was constructed"),
+        };
+        marshal_result.expect("Bad packet construction")
+    }
+
+    // Error result codes count: a router that refuses the request in the protocol still speaks it
+    fn is_answer(&self, buffer: &[u8]) -> bool {
+        match self.protocol {
+            AutomapProtocol::Pcp => match PcpPacket::try_from(buffer) {
+                Ok(packet) => {
+                    packet.direction == Direction::Response && packet.opcode == PcpOpcode::Announce
+                }
+                Err(_) => false,
+            },
+            AutomapProtocol::Pmp => match PmpPacket::try_from(buffer) {
+                Ok(packet) => {
+                    packet.direction == Direction::Response && packet.opcode == PmpOpcode::Get
+                }
+                Err(_) => false,
+            },
+            AutomapProtocol::Igdp => unreachable!("IGDP prober was constructed"),
+        }
+    }
+
+    fn no_answer(&self, router_ip: IpAddr) -> AutomapError {
+        AutomapError::FindRouterError(format!(
+            "No {} answer from {} within {}ms",
+            self.protocol,
+            router_ip,
+            self.timeout.as_millis()
+        ))
+    }
+}
+
+// The default gateway isn't always the router that will map ports: machines with several network
+// interfaces have several gateways, and behind a double NAT the router that speaks PCP or PMP may
+// be upstream of the one the default route points to. So every plausible router address is
+// probed at once, and the ones that answer come first, fastest first.
+pub struct RouterDiscovererReal {
+    default_gateway_finder: Box<dyn DefaultGatewayFinder>,
+    dhcp_lease_reader: Box<dyn DhcpLeaseReader>,
+    local_ip_finder: Box<dyn LocalIpFinder>,
+    prober: Arc<dyn RouterProber>,
+    logger: Logger,
+}
+
+impl RouterDiscoverer for RouterDiscovererReal {
+    fn discover(&self) -> Result<Vec<IpAddr>, AutomapError> {
+        let default_gateways_result = self.default_gateway_finder.find();
+        let local_ip_opt = self.local_ip_finder.find().ok();
+        let candidates = gather_candidates(
+            default_gateways_result.clone().unwrap_or_default(),
+            dhcp_routers(&self.dhcp_lease_reader.read_leases()),
+            local_ip_opt,
+        );
+        debug!(self.logger, "Probing router candidates {:?}", candidates);
+        let outcomes = self.probe_candidates(&candidates, local_ip_opt);
+        let routers = rank_candidates(&candidates, &outcomes);
+        debug!(self.logger, "Routers in order of preference: {:?}", routers);
+        match default_gateways_result {
+            Err(e) if routers.is_empty() => Err(e),
+            _ => Ok(routers),
+        }
+    }
+}
+
+impl RouterDiscovererReal {
+    pub fn new(protocol: AutomapProtocol) -> Self {
+        Self {
+            default_gateway_finder: Box::new(DefaultGatewayFinderReal::new()),
+            dhcp_lease_reader: Box::new(DhcpLeaseReaderReal::new()),
+            local_ip_finder: Box::new(LocalIpFinderReal::new()),
+            prober: Arc::new(RouterProberReal::new(protocol)),
+            logger: Logger::new(&format!("{}RouterDiscoverer", protocol)),
+        }
+    }
+
+    fn probe_candidates(
+        &self,
+        candidates: &[RouterCandidate],
+        client_ip_opt: Option<IpAddr>,
+    ) -> Vec<Result<Duration, AutomapError>> {
+        let handles: Vec<_> = candidates
+            .iter()
+            .map(|candidate| {
+                let prober = self.prober.clone();
+                let router_ip = candidate.router_ip;
+                thread::spawn(move || prober.probe(router_ip, client_ip_opt))
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("Router probe panicked"))
+            .collect()
+    }
+}
+
+pub fn gather_candidates(
+    default_gateways: Vec<IpAddr>,
+    dhcp_gateways: Vec<IpAddr>,
+    local_ip_opt: Option<IpAddr>,
+) -> Vec<RouterCandidate> {
+    let guesses = local_ip_opt.map(subnet_guesses).unwrap_or_default();
+    let tagged = |router_ips: Vec<IpAddr>, source: RouterCandidateSource| {
+        router_ips
+            .into_iter()
+            .map(move |router_ip| RouterCandidate { router_ip, source })
+    };
+    let init: Vec<RouterCandidate> = vec![];
+    tagged(default_gateways, RouterCandidateSource::DefaultGateway)
+        .chain(tagged(dhcp_gateways, RouterCandidateSource::DhcpLease))
+        .chain(tagged(guesses, RouterCandidateSource::SubnetGuess))
+        .filter(|candidate| Some(candidate.router_ip) != local_ip_opt)
+        .fold(init, |mut so_far, candidate| {
+            if !so_far
+                .iter()
+                .any(|existing| existing.router_ip == candidate.router_ip)
+            {
+                so_far.push(candidate);
+            }
+            so_far
+        })
+}
+
+// Home routers nearly always take the first or the last address on their subnet, and the usual
+// factory defaults are worth a try too: on a double NAT they're often the upstream router.
+pub fn subnet_guesses(local_ip: IpAddr) -> Vec<IpAddr> {
+    let local_ipv4 = match local_ip {
+        IpAddr::V4(ip) if ip.is_private() => ip,
+        _ => return vec![],
+    };
+    let [a, b, c, _] = local_ipv4.octets();
+    let mut guesses = vec![Ipv4Addr::new(a, b, c, 1), Ipv4Addr::new(a, b, c, 254)];
+    match (a, b) {
+        (192, 168) => {
+            guesses.extend([Ipv4Addr::new(192, 168, 0, 1), Ipv4Addr::new(192, 168, 1, 1)])
+        }
+        (10, _) => guesses.push(Ipv4Addr::new(10, 0, 0, 1)),
+        (172, _) => guesses.push(Ipv4Addr::new(172, b, 0, 1)),
+        _ => (),
+    }
+    guesses.into_iter().map(IpAddr::V4).collect()
+}
+
+// Understands dhclient lease files ("option routers 192.168.0.1;") and systemd-networkd lease
+// files ("ROUTER=192.168.0.1").
+pub fn dhcp_routers(leases: &[String]) -> Vec<IpAddr> {
+    leases
+        .iter()
+        .flat_map(|lease| lease.lines())
+        .map(|line| line.trim())
+        .flat_map(|line| {
+            let router_ip_strs: Vec<&str> = if let Some(rest) = line.strip_prefix("option routers ")
+            {
+                rest.trim_end_matches(';').split(',').collect()
+            } else if let Some(rest) = line.strip_prefix("ROUTER=") {
+                rest.split_whitespace().collect()
+            } else {
+                vec![]
+            };
+            router_ip_strs
+                .into_iter()
+                .filter_map(|router_ip_str| router_ip_str.trim().parse::<IpAddr>().ok())
+                .collect::<Vec<IpAddr>>()
+        })
+        .collect()
+}
+
+// Candidates that answered come first, fastest first. Default gateways that didn't answer follow,
+// so that the protocol still gets its chance to report what's wrong with them; other candidates
+// that didn't answer are most likely not routers at all, and are dropped.
+pub fn rank_candidates(
+    candidates: &[RouterCandidate],
+    outcomes: &[Result<Duration, AutomapError>],
+) -> Vec<IpAddr> {
+    let mut answered: Vec<(Duration, usize)> = outcomes
+        .iter()
+        .enumerate()
+        .filter_map(|(index, outcome)| outcome.as_ref().ok().map(|rtt| (*rtt, index)))
+        .collect();
+    answered.sort();
+    let silent_default_gateways = candidates
+        .iter()
+        .zip(outcomes.iter())
+        .filter(|(candidate, outcome)| {
+            outcome.is_err() && candidate.source == RouterCandidateSource::DefaultGateway
+        })
+        .map(|(candidate, _)| candidate.router_ip);
+    answered
+        .into_iter()
+        .map(|(_, index)| candidates[index].router_ip)
+        .chain(silent_default_gateways)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mocks::{
+        DefaultGatewayFinderMock, DhcpLeaseReaderMock, LocalIpFinderMock, RouterProberMock,
+    };
+    use masq_lib::utils::{find_free_port, localhost};
+    use std::str::FromStr;
+    use std::sync::Mutex;
+
+    fn ip(ip_str: &str) -> IpAddr {
+        IpAddr::from_str(ip_str).unwrap()
+    }
+
+    fn ips(ip_strs: Vec<&str>) -> Vec<IpAddr> {
+        ip_strs.into_iter().map(ip).collect()
+    }
+
+    fn candidate(ip_str: &str, source: RouterCandidateSource) -> RouterCandidate {
+        RouterCandidate {
+            router_ip: ip(ip_str),
+            source,
+        }
+    }
+
+    fn make_subject(
+        default_gateway_finder: DefaultGatewayFinderMock,
+        leases: Vec<&str>,
+        local_ip_result: Result<IpAddr, AutomapError>,
+        prober: RouterProberMock,
+    ) -> RouterDiscovererReal {
+        RouterDiscovererReal {
+            default_gateway_finder: Box::new(default_gateway_finder),
+            dhcp_lease_reader: Box::new(
+                DhcpLeaseReaderMock::new()
+                    .read_leases_result(leases.into_iter().map(|s| s.to_string()).collect()),
+            ),
+            local_ip_finder: Box::new(LocalIpFinderMock::new().find_result(local_ip_result)),
+            prober: Arc::new(prober),
+            logger: Logger::new("RouterDiscoverer"),
+        }
+    }
+
+    fn run_fake_router(response: Vec<u8>) -> (u16, Arc<Mutex<Vec<Vec<u8>>>>) {
+        let router_port = find_free_port();
+        let router_socket = UdpSocket::bind(SocketAddr::new(localhost(), router_port)).unwrap();
+        let requests_arc = Arc::new(Mutex::new(vec![]));
+        let inner_requests_arc = requests_arc.clone();
+        thread::spawn(move || {
+            let mut buffer = [0u8; 1100];
+            let (len, from) = router_socket.recv_from(&mut buffer).unwrap();
+            inner_requests_arc
+                .lock()
+                .unwrap()
+                .push(buffer[0..len].to_vec());
+            router_socket.send_to(&response, from).unwrap();
+        });
+        (router_port, requests_arc)
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(PROBE_TIMEOUT_MILLIS, 1000);
+    }
+
+    #[test]
+    fn subnet_guesses_cover_the_usual_router_addresses() {
+        assert_eq!(
+            subnet_guesses(ip("192.168.5.23")),
+            ips(vec![
+                "192.168.5.1",
+                "192.168.5.254",
+                "192.168.0.1",
+                "192.168.1.1"
+            ])
+        );
+        assert_eq!(
+            subnet_guesses(ip("10.1.2.3")),
+            ips(vec!["10.1.2.1", "10.1.2.254", "10.0.0.1"])
+        );
+        assert_eq!(
+            subnet_guesses(ip("172.20.1.2")),
+            ips(vec!["172.20.1.1", "172.20.1.254", "172.20.0.1"])
+        );
+    }
+
+    #[test]
+    fn subnet_guesses_are_not_made_for_public_or_ipv6_addresses() {
+        assert_eq!(subnet_guesses(ip("1.2.3.4")), vec![]);
+        assert_eq!(subnet_guesses(ip("fe80::1")), vec![]);
+    }
+
+    #[test]
+    fn dhcp_routers_understands_dhclient_and_networkd_leases() {
+        let dhclient_lease = "lease {\n  interface \"eth0\";\n  fixed-address 192.168.0.44;\n  \
+            option subnet-mask 255.255.255.0;\n  option routers 192.168.0.1,192.168.0.2;\n}\n"
+            .to_string();
+        let networkd_lease =
+            "# This is private data. Do not parse.\nADDRESS=10.0.0.44\nROUTER=10.0.0.1 10.0.0.2\n"
+                .to_string();
+        let garbage = "option routers booga;\nROUTER=\n".to_string();
+
+        let result = dhcp_routers(&[dhclient_lease, networkd_lease, garbage]);
+
+        assert_eq!(
+            result,
+            ips(vec!["192.168.0.1", "192.168.0.2", "10.0.0.1", "10.0.0.2"])
+        );
+    }
+
+    #[test]
+    fn gather_candidates_removes_duplicates_and_the_local_ip() {
+        let result = gather_candidates(
+            ips(vec!["192.168.0.1", "192.168.0.1"]),
+            ips(vec!["192.168.0.1", "192.168.0.254", "172.16.0.1"]),
+            Some(ip("192.168.0.254")),
+        );
+
+        assert_eq!(
+            result,
+            vec![
+                candidate("192.168.0.1", RouterCandidateSource::DefaultGateway),
+                candidate("172.16.0.1", RouterCandidateSource::DhcpLease),
+                candidate("192.168.1.1", RouterCandidateSource::SubnetGuess),
+            ]
+        );
+    }
+
+    #[test]
+    fn rank_candidates_puts_fast_answers_first_and_drops_silent_guesses() {
+        let candidates = vec![
+            candidate("192.168.0.1", RouterCandidateSource::DefaultGateway),
+            candidate("10.0.0.1", RouterCandidateSource::DefaultGateway),
+            candidate("172.16.0.1", RouterCandidateSource::DhcpLease),
+            candidate("192.168.0.254", RouterCandidateSource::SubnetGuess),
+            candidate("192.168.1.1", RouterCandidateSource::SubnetGuess),
+        ];
+        let no_answer = Err(AutomapError::FindRouterError("No answer".to_string()));
+        let outcomes = vec![
+            no_answer.clone(),
+            Ok(Duration::from_millis(30)),
+            no_answer.clone(),
+            no_answer,
+            Ok(Duration::from_millis(5)),
+        ];
+
+        let result = rank_candidates(&candidates, &outcomes);
+
+        assert_eq!(result, ips(vec!["192.168.1.1", "10.0.0.1", "192.168.0.1"]));
+    }
+
+    #[test]
+    fn discover_probes_every_candidate_at_once_and_ranks_them() {
+        let probe_params_arc = Arc::new(Mutex::new(vec![]));
+        let prober = RouterProberMock::new()
+            .probe_params(&probe_params_arc)
+            .probe_delay(Duration::from_millis(300))
+            .probe_result(ip("192.168.0.1"), Ok(Duration::from_millis(40)))
+            .probe_result(ip("192.168.1.1"), Ok(Duration::from_millis(10)));
+        let subject = make_subject(
+            DefaultGatewayFinderMock::new().find_result(Ok(ips(vec!["192.168.0.1"]))),
+            vec!["option routers 192.168.0.2;"],
+            Ok(ip("192.168.0.44")),
+            prober,
+        );
+        let started = Instant::now();
+
+        let result = subject.discover();
+
+        let elapsed = started.elapsed();
+        assert_eq!(result, Ok(ips(vec!["192.168.1.1", "192.168.0.1"])));
+        assert!(
+            elapsed < Duration::from_millis(900),
+            "Four probes of 300ms each took {:?}",
+            elapsed
+        );
+        let mut probe_params = probe_params_arc.lock().unwrap().clone();
+        probe_params.sort();
+        let client_ip_opt = Some(ip("192.168.0.44"));
+        assert_eq!(
+            *probe_params,
+            vec![
+                (ip("192.168.0.1"), client_ip_opt),
+                (ip("192.168.0.2"), client_ip_opt),
+                (ip("192.168.0.254"), client_ip_opt),
+                (ip("192.168.1.1"), client_ip_opt),
+            ]
+        );
+    }
+
+    #[test]
+    fn discover_finds_a_router_even_when_the_default_gateway_cant_be_found() {
+        let prober =
+            RouterProberMock::new().probe_result(ip("10.0.0.1"), Ok(Duration::from_millis(10)));
+        let subject = make_subject(
+            DefaultGatewayFinderMock::new().find_result(Err(AutomapError::CantFindDefaultGateway)),
+            vec![],
+            Ok(ip("10.1.2.3")),
+            prober,
+        );
+
+        let result = subject.discover();
+
+        assert_eq!(result, Ok(ips(vec!["10.0.0.1"])));
+    }
+
+    #[test]
+    fn discover_reports_default_gateway_error_when_nothing_else_answers() {
+        let subject = make_subject(
+            DefaultGatewayFinderMock::new().find_result(Err(AutomapError::CantFindDefaultGateway)),
+            vec![],
+            Err(AutomapError::NoLocalIpAddress),
+            RouterProberMock::new(),
+        );
+
+        let result = subject.discover();
+
+        assert_eq!(result, Err(AutomapError::CantFindDefaultGateway));
+    }
+
+    #[test]
+    fn pmp_prober_times_an_answer_to_a_get_request() {
+        let (router_port, requests_arc) = run_fake_router(vec![
+            0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05,
+        ]);
+        let subject = RouterProberReal {
+            router_port,
+            ..RouterProberReal::new(AutomapProtocol::Pmp)
+        };
+
+        let result = subject.probe(localhost(), None);
+
+        assert!(result.unwrap() < Duration::from_millis(PROBE_TIMEOUT_MILLIS));
+        assert_eq!(*requests_arc.lock().unwrap(), vec![vec![0x00, 0x00]]);
+    }
+
+    #[test]
+    fn pcp_prober_counts_an_error_answer_to_an_announce_request() {
+        let mut response = vec![0x02, 0x80, 0x00, 0x08]; // NO_RESOURCES
+        response.extend(vec![0u8; 20]);
+        let (router_port, requests_arc) = run_fake_router(response);
+        let subject = RouterProberReal {
+            router_port,
+            ..RouterProberReal::new(AutomapProtocol::Pcp)
+        };
+
+        let result = subject.probe(localhost(), Some(ip("192.168.0.44")));
+
+        assert!(result.is_ok());
+        let requests = requests_arc.lock().unwrap();
+        let request = PcpPacket::try_from(requests[0].as_slice()).unwrap();
+        assert_eq!(request.opcode, PcpOpcode::Announce);
+        assert_eq!(request.direction, Direction::Request);
+        assert_eq!(request.lifetime, 0);
+        assert_eq!(request.client_ip_opt, Some(ip("192.168.0.44")));
+    }
+
+    #[test]
+    fn prober_gives_up_on_a_router_that_answers_in_the_wrong_protocol() {
+        let (router_port, _) = run_fake_router(vec![0x00, 0x80, 0x00, 0x00]);
+        let subject = RouterProberReal {
+            router_port,
+            timeout: Duration::from_millis(200),
+            ..RouterProberReal::new(AutomapProtocol::Pcp)
+        };
+
+        let result = subject.probe(localhost(), None);
+
+        assert_eq!(
+            result,
+            Err(AutomapError::FindRouterError(
+                "No PCP answer from 127.0.0.1 within 200ms".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "IGDP finds its routers by itself; there's nothing to probe")]
+    fn igdp_prober_cannot_be_constructed() {
+        let _ = RouterProberReal::new(AutomapProtocol::Igdp);
+    }
+}
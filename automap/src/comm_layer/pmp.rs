@@ -2,12 +2,11 @@
 
 use std::any::Any;
 use std::convert::TryFrom;
-use std::io::ErrorKind;
 use std::net::{IpAddr, SocketAddr};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, MutexGuard};
 use std::thread;
 use std::thread::JoinHandle;
-use std::time::{Duration, Instant};
+use std::time::{Duration, SystemTime};
 
 use crossbeam_channel::{unbounded, Receiver, Sender};
 
@@ -15,10 +14,12 @@ use masq_lib::logger::Logger;
 use masq_lib::utils::AutomapProtocol;
 use masq_lib::{debug, error, info, warning};
 
+use crate::comm_layer::pcp_pmp_common::router_discovery::{RouterDiscoverer, RouterDiscovererReal};
 use crate::comm_layer::pcp_pmp_common::{
-    find_routers, make_local_socket_address, FreePortFactory, FreePortFactoryReal, MappingConfig,
-    UdpSocketFactoryReal, UdpSocketWrapperFactory, HOUSEKEEPING_THREAD_LOOP_DELAY_MILLIS,
-    ROUTER_PORT,
+    exchange_with_router, lifetime_reduction, make_local_socket_address, ExchangeError,
+    FreePortFactory, FreePortFactoryReal, HousekeepingStats, MappingConfig, MappingConfigs,
+    ReadTimeoutCalibrator, RouterTiming, UdpSocketFactoryReal, UdpSocketWrapperFactory,
+    HOUSEKEEPING_THREAD_LOOP_DELAY_MILLIS, ROUTER_PORT,
 };
 use crate::comm_layer::{AutomapError, AutomapErrorCause, HousekeepingThreadCommand, Transactor};
 use crate::control_layer::automap_control::{AutomapChange, ChangeHandler};
@@ -27,11 +28,10 @@ use crate::protocols::pmp::map_packet::MapOpcodeData;
 use crate::protocols::pmp::pmp_packet::{Opcode, PmpPacket, ResultCode};
 use crate::protocols::utils::{Direction, Packet};
 
-const PMP_READ_TIMEOUT_MS: u64 = 3000;
-
 struct Factories {
     socket_factory: Box<dyn UdpSocketWrapperFactory>,
     free_port_factory: Box<dyn FreePortFactory>,
+    read_timeout_calibrator: Arc<ReadTimeoutCalibrator>,
 }
 
 impl Default for Factories {
@@ -39,6 +39,7 @@ impl Default for Factories {
         Self {
             socket_factory: Box::new(UdpSocketFactoryReal::new()),
             free_port_factory: Box::new(FreePortFactoryReal::new()),
+            read_timeout_calibrator: Arc::new(ReadTimeoutCalibrator::new()),
         }
     }
 }
@@ -47,8 +48,10 @@ pub struct PmpTransactor {
     mapping_adder_arc: Arc<Mutex<Box<dyn MappingAdder>>>,
     factories_arc: Arc<Mutex<Factories>>,
     router_port: u16,
+    router_discoverer: Box<dyn RouterDiscoverer>,
     housekeeper_commander_opt: Option<Sender<HousekeepingThreadCommand>>,
     join_handle_opt: Option<JoinHandle<ChangeHandler>>,
+    housekeeping_stats_arc: Arc<Mutex<HousekeepingStats>>,
     read_timeout_millis: u64,
     logger: Logger,
 }
@@ -56,7 +59,7 @@ pub struct PmpTransactor {
 impl Transactor for PmpTransactor {
     fn find_routers(&self) -> Result<Vec<IpAddr>, AutomapError> {
         debug!(self.logger, "Seeking routers on LAN");
-        find_routers()
+        self.router_discoverer.discover()
     }
 
     fn get_public_ip(&self, router_ip: IpAddr) -> Result<IpAddr, AutomapError> {
@@ -77,7 +80,6 @@ impl Transactor for PmpTransactor {
             &self.factories_arc,
             SocketAddr::new(router_ip, self.router_port),
             &request,
-            PMP_READ_TIMEOUT_MS,
             &self.logger,
         )?;
         match response
@@ -118,7 +120,7 @@ impl Transactor for PmpTransactor {
         };
         self.mapping_adder_arc
             .lock()
-            .expect("Housekeeping thread is dead")
+            .expect("Housekeeping stats are dead")
             .add_mapping(
                 &self.factories_arc,
                 SocketAddr::new(router_ip, self.router_port),
@@ -127,11 +129,9 @@ impl Transactor for PmpTransactor {
             .map(|remap_interval| {
                 self.housekeeper_commander_opt
                     .as_ref()
-                    .expect("Housekeeping thread is dead")
-                    .send(HousekeepingThreadCommand::InitializeMappingConfig(
-                        mapping_config,
-                    ))
-                    .expect("Housekeeping thread is dead");
+                    .expect("Housekeeping stats are dead")
+                    .send(HousekeepingThreadCommand::AddMappingConfig(mapping_config))
+                    .expect("Housekeeping stats are dead");
                 remap_interval
             })
     }
@@ -150,6 +150,11 @@ impl Transactor for PmpTransactor {
             "Deleting mapping of port {} through router at {}", hole_port, router_ip
         );
         self.add_mapping(router_ip, hole_port, 0)?;
+        self.housekeeper_commander_opt
+            .as_ref()
+            .expect("Housekeeping stats are dead")
+            .send(HousekeepingThreadCommand::RemoveMappingConfig(hole_port))
+            .expect("Housekeeping stats are dead");
         Ok(())
     }
 
@@ -157,6 +162,20 @@ impl Transactor for PmpTransactor {
         AutomapProtocol::Pmp
     }
 
+    fn router_timing_opt(&self, router_ip: IpAddr) -> Option<RouterTiming> {
+        let factories = self.factories_arc.lock().expect("Factories are dead");
+        Some(factories.read_timeout_calibrator.timing(router_ip))
+    }
+
+    fn housekeeping_stats(&self) -> Option<HousekeepingStats> {
+        Some(
+            *self
+                .housekeeping_stats_arc
+                .lock()
+                .expect("Housekeeping stats are dead"),
+        )
+    }
+
     fn start_housekeeping_thread(
         &mut self,
         change_handler: ChangeHandler,
@@ -171,6 +190,10 @@ impl Transactor for PmpTransactor {
         }
         let (tx, rx) = unbounded();
         self.housekeeper_commander_opt = Some(tx.clone());
+        *self
+            .housekeeping_stats_arc
+            .lock()
+            .expect("Housekeeping stats are dead") = HousekeepingStats::default();
         let thread_guts = ThreadGuts::new(self, router_ip, change_handler, rx);
         self.join_handle_opt = Some(thread_guts.go());
         Ok(tx)
@@ -218,9 +241,11 @@ impl Default for PmpTransactor {
             mapping_adder_arc: Arc::new(Mutex::new(Box::new(MappingAdderReal::default()))),
             factories_arc: Arc::new(Mutex::new(Factories::default())),
             router_port: ROUTER_PORT,
+            router_discoverer: Box::new(RouterDiscovererReal::new(AutomapProtocol::Pmp)),
             housekeeper_commander_opt: None,
             read_timeout_millis: HOUSEKEEPING_THREAD_LOOP_DELAY_MILLIS,
             join_handle_opt: None,
+            housekeeping_stats_arc: Arc::new(Mutex::new(HousekeepingStats::default())),
             logger: Logger::new("PmpTransactor"),
         }
     }
@@ -235,21 +260,20 @@ impl PmpTransactor {
         factories_arc: &Arc<Mutex<Factories>>,
         router_addr: SocketAddr,
         request: &PmpPacket,
-        read_timeout_ms: u64,
         logger: &Logger,
     ) -> Result<PmpPacket, AutomapError> {
         let mut buffer = [0u8; 1100];
         let len = request
             .marshal(&mut buffer)
             .expect("Bad packet construction");
-        let socket = {
+        let (socket, read_timeout_calibrator) = {
             let factories = factories_arc.lock().expect("Factories are dead");
             let local_address = make_local_socket_address(
                 router_addr.ip().is_ipv4(),
                 factories.free_port_factory.make(),
             );
             match factories.socket_factory.make(local_address) {
-                Ok(s) => s,
+                Ok(s) => (s, factories.read_timeout_calibrator.clone()),
                 Err(e) => {
                     warning!(
                         logger,
@@ -264,29 +288,33 @@ impl PmpTransactor {
                 }
             }
         };
-        socket
-            .set_read_timeout(Some(Duration::from_millis(read_timeout_ms)))
-            .expect("set_read_timeout failed");
-        if let Err(e) = socket.send_to(&buffer[0..len], router_addr) {
-            warning!(
-                logger,
-                "Error transmitting to router at {}: \"{:?}\"",
-                router_addr,
-                e
-            );
-            return Err(AutomapError::SocketSendError(AutomapErrorCause::Unknown(
-                format!("{:?}", e),
-            )));
-        }
-        let (len, _) = match socket.recv_from(&mut buffer) {
+        let request_bytes = buffer[0..len].to_vec();
+        let len = match exchange_with_router(
+            socket.as_ref(),
+            router_addr,
+            &request_bytes,
+            &mut buffer,
+            &read_timeout_calibrator,
+        ) {
             Ok(len) => len,
-            Err(e) if (e.kind() == ErrorKind::WouldBlock) || (e.kind() == ErrorKind::TimedOut) => {
+            Err(ExchangeError::Send(e)) => {
+                warning!(
+                    logger,
+                    "Error transmitting to router at {}: \"{:?}\"",
+                    router_addr,
+                    e
+                );
+                return Err(AutomapError::SocketSendError(AutomapErrorCause::Unknown(
+                    format!("{:?}", e),
+                )));
+            }
+            Err(ExchangeError::TimedOut(elapsed)) => {
                 return Err(AutomapError::ProtocolError(format!(
                     "Timed out after {}ms",
-                    read_timeout_ms
+                    elapsed.as_millis()
                 )))
             }
-            Err(e) => {
+            Err(ExchangeError::Receive(e)) => {
                 warning!(
                     logger,
                     "Error receiving from router at {}: \"{:?}\"",
@@ -320,6 +348,7 @@ struct ThreadGuts {
     factories_arc: Arc<Mutex<Factories>>,
     router_addr: SocketAddr,
     change_handler: ChangeHandler,
+    housekeeping_stats_arc: Arc<Mutex<HousekeepingStats>>,
     read_timeout_millis: u64,
     logger: Logger,
 }
@@ -337,6 +366,7 @@ impl ThreadGuts {
             factories_arc: transactor.factories_arc.clone(),
             router_addr: SocketAddr::new(router_ip, transactor.router_port),
             change_handler,
+            housekeeping_stats_arc: transactor.housekeeping_stats_arc.clone(),
             read_timeout_millis: transactor.read_timeout_millis,
             logger: transactor.logger.clone(),
         }
@@ -347,28 +377,23 @@ impl ThreadGuts {
     }
 
     fn thread_guts(mut self) -> ChangeHandler {
-        let mut last_remapped = Instant::now();
-        let mut mapping_config_opt = None;
-        while self.thread_guts_iteration(&mut mapping_config_opt, &mut last_remapped) {}
+        let mut mapping_configs = MappingConfigs::new();
+        while self.thread_guts_iteration(&mut mapping_configs) {}
         self.change_handler
     }
 
-    fn thread_guts_iteration(
-        &mut self,
-        mapping_config_opt: &mut Option<MappingConfig>,
-        last_remapped: &mut Instant,
-    ) -> bool {
-        if let Some(mapping_config) = mapping_config_opt {
-            self.maybe_remap(mapping_config, last_remapped);
-        }
+    fn thread_guts_iteration(&mut self, mapping_configs: &mut MappingConfigs) -> bool {
+        self.maybe_remap(mapping_configs);
         match self.housekeeper_flunkie.try_recv() {
             Ok(HousekeepingThreadCommand::Stop) => return false,
             Ok(HousekeepingThreadCommand::SetRemapIntervalMs(remap_after)) => {
-                mapping_config_opt
-                    .map(|mut mc| mc.remap_interval = Duration::from_millis(remap_after));
+                mapping_configs.set_remap_interval(Duration::from_millis(remap_after))
+            }
+            Ok(HousekeepingThreadCommand::AddMappingConfig(mapping_config)) => {
+                mapping_configs.add(mapping_config);
             }
-            Ok(HousekeepingThreadCommand::InitializeMappingConfig(mapping_config)) => {
-                mapping_config_opt.replace(mapping_config);
+            Ok(HousekeepingThreadCommand::RemoveMappingConfig(hole_port)) => {
+                mapping_configs.remove(hole_port);
             }
             Err(_) => (),
         };
@@ -376,24 +401,47 @@ impl ThreadGuts {
         true
     }
 
-    fn maybe_remap(&self, mapping_config: &mut MappingConfig, last_remapped: &mut Instant) {
-        let since_last_remapped = last_remapped.elapsed();
-        if since_last_remapped.gt(&mapping_config.remap_interval) {
+    fn maybe_remap(&self, mapping_configs: &mut MappingConfigs) {
+        mapping_configs.remap_due(|mapping_config| {
             let mapping_adder = self
                 .mapping_adder_arc
                 .lock()
                 .expect("PmpTransactor is dead");
-            if let Err(e) = self.remap_port(mapping_adder.as_ref(), mapping_config) {
-                error!(
-                    &self.logger,
-                    "Automatic PMP remapping failed for port {}: {:?})",
-                    mapping_config.hole_port,
-                    e
-                );
-                self.change_handler.as_ref()(AutomapChange::Error(e));
+            let previous_lifetime = mapping_config.next_lifetime;
+            match self.remap_port(mapping_adder.as_ref(), mapping_config) {
+                Ok(_) => {
+                    self.housekeeping_stats().record_remap(SystemTime::now());
+                    if let Some(change) =
+                        lifetime_reduction(previous_lifetime, mapping_config.next_lifetime)
+                    {
+                        warning!(
+                            &self.logger,
+                            "Router cut the lifetime of port {} from {}s to {}s",
+                            mapping_config.hole_port,
+                            previous_lifetime.as_secs(),
+                            mapping_config.next_lifetime.as_secs()
+                        );
+                        self.change_handler.as_ref()(change);
+                    }
+                }
+                Err(e) => {
+                    self.housekeeping_stats().record_error();
+                    error!(
+                        &self.logger,
+                        "Automatic PMP remapping failed for port {}: {:?})",
+                        mapping_config.hole_port,
+                        e
+                    );
+                    self.change_handler.as_ref()(AutomapChange::Error(e));
+                }
             }
-            *last_remapped = Instant::now();
-        }
+        })
+    }
+
+    fn housekeeping_stats(&self) -> MutexGuard<HousekeepingStats> {
+        self.housekeeping_stats_arc
+            .lock()
+            .expect("Housekeeping stats are dead")
     }
 
     fn remap_port(
@@ -456,13 +504,7 @@ impl MappingAdder for MappingAdderReal {
                 lifetime: mapping_config.next_lifetime_secs(),
             }),
         };
-        let response = PmpTransactor::transact(
-            factories_arc,
-            router_addr,
-            &request,
-            PMP_READ_TIMEOUT_MS,
-            &self.logger,
-        )?;
+        let response = PmpTransactor::transact(factories_arc, router_addr, &request, &self.logger)?;
         if response.direction == Direction::Request {
             let e = AutomapError::ProtocolError("Map response labeled as request".to_string());
             warning!(
@@ -521,7 +563,7 @@ mod tests {
     use std::ops::Sub;
     use std::str::FromStr;
     use std::sync::{Arc, Mutex};
-    use std::time::Duration;
+    use std::time::{Duration, Instant};
     use std::{io, thread};
 
     use lazy_static::lazy_static;
@@ -529,10 +571,13 @@ mod tests {
     use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
     use masq_lib::utils::AutomapProtocol;
 
-    use crate::comm_layer::pcp_pmp_common::MappingConfig;
+    use crate::comm_layer::pcp_pmp_common::{MappingConfig, INITIAL_READ_TIMEOUT_MILLIS};
     use crate::comm_layer::AutomapErrorCause;
     use crate::control_layer::automap_control::AutomapChange;
-    use crate::mocks::{FreePortFactoryMock, UdpSocketWrapperFactoryMock, UdpSocketWrapperMock};
+    use crate::mocks::{
+        FreePortFactoryMock, RouterDiscovererMock, UdpSocketWrapperFactoryMock,
+        UdpSocketWrapperMock,
+    };
     use crate::protocols::pmp::get_packet::GetOpcodeData;
     use crate::protocols::pmp::map_packet::MapOpcodeData;
     use crate::protocols::pmp::pmp_packet::{Opcode, PmpOpcodeData, PmpPacket, ResultCode};
@@ -548,6 +593,7 @@ mod tests {
     struct MappingAdderMock {
         add_mapping_params: Arc<Mutex<Vec<(Arc<Mutex<Factories>>, SocketAddr, MappingConfig)>>>,
         add_mapping_results: RefCell<Vec<Result<u32, AutomapError>>>,
+        granted_lifetimes: RefCell<Vec<Duration>>,
     }
 
     impl MappingAdder for MappingAdderMock {
@@ -560,6 +606,9 @@ mod tests {
             let result = self.add_mapping_results.borrow_mut().remove(0);
             if let Ok(remap_interval) = &result {
                 mapping_config.remap_interval = Duration::from_secs(*remap_interval as u64);
+                if !self.granted_lifetimes.borrow().is_empty() {
+                    mapping_config.next_lifetime = self.granted_lifetimes.borrow_mut().remove(0);
+                }
             }
             self.add_mapping_params.lock().unwrap().push((
                 factories_arc.clone(),
@@ -575,6 +624,7 @@ mod tests {
             Self {
                 add_mapping_params: Default::default(),
                 add_mapping_results: Default::default(),
+                granted_lifetimes: Default::default(),
             }
         }
 
@@ -590,6 +640,12 @@ mod tests {
             self.add_mapping_results.borrow_mut().push(result);
             self
         }
+
+        // The lifetime the router grants in response to each successful add_mapping
+        fn granted_lifetime(self, lifetime: Duration) -> Self {
+            self.granted_lifetimes.borrow_mut().push(lifetime);
+            self
+        }
     }
 
     #[test]
@@ -710,7 +766,22 @@ mod tests {
 
         let result = subject.find_routers().unwrap();
 
-        assert_eq!(result.len(), 1);
+        assert!(result.len() > 0)
+    }
+
+    #[test]
+    fn find_routers_asks_the_router_discoverer() {
+        let router_ips = vec![
+            IpAddr::from_str("192.168.1.1").unwrap(),
+            IpAddr::from_str("192.168.0.1").unwrap(),
+        ];
+        let mut subject = PmpTransactor::default();
+        subject.router_discoverer =
+            Box::new(RouterDiscovererMock::new().discover_result(Ok(router_ips.clone())));
+
+        let result = subject.find_routers();
+
+        assert_eq!(result, Ok(router_ips));
     }
 
     #[test]
@@ -1028,7 +1099,7 @@ mod tests {
         let set_read_timeout_params = set_read_timeout_params_arc.lock().unwrap();
         assert_eq!(
             *set_read_timeout_params,
-            vec![Some(Duration::from_millis(3000))]
+            vec![Some(Duration::from_millis(INITIAL_READ_TIMEOUT_MILLIS))]
         );
         let send_to_params = send_to_params_arc.lock().unwrap();
         assert_eq!(
@@ -1039,7 +1110,9 @@ mod tests {
             )]
         );
         let recv_from_params = recv_from_params_arc.lock().unwrap();
-        assert_eq!(*recv_from_params, vec![()])
+        assert_eq!(*recv_from_params, vec![()]);
+        let router_timing = subject.router_timing_opt(router_ip).unwrap();
+        assert_eq!(router_timing.smoothed_rtt_opt.is_some(), true);
     }
 
     #[test]
@@ -1113,7 +1186,7 @@ mod tests {
         let set_read_timeout_params = set_read_timeout_params_arc.lock().unwrap();
         assert_eq!(
             *set_read_timeout_params,
-            vec![Some(Duration::from_millis(3000))]
+            vec![Some(Duration::from_millis(INITIAL_READ_TIMEOUT_MILLIS))]
         );
         let send_to_params = send_to_params_arc.lock().unwrap();
         assert_eq!(
@@ -1252,7 +1325,7 @@ mod tests {
         let set_read_timeout_params = set_read_timeout_params_arc.lock().unwrap();
         assert_eq!(
             *set_read_timeout_params,
-            vec![Some(Duration::from_millis(3000))]
+            vec![Some(Duration::from_millis(INITIAL_READ_TIMEOUT_MILLIS))]
         );
         let send_to_params = send_to_params_arc.lock().unwrap();
         assert_eq!(
@@ -1288,6 +1361,30 @@ mod tests {
         assert_eq!(change_log.last().unwrap(), &change)
     }
 
+    #[test]
+    fn start_housekeeping_thread_starts_housekeeping_stats_afresh() {
+        let mut subject = PmpTransactor::default();
+        subject
+            .housekeeping_stats_arc
+            .lock()
+            .unwrap()
+            .record_remap(SystemTime::now());
+        subject
+            .housekeeping_stats_arc
+            .lock()
+            .unwrap()
+            .record_error();
+
+        let _ = subject
+            .start_housekeeping_thread(Box::new(|_| ()), IpAddr::from_str("1.2.3.4").unwrap());
+
+        assert_eq!(
+            subject.housekeeping_stats(),
+            Some(HousekeepingStats::default())
+        );
+        let _ = subject.stop_housekeeping_thread();
+    }
+
     #[test]
     #[should_panic(expected = "No HousekeepingCommander: can't stop housekeeping thread")]
     fn stop_housekeeping_thread_handles_missing_housekeeper_commander() {
@@ -1352,10 +1449,8 @@ mod tests {
         let mut subject = ThreadGuts::new(&transactor, ROUTER_ADDR.ip(), Box::new(move |_| {}), rx);
         subject.mapping_adder_arc = Arc::new(Mutex::new(mapping_adder));
         subject.logger = Logger::new("no_remap_test");
-        tx.send(HousekeepingThreadCommand::InitializeMappingConfig(
-            mapping_config,
-        ))
-        .unwrap();
+        tx.send(HousekeepingThreadCommand::AddMappingConfig(mapping_config))
+            .unwrap();
         tx.send(HousekeepingThreadCommand::SetRemapIntervalMs(10000))
             .unwrap();
         tx.send(HousekeepingThreadCommand::Stop).unwrap();
@@ -1388,18 +1483,23 @@ mod tests {
         subject.mapping_adder_arc = mapping_adder_arc;
         subject.factories_arc = Arc::new(Mutex::new(factories));
         subject.logger = Logger::new("timed_remap_test");
-        tx.send(HousekeepingThreadCommand::InitializeMappingConfig(
-            mapping_config,
-        ))
-        .unwrap();
+        tx.send(HousekeepingThreadCommand::AddMappingConfig(mapping_config))
+            .unwrap();
         tx.send(HousekeepingThreadCommand::SetRemapIntervalMs(80))
             .unwrap();
+        let before = SystemTime::now();
 
         let handle = subject.go();
 
         thread::sleep(Duration::from_millis(100));
         tx.send(HousekeepingThreadCommand::Stop).unwrap();
         let _ = handle.join().unwrap();
+        let after = SystemTime::now();
+        let stats = transactor.housekeeping_stats().unwrap();
+        assert_eq!(stats.remaps, 1);
+        let last_remap = stats.last_successful_remap_opt.unwrap();
+        assert!(before <= last_remap && last_remap <= after);
+        assert_eq!(stats.errors, 0);
         let add_mapping_params = add_mapping_params_arc.lock().unwrap().remove(0);
         assert_eq!(
             add_mapping_params
@@ -1422,6 +1522,46 @@ mod tests {
         TestLogHandler::new().exists_log_containing("INFO: timed_remap_test: Remapping port 6689");
     }
 
+    #[test]
+    fn thread_guts_iteration_remaps_nearly_due_mappings_together_and_forgets_removed_ones() {
+        let (tx, rx) = unbounded();
+        let add_mapping_params_arc = Arc::new(Mutex::new(vec![]));
+        let mapping_adder: Box<dyn MappingAdder> = Box::new(
+            MappingAdderMock::new()
+                .add_mapping_params(&add_mapping_params_arc)
+                .add_mapping_result(Ok(100))
+                .add_mapping_result(Ok(100)),
+        );
+        let make_mapping_config = |hole_port: u16| MappingConfig {
+            hole_port,
+            next_lifetime: Duration::from_secs(200),
+            remap_interval: Duration::from_secs(100),
+        };
+        let now = Instant::now();
+        let mut mapping_configs = MappingConfigs::new();
+        mapping_configs.add_as_of(make_mapping_config(7001), now.sub(Duration::from_secs(101)));
+        mapping_configs.add_as_of(make_mapping_config(7002), now.sub(Duration::from_secs(60)));
+        mapping_configs.add_as_of(make_mapping_config(7003), now.sub(Duration::from_secs(10)));
+        let transactor = PmpTransactor::new();
+        let mut subject = ThreadGuts::new(&transactor, ROUTER_ADDR.ip(), Box::new(|_| ()), rx);
+        subject.mapping_adder_arc = Arc::new(Mutex::new(mapping_adder));
+        subject.read_timeout_millis = 0;
+        tx.send(HousekeepingThreadCommand::RemoveMappingConfig(7001))
+            .unwrap();
+
+        let result = subject.thread_guts_iteration(&mut mapping_configs);
+
+        assert_eq!(result, true);
+        let remapped_ports = add_mapping_params_arc
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, _, mapping_config)| mapping_config.hole_port)
+            .collect::<Vec<u16>>();
+        assert_eq!(remapped_ports, vec![7001, 7002]);
+        assert_eq!(mapping_configs.hole_ports(), vec![7002, 7003]);
+    }
+
     #[test]
     fn maybe_remap_handles_remapping_error() {
         init_test_logging();
@@ -1437,12 +1577,15 @@ mod tests {
         let change_handler: ChangeHandler = Box::new(move |change| {
             change_records_arc_inner.lock().unwrap().push(change);
         });
-        let mut mapping_config = MappingConfig {
-            hole_port: 6689,
-            next_lifetime: Duration::from_secs(600),
-            remap_interval: Duration::from_secs(0),
-        };
-        let mut last_remapped = Instant::now().sub(Duration::from_secs(60));
+        let mut mapping_configs = MappingConfigs::new();
+        mapping_configs.add_as_of(
+            MappingConfig {
+                hole_port: 6689,
+                next_lifetime: Duration::from_secs(600),
+                remap_interval: Duration::from_secs(0),
+            },
+            Instant::now().sub(Duration::from_secs(60)),
+        );
         let logger = Logger::new("maybe_remap_handles_remapping_error");
         let transactor = PmpTransactor::new();
         let mut subject =
@@ -1451,7 +1594,7 @@ mod tests {
         // subject.factories_arc = factories_arc;
         subject.logger = logger;
 
-        subject.maybe_remap(&mut mapping_config, &mut last_remapped);
+        subject.maybe_remap(&mut mapping_configs);
 
         let change_records = change_records_arc.lock().unwrap();
         assert_eq!(
@@ -1460,11 +1603,94 @@ mod tests {
                 "Booga".to_string()
             ))]
         );
+        assert_eq!(
+            transactor.housekeeping_stats(),
+            Some(HousekeepingStats {
+                remaps: 0,
+                last_successful_remap_opt: None,
+                announcements_received: 0,
+                errors: 1,
+            })
+        );
         TestLogHandler::new().exists_log_containing(
             "ERROR: maybe_remap_handles_remapping_error: Automatic PMP remapping failed for port 6689: ProtocolError(\"Booga\")"
         );
     }
 
+    #[test]
+    fn maybe_remap_reports_materially_reduced_lifetime() {
+        init_test_logging();
+        let mapping_adder: Box<dyn MappingAdder> = Box::new(
+            MappingAdderMock::new()
+                .add_mapping_result(Ok(150))
+                .granted_lifetime(Duration::from_secs(300)),
+        );
+        let changes_arc = Arc::new(Mutex::new(vec![]));
+        let changes_arc_inner = changes_arc.clone();
+        let change_handler: ChangeHandler = Box::new(move |change| {
+            changes_arc_inner.lock().unwrap().push(change);
+        });
+        let mut mapping_configs = MappingConfigs::new();
+        mapping_configs.add_as_of(
+            MappingConfig {
+                hole_port: 6689,
+                next_lifetime: Duration::from_secs(600),
+                remap_interval: Duration::from_secs(0),
+            },
+            Instant::now().sub(Duration::from_secs(60)),
+        );
+        let transactor = PmpTransactor::new();
+        let mut subject =
+            ThreadGuts::new(&transactor, ROUTER_ADDR.ip(), change_handler, unbounded().1);
+        subject.mapping_adder_arc = Arc::new(Mutex::new(mapping_adder));
+        subject.logger = Logger::new("maybe_remap_reports_materially_reduced_lifetime");
+
+        subject.maybe_remap(&mut mapping_configs);
+
+        let changes = changes_arc.lock().unwrap();
+        assert_eq!(
+            *changes,
+            vec![AutomapChange::LifetimeReduced {
+                old: Duration::from_secs(600),
+                new: Duration::from_secs(300),
+            }]
+        );
+        TestLogHandler::new().exists_log_containing(
+            "WARN: maybe_remap_reports_materially_reduced_lifetime: Router cut the lifetime of port 6689 from 600s to 300s",
+        );
+    }
+
+    #[test]
+    fn maybe_remap_ignores_unchanged_lifetime() {
+        let mapping_adder: Box<dyn MappingAdder> = Box::new(
+            MappingAdderMock::new()
+                .add_mapping_result(Ok(300))
+                .granted_lifetime(Duration::from_secs(600)),
+        );
+        let changes_arc = Arc::new(Mutex::new(vec![]));
+        let changes_arc_inner = changes_arc.clone();
+        let change_handler: ChangeHandler = Box::new(move |change| {
+            changes_arc_inner.lock().unwrap().push(change);
+        });
+        let mut mapping_configs = MappingConfigs::new();
+        mapping_configs.add_as_of(
+            MappingConfig {
+                hole_port: 6689,
+                next_lifetime: Duration::from_secs(600),
+                remap_interval: Duration::from_secs(0),
+            },
+            Instant::now().sub(Duration::from_secs(60)),
+        );
+        let transactor = PmpTransactor::new();
+        let mut subject =
+            ThreadGuts::new(&transactor, ROUTER_ADDR.ip(), change_handler, unbounded().1);
+        subject.mapping_adder_arc = Arc::new(Mutex::new(mapping_adder));
+
+        subject.maybe_remap(&mut mapping_configs);
+
+        assert!(changes_arc.lock().unwrap().is_empty());
+    }
+
     #[test]
     fn remap_port_correctly_converts_lifetime_greater_than_one_second() {
         let add_mapping_params_arc = Arc::new(Mutex::new(vec![]));
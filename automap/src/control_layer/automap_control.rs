@@ -6,6 +6,7 @@ use crate::comm_layer::pmp::PmpTransactor;
 use crate::comm_layer::{
     AutomapError, HousekeepingThreadCommand, Transactor, DEFAULT_MAPPING_LIFETIME_SECONDS,
 };
+use crate::control_layer::change_coalescer::coalesce_changes;
 use crossbeam_channel::Sender;
 use masq_lib::debug;
 use masq_lib::logger::Logger;
@@ -14,18 +15,35 @@ use std::cell::{RefCell, RefMut};
 use std::collections::HashSet;
 use std::fmt::Debug;
 use std::net::IpAddr;
+use std::time::Duration;
 
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub enum AutomapChange {
     NewIp(IpAddr),
     Error(AutomapError),
+    // A remap was granted a lifetime materially shorter than the one before it. Routers sometimes
+    // whittle lifetimes down this way shortly before they drop support for a protocol.
+    LifetimeReduced { old: Duration, new: Duration },
 }
 
 pub type ChangeHandler = Box<dyn Fn(AutomapChange) + Send>;
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct EstablishedMapping {
+    pub protocol: AutomapProtocol,
+    pub router_ip: IpAddr,
+}
+
 pub trait AutomapControl {
     fn get_public_ip(&mut self) -> Result<IpAddr, AutomapError>;
     fn add_mapping(&mut self, hole_port: u16) -> Result<(), AutomapError>;
+    // Tries the protocols one after another--the one already in use or the usual one first--until
+    // one of them maps every hole port. Whatever a failing protocol managed to map is deleted
+    // before the next one is tried; if they all fail, the error says why each one did.
+    fn establish_mapping_with_fallback(
+        &mut self,
+        hole_ports: &[u16],
+    ) -> Result<EstablishedMapping, AutomapError>;
     fn delete_mappings(&mut self) -> Result<(), AutomapError>;
     fn get_mapping_protocol(&self) -> Option<AutomapProtocol>;
 }
@@ -101,6 +119,31 @@ impl AutomapControl for AutomapControlReal {
         Ok(())
     }
 
+    fn establish_mapping_with_fallback(
+        &mut self,
+        hole_ports: &[u16],
+    ) -> Result<EstablishedMapping, AutomapError> {
+        debug!(
+            self.logger,
+            "Establishing mappings for ports {:?}", hole_ports
+        );
+        let init: Result<EstablishedMapping, Vec<(AutomapProtocol, AutomapError)>> = Err(vec![]);
+        self.protocols_in_fallback_order()
+            .into_iter()
+            .fold(init, |so_far, protocol| match so_far {
+                Ok(established) => Ok(established),
+                Err(failures) => match self.establish_mapping_with(protocol, hole_ports) {
+                    Ok(established) => Ok(established),
+                    Err(e) => {
+                        debug!(self.logger, "Couldn't map ports with {}: {:?}", protocol, e);
+                        self.abandon_protocol();
+                        Err(plus(failures, (protocol, e)))
+                    }
+                },
+            })
+            .map_err(AutomapError::AllProtocolsFailed)
+    }
+
     fn delete_mappings(&mut self) -> Result<(), AutomapError> {
         match &self.inner_opt {
             None => Err(AutomapError::DeleteMappingError(
@@ -151,6 +194,19 @@ impl AutomapControlReal {
         }
     }
 
+    // Like new(), except that bursts of changes are held back and delivered to change_handler as
+    // their last change, once no further change has arrived for quiet_period
+    pub fn new_coalescing(
+        usual_protocol_opt: Option<AutomapProtocol>,
+        change_handler: ChangeHandler,
+        quiet_period: Duration,
+    ) -> Self {
+        Self::new(
+            usual_protocol_opt,
+            coalesce_changes(change_handler, quiet_period),
+        )
+    }
+
     fn maybe_start_housekeeper(
         &self,
         transactor: &mut dyn Transactor,
@@ -322,6 +378,79 @@ impl AutomapControlReal {
             })
     }
 
+    fn protocols_in_fallback_order(&self) -> Vec<AutomapProtocol> {
+        let transactors = self.transactors.borrow();
+        let protocol_in_use_opt = self
+            .inner_opt
+            .as_ref()
+            .map(|inner| transactors[inner.transactor_idx].protocol());
+        let init: Vec<AutomapProtocol> = vec![];
+        protocol_in_use_opt
+            .into_iter()
+            .chain(self.usual_protocol_opt)
+            .chain(transactors.iter().map(|transactor| transactor.protocol()))
+            .fold(init, |so_far, protocol| {
+                if so_far.contains(&protocol) {
+                    so_far
+                } else {
+                    plus(so_far, protocol)
+                }
+            })
+    }
+
+    fn establish_mapping_with(
+        &mut self,
+        protocol: AutomapProtocol,
+        hole_ports: &[u16],
+    ) -> Result<EstablishedMapping, AutomapError> {
+        let transactor_idx = Self::find_transactor_index(self.transactors.borrow_mut(), protocol);
+        let in_use =
+            self.inner_opt.as_ref().map(|inner| inner.transactor_idx) == Some(transactor_idx);
+        if !in_use {
+            self.abandon_protocol();
+            let experiment: TransactorExperiment<IpAddr> =
+                Box::new(|transactor: &dyn Transactor, router_ip: IpAddr| {
+                    transactor.get_public_ip(router_ip)
+                });
+            let (router_ip, _) = {
+                let mut transactors = self.transactors.borrow_mut();
+                self.try_protocol(transactors[transactor_idx].as_mut(), &experiment)?
+            };
+            self.inner_opt = Some(AutomapControlRealInner {
+                router_ip,
+                transactor_idx,
+            });
+        }
+        hole_ports
+            .iter()
+            .try_for_each(|hole_port| self.add_mapping(*hole_port))?;
+        Ok(EstablishedMapping {
+            protocol,
+            router_ip: self
+                .inner_opt
+                .as_ref()
+                .expect("inner disappeared")
+                .router_ip,
+        })
+    }
+
+    // Deletes whatever mappings the protocol in use made and stops its housekeeping thread, so
+    // that another protocol can start from scratch
+    fn abandon_protocol(&mut self) {
+        if let Some(inner) = self.inner_opt.take() {
+            let mut transactors = self.transactors.borrow_mut();
+            let transactor = transactors[inner.transactor_idx].as_mut();
+            self.hole_ports.drain().for_each(|hole_port| {
+                let _ = transactor.delete_mapping(inner.router_ip, hole_port);
+            });
+            let mut housekeeping_tools = self.housekeeping_tools.borrow_mut();
+            if housekeeping_tools.change_handler_opt.is_none() {
+                Self::put_change_handler_back(transactor, &mut housekeeping_tools);
+            }
+            housekeeping_tools.housekeeping_thread_commander_opt = None;
+        }
+    }
+
     fn calculate_protocol_info<T: PartialEq + Debug>(
         &mut self,
         experiment: TransactorExperiment<T>,
@@ -360,6 +489,8 @@ mod tests {
     use std::ptr::addr_of;
     use std::str::FromStr;
     use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Instant;
 
     fn choose_working_protocol_works_for_success(protocol: AutomapProtocol) {
         let mut subject = make_multirouter_specific_success_subject(
@@ -1236,6 +1367,149 @@ mod tests {
         );
     }
 
+    #[test]
+    fn establish_mapping_with_fallback_undoes_a_protocol_that_cant_map_every_port() {
+        let pcp_delete_mapping_params_arc = Arc::new(Mutex::new(vec![]));
+        let igdp_add_mapping_params_arc = Arc::new(Mutex::new(vec![]));
+        let (pcp_tx, _pcp_rx) = unbounded();
+        let (igdp_tx, igdp_rx) = unbounded();
+        let subject = replace_transactor(
+            make_null_subject(),
+            Box::new(
+                TransactorMock::new(AutomapProtocol::Pcp)
+                    .find_routers_result(Ok(vec![*ROUTER_IP]))
+                    .start_housekeeping_thread_result(Ok(pcp_tx))
+                    .get_public_ip_result(Ok(*PUBLIC_IP))
+                    .add_mapping_result(Ok(1000))
+                    .add_mapping_result(Err(AutomapError::TemporaryMappingError(
+                        "NoResources".to_string(),
+                    )))
+                    .delete_mapping_params(&pcp_delete_mapping_params_arc)
+                    .delete_mapping_result(Ok(()))
+                    .stop_housekeeping_thread_result(Ok(Box::new(|_| ()))),
+            ),
+        );
+        let subject = replace_transactor(
+            subject,
+            Box::new(TransactorMock::new(AutomapProtocol::Pmp).find_routers_result(Ok(vec![]))),
+        );
+        let mut subject = replace_transactor(
+            subject,
+            Box::new(
+                TransactorMock::new(AutomapProtocol::Igdp)
+                    .find_routers_result(Ok(vec![*ROUTER_IP]))
+                    .start_housekeeping_thread_result(Ok(igdp_tx))
+                    .get_public_ip_result(Ok(*PUBLIC_IP))
+                    .add_mapping_params(&igdp_add_mapping_params_arc)
+                    .add_mapping_result(Ok(1000))
+                    .add_mapping_result(Ok(1000)),
+            ),
+        );
+
+        let result = subject.establish_mapping_with_fallback(&[1234, 2345]);
+
+        assert_eq!(
+            result,
+            Ok(EstablishedMapping {
+                protocol: AutomapProtocol::Igdp,
+                router_ip: *ROUTER_IP,
+            })
+        );
+        assert_eq!(subject.get_mapping_protocol(), Some(AutomapProtocol::Igdp));
+        assert_eq!(
+            *pcp_delete_mapping_params_arc.lock().unwrap(),
+            vec![(*ROUTER_IP, 1234)]
+        );
+        assert_eq!(
+            *igdp_add_mapping_params_arc.lock().unwrap(),
+            vec![(*ROUTER_IP, 1234, 600), (*ROUTER_IP, 2345, 600)]
+        );
+        let mut hole_ports = subject.hole_ports.iter().copied().collect::<Vec<u16>>();
+        hole_ports.sort();
+        assert_eq!(hole_ports, vec![1234, 2345]);
+        assert_eq!(
+            igdp_rx.try_recv(),
+            Ok(HousekeepingThreadCommand::SetRemapIntervalMs(1000000))
+        );
+    }
+
+    #[test]
+    fn establish_mapping_with_fallback_keeps_to_the_protocol_already_in_use() {
+        let get_public_ip_params_arc = Arc::new(Mutex::new(vec![]));
+        let add_mapping_params_arc = Arc::new(Mutex::new(vec![]));
+        let start_housekeeping_thread_params_arc = Arc::new(Mutex::new(vec![]));
+        let (tx, _rx) = unbounded();
+        let mut subject = make_general_success_subject(
+            AutomapProtocol::Pmp,
+            &get_public_ip_params_arc,
+            &add_mapping_params_arc,
+            &start_housekeeping_thread_params_arc,
+            tx,
+        );
+        subject.get_public_ip().unwrap();
+
+        let result = subject.establish_mapping_with_fallback(&[4567]);
+
+        assert_eq!(
+            result,
+            Ok(EstablishedMapping {
+                protocol: AutomapProtocol::Pmp,
+                router_ip: *ROUTER_IP,
+            })
+        );
+        assert_eq!(*get_public_ip_params_arc.lock().unwrap(), vec![*ROUTER_IP]);
+        assert_eq!(
+            *add_mapping_params_arc.lock().unwrap(),
+            vec![(*ROUTER_IP, 4567, 600)]
+        );
+    }
+
+    #[test]
+    fn establish_mapping_with_fallback_reports_why_every_protocol_failed() {
+        let mut subject = make_general_failure_subject();
+
+        let result = subject.establish_mapping_with_fallback(&[1234]);
+
+        assert_all_protocols_failed(
+            result,
+            AutomapError::ProtocolError("Booga!".to_string()),
+            AutomapError::ProtocolError("Booga!".to_string()),
+            AutomapError::ProtocolError("Booga!".to_string()),
+        );
+        assert_eq!(subject.get_mapping_protocol(), None);
+    }
+
+    #[test]
+    fn new_coalescing_hands_housekeeper_a_coalescing_change_handler() {
+        let changes_arc = Arc::new(Mutex::new(vec![]));
+        let inner_changes_arc = changes_arc.clone();
+        let subject = AutomapControlReal::new_coalescing(
+            None,
+            Box::new(move |change| inner_changes_arc.lock().unwrap().push(change)),
+            Duration::from_millis(100),
+        );
+        let change_handler = subject
+            .housekeeping_tools
+            .borrow_mut()
+            .change_handler_opt
+            .take()
+            .unwrap();
+
+        change_handler(AutomapChange::Error(AutomapError::Unknown));
+        change_handler(AutomapChange::NewIp(*PUBLIC_IP));
+        drop(change_handler);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while changes_arc.lock().unwrap().is_empty() {
+            assert!(Instant::now() < deadline, "Change never arrived");
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        assert_eq!(
+            *changes_arc.lock().unwrap(),
+            vec![AutomapChange::NewIp(*PUBLIC_IP)]
+        );
+    }
+
     fn make_multirouter_specific_success_subject(
         protocol: AutomapProtocol,
         router_ips: Vec<IpAddr>,
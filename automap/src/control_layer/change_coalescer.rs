@@ -0,0 +1,162 @@
+// Copyright (c) 2019-2021, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::control_layer::automap_control::{AutomapChange, ChangeHandler};
+use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError};
+use masq_lib::debug;
+use masq_lib::logger::Logger;
+use std::thread;
+use std::time::Duration;
+
+pub const DEFAULT_CHANGE_QUIET_PERIOD_MS: u64 = 2_000;
+
+// A flapping router makes the housekeeping thread report a burst of changes, each of which would
+// set the Node to re-gossiping. The ChangeHandler produced here holds every change back until no
+// other change has arrived for the quiet period, and then passes on only the last one.
+pub fn coalesce_changes(change_handler: ChangeHandler, quiet_period: Duration) -> ChangeHandler {
+    if quiet_period.is_zero() {
+        return change_handler;
+    }
+    let (tx, rx) = unbounded();
+    thread::spawn(move || {
+        ChangeCoalescer {
+            change_handler,
+            quiet_period,
+            logger: Logger::new("AutomapControl"),
+        }
+        .run(rx)
+    });
+    Box::new(move |change| {
+        // The coalescer only stops when this handler is dropped, so it's always listening
+        let _ = tx.send(change);
+    })
+}
+
+struct ChangeCoalescer {
+    change_handler: ChangeHandler,
+    quiet_period: Duration,
+    logger: Logger,
+}
+
+impl ChangeCoalescer {
+    fn run(self, rx: Receiver<AutomapChange>) {
+        while let Ok(first_change) = rx.recv() {
+            let mut latest_change = first_change;
+            let mut change_count = 1;
+            let disconnected = loop {
+                match rx.recv_timeout(self.quiet_period) {
+                    Ok(change) => {
+                        latest_change = change;
+                        change_count += 1;
+                    }
+                    Err(RecvTimeoutError::Timeout) => break false,
+                    Err(RecvTimeoutError::Disconnected) => break true,
+                }
+            };
+            if change_count > 1 {
+                debug!(
+                    self.logger,
+                    "Coalesced {} changes into {:?}", change_count, latest_change
+                );
+            }
+            (self.change_handler)(latest_change);
+            if disconnected {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comm_layer::AutomapError;
+    use std::net::IpAddr;
+    use std::str::FromStr;
+    use std::sync::{Arc, Mutex};
+    use std::time::Instant;
+
+    fn make_recording_handler() -> (ChangeHandler, Arc<Mutex<Vec<AutomapChange>>>) {
+        let changes_arc = Arc::new(Mutex::new(vec![]));
+        let inner_changes_arc = changes_arc.clone();
+        let change_handler: ChangeHandler =
+            Box::new(move |change| inner_changes_arc.lock().unwrap().push(change));
+        (change_handler, changes_arc)
+    }
+
+    fn wait_for_changes(changes_arc: &Arc<Mutex<Vec<AutomapChange>>>, count: usize) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while changes_arc.lock().unwrap().len() < count {
+            assert!(Instant::now() < deadline, "Changes never arrived");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(DEFAULT_CHANGE_QUIET_PERIOD_MS, 2_000);
+    }
+
+    #[test]
+    fn burst_of_changes_is_delivered_as_its_last_change() {
+        let (change_handler, changes_arc) = make_recording_handler();
+        let subject = coalesce_changes(change_handler, Duration::from_millis(200));
+
+        subject(AutomapChange::NewIp(IpAddr::from_str("1.2.3.4").unwrap()));
+        subject(AutomapChange::Error(AutomapError::Unknown));
+        subject(AutomapChange::NewIp(IpAddr::from_str("2.3.4.5").unwrap()));
+        wait_for_changes(&changes_arc, 1);
+        thread::sleep(Duration::from_millis(300));
+
+        assert_eq!(
+            *changes_arc.lock().unwrap(),
+            vec![AutomapChange::NewIp(IpAddr::from_str("2.3.4.5").unwrap())]
+        );
+    }
+
+    #[test]
+    fn changes_separated_by_the_quiet_period_are_delivered_separately() {
+        let (change_handler, changes_arc) = make_recording_handler();
+        let subject = coalesce_changes(change_handler, Duration::from_millis(50));
+
+        subject(AutomapChange::NewIp(IpAddr::from_str("1.2.3.4").unwrap()));
+        wait_for_changes(&changes_arc, 1);
+        subject(AutomapChange::Error(AutomapError::Unknown));
+        wait_for_changes(&changes_arc, 2);
+
+        assert_eq!(
+            *changes_arc.lock().unwrap(),
+            vec![
+                AutomapChange::NewIp(IpAddr::from_str("1.2.3.4").unwrap()),
+                AutomapChange::Error(AutomapError::Unknown),
+            ]
+        );
+    }
+
+    #[test]
+    fn pending_change_is_delivered_when_the_handler_is_dropped() {
+        let (change_handler, changes_arc) = make_recording_handler();
+        let subject = coalesce_changes(change_handler, Duration::from_secs(60));
+
+        subject(AutomapChange::Error(AutomapError::Unknown));
+        drop(subject);
+        wait_for_changes(&changes_arc, 1);
+
+        assert_eq!(
+            *changes_arc.lock().unwrap(),
+            vec![AutomapChange::Error(AutomapError::Unknown)]
+        );
+    }
+
+    #[test]
+    fn zero_quiet_period_delivers_changes_immediately() {
+        let (change_handler, changes_arc) = make_recording_handler();
+        let subject = coalesce_changes(change_handler, Duration::ZERO);
+
+        subject(AutomapChange::Error(AutomapError::Unknown));
+
+        assert_eq!(
+            *changes_arc.lock().unwrap(),
+            vec![AutomapChange::Error(AutomapError::Unknown)]
+        );
+    }
+}
@@ -1,3 +1,4 @@
 // Copyright (c) 2019-2021, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 pub mod automap_control;
+pub mod change_coalescer;
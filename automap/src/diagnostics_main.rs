@@ -0,0 +1,47 @@
+// Copyright (c) 2019-2021, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use automap_lib::automap_diagnostics::{diagnose, transactor_for};
+use automap_lib::logger::initiate_logger;
+use masq_lib::utils::{find_free_port, AutomapProtocol};
+use std::str::FromStr;
+
+/*
+Runs each automap protocol against the router on this subnet and prints what worked and what
+didn't, so that an operator whose Node can't open its clandestine port can see why.
+
+Usage: automap-diagnostics [pcp] [pmp] [igdp] [<port>]
+
+With no protocols named, all three are tried; with no port, a free one is chosen.
+ */
+
+pub fn main() {
+    let mut protocols = vec![];
+    let mut hole_port_opt = None;
+    std::env::args()
+        .skip(1)
+        .for_each(|arg| match AutomapProtocol::from_str(&arg) {
+            Ok(protocol) => protocols.push(protocol),
+            Err(_) => {
+                hole_port_opt = Some(
+                    arg.parse::<u16>()
+                        .unwrap_or_else(|_| panic!("Bad protocol or port number: {}", arg)),
+                )
+            }
+        });
+    if protocols.is_empty() {
+        protocols = AutomapProtocol::values()
+    }
+    let hole_port = hole_port_opt.unwrap_or_else(find_free_port);
+
+    println!("\nDetailed information about this run will appear in the log.\n");
+
+    initiate_logger();
+
+    let report = diagnose(
+        protocols.into_iter().map(transactor_for).collect(),
+        hole_port,
+    );
+    println!("{}", report);
+
+    std::process::exit(if report.is_operational() { 0 } else { 1 })
+}
@@ -1,6 +1,7 @@
 // Copyright (c) 2019-2021, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 pub mod automap_core_functions;
+pub mod automap_diagnostics;
 pub mod comm_layer;
 pub mod control_layer;
 pub mod logger;
@@ -2,8 +2,12 @@
 
 #![cfg(any(test, not(feature = "no_test_share")))]
 
+use crate::comm_layer::pcp_pmp_common::router_discovery::{
+    DefaultGatewayFinder, DhcpLeaseReader, RouterDiscoverer, RouterProber,
+};
 use crate::comm_layer::pcp_pmp_common::{
-    FindRoutersCommand, FreePortFactory, UdpSocketWrapper, UdpSocketWrapperFactory,
+    FindRoutersCommand, FreePortFactory, HousekeepingStats, RouterTiming, UdpSocketWrapper,
+    UdpSocketWrapperFactory,
 };
 use crate::comm_layer::{AutomapError, HousekeepingThreadCommand, LocalIpFinder, Transactor};
 use crate::control_layer::automap_control::{
@@ -14,6 +18,7 @@ use lazy_static::lazy_static;
 use masq_lib::utils::AutomapProtocol;
 use std::any::Any;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::io::ErrorKind;
 use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
@@ -220,6 +225,138 @@ impl FindRoutersCommandMock {
     }
 }
 
+pub struct DefaultGatewayFinderMock {
+    find_results: RefCell<Vec<Result<Vec<IpAddr>, AutomapError>>>,
+}
+
+impl DefaultGatewayFinder for DefaultGatewayFinderMock {
+    fn find(&self) -> Result<Vec<IpAddr>, AutomapError> {
+        self.find_results.borrow_mut().remove(0)
+    }
+}
+
+impl DefaultGatewayFinderMock {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            find_results: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn find_result(self, result: Result<Vec<IpAddr>, AutomapError>) -> Self {
+        self.find_results.borrow_mut().push(result);
+        self
+    }
+}
+
+pub struct DhcpLeaseReaderMock {
+    read_leases_results: RefCell<Vec<Vec<String>>>,
+}
+
+impl DhcpLeaseReader for DhcpLeaseReaderMock {
+    fn read_leases(&self) -> Vec<String> {
+        self.read_leases_results.borrow_mut().remove(0)
+    }
+}
+
+impl DhcpLeaseReaderMock {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            read_leases_results: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn read_leases_result(self, result: Vec<String>) -> Self {
+        self.read_leases_results.borrow_mut().push(result);
+        self
+    }
+}
+
+// Probes run on threads of their own and finish in no particular order, so results are looked up
+// by router IP rather than taken in turn. A router without a result doesn't answer.
+#[allow(clippy::type_complexity)]
+pub struct RouterProberMock {
+    probe_params: Arc<Mutex<Vec<(IpAddr, Option<IpAddr>)>>>,
+    probe_results: Mutex<HashMap<IpAddr, Result<Duration, AutomapError>>>,
+    probe_delay: Duration,
+}
+
+impl RouterProber for RouterProberMock {
+    fn probe(
+        &self,
+        router_ip: IpAddr,
+        client_ip_opt: Option<IpAddr>,
+    ) -> Result<Duration, AutomapError> {
+        self.probe_params
+            .lock()
+            .unwrap()
+            .push((router_ip, client_ip_opt));
+        thread::sleep(self.probe_delay);
+        self.probe_results
+            .lock()
+            .unwrap()
+            .get(&router_ip)
+            .cloned()
+            .unwrap_or_else(|| {
+                Err(AutomapError::FindRouterError(format!(
+                    "No answer from {}",
+                    router_ip
+                )))
+            })
+    }
+}
+
+impl RouterProberMock {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            probe_params: Arc::new(Mutex::new(vec![])),
+            probe_results: Mutex::new(HashMap::new()),
+            probe_delay: Duration::from_millis(0),
+        }
+    }
+
+    pub fn probe_params(mut self, params: &Arc<Mutex<Vec<(IpAddr, Option<IpAddr>)>>>) -> Self {
+        self.probe_params = params.clone();
+        self
+    }
+
+    pub fn probe_result(self, router_ip: IpAddr, result: Result<Duration, AutomapError>) -> Self {
+        self.probe_results.lock().unwrap().insert(router_ip, result);
+        self
+    }
+
+    pub fn probe_delay(mut self, delay: Duration) -> Self {
+        self.probe_delay = delay;
+        self
+    }
+}
+
+pub struct RouterDiscovererMock {
+    discover_results: RefCell<Vec<Result<Vec<IpAddr>, AutomapError>>>,
+}
+
+impl RouterDiscoverer for RouterDiscovererMock {
+    fn discover(&self) -> Result<Vec<IpAddr>, AutomapError> {
+        self.discover_results.borrow_mut().remove(0)
+    }
+}
+
+impl RouterDiscovererMock {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            discover_results: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn discover_result(self, result: Result<Vec<IpAddr>, AutomapError>) -> Self {
+        self.discover_results.borrow_mut().push(result);
+        self
+    }
+}
+
 pub struct TransactorMock {
     pub housekeeping_thread_started: bool,
     protocol: AutomapProtocol,
@@ -232,6 +369,9 @@ pub struct TransactorMock {
     add_permanent_mapping_results: RefCell<Vec<Result<u32, AutomapError>>>,
     delete_mapping_params: Arc<Mutex<Vec<(IpAddr, u16)>>>,
     delete_mapping_results: RefCell<Vec<Result<(), AutomapError>>>,
+    router_timing_opt_params: Arc<Mutex<Vec<IpAddr>>>,
+    router_timing_opt_results: RefCell<Vec<Option<RouterTiming>>>,
+    housekeeping_stats_results: RefCell<Vec<Option<HousekeepingStats>>>,
     start_housekeeping_thread_params: Arc<Mutex<Vec<(ChangeHandler, IpAddr)>>>,
     start_housekeeping_thread_results:
         RefCell<Vec<Result<Sender<HousekeepingThreadCommand>, AutomapError>>>,
@@ -295,6 +435,18 @@ impl Transactor for TransactorMock {
         self.protocol
     }
 
+    fn router_timing_opt(&self, router_ip: IpAddr) -> Option<RouterTiming> {
+        self.router_timing_opt_params
+            .lock()
+            .unwrap()
+            .push(router_ip);
+        self.router_timing_opt_results.borrow_mut().remove(0)
+    }
+
+    fn housekeeping_stats(&self) -> Option<HousekeepingStats> {
+        self.housekeeping_stats_results.borrow_mut().remove(0)
+    }
+
     fn start_housekeeping_thread(
         &mut self,
         change_handler: ChangeHandler,
@@ -341,6 +493,9 @@ impl TransactorMock {
             add_permanent_mapping_results: RefCell::new(vec![]),
             delete_mapping_params: Arc::new(Mutex::new(vec![])),
             delete_mapping_results: RefCell::new(vec![]),
+            router_timing_opt_params: Arc::new(Mutex::new(vec![])),
+            router_timing_opt_results: RefCell::new(vec![]),
+            housekeeping_stats_results: RefCell::new(vec![]),
             start_housekeeping_thread_params: Arc::new(Mutex::new(vec![])),
             start_housekeeping_thread_results: RefCell::new(vec![]),
             stop_housekeeping_thread_params: Arc::new(Mutex::new(vec![])),
@@ -394,6 +549,21 @@ impl TransactorMock {
         self
     }
 
+    pub fn router_timing_opt_params(mut self, params: &Arc<Mutex<Vec<IpAddr>>>) -> Self {
+        self.router_timing_opt_params = params.clone();
+        self
+    }
+
+    pub fn router_timing_opt_result(self, result: Option<RouterTiming>) -> Self {
+        self.router_timing_opt_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn housekeeping_stats_result(self, result: Option<HousekeepingStats>) -> Self {
+        self.housekeeping_stats_results.borrow_mut().push(result);
+        self
+    }
+
     pub fn start_housekeeping_thread_result(
         self,
         result: Result<Sender<HousekeepingThreadCommand>, AutomapError>,
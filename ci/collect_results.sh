@@ -31,6 +31,7 @@ function copy_windows_binaries() {
   cp ../node/target/release/MASQNodeW.exe generated/bin || echo "No non-console MASQNode binary"
   cp ../node/target/release/masq.exe generated/bin || echo "No masq binary"
   cp ../automap/target/release/automap.exe generated/bin || echo "No automap binary"
+  cp ../automap/target/release/automap-diagnostics.exe generated/bin || echo "No automap-diagnostics binary"
 }
 
 function copy_non_windows_binaries() {
@@ -39,6 +40,7 @@ function copy_non_windows_binaries() {
   cp ../node/target/release/MASQNode generated/bin || echo "No MASQNode binary"
   cp ../node/target/release/masq generated/bin || echo "No masq binary"
   cp ../automap/target/release/automap generated/bin || echo "No automap binary"
+  cp ../automap/target/release/automap-diagnostics generated/bin || echo "No automap-diagnostics binary"
 }
 
 mkdir -p "$CI_DIR/../results"
@@ -297,7 +297,7 @@ mod tests {
         let mut subject = CommandContextReal::new(port, None, Box::new(broadcast_handle)).unwrap();
 
         let response = subject.transact(
-            UiSetupRequest { values: vec![] }.tmb(1),
+            UiSetupRequest::new(vec![]).tmb(1),
             TRANSACT_TIMEOUT_MILLIS_FOR_TESTS,
         );
 
@@ -315,7 +315,7 @@ mod tests {
         let mut subject = CommandContextReal::new(port, None, Box::new(broadcast_handle)).unwrap();
 
         let response = subject.transact(
-            UiSetupRequest { values: vec![] }.tmb(1),
+            UiSetupRequest::new(vec![]).tmb(1),
             TRANSACT_TIMEOUT_MILLIS_FOR_TESTS,
         );
 
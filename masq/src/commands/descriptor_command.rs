@@ -30,7 +30,15 @@ impl Command for DescriptorCommand {
             Ok(response) => {
                 match response.node_descriptor_opt {
                     Some(node_descriptor) => {
-                        short_writeln!(context.stdout(), "{}", node_descriptor)
+                        short_writeln!(context.stdout(), "{}", node_descriptor);
+                        if let Some(token) = response.operator_notes_token_opt {
+                            short_writeln!(
+                                context.stdout(),
+                                "With operator notes: {}#{}",
+                                node_descriptor,
+                                token
+                            )
+                        }
                     }
                     None => short_writeln!(
                         context.stdout(),
@@ -90,6 +98,7 @@ mod tests {
         let factory = CommandFactoryReal::new();
         let mut context = CommandContextMock::new().transact_result(Ok(UiDescriptorResponse {
             node_descriptor_opt: Some("Node descriptor".to_string()),
+            operator_notes_token_opt: None,
         }
         .tmb(0)));
         let subject = factory.make(&["descriptor".to_string()]).unwrap();
@@ -129,6 +138,7 @@ mod tests {
         let transact_params_arc = Arc::new(Mutex::new(vec![]));
         let expected_response = UiDescriptorResponse {
             node_descriptor_opt: Some("Booga:1234".to_string()),
+            operator_notes_token_opt: None,
         };
         let mut context = CommandContextMock::new()
             .transact_params(&transact_params_arc)
@@ -152,11 +162,33 @@ mod tests {
         assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
     }
 
+    #[test]
+    fn descriptor_command_when_descriptor_comes_with_operator_notes() {
+        let mut context = CommandContextMock::new().transact_result(Ok(UiDescriptorResponse {
+            node_descriptor_opt: Some("Booga:1234".to_string()),
+            operator_notes_token_opt: Some("notes.key.signature".to_string()),
+        }
+        .tmb(42)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = DescriptorCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "Booga:1234\nWith operator notes: Booga:1234#notes.key.signature\n"
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
     #[test]
     fn descriptor_command_when_descriptor_is_not_returned() {
         let transact_params_arc = Arc::new(Mutex::new(vec![]));
         let expected_response = UiDescriptorResponse {
             node_descriptor_opt: None,
+            operator_notes_token_opt: None,
         };
         let mut context = CommandContextMock::new()
             .transact_params(&transact_params_arc)
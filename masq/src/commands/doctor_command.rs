@@ -0,0 +1,642 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::{
+    transaction, Command, CommandError, STANDARD_COMMAND_TIMEOUT_MILLIS,
+};
+use crate::commands::setup_command::SETUP_COMMAND_TIMEOUT_MILLIS;
+use clap::{App, SubCommand};
+use masq_lib::as_any_ref_in_trait_impl;
+use masq_lib::messages::{
+    UiConfigurationRequest, UiConfigurationResponse, UiConnectionStage, UiConnectionStatusRequest,
+    UiConnectionStatusResponse, UiDatabaseMaintenanceRequest, UiDatabaseMaintenanceResponse,
+    UiFinancialsRequest, UiFinancialsResponse, UiSetupRequest, UiSetupResponse,
+};
+use masq_lib::short_writeln;
+use std::fmt::{Debug, Display, Formatter};
+
+const DOCTOR_ABOUT: &str =
+    "Runs a battery of checks through the Daemon and the Node (setup, configuration, neighborhood \
+     connection, database, unpaid debts) and lists the problems it finds, most serious first, each \
+     with a suggested fix. Exits with 0 if it finds none and 1 otherwise.";
+// A database with more than this share of its pages free is worth vacuuming
+const DATABASE_FREE_SPACE_LIMIT_PERCENT: u64 = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Severity {
+    Critical,
+    Serious,
+    Minor,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Critical => write!(f, "CRITICAL"),
+            Severity::Serious => write!(f, "SERIOUS"),
+            Severity::Minor => write!(f, "MINOR"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Problem {
+    severity: Severity,
+    description: String,
+    fix: String,
+}
+
+impl Problem {
+    fn new(severity: Severity, description: String, fix: &str) -> Self {
+        Self {
+            severity,
+            description,
+            fix: fix.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DoctorCommand {}
+
+pub fn doctor_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("doctor").about(DOCTOR_ABOUT)
+}
+
+impl Command for DoctorCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let setup: UiSetupResponse = match transaction(
+            UiSetupRequest::new(vec![]),
+            context,
+            SETUP_COMMAND_TIMEOUT_MILLIS,
+        ) {
+            Ok(setup) => setup,
+            Err(e) => {
+                short_writeln!(
+                    context.stderr(),
+                    "Diagnosis failed: the Daemon didn't answer: {:?}",
+                    e
+                );
+                return Err(e);
+            }
+        };
+        let mut problems = Self::setup_problems(&setup);
+        if setup.running {
+            problems.extend(Self::node_problems(context));
+        }
+        Self::report(context, problems)
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+impl Default for DoctorCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DoctorCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    fn setup_problems(setup: &UiSetupResponse) -> Vec<Problem> {
+        let mut problems: Vec<Problem> = setup
+            .errors
+            .iter()
+            .map(|(name, message, _)| {
+                Problem::new(
+                    Severity::Critical,
+                    format!("Setup parameter '{}' is invalid: {}", name, message),
+                    &format!("Give it a valid value with 'masq setup --{} <value>'", name),
+                )
+            })
+            .collect();
+        if !setup.running {
+            problems.push(Problem::new(
+                Severity::Serious,
+                "The Node is not running, so it could not be checked".to_string(),
+                "Start it with 'masq start', then run 'masq doctor' again",
+            ));
+        }
+        problems
+    }
+
+    // Each check stands alone: one that can't be made is reported and the rest go ahead
+    fn node_problems(context: &mut dyn CommandContext) -> Vec<Problem> {
+        let mut problems = vec![];
+        let configuration_opt = match transaction::<_, UiConfigurationResponse>(
+            UiConfigurationRequest {
+                db_password_opt: None,
+            },
+            context,
+            STANDARD_COMMAND_TIMEOUT_MILLIS,
+        ) {
+            Ok(configuration) => {
+                problems.extend(Self::configuration_problems(&configuration));
+                Some(configuration)
+            }
+            Err(e) => {
+                problems.push(Self::check_failed("configuration", e));
+                None
+            }
+        };
+        match transaction::<_, UiConnectionStatusResponse>(
+            UiConnectionStatusRequest {},
+            context,
+            STANDARD_COMMAND_TIMEOUT_MILLIS,
+        ) {
+            Ok(status) => problems.extend(Self::connection_problems(
+                &status.stage,
+                configuration_opt.as_ref(),
+            )),
+            Err(e) => problems.push(Self::check_failed("connection status", e)),
+        }
+        match transaction::<_, UiDatabaseMaintenanceResponse>(
+            UiDatabaseMaintenanceRequest { vacuum: false },
+            context,
+            STANDARD_COMMAND_TIMEOUT_MILLIS,
+        ) {
+            Ok(maintenance) => problems.extend(Self::database_problems(&maintenance)),
+            Err(e) => problems.push(Self::check_failed("database", e)),
+        }
+        match transaction::<_, UiFinancialsResponse>(
+            UiFinancialsRequest {
+                stats_required: true,
+                top_records_opt: None,
+                custom_queries_opt: None,
+            },
+            context,
+            STANDARD_COMMAND_TIMEOUT_MILLIS,
+        ) {
+            Ok(financials) => {
+                problems.extend(Self::debt_problems(&financials, configuration_opt.as_ref()))
+            }
+            Err(e) => problems.push(Self::check_failed("financials", e)),
+        }
+        problems
+    }
+
+    fn configuration_problems(configuration: &UiConfigurationResponse) -> Vec<Problem> {
+        let mut problems = vec![];
+        if configuration.blockchain_service_url_opt.is_none() {
+            problems.push(Problem::new(
+                Severity::Serious,
+                "No blockchain service URL is configured, so the Node can neither pay its debts \
+                 nor see what it's owed"
+                    .to_string(),
+                "Shut the Node down, set one with 'masq setup --blockchain-service-url <URL>', \
+                 and start it again",
+            ));
+        }
+        if configuration.earning_wallet_address_opt.is_none() {
+            problems.push(Problem::new(
+                Severity::Serious,
+                "No earning wallet is set, so what other Nodes pay you goes to the default \
+                 earning wallet"
+                    .to_string(),
+                "Set one up with 'masq generate-wallets' or 'masq recover-wallets'",
+            ));
+        }
+        if configuration.neighborhood_mode == "standard"
+            && configuration.port_mapping_protocol_opt.is_none()
+        {
+            problems.push(Problem::new(
+                Severity::Minor,
+                format!(
+                    "No port-mapping protocol has worked with your router yet, so clandestine \
+                     port {} may not be open to the Internet",
+                    configuration.clandestine_port
+                ),
+                "If your router supports none of PCP, PMP, and IGDP, forward the clandestine \
+                 port to this machine by hand",
+            ));
+        }
+        problems
+    }
+
+    fn connection_problems(
+        stage: &UiConnectionStage,
+        configuration_opt: Option<&UiConfigurationResponse>,
+    ) -> Vec<Problem> {
+        let zero_hop = configuration_opt
+            .map(|configuration| configuration.neighborhood_mode == "zero-hop")
+            .unwrap_or(false);
+        let port = configuration_opt
+            .map(|configuration| configuration.clandestine_port.to_string())
+            .unwrap_or_else(|| "<unknown>".to_string());
+        match stage {
+            _ if zero_hop => vec![],
+            UiConnectionStage::NotConnected => vec![Problem::new(
+                Severity::Serious,
+                "No other Node is connected to this one".to_string(),
+                &format!(
+                    "Make sure clandestine port {} is reachable from the Internet and that \
+                     --neighbors names Nodes that are up",
+                    port
+                ),
+            )],
+            UiConnectionStage::ConnectedToNeighbor => vec![Problem::new(
+                Severity::Minor,
+                "Neighbors are connected, but no route over the network has been found yet"
+                    .to_string(),
+                "Give Gossip a few minutes to bring in more of the network; if this persists, \
+                 add more --neighbors",
+            )],
+            UiConnectionStage::RouteFound => vec![],
+        }
+    }
+
+    fn database_problems(maintenance: &UiDatabaseMaintenanceResponse) -> Vec<Problem> {
+        let stats = &maintenance.stats;
+        if stats.file_size == 0 {
+            return vec![];
+        }
+        let free_percent = stats.free_pages * stats.page_size * 100 / stats.file_size;
+        if free_percent <= DATABASE_FREE_SPACE_LIMIT_PERCENT {
+            return vec![];
+        }
+        vec![Problem::new(
+            Severity::Minor,
+            format!(
+                "{}% of the database file is free space left behind by deleted records",
+                free_percent
+            ),
+            "Vacuum the database (a databaseMaintenance request with vacuum: true) while the \
+             Node is lightly loaded",
+        )]
+    }
+
+    fn debt_problems(
+        financials: &UiFinancialsResponse,
+        configuration_opt: Option<&UiConfigurationResponse>,
+    ) -> Vec<Problem> {
+        let (stats, configuration) = match (&financials.stats_opt, configuration_opt) {
+            (Some(stats), Some(configuration)) => (stats, configuration),
+            _ => return vec![],
+        };
+        let debt_threshold_gwei = configuration.payment_thresholds.debt_threshold_gwei;
+        if stats.total_unpaid_and_pending_payable_gwei <= debt_threshold_gwei {
+            return vec![];
+        }
+        vec![Problem::new(
+            Severity::Serious,
+            format!(
+                "This Node owes {} gwei, more than the {} gwei debt threshold, so creditors may \
+                 ban it",
+                stats.total_unpaid_and_pending_payable_gwei, debt_threshold_gwei
+            ),
+            "Make sure the consuming wallet holds enough MASQ and gas to pay, and see \
+             'masq financials' for who is owed",
+        )]
+    }
+
+    fn check_failed(check: &str, error: CommandError) -> Problem {
+        Problem::new(
+            Severity::Minor,
+            format!("The {} check couldn't be made: {:?}", check, error),
+            "Look in the Node's log for the cause",
+        )
+    }
+
+    fn report(
+        context: &mut dyn CommandContext,
+        mut problems: Vec<Problem>,
+    ) -> Result<(), CommandError> {
+        if problems.is_empty() {
+            short_writeln!(context.stdout(), "No problems found.");
+            return Ok(());
+        }
+        problems.sort_by_key(|problem| problem.severity);
+        short_writeln!(
+            context.stdout(),
+            "Found {} problem(s), most serious first:\n",
+            problems.len()
+        );
+        problems.iter().enumerate().for_each(|(index, problem)| {
+            short_writeln!(
+                context.stdout(),
+                "{}. {}: {}\n   Fix: {}",
+                index + 1,
+                problem.severity,
+                problem.description,
+                problem.fix
+            )
+        });
+        short_writeln!(context.stdout());
+        Err(CommandError::Other(format!(
+            "Diagnosis found {} problem(s)",
+            problems.len()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError;
+    use crate::command_context::ContextError::ConnectionDropped;
+    use crate::command_factory::{CommandFactory, CommandFactoryReal};
+    use crate::commands::commands_common::CommandError::ConnectionProblem;
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::messages::{
+        ToMessageBody, UiDatabaseStats, UiFinancialStatistics, UiPaymentThresholds, UiRatePack,
+        UiScanIntervals,
+    };
+    use std::sync::{Arc, Mutex};
+
+    fn make_setup(running: bool, errors: Vec<(&str, &str)>) -> UiSetupResponse {
+        UiSetupResponse {
+            running,
+            values: vec![],
+            errors: errors
+                .into_iter()
+                .map(|(name, message)| (name.to_string(), message.to_string(), 0))
+                .collect(),
+            command_line: vec![],
+            revision: 0,
+        }
+    }
+
+    fn make_configuration() -> UiConfigurationResponse {
+        UiConfigurationResponse {
+            blockchain_service_url_opt: Some("https://blockchain.service.com".to_string()),
+            chain_name: "polygon-mainnet".to_string(),
+            clandestine_port: 1234,
+            current_schema_version: "20".to_string(),
+            earning_wallet_address_opt: Some("0x01234567890123456789".to_string()),
+            gas_price: 1,
+            max_block_count_opt: None,
+            neighborhood_mode: "standard".to_string(),
+            port_mapping_protocol_opt: Some("PCP".to_string()),
+            start_block_opt: None,
+            consuming_wallet_private_key_opt: None,
+            consuming_wallet_address_opt: None,
+            past_neighbors: vec![],
+            payment_thresholds: UiPaymentThresholds {
+                threshold_interval_sec: 1,
+                debt_threshold_gwei: 1000,
+                payment_grace_period_sec: 1,
+                maturity_threshold_sec: 1,
+                permanent_debt_allowed_gwei: 1,
+                unban_below_gwei: 1,
+            },
+            rate_pack: UiRatePack {
+                routing_byte_rate: 1,
+                routing_service_rate: 1,
+                exit_byte_rate: 1,
+                exit_service_rate: 1,
+            },
+            scan_intervals: UiScanIntervals {
+                pending_payable_sec: 1,
+                payable_sec: 1,
+                receivable_sec: 1,
+            },
+        }
+    }
+
+    fn make_maintenance(free_pages: u64) -> UiDatabaseMaintenanceResponse {
+        UiDatabaseMaintenanceResponse {
+            stats: UiDatabaseStats {
+                file_size: 409600,
+                page_size: 4096,
+                free_pages,
+                tables: vec![],
+            },
+            reclaimed_bytes_opt: None,
+        }
+    }
+
+    fn make_financials(owed_gwei: u64) -> UiFinancialsResponse {
+        UiFinancialsResponse {
+            stats_opt: Some(UiFinancialStatistics {
+                total_unpaid_and_pending_payable_gwei: owed_gwei,
+                total_paid_payable_gwei: 0,
+                total_unpaid_receivable_gwei: 0,
+                total_paid_receivable_gwei: 0,
+            }),
+            query_results_opt: None,
+            fiat_opt: None,
+        }
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            DOCTOR_ABOUT,
+            "Runs a battery of checks through the Daemon and the Node (setup, configuration, neighborhood \
+             connection, database, unpaid debts) and lists the problems it finds, most serious first, each \
+             with a suggested fix. Exits with 0 if it finds none and 1 otherwise."
+        );
+        assert_eq!(DATABASE_FREE_SPACE_LIMIT_PERCENT, 25);
+    }
+
+    #[test]
+    fn testing_command_factory_here() {
+        let factory = CommandFactoryReal::new();
+
+        let command = factory.make(&["doctor".to_string()]).unwrap();
+
+        assert_eq!(
+            command.as_any().downcast_ref::<DoctorCommand>().unwrap(),
+            &DoctorCommand {}
+        );
+    }
+
+    #[test]
+    fn healthy_node_passes_every_check() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(make_setup(true, vec![]).tmb(0)))
+            .transact_result(Ok(make_configuration().tmb(0)))
+            .transact_result(Ok(UiConnectionStatusResponse {
+                stage: UiConnectionStage::RouteFound,
+            }
+            .tmb(0)))
+            .transact_result(Ok(make_maintenance(25).tmb(0)))
+            .transact_result(Ok(make_financials(1000).tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = DoctorCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            *transact_params,
+            vec![
+                (
+                    UiSetupRequest::new(vec![]).tmb(0),
+                    SETUP_COMMAND_TIMEOUT_MILLIS
+                ),
+                (
+                    UiConfigurationRequest {
+                        db_password_opt: None
+                    }
+                    .tmb(0),
+                    STANDARD_COMMAND_TIMEOUT_MILLIS
+                ),
+                (
+                    UiConnectionStatusRequest {}.tmb(0),
+                    STANDARD_COMMAND_TIMEOUT_MILLIS
+                ),
+                (
+                    UiDatabaseMaintenanceRequest { vacuum: false }.tmb(0),
+                    STANDARD_COMMAND_TIMEOUT_MILLIS
+                ),
+                (
+                    UiFinancialsRequest {
+                        stats_required: true,
+                        top_records_opt: None,
+                        custom_queries_opt: None,
+                    }
+                    .tmb(0),
+                    STANDARD_COMMAND_TIMEOUT_MILLIS
+                ),
+            ]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "No problems found.\n"
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn node_that_is_not_running_is_diagnosed_from_the_setup_alone() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(make_setup(
+                false,
+                vec![("chain", "Unrecognized chain 'booga'")],
+            )
+            .tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let subject = DoctorCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Other(
+                "Diagnosis found 2 problem(s)".to_string()
+            ))
+        );
+        assert_eq!(transact_params_arc.lock().unwrap().len(), 1);
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "Found 2 problem(s), most serious first:\n\n\
+             1. CRITICAL: Setup parameter 'chain' is invalid: Unrecognized chain 'booga'\n   \
+             Fix: Give it a valid value with 'masq setup --chain <value>'\n\
+             2. SERIOUS: The Node is not running, so it could not be checked\n   \
+             Fix: Start it with 'masq start', then run 'masq doctor' again\n\n"
+        );
+    }
+
+    #[test]
+    fn problems_are_listed_most_serious_first() {
+        let mut configuration = make_configuration();
+        configuration.blockchain_service_url_opt = None;
+        configuration.port_mapping_protocol_opt = None;
+        let mut context = CommandContextMock::new()
+            .transact_result(Ok(make_setup(true, vec![]).tmb(0)))
+            .transact_result(Ok(configuration.tmb(0)))
+            .transact_result(Ok(UiConnectionStatusResponse {
+                stage: UiConnectionStage::NotConnected,
+            }
+            .tmb(0)))
+            .transact_result(Ok(make_maintenance(50).tmb(0)))
+            .transact_result(Ok(make_financials(1001).tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let subject = DoctorCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Other(
+                "Diagnosis found 5 problem(s)".to_string()
+            ))
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "Found 5 problem(s), most serious first:\n\n\
+             1. SERIOUS: No blockchain service URL is configured, so the Node can neither pay its \
+             debts nor see what it's owed\n   \
+             Fix: Shut the Node down, set one with 'masq setup --blockchain-service-url <URL>', \
+             and start it again\n\
+             2. SERIOUS: No other Node is connected to this one\n   \
+             Fix: Make sure clandestine port 1234 is reachable from the Internet and that \
+             --neighbors names Nodes that are up\n\
+             3. SERIOUS: This Node owes 1001 gwei, more than the 1000 gwei debt threshold, so \
+             creditors may ban it\n   \
+             Fix: Make sure the consuming wallet holds enough MASQ and gas to pay, and see \
+             'masq financials' for who is owed\n\
+             4. MINOR: No port-mapping protocol has worked with your router yet, so clandestine \
+             port 1234 may not be open to the Internet\n   \
+             Fix: If your router supports none of PCP, PMP, and IGDP, forward the clandestine \
+             port to this machine by hand\n\
+             5. MINOR: 50% of the database file is free space left behind by deleted records\n   \
+             Fix: Vacuum the database (a databaseMaintenance request with vacuum: true) while the \
+             Node is lightly loaded\n\n"
+        );
+    }
+
+    #[test]
+    fn a_check_that_cant_be_made_does_not_stop_the_others() {
+        let mut configuration = make_configuration();
+        configuration.neighborhood_mode = "zero-hop".to_string();
+        configuration.port_mapping_protocol_opt = None;
+        let mut context = CommandContextMock::new()
+            .transact_result(Ok(make_setup(true, vec![]).tmb(0)))
+            .transact_result(Ok(configuration.tmb(0)))
+            .transact_result(Ok(UiConnectionStatusResponse {
+                stage: UiConnectionStage::NotConnected,
+            }
+            .tmb(0)))
+            .transact_result(Err(ContextError::PayloadError(
+                1234,
+                "Database is locked".to_string(),
+            )))
+            .transact_result(Ok(make_financials(0).tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let subject = DoctorCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Other(
+                "Diagnosis found 1 problem(s)".to_string()
+            ))
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "Found 1 problem(s), most serious first:\n\n\
+             1. MINOR: The database check couldn't be made: Payload(1234, \"Database is locked\")\n   \
+             Fix: Look in the Node's log for the cause\n\n"
+        );
+    }
+
+    #[test]
+    fn doctor_command_sad_path() {
+        let mut context =
+            CommandContextMock::new().transact_result(Err(ConnectionDropped("Booga".to_string())));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = DoctorCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Err(ConnectionProblem("Booga".to_string())));
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), String::new());
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "Diagnosis failed: the Daemon didn't answer: ConnectionProblem(\"Booga\")\n"
+        );
+    }
+}
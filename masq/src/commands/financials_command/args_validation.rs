@@ -24,6 +24,9 @@ const NO_STATS_ARG_HELP: &str = "Disables statistics that display by default, co
 const GWEI_HELP: &str =
     "Orders money values rendering in gwei of MASQ instead of whole MASQs as the default.";
 const ORDERED_HELP: &str = "Determines in what ordering the top records will be returned. This option works only with the '--top' argument.";
+const OFFSET_HELP: &str = "Skips this many records at the start of each table, so that a long table can be paged through with repeated queries. Defaults to 0.";
+const ASCENDING_HELP: &str = "Reverses the ordering of the top records so that the youngest or smallest come first. This option works only with the '--top' argument.";
+const LIMIT_HELP: &str = "Returns no more than this many records from each table queried by '--payable' or '--receivable'. By default all matching records are returned.";
 
 pub fn financials_subcommand() -> App<'static, 'static> {
     SubCommand::with_name("financials")
@@ -92,6 +95,37 @@ pub fn financials_subcommand() -> App<'static, 'static> {
                 .possible_values(&["balance", "age"])
                 .required(false),
         )
+        .arg(
+            Arg::with_name("offset")
+                .help(OFFSET_HELP)
+                .value_name("OFFSET")
+                .long("offset")
+                .case_insensitive(false)
+                .takes_value(true)
+                .required(false)
+                .validator(validate_offset),
+        )
+        .arg(
+            Arg::with_name("ascending")
+                .help(ASCENDING_HELP)
+                .value_name("ASCENDING")
+                .long("ascending")
+                .short("a")
+                .case_insensitive(false)
+                .takes_value(false)
+                .required(false),
+        )
+        .arg(
+            Arg::with_name("limit")
+                .help(LIMIT_HELP)
+                .value_name("LIMIT")
+                .long("limit")
+                .short("l")
+                .case_insensitive(false)
+                .takes_value(true)
+                .required(false)
+                .validator(validate_non_zero_u16),
+        )
         .groups(&[
             ArgGroup::with_name("at_least_one_query")
                 .args(&["receivable", "payable", "top"])
@@ -109,9 +143,27 @@ pub fn financials_subcommand() -> App<'static, 'static> {
             ArgGroup::with_name("ordered-conflicts")
                 .arg("ordered")
                 .conflicts_with("custom-queries"),
+            ArgGroup::with_name("offset-requirement")
+                .arg("offset")
+                .requires("at_least_one_query"),
+            ArgGroup::with_name("ascending-conflicts")
+                .arg("ascending")
+                .conflicts_with("custom-queries")
+                .requires("top"),
+            ArgGroup::with_name("limit-conflicts")
+                .arg("limit")
+                .conflicts_with("top")
+                .requires("custom-queries"),
         ])
 }
 
+fn validate_offset(offset: String) -> Result<(), String> {
+    match offset.parse::<u64>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("Offset must be a whole number, not '{}'", offset)),
+    }
+}
+
 fn validate_two_ranges<N>(two_ranges: String) -> Result<(), String>
 where
     N: FromStr<Err = ParseIntError>
@@ -183,6 +235,23 @@ mod tests {
             "Orders money values rendering in gwei of MASQ instead of whole MASQs as the default."
         );
         assert_eq!(ORDERED_HELP, "Determines in what ordering the top records will be returned. This option works only with the '--top' argument.");
+        assert_eq!(OFFSET_HELP, "Skips this many records at the start of each table, so that a long table can be paged through with repeated queries. Defaults to 0.");
+        assert_eq!(ASCENDING_HELP, "Reverses the ordering of the top records so that the youngest or smallest come first. This option works only with the '--top' argument.");
+        assert_eq!(LIMIT_HELP, "Returns no more than this many records from each table queried by '--payable' or '--receivable'. By default all matching records are returned.");
+    }
+
+    #[test]
+    fn validate_offset_accepts_whole_numbers_only() {
+        assert_eq!(validate_offset("0".to_string()), Ok(()));
+        assert_eq!(validate_offset("18446744073709551615".to_string()), Ok(()));
+        assert_eq!(
+            validate_offset("-1".to_string()),
+            Err("Offset must be a whole number, not '-1'".to_string())
+        );
+        assert_eq!(
+            validate_offset("ten".to_string()),
+            Err("Offset must be a whole number, not 'ten'".to_string())
+        );
     }
 
     #[test]
@@ -215,9 +215,18 @@ impl FinancialsCommand {
                 .expect("should be required and defaulted")
                 .try_into()
                 .expect("Clap did not catch invalid value"),
+            offset: Self::parse_offset_arg(matches),
+            ascending: matches.is_present("ascending"),
         })
     }
 
+    fn parse_offset_arg(matches: &ArgMatches) -> u64 {
+        matches
+            .value_of("offset")
+            .map(|str| str.parse::<u64>().expect("offset not properly validated"))
+            .unwrap_or(0)
+    }
+
     fn parse_custom_query_args(matches: &ArgMatches) -> Option<CustomQueryInput> {
         fn decompose_optional_inputs<N>(
             composed_parameters_opt: Option<RangeQueryInput<N>>,
@@ -303,6 +312,11 @@ impl FinancialsCommand {
                     max_age_s: max_age,
                     min_amount_gwei: min_balance_num,
                     max_amount_gwei: max_balance_num,
+                    offset: Self::parse_offset_arg(matches),
+                    limit_opt: matches.value_of("limit").map(|str| {
+                        str.parse::<u16>()
+                            .expect("range query limit not properly validated")
+                    }),
                 },
                 captured_literal_input: (
                     (min_age_str.to_string(), max_age_str.to_string()),
@@ -336,6 +350,7 @@ mod tests {
         UiFinancialsResponse {
             stats_opt: None,
             query_results_opt: None,
+            fiat_opt: None,
         }
         .tmb(0)
     }
@@ -394,7 +409,9 @@ mod tests {
                     stats_required: false,
                     top_records_opt: Some(TopRecordsConfig {
                         count: 20,
-                        ordered_by: TopRecordsOrdering::Balance
+                        ordered_by: TopRecordsOrdering::Balance,
+                        offset: 0,
+                        ascending: false,
                     }),
                     custom_queries_opt: None
                 }
@@ -431,7 +448,9 @@ mod tests {
                     stats_required: true,
                     top_records_opt: Some(TopRecordsConfig {
                         count: 10,
-                        ordered_by: TopRecordsOrdering::Balance
+                        ordered_by: TopRecordsOrdering::Balance,
+                        offset: 0,
+                        ascending: false,
                     }),
                     custom_queries_opt: None
                 }
@@ -474,13 +493,17 @@ mod tests {
                             min_age_s: 200,
                             max_age_s: 450,
                             min_amount_gwei: 480000000000000,
-                            max_amount_gwei: 158000008000000000
+                            max_amount_gwei: 158000008000000000,
+                            offset: 0,
+                            limit_opt: None,
                         }),
                         receivable_opt: Some(RangeQuery {
                             min_age_s: 5000,
                             max_age_s: 10000,
                             min_amount_gwei: 3000000,
-                            max_amount_gwei: 5600070000
+                            max_amount_gwei: 5600070000,
+                            offset: 0,
+                            limit_opt: None,
                         })
                     })
                 }
@@ -721,7 +744,9 @@ mod tests {
                     stats_required: false,
                     top_records_opt: Some(TopRecordsConfig {
                         count: 123,
-                        ordered_by: TopRecordsOrdering::Balance
+                        ordered_by: TopRecordsOrdering::Balance,
+                        offset: 0,
+                        ascending: false,
                     }),
                     custom_queries_opt: None
                 }
@@ -763,13 +788,17 @@ mod tests {
                             min_age_s: 0,
                             max_age_s: 350000,
                             min_amount_gwei: 5000000,
-                            max_amount_gwei: 9000000000
+                            max_amount_gwei: 9000000000,
+                            offset: 0,
+                            limit_opt: None,
                         }),
                         receivable_opt: Some(RangeQuery {
                             min_age_s: 5000,
                             max_age_s: 10000,
                             min_amount_gwei: 4000,
-                            max_amount_gwei: 50003000000
+                            max_amount_gwei: 50003000000,
+                            offset: 0,
+                            limit_opt: None,
                         })
                     })
                 }
@@ -806,7 +835,9 @@ mod tests {
                     stats_required: false,
                     top_records_opt: Some(TopRecordsConfig {
                         count: 7,
-                        ordered_by: TopRecordsOrdering::Age
+                        ordered_by: TopRecordsOrdering::Age,
+                        offset: 0,
+                        ascending: false,
                     }),
                     custom_queries_opt: None
                 }
@@ -816,6 +847,94 @@ mod tests {
         );
     }
 
+    #[test]
+    fn financials_command_pages_through_top_records_in_ascending_order() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let args = slice_of_strs_to_vec_of_strings(&[
+            "financials",
+            "--no-stats",
+            "--top",
+            "20",
+            "--offset",
+            "40",
+            "--ascending",
+        ]);
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(meaningless_financials_response()));
+        let subject = FinancialsCommand::new(&args).unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            *transact_params,
+            vec![(
+                UiFinancialsRequest {
+                    stats_required: false,
+                    top_records_opt: Some(TopRecordsConfig {
+                        count: 20,
+                        ordered_by: TopRecordsOrdering::Balance,
+                        offset: 40,
+                        ascending: true,
+                    }),
+                    custom_queries_opt: None
+                }
+                .tmb(0),
+                STANDARD_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+    }
+
+    #[test]
+    fn parse_custom_query_args_applies_offset_and_limit_to_both_ranges() {
+        let args = slice_of_strs_to_vec_of_strings(&[
+            "financials",
+            "--payable",
+            "200-450|48000-158000008",
+            "--receivable",
+            "5000-10000|0.003-5.60007",
+            "--offset",
+            "10",
+            "--limit",
+            "5",
+        ]);
+        let matches = financials_subcommand().get_matches_from_safe(args).unwrap();
+
+        let result = FinancialsCommand::parse_custom_query_args(&matches)
+            .unwrap()
+            .query;
+
+        let payable = result.payable_opt.unwrap();
+        let receivable = result.receivable_opt.unwrap();
+        assert_eq!((payable.offset, payable.limit_opt), (10, Some(5)));
+        assert_eq!((receivable.offset, receivable.limit_opt), (10, Some(5)));
+    }
+
+    #[test]
+    fn ascending_cannot_be_combined_with_custom_queries() {
+        let args = slice_of_strs_to_vec_of_strings(&[
+            "financials",
+            "--payable",
+            "200-450|48000-158000008",
+            "--ascending",
+        ]);
+
+        let result = financials_subcommand().get_matches_from_safe(args);
+
+        assert!(result.is_err(), "{:?}", result)
+    }
+
+    #[test]
+    fn limit_cannot_be_combined_with_top_records() {
+        let args = slice_of_strs_to_vec_of_strings(&["financials", "--top", "10", "--limit", "5"]);
+
+        let result = financials_subcommand().get_matches_from_safe(args);
+
+        assert!(result.is_err(), "{:?}", result)
+    }
+
     #[test]
     fn parse_top_records_arg_with_ordered_defaulted_to_balance() {
         let args = slice_of_strs_to_vec_of_strings(&["financials", "--top", "11"]);
@@ -827,7 +946,9 @@ mod tests {
             result,
             Some(TopRecordsConfig {
                 count: 11,
-                ordered_by: TopRecordsOrdering::Balance
+                ordered_by: TopRecordsOrdering::Balance,
+                offset: 0,
+                ascending: false,
             })
         )
     }
@@ -854,7 +975,9 @@ mod tests {
                             min_age_s: 5000,
                             max_age_s: 10000,
                             min_amount_gwei: 40000000000,
-                            max_amount_gwei: 50000000000
+                            max_amount_gwei: 50000000000,
+                            offset: 0,
+                            limit_opt: None,
                         })
                     },
                     users_payable_format_opt: None,
@@ -889,7 +1012,9 @@ mod tests {
                             min_age_s: 5000,
                             max_age_s: 10000,
                             min_amount_gwei: -50000000000,
-                            max_amount_gwei: -40000000000
+                            max_amount_gwei: -40000000000,
+                            offset: 0,
+                            limit_opt: None,
                         })
                     },
                     users_payable_format_opt: None,
@@ -913,6 +1038,7 @@ mod tests {
                 total_paid_receivable_gwei: 1_278_766_555_456,
             }),
             query_results_opt: None,
+            fiat_opt: None,
         };
         let mut context = CommandContextMock::new()
             .transact_params(&transact_params_arc)
@@ -1029,6 +1155,7 @@ mod tests {
                             age_s: 5645405400,
                             balance_gwei: 68843325667,
                             pending_payable_hash_opt: None,
+                            label_opt: None,
                         },
                         UiPayableAccount {
                             wallet: "0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440".to_string(),
@@ -1038,6 +1165,7 @@ mod tests {
                                 "0x0290db1d56121112f4d45c1c3f36348644f6afd20b759b762f1dba9c4949066e"
                                     .to_string(),
                             ),
+                            label_opt: None,
                         },
                     ]),
                     receivable_opt: Some(vec![
@@ -1045,11 +1173,13 @@ mod tests {
                             wallet: "0x6e250504DdfFDb986C4F0bb8Df162503B4118b05".to_string(),
                             age_s: 22000,
                             balance_gwei: 2444533124512,
+                            label_opt: None,
                         },
                         UiReceivableAccount {
                             wallet: "0x8bA50675e590b545D2128905b89039256Eaa24F6".to_string(),
                             age_s: 19000,
                             balance_gwei: -328123256546,
+                            label_opt: None,
                         },
                     ]),
                 }
@@ -1063,10 +1193,12 @@ mod tests {
                             "0x0290db1d56121112f4d45c1c3f36348644f6afd20b759b762f1dba9c4949066e"
                                 .to_string(),
                         ),
+                        label_opt: None,
                     }]),
                     receivable_opt: None,
                 }
             }),
+            fiat_opt: None,
         }
     }
 
@@ -1093,7 +1225,9 @@ mod tests {
                     stats_required: true,
                     top_records_opt: Some(TopRecordsConfig {
                         count: 123,
-                        ordered_by: TopRecordsOrdering::Balance
+                        ordered_by: TopRecordsOrdering::Balance,
+                        offset: 0,
+                        ascending: false,
                     }),
                     custom_queries_opt: None
                 }
@@ -1164,13 +1298,17 @@ mod tests {
                             min_age_s: 0,
                             max_age_s: 350000,
                             min_amount_gwei: 5000000,
-                            max_amount_gwei: 9000000000
+                            max_amount_gwei: 9000000000,
+                            offset: 0,
+                            limit_opt: None,
                         }),
                         receivable_opt: Some(RangeQuery {
                             min_age_s: 5000,
                             max_age_s: 10000,
                             min_amount_gwei: 3000000,
-                            max_amount_gwei: 5600070000
+                            max_amount_gwei: 5600070000,
+                            offset: 0,
+                            limit_opt: None,
                         })
                     })
                 }
@@ -1229,7 +1367,9 @@ mod tests {
                     stats_required: true,
                     top_records_opt: Some(TopRecordsConfig {
                         count: 123,
-                        ordered_by: TopRecordsOrdering::Balance
+                        ordered_by: TopRecordsOrdering::Balance,
+                        offset: 0,
+                        ascending: false,
                     }),
                     custom_queries_opt: None
                 }
@@ -1302,13 +1442,17 @@ mod tests {
                             min_age_s: 0,
                             max_age_s: 350000,
                             min_amount_gwei: 5000000,
-                            max_amount_gwei: 9000000000
+                            max_amount_gwei: 9000000000,
+                            offset: 0,
+                            limit_opt: None,
                         }),
                         receivable_opt: Some(RangeQuery {
                             min_age_s: 5000,
                             max_age_s: 10000,
                             min_amount_gwei: 4000,
-                            max_amount_gwei: 455000000
+                            max_amount_gwei: 455000000,
+                            offset: 0,
+                            limit_opt: None,
                         })
                     })
                 }
@@ -1356,13 +1500,16 @@ mod tests {
                         "0x0290db1d56121112f4d45c1c3f36348644f6afd20b759b762f1dba9c4949066e"
                             .to_string(),
                     ),
+                    label_opt: None,
                 }]),
                 receivable_opt: Some(vec![UiReceivableAccount {
                     wallet: "0x8bA50675e590b545D2128905b89039256Eaa24F6".to_string(),
                     age_s: 45700,
                     balance_gwei: 5050330000,
+                    label_opt: None,
                 }]),
             }),
+            fiat_opt: None,
         };
         let args = slice_of_strs_to_vec_of_strings(&[
             "financials",
@@ -1393,13 +1540,17 @@ mod tests {
                             min_age_s: 0,
                             max_age_s: 350000,
                             min_amount_gwei: 5000000000,
-                            max_amount_gwei: i64::MAX as u64
+                            max_amount_gwei: i64::MAX as u64,
+                            offset: 0,
+                            limit_opt: None,
                         }),
                         receivable_opt: Some(RangeQuery {
                             min_age_s: 5000,
                             max_age_s: 10000,
                             min_amount_gwei: 800000000,
-                            max_amount_gwei: i64::MAX
+                            max_amount_gwei: i64::MAX,
+                            offset: 0,
+                            limit_opt: None,
                         })
                     })
                 }
@@ -1438,6 +1589,7 @@ mod tests {
                 payable_opt: Some(vec![]),
                 receivable_opt: Some(vec![]),
             }),
+            fiat_opt: None,
         };
         let args = slice_of_strs_to_vec_of_strings(&["financials", "--top", "10"]);
         let mut context = CommandContextMock::new()
@@ -1458,7 +1610,9 @@ mod tests {
                     stats_required: true,
                     top_records_opt: Some(TopRecordsConfig {
                         count: 10,
-                        ordered_by: TopRecordsOrdering::Balance
+                        ordered_by: TopRecordsOrdering::Balance,
+                        offset: 0,
+                        ascending: false,
                     }),
                     custom_queries_opt: None
                 }
@@ -1515,6 +1669,7 @@ mod tests {
                 payable_opt: None,
                 receivable_opt: None,
             }),
+            fiat_opt: None,
         };
         let args = slice_of_strs_to_vec_of_strings(&[
             "financials",
@@ -1545,13 +1700,17 @@ mod tests {
                             min_age_s: 0,
                             max_age_s: 400000,
                             min_amount_gwei: 355000000000,
-                            max_amount_gwei: 6000000000000
+                            max_amount_gwei: 6000000000000,
+                            offset: 0,
+                            limit_opt: None,
                         }),
                         receivable_opt: Some(RangeQuery {
                             min_age_s: 40000,
                             max_age_s: 80000,
                             min_amount_gwei: 111000000000,
-                            max_amount_gwei: 10000000000000
+                            max_amount_gwei: 10000000000000,
+                            offset: 0,
+                            limit_opt: None,
                         })
                     })
                 }
@@ -1607,12 +1766,14 @@ mod tests {
                             "0x3648c8b8c7e067ac30b80b6936159326d564dd13b7ae465b26647154ada2c638"
                                 .to_string(),
                         ),
+                        label_opt: None,
                     },
                     UiPayableAccount {
                         wallet: "0xEA674fdac714fd979de3EdF0F56AA9716B198ec8".to_string(),
                         age_s: 28120444,
                         balance_gwei: 97524120,
                         pending_payable_hash_opt: None,
+                        label_opt: None,
                     },
                 ]),
                 receivable_opt: Some(vec![
@@ -1620,14 +1781,17 @@ mod tests {
                         wallet: "0xaa22968a5263f165F014d3F21A443f10a116EDe0".to_string(),
                         age_s: 566668,
                         balance_gwei: 550,
+                        label_opt: None,
                     },
                     UiReceivableAccount {
                         wallet: "0x6e250504DdfFDb986C4F0bb8Df162503B4118b05".to_string(),
                         age_s: 11111111,
                         balance_gwei: -4551012,
+                        label_opt: None,
                     },
                 ]),
             }),
+            fiat_opt: None,
         };
         let args = slice_of_strs_to_vec_of_strings(&["financials", "--no-stats", "--top", "7"]);
         let mut context = CommandContextMock::new()
@@ -1648,7 +1812,9 @@ mod tests {
                     stats_required: false,
                     top_records_opt: Some(TopRecordsConfig {
                         count: 7,
-                        ordered_by: TopRecordsOrdering::Balance
+                        ordered_by: TopRecordsOrdering::Balance,
+                        offset: 0,
+                        ascending: false,
                     }),
                     custom_queries_opt: None
                 }
@@ -1693,22 +1859,26 @@ mod tests {
                             "0x5fe272ed1e941cc05fbd624ec4b1546cd03c25d53e24ba2c18b11feb83cd4581"
                                 .to_string(),
                         ),
+                        label_opt: None,
                     },
                     UiPayableAccount {
                         wallet: "0xA884A2F1A5Ec6C2e499644666a5E6af97B966888".to_string(),
                         age_s: 70000,
                         balance_gwei: 708090,
                         pending_payable_hash_opt: None,
+                        label_opt: None,
                     },
                     UiPayableAccount {
                         wallet: "0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440".to_string(),
                         age_s: 6089909,
                         balance_gwei: 66658,
                         pending_payable_hash_opt: None,
+                        label_opt: None,
                     },
                 ]),
                 receivable_opt: None,
             }),
+            fiat_opt: None,
         };
         let args = slice_of_strs_to_vec_of_strings(&[
             "financials",
@@ -1738,7 +1908,9 @@ mod tests {
                             min_age_s: 3000,
                             max_age_s: 40000,
                             min_amount_gwei: 88000000000,
-                            max_amount_gwei: 1000000000000
+                            max_amount_gwei: 1000000000000,
+                            offset: 0,
+                            limit_opt: None,
                         }),
                         receivable_opt: None
                     })
@@ -1772,19 +1944,23 @@ mod tests {
                         wallet: "0x6e250504DdfFDb986C4F0bb8Df162503B4118b05".to_string(),
                         age_s: 4445,
                         balance_gwei: 9898999888,
+                        label_opt: None,
                     },
                     UiReceivableAccount {
                         wallet: "0xA884A2F1A5Ec6C2e499644666a5E6af97B966888".to_string(),
                         age_s: 70000,
                         balance_gwei: 708090,
+                        label_opt: None,
                     },
                     UiReceivableAccount {
                         wallet: "0x6DbcCaC5596b7ac986ff8F7ca06F212aEB444440".to_string(),
                         age_s: 6089909,
                         balance_gwei: 66658,
+                        label_opt: None,
                     },
                 ]),
             }),
+            fiat_opt: None,
         };
         let args = slice_of_strs_to_vec_of_strings(&[
             "financials",
@@ -1816,7 +1992,9 @@ mod tests {
                             min_age_s: 3000,
                             max_age_s: 40000,
                             min_amount_gwei: 66000000000,
-                            max_amount_gwei: 980000000000
+                            max_amount_gwei: 980000000000,
+                            offset: 0,
+                            limit_opt: None,
                         })
                     })
                 }
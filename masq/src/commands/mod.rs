@@ -7,12 +7,16 @@ pub mod configuration_command;
 pub mod connection_status_command;
 pub mod crash_command;
 pub mod descriptor_command;
+pub mod doctor_command;
 pub mod financials_command;
 pub mod generate_wallets_command;
+pub mod neighborhood_graph_command;
+pub mod ping_neighbor_command;
 pub mod recover_wallets_command;
 pub mod scan_command;
 pub mod set_configuration_command;
 pub mod setup_command;
+pub mod setup_diff_command;
 pub mod shutdown_command;
 pub mod start_command;
 pub mod wallet_addresses_command;
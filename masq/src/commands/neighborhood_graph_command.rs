@@ -0,0 +1,467 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::CommandError::Payload;
+use crate::commands::commands_common::{transaction, Command, CommandError};
+use clap::{App, AppSettings, Arg, SubCommand};
+use masq_lib::as_any_ref_in_trait_impl;
+use masq_lib::constants::NODE_NOT_RUNNING_ERROR;
+use masq_lib::messages::{UiGraphNode, UiNeighborhoodGraphRequest, UiNeighborhoodGraphResponse};
+use masq_lib::short_writeln;
+use serde_json::json;
+use std::collections::HashSet;
+use std::fmt::Debug;
+
+pub const NEIGHBORHOOD_GRAPH_COMMAND_TIMEOUT_MILLIS: u64 = 10000;
+// Long enough to tell Nodes apart in a picture; short enough to keep the picture readable
+pub const KEY_PREFIX_LENGTH: usize = 8;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GraphFormat {
+    Dot,
+    Json,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct NeighborhoodGraphCommand {
+    pub format: GraphFormat,
+    pub include_addresses: bool,
+}
+
+const NEIGHBORHOOD_ABOUT: &str = "Examines the Node's view of the MASQ Network.";
+const EXPORT_GRAPH_ABOUT: &str =
+    "Writes the Nodes the Node knows about, and the neighborships among them, as a graph for \
+     visualization tools. Nodes are labeled with the first few characters of their public keys; \
+     IP addresses are left out unless you ask for them.";
+const FORMAT_ARG_HELP: &str =
+    "dot for Graphviz (the default), or json for anything else. Full neighborships are drawn both \
+     ways; dashed arrows are neighborships only one Node claims.";
+const INCLUDE_ADDRESSES_ARG_HELP: &str =
+    "Include each Node's IP address and ports, if known. Think twice before sharing the result.";
+
+pub fn neighborhood_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("neighborhood")
+        .about(NEIGHBORHOOD_ABOUT)
+        .setting(AppSettings::SubcommandRequired)
+        .subcommand(
+            SubCommand::with_name("export-graph")
+                .about(EXPORT_GRAPH_ABOUT)
+                .arg(
+                    Arg::with_name("format")
+                        .help(FORMAT_ARG_HELP)
+                        .long("format")
+                        .value_name("FORMAT")
+                        .takes_value(true)
+                        .possible_values(&["dot", "json"])
+                        .default_value("dot")
+                        .case_insensitive(true),
+                )
+                .arg(
+                    Arg::with_name("include-addresses")
+                        .help(INCLUDE_ADDRESSES_ARG_HELP)
+                        .long("include-addresses")
+                        .takes_value(false),
+                ),
+        )
+}
+
+impl Command for NeighborhoodGraphCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let input = UiNeighborhoodGraphRequest {
+            include_addresses: self.include_addresses,
+        };
+        let output: Result<UiNeighborhoodGraphResponse, CommandError> =
+            transaction(input, context, NEIGHBORHOOD_GRAPH_COMMAND_TIMEOUT_MILLIS);
+        match output {
+            Ok(response) => {
+                let graph = match self.format {
+                    GraphFormat::Dot => Self::to_dot(&response.nodes),
+                    GraphFormat::Json => Self::to_json(&response.nodes),
+                };
+                short_writeln!(context.stdout(), "{}", graph);
+                Ok(())
+            }
+            Err(Payload(code, message)) if code == NODE_NOT_RUNNING_ERROR => {
+                short_writeln!(
+                    context.stderr(),
+                    "MASQNode is not running; therefore its neighborhood cannot be exported."
+                );
+                Err(Payload(code, message))
+            }
+            Err(e) => {
+                short_writeln!(context.stderr(), "Neighborhood export failed: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Edge {
+    from: String,
+    to: String,
+    full: bool,
+}
+
+impl NeighborhoodGraphCommand {
+    pub fn new(pieces: &[String]) -> Result<Self, String> {
+        let matches = match neighborhood_subcommand().get_matches_from_safe(pieces) {
+            Ok(matches) => matches,
+            Err(e) => return Err(format!("{}", e)),
+        };
+        let export_matches = matches
+            .subcommand_matches("export-graph")
+            .expect("neighborhood subcommand is not properly required");
+        let format = match export_matches
+            .value_of("format")
+            .expect("format parameter is not properly defaulted")
+            .to_lowercase()
+            .as_str()
+        {
+            "json" => GraphFormat::Json,
+            _ => GraphFormat::Dot,
+        };
+        Ok(Self {
+            format,
+            include_addresses: export_matches.is_present("include-addresses"),
+        })
+    }
+
+    fn key_prefix(public_key: &str) -> String {
+        public_key.chars().take(KEY_PREFIX_LENGTH).collect()
+    }
+
+    // One edge per neighborship: a full one is claimed by both Nodes, so it's listed only once
+    fn edges(nodes: &[UiGraphNode]) -> Vec<Edge> {
+        let claims = nodes
+            .iter()
+            .flat_map(|node| {
+                node.neighbors
+                    .iter()
+                    .map(move |neighbor| (node.public_key.as_str(), neighbor.as_str()))
+            })
+            .collect::<HashSet<(&str, &str)>>();
+        nodes
+            .iter()
+            .flat_map(|node| {
+                node.neighbors
+                    .iter()
+                    .map(move |neighbor| (node.public_key.as_str(), neighbor.as_str()))
+            })
+            .filter_map(|(from, to)| {
+                let full = claims.contains(&(to, from));
+                if full && to < from {
+                    None
+                } else {
+                    Some(Edge {
+                        from: Self::key_prefix(from),
+                        to: Self::key_prefix(to),
+                        full,
+                    })
+                }
+            })
+            .collect()
+    }
+
+    fn to_dot(nodes: &[UiGraphNode]) -> String {
+        let mut lines = vec!["digraph neighborhood {".to_string()];
+        nodes.iter().for_each(|node| {
+            let mut label = format!(
+                "{}\\nv{}, degree {}",
+                Self::key_prefix(&node.public_key),
+                node.version,
+                node.neighbors.len()
+            );
+            if let Some(node_addr) = &node.node_addr_opt {
+                label.push_str(&format!("\\n{}", node_addr));
+            }
+            lines.push(format!(
+                "    \"{}\" [label=\"{}\"{}];",
+                Self::key_prefix(&node.public_key),
+                label,
+                if node.is_root {
+                    ", shape=doublecircle"
+                } else {
+                    ""
+                }
+            ));
+        });
+        Self::edges(nodes).into_iter().for_each(|edge| {
+            lines.push(format!(
+                "    \"{}\" -> \"{}\" [{}];",
+                edge.from,
+                edge.to,
+                if edge.full {
+                    "dir=both"
+                } else {
+                    "style=dashed"
+                }
+            ))
+        });
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+
+    fn to_json(nodes: &[UiGraphNode]) -> String {
+        let json_nodes = nodes
+            .iter()
+            .map(|node| {
+                let mut json_node = json!({
+                    "key": Self::key_prefix(&node.public_key),
+                    "version": node.version,
+                    "degree": node.neighbors.len(),
+                    "root": node.is_root,
+                });
+                if let Some(node_addr) = &node.node_addr_opt {
+                    json_node["address"] = json!(node_addr);
+                }
+                json_node
+            })
+            .collect::<Vec<_>>();
+        let json_edges = Self::edges(nodes)
+            .into_iter()
+            .map(|edge| json!({"from": edge.from, "to": edge.to, "full": edge.full}))
+            .collect::<Vec<_>>();
+        serde_json::to_string_pretty(&json!({
+            "nodes": json_nodes,
+            "edges": json_edges,
+        }))
+        .expect("Graph won't serialize")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError;
+    use crate::command_factory::{CommandFactory, CommandFactoryError, CommandFactoryReal};
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::messages::ToMessageBody;
+    use std::sync::{Arc, Mutex};
+
+    const ROOT_KEY: &str = "AQIDBAUGBwgJCgsMDQ4PEA";
+    const NEIGHBOR_KEY: &str = "EBESExQVFhcYGRobHB0eHw";
+    const DISTANT_KEY: &str = "ICEiIyQlJicoKSorLC0uLw";
+
+    fn make_nodes(with_addresses: bool) -> Vec<UiGraphNode> {
+        let addr = |addr: &str| with_addresses.then(|| addr.to_string());
+        vec![
+            UiGraphNode {
+                public_key: ROOT_KEY.to_string(),
+                version: 3,
+                is_root: true,
+                node_addr_opt: addr("1.2.3.4:1234"),
+                neighbors: vec![NEIGHBOR_KEY.to_string()],
+            },
+            UiGraphNode {
+                public_key: NEIGHBOR_KEY.to_string(),
+                version: 7,
+                is_root: false,
+                node_addr_opt: addr("2.3.4.5:2345/3456"),
+                neighbors: vec![ROOT_KEY.to_string(), DISTANT_KEY.to_string()],
+            },
+            UiGraphNode {
+                public_key: DISTANT_KEY.to_string(),
+                version: 1,
+                is_root: false,
+                node_addr_opt: None,
+                neighbors: vec![],
+            },
+        ]
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(NEIGHBORHOOD_GRAPH_COMMAND_TIMEOUT_MILLIS, 10000);
+        assert_eq!(KEY_PREFIX_LENGTH, 8);
+        assert_eq!(
+            NEIGHBORHOOD_ABOUT,
+            "Examines the Node's view of the MASQ Network."
+        );
+        assert_eq!(
+            EXPORT_GRAPH_ABOUT,
+            "Writes the Nodes the Node knows about, and the neighborships among them, as a graph for \
+             visualization tools. Nodes are labeled with the first few characters of their public keys; \
+             IP addresses are left out unless you ask for them."
+        );
+        assert_eq!(
+            FORMAT_ARG_HELP,
+            "dot for Graphviz (the default), or json for anything else. Full neighborships are drawn both \
+             ways; dashed arrows are neighborships only one Node claims."
+        );
+        assert_eq!(
+            INCLUDE_ADDRESSES_ARG_HELP,
+            "Include each Node's IP address and ports, if known. Think twice before sharing the result."
+        );
+    }
+
+    #[test]
+    fn testing_command_factory_with_defaults() {
+        let subject = CommandFactoryReal::new();
+
+        let result = subject
+            .make(&["neighborhood".to_string(), "export-graph".to_string()])
+            .unwrap();
+
+        let command: &NeighborhoodGraphCommand = result.as_any().downcast_ref().unwrap();
+        assert_eq!(
+            command,
+            &NeighborhoodGraphCommand {
+                format: GraphFormat::Dot,
+                include_addresses: false,
+            }
+        );
+    }
+
+    #[test]
+    fn testing_command_factory_with_everything_specified() {
+        let subject = CommandFactoryReal::new();
+
+        let result = subject
+            .make(&[
+                "neighborhood".to_string(),
+                "export-graph".to_string(),
+                "--format".to_string(),
+                "JSON".to_string(),
+                "--include-addresses".to_string(),
+            ])
+            .unwrap();
+
+        let command: &NeighborhoodGraphCommand = result.as_any().downcast_ref().unwrap();
+        assert_eq!(
+            command,
+            &NeighborhoodGraphCommand {
+                format: GraphFormat::Json,
+                include_addresses: true,
+            }
+        );
+    }
+
+    #[test]
+    fn testing_command_factory_without_export_graph() {
+        let subject = CommandFactoryReal::new();
+
+        let result = subject.make(&["neighborhood".to_string()]);
+
+        match result {
+            Err(CommandFactoryError::CommandSyntax(_)) => (),
+            x => panic!("Expected CommandSyntax error, got {:?}", x),
+        }
+    }
+
+    #[test]
+    fn export_graph_writes_dot_without_addresses() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiNeighborhoodGraphResponse {
+                nodes: make_nodes(false),
+            }
+            .tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = NeighborhoodGraphCommand {
+            format: GraphFormat::Dot,
+            include_addresses: false,
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            *transact_params_arc.lock().unwrap(),
+            vec![(
+                UiNeighborhoodGraphRequest {
+                    include_addresses: false
+                }
+                .tmb(0),
+                NEIGHBORHOOD_GRAPH_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "digraph neighborhood {\n\
+             \x20   \"AQIDBAUG\" [label=\"AQIDBAUG\\nv3, degree 1\", shape=doublecircle];\n\
+             \x20   \"EBESExQV\" [label=\"EBESExQV\\nv7, degree 2\"];\n\
+             \x20   \"ICEiIyQl\" [label=\"ICEiIyQl\\nv1, degree 0\"];\n\
+             \x20   \"AQIDBAUG\" -> \"EBESExQV\" [dir=both];\n\
+             \x20   \"EBESExQV\" -> \"ICEiIyQl\" [style=dashed];\n\
+             }\n"
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn export_graph_writes_json_with_addresses() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiNeighborhoodGraphResponse {
+                nodes: make_nodes(true),
+            }
+            .tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let subject = NeighborhoodGraphCommand {
+            format: GraphFormat::Json,
+            include_addresses: true,
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            *transact_params_arc.lock().unwrap(),
+            vec![(
+                UiNeighborhoodGraphRequest {
+                    include_addresses: true
+                }
+                .tmb(0),
+                NEIGHBORHOOD_GRAPH_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        let output: serde_json::Value =
+            serde_json::from_str(&stdout_arc.lock().unwrap().get_string()).unwrap();
+        assert_eq!(
+            output,
+            json!({
+                "nodes": [
+                    {"key": "AQIDBAUG", "version": 3, "degree": 1, "root": true, "address": "1.2.3.4:1234"},
+                    {"key": "EBESExQV", "version": 7, "degree": 2, "root": false, "address": "2.3.4.5:2345/3456"},
+                    {"key": "ICEiIyQl", "version": 1, "degree": 0, "root": false},
+                ],
+                "edges": [
+                    {"from": "AQIDBAUG", "to": "EBESExQV", "full": true},
+                    {"from": "EBESExQV", "to": "ICEiIyQl", "full": false},
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn export_graph_complains_if_node_is_not_running() {
+        let mut context = CommandContextMock::new().transact_result(Err(
+            ContextError::PayloadError(NODE_NOT_RUNNING_ERROR, "irrelevant".to_string()),
+        ));
+        let stderr_arc = context.stderr_arc();
+        let subject = NeighborhoodGraphCommand {
+            format: GraphFormat::Dot,
+            include_addresses: false,
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Payload(
+                NODE_NOT_RUNNING_ERROR,
+                "irrelevant".to_string()
+            ))
+        );
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "MASQNode is not running; therefore its neighborhood cannot be exported.\n"
+        );
+    }
+}
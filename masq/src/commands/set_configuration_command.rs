@@ -69,6 +69,10 @@ const SET_CONFIGURATION_ABOUT: &str =
     "Sets Node configuration parameters being enabled for this operation when the Node is running.";
 const START_BLOCK_HELP: &str =
     "Ordinal number of the Ethereum block where scanning for transactions will start. Use 'latest' or 'none' for Latest block.";
+const DB_PRAGMAS_HELP: &str =
+    "SQLite tuning for the Node's database, as <journal mode>|<synchronous level>|<cache size>|<busy timeout in ms>; \
+     for example WAL|NORMAL|-2000|5000, which is the default. A negative cache size is in KiB, a positive one in pages. \
+     Database connections the Node opens from now on use the new values; restart the Node to have all of them use it.";
 
 pub fn set_configurationify<'a>(shared_schema_arg: Arg<'a, 'a>) -> Arg<'a, 'a> {
     shared_schema_arg.takes_value(true).min_values(1)
@@ -88,9 +92,17 @@ pub fn set_configuration_subcommand() -> App<'static, 'static> {
                 .required(false)
                 .validator(validate_start_block),
         )
+        .arg(
+            Arg::with_name("db-pragmas")
+                .help(DB_PRAGMAS_HELP)
+                .long("db-pragmas")
+                .value_name("DB-PRAGMAS")
+                .takes_value(true)
+                .required(false),
+        )
         .group(
             ArgGroup::with_name("parameter")
-                .args(&["gas-price", "min-hops", "start-block"])
+                .args(&["gas-price", "min-hops", "start-block", "db-pragmas"])
                 .required(true),
         )
 }
@@ -114,6 +126,12 @@ mod tests {
             START_BLOCK_HELP,
             "Ordinal number of the Ethereum block where scanning for transactions will start. Use 'latest' or 'none' for Latest block."
         );
+        assert_eq!(
+            DB_PRAGMAS_HELP,
+            "SQLite tuning for the Node's database, as <journal mode>|<synchronous level>|<cache size>|<busy timeout in ms>; \
+             for example WAL|NORMAL|-2000|5000, which is the default. A negative cache size is in KiB, a positive one in pages. \
+             Database connections the Node opens from now on use the new values; restart the Node to have all of them use it."
+        );
     }
 
     #[test]
@@ -160,6 +178,7 @@ mod tests {
         test_command_execution("--start-block", "123456");
         test_command_execution("--gas-price", "123456");
         test_command_execution("--min-hops", "6");
+        test_command_execution("--db-pragmas", "WAL|FULL|-8000|10000");
     }
 
     #[test]
@@ -167,6 +186,7 @@ mod tests {
         set_configuration_command_throws_err_for_missing_value("--start-block");
         set_configuration_command_throws_err_for_missing_value("--gas-price");
         set_configuration_command_throws_err_for_missing_value("--min-hops");
+        set_configuration_command_throws_err_for_missing_value("--db-pragmas");
     }
 
     #[test]
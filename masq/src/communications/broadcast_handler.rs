@@ -6,8 +6,10 @@ use crate::notifications::crashed_notification::CrashNotifier;
 use crate::terminal::terminal_interface::TerminalWrapper;
 use crossbeam_channel::{unbounded, RecvError, Sender};
 use masq_lib::messages::{
-    FromMessageBody, UiConnectionChangeBroadcast, UiLogBroadcast, UiNewPasswordBroadcast,
-    UiNodeCrashedBroadcast, UiSetupBroadcast, UiUndeliveredFireAndForget,
+    FromMessageBody, UiActorRestartedBroadcast, UiAlertBroadcast, UiBanRiskBroadcast,
+    UiConnectionChangeBroadcast, UiLogBroadcast, UiNewPasswordBroadcast, UiNodeCrashedBroadcast,
+    UiProgressBroadcast, UiSetupBroadcast, UiTransactionFeeBalanceBroadcast,
+    UiUndeliveredFireAndForget,
 };
 use masq_lib::ui_gateway::MessageBody;
 use masq_lib::utils::ExpectValue;
@@ -16,7 +18,12 @@ use std::fmt::Debug;
 use std::io::Write;
 use std::thread;
 
+use crate::notifications::actor_restarted_notification::ActorRestartedNotification;
+use crate::notifications::alert_notification::AlertNotification;
+use crate::notifications::ban_risk_notification::BanRiskNotification;
 use crate::notifications::connection_change_notification::ConnectionChangeNotification;
+use crate::notifications::progress_notification::ProgressNotification;
+use crate::notifications::transaction_fee_balance_notification::TransactionFeeBalanceNotification;
 
 pub trait BroadcastHandle: Send {
     fn send(&self, message_body: MessageBody);
@@ -111,6 +118,22 @@ impl BroadcastHandlerReal {
                         stdout,
                         terminal_interface,
                     );
+                } else if let Ok((body, _)) =
+                    UiTransactionFeeBalanceBroadcast::fmb(message_body.clone())
+                {
+                    TransactionFeeBalanceNotification::handle_broadcast(
+                        body,
+                        stdout,
+                        terminal_interface,
+                    );
+                } else if let Ok((body, _)) = UiActorRestartedBroadcast::fmb(message_body.clone()) {
+                    ActorRestartedNotification::handle_broadcast(body, stdout, terminal_interface);
+                } else if let Ok((body, _)) = UiAlertBroadcast::fmb(message_body.clone()) {
+                    AlertNotification::handle_broadcast(body, stdout, terminal_interface);
+                } else if let Ok((body, _)) = UiBanRiskBroadcast::fmb(message_body.clone()) {
+                    BanRiskNotification::handle_broadcast(body, stdout, terminal_interface);
+                } else if let Ok((body, _)) = UiProgressBroadcast::fmb(message_body.clone()) {
+                    ProgressNotification::handle_broadcast(body, stdout, terminal_interface);
                 } else {
                     handle_unrecognized_broadcast(message_body, stderr, terminal_interface)
                 }
@@ -215,6 +238,7 @@ mod tests {
                 UiSetupResponseValue::new("data-directory", "/home/booga", Default),
             ],
             errors: vec![],
+            revision: 0,
         }
         .tmb(0);
 
@@ -397,6 +421,7 @@ mod tests {
                 UiSetupResponseValue::new("data-directory", "/home/booga", Default),
             ],
             errors: vec![],
+            revision: 0,
         }
         .tmb(0);
 
@@ -457,29 +482,35 @@ mod tests {
                     name: "ip".to_string(),
                     value: "4.4.4.4".to_string(),
                     status: UiSetupResponseValueStatus::Set,
+                    provenance_opt: None,
                 },
                 UiSetupResponseValue {
                     name: "neighborhood-mode".to_string(),
                     value: "standard".to_string(),
                     status: UiSetupResponseValueStatus::Default,
+                    provenance_opt: None,
                 },
                 UiSetupResponseValue {
                     name: "chain".to_string(),
                     value: "ropsten".to_string(),
                     status: UiSetupResponseValueStatus::Configured,
+                    provenance_opt: None,
                 },
                 UiSetupResponseValue {
                     name: "log-level".to_string(),
                     value: "error".to_string(),
                     status: UiSetupResponseValueStatus::Set,
+                    provenance_opt: None,
                 },
                 UiSetupResponseValue {
                     name: "data-directory".to_string(),
                     value: "/home/booga".to_string(),
                     status: UiSetupResponseValueStatus::Default,
+                    provenance_opt: None,
                 },
             ],
             errors: vec![],
+            revision: 0,
         };
 
         //for the sake of simplification, tested on a small sample of setup parameters
@@ -17,6 +17,7 @@ use std::net::TcpStream;
 use std::thread;
 use std::thread::JoinHandle;
 use std::time::Duration;
+use websocket::header::Headers;
 use websocket::sender::Writer;
 use websocket::sync::Client;
 use websocket::ws::sender::Sender as WsSender;
@@ -160,7 +161,15 @@ fn make_client_listener(
 ) -> Result<Writer<TcpStream>, ClientListenerError> {
     let url = format!("ws://{}:{}", localhost(), port);
     let builder = ClientBuilder::new(url.as_str()).expect("Bad URL");
-    let result = builder.add_protocol(NODE_UI_PROTOCOL);
+    // The Node keeps this in its connection audit, so that an operator can tell the UIs apart
+    let mut headers = Headers::new();
+    headers.set_raw(
+        "User-Agent",
+        vec![format!("masq/{}", env!("CARGO_PKG_VERSION")).into_bytes()],
+    );
+    let result = builder
+        .add_protocol(NODE_UI_PROTOCOL)
+        .custom_headers(&headers);
     let result = match connect_insecure_timeout(result, timeout_millis) {
         Err(RecvTimeoutError::Disconnected) => return Err(ClientListenerError::Closed),
         Err(RecvTimeoutError::Timeout) => return Err(ClientListenerError::Timeout),
@@ -868,6 +877,8 @@ mod tests {
             running: false,
             values: vec![],
             errors: vec![],
+            command_line: vec![],
+            revision: 0,
         }
         .tmb(4);
         let daemon = MockWebSocketsServer::new(daemon_port)
@@ -899,13 +910,13 @@ mod tests {
         let _ = ConnectionManagerThread::handle_outgoing_message_body(
             inner,
             Ok(OutgoingMessageType::ConversationMessage(
-                UiSetupRequest { values: vec![] }.tmb(4),
+                UiSetupRequest::new(vec![]).tmb(4),
             )),
         );
         let mut outgoing_messages = stop_handle.stop();
         assert_eq!(
             outgoing_messages.remove(0),
-            Ok(UiSetupRequest { values: vec![] }.tmb(4))
+            Ok(UiSetupRequest::new(vec![]).tmb(4))
         );
     }
 
@@ -1062,6 +1073,8 @@ mod tests {
             running: false,
             values: vec![],
             errors: vec![],
+            command_line: vec![],
+            revision: 0,
         }
         .tmb(4);
         let daemon = MockWebSocketsServer::new(daemon_port)
@@ -1096,13 +1109,13 @@ mod tests {
         let _ = ConnectionManagerThread::handle_outgoing_message_body(
             inner,
             Ok(OutgoingMessageType::ConversationMessage(
-                UiSetupRequest { values: vec![] }.tmb(4),
+                UiSetupRequest::new(vec![]).tmb(4),
             )),
         );
         let mut outgoing_messages = stop_handle.stop();
         assert_eq!(
             outgoing_messages.remove(0),
-            Ok(UiSetupRequest { values: vec![] }.tmb(4))
+            Ok(UiSetupRequest::new(vec![]).tmb(4))
         );
     }
 
@@ -1136,6 +1149,7 @@ mod tests {
             running: false,
             values: vec![],
             errors: vec![],
+            revision: 0,
         }
         .tmb(0);
         let (conversation_tx, conversation_rx) = unbounded();
@@ -1178,6 +1192,7 @@ mod tests {
                     total_paid_receivable_gwei: 32,
                 }),
                 query_results_opt: None,
+                fiat_opt: None,
             }
             .tmb(1),
         );
@@ -1219,7 +1234,8 @@ mod tests {
                     total_unpaid_receivable_gwei: 29,
                     total_paid_receivable_gwei: 32,
                 }),
-                query_results_opt: None
+                query_results_opt: None,
+                fiat_opt: None,
             }
         );
         assert_eq!(context_id, 1);
@@ -1234,6 +1250,8 @@ mod tests {
             running: false,
             values: vec![],
             errors: vec![],
+            command_line: vec![],
+            revision: 0,
         }
         .tmb(3);
         let (conversation_tx, conversation_rx) = unbounded();
@@ -1256,6 +1274,8 @@ mod tests {
             running: false,
             values: vec![],
             errors: vec![],
+            command_line: vec![],
+            revision: 0,
         }
         .tmb(4);
         let (conversation_tx, _) = unbounded();
@@ -1354,7 +1374,7 @@ mod tests {
         inner = ConnectionManagerThread::handle_outgoing_message_body(
             inner,
             Ok(OutgoingMessageType::ConversationMessage(
-                UiSetupRequest { values: vec![] }.tmb(2),
+                UiSetupRequest::new(vec![]).tmb(2),
             )),
         );
 
@@ -1383,7 +1403,7 @@ mod tests {
         inner = ConnectionManagerThread::handle_outgoing_message_body(
             inner,
             Ok(OutgoingMessageType::ConversationMessage(
-                UiSetupRequest { values: vec![] }.tmb(42),
+                UiSetupRequest::new(vec![]).tmb(42),
             )),
         );
 
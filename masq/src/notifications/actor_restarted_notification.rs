@@ -0,0 +1,57 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::terminal::terminal_interface::TerminalWrapper;
+use masq_lib::messages::UiActorRestartedBroadcast;
+use masq_lib::short_writeln;
+use std::io::Write;
+
+pub struct ActorRestartedNotification {}
+
+impl ActorRestartedNotification {
+    pub fn handle_broadcast(
+        response: UiActorRestartedBroadcast,
+        stdout: &mut dyn Write,
+        term_interface: &TerminalWrapper,
+    ) {
+        let _lock = term_interface.lock();
+        short_writeln!(
+            stdout,
+            "\nThe Node's {} panicked and was restarted with clean state (restart #{}): {}\n",
+            response.actor,
+            response.restart_count,
+            response.panic_message
+        );
+        stdout.flush().expect("flush failed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::mocks::TerminalPassiveMock;
+    use masq_lib::test_utils::fake_stream_holder::ByteArrayWriter;
+    use masq_lib::utils::running_test;
+    use std::sync::Arc;
+
+    #[test]
+    fn broadcasts_actor_restart() {
+        running_test();
+        let mut stdout = ByteArrayWriter::new();
+        let term_interface = TerminalWrapper::new(Arc::new(TerminalPassiveMock::new()));
+
+        ActorRestartedNotification::handle_broadcast(
+            UiActorRestartedBroadcast {
+                actor: "ProxyClient".to_string(),
+                panic_message: "Hopper is dead".to_string(),
+                restart_count: 2,
+            },
+            &mut stdout,
+            &term_interface,
+        );
+
+        assert_eq!(
+            stdout.get_string(),
+            "\nThe Node's ProxyClient panicked and was restarted with clean state (restart #2): Hopper is dead\n\n"
+        );
+    }
+}
@@ -0,0 +1,59 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::terminal::terminal_interface::TerminalWrapper;
+use masq_lib::messages::UiAlertBroadcast;
+use masq_lib::short_writeln;
+use std::io::Write;
+
+pub struct AlertNotification {}
+
+impl AlertNotification {
+    pub fn handle_broadcast(
+        response: UiAlertBroadcast,
+        stdout: &mut dyn Write,
+        term_interface: &TerminalWrapper,
+    ) {
+        let _lock = term_interface.lock();
+        short_writeln!(
+            stdout,
+            "\nAlert ({:?}): {}\n",
+            response.rule,
+            response.message
+        );
+        stdout.flush().expect("flush failed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::mocks::TerminalPassiveMock;
+    use masq_lib::messages::UiAlertRule;
+    use masq_lib::test_utils::fake_stream_holder::ByteArrayWriter;
+    use masq_lib::utils::running_test;
+    use std::sync::Arc;
+
+    #[test]
+    fn broadcasts_alert() {
+        running_test();
+        let mut stdout = ByteArrayWriter::new();
+        let term_interface = TerminalWrapper::new(Arc::new(TerminalPassiveMock::new()));
+
+        AlertNotification::handle_broadcast(
+            UiAlertBroadcast {
+                rule: UiAlertRule::ExcessivePayables,
+                message: "This Node owes 6000000000000000000 wei in all, more than the \
+                          5000000000 gwei alert threshold"
+                    .to_string(),
+            },
+            &mut stdout,
+            &term_interface,
+        );
+
+        assert_eq!(
+            stdout.get_string(),
+            "\nAlert (ExcessivePayables): This Node owes 6000000000000000000 wei in all, more than \
+             the 5000000000 gwei alert threshold\n\n"
+        );
+    }
+}
@@ -0,0 +1,103 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::terminal::terminal_interface::TerminalWrapper;
+use masq_lib::messages::UiBanRiskBroadcast;
+use masq_lib::short_writeln;
+use std::io::Write;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub struct BanRiskNotification {}
+
+impl BanRiskNotification {
+    pub fn handle_broadcast(
+        response: UiBanRiskBroadcast,
+        stdout: &mut dyn Write,
+        term_interface: &TerminalWrapper,
+    ) {
+        let _lock = term_interface.lock();
+        short_writeln!(
+            stdout,
+            "\nThese creditors are likely to ban this Node unless they're paid:"
+        );
+        response.bans.iter().for_each(|ban| {
+            short_writeln!(
+                stdout,
+                "  {} (owed {} gwei), at {}: {:?}. {}",
+                ban.wallet,
+                ban.balance_gwei,
+                Self::render_timestamp(ban.ban_timestamp),
+                ban.cause,
+                ban.detail
+            )
+        });
+        short_writeln!(
+            stdout,
+            "Check the blockchain-service-url and the gas price, and that the consuming wallet \
+             has funds for gas\n"
+        );
+        stdout.flush().expect("flush failed");
+    }
+
+    fn render_timestamp(timestamp: i64) -> String {
+        let time = UNIX_EPOCH + Duration::from_secs(timestamp.max(0) as u64);
+        let now = SystemTime::now();
+        match time.duration_since(now) {
+            Ok(duration) => format!("{} (in {} seconds)", timestamp, duration.as_secs()),
+            Err(_) => format!("{} (already past)", timestamp),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::mocks::TerminalPassiveMock;
+    use masq_lib::messages::{UiBanRiskCause, UiImminentBan};
+    use masq_lib::test_utils::fake_stream_holder::ByteArrayWriter;
+    use masq_lib::utils::running_test;
+    use std::sync::Arc;
+
+    #[test]
+    fn broadcasts_ban_risks() {
+        running_test();
+        let mut stdout = ByteArrayWriter::new();
+        let term_interface = TerminalWrapper::new(Arc::new(TerminalPassiveMock::new()));
+
+        BanRiskNotification::handle_broadcast(
+            UiBanRiskBroadcast {
+                bans: vec![
+                    UiImminentBan {
+                        wallet: "0x0000000000000000000000000000000000000001".to_string(),
+                        balance_gwei: 1_234_567,
+                        ban_timestamp: 1_000_000,
+                        cause: UiBanRiskCause::PaymentFailing,
+                        detail:
+                            "The last attempt to pay creditors failed: Signing phase: \"booga\""
+                                .to_string(),
+                    },
+                    UiImminentBan {
+                        wallet: "0x0000000000000000000000000000000000000002".to_string(),
+                        balance_gwei: 7_654_321,
+                        ban_timestamp: 2_000_000,
+                        cause: UiBanRiskCause::Unpaid,
+                        detail: "The debt should have been paid 10 seconds ago".to_string(),
+                    },
+                ],
+            },
+            &mut stdout,
+            &term_interface,
+        );
+
+        assert_eq!(
+            stdout.get_string(),
+            "\nThese creditors are likely to ban this Node unless they're paid:\n  \
+             0x0000000000000000000000000000000000000001 (owed 1234567 gwei), at 1000000 (already \
+             past): PaymentFailing. The last attempt to pay creditors failed: Signing phase: \
+             \"booga\"\n  \
+             0x0000000000000000000000000000000000000002 (owed 7654321 gwei), at 2000000 (already \
+             past): Unpaid. The debt should have been paid 10 seconds ago\n\
+             Check the blockchain-service-url and the gas price, and that the consuming wallet has \
+             funds for gas\n\n"
+        );
+    }
+}
@@ -1,4 +1,9 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+pub mod actor_restarted_notification;
+pub mod alert_notification;
+pub mod ban_risk_notification;
 pub mod connection_change_notification;
 pub mod crashed_notification;
+pub mod progress_notification;
+pub mod transaction_fee_balance_notification;
@@ -0,0 +1,87 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::terminal::terminal_interface::TerminalWrapper;
+use masq_lib::messages::UiProgressBroadcast;
+use masq_lib::short_writeln;
+use std::io::Write;
+
+pub struct ProgressNotification {}
+
+impl ProgressNotification {
+    pub fn handle_broadcast(
+        response: UiProgressBroadcast,
+        stdout: &mut dyn Write,
+        term_interface: &TerminalWrapper,
+    ) {
+        let steps = match response.total_opt {
+            Some(total) => format!("{} of {}", response.completed, total),
+            None => format!("{} done", response.completed),
+        };
+        let _lock = term_interface.lock();
+        short_writeln!(
+            stdout,
+            "\n{}: {} ({})\n",
+            response.opcode,
+            response.stage,
+            steps
+        );
+        stdout.flush().expect("flush failed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::mocks::TerminalPassiveMock;
+    use masq_lib::test_utils::fake_stream_holder::ByteArrayWriter;
+    use masq_lib::utils::running_test;
+    use std::sync::Arc;
+
+    #[test]
+    fn broadcasts_progress_with_a_known_total() {
+        running_test();
+        let mut stdout = ByteArrayWriter::new();
+        let term_interface = TerminalWrapper::new(Arc::new(TerminalPassiveMock::new()));
+
+        ProgressNotification::handle_broadcast(
+            UiProgressBroadcast {
+                context_id: 123,
+                opcode: "databaseMaintenance".to_string(),
+                stage: "vacuuming".to_string(),
+                completed: 1,
+                total_opt: Some(3),
+            },
+            &mut stdout,
+            &term_interface,
+        );
+
+        assert_eq!(
+            stdout.get_string(),
+            "\ndatabaseMaintenance: vacuuming (1 of 3)\n\n"
+        );
+    }
+
+    #[test]
+    fn broadcasts_progress_with_an_unknown_total() {
+        running_test();
+        let mut stdout = ByteArrayWriter::new();
+        let term_interface = TerminalWrapper::new(Arc::new(TerminalPassiveMock::new()));
+
+        ProgressNotification::handle_broadcast(
+            UiProgressBroadcast {
+                context_id: 123,
+                opcode: "databaseMaintenance".to_string(),
+                stage: "measuring".to_string(),
+                completed: 2,
+                total_opt: None,
+            },
+            &mut stdout,
+            &term_interface,
+        );
+
+        assert_eq!(
+            stdout.get_string(),
+            "\ndatabaseMaintenance: measuring (2 done)\n\n"
+        );
+    }
+}
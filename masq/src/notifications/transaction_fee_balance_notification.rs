@@ -0,0 +1,92 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::terminal::terminal_interface::TerminalWrapper;
+use masq_lib::messages::{UiTransactionFeeBalanceBroadcast, UiTransactionFeeBalanceLevel};
+use masq_lib::short_writeln;
+use std::io::Write;
+
+pub struct TransactionFeeBalanceNotification {}
+
+impl TransactionFeeBalanceNotification {
+    pub fn handle_broadcast(
+        response: UiTransactionFeeBalanceBroadcast,
+        stdout: &mut dyn Write,
+        term_interface: &TerminalWrapper,
+    ) {
+        let _lock = term_interface.lock();
+        let output_string = match response.level {
+            UiTransactionFeeBalanceLevel::Sufficient => format!(
+                "\nThe consuming wallet's transaction fee balance is sufficient again: it covers {} more transactions.\n",
+                response.affordable_transactions
+            ),
+            UiTransactionFeeBalanceLevel::Low => format!(
+                "\nWarning: the consuming wallet's transaction fee balance covers only {} more transactions. Consider topping it up.\n",
+                response.affordable_transactions
+            ),
+            UiTransactionFeeBalanceLevel::Critical => format!(
+                "\nWarning: the consuming wallet's transaction fee balance covers only {} more transactions. Top it up before payments start failing.\n",
+                response.affordable_transactions
+            ),
+            UiTransactionFeeBalanceLevel::Depleted => "\nWarning: the consuming wallet can't pay the transaction fee for another payment. Payments will fail until it's topped up.\n".to_string(),
+        };
+        short_writeln!(stdout, "{}", output_string);
+        stdout.flush().expect("flush failed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::mocks::TerminalPassiveMock;
+    use masq_lib::test_utils::fake_stream_holder::ByteArrayWriter;
+    use masq_lib::utils::running_test;
+    use std::sync::Arc;
+
+    fn make_broadcast(
+        level: UiTransactionFeeBalanceLevel,
+        affordable_transactions: u64,
+    ) -> UiTransactionFeeBalanceBroadcast {
+        UiTransactionFeeBalanceBroadcast {
+            level,
+            balance_wei: 1_000,
+            fee_per_transaction_wei: 100,
+            affordable_transactions,
+        }
+    }
+
+    #[test]
+    fn broadcasts_critical_balance() {
+        running_test();
+        let mut stdout = ByteArrayWriter::new();
+        let term_interface = TerminalWrapper::new(Arc::new(TerminalPassiveMock::new()));
+
+        TransactionFeeBalanceNotification::handle_broadcast(
+            make_broadcast(UiTransactionFeeBalanceLevel::Critical, 3),
+            &mut stdout,
+            &term_interface,
+        );
+
+        assert_eq!(
+            stdout.get_string(),
+            "\nWarning: the consuming wallet's transaction fee balance covers only 3 more transactions. Top it up before payments start failing.\n\n"
+        );
+    }
+
+    #[test]
+    fn broadcasts_depleted_balance() {
+        running_test();
+        let mut stdout = ByteArrayWriter::new();
+        let term_interface = TerminalWrapper::new(Arc::new(TerminalPassiveMock::new()));
+
+        TransactionFeeBalanceNotification::handle_broadcast(
+            make_broadcast(UiTransactionFeeBalanceLevel::Depleted, 0),
+            &mut stdout,
+            &term_interface,
+        );
+
+        assert_eq!(
+            stdout.get_string(),
+            "\nWarning: the consuming wallet can't pay the transaction fee for another payment. Payments will fail until it's topped up.\n\n"
+        );
+    }
+}
@@ -8,8 +8,11 @@ use crate::commands::configuration_command::configuration_subcommand;
 use crate::commands::connection_status_command::connection_status_subcommand;
 use crate::commands::crash_command::crash_subcommand;
 use crate::commands::descriptor_command::descriptor_subcommand;
+use crate::commands::doctor_command::doctor_subcommand;
 use crate::commands::financials_command::args_validation::financials_subcommand;
 use crate::commands::generate_wallets_command::generate_wallets_subcommand;
+use crate::commands::neighborhood_graph_command::neighborhood_subcommand;
+use crate::commands::ping_neighbor_command::ping_neighbor_subcommand;
 use crate::commands::recover_wallets_command::recover_wallets_subcommand;
 use crate::commands::scan_command::scan_subcommand;
 use crate::commands::set_configuration_command::set_configuration_subcommand;
@@ -67,8 +70,11 @@ pub fn app() -> App<'static, 'static> {
         .subcommand(configuration_subcommand())
         .subcommand(connection_status_subcommand())
         .subcommand(descriptor_subcommand())
+        .subcommand(doctor_subcommand())
         .subcommand(financials_subcommand())
         .subcommand(generate_wallets_subcommand())
+        .subcommand(neighborhood_subcommand())
+        .subcommand(ping_neighbor_subcommand())
         .subcommand(recover_wallets_subcommand())
         .subcommand(scan_subcommand())
         .subcommand(set_configuration_subcommand())
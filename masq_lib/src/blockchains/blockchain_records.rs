@@ -3,13 +3,14 @@
 use crate::blockchains::chains::Chain;
 use crate::constants::{
     BASE_MAINNET_CONTRACT_CREATION_BLOCK, BASE_MAINNET_FULL_IDENTIFIER,
-    BASE_SEPOLIA_CONTRACT_CREATION_BLOCK, BASE_SEPOLIA_FULL_IDENTIFIER, DEV_CHAIN_FULL_IDENTIFIER,
-    ETH_MAINNET_CONTRACT_CREATION_BLOCK, ETH_MAINNET_FULL_IDENTIFIER,
+    BASE_SEPOLIA_CONTRACT_CREATION_BLOCK, BASE_SEPOLIA_FULL_IDENTIFIER, DEFAULT_GAS_PRICE,
+    DEV_CHAIN_FULL_IDENTIFIER, ETH_MAINNET_CONTRACT_CREATION_BLOCK, ETH_MAINNET_FULL_IDENTIFIER,
     ETH_ROPSTEN_CONTRACT_CREATION_BLOCK, ETH_ROPSTEN_FULL_IDENTIFIER,
     MULTINODE_TESTNET_CONTRACT_CREATION_BLOCK, POLYGON_AMOY_CONTRACT_CREATION_BLOCK,
     POLYGON_AMOY_FULL_IDENTIFIER, POLYGON_MAINNET_CONTRACT_CREATION_BLOCK,
     POLYGON_MAINNET_FULL_IDENTIFIER,
 };
+use crate::messages::UiChainRecord;
 use ethereum_types::{Address, H160};
 
 pub const CHAINS: [BlockchainRecord; 7] = [
@@ -19,6 +20,7 @@ pub const CHAINS: [BlockchainRecord; 7] = [
         literal_identifier: POLYGON_MAINNET_FULL_IDENTIFIER,
         contract: POLYGON_MAINNET_CONTRACT_ADDRESS,
         contract_creation_block: POLYGON_MAINNET_CONTRACT_CREATION_BLOCK,
+        explorer_url_opt: Some("https://polygonscan.com"),
     },
     BlockchainRecord {
         self_id: Chain::EthMainnet,
@@ -26,6 +28,7 @@ pub const CHAINS: [BlockchainRecord; 7] = [
         literal_identifier: ETH_MAINNET_FULL_IDENTIFIER,
         contract: ETH_MAINNET_CONTRACT_ADDRESS,
         contract_creation_block: ETH_MAINNET_CONTRACT_CREATION_BLOCK,
+        explorer_url_opt: Some("https://etherscan.io"),
     },
     BlockchainRecord {
         self_id: Chain::BaseMainnet,
@@ -33,6 +36,7 @@ pub const CHAINS: [BlockchainRecord; 7] = [
         literal_identifier: BASE_MAINNET_FULL_IDENTIFIER,
         contract: BASE_MAINNET_CONTRACT_ADDRESS,
         contract_creation_block: BASE_MAINNET_CONTRACT_CREATION_BLOCK,
+        explorer_url_opt: Some("https://basescan.org"),
     },
     BlockchainRecord {
         self_id: Chain::BaseSepolia,
@@ -40,6 +44,7 @@ pub const CHAINS: [BlockchainRecord; 7] = [
         literal_identifier: BASE_SEPOLIA_FULL_IDENTIFIER,
         contract: BASE_SEPOLIA_TESTNET_CONTRACT_ADDRESS,
         contract_creation_block: BASE_SEPOLIA_CONTRACT_CREATION_BLOCK,
+        explorer_url_opt: Some("https://sepolia.basescan.org"),
     },
     BlockchainRecord {
         self_id: Chain::PolyAmoy,
@@ -47,6 +52,7 @@ pub const CHAINS: [BlockchainRecord; 7] = [
         literal_identifier: POLYGON_AMOY_FULL_IDENTIFIER,
         contract: POLYGON_AMOY_TESTNET_CONTRACT_ADDRESS,
         contract_creation_block: POLYGON_AMOY_CONTRACT_CREATION_BLOCK,
+        explorer_url_opt: Some("https://amoy.polygonscan.com"),
     },
     BlockchainRecord {
         self_id: Chain::EthRopsten,
@@ -54,6 +60,7 @@ pub const CHAINS: [BlockchainRecord; 7] = [
         literal_identifier: ETH_ROPSTEN_FULL_IDENTIFIER,
         contract: ETH_ROPSTEN_TESTNET_CONTRACT_ADDRESS,
         contract_creation_block: ETH_ROPSTEN_CONTRACT_CREATION_BLOCK,
+        explorer_url_opt: Some("https://ropsten.etherscan.io"),
     },
     BlockchainRecord {
         self_id: Chain::Dev,
@@ -61,6 +68,7 @@ pub const CHAINS: [BlockchainRecord; 7] = [
         literal_identifier: DEV_CHAIN_FULL_IDENTIFIER,
         contract: MULTINODE_TESTNET_CONTRACT_ADDRESS,
         contract_creation_block: MULTINODE_TESTNET_CONTRACT_CREATION_BLOCK,
+        explorer_url_opt: None,
     },
 ];
 
@@ -71,6 +79,21 @@ pub struct BlockchainRecord {
     pub literal_identifier: &'static str,
     pub contract: Address,
     pub contract_creation_block: u64,
+    pub explorer_url_opt: Option<&'static str>,
+}
+
+impl From<&BlockchainRecord> for UiChainRecord {
+    fn from(record: &BlockchainRecord) -> Self {
+        UiChainRecord {
+            literal_identifier: record.literal_identifier.to_string(),
+            chain_id: record.num_chain_id,
+            contract_address: format!("{:?}", record.contract),
+            contract_creation_block: record.contract_creation_block,
+            default_gas_price_gwei: DEFAULT_GAS_PRICE,
+            explorer_url_opt: record.explorer_url_opt.map(|url| url.to_string()),
+            is_testnet: !record.self_id.is_mainnet(),
+        }
+    }
 }
 
 const POLYGON_MAINNET_CONTRACT_ADDRESS: Address = H160([
@@ -196,6 +219,7 @@ mod tests {
                 literal_identifier: "eth-mainnet",
                 contract: ETH_MAINNET_CONTRACT_ADDRESS,
                 contract_creation_block: ETH_MAINNET_CONTRACT_CREATION_BLOCK,
+                explorer_url_opt: Some("https://etherscan.io"),
             }
         );
     }
@@ -212,6 +236,7 @@ mod tests {
                 literal_identifier: "eth-ropsten",
                 contract: ETH_ROPSTEN_TESTNET_CONTRACT_ADDRESS,
                 contract_creation_block: ETH_ROPSTEN_CONTRACT_CREATION_BLOCK,
+                explorer_url_opt: Some("https://ropsten.etherscan.io"),
             }
         );
     }
@@ -228,6 +253,7 @@ mod tests {
                 literal_identifier: "polygon-mainnet",
                 contract: POLYGON_MAINNET_CONTRACT_ADDRESS,
                 contract_creation_block: POLYGON_MAINNET_CONTRACT_CREATION_BLOCK,
+                explorer_url_opt: Some("https://polygonscan.com"),
             }
         );
     }
@@ -244,6 +270,7 @@ mod tests {
                 literal_identifier: "polygon-amoy",
                 contract: POLYGON_AMOY_TESTNET_CONTRACT_ADDRESS,
                 contract_creation_block: POLYGON_AMOY_CONTRACT_CREATION_BLOCK,
+                explorer_url_opt: Some("https://amoy.polygonscan.com"),
             }
         );
     }
@@ -260,6 +287,7 @@ mod tests {
                 literal_identifier: "base-mainnet",
                 contract: BASE_MAINNET_CONTRACT_ADDRESS,
                 contract_creation_block: BASE_MAINNET_CONTRACT_CREATION_BLOCK,
+                explorer_url_opt: Some("https://basescan.org"),
             }
         );
     }
@@ -276,6 +304,7 @@ mod tests {
                 literal_identifier: "base-sepolia",
                 contract: BASE_SEPOLIA_TESTNET_CONTRACT_ADDRESS,
                 contract_creation_block: BASE_SEPOLIA_CONTRACT_CREATION_BLOCK,
+                explorer_url_opt: Some("https://sepolia.basescan.org"),
             }
         );
     }
@@ -292,8 +321,31 @@ mod tests {
                 literal_identifier: "dev",
                 contract: MULTINODE_TESTNET_CONTRACT_ADDRESS,
                 contract_creation_block: MULTINODE_TESTNET_CONTRACT_CREATION_BLOCK,
+                explorer_url_opt: None,
+            }
+        );
+    }
+
+    #[test]
+    fn blockchain_record_converts_to_ui_chain_record() {
+        let mainnet = UiChainRecord::from(Chain::PolyMainnet.rec());
+        let testnet = UiChainRecord::from(Chain::Dev.rec());
+
+        assert_eq!(
+            mainnet,
+            UiChainRecord {
+                literal_identifier: "polygon-mainnet".to_string(),
+                chain_id: 137,
+                contract_address: "0xee9a352f6aac4af1a5b9f467f6a93e0ffbe9dd35".to_string(),
+                contract_creation_block: POLYGON_MAINNET_CONTRACT_CREATION_BLOCK,
+                default_gas_price_gwei: DEFAULT_GAS_PRICE,
+                explorer_url_opt: Some("https://polygonscan.com".to_string()),
+                is_testnet: false,
             }
         );
+        assert_eq!(testnet.literal_identifier, "dev".to_string());
+        assert_eq!(testnet.explorer_url_opt, None);
+        assert_eq!(testnet.is_testnet, true);
     }
 
     fn return_examined<'a>(chain: Chain) -> &'a BlockchainRecord {
@@ -143,6 +143,7 @@ mod tests {
             literal_identifier: "",
             contract: Default::default(),
             contract_creation_block: 0,
+            explorer_url_opt: None,
         }
     }
 
@@ -5,7 +5,7 @@ use crate::data_version::DataVersion;
 use const_format::concatcp;
 
 pub const DEFAULT_CHAIN: Chain = Chain::PolyMainnet;
-pub const CURRENT_SCHEMA_VERSION: usize = 10;
+pub const CURRENT_SCHEMA_VERSION: usize = 21;
 
 pub const HIGHEST_RANDOM_CLANDESTINE_PORT: u16 = 9999;
 pub const HTTP_PORT: u16 = 80;
@@ -13,6 +13,9 @@ pub const TLS_PORT: u16 = 443;
 pub const LOWEST_USABLE_INSECURE_PORT: u16 = 1025;
 pub const HIGHEST_USABLE_PORT: u16 = 65535;
 pub const DEFAULT_UI_PORT: u16 = 5333;
+pub const CLANDESTINE_PORT_AUTO_STABLE: &str = "auto-stable";
+pub const CLANDESTINE_PORT_AUTO_ROTATE: &str = "auto-rotate";
+pub const TRAFFIC_DB_IN_MAIN_DATABASE: &str = "main";
 
 pub const MASQ_URL_PREFIX: &str = "masq://";
 pub const CURRENT_LOGFILE_NAME: &str = "MASQNode_rCURRENT.log";
@@ -21,6 +24,8 @@ pub const MASQ_PROMPT: &str = "masq> ";
 pub const DEFAULT_GAS_PRICE: u64 = 1; //TODO ?? Really
 
 pub const WALLET_ADDRESS_LENGTH: usize = 42;
+pub const MAX_OPERATOR_NOTES_LENGTH: usize = 200;
+pub const MAX_ADDRESS_BOOK_LABEL_LENGTH: usize = 100;
 pub const MASQ_TOTAL_SUPPLY: u64 = 37_500_000;
 pub const WEIS_IN_GWEI: i128 = 1_000_000_000;
 
@@ -45,6 +50,8 @@ pub const DNS_RESOLVER_FAILURE_CURRENT_VERSION: DataVersion = DataVersion { majo
 pub const GOSSIP_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
 pub const GOSSIP_FAILURE_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
 pub const NODE_RECORD_INNER_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
+pub const PAYMENT_OVERDUE_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
+pub const RENDEZVOUS_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
 
 //error codes
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -75,12 +82,36 @@ pub const UNMARSHAL_ERROR: u64 = UI_NODE_COMMUNICATION_PREFIX | 4;
 pub const SETUP_ERROR: u64 = UI_NODE_COMMUNICATION_PREFIX | 5;
 pub const TIMEOUT_ERROR: u64 = UI_NODE_COMMUNICATION_PREFIX | 6;
 pub const SCAN_ERROR: u64 = UI_NODE_COMMUNICATION_PREFIX | 7;
+pub const REQUEST_CANCELLED_ERROR: u64 = UI_NODE_COMMUNICATION_PREFIX | 8;
+pub const PROFILE_ERROR: u64 = UI_NODE_COMMUNICATION_PREFIX | 9;
 
 //accountant
 pub const ACCOUNTANT_PREFIX: u64 = 0x0040_0000_0000_0000;
 pub const REQUEST_WITH_NO_VALUES: u64 = ACCOUNTANT_PREFIX | 1;
 pub const REQUEST_WITH_MUTUALLY_EXCLUSIVE_PARAMS: u64 = ACCOUNTANT_PREFIX | 2;
 pub const VALUE_EXCEEDS_ALLOWED_LIMIT: u64 = ACCOUNTANT_PREFIX | 3;
+pub const DATABASE_MAINTENANCE_ERROR: u64 = ACCOUNTANT_PREFIX | 4;
+pub const INVALID_ADDRESS_BOOK_ENTRY_ERROR: u64 = ACCOUNTANT_PREFIX | 5;
+
+//blockchain bridge
+pub const BLOCKCHAIN_BRIDGE_PREFIX: u64 = 0x0080_0000_0000_0000;
+pub const INVALID_PAYMENT_REFERENCE_ERROR: u64 = BLOCKCHAIN_BRIDGE_PREFIX | 1;
+pub const TRANSACTION_RECEIPT_QUERY_ERROR: u64 = BLOCKCHAIN_BRIDGE_PREFIX | 2;
+
+//setup parameters (ParamError codes)
+pub const PARAM_ERROR_PREFIX: u64 = 0x0002_0000_0000_0000;
+pub const INVALID_PARAM_VALUE_ERROR: u64 = PARAM_ERROR_PREFIX | 1;
+pub const MISSING_PARAM_ERROR: u64 = PARAM_ERROR_PREFIX | 2;
+pub const PARAM_CONFLICT_ERROR: u64 = PARAM_ERROR_PREFIX | 3;
+pub const CONFIG_FILE_ERROR: u64 = PARAM_ERROR_PREFIX | 4;
+pub const PARAM_DATABASE_ERROR: u64 = PARAM_ERROR_PREFIX | 5;
+pub const UNFAMILIAR_PARAM_ERROR: u64 = PARAM_ERROR_PREFIX | 6;
+pub const DATA_DIRECTORY_CONFLICT_ERROR: u64 = PARAM_ERROR_PREFIX | 7;
+pub const PORT_UNAVAILABLE_ERROR: u64 = PARAM_ERROR_PREFIX | 8;
+pub const DB_PASSWORD_ERROR: u64 = PARAM_ERROR_PREFIX | 9;
+pub const DATA_DIRECTORY_LOCKED_ERROR: u64 = PARAM_ERROR_PREFIX | 10;
+pub const DNS_SERVERS_HIJACKED_ERROR: u64 = PARAM_ERROR_PREFIX | 11;
+pub const SETUP_REVISION_CONFLICT_ERROR: u64 = PARAM_ERROR_PREFIX | 12;
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
@@ -117,11 +148,16 @@ mod tests {
         assert_eq!(LOWEST_USABLE_INSECURE_PORT, 1025);
         assert_eq!(HIGHEST_USABLE_PORT, 65535);
         assert_eq!(DEFAULT_UI_PORT, 5333);
+        assert_eq!(CLANDESTINE_PORT_AUTO_STABLE, "auto-stable");
+        assert_eq!(CLANDESTINE_PORT_AUTO_ROTATE, "auto-rotate");
+        assert_eq!(TRAFFIC_DB_IN_MAIN_DATABASE, "main");
         assert_eq!(MASQ_URL_PREFIX, "masq://");
         assert_eq!(CURRENT_LOGFILE_NAME, "MASQNode_rCURRENT.log");
         assert_eq!(MASQ_PROMPT, "masq> ");
         assert_eq!(DEFAULT_GAS_PRICE, 1);
         assert_eq!(WALLET_ADDRESS_LENGTH, 42);
+        assert_eq!(MAX_OPERATOR_NOTES_LENGTH, 200);
+        assert_eq!(MAX_ADDRESS_BOOK_LABEL_LENGTH, 100);
         assert_eq!(MASQ_TOTAL_SUPPLY, 37_500_000);
         assert_eq!(WEIS_IN_GWEI, 1_000_000_000);
         assert_eq!(ETH_MAINNET_CONTRACT_CREATION_BLOCK, 11_170_708);
@@ -157,6 +193,8 @@ mod tests {
         assert_eq!(SETUP_ERROR, UI_NODE_COMMUNICATION_PREFIX | 5);
         assert_eq!(TIMEOUT_ERROR, UI_NODE_COMMUNICATION_PREFIX | 6);
         assert_eq!(SCAN_ERROR, UI_NODE_COMMUNICATION_PREFIX | 7);
+        assert_eq!(REQUEST_CANCELLED_ERROR, UI_NODE_COMMUNICATION_PREFIX | 8);
+        assert_eq!(PROFILE_ERROR, UI_NODE_COMMUNICATION_PREFIX | 9);
         assert_eq!(ACCOUNTANT_PREFIX, 0x0040_0000_0000_0000);
         assert_eq!(REQUEST_WITH_NO_VALUES, ACCOUNTANT_PREFIX | 1);
         assert_eq!(
@@ -164,6 +202,30 @@ mod tests {
             ACCOUNTANT_PREFIX | 2
         );
         assert_eq!(VALUE_EXCEEDS_ALLOWED_LIMIT, ACCOUNTANT_PREFIX | 3);
+        assert_eq!(DATABASE_MAINTENANCE_ERROR, ACCOUNTANT_PREFIX | 4);
+        assert_eq!(INVALID_ADDRESS_BOOK_ENTRY_ERROR, ACCOUNTANT_PREFIX | 5);
+        assert_eq!(BLOCKCHAIN_BRIDGE_PREFIX, 0x0080_0000_0000_0000);
+        assert_eq!(
+            INVALID_PAYMENT_REFERENCE_ERROR,
+            BLOCKCHAIN_BRIDGE_PREFIX | 1
+        );
+        assert_eq!(
+            TRANSACTION_RECEIPT_QUERY_ERROR,
+            BLOCKCHAIN_BRIDGE_PREFIX | 2
+        );
+        assert_eq!(PARAM_ERROR_PREFIX, 0x0002_0000_0000_0000);
+        assert_eq!(INVALID_PARAM_VALUE_ERROR, PARAM_ERROR_PREFIX | 1);
+        assert_eq!(MISSING_PARAM_ERROR, PARAM_ERROR_PREFIX | 2);
+        assert_eq!(PARAM_CONFLICT_ERROR, PARAM_ERROR_PREFIX | 3);
+        assert_eq!(CONFIG_FILE_ERROR, PARAM_ERROR_PREFIX | 4);
+        assert_eq!(PARAM_DATABASE_ERROR, PARAM_ERROR_PREFIX | 5);
+        assert_eq!(UNFAMILIAR_PARAM_ERROR, PARAM_ERROR_PREFIX | 6);
+        assert_eq!(DATA_DIRECTORY_CONFLICT_ERROR, PARAM_ERROR_PREFIX | 7);
+        assert_eq!(PORT_UNAVAILABLE_ERROR, PARAM_ERROR_PREFIX | 8);
+        assert_eq!(DB_PASSWORD_ERROR, PARAM_ERROR_PREFIX | 9);
+        assert_eq!(DATA_DIRECTORY_LOCKED_ERROR, PARAM_ERROR_PREFIX | 10);
+        assert_eq!(DNS_SERVERS_HIJACKED_ERROR, PARAM_ERROR_PREFIX | 11);
+        assert_eq!(SETUP_REVISION_CONFLICT_ERROR, PARAM_ERROR_PREFIX | 12);
         assert_eq!(CENTRAL_DELIMITER, '@');
         assert_eq!(CHAIN_IDENTIFIER_DELIMITER, ':');
         assert_eq!(POLYGON_FAMILY, "polygon");
@@ -198,6 +260,14 @@ mod tests {
             NODE_RECORD_INNER_CURRENT_VERSION,
             DataVersion { major: 0, minor: 1 }
         );
+        assert_eq!(
+            PAYMENT_OVERDUE_CURRENT_VERSION,
+            DataVersion { major: 0, minor: 1 }
+        );
+        assert_eq!(
+            RENDEZVOUS_CURRENT_VERSION,
+            DataVersion { major: 0, minor: 1 }
+        );
     }
 
     #[test]
@@ -209,6 +279,8 @@ mod tests {
             GOSSIP_CURRENT_VERSION,
             GOSSIP_FAILURE_CURRENT_VERSION,
             NODE_RECORD_INNER_CURRENT_VERSION,
+            PAYMENT_OVERDUE_CURRENT_VERSION,
+            RENDEZVOUS_CURRENT_VERSION,
         ]
         .into_iter()
         .for_each(|item| {
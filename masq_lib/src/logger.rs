@@ -3,7 +3,7 @@ use std::fmt::{Debug, Formatter};
 use crate::constants::{
     CLIENT_REQUEST_PAYLOAD_CURRENT_VERSION, CLIENT_RESPONSE_PAYLOAD_CURRENT_VERSION,
     CURRENT_SCHEMA_VERSION, DNS_RESOLVER_FAILURE_CURRENT_VERSION, GOSSIP_CURRENT_VERSION,
-    GOSSIP_FAILURE_CURRENT_VERSION, NODE_RECORD_INNER_CURRENT_VERSION,
+    GOSSIP_FAILURE_CURRENT_VERSION, NODE_RECORD_INNER_CURRENT_VERSION, RENDEZVOUS_CURRENT_VERSION,
 };
 use crate::data_version::DataVersion;
 use crate::messages::SerializableLogLevel;
@@ -18,26 +18,30 @@ use actix::Recipient;
 use lazy_static::lazy_static;
 use log::logger;
 use log::Level;
+use log::LevelFilter;
 #[allow(unused_imports)]
 use log::Metadata;
 #[allow(unused_imports)]
 use log::Record;
-use std::sync::Mutex;
-use std::{io, thread};
+use serde_derive::Serialize;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::{Mutex, RwLock};
+use std::{io, process, thread};
 use time::format_description::parse;
+use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
 
-pub static mut POINTER_TO_FORMAT_FUNCTION: fn(
-    &mut dyn io::Write,
-    OffsetDateTime,
-    &Record,
-) -> Result<(), io::Error> = heading_format_function;
+pub type FormatFunction = fn(&mut dyn io::Write, OffsetDateTime, &Record) -> Result<(), io::Error>;
+
+pub static mut POINTER_TO_FORMAT_FUNCTION: FormatFunction = heading_format_function;
 const UI_MESSAGE_LOG_LEVEL: Level = Level::Info;
 pub const TIME_FORMATTING_STRING: &str =
     "[year]-[month]-[day] [hour]:[minute]:[second].[subsecond digits:3]";
 
 lazy_static! {
     pub static ref LOG_RECIPIENT_OPT: Mutex<Option<Recipient<NodeToUiMessage>>> = Mutex::new(None);
+    static ref LOG_LEVEL_SPEC_OPT: RwLock<Option<LogLevelSpec>> = RwLock::new(None);
 }
 
 #[cfg(not(feature = "log_recipient_test"))]
@@ -52,6 +56,208 @@ pub fn prepare_log_recipient(recipient: Recipient<NodeToUiMessage>) {
     }
 }
 
+// Until a spec is installed, every Logger defers entirely to the underlying log implementation
+pub fn set_log_level_spec(spec: LogLevelSpec) {
+    *LOG_LEVEL_SPEC_OPT.write().expect("log level spec poisoned") = Some(spec);
+}
+
+// A default level, optionally followed by levels for particular subsystems, like
+// "warn,neighborhood=debug,automap=trace". A subsystem name selects every Logger whose name
+// starts with it, ignoring case; where several match, the longest one wins.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogLevelSpec {
+    default_level: LevelFilter,
+    subsystem_levels: Vec<(String, LevelFilter)>,
+}
+
+impl Default for LogLevelSpec {
+    fn default() -> Self {
+        Self::new(LevelFilter::Warn)
+    }
+}
+
+impl FromStr for LogLevelSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let complain = || {
+            format!(
+                "Bad log-level value '{}': expected a level like 'warn', optionally followed by \
+                 subsystem levels like 'warn,neighborhood=debug'",
+                s
+            )
+        };
+        let mut default_level_opt: Option<LevelFilter> = None;
+        let mut subsystem_levels: Vec<(String, LevelFilter)> = vec![];
+        for element in s.split(',').map(|element| element.trim()) {
+            match element.split_once('=') {
+                None => {
+                    if default_level_opt.is_some() {
+                        return Err(complain());
+                    }
+                    default_level_opt =
+                        Some(LevelFilter::from_str(element).map_err(|_| complain())?);
+                }
+                Some((name, level)) => {
+                    subsystem_levels.push(parse_subsystem_level(name, level).ok_or_else(complain)?)
+                }
+            }
+        }
+        Ok(LogLevelSpec {
+            default_level: default_level_opt.unwrap_or(LevelFilter::Warn),
+            subsystem_levels,
+        })
+    }
+}
+
+fn parse_subsystem_level(name: &str, level: &str) -> Option<(String, LevelFilter)> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return None;
+    }
+    let level = LevelFilter::from_str(level.trim()).ok()?;
+    Some((name.to_lowercase(), level))
+}
+
+impl Display for LogLevelSpec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.default_level.to_string().to_lowercase())?;
+        self.subsystem_levels.iter().try_for_each(|(name, level)| {
+            write!(f, ",{}={}", name, level.to_string().to_lowercase())
+        })
+    }
+}
+
+// Levels for particular subsystems, like "neighborhood=debug,accountant=trace", given apart from
+// the log level so that one subsystem can be turned up without restating the level for the rest
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct LogLevelOverrides {
+    subsystem_levels: Vec<(String, LevelFilter)>,
+}
+
+impl FromStr for LogLevelOverrides {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let subsystem_levels = s
+            .split(',')
+            .map(|element| {
+                element
+                    .split_once('=')
+                    .and_then(|(name, level)| parse_subsystem_level(name, level))
+            })
+            .collect::<Option<Vec<(String, LevelFilter)>>>()
+            .ok_or_else(|| {
+                format!(
+                    "Bad log-level-overrides value '{}': expected subsystem levels like \
+                     'neighborhood=debug,accountant=trace'",
+                    s
+                )
+            })?;
+        Ok(LogLevelOverrides { subsystem_levels })
+    }
+}
+
+impl Display for LogLevelOverrides {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let elements = self
+            .subsystem_levels
+            .iter()
+            .map(|(name, level)| format!("{}={}", name, level.to_string().to_lowercase()))
+            .collect::<Vec<String>>();
+        write!(f, "{}", elements.join(","))
+    }
+}
+
+// How each line in the log file is laid out: `text` for people reading it, `json` for log
+// aggregation systems, which get one self-contained JSON object per line
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl Display for LogFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogFormat::Text => write!(f, "text"),
+            LogFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!(
+                "Bad log-format value '{}': expected text or json",
+                s
+            )),
+        }
+    }
+}
+
+impl LogFormat {
+    pub fn format_function(&self) -> FormatFunction {
+        match self {
+            LogFormat::Text => real_format_function,
+            LogFormat::Json => json_format_function,
+        }
+    }
+}
+
+impl LogLevelSpec {
+    pub fn new(default_level: LevelFilter) -> Self {
+        Self {
+            default_level,
+            subsystem_levels: vec![],
+        }
+    }
+
+    pub fn level_for(&self, logger_name: &str) -> LevelFilter {
+        self.subsystem_levels
+            .iter()
+            .filter(|(name, _)| {
+                logger_name
+                    .get(..name.len())
+                    .map_or(false, |prefix| prefix.eq_ignore_ascii_case(name))
+            })
+            .max_by_key(|(name, _)| name.len())
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default_level)
+    }
+
+    // An override replaces whatever level the spec already had for the same subsystem
+    pub fn overridden_by(mut self, overrides: &LogLevelOverrides) -> Self {
+        self.subsystem_levels.retain(|(name, _)| {
+            !overrides
+                .subsystem_levels
+                .iter()
+                .any(|(override_name, _)| override_name == name)
+        });
+        self.subsystem_levels
+            .extend(overrides.subsystem_levels.iter().cloned());
+        self
+    }
+
+    pub fn enables(&self, logger_name: &str, level: Level) -> bool {
+        level <= self.level_for(logger_name)
+    }
+
+    // The most verbose level anything in the spec asks for
+    pub fn max_level(&self) -> LevelFilter {
+        self.subsystem_levels
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default_level, Ord::max)
+    }
+}
+
 #[derive(Clone)]
 pub struct Logger {
     name: String,
@@ -180,6 +386,17 @@ impl Logger {
         self.level_enabled(Level::Error)
     }
 
+    fn spec_enabled(&self, level: Level) -> bool {
+        match LOG_LEVEL_SPEC_OPT
+            .read()
+            .expect("log level spec poisoned")
+            .as_ref()
+        {
+            Some(spec) => spec.enables(&self.name, level),
+            None => true,
+        }
+    }
+
     fn generic_log<F>(&self, level: Level, log_function: F)
     where
         F: FnOnce() -> String,
@@ -217,7 +434,8 @@ impl Logger {
     /__/ /    /__/ /__/ /__/ /________/ /_____   / /           dns_resolve_failure::MIGRATIONS {}
     |__|/     |__|/|__|/|__|/|________|/|____/__/ /            gossip::MIGRATIONS {}
                                              |__|/             gossip_failure::MIGRATIONS {}
-                                                               node_record_inner::MIGRATIONS {}\n",
+                                                               node_record_inner::MIGRATIONS {}
+                                                               rendezvous::MIGRATIONS {}\n",
             env!("CARGO_PKG_VERSION"),
             CURRENT_SCHEMA_VERSION,
             std::env::consts::OS,
@@ -226,7 +444,8 @@ impl Logger {
             Logger::data_version_pretty_print(DNS_RESOLVER_FAILURE_CURRENT_VERSION),
             Logger::data_version_pretty_print(GOSSIP_CURRENT_VERSION),
             Logger::data_version_pretty_print(GOSSIP_FAILURE_CURRENT_VERSION),
-            Logger::data_version_pretty_print(NODE_RECORD_INNER_CURRENT_VERSION)
+            Logger::data_version_pretty_print(NODE_RECORD_INNER_CURRENT_VERSION),
+            Logger::data_version_pretty_print(RENDEZVOUS_CURRENT_VERSION)
         )
     }
 
@@ -253,7 +472,8 @@ impl Logger {
 #[cfg(feature = "no_test_share")]
 impl Logger {
     pub fn level_enabled(&self, level: Level) -> bool {
-        logger().enabled(&Metadata::builder().level(level).target(&self.name).build())
+        self.spec_enabled(level)
+            && logger().enabled(&Metadata::builder().level(level).target(&self.name).build())
     }
 }
 
@@ -284,8 +504,7 @@ pub fn real_format_function(
     let timestamp = timestamp
         .format(&parse(TIME_FORMATTING_STRING).expect("Unable to parse the formatting type."))
         .expect("Unable to format date and time.");
-    let thread_id_str = format!("{:?}", thread::current().id());
-    let thread_id = &thread_id_str[9..(thread_id_str.len() - 1)];
+    let thread_id = current_thread_id();
     let level = record.level();
     let name = record.module_path().unwrap_or("<unnamed>");
     write.write_fmt(format_args!(
@@ -295,6 +514,42 @@ pub fn real_format_function(
     write.write_fmt(*record.args())
 }
 
+#[derive(Serialize)]
+struct JsonLogLine<'a> {
+    timestamp: String,
+    level: String,
+    module: &'a str,
+    thread: String,
+    pid: u32,
+    message: String,
+}
+
+// The thread and process IDs let an aggregator tell apart interleaved lines from concurrent work
+// and from several Nodes logging to the same place
+pub fn json_format_function(
+    write: &mut dyn io::Write,
+    timestamp: OffsetDateTime,
+    record: &Record,
+) -> Result<(), io::Error> {
+    let line = JsonLogLine {
+        timestamp: timestamp
+            .format(&Rfc3339)
+            .expect("Unable to format date and time."),
+        level: record.level().to_string(),
+        module: record.module_path().unwrap_or("<unnamed>"),
+        thread: current_thread_id(),
+        pid: process::id(),
+        message: record.args().to_string(),
+    };
+    serde_json::to_writer(write, &line).map_err(io::Error::from)
+}
+
+// ThreadId's Debug output looks like "ThreadId(12)"
+fn current_thread_id() -> String {
+    let thread_id_str = format!("{:?}", thread::current().id());
+    thread_id_str[9..(thread_id_str.len() - 1)].to_string()
+}
+
 #[cfg(feature = "log_recipient_test")]
 lazy_static! {
     pub static ref INITIALIZATION_COUNTER: Mutex<MutexIncrementInset> =
@@ -314,7 +569,7 @@ pub fn prepare_log_recipient(_recipient: Recipient<NodeToUiMessage>) {
 #[cfg(not(feature = "no_test_share"))]
 impl Logger {
     pub fn level_enabled(&self, level: Level) -> bool {
-        level <= self.level_limit
+        level <= self.level_limit && self.spec_enabled(level)
     }
 
     pub fn set_level_for_test(&mut self, level: Level) {
@@ -334,6 +589,7 @@ mod tests {
         CLIENT_REQUEST_PAYLOAD_CURRENT_VERSION, CLIENT_RESPONSE_PAYLOAD_CURRENT_VERSION,
         DNS_RESOLVER_FAILURE_CURRENT_VERSION, GOSSIP_CURRENT_VERSION,
         GOSSIP_FAILURE_CURRENT_VERSION, NODE_RECORD_INNER_CURRENT_VERSION,
+        RENDEZVOUS_CURRENT_VERSION,
     };
     use crate::messages::{ToMessageBody, UiLogBroadcast};
     use crate::test_utils::logging::init_test_logging;
@@ -753,14 +1009,16 @@ mod tests {
     /__/ /    /__/ /__/ /__/ /________/ /_____   / /           dns_resolve_failure::MIGRATIONS {}
     |__|/     |__|/|__|/|__|/|________|/|____/__/ /            gossip::MIGRATIONS {}
                                              |__|/             gossip_failure::MIGRATIONS {}
-                                                               node_record_inner::MIGRATIONS {}\n"#,
+                                                               node_record_inner::MIGRATIONS {}
+                                                               rendezvous::MIGRATIONS {}\n"#,
             std::env::consts::OS,
             Logger::data_version_pretty_print(CLIENT_REQUEST_PAYLOAD_CURRENT_VERSION),
             Logger::data_version_pretty_print(CLIENT_RESPONSE_PAYLOAD_CURRENT_VERSION),
             Logger::data_version_pretty_print(DNS_RESOLVER_FAILURE_CURRENT_VERSION),
             Logger::data_version_pretty_print(GOSSIP_CURRENT_VERSION),
             Logger::data_version_pretty_print(GOSSIP_FAILURE_CURRENT_VERSION),
-            Logger::data_version_pretty_print(NODE_RECORD_INNER_CURRENT_VERSION)
+            Logger::data_version_pretty_print(NODE_RECORD_INNER_CURRENT_VERSION),
+            Logger::data_version_pretty_print(RENDEZVOUS_CURRENT_VERSION)
         );
 
         let replace_rules = vec![("(", "\\("), (")", "\\)"), ("|", "\\|")];
@@ -986,6 +1244,202 @@ mod tests {
         assert_eq!(format!("{:?}", logger), "Logger{ name: \"my new logger\" }")
     }
 
+    #[test]
+    fn log_level_spec_parses_default_and_subsystem_levels() {
+        let result = LogLevelSpec::from_str(" Info , neighborhood=DEBUG,AutoMap = trace").unwrap();
+
+        assert_eq!(
+            result,
+            LogLevelSpec {
+                default_level: LevelFilter::Info,
+                subsystem_levels: vec![
+                    ("neighborhood".to_string(), LevelFilter::Debug),
+                    ("automap".to_string(), LevelFilter::Trace),
+                ],
+            }
+        );
+        assert_eq!(
+            result.to_string(),
+            "info,neighborhood=debug,automap=trace".to_string()
+        );
+        assert_eq!(
+            LogLevelSpec::from_str("proxyclient=off").unwrap(),
+            LogLevelSpec {
+                default_level: LevelFilter::Warn,
+                subsystem_levels: vec![("proxyclient".to_string(), LevelFilter::Off)],
+            }
+        );
+        assert_eq!(
+            LogLevelSpec::default(),
+            LogLevelSpec::new(LevelFilter::Warn)
+        );
+    }
+
+    #[test]
+    fn log_level_spec_rejects_bad_values() {
+        vec![
+            "",
+            "loud",
+            "warn,info",
+            "warn,neighborhood",
+            "warn,=debug",
+            "warn,neighbor hood=debug",
+            "warn,neighborhood=loud",
+            "warn,",
+        ]
+        .into_iter()
+        .for_each(|bad_value| {
+            assert_eq!(
+                LogLevelSpec::from_str(bad_value),
+                Err(format!(
+                    "Bad log-level value '{}': expected a level like 'warn', optionally followed \
+                     by subsystem levels like 'warn,neighborhood=debug'",
+                    bad_value
+                )),
+                "{}",
+                bad_value
+            )
+        });
+    }
+
+    #[test]
+    fn log_level_spec_chooses_the_longest_matching_subsystem_ignoring_case() {
+        let subject =
+            LogLevelSpec::from_str("error,neighborhood=debug,NeighborhoodDatabase=trace").unwrap();
+
+        assert_eq!(subject.level_for("Neighborhood"), LevelFilter::Debug);
+        assert_eq!(
+            subject.level_for("NeighborhoodDatabase"),
+            LevelFilter::Trace
+        );
+        assert_eq!(subject.level_for("Neighbor"), LevelFilter::Error);
+        assert_eq!(subject.level_for("ProxyServer"), LevelFilter::Error);
+        assert_eq!(subject.enables("Neighborhood", Level::Debug), true);
+        assert_eq!(subject.enables("Neighborhood", Level::Trace), false);
+        assert_eq!(subject.enables("ProxyServer", Level::Error), true);
+        assert_eq!(subject.enables("ProxyServer", Level::Warn), false);
+        assert_eq!(subject.max_level(), LevelFilter::Trace);
+        assert_eq!(
+            LogLevelSpec::from_str("info,accountant=off")
+                .unwrap()
+                .max_level(),
+            LevelFilter::Info
+        );
+    }
+
+    #[test]
+    fn log_level_overrides_parse_and_display() {
+        let subject = LogLevelOverrides::from_str("Neighborhood=DEBUG, accountant=trace").unwrap();
+
+        assert_eq!(subject.to_string(), "neighborhood=debug,accountant=trace");
+        assert_eq!(LogLevelOverrides::default().to_string(), "");
+    }
+
+    #[test]
+    fn log_level_overrides_reject_bad_values() {
+        vec![
+            "",
+            "debug",
+            "neighborhood=debug,",
+            "=debug",
+            "neighborhood=loud",
+        ]
+        .into_iter()
+        .for_each(|bad_value| {
+            assert_eq!(
+                LogLevelOverrides::from_str(bad_value),
+                Err(format!(
+                    "Bad log-level-overrides value '{}': expected subsystem levels like \
+                         'neighborhood=debug,accountant=trace'",
+                    bad_value
+                )),
+                "{}",
+                bad_value
+            )
+        });
+    }
+
+    #[test]
+    fn log_level_spec_overridden_by_replaces_and_adds_subsystem_levels() {
+        let subject = LogLevelSpec::from_str("info,neighborhood=trace,hopper=error").unwrap();
+        let overrides = LogLevelOverrides::from_str("neighborhood=debug,accountant=trace").unwrap();
+
+        let result = subject.overridden_by(&overrides);
+
+        assert_eq!(
+            result,
+            LogLevelSpec::from_str("info,hopper=error,neighborhood=debug,accountant=trace")
+                .unwrap()
+        );
+        assert_eq!(result.level_for("Neighborhood"), LevelFilter::Debug);
+        assert_eq!(result.level_for("ProxyServer"), LevelFilter::Info);
+    }
+
+    #[test]
+    fn log_format_parses_and_displays_ignoring_case() {
+        assert_eq!(LogFormat::default(), LogFormat::Text);
+        assert_eq!(LogFormat::from_str("TEXT"), Ok(LogFormat::Text));
+        assert_eq!(LogFormat::from_str("json"), Ok(LogFormat::Json));
+        assert_eq!(LogFormat::Text.to_string(), "text");
+        assert_eq!(LogFormat::Json.to_string(), "json");
+        assert_eq!(
+            LogFormat::from_str("xml"),
+            Err("Bad log-format value 'xml': expected text or json".to_string())
+        );
+    }
+
+    #[test]
+    fn json_format_function_writes_one_json_object_per_log_line() {
+        let timestamp = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let mut buffer = vec![];
+
+        LogFormat::Json.format_function()(
+            &mut buffer,
+            timestamp,
+            &Record::builder()
+                .args(format_args!("two\nlines with \"quotes\""))
+                .module_path(Some("Neighborhood"))
+                .level(Level::Warn)
+                .build(),
+        )
+        .unwrap();
+
+        let line = String::from_utf8(buffer).unwrap();
+        assert_eq!(line.contains('\n'), false, "{}", line);
+        let json = serde_json::from_str::<serde_json::Value>(&line).unwrap();
+        assert_eq!(json["timestamp"], "2023-11-14T22:13:20Z");
+        assert_eq!(json["level"], "WARN");
+        assert_eq!(json["module"], "Neighborhood");
+        assert_eq!(json["thread"], thread_id_as_string(thread::current().id()));
+        assert_eq!(json["pid"], process::id());
+        assert_eq!(json["message"], "two\nlines with \"quotes\"");
+    }
+
+    #[test]
+    fn text_format_function_is_the_familiar_one() {
+        let timestamp = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let mut buffer = vec![];
+
+        LogFormat::Text.format_function()(
+            &mut buffer,
+            timestamp,
+            &Record::builder()
+                .args(format_args!("booga"))
+                .module_path(Some("Neighborhood"))
+                .level(Level::Warn)
+                .build(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            format!(
+                "2023-11-14 22:13:20.000 Thd{}: WARN: Neighborhood: booga",
+                thread_id_as_string(thread::current().id())
+            )
+        );
+    }
+
     fn timestamp_as_string(timestamp: OffsetDateTime) -> String {
         timestamp
             .format(&parse(TIME_FORMATTING_STRING).unwrap())
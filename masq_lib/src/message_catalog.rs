@@ -0,0 +1,418 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::constants::*;
+use crate::messages::{UiAlertRule, UiBanRiskCause, UiConnectionStage};
+
+// The only locale whose texts ship with the Node; every entry has one
+pub const DEFAULT_LOCALE: &str = "en";
+
+// A user-facing text the Node can send through the UiGateway. The id never changes once
+// published, so a GUI can key its own translations by it instead of by the English wording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CatalogEntry {
+    pub id: &'static str,
+    pub code_opt: Option<u64>,
+    pub english: &'static str,
+}
+
+const fn error(id: &'static str, code: u64, english: &'static str) -> CatalogEntry {
+    CatalogEntry {
+        id,
+        code_opt: Some(code),
+        english,
+    }
+}
+
+const fn status(id: &'static str, english: &'static str) -> CatalogEntry {
+    CatalogEntry {
+        id,
+        code_opt: None,
+        english,
+    }
+}
+
+pub const CATALOG: &[CatalogEntry] = &[
+    error(
+        "error.configuratorRead",
+        CONFIGURATOR_READ_ERROR,
+        "Couldn't read the configuration",
+    ),
+    error(
+        "error.configuratorWrite",
+        CONFIGURATOR_WRITE_ERROR,
+        "Couldn't write the configuration",
+    ),
+    error(
+        "error.unrecognizedMnemonicLanguage",
+        UNRECOGNIZED_MNEMONIC_LANGUAGE_ERROR,
+        "The mnemonic language isn't recognized",
+    ),
+    error(
+        "error.illegalMnemonicWordCount",
+        ILLEGAL_MNEMONIC_WORD_COUNT_ERROR,
+        "The mnemonic phrase has an illegal number of words",
+    ),
+    error(
+        "error.keyPairConstruction",
+        KEY_PAIR_CONSTRUCTION_ERROR,
+        "Couldn't make a key pair for the wallet",
+    ),
+    error(
+        "error.badPassword",
+        BAD_PASSWORD_ERROR,
+        "The database password is wrong",
+    ),
+    error(
+        "error.alreadyInitialized",
+        ALREADY_INITIALIZED_ERROR,
+        "The wallets are already set up",
+    ),
+    error(
+        "error.derivationPath",
+        DERIVATION_PATH_ERROR,
+        "The derivation path is invalid",
+    ),
+    error(
+        "error.mnemonicPhrase",
+        MNEMONIC_PHRASE_ERROR,
+        "The mnemonic phrase is invalid",
+    ),
+    error(
+        "error.earlyQuestioningAboutData",
+        EARLY_QUESTIONING_ABOUT_DATA,
+        "That information isn't available until the Node has a database password",
+    ),
+    error(
+        "error.unrecognizedParameter",
+        UNRECOGNIZED_PARAMETER,
+        "The parameter isn't recognized",
+    ),
+    error(
+        "error.nonParsableValue",
+        NON_PARSABLE_VALUE,
+        "The value can't be parsed",
+    ),
+    error(
+        "error.missingData",
+        MISSING_DATA,
+        "Required data is missing",
+    ),
+    error("error.unknown", UNKNOWN_ERROR, "An unknown error occurred"),
+    error(
+        "error.nodeLaunch",
+        NODE_LAUNCH_ERROR,
+        "The Node couldn't be started",
+    ),
+    error(
+        "error.nodeNotRunning",
+        NODE_NOT_RUNNING_ERROR,
+        "The Node isn't running",
+    ),
+    error(
+        "error.nodeAlreadyRunning",
+        NODE_ALREADY_RUNNING_ERROR,
+        "The Node is already running",
+    ),
+    error(
+        "error.unmarshal",
+        UNMARSHAL_ERROR,
+        "The message couldn't be understood",
+    ),
+    error("error.setup", SETUP_ERROR, "The setup couldn't be changed"),
+    error("error.timeout", TIMEOUT_ERROR, "The request timed out"),
+    error("error.scan", SCAN_ERROR, "The scan couldn't be run"),
+    error(
+        "error.requestCancelled",
+        REQUEST_CANCELLED_ERROR,
+        "The request was cancelled",
+    ),
+    error(
+        "error.profile",
+        PROFILE_ERROR,
+        "The setup profile couldn't be saved or loaded",
+    ),
+    error(
+        "error.requestWithNoValues",
+        REQUEST_WITH_NO_VALUES,
+        "The request has no values",
+    ),
+    error(
+        "error.requestWithMutuallyExclusiveParams",
+        REQUEST_WITH_MUTUALLY_EXCLUSIVE_PARAMS,
+        "The request has parameters that can't be used together",
+    ),
+    error(
+        "error.valueExceedsAllowedLimit",
+        VALUE_EXCEEDS_ALLOWED_LIMIT,
+        "A value is beyond the allowed limit",
+    ),
+    error(
+        "error.databaseMaintenance",
+        DATABASE_MAINTENANCE_ERROR,
+        "Database maintenance failed",
+    ),
+    error(
+        "error.invalidAddressBookEntry",
+        INVALID_ADDRESS_BOOK_ENTRY_ERROR,
+        "The address book entry is invalid",
+    ),
+    error(
+        "error.invalidPaymentReference",
+        INVALID_PAYMENT_REFERENCE_ERROR,
+        "The payment reference is invalid",
+    ),
+    error(
+        "error.transactionReceiptQuery",
+        TRANSACTION_RECEIPT_QUERY_ERROR,
+        "Couldn't look up the transaction receipt",
+    ),
+    error(
+        "error.invalidParamValue",
+        INVALID_PARAM_VALUE_ERROR,
+        "A parameter has an invalid value",
+    ),
+    error(
+        "error.missingParam",
+        MISSING_PARAM_ERROR,
+        "A required parameter is missing",
+    ),
+    error(
+        "error.paramConflict",
+        PARAM_CONFLICT_ERROR,
+        "Some parameters conflict with each other",
+    ),
+    error(
+        "error.configFile",
+        CONFIG_FILE_ERROR,
+        "The configuration file has a problem",
+    ),
+    error(
+        "error.paramDatabase",
+        PARAM_DATABASE_ERROR,
+        "A parameter couldn't be read from the database",
+    ),
+    error(
+        "error.unfamiliarParam",
+        UNFAMILIAR_PARAM_ERROR,
+        "A parameter isn't one the Node knows",
+    ),
+    error(
+        "error.dataDirectoryConflict",
+        DATA_DIRECTORY_CONFLICT_ERROR,
+        "Another Node is using the data directory",
+    ),
+    error(
+        "error.portUnavailable",
+        PORT_UNAVAILABLE_ERROR,
+        "A port the Node needs is in use",
+    ),
+    error(
+        "error.dbPassword",
+        DB_PASSWORD_ERROR,
+        "The database password is missing or wrong",
+    ),
+    error(
+        "error.dataDirectoryLocked",
+        DATA_DIRECTORY_LOCKED_ERROR,
+        "The data directory is locked by another process",
+    ),
+    error(
+        "error.dnsServersHijacked",
+        DNS_SERVERS_HIJACKED_ERROR,
+        "The DNS servers point back at the Node",
+    ),
+    error(
+        "error.setupRevisionConflict",
+        SETUP_REVISION_CONFLICT_ERROR,
+        "Another UI changed the setup first",
+    ),
+    status(
+        "connectionChange.NotConnected",
+        "Not connected to the MASQ Network",
+    ),
+    status(
+        "connectionChange.ConnectedToNeighbor",
+        "Connected to a neighbor",
+    ),
+    status(
+        "connectionChange.RouteFound",
+        "Connected to the MASQ Network",
+    ),
+    status(
+        "alert.OverdueReceivables",
+        "Some debtors are overdue paying this Node",
+    ),
+    status(
+        "alert.ExcessivePayables",
+        "This Node owes more than expected",
+    ),
+    status(
+        "alert.ExternalWalletUse",
+        "The consuming wallet was used outside the Node",
+    ),
+    status(
+        "alert.PaymentOverdue",
+        "A payment from this Node is overdue",
+    ),
+    status(
+        "alert.BlockchainServiceFailing",
+        "The blockchain service keeps failing",
+    ),
+    status(
+        "banRisk.NoConsumingWallet",
+        "A creditor may ban this Node because it has no consuming wallet to pay with",
+    ),
+    status(
+        "banRisk.PaymentFailing",
+        "A creditor may ban this Node because payments to it are failing",
+    ),
+    status(
+        "banRisk.Unpaid",
+        "A creditor may ban this Node because it hasn't been paid yet",
+    ),
+];
+
+pub fn entry(id: &str) -> Option<&'static CatalogEntry> {
+    CATALOG.iter().find(|entry| entry.id == id)
+}
+
+pub fn entry_for_code(code: u64) -> Option<&'static CatalogEntry> {
+    CATALOG.iter().find(|entry| entry.code_opt == Some(code))
+}
+
+pub fn connection_stage_id(stage: &UiConnectionStage) -> String {
+    format!("connectionChange.{:?}", stage)
+}
+
+pub fn alert_rule_id(rule: UiAlertRule) -> String {
+    format!("alert.{:?}", rule)
+}
+
+pub fn ban_risk_cause_id(cause: UiBanRiskCause) -> String {
+    format!("banRisk.{:?}", cause)
+}
+
+// Picks the most preferred language tag from an Accept-Language header, ignoring weights below
+// that and the wildcard; "fr-CA;q=0.9, en;q=0.8" gives "fr-ca"
+pub fn locale_from_accept_language(header: &str) -> Option<String> {
+    header
+        .split(',')
+        .filter_map(|item| {
+            let mut pieces = item.split(';');
+            let tag = pieces.next()?.trim().to_lowercase();
+            let weight = pieces
+                .find_map(|piece| piece.trim().strip_prefix("q="))
+                .map(|q| q.trim().parse::<f32>().unwrap_or(0.0))
+                .unwrap_or(1.0);
+            if tag.is_empty() || tag == "*" || weight <= 0.0 {
+                None
+            } else {
+                Some((tag, weight))
+            }
+        })
+        .fold(
+            None,
+            |best: Option<(String, f32)>, (tag, weight)| match best {
+                Some((_, best_weight)) if best_weight >= weight => best,
+                _ => Some((tag, weight)),
+            },
+        )
+        .map(|(tag, _)| tag)
+}
+
+// The locale the catalog can actually supply for the one asked for. Only English texts are
+// bundled, so until translations ship this is always DEFAULT_LOCALE.
+pub fn supported_locale(_requested: &str) -> &'static str {
+    DEFAULT_LOCALE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(DEFAULT_LOCALE, "en");
+    }
+
+    #[test]
+    fn catalog_ids_and_codes_are_unique() {
+        let ids = CATALOG.iter().map(|entry| entry.id).collect::<HashSet<_>>();
+        let codes = CATALOG
+            .iter()
+            .filter_map(|entry| entry.code_opt)
+            .collect::<HashSet<_>>();
+
+        assert_eq!(ids.len(), CATALOG.len());
+        assert_eq!(
+            codes.len(),
+            CATALOG
+                .iter()
+                .filter(|entry| entry.code_opt.is_some())
+                .count()
+        );
+        assert_eq!(CATALOG.iter().any(|entry| entry.english.is_empty()), false);
+    }
+
+    #[test]
+    fn entries_can_be_found_by_id_and_by_error_code() {
+        assert_eq!(
+            entry_for_code(SETUP_REVISION_CONFLICT_ERROR),
+            Some(&CatalogEntry {
+                id: "error.setupRevisionConflict",
+                code_opt: Some(SETUP_REVISION_CONFLICT_ERROR),
+                english: "Another UI changed the setup first",
+            })
+        );
+        assert_eq!(
+            entry("error.setupRevisionConflict"),
+            entry_for_code(SETUP_REVISION_CONFLICT_ERROR)
+        );
+        assert_eq!(entry("booga"), None);
+        assert_eq!(entry_for_code(0), None);
+    }
+
+    #[test]
+    fn every_status_enum_value_has_an_entry() {
+        vec![
+            connection_stage_id(&UiConnectionStage::NotConnected),
+            connection_stage_id(&UiConnectionStage::ConnectedToNeighbor),
+            connection_stage_id(&UiConnectionStage::RouteFound),
+            alert_rule_id(UiAlertRule::OverdueReceivables),
+            alert_rule_id(UiAlertRule::ExcessivePayables),
+            alert_rule_id(UiAlertRule::ExternalWalletUse),
+            alert_rule_id(UiAlertRule::PaymentOverdue),
+            alert_rule_id(UiAlertRule::BlockchainServiceFailing),
+            ban_risk_cause_id(UiBanRiskCause::NoConsumingWallet),
+            ban_risk_cause_id(UiBanRiskCause::PaymentFailing),
+            ban_risk_cause_id(UiBanRiskCause::Unpaid),
+        ]
+        .into_iter()
+        .for_each(|id| assert_eq!(entry(&id).is_some(), true, "{}", id));
+    }
+
+    #[test]
+    fn locale_is_taken_from_the_most_preferred_accept_language_tag() {
+        assert_eq!(
+            locale_from_accept_language("fr-CA;q=0.9, en;q=0.8"),
+            Some("fr-ca".to_string())
+        );
+        assert_eq!(
+            locale_from_accept_language("en;q=0.5, de, *"),
+            Some("de".to_string())
+        );
+        assert_eq!(
+            locale_from_accept_language("es;q=0, it;q=0.1"),
+            Some("it".to_string())
+        );
+        assert_eq!(locale_from_accept_language("*"), None);
+        assert_eq!(locale_from_accept_language(""), None);
+    }
+
+    #[test]
+    fn only_english_is_supplied_for_now() {
+        assert_eq!(supported_locale("fr-ca"), "en");
+        assert_eq!(supported_locale("en"), "en");
+    }
+}
@@ -14,6 +14,48 @@ use std::fmt::Debug;
 use std::str::FromStr;
 
 pub const NODE_UI_PROTOCOL: &str = "MASQNode-UIv2";
+pub const NODE_UI_PROTOCOL_PREFIX: &str = "MASQNode-UIv";
+pub const NODE_UI_PROTOCOL_VERSION: u32 = 2;
+
+// UIs offer every protocol version they can speak as WebSocket subprotocols during the initial
+// handshake; the Node picks the newest one it supports.
+pub fn node_ui_protocol_name(version: u32) -> String {
+    format!("{}{}", NODE_UI_PROTOCOL_PREFIX, version)
+}
+
+pub fn node_ui_protocol_version(protocol_name: &str) -> Option<u32> {
+    protocol_name
+        .strip_prefix(NODE_UI_PROTOCOL_PREFIX)
+        .and_then(|version| version.parse::<u32>().ok())
+}
+
+// A UI may put this field in the payload of a request that changes something, with a value that
+// stands for the one operation it wants done. If it has to send the request again, say because its
+// connection dropped before the answer came, it sends the same value and gets the first answer back
+// instead of having the operation done twice.
+pub const IDEMPOTENCY_KEY_FIELD: &str = "idempotencyKey";
+
+pub fn idempotency_key_opt(body: &MessageBody) -> Option<String> {
+    let payload = body.payload.as_ref().ok()?;
+    let json = serde_json::from_str::<serde_json::Value>(payload).ok()?;
+    match json.get(IDEMPOTENCY_KEY_FIELD)? {
+        serde_json::Value::String(key) => Some(key.clone()),
+        _ => None,
+    }
+}
+
+pub fn with_idempotency_key(mut body: MessageBody, key: &str) -> MessageBody {
+    if let Ok(payload) = &body.payload {
+        if let Ok(serde_json::Value::Object(mut fields)) = serde_json::from_str(payload) {
+            fields.insert(
+                IDEMPOTENCY_KEY_FIELD.to_string(),
+                serde_json::Value::String(key.to_string()),
+            );
+            body.payload = Ok(serde_json::Value::Object(fields).to_string());
+        }
+    }
+    body
+}
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum UiMessageError {
@@ -209,10 +251,203 @@ impl UiCrashRequest {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiChainListRequest {}
+conversation_message!(UiChainListRequest, "chainList");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiChainRecord {
+    #[serde(rename = "literalIdentifier")]
+    pub literal_identifier: String,
+    #[serde(rename = "chainId")]
+    pub chain_id: u64,
+    #[serde(rename = "contractAddress")]
+    pub contract_address: String,
+    #[serde(rename = "contractCreationBlock")]
+    pub contract_creation_block: u64,
+    #[serde(rename = "defaultGasPriceGwei")]
+    pub default_gas_price_gwei: u64,
+    #[serde(rename = "explorerUrlOpt")]
+    pub explorer_url_opt: Option<String>,
+    #[serde(rename = "isTestnet")]
+    pub is_testnet: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiChainListResponse {
+    pub chains: Vec<UiChainRecord>,
+}
+conversation_message!(UiChainListResponse, "chainList");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiChainMigrationRequest {
+    #[serde(rename = "dataDirectoryOpt")]
+    pub data_directory_opt: Option<String>,
+    #[serde(rename = "copyConfig")]
+    pub copy_config: bool,
+}
+conversation_message!(UiChainMigrationRequest, "chainMigration");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiChainMigrationResponse {
+    #[serde(rename = "dataDirectory")]
+    pub data_directory: String,
+    #[serde(rename = "configCopied")]
+    pub config_copied: bool,
+}
+conversation_message!(UiChainMigrationResponse, "chainMigration");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiMissedEventsRequest {
+    #[serde(rename = "afterSequence")]
+    pub after_sequence: u64,
+}
+conversation_message!(UiMissedEventsRequest, "missedEvents");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiMissedEvent {
+    pub sequence: u64,
+    #[serde(rename = "timestampS")]
+    pub timestamp_s: u64,
+    pub critical: bool,
+    pub opcode: String,
+    pub payload: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiMissedEventsResponse {
+    #[serde(rename = "latestSequence")]
+    pub latest_sequence: u64,
+    pub events: Vec<UiMissedEvent>,
+}
+conversation_message!(UiMissedEventsResponse, "missedEvents");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum OrphanedNodeAction {
+    Report,
+    Adopt,
+    Terminate,
+}
+
+// An orphaned Node is one running against the setup's data directory that the Daemon didn't
+// launch, or has forgotten launching (because the Daemon was restarted, for instance).
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiOrphanedNodeRequest {
+    pub action: OrphanedNodeAction,
+}
+conversation_message!(UiOrphanedNodeRequest, "orphanedNode");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiOrphanedNode {
+    #[serde(rename = "processId")]
+    pub process_id: u32,
+    #[serde(rename = "uiPort")]
+    pub ui_port: u16,
+    #[serde(rename = "dataDirectory")]
+    pub data_directory: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiOrphanedNodeResponse {
+    pub action: OrphanedNodeAction,
+    #[serde(rename = "orphanOpt")]
+    pub orphan_opt: Option<UiOrphanedNode>,
+}
+conversation_message!(UiOrphanedNodeResponse, "orphanedNode");
+
+// Left behind by a Node as it shuts down, so that its operator can see what its downtime will cost.
+// Timestamps are in seconds since the Unix epoch.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiShutdownReport {
+    #[serde(rename = "shutdownTimestamp")]
+    pub shutdown_timestamp: i64,
+    #[serde(rename = "totalPayableGwei")]
+    pub total_payable_gwei: u64,
+    #[serde(rename = "totalReceivableGwei")]
+    pub total_receivable_gwei: i64,
+    #[serde(rename = "unconfirmedPaymentCount")]
+    pub unconfirmed_payment_count: usize,
+    #[serde(rename = "banRisks")]
+    pub ban_risks: Vec<UiBanRisk>,
+}
+
+// A creditor that will probably ban this Node if its debt isn't paid by banTimestamp, which may
+// already be past
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiBanRisk {
+    pub wallet: String,
+    #[serde(rename = "balanceGwei")]
+    pub balance_gwei: u64,
+    #[serde(rename = "banTimestamp")]
+    pub ban_timestamp: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiShutdownReportRequest {}
+conversation_message!(UiShutdownReportRequest, "shutdownReport");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiShutdownReportResponse {
+    #[serde(rename = "reportOpt")]
+    pub report_opt: Option<UiShutdownReport>,
+}
+conversation_message!(UiShutdownReportResponse, "shutdownReport");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiSchemaDescriptionRequest {}
+conversation_message!(UiSchemaDescriptionRequest, "schemaDescription");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiParameterDescription {
+    pub name: String,
+    pub group: String,
+    #[serde(rename = "displayOrder")]
+    pub display_order: usize,
+    pub help: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiSchemaDescriptionResponse {
+    pub parameters: Vec<UiParameterDescription>,
+}
+conversation_message!(UiSchemaDescriptionResponse, "schemaDescription");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiSaveProfileRequest {
+    pub name: String,
+}
+conversation_message!(UiSaveProfileRequest, "saveProfile");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiSaveProfileResponse {
+    pub name: String,
+    #[serde(rename = "savedParameters")]
+    pub saved_parameters: Vec<String>,
+}
+conversation_message!(UiSaveProfileResponse, "saveProfile");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiLoadProfileRequest {
+    pub name: String,
+}
+conversation_message!(UiLoadProfileRequest, "loadProfile");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiLoadProfileResponse {
+    pub name: String,
+    #[serde(rename = "loadedParameters")]
+    pub loaded_parameters: Vec<String>,
+}
+conversation_message!(UiLoadProfileResponse, "loadProfile");
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct UiSetupRequestValue {
     pub name: String,
     pub value: Option<String>,
+    // Unlike clearing, resetting leaves nothing of the old value to fall back on: the parameter,
+    // and everything computed from it, is worked out again as if the UI had never mentioned it.
+    #[serde(default)]
+    pub reset: bool,
 }
 
 impl UiSetupRequestValue {
@@ -220,6 +455,7 @@ impl UiSetupRequestValue {
         UiSetupRequestValue {
             name: name.to_string(),
             value: Some(value.to_string()),
+            reset: false,
         }
     }
 
@@ -227,6 +463,15 @@ impl UiSetupRequestValue {
         UiSetupRequestValue {
             name: name.to_string(),
             value: None,
+            reset: false,
+        }
+    }
+
+    pub fn reset(name: &str) -> Self {
+        UiSetupRequestValue {
+            name: name.to_string(),
+            value: None,
+            reset: true,
         }
     }
 }
@@ -234,6 +479,10 @@ impl UiSetupRequestValue {
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct UiSetupRequest {
     pub values: Vec<UiSetupRequestValue>,
+    // The revision of the setup these changes were based on. If another UI has changed any of the
+    // same parameters since, the request is refused; left out, the request simply wins.
+    #[serde(rename = "revisionOpt", default)]
+    pub revision_opt: Option<u64>,
 }
 conversation_message!(UiSetupRequest, "setup");
 
@@ -245,8 +494,10 @@ impl UiSetupRequest {
                 .map(|(name, value)| UiSetupRequestValue {
                     name: name.to_string(),
                     value: value.map(to_string),
+                    reset: false,
                 })
                 .collect(),
+            revision_opt: None,
         }
     }
 }
@@ -258,6 +509,12 @@ pub enum UiSetupResponseValueStatus {
     Set,
     Blank,
     Required,
+    // Only ever proposed on a first setup, from what the Daemon found out about the environment;
+    // the Node will use it, but anything Configured or Set takes precedence
+    Suggested,
+    // Only ever reported, never stored: the value is in effect, but it conflicts with another
+    // one, and the errors in the same message explain how
+    Warning,
 }
 
 impl UiSetupResponseValueStatus {
@@ -266,8 +523,10 @@ impl UiSetupResponseValueStatus {
             UiSetupResponseValueStatus::Blank => 0,
             UiSetupResponseValueStatus::Required => 0,
             UiSetupResponseValueStatus::Default => 1,
-            UiSetupResponseValueStatus::Configured => 2,
-            UiSetupResponseValueStatus::Set => 3,
+            UiSetupResponseValueStatus::Suggested => 2,
+            UiSetupResponseValueStatus::Configured => 3,
+            UiSetupResponseValueStatus::Set => 4,
+            UiSetupResponseValueStatus::Warning => 4,
         }
     }
 }
@@ -277,6 +536,10 @@ pub struct UiSetupResponseValue {
     pub name: String,
     pub value: String,
     pub status: UiSetupResponseValueStatus,
+    // Where a computed or configured value came from ("from database", "from config file at ...",
+    // "environment variable MASQ_...", "DNS inspection"), when the Daemon can tell
+    #[serde(rename = "provenanceOpt", default)]
+    pub provenance_opt: Option<String>,
 }
 
 impl UiSetupResponseValue {
@@ -289,15 +552,77 @@ impl UiSetupResponseValue {
             name: name.to_string(),
             value: value.to_string(),
             status,
+            provenance_opt: None,
         }
     }
+
+    pub fn provenance(mut self, provenance_opt: Option<String>) -> UiSetupResponseValue {
+        self.provenance_opt = provenance_opt;
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum UiSetupChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+// One parameter's difference between two setups, as a UI would show it before the change is made.
+// A parameter whose value stays put but whose status moves (Default to Set, say) counts as Changed;
+// one whose provenance alone moves does not.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiSetupChange {
+    pub name: String,
+    pub kind: UiSetupChangeKind,
+    #[serde(rename = "oldValueOpt")]
+    pub old_value_opt: Option<String>,
+    #[serde(rename = "oldStatusOpt")]
+    pub old_status_opt: Option<UiSetupResponseValueStatus>,
+    #[serde(rename = "newValueOpt")]
+    pub new_value_opt: Option<String>,
+    #[serde(rename = "newStatusOpt")]
+    pub new_status_opt: Option<UiSetupResponseValueStatus>,
+}
+
+impl UiSetupChange {
+    pub fn new(
+        old_opt: Option<&UiSetupResponseValue>,
+        new_opt: Option<&UiSetupResponseValue>,
+    ) -> Option<UiSetupChange> {
+        let (name, kind) = match (old_opt, new_opt) {
+            (None, None) => return None,
+            (None, Some(new)) => (new.name.clone(), UiSetupChangeKind::Added),
+            (Some(old), None) => (old.name.clone(), UiSetupChangeKind::Removed),
+            (Some(old), Some(new)) if (&old.value, old.status) == (&new.value, new.status) => {
+                return None
+            }
+            (Some(old), Some(_)) => (old.name.clone(), UiSetupChangeKind::Changed),
+        };
+        Some(UiSetupChange {
+            name,
+            kind,
+            old_value_opt: old_opt.map(|old| old.value.clone()),
+            old_status_opt: old_opt.map(|old| old.status),
+            new_value_opt: new_opt.map(|new| new.value.clone()),
+            new_status_opt: new_opt.map(|new| new.status),
+        })
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct UiSetupResponse {
     pub running: bool,
     pub values: Vec<UiSetupResponseValue>,
-    pub errors: Vec<(String, String)>,
+    pub errors: Vec<(String, String, u64)>,
+    // The arguments the Daemon would start the Node with, secrets masked; for comparison with
+    // headless runs. The UI port is chosen at launch, so it never appears here.
+    #[serde(rename = "commandLine", default)]
+    pub command_line: Vec<String>,
+    // Goes up by one every time the setup changes
+    #[serde(default)]
+    pub revision: u64,
 }
 conversation_message!(UiSetupResponse, "setup");
 impl UiSetupResponse {
@@ -305,6 +630,8 @@ impl UiSetupResponse {
         running: bool,
         values: HashMap<String, UiSetupResponseValue>,
         errors: ConfiguratorError,
+        command_line: Vec<String>,
+        revision: u64,
     ) -> UiSetupResponse {
         UiSetupResponse {
             running,
@@ -316,8 +643,10 @@ impl UiSetupResponse {
             errors: errors
                 .param_errors
                 .into_iter()
-                .map(|pe| (pe.parameter, pe.reason))
+                .map(|pe| (pe.parameter, pe.reason, pe.code))
                 .collect(),
+            command_line,
+            revision,
         }
     }
 }
@@ -326,7 +655,9 @@ impl UiSetupResponse {
 pub struct UiSetupBroadcast {
     pub running: bool,
     pub values: Vec<UiSetupResponseValue>,
-    pub errors: Vec<(String, String)>,
+    pub errors: Vec<(String, String, u64)>,
+    #[serde(default)]
+    pub revision: u64,
 }
 fire_and_forget_message!(UiSetupBroadcast, "setup");
 impl UiSetupBroadcast {
@@ -334,6 +665,7 @@ impl UiSetupBroadcast {
         running: bool,
         values: HashMap<String, UiSetupResponseValue>,
         errors: ConfiguratorError,
+        revision: u64,
     ) -> UiSetupBroadcast {
         UiSetupBroadcast {
             running,
@@ -345,8 +677,9 @@ impl UiSetupBroadcast {
             errors: errors
                 .param_errors
                 .into_iter()
-                .map(|pe| (pe.parameter, pe.reason))
+                .map(|pe| (pe.parameter, pe.reason, pe.code))
                 .collect(),
+            revision,
         }
     }
 }
@@ -355,7 +688,8 @@ impl UiSetupBroadcast {
 pub struct UiSetupInner {
     pub running: bool,
     pub values: Vec<UiSetupResponseValue>,
-    pub errors: Vec<(String, String)>,
+    pub errors: Vec<(String, String, u64)>,
+    pub command_line: Vec<String>,
 }
 
 impl From<UiSetupResponse> for UiSetupInner {
@@ -364,6 +698,7 @@ impl From<UiSetupResponse> for UiSetupInner {
             running: input.running,
             values: input.values,
             errors: input.errors,
+            command_line: input.command_line,
         }
     }
 }
@@ -374,6 +709,7 @@ impl From<UiSetupBroadcast> for UiSetupInner {
             running: input.running,
             values: input.values,
             errors: input.errors,
+            command_line: vec![],
         }
     }
 }
@@ -408,6 +744,16 @@ pub struct UiNodeCrashedBroadcast {
 }
 fire_and_forget_message!(UiNodeCrashedBroadcast, "crashed");
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiActorRestartedBroadcast {
+    pub actor: String,
+    #[serde(rename = "panicMessage")]
+    pub panic_message: String,
+    #[serde(rename = "restartCount")]
+    pub restart_count: u32,
+}
+fire_and_forget_message!(UiActorRestartedBroadcast, "actorRestarted");
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct UiRedirect {
     pub port: u16,
@@ -430,10 +776,285 @@ pub struct UiUnmarshalError {
 }
 fire_and_forget_message!(UiUnmarshalError, "unmarshalError");
 
+// Answered by the UiGateway of whichever process, Daemon or Node, receives it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiConnectionAuditRequest {}
+conversation_message!(UiConnectionAuditRequest, "connectionAudit");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiRequestCount {
+    pub opcode: String,
+    pub count: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiConnectionRecord {
+    #[serde(rename = "clientId")]
+    pub client_id: u64,
+    pub address: String,
+    #[serde(rename = "userAgentOpt")]
+    pub user_agent_opt: Option<String>,
+    #[serde(rename = "localeOpt")]
+    pub locale_opt: Option<String>,
+    #[serde(rename = "protocolVersion")]
+    pub protocol_version: u32,
+    #[serde(rename = "connectedAt")]
+    pub connected_at: i64,
+    pub requests: Vec<UiRequestCount>,
+    #[serde(rename = "disconnectedAtOpt")]
+    pub disconnected_at_opt: Option<i64>,
+    #[serde(rename = "disconnectReasonOpt")]
+    pub disconnect_reason_opt: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiConnectionAuditResponse {
+    pub connections: Vec<UiConnectionRecord>,
+}
+conversation_message!(UiConnectionAuditResponse, "connectionAudit");
+
+// Answered by the UiGateway of whichever process, Daemon or Node, receives it. Without a locale,
+// the one from the Accept-Language header of the UI's WebSocket handshake is used.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiMessageCatalogRequest {
+    #[serde(rename = "localeOpt")]
+    pub locale_opt: Option<String>,
+}
+conversation_message!(UiMessageCatalogRequest, "messageCatalog");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiCatalogEntry {
+    pub id: String,
+    #[serde(rename = "codeOpt")]
+    pub code_opt: Option<u64>,
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiMessageCatalogResponse {
+    pub locale: String,
+    pub entries: Vec<UiCatalogEntry>,
+}
+conversation_message!(UiMessageCatalogResponse, "messageCatalog");
+
 ///////////////////////////////////////////////////////////////////
 // These messages are sent to or by the Node only
 ///////////////////////////////////////////////////////////////////
 
+// Reports how far a slow request has got. It travels outside the request's conversation, so that
+// the next message in the conversation is still the response; contextId says which request it's about.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiProgressBroadcast {
+    #[serde(rename = "contextId")]
+    pub context_id: u64,
+    pub opcode: String,
+    pub stage: String,
+    pub completed: u64,
+    #[serde(rename = "totalOpt")]
+    pub total_opt: Option<u64>,
+}
+fire_and_forget_message!(UiProgressBroadcast, "progress");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiCancelRequest {
+    #[serde(rename = "contextId")]
+    pub context_id: u64,
+}
+fire_and_forget_message!(UiCancelRequest, "cancel");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiBalanceAuditRequest {
+    pub offset: u64,
+    #[serde(rename = "pageSize")]
+    pub page_size: u16,
+}
+conversation_message!(UiBalanceAuditRequest, "balanceAudit");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiBalanceAuditRecord {
+    pub rowid: u64,
+    pub timestamp: i64,
+    pub wallet: String,
+    pub account: String,
+    #[serde(rename = "changeWei")]
+    pub change_wei: i128,
+    pub cause: String,
+    #[serde(rename = "referenceOpt")]
+    pub reference_opt: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiBalanceAuditResponse {
+    #[serde(rename = "totalRecords")]
+    pub total_records: u64,
+    pub records: Vec<UiBalanceAuditRecord>,
+}
+conversation_message!(UiBalanceAuditResponse, "balanceAudit");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiPaymentCurveRequest {
+    #[serde(rename = "pointCount")]
+    pub point_count: u16,
+}
+conversation_message!(UiPaymentCurveRequest, "paymentCurve");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiPaymentCurvePoint {
+    #[serde(rename = "ageSec")]
+    pub age_sec: u64,
+    #[serde(rename = "payoutThresholdGweiOpt")]
+    pub payout_threshold_gwei_opt: Option<u64>,
+    #[serde(rename = "banThresholdGweiOpt")]
+    pub ban_threshold_gwei_opt: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiPaymentCurveResponse {
+    pub points: Vec<UiPaymentCurvePoint>,
+}
+conversation_message!(UiPaymentCurveResponse, "paymentCurve");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiVerifyPaymentRequest {
+    #[serde(rename = "transactionHash")]
+    pub transaction_hash: String,
+    #[serde(rename = "creditorWallet")]
+    pub creditor_wallet: String,
+}
+conversation_message!(UiVerifyPaymentRequest, "verifyPayment");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum UiPaymentVerdict {
+    // The chain has no receipt for the transaction: it's still pending, or it never existed
+    Unconfirmed,
+    Failed,
+    // The transaction went through, but none of the tokens it moved went to the creditor
+    NotPaidToCreditor,
+    Settled,
+    StillDelinquent,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiVerifyPaymentResponse {
+    pub verdict: UiPaymentVerdict,
+    #[serde(rename = "blockNumberOpt")]
+    pub block_number_opt: Option<u64>,
+    #[serde(rename = "paidToCreditorWei")]
+    pub paid_to_creditor_wei: u128,
+    #[serde(rename = "remainingDebtWei")]
+    pub remaining_debt_wei: u128,
+    #[serde(rename = "debtAgeSec")]
+    pub debt_age_sec: u64,
+    #[serde(rename = "banThresholdWeiOpt")]
+    pub ban_threshold_wei_opt: Option<u128>,
+}
+conversation_message!(UiVerifyPaymentResponse, "verifyPayment");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiEarningsHistoryRequest {
+    #[serde(rename = "startTimestamp")]
+    pub start_timestamp: i64,
+    #[serde(rename = "endTimestamp")]
+    pub end_timestamp: i64,
+}
+conversation_message!(UiEarningsHistoryRequest, "earningsHistory");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiDailyEarnings {
+    #[serde(rename = "dayStart")]
+    pub day_start: i64,
+    #[serde(rename = "bytesRouted")]
+    pub bytes_routed: u64,
+    #[serde(rename = "bytesExited")]
+    pub bytes_exited: u64,
+    #[serde(rename = "earnedWei")]
+    pub earned_wei: u128,
+    #[serde(rename = "spentWei")]
+    pub spent_wei: u128,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiEarningsHistoryResponse {
+    pub days: Vec<UiDailyEarnings>,
+}
+conversation_message!(UiEarningsHistoryResponse, "earningsHistory");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiAddressBookRequest {}
+conversation_message!(UiAddressBookRequest, "addressBook");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiAddressBookEntry {
+    pub wallet: String,
+    pub label: String,
+    #[serde(rename = "descriptorOpt")]
+    pub descriptor_opt: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiAddressBookResponse {
+    pub entries: Vec<UiAddressBookEntry>,
+}
+conversation_message!(UiAddressBookResponse, "addressBook");
+
+// A missing label removes the wallet from the address book
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiSetAddressBookEntryRequest {
+    pub wallet: String,
+    #[serde(rename = "labelOpt")]
+    pub label_opt: Option<String>,
+    #[serde(rename = "descriptorOpt")]
+    pub descriptor_opt: Option<String>,
+}
+conversation_message!(UiSetAddressBookEntryRequest, "setAddressBookEntry");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiSetAddressBookEntryResponse {}
+conversation_message!(UiSetAddressBookEntryResponse, "setAddressBookEntry");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiDatabaseMaintenanceRequest {
+    pub vacuum: bool,
+}
+conversation_message!(UiDatabaseMaintenanceRequest, "databaseMaintenance");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiTableRowCount {
+    pub name: String,
+    #[serde(rename = "rowCount")]
+    pub row_count: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiDatabaseStats {
+    #[serde(rename = "fileSize")]
+    pub file_size: u64,
+    #[serde(rename = "pageSize")]
+    pub page_size: u64,
+    #[serde(rename = "freePages")]
+    pub free_pages: u64,
+    pub tables: Vec<UiTableRowCount>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiDatabaseMaintenanceResponse {
+    pub stats: UiDatabaseStats,
+    #[serde(rename = "reclaimedBytesOpt")]
+    pub reclaimed_bytes_opt: Option<u64>,
+}
+conversation_message!(UiDatabaseMaintenanceResponse, "databaseMaintenance");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiChangeLogLevelRequest {
+    #[serde(rename = "logLevel")]
+    pub log_level: String,
+}
+conversation_message!(UiChangeLogLevelRequest, "changeLogLevel");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiChangeLogLevelResponse {}
+conversation_message!(UiChangeLogLevelResponse, "changeLogLevel");
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct UiChangePasswordRequest {
     #[serde(rename = "oldPasswordOpt")]
@@ -582,6 +1203,8 @@ conversation_message!(UiDescriptorRequest, "descriptor");
 pub struct UiDescriptorResponse {
     #[serde(rename = "nodeDescriptorOpt")]
     pub node_descriptor_opt: Option<String>,
+    #[serde(rename = "operatorNotesTokenOpt")]
+    pub operator_notes_token_opt: Option<String>,
 }
 conversation_message!(UiDescriptorResponse, "descriptor");
 
@@ -601,6 +1224,12 @@ pub struct TopRecordsConfig {
     pub count: u16,
     #[serde(rename = "orderedBy")]
     pub ordered_by: TopRecordsOrdering,
+    // Number of records to skip, so that a UI can page through a long table count at a time
+    #[serde(default)]
+    pub offset: u64,
+    // Youngest or smallest first instead of oldest or biggest first
+    #[serde(default)]
+    pub ascending: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
@@ -639,6 +1268,12 @@ pub struct RangeQuery<T> {
     pub min_amount_gwei: T,
     #[serde(rename = "maxAmountGwei")]
     pub max_amount_gwei: T,
+    // Number of matching records to skip
+    #[serde(default)]
+    pub offset: u64,
+    // Largest number of matching records to return; all of them if None
+    #[serde(rename = "limitOpt", default)]
+    pub limit_opt: Option<u16>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
@@ -647,6 +1282,8 @@ pub struct UiFinancialsResponse {
     pub stats_opt: Option<UiFinancialStatistics>,
     #[serde(rename = "queryResultsOpt")]
     pub query_results_opt: Option<QueryResults>,
+    #[serde(rename = "fiatOpt")]
+    pub fiat_opt: Option<UiFiatValues>,
 }
 conversation_message!(UiFinancialsResponse, "financials");
 
@@ -679,6 +1316,8 @@ pub struct UiPayableAccount {
     pub balance_gwei: u64,
     #[serde(rename = "pendingPayableHashOpt")]
     pub pending_payable_hash_opt: Option<String>,
+    #[serde(rename = "labelOpt")]
+    pub label_opt: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
@@ -688,6 +1327,38 @@ pub struct UiReceivableAccount {
     pub age_s: u64,
     #[serde(rename = "balanceGwei")]
     pub balance_gwei: i64,
+    #[serde(rename = "labelOpt")]
+    pub label_opt: Option<String>,
+}
+
+// Approximate values of the amounts in a UiFinancialsResponse, in whatever currency the price feed
+// quotes, as decimal strings rounded to hundredths. The account lists are in the same order as
+// the ones in the query results.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiFiatValues {
+    pub currency: String,
+    #[serde(rename = "masqPrice")]
+    pub masq_price: String,
+    #[serde(rename = "priceAgeS")]
+    pub price_age_s: u64,
+    #[serde(rename = "statsOpt")]
+    pub stats_opt: Option<UiFiatStatistics>,
+    #[serde(rename = "payableOpt")]
+    pub payable_opt: Option<Vec<String>>,
+    #[serde(rename = "receivableOpt")]
+    pub receivable_opt: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiFiatStatistics {
+    #[serde(rename = "totalUnpaidAndPendingPayable")]
+    pub total_unpaid_and_pending_payable: String,
+    #[serde(rename = "totalPaidPayable")]
+    pub total_paid_payable: String,
+    #[serde(rename = "totalUnpaidReceivable")]
+    pub total_unpaid_receivable: String,
+    #[serde(rename = "totalPaidReceivable")]
+    pub total_paid_receivable: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -743,10 +1414,130 @@ pub enum SerializableLogLevel {
     Info,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiNeighborhoodGraphRequest {
+    #[serde(rename = "includeAddresses")]
+    pub include_addresses: bool,
+}
+conversation_message!(UiNeighborhoodGraphRequest, "neighborhoodGraph");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiGraphNode {
+    #[serde(rename = "publicKey")]
+    pub public_key: String,
+    pub version: u32,
+    #[serde(rename = "isRoot")]
+    pub is_root: bool,
+    #[serde(rename = "nodeAddrOpt")]
+    pub node_addr_opt: Option<String>,
+    // Public keys of the Nodes this one claims as neighbors
+    pub neighbors: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiNeighborhoodGraphResponse {
+    pub nodes: Vec<UiGraphNode>,
+}
+conversation_message!(UiNeighborhoodGraphResponse, "neighborhoodGraph");
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct UiNewPasswordBroadcast {}
 fire_and_forget_message!(UiNewPasswordBroadcast, "newPassword");
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiPauseServingRequest {}
+conversation_message!(UiPauseServingRequest, "pauseServing");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiPauseServingResponse {
+    // False if serving was already paused, or if the Node never routes or exits data anyway
+    pub changed: bool,
+}
+conversation_message!(UiPauseServingResponse, "pauseServing");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiPingNeighborRequest {
+    pub descriptor: String,
+}
+conversation_message!(UiPingNeighborRequest, "pingNeighbor");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum UiPingStage {
+    Descriptor,
+    Address,
+    Connection,
+    Timeout,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiPingFailure {
+    pub stage: UiPingStage,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiPingNeighborResponse {
+    #[serde(rename = "roundTripMillisOpt")]
+    pub round_trip_millis_opt: Option<u64>,
+    #[serde(rename = "failureOpt")]
+    pub failure_opt: Option<UiPingFailure>,
+}
+conversation_message!(UiPingNeighborResponse, "pingNeighbor");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiPinNeighborRequest {
+    // Base64, as in a Node descriptor
+    #[serde(rename = "publicKey")]
+    pub public_key: String,
+    // False to unpin
+    pub pinned: bool,
+}
+conversation_message!(UiPinNeighborRequest, "pinNeighbor");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiPinNeighborResponse {
+    // Every key pinned once the request has been carried out, sorted
+    #[serde(rename = "pinnedNeighbors")]
+    pub pinned_neighbors: Vec<String>,
+}
+conversation_message!(UiPinNeighborResponse, "pinNeighbor");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiRecentFailuresRequest {}
+conversation_message!(UiRecentFailuresRequest, "recentFailures");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiRouteHop {
+    // "routing" or "exit"
+    pub role: String,
+    #[serde(rename = "publicKey")]
+    pub public_key: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiRequestFailure {
+    #[serde(rename = "failedAt")]
+    pub failed_at: i64,
+    #[serde(rename = "streamKey")]
+    pub stream_key: String,
+    #[serde(rename = "hostnameOpt")]
+    pub hostname_opt: Option<String>,
+    // Empty if no route could be found
+    pub route: Vec<UiRouteHop>,
+    // "routeQuery" or "exit"
+    pub stage: String,
+    #[serde(rename = "errorClass")]
+    pub error_class: String,
+    #[serde(rename = "retryCount")]
+    pub retry_count: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiRecentFailuresResponse {
+    pub failures: Vec<UiRequestFailure>,
+}
+conversation_message!(UiRecentFailuresResponse, "recentFailures");
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct UiRecoverSeedSpec {
     #[serde(rename = "mnemonicPhrase")]
@@ -778,6 +1569,57 @@ conversation_message!(UiRecoverWalletsRequest, "recoverWallets");
 pub struct UiRecoverWalletsResponse {}
 conversation_message!(UiRecoverWalletsResponse, "recoverWallets");
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiResumeServingRequest {}
+conversation_message!(UiResumeServingRequest, "resumeServing");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiResumeServingResponse {
+    // False if serving wasn't paused
+    pub changed: bool,
+}
+conversation_message!(UiResumeServingResponse, "resumeServing");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiRouteExplanationRequest {
+    #[serde(rename = "hostnameOpt")]
+    pub hostname_opt: Option<String>,
+    #[serde(rename = "payloadSize")]
+    pub payload_size: usize,
+}
+conversation_message!(UiRouteExplanationRequest, "routeExplanation");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiCostedHop {
+    #[serde(rename = "publicKey")]
+    pub public_key: String,
+    // "origin", "routing", or "exit"
+    pub role: String,
+    // What the Node's rate pack charges for its part in the route
+    #[serde(rename = "rateContribution")]
+    pub rate_contribution: i64,
+    // For hosts the Node has failed to reach, and for creditors this Node owes too much
+    pub penalty: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiRouteCandidate {
+    pub hops: Vec<UiCostedHop>,
+    pub undesirability: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiRouteExplanationResponse {
+    #[serde(rename = "minimumHops")]
+    pub minimum_hops: usize,
+    // In the order the routing engine found them
+    pub candidates: Vec<UiRouteCandidate>,
+    #[serde(rename = "winnerIndexOpt")]
+    pub winner_index_opt: Option<usize>,
+    pub reason: String,
+}
+conversation_message!(UiRouteExplanationResponse, "routeExplanation");
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum ScanType {
     Payables,
@@ -829,6 +1671,88 @@ conversation_message!(UiShutdownRequest, "shutdown");
 pub struct UiShutdownResponse {}
 conversation_message!(UiShutdownResponse, "shutdown");
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UiTransactionFeeBalanceLevel {
+    Sufficient,
+    Low,
+    Critical,
+    Depleted,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiTransactionFeeBalanceBroadcast {
+    pub level: UiTransactionFeeBalanceLevel,
+    #[serde(rename = "balanceWei")]
+    pub balance_wei: u128,
+    #[serde(rename = "feePerTransactionWei")]
+    pub fee_per_transaction_wei: u128,
+    #[serde(rename = "affordableTransactions")]
+    pub affordable_transactions: u64,
+}
+fire_and_forget_message!(UiTransactionFeeBalanceBroadcast, "transactionFeeBalance");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiAbandonedPayable {
+    #[serde(rename = "transactionHash")]
+    pub transaction_hash: String,
+    #[serde(rename = "amountWei")]
+    pub amount_wei: u128,
+    pub attempts: u16,
+    #[serde(rename = "ageSec")]
+    pub age_sec: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiPendingPayablesAbandonedBroadcast {
+    pub payables: Vec<UiAbandonedPayable>,
+}
+fire_and_forget_message!(
+    UiPendingPayablesAbandonedBroadcast,
+    "pendingPayablesAbandoned"
+);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UiAlertRule {
+    OverdueReceivables,
+    ExcessivePayables,
+    ExternalWalletUse,
+    PaymentOverdue,
+    BlockchainServiceFailing,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiAlertBroadcast {
+    pub rule: UiAlertRule,
+    pub message: String,
+}
+fire_and_forget_message!(UiAlertBroadcast, "alert");
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UiBanRiskCause {
+    NoConsumingWallet,
+    PaymentFailing,
+    Unpaid,
+}
+
+// A creditor that, by the standard payment curve, will probably ban this Node at banTimestamp
+// (which may already be past) unless it's paid first
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiImminentBan {
+    pub wallet: String,
+    #[serde(rename = "balanceGwei")]
+    pub balance_gwei: u64,
+    #[serde(rename = "banTimestamp")]
+    pub ban_timestamp: i64,
+    pub cause: UiBanRiskCause,
+    pub detail: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiBanRiskBroadcast {
+    pub bans: Vec<UiImminentBan>,
+}
+fire_and_forget_message!(UiBanRiskBroadcast, "banRisk");
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct UiWalletAddressesRequest {
     #[serde(rename = "dbPassword")]
@@ -849,12 +1773,277 @@ conversation_message!(UiWalletAddressesResponse, "walletAddresses");
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::constants::{INVALID_PARAM_VALUE_ERROR, MISSING_PARAM_ERROR};
     use crate::messages::UiMessageError::{DeserializationError, PayloadError, UnexpectedMessage};
     use crate::ui_gateway::MessagePath::{Conversation, FireAndForget};
 
     #[test]
     fn constants_have_correct_values() {
         assert_eq!(NODE_UI_PROTOCOL, "MASQNode-UIv2");
+        assert_eq!(NODE_UI_PROTOCOL_PREFIX, "MASQNode-UIv");
+        assert_eq!(NODE_UI_PROTOCOL_VERSION, 2);
+    }
+
+    #[test]
+    fn current_protocol_name_is_built_from_current_version() {
+        assert_eq!(
+            node_ui_protocol_name(NODE_UI_PROTOCOL_VERSION),
+            NODE_UI_PROTOCOL
+        );
+        assert_eq!(node_ui_protocol_name(1), "MASQNode-UIv1");
+    }
+
+    #[test]
+    fn protocol_versions_are_parsed_only_from_node_ui_protocol_names() {
+        assert_eq!(node_ui_protocol_version("MASQNode-UIv2"), Some(2));
+        assert_eq!(node_ui_protocol_version("MASQNode-UIv17"), Some(17));
+        assert_eq!(node_ui_protocol_version("MASQNode-UIv"), None);
+        assert_eq!(node_ui_protocol_version("MASQNode-UIvTwo"), None);
+        assert_eq!(node_ui_protocol_version("chat"), None);
+    }
+
+    #[test]
+    fn idempotency_key_rides_along_in_the_payload_of_a_request() {
+        assert_eq!(IDEMPOTENCY_KEY_FIELD, "idempotencyKey");
+        let body = with_idempotency_key(UiShutdownRequest {}.tmb(1234), "shutdown-1");
+
+        assert_eq!(
+            body.payload,
+            Ok(r#"{"idempotencyKey":"shutdown-1"}"#.to_string())
+        );
+        assert_eq!(idempotency_key_opt(&body), Some("shutdown-1".to_string()));
+        assert_eq!(
+            UiShutdownRequest::fmb(body),
+            Ok((UiShutdownRequest {}, 1234))
+        );
+    }
+
+    #[test]
+    fn idempotency_key_is_absent_unless_it_is_a_string_in_a_successful_payload() {
+        let body_with_payload = |payload: Result<String, (u64, String)>| MessageBody {
+            opcode: "shutdown".to_string(),
+            path: Conversation(1234),
+            payload,
+        };
+
+        vec![
+            body_with_payload(Ok("{}".to_string())),
+            body_with_payload(Ok(r#"{"idempotencyKey":17}"#.to_string())),
+            body_with_payload(Ok("not JSON".to_string())),
+            body_with_payload(Err((1, r#"{"idempotencyKey":"key"}"#.to_string()))),
+        ]
+        .into_iter()
+        .for_each(|body| assert_eq!(idempotency_key_opt(&body), None, "{:?}", body));
+        let error_body = body_with_payload(Err((1, "booga".to_string())));
+        assert_eq!(with_idempotency_key(error_body.clone(), "key"), error_body);
+    }
+
+    #[test]
+    fn setup_messages_carry_the_codes_of_configurator_errors() {
+        let errors = ConfiguratorError::required("ip", "Bad IP", INVALID_PARAM_VALUE_ERROR)
+            .another_required("neighbors", "Missing neighbors", MISSING_PARAM_ERROR);
+        let expected_errors = vec![
+            (
+                "ip".to_string(),
+                "Bad IP".to_string(),
+                INVALID_PARAM_VALUE_ERROR,
+            ),
+            (
+                "neighbors".to_string(),
+                "Missing neighbors".to_string(),
+                MISSING_PARAM_ERROR,
+            ),
+        ];
+
+        let response = UiSetupResponse::new(false, HashMap::new(), errors.clone(), vec![], 0);
+        let broadcast = UiSetupBroadcast::new(false, HashMap::new(), errors, 0);
+
+        assert_eq!(response.errors, expected_errors);
+        assert_eq!(broadcast.errors, expected_errors);
+        let json = serde_json::to_string(&response).unwrap();
+        assert_eq!(
+            json.contains(r#""errors":[["ip","Bad IP",562949953421313],"#),
+            true,
+            "{}",
+            json
+        );
+    }
+
+    #[test]
+    fn setup_change_tells_added_removed_and_changed_apart() {
+        let default_value = UiSetupResponseValue::new(
+            "chain",
+            "polygon-mainnet",
+            UiSetupResponseValueStatus::Default,
+        );
+        let set_value =
+            UiSetupResponseValue::new("chain", "polygon-mainnet", UiSetupResponseValueStatus::Set);
+
+        let added = UiSetupChange::new(None, Some(&set_value)).unwrap();
+        let removed = UiSetupChange::new(Some(&set_value), None).unwrap();
+        let changed = UiSetupChange::new(Some(&default_value), Some(&set_value)).unwrap();
+        let unchanged = UiSetupChange::new(Some(&set_value), Some(&set_value));
+        let nothing = UiSetupChange::new(None, None);
+
+        assert_eq!(added.kind, UiSetupChangeKind::Added);
+        assert_eq!(
+            (added.old_value_opt, added.new_value_opt),
+            (None, Some("polygon-mainnet".to_string()))
+        );
+        assert_eq!(removed.kind, UiSetupChangeKind::Removed);
+        assert_eq!(
+            (removed.old_status_opt, removed.new_status_opt),
+            (Some(UiSetupResponseValueStatus::Set), None)
+        );
+        assert_eq!(changed.kind, UiSetupChangeKind::Changed);
+        assert_eq!(
+            (changed.old_status_opt, changed.new_status_opt),
+            (
+                Some(UiSetupResponseValueStatus::Default),
+                Some(UiSetupResponseValueStatus::Set)
+            )
+        );
+        assert_eq!(unchanged, None);
+        assert_eq!(nothing, None);
+    }
+
+    #[test]
+    fn setup_change_serializes_in_the_ui_message_format() {
+        let subject = UiSetupChange::new(
+            Some(&UiSetupResponseValue::new(
+                "log-level",
+                "warn",
+                UiSetupResponseValueStatus::Default,
+            )),
+            Some(&UiSetupResponseValue::new(
+                "log-level",
+                "debug",
+                UiSetupResponseValueStatus::Set,
+            )),
+        )
+        .unwrap();
+
+        let json = serde_json::to_string(&subject).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"name":"log-level","kind":"Changed","oldValueOpt":"warn","oldStatusOpt":"Default","newValueOpt":"debug","newStatusOpt":"Set"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<UiSetupChange>(&json).unwrap(),
+            subject
+        );
+    }
+
+    #[test]
+    fn setup_change_ignores_a_change_of_provenance_alone() {
+        let old_value =
+            UiSetupResponseValue::new("gas-price", "2", UiSetupResponseValueStatus::Configured)
+                .provenance(Some("from database".to_string()));
+        let new_value =
+            UiSetupResponseValue::new("gas-price", "2", UiSetupResponseValueStatus::Configured)
+                .provenance(Some("environment variable MASQ_GAS_PRICE".to_string()));
+
+        let result = UiSetupChange::new(Some(&old_value), Some(&new_value));
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn setup_response_value_carries_provenance_and_tolerates_its_absence() {
+        let value =
+            UiSetupResponseValue::new("gas-price", "2", UiSetupResponseValueStatus::Configured)
+                .provenance(Some("from database".to_string()));
+
+        let json = serde_json::to_string(&value).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"name":"gas-price","value":"2","status":"Configured","provenanceOpt":"from database"}"#
+        );
+        let old_json = r#"{"name":"gas-price","value":"2","status":"Configured"}"#;
+        let old_value = serde_json::from_str::<UiSetupResponseValue>(old_json).unwrap();
+        assert_eq!(old_value.provenance_opt, None);
+    }
+
+    #[test]
+    fn setup_response_carries_command_line_and_tolerates_its_absence() {
+        let response = UiSetupResponse::new(
+            false,
+            HashMap::new(),
+            ConfiguratorError::new(vec![]),
+            vec!["--chain".to_string(), "polygon-mainnet".to_string()],
+            0,
+        );
+
+        let json = serde_json::to_string(&response).unwrap();
+
+        assert_eq!(
+            json.contains(r#""commandLine":["--chain","polygon-mainnet"]"#),
+            true,
+            "{}",
+            json
+        );
+        let old_json = r#"{"running":false,"values":[],"errors":[]}"#;
+        let old_response = serde_json::from_str::<UiSetupResponse>(old_json).unwrap();
+        assert_eq!(old_response.command_line, Vec::<String>::new());
+    }
+
+    #[test]
+    fn setup_messages_carry_revisions_and_tolerate_their_absence() {
+        let request = UiSetupRequest {
+            values: vec![],
+            revision_opt: Some(12),
+        };
+        let response = UiSetupResponse::new(
+            false,
+            HashMap::new(),
+            ConfiguratorError::new(vec![]),
+            vec![],
+            13,
+        );
+
+        let request_json = serde_json::to_string(&request).unwrap();
+        let response_json = serde_json::to_string(&response).unwrap();
+
+        assert_eq!(request_json, r#"{"values":[],"revisionOpt":12}"#);
+        assert_eq!(
+            response_json.contains(r#""revision":13"#),
+            true,
+            "{}",
+            response_json
+        );
+        let old_request = serde_json::from_str::<UiSetupRequest>(r#"{"values":[]}"#).unwrap();
+        assert_eq!(old_request.revision_opt, None);
+        let old_broadcast = serde_json::from_str::<UiSetupBroadcast>(
+            r#"{"running":false,"values":[],"errors":[]}"#,
+        )
+        .unwrap();
+        assert_eq!(old_broadcast.revision, 0);
+    }
+
+    #[test]
+    fn financials_paging_fields_tolerate_their_absence() {
+        let top_records =
+            serde_json::from_str::<TopRecordsConfig>(r#"{"count":10,"orderedBy":"Balance"}"#)
+                .unwrap();
+        let range_query = serde_json::from_str::<RangeQuery<i64>>(
+            r#"{"minAgeS":0,"maxAgeS":100,"minAmountGwei":-5,"maxAmountGwei":5}"#,
+        )
+        .unwrap();
+
+        assert_eq!((top_records.offset, top_records.ascending), (0, false));
+        assert_eq!((range_query.offset, range_query.limit_opt), (0, None));
+        let paged_json = serde_json::to_string(&RangeQuery {
+            offset: 30,
+            limit_opt: Some(10),
+            ..range_query
+        })
+        .unwrap();
+        assert_eq!(
+            paged_json,
+            r#"{"minAgeS":0,"maxAgeS":100,"minAmountGwei":-5,"maxAmountGwei":5,"offset":30,"limitOpt":10}"#
+        );
     }
 
     #[test]
@@ -907,16 +2096,80 @@ mod tests {
     fn ui_descriptor_methods_were_correctly_generated() {
         let subject = UiDescriptorResponse {
             node_descriptor_opt: Some("descriptor".to_string()),
+            operator_notes_token_opt: None,
         };
 
         assert_eq!(subject.opcode(), "descriptor");
         assert_eq!(subject.is_conversational(), true);
     }
 
+    #[test]
+    fn ui_balance_audit_response_survives_wei_values_beyond_i64() {
+        let subject = UiBalanceAuditResponse {
+            total_records: 1,
+            records: vec![UiBalanceAuditRecord {
+                rowid: 1,
+                timestamp: 1_000,
+                wallet: "0x0000000000000000000000000000000000001234".to_string(),
+                account: "payable".to_string(),
+                change_wei: -123_456_789_012_345_678_901_234,
+                cause: "payment sent".to_string(),
+                reference_opt: Some("0xabcd".to_string()),
+            }],
+        };
+
+        let body = subject.clone().tmb(2468);
+        let (result, context_id) = UiBalanceAuditResponse::fmb(body).unwrap();
+
+        assert_eq!(result, subject);
+        assert_eq!(context_id, 2468);
+    }
+
+    #[test]
+    fn ui_verify_payment_response_survives_the_round_trip() {
+        let subject = UiVerifyPaymentResponse {
+            verdict: UiPaymentVerdict::StillDelinquent,
+            block_number_opt: Some(1_234_567),
+            paid_to_creditor_wei: 123_456_789_012_345_678_901_234,
+            remaining_debt_wei: 9_876_543_210_987_654_321,
+            debt_age_sec: 86_400,
+            ban_threshold_wei_opt: None,
+        };
+
+        let body = subject.clone().tmb(2468);
+        let (result, context_id) = UiVerifyPaymentResponse::fmb(body).unwrap();
+
+        assert_eq!(result, subject);
+        assert_eq!(context_id, 2468);
+    }
+
+    #[test]
+    fn ui_progress_broadcast_refers_to_its_request_from_outside_the_conversation() {
+        let subject = UiProgressBroadcast {
+            context_id: 2468,
+            opcode: "databaseMaintenance".to_string(),
+            stage: "vacuuming".to_string(),
+            completed: 1,
+            total_opt: Some(3),
+        };
+
+        let body = subject.clone().tmb(0);
+
+        assert_eq!(body.opcode, "progress");
+        assert_eq!(body.path, FireAndForget);
+        assert_eq!(
+            body.payload,
+            Ok(r#"{"contextId":2468,"opcode":"databaseMaintenance","stage":"vacuuming","completed":1,"totalOpt":3}"#.to_string())
+        );
+        let (result, _) = UiProgressBroadcast::fmb(body).unwrap();
+        assert_eq!(result, subject);
+    }
+
     #[test]
     fn can_serialize_ui_descriptor_response() {
         let subject = UiDescriptorResponse {
             node_descriptor_opt: None,
+            operator_notes_token_opt: None,
         };
         let subject_json = serde_json::to_string(&subject).unwrap();
 
@@ -1028,7 +2281,8 @@ mod tests {
             result,
             Ok((
                 UiDescriptorResponse {
-                    node_descriptor_opt: Some("descriptor".to_string())
+                    node_descriptor_opt: Some("descriptor".to_string()),
+                    operator_notes_token_opt: None,
                 },
                 4321
             ))
@@ -1,11 +1,12 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+use crate::constants::{INVALID_PARAM_VALUE_ERROR, MISSING_PARAM_ERROR, UNFAMILIAR_PARAM_ERROR};
 use crate::shared_schema::{ConfiguratorError, ParamError};
 #[allow(unused_imports)]
 use clap::{value_t, values_t};
 use clap::{App, ArgMatches};
 use regex::Regex;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Display, Formatter};
 use std::fs::File;
 use std::io::{ErrorKind, Read};
@@ -50,6 +51,7 @@ macro_rules! values_m {
 #[derive(Debug)]
 pub struct MultiConfig<'a> {
     arg_matches: ArgMatches<'a>,
+    provenances: HashMap<String, String>,
 }
 
 impl<'a> MultiConfig<'a> {
@@ -61,6 +63,7 @@ impl<'a> MultiConfig<'a> {
         schema: &App<'a, 'a>,
         vcls: Vec<Box<dyn VirtualCommandLine>>,
     ) -> Result<MultiConfig<'a>, ConfiguratorError> {
+        let provenances = Self::provenances(&vcls);
         let initial: Box<dyn VirtualCommandLine> =
             Box::new(CommandLineVcl::new(vec![String::new()]));
         let merged = vcls
@@ -80,7 +83,26 @@ impl<'a> MultiConfig<'a> {
             },
         };
 
-        Ok(MultiConfig { arg_matches })
+        Ok(MultiConfig {
+            arg_matches,
+            provenances,
+        })
+    }
+
+    // The same priority as the merge: a parameter's provenance is that of the last VCL supplying
+    // it, and a VCL that can't say where its values came from (the command line) leaves none
+    fn provenances(vcls: &[Box<dyn VirtualCommandLine>]) -> HashMap<String, String> {
+        let mut provenances = HashMap::new();
+        vcls.iter().for_each(|vcl| {
+            vcl.vcl_args().into_iter().for_each(|vcl_arg| {
+                let name = vcl_arg.name().trim_start_matches("--").to_string();
+                match vcl.provenance(vcl_arg.name()) {
+                    Some(provenance) => provenances.insert(name, provenance),
+                    None => provenances.remove(&name),
+                };
+            })
+        });
+        provenances
     }
 
     fn check_for_invalid_value_err(
@@ -96,7 +118,11 @@ impl<'a> MultiConfig<'a> {
                 let name = &captures[index_of_name];
                 let message = format!("Invalid value: {}", &captures[index_of_value]);
 
-                Some(ConfiguratorError::required(name, &message))
+                Some(ConfiguratorError::required(
+                    name,
+                    &message,
+                    INVALID_PARAM_VALUE_ERROR,
+                ))
             }
             None => None,
         }
@@ -130,6 +156,7 @@ impl<'a> MultiConfig<'a> {
                 requireds.push(ParamError::new(
                     &captures[1],
                     "ParamError parameter not provided",
+                    MISSING_PARAM_ERROR,
                 ));
                 match remaining_message.find(&captures[1]) {
                     Some(idx) => remaining_message = remaining_message[idx..].to_string(),
@@ -138,7 +165,11 @@ impl<'a> MultiConfig<'a> {
             }
             return ConfiguratorError::new(requireds);
         }
-        ConfiguratorError::required("<unknown>", &format!("Unfamiliar message: {}", e.message))
+        ConfiguratorError::required(
+            "<unknown>",
+            &format!("Unfamiliar message: {}", e.message),
+            UNFAMILIAR_PARAM_ERROR,
+        )
     }
 
     pub fn occurrences_of(&self, parameter: &str) -> u64 {
@@ -148,6 +179,10 @@ impl<'a> MultiConfig<'a> {
     pub fn arg_matches_ref(&self) -> &ArgMatches {
         &self.arg_matches
     }
+
+    pub fn provenance_of(&self, parameter: &str) -> Option<&str> {
+        self.provenances.get(parameter).map(|p| p.as_str())
+    }
 }
 
 pub trait VclArg: Debug {
@@ -229,6 +264,9 @@ pub trait VirtualCommandLine {
     fn is_computed(&self) -> bool {
         false
     }
+    fn provenance(&self, _name: &str) -> Option<String> {
+        None
+    }
 }
 
 impl Debug for dyn VirtualCommandLine {
@@ -327,6 +365,13 @@ impl VirtualCommandLine for EnvironmentVcl {
     fn args(&self) -> Vec<String> {
         vcl_args_to_args(&self.vcl_args)
     }
+
+    fn provenance(&self, name: &str) -> Option<String> {
+        Some(format!(
+            "environment variable {}",
+            Self::variable_name(name.trim_start_matches("--"))
+        ))
+    }
 }
 
 impl EnvironmentVcl {
@@ -350,17 +395,23 @@ impl EnvironmentVcl {
         }
         EnvironmentVcl { vcl_args }
     }
+
+    pub fn variable_name(parameter: &str) -> String {
+        format!("MASQ_{}", str::replace(&parameter.to_uppercase(), "-", "_"))
+    }
 }
 
 #[derive(Debug)]
 pub struct ConfigFileVcl {
     vcl_args: Vec<Box<dyn VclArg>>,
+    file_path: PathBuf,
 }
 
 impl Clone for ConfigFileVcl {
     fn clone(&self) -> Self {
         ConfigFileVcl {
             vcl_args: self.vcl_args.iter().map(|arg| arg.dup()).collect(),
+            file_path: self.file_path.clone(),
         }
     }
 }
@@ -373,6 +424,10 @@ impl VirtualCommandLine for ConfigFileVcl {
     fn args(&self) -> Vec<String> {
         vcl_args_to_args(&self.vcl_args)
     }
+
+    fn provenance(&self, _name: &str) -> Option<String> {
+        Some(format!("from config file at {}", self.file_path.display()))
+    }
 }
 
 #[derive(Debug)]
@@ -426,7 +481,10 @@ impl ConfigFileVcl {
                 if user_specified {
                     return Err(ConfigFileVclError::OpenError(file_path.to_path_buf(), e));
                 } else {
-                    return Ok(ConfigFileVcl { vcl_args: vec![] });
+                    return Ok(ConfigFileVcl {
+                        vcl_args: vec![],
+                        file_path: file_path.to_path_buf(),
+                    });
                 }
             }
             Ok(file) => file,
@@ -490,7 +548,10 @@ impl ConfigFileVcl {
                 Err(err) => (args, append(errs, err)),
             });
         if vcl_errs.is_empty() {
-            Ok(ConfigFileVcl { vcl_args })
+            Ok(ConfigFileVcl {
+                vcl_args,
+                file_path: file_path.to_path_buf(),
+            })
         } else {
             Err(vcl_errs.remove(0))
         }
@@ -506,7 +567,10 @@ fn append<T>(ts: Vec<T>, t: T) -> Vec<T> {
 #[cfg(not(feature = "no_test_share"))]
 impl<'a> MultiConfig<'a> {
     pub fn new_test_only(arg_matches: ArgMatches<'a>) -> Self {
-        Self { arg_matches }
+        Self {
+            arg_matches,
+            provenances: HashMap::new(),
+        }
     }
 }
 
@@ -597,7 +661,11 @@ pub mod tests {
 
         assert_eq!(
             result,
-            ConfiguratorError::required("<unknown>", "Unfamiliar message: unfamiliar")
+            ConfiguratorError::required(
+                "<unknown>",
+                "Unfamiliar message: unfamiliar",
+                UNFAMILIAR_PARAM_ERROR
+            )
         )
     }
 
@@ -926,9 +994,16 @@ pub mod tests {
 
         let result = MultiConfig::try_new(&schema, vcls).err().unwrap();
 
-        let expected =
-            ConfiguratorError::required("another-arg", "ParamError parameter not provided")
-                .another_required("numeric-arg", "ParamError parameter not provided");
+        let expected = ConfiguratorError::required(
+            "another-arg",
+            "ParamError parameter not provided",
+            MISSING_PARAM_ERROR,
+        )
+        .another_required(
+            "numeric-arg",
+            "ParamError parameter not provided",
+            MISSING_PARAM_ERROR,
+        );
         assert_eq!(result, expected);
     }
 
@@ -1038,6 +1113,94 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn multi_config_knows_which_vcl_each_value_came_from() {
+        let _guard = EnvironmentGuard::new();
+        let home_dir = ensure_node_home_directory_exists(
+            "multi_config",
+            "multi_config_knows_which_vcl_each_value_came_from",
+        );
+        let file_path = home_dir.join("config.toml");
+        {
+            let mut toml_file = File::create(&file_path).unwrap();
+            toml_file
+                .write_all(b"file-arg = \"booga\"\nenvironment-arg = \"booga\"\n")
+                .unwrap();
+        }
+        std::env::set_var("MASQ_ENVIRONMENT_ARG", "agoob");
+        let schema = App::new("test")
+            .arg(
+                Arg::with_name("command-arg")
+                    .long("command-arg")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("file-arg")
+                    .long("file-arg")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("environment-arg")
+                    .long("environment-arg")
+                    .takes_value(true),
+            );
+        let vcls: Vec<Box<dyn VirtualCommandLine>> = vec![
+            Box::new(CommandLineVcl::new(vec![
+                String::new(),
+                "--command-arg".to_string(),
+                "10".to_string(),
+                "--file-arg".to_string(),
+                "20".to_string(),
+            ])),
+            Box::new(ConfigFileVcl::new(&file_path, true).unwrap()),
+            Box::new(EnvironmentVcl::new(&schema)),
+        ];
+
+        let subject = MultiConfig::try_new(&schema, vcls).unwrap();
+
+        assert_eq!(subject.provenance_of("command-arg"), None);
+        assert_eq!(
+            subject.provenance_of("file-arg"),
+            Some(format!("from config file at {}", file_path.display()).as_str())
+        );
+        assert_eq!(
+            subject.provenance_of("environment-arg"),
+            Some("environment variable MASQ_ENVIRONMENT_ARG")
+        );
+        assert_eq!(subject.provenance_of("absent-arg"), None);
+    }
+
+    #[test]
+    fn command_line_that_overrides_another_vcl_leaves_no_provenance() {
+        let home_dir = ensure_node_home_directory_exists(
+            "multi_config",
+            "command_line_that_overrides_another_vcl_leaves_no_provenance",
+        );
+        let file_path = home_dir.join("config.toml");
+        {
+            let mut toml_file = File::create(&file_path).unwrap();
+            toml_file.write_all(b"numeric-arg = 10\n").unwrap();
+        }
+        let schema = App::new("test").arg(
+            Arg::with_name("numeric-arg")
+                .long("numeric-arg")
+                .takes_value(true),
+        );
+        let vcls: Vec<Box<dyn VirtualCommandLine>> = vec![
+            Box::new(ConfigFileVcl::new(&file_path, true).unwrap()),
+            Box::new(CommandLineVcl::new(vec![
+                String::new(),
+                "--numeric-arg".to_string(),
+                "20".to_string(),
+            ])),
+        ];
+
+        let subject = MultiConfig::try_new(&schema, vcls).unwrap();
+
+        assert_eq!(value_m!(subject, "numeric-arg", u64), Some(20));
+        assert_eq!(subject.provenance_of("numeric-arg"), None);
+    }
+
     #[test]
     fn config_file_vcl_handles_missing_file_when_not_user_specified() {
         let home_dir = ensure_node_home_directory_exists(
@@ -466,6 +466,7 @@ mod tests {
         .tmb(0);
         let conversation_number_three_response = UiDescriptorResponse {
             node_descriptor_opt: Some("ae15fe6".to_string()),
+            operator_notes_token_opt: None,
         }
         .tmb(3);
         let broadcast_number_three = UiNewPasswordBroadcast {}.tmb(0);
@@ -241,6 +241,7 @@ pub enum NeighborhoodModeLight {
     Standard,
     ConsumeOnly,
     OriginateOnly,
+    RelayOnly,
     ZeroHop,
 }
 
@@ -250,6 +251,7 @@ impl Display for NeighborhoodModeLight {
             Self::Standard => write!(f, "standard"),
             Self::ConsumeOnly => write!(f, "consume-only"),
             Self::OriginateOnly => write!(f, "originate-only"),
+            Self::RelayOnly => write!(f, "relay-only"),
             Self::ZeroHop => write!(f, "zero-hop"),
         }
     }
@@ -263,6 +265,7 @@ impl FromStr for NeighborhoodModeLight {
             "standard" => Self::Standard,
             "consume-only" => Self::ConsumeOnly,
             "originate-only" => Self::OriginateOnly,
+            "relay-only" => Self::RelayOnly,
             "zero-hop" => Self::ZeroHop,
             x => return Err(format!("Invalid value read for neighborhood mode: {}", x)),
         })
@@ -688,6 +691,7 @@ mod tests {
             NeighborhoodModeLight::OriginateOnly.to_string(),
             "originate-only"
         );
+        assert_eq!(NeighborhoodModeLight::RelayOnly.to_string(), "relay-only");
         assert_eq!(NeighborhoodModeLight::ZeroHop.to_string(), "zero-hop")
     }
 
@@ -705,6 +709,10 @@ mod tests {
             NeighborhoodModeLight::from_str("originate-only").unwrap(),
             NeighborhoodModeLight::OriginateOnly
         );
+        assert_eq!(
+            NeighborhoodModeLight::from_str("relay-only").unwrap(),
+            NeighborhoodModeLight::RelayOnly
+        );
         assert_eq!(
             NeighborhoodModeLight::from_str("zero-hop").unwrap(),
             NeighborhoodModeLight::ZeroHop
@@ -0,0 +1,361 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use masq_lib::blockchains::chains::Chain;
+use masq_lib::command::{Command, StdStreams};
+use multinode_integration_tests_lib::blockchain::BlockchainServer;
+use multinode_integration_tests_lib::masq_node::{MASQNode, NodeReference};
+use multinode_integration_tests_lib::masq_node_cluster::MASQNodeCluster;
+use multinode_integration_tests_lib::masq_real_node::{
+    make_consuming_wallet_info, make_earning_wallet_info, NodeStartupConfig,
+    NodeStartupConfigBuilder,
+};
+use multinode_integration_tests_lib::utils::UrlHolder;
+use std::env;
+use std::io;
+use std::io::{BufRead, BufReader};
+use std::process;
+
+pub const DEFAULT_NODE_COUNT: usize = 3;
+pub const MAX_NODE_COUNT: usize = 20;
+
+const USAGE: &str = "Usage: dev_chain_network [--nodes <count>] \
+    [--neighborhood-mode standard|zero-hop] [--no-blockchain]\n\n\
+    Starts <count> (default 3, at most 20) MASQ Nodes on the dev chain, each in its own Docker \
+    container on the integration_net network, along with a local ganache-cli blockchain unless \
+    --no-blockchain is specified. In standard mode every Node after the first is given the first \
+    Node as its neighbor; in zero-hop mode the Nodes are left unconnected. Press Enter to shut \
+    the network down.";
+
+pub fn main() {
+    let mut streams: StdStreams<'_> = StdStreams {
+        stdin: &mut io::stdin(),
+        stdout: &mut io::stdout(),
+        stderr: &mut io::stderr(),
+    };
+    let mut command = DevChainNetwork::new();
+    let args: Vec<String> = env::args().collect();
+    let exit_code = command.go(&mut streams, &args);
+    process::exit(exit_code as i32);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DevChainNeighborhoodMode {
+    Standard,
+    ZeroHop,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DevChainNetworkConfig {
+    pub node_count: usize,
+    pub neighborhood_mode: DevChainNeighborhoodMode,
+    pub blockchain: bool,
+}
+
+impl Default for DevChainNetworkConfig {
+    fn default() -> Self {
+        Self {
+            node_count: DEFAULT_NODE_COUNT,
+            neighborhood_mode: DevChainNeighborhoodMode::Standard,
+            blockchain: true,
+        }
+    }
+}
+
+impl DevChainNetworkConfig {
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut config = Self::default();
+        let mut iter = args.iter().skip(1);
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--nodes" => {
+                    let value = iter.next().ok_or("--nodes requires a value")?;
+                    config.node_count = match value.parse::<usize>() {
+                        Ok(count) if (1..=MAX_NODE_COUNT).contains(&count) => count,
+                        _ => {
+                            return Err(format!(
+                                "--nodes must be between 1 and {}, not '{}'",
+                                MAX_NODE_COUNT, value
+                            ))
+                        }
+                    }
+                }
+                "--neighborhood-mode" => {
+                    let value = iter.next().ok_or("--neighborhood-mode requires a value")?;
+                    config.neighborhood_mode = match value.as_str() {
+                        "standard" => DevChainNeighborhoodMode::Standard,
+                        "zero-hop" => DevChainNeighborhoodMode::ZeroHop,
+                        _ => {
+                            return Err(format!(
+                                "--neighborhood-mode must be standard or zero-hop, not '{}'",
+                                value
+                            ))
+                        }
+                    }
+                }
+                "--no-blockchain" => config.blockchain = false,
+                unrecognized => return Err(format!("Unrecognized argument: '{}'", unrecognized)),
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn node_startup_config(
+        &self,
+        index: usize,
+        blockchain_service_url_opt: Option<String>,
+        neighbor_opt: Option<NodeReference>,
+    ) -> NodeStartupConfig {
+        let token = format!("dev{}", index);
+        let builder = match self.neighborhood_mode {
+            DevChainNeighborhoodMode::Standard => NodeStartupConfigBuilder::standard(),
+            DevChainNeighborhoodMode::ZeroHop => NodeStartupConfigBuilder::zero_hop(),
+        }
+        .chain(Chain::Dev)
+        .earning_wallet_info(make_earning_wallet_info(&token))
+        .consuming_wallet_info(make_consuming_wallet_info(&token));
+        let builder = match blockchain_service_url_opt {
+            Some(url) => builder.blockchain_service_url(url),
+            None => builder,
+        };
+        let builder = match (self.neighborhood_mode, neighbor_opt) {
+            (DevChainNeighborhoodMode::Standard, Some(neighbor)) => builder.neighbor(neighbor),
+            _ => builder,
+        };
+        builder.build()
+    }
+}
+
+pub struct DevChainNetwork {}
+
+impl Command<u8> for DevChainNetwork {
+    fn go(&mut self, streams: &mut StdStreams<'_>, args: &[String]) -> u8 {
+        let config = match DevChainNetworkConfig::from_args(args) {
+            Ok(config) => config,
+            Err(msg) => {
+                writeln!(streams.stderr, "{}\n\n{}", msg, USAGE).unwrap();
+                return 1;
+            }
+        };
+        let mut cluster = match MASQNodeCluster::start() {
+            Ok(cluster) => cluster,
+            Err(e) => {
+                writeln!(streams.stderr, "Couldn't start the Docker cluster: {}", e).unwrap();
+                return 1;
+            }
+        };
+        let blockchain_server = BlockchainServer {
+            name: "ganache-cli",
+        };
+        let blockchain_service_url_opt = if config.blockchain {
+            blockchain_server.start();
+            blockchain_server.wait_until_ready();
+            let url = blockchain_server.url();
+            writeln!(streams.stdout, "Blockchain service URL: {}", url).unwrap();
+            Some(url)
+        } else {
+            None
+        };
+        let mut first_node_reference_opt: Option<NodeReference> = None;
+        (1..=config.node_count).for_each(|index| {
+            let startup_config = config.node_startup_config(
+                index,
+                blockchain_service_url_opt.clone(),
+                first_node_reference_opt.clone(),
+            );
+            let node = cluster.start_real_node(startup_config);
+            writeln!(streams.stdout, "{}: {}", node.name(), node.node_reference()).unwrap();
+            if first_node_reference_opt.is_none() {
+                first_node_reference_opt = Some(node.node_reference());
+            }
+        });
+        writeln!(
+            streams.stdout,
+            "Dev chain network with {} Node(s) is running. Press Enter to shut it down.",
+            config.node_count
+        )
+        .unwrap();
+        let mut line = String::new();
+        let _ = BufReader::new(&mut streams.stdin).read_line(&mut line);
+        cluster.stop();
+        0
+    }
+}
+
+impl Default for DevChainNetwork {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DevChainNetwork {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::test_utils::fake_stream_holder::FakeStreamHolder;
+    use masq_lib::utils::slice_of_strs_to_vec_of_strings;
+    use multinode_integration_tests_lib::masq_real_node::{ConsumingWalletInfo, EarningWalletInfo};
+    use node_lib::sub_lib::cryptde::PublicKey;
+    use std::net::IpAddr;
+    use std::str::FromStr;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(DEFAULT_NODE_COUNT, 3);
+        assert_eq!(MAX_NODE_COUNT, 20);
+    }
+
+    #[test]
+    fn config_defaults_when_no_args_are_supplied() {
+        let result = DevChainNetworkConfig::from_args(&["binary".to_string()]);
+
+        assert_eq!(
+            result,
+            Ok(DevChainNetworkConfig {
+                node_count: 3,
+                neighborhood_mode: DevChainNeighborhoodMode::Standard,
+                blockchain: true,
+            })
+        );
+    }
+
+    #[test]
+    fn config_accepts_all_args() {
+        let args = slice_of_strs_to_vec_of_strings(&[
+            "binary",
+            "--nodes",
+            "5",
+            "--neighborhood-mode",
+            "zero-hop",
+            "--no-blockchain",
+        ]);
+
+        let result = DevChainNetworkConfig::from_args(&args);
+
+        assert_eq!(
+            result,
+            Ok(DevChainNetworkConfig {
+                node_count: 5,
+                neighborhood_mode: DevChainNeighborhoodMode::ZeroHop,
+                blockchain: false,
+            })
+        );
+    }
+
+    #[test]
+    fn config_rejects_bad_node_counts() {
+        vec!["0", "21", "booga"].into_iter().for_each(|count| {
+            let args = slice_of_strs_to_vec_of_strings(&["binary", "--nodes", count]);
+
+            let result = DevChainNetworkConfig::from_args(&args);
+
+            assert_eq!(
+                result,
+                Err(format!("--nodes must be between 1 and 20, not '{}'", count))
+            );
+        });
+    }
+
+    #[test]
+    fn config_rejects_unsupported_neighborhood_mode() {
+        let args =
+            slice_of_strs_to_vec_of_strings(&["binary", "--neighborhood-mode", "consume-only"]);
+
+        let result = DevChainNetworkConfig::from_args(&args);
+
+        assert_eq!(
+            result,
+            Err("--neighborhood-mode must be standard or zero-hop, not 'consume-only'".to_string())
+        );
+    }
+
+    #[test]
+    fn config_rejects_missing_value_and_unrecognized_args() {
+        let missing = DevChainNetworkConfig::from_args(&slice_of_strs_to_vec_of_strings(&[
+            "binary", "--nodes",
+        ]));
+        let unrecognized = DevChainNetworkConfig::from_args(&slice_of_strs_to_vec_of_strings(&[
+            "binary", "--booga",
+        ]));
+
+        assert_eq!(missing, Err("--nodes requires a value".to_string()));
+        assert_eq!(
+            unrecognized,
+            Err("Unrecognized argument: '--booga'".to_string())
+        );
+    }
+
+    #[test]
+    fn standard_startup_config_is_wired_to_neighbor_and_blockchain() {
+        let subject = DevChainNetworkConfig::default();
+        let neighbor = NodeReference::new(
+            PublicKey::new(&[1, 2, 3, 4]),
+            Some(IpAddr::from_str("172.18.1.1").unwrap()),
+            vec![1234],
+            Chain::Dev,
+        );
+
+        let result = subject.node_startup_config(
+            2,
+            Some("http://172.18.1.250:18545".to_string()),
+            Some(neighbor.clone()),
+        );
+
+        assert_eq!(result.neighborhood_mode, "standard".to_string());
+        assert_eq!(result.chain, Chain::Dev);
+        assert_eq!(result.neighbors, vec![neighbor]);
+        assert_eq!(
+            result.blockchain_service_url_opt,
+            Some("http://172.18.1.250:18545".to_string())
+        );
+        assert_eq!(result.earning_wallet_info, make_earning_wallet_info("dev2"));
+        assert_eq!(
+            result.consuming_wallet_info,
+            make_consuming_wallet_info("dev2")
+        );
+        assert_ne!(result.earning_wallet_info, EarningWalletInfo::None);
+        assert_ne!(result.consuming_wallet_info, ConsumingWalletInfo::None);
+    }
+
+    #[test]
+    fn zero_hop_startup_config_ignores_neighbor() {
+        let subject = DevChainNetworkConfig {
+            node_count: 2,
+            neighborhood_mode: DevChainNeighborhoodMode::ZeroHop,
+            blockchain: false,
+        };
+        let neighbor = NodeReference::new(
+            PublicKey::new(&[1, 2, 3, 4]),
+            Some(IpAddr::from_str("172.18.1.1").unwrap()),
+            vec![1234],
+            Chain::Dev,
+        );
+
+        let result = subject.node_startup_config(2, None, Some(neighbor));
+
+        assert_eq!(result.neighborhood_mode, "zero-hop".to_string());
+        assert_eq!(result.neighbors, vec![]);
+        assert_eq!(result.blockchain_service_url_opt, None);
+    }
+
+    #[test]
+    fn go_complains_about_bad_args_without_touching_docker() {
+        let mut holder = FakeStreamHolder::new();
+        let mut subject = DevChainNetwork::new();
+
+        let result = subject.go(
+            &mut holder.streams(),
+            &slice_of_strs_to_vec_of_strings(&["binary", "--booga"]),
+        );
+
+        assert_eq!(result, 1);
+        assert_eq!(
+            holder.stderr.get_string(),
+            format!("Unrecognized argument: '--booga'\n\n{}\n", USAGE)
+        );
+    }
+}
@@ -486,11 +486,15 @@ impl MASQMockNode {
         public_key_opt: Option<&PublicKey>,
         chain: Chain,
     ) -> (RefCell<TcpStream>, MASQMockNodeGuts) {
-        let cryptde_enum = Self::initiate_cryptde_enum(public_key_opt, chain);
+        let cryptde_enum = Self::initiate_cryptde_enum(public_key_opt, index, chain);
         Self::start_with_cryptde_enum(ports, index, host_node_parent_dir, cryptde_enum)
     }
 
-    fn initiate_cryptde_enum(public_key_opt: Option<&PublicKey>, chain: Chain) -> CryptDEEnum {
+    fn initiate_cryptde_enum(
+        public_key_opt: Option<&PublicKey>,
+        index: usize,
+        chain: Chain,
+    ) -> CryptDEEnum {
         match public_key_opt {
             Some(public_key) => {
                 let main_cryptde = CryptDENull::from(public_key, chain);
@@ -499,7 +503,11 @@ impl MASQMockNode {
                 let alias_cryptde = CryptDENull::from(&PublicKey::new(&key), chain);
                 CryptDEEnum::Fake((main_cryptde, alias_cryptde))
             }
-            None => CryptDEEnum::Real(CryptDEReal::new(chain)),
+            // Seeded by position in the cluster, so that a rerun of a failing test sees the same keys
+            None => CryptDEEnum::Real(CryptDEReal::from_seed(
+                format!("mock-node-{}", index).as_bytes(),
+                chain,
+            )),
         }
     }
 
@@ -883,9 +883,9 @@ impl MASQRealNode {
                 }
             },
             chain: real_startup_config.chain,
-            accepts_connections: vec!["standard"]
+            accepts_connections: vec!["standard", "relay-only"]
                 .contains(&real_startup_config.neighborhood_mode.as_str()),
-            routes_data: vec!["standard", "originate-only"]
+            routes_data: vec!["standard", "originate-only", "relay-only"]
                 .contains(&real_startup_config.neighborhood_mode.as_str()),
         });
         let mut result = Self { guts };
@@ -137,6 +137,7 @@ impl From<&dyn MASQNode> for AccessibleGossipRecord {
                 neighbors: BTreeSet::new(),
                 accepts_connections: masq_node.accepts_connections(),
                 routes_data: masq_node.routes_data(),
+                exits_data: true,
                 version: 0,
             },
             node_addr_opt: Some(masq_node.node_addr()),
@@ -99,6 +99,8 @@ fn debtors_are_credited_once_but_not_twice() {
                 min_amount_gwei: 0,
                 max_amount_gwei: i64::MAX,
                 timestamp: SystemTime::now(),
+                offset: 0,
+                limit_opt: None,
             })
             .unwrap_or_default();
         assert_eq!(receivable_accounts.len(), 1);
@@ -136,6 +138,8 @@ fn debtors_are_credited_once_but_not_twice() {
                 min_amount_gwei: i64::MIN,
                 max_amount_gwei: i64::MAX,
                 timestamp: SystemTime::now(),
+                offset: 0,
+                limit_opt: None,
             })
             .unwrap_or_default();
         assert_eq!(receivable_accounts.len(), 1);
@@ -92,6 +92,8 @@ fn receivables(node: &MASQRealNode) -> Vec<ReceivableAccount> {
             min_amount_gwei: i64::MIN,
             max_amount_gwei: i64::MAX,
             timestamp: SystemTime::now(),
+            offset: 0,
+            limit_opt: None,
         })
         .unwrap_or_default()
 }
@@ -453,7 +453,7 @@ fn make_package_for_client(
         MessageTypeLite::DnsResolveFailed => {
             let dns_fail_vdata = VersionedData::new(
                 &node_lib::sub_lib::migrations::dns_resolve_failure::MIGRATIONS,
-                &DnsResolveFailure_0v1 { stream_key },
+                &DnsResolveFailure_0v1::new(stream_key),
             );
             MessageType::DnsResolveFailed(dns_fail_vdata)
         }
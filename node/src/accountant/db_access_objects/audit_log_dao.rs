@@ -0,0 +1,494 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::accountant::checked_conversion;
+use crate::accountant::db_access_objects::utils::{
+    from_time_t, to_time_t, DaoFactoryReal, VigilantRusqliteFlatten,
+};
+use crate::accountant::db_big_integer::big_int_divider::BigIntDivider;
+use crate::database::rusqlite_wrappers::{ConnectionWrapper, TransactionSafeWrapper};
+use crate::sub_lib::wallet::Wallet;
+use itertools::Either;
+use masq_lib::messages::UiBalanceAuditRecord;
+use rusqlite::{named_params, Row, Statement};
+use serde_derive::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::time::SystemTime;
+use web3::types::H256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditedAccount {
+    Payable,
+    Receivable,
+}
+
+impl AuditedAccount {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AuditedAccount::Payable => "payable",
+            AuditedAccount::Receivable => "receivable",
+        }
+    }
+
+    fn from_db(value: &str) -> Self {
+        match value {
+            "payable" => AuditedAccount::Payable,
+            "receivable" => AuditedAccount::Receivable,
+            x => panic!("Database corrupt: unknown audited account type '{}'", x),
+        }
+    }
+}
+
+const PAYMENT_RECEIVED: &str = "payment received";
+const PAYMENT_ORPHANED: &str = "payment orphaned";
+const SERVICES_PROVIDED_SAMPLED: &str = "services provided (sampled)";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BalanceChangeCause {
+    ServicesConsumed,
+    ServicesProvided,
+    // Extrapolated from one CORES package in every sample_size, under a low-power preset
+    ServicesProvidedSampled { sample_size: u32 },
+    PaymentSent { transaction_hash: H256 },
+    PaymentReceived { block_number: u64 },
+    // Takes back a received payment whose block was dropped in a chain reorganization
+    PaymentOrphaned { block_number: u64 },
+}
+
+impl BalanceChangeCause {
+    fn as_db_pair(&self) -> (&'static str, Option<String>) {
+        match self {
+            BalanceChangeCause::ServicesConsumed => ("services consumed", None),
+            BalanceChangeCause::ServicesProvided => ("services provided", None),
+            BalanceChangeCause::ServicesProvidedSampled { sample_size } => {
+                (SERVICES_PROVIDED_SAMPLED, Some(sample_size.to_string()))
+            }
+            BalanceChangeCause::PaymentSent { transaction_hash } => {
+                ("payment sent", Some(format!("{:?}", transaction_hash)))
+            }
+            BalanceChangeCause::PaymentReceived { block_number } => {
+                (PAYMENT_RECEIVED, Some(block_number.to_string()))
+            }
+            BalanceChangeCause::PaymentOrphaned { block_number } => {
+                (PAYMENT_ORPHANED, Some(block_number.to_string()))
+            }
+        }
+    }
+
+    fn from_db_pair(cause: &str, reference_opt: Option<String>) -> Self {
+        match (cause, reference_opt) {
+            ("services consumed", None) => BalanceChangeCause::ServicesConsumed,
+            ("services provided", None) => BalanceChangeCause::ServicesProvided,
+            (SERVICES_PROVIDED_SAMPLED, Some(sample_size)) => {
+                BalanceChangeCause::ServicesProvidedSampled {
+                    sample_size: sample_size.parse().unwrap_or_else(|_| {
+                        panic!("Database corrupt: bad sample size '{}'", sample_size)
+                    }),
+                }
+            }
+            ("payment sent", Some(hash)) => BalanceChangeCause::PaymentSent {
+                transaction_hash: hash.trim_start_matches("0x").parse().unwrap_or_else(|_| {
+                    panic!("Database corrupt: bad transaction hash '{}'", hash)
+                }),
+            },
+            (PAYMENT_RECEIVED, Some(block)) => BalanceChangeCause::PaymentReceived {
+                block_number: parse_block_number(&block),
+            },
+            (PAYMENT_ORPHANED, Some(block)) => BalanceChangeCause::PaymentOrphaned {
+                block_number: parse_block_number(&block),
+            },
+            (cause, reference_opt) => panic!(
+                "Database corrupt: unknown balance change cause '{}' with reference {:?}",
+                cause, reference_opt
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceChange {
+    pub timestamp: SystemTime,
+    pub wallet: Wallet,
+    pub account: AuditedAccount,
+    // Positive for increments, negative for decrements
+    pub change_wei: i128,
+    pub cause: BalanceChangeCause,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceAuditRecord {
+    pub rowid: u64,
+    pub change: BalanceChange,
+}
+
+impl From<BalanceAuditRecord> for UiBalanceAuditRecord {
+    fn from(record: BalanceAuditRecord) -> Self {
+        let change = record.change;
+        let (cause, reference_opt) = change.cause.as_db_pair();
+        UiBalanceAuditRecord {
+            rowid: record.rowid,
+            timestamp: to_time_t(change.timestamp),
+            wallet: change.wallet.to_string(),
+            account: change.account.as_str().to_string(),
+            change_wei: change.change_wei,
+            cause: cause.to_string(),
+            reference_opt,
+        }
+    }
+}
+
+// The table is append-only: records are written by the payable and receivable DAOs as side
+// effects of the balance changes they perform, and this DAO only reads them back.
+pub fn record_balance_change(
+    conn: Either<&dyn ConnectionWrapper, &TransactionSafeWrapper>,
+    change: &BalanceChange,
+) -> rusqlite::Result<()> {
+    let sql = "insert into balance_audit_log (timestamp, wallet_address, account_type, \
+        change_high_b, change_low_b, cause, reference) values (:timestamp, :wallet, :account, \
+        :change_high_b, :change_low_b, :cause, :reference)";
+    let (change_high_b, change_low_b) = BigIntDivider::deconstruct(change.change_wei);
+    let (cause, reference_opt) = change.cause.as_db_pair();
+    let params = named_params! {
+        ":timestamp": to_time_t(change.timestamp),
+        ":wallet": &change.wallet,
+        ":account": change.account.as_str(),
+        ":change_high_b": change_high_b,
+        ":change_low_b": change_low_b,
+        ":cause": cause,
+        ":reference": reference_opt,
+    };
+    let mut stmt: Statement = match conn {
+        Either::Left(conn) => conn.prepare(sql)?,
+        Either::Right(txn) => txn.prepare(sql)?,
+    };
+    stmt.execute(params).map(|_| ())
+}
+
+// Nets the receivable changes made by payments in blocks from the given one on, per wallet and
+// block; these are what must be taken back if those blocks got orphaned. Payments taken back
+// already net to zero and are left out.
+pub fn net_payments_received_since_block(
+    txn: &TransactionSafeWrapper,
+    block_number: u64,
+) -> rusqlite::Result<Vec<(Wallet, u64, i128)>> {
+    let mut stmt = txn.prepare(
+        "select wallet_address, reference, change_high_b, change_low_b from balance_audit_log \
+        where account_type = :account and cause in (:received, :orphaned) \
+        and cast(reference as integer) >= :block_number order by rowid asc",
+    )?;
+    let rows = stmt.query_map(
+        named_params! {
+            ":account": AuditedAccount::Receivable.as_str(),
+            ":received": PAYMENT_RECEIVED,
+            ":orphaned": PAYMENT_ORPHANED,
+            ":block_number": checked_conversion::<u64, i64>(block_number),
+        },
+        |row| {
+            let wallet: Wallet = row.get(0)?;
+            let reference: String = row.get(1)?;
+            let change_high_b: i64 = row.get(2)?;
+            let change_low_b: i64 = row.get(3)?;
+            Ok((
+                wallet,
+                parse_block_number(&reference),
+                BigIntDivider::reconstitute(change_high_b, change_low_b),
+            ))
+        },
+    )?;
+    let mut net_changes: Vec<(Wallet, u64, i128)> = vec![];
+    for row in rows {
+        let (wallet, block_number, change_wei) = row?;
+        match net_changes
+            .iter_mut()
+            .find(|(known_wallet, known_block, _)| {
+                known_wallet == &wallet && *known_block == block_number
+            }) {
+            Some((_, _, net_change_wei)) => *net_change_wei += change_wei,
+            None => net_changes.push((wallet, block_number, change_wei)),
+        }
+    }
+    net_changes.retain(|(_, _, net_change_wei)| *net_change_wei != 0);
+    Ok(net_changes)
+}
+
+fn parse_block_number(block: &str) -> u64 {
+    block
+        .parse()
+        .unwrap_or_else(|_| panic!("Database corrupt: bad block number '{}'", block))
+}
+
+pub trait AuditLogDao: Debug + Send {
+    fn records(&self, offset: u64, limit: u16) -> Vec<BalanceAuditRecord>;
+
+    fn count(&self) -> u64;
+}
+
+pub trait AuditLogDaoFactory {
+    fn make(&self) -> Box<dyn AuditLogDao>;
+}
+
+impl AuditLogDaoFactory for DaoFactoryReal {
+    fn make(&self) -> Box<dyn AuditLogDao> {
+        Box::new(AuditLogDaoReal::new(self.make_connection()))
+    }
+}
+
+#[derive(Debug)]
+pub struct AuditLogDaoReal {
+    conn: Box<dyn ConnectionWrapper>,
+}
+
+impl AuditLogDao for AuditLogDaoReal {
+    fn records(&self, offset: u64, limit: u16) -> Vec<BalanceAuditRecord> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "select rowid, timestamp, wallet_address, account_type, change_high_b, \
+                change_low_b, cause, reference from balance_audit_log order by rowid asc \
+                limit :limit offset :offset",
+            )
+            .expect("Failed to prepare a statement");
+        stmt.query_map(
+            named_params! {
+                ":limit": limit,
+                ":offset": checked_conversion::<u64, i64>(offset),
+            },
+            Self::row_to_record,
+        )
+        .expect("Couldn't retrieve balance audit log: database corrupt")
+        .vigilant_flatten()
+        .collect()
+    }
+
+    fn count(&self) -> u64 {
+        let mut stmt = self
+            .conn
+            .prepare("select count(*) from balance_audit_log")
+            .expect("Failed to prepare a statement");
+        let count: i64 = stmt
+            .query_row([], |row| row.get(0))
+            .expect("Couldn't count balance audit records: database corrupt");
+        checked_conversion::<i64, u64>(count)
+    }
+}
+
+impl AuditLogDaoReal {
+    pub fn new(conn: Box<dyn ConnectionWrapper>) -> Self {
+        Self { conn }
+    }
+
+    fn row_to_record(row: &Row) -> rusqlite::Result<BalanceAuditRecord> {
+        let rowid: i64 = row.get(0)?;
+        let timestamp: i64 = row.get(1)?;
+        let wallet: Wallet = row.get(2)?;
+        let account: String = row.get(3)?;
+        let change_high_b: i64 = row.get(4)?;
+        let change_low_b: i64 = row.get(5)?;
+        let cause: String = row.get(6)?;
+        let reference_opt: Option<String> = row.get(7)?;
+        Ok(BalanceAuditRecord {
+            rowid: checked_conversion::<i64, u64>(rowid),
+            change: BalanceChange {
+                timestamp: from_time_t(timestamp),
+                wallet,
+                account: AuditedAccount::from_db(&account),
+                change_wei: BigIntDivider::reconstitute(change_high_b, change_low_b),
+                cause: BalanceChangeCause::from_db_pair(&cause, reference_opt),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal,
+    };
+    use crate::test_utils::make_wallet;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use std::time::Duration;
+
+    fn make_change(wallet: &str, seconds: u64, change_wei: i128) -> BalanceChange {
+        BalanceChange {
+            timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(seconds),
+            wallet: make_wallet(wallet),
+            account: AuditedAccount::Payable,
+            change_wei,
+            cause: BalanceChangeCause::ServicesConsumed,
+        }
+    }
+
+    #[test]
+    fn records_can_be_written_and_read_back_in_pages() {
+        let home_dir = ensure_node_home_directory_exists(
+            "audit_log_dao",
+            "records_can_be_written_and_read_back_in_pages",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let first = make_change("booga", 1_000, 123_456_789_012_345_678_901_234);
+        let second = BalanceChange {
+            account: AuditedAccount::Receivable,
+            cause: BalanceChangeCause::PaymentReceived { block_number: 4321 },
+            ..make_change("agoob", 2_000, -5_000)
+        };
+        let third = BalanceChange {
+            cause: BalanceChangeCause::PaymentSent {
+                transaction_hash: H256::from_low_u64_be(0xabcd),
+            },
+            ..make_change("booga", 3_000, -1)
+        };
+        let fourth = BalanceChange {
+            account: AuditedAccount::Receivable,
+            cause: BalanceChangeCause::ServicesProvidedSampled { sample_size: 16 },
+            ..make_change("agoob", 4_000, 160_000)
+        };
+        [&first, &second, &third, &fourth]
+            .iter()
+            .for_each(|change| record_balance_change(Either::Left(conn.as_ref()), change).unwrap());
+        let subject = AuditLogDaoReal::new(conn);
+
+        let count = subject.count();
+        let first_page = subject.records(0, 2);
+        let second_page = subject.records(2, 2);
+
+        assert_eq!(count, 4);
+        assert_eq!(
+            first_page,
+            vec![
+                BalanceAuditRecord {
+                    rowid: 1,
+                    change: first
+                },
+                BalanceAuditRecord {
+                    rowid: 2,
+                    change: second
+                },
+            ]
+        );
+        assert_eq!(
+            second_page,
+            vec![
+                BalanceAuditRecord {
+                    rowid: 3,
+                    change: third
+                },
+                BalanceAuditRecord {
+                    rowid: 4,
+                    change: fourth
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn net_payments_received_since_block_leaves_out_older_and_taken_back_payments() {
+        let home_dir = ensure_node_home_directory_exists(
+            "audit_log_dao",
+            "net_payments_received_since_block_leaves_out_older_and_taken_back_payments",
+        );
+        let mut conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let receivable_change =
+            |wallet: &str, change_wei: i128, cause: BalanceChangeCause| BalanceChange {
+                account: AuditedAccount::Receivable,
+                cause,
+                ..make_change(wallet, 1_000, change_wei)
+            };
+        [
+            receivable_change(
+                "booga",
+                -500,
+                BalanceChangeCause::PaymentReceived { block_number: 100 },
+            ),
+            receivable_change(
+                "agoob",
+                -300,
+                BalanceChangeCause::PaymentReceived { block_number: 105 },
+            ),
+            receivable_change(
+                "booga",
+                -200,
+                BalanceChangeCause::PaymentReceived { block_number: 110 },
+            ),
+            receivable_change(
+                "booga",
+                200,
+                BalanceChangeCause::PaymentOrphaned { block_number: 110 },
+            ),
+            receivable_change("agoob", 700, BalanceChangeCause::ServicesProvided),
+            BalanceChange {
+                cause: BalanceChangeCause::PaymentSent {
+                    transaction_hash: H256::from_low_u64_be(0xabcd),
+                },
+                ..make_change("agoob", 1_000, -100)
+            },
+        ]
+        .iter()
+        .for_each(|change| record_balance_change(Either::Left(conn.as_ref()), change).unwrap());
+        let txn = conn.transaction().unwrap();
+
+        let since_105 = net_payments_received_since_block(&txn, 105).unwrap();
+        let since_100 = net_payments_received_since_block(&txn, 100).unwrap();
+        let since_111 = net_payments_received_since_block(&txn, 111).unwrap();
+
+        assert_eq!(since_105, vec![(make_wallet("agoob"), 105, -300)]);
+        assert_eq!(
+            since_100,
+            vec![
+                (make_wallet("booga"), 100, -500),
+                (make_wallet("agoob"), 105, -300)
+            ]
+        );
+        assert_eq!(since_111, vec![]);
+    }
+
+    #[test]
+    fn payment_orphaned_cause_survives_a_round_trip_through_the_database_form() {
+        let cause = BalanceChangeCause::PaymentOrphaned { block_number: 4321 };
+        let (db_cause, reference_opt) = cause.as_db_pair();
+
+        let result = BalanceChangeCause::from_db_pair(db_cause, reference_opt.clone());
+
+        assert_eq!(db_cause, "payment orphaned");
+        assert_eq!(reference_opt, Some("4321".to_string()));
+        assert_eq!(result, cause);
+    }
+
+    #[test]
+    fn record_converts_to_ui_record() {
+        let record = BalanceAuditRecord {
+            rowid: 7,
+            change: BalanceChange {
+                cause: BalanceChangeCause::PaymentReceived { block_number: 4321 },
+                account: AuditedAccount::Receivable,
+                ..make_change("booga", 1_000, -5_000)
+            },
+        };
+
+        let result = UiBalanceAuditRecord::from(record);
+
+        assert_eq!(
+            result,
+            UiBalanceAuditRecord {
+                rowid: 7,
+                timestamp: 1_000,
+                wallet: make_wallet("booga").to_string(),
+                account: "receivable".to_string(),
+                change_wei: -5_000,
+                cause: "payment received".to_string(),
+                reference_opt: Some("4321".to_string()),
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Database corrupt: unknown balance change cause 'booga' with reference None"
+    )]
+    fn unknown_cause_is_reported_as_corruption() {
+        let _ = BalanceChangeCause::from_db_pair("booga", None);
+    }
+}
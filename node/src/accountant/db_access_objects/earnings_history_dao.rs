@@ -0,0 +1,278 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::accountant::checked_conversion;
+use crate::accountant::db_access_objects::utils::{
+    from_time_t, to_time_t, DaoFactoryReal, VigilantRusqliteFlatten,
+};
+use crate::accountant::db_big_integer::big_int_divider::BigIntDivider;
+use crate::database::rusqlite_wrappers::ConnectionWrapper;
+use masq_lib::messages::UiDailyEarnings;
+use rusqlite::{named_params, OptionalExtension, Row};
+use std::fmt::Debug;
+use std::time::SystemTime;
+
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+// Totals for one UTC day. The same structure serves as the increment added to a day by a single
+// service report: it's just a day's worth of totals with only one service in it.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DailyEarnings {
+    pub bytes_routed: u64,
+    pub bytes_exited: u64,
+    pub earned_wei: u128,
+    pub spent_wei: u128,
+}
+
+impl DailyEarnings {
+    fn plus(&self, other: &DailyEarnings) -> DailyEarnings {
+        DailyEarnings {
+            bytes_routed: self.bytes_routed.saturating_add(other.bytes_routed),
+            bytes_exited: self.bytes_exited.saturating_add(other.bytes_exited),
+            earned_wei: self.earned_wei.saturating_add(other.earned_wei),
+            spent_wei: self.spent_wei.saturating_add(other.spent_wei),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyEarningsRecord {
+    pub day_start: SystemTime,
+    pub earnings: DailyEarnings,
+}
+
+impl From<DailyEarningsRecord> for UiDailyEarnings {
+    fn from(record: DailyEarningsRecord) -> Self {
+        UiDailyEarnings {
+            day_start: to_time_t(record.day_start),
+            bytes_routed: record.earnings.bytes_routed,
+            bytes_exited: record.earnings.bytes_exited,
+            earned_wei: record.earnings.earned_wei,
+            spent_wei: record.earnings.spent_wei,
+        }
+    }
+}
+
+pub fn day_start_of(time_t: i64) -> i64 {
+    time_t - time_t.rem_euclid(SECONDS_PER_DAY)
+}
+
+pub trait EarningsHistoryDao: Debug + Send {
+    fn add(&self, timestamp: SystemTime, earnings: &DailyEarnings);
+
+    // Days with no activity have no record, and so are absent from the result
+    fn history(&self, start: SystemTime, end: SystemTime) -> Vec<DailyEarningsRecord>;
+}
+
+pub trait EarningsHistoryDaoFactory {
+    fn make(&self) -> Box<dyn EarningsHistoryDao>;
+}
+
+impl EarningsHistoryDaoFactory for DaoFactoryReal {
+    fn make(&self) -> Box<dyn EarningsHistoryDao> {
+        Box::new(EarningsHistoryDaoReal::new(self.make_connection()))
+    }
+}
+
+#[derive(Debug)]
+pub struct EarningsHistoryDaoReal {
+    conn: Box<dyn ConnectionWrapper>,
+}
+
+impl EarningsHistoryDao for EarningsHistoryDaoReal {
+    fn add(&self, timestamp: SystemTime, earnings: &DailyEarnings) {
+        // The Accountant is the only writer, and it writes from a single thread, so nothing can
+        // sneak in between the read and the write
+        let day_start = day_start_of(to_time_t(timestamp));
+        let total = self.day(day_start).unwrap_or_default().plus(earnings);
+        let (earned_high_b, earned_low_b) =
+            BigIntDivider::deconstruct(Self::as_i128(total.earned_wei));
+        let (spent_high_b, spent_low_b) =
+            BigIntDivider::deconstruct(Self::as_i128(total.spent_wei));
+        let mut stmt = self
+            .conn
+            .prepare(
+                "insert or replace into earnings_history (day_start, bytes_routed, bytes_exited, \
+                earned_high_b, earned_low_b, spent_high_b, spent_low_b) values (:day_start, \
+                :bytes_routed, :bytes_exited, :earned_high_b, :earned_low_b, :spent_high_b, \
+                :spent_low_b)",
+            )
+            .expect("Failed to prepare a statement");
+        stmt.execute(named_params! {
+            ":day_start": day_start,
+            ":bytes_routed": checked_conversion::<u64, i64>(total.bytes_routed),
+            ":bytes_exited": checked_conversion::<u64, i64>(total.bytes_exited),
+            ":earned_high_b": earned_high_b,
+            ":earned_low_b": earned_low_b,
+            ":spent_high_b": spent_high_b,
+            ":spent_low_b": spent_low_b,
+        })
+        .unwrap_or_else(|e| panic!("Couldn't record daily earnings: {:?}", e));
+    }
+
+    fn history(&self, start: SystemTime, end: SystemTime) -> Vec<DailyEarningsRecord> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "select day_start, bytes_routed, bytes_exited, earned_high_b, earned_low_b, \
+                spent_high_b, spent_low_b from earnings_history where day_start >= :start and \
+                day_start < :end order by day_start asc",
+            )
+            .expect("Failed to prepare a statement");
+        stmt.query_map(
+            named_params! {
+                ":start": day_start_of(to_time_t(start)),
+                ":end": to_time_t(end),
+            },
+            Self::row_to_record,
+        )
+        .expect("Couldn't retrieve earnings history: database corrupt")
+        .vigilant_flatten()
+        .collect()
+    }
+}
+
+impl EarningsHistoryDaoReal {
+    pub fn new(conn: Box<dyn ConnectionWrapper>) -> Self {
+        Self { conn }
+    }
+
+    fn day(&self, day_start: i64) -> Option<DailyEarnings> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "select day_start, bytes_routed, bytes_exited, earned_high_b, earned_low_b, \
+                spent_high_b, spent_low_b from earnings_history where day_start = :day_start",
+            )
+            .expect("Failed to prepare a statement");
+        stmt.query_row(named_params! {":day_start": day_start}, Self::row_to_record)
+            .optional()
+            .expect("Couldn't retrieve daily earnings: database corrupt")
+            .map(|record| record.earnings)
+    }
+
+    fn row_to_record(row: &Row) -> rusqlite::Result<DailyEarningsRecord> {
+        let day_start: i64 = row.get(0)?;
+        let bytes_routed: i64 = row.get(1)?;
+        let bytes_exited: i64 = row.get(2)?;
+        let earned_high_b: i64 = row.get(3)?;
+        let earned_low_b: i64 = row.get(4)?;
+        let spent_high_b: i64 = row.get(5)?;
+        let spent_low_b: i64 = row.get(6)?;
+        Ok(DailyEarningsRecord {
+            day_start: from_time_t(day_start),
+            earnings: DailyEarnings {
+                bytes_routed: checked_conversion::<i64, u64>(bytes_routed),
+                bytes_exited: checked_conversion::<i64, u64>(bytes_exited),
+                earned_wei: checked_conversion::<i128, u128>(BigIntDivider::reconstitute(
+                    earned_high_b,
+                    earned_low_b,
+                )),
+                spent_wei: checked_conversion::<i128, u128>(BigIntDivider::reconstitute(
+                    spent_high_b,
+                    spent_low_b,
+                )),
+            },
+        })
+    }
+
+    fn as_i128(wei: u128) -> i128 {
+        i128::try_from(wei).unwrap_or(i128::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal,
+    };
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+
+    fn make_earnings(bytes_routed: u64, bytes_exited: u64, earned_wei: u128) -> DailyEarnings {
+        DailyEarnings {
+            bytes_routed,
+            bytes_exited,
+            earned_wei,
+            spent_wei: 0,
+        }
+    }
+
+    #[test]
+    fn day_start_of_truncates_to_utc_midnight() {
+        assert_eq!(SECONDS_PER_DAY, 86_400);
+        assert_eq!(day_start_of(0), 0);
+        assert_eq!(day_start_of(86_399), 0);
+        assert_eq!(day_start_of(86_400), 86_400);
+        assert_eq!(day_start_of(3 * 86_400 + 12_345), 3 * 86_400);
+    }
+
+    #[test]
+    fn earnings_accumulate_by_day_and_can_be_read_back_by_range() {
+        let home_dir = ensure_node_home_directory_exists(
+            "earnings_history_dao",
+            "earnings_accumulate_by_day_and_can_be_read_back_by_range",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = EarningsHistoryDaoReal::new(conn);
+        let big_wei = 50_000_000_000_000_000_000_000_u128;
+        subject.add(from_time_t(86_400 + 100), &make_earnings(1_000, 0, big_wei));
+        subject.add(from_time_t(2 * 86_400 - 1), &make_earnings(0, 500, big_wei));
+        subject.add(
+            from_time_t(2 * 86_400 + 5),
+            &DailyEarnings {
+                spent_wei: 7,
+                ..DailyEarnings::default()
+            },
+        );
+        subject.add(from_time_t(5 * 86_400), &make_earnings(1, 1, 1));
+
+        let all = subject.history(from_time_t(0), from_time_t(10 * 86_400));
+        let middle = subject.history(from_time_t(86_400 + 3_600), from_time_t(5 * 86_400));
+
+        let first_day = DailyEarningsRecord {
+            day_start: from_time_t(86_400),
+            earnings: make_earnings(1_000, 500, 2 * big_wei),
+        };
+        let second_day = DailyEarningsRecord {
+            day_start: from_time_t(2 * 86_400),
+            earnings: DailyEarnings {
+                spent_wei: 7,
+                ..DailyEarnings::default()
+            },
+        };
+        let third_day = DailyEarningsRecord {
+            day_start: from_time_t(5 * 86_400),
+            earnings: make_earnings(1, 1, 1),
+        };
+        assert_eq!(all, vec![first_day.clone(), second_day.clone(), third_day]);
+        assert_eq!(middle, vec![first_day, second_day]);
+    }
+
+    #[test]
+    fn record_converts_to_ui_daily_earnings() {
+        let record = DailyEarningsRecord {
+            day_start: from_time_t(86_400),
+            earnings: DailyEarnings {
+                bytes_routed: 1,
+                bytes_exited: 2,
+                earned_wei: 3,
+                spent_wei: 4,
+            },
+        };
+
+        let result = UiDailyEarnings::from(record);
+
+        assert_eq!(
+            result,
+            UiDailyEarnings {
+                day_start: 86_400,
+                bytes_routed: 1,
+                bytes_exited: 2,
+                earned_wei: 3,
+                spent_wei: 4,
+            }
+        )
+    }
+}
@@ -0,0 +1,227 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::accountant::checked_conversion;
+use crate::accountant::db_access_objects::utils::{DaoFactoryReal, VigilantRusqliteFlatten};
+use crate::database::rusqlite_wrappers::ConnectionWrapper;
+use masq_lib::messages::{UiDatabaseStats, UiTableRowCount};
+use std::fmt::Debug;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatabaseStats {
+    pub page_size: u64,
+    pub page_count: u64,
+    pub free_pages: u64,
+    pub table_row_counts: Vec<(String, u64)>,
+}
+
+impl DatabaseStats {
+    pub fn file_size(&self) -> u64 {
+        self.page_size * self.page_count
+    }
+}
+
+impl From<DatabaseStats> for UiDatabaseStats {
+    fn from(stats: DatabaseStats) -> Self {
+        UiDatabaseStats {
+            file_size: stats.file_size(),
+            page_size: stats.page_size,
+            free_pages: stats.free_pages,
+            tables: stats
+                .table_row_counts
+                .into_iter()
+                .map(|(name, row_count)| UiTableRowCount { name, row_count })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MaintenanceDaoError {
+    RusqliteError(String),
+}
+
+pub trait MaintenanceDao: Debug + Send {
+    fn stats(&self) -> Result<DatabaseStats, MaintenanceDaoError>;
+
+    // Rebuilds the database file without its free pages and refreshes the query planner's statistics
+    fn vacuum_and_analyze(&self) -> Result<(), MaintenanceDaoError>;
+}
+
+pub trait MaintenanceDaoFactory {
+    fn make(&self) -> Box<dyn MaintenanceDao>;
+}
+
+impl MaintenanceDaoFactory for DaoFactoryReal {
+    fn make(&self) -> Box<dyn MaintenanceDao> {
+        Box::new(MaintenanceDaoReal::new(self.make_connection()))
+    }
+}
+
+#[derive(Debug)]
+pub struct MaintenanceDaoReal {
+    conn: Box<dyn ConnectionWrapper>,
+}
+
+impl MaintenanceDao for MaintenanceDaoReal {
+    fn stats(&self) -> Result<DatabaseStats, MaintenanceDaoError> {
+        let table_names = self.table_names()?;
+        let table_row_counts = table_names
+            .into_iter()
+            .map(|name| {
+                let row_count =
+                    self.single_number(&format!("select count(*) from \"{}\"", name))?;
+                Ok((name, row_count))
+            })
+            .collect::<Result<Vec<(String, u64)>, MaintenanceDaoError>>()?;
+        Ok(DatabaseStats {
+            page_size: self.single_number("pragma page_size")?,
+            page_count: self.single_number("pragma page_count")?,
+            free_pages: self.single_number("pragma freelist_count")?,
+            table_row_counts,
+        })
+    }
+
+    fn vacuum_and_analyze(&self) -> Result<(), MaintenanceDaoError> {
+        ["vacuum", "analyze"].into_iter().try_for_each(|sql| {
+            self.conn
+                .prepare(sql)
+                .and_then(|mut stmt| stmt.execute([]))
+                .map(|_| ())
+                .map_err(|e| MaintenanceDaoError::RusqliteError(format!("{}: {:?}", sql, e)))
+        })
+    }
+}
+
+impl MaintenanceDaoReal {
+    pub fn new(conn: Box<dyn ConnectionWrapper>) -> Self {
+        Self { conn }
+    }
+
+    fn table_names(&self) -> Result<Vec<String>, MaintenanceDaoError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "select name from sqlite_master where type = 'table' and name not like 'sqlite_%' \
+                order by name",
+            )
+            .expect("Failed to prepare a statement");
+        stmt.query_map([], |row| row.get::<usize, String>(0))
+            .map_err(|e| MaintenanceDaoError::RusqliteError(format!("{:?}", e)))
+            .map(|rows| rows.vigilant_flatten().collect())
+    }
+
+    fn single_number(&self, sql: &str) -> Result<u64, MaintenanceDaoError> {
+        self.conn
+            .prepare(sql)
+            .and_then(|mut stmt| stmt.query_row([], |row| row.get::<usize, i64>(0)))
+            .map(checked_conversion::<i64, u64>)
+            .map_err(|e| MaintenanceDaoError::RusqliteError(format!("{}: {:?}", sql, e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal,
+    };
+    use crate::database::rusqlite_wrappers::ConnectionWrapperReal;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use rusqlite::Connection;
+
+    #[test]
+    fn stats_reports_pages_and_row_counts_of_every_table() {
+        let home_dir = ensure_node_home_directory_exists(
+            "maintenance_dao",
+            "stats_reports_pages_and_row_counts_of_every_table",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = MaintenanceDaoReal::new(conn);
+
+        let result = subject.stats().unwrap();
+
+        assert_eq!(result.page_size > 0, true);
+        assert_eq!(result.page_count > 0, true);
+        assert_eq!(result.file_size(), result.page_size * result.page_count);
+        let table_names = result
+            .table_row_counts
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<&str>>();
+        let mut sorted_table_names = table_names.clone();
+        sorted_table_names.sort();
+        assert_eq!(table_names, sorted_table_names);
+        assert_eq!(table_names.contains(&"config"), true);
+        assert_eq!(table_names.contains(&"payable"), true);
+        assert_eq!(table_names.contains(&"receivable"), true);
+        let config_row_count = result
+            .table_row_counts
+            .iter()
+            .find(|(name, _)| name == "config")
+            .map(|(_, row_count)| *row_count)
+            .unwrap();
+        assert_eq!(config_row_count > 0, true);
+    }
+
+    #[test]
+    fn vacuum_and_analyze_reclaims_free_pages() {
+        let home_dir = ensure_node_home_directory_exists(
+            "maintenance_dao",
+            "vacuum_and_analyze_reclaims_free_pages",
+        );
+        let conn = Connection::open(home_dir.join("vacuum.db")).unwrap();
+        conn.execute("create table churn (data blob)", []).unwrap();
+        (0..200).for_each(|_| {
+            conn.execute("insert into churn (data) values (zeroblob(4096))", [])
+                .unwrap();
+        });
+        conn.execute("delete from churn", []).unwrap();
+        let subject = MaintenanceDaoReal::new(Box::new(ConnectionWrapperReal::new(conn)));
+        let before = subject.stats().unwrap();
+
+        let result = subject.vacuum_and_analyze();
+
+        assert_eq!(result, Ok(()));
+        let after = subject.stats().unwrap();
+        assert_eq!(before.free_pages > 0, true);
+        assert_eq!(after.free_pages, 0);
+        assert_eq!(after.file_size() < before.file_size(), true);
+        assert_eq!(
+            after.table_row_counts.contains(&("churn".to_string(), 0)),
+            true
+        );
+    }
+
+    #[test]
+    fn stats_converts_to_ui_database_stats() {
+        let stats = DatabaseStats {
+            page_size: 4096,
+            page_count: 10,
+            free_pages: 3,
+            table_row_counts: vec![("config".to_string(), 40), ("payable".to_string(), 2)],
+        };
+
+        let result = UiDatabaseStats::from(stats);
+
+        assert_eq!(
+            result,
+            UiDatabaseStats {
+                file_size: 40_960,
+                page_size: 4096,
+                free_pages: 3,
+                tables: vec![
+                    UiTableRowCount {
+                        name: "config".to_string(),
+                        row_count: 40
+                    },
+                    UiTableRowCount {
+                        name: "payable".to_string(),
+                        row_count: 2
+                    },
+                ],
+            }
+        )
+    }
+}
@@ -1,6 +1,10 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+pub mod address_book_dao;
+pub mod audit_log_dao;
 pub mod banned_dao;
+pub mod earnings_history_dao;
+pub mod maintenance_dao;
 pub mod payable_dao;
 pub mod pending_payable_dao;
 pub mod receivable_dao;
@@ -5,6 +5,9 @@ use crate::accountant::db_big_integer::big_int_db_processor::KeyVariants::{
 };
 use crate::accountant::db_big_integer::big_int_db_processor::{BigIntDbProcessor, BigIntDbProcessorReal, BigIntSqlConfig, DisplayableRusqliteParamPair, ParamByUse, SQLParamsBuilder, TableNameDAO, WeiChange, WeiChangeDirection};
 use crate::accountant::db_big_integer::big_int_divider::BigIntDivider;
+use crate::accountant::db_access_objects::audit_log_dao::{
+    record_balance_change, AuditedAccount, BalanceChange, BalanceChangeCause,
+};
 use crate::accountant::db_access_objects::utils;
 use crate::accountant::db_access_objects::utils::{
     sum_i128_values_from_table, to_time_t, AssemblerFeeder, CustomQuery, DaoFactoryReal,
@@ -17,12 +20,9 @@ use crate::accountant::{checked_conversion, sign_conversion, PendingPayableId};
 use crate::blockchain::blockchain_bridge::PendingPayableFingerprint;
 use crate::database::rusqlite_wrappers::ConnectionWrapper;
 use crate::sub_lib::wallet::Wallet;
-#[cfg(test)]
 use ethereum_types::{BigEndianHash, U256};
 use masq_lib::utils::ExpectValue;
-#[cfg(test)]
-use rusqlite::OptionalExtension;
-use rusqlite::{Error, Row};
+use rusqlite::{named_params, Error, OptionalExtension, Row};
 use std::fmt::Debug;
 use std::str::FromStr;
 use std::time::SystemTime;
@@ -56,6 +56,10 @@ pub trait PayableDao: Debug + Send {
         wallets_and_rowids: &[(&Wallet, u64)],
     ) -> Result<(), PayableDaoError>;
 
+    // Releases the accounts whose payments went out under these fingerprints but never made it
+    // onto the blockchain; their balances stay as they were and so get paid again
+    fn unmark_pending_payables_rowids(&self, rowids: &[u64]) -> Result<(), PayableDaoError>;
+
     fn transactions_confirmed(
         &self,
         confirmed_payables: &[PendingPayableFingerprint],
@@ -63,11 +67,12 @@ pub trait PayableDao: Debug + Send {
 
     fn non_pending_payables(&self) -> Vec<PayableAccount>;
 
+    fn apply_replicated_change(&self, change: &BalanceChange) -> Result<(), PayableDaoError>;
+
     fn custom_query(&self, custom_query: CustomQuery<u64>) -> Option<Vec<PayableAccount>>;
 
     fn total(&self) -> u128;
 
-    #[cfg(test)]
     fn account_status(&self, wallet: &Wallet) -> Option<PayableAccount>;
 }
 
@@ -118,7 +123,16 @@ impl PayableDao for PayableDaoReal {
             BigIntSqlConfig::new(main_sql, update_clause_with_compensated_overflow, params),
         )?;
 
-        Ok(())
+        Self::audit(
+            self.conn.as_ref(),
+            BalanceChange {
+                timestamp,
+                wallet: wallet.clone(),
+                account: AuditedAccount::Payable,
+                change_wei: checked_conversion::<u128, i128>(amount),
+                cause: BalanceChangeCause::ServicesConsumed,
+            },
+        )
     }
 
     fn mark_pending_payables_rowids(
@@ -144,6 +158,22 @@ impl PayableDao for PayableDaoReal {
         execute_command(&*self.conn, wallets_and_rowids, &sql)
     }
 
+    fn unmark_pending_payables_rowids(&self, rowids: &[u64]) -> Result<(), PayableDaoError> {
+        let sql = format!(
+            "update payable set pending_payable_rowid = null where pending_payable_rowid in ({})",
+            rowids
+                .iter()
+                .map(|rowid| rowid.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+        self.conn
+            .prepare(&sql)
+            .and_then(|mut stmt| stmt.execute([]))
+            .map(|_| ())
+            .map_err(|e| PayableDaoError::RusqliteError(e.to_string()))
+    }
+
     fn transactions_confirmed(
         &self,
         confirmed_payables: &[PendingPayableFingerprint],
@@ -158,6 +188,7 @@ impl PayableDao for PayableDaoReal {
                     pending_payable_rowid = null where pending_payable_rowid = :rowid";
 
             let i64_rowid = checked_conversion::<u64, i64>(pending_payable_fingerprint.rowid);
+            let wallet_opt = self.wallet_by_pending_payable_rowid(i64_rowid)?;
             let last_paid = to_time_t(pending_payable_fingerprint.timestamp);
             let params = SQLParamsBuilder::default()
                 .key( PendingPayableRowid(&i64_rowid))
@@ -170,7 +201,16 @@ impl PayableDao for PayableDaoReal {
                 update_clause_with_compensated_overflow,
                 params))?;
 
-            Ok(())
+            match wallet_opt {
+                Some(wallet) => Self::audit(self.conn.as_ref(), BalanceChange {
+                    timestamp: pending_payable_fingerprint.timestamp,
+                    wallet,
+                    account: AuditedAccount::Payable,
+                    change_wei: -checked_conversion::<u128, i128>(pending_payable_fingerprint.amount),
+                    cause: BalanceChangeCause::PaymentSent { transaction_hash: pending_payable_fingerprint.hash },
+                }),
+                None => Ok(()),
+            }
         })
     }
 
@@ -208,18 +248,64 @@ impl PayableDao for PayableDaoReal {
         .collect()
     }
 
+    // Conflict rules for a standby replaying the primary's balance changes: a missing account is
+    // created, a payment never takes the balance below zero (the standby may have missed the debt
+    // it pays off) and only payments move the last-paid timestamp, never backwards. The audit log
+    // records the change as actually applied.
+    fn apply_replicated_change(&self, change: &BalanceChange) -> Result<(), PayableDaoError> {
+        let current_balance = self
+            .balance_opt(&change.wallet)?
+            .map(checked_conversion::<u128, i128>)
+            .unwrap_or(0);
+        let new_balance = current_balance.saturating_add(change.change_wei).max(0);
+        let (balance_high_b, balance_low_b) = BigIntDivider::deconstruct(new_balance);
+        let timestamp = to_time_t(change.timestamp);
+        let paid_timestamp_opt = match change.cause {
+            BalanceChangeCause::PaymentSent { .. } => Some(timestamp),
+            _ => None,
+        };
+        self.conn
+            .prepare(
+                "insert into payable (wallet_address, balance_high_b, balance_low_b, \
+                last_paid_timestamp, pending_payable_rowid) values (:wallet, :balance_high_b, \
+                :balance_low_b, :last_paid_timestamp, null) on conflict (wallet_address) do update \
+                set balance_high_b = :balance_high_b, balance_low_b = :balance_low_b, \
+                last_paid_timestamp = max(last_paid_timestamp, coalesce(:paid_timestamp, \
+                last_paid_timestamp))",
+            )
+            .and_then(|mut stmt| {
+                stmt.execute(named_params! {
+                    ":wallet": &change.wallet,
+                    ":balance_high_b": balance_high_b,
+                    ":balance_low_b": balance_low_b,
+                    ":last_paid_timestamp": timestamp,
+                    ":paid_timestamp": paid_timestamp_opt,
+                })
+            })
+            .map_err(|e| PayableDaoError::RusqliteError(e.to_string()))?;
+
+        Self::audit(
+            self.conn.as_ref(),
+            BalanceChange {
+                change_wei: new_balance - current_balance,
+                ..change.clone()
+            },
+        )
+    }
+
     fn custom_query(&self, custom_query: CustomQuery<u64>) -> Option<Vec<PayableAccount>> {
         let variant_top = TopStmConfig{
-            limit_clause: "limit :limit_count",
+            limit_clause: "limit :limit_count offset :offset",
             gwei_min_resolution_clause: "where (balance_high_b > 0) or ((balance_high_b = 0) and (balance_low_b >= 1000000000))",
-            age_ordering_clause: "last_paid_timestamp asc",
+            age_column: "last_paid_timestamp",
         };
         let variant_range = RangeStmConfig {
             where_clause: "where ((last_paid_timestamp <= :max_timestamp) and (last_paid_timestamp >= :min_timestamp)) \
             and ((balance_high_b > :min_balance_high_b) or ((balance_high_b = :min_balance_high_b) and (balance_low_b >= :min_balance_low_b))) \
             and ((balance_high_b < :max_balance_high_b) or ((balance_high_b = :max_balance_high_b) and (balance_low_b <= :max_balance_low_b)))",
             gwei_min_resolution_clause: "and ((balance_high_b > 0) or ((balance_high_b = 0) and (balance_low_b >= 1000000000)))",
-            secondary_order_param: "last_paid_timestamp asc"
+            secondary_order_param: "last_paid_timestamp asc",
+            limit_clause: "limit :limit_count offset :offset",
         };
 
         custom_query.query::<_, i64, _, _>(
@@ -258,7 +344,6 @@ impl PayableDao for PayableDaoReal {
         })
     }
 
-    #[cfg(test)]
     fn account_status(&self, wallet: &Wallet) -> Option<PayableAccount> {
         let stm = "\
             select balance_high_b, balance_low_b, last_paid_timestamp, pending_payable_rowid \
@@ -311,6 +396,34 @@ impl PayableDaoReal {
         }
     }
 
+    fn wallet_by_pending_payable_rowid(
+        &self,
+        rowid: i64,
+    ) -> Result<Option<Wallet>, PayableDaoError> {
+        self.conn
+            .prepare("select wallet_address from payable where pending_payable_rowid = ?")
+            .and_then(|mut stmt| stmt.query_row([rowid], |row| row.get(0)).optional())
+            .map_err(|e| PayableDaoError::RusqliteError(e.to_string()))
+    }
+
+    fn balance_opt(&self, wallet: &Wallet) -> Result<Option<u128>, PayableDaoError> {
+        self.conn
+            .prepare("select balance_high_b, balance_low_b from payable where wallet_address = ?")
+            .and_then(|mut stmt| {
+                stmt.query_row([wallet], |row| {
+                    Ok(BigIntDivider::reconstitute(row.get(0)?, row.get(1)?))
+                })
+                .optional()
+            })
+            .map(|balance_opt| balance_opt.map(checked_conversion::<i128, u128>))
+            .map_err(|e| PayableDaoError::RusqliteError(e.to_string()))
+    }
+
+    fn audit(conn: &dyn ConnectionWrapper, change: BalanceChange) -> Result<(), PayableDaoError> {
+        record_balance_change(Either::Left(conn), &change)
+            .map_err(|e| PayableDaoError::RusqliteError(e.to_string()))
+    }
+
     fn create_payable_account(row: &Row) -> rusqlite::Result<PayableAccount> {
         let wallet_result: Result<Wallet, Error> = row.get(0);
         let balance_high_bytes_result = row.get(1);
@@ -541,6 +654,9 @@ mod mark_pending_payable_associated_functions {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::accountant::db_access_objects::audit_log_dao::{
+        AuditLogDao, AuditLogDaoReal, BalanceAuditRecord,
+    };
     use crate::accountant::db_access_objects::utils::{from_time_t, now_time_t, to_time_t};
     use crate::accountant::gwei_to_wei;
     use crate::accountant::db_access_objects::payable_dao::mark_pending_payable_associated_functions::explanatory_extension;
@@ -580,6 +696,120 @@ mod tests {
         assert_eq!(to_time_t(status.last_paid_timestamp), to_time_t(now));
     }
 
+    #[test]
+    fn balance_changes_are_recorded_in_audit_log() {
+        let home_dir = ensure_node_home_directory_exists(
+            "payable_dao",
+            "balance_changes_are_recorded_in_audit_log",
+        );
+        let wallet = make_wallet("booga");
+        let subject = PayableDaoReal::new(
+            DbInitializerReal::default()
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+        let audit_log_dao = AuditLogDaoReal::new(
+            DbInitializerReal::default()
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+        let fingerprint = PendingPayableFingerprint {
+            rowid: 33,
+            amount: 1000,
+            ..make_pending_payable_fingerprint()
+        };
+        subject
+            .more_money_payable(from_time_t(111_111_111), &wallet, 1234)
+            .unwrap();
+        subject
+            .mark_pending_payables_rowids(&[(&wallet, fingerprint.rowid)])
+            .unwrap();
+
+        subject
+            .transactions_confirmed(&[fingerprint.clone()])
+            .unwrap();
+
+        assert_eq!(
+            audit_log_dao.records(0, 10),
+            vec![
+                BalanceAuditRecord {
+                    rowid: 1,
+                    change: BalanceChange {
+                        timestamp: from_time_t(111_111_111),
+                        wallet: wallet.clone(),
+                        account: AuditedAccount::Payable,
+                        change_wei: 1234,
+                        cause: BalanceChangeCause::ServicesConsumed,
+                    }
+                },
+                BalanceAuditRecord {
+                    rowid: 2,
+                    change: BalanceChange {
+                        timestamp: fingerprint.timestamp,
+                        wallet,
+                        account: AuditedAccount::Payable,
+                        change_wei: -1000,
+                        cause: BalanceChangeCause::PaymentSent {
+                            transaction_hash: fingerprint.hash
+                        },
+                    }
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn apply_replicated_change_creates_accounts_and_never_goes_below_zero() {
+        let home_dir = ensure_node_home_directory_exists(
+            "payable_dao",
+            "apply_replicated_change_creates_accounts_and_never_goes_below_zero",
+        );
+        let wallet = make_wallet("booga");
+        let subject = PayableDaoReal::new(
+            DbInitializerReal::default()
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+        let audit_log_dao = AuditLogDaoReal::new(
+            DbInitializerReal::default()
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+        let debt = BalanceChange {
+            timestamp: from_time_t(111_111_111),
+            wallet: wallet.clone(),
+            account: AuditedAccount::Payable,
+            change_wei: 1234,
+            cause: BalanceChangeCause::ServicesConsumed,
+        };
+        let late_debt = BalanceChange {
+            timestamp: from_time_t(333_333_333),
+            ..debt.clone()
+        };
+        let overpayment = BalanceChange {
+            timestamp: from_time_t(222_222_222),
+            change_wei: -5000,
+            cause: BalanceChangeCause::PaymentSent {
+                transaction_hash: make_tx_hash(0x1234),
+            },
+            ..debt.clone()
+        };
+
+        subject.apply_replicated_change(&debt).unwrap();
+        subject.apply_replicated_change(&overpayment).unwrap();
+        subject.apply_replicated_change(&late_debt).unwrap();
+
+        let status = subject.account_status(&wallet).unwrap();
+        assert_eq!(status.balance_wei, 1234);
+        assert_eq!(status.last_paid_timestamp, from_time_t(222_222_222));
+        let applied_changes = audit_log_dao
+            .records(0, 10)
+            .into_iter()
+            .map(|record| record.change.change_wei)
+            .collect::<Vec<_>>();
+        assert_eq!(applied_changes, vec![1234, -1234, 1234]);
+    }
+
     #[test]
     fn more_money_payable_works_for_existing_address_without_overflow() {
         //asserting on correctness of the main sql clause
@@ -854,6 +1084,58 @@ mod tests {
         requested: preventive measures failed.\n".to_string())
     }
 
+    #[test]
+    fn unmark_pending_payables_rowids_releases_only_the_given_accounts() {
+        let home_dir = ensure_node_home_directory_exists(
+            "payable_dao",
+            "unmark_pending_payables_rowids_releases_only_the_given_accounts",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let wallet_1 = make_wallet("booga");
+        let wallet_2 = make_wallet("bagaboo");
+        let wallet_3 = make_wallet("foo");
+        insert_payable_record_fn(&*conn, &wallet_1.to_string(), 1111, 100_000, Some(5));
+        insert_payable_record_fn(&*conn, &wallet_2.to_string(), 2222, 200_000, Some(6));
+        insert_payable_record_fn(&*conn, &wallet_3.to_string(), 3333, 300_000, Some(7));
+        let subject = PayableDaoReal::new(conn);
+
+        let result = subject.unmark_pending_payables_rowids(&[5, 7]);
+
+        assert_eq!(result, Ok(()));
+        let account_1 = subject.account_status(&wallet_1).unwrap();
+        assert_eq!(account_1.pending_payable_opt, None);
+        assert_eq!(account_1.balance_wei, 1111);
+        let account_2 = subject.account_status(&wallet_2).unwrap();
+        assert_eq!(
+            account_2.pending_payable_opt.map(|pending| pending.rowid),
+            Some(6)
+        );
+        let account_3 = subject.account_status(&wallet_3).unwrap();
+        assert_eq!(account_3.pending_payable_opt, None);
+        assert_eq!(account_3.balance_wei, 3333);
+    }
+
+    #[test]
+    fn unmark_pending_payables_rowids_handles_general_sql_error() {
+        let home_dir = ensure_node_home_directory_exists(
+            "payable_dao",
+            "unmark_pending_payables_rowids_handles_general_sql_error",
+        );
+        let conn = payable_read_only_conn(&home_dir);
+        let subject = PayableDaoReal::new(Box::new(ConnectionWrapperReal::new(conn)));
+
+        let result = subject.unmark_pending_payables_rowids(&[5]);
+
+        assert_eq!(
+            result,
+            Err(PayableDaoError::RusqliteError(
+                "attempt to write a readonly database".to_string()
+            ))
+        )
+    }
+
     #[test]
     fn mark_pending_payables_rowids_handles_general_sql_error() {
         let home_dir = ensure_node_home_directory_exists(
@@ -1227,6 +1509,8 @@ mod tests {
         let result = subject.custom_query(CustomQuery::TopRecords {
             count: 6,
             ordered_by: Balance,
+            offset: 0,
+            ascending: false,
         });
 
         assert_eq!(result, None)
@@ -1315,6 +1599,8 @@ mod tests {
             .custom_query(CustomQuery::TopRecords {
                 count: 3,
                 ordered_by: Balance,
+                offset: 0,
+                ascending: false,
             })
             .unwrap();
 
@@ -1365,6 +1651,8 @@ mod tests {
             .custom_query(CustomQuery::TopRecords {
                 count: 3,
                 ordered_by: Age,
+                offset: 0,
+                ascending: false,
             })
             .unwrap();
 
@@ -1399,6 +1687,85 @@ mod tests {
         );
     }
 
+    #[test]
+    fn custom_query_in_top_records_mode_pages_through_ascending_records() {
+        //Ascending by balance, then youngest first: 0x11.., 0x44.., 0x55.., 0x22..
+        let now = now_time_t();
+        let main_test_setup = accounts_for_tests_of_top_records(now);
+        let subject = custom_query_test_body_for_payable(
+            "custom_query_in_top_records_mode_pages_through_ascending_records",
+            main_test_setup,
+        );
+
+        let result = subject
+            .custom_query(CustomQuery::TopRecords {
+                count: 2,
+                ordered_by: Balance,
+                offset: 1,
+                ascending: true,
+            })
+            .unwrap();
+
+        assert_eq!(
+            result,
+            vec![
+                PayableAccount {
+                    wallet: Wallet::new("0x4444444444444444444444444444444444444444"),
+                    balance_wei: 10_000_000_100,
+                    last_paid_timestamp: from_time_t(now - 86_300),
+                    pending_payable_opt: None
+                },
+                PayableAccount {
+                    wallet: Wallet::new("0x5555555555555555555555555555555555555555"),
+                    balance_wei: 10_000_000_100,
+                    last_paid_timestamp: from_time_t(now - 86_401),
+                    pending_payable_opt: Some(PendingPayableId::new(
+                        1,
+                        H256::from_str(
+                            "abc4546cce78230a2312e12f3acb78747340456fe5237896666100143abcd223"
+                        )
+                        .unwrap()
+                    ))
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_query_in_range_mode_pages_through_records() {
+        //Biggest first, then oldest first: 0x22.., 0x55.., 0x44.., 0x11..
+        let now = now_time_t();
+        let main_test_setup = accounts_for_tests_of_top_records(now);
+        let subject = custom_query_test_body_for_payable(
+            "custom_query_in_range_mode_pages_through_records",
+            main_test_setup,
+        );
+
+        let result = subject
+            .custom_query(CustomQuery::RangeQuery {
+                min_age_s: 0,
+                max_age_s: 200000,
+                min_amount_gwei: 0,
+                max_amount_gwei: 1_000_000,
+                timestamp: from_time_t(now),
+                offset: 1,
+                limit_opt: Some(2),
+            })
+            .unwrap();
+
+        let wallets = result
+            .into_iter()
+            .map(|account| account.wallet)
+            .collect::<Vec<Wallet>>();
+        assert_eq!(
+            wallets,
+            vec![
+                Wallet::new("0x5555555555555555555555555555555555555555"),
+                Wallet::new("0x4444444444444444444444444444444444444444"),
+            ]
+        );
+    }
+
     #[test]
     fn custom_query_handles_empty_table_in_range_mode() {
         let main_test_setup = |_conn: &dyn ConnectionWrapper, _insert: InsertPayableHelperFn| {};
@@ -1413,6 +1780,8 @@ mod tests {
             min_amount_gwei: 500000000,
             max_amount_gwei: 3500000000,
             timestamp: SystemTime::now(),
+            offset: 0,
+            limit_opt: None,
         });
 
         assert_eq!(result, None)
@@ -1483,6 +1852,8 @@ mod tests {
                 min_amount_gwei: 500_000_000,
                 max_amount_gwei: 35_000_000_000,
                 timestamp: from_time_t(now),
+                offset: 0,
+                limit_opt: None,
             })
             .unwrap();
 
@@ -1550,6 +1921,8 @@ mod tests {
                 min_amount_gwei: u64::MIN,
                 max_amount_gwei: 35,
                 timestamp: SystemTime::now(),
+                offset: 0,
+                limit_opt: None,
             })
             .unwrap();
 
@@ -1,6 +1,10 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use crate::accountant::checked_conversion;
+use crate::accountant::db_access_objects::audit_log_dao::{
+    net_payments_received_since_block, record_balance_change, AuditedAccount, BalanceChange,
+    BalanceChangeCause,
+};
 use crate::accountant::db_access_objects::receivable_dao::ReceivableDaoError::RusqliteError;
 use crate::accountant::db_access_objects::utils;
 use crate::accountant::db_access_objects::utils::{
@@ -58,10 +62,22 @@ pub trait ReceivableDao {
         amount: u128,
     ) -> Result<(), ReceivableDaoError>;
 
+    // Same as more_money_receivable, but the amount was extrapolated from one package in every
+    // sample_size, and the audit log says so
+    fn more_sampled_money_receivable(
+        &self,
+        now: SystemTime,
+        wallet: &Wallet,
+        amount: u128,
+        sample_size: u32,
+    ) -> Result<(), ReceivableDaoError>;
+
+    // With a rewind block, payments credited from that block on are taken back first
     fn more_money_received(
         &mut self,
         now: SystemTime,
         transactions: &[BlockchainTransaction],
+        rewind_to_block_opt: Option<u64>,
     ) -> TransactionSafeWrapper;
 
     fn new_delinquencies(
@@ -72,6 +88,8 @@ pub trait ReceivableDao {
 
     fn paid_delinquencies(&self, payment_thresholds: &PaymentThresholds) -> Vec<ReceivableAccount>;
 
+    fn apply_replicated_change(&self, change: &BalanceChange) -> Result<(), ReceivableDaoError>;
+
     fn custom_query(&self, custom_query: CustomQuery<i64>) -> Option<Vec<ReceivableAccount>>;
 
     fn total(&self) -> i128;
@@ -114,53 +132,59 @@ impl ReceivableDao for ReceivableDaoReal {
         wallet: &Wallet,
         amount: u128,
     ) -> Result<(), ReceivableDaoError> {
-        let main_sql = "insert into receivable (wallet_address, balance_high_b, balance_low_b, last_received_timestamp) values \
-        (:wallet, :balance_high_b, :balance_low_b, :last_received_timestamp) on conflict (wallet_address) do update set \
-        balance_high_b = balance_high_b + :balance_high_b, balance_low_b = balance_low_b + :balance_low_b";
-        let update_clause_with_compensated_overflow = "update receivable set balance_high_b = :balance_high_b, balance_low_b = :balance_low_b \
-        where wallet_address = :wallet";
-
-        let last_received_timestamp = to_time_t(timestamp);
-        let params = SQLParamsBuilder::default()
-            .key(WalletAddress(wallet))
-            .wei_change(WeiChange::new(
-                "balance",
-                amount,
-                WeiChangeDirection::Addition,
-            ))
-            .other_params(vec![ParamByUse::BeforeOverflowOnly(
-                DisplayableRusqliteParamPair::new(
-                    ":last_received_timestamp",
-                    &last_received_timestamp,
-                ),
-            )])
-            .build();
-
-        self.big_int_db_processor.execute(
-            Either::Left(self.conn.as_ref()),
-            BigIntSqlConfig::new(main_sql, update_clause_with_compensated_overflow, params),
-        )?;
+        self.add_receivable(
+            timestamp,
+            wallet,
+            amount,
+            BalanceChangeCause::ServicesProvided,
+        )
+    }
 
-        Ok(())
+    fn more_sampled_money_receivable(
+        &self,
+        timestamp: SystemTime,
+        wallet: &Wallet,
+        amount: u128,
+        sample_size: u32,
+    ) -> Result<(), ReceivableDaoError> {
+        self.add_receivable(
+            timestamp,
+            wallet,
+            amount,
+            BalanceChangeCause::ServicesProvidedSampled { sample_size },
+        )
     }
 
     fn more_money_received(
         &mut self,
         timestamp: SystemTime,
         received_payments: &[BlockchainTransaction],
+        rewind_to_block_opt: Option<u64>,
     ) -> TransactionSafeWrapper<'_> {
         let accounting_result = match self.conn.transaction() {
             Ok(txn) => {
                 let big_int_db_processor = &*self.big_int_db_processor;
                 let logger = &self.logger;
 
-                Self::process_received_payments_and_return_txn(
-                    big_int_db_processor,
-                    received_payments,
-                    timestamp,
-                    txn,
-                    logger,
-                )
+                match rewind_to_block_opt {
+                    Some(rewind_to_block) => Self::take_back_orphaned_payments(
+                        big_int_db_processor,
+                        rewind_to_block,
+                        timestamp,
+                        &txn,
+                        logger,
+                    ),
+                    None => Ok(()),
+                }
+                .and_then(|_| {
+                    Self::process_received_payments_and_return_txn(
+                        big_int_db_processor,
+                        received_payments,
+                        timestamp,
+                        txn,
+                        logger,
+                    )
+                })
             }
             // Even though done with the accountancy, we still haven't updated the start block.
             // We will proceed that as the last thing because of our concern about data continuity
@@ -252,11 +276,46 @@ impl ReceivableDao for ReceivableDaoReal {
         .collect()
     }
 
+    // Conflict rules for a standby replaying the primary's balance changes: a missing account is
+    // created and only payments move the last-received timestamp, never backwards. Unlike
+    // payables, receivables may legitimately end up negative, so the change is applied unaltered.
+    fn apply_replicated_change(&self, change: &BalanceChange) -> Result<(), ReceivableDaoError> {
+        let current_balance = self
+            .account_status(&change.wallet)
+            .map(|account| account.balance_wei)
+            .unwrap_or(0);
+        let (balance_high_b, balance_low_b) =
+            BigIntDivider::deconstruct(current_balance.saturating_add(change.change_wei));
+        let timestamp = to_time_t(change.timestamp);
+        let received_timestamp_opt = match change.cause {
+            BalanceChangeCause::PaymentReceived { .. } => Some(timestamp),
+            _ => None,
+        };
+        let mut stmt = self.conn.prepare(
+            "insert into receivable (wallet_address, balance_high_b, balance_low_b, \
+            last_received_timestamp) values (:wallet, :balance_high_b, :balance_low_b, \
+            :last_received_timestamp) on conflict (wallet_address) do update set \
+            balance_high_b = :balance_high_b, balance_low_b = :balance_low_b, \
+            last_received_timestamp = max(last_received_timestamp, \
+            coalesce(:received_timestamp, last_received_timestamp))",
+        )?;
+        stmt.execute(named_params! {
+            ":wallet": &change.wallet,
+            ":balance_high_b": balance_high_b,
+            ":balance_low_b": balance_low_b,
+            ":last_received_timestamp": timestamp,
+            ":received_timestamp": received_timestamp_opt,
+        })?;
+
+        record_balance_change(Either::Left(self.conn.as_ref()), change)
+            .map_err(ReceivableDaoError::from)
+    }
+
     fn custom_query(&self, custom_query: CustomQuery<i64>) -> Option<Vec<ReceivableAccount>> {
         let variant_top = TopStmConfig{
-            limit_clause: "limit :limit_count",
+            limit_clause: "limit :limit_count offset :offset",
             gwei_min_resolution_clause: "where (balance_high_b > 0) or ((balance_high_b = 0) and (balance_low_b >= 1000000000))",
-            age_ordering_clause: "last_received_timestamp asc",
+            age_column: "last_received_timestamp",
         };
         let variant_range = RangeStmConfig {
             where_clause: "where ((last_received_timestamp <= :max_timestamp) and (last_received_timestamp >= :min_timestamp)) \
@@ -264,7 +323,8 @@ impl ReceivableDao for ReceivableDaoReal {
             and ((balance_high_b < :max_balance_high_b) or ((balance_high_b = :max_balance_high_b) and (balance_low_b <= :max_balance_low_b)))",
             gwei_min_resolution_clause: "and (((balance_high_b > 0) or ((balance_high_b = 0) and (balance_low_b >= 1000000000))) \
             or ((balance_high_b < -1) or ((balance_high_b = -1) and (balance_low_b <= 9223372035854775807))))", //i64::MAX - 1*10^9
-            secondary_order_param: "last_received_timestamp asc"
+            secondary_order_param: "last_received_timestamp asc",
+            limit_clause: "limit :limit_count offset :offset",
         };
 
         custom_query.query::<_, i64, _, _>(
@@ -356,7 +416,19 @@ impl ReceivableDaoReal {
             );
 
             match result {
-                Ok(_) => Ok(()),
+                Ok(_) => record_balance_change(
+                    Either::Right(&txn),
+                    &BalanceChange {
+                        timestamp,
+                        wallet: received_payment.from.clone(),
+                        account: AuditedAccount::Receivable,
+                        change_wei: -checked_conversion::<u128, i128>(received_payment.wei_amount),
+                        cause: BalanceChangeCause::PaymentReceived {
+                            block_number: received_payment.block_number,
+                        },
+                    },
+                )
+                .map_err(ReceivableDaoError::from),
                 Err(BigIntDatabaseError::General(err_msg)) => {
                     Err(ReceivableDaoError::RusqliteError(err_msg))
                 }
@@ -370,6 +442,121 @@ impl ReceivableDaoReal {
         }
     }
 
+    fn add_receivable(
+        &self,
+        timestamp: SystemTime,
+        wallet: &Wallet,
+        amount: u128,
+        cause: BalanceChangeCause,
+    ) -> Result<(), ReceivableDaoError> {
+        let main_sql = "insert into receivable (wallet_address, balance_high_b, balance_low_b, last_received_timestamp) values \
+        (:wallet, :balance_high_b, :balance_low_b, :last_received_timestamp) on conflict (wallet_address) do update set \
+        balance_high_b = balance_high_b + :balance_high_b, balance_low_b = balance_low_b + :balance_low_b";
+        let update_clause_with_compensated_overflow = "update receivable set balance_high_b = :balance_high_b, balance_low_b = :balance_low_b \
+        where wallet_address = :wallet";
+
+        let last_received_timestamp = to_time_t(timestamp);
+        let params = SQLParamsBuilder::default()
+            .key(WalletAddress(wallet))
+            .wei_change(WeiChange::new(
+                "balance",
+                amount,
+                WeiChangeDirection::Addition,
+            ))
+            .other_params(vec![ParamByUse::BeforeOverflowOnly(
+                DisplayableRusqliteParamPair::new(
+                    ":last_received_timestamp",
+                    &last_received_timestamp,
+                ),
+            )])
+            .build();
+
+        self.big_int_db_processor.execute(
+            Either::Left(self.conn.as_ref()),
+            BigIntSqlConfig::new(main_sql, update_clause_with_compensated_overflow, params),
+        )?;
+
+        record_balance_change(
+            Either::Left(self.conn.as_ref()),
+            &BalanceChange {
+                timestamp,
+                wallet: wallet.clone(),
+                account: AuditedAccount::Receivable,
+                change_wei: checked_conversion::<u128, i128>(amount),
+                cause,
+            },
+        )
+        .map_err(ReceivableDaoError::from)
+    }
+
+    fn take_back_orphaned_payments(
+        big_int_db_processor: &dyn BigIntDbProcessor<ReceivableDaoReal>,
+        rewind_to_block: u64,
+        timestamp: SystemTime,
+        txn: &TransactionSafeWrapper,
+        logger: &Logger,
+    ) -> Result<(), ReceivableDaoError> {
+        let main_sql = "update receivable set balance_high_b = balance_high_b + :balance_high_b, \
+                 balance_low_b = balance_low_b + :balance_low_b where wallet_address = :wallet";
+        let update_clause_with_compensated_overflow =
+            "update receivable set balance_high_b = :balance_high_b, \
+                 balance_low_b = :balance_low_b where wallet_address = :wallet";
+
+        net_payments_received_since_block(txn, rewind_to_block)?
+            .into_iter()
+            .try_for_each(|(wallet, block_number, net_change_wei)| {
+                // Received payments lowered the balance, so taking them back raises it again
+                let direction = if net_change_wei < 0 {
+                    WeiChangeDirection::Addition
+                } else {
+                    WeiChangeDirection::Subtraction
+                };
+                let params = SQLParamsBuilder::default()
+                    .key(WalletAddress(&wallet))
+                    .wei_change(WeiChange::new(
+                        "balance",
+                        net_change_wei.unsigned_abs(),
+                        direction,
+                    ))
+                    .build();
+
+                match big_int_db_processor.execute(
+                    Either::Right(txn),
+                    BigIntSqlConfig::new(main_sql, update_clause_with_compensated_overflow, params),
+                ) {
+                    Ok(_) => warning!(
+                        logger,
+                        "Took back {} wei received from {} in orphaned block {}",
+                        net_change_wei.unsigned_abs(),
+                        wallet,
+                        block_number
+                    ),
+                    Err(BigIntDatabaseError::General(err_msg)) => {
+                        return Err(ReceivableDaoError::RusqliteError(err_msg))
+                    }
+                    Err(BigIntDatabaseError::RowChangeMismatch { .. }) => warning!(
+                        logger,
+                        "Payment of {} wei from {} in orphaned block {} cannot be taken back; the account is gone",
+                        net_change_wei.unsigned_abs(),
+                        wallet,
+                        block_number
+                    ),
+                }
+
+                record_balance_change(
+                    Either::Right(txn),
+                    &BalanceChange {
+                        timestamp,
+                        wallet,
+                        account: AuditedAccount::Receivable,
+                        change_wei: -net_change_wei,
+                        cause: BalanceChangeCause::PaymentOrphaned { block_number },
+                    },
+                )
+                .map_err(ReceivableDaoError::from)
+            })
+    }
+
     fn verify_possibly_unknown_wallet(
         txn: &TransactionSafeWrapper,
         logger: &Logger,
@@ -492,6 +679,9 @@ impl TableNameDAO for ReceivableDaoReal {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::accountant::db_access_objects::audit_log_dao::{
+        AuditLogDao, AuditLogDaoReal, BalanceAuditRecord,
+    };
     use crate::accountant::db_access_objects::utils::{
         from_time_t, now_time_t, to_time_t, CustomQuery,
     };
@@ -575,7 +765,7 @@ mod tests {
             wei_amount: u128::MAX,
         }];
 
-        let _ = subject.more_money_received(SystemTime::now(), &payments);
+        let _ = subject.more_money_received(SystemTime::now(), &payments, None);
     }
 
     #[test]
@@ -600,7 +790,7 @@ mod tests {
             wei_amount: 18446744073709551615,
         }];
 
-        let _ = subject.more_money_received(SystemTime::now(), &payments);
+        let _ = subject.more_money_received(SystemTime::now(), &payments, None);
     }
 
     #[test]
@@ -628,6 +818,251 @@ mod tests {
         assert_eq!(to_time_t(status.last_received_timestamp), payment_time_t);
     }
 
+    #[test]
+    fn balance_changes_are_recorded_in_audit_log() {
+        let home_dir = ensure_node_home_directory_exists(
+            "receivable_dao",
+            "balance_changes_are_recorded_in_audit_log",
+        );
+        let wallet = make_wallet("booga");
+        let mut subject = ReceivableDaoReal::new(
+            DbInitializerReal::default()
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+        let audit_log_dao = AuditLogDaoReal::new(
+            DbInitializerReal::default()
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+        subject
+            .more_money_receivable(from_time_t(111_111_111), &wallet, 1234)
+            .unwrap();
+
+        let txn = subject.more_money_received(
+            from_time_t(222_222_222),
+            &[BlockchainTransaction {
+                block_number: 4321,
+                from: wallet.clone(),
+                wei_amount: 1000,
+            }],
+            None,
+        );
+        txn.commit().unwrap();
+
+        assert_eq!(
+            audit_log_dao.records(0, 10),
+            vec![
+                BalanceAuditRecord {
+                    rowid: 1,
+                    change: BalanceChange {
+                        timestamp: from_time_t(111_111_111),
+                        wallet: wallet.clone(),
+                        account: AuditedAccount::Receivable,
+                        change_wei: 1234,
+                        cause: BalanceChangeCause::ServicesProvided,
+                    }
+                },
+                BalanceAuditRecord {
+                    rowid: 2,
+                    change: BalanceChange {
+                        timestamp: from_time_t(222_222_222),
+                        wallet,
+                        account: AuditedAccount::Receivable,
+                        change_wei: -1000,
+                        cause: BalanceChangeCause::PaymentReceived { block_number: 4321 },
+                    }
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn sampled_receivables_are_labeled_as_such_in_audit_log() {
+        let home_dir = ensure_node_home_directory_exists(
+            "receivable_dao",
+            "sampled_receivables_are_labeled_as_such_in_audit_log",
+        );
+        let wallet = make_wallet("booga");
+        let subject = ReceivableDaoReal::new(
+            DbInitializerReal::default()
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+        let audit_log_dao = AuditLogDaoReal::new(
+            DbInitializerReal::default()
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+
+        subject
+            .more_sampled_money_receivable(from_time_t(111_111_111), &wallet, 16_000, 16)
+            .unwrap();
+
+        assert_eq!(subject.account_status(&wallet).unwrap().balance_wei, 16_000);
+        assert_eq!(
+            audit_log_dao.records(0, 10),
+            vec![BalanceAuditRecord {
+                rowid: 1,
+                change: BalanceChange {
+                    timestamp: from_time_t(111_111_111),
+                    wallet,
+                    account: AuditedAccount::Receivable,
+                    change_wei: 16_000,
+                    cause: BalanceChangeCause::ServicesProvidedSampled { sample_size: 16 },
+                }
+            }]
+        );
+    }
+
+    #[test]
+    fn more_money_received_takes_back_payments_from_orphaned_blocks_before_crediting_new_ones() {
+        init_test_logging();
+        let home_dir = ensure_node_home_directory_exists(
+            "receivable_dao",
+            "more_money_received_takes_back_payments_from_orphaned_blocks_before_crediting_new_ones",
+        );
+        let wallet_a = make_wallet("booga");
+        let wallet_b = make_wallet("agoob");
+        let mut subject = ReceivableDaoReal::new(
+            DbInitializerReal::default()
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+        let audit_log_dao = AuditLogDaoReal::new(
+            DbInitializerReal::default()
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+        subject
+            .more_money_receivable(from_time_t(111_111_111), &wallet_a, 10_000)
+            .unwrap();
+        subject
+            .more_money_receivable(from_time_t(111_111_111), &wallet_b, 5_000)
+            .unwrap();
+        subject
+            .more_money_received(
+                from_time_t(222_222_222),
+                &[
+                    BlockchainTransaction {
+                        block_number: 100,
+                        from: wallet_a.clone(),
+                        wei_amount: 1_000,
+                    },
+                    BlockchainTransaction {
+                        block_number: 105,
+                        from: wallet_b.clone(),
+                        wei_amount: 2_000,
+                    },
+                ],
+                None,
+            )
+            .commit()
+            .unwrap();
+
+        let txn = subject.more_money_received(
+            from_time_t(333_333_333),
+            &[BlockchainTransaction {
+                block_number: 106,
+                from: wallet_b.clone(),
+                wei_amount: 2_000,
+            }],
+            Some(103),
+        );
+        txn.commit().unwrap();
+
+        assert_eq!(
+            subject.account_status(&wallet_a).unwrap().balance_wei,
+            9_000
+        );
+        assert_eq!(
+            subject.account_status(&wallet_b).unwrap().balance_wei,
+            3_000
+        );
+        let records = audit_log_dao.records(4, 10);
+        assert_eq!(
+            records,
+            vec![
+                BalanceAuditRecord {
+                    rowid: 5,
+                    change: BalanceChange {
+                        timestamp: from_time_t(333_333_333),
+                        wallet: wallet_b.clone(),
+                        account: AuditedAccount::Receivable,
+                        change_wei: 2_000,
+                        cause: BalanceChangeCause::PaymentOrphaned { block_number: 105 },
+                    }
+                },
+                BalanceAuditRecord {
+                    rowid: 6,
+                    change: BalanceChange {
+                        timestamp: from_time_t(333_333_333),
+                        wallet: wallet_b.clone(),
+                        account: AuditedAccount::Receivable,
+                        change_wei: -2_000,
+                        cause: BalanceChangeCause::PaymentReceived { block_number: 106 },
+                    }
+                }
+            ]
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: ReceivableDaoReal: Took back 2000 wei received from {} in orphaned block 105",
+            wallet_b
+        ));
+    }
+
+    #[test]
+    fn apply_replicated_change_creates_accounts_and_moves_timestamp_only_on_payment() {
+        let home_dir = ensure_node_home_directory_exists(
+            "receivable_dao",
+            "apply_replicated_change_creates_accounts_and_moves_timestamp_only_on_payment",
+        );
+        let wallet = make_wallet("booga");
+        let subject = ReceivableDaoReal::new(
+            DbInitializerReal::default()
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+        let audit_log_dao = AuditLogDaoReal::new(
+            DbInitializerReal::default()
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+        let debt = BalanceChange {
+            timestamp: from_time_t(111_111_111),
+            wallet: wallet.clone(),
+            account: AuditedAccount::Receivable,
+            change_wei: 1234,
+            cause: BalanceChangeCause::ServicesProvided,
+        };
+        let overpayment = BalanceChange {
+            timestamp: from_time_t(222_222_222),
+            change_wei: -5000,
+            cause: BalanceChangeCause::PaymentReceived { block_number: 4321 },
+            ..debt.clone()
+        };
+        let late_debt = BalanceChange {
+            timestamp: from_time_t(333_333_333),
+            ..debt.clone()
+        };
+
+        subject.apply_replicated_change(&debt).unwrap();
+        subject.apply_replicated_change(&overpayment).unwrap();
+        subject.apply_replicated_change(&late_debt).unwrap();
+
+        let status = subject.account_status(&wallet).unwrap();
+        assert_eq!(status.balance_wei, 1234 - 5000 + 1234);
+        assert_eq!(status.last_received_timestamp, from_time_t(222_222_222));
+        assert_eq!(
+            audit_log_dao
+                .records(0, 10)
+                .into_iter()
+                .map(|record| record.change)
+                .collect::<Vec<_>>(),
+            vec![debt, overpayment, late_debt]
+        );
+    }
+
     #[test]
     fn more_money_receivable_works_for_existing_address_without_overflow() {
         //testing correctness of the main SQL
@@ -805,7 +1240,7 @@ mod tests {
             },
         ];
 
-        let txn = subject.more_money_received(payment_time, &transactions);
+        let txn = subject.more_money_received(payment_time, &transactions, None);
 
         txn.commit().unwrap();
         let status1 = subject.account_status(&debtor1).unwrap();
@@ -877,7 +1312,7 @@ mod tests {
         };
         let transactions = vec![transaction_1, transaction_2, transaction_3];
 
-        let txn = subject.more_money_received(time_of_change, transactions.as_slice());
+        let txn = subject.more_money_received(time_of_change, transactions.as_slice(), None);
 
         txn.commit().unwrap();
         let actual_record_1 = subject.account_status(&first_tracked_wallet).unwrap();
@@ -939,7 +1374,7 @@ mod tests {
         let transactions = vec![transaction];
 
         let caught_err = catch_unwind(AssertUnwindSafe(|| {
-            let _ = subject.more_money_received(time_of_change, transactions.as_slice());
+            let _ = subject.more_money_received(time_of_change, transactions.as_slice(), None);
         }))
         .unwrap_err();
 
@@ -985,7 +1420,7 @@ mod tests {
         };
         let transactions = vec![transaction];
 
-        subject.more_money_received(SystemTime::now(), &transactions);
+        subject.more_money_received(SystemTime::now(), &transactions, None);
     }
 
     #[test]
@@ -1031,6 +1466,7 @@ mod tests {
                     StmtTypeDirective::ExecuteProdCode,
                     StmtTypeDirective::ExecuteProdCode,
                     StmtTypeDirective::ExecuteProdCode,
+                    StmtTypeDirective::ExecuteProdCode,
                     StmtTypeDirective::UseAlteredStmt(
                         AlteredStmtBySQLOrigin::SQLIdenticalWithProdCode,
                     ),
@@ -1057,7 +1493,7 @@ mod tests {
         let transactions = vec![first_transaction, second_transaction];
 
         let result = catch_unwind(AssertUnwindSafe(|| {
-            let _ = subject.more_money_received(time_of_change, &transactions);
+            let _ = subject.more_money_received(time_of_change, &transactions, None);
         }));
 
         let caught_panic = result.unwrap_err();
@@ -1070,8 +1506,9 @@ mod tests {
         assert_eq!(panic_msg, &expected_panic_msg);
         let prepare_params = prepare_params_arc.lock().unwrap();
         // Asserting that we did perform the first transaction completely which is a process
-        // composed of three SQL statements if it includes handling an overflow like here
-        assert_eq!(&prepare_params[0..3],
+        // composed of four SQL statements if it includes handling an overflow like here, the last
+        // one being the record in the audit log
+        assert_eq!(&prepare_params[0..4],
             &[
               "update receivable set balance_high_b = balance_high_b + :balance_high_b, balance_low_b \
               = balance_low_b + :balance_low_b, last_received_timestamp = :last_received where wallet_address \
@@ -1079,7 +1516,10 @@ mod tests {
               "select balance_high_b, balance_low_b from receivable where wallet_address = \
               '0x0000000000000000000000000000000000616263'",
               "update receivable set balance_high_b = :balance_high_b, balance_low_b = :balance_low_b, \
-              last_received_timestamp = :last_received where wallet_address = :wallet"
+              last_received_timestamp = :last_received where wallet_address = :wallet",
+              "insert into balance_audit_log (timestamp, wallet_address, account_type, \
+              change_high_b, change_low_b, cause, reference) values (:timestamp, :wallet, :account, \
+              :change_high_b, :change_low_b, :cause, :reference)"
             ]);
         // The first transaction did not affect the db, was rolled back
         let account_status = receivable_dao.account_status(&first_wallet);
@@ -1485,6 +1925,8 @@ mod tests {
         let result = subject.custom_query(CustomQuery::TopRecords {
             count: 6,
             ordered_by: Balance,
+            offset: 0,
+            ascending: false,
         });
 
         assert_eq!(result, None)
@@ -1546,6 +1988,8 @@ mod tests {
             .custom_query(CustomQuery::TopRecords {
                 count: 3,
                 ordered_by: Balance,
+                offset: 0,
+                ascending: false,
             })
             .unwrap();
 
@@ -1584,6 +2028,8 @@ mod tests {
             .custom_query(CustomQuery::TopRecords {
                 count: 3,
                 ordered_by: Age,
+                offset: 0,
+                ascending: false,
             })
             .unwrap();
 
@@ -1623,6 +2069,8 @@ mod tests {
             min_amount_gwei: 500000000,
             max_amount_gwei: 3500000000,
             timestamp: SystemTime::now(),
+            offset: 0,
+            limit_opt: None,
         });
 
         assert_eq!(result, None)
@@ -1693,6 +2141,8 @@ mod tests {
                 min_amount_gwei: -560000,
                 max_amount_gwei: 1_100_000_000,
                 timestamp: from_time_t(now),
+                offset: 0,
+                limit_opt: None,
             })
             .unwrap();
 
@@ -1765,6 +2215,8 @@ mod tests {
                 min_amount_gwei: i64::MIN,
                 max_amount_gwei: 35_000_000_000,
                 timestamp: SystemTime::now(),
+                offset: 0,
+                limit_opt: None,
             })
             .unwrap();
 
@@ -14,6 +14,7 @@ use masq_lib::messages::{
     RangeQuery, TopRecordsConfig, TopRecordsOrdering, UiPayableAccount, UiReceivableAccount,
 };
 use rusqlite::{Row, Statement, ToSql};
+use std::collections::HashMap;
 use std::fmt::{Debug, Display};
 use std::iter::FlatMap;
 use std::path::{Path, PathBuf};
@@ -67,6 +68,8 @@ impl<T> From<TopRecordsConfig> for CustomQuery<T> {
         CustomQuery::TopRecords {
             count: config.count,
             ordered_by: config.ordered_by,
+            offset: config.offset,
+            ascending: config.ascending,
         }
     }
 }
@@ -76,6 +79,8 @@ pub enum CustomQuery<N> {
     TopRecords {
         count: u16,
         ordered_by: TopRecordsOrdering,
+        offset: u64,
+        ascending: bool,
     },
     RangeQuery {
         min_age_s: u64,
@@ -83,6 +88,8 @@ pub enum CustomQuery<N> {
         min_amount_gwei: N,
         max_amount_gwei: N,
         timestamp: SystemTime,
+        offset: u64,
+        limit_opt: Option<u16>,
     },
 }
 
@@ -91,20 +98,21 @@ type RusqliteParamsWithOwnedToSql = Vec<(&'static str, Box<dyn ToSql>)>;
 pub struct TopStmConfig {
     pub limit_clause: &'static str,
     pub gwei_min_resolution_clause: &'static str,
-    pub age_ordering_clause: &'static str,
+    pub age_column: &'static str,
 }
 
 pub struct RangeStmConfig {
     pub where_clause: &'static str,
     pub gwei_min_resolution_clause: &'static str,
     pub secondary_order_param: &'static str,
+    pub limit_clause: &'static str,
 }
 
 pub struct AssemblerFeeder {
     pub main_where_clause: &'static str,
     pub where_clause_extension: &'static str,
-    pub order_by_first_param: &'static str,
-    pub order_by_second_param: &'static str,
+    pub order_by_first_param: String,
+    pub order_by_second_param: String,
     pub limit_clause: &'static str,
 }
 
@@ -126,9 +134,14 @@ impl<N: Copy + Display> CustomQuery<N> {
         i128: From<N>,
     {
         let (finalized_stm, params): (String, RusqliteParamsWithOwnedToSql) = match self {
-            Self::TopRecords { count, ordered_by } => {
+            Self::TopRecords {
+                count,
+                ordered_by,
+                offset,
+                ascending,
+            } => {
                 let (order_by_first_param, order_by_second_param) =
-                    Self::ordering(ordered_by, variant_top.age_ordering_clause);
+                    Self::ordering(ordered_by, ascending, variant_top.age_column);
                 (
                     stm_assembler(AssemblerFeeder {
                         main_where_clause: variant_top.gwei_min_resolution_clause,
@@ -137,7 +150,7 @@ impl<N: Copy + Display> CustomQuery<N> {
                         order_by_second_param,
                         limit_clause: variant_top.limit_clause,
                     }),
-                    vec![(":limit_count", Box::new(count as i64))],
+                    Self::set_page_constraints(Some(count), offset),
                 )
             }
             Self::RangeQuery {
@@ -146,17 +159,20 @@ impl<N: Copy + Display> CustomQuery<N> {
                 min_amount_gwei: min_amount,
                 max_amount_gwei: max_amount,
                 timestamp,
+                offset,
+                limit_opt,
             } => (
                 stm_assembler(AssemblerFeeder {
                     main_where_clause: variant_range.where_clause,
                     where_clause_extension: variant_range.gwei_min_resolution_clause,
-                    order_by_first_param: "balance_high_b desc, balance_low_b desc",
-                    order_by_second_param: variant_range.secondary_order_param,
-                    limit_clause: "",
+                    order_by_first_param: "balance_high_b desc, balance_low_b desc".to_string(),
+                    order_by_second_param: variant_range.secondary_order_param.to_string(),
+                    limit_clause: variant_range.limit_clause,
                 }),
                 Self::set_age_constraints(min_age, max_age, timestamp)
                     .into_iter()
                     .chain(Self::set_wei_constraints(min_amount, max_amount))
+                    .chain(Self::set_page_constraints(limit_opt, offset))
                     .collect::<Vec<(&str, Box<dyn ToSql>)>>(),
             ),
         };
@@ -201,6 +217,17 @@ impl<N: Copy + Display> CustomQuery<N> {
         ]
     }
 
+    // SQLite takes a negative limit to mean no limit at all
+    fn set_page_constraints(limit_opt: Option<u16>, offset: u64) -> RusqliteParamsWithOwnedToSql {
+        vec![
+            (
+                ":limit_count",
+                Box::new(limit_opt.map(|limit| limit as i64).unwrap_or(-1)),
+            ),
+            (":offset", Box::new(checked_conversion::<u64, i64>(offset))),
+        ]
+    }
+
     fn set_wei_constraints(min_amount: N, max_amount: N) -> RusqliteParamsWithOwnedToSql
     where
         i128: From<N>,
@@ -231,13 +258,26 @@ impl<N: Copy + Display> CustomQuery<N> {
         ]
     }
 
+    // Oldest and biggest come first unless ascending is asked for. The oldest record has the
+    // smallest timestamp, so its column sorts the opposite way to the balance.
     fn ordering(
         ordering: TopRecordsOrdering,
-        age_param: &'static str,
-    ) -> (&'static str, &'static str) {
+        ascending: bool,
+        age_column: &'static str,
+    ) -> (String, String) {
+        let (age_direction, balance_direction) = if ascending {
+            ("desc", "asc")
+        } else {
+            ("asc", "desc")
+        };
+        let by_age = format!("{} {}", age_column, age_direction);
+        let by_balance = format!(
+            "balance_high_b {}, balance_low_b {}",
+            balance_direction, balance_direction
+        );
         match ordering {
-            TopRecordsOrdering::Age => (age_param, "balance_high_b desc, balance_low_b desc"),
-            TopRecordsOrdering::Balance => ("balance_high_b desc, balance_low_b desc", age_param),
+            TopRecordsOrdering::Age => (by_age, by_balance),
+            TopRecordsOrdering::Balance => (by_balance, by_age),
         }
     }
 }
@@ -250,15 +290,21 @@ impl<T: Copy> From<&RangeQuery<T>> for CustomQuery<T> {
             min_amount_gwei: user_input.min_amount_gwei,
             max_amount_gwei: user_input.max_amount_gwei,
             timestamp: SystemTime::now(),
+            offset: user_input.offset,
+            limit_opt: user_input.limit_opt,
         }
     }
 }
 
-pub fn remap_payable_accounts(accounts: Vec<PayableAccount>) -> Vec<UiPayableAccount> {
+pub fn remap_payable_accounts(
+    accounts: Vec<PayableAccount>,
+    labels: &HashMap<String, String>,
+) -> Vec<UiPayableAccount> {
     accounts
         .into_iter()
         .map(|account| UiPayableAccount {
             wallet: account.wallet.to_string(),
+            label_opt: labels.get(&account.wallet.to_string()).cloned(),
             age_s: to_age(account.last_paid_timestamp),
             balance_gwei: {
                 let gwei = (account.balance_wei / (WEIS_IN_GWEI as u128)) as u64;
@@ -279,11 +325,15 @@ pub fn remap_payable_accounts(accounts: Vec<PayableAccount>) -> Vec<UiPayableAcc
         .collect()
 }
 
-pub fn remap_receivable_accounts(accounts: Vec<ReceivableAccount>) -> Vec<UiReceivableAccount> {
+pub fn remap_receivable_accounts(
+    accounts: Vec<ReceivableAccount>,
+    labels: &HashMap<String, String>,
+) -> Vec<UiReceivableAccount> {
     accounts
         .into_iter()
         .map(|account| UiReceivableAccount {
             wallet: account.wallet.to_string(),
+            label_opt: labels.get(&account.wallet.to_string()).cloned(),
             age_s: to_age(account.last_received_timestamp),
             balance_gwei:{
                 let gwei =  (account.balance_wei / (WEIS_IN_GWEI as i128)) as i64;
@@ -414,6 +464,39 @@ impl ThresholdUtils {
         y as u128
     }
 
+    pub fn calculate_payout_threshold_by_age(
+        payment_thresholds: &PaymentThresholds,
+        debt_age_s: u64,
+    ) -> Option<u128> {
+        if debt_age_s <= payment_thresholds.maturity_threshold_sec {
+            None
+        } else {
+            Some(Self::calculate_finite_debt_limit_by_age(
+                payment_thresholds,
+                debt_age_s,
+            ))
+        }
+    }
+
+    pub fn calculate_ban_threshold_by_age(
+        payment_thresholds: &PaymentThresholds,
+        debt_age_s: u64,
+    ) -> Option<u128> {
+        // The same curve the payables follow, only postponed by the grace period; this has to
+        // stay in agreement with the query in ReceivableDao::new_delinquencies()
+        if debt_age_s
+            <= payment_thresholds.maturity_threshold_sec
+                + payment_thresholds.payment_grace_period_sec
+        {
+            None
+        } else {
+            Some(Self::calculate_finite_debt_limit_by_age(
+                payment_thresholds,
+                debt_age_s - payment_thresholds.payment_grace_period_sec,
+            ))
+        }
+    }
+
     fn compute_theoretical_interception_with_y_axis(
         m: i128, //is negative
         maturity_threshold_sec: i128,
@@ -444,7 +527,6 @@ mod tests {
     use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
     use rusqlite::types::{ToSqlOutput, Value};
     use rusqlite::{Connection, OpenFlags};
-    use std::collections::HashMap;
     use std::time::UNIX_EPOCH;
 
     #[test]
@@ -489,6 +571,8 @@ mod tests {
         let subject = CustomQuery::<u64>::TopRecords {
             count: 12,
             ordered_by: Balance,
+            offset: 0,
+            ascending: false,
         };
 
         let _ = subject.query::<_, i64, _, _>(
@@ -497,17 +581,60 @@ mod tests {
             TopStmConfig {
                 limit_clause: "",
                 gwei_min_resolution_clause: "",
-                age_ordering_clause: "",
+                age_column: "",
             },
             RangeStmConfig {
                 where_clause: "",
                 gwei_min_resolution_clause: "",
                 secondary_order_param: "",
+                limit_clause: "",
             },
             |_row| Ok(()),
         );
     }
 
+    #[test]
+    fn remapped_accounts_carry_the_labels_of_their_wallets() {
+        let labels = HashMap::from([(
+            make_wallet("brother").to_string(),
+            "My brother's Node".to_string(),
+        )]);
+        let payables = vec![
+            PayableAccount {
+                wallet: make_wallet("brother"),
+                balance_wei: 4_000_000_000,
+                last_paid_timestamp: SystemTime::now(),
+                pending_payable_opt: None,
+            },
+            PayableAccount {
+                wallet: make_wallet("stranger"),
+                balance_wei: 5_000_000_000,
+                last_paid_timestamp: SystemTime::now(),
+                pending_payable_opt: None,
+            },
+        ];
+        let receivables = vec![ReceivableAccount {
+            wallet: make_wallet("brother"),
+            balance_wei: -6_000_000_000,
+            last_received_timestamp: SystemTime::now(),
+        }];
+
+        let payable_result = remap_payable_accounts(payables, &labels);
+        let receivable_result = remap_receivable_accounts(receivables, &labels);
+
+        assert_eq!(
+            payable_result
+                .into_iter()
+                .map(|account| account.label_opt)
+                .collect::<Vec<Option<String>>>(),
+            vec![Some("My brother's Node".to_string()), None]
+        );
+        assert_eq!(
+            receivable_result[0].label_opt,
+            Some("My brother's Node".to_string())
+        );
+    }
+
     #[test]
     #[should_panic(
         expected = "Broken code: PayableAccount with less than 1 gwei passed through db query constraints; \
@@ -528,7 +655,7 @@ mod tests {
                 pending_payable_opt: None,
             },
         ];
-        remap_payable_accounts(accounts);
+        remap_payable_accounts(accounts, &HashMap::new());
     }
 
     #[test]
@@ -550,7 +677,7 @@ mod tests {
                 last_received_timestamp: SystemTime::now(),
             },
         ];
-        remap_receivable_accounts(accounts);
+        remap_receivable_accounts(accounts, &HashMap::new());
     }
 
     #[test]
@@ -572,7 +699,7 @@ mod tests {
                 last_received_timestamp: SystemTime::now(),
             },
         ];
-        remap_receivable_accounts(accounts);
+        remap_receivable_accounts(accounts, &HashMap::new());
     }
 
     #[test]
@@ -582,6 +709,8 @@ mod tests {
             max_age_s: 55,
             min_amount_gwei: 89_i64,
             max_amount_gwei: 12222,
+            offset: 0,
+            limit_opt: None,
         };
         let before = SystemTime::now();
 
@@ -840,6 +969,71 @@ mod tests {
         )
     }
 
+    #[test]
+    fn payout_threshold_appears_only_after_maturity() {
+        let payment_thresholds = PaymentThresholds {
+            maturity_threshold_sec: 1000,
+            payment_grace_period_sec: 444,
+            permanent_debt_allowed_gwei: 44,
+            debt_threshold_gwei: 8888,
+            threshold_interval_sec: 11111,
+            unban_below_gwei: 0,
+        };
+
+        let at_maturity =
+            ThresholdUtils::calculate_payout_threshold_by_age(&payment_thresholds, 1000);
+        let just_after_maturity =
+            ThresholdUtils::calculate_payout_threshold_by_age(&payment_thresholds, 1001);
+        let long_after =
+            ThresholdUtils::calculate_payout_threshold_by_age(&payment_thresholds, 50000);
+
+        assert_eq!(at_maturity, None);
+        assert_eq!(
+            just_after_maturity,
+            Some(ThresholdUtils::calculate_finite_debt_limit_by_age(
+                &payment_thresholds,
+                1001
+            ))
+        );
+        assert_eq!(
+            long_after,
+            Some(gwei_to_wei(payment_thresholds.permanent_debt_allowed_gwei))
+        );
+    }
+
+    #[test]
+    fn ban_threshold_follows_payout_threshold_delayed_by_grace_period() {
+        let payment_thresholds = PaymentThresholds {
+            maturity_threshold_sec: 1000,
+            payment_grace_period_sec: 444,
+            permanent_debt_allowed_gwei: 44,
+            debt_threshold_gwei: 8888,
+            threshold_interval_sec: 11111,
+            unban_below_gwei: 0,
+        };
+
+        let at_end_of_grace =
+            ThresholdUtils::calculate_ban_threshold_by_age(&payment_thresholds, 1444);
+        let just_after_grace =
+            ThresholdUtils::calculate_ban_threshold_by_age(&payment_thresholds, 1445);
+        let midway = ThresholdUtils::calculate_ban_threshold_by_age(&payment_thresholds, 7000);
+        let long_after = ThresholdUtils::calculate_ban_threshold_by_age(&payment_thresholds, 50000);
+
+        assert_eq!(at_end_of_grace, None);
+        assert_eq!(
+            just_after_grace,
+            ThresholdUtils::calculate_payout_threshold_by_age(&payment_thresholds, 1001)
+        );
+        assert_eq!(
+            midway,
+            ThresholdUtils::calculate_payout_threshold_by_age(&payment_thresholds, 6556)
+        );
+        assert_eq!(
+            long_after,
+            Some(gwei_to_wei(payment_thresholds.permanent_debt_allowed_gwei))
+        );
+    }
+
     #[test]
     #[should_panic(
         expected = "Couldn't initialize database due to \"Nonexistent\" at \"generated/test\
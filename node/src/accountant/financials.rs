@@ -148,6 +148,8 @@ mod tests {
             min_amount_gwei: 55,
             max_amount_gwei: 6666,
             timestamp: SystemTime::now(),
+            offset: 0,
+            limit_opt: None,
         };
 
         assert_excessive_values_in_check_query_is_within_tech_limits(
@@ -165,6 +167,8 @@ mod tests {
             min_amount_gwei: 55,
             max_amount_gwei: 6666,
             timestamp: SystemTime::now(),
+            offset: 0,
+            limit_opt: None,
         };
 
         assert_excessive_values_in_check_query_is_within_tech_limits(
@@ -182,6 +186,8 @@ mod tests {
             min_amount_gwei: i64::MAX as u64 + 1,
             max_amount_gwei: 6666,
             timestamp: SystemTime::now(),
+            offset: 0,
+            limit_opt: None,
         };
 
         assert_excessive_values_in_check_query_is_within_tech_limits(
@@ -199,6 +205,8 @@ mod tests {
             min_amount_gwei: 144,
             max_amount_gwei: i64::MAX as u64 + 1,
             timestamp: SystemTime::now(),
+            offset: 0,
+            limit_opt: None,
         };
 
         assert_excessive_values_in_check_query_is_within_tech_limits(
@@ -219,6 +227,8 @@ mod tests {
                     min_amount_gwei: val,
                     max_amount_gwei: val,
                     timestamp: SystemTime::now(),
+                    offset: 0,
+                    limit_opt: None,
                 };
                 let result = check_query_is_within_tech_limits(&query, "payable", 1234);
                 assert_eq!(result, Ok(()))
@@ -233,6 +243,8 @@ mod tests {
             min_amount_gwei: -500000,
             max_amount_gwei: -500,
             timestamp: SystemTime::now(),
+            offset: 0,
+            limit_opt: None,
         };
 
         let result = check_query_is_within_tech_limits(&query, "receivable", 789);
@@ -252,6 +264,8 @@ mod tests {
         let query = CustomQuery::<i64>::TopRecords {
             count: 123,
             ordered_by: Age,
+            offset: 0,
+            ascending: false,
         };
 
         let _ = check_query_is_within_tech_limits(&query, "payable", 1234);
@@ -0,0 +1,401 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use masq_lib::logger::Logger;
+use masq_lib::messages::{QueryResults, UiFiatStatistics, UiFiatValues, UiFinancialStatistics};
+use serde_derive::Deserialize;
+use std::cell::RefCell;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, SystemTime};
+
+pub const PRICE_CACHE_LIFETIME: Duration = Duration::from_secs(600);
+pub const PRICE_FEED_TIMEOUT: Duration = Duration::from_secs(3);
+const GWEI_PER_MASQ: f64 = 1_000_000_000.0;
+
+// What the price feed answers with: the price of one MASQ in the currency named
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct FiatPrice {
+    pub currency: String,
+    pub price: f64,
+}
+
+pub trait PriceFeed {
+    fn fetch(&self) -> Result<FiatPrice, String>;
+}
+
+pub struct PriceFeedHttp {
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl PriceFeed for PriceFeedHttp {
+    fn fetch(&self) -> Result<FiatPrice, String> {
+        let address = (self.host.as_str(), self.port)
+            .to_socket_addrs()
+            .map_err(|e| format!("Couldn't resolve {}: {}", self.host, e))?
+            .next()
+            .ok_or_else(|| format!("Couldn't resolve {}", self.host))?;
+        let mut stream = TcpStream::connect_timeout(&address, PRICE_FEED_TIMEOUT)
+            .map_err(|e| format!("Couldn't connect to {}: {}", address, e))?;
+        let mut response = String::new();
+        stream
+            .set_read_timeout(Some(PRICE_FEED_TIMEOUT))
+            .and_then(|_| stream.set_write_timeout(Some(PRICE_FEED_TIMEOUT)))
+            .and_then(|_| {
+                write!(
+                    stream,
+                    "GET {} HTTP/1.0\r\nHost: {}\r\nAccept: application/json\r\nConnection: close\r\n\r\n",
+                    self.path, self.host
+                )
+            })
+            .and_then(|_| stream.read_to_string(&mut response))
+            .map_err(|e| format!("Couldn't talk to {}: {}", address, e))?;
+        Self::parse_response(&response)
+    }
+}
+
+impl PriceFeedHttp {
+    // The URL has already been checked by validate_price_feed_url
+    pub fn new(url: &str) -> Self {
+        let rest = url.strip_prefix("http://").unwrap_or(url);
+        let (authority, path) = match rest.find('/') {
+            Some(idx) => (&rest[..idx], &rest[idx..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.split_once(':') {
+            Some((host, port)) => (host, port.parse::<u16>().unwrap_or(80)),
+            None => (authority, 80),
+        };
+        Self {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        }
+    }
+
+    fn parse_response(response: &str) -> Result<FiatPrice, String> {
+        let (head, body) = response
+            .split_once("\r\n\r\n")
+            .ok_or_else(|| "Price feed sent a malformed HTTP response".to_string())?;
+        let status_line = head.lines().next().unwrap_or_default();
+        if status_line.split_whitespace().nth(1) != Some("200") {
+            return Err(format!("Price feed answered '{}'", status_line));
+        }
+        let price = serde_json::from_str::<FiatPrice>(body.trim())
+            .map_err(|e| format!("Price feed sent unusable JSON: {}", e))?;
+        if !price.price.is_finite() || price.price < 0.0 {
+            return Err(format!(
+                "Price feed sent an impossible price: {}",
+                price.price
+            ));
+        }
+        Ok(price)
+    }
+}
+
+// Asking the feed is slow and the price changes slowly, so the feed is asked at most once per
+// PRICE_CACHE_LIFETIME, and only when a UI wants financials. If the feed can't be reached, the
+// last price it gave stays in use; its age goes out with it, so that a UI can decide whether to
+// show it.
+pub struct Pricing {
+    feed: Box<dyn PriceFeed>,
+    cache: RefCell<PriceCache>,
+    logger: Logger,
+}
+
+#[derive(Default)]
+struct PriceCache {
+    price_opt: Option<(FiatPrice, SystemTime)>,
+    last_attempt_opt: Option<SystemTime>,
+}
+
+impl Pricing {
+    pub fn new(feed: Box<dyn PriceFeed>) -> Self {
+        Self {
+            feed,
+            cache: RefCell::new(PriceCache::default()),
+            logger: Logger::new("Pricing"),
+        }
+    }
+
+    pub fn fiat_values(
+        &self,
+        stats_opt: Option<&UiFinancialStatistics>,
+        query_results_opt: Option<&QueryResults>,
+        now: SystemTime,
+    ) -> Option<UiFiatValues> {
+        let (price, price_age_s) = self.price(now)?;
+        let fiat = |gwei: i128| fiat_amount(gwei, price.price);
+        Some(UiFiatValues {
+            currency: price.currency.clone(),
+            masq_price: price.price.to_string(),
+            price_age_s,
+            stats_opt: stats_opt.map(|stats| UiFiatStatistics {
+                total_unpaid_and_pending_payable: fiat(
+                    stats.total_unpaid_and_pending_payable_gwei as i128,
+                ),
+                total_paid_payable: fiat(stats.total_paid_payable_gwei as i128),
+                total_unpaid_receivable: fiat(stats.total_unpaid_receivable_gwei as i128),
+                total_paid_receivable: fiat(stats.total_paid_receivable_gwei as i128),
+            }),
+            payable_opt: query_results_opt
+                .and_then(|results| results.payable_opt.as_ref())
+                .map(|accounts| {
+                    accounts
+                        .iter()
+                        .map(|account| fiat(account.balance_gwei as i128))
+                        .collect()
+                }),
+            receivable_opt: query_results_opt
+                .and_then(|results| results.receivable_opt.as_ref())
+                .map(|accounts| {
+                    accounts
+                        .iter()
+                        .map(|account| fiat(account.balance_gwei as i128))
+                        .collect()
+                }),
+        })
+    }
+
+    fn price(&self, now: SystemTime) -> Option<(FiatPrice, u64)> {
+        let mut cache = self.cache.borrow_mut();
+        let due = match cache.last_attempt_opt {
+            None => true,
+            Some(last_attempt) => age(last_attempt, now) >= PRICE_CACHE_LIFETIME,
+        };
+        if due {
+            cache.last_attempt_opt = Some(now);
+            match self.feed.fetch() {
+                Ok(price) => cache.price_opt = Some((price, now)),
+                Err(e) => warning!(
+                    self.logger,
+                    "Couldn't get the MASQ price from the price feed: {}",
+                    e
+                ),
+            }
+        }
+        cache
+            .price_opt
+            .as_ref()
+            .map(|(price, fetched_at)| (price.clone(), age(*fetched_at, now).as_secs()))
+    }
+}
+
+fn age(then: SystemTime, now: SystemTime) -> Duration {
+    now.duration_since(then)
+        .unwrap_or_else(|_| Duration::from_secs(0))
+}
+
+fn fiat_amount(gwei: i128, price: f64) -> String {
+    format!("{:.2}", gwei as f64 / GWEI_PER_MASQ * price)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accountant::test_utils::PriceFeedMock;
+    use masq_lib::messages::{UiPayableAccount, UiReceivableAccount};
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use masq_lib::utils::{find_free_port, localhost};
+    use std::net::{SocketAddr, TcpListener};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    fn make_price(currency: &str, price: f64) -> FiatPrice {
+        FiatPrice {
+            currency: currency.to_string(),
+            price,
+        }
+    }
+
+    fn serve_once(response: &'static str) -> u16 {
+        let port = find_free_port();
+        let listener = TcpListener::bind(SocketAddr::new(localhost(), port)).unwrap();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf).unwrap();
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+        port
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(PRICE_CACHE_LIFETIME, Duration::from_secs(600));
+        assert_eq!(PRICE_FEED_TIMEOUT, Duration::from_secs(3));
+        assert_eq!(GWEI_PER_MASQ, 1_000_000_000.0);
+    }
+
+    #[test]
+    fn price_feed_http_takes_urls_apart() {
+        let subject = PriceFeedHttp::new("http://prices.example.com:8080/v1/masq?currency=EUR");
+
+        assert_eq!(subject.host, "prices.example.com");
+        assert_eq!(subject.port, 8080);
+        assert_eq!(subject.path, "/v1/masq?currency=EUR");
+
+        let subject = PriceFeedHttp::new("http://prices.example.com");
+
+        assert_eq!(subject.host, "prices.example.com");
+        assert_eq!(subject.port, 80);
+        assert_eq!(subject.path, "/");
+    }
+
+    #[test]
+    fn price_feed_http_fetches_price() {
+        let port = serve_once(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n{\"currency\": \"USD\", \"price\": 0.125}",
+        );
+        let subject = PriceFeedHttp::new(&format!("http://127.0.0.1:{}/masq-price", port));
+
+        let result = subject.fetch();
+
+        assert_eq!(result, Ok(make_price("USD", 0.125)));
+    }
+
+    #[test]
+    fn price_feed_http_rejects_unsuccessful_status() {
+        let port = serve_once("HTTP/1.1 404 Not Found\r\n\r\n");
+        let subject = PriceFeedHttp::new(&format!("http://127.0.0.1:{}/masq-price", port));
+
+        let result = subject.fetch();
+
+        assert_eq!(
+            result,
+            Err("Price feed answered 'HTTP/1.1 404 Not Found'".to_string())
+        );
+    }
+
+    #[test]
+    fn price_feed_http_rejects_unusable_bodies() {
+        assert_eq!(
+            PriceFeedHttp::parse_response("HTTP/1.1 200 OK"),
+            Err("Price feed sent a malformed HTTP response".to_string())
+        );
+        assert_eq!(
+            PriceFeedHttp::parse_response("HTTP/1.1 200 OK\r\n\r\n{\"price\": 0.125}"),
+            Err(
+                "Price feed sent unusable JSON: missing field `currency` at line 1 column 16"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            PriceFeedHttp::parse_response(
+                "HTTP/1.1 200 OK\r\n\r\n{\"currency\": \"USD\", \"price\": -1.0}"
+            ),
+            Err("Price feed sent an impossible price: -1".to_string())
+        );
+    }
+
+    #[test]
+    fn fiat_values_convert_every_amount() {
+        let feed = PriceFeedMock::new().fetch_result(Ok(make_price("EUR", 0.5)));
+        let subject = Pricing::new(Box::new(feed));
+        let stats = UiFinancialStatistics {
+            total_unpaid_and_pending_payable_gwei: 3_000_000_000,
+            total_paid_payable_gwei: 1_234_567_890,
+            total_unpaid_receivable_gwei: -2_000_000_000,
+            total_paid_receivable_gwei: 0,
+        };
+        let query_results = QueryResults {
+            payable_opt: Some(vec![UiPayableAccount {
+                wallet: "0x0000000000000000000000000000000000000001".to_string(),
+                age_s: 100,
+                balance_gwei: 20_000_000_000,
+                pending_payable_hash_opt: None,
+                label_opt: None,
+            }]),
+            receivable_opt: Some(vec![UiReceivableAccount {
+                wallet: "0x0000000000000000000000000000000000000002".to_string(),
+                age_s: 200,
+                balance_gwei: -20_000_000,
+                label_opt: None,
+            }]),
+        };
+
+        let result = subject.fiat_values(Some(&stats), Some(&query_results), SystemTime::now());
+
+        assert_eq!(
+            result,
+            Some(UiFiatValues {
+                currency: "EUR".to_string(),
+                masq_price: "0.5".to_string(),
+                price_age_s: 0,
+                stats_opt: Some(UiFiatStatistics {
+                    total_unpaid_and_pending_payable: "1.50".to_string(),
+                    total_paid_payable: "0.62".to_string(),
+                    total_unpaid_receivable: "-1.00".to_string(),
+                    total_paid_receivable: "0.00".to_string(),
+                }),
+                payable_opt: Some(vec!["10.00".to_string()]),
+                receivable_opt: Some(vec!["-0.01".to_string()]),
+            })
+        );
+    }
+
+    #[test]
+    fn price_is_cached_for_its_lifetime() {
+        let fetch_params_arc = Arc::new(Mutex::new(vec![]));
+        let feed = PriceFeedMock::new()
+            .fetch_params(&fetch_params_arc)
+            .fetch_result(Ok(make_price("USD", 0.1)))
+            .fetch_result(Ok(make_price("USD", 0.2)));
+        let subject = Pricing::new(Box::new(feed));
+        let start = SystemTime::now();
+
+        let first = subject.fiat_values(None, None, start).unwrap();
+        let second = subject
+            .fiat_values(None, None, start + Duration::from_secs(599))
+            .unwrap();
+        let third = subject
+            .fiat_values(None, None, start + Duration::from_secs(600))
+            .unwrap();
+
+        assert_eq!((first.masq_price.as_str(), first.price_age_s), ("0.1", 0));
+        assert_eq!(
+            (second.masq_price.as_str(), second.price_age_s),
+            ("0.1", 599)
+        );
+        assert_eq!((third.masq_price.as_str(), third.price_age_s), ("0.2", 0));
+        assert_eq!(fetch_params_arc.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn stale_price_is_kept_when_feed_fails() {
+        init_test_logging();
+        let fetch_params_arc = Arc::new(Mutex::new(vec![]));
+        let feed = PriceFeedMock::new()
+            .fetch_params(&fetch_params_arc)
+            .fetch_result(Ok(make_price("USD", 0.1)))
+            .fetch_result(Err("booga".to_string()));
+        let subject = Pricing::new(Box::new(feed));
+        let start = SystemTime::now();
+        subject.fiat_values(None, None, start);
+
+        let stale = subject
+            .fiat_values(None, None, start + Duration::from_secs(700))
+            .unwrap();
+        let still_stale = subject
+            .fiat_values(None, None, start + Duration::from_secs(800))
+            .unwrap();
+
+        assert_eq!((stale.masq_price.as_str(), stale.price_age_s), ("0.1", 700));
+        assert_eq!(still_stale.price_age_s, 800);
+        assert_eq!(fetch_params_arc.lock().unwrap().len(), 2);
+        TestLogHandler::new().exists_log_containing(
+            "WARN: Pricing: Couldn't get the MASQ price from the price feed: booga",
+        );
+    }
+
+    #[test]
+    fn no_fiat_values_without_a_price() {
+        let feed = PriceFeedMock::new().fetch_result(Err("booga".to_string()));
+        let subject = Pricing::new(Box::new(feed));
+
+        let result = subject.fiat_values(None, None, SystemTime::now());
+
+        assert_eq!(result, None);
+    }
+}
@@ -6,7 +6,8 @@ pub mod test_utils;
 
 use crate::accountant::db_access_objects::payable_dao::{PayableAccount, PayableDao};
 use crate::accountant::db_access_objects::pending_payable_dao::{PendingPayable, PendingPayableDao};
-use crate::accountant::db_access_objects::receivable_dao::ReceivableDao;
+use crate::accountant::db_access_objects::receivable_dao::{ReceivableAccount, ReceivableDao};
+use crate::accountant::db_access_objects::utils::to_time_t;
 use crate::accountant::payment_adjuster::{PaymentAdjuster, PaymentAdjusterReal};
 use crate::accountant::scanners::scanners_utils::payable_scanner_utils::PayableTransactingErrorEnum::{
     LocallyCausedError, RemotelyCausedErrors,
@@ -18,31 +19,36 @@ use crate::accountant::scanners::scanners_utils::payable_scanner_utils::{
     PayableThresholdsGaugeReal, PayableTransactingErrorEnum, PendingPayableMetadata,
 };
 use crate::accountant::scanners::scanners_utils::pending_payable_scanner_utils::{
-    elapsed_in_ms, handle_none_status, handle_status_with_failure, handle_status_with_success,
-    PendingPayableScanReport,
+    age_in_secs, elapsed_in_ms, handle_none_status, handle_status_with_failure,
+    handle_status_with_success, PendingPayableScanReport,
 };
 use crate::accountant::scanners::scanners_utils::receivable_scanner_utils::balance_and_age;
 use crate::accountant::PendingPayableId;
 use crate::accountant::{
-    comma_joined_stringifiable, gwei_to_wei, Accountant, ReceivedPayments,
+    comma_joined_stringifiable, gwei_to_wei, wei_to_gwei, Accountant, ReceivedPayments,
     ReportTransactionReceipts, RequestTransactionReceipts, ResponseSkeleton, ScanForPayables,
-    ScanForPendingPayables, ScanForReceivables, SentPayables,
+    ScanForPendingPayables, ScanForReceivables, SentPayables, PAYMENT_OVERDUE_GRACE_PERIOD_SEC,
 };
 use crate::accountant::db_access_objects::banned_dao::BannedDao;
 use crate::blockchain::blockchain_bridge::{PendingPayableFingerprint, RetrieveTransactions};
 use crate::sub_lib::accountant::{
-    DaoFactories, FinancialStatistics, PaymentThresholds, ScanIntervals,
+    DaoFactories, FinancialStatistics, PaymentOverdue_0v1, PaymentThresholds,
+    PendingPayableLimits, ScanIntervals,
 };
 use crate::sub_lib::blockchain_bridge::{
     OutboundPaymentsInstructions,
 };
+use crate::sub_lib::proxy_client::PaymentOverdueNoticeMessage;
 use crate::sub_lib::utils::{NotifyLaterHandle, NotifyLaterHandleReal};
 use crate::sub_lib::wallet::Wallet;
-use actix::{Context, Message};
+use actix::{Context, Message, Recipient};
 use itertools::{Either, Itertools};
 use masq_lib::logger::Logger;
 use masq_lib::logger::TIME_FORMATTING_STRING;
-use masq_lib::messages::{ScanType, ToMessageBody, UiScanResponse};
+use masq_lib::messages::{
+    ScanType, ToMessageBody, UiAbandonedPayable, UiPendingPayablesAbandonedBroadcast,
+    UiScanResponse,
+};
 use masq_lib::ui_gateway::{MessageTarget, NodeToUiMessage};
 use masq_lib::utils::ExpectValue;
 use std::cell::RefCell;
@@ -68,7 +74,7 @@ impl Scanners {
     pub fn new(
         dao_factories: DaoFactories,
         payment_thresholds: Rc<PaymentThresholds>,
-        when_pending_too_long_sec: u64,
+        pending_payable_limits: PendingPayableLimits,
         financial_statistics: Rc<RefCell<FinancialStatistics>>,
     ) -> Self {
         let payable = Box::new(PayableScanner::new(
@@ -82,7 +88,7 @@ impl Scanners {
             dao_factories.payable_dao_factory.make(),
             dao_factories.pending_payable_dao_factory.make(),
             Rc::clone(&payment_thresholds),
-            when_pending_too_long_sec,
+            pending_payable_limits,
             Rc::clone(&financial_statistics),
         ));
 
@@ -120,6 +126,16 @@ where
     fn scan_started_at(&self) -> Option<SystemTime>;
     fn mark_as_started(&mut self, timestamp: SystemTime);
     fn mark_as_ended(&mut self, logger: &Logger);
+    // Only a scanner that has news for the UIs beyond its scan responses needs this
+    fn bind_ui_message_sub(&mut self, _ui_message_sub: Recipient<NodeToUiMessage>) {}
+    // Only a scanner that warns debtors before it bans them needs this
+    fn bind_overdue_notice_sub(
+        &mut self,
+        _overdue_notice_sub: Recipient<PaymentOverdueNoticeMessage>,
+    ) {
+    }
+    // Only a scanner that judges debts by the payment thresholds needs this
+    fn update_payment_thresholds(&mut self, _payment_thresholds: Rc<PaymentThresholds>) {}
 
     as_any_ref_in_trait!();
     as_any_mut_in_trait!();
@@ -260,6 +276,10 @@ impl Scanner<QualifiedPayablesMessage, SentPayables> for PayableScanner {
 
     time_marking_methods!(Payables);
 
+    fn update_payment_thresholds(&mut self, payment_thresholds: Rc<PaymentThresholds>) {
+        self.common.payment_thresholds = payment_thresholds;
+    }
+
     as_any_ref_in_trait_impl!();
 }
 
@@ -564,7 +584,9 @@ pub struct PendingPayableScanner {
     pub payable_dao: Box<dyn PayableDao>,
     pub pending_payable_dao: Box<dyn PendingPayableDao>,
     pub when_pending_too_long_sec: u64,
+    pub max_pending_attempts: u16,
     pub financial_statistics: Rc<RefCell<FinancialStatistics>>,
+    pub ui_message_sub_opt: Option<Recipient<NodeToUiMessage>>,
 }
 
 impl Scanner<RequestTransactionReceipts, ReportTransactionReceipts> for PendingPayableScanner {
@@ -629,6 +651,14 @@ impl Scanner<RequestTransactionReceipts, ReportTransactionReceipts> for PendingP
 
     time_marking_methods!(PendingPayables);
 
+    fn update_payment_thresholds(&mut self, payment_thresholds: Rc<PaymentThresholds>) {
+        self.common.payment_thresholds = payment_thresholds;
+    }
+
+    fn bind_ui_message_sub(&mut self, ui_message_sub: Recipient<NodeToUiMessage>) {
+        self.ui_message_sub_opt = Some(ui_message_sub);
+    }
+
     as_any_ref_in_trait_impl!();
 }
 
@@ -637,15 +667,17 @@ impl PendingPayableScanner {
         payable_dao: Box<dyn PayableDao>,
         pending_payable_dao: Box<dyn PendingPayableDao>,
         payment_thresholds: Rc<PaymentThresholds>,
-        when_pending_too_long_sec: u64,
+        pending_payable_limits: PendingPayableLimits,
         financial_statistics: Rc<RefCell<FinancialStatistics>>,
     ) -> Self {
         Self {
             common: ScannerCommon::new(payment_thresholds),
             payable_dao,
             pending_payable_dao,
-            when_pending_too_long_sec,
+            when_pending_too_long_sec: pending_payable_limits.max_age_sec,
+            max_pending_attempts: pending_payable_limits.max_attempts,
             financial_statistics,
+            ui_message_sub_opt: None,
         }
     }
 
@@ -654,22 +686,6 @@ impl PendingPayableScanner {
         msg: ReportTransactionReceipts,
         logger: &Logger,
     ) -> PendingPayableScanReport {
-        fn handle_none_receipt(
-            mut scan_report: PendingPayableScanReport,
-            payable: PendingPayableFingerprint,
-            logger: &Logger,
-        ) -> PendingPayableScanReport {
-            debug!(logger,
-                "Interpreting a receipt for transaction {:?} but none was given; attempt {}, {}ms since sending",
-                payable.hash, payable.attempt,elapsed_in_ms(payable.timestamp)
-            );
-
-            scan_report
-                .still_pending
-                .push(PendingPayableId::new(payable.rowid, payable.hash));
-            scan_report
-        }
-
         let scan_report = PendingPayableScanReport::default();
         msg.fingerprints_with_receipts.into_iter().fold(
             scan_report,
@@ -680,11 +696,39 @@ impl PendingPayableScanner {
                     fingerprint,
                     logger,
                 ),
-                None => handle_none_receipt(scan_report_so_far, fingerprint, logger),
+                None => self.handle_none_receipt(scan_report_so_far, fingerprint, logger),
             },
         )
     }
 
+    // No receipt means the blockchain doesn't know the transaction: it may still be waiting in a
+    // mempool, or it may have been dropped or replaced, in which case it will never confirm
+    fn handle_none_receipt(
+        &self,
+        mut scan_report: PendingPayableScanReport,
+        payable: PendingPayableFingerprint,
+        logger: &Logger,
+    ) -> PendingPayableScanReport {
+        debug!(logger,
+            "Interpreting a receipt for transaction {:?} but none was given; attempt {}, {}ms since sending",
+            payable.hash, payable.attempt,elapsed_in_ms(payable.timestamp)
+        );
+
+        if self.is_past_pending_limits(&payable) {
+            scan_report.abandoned.push(payable)
+        } else {
+            scan_report
+                .still_pending
+                .push(PendingPayableId::new(payable.rowid, payable.hash));
+        }
+        scan_report
+    }
+
+    fn is_past_pending_limits(&self, fingerprint: &PendingPayableFingerprint) -> bool {
+        fingerprint.attempt >= self.max_pending_attempts
+            || age_in_secs(fingerprint.timestamp) > self.when_pending_too_long_sec
+    }
+
     fn interpret_transaction_receipt(
         &self,
         scan_report: PendingPayableScanReport,
@@ -727,6 +771,7 @@ impl PendingPayableScanner {
     ) {
         self.confirm_transactions(scan_report.confirmed, logger);
         self.cancel_failed_transactions(scan_report.failures, logger);
+        self.abandon_stale_transactions(scan_report.abandoned, logger);
         self.update_remaining_fingerprints(scan_report.still_pending, logger)
     }
 
@@ -770,6 +815,62 @@ impl PendingPayableScanner {
         }
     }
 
+    fn abandon_stale_transactions(
+        &self,
+        fingerprints: Vec<PendingPayableFingerprint>,
+        logger: &Logger,
+    ) {
+        if fingerprints.is_empty() {
+            return;
+        }
+        let hashes = comma_joined_stringifiable(&fingerprints, |fingerprint| {
+            format!("{:?}", fingerprint.hash)
+        });
+        let rowids = fingerprints
+            .iter()
+            .map(|fingerprint| fingerprint.rowid)
+            .collect::<Vec<u64>>();
+        if let Err(e) = self.payable_dao.unmark_pending_payables_rowids(&rowids) {
+            panic!(
+                "Unable to release the payables of abandoned transactions {} due to {:?}",
+                hashes, e
+            )
+        }
+        if let Err(e) = self.pending_payable_dao.delete_fingerprints(&rowids) {
+            panic!(
+                "Unable to delete payable fingerprints {} of abandoned transactions due to {:?}",
+                hashes, e
+            )
+        }
+        warning!(
+            logger,
+            "Transactions {} never appeared on the blockchain within {} attempts or {}sec and were \
+            abandoned as failed; the debts they were meant to settle will be paid anew",
+            hashes,
+            self.max_pending_attempts,
+            self.when_pending_too_long_sec
+        );
+        if let Some(ui_message_sub) = self.ui_message_sub_opt.as_ref() {
+            let broadcast = UiPendingPayablesAbandonedBroadcast {
+                payables: fingerprints
+                    .iter()
+                    .map(|fingerprint| UiAbandonedPayable {
+                        transaction_hash: format!("{:?}", fingerprint.hash),
+                        amount_wei: fingerprint.amount,
+                        attempts: fingerprint.attempt,
+                        age_sec: age_in_secs(fingerprint.timestamp),
+                    })
+                    .collect(),
+            };
+            ui_message_sub
+                .try_send(NodeToUiMessage {
+                    target: MessageTarget::AllClients,
+                    body: broadcast.tmb(0),
+                })
+                .expect("UiGateway is dead");
+        }
+    }
+
     fn confirm_transactions(
         &mut self,
         fingerprints: Vec<PendingPayableFingerprint>,
@@ -830,6 +931,8 @@ pub struct ReceivableScanner {
     pub banned_dao: Box<dyn BannedDao>,
     pub persistent_configuration: Box<dyn PersistentConfiguration>,
     pub financial_statistics: Rc<RefCell<FinancialStatistics>>,
+    pub overdue_notice_sub_opt: Option<Recipient<PaymentOverdueNoticeMessage>>,
+    pub overdue_deadlines: HashMap<Wallet, SystemTime>,
 }
 
 impl Scanner<RetrieveTransactions, ReceivedPayments> for ReceivableScanner {
@@ -854,7 +957,7 @@ impl Scanner<RetrieveTransactions, ReceivedPayments> for ReceivableScanner {
     }
 
     fn finish_scan(&mut self, msg: ReceivedPayments, logger: &Logger) -> Option<NodeToUiMessage> {
-        if msg.payments.is_empty() {
+        if msg.payments.is_empty() && msg.rewind_to_block_opt.is_none() {
             info!(
                 logger,
                 "No newly received payments were detected during the scanning process."
@@ -870,6 +973,13 @@ impl Scanner<RetrieveTransactions, ReceivedPayments> for ReceivableScanner {
                     msg.new_start_block, e
                 ),
             }
+            // Only a hint for noticing chain reorganizations; losing it is no reason to stop
+            if let Err(e) = self
+                .persistent_configuration
+                .set_scanned_blocks(&msg.scanned_blocks)
+            {
+                warning!(logger, "Failed to remember the scanned blocks: {:?}", e)
+            }
         } else {
             self.handle_new_received_payments(&msg, logger)
         }
@@ -884,6 +994,17 @@ impl Scanner<RetrieveTransactions, ReceivedPayments> for ReceivableScanner {
 
     time_marking_methods!(Receivables);
 
+    fn update_payment_thresholds(&mut self, payment_thresholds: Rc<PaymentThresholds>) {
+        self.common.payment_thresholds = payment_thresholds;
+    }
+
+    fn bind_overdue_notice_sub(
+        &mut self,
+        overdue_notice_sub: Recipient<PaymentOverdueNoticeMessage>,
+    ) {
+        self.overdue_notice_sub_opt = Some(overdue_notice_sub);
+    }
+
     as_any_ref_in_trait_impl!();
     as_any_mut_in_trait_impl!();
 }
@@ -902,14 +1023,17 @@ impl ReceivableScanner {
             banned_dao,
             persistent_configuration,
             financial_statistics,
+            overdue_notice_sub_opt: None,
+            overdue_deadlines: HashMap::new(),
         }
     }
 
     fn handle_new_received_payments(&mut self, msg: &ReceivedPayments, logger: &Logger) {
-        let mut txn = self
-            .receivable_dao
-            .as_mut()
-            .more_money_received(msg.timestamp, &msg.payments);
+        let mut txn = self.receivable_dao.as_mut().more_money_received(
+            msg.timestamp,
+            &msg.payments,
+            msg.rewind_to_block_opt,
+        );
 
         let new_start_block = msg.new_start_block;
         match self
@@ -922,6 +1046,14 @@ impl ReceivableScanner {
                 new_start_block, e
             ),
         }
+        // In the same transaction, so that a rewind is never remembered without its payments taken back
+        match self
+            .persistent_configuration
+            .set_scanned_blocks_from_txn(&msg.scanned_blocks, &mut txn)
+        {
+            Ok(()) => (),
+            Err(e) => panic!("Attempt to remember scanned blocks failed due to: {:?}", e),
+        }
 
         match txn.commit() {
             Ok(_) => {
@@ -940,27 +1072,71 @@ impl ReceivableScanner {
             .total_paid_receivable_wei += total_newly_paid_receivable;
     }
 
-    pub fn scan_for_delinquencies(&self, timestamp: SystemTime, logger: &Logger) {
+    pub fn scan_for_delinquencies(&mut self, timestamp: SystemTime, logger: &Logger) {
         info!(logger, "Scanning for delinquencies");
         self.find_and_ban_delinquents(timestamp, logger);
         self.find_and_unban_reformed_nodes(timestamp, logger);
     }
 
-    fn find_and_ban_delinquents(&self, timestamp: SystemTime, logger: &Logger) {
-        self.receivable_dao
-            .new_delinquencies(timestamp, self.common.payment_thresholds.as_ref())
-            .into_iter()
-            .for_each(|account| {
-                self.banned_dao.ban(&account.wallet);
-                let (balance_str_wei, age) = balance_and_age(timestamp, &account);
-                info!(
-                    logger,
-                    "Wallet {} (balance: {} gwei, age: {} sec) banned for delinquency",
-                    account.wallet,
-                    balance_str_wei,
-                    age.as_secs()
-                )
-            });
+    fn find_and_ban_delinquents(&mut self, timestamp: SystemTime, logger: &Logger) {
+        let delinquents = self
+            .receivable_dao
+            .new_delinquencies(timestamp, self.common.payment_thresholds.as_ref());
+        self.overdue_deadlines
+            .retain(|wallet, _| delinquents.iter().any(|account| &account.wallet == wallet));
+        delinquents.into_iter().for_each(|account| {
+            if self.is_warned_in_time(&account, timestamp, logger) {
+                return;
+            }
+            self.banned_dao.ban(&account.wallet);
+            let (balance_str_wei, age) = balance_and_age(timestamp, &account);
+            info!(
+                logger,
+                "Wallet {} (balance: {} gwei, age: {} sec) banned for delinquency",
+                account.wallet,
+                balance_str_wei,
+                age.as_secs()
+            )
+        });
+    }
+
+    // A delinquent is told what it owes and given a deadline before it's banned, if the debtor can
+    // be reached at all; until that deadline passes, it isn't banned
+    fn is_warned_in_time(
+        &mut self,
+        account: &ReceivableAccount,
+        timestamp: SystemTime,
+        logger: &Logger,
+    ) -> bool {
+        let overdue_notice_sub = match self.overdue_notice_sub_opt.as_ref() {
+            Some(sub) => sub,
+            None => return false,
+        };
+        if let Some(deadline) = self.overdue_deadlines.get(&account.wallet) {
+            return timestamp < *deadline;
+        }
+        let deadline = timestamp + Duration::from_secs(PAYMENT_OVERDUE_GRACE_PERIOD_SEC);
+        overdue_notice_sub
+            .try_send(PaymentOverdueNoticeMessage {
+                debtor_wallet: account.wallet.clone(),
+                notice: PaymentOverdue_0v1 {
+                    balance_gwei: wei_to_gwei(account.balance_wei),
+                    deadline: to_time_t(deadline) as u64,
+                },
+            })
+            .expect("ProxyClient is dead");
+        self.overdue_deadlines
+            .insert(account.wallet.clone(), deadline);
+        let (balance_str_wei, age) = balance_and_age(timestamp, account);
+        info!(
+            logger,
+            "Wallet {} (balance: {} gwei, age: {} sec) warned that it will be banned for delinquency in {} sec",
+            account.wallet,
+            balance_str_wei,
+            age.as_secs(),
+            PAYMENT_OVERDUE_GRACE_PERIOD_SEC
+        );
+        true
     }
 
     fn find_and_unban_reformed_nodes(&self, timestamp: SystemTime, logger: &Logger) {
@@ -1114,20 +1290,22 @@ mod tests {
     };
     use crate::accountant::test_utils::{
         make_custom_payment_thresholds, make_payable_account, make_payables,
-        make_pending_payable_fingerprint, make_receivable_account, BannedDaoFactoryMock,
-        BannedDaoMock, ConfigDaoFactoryMock, PayableDaoFactoryMock, PayableDaoMock,
-        PayableScannerBuilder, PayableThresholdsGaugeMock, PendingPayableDaoFactoryMock,
-        PendingPayableDaoMock, PendingPayableScannerBuilder, ReceivableDaoFactoryMock,
-        ReceivableDaoMock, ReceivableScannerBuilder,
+        make_pending_payable_fingerprint, make_receivable_account, AddressBookDaoFactoryMock,
+        AuditLogDaoFactoryMock, BannedDaoFactoryMock, BannedDaoMock, ConfigDaoFactoryMock,
+        EarningsHistoryDaoFactoryMock, MaintenanceDaoFactoryMock, PayableDaoFactoryMock,
+        PayableDaoMock, PayableScannerBuilder, PayableThresholdsGaugeMock,
+        PendingPayableDaoFactoryMock, PendingPayableDaoMock, PendingPayableScannerBuilder,
+        ReceivableDaoFactoryMock, ReceivableDaoMock, ReceivableScannerBuilder,
     };
     use crate::accountant::{
         gwei_to_wei, PendingPayableId, ReceivedPayments, ReportTransactionReceipts,
         RequestTransactionReceipts, SentPayables, DEFAULT_PENDING_TOO_LONG_SEC,
+        PAYMENT_OVERDUE_GRACE_PERIOD_SEC,
     };
     use crate::blockchain::blockchain_bridge::{PendingPayableFingerprint, RetrieveTransactions};
     use crate::blockchain::blockchain_interface::data_structures::errors::PayableTransactionError;
     use crate::blockchain::blockchain_interface::data_structures::{
-        BlockchainTransaction, RpcPayablesFailure,
+        BlockchainTransaction, RpcPayablesFailure, ScannedBlock,
     };
     use crate::blockchain::test_utils::make_tx_hash;
     use crate::database::rusqlite_wrappers::TransactionSafeWrapper;
@@ -1135,17 +1313,22 @@ mod tests {
     use crate::db_config::mocks::ConfigDaoMock;
     use crate::db_config::persistent_configuration::PersistentConfigError;
     use crate::sub_lib::accountant::{
-        DaoFactories, FinancialStatistics, PaymentThresholds, ScanIntervals,
-        DEFAULT_PAYMENT_THRESHOLDS,
+        DaoFactories, FinancialStatistics, PaymentOverdue_0v1, PaymentThresholds,
+        PendingPayableLimits, ScanIntervals, DEFAULT_PAYMENT_THRESHOLDS,
     };
+    use crate::sub_lib::proxy_client::PaymentOverdueNoticeMessage;
     use crate::test_utils::persistent_configuration_mock::PersistentConfigurationMock;
+    use crate::test_utils::recorder::make_recorder;
     use crate::test_utils::unshared_test_utils::arbitrary_id_stamp::ArbitraryIdStamp;
     use crate::test_utils::{make_paying_wallet, make_wallet};
-    use actix::{Message, System};
+    use actix::{Actor, Message, System};
     use ethereum_types::U64;
     use masq_lib::logger::Logger;
-    use masq_lib::messages::ScanType;
+    use masq_lib::messages::{
+        FromMessageBody, ScanType, UiAbandonedPayable, UiPendingPayablesAbandonedBroadcast,
+    };
     use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use masq_lib::ui_gateway::{MessageTarget, NodeToUiMessage};
     use regex::Regex;
     use rusqlite::{ffi, ErrorCode};
     use std::cell::RefCell;
@@ -1174,7 +1357,10 @@ mod tests {
             .set_params(&set_params_arc)
             .set_result(Ok(()));
         let config_dao_factory = ConfigDaoFactoryMock::new().make_result(config_dao_mock);
-        let when_pending_too_long_sec = 1234;
+        let pending_payable_limits = PendingPayableLimits {
+            max_attempts: 17,
+            max_age_sec: 1234,
+        };
         let financial_statistics = FinancialStatistics {
             total_paid_payable_wei: 1,
             total_paid_receivable_wei: 2,
@@ -1185,6 +1371,10 @@ mod tests {
 
         let mut scanners = Scanners::new(
             DaoFactories {
+                address_book_dao_factory: Box::new(AddressBookDaoFactoryMock::new()),
+                audit_log_dao_factory: Box::new(AuditLogDaoFactoryMock::new()),
+                earnings_history_dao_factory: Box::new(EarningsHistoryDaoFactoryMock::new()),
+                maintenance_dao_factory: Box::new(MaintenanceDaoFactoryMock::new()),
                 payable_dao_factory: Box::new(payable_dao_factory),
                 pending_payable_dao_factory: Box::new(pending_payable_dao_factory),
                 receivable_dao_factory: Box::new(receivable_dao_factory),
@@ -1192,7 +1382,7 @@ mod tests {
                 config_dao_factory: Box::new(config_dao_factory),
             },
             Rc::clone(&payment_thresholds_rc),
-            when_pending_too_long_sec,
+            pending_payable_limits,
             Rc::new(RefCell::new(financial_statistics.clone())),
         );
 
@@ -1216,10 +1406,9 @@ mod tests {
             &payment_thresholds
         );
         assert_eq!(payable_scanner.common.initiated_at_opt.is_some(), false);
-        assert_eq!(
-            pending_payable_scanner.when_pending_too_long_sec,
-            when_pending_too_long_sec
-        );
+        assert_eq!(pending_payable_scanner.when_pending_too_long_sec, 1234);
+        assert_eq!(pending_payable_scanner.max_pending_attempts, 17);
+        assert_eq!(pending_payable_scanner.ui_message_sub_opt.is_none(), true);
         assert_eq!(
             *pending_payable_scanner.financial_statistics.borrow(),
             financial_statistics
@@ -2391,7 +2580,8 @@ mod tests {
             PendingPayableScanReport {
                 still_pending: vec![],
                 failures: vec![PendingPayableId::new(rowid, hash)],
-                confirmed: vec![]
+                confirmed: vec![],
+                abandoned: vec![]
             }
         );
         let capture_regex = "(\\d+){2}sec";
@@ -2424,7 +2614,8 @@ mod tests {
             PendingPayableScanReport {
                 still_pending: vec![PendingPayableId::new(rowid, hash)],
                 failures: vec![],
-                confirmed: vec![]
+                confirmed: vec![],
+                abandoned: vec![]
             }
         );
         let capture_regex = r#"\s(\d+)ms"#;
@@ -2454,7 +2645,8 @@ mod tests {
             PendingPayableScanReport {
                 still_pending: vec![PendingPayableId::new(rowid, hash)],
                 failures: vec![],
-                confirmed: vec![]
+                confirmed: vec![],
+                abandoned: vec![]
             }
         );
         let capture_regex = r#"\s(\d+)ms"#;
@@ -2509,7 +2701,8 @@ mod tests {
             PendingPayableScanReport {
                 still_pending: vec![],
                 failures: vec![PendingPayableId::new(777777, hash,)],
-                confirmed: vec![]
+                confirmed: vec![],
+                abandoned: vec![]
             }
         );
         TestLogHandler::new().exists_log_matching(&format!(
@@ -2547,7 +2740,8 @@ mod tests {
             PendingPayableScanReport {
                 still_pending: vec![PendingPayableId::new(rowid, hash)],
                 failures: vec![],
-                confirmed: vec![]
+                confirmed: vec![],
+                abandoned: vec![]
             }
         );
         TestLogHandler::new().exists_log_matching(&format!(
@@ -2557,6 +2751,234 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn handle_pending_txs_with_receipts_abandons_none_receipt_at_max_attempts() {
+        let subject = PendingPayableScannerBuilder::new()
+            .max_pending_attempts(5)
+            .build();
+        let mut fingerprint_1 = make_pending_payable_fingerprint();
+        fingerprint_1.rowid = 455;
+        fingerprint_1.hash = make_tx_hash(0x913);
+        fingerprint_1.attempt = 4;
+        fingerprint_1.timestamp = SystemTime::now();
+        let mut fingerprint_2 = make_pending_payable_fingerprint();
+        fingerprint_2.rowid = 456;
+        fingerprint_2.hash = make_tx_hash(0x914);
+        fingerprint_2.attempt = 5;
+        fingerprint_2.timestamp = SystemTime::now();
+        let msg = ReportTransactionReceipts {
+            fingerprints_with_receipts: vec![
+                (None, fingerprint_1.clone()),
+                (None, fingerprint_2.clone()),
+            ],
+            response_skeleton_opt: None,
+        };
+
+        let result = subject.handle_receipts_for_pending_transactions(msg, &Logger::new("test"));
+
+        assert_eq!(
+            result,
+            PendingPayableScanReport {
+                still_pending: vec![PendingPayableId::new(455, fingerprint_1.hash)],
+                failures: vec![],
+                confirmed: vec![],
+                abandoned: vec![fingerprint_2]
+            }
+        );
+    }
+
+    #[test]
+    fn handle_pending_txs_with_receipts_abandons_none_receipt_older_than_max_age() {
+        let subject = PendingPayableScannerBuilder::new()
+            .when_pending_too_long_sec(100)
+            .build();
+        let mut fingerprint_1 = make_pending_payable_fingerprint();
+        fingerprint_1.rowid = 455;
+        fingerprint_1.hash = make_tx_hash(0x913);
+        fingerprint_1.attempt = 1;
+        fingerprint_1.timestamp = SystemTime::now().sub(Duration::from_secs(99));
+        let mut fingerprint_2 = make_pending_payable_fingerprint();
+        fingerprint_2.rowid = 456;
+        fingerprint_2.hash = make_tx_hash(0x914);
+        fingerprint_2.attempt = 1;
+        fingerprint_2.timestamp = SystemTime::now().sub(Duration::from_secs(101));
+        let msg = ReportTransactionReceipts {
+            fingerprints_with_receipts: vec![
+                (None, fingerprint_1.clone()),
+                (None, fingerprint_2.clone()),
+            ],
+            response_skeleton_opt: None,
+        };
+
+        let result = subject.handle_receipts_for_pending_transactions(msg, &Logger::new("test"));
+
+        assert_eq!(
+            result,
+            PendingPayableScanReport {
+                still_pending: vec![PendingPayableId::new(455, fingerprint_1.hash)],
+                failures: vec![],
+                confirmed: vec![],
+                abandoned: vec![fingerprint_2]
+            }
+        );
+    }
+
+    #[test]
+    fn abandon_stale_transactions_works() {
+        init_test_logging();
+        let test_name = "abandon_stale_transactions_works";
+        let unmark_pending_payables_rowids_params_arc = Arc::new(Mutex::new(vec![]));
+        let delete_fingerprints_params_arc = Arc::new(Mutex::new(vec![]));
+        let payable_dao = PayableDaoMock::new()
+            .unmark_pending_payables_rowids_params(&unmark_pending_payables_rowids_params_arc)
+            .unmark_pending_payables_rowids_result(Ok(()));
+        let pending_payable_dao = PendingPayableDaoMock::default()
+            .delete_fingerprints_params(&delete_fingerprints_params_arc)
+            .delete_fingerprints_result(Ok(()));
+        let mut subject = PendingPayableScannerBuilder::new()
+            .payable_dao(payable_dao)
+            .pending_payable_dao(pending_payable_dao)
+            .max_pending_attempts(5)
+            .when_pending_too_long_sec(600)
+            .build();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let system = System::new(test_name);
+        subject.bind_ui_message_sub(ui_gateway.start().recipient());
+        let mut fingerprint_1 = make_pending_payable_fingerprint();
+        fingerprint_1.rowid = 2;
+        fingerprint_1.hash = make_tx_hash(0x7b);
+        fingerprint_1.attempt = 5;
+        fingerprint_1.amount = 1_000_000;
+        fingerprint_1.timestamp = SystemTime::now().sub(Duration::from_secs(30));
+        let mut fingerprint_2 = make_pending_payable_fingerprint();
+        fingerprint_2.rowid = 3;
+        fingerprint_2.hash = make_tx_hash(0x1c8);
+        fingerprint_2.attempt = 2;
+        fingerprint_2.amount = 2_000_000;
+        fingerprint_2.timestamp = SystemTime::now().sub(Duration::from_secs(700));
+
+        subject.abandon_stale_transactions(
+            vec![fingerprint_1, fingerprint_2],
+            &Logger::new(test_name),
+        );
+
+        System::current().stop();
+        system.run();
+        let unmark_pending_payables_rowids_params =
+            unmark_pending_payables_rowids_params_arc.lock().unwrap();
+        assert_eq!(*unmark_pending_payables_rowids_params, vec![vec![2, 3]]);
+        let delete_fingerprints_params = delete_fingerprints_params_arc.lock().unwrap();
+        assert_eq!(*delete_fingerprints_params, vec![vec![2, 3]]);
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let message = ui_gateway_recording.get_record::<NodeToUiMessage>(0);
+        assert_eq!(message.target, MessageTarget::AllClients);
+        let (broadcast, context_id) =
+            UiPendingPayablesAbandonedBroadcast::fmb(message.body.clone()).unwrap();
+        assert_eq!(context_id, 0);
+        let ages = broadcast
+            .payables
+            .iter()
+            .map(|payable| payable.age_sec)
+            .collect::<Vec<u64>>();
+        assert!(30 <= ages[0] && ages[0] < 40, "{:?}", ages);
+        assert!(700 <= ages[1] && ages[1] < 710, "{:?}", ages);
+        assert_eq!(
+            broadcast,
+            UiPendingPayablesAbandonedBroadcast {
+                payables: vec![
+                    UiAbandonedPayable {
+                        transaction_hash: format!("{:?}", make_tx_hash(0x7b)),
+                        amount_wei: 1_000_000,
+                        attempts: 5,
+                        age_sec: ages[0],
+                    },
+                    UiAbandonedPayable {
+                        transaction_hash: format!("{:?}", make_tx_hash(0x1c8)),
+                        amount_wei: 2_000_000,
+                        attempts: 2,
+                        age_sec: ages[1],
+                    },
+                ]
+            }
+        );
+        assert_eq!(ui_gateway_recording.len(), 1);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Transactions 0x000000000000000000000000000000000000000000000000000000000000007b, \
+            0x00000000000000000000000000000000000000000000000000000000000001c8 never appeared on the blockchain \
+            within 5 attempts or 600sec and were abandoned as failed; the debts they were meant to settle will be \
+            paid anew",
+        ));
+    }
+
+    #[test]
+    fn abandon_stale_transactions_without_ui_message_sub_only_logs() {
+        init_test_logging();
+        let test_name = "abandon_stale_transactions_without_ui_message_sub_only_logs";
+        let payable_dao = PayableDaoMock::new().unmark_pending_payables_rowids_result(Ok(()));
+        let pending_payable_dao =
+            PendingPayableDaoMock::default().delete_fingerprints_result(Ok(()));
+        let subject = PendingPayableScannerBuilder::new()
+            .payable_dao(payable_dao)
+            .pending_payable_dao(pending_payable_dao)
+            .build();
+        let mut fingerprint = make_pending_payable_fingerprint();
+        fingerprint.hash = make_tx_hash(0x315);
+
+        subject.abandon_stale_transactions(vec![fingerprint], &Logger::new(test_name));
+
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Transactions 0x0000000000000000000000000000000000000000000000000000000000000315 \
+            never appeared on the blockchain"
+        ));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Unable to release the payables of abandoned transactions 0x0000000000000000000\
+        000000000000000000000000000000000000000000315 due to RusqliteError(\"blah\")"
+    )]
+    fn abandon_stale_transactions_panics_when_it_fails_to_release_payables() {
+        let payable_dao = PayableDaoMock::new().unmark_pending_payables_rowids_result(Err(
+            PayableDaoError::RusqliteError("blah".to_string()),
+        ));
+        let subject = PendingPayableScannerBuilder::new()
+            .payable_dao(payable_dao)
+            .build();
+        let mut fingerprint = make_pending_payable_fingerprint();
+        fingerprint.hash = make_tx_hash(0x315);
+
+        subject.abandon_stale_transactions(vec![fingerprint], &Logger::new("test"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Unable to delete payable fingerprints 0x00000000000000000000000000000000000000\
+        0000000000000000000000021a of abandoned transactions due to RecordDeletion(\"gone wrong\")"
+    )]
+    fn abandon_stale_transactions_panics_when_it_fails_to_delete_fingerprints() {
+        let payable_dao = PayableDaoMock::new().unmark_pending_payables_rowids_result(Ok(()));
+        let pending_payable_dao = PendingPayableDaoMock::default().delete_fingerprints_result(Err(
+            PendingPayableDaoError::RecordDeletion("gone wrong".to_string()),
+        ));
+        let subject = PendingPayableScannerBuilder::new()
+            .payable_dao(payable_dao)
+            .pending_payable_dao(pending_payable_dao)
+            .build();
+        let mut fingerprint = make_pending_payable_fingerprint();
+        fingerprint.hash = make_tx_hash(0x21a);
+
+        subject.abandon_stale_transactions(vec![fingerprint], &Logger::new("test"));
+    }
+
+    #[test]
+    fn abandon_stale_transactions_does_nothing_if_none_are_stale() {
+        let subject = PendingPayableScannerBuilder::new().build();
+
+        subject.abandon_stale_transactions(vec![], &Logger::new("test"))
+
+        //mocked DAOs didn't panic which means we skipped the actual process
+    }
+
     #[test]
     fn increment_scan_attempts_happy_path() {
         let update_remaining_fingerprints_params_arc = Arc::new(Mutex::new(vec![]));
@@ -3063,23 +3485,118 @@ mod tests {
         );
     }
 
+    #[test]
+    fn receivable_scanner_warns_a_reachable_delinquent_and_bans_it_only_after_its_deadline() {
+        init_test_logging();
+        let test_name =
+            "receivable_scanner_warns_a_reachable_delinquent_and_bans_it_only_after_its_deadline";
+        let delinquent = make_receivable_account(1234, true);
+        let receivable_dao = ReceivableDaoMock::new()
+            .new_delinquencies_result(vec![delinquent.clone()])
+            .new_delinquencies_result(vec![delinquent.clone()])
+            .new_delinquencies_result(vec![delinquent.clone()])
+            .paid_delinquencies_result(vec![])
+            .paid_delinquencies_result(vec![])
+            .paid_delinquencies_result(vec![]);
+        let ban_parameters_arc = Arc::new(Mutex::new(vec![]));
+        let banned_dao = BannedDaoMock::new().ban_parameters(&ban_parameters_arc);
+        let mut subject = ReceivableScannerBuilder::new()
+            .receivable_dao(receivable_dao)
+            .banned_dao(banned_dao)
+            .build();
+        let (proxy_client, _, proxy_client_recording_arc) = make_recorder();
+        let system = System::new(test_name);
+        subject.bind_overdue_notice_sub(proxy_client.start().recipient());
+        let logger = Logger::new(test_name);
+        let warned_at = from_time_t(1_700_000_000);
+        let grace_period = Duration::from_secs(PAYMENT_OVERDUE_GRACE_PERIOD_SEC);
+
+        subject.scan_for_delinquencies(warned_at, &logger);
+        subject.scan_for_delinquencies(warned_at + grace_period - Duration::from_secs(1), &logger);
+        let ban_parameters_before_deadline = ban_parameters_arc.lock().unwrap().clone();
+        subject.scan_for_delinquencies(warned_at + grace_period, &logger);
+
+        System::current().stop();
+        system.run();
+        assert_eq!(ban_parameters_before_deadline, vec![]);
+        assert_eq!(
+            *ban_parameters_arc.lock().unwrap(),
+            vec![delinquent.wallet.clone()]
+        );
+        let proxy_client_recording = proxy_client_recording_arc.lock().unwrap();
+        assert_eq!(
+            proxy_client_recording.get_record::<PaymentOverdueNoticeMessage>(0),
+            &PaymentOverdueNoticeMessage {
+                debtor_wallet: delinquent.wallet.clone(),
+                notice: PaymentOverdue_0v1 {
+                    balance_gwei: 1_234,
+                    deadline: 1_700_000_000 + PAYMENT_OVERDUE_GRACE_PERIOD_SEC,
+                },
+            }
+        );
+        assert_eq!(proxy_client_recording.len(), 1);
+        TestLogHandler::new().exists_log_matching(&format!(
+            "INFO: {}: Wallet 0x00000000000000000077616c6c65743132333464 \\(balance: 1,234 gwei, \
+            age: \\d+ sec\\) warned that it will be banned for delinquency in 3600 sec",
+            test_name
+        ));
+    }
+
+    #[test]
+    fn receivable_scanner_forgets_the_deadline_of_a_debtor_that_is_no_longer_delinquent() {
+        let delinquent = make_receivable_account(1234, true);
+        let receivable_dao = ReceivableDaoMock::new()
+            .new_delinquencies_result(vec![delinquent.clone()])
+            .new_delinquencies_result(vec![])
+            .paid_delinquencies_result(vec![])
+            .paid_delinquencies_result(vec![]);
+        let mut subject = ReceivableScannerBuilder::new()
+            .receivable_dao(receivable_dao)
+            .build();
+        let (proxy_client, _, _) = make_recorder();
+        let _system = System::new(
+            "receivable_scanner_forgets_the_deadline_of_a_debtor_that_is_no_longer_delinquent",
+        );
+        subject.bind_overdue_notice_sub(proxy_client.start().recipient());
+        let logger = Logger::new("test");
+        let now = SystemTime::now();
+        subject.scan_for_delinquencies(now, &logger);
+        assert_eq!(
+            subject.overdue_deadlines.keys().collect::<Vec<_>>(),
+            vec![&delinquent.wallet]
+        );
+
+        subject.scan_for_delinquencies(now, &logger);
+
+        assert!(subject.overdue_deadlines.is_empty());
+    }
+
     #[test]
     fn receivable_scanner_handles_no_new_payments_found() {
         init_test_logging();
         let test_name = "receivable_scanner_aborts_scan_if_no_payments_were_supplied";
         let set_start_block_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_scanned_blocks_params_arc = Arc::new(Mutex::new(vec![]));
         let new_start_block = 4321;
         let persistent_config = PersistentConfigurationMock::new()
             .start_block_result(Ok(None))
             .set_start_block_params(&set_start_block_params_arc)
-            .set_start_block_result(Ok(()));
+            .set_start_block_result(Ok(()))
+            .set_scanned_blocks_params(&set_scanned_blocks_params_arc)
+            .set_scanned_blocks_result(Ok(()));
         let mut subject = ReceivableScannerBuilder::new()
             .persistent_configuration(persistent_config)
             .build();
+        let scanned_blocks = vec![ScannedBlock {
+            number: 4320,
+            hash: make_tx_hash(4320),
+        }];
         let msg = ReceivedPayments {
             timestamp: SystemTime::now(),
             payments: vec![],
             new_start_block,
+            rewind_to_block_opt: None,
+            scanned_blocks: scanned_blocks.clone(),
             response_skeleton_opt: None,
         };
 
@@ -3088,11 +3605,43 @@ mod tests {
         assert_eq!(message_opt, None);
         let set_start_block_params = set_start_block_params_arc.lock().unwrap();
         assert_eq!(*set_start_block_params, vec![Some(4321)]);
+        let set_scanned_blocks_params = set_scanned_blocks_params_arc.lock().unwrap();
+        assert_eq!(*set_scanned_blocks_params, vec![scanned_blocks]);
         TestLogHandler::new().exists_log_containing(&format!(
             "INFO: {test_name}: No newly received payments were detected during the scanning process."
         ));
     }
 
+    #[test]
+    fn no_transactions_received_and_scanned_blocks_setting_fails_only_with_a_warning() {
+        init_test_logging();
+        let test_name =
+            "no_transactions_received_and_scanned_blocks_setting_fails_only_with_a_warning";
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_start_block_result(Ok(()))
+            .set_scanned_blocks_result(Err(PersistentConfigError::DatabaseError(
+                "Fatigue".to_string(),
+            )));
+        let mut subject = ReceivableScannerBuilder::new()
+            .persistent_configuration(persistent_config)
+            .build();
+        let msg = ReceivedPayments {
+            timestamp: SystemTime::now(),
+            payments: vec![],
+            new_start_block: 4321,
+            rewind_to_block_opt: None,
+            scanned_blocks: vec![],
+            response_skeleton_opt: None,
+        };
+
+        let message_opt = subject.finish_scan(msg, &Logger::new(test_name));
+
+        assert_eq!(message_opt, None);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Failed to remember the scanned blocks: DatabaseError(\"Fatigue\")"
+        ));
+    }
+
     #[test]
     #[should_panic(expected = "Attempt to set new start block to 6709 failed due to: \
     UninterpretableValue(\"Illiterate database manager\")")]
@@ -3115,6 +3664,8 @@ mod tests {
             timestamp: now,
             payments: vec![],
             new_start_block,
+            rewind_to_block_opt: None,
+            scanned_blocks: vec![],
             response_skeleton_opt: None,
         };
         // Not necessary, rather for preciseness
@@ -3130,6 +3681,7 @@ mod tests {
         let now = SystemTime::now();
         let more_money_received_params_arc = Arc::new(Mutex::new(vec![]));
         let set_start_block_from_txn_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_scanned_blocks_from_txn_params_arc = Arc::new(Mutex::new(vec![]));
         let commit_params_arc = Arc::new(Mutex::new(vec![]));
         let transaction_id = ArbitraryIdStamp::new();
         let txn_inner_builder = TransactionInnerWrapperMockBuilder::default()
@@ -3140,7 +3692,9 @@ mod tests {
         let persistent_config = PersistentConfigurationMock::new()
             .start_block_result(Ok(None))
             .set_start_block_from_txn_params(&set_start_block_from_txn_params_arc)
-            .set_start_block_from_txn_result(Ok(()));
+            .set_start_block_from_txn_result(Ok(()))
+            .set_scanned_blocks_from_txn_params(&set_scanned_blocks_from_txn_params_arc)
+            .set_scanned_blocks_from_txn_result(Ok(()));
         let receivable_dao = ReceivableDaoMock::new()
             .more_money_received_params(&more_money_received_params_arc)
             .more_money_received_result(transaction);
@@ -3163,10 +3717,16 @@ mod tests {
                 wei_amount: 3_333_345,
             },
         ];
+        let scanned_blocks = vec![ScannedBlock {
+            number: 7890122,
+            hash: make_tx_hash(7890122),
+        }];
         let msg = ReceivedPayments {
             timestamp: now,
             payments: receivables.clone(),
             new_start_block: 7890123,
+            rewind_to_block_opt: None,
+            scanned_blocks: scanned_blocks.clone(),
             response_skeleton_opt: None,
         };
         subject.mark_as_started(SystemTime::now());
@@ -3181,12 +3741,18 @@ mod tests {
         assert_eq!(subject.scan_started_at(), None);
         assert_eq!(total_paid_receivable, 2_222_123_123 + 45_780 + 3_333_345);
         let more_money_received_params = more_money_received_params_arc.lock().unwrap();
-        assert_eq!(*more_money_received_params, vec![(now, receivables)]);
+        assert_eq!(*more_money_received_params, vec![(now, receivables, None)]);
         let set_by_guest_transaction_params = set_start_block_from_txn_params_arc.lock().unwrap();
         assert_eq!(
             *set_by_guest_transaction_params,
             vec![(Some(7890123u64), transaction_id)]
         );
+        let set_scanned_blocks_from_txn_params =
+            set_scanned_blocks_from_txn_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_scanned_blocks_from_txn_params,
+            vec![(scanned_blocks, transaction_id)]
+        );
         let commit_params = commit_params_arc.lock().unwrap();
         assert_eq!(*commit_params, vec![()]);
         TestLogHandler::new().exists_log_matching(
@@ -3194,6 +3760,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn receivable_scanner_takes_back_orphaned_payments_even_without_new_ones() {
+        let more_money_received_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_start_block_from_txn_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_scanned_blocks_from_txn_params_arc = Arc::new(Mutex::new(vec![]));
+        let commit_params_arc = Arc::new(Mutex::new(vec![]));
+        let transaction_id = ArbitraryIdStamp::new();
+        let txn_inner_builder = TransactionInnerWrapperMockBuilder::default()
+            .commit_params(&commit_params_arc)
+            .commit_result(Ok(()))
+            .set_arbitrary_id_stamp(transaction_id);
+        let transaction = TransactionSafeWrapper::new_with_builder(txn_inner_builder);
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_start_block_from_txn_params(&set_start_block_from_txn_params_arc)
+            .set_start_block_from_txn_result(Ok(()))
+            .set_scanned_blocks_from_txn_params(&set_scanned_blocks_from_txn_params_arc)
+            .set_scanned_blocks_from_txn_result(Ok(()));
+        let receivable_dao = ReceivableDaoMock::new()
+            .more_money_received_params(&more_money_received_params_arc)
+            .more_money_received_result(transaction);
+        let mut subject = ReceivableScannerBuilder::new()
+            .receivable_dao(receivable_dao)
+            .persistent_configuration(persistent_config)
+            .build();
+        let now = SystemTime::now();
+        let scanned_blocks = vec![
+            ScannedBlock {
+                number: 1000,
+                hash: make_tx_hash(1000),
+            },
+            ScannedBlock {
+                number: 1020,
+                hash: make_tx_hash(1020),
+            },
+        ];
+        let msg = ReceivedPayments {
+            timestamp: now,
+            payments: vec![],
+            new_start_block: 1021,
+            rewind_to_block_opt: Some(1001),
+            scanned_blocks: scanned_blocks.clone(),
+            response_skeleton_opt: None,
+        };
+
+        let message_opt = subject.finish_scan(msg, &Logger::new("test"));
+
+        assert_eq!(message_opt, None);
+        let more_money_received_params = more_money_received_params_arc.lock().unwrap();
+        assert_eq!(*more_money_received_params, vec![(now, vec![], Some(1001))]);
+        let set_start_block_from_txn_params = set_start_block_from_txn_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_start_block_from_txn_params,
+            vec![(Some(1021), transaction_id)]
+        );
+        let set_scanned_blocks_from_txn_params =
+            set_scanned_blocks_from_txn_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_scanned_blocks_from_txn_params,
+            vec![(scanned_blocks, transaction_id)]
+        );
+        let commit_params = commit_params_arc.lock().unwrap();
+        assert_eq!(*commit_params, vec![()]);
+    }
+
     #[test]
     #[should_panic(expected = "Attempt to set new start block to 7890123 failed due to: \
     DatabaseError(\"Fatigue\")")]
@@ -3222,6 +3852,8 @@ mod tests {
             timestamp: now,
             payments: receivables,
             new_start_block: 7890123,
+            rewind_to_block_opt: None,
+            scanned_blocks: vec![],
             response_skeleton_opt: None,
         };
         // Not necessary, rather for preciseness
@@ -3251,7 +3883,8 @@ mod tests {
         let transaction = TransactionSafeWrapper::new_with_builder(txn_inner_builder);
         let persistent_config = PersistentConfigurationMock::new()
             .start_block_result(Ok(None))
-            .set_start_block_from_txn_result(Ok(()));
+            .set_start_block_from_txn_result(Ok(()))
+            .set_scanned_blocks_from_txn_result(Ok(()));
         let receivable_dao = ReceivableDaoMock::new().more_money_received_result(transaction);
         let mut subject = ReceivableScannerBuilder::new()
             .receivable_dao(receivable_dao)
@@ -3266,6 +3899,8 @@ mod tests {
             timestamp: now,
             payments: receivables,
             new_start_block: 7890123,
+            rewind_to_block_opt: None,
+            scanned_blocks: vec![],
             response_skeleton_opt: None,
         };
         // Not necessary, rather for preciseness
@@ -317,6 +317,7 @@ pub mod pending_payable_scanner_utils {
         pub still_pending: Vec<PendingPayableId>,
         pub failures: Vec<PendingPayableId>,
         pub confirmed: Vec<PendingPayableFingerprint>,
+        pub abandoned: Vec<PendingPayableFingerprint>,
     }
 
     pub fn elapsed_in_ms(timestamp: SystemTime) -> u128 {
@@ -326,6 +327,13 @@ pub mod pending_payable_scanner_utils {
             .as_millis()
     }
 
+    pub fn age_in_secs(timestamp: SystemTime) -> u64 {
+        timestamp
+            .elapsed()
+            .expect("time calculation for elapsed failed")
+            .as_secs()
+    }
+
     pub fn handle_none_status(
         mut scan_report: PendingPayableScanReport,
         fingerprint: PendingPayableFingerprint,
@@ -2,7 +2,19 @@
 
 #![cfg(test)]
 
+use crate::accountant::db_access_objects::address_book_dao::{
+    AddressBookDao, AddressBookDaoFactory, AddressBookEntry,
+};
+use crate::accountant::db_access_objects::audit_log_dao::{
+    AuditLogDao, AuditLogDaoFactory, BalanceAuditRecord, BalanceChange,
+};
 use crate::accountant::db_access_objects::banned_dao::{BannedDao, BannedDaoFactory};
+use crate::accountant::db_access_objects::earnings_history_dao::{
+    DailyEarnings, DailyEarningsRecord, EarningsHistoryDao, EarningsHistoryDaoFactory,
+};
+use crate::accountant::db_access_objects::maintenance_dao::{
+    DatabaseStats, MaintenanceDao, MaintenanceDaoError, MaintenanceDaoFactory,
+};
 use crate::accountant::db_access_objects::payable_dao::{
     PayableAccount, PayableDao, PayableDaoError, PayableDaoFactory,
 };
@@ -14,6 +26,7 @@ use crate::accountant::db_access_objects::receivable_dao::{
 };
 use crate::accountant::db_access_objects::utils::{from_time_t, to_time_t, CustomQuery};
 use crate::accountant::payment_adjuster::{Adjustment, AnalysisError, PaymentAdjuster};
+use crate::accountant::pricing::{FiatPrice, PriceFeed};
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::{
     BlockchainAgentWithContextMessage, QualifiedPayablesMessage,
 };
@@ -26,7 +39,8 @@ use crate::accountant::scanners::{
     ReceivableScanner, ScanSchedulers, Scanner,
 };
 use crate::accountant::{
-    gwei_to_wei, Accountant, ResponseSkeleton, SentPayables, DEFAULT_PENDING_TOO_LONG_SEC,
+    gwei_to_wei, Accountant, ResponseSkeleton, SentPayables, DEFAULT_MAX_PENDING_ATTEMPTS,
+    DEFAULT_PENDING_TOO_LONG_SEC,
 };
 use crate::blockchain::blockchain_bridge::PendingPayableFingerprint;
 use crate::blockchain::blockchain_interface::data_structures::BlockchainTransaction;
@@ -36,13 +50,13 @@ use crate::database::rusqlite_wrappers::TransactionSafeWrapper;
 use crate::db_config::config_dao::{ConfigDao, ConfigDaoFactory};
 use crate::db_config::mocks::ConfigDaoMock;
 use crate::sub_lib::accountant::{DaoFactories, FinancialStatistics};
-use crate::sub_lib::accountant::{MessageIdGenerator, PaymentThresholds};
+use crate::sub_lib::accountant::{MessageIdGenerator, PaymentThresholds, PendingPayableLimits};
 use crate::sub_lib::blockchain_bridge::OutboundPaymentsInstructions;
 use crate::sub_lib::utils::NotifyLaterHandle;
 use crate::sub_lib::wallet::Wallet;
 use crate::test_utils::make_wallet;
 use crate::test_utils::persistent_configuration_mock::PersistentConfigurationMock;
-use crate::test_utils::unshared_test_utils::make_bc_with_defaults;
+use crate::test_utils::unshared_test_utils::{make_bc_with_defaults, BootstrapperConfigBuilder};
 use actix::{Message, System};
 use ethereum_types::H256;
 use itertools::Either;
@@ -98,6 +112,10 @@ pub struct AccountantBuilder {
     config_opt: Option<BootstrapperConfig>,
     consuming_wallet_opt: Option<Wallet>,
     logger_opt: Option<Logger>,
+    address_book_dao_factory_opt: Option<AddressBookDaoFactoryMock>,
+    audit_log_dao_factory_opt: Option<AuditLogDaoFactoryMock>,
+    earnings_history_dao_factory_opt: Option<EarningsHistoryDaoFactoryMock>,
+    maintenance_dao_factory_opt: Option<MaintenanceDaoFactoryMock>,
     payable_dao_factory_opt: Option<PayableDaoFactoryMock>,
     receivable_dao_factory_opt: Option<ReceivableDaoFactoryMock>,
     pending_payable_dao_factory_opt: Option<PendingPayableDaoFactoryMock>,
@@ -111,6 +129,10 @@ impl Default for AccountantBuilder {
             config_opt: None,
             consuming_wallet_opt: None,
             logger_opt: None,
+            address_book_dao_factory_opt: None,
+            audit_log_dao_factory_opt: None,
+            earnings_history_dao_factory_opt: None,
+            maintenance_dao_factory_opt: None,
             payable_dao_factory_opt: None,
             receivable_dao_factory_opt: None,
             pending_payable_dao_factory_opt: None,
@@ -334,6 +356,30 @@ impl AccountantBuilder {
         self
     }
 
+    pub fn address_book_dao(mut self, address_book_dao: AddressBookDaoMock) -> Self {
+        self.address_book_dao_factory_opt =
+            Some(AddressBookDaoFactoryMock::new().make_result(address_book_dao));
+        self
+    }
+
+    pub fn audit_log_dao(mut self, audit_log_dao: AuditLogDaoMock) -> Self {
+        self.audit_log_dao_factory_opt =
+            Some(AuditLogDaoFactoryMock::new().make_result(audit_log_dao));
+        self
+    }
+
+    pub fn earnings_history_dao(mut self, earnings_history_dao: EarningsHistoryDaoMock) -> Self {
+        self.earnings_history_dao_factory_opt =
+            Some(EarningsHistoryDaoFactoryMock::new().make_result(earnings_history_dao));
+        self
+    }
+
+    pub fn maintenance_dao(mut self, maintenance_dao: MaintenanceDaoMock) -> Self {
+        self.maintenance_dao_factory_opt =
+            Some(MaintenanceDaoFactoryMock::new().make_result(maintenance_dao));
+        self
+    }
+
     pub fn config_dao(mut self, config_dao: ConfigDaoMock) -> Self {
         self.config_dao_factory_opt = Some(ConfigDaoFactoryMock::new().make_result(config_dao));
         self
@@ -341,6 +387,19 @@ impl AccountantBuilder {
 
     pub fn build(self) -> Accountant {
         let config = self.config_opt.unwrap_or(make_bc_with_defaults());
+        let address_book_dao_factory = self.address_book_dao_factory_opt.unwrap_or(
+            AddressBookDaoFactoryMock::new()
+                .make_result(AddressBookDaoMock::new().entries_result(vec![])),
+        );
+        let audit_log_dao_factory = self
+            .audit_log_dao_factory_opt
+            .unwrap_or(AuditLogDaoFactoryMock::new().make_result(AuditLogDaoMock::new()));
+        let earnings_history_dao_factory = self.earnings_history_dao_factory_opt.unwrap_or(
+            EarningsHistoryDaoFactoryMock::new().make_result(EarningsHistoryDaoMock::new()),
+        );
+        let maintenance_dao_factory = self
+            .maintenance_dao_factory_opt
+            .unwrap_or(MaintenanceDaoFactoryMock::new().make_result(MaintenanceDaoMock::new()));
         let payable_dao_factory = self.payable_dao_factory_opt.unwrap_or(
             PayableDaoFactoryMock::new()
                 .make_result(PayableDaoMock::new())
@@ -367,6 +426,10 @@ impl AccountantBuilder {
         let mut accountant = Accountant::new(
             config,
             DaoFactories {
+                address_book_dao_factory: Box::new(address_book_dao_factory),
+                audit_log_dao_factory: Box::new(audit_log_dao_factory),
+                earnings_history_dao_factory: Box::new(earnings_history_dao_factory),
+                maintenance_dao_factory: Box::new(maintenance_dao_factory),
                 payable_dao_factory: Box::new(payable_dao_factory),
                 pending_payable_dao_factory: Box::new(pending_payable_dao_factory),
                 receivable_dao_factory: Box::new(receivable_dao_factory),
@@ -457,6 +520,142 @@ impl ReceivableDaoFactoryMock {
     }
 }
 
+pub struct AuditLogDaoFactoryMock {
+    make_params: Arc<Mutex<Vec<()>>>,
+    make_results: RefCell<Vec<Box<dyn AuditLogDao>>>,
+}
+
+impl AuditLogDaoFactory for AuditLogDaoFactoryMock {
+    fn make(&self) -> Box<dyn AuditLogDao> {
+        if self.make_results.borrow().len() == 0 {
+            panic!("AuditLogDao Missing.")
+        };
+        self.make_params.lock().unwrap().push(());
+        self.make_results.borrow_mut().remove(0)
+    }
+}
+
+impl AuditLogDaoFactoryMock {
+    pub fn new() -> Self {
+        Self {
+            make_params: Arc::new(Mutex::new(vec![])),
+            make_results: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn make_params(mut self, params: &Arc<Mutex<Vec<()>>>) -> Self {
+        self.make_params = params.clone();
+        self
+    }
+
+    pub fn make_result(self, result: AuditLogDaoMock) -> Self {
+        self.make_results.borrow_mut().push(Box::new(result));
+        self
+    }
+}
+
+pub struct AddressBookDaoFactoryMock {
+    make_params: Arc<Mutex<Vec<()>>>,
+    make_results: RefCell<Vec<Box<dyn AddressBookDao>>>,
+}
+
+impl AddressBookDaoFactory for AddressBookDaoFactoryMock {
+    fn make(&self) -> Box<dyn AddressBookDao> {
+        if self.make_results.borrow().len() == 0 {
+            panic!("AddressBookDao Missing.")
+        };
+        self.make_params.lock().unwrap().push(());
+        self.make_results.borrow_mut().remove(0)
+    }
+}
+
+impl AddressBookDaoFactoryMock {
+    pub fn new() -> Self {
+        Self {
+            make_params: Arc::new(Mutex::new(vec![])),
+            make_results: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn make_params(mut self, params: &Arc<Mutex<Vec<()>>>) -> Self {
+        self.make_params = params.clone();
+        self
+    }
+
+    pub fn make_result(self, result: AddressBookDaoMock) -> Self {
+        self.make_results.borrow_mut().push(Box::new(result));
+        self
+    }
+}
+
+pub struct EarningsHistoryDaoFactoryMock {
+    make_params: Arc<Mutex<Vec<()>>>,
+    make_results: RefCell<Vec<Box<dyn EarningsHistoryDao>>>,
+}
+
+impl EarningsHistoryDaoFactory for EarningsHistoryDaoFactoryMock {
+    fn make(&self) -> Box<dyn EarningsHistoryDao> {
+        if self.make_results.borrow().len() == 0 {
+            panic!("EarningsHistoryDao Missing.")
+        };
+        self.make_params.lock().unwrap().push(());
+        self.make_results.borrow_mut().remove(0)
+    }
+}
+
+impl EarningsHistoryDaoFactoryMock {
+    pub fn new() -> Self {
+        Self {
+            make_params: Arc::new(Mutex::new(vec![])),
+            make_results: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn make_params(mut self, params: &Arc<Mutex<Vec<()>>>) -> Self {
+        self.make_params = params.clone();
+        self
+    }
+
+    pub fn make_result(self, result: EarningsHistoryDaoMock) -> Self {
+        self.make_results.borrow_mut().push(Box::new(result));
+        self
+    }
+}
+
+pub struct MaintenanceDaoFactoryMock {
+    make_params: Arc<Mutex<Vec<()>>>,
+    make_results: RefCell<Vec<Box<dyn MaintenanceDao>>>,
+}
+
+impl MaintenanceDaoFactory for MaintenanceDaoFactoryMock {
+    fn make(&self) -> Box<dyn MaintenanceDao> {
+        if self.make_results.borrow().len() == 0 {
+            panic!("MaintenanceDao Missing.")
+        };
+        self.make_params.lock().unwrap().push(());
+        self.make_results.borrow_mut().remove(0)
+    }
+}
+
+impl MaintenanceDaoFactoryMock {
+    pub fn new() -> Self {
+        Self {
+            make_params: Arc::new(Mutex::new(vec![])),
+            make_results: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn make_params(mut self, params: &Arc<Mutex<Vec<()>>>) -> Self {
+        self.make_params = params.clone();
+        self
+    }
+
+    pub fn make_result(self, result: MaintenanceDaoMock) -> Self {
+        self.make_results.borrow_mut().push(Box::new(result));
+        self
+    }
+}
+
 pub struct BannedDaoFactoryMock {
     make_params: Arc<Mutex<Vec<()>>>,
     make_results: RefCell<Vec<Box<dyn BannedDao>>>,
@@ -530,11 +729,17 @@ pub struct PayableDaoMock {
     non_pending_payables_results: RefCell<Vec<Vec<PayableAccount>>>,
     mark_pending_payables_rowids_params: Arc<Mutex<Vec<Vec<(Wallet, u64)>>>>,
     mark_pending_payables_rowids_results: RefCell<Vec<Result<(), PayableDaoError>>>,
+    unmark_pending_payables_rowids_params: Arc<Mutex<Vec<Vec<u64>>>>,
+    unmark_pending_payables_rowids_results: RefCell<Vec<Result<(), PayableDaoError>>>,
     transactions_confirmed_params: Arc<Mutex<Vec<Vec<PendingPayableFingerprint>>>>,
     transactions_confirmed_results: RefCell<Vec<Result<(), PayableDaoError>>>,
+    apply_replicated_change_params: Arc<Mutex<Vec<BalanceChange>>>,
+    apply_replicated_change_results: RefCell<Vec<Result<(), PayableDaoError>>>,
     custom_query_params: Arc<Mutex<Vec<CustomQuery<u64>>>>,
     custom_query_result: RefCell<Vec<Option<Vec<PayableAccount>>>>,
     total_results: RefCell<Vec<u128>>,
+    account_status_params: Arc<Mutex<Vec<Wallet>>>,
+    account_status_results: RefCell<Vec<Option<PayableAccount>>>,
 }
 
 impl PayableDao for PayableDaoMock {
@@ -569,6 +774,16 @@ impl PayableDao for PayableDaoMock {
             .remove(0)
     }
 
+    fn unmark_pending_payables_rowids(&self, rowids: &[u64]) -> Result<(), PayableDaoError> {
+        self.unmark_pending_payables_rowids_params
+            .lock()
+            .unwrap()
+            .push(rowids.to_vec());
+        self.unmark_pending_payables_rowids_results
+            .borrow_mut()
+            .remove(0)
+    }
+
     fn transactions_confirmed(
         &self,
         confirmed_payables: &[PendingPayableFingerprint],
@@ -585,6 +800,14 @@ impl PayableDao for PayableDaoMock {
         self.non_pending_payables_results.borrow_mut().remove(0)
     }
 
+    fn apply_replicated_change(&self, change: &BalanceChange) -> Result<(), PayableDaoError> {
+        self.apply_replicated_change_params
+            .lock()
+            .unwrap()
+            .push(change.clone());
+        self.apply_replicated_change_results.borrow_mut().remove(0)
+    }
+
     fn custom_query(&self, custom_query: CustomQuery<u64>) -> Option<Vec<PayableAccount>> {
         self.custom_query_params.lock().unwrap().push(custom_query);
         self.custom_query_result.borrow_mut().remove(0)
@@ -594,9 +817,12 @@ impl PayableDao for PayableDaoMock {
         self.total_results.borrow_mut().remove(0)
     }
 
-    fn account_status(&self, _wallet: &Wallet) -> Option<PayableAccount> {
-        //test-only trait member
-        intentionally_blank!()
+    fn account_status(&self, wallet: &Wallet) -> Option<PayableAccount> {
+        self.account_status_params
+            .lock()
+            .unwrap()
+            .push(wallet.clone());
+        self.account_status_results.borrow_mut().remove(0)
     }
 }
 
@@ -628,6 +854,21 @@ impl PayableDaoMock {
         self
     }
 
+    pub fn apply_replicated_change_params(
+        mut self,
+        params: &Arc<Mutex<Vec<BalanceChange>>>,
+    ) -> Self {
+        self.apply_replicated_change_params = params.clone();
+        self
+    }
+
+    pub fn apply_replicated_change_result(self, result: Result<(), PayableDaoError>) -> Self {
+        self.apply_replicated_change_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
+
     pub fn mark_pending_payables_rowids_params(
         mut self,
         params: &Arc<Mutex<Vec<Vec<(Wallet, u64)>>>>,
@@ -643,6 +884,24 @@ impl PayableDaoMock {
         self
     }
 
+    pub fn unmark_pending_payables_rowids_params(
+        mut self,
+        params: &Arc<Mutex<Vec<Vec<u64>>>>,
+    ) -> Self {
+        self.unmark_pending_payables_rowids_params = params.clone();
+        self
+    }
+
+    pub fn unmark_pending_payables_rowids_result(
+        self,
+        result: Result<(), PayableDaoError>,
+    ) -> Self {
+        self.unmark_pending_payables_rowids_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
+
     pub fn transactions_confirmed_params(
         mut self,
         params: &Arc<Mutex<Vec<Vec<PendingPayableFingerprint>>>>,
@@ -672,18 +931,33 @@ impl PayableDaoMock {
         self.total_results.borrow_mut().push(result);
         self
     }
+
+    pub fn account_status_params(mut self, params: &Arc<Mutex<Vec<Wallet>>>) -> Self {
+        self.account_status_params = params.clone();
+        self
+    }
+
+    pub fn account_status_result(self, result: Option<PayableAccount>) -> Self {
+        self.account_status_results.borrow_mut().push(result);
+        self
+    }
 }
 
 #[derive(Debug, Default)]
 pub struct ReceivableDaoMock {
     more_money_receivable_parameters: Arc<Mutex<Vec<(SystemTime, Wallet, u128)>>>,
     more_money_receivable_results: RefCell<Vec<Result<(), ReceivableDaoError>>>,
-    more_money_received_parameters: Arc<Mutex<Vec<(SystemTime, Vec<BlockchainTransaction>)>>>,
+    more_sampled_money_receivable_params: Arc<Mutex<Vec<(SystemTime, Wallet, u128, u32)>>>,
+    more_sampled_money_receivable_results: RefCell<Vec<Result<(), ReceivableDaoError>>>,
+    more_money_received_parameters:
+        Arc<Mutex<Vec<(SystemTime, Vec<BlockchainTransaction>, Option<u64>)>>>,
     more_money_received_results: RefCell<Vec<TransactionSafeWrapper<'static>>>,
     new_delinquencies_parameters: Arc<Mutex<Vec<(SystemTime, PaymentThresholds)>>>,
     new_delinquencies_results: RefCell<Vec<Vec<ReceivableAccount>>>,
     paid_delinquencies_parameters: Arc<Mutex<Vec<PaymentThresholds>>>,
     paid_delinquencies_results: RefCell<Vec<Vec<ReceivableAccount>>>,
+    apply_replicated_change_params: Arc<Mutex<Vec<BalanceChange>>>,
+    apply_replicated_change_results: RefCell<Vec<Result<(), ReceivableDaoError>>>,
     custom_query_params: Arc<Mutex<Vec<CustomQuery<i64>>>>,
     custom_query_result: RefCell<Vec<Option<Vec<ReceivableAccount>>>>,
     total_results: RefCell<Vec<i128>>,
@@ -703,15 +977,33 @@ impl ReceivableDao for ReceivableDaoMock {
         self.more_money_receivable_results.borrow_mut().remove(0)
     }
 
+    fn more_sampled_money_receivable(
+        &self,
+        now: SystemTime,
+        wallet: &Wallet,
+        amount: u128,
+        sample_size: u32,
+    ) -> Result<(), ReceivableDaoError> {
+        self.more_sampled_money_receivable_params
+            .lock()
+            .unwrap()
+            .push((now, wallet.clone(), amount, sample_size));
+        self.more_sampled_money_receivable_results
+            .borrow_mut()
+            .remove(0)
+    }
+
     fn more_money_received(
         &mut self,
         now: SystemTime,
         transactions: &[BlockchainTransaction],
+        rewind_to_block_opt: Option<u64>,
     ) -> TransactionSafeWrapper {
-        self.more_money_received_parameters
-            .lock()
-            .unwrap()
-            .push((now, transactions.to_vec()));
+        self.more_money_received_parameters.lock().unwrap().push((
+            now,
+            transactions.to_vec(),
+            rewind_to_block_opt,
+        ));
         self.more_money_received_results.borrow_mut().remove(0)
     }
 
@@ -735,6 +1027,14 @@ impl ReceivableDao for ReceivableDaoMock {
         self.paid_delinquencies_results.borrow_mut().remove(0)
     }
 
+    fn apply_replicated_change(&self, change: &BalanceChange) -> Result<(), ReceivableDaoError> {
+        self.apply_replicated_change_params
+            .lock()
+            .unwrap()
+            .push(change.clone());
+        self.apply_replicated_change_results.borrow_mut().remove(0)
+    }
+
     fn custom_query(&self, custom_query: CustomQuery<i64>) -> Option<Vec<ReceivableAccount>> {
         self.custom_query_params.lock().unwrap().push(custom_query);
         self.custom_query_result.borrow_mut().remove(0)
@@ -768,9 +1068,27 @@ impl ReceivableDaoMock {
         self
     }
 
+    pub fn more_sampled_money_receivable_params(
+        mut self,
+        params: &Arc<Mutex<Vec<(SystemTime, Wallet, u128, u32)>>>,
+    ) -> Self {
+        self.more_sampled_money_receivable_params = params.clone();
+        self
+    }
+
+    pub fn more_sampled_money_receivable_result(
+        self,
+        result: Result<(), ReceivableDaoError>,
+    ) -> Self {
+        self.more_sampled_money_receivable_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
+
     pub fn more_money_received_params(
         mut self,
-        parameters: &Arc<Mutex<Vec<(SystemTime, Vec<BlockchainTransaction>)>>>,
+        parameters: &Arc<Mutex<Vec<(SystemTime, Vec<BlockchainTransaction>, Option<u64>)>>>,
     ) -> Self {
         self.more_money_received_parameters = parameters.clone();
         self
@@ -781,6 +1099,21 @@ impl ReceivableDaoMock {
         self
     }
 
+    pub fn apply_replicated_change_params(
+        mut self,
+        params: &Arc<Mutex<Vec<BalanceChange>>>,
+    ) -> Self {
+        self.apply_replicated_change_params = params.clone();
+        self
+    }
+
+    pub fn apply_replicated_change_result(self, result: Result<(), ReceivableDaoError>) -> Self {
+        self.apply_replicated_change_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
+
     pub fn new_delinquencies_parameters(
         mut self,
         parameters: &Arc<Mutex<Vec<(SystemTime, PaymentThresholds)>>>,
@@ -823,6 +1156,197 @@ impl ReceivableDaoMock {
     }
 }
 
+#[derive(Debug, Default)]
+pub struct AuditLogDaoMock {
+    records_params: Arc<Mutex<Vec<(u64, u16)>>>,
+    records_results: RefCell<Vec<Vec<BalanceAuditRecord>>>,
+    count_results: RefCell<Vec<u64>>,
+}
+
+impl AuditLogDao for AuditLogDaoMock {
+    fn records(&self, offset: u64, limit: u16) -> Vec<BalanceAuditRecord> {
+        self.records_params.lock().unwrap().push((offset, limit));
+        self.records_results.borrow_mut().remove(0)
+    }
+
+    fn count(&self) -> u64 {
+        self.count_results.borrow_mut().remove(0)
+    }
+}
+
+impl AuditLogDaoMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn records_params(mut self, params: &Arc<Mutex<Vec<(u64, u16)>>>) -> Self {
+        self.records_params = params.clone();
+        self
+    }
+
+    pub fn records_result(self, result: Vec<BalanceAuditRecord>) -> Self {
+        self.records_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn count_result(self, result: u64) -> Self {
+        self.count_results.borrow_mut().push(result);
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct AddressBookDaoMock {
+    set_params: Arc<Mutex<Vec<AddressBookEntry>>>,
+    remove_params: Arc<Mutex<Vec<Wallet>>>,
+    entries_results: RefCell<Vec<Vec<AddressBookEntry>>>,
+}
+
+impl AddressBookDao for AddressBookDaoMock {
+    fn set(&self, entry: &AddressBookEntry) {
+        self.set_params.lock().unwrap().push(entry.clone());
+    }
+
+    fn remove(&self, wallet: &Wallet) {
+        self.remove_params.lock().unwrap().push(wallet.clone());
+    }
+
+    fn entries(&self) -> Vec<AddressBookEntry> {
+        self.entries_results.borrow_mut().remove(0)
+    }
+}
+
+impl AddressBookDaoMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_params(mut self, params: &Arc<Mutex<Vec<AddressBookEntry>>>) -> Self {
+        self.set_params = params.clone();
+        self
+    }
+
+    pub fn remove_params(mut self, params: &Arc<Mutex<Vec<Wallet>>>) -> Self {
+        self.remove_params = params.clone();
+        self
+    }
+
+    pub fn entries_result(self, result: Vec<AddressBookEntry>) -> Self {
+        self.entries_results.borrow_mut().push(result);
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct EarningsHistoryDaoMock {
+    add_params: Arc<Mutex<Vec<(SystemTime, DailyEarnings)>>>,
+    history_params: Arc<Mutex<Vec<(SystemTime, SystemTime)>>>,
+    history_results: RefCell<Vec<Vec<DailyEarningsRecord>>>,
+}
+
+impl EarningsHistoryDao for EarningsHistoryDaoMock {
+    fn add(&self, timestamp: SystemTime, earnings: &DailyEarnings) {
+        self.add_params
+            .lock()
+            .unwrap()
+            .push((timestamp, earnings.clone()));
+    }
+
+    fn history(&self, start: SystemTime, end: SystemTime) -> Vec<DailyEarningsRecord> {
+        self.history_params.lock().unwrap().push((start, end));
+        self.history_results.borrow_mut().remove(0)
+    }
+}
+
+impl EarningsHistoryDaoMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_params(mut self, params: &Arc<Mutex<Vec<(SystemTime, DailyEarnings)>>>) -> Self {
+        self.add_params = params.clone();
+        self
+    }
+
+    pub fn history_params(mut self, params: &Arc<Mutex<Vec<(SystemTime, SystemTime)>>>) -> Self {
+        self.history_params = params.clone();
+        self
+    }
+
+    pub fn history_result(self, result: Vec<DailyEarningsRecord>) -> Self {
+        self.history_results.borrow_mut().push(result);
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MaintenanceDaoMock {
+    stats_results: RefCell<Vec<Result<DatabaseStats, MaintenanceDaoError>>>,
+    vacuum_and_analyze_params: Arc<Mutex<Vec<()>>>,
+    vacuum_and_analyze_results: RefCell<Vec<Result<(), MaintenanceDaoError>>>,
+}
+
+impl MaintenanceDao for MaintenanceDaoMock {
+    fn stats(&self) -> Result<DatabaseStats, MaintenanceDaoError> {
+        self.stats_results.borrow_mut().remove(0)
+    }
+
+    fn vacuum_and_analyze(&self) -> Result<(), MaintenanceDaoError> {
+        self.vacuum_and_analyze_params.lock().unwrap().push(());
+        self.vacuum_and_analyze_results.borrow_mut().remove(0)
+    }
+}
+
+impl MaintenanceDaoMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn stats_result(self, result: Result<DatabaseStats, MaintenanceDaoError>) -> Self {
+        self.stats_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn vacuum_and_analyze_params(mut self, params: &Arc<Mutex<Vec<()>>>) -> Self {
+        self.vacuum_and_analyze_params = params.clone();
+        self
+    }
+
+    pub fn vacuum_and_analyze_result(self, result: Result<(), MaintenanceDaoError>) -> Self {
+        self.vacuum_and_analyze_results.borrow_mut().push(result);
+        self
+    }
+}
+
+#[derive(Default)]
+pub struct PriceFeedMock {
+    fetch_params: Arc<Mutex<Vec<()>>>,
+    fetch_results: RefCell<Vec<Result<FiatPrice, String>>>,
+}
+
+impl PriceFeed for PriceFeedMock {
+    fn fetch(&self) -> Result<FiatPrice, String> {
+        self.fetch_params.lock().unwrap().push(());
+        self.fetch_results.borrow_mut().remove(0)
+    }
+}
+
+impl PriceFeedMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn fetch_params(mut self, params: &Arc<Mutex<Vec<()>>>) -> Self {
+        self.fetch_params = params.clone();
+        self
+    }
+
+    pub fn fetch_result(self, result: Result<FiatPrice, String>) -> Self {
+        self.fetch_results.borrow_mut().push(result);
+        self
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct BannedDaoMock {
     ban_list_parameters: Arc<Mutex<Vec<()>>>,
@@ -873,16 +1397,16 @@ impl BannedDaoMock {
 }
 
 pub fn bc_from_earning_wallet(earning_wallet: Wallet) -> BootstrapperConfig {
-    let mut bc = make_bc_with_defaults();
-    bc.earning_wallet = earning_wallet;
-    bc
+    BootstrapperConfigBuilder::new()
+        .earning_wallet(earning_wallet)
+        .build()
 }
 
 pub fn bc_from_wallets(consuming_wallet: Wallet, earning_wallet: Wallet) -> BootstrapperConfig {
-    let mut bc = make_bc_with_defaults();
-    bc.consuming_wallet_opt = Some(consuming_wallet);
-    bc.earning_wallet = earning_wallet;
-    bc
+    BootstrapperConfigBuilder::new()
+        .consuming_wallet(consuming_wallet)
+        .earning_wallet(earning_wallet)
+        .build()
 }
 
 #[derive(Default)]
@@ -1142,6 +1666,7 @@ pub struct PendingPayableScannerBuilder {
     pending_payable_dao: PendingPayableDaoMock,
     payment_thresholds: PaymentThresholds,
     when_pending_too_long_sec: u64,
+    max_pending_attempts: u16,
     financial_statistics: FinancialStatistics,
 }
 
@@ -1152,6 +1677,7 @@ impl PendingPayableScannerBuilder {
             pending_payable_dao: PendingPayableDaoMock::new(),
             payment_thresholds: PaymentThresholds::default(),
             when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC,
+            max_pending_attempts: DEFAULT_MAX_PENDING_ATTEMPTS,
             financial_statistics: FinancialStatistics::default(),
         }
     }
@@ -1171,12 +1697,20 @@ impl PendingPayableScannerBuilder {
         self
     }
 
+    pub fn max_pending_attempts(mut self, attempts: u16) -> Self {
+        self.max_pending_attempts = attempts;
+        self
+    }
+
     pub fn build(self) -> PendingPayableScanner {
         PendingPayableScanner::new(
             Box::new(self.payable_dao),
             Box::new(self.pending_payable_dao),
             Rc::new(self.payment_thresholds),
-            self.when_pending_too_long_sec,
+            PendingPayableLimits {
+                max_attempts: self.max_pending_attempts,
+                max_age_sec: self.when_pending_too_long_sec,
+            },
             Rc::new(RefCell::new(self.financial_statistics)),
         )
     }
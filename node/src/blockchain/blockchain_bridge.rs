@@ -8,21 +8,33 @@ use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::{
 use crate::accountant::{
     ReceivedPayments, ResponseSkeleton, ScanError, SentPayables, SkeletonOptHolder,
 };
-use crate::accountant::{ReportTransactionReceipts, RequestTransactionReceipts};
+use crate::accountant::{
+    ReportPaymentReceipt, ReportTransactionReceipts, RequestTransactionReceipts,
+};
 use crate::actor_system_factory::SubsFactory;
 use crate::blockchain::blockchain_interface::blockchain_interface_null::BlockchainInterfaceNull;
+use crate::blockchain::blockchain_interface::blockchain_interface_web3::tokens_transferred_to;
 use crate::blockchain::blockchain_interface::data_structures::errors::{
     BlockchainError, PayableTransactionError,
 };
-use crate::blockchain::blockchain_interface::data_structures::ProcessedPayableFallible;
+use crate::blockchain::blockchain_interface::data_structures::{
+    ProcessedPayableFallible, ScannedBlock,
+};
 use crate::blockchain::blockchain_interface::BlockchainInterface;
-use crate::blockchain::blockchain_interface_initializer::BlockchainInterfaceInitializer;
+use crate::blockchain::blockchain_interface_initializer::{
+    BlockchainInterfaceFactory, BlockchainInterfaceInitializer,
+};
+use crate::blockchain::blockchain_service_health::BlockchainServiceHealthMonitor;
+use crate::blockchain::nonce_tracker::ConsumingWalletNonceTracker;
+use crate::blockchain::transaction_fee_monitor::TransactionFeeBalanceMonitor;
 use crate::database::db_initializer::{DbInitializationConfig, DbInitializer, DbInitializerReal};
 use crate::db_config::config_dao::ConfigDaoReal;
 use crate::db_config::persistent_configuration::{
     PersistentConfiguration, PersistentConfigurationReal,
 };
-use crate::sub_lib::blockchain_bridge::{BlockchainBridgeSubs, OutboundPaymentsInstructions};
+use crate::sub_lib::blockchain_bridge::{
+    BlockchainBridgeSubs, BlockchainServiceFailover, OutboundPaymentsInstructions,
+};
 use crate::sub_lib::peer_actors::BindMessage;
 use crate::sub_lib::utils::{db_connection_launch_panic, handle_ui_crash_request};
 use crate::sub_lib::wallet::Wallet;
@@ -33,17 +45,27 @@ use actix::Message;
 use actix::{Addr, Recipient};
 use itertools::Itertools;
 use masq_lib::blockchains::chains::Chain;
-use masq_lib::constants::DEFAULT_MAX_BLOCK_COUNT;
+use masq_lib::constants::{
+    DEFAULT_MAX_BLOCK_COUNT, INVALID_PAYMENT_REFERENCE_ERROR, TRANSACTION_RECEIPT_QUERY_ERROR,
+};
 use masq_lib::logger::Logger;
-use masq_lib::messages::ScanType;
-use masq_lib::ui_gateway::NodeFromUiMessage;
+use masq_lib::messages::{
+    FromMessageBody, ScanType, ToMessageBody, UiAlertBroadcast, UiVerifyPaymentRequest,
+};
+use masq_lib::ui_gateway::{
+    MessageBody, MessagePath, MessageTarget, NodeFromUiMessage, NodeToUiMessage,
+};
 use masq_lib::utils::to_string;
 use regex::Regex;
 use std::path::Path;
+use std::str::FromStr;
 use std::time::SystemTime;
-use web3::types::{BlockNumber, TransactionReceipt, H256};
+use web3::types::{Address, BlockNumber, TransactionReceipt, H256};
 
 pub const CRASH_KEY: &str = "BLOCKCHAINBRIDGE";
+// One block is remembered per receivable scan; a reorganization reaching deeper than the oldest
+// of them is still caught, but can only be rescanned from that oldest block
+pub const SCANNED_BLOCKS_TO_KEEP: usize = 10;
 
 pub struct BlockchainBridge {
     blockchain_interface: Box<dyn BlockchainInterface>,
@@ -52,9 +74,14 @@ pub struct BlockchainBridge {
     sent_payable_subs_opt: Option<Recipient<SentPayables>>,
     payable_payments_setup_subs_opt: Option<Recipient<BlockchainAgentWithContextMessage>>,
     received_payments_subs_opt: Option<Recipient<ReceivedPayments>>,
+    payment_receipt_sub_opt: Option<Recipient<ReportPaymentReceipt>>,
     scan_error_subs_opt: Option<Recipient<ScanError>>,
+    ui_gateway_sub_opt: Option<Recipient<NodeToUiMessage>>,
     crashable: bool,
     pending_payable_confirmation: TransactionConfirmationTools,
+    transaction_fee_monitor: TransactionFeeBalanceMonitor,
+    nonce_tracker: ConsumingWalletNonceTracker,
+    service_failover_opt: Option<ServiceFailoverTools>,
 }
 
 struct TransactionConfirmationTools {
@@ -62,6 +89,11 @@ struct TransactionConfirmationTools {
     report_transaction_receipts_sub_opt: Option<Recipient<ReportTransactionReceipts>>,
 }
 
+struct ServiceFailoverTools {
+    health_monitor: BlockchainServiceHealthMonitor,
+    interface_factory: Box<dyn BlockchainInterfaceFactory>,
+}
+
 impl Actor for BlockchainBridge {
     type Context = Context<Self>;
 }
@@ -80,7 +112,9 @@ impl Handler<BindMessage> for BlockchainBridge {
             Some(msg.peer_actors.accountant.report_payable_payments_setup);
         self.sent_payable_subs_opt = Some(msg.peer_actors.accountant.report_sent_payments);
         self.received_payments_subs_opt = Some(msg.peer_actors.accountant.report_inbound_payments);
+        self.payment_receipt_sub_opt = Some(msg.peer_actors.accountant.report_payment_receipt);
         self.scan_error_subs_opt = Some(msg.peer_actors.accountant.scan_errors);
+        self.ui_gateway_sub_opt = Some(msg.peer_actors.ui_gateway.node_to_ui_message_sub);
         // There's a multinode integration test looking for this message
         debug!(self.logger, "Received BindMessage");
     }
@@ -168,7 +202,17 @@ impl Handler<NodeFromUiMessage> for BlockchainBridge {
     type Result = ();
 
     fn handle(&mut self, msg: NodeFromUiMessage, _ctx: &mut Self::Context) -> Self::Result {
-        handle_ui_crash_request(msg, &self.logger, self.crashable, CRASH_KEY)
+        if let Ok((request, context_id)) = UiVerifyPaymentRequest::fmb(msg.body.clone()) {
+            self.handle_verify_payment(
+                request,
+                ResponseSkeleton {
+                    client_id: msg.client_id,
+                    context_id,
+                },
+            )
+        } else {
+            handle_ui_crash_request(msg, &self.logger, self.crashable, CRASH_KEY)
+        }
     }
 }
 
@@ -184,16 +228,40 @@ impl BlockchainBridge {
             sent_payable_subs_opt: None,
             payable_payments_setup_subs_opt: None,
             received_payments_subs_opt: None,
+            payment_receipt_sub_opt: None,
             scan_error_subs_opt: None,
+            ui_gateway_sub_opt: None,
             crashable,
             logger: Logger::new("BlockchainBridge"),
             pending_payable_confirmation: TransactionConfirmationTools {
                 new_pp_fingerprints_sub_opt: None,
                 report_transaction_receipts_sub_opt: None,
             },
+            transaction_fee_monitor: TransactionFeeBalanceMonitor::new(Logger::new(
+                "BlockchainBridge",
+            )),
+            nonce_tracker: ConsumingWalletNonceTracker::new(Logger::new("BlockchainBridge")),
+            service_failover_opt: None,
         }
     }
 
+    pub fn with_failover(
+        mut self,
+        failover: BlockchainServiceFailover,
+        primary_url: String,
+        interface_factory: Box<dyn BlockchainInterfaceFactory>,
+    ) -> Self {
+        self.service_failover_opt = Some(ServiceFailoverTools {
+            health_monitor: BlockchainServiceHealthMonitor::new(
+                failover,
+                primary_url,
+                Logger::new("BlockchainBridge"),
+            ),
+            interface_factory,
+        });
+        self
+    }
+
     pub fn initialize_persistent_configuration(
         data_directory: &Path,
     ) -> Box<dyn PersistentConfiguration> {
@@ -208,12 +276,17 @@ impl BlockchainBridge {
     pub fn initialize_blockchain_interface(
         blockchain_service_url_opt: Option<String>,
         chain: Chain,
+        token_contract_address_opt: Option<Address>,
     ) -> Box<dyn BlockchainInterface> {
         match blockchain_service_url_opt {
             Some(url) => {
                 // TODO if we decided to have interchangeably runtime switchable or simultaneously usable interfaces we will
                 // probably want to make BlockchainInterfaceInitializer a collaborator that's a part of the actor
-                BlockchainInterfaceInitializer {}.initialize_interface(&url, chain)
+                BlockchainInterfaceInitializer {}.initialize_interface(
+                    &url,
+                    chain,
+                    token_contract_address_opt,
+                )
             }
             None => Box::new(BlockchainInterfaceNull::default()),
         }
@@ -239,6 +312,9 @@ impl BlockchainBridge {
             .build_blockchain_agent(&incoming_message.consuming_wallet, &*self.persistent_config)
             .map_err(to_string)?;
 
+        self.check_transaction_fee_balance(&*agent);
+        self.resynchronize_nonce(&*agent);
+
         let outgoing_message = BlockchainAgentWithContextMessage::new(
             incoming_message.protected_qualified_payables,
             agent,
@@ -254,6 +330,57 @@ impl BlockchainBridge {
         Ok(())
     }
 
+    fn check_transaction_fee_balance(&mut self, agent: &dyn BlockchainAgent) {
+        if let Some(broadcast) = self.transaction_fee_monitor.check(agent) {
+            self.ui_gateway_sub_opt
+                .as_ref()
+                .expect("UiGateway is unbound")
+                .try_send(NodeToUiMessage {
+                    target: MessageTarget::AllClients,
+                    body: broadcast.tmb(0),
+                })
+                .expect("UiGateway is dead");
+        }
+    }
+
+    fn resynchronize_nonce(&mut self, agent: &dyn BlockchainAgent) {
+        let consuming_wallet = agent.consuming_wallet();
+        let confirmed_nonce_opt = match self
+            .blockchain_interface
+            .lower_interface()
+            .get_confirmed_transaction_id(consuming_wallet)
+        {
+            Ok(nonce) => Some(nonce),
+            Err(e) => {
+                debug!(
+                    self.logger,
+                    "Couldn't fetch the confirmed nonce of consuming wallet {}: {:?}",
+                    consuming_wallet,
+                    e
+                );
+                None
+            }
+        };
+        if let Some(alert) = self.nonce_tracker.resynchronize(
+            consuming_wallet,
+            agent.pending_transaction_id(),
+            confirmed_nonce_opt,
+        ) {
+            self.send_alert(alert);
+        }
+    }
+
+    fn send_alert(&self, alert: UiAlertBroadcast) {
+        self.ui_gateway_sub_opt
+            .as_ref()
+            .expect("UiGateway is unbound")
+            .try_send(NodeToUiMessage {
+                target: MessageTarget::AllClients,
+                body: alert.tmb(0),
+            })
+            .expect("UiGateway is dead");
+    }
+
     fn handle_outbound_payments_instructions(
         &mut self,
         msg: OutboundPaymentsInstructions,
@@ -262,6 +389,9 @@ impl BlockchainBridge {
         let agent = msg.agent;
         let checked_accounts = msg.affordable_accounts;
         let result = self.process_payments(agent, checked_accounts);
+        if let Some(alert) = self.nonce_tracker.record_batch(&result) {
+            self.send_alert(alert);
+        }
 
         let locally_produced_result = match &result {
             Err(e) => Err(format!("ReportAccountsPayable: {}", e)),
@@ -286,6 +416,23 @@ impl BlockchainBridge {
             Ok(None) => u64::MAX,
             Err(e) => panic!("Cannot retrieve start block from database; payments to you may not be processed: {:?}", e)
         };
+        let scanned_blocks = match self.persistent_config.scanned_blocks() {
+            Ok(blocks) => blocks,
+            Err(e) => {
+                warning!(
+                    self.logger,
+                    "Cannot retrieve scanned blocks from database; a chain reorganization would go unnoticed: {:?}",
+                    e
+                );
+                vec![]
+            }
+        };
+        let (rewind_to_block_opt, scanned_blocks) = if u64::MAX == start_block_nbr {
+            (None, scanned_blocks)
+        } else {
+            self.check_for_reorganization(scanned_blocks)
+        };
+        let start_block_nbr = rewind_to_block_opt.unwrap_or(start_block_nbr);
         let max_block_count = match self.persistent_config.max_block_count() {
             Ok(Some(mbc)) => mbc,
             _ => DEFAULT_MAX_BLOCK_COUNT,
@@ -336,6 +483,8 @@ impl BlockchainBridge {
                     if transactions.transactions.is_empty() {
                         debug!(self.logger, "No new receivable detected");
                     }
+                    let scanned_blocks =
+                        self.add_scanned_block(scanned_blocks, new_start_block_number.as_u64());
                     self.received_payments_subs_opt
                         .as_ref()
                         .expect("Accountant is unbound")
@@ -343,6 +492,8 @@ impl BlockchainBridge {
                             timestamp: SystemTime::now(),
                             payments: transactions.transactions,
                             new_start_block: new_start_block_number.as_u64(),
+                            rewind_to_block_opt,
+                            scanned_blocks,
                             response_skeleton_opt: msg.response_skeleton_opt,
                         })
                         .expect("Accountant is dead.");
@@ -379,6 +530,93 @@ impl BlockchainBridge {
         }
     }
 
+    // Walks back from the most recently scanned block until it finds one the chain still agrees
+    // with. Everything after that one has to be scanned again, and the payments credited from
+    // there on have to be taken back by the Accountant, because they may have been orphaned.
+    fn check_for_reorganization(
+        &self,
+        scanned_blocks: Vec<ScannedBlock>,
+    ) -> (Option<u64>, Vec<ScannedBlock>) {
+        let lower_interface = self.blockchain_interface.lower_interface();
+        let mut surviving_count = scanned_blocks.len();
+        for block in scanned_blocks.iter().rev() {
+            match lower_interface.get_block_hash(block.number) {
+                Ok(Some(hash)) if hash == block.hash => break,
+                Ok(_) => surviving_count -= 1,
+                Err(e) => {
+                    debug!(
+                        self.logger,
+                        "Cannot check block {} for a chain reorganization: {:?}", block.number, e
+                    );
+                    return (None, scanned_blocks);
+                }
+            }
+        }
+        if surviving_count == scanned_blocks.len() {
+            return (None, scanned_blocks);
+        }
+        let rewind_to_block = match surviving_count {
+            0 => scanned_blocks[0].number,
+            count => scanned_blocks[count - 1].number + 1,
+        };
+        warning!(
+            self.logger,
+            "Chain reorganization detected: block {} is no longer on the chain; rescanning for receivables from block {}",
+            scanned_blocks[surviving_count].number,
+            rewind_to_block
+        );
+        if surviving_count == 0 {
+            warning!(
+                self.logger,
+                "None of the {} remembered blocks survived the reorganization; payments orphaned before block {} cannot be detected",
+                scanned_blocks.len(),
+                rewind_to_block
+            )
+        }
+        let surviving_blocks = scanned_blocks.into_iter().take(surviving_count).collect();
+        (Some(rewind_to_block), surviving_blocks)
+    }
+
+    fn add_scanned_block(
+        &self,
+        mut scanned_blocks: Vec<ScannedBlock>,
+        new_start_block: u64,
+    ) -> Vec<ScannedBlock> {
+        let last_scanned_block = match new_start_block.checked_sub(1) {
+            Some(number) => number,
+            None => return scanned_blocks,
+        };
+        if scanned_blocks
+            .last()
+            .map_or(false, |block| block.number >= last_scanned_block)
+        {
+            return scanned_blocks;
+        }
+        match self
+            .blockchain_interface
+            .lower_interface()
+            .get_block_hash(last_scanned_block)
+        {
+            Ok(Some(hash)) => {
+                scanned_blocks.push(ScannedBlock {
+                    number: last_scanned_block,
+                    hash,
+                });
+                let excess = scanned_blocks.len().saturating_sub(SCANNED_BLOCKS_TO_KEEP);
+                scanned_blocks.drain(..excess);
+            }
+            Ok(None) => debug!(
+                self.logger,
+                "Block {} is unknown to the chain; not remembering it", last_scanned_block
+            ),
+            Err(e) => debug!(
+                self.logger,
+                "Cannot remember block {}: {:?}", last_scanned_block, e
+            ),
+        }
+        scanned_blocks
+    }
+
     fn handle_request_transaction_receipts(
         &mut self,
         msg: RequestTransactionReceipts,
@@ -426,13 +664,106 @@ impl BlockchainBridge {
         Ok(())
     }
 
+    // The Accountant finishes the verification: only it knows what's owed to the creditor
+    fn handle_verify_payment(
+        &self,
+        request: UiVerifyPaymentRequest,
+        response_skeleton: ResponseSkeleton,
+    ) {
+        match self.fetch_payment_receipt(&request) {
+            Ok((transaction_hash, creditor, receipt_opt)) => {
+                let paid_to_creditor_wei = receipt_opt
+                    .as_ref()
+                    .map(|receipt| {
+                        tokens_transferred_to(
+                            receipt,
+                            self.blockchain_interface.contract_address(),
+                            &creditor,
+                        )
+                    })
+                    .unwrap_or(0);
+                self.payment_receipt_sub_opt
+                    .as_ref()
+                    .expect("Accountant is unbound")
+                    .try_send(ReportPaymentReceipt {
+                        transaction_hash,
+                        creditor,
+                        receipt_opt,
+                        paid_to_creditor_wei,
+                        response_skeleton,
+                    })
+                    .expect("Accountant is dead")
+            }
+            Err((code, message)) => self
+                .ui_gateway_sub_opt
+                .as_ref()
+                .expect("UiGateway is unbound")
+                .try_send(NodeToUiMessage {
+                    target: MessageTarget::ClientId(response_skeleton.client_id),
+                    body: MessageBody {
+                        opcode: request.opcode().to_string(),
+                        path: MessagePath::Conversation(response_skeleton.context_id),
+                        payload: Err((code, message)),
+                    },
+                })
+                .expect("UiGateway is dead"),
+        }
+    }
+
+    fn fetch_payment_receipt(
+        &self,
+        request: &UiVerifyPaymentRequest,
+    ) -> Result<(H256, Wallet, Option<TransactionReceipt>), (u64, String)> {
+        let transaction_hash = serde_json::from_value::<H256>(serde_json::json!(
+            request.transaction_hash
+        ))
+        .map_err(|_| {
+            (
+                INVALID_PAYMENT_REFERENCE_ERROR,
+                format!(
+                    "Transaction hash '{}' should be 0x and 64 hex digits",
+                    request.transaction_hash
+                ),
+            )
+        })?;
+        let creditor = Wallet::from_str(&request.creditor_wallet).map_err(|_| {
+            (
+                INVALID_PAYMENT_REFERENCE_ERROR,
+                format!(
+                    "Creditor wallet '{}' should be 0x and 40 hex digits",
+                    request.creditor_wallet
+                ),
+            )
+        })?;
+        let receipt_opt = self
+            .blockchain_interface
+            .get_transaction_receipt(transaction_hash)
+            .map_err(|e| {
+                (
+                    TRANSACTION_RECEIPT_QUERY_ERROR,
+                    format!(
+                        "Couldn't get the receipt for transaction {:?}: {:?}",
+                        transaction_hash, e
+                    ),
+                )
+            })?;
+        Ok((transaction_hash, creditor, receipt_opt))
+    }
+
     fn handle_scan<M, F>(&mut self, handler: F, scan_type: ScanType, msg: M)
     where
         F: FnOnce(&mut BlockchainBridge, M) -> Result<(), String>,
         M: SkeletonOptHolder,
     {
         let skeleton_opt = msg.skeleton_opt();
-        match handler(self, msg) {
+        let result = if scan_type == ScanType::Payables && self.payables_paused() {
+            Err("Payable settlement is paused until the blockchain service recovers".to_string())
+        } else {
+            let result = handler(self, msg);
+            self.record_service_health(result.is_ok());
+            result
+        };
+        match result {
             Ok(_r) => (),
             Err(e) => {
                 warning!(self.logger, "{}", e);
@@ -449,6 +780,31 @@ impl BlockchainBridge {
         }
     }
 
+    fn payables_paused(&self) -> bool {
+        self.service_failover_opt
+            .as_ref()
+            .map(|tools| tools.health_monitor.payables_paused())
+            .unwrap_or(false)
+    }
+
+    fn record_service_health(&mut self, healthy: bool) {
+        let tools = match self.service_failover_opt.as_mut() {
+            Some(tools) => tools,
+            None => return,
+        };
+        if healthy {
+            tools.health_monitor.record_success();
+            return;
+        }
+        let decision = tools.health_monitor.record_failure(SystemTime::now());
+        if let Some(url) = decision.switch_to_opt {
+            self.blockchain_interface = tools.interface_factory.make(&url);
+        }
+        if let Some(alert) = decision.alert_opt {
+            self.send_alert(alert);
+        }
+    }
+
     fn process_payments(
         &self,
         agent: Box<dyn BlockchainAgent>,
@@ -518,20 +874,24 @@ mod tests {
     use crate::accountant::scanners::test_utils::protect_payables_in_test;
     use crate::accountant::test_utils::make_pending_payable_fingerprint;
     use crate::blockchain::blockchain_interface::blockchain_interface_null::BlockchainInterfaceNull;
+    use crate::blockchain::blockchain_interface::blockchain_interface_web3::test_utils::make_transfer_log;
     use crate::blockchain::blockchain_interface::data_structures::errors::{
         BlockchainAgentBuildError, PayableTransactionError,
     };
     use crate::blockchain::blockchain_interface::data_structures::{
-        BlockchainTransaction, RetrievedBlockchainTransactions,
+        BlockchainTransaction, RetrievedBlockchainTransactions, RpcPayablesFailure, ScannedBlock,
     };
     use crate::blockchain::blockchain_interface::lower_level_interface::LatestBlockNumber;
     use crate::blockchain::blockchain_interface::test_utils::LowBlockchainIntMock;
-    use crate::blockchain::test_utils::{make_tx_hash, BlockchainInterfaceMock};
+    use crate::blockchain::test_utils::{
+        make_tx_hash, BlockchainInterfaceFactoryMock, BlockchainInterfaceMock,
+    };
     use crate::db_config::persistent_configuration::PersistentConfigError;
     use crate::match_every_type_id;
     use crate::node_test_utils::check_timestamp;
+    use crate::sub_lib::blockchain_bridge::ConsumingWalletBalances;
     use crate::test_utils::persistent_configuration_mock::PersistentConfigurationMock;
-    use crate::test_utils::recorder::{make_recorder, peer_actors_builder};
+    use crate::test_utils::recorder::{make_recorder, peer_actors_builder, Recording};
     use crate::test_utils::recorder_stop_conditions::StopCondition;
     use crate::test_utils::recorder_stop_conditions::StopConditions;
     use crate::test_utils::unshared_test_utils::arbitrary_id_stamp::ArbitraryIdStamp;
@@ -543,7 +903,9 @@ mod tests {
     use actix::System;
     use ethereum_types::U64;
     use ethsign_crypto::Keccak256;
-    use masq_lib::messages::ScanType;
+    use masq_lib::messages::{
+        ScanType, UiAlertRule, UiTransactionFeeBalanceBroadcast, UiTransactionFeeBalanceLevel,
+    };
     use masq_lib::test_utils::logging::init_test_logging;
     use masq_lib::test_utils::logging::TestLogHandler;
     use masq_lib::test_utils::utils::{ensure_node_home_directory_exists, TEST_DEFAULT_CHAIN};
@@ -551,7 +913,7 @@ mod tests {
     use std::path::Path;
     use std::sync::{Arc, Mutex};
     use std::time::{Duration, SystemTime};
-    use web3::types::{TransactionReceipt, H160, H256};
+    use web3::types::{TransactionReceipt, H160, H256, U256};
 
     impl Handler<AssertionsMessage<Self>> for BlockchainBridge {
         type Result = ();
@@ -572,7 +934,8 @@ mod tests {
 
     #[test]
     fn blockchain_interface_null_as_result_of_missing_blockchain_service_url() {
-        let result = BlockchainBridge::initialize_blockchain_interface(None, TEST_DEFAULT_CHAIN);
+        let result =
+            BlockchainBridge::initialize_blockchain_interface(None, TEST_DEFAULT_CHAIN, None);
 
         result
             .as_any()
@@ -589,10 +952,22 @@ mod tests {
         let build_blockchain_agent_params_arc = Arc::new(Mutex::new(vec![]));
         let (accountant, _, accountant_recording_arc) = make_recorder();
         let agent_id_stamp = ArbitraryIdStamp::new();
-        let agent = BlockchainAgentMock::default().set_arbitrary_id_stamp(agent_id_stamp);
+        let agent = BlockchainAgentMock::default()
+            .consuming_wallet_balances_result(ConsumingWalletBalances {
+                transaction_fee_balance_in_minor_units: U256::from(u128::MAX),
+                masq_token_balance_in_minor_units: U256::zero(),
+            })
+            .estimated_transaction_fee_total_result(21_000)
+            .consuming_wallet_result(make_paying_wallet(b"somewallet"))
+            .pending_transaction_id_result(U256::from(5))
+            .set_arbitrary_id_stamp(agent_id_stamp);
         let blockchain_interface = BlockchainInterfaceMock::default()
             .build_blockchain_agent_params(&build_blockchain_agent_params_arc)
-            .build_blockchain_agent_result(Ok(Box::new(agent)));
+            .build_blockchain_agent_result(Ok(Box::new(agent)))
+            .lower_interface_results(Box::new(
+                LowBlockchainIntMock::default()
+                    .get_confirmed_transaction_id_result(Ok(U256::from(4))),
+            ));
         let consuming_wallet = make_paying_wallet(b"somewallet");
         let persistent_config_id_stamp = ArbitraryIdStamp::new();
         let persistent_configuration = PersistentConfigurationMock::default()
@@ -662,6 +1037,154 @@ mod tests {
         assert_eq!(accountant_received_payment.len(), 1);
     }
 
+    #[test]
+    fn qualified_payables_msg_handling_warns_ui_about_low_transaction_fee_balance() {
+        let system = System::new(
+            "qualified_payables_msg_handling_warns_ui_about_low_transaction_fee_balance",
+        );
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let make_agent = || {
+            BlockchainAgentMock::default()
+                .consuming_wallet_balances_result(ConsumingWalletBalances {
+                    transaction_fee_balance_in_minor_units: U256::from(3 * 21_000_000_000_000_u128),
+                    masq_token_balance_in_minor_units: U256::zero(),
+                })
+                .estimated_transaction_fee_total_result(21_000)
+                .consuming_wallet_result(make_paying_wallet(b"somewallet"))
+                .pending_transaction_id_result(U256::from(5))
+        };
+        let blockchain_interface = BlockchainInterfaceMock::default()
+            .build_blockchain_agent_result(Ok(Box::new(make_agent())))
+            .build_blockchain_agent_result(Ok(Box::new(make_agent())))
+            .lower_interface_results(Box::new(
+                LowBlockchainIntMock::default()
+                    .get_confirmed_transaction_id_result(Ok(U256::from(5)))
+                    .get_confirmed_transaction_id_result(Ok(U256::from(5))),
+            ));
+        let subject = BlockchainBridge::new(
+            Box::new(blockchain_interface),
+            Box::new(PersistentConfigurationMock::default()),
+            false,
+        );
+        let addr = subject.start();
+        let subject_subs = BlockchainBridge::make_subs_from(&addr);
+        let peer_actors = peer_actors_builder()
+            .accountant(accountant)
+            .ui_gateway(ui_gateway)
+            .build();
+        let make_msg = || QualifiedPayablesMessage {
+            protected_qualified_payables: protect_payables_in_test(vec![PayableAccount {
+                wallet: make_wallet("booga"),
+                balance_wei: 78_654_321_124,
+                last_paid_timestamp: SystemTime::now(),
+                pending_payable_opt: None,
+            }]),
+            consuming_wallet: make_paying_wallet(b"somewallet"),
+            response_skeleton_opt: None,
+        };
+        send_bind_message!(subject_subs, peer_actors);
+
+        addr.try_send(make_msg()).unwrap();
+        addr.try_send(make_msg()).unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            &NodeToUiMessage {
+                target: MessageTarget::AllClients,
+                body: UiTransactionFeeBalanceBroadcast {
+                    level: UiTransactionFeeBalanceLevel::Critical,
+                    balance_wei: 3 * 21_000_000_000_000,
+                    fee_per_transaction_wei: 21_000_000_000_000,
+                    affordable_transactions: 3,
+                }
+                .tmb(0),
+            }
+        );
+        assert_eq!(ui_gateway_recording.len(), 1);
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(accountant_recording.len(), 2);
+    }
+
+    #[test]
+    fn qualified_payables_msg_handling_alerts_ui_about_external_wallet_use() {
+        let test_name = "qualified_payables_msg_handling_alerts_ui_about_external_wallet_use";
+        let system = System::new(test_name);
+        let get_confirmed_transaction_id_params_arc = Arc::new(Mutex::new(vec![]));
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let consuming_wallet = make_paying_wallet(b"somewallet");
+        let make_agent = |pending_nonce: u64| {
+            BlockchainAgentMock::default()
+                .consuming_wallet_balances_result(ConsumingWalletBalances {
+                    transaction_fee_balance_in_minor_units: U256::from(u128::MAX),
+                    masq_token_balance_in_minor_units: U256::zero(),
+                })
+                .estimated_transaction_fee_total_result(21_000)
+                .consuming_wallet_result(consuming_wallet.clone())
+                .pending_transaction_id_result(U256::from(pending_nonce))
+        };
+        let blockchain_interface = BlockchainInterfaceMock::default()
+            .build_blockchain_agent_result(Ok(Box::new(make_agent(5))))
+            .build_blockchain_agent_result(Ok(Box::new(make_agent(8))))
+            .lower_interface_results(Box::new(
+                LowBlockchainIntMock::default()
+                    .get_confirmed_transaction_id_params(&get_confirmed_transaction_id_params_arc)
+                    .get_confirmed_transaction_id_result(Ok(U256::from(5)))
+                    .get_confirmed_transaction_id_result(Err(BlockchainError::QueryFailed(
+                        "booga".to_string(),
+                    ))),
+            ));
+        let subject = BlockchainBridge::new(
+            Box::new(blockchain_interface),
+            Box::new(PersistentConfigurationMock::default()),
+            false,
+        );
+        let addr = subject.start();
+        let subject_subs = BlockchainBridge::make_subs_from(&addr);
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        let make_msg = || QualifiedPayablesMessage {
+            protected_qualified_payables: protect_payables_in_test(vec![PayableAccount {
+                wallet: make_wallet("booga"),
+                balance_wei: 78_654_321_124,
+                last_paid_timestamp: SystemTime::now(),
+                pending_payable_opt: None,
+            }]),
+            consuming_wallet: consuming_wallet.clone(),
+            response_skeleton_opt: None,
+        };
+        send_bind_message!(subject_subs, peer_actors);
+
+        addr.try_send(make_msg()).unwrap();
+        addr.try_send(make_msg()).unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            &NodeToUiMessage {
+                target: MessageTarget::AllClients,
+                body: UiAlertBroadcast {
+                    rule: UiAlertRule::ExternalWalletUse,
+                    message: format!(
+                        "3 transaction(s) were sent from consuming wallet {} outside this Node; \
+                        its payments will continue from nonce 8",
+                        consuming_wallet
+                    ),
+                }
+                .tmb(0),
+            }
+        );
+        assert_eq!(ui_gateway_recording.len(), 1);
+        assert_eq!(
+            *get_confirmed_transaction_id_params_arc.lock().unwrap(),
+            vec![consuming_wallet.clone(), consuming_wallet]
+        );
+    }
+
     #[test]
     fn build_of_blockchain_agent_throws_err_out_and_ends_handling_qualified_payables_message() {
         init_test_logging();
@@ -899,6 +1422,75 @@ mod tests {
         assert_eq!(accountant_recording.len(), 2)
     }
 
+    #[test]
+    fn handle_outbound_payments_instructions_alerts_ui_about_nonce_collisions() {
+        let system =
+            System::new("handle_outbound_payments_instructions_alerts_ui_about_nonce_collisions");
+        let (accountant, _, _) = make_recorder();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let consuming_wallet = make_paying_wallet(b"somewallet");
+        let recipient_wallet = make_wallet("blah");
+        let blockchain_interface_mock = BlockchainInterfaceMock::default()
+            .send_batch_of_payables_result(Ok(vec![Err(RpcPayablesFailure {
+                rpc_error: web3::Error::Rpc(jsonrpc_core::Error {
+                    code: jsonrpc_core::ErrorCode::ServerError(-32000),
+                    message: "nonce too low".to_string(),
+                    data: None,
+                }),
+                recipient_wallet: recipient_wallet.clone(),
+                hash: make_tx_hash(0xde),
+            })]));
+        let mut subject = BlockchainBridge::new(
+            Box::new(blockchain_interface_mock),
+            Box::new(PersistentConfigurationMock::default()),
+            false,
+        );
+        subject
+            .nonce_tracker
+            .resynchronize(&consuming_wallet, U256::from(5), None);
+        let addr = subject.start();
+        let subject_subs = BlockchainBridge::make_subs_from(&addr);
+        let peer_actors = peer_actors_builder()
+            .accountant(accountant)
+            .ui_gateway(ui_gateway)
+            .build();
+        send_bind_message!(subject_subs, peer_actors);
+
+        let _ = addr
+            .try_send(OutboundPaymentsInstructions {
+                affordable_accounts: vec![PayableAccount {
+                    wallet: recipient_wallet.clone(),
+                    balance_wei: 111_420_204,
+                    last_paid_timestamp: from_time_t(150_000_000),
+                    pending_payable_opt: None,
+                }],
+                agent: Box::new(BlockchainAgentMock::default()),
+                response_skeleton_opt: None,
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            &NodeToUiMessage {
+                target: MessageTarget::AllClients,
+                body: UiAlertBroadcast {
+                    rule: UiAlertRule::ExternalWalletUse,
+                    message: format!(
+                        "Payment(s) to {} collided with transactions sent from consuming wallet \
+                        {} outside this Node; they will be retried with fresh nonces at the next \
+                        payable scan",
+                        recipient_wallet, consuming_wallet
+                    ),
+                }
+                .tmb(0),
+            }
+        );
+        assert_eq!(ui_gateway_recording.len(), 1);
+    }
+
     #[test]
     fn process_payments_returns_error_from_sending_batch() {
         let transaction_hash = make_tx_hash(789);
@@ -1021,7 +1613,8 @@ mod tests {
             .lower_interface_results(Box::new(lower_interface));
         let persistent_config = PersistentConfigurationMock::new()
             .max_block_count_result(Ok(Some(DEFAULT_MAX_BLOCK_COUNT)))
-            .start_block_result(Ok(Some(5))); // no set_start_block_result: set_start_block() must not be called
+            .start_block_result(Ok(Some(5))) // no set_start_block_result: set_start_block() must not be called
+            .scanned_blocks_result(Ok(vec![]));
         let mut subject = BlockchainBridge::new(
             Box::new(blockchain_interface),
             Box::new(persistent_config),
@@ -1304,17 +1897,19 @@ mod tests {
                 },
             ],
         };
-        let lower_interface =
-            LowBlockchainIntMock::default().get_block_number_result(LatestBlockNumber::Err(
-                BlockchainError::QueryFailed("Failed to read the latest block number".to_string()),
-            ));
+        let lower_interface = LowBlockchainIntMock::default()
+            .get_block_number_result(LatestBlockNumber::Err(BlockchainError::QueryFailed(
+                "Failed to read the latest block number".to_string(),
+            )))
+            .get_block_hash_result(Ok(None));
         let blockchain_interface_mock = BlockchainInterfaceMock::default()
             .retrieve_transactions_params(&retrieve_transactions_params_arc)
             .retrieve_transactions_result(Ok(expected_transactions.clone()))
             .lower_interface_results(Box::new(lower_interface));
         let persistent_config = PersistentConfigurationMock::new()
             .max_block_count_result(Ok(Some(DEFAULT_MAX_BLOCK_COUNT)))
-            .start_block_result(Ok(Some(6)));
+            .start_block_result(Ok(Some(6)))
+            .scanned_blocks_result(Ok(vec![]));
         let subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(persistent_config),
@@ -1357,6 +1952,8 @@ mod tests {
                 timestamp: received_payments.timestamp,
                 payments: expected_transactions.transactions,
                 new_start_block: 8675309u64,
+                rewind_to_block_opt: None,
+                scanned_blocks: vec![],
                 response_skeleton_opt: Some(ResponseSkeleton {
                     client_id: 1234,
                     context_id: 4321
@@ -1392,18 +1989,19 @@ mod tests {
                 },
             ],
         };
-        let lower_interface = LowBlockchainIntMock::default().get_block_number_result(
-            LatestBlockNumber::Err(BlockchainError::QueryFailed(
+        let lower_interface = LowBlockchainIntMock::default()
+            .get_block_number_result(LatestBlockNumber::Err(BlockchainError::QueryFailed(
                 "\"Failed to read the latest block number\"".to_string(),
-            )),
-        );
+            )))
+            .get_block_hash_result(Ok(None));
         let blockchain_interface_mock = BlockchainInterfaceMock::default()
             .retrieve_transactions_params(&retrieve_transactions_params_arc)
             .retrieve_transactions_result(Ok(expected_transactions.clone()))
             .lower_interface_results(Box::new(lower_interface));
         let persistent_config = PersistentConfigurationMock::new()
             .max_block_count_result(Ok(Some(DEFAULT_MAX_BLOCK_COUNT)))
-            .start_block_result(Ok(None));
+            .start_block_result(Ok(None))
+            .scanned_blocks_result(Ok(vec![]));
         let subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(persistent_config),
@@ -1442,6 +2040,8 @@ mod tests {
                 timestamp: received_payments.timestamp,
                 payments: expected_transactions.transactions,
                 new_start_block: 8675309u64,
+                rewind_to_block_opt: None,
+                scanned_blocks: vec![],
                 response_skeleton_opt: Some(ResponseSkeleton {
                     client_id: 1234,
                     context_id: 4321
@@ -1479,12 +2079,14 @@ mod tests {
         let (accountant, _, accountant_recording_arc) = make_recorder();
         let persistent_config = PersistentConfigurationMock::new()
             .max_block_count_result(Ok(Some(DEFAULT_MAX_BLOCK_COUNT)))
-            .start_block_result(Ok(None));
+            .start_block_result(Ok(None))
+            .scanned_blocks_result(Ok(vec![]));
         let latest_block_number = LatestBlockNumber::Err(BlockchainError::QueryFailed(
             "Failed to read from block chain service".to_string(),
         ));
-        let lower_interface =
-            LowBlockchainIntMock::default().get_block_number_result(latest_block_number);
+        let lower_interface = LowBlockchainIntMock::default()
+            .get_block_number_result(latest_block_number)
+            .get_block_hash_result(Ok(None));
         let blockchain_interface = BlockchainInterfaceMock::default()
             .retrieve_transactions_params(&retrieve_transactions_params_arc)
             .retrieve_transactions_result(Ok(expected_transactions.clone()))
@@ -1527,6 +2129,8 @@ mod tests {
                 timestamp: received_payments.timestamp,
                 payments: expected_transactions.transactions,
                 new_start_block: 98765,
+                rewind_to_block_opt: None,
+                scanned_blocks: vec![],
                 response_skeleton_opt: Some(ResponseSkeleton {
                     client_id: 1234,
                     context_id: 4321
@@ -1560,15 +2164,19 @@ mod tests {
             ],
         };
         let latest_block_number = LatestBlockNumber::Ok(1024u64.into());
-        let lower_interface =
-            LowBlockchainIntMock::default().get_block_number_result(latest_block_number);
+        let get_block_hash_params_arc = Arc::new(Mutex::new(vec![]));
+        let lower_interface = LowBlockchainIntMock::default()
+            .get_block_number_result(latest_block_number)
+            .get_block_hash_params(&get_block_hash_params_arc)
+            .get_block_hash_result(Ok(Some(make_tx_hash(9875))));
         let blockchain_interface_mock = BlockchainInterfaceMock::default()
             .retrieve_transactions_params(&retrieve_transactions_params_arc)
             .retrieve_transactions_result(Ok(expected_transactions.clone()))
             .lower_interface_results(Box::new(lower_interface));
         let persistent_config = PersistentConfigurationMock::new()
             .max_block_count_result(Ok(Some(10000u64)))
-            .start_block_result(Ok(Some(6)));
+            .start_block_result(Ok(Some(6)))
+            .scanned_blocks_result(Ok(vec![]));
         let subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(persistent_config),
@@ -1601,6 +2209,8 @@ mod tests {
                 earning_wallet
             )]
         );
+        let get_block_hash_params = get_block_hash_params_arc.lock().unwrap();
+        assert_eq!(*get_block_hash_params, vec![9875]);
         let accountant_received_payment = accountant_recording_arc.lock().unwrap();
         assert_eq!(accountant_received_payment.len(), 1);
         let received_payments = accountant_received_payment.get_record::<ReceivedPayments>(0);
@@ -1611,6 +2221,11 @@ mod tests {
                 timestamp: received_payments.timestamp,
                 payments: expected_transactions.transactions,
                 new_start_block: 9876,
+                rewind_to_block_opt: None,
+                scanned_blocks: vec![ScannedBlock {
+                    number: 9875,
+                    hash: make_tx_hash(9875)
+                }],
                 response_skeleton_opt: Some(ResponseSkeleton {
                     client_id: 1234,
                     context_id: 4321
@@ -1622,8 +2237,9 @@ mod tests {
     #[test]
     fn processing_of_received_payments_continues_even_if_no_payments_are_detected() {
         init_test_logging();
-        let lower_interface =
-            LowBlockchainIntMock::default().get_block_number_result(Ok(0u64.into()));
+        let lower_interface = LowBlockchainIntMock::default()
+            .get_block_number_result(Ok(0u64.into()))
+            .get_block_hash_result(Ok(None));
         let blockchain_interface_mock = BlockchainInterfaceMock::default()
             .retrieve_transactions_result(Ok(RetrievedBlockchainTransactions {
                 new_start_block: BlockNumber::Number(7.into()),
@@ -1632,7 +2248,8 @@ mod tests {
             .lower_interface_results(Box::new(lower_interface));
         let persistent_config = PersistentConfigurationMock::new()
             .max_block_count_result(Ok(Some(10000u64)))
-            .start_block_result(Ok(Some(6)));
+            .start_block_result(Ok(Some(6)))
+            .scanned_blocks_result(Ok(vec![]));
         let (accountant, _, accountant_recording_arc) = make_recorder();
         let system = System::new(
             "processing_of_received_payments_continues_even_if_no_payments_are_detected",
@@ -1669,6 +2286,8 @@ mod tests {
                 timestamp: received_payments.timestamp,
                 payments: vec![],
                 new_start_block: 7,
+                rewind_to_block_opt: None,
+                scanned_blocks: vec![],
                 response_skeleton_opt: Some(ResponseSkeleton {
                     client_id: 1234,
                     context_id: 4321
@@ -1680,18 +2299,252 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(
-        expected = "Cannot retrieve start block from database; payments to you may not be processed: TransactionError"
-    )]
-    fn handle_retrieve_transactions_panics_if_start_block_cannot_be_read() {
-        let lower_interface =
-            LowBlockchainIntMock::default().get_block_number_result(Ok(0u64.into()));
-        let blockchain_interface =
-            BlockchainInterfaceMock::default().lower_interface_results(Box::new(lower_interface));
+    fn handle_retrieve_transactions_rescans_from_the_last_block_surviving_a_chain_reorganization() {
+        init_test_logging();
+        let test_name = "handle_retrieve_transactions_rescans_from_the_last_block_surviving_a_chain_reorganization";
+        let retrieve_transactions_params_arc = Arc::new(Mutex::new(vec![]));
+        let get_block_hash_params_arc = Arc::new(Mutex::new(vec![]));
+        let system = System::new(test_name);
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let earning_wallet = make_wallet("somewallet");
+        let scanned_blocks = vec![
+            ScannedBlock {
+                number: 1000,
+                hash: make_tx_hash(1000),
+            },
+            ScannedBlock {
+                number: 1010,
+                hash: make_tx_hash(1010),
+            },
+            ScannedBlock {
+                number: 1020,
+                hash: make_tx_hash(1020),
+            },
+        ];
+        let expected_transactions = RetrievedBlockchainTransactions {
+            new_start_block: BlockNumber::Number(1101u64.into()),
+            transactions: vec![BlockchainTransaction {
+                block_number: 1015,
+                from: earning_wallet.clone(),
+                wei_amount: 4_567,
+            }],
+        };
+        let lower_interface = LowBlockchainIntMock::default()
+            .get_block_number_result(Ok(1100u64.into()))
+            .get_block_hash_params(&get_block_hash_params_arc)
+            .get_block_hash_result(Ok(Some(make_tx_hash(2020))))
+            .get_block_hash_result(Ok(Some(make_tx_hash(1010))))
+            .get_block_hash_result(Ok(Some(make_tx_hash(1100))));
+        let blockchain_interface_mock = BlockchainInterfaceMock::default()
+            .retrieve_transactions_params(&retrieve_transactions_params_arc)
+            .retrieve_transactions_result(Ok(expected_transactions.clone()))
+            .lower_interface_results(Box::new(lower_interface));
         let persistent_config = PersistentConfigurationMock::new()
-            .start_block_result(Err(PersistentConfigError::TransactionError));
+            .max_block_count_result(Ok(Some(10000u64)))
+            .start_block_result(Ok(Some(1050)))
+            .scanned_blocks_result(Ok(scanned_blocks));
         let mut subject = BlockchainBridge::new(
-            Box::new(blockchain_interface),
+            Box::new(blockchain_interface_mock),
+            Box::new(persistent_config),
+            false,
+        );
+        subject.logger = Logger::new(test_name);
+        let addr = subject.start();
+        let subject_subs = BlockchainBridge::make_subs_from(&addr);
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        send_bind_message!(subject_subs, peer_actors);
+        let retrieve_transactions = RetrieveTransactions {
+            recipient: earning_wallet.clone(),
+            response_skeleton_opt: None,
+        };
+
+        let _ = addr.try_send(retrieve_transactions).unwrap();
+
+        System::current().stop();
+        system.run();
+        let retrieve_transactions_params = retrieve_transactions_params_arc.lock().unwrap();
+        assert_eq!(
+            *retrieve_transactions_params,
+            vec![(
+                BlockNumber::Number(1011u64.into()),
+                BlockNumber::Number(1100u64.into()),
+                earning_wallet
+            )]
+        );
+        let get_block_hash_params = get_block_hash_params_arc.lock().unwrap();
+        assert_eq!(*get_block_hash_params, vec![1020, 1010, 1100]);
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        let received_payments = accountant_recording.get_record::<ReceivedPayments>(0);
+        assert_eq!(
+            received_payments,
+            &ReceivedPayments {
+                timestamp: received_payments.timestamp,
+                payments: expected_transactions.transactions,
+                new_start_block: 1101,
+                rewind_to_block_opt: Some(1011),
+                scanned_blocks: vec![
+                    ScannedBlock {
+                        number: 1000,
+                        hash: make_tx_hash(1000)
+                    },
+                    ScannedBlock {
+                        number: 1010,
+                        hash: make_tx_hash(1010)
+                    },
+                    ScannedBlock {
+                        number: 1100,
+                        hash: make_tx_hash(1100)
+                    },
+                ],
+                response_skeleton_opt: None,
+            }
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Chain reorganization detected: block 1020 is no longer on the chain; \
+            rescanning for receivables from block 1011"
+        ));
+    }
+
+    #[test]
+    fn check_for_reorganization_rewinds_to_the_oldest_block_if_none_survived() {
+        init_test_logging();
+        let test_name = "check_for_reorganization_rewinds_to_the_oldest_block_if_none_survived";
+        let scanned_blocks = vec![
+            ScannedBlock {
+                number: 500,
+                hash: make_tx_hash(500),
+            },
+            ScannedBlock {
+                number: 501,
+                hash: make_tx_hash(501),
+            },
+        ];
+        let lower_interface = LowBlockchainIntMock::default()
+            .get_block_hash_result(Ok(None))
+            .get_block_hash_result(Ok(Some(make_tx_hash(1500))));
+        let blockchain_interface =
+            BlockchainInterfaceMock::default().lower_interface_results(Box::new(lower_interface));
+        let mut subject = BlockchainBridge::new(
+            Box::new(blockchain_interface),
+            Box::new(PersistentConfigurationMock::new()),
+            false,
+        );
+        subject.logger = Logger::new(test_name);
+
+        let result = subject.check_for_reorganization(scanned_blocks);
+
+        assert_eq!(result, (Some(500), vec![]));
+        let log_handler = TestLogHandler::new();
+        log_handler.exists_log_containing(&format!(
+            "WARN: {test_name}: Chain reorganization detected: block 500 is no longer on the chain; \
+            rescanning for receivables from block 500"
+        ));
+        log_handler.exists_log_containing(&format!(
+            "WARN: {test_name}: None of the 2 remembered blocks survived the reorganization; \
+            payments orphaned before block 500 cannot be detected"
+        ));
+    }
+
+    #[test]
+    fn check_for_reorganization_assumes_no_reorganization_if_the_chain_cannot_be_asked() {
+        init_test_logging();
+        let test_name =
+            "check_for_reorganization_assumes_no_reorganization_if_the_chain_cannot_be_asked";
+        let scanned_blocks = vec![
+            ScannedBlock {
+                number: 500,
+                hash: make_tx_hash(500),
+            },
+            ScannedBlock {
+                number: 501,
+                hash: make_tx_hash(501),
+            },
+        ];
+        let lower_interface = LowBlockchainIntMock::default()
+            .get_block_hash_result(Ok(Some(make_tx_hash(1501))))
+            .get_block_hash_result(Err(BlockchainError::QueryFailed("booga".to_string())));
+        let blockchain_interface =
+            BlockchainInterfaceMock::default().lower_interface_results(Box::new(lower_interface));
+        let mut subject = BlockchainBridge::new(
+            Box::new(blockchain_interface),
+            Box::new(PersistentConfigurationMock::new()),
+            false,
+        );
+        subject.logger = Logger::new(test_name);
+
+        let result = subject.check_for_reorganization(scanned_blocks.clone());
+
+        assert_eq!(result, (None, scanned_blocks));
+        TestLogHandler::new().exists_log_containing(&format!(
+            "DEBUG: {test_name}: Cannot check block 500 for a chain reorganization: \
+            QueryFailed(\"booga\")"
+        ));
+    }
+
+    #[test]
+    fn add_scanned_block_forgets_the_oldest_blocks_beyond_the_limit() {
+        let get_block_hash_params_arc = Arc::new(Mutex::new(vec![]));
+        let scanned_blocks = (0..SCANNED_BLOCKS_TO_KEEP as u64)
+            .map(|number| ScannedBlock {
+                number: number * 10,
+                hash: make_tx_hash(number as u32 * 10),
+            })
+            .collect::<Vec<ScannedBlock>>();
+        let lower_interface = LowBlockchainIntMock::default()
+            .get_block_hash_params(&get_block_hash_params_arc)
+            .get_block_hash_result(Ok(Some(make_tx_hash(200))));
+        let blockchain_interface =
+            BlockchainInterfaceMock::default().lower_interface_results(Box::new(lower_interface));
+        let subject = BlockchainBridge::new(
+            Box::new(blockchain_interface),
+            Box::new(PersistentConfigurationMock::new()),
+            false,
+        );
+
+        let result = subject.add_scanned_block(scanned_blocks.clone(), 201);
+
+        let mut expected_blocks = scanned_blocks[1..].to_vec();
+        expected_blocks.push(ScannedBlock {
+            number: 200,
+            hash: make_tx_hash(200),
+        });
+        assert_eq!(result, expected_blocks);
+        let get_block_hash_params = get_block_hash_params_arc.lock().unwrap();
+        assert_eq!(*get_block_hash_params, vec![200]);
+    }
+
+    #[test]
+    fn add_scanned_block_does_not_remember_a_block_that_did_not_move_the_scan_forward() {
+        let scanned_blocks = vec![ScannedBlock {
+            number: 200,
+            hash: make_tx_hash(200),
+        }];
+        let blockchain_interface = BlockchainInterfaceMock::default()
+            .lower_interface_results(Box::new(LowBlockchainIntMock::default()));
+        let subject = BlockchainBridge::new(
+            Box::new(blockchain_interface),
+            Box::new(PersistentConfigurationMock::new()),
+            false,
+        );
+
+        let result = subject.add_scanned_block(scanned_blocks.clone(), 201);
+
+        assert_eq!(result, scanned_blocks);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot retrieve start block from database; payments to you may not be processed: TransactionError"
+    )]
+    fn handle_retrieve_transactions_panics_if_start_block_cannot_be_read() {
+        let lower_interface =
+            LowBlockchainIntMock::default().get_block_number_result(Ok(0u64.into()));
+        let blockchain_interface =
+            BlockchainInterfaceMock::default().lower_interface_results(Box::new(lower_interface));
+        let persistent_config = PersistentConfigurationMock::new()
+            .start_block_result(Err(PersistentConfigError::TransactionError));
+        let mut subject = BlockchainBridge::new(
+            Box::new(blockchain_interface),
             Box::new(persistent_config),
             false,
         );
@@ -1827,6 +2680,155 @@ mod tests {
         TestLogHandler::new().exists_log_containing("WARN: BlockchainBridge: My tummy hurts");
     }
 
+    fn make_retrieve_transactions() -> RetrieveTransactions {
+        RetrieveTransactions {
+            recipient: make_wallet("somewallet"),
+            response_skeleton_opt: None,
+        }
+    }
+
+    #[test]
+    fn handle_scan_fails_over_to_an_alternate_url_when_the_blockchain_service_keeps_failing() {
+        let (accountant, _, _) = make_recorder();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let make_params_arc = Arc::new(Mutex::new(vec![]));
+        let alternate_contract_address = Address::from_low_u64_be(0x4321);
+        let interface_factory = BlockchainInterfaceFactoryMock::default()
+            .make_params(&make_params_arc)
+            .make_result(Box::new(
+                BlockchainInterfaceMock::default()
+                    .contract_address_result(alternate_contract_address),
+            ));
+        let mut subject = BlockchainBridge::new(
+            Box::new(BlockchainInterfaceMock::default()),
+            Box::new(PersistentConfigurationMock::new()),
+            false,
+        )
+        .with_failover(
+            BlockchainServiceFailover {
+                failure_threshold: 2,
+                failure_window_minutes: 5,
+                pause_payables: false,
+                alternate_urls: vec!["https://alternate.com".to_string()],
+            },
+            "https://primary.com".to_string(),
+            Box::new(interface_factory),
+        );
+        let system = System::new("test");
+        subject.scan_error_subs_opt = Some(accountant.start().recipient());
+        subject.ui_gateway_sub_opt = Some(ui_gateway.start().recipient());
+
+        subject.handle_scan(
+            failure_handler,
+            ScanType::Receivables,
+            make_retrieve_transactions(),
+        );
+        subject.handle_scan(
+            failure_handler,
+            ScanType::Receivables,
+            make_retrieve_transactions(),
+        );
+
+        System::current().stop();
+        system.run();
+        assert_eq!(
+            *make_params_arc.lock().unwrap(),
+            vec!["https://alternate.com".to_string()]
+        );
+        assert_eq!(
+            subject.blockchain_interface.contract_address(),
+            alternate_contract_address
+        );
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            &NodeToUiMessage {
+                target: MessageTarget::AllClients,
+                body: UiAlertBroadcast {
+                    rule: UiAlertRule::BlockchainServiceFailing,
+                    message: "2 requests in a row to blockchain service https://primary.com \
+                        failed within 5 minute(s); failing over to https://alternate.com"
+                        .to_string(),
+                }
+                .tmb(0),
+            }
+        );
+        assert_eq!(ui_gateway_recording.len(), 1);
+    }
+
+    #[test]
+    fn handle_scan_pauses_payables_while_the_blockchain_service_is_failing() {
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let mut subject = BlockchainBridge::new(
+            Box::new(BlockchainInterfaceMock::default()),
+            Box::new(PersistentConfigurationMock::new()),
+            false,
+        )
+        .with_failover(
+            BlockchainServiceFailover {
+                failure_threshold: 1,
+                failure_window_minutes: 5,
+                pause_payables: true,
+                alternate_urls: vec![],
+            },
+            "https://primary.com".to_string(),
+            Box::new(BlockchainInterfaceFactoryMock::default()),
+        );
+        let system = System::new("test");
+        subject.scan_error_subs_opt = Some(accountant.start().recipient());
+        subject.ui_gateway_sub_opt = Some(ui_gateway.start().recipient());
+        let untouchable_handler =
+            |_bcb: &mut BlockchainBridge, _msg: RetrieveTransactions| -> Result<(), String> {
+                panic!("Payables shouldn't be handled while paused")
+            };
+
+        subject.handle_scan(
+            failure_handler,
+            ScanType::Receivables,
+            make_retrieve_transactions(),
+        );
+        subject.handle_scan(
+            untouchable_handler,
+            ScanType::Payables,
+            make_retrieve_transactions(),
+        );
+        subject.handle_scan(
+            success_handler,
+            ScanType::Receivables,
+            make_retrieve_transactions(),
+        );
+        subject.handle_scan(
+            success_handler,
+            ScanType::Payables,
+            make_retrieve_transactions(),
+        );
+
+        System::current().stop();
+        system.run();
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(
+            accountant_recording.get_record::<ScanError>(0),
+            &ScanError {
+                scan_type: ScanType::Receivables,
+                response_skeleton_opt: None,
+                msg: "My tummy hurts".to_string()
+            }
+        );
+        assert_eq!(
+            accountant_recording.get_record::<ScanError>(1),
+            &ScanError {
+                scan_type: ScanType::Payables,
+                response_skeleton_opt: None,
+                msg: "Payable settlement is paused until the blockchain service recovers"
+                    .to_string()
+            }
+        );
+        assert_eq!(accountant_recording.len(), 2);
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(ui_gateway_recording.len(), 1);
+    }
+
     #[test]
     #[should_panic(
         expected = "panic message (processed with: node_lib::sub_lib::utils::crash_request_analyzer)"
@@ -1842,6 +2844,201 @@ mod tests {
         prove_that_crash_request_handler_is_hooked_up(subject, CRASH_KEY);
     }
 
+    fn run_verify_payment_request(
+        test_name: &str,
+        blockchain_interface: BlockchainInterfaceMock,
+        request: UiVerifyPaymentRequest,
+    ) -> (Arc<Mutex<Recording>>, Arc<Mutex<Recording>>) {
+        let system = System::new(test_name);
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let subject = BlockchainBridge::new(
+            Box::new(blockchain_interface),
+            Box::new(PersistentConfigurationMock::default()),
+            false,
+        );
+        let addr = subject.start();
+        let subject_subs = BlockchainBridge::make_subs_from(&addr);
+        let peer_actors = peer_actors_builder()
+            .accountant(accountant)
+            .ui_gateway(ui_gateway)
+            .build();
+        send_bind_message!(subject_subs, peer_actors);
+
+        addr.try_send(NodeFromUiMessage {
+            client_id: 1234,
+            body: request.tmb(4321),
+        })
+        .unwrap();
+
+        System::current().stop();
+        system.run();
+        (accountant_recording_arc, ui_gateway_recording_arc)
+    }
+
+    #[test]
+    fn verify_payment_request_sends_the_receipt_and_amount_paid_on_to_the_accountant() {
+        let get_transaction_receipt_params_arc = Arc::new(Mutex::new(vec![]));
+        let contract_address = TEST_DEFAULT_CHAIN.rec().contract;
+        let creditor = make_wallet("creditor");
+        let hash = make_tx_hash(0x1f2e);
+        let mut receipt = TransactionReceipt::default();
+        receipt.transaction_hash = hash;
+        receipt.status = Some(U64::from(1));
+        receipt.block_number = Some(U64::from(4_444));
+        receipt.logs = vec![
+            make_transfer_log(contract_address, &creditor, 123_456_789),
+            make_transfer_log(contract_address, &make_wallet("bystander"), 1_000),
+        ];
+        let blockchain_interface = BlockchainInterfaceMock::default()
+            .contract_address_result(contract_address)
+            .get_transaction_receipt_params(&get_transaction_receipt_params_arc)
+            .get_transaction_receipt_result(Ok(Some(receipt.clone())));
+        let request = UiVerifyPaymentRequest {
+            transaction_hash: format!("{:#x}", hash),
+            creditor_wallet: creditor.to_string(),
+        };
+
+        let (accountant_recording_arc, ui_gateway_recording_arc) = run_verify_payment_request(
+            "verify_payment_request_sends_the_receipt_and_amount_paid_on_to_the_accountant",
+            blockchain_interface,
+            request,
+        );
+
+        let get_transaction_receipt_params = get_transaction_receipt_params_arc.lock().unwrap();
+        assert_eq!(*get_transaction_receipt_params, vec![hash]);
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(
+            accountant_recording.get_record::<ReportPaymentReceipt>(0),
+            &ReportPaymentReceipt {
+                transaction_hash: hash,
+                creditor,
+                receipt_opt: Some(receipt),
+                paid_to_creditor_wei: 123_456_789,
+                response_skeleton: ResponseSkeleton {
+                    client_id: 1234,
+                    context_id: 4321,
+                },
+            }
+        );
+        assert_eq!(accountant_recording.len(), 1);
+        assert_eq!(ui_gateway_recording_arc.lock().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn verify_payment_request_passes_along_a_transaction_without_a_receipt_yet() {
+        let creditor = make_wallet("creditor");
+        let hash = make_tx_hash(0x1f2e);
+        let blockchain_interface =
+            BlockchainInterfaceMock::default().get_transaction_receipt_result(Ok(None));
+        let request = UiVerifyPaymentRequest {
+            transaction_hash: format!("{:#x}", hash),
+            creditor_wallet: creditor.to_string(),
+        };
+
+        let (accountant_recording_arc, _) = run_verify_payment_request(
+            "verify_payment_request_passes_along_a_transaction_without_a_receipt_yet",
+            blockchain_interface,
+            request,
+        );
+
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        let report = accountant_recording.get_record::<ReportPaymentReceipt>(0);
+        assert_eq!(report.receipt_opt, None);
+        assert_eq!(report.paid_to_creditor_wei, 0);
+    }
+
+    fn assert_verify_payment_error(
+        ui_gateway_recording_arc: &Arc<Mutex<Recording>>,
+        expected_code: u64,
+        expected_message: &str,
+    ) {
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            &NodeToUiMessage {
+                target: MessageTarget::ClientId(1234),
+                body: MessageBody {
+                    opcode: "verifyPayment".to_string(),
+                    path: MessagePath::Conversation(4321),
+                    payload: Err((expected_code, expected_message.to_string())),
+                },
+            }
+        );
+        assert_eq!(ui_gateway_recording.len(), 1);
+    }
+
+    #[test]
+    fn verify_payment_request_complains_about_a_malformed_transaction_hash() {
+        let request = UiVerifyPaymentRequest {
+            transaction_hash: "0xbooga".to_string(),
+            creditor_wallet: make_wallet("creditor").to_string(),
+        };
+
+        let (accountant_recording_arc, ui_gateway_recording_arc) = run_verify_payment_request(
+            "verify_payment_request_complains_about_a_malformed_transaction_hash",
+            BlockchainInterfaceMock::default(),
+            request,
+        );
+
+        assert_verify_payment_error(
+            &ui_gateway_recording_arc,
+            INVALID_PAYMENT_REFERENCE_ERROR,
+            "Transaction hash '0xbooga' should be 0x and 64 hex digits",
+        );
+        assert_eq!(accountant_recording_arc.lock().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn verify_payment_request_complains_about_a_malformed_creditor_wallet() {
+        let request = UiVerifyPaymentRequest {
+            transaction_hash: format!("{:#x}", make_tx_hash(0x1f2e)),
+            creditor_wallet: "0x123".to_string(),
+        };
+
+        let (accountant_recording_arc, ui_gateway_recording_arc) = run_verify_payment_request(
+            "verify_payment_request_complains_about_a_malformed_creditor_wallet",
+            BlockchainInterfaceMock::default(),
+            request,
+        );
+
+        assert_verify_payment_error(
+            &ui_gateway_recording_arc,
+            INVALID_PAYMENT_REFERENCE_ERROR,
+            "Creditor wallet '0x123' should be 0x and 40 hex digits",
+        );
+        assert_eq!(accountant_recording_arc.lock().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn verify_payment_request_reports_a_failed_receipt_query() {
+        let hash = make_tx_hash(0x1f2e);
+        let blockchain_interface = BlockchainInterfaceMock::default()
+            .get_transaction_receipt_result(Err(BlockchainError::QueryFailed(
+                "bad timing".to_string(),
+            )));
+        let request = UiVerifyPaymentRequest {
+            transaction_hash: format!("{:#x}", hash),
+            creditor_wallet: make_wallet("creditor").to_string(),
+        };
+
+        let (accountant_recording_arc, ui_gateway_recording_arc) = run_verify_payment_request(
+            "verify_payment_request_reports_a_failed_receipt_query",
+            blockchain_interface,
+            request,
+        );
+
+        assert_verify_payment_error(
+            &ui_gateway_recording_arc,
+            TRANSACTION_RECEIPT_QUERY_ERROR,
+            &format!(
+                "Couldn't get the receipt for transaction {:?}: QueryFailed(\"bad timing\")",
+                hash
+            ),
+        );
+        assert_eq!(accountant_recording_arc.lock().unwrap().len(), 0);
+    }
+
     #[test]
     fn extract_max_block_range_from_error_response() {
         let result = BlockchainError::QueryFailed("RPC error: Error { code: ServerError(-32005), message: \"eth_getLogs block range too large, range: 33636, max: 3500\", data: None }".to_string());
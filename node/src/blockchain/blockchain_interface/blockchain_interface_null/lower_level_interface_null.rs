@@ -2,7 +2,7 @@
 
 use crate::blockchain::blockchain_interface::data_structures::errors::BlockchainError;
 use crate::blockchain::blockchain_interface::lower_level_interface::{
-    LatestBlockNumber, LowBlockchainInt, ResultForBalance, ResultForNonce,
+    LatestBlockNumber, LowBlockchainInt, ResultForBalance, ResultForBlockHash, ResultForNonce,
 };
 use crate::sub_lib::wallet::Wallet;
 use masq_lib::logger::Logger;
@@ -24,9 +24,17 @@ impl LowBlockchainInt for LowBlockChainIntNull {
         Err(self.handle_null_call("block number"))
     }
 
+    fn get_block_hash(&self, _block_number: u64) -> ResultForBlockHash {
+        Err(self.handle_null_call("block hash"))
+    }
+
     fn get_transaction_id(&self, _wallet: &Wallet) -> ResultForNonce {
         Err(self.handle_null_call("transaction id"))
     }
+
+    fn get_confirmed_transaction_id(&self, _wallet: &Wallet) -> ResultForNonce {
+        Err(self.handle_null_call("confirmed transaction id"))
+    }
 }
 
 impl LowBlockChainIntNull {
@@ -81,6 +89,14 @@ mod tests {
         test_null_method(test_name, act, "block number");
     }
 
+    #[test]
+    fn low_bci_null_gets_no_block_hash() {
+        let test_name = "low_bci_null_gets_no_block_hash";
+        let act = |subject: &LowBlockChainIntNull, _wallet: &Wallet| subject.get_block_hash(123);
+
+        test_null_method(test_name, act, "block hash");
+    }
+
     #[test]
     fn low_bci_null_gets_no_transaction_id() {
         let test_name = "low_bci_null_gets_no_transaction_id";
@@ -90,6 +106,16 @@ mod tests {
         test_null_method(test_name, act, "transaction id");
     }
 
+    #[test]
+    fn low_bci_null_gets_no_confirmed_transaction_id() {
+        let test_name = "low_bci_null_gets_no_confirmed_transaction_id";
+        let act = |subject: &LowBlockChainIntNull, wallet: &Wallet| {
+            subject.get_confirmed_transaction_id(wallet)
+        };
+
+        test_null_method(test_name, act, "confirmed transaction id");
+    }
+
     fn test_null_method<T: Debug + PartialEq>(
         test_name: &str,
         act: fn(&LowBlockChainIntNull, &Wallet) -> Result<T, BlockchainError>,
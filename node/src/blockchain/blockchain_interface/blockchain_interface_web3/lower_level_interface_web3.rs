@@ -2,14 +2,14 @@
 
 use crate::blockchain::blockchain_interface::data_structures::errors::BlockchainError;
 use crate::blockchain::blockchain_interface::lower_level_interface::{
-    LatestBlockNumber, LowBlockchainInt, ResultForBalance, ResultForNonce,
+    LatestBlockNumber, LowBlockchainInt, ResultForBalance, ResultForBlockHash, ResultForNonce,
 };
 use crate::sub_lib::wallet::Wallet;
 use futures::Future;
 use std::rc::Rc;
 use web3::contract::{Contract, Options};
 use web3::transports::Batch;
-use web3::types::BlockNumber;
+use web3::types::{BlockId, BlockNumber};
 use web3::{BatchTransport, Web3};
 
 pub struct LowBlockchainIntWeb3<T>
@@ -55,6 +55,15 @@ where
             .wait()
     }
 
+    fn get_block_hash(&self, block_number: u64) -> ResultForBlockHash {
+        self.web3
+            .eth()
+            .block(BlockId::Number(BlockNumber::Number(block_number.into())))
+            .map(|block_opt| block_opt.and_then(|block| block.hash))
+            .map_err(|e| BlockchainError::QueryFailed(format!("{} for block {}", e, block_number)))
+            .wait()
+    }
+
     fn get_transaction_id(&self, wallet: &Wallet) -> ResultForNonce {
         self.web3
             .eth()
@@ -62,6 +71,14 @@ where
             .map_err(|e| BlockchainError::QueryFailed(format!("{} for wallet {}", e, wallet)))
             .wait()
     }
+
+    fn get_confirmed_transaction_id(&self, wallet: &Wallet) -> ResultForNonce {
+        self.web3
+            .eth()
+            .transaction_count(wallet.address(), Some(BlockNumber::Latest))
+            .map_err(|e| BlockchainError::QueryFailed(format!("{} for wallet {}", e, wallet)))
+            .wait()
+    }
 }
 
 impl<T> LowBlockchainIntWeb3<T>
@@ -99,7 +116,7 @@ mod tests {
     use std::sync::{Arc, Mutex};
     use web3::contract::Contract;
     use web3::transports::{Batch, Http};
-    use web3::types::U256;
+    use web3::types::{H256, U256};
     use web3::{BatchTransport, Web3};
     use crate::blockchain::test_utils::TestTransport;
 
@@ -295,6 +312,56 @@ mod tests {
         assert_eq!(actual_arguments, expected_arguments);
     }
 
+    #[test]
+    fn low_interface_web3_can_fetch_block_hash_successfully() {
+        let prepare_params_arc = Arc::new(Mutex::new(vec![]));
+        let transport = TestTransport::default()
+            .prepare_params(&prepare_params_arc)
+            .send_result(json!({
+                "hash": "0x000000000000000000000000000000000000000000000000000000000000abcd",
+                "parentHash": "0x0000000000000000000000000000000000000000000000000000000000001234",
+                "sha3Uncles": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "miner": "0x0000000000000000000000000000000000000000",
+                "stateRoot": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "transactionsRoot": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "receiptsRoot": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "number": "0x1e37066",
+                "gasUsed": "0x0",
+                "gasLimit": "0x0",
+                "extraData": "0x",
+                "logsBloom": format!("0x{}", "0".repeat(512)),
+                "timestamp": "0x0",
+                "difficulty": "0x0",
+                "totalDifficulty": "0x0",
+                "sealFields": [],
+                "uncles": [],
+                "transactions": [],
+                "size": "0x0",
+                "mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "nonce": "0x0000000000000000"
+            }));
+        let subject = make_subject(transport, TEST_DEFAULT_CHAIN);
+
+        let result = subject.get_block_hash(0x1e37066);
+
+        assert_eq!(result, Ok(Some(H256::from_low_u64_be(0xabcd))));
+        let mut prepare_params = prepare_params_arc.lock().unwrap();
+        let (method_name, actual_arguments) = prepare_params.remove(0);
+        assert!(prepare_params.is_empty());
+        assert_eq!(method_name, "eth_getBlockByNumber".to_string());
+        assert_eq!(actual_arguments, vec![json!("0x1e37066"), json!(false)]);
+    }
+
+    #[test]
+    fn low_interface_web3_reports_no_hash_for_block_unknown_to_the_chain() {
+        let transport = TestTransport::default().send_result(Value::Null);
+        let subject = make_subject(transport, TEST_DEFAULT_CHAIN);
+
+        let result = subject.get_block_hash(0x1e37066);
+
+        assert_eq!(result, Ok(None));
+    }
+
     #[test]
     fn low_interface_web3_get_transaction_id_works() {
         let prepare_params_arc = Arc::new(Mutex::new(vec![]));
@@ -345,6 +412,46 @@ mod tests {
         assert_error_from_unintelligible_response(act, "invalid hex character")
     }
 
+    #[test]
+    fn low_interface_web3_get_confirmed_transaction_id_works() {
+        let prepare_params_arc = Arc::new(Mutex::new(vec![]));
+        let transport = TestTransport::default()
+            .prepare_params(&prepare_params_arc)
+            .send_result(json!(
+                "0x0000000000000000000000000000000000000000000000000000000000000007"
+            ));
+        let chain = TEST_DEFAULT_CHAIN;
+        let subject = make_subject(transport, chain);
+
+        let result = subject.get_confirmed_transaction_id(&make_paying_wallet(b"gdasgsa"));
+
+        assert_eq!(result, Ok(U256::from(7)));
+        let mut prepare_params = prepare_params_arc.lock().unwrap();
+        let (method_name, actual_arguments) = prepare_params.remove(0);
+        assert!(prepare_params.is_empty());
+        let actual_arguments: Vec<String> = actual_arguments
+            .into_iter()
+            .map(|arg| serde_json::to_string(&arg).unwrap())
+            .collect();
+        assert_eq!(method_name, "eth_getTransactionCount".to_string());
+        assert_eq!(
+            actual_arguments,
+            vec![
+                String::from(r#""0x5c361ba8d82fcf0e5538b2a823e9d457a2296725""#),
+                String::from(r#""latest""#),
+            ]
+        );
+    }
+
+    #[test]
+    fn low_interface_web3_get_confirmed_transaction_id_handles_err() {
+        let act = |subject: &LowBlockchainIntWeb3<Http>, wallet: &Wallet| {
+            subject.get_confirmed_transaction_id(wallet)
+        };
+
+        assert_error_from_unintelligible_response(act, "invalid hex character")
+    }
+
     fn assert_error_from_unintelligible_response<F>(act: F, expected_err_fragment: &str)
     where
         F: FnOnce(&LowBlockchainIntWeb3<Http>, &Wallet) -> ResultForBalance,
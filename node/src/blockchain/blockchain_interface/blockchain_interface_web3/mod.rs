@@ -33,7 +33,7 @@ use web3::contract::Contract;
 use web3::transports::{Batch, EventLoopHandle};
 use web3::types::{
     Address, BlockNumber, Bytes, FilterBuilder, Log, SignedTransaction, TransactionParameters,
-    H160, H256, U256,
+    TransactionReceipt, H160, H256, U256,
 };
 use web3::{BatchTransport, Error, Web3};
 use crate::accountant::db_access_objects::pending_payable_dao::PendingPayable;
@@ -74,6 +74,7 @@ where
 {
     logger: Logger,
     chain: Chain,
+    contract_address: Address,
     gas_limit_const_part: u64,
     // This must not be dropped for Web3 requests to be completed
     _event_loop_handle: EventLoopHandle,
@@ -88,7 +89,7 @@ where
     T: 'static + BatchTransport + Debug,
 {
     fn contract_address(&self) -> Address {
-        self.chain.rec().contract
+        self.contract_address
     }
 
     fn retrieve_transactions(
@@ -280,7 +281,7 @@ where
             self.logger,
             "Common attributes of payables to be transacted: sender wallet: {}, contract: {:?}, chain_id: {}, gas_price: {}",
             consuming_wallet,
-            self.chain.rec().contract,
+            self.contract_address,
             self.chain.rec().num_chain_id,
             gas_price
         );
@@ -333,12 +334,21 @@ where
     T: 'static + BatchTransport + Debug,
 {
     pub fn new(transport: T, event_loop_handle: EventLoopHandle, chain: Chain) -> Self {
+        Self::new_with_contract(transport, event_loop_handle, chain, chain.rec().contract)
+    }
+
+    // For private forks and new deployments whose token contract isn't in the chain records
+    pub fn new_with_contract(
+        transport: T,
+        event_loop_handle: EventLoopHandle,
+        chain: Chain,
+        contract_address: Address,
+    ) -> Self {
         let web3 = Rc::new(Web3::new(transport.clone()));
         let web3_batch = Rc::new(Web3::new(Batch::new(transport)));
         let batch_payable_tools = Box::new(BatchPayableToolsReal::<T>::default());
-        let contract =
-            Contract::from_json(web3.eth(), chain.rec().contract, CONTRACT_ABI.as_bytes())
-                .expect("Unable to initialize contract.");
+        let contract = Contract::from_json(web3.eth(), contract_address, CONTRACT_ABI.as_bytes())
+            .expect("Unable to initialize contract.");
         let lower_level_blockchain_interface = Box::new(LowBlockchainIntWeb3::new(
             Rc::clone(&web3),
             Rc::clone(&web3_batch),
@@ -349,6 +359,7 @@ where
         Self {
             logger: Logger::new("BlockchainInterface"),
             chain,
+            contract_address,
             gas_limit_const_part,
             _event_loop_handle: event_loop_handle,
             web3,
@@ -623,6 +634,26 @@ where
 
 type HashAndAmountResult = Result<Vec<(H256, u128)>, PayableTransactionError>;
 
+// Adds up what a transaction's Transfer events say it moved through the token contract to the
+// recipient; a receipt for anything but a token payment to that wallet comes to zero
+pub fn tokens_transferred_to(
+    receipt: &TransactionReceipt,
+    contract_address: Address,
+    recipient: &Wallet,
+) -> u128 {
+    receipt
+        .logs
+        .iter()
+        .filter(|log| {
+            log.address == contract_address
+                && log.topics.len() == 3
+                && log.topics[0] == TRANSACTION_LITERAL
+                && Wallet::from(log.topics[2]) == *recipient
+        })
+        .map(|log| U256::from(log.data.0.as_slice()).as_u128())
+        .fold(0, u128::saturating_add)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::accountant::db_access_objects::utils::from_time_t;
@@ -636,8 +667,8 @@ mod tests {
     use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
 
     use crate::blockchain::blockchain_interface::blockchain_interface_web3::{
-        BlockchainInterfaceWeb3, CONTRACT_ABI, REQUESTS_IN_PARALLEL, TRANSACTION_LITERAL,
-        TRANSFER_METHOD_ID,
+        tokens_transferred_to, BlockchainInterfaceWeb3, CONTRACT_ABI, REQUESTS_IN_PARALLEL,
+        TRANSACTION_LITERAL, TRANSFER_METHOD_ID,
     };
     use crate::blockchain::blockchain_interface::test_utils::{
         test_blockchain_interface_is_connected_and_functioning, LowBlockchainIntMock,
@@ -677,7 +708,7 @@ mod tests {
     use crate::accountant::db_access_objects::pending_payable_dao::PendingPayable;
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::test_utils::BlockchainAgentMock;
     use crate::blockchain::blockchain_interface::blockchain_interface_web3::test_utils::{
-        make_default_signed_transaction, BatchPayableToolsMock,
+        make_default_signed_transaction, make_transfer_log, BatchPayableToolsMock,
     };
     use crate::blockchain::blockchain_interface::data_structures::{
         BlockchainTransaction, RpcPayablesFailure,
@@ -741,6 +772,19 @@ mod tests {
         })
     }
 
+    #[test]
+    fn blockchain_interface_web3_can_return_overriding_contract() {
+        let contract_address = H160::from_low_u64_be(0x1234);
+        let subject = BlockchainInterfaceWeb3::new_with_contract(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            TEST_DEFAULT_CHAIN,
+            contract_address,
+        );
+
+        assert_eq!(subject.contract_address(), contract_address);
+    }
+
     #[test]
     fn blockchain_interface_web3_provides_plain_rp_calls_correctly() {
         let subject_factory = |port: u16, _chain: Chain| {
@@ -2304,6 +2348,37 @@ mod tests {
         )
     }
 
+    #[test]
+    fn tokens_transferred_to_adds_up_transfers_to_the_recipient_through_the_contract() {
+        let contract_address = TEST_DEFAULT_CHAIN.rec().contract;
+        let creditor = make_wallet("creditor");
+        let mut receipt = TransactionReceipt::default();
+        receipt.logs = vec![
+            make_transfer_log(contract_address, &creditor, 1_000_000),
+            make_transfer_log(contract_address, &make_wallet("bystander"), 20_000_000),
+            make_transfer_log(Address::from_low_u64_be(0x1234), &creditor, 300_000_000),
+            make_transfer_log(contract_address, &creditor, 4_000),
+        ];
+
+        let result = tokens_transferred_to(&receipt, contract_address, &creditor);
+
+        assert_eq!(result, 1_004_000);
+    }
+
+    #[test]
+    fn tokens_transferred_to_ignores_logs_that_arent_transfers() {
+        let contract_address = TEST_DEFAULT_CHAIN.rec().contract;
+        let creditor = make_wallet("creditor");
+        let mut approval_log = make_transfer_log(contract_address, &creditor, 1_000_000);
+        approval_log.topics[0] = make_tx_hash(0x1234);
+        let mut receipt = TransactionReceipt::default();
+        receipt.logs = vec![approval_log];
+
+        let result = tokens_transferred_to(&receipt, contract_address, &creditor);
+
+        assert_eq!(result, 0);
+    }
+
     #[test]
     fn hash_the_smart_contract_transfer_function_signature() {
         assert_eq!(
@@ -4,13 +4,17 @@
 
 use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
 use crate::blockchain::blockchain_interface::blockchain_interface_web3::batch_payable_tools::BatchPayableTools;
+use crate::blockchain::blockchain_interface::blockchain_interface_web3::TRANSACTION_LITERAL;
+use crate::sub_lib::wallet::Wallet;
+use crate::test_utils::make_wallet;
 use actix::Recipient;
+use ethereum_types::BigEndianHash;
 use jsonrpc_core as rpc;
 use std::cell::RefCell;
 use std::sync::{Arc, Mutex};
 use std::time::SystemTime;
 use web3::transports::Batch;
-use web3::types::{Bytes, SignedTransaction, TransactionParameters, H256};
+use web3::types::{Address, Bytes, Log, SignedTransaction, TransactionParameters, H256, U256};
 use web3::{BatchTransport, Error as Web3Error, Web3};
 
 #[derive(Default)]
@@ -170,3 +174,16 @@ pub fn make_default_signed_transaction() -> SignedTransaction {
         transaction_hash: Default::default(),
     }
 }
+
+pub fn make_transfer_log(contract_address: Address, to: &Wallet, wei: u128) -> Log {
+    serde_json::from_value(serde_json::json!({
+        "address": contract_address,
+        "topics": [
+            TRANSACTION_LITERAL,
+            H256::from(make_wallet("sender").address()),
+            H256::from(to.address()),
+        ],
+        "data": Bytes(H256::from_uint(&U256::from(wei)).0.to_vec()),
+    }))
+    .unwrap()
+}
@@ -3,6 +3,8 @@
 pub mod errors;
 use crate::accountant::db_access_objects::pending_payable_dao::PendingPayable;
 use crate::sub_lib::wallet::Wallet;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 use web3::types::{BlockNumber, H256};
 use web3::Error;
 
@@ -27,3 +29,75 @@ pub struct RpcPayablesFailure {
     pub recipient_wallet: Wallet,
     pub hash: H256,
 }
+
+// A block the receivable scan has already passed over, remembered together with its hash so that
+// a later scan can tell whether the chain still agrees with what we saw
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScannedBlock {
+    pub number: u64,
+    pub hash: H256,
+}
+
+impl Display for ScannedBlock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{:?}", self.number, self.hash)
+    }
+}
+
+impl FromStr for ScannedBlock {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (number, hash) = s
+            .split_once(':')
+            .ok_or_else(|| format!("Scanned block '{}' is missing a colon", s))?;
+        let number = number
+            .parse::<u64>()
+            .map_err(|e| format!("Bad block number in scanned block '{}': {}", s, e))?;
+        let hash = H256::from_str(hash.trim_start_matches("0x"))
+            .map_err(|e| format!("Bad block hash in scanned block '{}': {}", s, e))?;
+        Ok(ScannedBlock { number, hash })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scanned_block_survives_a_round_trip_through_its_string_form() {
+        let subject = ScannedBlock {
+            number: 1_234_567,
+            hash: H256::from_low_u64_be(0xabcdef),
+        };
+
+        let string = subject.to_string();
+        let result = ScannedBlock::from_str(&string);
+
+        assert_eq!(
+            string,
+            "1234567:0x0000000000000000000000000000000000000000000000000000000000abcdef"
+        );
+        assert_eq!(result, Ok(subject));
+    }
+
+    #[test]
+    fn scanned_block_rejects_malformed_strings() {
+        assert_eq!(
+            ScannedBlock::from_str("1234567"),
+            Err("Scanned block '1234567' is missing a colon".to_string())
+        );
+        assert_eq!(
+            ScannedBlock::from_str("booga:0x1234")
+                .unwrap_err()
+                .starts_with("Bad block number in scanned block 'booga:0x1234': "),
+            true
+        );
+        assert_eq!(
+            ScannedBlock::from_str("12:0xnothex")
+                .unwrap_err()
+                .starts_with("Bad block hash in scanned block '12:0xnothex': "),
+            true
+        );
+    }
+}
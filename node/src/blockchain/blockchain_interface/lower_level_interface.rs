@@ -3,7 +3,7 @@
 use crate::blockchain::blockchain_interface::data_structures::errors::BlockchainResult;
 use crate::sub_lib::wallet::Wallet;
 use ethereum_types::U64;
-use web3::types::U256;
+use web3::types::{H256, U256};
 
 pub trait LowBlockchainInt {
     fn get_transaction_fee_balance(&self, wallet: &Wallet) -> ResultForBalance;
@@ -12,10 +12,17 @@ pub trait LowBlockchainInt {
 
     fn get_block_number(&self) -> LatestBlockNumber;
 
+    // Ok(None) means the chain doesn't know of a block with this number (yet)
+    fn get_block_hash(&self, block_number: u64) -> ResultForBlockHash;
+
     fn get_transaction_id(&self, wallet: &Wallet) -> ResultForNonce;
+
+    // Unlike get_transaction_id(), counts only transactions already in a block
+    fn get_confirmed_transaction_id(&self, wallet: &Wallet) -> ResultForNonce;
 }
 
 pub type ResultForBalance = BlockchainResult<web3::types::U256>;
 pub type ResultForBothBalances = BlockchainResult<(web3::types::U256, web3::types::U256)>;
 pub type ResultForNonce = BlockchainResult<U256>;
 pub type LatestBlockNumber = BlockchainResult<U64>;
+pub type ResultForBlockHash = BlockchainResult<Option<H256>>;
@@ -3,7 +3,7 @@
 #![cfg(test)]
 
 use crate::blockchain::blockchain_interface::lower_level_interface::{
-    LatestBlockNumber, LowBlockchainInt, ResultForBalance, ResultForNonce,
+    LatestBlockNumber, LowBlockchainInt, ResultForBalance, ResultForBlockHash, ResultForNonce,
 };
 use crate::blockchain::blockchain_interface::BlockchainInterface;
 use crate::sub_lib::wallet::Wallet;
@@ -22,8 +22,12 @@ pub struct LowBlockchainIntMock {
     get_masq_balance_params: Arc<Mutex<Vec<Wallet>>>,
     get_masq_balance_results: RefCell<Vec<ResultForBalance>>,
     get_block_number_results: RefCell<Vec<LatestBlockNumber>>,
+    get_block_hash_params: Arc<Mutex<Vec<u64>>>,
+    get_block_hash_results: RefCell<Vec<ResultForBlockHash>>,
     get_transaction_id_params: Arc<Mutex<Vec<Wallet>>>,
     get_transaction_id_results: RefCell<Vec<ResultForNonce>>,
+    get_confirmed_transaction_id_params: Arc<Mutex<Vec<Wallet>>>,
+    get_confirmed_transaction_id_results: RefCell<Vec<ResultForNonce>>,
 }
 
 impl LowBlockchainInt for LowBlockchainIntMock {
@@ -49,6 +53,14 @@ impl LowBlockchainInt for LowBlockchainIntMock {
         self.get_block_number_results.borrow_mut().remove(0)
     }
 
+    fn get_block_hash(&self, block_number: u64) -> ResultForBlockHash {
+        self.get_block_hash_params
+            .lock()
+            .unwrap()
+            .push(block_number);
+        self.get_block_hash_results.borrow_mut().remove(0)
+    }
+
     fn get_transaction_id(&self, address: &Wallet) -> ResultForNonce {
         self.get_transaction_id_params
             .lock()
@@ -56,6 +68,16 @@ impl LowBlockchainInt for LowBlockchainIntMock {
             .push(address.clone());
         self.get_transaction_id_results.borrow_mut().remove(0)
     }
+
+    fn get_confirmed_transaction_id(&self, address: &Wallet) -> ResultForNonce {
+        self.get_confirmed_transaction_id_params
+            .lock()
+            .unwrap()
+            .push(address.clone());
+        self.get_confirmed_transaction_id_results
+            .borrow_mut()
+            .remove(0)
+    }
 }
 
 impl LowBlockchainIntMock {
@@ -86,6 +108,16 @@ impl LowBlockchainIntMock {
         self
     }
 
+    pub fn get_block_hash_params(mut self, params: &Arc<Mutex<Vec<u64>>>) -> Self {
+        self.get_block_hash_params = params.clone();
+        self
+    }
+
+    pub fn get_block_hash_result(self, result: ResultForBlockHash) -> Self {
+        self.get_block_hash_results.borrow_mut().push(result);
+        self
+    }
+
     pub fn get_transaction_id_params(mut self, params: &Arc<Mutex<Vec<Wallet>>>) -> Self {
         self.get_transaction_id_params = params.clone();
         self
@@ -95,6 +127,18 @@ impl LowBlockchainIntMock {
         self.get_transaction_id_results.borrow_mut().push(result);
         self
     }
+
+    pub fn get_confirmed_transaction_id_params(mut self, params: &Arc<Mutex<Vec<Wallet>>>) -> Self {
+        self.get_confirmed_transaction_id_params = params.clone();
+        self
+    }
+
+    pub fn get_confirmed_transaction_id_result(self, result: ResultForNonce) -> Self {
+        self.get_confirmed_transaction_id_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
 }
 
 pub fn test_blockchain_interface_is_connected_and_functioning<F>(subject_factory: F)
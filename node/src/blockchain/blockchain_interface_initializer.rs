@@ -6,6 +6,7 @@ use crate::blockchain::blockchain_interface::blockchain_interface_web3::{
 use crate::blockchain::blockchain_interface::BlockchainInterface;
 use masq_lib::blockchains::chains::Chain;
 use web3::transports::Http;
+use web3::types::Address;
 
 pub(in crate::blockchain) struct BlockchainInterfaceInitializer {}
 
@@ -16,21 +17,26 @@ impl BlockchainInterfaceInitializer {
         &self,
         blockchain_service_url: &str,
         chain: Chain,
+        token_contract_address_opt: Option<Address>,
     ) -> Box<dyn BlockchainInterface> {
-        self.initialize_web3_interface(blockchain_service_url, chain)
+        self.initialize_web3_interface(blockchain_service_url, chain, token_contract_address_opt)
     }
 
     fn initialize_web3_interface(
         &self,
         blockchain_service_url: &str,
         chain: Chain,
+        token_contract_address_opt: Option<Address>,
     ) -> Box<dyn BlockchainInterface> {
         match Http::with_max_parallel(blockchain_service_url, REQUESTS_IN_PARALLEL) {
-            Ok((event_loop_handle, transport)) => Box::new(BlockchainInterfaceWeb3::new(
-                transport,
-                event_loop_handle,
-                chain,
-            )),
+            Ok((event_loop_handle, transport)) => {
+                Box::new(BlockchainInterfaceWeb3::new_with_contract(
+                    transport,
+                    event_loop_handle,
+                    chain,
+                    token_contract_address_opt.unwrap_or(chain.rec().contract),
+                ))
+            }
             Err(e) => panic!(
                 "Invalid blockchain service URL \"{}\". Error: {:?}. Chain: {}",
                 blockchain_service_url,
@@ -41,9 +47,40 @@ impl BlockchainInterfaceInitializer {
     }
 }
 
+// Lets the BlockchainBridge bring up an interface to another blockchain service URL at runtime
+pub trait BlockchainInterfaceFactory {
+    fn make(&self, blockchain_service_url: &str) -> Box<dyn BlockchainInterface>;
+}
+
+pub struct BlockchainInterfaceFactoryReal {
+    chain: Chain,
+    token_contract_address_opt: Option<Address>,
+}
+
+impl BlockchainInterfaceFactory for BlockchainInterfaceFactoryReal {
+    fn make(&self, blockchain_service_url: &str) -> Box<dyn BlockchainInterface> {
+        BlockchainInterfaceInitializer {}.initialize_interface(
+            blockchain_service_url,
+            self.chain,
+            self.token_contract_address_opt,
+        )
+    }
+}
+
+impl BlockchainInterfaceFactoryReal {
+    pub fn new(chain: Chain, token_contract_address_opt: Option<Address>) -> Self {
+        Self {
+            chain,
+            token_contract_address_opt,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::blockchain::blockchain_interface_initializer::BlockchainInterfaceInitializer;
+    use crate::blockchain::blockchain_interface_initializer::{
+        BlockchainInterfaceFactory, BlockchainInterfaceFactoryReal, BlockchainInterfaceInitializer,
+    };
     use masq_lib::blockchains::chains::Chain;
 
     use std::net::Ipv4Addr;
@@ -51,18 +88,53 @@ mod tests {
     use crate::blockchain::blockchain_interface::test_utils::test_blockchain_interface_is_connected_and_functioning;
 
     use masq_lib::constants::DEFAULT_CHAIN;
+    use web3::types::Address;
 
     #[test]
     fn initialize_web3_interface_works() {
         let subject_factory = |port: u16, chain: Chain| {
             let subject = BlockchainInterfaceInitializer {};
             let server_url = &format!("http://{}:{}", &Ipv4Addr::LOCALHOST.to_string(), port);
-            subject.initialize_web3_interface(server_url, chain)
+            subject.initialize_web3_interface(server_url, chain, None)
         };
 
         test_blockchain_interface_is_connected_and_functioning(subject_factory)
     }
 
+    #[test]
+    fn initialize_web3_interface_uses_the_chain_contract_without_an_override() {
+        let subject = BlockchainInterfaceInitializer {};
+
+        let result =
+            subject.initialize_web3_interface("http://127.0.0.1:8545", DEFAULT_CHAIN, None);
+
+        assert_eq!(result.contract_address(), DEFAULT_CHAIN.rec().contract);
+    }
+
+    #[test]
+    fn initialize_web3_interface_uses_the_overriding_contract() {
+        let contract_address = Address::from_low_u64_be(0x1234);
+        let subject = BlockchainInterfaceInitializer {};
+
+        let result = subject.initialize_web3_interface(
+            "http://127.0.0.1:8545",
+            DEFAULT_CHAIN,
+            Some(contract_address),
+        );
+
+        assert_eq!(result.contract_address(), contract_address);
+    }
+
+    #[test]
+    fn factory_makes_interfaces_with_its_chain_and_contract() {
+        let contract_address = Address::from_low_u64_be(0x5678);
+        let subject = BlockchainInterfaceFactoryReal::new(DEFAULT_CHAIN, Some(contract_address));
+
+        let result = subject.make("http://127.0.0.1:8546");
+
+        assert_eq!(result.contract_address(), contract_address);
+    }
+
     #[test]
     #[should_panic(expected = "Invalid blockchain service URL \"http://λ:8545\". \
     Error: Transport(\"InvalidUri(InvalidUriChar)\"). Chain: polygon-mainnet")]
@@ -70,6 +142,6 @@ mod tests {
         let blockchain_service_url = "http://λ:8545";
         let subject = BlockchainInterfaceInitializer {};
 
-        subject.initialize_web3_interface(blockchain_service_url, DEFAULT_CHAIN);
+        subject.initialize_web3_interface(blockchain_service_url, DEFAULT_CHAIN, None);
     }
 }
@@ -0,0 +1,279 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::sub_lib::blockchain_bridge::BlockchainServiceFailover;
+use masq_lib::logger::Logger;
+use masq_lib::messages::{UiAlertBroadcast, UiAlertRule};
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FailoverDecision {
+    pub alert_opt: Option<UiAlertBroadcast>,
+    pub switch_to_opt: Option<String>,
+}
+
+// Keeps track of how the blockchain service has been answering lately. Failures only count while
+// they come one after another; a single success wipes the slate clean. Once enough of them have
+// piled up within the window, the service is declared failing and the next URL takes over.
+pub struct BlockchainServiceHealthMonitor {
+    failover: BlockchainServiceFailover,
+    urls: Vec<String>,
+    current_index: usize,
+    failure_times: Vec<SystemTime>,
+    failing: bool,
+    logger: Logger,
+}
+
+impl BlockchainServiceHealthMonitor {
+    pub fn new(failover: BlockchainServiceFailover, primary_url: String, logger: Logger) -> Self {
+        let urls = vec![primary_url]
+            .into_iter()
+            .chain(failover.alternate_urls.iter().cloned())
+            .collect();
+        Self {
+            failover,
+            urls,
+            current_index: 0,
+            failure_times: vec![],
+            failing: false,
+            logger,
+        }
+    }
+
+    pub fn current_url(&self) -> &str {
+        &self.urls[self.current_index]
+    }
+
+    pub fn payables_paused(&self) -> bool {
+        self.failing && self.failover.pause_payables
+    }
+
+    pub fn record_failure(&mut self, now: SystemTime) -> FailoverDecision {
+        let window = Duration::from_secs(self.failover.failure_window_minutes * 60);
+        self.failure_times.retain(|failure_time| {
+            now.duration_since(*failure_time)
+                .map(|age| age <= window)
+                .unwrap_or(true)
+        });
+        self.failure_times.push(now);
+        if self.failure_times.len() < self.failover.failure_threshold {
+            return FailoverDecision::default();
+        }
+        self.failure_times.clear();
+        let failing_url = self.current_url().to_string();
+        let switch_to_opt = if self.urls.len() > 1 {
+            self.current_index = (self.current_index + 1) % self.urls.len();
+            Some(self.current_url().to_string())
+        } else {
+            None
+        };
+        let message = format!(
+            "{} requests in a row to blockchain service {} failed within {} minute(s); {}{}",
+            self.failover.failure_threshold,
+            failing_url,
+            self.failover.failure_window_minutes,
+            match &switch_to_opt {
+                Some(url) => format!("failing over to {}", url),
+                None => "no alternate URL is configured".to_string(),
+            },
+            if self.failover.pause_payables {
+                "; payable settlement is paused until the service recovers"
+            } else {
+                ""
+            }
+        );
+        warning!(self.logger, "{}", message);
+        let alert_opt = if self.failing {
+            None
+        } else {
+            self.failing = true;
+            Some(UiAlertBroadcast {
+                rule: UiAlertRule::BlockchainServiceFailing,
+                message,
+            })
+        };
+        FailoverDecision {
+            alert_opt,
+            switch_to_opt,
+        }
+    }
+
+    pub fn record_success(&mut self) {
+        self.failure_times.clear();
+        if self.failing {
+            self.failing = false;
+            info!(
+                self.logger,
+                "Blockchain service {} is answering again{}",
+                self.current_url(),
+                if self.failover.pause_payables {
+                    "; payable settlement resumes"
+                } else {
+                    ""
+                }
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+
+    fn make_subject(
+        test_name: &str,
+        pause_payables: bool,
+        alternate_urls: Vec<&str>,
+    ) -> BlockchainServiceHealthMonitor {
+        BlockchainServiceHealthMonitor::new(
+            BlockchainServiceFailover {
+                failure_threshold: 3,
+                failure_window_minutes: 10,
+                pause_payables,
+                alternate_urls: alternate_urls
+                    .into_iter()
+                    .map(|url| url.to_string())
+                    .collect(),
+            },
+            "https://primary.com".to_string(),
+            Logger::new(test_name),
+        )
+    }
+
+    fn minutes_after(start: SystemTime, minutes: u64) -> SystemTime {
+        start + Duration::from_secs(minutes * 60)
+    }
+
+    #[test]
+    fn failures_below_the_threshold_change_nothing() {
+        let mut subject = make_subject("failures_below_the_threshold_change_nothing", true, vec![]);
+        let start = SystemTime::now();
+
+        let first = subject.record_failure(start);
+        let second = subject.record_failure(minutes_after(start, 1));
+
+        assert_eq!(first, FailoverDecision::default());
+        assert_eq!(second, FailoverDecision::default());
+        assert_eq!(subject.payables_paused(), false);
+        assert_eq!(subject.current_url(), "https://primary.com");
+    }
+
+    #[test]
+    fn failures_outside_the_window_are_forgotten() {
+        let mut subject = make_subject("failures_outside_the_window_are_forgotten", true, vec![]);
+        let start = SystemTime::now();
+        subject.record_failure(start);
+        subject.record_failure(minutes_after(start, 1));
+
+        let result = subject.record_failure(minutes_after(start, 11));
+
+        assert_eq!(result, FailoverDecision::default());
+        assert_eq!(subject.payables_paused(), false);
+    }
+
+    #[test]
+    fn a_success_resets_the_count_of_failures() {
+        let mut subject = make_subject("a_success_resets_the_count_of_failures", true, vec![]);
+        let now = SystemTime::now();
+        subject.record_failure(now);
+        subject.record_failure(now);
+        subject.record_success();
+
+        let result = subject.record_failure(now);
+
+        assert_eq!(result, FailoverDecision::default());
+    }
+
+    #[test]
+    fn reaching_the_threshold_fails_over_to_the_next_url_and_alerts_once() {
+        init_test_logging();
+        let test_name = "reaching_the_threshold_fails_over_to_the_next_url_and_alerts_once";
+        let mut subject = make_subject(
+            test_name,
+            false,
+            vec!["https://alternate1.com", "https://alternate2.com"],
+        );
+        let now = SystemTime::now();
+        subject.record_failure(now);
+        subject.record_failure(now);
+
+        let first = subject.record_failure(now);
+        subject.record_failure(now);
+        subject.record_failure(now);
+        let second = subject.record_failure(now);
+        subject.record_failure(now);
+        subject.record_failure(now);
+        let third = subject.record_failure(now);
+
+        let expected_message = "3 requests in a row to blockchain service https://primary.com \
+            failed within 10 minute(s); failing over to https://alternate1.com";
+        assert_eq!(
+            first,
+            FailoverDecision {
+                alert_opt: Some(UiAlertBroadcast {
+                    rule: UiAlertRule::BlockchainServiceFailing,
+                    message: expected_message.to_string(),
+                }),
+                switch_to_opt: Some("https://alternate1.com".to_string()),
+            }
+        );
+        assert_eq!(
+            second,
+            FailoverDecision {
+                alert_opt: None,
+                switch_to_opt: Some("https://alternate2.com".to_string()),
+            }
+        );
+        assert_eq!(
+            third,
+            FailoverDecision {
+                alert_opt: None,
+                switch_to_opt: Some("https://primary.com".to_string()),
+            }
+        );
+        assert_eq!(subject.payables_paused(), false);
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_containing(&format!("WARN: {}: {}", test_name, expected_message));
+        tlh.exists_log_containing(&format!(
+            "WARN: {}: 3 requests in a row to blockchain service https://alternate2.com failed \
+            within 10 minute(s); failing over to https://primary.com",
+            test_name
+        ));
+    }
+
+    #[test]
+    fn payables_are_paused_while_failing_and_resume_on_recovery() {
+        init_test_logging();
+        let test_name = "payables_are_paused_while_failing_and_resume_on_recovery";
+        let mut subject = make_subject(test_name, true, vec![]);
+        let now = SystemTime::now();
+        subject.record_failure(now);
+        subject.record_failure(now);
+
+        let result = subject.record_failure(now);
+
+        assert_eq!(
+            result,
+            FailoverDecision {
+                alert_opt: Some(UiAlertBroadcast {
+                    rule: UiAlertRule::BlockchainServiceFailing,
+                    message: "3 requests in a row to blockchain service https://primary.com \
+                        failed within 10 minute(s); no alternate URL is configured; payable \
+                        settlement is paused until the service recovers"
+                        .to_string(),
+                }),
+                switch_to_opt: None,
+            }
+        );
+        assert_eq!(subject.payables_paused(), true);
+
+        subject.record_success();
+
+        assert_eq!(subject.payables_paused(), false);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {}: Blockchain service https://primary.com is answering again; payable \
+            settlement resumes",
+            test_name
+        ));
+    }
+}
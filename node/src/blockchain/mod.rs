@@ -5,8 +5,11 @@ pub mod bip39;
 pub mod blockchain_bridge;
 pub mod blockchain_interface;
 pub mod blockchain_interface_initializer;
+pub mod blockchain_service_health;
+pub mod nonce_tracker;
 pub mod payer;
 pub mod signature;
+pub mod transaction_fee_monitor;
 
 #[cfg(test)]
 pub mod test_utils;
@@ -0,0 +1,313 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::blockchain::blockchain_interface::data_structures::errors::PayableTransactionError;
+use crate::blockchain::blockchain_interface::data_structures::ProcessedPayableFallible;
+use crate::sub_lib::wallet::Wallet;
+use masq_lib::logger::Logger;
+use masq_lib::messages::{UiAlertBroadcast, UiAlertRule};
+use web3::types::U256;
+
+// Phrasings of the same complaint by different blockchain clients
+const NONCE_TOO_LOW_FRAGMENTS: [&str; 2] = ["nonce too low", "nonce is too low"];
+
+// Remembers which nonce the consuming wallet should be at if nobody but this Node is using it.
+// The chain always has the last word: every batch is built on a freshly queried pending nonce,
+// and this only notices when the chain disagrees with the Node's own bookkeeping.
+pub struct ConsumingWalletNonceTracker {
+    expected_opt: Option<(Wallet, U256)>,
+    logger: Logger,
+}
+
+impl ConsumingWalletNonceTracker {
+    pub fn new(logger: Logger) -> Self {
+        Self {
+            expected_opt: None,
+            logger,
+        }
+    }
+
+    // Called with the nonce the next batch will start from, as soon as it's been queried
+    pub fn resynchronize(
+        &mut self,
+        consuming_wallet: &Wallet,
+        pending_nonce: U256,
+        confirmed_nonce_opt: Option<U256>,
+    ) -> Option<UiAlertBroadcast> {
+        if let Some(confirmed_nonce) = confirmed_nonce_opt {
+            debug!(
+                self.logger,
+                "Consuming wallet {} is at nonce {} ({} confirmed)",
+                consuming_wallet,
+                pending_nonce,
+                confirmed_nonce
+            );
+        }
+        let expected_nonce = match self
+            .expected_opt
+            .replace((consuming_wallet.clone(), pending_nonce))
+        {
+            Some((wallet, nonce)) if &wallet == consuming_wallet => nonce,
+            _ => return None,
+        };
+        if pending_nonce > expected_nonce {
+            let message = format!(
+                "{} transaction(s) were sent from consuming wallet {} outside this Node; its \
+                payments will continue from nonce {}",
+                pending_nonce - expected_nonce,
+                consuming_wallet,
+                pending_nonce
+            );
+            Some(self.alert(message))
+        } else {
+            if pending_nonce < expected_nonce {
+                info!(
+                    self.logger,
+                    "{} transaction(s) sent from consuming wallet {} are no longer known to the \
+                    blockchain service; reusing nonces from {}",
+                    expected_nonce - pending_nonce,
+                    consuming_wallet,
+                    pending_nonce
+                );
+            }
+            None
+        }
+    }
+
+    // Called with the outcome of the batch built on the last resynchronization
+    pub fn record_batch(
+        &mut self,
+        result: &Result<Vec<ProcessedPayableFallible>, PayableTransactionError>,
+    ) -> Option<UiAlertBroadcast> {
+        let (consuming_wallet, first_nonce) = self.expected_opt.take()?;
+        let processed_payables = match result {
+            Ok(processed_payables) => processed_payables,
+            // There's no telling how many of the batch's nonces were used up
+            Err(_) => return None,
+        };
+        self.expected_opt = Some((
+            consuming_wallet.clone(),
+            first_nonce + U256::from(processed_payables.len()),
+        ));
+        let collided_recipients = processed_payables
+            .iter()
+            .filter_map(|processed_payable| match processed_payable {
+                Err(failure) if Self::is_nonce_collision(&failure.rpc_error) => {
+                    Some(failure.recipient_wallet.to_string())
+                }
+                _ => None,
+            })
+            .collect::<Vec<String>>();
+        if collided_recipients.is_empty() {
+            return None;
+        }
+        let message = format!(
+            "Payment(s) to {} collided with transactions sent from consuming wallet {} outside \
+            this Node; they will be retried with fresh nonces at the next payable scan",
+            collided_recipients.join(", "),
+            consuming_wallet
+        );
+        Some(self.alert(message))
+    }
+
+    fn is_nonce_collision(rpc_error: &web3::Error) -> bool {
+        let description = rpc_error.to_string().to_lowercase();
+        NONCE_TOO_LOW_FRAGMENTS
+            .iter()
+            .any(|fragment| description.contains(fragment))
+    }
+
+    fn alert(&self, message: String) -> UiAlertBroadcast {
+        warning!(self.logger, "{}", message);
+        UiAlertBroadcast {
+            rule: UiAlertRule::ExternalWalletUse,
+            message,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accountant::db_access_objects::pending_payable_dao::PendingPayable;
+    use crate::blockchain::blockchain_interface::data_structures::RpcPayablesFailure;
+    use crate::test_utils::make_wallet;
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use web3::types::H256;
+
+    fn make_subject(test_name: &str) -> ConsumingWalletNonceTracker {
+        ConsumingWalletNonceTracker::new(Logger::new(test_name))
+    }
+
+    fn make_failure(recipient: &str, message: &str) -> ProcessedPayableFallible {
+        Err(RpcPayablesFailure {
+            rpc_error: web3::Error::Rpc(jsonrpc_core::Error {
+                code: jsonrpc_core::ErrorCode::ServerError(-32000),
+                message: message.to_string(),
+                data: None,
+            }),
+            recipient_wallet: make_wallet(recipient),
+            hash: H256::from_low_u64_be(123),
+        })
+    }
+
+    fn make_success(recipient: &str) -> ProcessedPayableFallible {
+        Ok(PendingPayable {
+            recipient_wallet: make_wallet(recipient),
+            hash: H256::from_low_u64_be(456),
+        })
+    }
+
+    #[test]
+    fn first_resynchronization_only_learns_the_nonce() {
+        let test_name = "first_resynchronization_only_learns_the_nonce";
+        init_test_logging();
+        let mut subject = make_subject(test_name);
+        let wallet = make_wallet("consuming");
+
+        let result = subject.resynchronize(&wallet, U256::from(12), Some(U256::from(10)));
+
+        assert_eq!(result, None);
+        assert_eq!(subject.expected_opt, Some((wallet.clone(), U256::from(12))));
+        TestLogHandler::new().exists_log_containing(&format!(
+            "DEBUG: {test_name}: Consuming wallet {} is at nonce 12 (10 confirmed)",
+            wallet
+        ));
+    }
+
+    #[test]
+    fn nonce_where_the_node_left_it_is_not_alarming() {
+        let mut subject = make_subject("test");
+        let wallet = make_wallet("consuming");
+        subject.resynchronize(&wallet, U256::from(12), None);
+        subject.record_batch(&Ok(vec![make_success("abc"), make_success("def")]));
+
+        let result = subject.resynchronize(&wallet, U256::from(14), Some(U256::from(14)));
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn nonce_beyond_where_the_node_left_it_reveals_external_use() {
+        let test_name = "nonce_beyond_where_the_node_left_it_reveals_external_use";
+        init_test_logging();
+        let mut subject = make_subject(test_name);
+        let wallet = make_wallet("consuming");
+        subject.resynchronize(&wallet, U256::from(12), None);
+        subject.record_batch(&Ok(vec![make_success("abc")]));
+
+        let result = subject.resynchronize(&wallet, U256::from(16), None);
+
+        let expected_message = format!(
+            "3 transaction(s) were sent from consuming wallet {} outside this Node; its payments \
+            will continue from nonce 16",
+            wallet
+        );
+        assert_eq!(
+            result,
+            Some(UiAlertBroadcast {
+                rule: UiAlertRule::ExternalWalletUse,
+                message: expected_message.clone(),
+            })
+        );
+        assert_eq!(subject.expected_opt, Some((wallet, U256::from(16))));
+        TestLogHandler::new()
+            .exists_log_containing(&format!("WARN: {test_name}: {expected_message}"));
+    }
+
+    #[test]
+    fn nonce_short_of_where_the_node_left_it_is_reused() {
+        let test_name = "nonce_short_of_where_the_node_left_it_is_reused";
+        init_test_logging();
+        let mut subject = make_subject(test_name);
+        let wallet = make_wallet("consuming");
+        subject.resynchronize(&wallet, U256::from(12), None);
+        subject.record_batch(&Ok(vec![make_success("abc"), make_success("def")]));
+
+        let result = subject.resynchronize(&wallet, U256::from(13), None);
+
+        assert_eq!(result, None);
+        assert_eq!(subject.expected_opt, Some((wallet.clone(), U256::from(13))));
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {test_name}: 1 transaction(s) sent from consuming wallet {} are no longer known \
+            to the blockchain service; reusing nonces from 13",
+            wallet
+        ));
+    }
+
+    #[test]
+    fn a_different_consuming_wallet_is_learned_afresh() {
+        let mut subject = make_subject("test");
+        subject.resynchronize(&make_wallet("old"), U256::from(12), None);
+
+        let result = subject.resynchronize(&make_wallet("new"), U256::from(40), None);
+
+        assert_eq!(result, None);
+        assert_eq!(
+            subject.expected_opt,
+            Some((make_wallet("new"), U256::from(40)))
+        );
+    }
+
+    #[test]
+    fn failed_batch_makes_the_next_resynchronization_start_over() {
+        let mut subject = make_subject("test");
+        let wallet = make_wallet("consuming");
+        subject.resynchronize(&wallet, U256::from(12), None);
+
+        let result = subject.record_batch(&Err(PayableTransactionError::Sending {
+            msg: "Bad luck".to_string(),
+            hashes: vec![],
+        }));
+
+        assert_eq!(result, None);
+        assert_eq!(subject.expected_opt, None);
+        assert_eq!(subject.resynchronize(&wallet, U256::from(20), None), None);
+    }
+
+    #[test]
+    fn nonce_too_low_failures_are_reported_as_collisions() {
+        let test_name = "nonce_too_low_failures_are_reported_as_collisions";
+        init_test_logging();
+        let mut subject = make_subject(test_name);
+        let wallet = make_wallet("consuming");
+        subject.resynchronize(&wallet, U256::from(5), None);
+
+        let result = subject.record_batch(&Ok(vec![
+            make_failure("abc", "nonce too low"),
+            make_success("def"),
+            make_failure("ghi", "insufficient funds for gas * price + value"),
+            make_failure(
+                "jkl",
+                "Transaction nonce is too low. Try incrementing the nonce.",
+            ),
+        ]));
+
+        let expected_message = format!(
+            "Payment(s) to {}, {} collided with transactions sent from consuming wallet {} \
+            outside this Node; they will be retried with fresh nonces at the next payable scan",
+            make_wallet("abc"),
+            make_wallet("jkl"),
+            wallet
+        );
+        assert_eq!(
+            result,
+            Some(UiAlertBroadcast {
+                rule: UiAlertRule::ExternalWalletUse,
+                message: expected_message.clone(),
+            })
+        );
+        assert_eq!(subject.expected_opt, Some((wallet, U256::from(9))));
+        TestLogHandler::new()
+            .exists_log_containing(&format!("WARN: {test_name}: {expected_message}"));
+    }
+
+    #[test]
+    fn batch_without_resynchronization_is_ignored() {
+        let mut subject = make_subject("test");
+
+        let result = subject.record_batch(&Ok(vec![make_failure("abc", "nonce too low")]));
+
+        assert_eq!(result, None);
+        assert_eq!(subject.expected_opt, None);
+    }
+}
@@ -15,6 +15,7 @@ use crate::blockchain::blockchain_interface::data_structures::{
 use crate::blockchain::blockchain_interface::lower_level_interface::LowBlockchainInt;
 use crate::blockchain::blockchain_interface::test_utils::LowBlockchainIntMock;
 use crate::blockchain::blockchain_interface::BlockchainInterface;
+use crate::blockchain::blockchain_interface_initializer::BlockchainInterfaceFactory;
 use crate::db_config::persistent_configuration::PersistentConfiguration;
 use crate::set_arbitrary_id_stamp_in_mock_impl;
 use crate::sub_lib::wallet::Wallet;
@@ -58,6 +59,7 @@ pub fn make_meaningless_seed() -> Seed {
 
 #[derive(Default)]
 pub struct BlockchainInterfaceMock {
+    contract_address_results: RefCell<Vec<Address>>,
     retrieve_transactions_parameters: Arc<Mutex<Vec<(BlockNumber, BlockNumber, Wallet)>>>,
     retrieve_transactions_results:
         RefCell<Vec<Result<RetrievedBlockchainTransactions, BlockchainError>>>,
@@ -83,7 +85,7 @@ pub struct BlockchainInterfaceMock {
 
 impl BlockchainInterface for BlockchainInterfaceMock {
     fn contract_address(&self) -> Address {
-        unimplemented!("not needed so far")
+        self.contract_address_results.borrow_mut().remove(0)
     }
 
     fn retrieve_transactions(
@@ -200,6 +202,11 @@ impl BlockchainInterfaceMock {
         self
     }
 
+    pub fn contract_address_result(self, result: Address) -> Self {
+        self.contract_address_results.borrow_mut().push(result);
+        self
+    }
+
     pub fn get_transaction_receipt_params(mut self, params: &Arc<Mutex<Vec<H256>>>) -> Self {
         self.get_transaction_receipt_params = params.clone();
         self
@@ -223,6 +230,34 @@ impl BlockchainInterfaceMock {
     set_arbitrary_id_stamp_in_mock_impl!();
 }
 
+#[derive(Default)]
+pub struct BlockchainInterfaceFactoryMock {
+    make_params: Arc<Mutex<Vec<String>>>,
+    make_results: RefCell<Vec<Box<dyn BlockchainInterface>>>,
+}
+
+impl BlockchainInterfaceFactory for BlockchainInterfaceFactoryMock {
+    fn make(&self, blockchain_service_url: &str) -> Box<dyn BlockchainInterface> {
+        self.make_params
+            .lock()
+            .unwrap()
+            .push(blockchain_service_url.to_string());
+        self.make_results.borrow_mut().remove(0)
+    }
+}
+
+impl BlockchainInterfaceFactoryMock {
+    pub fn make_params(mut self, params: &Arc<Mutex<Vec<String>>>) -> Self {
+        self.make_params = params.clone();
+        self
+    }
+
+    pub fn make_result(self, result: Box<dyn BlockchainInterface>) -> Self {
+        self.make_results.borrow_mut().push(result);
+        self
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct TestTransport {
     // neither prepare_results or send_results can be effectively implemented the traditional way,
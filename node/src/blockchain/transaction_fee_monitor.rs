@@ -0,0 +1,231 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::accountant::gwei_to_wei;
+use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
+use masq_lib::logger::Logger;
+use masq_lib::messages::{UiTransactionFeeBalanceBroadcast, UiTransactionFeeBalanceLevel};
+use web3::types::U256;
+
+// Numbers of settlements the consuming wallet must still be able to pay gas for before the
+// corresponding warning level is left behind
+pub const LOW_TRANSACTION_FEE_BALANCE_THRESHOLD: u64 = 20;
+pub const CRITICAL_TRANSACTION_FEE_BALANCE_THRESHOLD: u64 = 5;
+
+pub struct TransactionFeeBalanceMonitor {
+    last_level_opt: Option<UiTransactionFeeBalanceLevel>,
+    logger: Logger,
+}
+
+impl TransactionFeeBalanceMonitor {
+    pub fn new(logger: Logger) -> Self {
+        Self {
+            last_level_opt: None,
+            logger,
+        }
+    }
+
+    // Logs the projection every time; a broadcast for the UI is produced only when the level
+    // differs from what was seen at the previous check, so that clients aren't flooded with the
+    // same warning on every payable scan. The initial check stays silent if all is well.
+    pub fn check(
+        &mut self,
+        agent: &dyn BlockchainAgent,
+    ) -> Option<UiTransactionFeeBalanceBroadcast> {
+        let balance_wei = agent
+            .consuming_wallet_balances()
+            .transaction_fee_balance_in_minor_units;
+        let fee_per_transaction_wei: u128 = gwei_to_wei(agent.estimated_transaction_fee_total(1));
+        let affordable_transactions =
+            Self::affordable_transactions(balance_wei, fee_per_transaction_wei);
+        let level = Self::level(affordable_transactions);
+        self.log(level, balance_wei, affordable_transactions);
+        let previous_level = self
+            .last_level_opt
+            .replace(level)
+            .unwrap_or(UiTransactionFeeBalanceLevel::Sufficient);
+        if level == previous_level {
+            return None;
+        }
+        Some(UiTransactionFeeBalanceBroadcast {
+            level,
+            balance_wei: Self::saturated_u128(balance_wei),
+            fee_per_transaction_wei,
+            affordable_transactions,
+        })
+    }
+
+    fn affordable_transactions(balance_wei: U256, fee_per_transaction_wei: u128) -> u64 {
+        if fee_per_transaction_wei == 0 {
+            return u64::MAX;
+        }
+        let affordable = balance_wei / U256::from(fee_per_transaction_wei);
+        if affordable > U256::from(u64::MAX) {
+            u64::MAX
+        } else {
+            affordable.as_u64()
+        }
+    }
+
+    fn level(affordable_transactions: u64) -> UiTransactionFeeBalanceLevel {
+        match affordable_transactions {
+            0 => UiTransactionFeeBalanceLevel::Depleted,
+            n if n < CRITICAL_TRANSACTION_FEE_BALANCE_THRESHOLD => {
+                UiTransactionFeeBalanceLevel::Critical
+            }
+            n if n < LOW_TRANSACTION_FEE_BALANCE_THRESHOLD => UiTransactionFeeBalanceLevel::Low,
+            _ => UiTransactionFeeBalanceLevel::Sufficient,
+        }
+    }
+
+    fn saturated_u128(value: U256) -> u128 {
+        if value > U256::from(u128::MAX) {
+            u128::MAX
+        } else {
+            value.as_u128()
+        }
+    }
+
+    fn log(&self, level: UiTransactionFeeBalanceLevel, balance_wei: U256, affordable: u64) {
+        match level {
+            UiTransactionFeeBalanceLevel::Sufficient => debug!(
+                self.logger,
+                "Transaction fee balance of {} wei covers {} more transactions at the current gas price",
+                balance_wei,
+                affordable
+            ),
+            UiTransactionFeeBalanceLevel::Low => warning!(
+                self.logger,
+                "Transaction fee balance of {} wei covers only {} more transactions at the current \
+                gas price; consider topping up the consuming wallet",
+                balance_wei,
+                affordable
+            ),
+            UiTransactionFeeBalanceLevel::Critical => error!(
+                self.logger,
+                "Transaction fee balance of {} wei covers only {} more transactions at the current \
+                gas price; top up the consuming wallet before payments start failing",
+                balance_wei,
+                affordable
+            ),
+            UiTransactionFeeBalanceLevel::Depleted => error!(
+                self.logger,
+                "Transaction fee balance of {} wei cannot cover another transaction at the current \
+                gas price; payments will fail until the consuming wallet is topped up",
+                balance_wei
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::test_utils::BlockchainAgentMock;
+    use crate::sub_lib::blockchain_bridge::ConsumingWalletBalances;
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use std::sync::{Arc, Mutex};
+
+    // 21_000 gas at 1 gwei costs exactly this many wei
+    const FEE_PER_TRANSACTION_WEI: u128 = 21_000_000_000_000;
+
+    fn make_agent(affordable_transactions: u128) -> BlockchainAgentMock {
+        BlockchainAgentMock::default()
+            .consuming_wallet_balances_result(ConsumingWalletBalances {
+                transaction_fee_balance_in_minor_units: U256::from(
+                    affordable_transactions * FEE_PER_TRANSACTION_WEI + 1,
+                ),
+                masq_token_balance_in_minor_units: U256::zero(),
+            })
+            .estimated_transaction_fee_total_result(21_000)
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(LOW_TRANSACTION_FEE_BALANCE_THRESHOLD, 20);
+        assert_eq!(CRITICAL_TRANSACTION_FEE_BALANCE_THRESHOLD, 5);
+    }
+
+    #[test]
+    fn sufficient_balance_is_not_broadcast_at_first_check() {
+        let estimated_transaction_fee_total_params_arc = Arc::new(Mutex::new(vec![]));
+        let agent = make_agent(20)
+            .estimated_transaction_fee_total_params(&estimated_transaction_fee_total_params_arc);
+        let mut subject = TransactionFeeBalanceMonitor::new(Logger::new("test"));
+
+        let result = subject.check(&agent);
+
+        assert_eq!(result, None);
+        assert_eq!(
+            *estimated_transaction_fee_total_params_arc.lock().unwrap(),
+            vec![1]
+        );
+    }
+
+    #[test]
+    fn warnings_escalate_and_are_broadcast_only_when_the_level_changes() {
+        init_test_logging();
+        let test_name = "warnings_escalate_and_are_broadcast_only_when_the_level_changes";
+        let mut subject = TransactionFeeBalanceMonitor::new(Logger::new(test_name));
+
+        let results = vec![19, 18, 4, 0, 25]
+            .into_iter()
+            .map(|affordable| subject.check(&make_agent(affordable)))
+            .collect::<Vec<_>>();
+
+        let expected_broadcast = |level, affordable_transactions: u64| {
+            Some(UiTransactionFeeBalanceBroadcast {
+                level,
+                balance_wei: affordable_transactions as u128 * FEE_PER_TRANSACTION_WEI + 1,
+                fee_per_transaction_wei: FEE_PER_TRANSACTION_WEI,
+                affordable_transactions,
+            })
+        };
+        assert_eq!(
+            results,
+            vec![
+                expected_broadcast(UiTransactionFeeBalanceLevel::Low, 19),
+                None,
+                expected_broadcast(UiTransactionFeeBalanceLevel::Critical, 4),
+                expected_broadcast(UiTransactionFeeBalanceLevel::Depleted, 0),
+                expected_broadcast(UiTransactionFeeBalanceLevel::Sufficient, 25),
+            ]
+        );
+        let log_handler = TestLogHandler::new();
+        log_handler.exists_log_containing(&format!(
+            "WARN: {test_name}: Transaction fee balance of 399000000000001 wei covers only 19 more \
+            transactions at the current gas price; consider topping up the consuming wallet"
+        ));
+        log_handler.exists_log_containing(&format!(
+            "WARN: {test_name}: Transaction fee balance of 378000000000001 wei covers only 18 more"
+        ));
+        log_handler.exists_log_containing(&format!(
+            "ERROR: {test_name}: Transaction fee balance of 84000000000001 wei covers only 4 more \
+            transactions at the current gas price; top up the consuming wallet before payments \
+            start failing"
+        ));
+        log_handler.exists_log_containing(&format!(
+            "ERROR: {test_name}: Transaction fee balance of 1 wei cannot cover another transaction \
+            at the current gas price; payments will fail until the consuming wallet is topped up"
+        ));
+        log_handler.exists_log_containing(&format!(
+            "DEBUG: {test_name}: Transaction fee balance of 525000000000001 wei covers 25 more \
+            transactions at the current gas price"
+        ));
+    }
+
+    #[test]
+    fn affordable_transactions_saturates_instead_of_overflowing() {
+        assert_eq!(
+            TransactionFeeBalanceMonitor::affordable_transactions(U256::MAX, 1),
+            u64::MAX
+        );
+        assert_eq!(
+            TransactionFeeBalanceMonitor::affordable_transactions(U256::from(1_000), 0),
+            u64::MAX
+        );
+        assert_eq!(
+            TransactionFeeBalanceMonitor::saturated_u128(U256::MAX),
+            u128::MAX
+        );
+    }
+}
@@ -1,9 +1,9 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
-use crate::accountant::DEFAULT_PENDING_TOO_LONG_SEC;
 use crate::actor_system_factory::ActorSystemFactory;
 use crate::actor_system_factory::ActorSystemFactoryReal;
-use crate::actor_system_factory::{ActorFactoryReal, ActorSystemFactoryToolsReal};
+use crate::actor_system_factory::{ActorFactoryReal, ActorSystemFactoryToolsReal, ActorThreads};
 use crate::crash_test_dummy::CrashTestDummy;
+use crate::daemon::launch_verifier::{VerifierTools, VerifierToolsReal};
 use crate::database::db_initializer::DbInitializationConfig;
 use crate::database::db_initializer::{DbInitializer, DbInitializerReal};
 use crate::db_config::config_dao::ConfigDaoReal;
@@ -15,24 +15,37 @@ use crate::json_discriminator_factory::JsonDiscriminatorFactory;
 use crate::listener_handler::ListenerHandler;
 use crate::listener_handler::ListenerHandlerFactory;
 use crate::listener_handler::ListenerHandlerFactoryReal;
+use crate::neighborhood::neighborhood_database::NeighborhoodLimits;
+use crate::neighborhood::rendezvous::RendezvousBrokerLimits;
 use crate::neighborhood::DEFAULT_MIN_HOPS;
 use crate::node_configurator::node_configurator_standard::{
     NodeConfiguratorStandardPrivileged, NodeConfiguratorStandardUnprivileged,
 };
 use crate::node_configurator::{initialize_database, DirsWrapper, NodeConfigurator};
 use crate::privilege_drop::{IdWrapper, IdWrapperReal};
+use crate::proxy_server::route_pool::RoutePoolConfig;
 use crate::server_initializer::LoggerInitializerWrapper;
 use crate::stream_handler_pool::StreamHandlerPoolSubs;
 use crate::sub_lib::accountant;
-use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals};
+use crate::sub_lib::accountant::{
+    AlertThresholds, PaymentThresholds, PendingPayableLimits, ScanIntervals,
+};
 use crate::sub_lib::blockchain_bridge::BlockchainBridgeConfig;
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::cryptde_null::CryptDENull;
 use crate::sub_lib::cryptde_real::CryptDEReal;
+use crate::sub_lib::data_directory_lock::{DataDirectoryLock, DataDirectoryLockError};
+use crate::sub_lib::hopper::{PerformancePreset, TrafficPadding};
 use crate::sub_lib::neighborhood::NodeDescriptor;
-use crate::sub_lib::neighborhood::{NeighborhoodConfig, NeighborhoodMode};
+use crate::sub_lib::neighborhood::{
+    NeighborhoodConfig, NeighborhoodMode, PinnedNeighbors, VersionQuarantinePolicy,
+};
 use crate::sub_lib::node_addr::NodeAddr;
+use crate::sub_lib::node_pid_file::NodePidFile;
+use crate::sub_lib::operator_notes::OperatorNotes;
+use crate::sub_lib::proxy_client::ExitHeaderPolicy;
 use crate::sub_lib::socket_server::ConfiguredByPrivilege;
+use crate::sub_lib::stream_connector::SocketTuning;
 use crate::sub_lib::ui_gateway::UiGatewayConfig;
 use crate::sub_lib::utils::db_connection_launch_panic;
 use crate::sub_lib::wallet::Wallet;
@@ -41,9 +54,11 @@ use itertools::Itertools;
 use log::LevelFilter;
 use masq_lib::blockchains::chains::Chain;
 use masq_lib::command::StdStreams;
-use masq_lib::constants::DEFAULT_UI_PORT;
+use masq_lib::constants::{
+    DATA_DIRECTORY_LOCKED_ERROR, DEFAULT_UI_PORT, INVALID_PARAM_VALUE_ERROR,
+};
 use masq_lib::crash_point::CrashPoint;
-use masq_lib::logger::Logger;
+use masq_lib::logger::{LogFormat, LogLevelSpec, Logger};
 use masq_lib::multi_config::MultiConfig;
 use masq_lib::shared_schema::ConfiguratorError;
 use masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN;
@@ -52,8 +67,9 @@ use std::collections::HashMap;
 use std::env::var;
 use std::fmt;
 use std::fmt::{Debug, Display, Error, Formatter};
-use std::net::{Ipv4Addr, SocketAddr};
+use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::process;
 use std::str::FromStr;
 use std::vec::Vec;
 use tokio::prelude::stream::futures_unordered::FuturesUnordered;
@@ -332,30 +348,53 @@ impl Display for RealUser {
 #[derive(Clone, Debug)]
 pub struct BootstrapperConfig {
     // These fields can be set while privileged without penalty
-    pub log_level: LevelFilter,
+    pub log_level: LogLevelSpec,
+    pub log_format: LogFormat,
     pub dns_servers: Vec<SocketAddr>,
     pub scan_intervals_opt: Option<ScanIntervals>,
     pub suppress_initial_scans: bool,
-    pub when_pending_too_long_sec: u64,
+    pub pending_payable_limits: PendingPayableLimits,
+    pub alert_thresholds_opt: Option<AlertThresholds>,
     pub crash_point: CrashPoint,
     pub clandestine_discriminator_factories: Vec<Box<dyn DiscriminatorFactory>>,
     pub ui_gateway_config: UiGatewayConfig,
     pub blockchain_bridge_config: BlockchainBridgeConfig,
     pub port_configurations: HashMap<u16, PortConfiguration>,
     pub data_directory: PathBuf,
+    pub config_file_opt: Option<PathBuf>,
     pub node_descriptor: NodeDescriptor,
     pub main_cryptde_null_opt: Option<CryptDENull>,
     pub alias_cryptde_null_opt: Option<CryptDENull>,
     pub mapping_protocol_opt: Option<AutomapProtocol>,
     pub real_user: RealUser,
     pub payment_thresholds_opt: Option<PaymentThresholds>,
+    pub route_pool_config: RoutePoolConfig,
+    pub neighborhood_limits: NeighborhoodLimits,
+    pub performance_preset: PerformancePreset,
+    pub socket_tuning: SocketTuning,
+    pub exit_header_policy: ExitHeaderPolicy,
+    pub version_quarantine_opt: Option<VersionQuarantinePolicy>,
+    pub pinned_neighbors: PinnedNeighbors,
+    pub health_check_port_opt: Option<u16>,
+    pub price_feed_url_opt: Option<String>,
+    pub public_ip_server_opt: Option<String>,
+    pub rendezvous_broker_opt: Option<RendezvousBrokerLimits>,
+    pub traffic_padding_opt: Option<TrafficPadding>,
+    pub actor_threads_opt: Option<ActorThreads>,
+    pub operator_notes_opt: Option<String>,
+    pub operator_notes_token_opt: Option<String>,
 
     // These fields must be set without privilege: otherwise the database will be created as root
     pub db_password_opt: Option<String>,
     pub clandestine_port_opt: Option<u16>,
+    // Some(true) for auto-rotate, Some(false) for auto-stable or a specific port, None if unspecified
+    pub clandestine_port_rotates_opt: Option<bool>,
+    // An absolute path, or "main" to bring the traffic tables home; None if unspecified
+    pub traffic_db_path_opt: Option<String>,
     pub consuming_wallet_opt: Option<Wallet>,
     pub earning_wallet: Wallet,
     pub neighborhood_config: NeighborhoodConfig,
+    pub neighbor_notes: Vec<OperatorNotes>,
 }
 
 impl Default for BootstrapperConfig {
@@ -368,7 +407,8 @@ impl BootstrapperConfig {
     pub fn new() -> BootstrapperConfig {
         BootstrapperConfig {
             // These fields can be set while privileged without penalty
-            log_level: LevelFilter::Off,
+            log_level: LogLevelSpec::new(LevelFilter::Off),
+            log_format: LogFormat::default(),
             dns_servers: vec![],
             scan_intervals_opt: None,
             suppress_initial_scans: false,
@@ -380,27 +420,49 @@ impl BootstrapperConfig {
             blockchain_bridge_config: BlockchainBridgeConfig {
                 blockchain_service_url_opt: None,
                 chain: TEST_DEFAULT_CHAIN,
+                token_contract_address_opt: None,
                 gas_price: 1,
+                failover_opt: None,
             },
             port_configurations: HashMap::new(),
             data_directory: PathBuf::new(),
+            config_file_opt: None,
             node_descriptor: NodeDescriptor::default(),
             main_cryptde_null_opt: None,
             alias_cryptde_null_opt: None,
             mapping_protocol_opt: None,
             real_user: RealUser::new(None, None, None),
             payment_thresholds_opt: Default::default(),
+            route_pool_config: RoutePoolConfig::default(),
+            neighborhood_limits: NeighborhoodLimits::default(),
+            performance_preset: PerformancePreset::default(),
+            socket_tuning: SocketTuning::default(),
+            exit_header_policy: ExitHeaderPolicy::default(),
+            version_quarantine_opt: None,
+            pinned_neighbors: PinnedNeighbors::default(),
+            health_check_port_opt: None,
+            price_feed_url_opt: None,
+            public_ip_server_opt: None,
+            rendezvous_broker_opt: None,
+            traffic_padding_opt: None,
+            actor_threads_opt: None,
+            operator_notes_opt: None,
+            operator_notes_token_opt: None,
 
             // These fields must be set without privilege: otherwise the database will be created as root
             db_password_opt: None,
             clandestine_port_opt: None,
+            clandestine_port_rotates_opt: None,
+            traffic_db_path_opt: None,
             earning_wallet: accountant::DEFAULT_EARNING_WALLET.clone(),
             consuming_wallet_opt: None,
             neighborhood_config: NeighborhoodConfig {
                 mode: NeighborhoodMode::ZeroHop,
                 min_hops: DEFAULT_MIN_HOPS,
             },
-            when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC,
+            neighbor_notes: vec![],
+            pending_payable_limits: PendingPayableLimits::default(),
+            alert_thresholds_opt: None,
         }
     }
 
@@ -409,7 +471,11 @@ impl BootstrapperConfig {
         self.blockchain_bridge_config.blockchain_service_url_opt = unprivileged
             .blockchain_bridge_config
             .blockchain_service_url_opt;
+        self.blockchain_bridge_config.failover_opt =
+            unprivileged.blockchain_bridge_config.failover_opt;
         self.clandestine_port_opt = unprivileged.clandestine_port_opt;
+        self.clandestine_port_rotates_opt = unprivileged.clandestine_port_rotates_opt;
+        self.traffic_db_path_opt = unprivileged.traffic_db_path_opt;
         self.neighborhood_config = unprivileged.neighborhood_config;
         self.earning_wallet = unprivileged.earning_wallet;
         self.consuming_wallet_opt = unprivileged.consuming_wallet_opt;
@@ -417,7 +483,8 @@ impl BootstrapperConfig {
         self.scan_intervals_opt = unprivileged.scan_intervals_opt;
         self.suppress_initial_scans = unprivileged.suppress_initial_scans;
         self.payment_thresholds_opt = unprivileged.payment_thresholds_opt;
-        self.when_pending_too_long_sec = unprivileged.when_pending_too_long_sec;
+        self.pending_payable_limits = unprivileged.pending_payable_limits;
+        self.alert_thresholds_opt = unprivileged.alert_thresholds_opt;
     }
 
     pub fn exit_service_rate(&self) -> u64 {
@@ -446,6 +513,7 @@ pub struct Bootstrapper {
     actor_system_factory: Box<dyn ActorSystemFactory>,
     logger_initializer: Box<dyn LoggerInitializerWrapper>,
     config: BootstrapperConfig,
+    data_directory_lock_opt: Option<DataDirectoryLock>,
 }
 
 impl Future for Bootstrapper {
@@ -468,7 +536,8 @@ impl ConfiguredByPrivilege for Bootstrapper {
         self.logger_initializer.init(
             self.config.data_directory.clone(),
             &self.config.real_user,
-            self.config.log_level,
+            &self.config.log_level,
+            self.config.log_format,
             None,
         );
         self.listener_handlers =
@@ -477,7 +546,8 @@ impl ConfiguredByPrivilege for Bootstrapper {
         port_configurations
             .iter()
             .for_each(|(port, port_configuration)| {
-                let mut listener_handler = self.listener_handler_factory.make();
+                let mut listener_handler =
+                    self.listener_handler_factory.make(SocketTuning::default());
                 if let Err(e) =
                     listener_handler.bind_port_and_configuration(*port, port_configuration.clone())
                 {
@@ -495,6 +565,8 @@ impl ConfiguredByPrivilege for Bootstrapper {
     ) -> Result<(), ConfiguratorError> {
         // NOTE: The following line of code is not covered by unit tests
         fdlimit::raise_fd_limit();
+        // The database mustn't be touched before the lock is in hand
+        self.data_directory_lock_opt = Some(self.lock_data_directory()?);
         let unprivileged_config =
             NodeConfiguratorStandardUnprivileged::new(&self.config).configure(multi_config)?;
         self.config.merge_unprivileged(unprivileged_config);
@@ -511,18 +583,23 @@ impl ConfiguredByPrivilege for Bootstrapper {
             self.config.blockchain_bridge_config.chain,
         );
         self.config.node_descriptor = node_descriptor;
+        self.config.operator_notes_token_opt = self
+            .config
+            .operator_notes_opt
+            .as_ref()
+            .map(|notes| OperatorNotes::sign(cryptdes.main, notes).to_token());
         // Before you remove local-descriptor reporting for non-Standard neighborhood modes, make
         // sure you modify the multinode tests so that they can tell A) when a Node has started up,
         // and B) what its public key is.
-        match &self.config.neighborhood_config.mode {
-            NeighborhoodMode::Standard(node_addr, _, _)
-                if node_addr.ip_addr() == Ipv4Addr::new(0, 0, 0, 0) => {} // node_addr still coming
+        match self.config.neighborhood_config.mode.node_addr_opt() {
+            Some(node_addr) if node_addr.ip_addr().is_unspecified() => {} // node_addr still coming
             _ => Bootstrapper::report_local_descriptor(cryptdes.main, &self.config.node_descriptor), // here or not coming
         }
         let stream_handler_pool_subs = self.start_actors_and_return_shp_subs();
         self.listener_handlers
             .iter_mut()
             .for_each(|f| f.bind_subs(stream_handler_pool_subs.add_sub.clone()));
+        self.write_pid_file();
         Ok(())
     }
 }
@@ -538,6 +615,7 @@ impl Bootstrapper {
             ))),
             logger_initializer,
             config: BootstrapperConfig::new(),
+            data_directory_lock_opt: None,
         }
     }
 
@@ -611,7 +689,7 @@ impl Bootstrapper {
     fn start_actors_and_return_shp_subs(&self) -> StreamHandlerPoolSubs {
         self.actor_system_factory.make_and_start_actors(
             self.config.clone(),
-            Box::new(ActorFactoryReal {}),
+            Box::new(ActorFactoryReal::new(self.config.actor_threads_opt)),
             initialize_database(
                 &self.config.data_directory,
                 DbInitializationConfig::panic_on_migration(),
@@ -627,44 +705,67 @@ impl Bootstrapper {
         info!(Logger::new("Bootstrapper"), "{}", descriptor_msg);
     }
 
-    fn set_up_clandestine_port(&mut self) -> Option<u16> {
-        let clandestine_port_opt =
-            if let NeighborhoodMode::Standard(node_addr, neighbor_configs, rate_pack) =
-                &self.config.neighborhood_config.mode
-            {
-                let conn = DbInitializerReal::default()
-                    .initialize(
-                        &self.config.data_directory,
-                        DbInitializationConfig::panic_on_migration(),
-                    )
-                    .unwrap_or_else(|err| {
-                        db_connection_launch_panic(err, &self.config.data_directory)
-                    });
-                let config_dao = ConfigDaoReal::new(conn);
-                let mut persistent_config = PersistentConfigurationReal::new(Box::new(config_dao));
-                let clandestine_port = self.establish_clandestine_port(&mut persistent_config);
-                let mut listener_handler = self.listener_handler_factory.make();
-                listener_handler
-                    .bind_port_and_configuration(
-                        clandestine_port,
-                        PortConfiguration {
-                            discriminator_factories: vec![
-                                Box::new(JsonDiscriminatorFactory::new()),
-                            ],
-                            is_clandestine: true,
-                        },
-                    )
-                    .expect("Failed to bind ListenerHandler to clandestine port");
-                self.listener_handlers.push(listener_handler);
-                self.config.neighborhood_config.mode = NeighborhoodMode::Standard(
-                    NodeAddr::new(&node_addr.ip_addr(), &[clandestine_port]),
-                    neighbor_configs.clone(),
-                    *rate_pack,
-                );
-                Some(clandestine_port)
-            } else {
-                None
+    fn lock_data_directory(&self) -> Result<DataDirectoryLock, ConfiguratorError> {
+        let verifier_tools = VerifierToolsReal::new();
+        DataDirectoryLock::acquire(&self.config.data_directory, &|process_id| {
+            verifier_tools.process_is_running(process_id)
+        })
+        .map_err(|e| {
+            let code = match e {
+                DataDirectoryLockError::HeldBy(_) => DATA_DIRECTORY_LOCKED_ERROR,
+                DataDirectoryLockError::Io(_) => INVALID_PARAM_VALUE_ERROR,
             };
+            ConfiguratorError::required("data-directory", &e.to_string(), code)
+        })
+    }
+
+    fn write_pid_file(&self) {
+        let pid_file = NodePidFile::new(process::id(), self.config.ui_gateway_config.ui_port);
+        if let Err(e) = pid_file.write(&self.config.data_directory) {
+            warning!(
+                Logger::new("Bootstrapper"),
+                "Couldn't write pid file to {:?}: {}; a restarted Daemon won't be able to find this Node",
+                self.config.data_directory,
+                e
+            );
+        }
+    }
+
+    fn set_up_clandestine_port(&mut self) -> Option<u16> {
+        let clandestine_port_opt = if let Some(node_addr) =
+            self.config.neighborhood_config.mode.node_addr_opt()
+        {
+            let conn = DbInitializerReal::default()
+                .initialize(
+                    &self.config.data_directory,
+                    DbInitializationConfig::panic_on_migration(),
+                )
+                .unwrap_or_else(|err| db_connection_launch_panic(err, &self.config.data_directory));
+            let config_dao = ConfigDaoReal::new(conn);
+            let mut persistent_config = PersistentConfigurationReal::new(Box::new(config_dao));
+            let clandestine_port = self.establish_clandestine_port(&mut persistent_config);
+            let mut listener_handler = self
+                .listener_handler_factory
+                .make(self.config.socket_tuning);
+            listener_handler
+                .bind_port_and_configuration(
+                    clandestine_port,
+                    PortConfiguration {
+                        discriminator_factories: vec![Box::new(JsonDiscriminatorFactory::new())],
+                        is_clandestine: true,
+                    },
+                )
+                .expect("Failed to bind ListenerHandler to clandestine port");
+            self.listener_handlers.push(listener_handler);
+            self.config.neighborhood_config.mode = self
+                .config
+                .neighborhood_config
+                .mode
+                .with_node_addr(NodeAddr::new(&node_addr.ip_addr(), &[clandestine_port]));
+            Some(clandestine_port)
+        } else {
+            None
+        };
         self.config
             .clandestine_discriminator_factories
             .push(Box::new(JsonDiscriminatorFactory::new()));
@@ -709,7 +810,6 @@ impl Bootstrapper {
 
 #[cfg(test)]
 mod tests {
-    use crate::accountant::DEFAULT_PENDING_TOO_LONG_SEC;
     use crate::actor_system_factory::{ActorFactory, ActorSystemFactory};
     use crate::bootstrapper::{
         main_cryptde_ref, Bootstrapper, BootstrapperConfig, EnvironmentWrapper, PortConfiguration,
@@ -730,14 +830,17 @@ mod tests {
     use crate::server_initializer::LoggerInitializerWrapper;
     use crate::stream_handler_pool::StreamHandlerPoolSubs;
     use crate::stream_messages::AddStreamMsg;
-    use crate::sub_lib::accountant::ScanIntervals;
+    use crate::sub_lib::accountant::{AlertThresholds, PendingPayableLimits, ScanIntervals};
+    use crate::sub_lib::blockchain_bridge::BlockchainServiceFailover;
     use crate::sub_lib::cryptde::PublicKey;
     use crate::sub_lib::cryptde::{CryptDE, PlainData};
     use crate::sub_lib::cryptde_null::CryptDENull;
+    use crate::sub_lib::data_directory_lock::DATA_DIRECTORY_LOCK_FILE_NAME;
     use crate::sub_lib::neighborhood::{
         NeighborhoodConfig, NeighborhoodMode, NodeDescriptor, DEFAULT_RATE_PACK,
     };
     use crate::sub_lib::node_addr::NodeAddr;
+    use crate::sub_lib::node_pid_file::NodePidFile;
     use crate::sub_lib::socket_server::ConfiguredByPrivilege;
     use crate::sub_lib::stream_connector::ConnectionInfo;
     use crate::test_utils::neighborhood_test_utils::MIN_HOPS_FOR_TEST;
@@ -760,8 +863,12 @@ mod tests {
     use log::LevelFilter;
     use log::LevelFilter::Off;
     use masq_lib::blockchains::chains::Chain;
+    use masq_lib::constants::DATA_DIRECTORY_LOCKED_ERROR;
+    use masq_lib::logger::LogFormat;
+    use masq_lib::logger::LogLevelSpec;
     use masq_lib::logger::Logger;
     use masq_lib::logger::TEST_LOG_RECIPIENT_GUARD;
+    use masq_lib::shared_schema::ConfiguratorError;
     use masq_lib::test_utils::environment_guard::ClapGuard;
     use masq_lib::test_utils::fake_stream_holder::FakeStreamHolder;
     use masq_lib::test_utils::logging::{init_test_logging, TestLog, TestLogHandler};
@@ -788,14 +895,19 @@ mod tests {
 
     struct ListenerHandlerFactoryMock {
         log: TestLog,
+        make_params: Arc<Mutex<Vec<SocketTuning>>>,
         mocks: RefCell<Vec<Box<dyn ListenerHandler<Item = (), Error = ()>>>>,
     }
 
     unsafe impl Sync for ListenerHandlerFactoryMock {}
 
     impl ListenerHandlerFactory for ListenerHandlerFactoryMock {
-        fn make(&self) -> Box<dyn ListenerHandler<Item = (), Error = ()>> {
-            self.log.log(String::from("make ()"));
+        fn make(
+            &self,
+            socket_tuning: SocketTuning,
+        ) -> Box<dyn ListenerHandler<Item = (), Error = ()>> {
+            self.log.log(format!("make ({})", socket_tuning));
+            self.make_params.lock().unwrap().push(socket_tuning);
             self.mocks.borrow_mut().remove(0)
         }
     }
@@ -804,10 +916,15 @@ mod tests {
         fn new() -> ListenerHandlerFactoryMock {
             ListenerHandlerFactoryMock {
                 log: TestLog::new(),
+                make_params: Arc::new(Mutex::new(vec![])),
                 mocks: RefCell::new(vec![]),
             }
         }
 
+        fn make_params(&mut self, params: &Arc<Mutex<Vec<SocketTuning>>>) {
+            self.make_params = params.clone();
+        }
+
         fn add(&mut self, mock: Box<dyn ListenerHandler<Item = (), Error = ()>>) {
             self.mocks.borrow_mut().push(mock)
         }
@@ -1162,7 +1279,8 @@ mod tests {
             vec![(
                 data_dir,
                 RealUser::new(Some(123), Some(456), Some("/home/booga".into())),
-                LevelFilter::Warn,
+                LogLevelSpec::new(LevelFilter::Warn),
+                LogFormat::Text,
                 None,
             )]
         )
@@ -1225,7 +1343,7 @@ mod tests {
             },
         );
         privileged_config.port_configurations = port_configuration;
-        privileged_config.log_level = Off;
+        privileged_config.log_level = LogLevelSpec::new(Off);
         privileged_config.dns_servers =
             vec![SocketAddr::new(IpAddr::from_str("1.2.3.4").unwrap(), 1111)];
         let mut unprivileged_config = BootstrapperConfig::new();
@@ -1244,14 +1362,31 @@ mod tests {
         unprivileged_config
             .blockchain_bridge_config
             .blockchain_service_url_opt = blockchain_url_opt.clone();
+        let failover = BlockchainServiceFailover {
+            failure_threshold: 5,
+            failure_window_minutes: 10,
+            pause_payables: true,
+            alternate_urls: vec!["https://alternate.com".to_string()],
+        };
+        unprivileged_config.blockchain_bridge_config.failover_opt = Some(failover.clone());
         unprivileged_config.clandestine_port_opt = clandestine_port_opt;
+        unprivileged_config.clandestine_port_rotates_opt = Some(false);
+        unprivileged_config.traffic_db_path_opt = Some("main".to_string());
         unprivileged_config.neighborhood_config = neighborhood_config.clone();
         unprivileged_config.earning_wallet = earning_wallet.clone();
         unprivileged_config.consuming_wallet_opt = consuming_wallet_opt.clone();
         unprivileged_config.db_password_opt = db_password_opt.clone();
         unprivileged_config.scan_intervals_opt = Some(ScanIntervals::default());
         unprivileged_config.suppress_initial_scans = false;
-        unprivileged_config.when_pending_too_long_sec = DEFAULT_PENDING_TOO_LONG_SEC;
+        unprivileged_config.pending_payable_limits = PendingPayableLimits {
+            max_attempts: 12,
+            max_age_sec: 3456,
+        };
+        unprivileged_config.alert_thresholds_opt = Some(AlertThresholds {
+            receivable_age_sec: 86400,
+            receivable_balance_gwei: 1_000_000_000,
+            total_payables_gwei: 5_000_000_000,
+        });
 
         privileged_config.merge_unprivileged(unprivileged_config);
 
@@ -1267,6 +1402,11 @@ mod tests {
             blockchain_url_opt
         );
         assert_eq!(privileged_config.clandestine_port_opt, clandestine_port_opt);
+        assert_eq!(privileged_config.clandestine_port_rotates_opt, Some(false));
+        assert_eq!(
+            privileged_config.traffic_db_path_opt,
+            Some("main".to_string())
+        );
         assert_eq!(privileged_config.neighborhood_config, neighborhood_config);
         assert_eq!(privileged_config.earning_wallet, earning_wallet);
         assert_eq!(privileged_config.consuming_wallet_opt, consuming_wallet_opt);
@@ -1277,11 +1417,26 @@ mod tests {
         );
         assert_eq!(privileged_config.suppress_initial_scans, false);
         assert_eq!(
-            privileged_config.when_pending_too_long_sec,
-            DEFAULT_PENDING_TOO_LONG_SEC
+            privileged_config.pending_payable_limits,
+            PendingPayableLimits {
+                max_attempts: 12,
+                max_age_sec: 3456,
+            }
+        );
+        assert_eq!(
+            privileged_config.blockchain_bridge_config.failover_opt,
+            Some(failover)
+        );
+        assert_eq!(
+            privileged_config.alert_thresholds_opt,
+            Some(AlertThresholds {
+                receivable_age_sec: 86400,
+                receivable_balance_gwei: 1_000_000_000,
+                total_payables_gwei: 5_000_000_000,
+            })
         );
         //some values from the privileged config
-        assert_eq!(privileged_config.log_level, Off);
+        assert_eq!(privileged_config.log_level, LogLevelSpec::new(Off));
         assert_eq!(
             privileged_config.dns_servers,
             vec![SocketAddr::new(IpAddr::from_str("1.2.3.4").unwrap(), 1111)]
@@ -1329,6 +1484,39 @@ mod tests {
         TestLogHandler::new().exists_log_matching("INFO: Bootstrapper: MASQ Node local descriptor: masq://eth-ropsten:.+@1\\.2\\.3\\.4:5123");
     }
 
+    #[test]
+    fn initialize_as_unprivileged_signs_operator_notes() {
+        let _lock = INITIALIZATION.lock();
+        let data_dir = ensure_node_home_directory_exists(
+            "bootstrapper",
+            "initialize_as_unprivileged_signs_operator_notes",
+        );
+        let mut config = BootstrapperConfig::new();
+        config.clandestine_port_opt = Some(1234);
+        config.data_directory = data_dir.clone();
+        config.operator_notes_opt = Some("Run by alice".to_string());
+        let mut subject = BootstrapperBuilder::new()
+            .add_listener_handler(Box::new(
+                ListenerHandlerNull::new(vec![]).bind_port_result(Ok(())),
+            ))
+            .config(config)
+            .build();
+
+        subject
+            .initialize_as_unprivileged(
+                &make_simplified_multi_config(["--ip", "1.2.3.4", "--clandestine-port", "5123"]),
+                &mut FakeStreamHolder::new().streams(),
+            )
+            .unwrap();
+
+        let config = subject.config;
+        let token = config.operator_notes_token_opt.unwrap();
+        let operator_notes =
+            OperatorNotes::from_token(main_cryptde_ref(), &token, &config.node_descriptor).unwrap();
+        assert_eq!(operator_notes.notes, "Run by alice".to_string());
+        assert_eq!(&operator_notes.public_key, main_cryptde_ref().public_key());
+    }
+
     #[test]
     fn initialize_as_unprivileged_does_not_report_descriptor_when_ip_is_not_supplied_in_standard_mode(
     ) {
@@ -1398,6 +1586,105 @@ mod tests {
         assert_eq!(config.blockchain_bridge_config.gas_price, 11);
     }
 
+    #[test]
+    fn initialize_as_unprivileged_leaves_pid_file_in_data_directory() {
+        let _lock = INITIALIZATION.lock();
+        let data_dir = ensure_node_home_directory_exists(
+            "bootstrapper",
+            "initialize_as_unprivileged_leaves_pid_file_in_data_directory",
+        );
+        let mut config = BootstrapperConfig::new();
+        config.data_directory = data_dir.clone();
+        config.ui_gateway_config.ui_port = 5432;
+        let mut subject = BootstrapperBuilder::new()
+            .add_listener_handler(Box::new(
+                ListenerHandlerNull::new(vec![]).bind_port_result(Ok(())),
+            ))
+            .config(config)
+            .build();
+
+        subject
+            .initialize_as_unprivileged(
+                &make_simplified_multi_config(["--ip", "1.2.3.4"]),
+                &mut FakeStreamHolder::new().streams(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            NodePidFile::read(&data_dir),
+            Some(NodePidFile::new(std::process::id(), 5432))
+        );
+    }
+
+    #[test]
+    fn initialize_as_unprivileged_locks_data_directory() {
+        let _lock = INITIALIZATION.lock();
+        let data_dir = ensure_node_home_directory_exists(
+            "bootstrapper",
+            "initialize_as_unprivileged_locks_data_directory",
+        );
+        let mut config = BootstrapperConfig::new();
+        config.data_directory = data_dir.clone();
+        let mut subject = BootstrapperBuilder::new()
+            .add_listener_handler(Box::new(
+                ListenerHandlerNull::new(vec![]).bind_port_result(Ok(())),
+            ))
+            .config(config)
+            .build();
+
+        subject
+            .initialize_as_unprivileged(
+                &make_simplified_multi_config(["--ip", "1.2.3.4"]),
+                &mut FakeStreamHolder::new().streams(),
+            )
+            .unwrap();
+
+        let lock_path = data_dir.join(DATA_DIRECTORY_LOCK_FILE_NAME);
+        assert_eq!(
+            std::fs::read_to_string(&lock_path).unwrap(),
+            format!("{}\n", std::process::id())
+        );
+        drop(subject);
+        assert_eq!(lock_path.exists(), false);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn initialize_as_unprivileged_refuses_data_directory_locked_by_another_node() {
+        let _lock = INITIALIZATION.lock();
+        let data_dir = ensure_node_home_directory_exists(
+            "bootstrapper",
+            "initialize_as_unprivileged_refuses_data_directory_locked_by_another_node",
+        );
+        // Our parent process is certainly running, and it's not us
+        let other_process_id = std::os::unix::process::parent_id();
+        std::fs::write(
+            data_dir.join(DATA_DIRECTORY_LOCK_FILE_NAME),
+            format!("{}\n", other_process_id),
+        )
+        .unwrap();
+        let mut config = BootstrapperConfig::new();
+        config.data_directory = data_dir.clone();
+        let mut subject = BootstrapperBuilder::new().config(config).build();
+
+        let result = subject.initialize_as_unprivileged(
+            &make_simplified_multi_config(["--ip", "1.2.3.4"]),
+            &mut FakeStreamHolder::new().streams(),
+        );
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "data-directory",
+                &format!(
+                    "Another Node (process {}) is already running against this data directory",
+                    other_process_id
+                ),
+                DATA_DIRECTORY_LOCKED_ERROR
+            ))
+        );
+    }
+
     #[test]
     fn initialize_as_unprivileged_implements_panic_on_migration_for_make_and_start_actors() {
         let _lock = INITIALIZATION.lock();
@@ -1859,9 +2146,16 @@ mod tests {
         };
         config.data_directory = data_dir.clone();
         config.clandestine_port_opt = Some(port);
+        config.socket_tuning = SocketTuning {
+            recv_buffer_size: 1048576,
+            send_buffer_size: 65536,
+            nodelay: true,
+        };
         let listener_handler = ListenerHandlerNull::new(vec![]).bind_port_result(Ok(()));
+        let make_params_arc = Arc::new(Mutex::new(vec![]));
         let mut subject = BootstrapperBuilder::new()
             .add_listener_handler(Box::new(listener_handler))
+            .listener_handler_factory_make_params(&make_params_arc)
             .config(config)
             .build();
 
@@ -1882,6 +2176,14 @@ mod tests {
             vec![port],
         );
         assert_eq!(1, subject.listener_handlers.len());
+        assert_eq!(
+            *make_params_arc.lock().unwrap(),
+            vec![SocketTuning {
+                recv_buffer_size: 1048576,
+                send_buffer_size: 65536,
+                nodelay: true,
+            }]
+        );
 
         let config = subject.config;
         let mut clandestine_discriminators = config
@@ -1953,6 +2255,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn set_up_clandestine_port_handles_relay_only() {
+        let data_dir = ensure_node_home_directory_exists(
+            "bootstrapper",
+            "set_up_clandestine_port_handles_relay_only",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&data_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let mut config = BootstrapperConfig::new();
+        config.neighborhood_config = NeighborhoodConfig {
+            mode: NeighborhoodMode::RelayOnly(
+                NodeAddr::new(&IpAddr::from_str("1.2.3.4").unwrap(), &[]),
+                vec![],
+                rate_pack(100),
+            ),
+            min_hops: MIN_HOPS_FOR_TEST,
+        };
+        config.data_directory = data_dir.clone();
+        config.clandestine_port_opt = None;
+        let listener_handler = ListenerHandlerNull::new(vec![]).bind_port_result(Ok(()));
+        let mut subject = BootstrapperBuilder::new()
+            .add_listener_handler(Box::new(listener_handler))
+            .config(config)
+            .build();
+
+        let result = subject.set_up_clandestine_port();
+
+        let config_dao = ConfigDaoReal::new(conn);
+        let persistent_config = PersistentConfigurationReal::new(Box::new(config_dao));
+        let clandestine_port = persistent_config.clandestine_port().unwrap();
+        assert_eq!(result, Some(clandestine_port));
+        assert_eq!(
+            subject.config.neighborhood_config.mode,
+            NeighborhoodMode::RelayOnly(
+                NodeAddr::new(&IpAddr::from_str("1.2.3.4").unwrap(), &[clandestine_port]),
+                vec![],
+                rate_pack(100),
+            )
+        );
+    }
+
     #[test]
     fn set_up_clandestine_port_handles_originate_only() {
         let cryptde_actual = CryptDENull::from(&PublicKey::new(&[1, 2, 3, 4]), TEST_DEFAULT_CHAIN);
@@ -2280,6 +2624,14 @@ mod tests {
             self
         }
 
+        fn listener_handler_factory_make_params(
+            mut self,
+            params: &Arc<Mutex<Vec<SocketTuning>>>,
+        ) -> BootstrapperBuilder {
+            self.listener_handler_factory.make_params(params);
+            self
+        }
+
         fn config(mut self, config: BootstrapperConfig) -> Self {
             self.config = config;
             self
@@ -2294,6 +2646,7 @@ mod tests {
                 >::new(),
                 logger_initializer: self.log_initializer_wrapper,
                 config: self.config,
+                data_directory_lock_opt: None,
             }
         }
     }
@@ -0,0 +1,315 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use masq_lib::logger::Logger;
+use masq_lib::messages::UiMissedEvent;
+use masq_lib::ui_gateway::MessageBody;
+use serde_derive::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const BROADCAST_BACKLOG_CAPACITY: usize = 200;
+pub const BROADCAST_BACKLOG_FILENAME: &str = "daemon-broadcast-backlog.json";
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+pub enum BroadcastPriority {
+    Important,
+    Critical,
+}
+
+// Only broadcasts a user would want to hear about after the fact are kept; setup chatter and the
+// like are of no use to a GUI that wasn't there to see them.
+pub fn broadcast_priority(opcode: &str) -> Option<BroadcastPriority> {
+    match opcode {
+        "crashed" => Some(BroadcastPriority::Critical),
+        "transactionFeeBalance" => Some(BroadcastPriority::Critical),
+        "connectionChange" => Some(BroadcastPriority::Important),
+        _ => None,
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+struct PersistedBacklog {
+    latest_sequence: u64,
+    events: Vec<PersistedEvent>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+struct PersistedEvent {
+    sequence: u64,
+    timestamp_s: u64,
+    critical: bool,
+    opcode: String,
+    payload: String,
+}
+
+impl From<&PersistedEvent> for UiMissedEvent {
+    fn from(event: &PersistedEvent) -> Self {
+        UiMissedEvent {
+            sequence: event.sequence,
+            timestamp_s: event.timestamp_s,
+            critical: event.critical,
+            opcode: event.opcode.clone(),
+            payload: event.payload.clone(),
+        }
+    }
+}
+
+pub struct BroadcastBacklog {
+    file_path_opt: Option<PathBuf>,
+    capacity: usize,
+    backlog: PersistedBacklog,
+    logger: Logger,
+}
+
+impl BroadcastBacklog {
+    pub fn in_memory() -> Self {
+        Self {
+            file_path_opt: None,
+            capacity: BROADCAST_BACKLOG_CAPACITY,
+            backlog: PersistedBacklog::default(),
+            logger: Logger::new("BroadcastBacklog"),
+        }
+    }
+
+    // A missing file is an empty backlog; an unreadable one is reported and started over, since
+    // losing old events is better than keeping the Daemon from coming up.
+    pub fn load(file_path: PathBuf) -> Self {
+        let logger = Logger::new("BroadcastBacklog");
+        let backlog = match fs::read_to_string(&file_path) {
+            Ok(json) => match serde_json::from_str(&json) {
+                Ok(backlog) => backlog,
+                Err(e) => {
+                    warning!(
+                        logger,
+                        "Discarding unreadable broadcast backlog at {:?}: {}",
+                        file_path,
+                        e
+                    );
+                    PersistedBacklog::default()
+                }
+            },
+            Err(_) => PersistedBacklog::default(),
+        };
+        Self {
+            file_path_opt: Some(file_path),
+            capacity: BROADCAST_BACKLOG_CAPACITY,
+            backlog,
+            logger,
+        }
+    }
+
+    pub fn latest_sequence(&self) -> u64 {
+        self.backlog.latest_sequence
+    }
+
+    pub fn record(&mut self, body: &MessageBody, now: SystemTime) {
+        let priority = match broadcast_priority(&body.opcode) {
+            Some(priority) => priority,
+            None => return,
+        };
+        let payload = match &body.payload {
+            Ok(json) => json.clone(),
+            Err(_) => return,
+        };
+        self.backlog.latest_sequence += 1;
+        self.backlog.events.push(PersistedEvent {
+            sequence: self.backlog.latest_sequence,
+            timestamp_s: now
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            critical: priority == BroadcastPriority::Critical,
+            opcode: body.opcode.clone(),
+            payload,
+        });
+        if self.backlog.events.len() > self.capacity {
+            self.evict_one();
+        }
+        self.persist();
+    }
+
+    pub fn events_after(&self, sequence: u64) -> Vec<UiMissedEvent> {
+        self.backlog
+            .events
+            .iter()
+            .filter(|event| event.sequence > sequence)
+            .map(UiMissedEvent::from)
+            .collect()
+    }
+
+    // When the backlog is full, the oldest of the least important events goes first, so a
+    // chatty night of connection changes can't push a crash report out.
+    fn evict_one(&mut self) {
+        let evict_critical = self.backlog.events.iter().all(|event| event.critical);
+        let victim_idx = self
+            .backlog
+            .events
+            .iter()
+            .position(|event| event.critical == evict_critical)
+            .expect("Backlog is full but empty");
+        self.backlog.events.remove(victim_idx);
+    }
+
+    fn persist(&self) {
+        if let Some(file_path) = &self.file_path_opt {
+            let json = serde_json::to_string(&self.backlog)
+                .unwrap_or_else(|e| panic!("Couldn't serialize broadcast backlog: {:?}", e));
+            if let Err(e) = fs::write(file_path, json) {
+                warning!(
+                    self.logger,
+                    "Couldn't save broadcast backlog to {:?}: {}",
+                    file_path,
+                    e
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::messages::{
+        CrashReason, ToMessageBody, UiConnectionChangeBroadcast, UiConnectionStage,
+        UiNodeCrashedBroadcast, UiSetupBroadcast,
+    };
+    use masq_lib::shared_schema::ConfiguratorError;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    fn crashed_body(process_id: u32) -> MessageBody {
+        UiNodeCrashedBroadcast {
+            process_id,
+            crash_reason: CrashReason::Unrecognized("booga".to_string()),
+        }
+        .tmb(0)
+    }
+
+    fn connection_change_body() -> MessageBody {
+        UiConnectionChangeBroadcast {
+            stage: UiConnectionStage::ConnectedToNeighbor,
+        }
+        .tmb(0)
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(BROADCAST_BACKLOG_CAPACITY, 200);
+        assert_eq!(BROADCAST_BACKLOG_FILENAME, "daemon-broadcast-backlog.json");
+    }
+
+    #[test]
+    fn broadcasts_are_prioritized_by_opcode() {
+        assert_eq!(
+            broadcast_priority("crashed"),
+            Some(BroadcastPriority::Critical)
+        );
+        assert_eq!(
+            broadcast_priority("transactionFeeBalance"),
+            Some(BroadcastPriority::Critical)
+        );
+        assert_eq!(
+            broadcast_priority("connectionChange"),
+            Some(BroadcastPriority::Important)
+        );
+        assert_eq!(broadcast_priority("setup"), None);
+    }
+
+    #[test]
+    fn record_keeps_prioritized_broadcasts_and_ignores_the_rest() {
+        let mut subject = BroadcastBacklog::in_memory();
+        let now = UNIX_EPOCH + Duration::from_secs(1_000_000);
+
+        subject.record(&crashed_body(1234), now);
+        subject.record(
+            &UiSetupBroadcast::new(false, HashMap::new(), ConfiguratorError::new(vec![]), 0).tmb(0),
+            now,
+        );
+        subject.record(&connection_change_body(), now);
+
+        assert_eq!(subject.latest_sequence(), 2);
+        assert_eq!(
+            subject.events_after(0),
+            vec![
+                UiMissedEvent {
+                    sequence: 1,
+                    timestamp_s: 1_000_000,
+                    critical: true,
+                    opcode: "crashed".to_string(),
+                    payload: crashed_body(1234).payload.unwrap(),
+                },
+                UiMissedEvent {
+                    sequence: 2,
+                    timestamp_s: 1_000_000,
+                    critical: false,
+                    opcode: "connectionChange".to_string(),
+                    payload: connection_change_body().payload.unwrap(),
+                },
+            ]
+        );
+        assert_eq!(subject.events_after(1).len(), 1);
+        assert_eq!(subject.events_after(2), vec![]);
+    }
+
+    #[test]
+    fn full_backlog_evicts_oldest_important_events_before_critical_ones() {
+        let mut subject = BroadcastBacklog::in_memory();
+        subject.capacity = 3;
+        let now = SystemTime::now();
+
+        subject.record(&crashed_body(1), now);
+        subject.record(&connection_change_body(), now);
+        subject.record(&connection_change_body(), now);
+        subject.record(&crashed_body(2), now);
+        subject.record(&crashed_body(3), now);
+        subject.record(&crashed_body(4), now);
+
+        let sequences = subject
+            .events_after(0)
+            .into_iter()
+            .map(|event| event.sequence)
+            .collect::<Vec<u64>>();
+        assert_eq!(sequences, vec![4, 5, 6]);
+    }
+
+    #[test]
+    fn backlog_survives_being_reloaded() {
+        let home_dir = ensure_node_home_directory_exists(
+            "broadcast_backlog",
+            "backlog_survives_being_reloaded",
+        );
+        let file_path = home_dir.join(BROADCAST_BACKLOG_FILENAME);
+        let _ = fs::remove_file(&file_path);
+        let now = SystemTime::now();
+        {
+            let mut subject = BroadcastBacklog::load(file_path.clone());
+            subject.record(&crashed_body(1234), now);
+            subject.record(&connection_change_body(), now);
+        }
+
+        let subject = BroadcastBacklog::load(file_path);
+
+        assert_eq!(subject.latest_sequence(), 2);
+        assert_eq!(subject.events_after(0).len(), 2);
+        assert_eq!(subject.events_after(0)[0].opcode, "crashed".to_string());
+    }
+
+    #[test]
+    fn unreadable_backlog_is_discarded() {
+        let home_dir = ensure_node_home_directory_exists(
+            "broadcast_backlog",
+            "unreadable_backlog_is_discarded",
+        );
+        let file_path = home_dir.join(BROADCAST_BACKLOG_FILENAME);
+        fs::write(&file_path, "booga").unwrap();
+
+        let mut subject = BroadcastBacklog::load(file_path.clone());
+
+        assert_eq!(subject.latest_sequence(), 0);
+        assert_eq!(subject.events_after(0), vec![]);
+        subject.record(&crashed_body(1234), SystemTime::now());
+        assert_eq!(BroadcastBacklog::load(file_path).latest_sequence(), 1);
+    }
+}
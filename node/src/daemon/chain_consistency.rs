@@ -0,0 +1,336 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::daemon::setup_reporter::SetupCluster;
+use crate::database::db_initializer::{DbInitializationConfig, DbInitializer, DbInitializerReal};
+use crate::db_config::persistent_configuration::{
+    PersistentConfiguration, PersistentConfigurationReal,
+};
+use masq_lib::blockchains::blockchain_records::CHAINS;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+// A database belongs to the chain it was created for: its wallets, balances and transaction
+// numbers mean nothing on any other chain. These are the ways a setup can end up pointing the
+// Node at data that belongs to a chain other than the one it's configured for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChainConflict {
+    Directory {
+        chain: String,
+        data_directory: PathBuf,
+        directory_chain: String,
+    },
+    Database {
+        chain: String,
+        data_directory: PathBuf,
+        database_chain: String,
+    },
+}
+
+impl Display for ChainConflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChainConflict::Directory {
+                chain,
+                data_directory,
+                directory_chain,
+            } => write!(
+                f,
+                "Data directory {:?} lies in the directory for chain '{}', but the chain is '{}'; \
+                 migrate to a data directory for '{}'",
+                data_directory, directory_chain, chain, chain
+            ),
+            ChainConflict::Database {
+                chain,
+                data_directory,
+                database_chain,
+            } => write!(
+                f,
+                "Data directory {:?} holds a database for chain '{}', which cannot be used with \
+                 chain '{}'; migrate to a data directory for '{}'",
+                data_directory, database_chain, chain, chain
+            ),
+        }
+    }
+}
+
+impl ChainConflict {
+    pub fn data_directory(&self) -> &Path {
+        match self {
+            ChainConflict::Directory { data_directory, .. } => data_directory,
+            ChainConflict::Database { data_directory, .. } => data_directory,
+        }
+    }
+
+    // Where the data directory would be if it had been chosen for the right chain in the first
+    // place, if that's anywhere other than where it already is
+    pub fn suggested_data_directory(&self) -> Option<PathBuf> {
+        let (chain, data_directory) = match self {
+            ChainConflict::Directory {
+                chain,
+                data_directory,
+                ..
+            } => (chain, data_directory),
+            ChainConflict::Database {
+                chain,
+                data_directory,
+                ..
+            } => (chain, data_directory),
+        };
+        let mut root = data_directory.clone();
+        while last_component_chain(&root).is_some() {
+            root.pop();
+        }
+        let suggestion = root.join(chain);
+        if &suggestion == data_directory {
+            None
+        } else {
+            Some(suggestion)
+        }
+    }
+}
+
+pub trait ChainConsistencyChecker {
+    fn check(&self, setup: &SetupCluster) -> Option<ChainConflict>;
+    // Returns whether the config file was copied
+    fn prepare_data_directory(
+        &self,
+        old_data_directory: &Path,
+        new_data_directory: &Path,
+        copy_config: bool,
+    ) -> Result<bool, String>;
+}
+
+#[derive(Default)]
+pub struct ChainConsistencyCheckerReal {}
+
+impl ChainConsistencyChecker for ChainConsistencyCheckerReal {
+    fn check(&self, setup: &SetupCluster) -> Option<ChainConflict> {
+        let chain = setup.get("chain").map(|uisrv| uisrv.value.clone())?;
+        let data_directory = setup
+            .get("data-directory")
+            .map(|uisrv| PathBuf::from(&uisrv.value))?;
+        if chain.is_empty() || data_directory.as_os_str().is_empty() {
+            return None;
+        }
+        Self::directory_conflict(&chain, &data_directory)
+            .or_else(|| Self::database_conflict(&chain, &data_directory))
+    }
+
+    fn prepare_data_directory(
+        &self,
+        old_data_directory: &Path,
+        new_data_directory: &Path,
+        copy_config: bool,
+    ) -> Result<bool, String> {
+        fs::create_dir_all(new_data_directory).map_err(|e| {
+            format!(
+                "Couldn't create data directory {:?}: {}",
+                new_data_directory, e
+            )
+        })?;
+        let old_config_file = old_data_directory.join(CONFIG_FILE_NAME);
+        let new_config_file = new_data_directory.join(CONFIG_FILE_NAME);
+        if !copy_config || !old_config_file.exists() || new_config_file.exists() {
+            return Ok(false);
+        }
+        fs::copy(&old_config_file, &new_config_file).map_err(|e| {
+            format!(
+                "Couldn't copy {:?} to {:?}: {}",
+                old_config_file, new_config_file, e
+            )
+        })?;
+        Ok(true)
+    }
+}
+
+impl ChainConsistencyCheckerReal {
+    fn directory_conflict(chain: &str, data_directory: &Path) -> Option<ChainConflict> {
+        let mut parent = data_directory.to_path_buf();
+        if last_component_chain(&parent) == Some(chain) {
+            parent.pop();
+        }
+        match last_component_chain(&parent) {
+            Some(directory_chain) if directory_chain != chain => Some(ChainConflict::Directory {
+                chain: chain.to_string(),
+                data_directory: data_directory.to_path_buf(),
+                directory_chain: directory_chain.to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn database_conflict(chain: &str, data_directory: &Path) -> Option<ChainConflict> {
+        // A database that doesn't exist yet, or is too old for the Daemon to read, will be
+        // created or migrated by the Node for whatever chain it's started on
+        let conn = DbInitializerReal::default()
+            .initialize(
+                data_directory,
+                DbInitializationConfig::migration_suppressed_with_error(),
+            )
+            .ok()?;
+        let database_chain = PersistentConfigurationReal::from(conn).chain_name();
+        if database_chain == chain {
+            None
+        } else {
+            Some(ChainConflict::Database {
+                chain: chain.to_string(),
+                data_directory: data_directory.to_path_buf(),
+                database_chain,
+            })
+        }
+    }
+}
+
+fn last_component_chain(path: &Path) -> Option<&'static str> {
+    let last_component = path.file_name()?.to_str()?;
+    CHAINS
+        .iter()
+        .map(|record| record.literal_identifier)
+        .find(|literal_identifier| *literal_identifier == last_component)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::setup_reporter::setup_cluster_from;
+    use crate::database::db_initializer::ExternalData;
+    use masq_lib::blockchains::chains::Chain;
+    use masq_lib::messages::UiSetupResponseValueStatus::{Default, Set};
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use masq_lib::utils::NeighborhoodModeLight;
+    use std::fs::File;
+    use std::io::Write;
+
+    fn make_setup(chain: Chain, data_directory: &Path) -> SetupCluster {
+        setup_cluster_from(vec![
+            ("chain", chain.rec().literal_identifier, Set),
+            ("data-directory", data_directory.to_str().unwrap(), Default),
+        ])
+    }
+
+    #[test]
+    fn matching_chain_and_data_directory_are_consistent() {
+        let home_dir = ensure_node_home_directory_exists(
+            "chain_consistency",
+            "matching_chain_and_data_directory_are_consistent",
+        );
+        let data_directory = home_dir.join("MASQ").join("polygon-amoy");
+        let subject = ChainConsistencyCheckerReal::default();
+
+        let result = subject.check(&make_setup(Chain::PolyAmoy, &data_directory));
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn data_directory_inside_another_chains_directory_is_a_conflict() {
+        let home_dir = ensure_node_home_directory_exists(
+            "chain_consistency",
+            "data_directory_inside_another_chains_directory_is_a_conflict",
+        );
+        let data_directory = home_dir
+            .join("MASQ")
+            .join("polygon-mainnet")
+            .join("polygon-amoy");
+        let subject = ChainConsistencyCheckerReal::default();
+
+        let result = subject.check(&make_setup(Chain::PolyAmoy, &data_directory));
+
+        let conflict = result.unwrap();
+        assert_eq!(
+            conflict,
+            ChainConflict::Directory {
+                chain: "polygon-amoy".to_string(),
+                data_directory: data_directory.clone(),
+                directory_chain: "polygon-mainnet".to_string(),
+            }
+        );
+        assert_eq!(
+            conflict.suggested_data_directory(),
+            Some(home_dir.join("MASQ").join("polygon-amoy"))
+        );
+        assert_eq!(
+            conflict.to_string(),
+            format!(
+                "Data directory {:?} lies in the directory for chain 'polygon-mainnet', but the \
+                 chain is 'polygon-amoy'; migrate to a data directory for 'polygon-amoy'",
+                data_directory
+            )
+        );
+    }
+
+    #[test]
+    fn data_directory_holding_another_chains_database_is_a_conflict() {
+        let data_directory = ensure_node_home_directory_exists(
+            "chain_consistency",
+            "data_directory_holding_another_chains_database_is_a_conflict",
+        )
+        .join("polygon-amoy");
+        let _ = DbInitializerReal::default()
+            .initialize(
+                &data_directory,
+                DbInitializationConfig::create_or_migrate(ExternalData::new(
+                    Chain::PolyMainnet,
+                    NeighborhoodModeLight::Standard,
+                    None,
+                )),
+            )
+            .unwrap();
+        let subject = ChainConsistencyCheckerReal::default();
+
+        let result = subject.check(&make_setup(Chain::PolyAmoy, &data_directory));
+
+        let conflict = result.unwrap();
+        assert_eq!(
+            conflict,
+            ChainConflict::Database {
+                chain: "polygon-amoy".to_string(),
+                data_directory: data_directory.clone(),
+                database_chain: "polygon-mainnet".to_string(),
+            }
+        );
+        assert_eq!(conflict.suggested_data_directory(), None);
+    }
+
+    #[test]
+    fn missing_values_are_not_a_conflict() {
+        let subject = ChainConsistencyCheckerReal::default();
+
+        let result = subject.check(&setup_cluster_from(vec![("chain", "polygon-amoy", Set)]));
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn prepare_data_directory_creates_directory_and_copies_config_on_request() {
+        let home_dir = ensure_node_home_directory_exists(
+            "chain_consistency",
+            "prepare_data_directory_creates_directory_and_copies_config_on_request",
+        );
+        let old_data_directory = home_dir.join("polygon-mainnet");
+        fs::create_dir_all(&old_data_directory).unwrap();
+        File::create(old_data_directory.join(CONFIG_FILE_NAME))
+            .unwrap()
+            .write_all(b"log-level = \"debug\"\n")
+            .unwrap();
+        let copied_directory = home_dir.join("polygon-amoy");
+        let uncopied_directory = home_dir.join("base-sepolia");
+        let subject = ChainConsistencyCheckerReal::default();
+
+        let copied = subject.prepare_data_directory(&old_data_directory, &copied_directory, true);
+        let uncopied =
+            subject.prepare_data_directory(&old_data_directory, &uncopied_directory, false);
+
+        assert_eq!(copied, Ok(true));
+        assert_eq!(
+            fs::read_to_string(copied_directory.join(CONFIG_FILE_NAME)).unwrap(),
+            "log-level = \"debug\"\n"
+        );
+        assert_eq!(uncopied, Ok(false));
+        assert!(uncopied_directory.is_dir());
+        assert!(!uncopied_directory.join(CONFIG_FILE_NAME).exists());
+    }
+}
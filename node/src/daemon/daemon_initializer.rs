@@ -1,7 +1,11 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use crate::bootstrapper::RealUser;
+use crate::daemon::broadcast_backlog::{BroadcastBacklog, BROADCAST_BACKLOG_FILENAME};
+use crate::daemon::dns_hijack_detector::DnsHijackDetectorReal;
+use crate::daemon::environment_probe::EnvironmentProbeReal;
 use crate::daemon::launcher::LauncherReal;
+use crate::daemon::setup_reporter::SetupReporterBuilder;
 use crate::daemon::{
     ChannelFactory, ChannelFactoryReal, Daemon, DaemonBindMessage, Launcher, Recipients,
 };
@@ -16,24 +20,35 @@ use crossbeam_channel::{unbounded, Receiver, Sender};
 use flexi_logger::LevelFilter;
 use itertools::Itertools;
 use masq_lib::command::StdStreams;
+use masq_lib::constants::PORT_UNAVAILABLE_ERROR;
+use masq_lib::logger::{LogFormat, LogLevelSpec};
 use masq_lib::shared_schema::ConfiguratorError;
 use std::collections::HashMap;
 
 use masq_lib::utils::ExpectValue;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 pub trait RecipientsFactory {
-    fn make(&self, launcher: Box<dyn Launcher>, ui_port: u16) -> Recipients;
+    fn make(&self, launcher: Box<dyn Launcher>, ui_port: u16, data_dir: &Path) -> Recipients;
 }
 
 #[derive(Default)]
 pub struct RecipientsFactoryReal {}
 
 impl RecipientsFactory for RecipientsFactoryReal {
-    fn make(&self, launcher: Box<dyn Launcher>, ui_port: u16) -> Recipients {
+    fn make(&self, launcher: Box<dyn Launcher>, ui_port: u16, data_dir: &Path) -> Recipients {
         let ui_gateway_addr = UiGateway::new(&UiGatewayConfig { ui_port }, false).start();
-        let daemon_addr = Daemon::new(launcher).start();
+        let mut daemon = Daemon::new(launcher);
+        daemon.broadcast_backlog =
+            BroadcastBacklog::load(data_dir.join(BROADCAST_BACKLOG_FILENAME));
+        daemon.dns_hijack_detector_opt = Some(Box::new(DnsHijackDetectorReal::new()));
+        daemon.setup_reporter = Box::new(
+            SetupReporterBuilder::new()
+                .environment_probe(Box::new(EnvironmentProbeReal::new()))
+                .build(),
+        );
+        let daemon_addr = daemon.start();
         Recipients {
             ui_gateway_from_sub: ui_gateway_addr.clone().recipient(),
             ui_gateway_to_sub: ui_gateway_addr.clone().recipient(),
@@ -67,13 +82,18 @@ pub struct DaemonInitializerReal {
     channel_factory: Box<dyn ChannelFactory>,
     recipients_factory: Box<dyn RecipientsFactory>,
     rerunner: Box<dyn Rerunner>,
+    data_dir: PathBuf,
 }
 
 impl DaemonInitializer for DaemonInitializerReal {
     fn go(&mut self, _streams: &mut StdStreams<'_>, _args: &[String]) -> RunModeResult {
         if port_is_busy(self.config.ui_port) {
             let message = format!("There appears to be a process already listening on port {}; are you sure there's not a Daemon already running?", self.config.ui_port);
-            return Err(ConfiguratorError::required("ui-port", message.as_str()));
+            return Err(ConfiguratorError::required(
+                "ui-port",
+                message.as_str(),
+                PORT_UNAVAILABLE_ERROR,
+            ));
         }
         let system = System::new("daemon");
         let (sender, receiver) = self.channel_factory.make();
@@ -136,10 +156,12 @@ impl DaemonInitializerReal {
         let real_data_dir = PathBuf::from_str(real_home_dir)
             .expectv("path string")
             .join(relative_data_dir);
+        let data_dir = real_data_dir.join("MASQ");
         params.logger_initializer_wrapper.init(
-            real_data_dir.join("MASQ"),
+            data_dir.clone(),
             &real_user,
-            LevelFilter::Trace,
+            &LogLevelSpec::new(LevelFilter::Trace),
+            LogFormat::default(),
             Some("daemon"),
         );
         DaemonInitializerReal {
@@ -147,14 +169,15 @@ impl DaemonInitializerReal {
             channel_factory: params.channel_factory,
             recipients_factory: params.recipients_factory,
             rerunner: params.rerunner,
+            data_dir,
         }
     }
 
     fn bind(&mut self, sender: Sender<HashMap<String, String>>) {
         let launcher = LauncherReal::new(sender);
-        let recipients = self
-            .recipients_factory
-            .make(Box::new(launcher), self.config.ui_port);
+        let recipients =
+            self.recipients_factory
+                .make(Box::new(launcher), self.config.ui_port, &self.data_dir);
         let bind_message = DaemonBindMessage {
             to_ui_message_recipient: recipients.ui_gateway_to_sub,
             from_ui_message_recipient: recipients.ui_gateway_from_sub,
@@ -207,13 +230,16 @@ mod tests {
     use std::sync::{Arc, Mutex};
 
     struct RecipientsFactoryMock {
-        make_params: Arc<Mutex<Vec<(Box<dyn Launcher>, u16)>>>,
+        make_params: Arc<Mutex<Vec<(Box<dyn Launcher>, u16, PathBuf)>>>,
         make_results: RefCell<Vec<Recipients>>,
     }
 
     impl RecipientsFactory for RecipientsFactoryMock {
-        fn make(&self, launcher: Box<dyn Launcher>, ui_port: u16) -> Recipients {
-            self.make_params.lock().unwrap().push((launcher, ui_port));
+        fn make(&self, launcher: Box<dyn Launcher>, ui_port: u16, data_dir: &Path) -> Recipients {
+            self.make_params
+                .lock()
+                .unwrap()
+                .push((launcher, ui_port, data_dir.to_path_buf()));
             self.make_results.borrow_mut().remove(0)
         }
     }
@@ -428,7 +454,8 @@ mod tests {
         let result = subject.go(&mut holder.streams(), &[]);
 
         assert_eq!(result,Err(ConfiguratorError::required("ui-port",&format!("There \
-         appears to be a process already listening on port {}; are you sure there's not a Daemon already running?", port))));
+         appears to be a process already listening on port {}; are you sure there's not a Daemon already running?", port),
+         PORT_UNAVAILABLE_ERROR)));
         assert!(holder.stderr.get_string().is_empty());
     }
 
@@ -0,0 +1,426 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::daemon::setup_reporter::SetupCluster;
+use automap_lib::comm_layer::pcp_pmp_common::find_routers;
+use masq_lib::logger::Logger;
+use native_tls::TlsConnector;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::io::{Read, Write};
+use std::net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::str::FromStr;
+use std::time::Duration;
+use trust_dns::op::{Message, Query};
+use trust_dns::rr::{Name, RData, RecordType};
+
+// A name whose addresses are the same wherever it's looked up from
+pub const KNOWN_GOOD_NAME: &str = "one.one.one.one";
+pub const DOH_SERVER_HOST: &str = "cloudflare-dns.com";
+pub const DOH_SERVER_PORT: u16 = 443;
+pub const DNS_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+// An exit Node looks up names for everyone who routes through it, so DNS servers that don't tell
+// the truth make it serve the wrong content to the whole network without anybody noticing
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DnsHijack {
+    Router {
+        server: IpAddr,
+    },
+    CaptivePortal {
+        server: IpAddr,
+        answers: Vec<IpAddr>,
+        trusted_answers: Vec<IpAddr>,
+    },
+}
+
+impl Display for DnsHijack {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DnsHijack::Router { server } => write!(
+                f,
+                "DNS server {} is the router, which may rewrite or censor answers; choose public \
+                 DNS servers instead",
+                server
+            ),
+            DnsHijack::CaptivePortal {
+                server,
+                answers,
+                trusted_answers,
+            } => write!(
+                f,
+                "DNS server {} says {} is at [{}], but DNS-over-HTTPS says [{}]; it may be a \
+                 captive portal or a hijacking resolver, so choose public DNS servers instead",
+                server,
+                KNOWN_GOOD_NAME,
+                join_addresses(answers),
+                join_addresses(trusted_answers)
+            ),
+        }
+    }
+}
+
+pub trait DnsHijackDetector {
+    fn check(&self, setup: &SetupCluster) -> Option<DnsHijack>;
+}
+
+pub struct DnsHijackDetectorReal {
+    // Probing takes a while, so each set of DNS servers is judged only once
+    verdicts: RefCell<HashMap<String, Option<DnsHijack>>>,
+    logger: Logger,
+}
+
+impl Default for DnsHijackDetectorReal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DnsHijackDetector for DnsHijackDetectorReal {
+    fn check(&self, setup: &SetupCluster) -> Option<DnsHijack> {
+        let dns_servers = setup.get("dns-servers").map(|uisrv| uisrv.value.clone())?;
+        if !uses_dns_servers(setup) || dns_servers.is_empty() {
+            return None;
+        }
+        if let Some(verdict) = self.verdicts.borrow().get(&dns_servers) {
+            return verdict.clone();
+        }
+        let verdict = self.judge(&dns_servers);
+        self.verdicts
+            .borrow_mut()
+            .insert(dns_servers, verdict.clone());
+        verdict
+    }
+}
+
+impl DnsHijackDetectorReal {
+    pub fn new() -> Self {
+        Self {
+            verdicts: RefCell::new(HashMap::new()),
+            logger: Logger::new("DnsHijackDetector"),
+        }
+    }
+
+    fn judge(&self, dns_servers: &str) -> Option<DnsHijack> {
+        // Malformed values are the business of setup validation
+        let servers = dns_servers
+            .split(',')
+            .map(|server| IpAddr::from_str(server.trim()))
+            .collect::<Result<Vec<IpAddr>, _>>()
+            .ok()?;
+        let routers = find_routers().unwrap_or_else(|e| {
+            debug!(self.logger, "Couldn't find the router: {:?}", e);
+            vec![]
+        });
+        if let Some(hijack) = spot_router(&servers, &routers) {
+            return Some(hijack);
+        }
+        let trusted_answers = match doh_lookup(KNOWN_GOOD_NAME) {
+            Ok(answers) if !answers.is_empty() => answers,
+            Ok(_) => return None,
+            Err(e) => {
+                debug!(
+                    self.logger,
+                    "Couldn't look up {} over HTTPS; DNS servers unverified: {}",
+                    KNOWN_GOOD_NAME,
+                    e
+                );
+                return None;
+            }
+        };
+        let answers = servers
+            .into_iter()
+            .filter_map(|server| match udp_lookup(server, KNOWN_GOOD_NAME) {
+                Ok(answers) => Some((server, answers)),
+                Err(e) => {
+                    debug!(self.logger, "DNS server {} didn't answer: {}", server, e);
+                    None
+                }
+            })
+            .collect::<Vec<(IpAddr, Vec<IpAddr>)>>();
+        spot_captive_portal(&answers, &trusted_answers)
+    }
+}
+
+// The modes in which the Node looks up names with dns-servers at all
+fn uses_dns_servers(setup: &SetupCluster) -> bool {
+    !matches!(
+        setup.get("neighborhood-mode"),
+        Some(nhm) if &nhm.value == "consume-only" || &nhm.value == "relay-only"
+    )
+}
+
+pub fn spot_router(servers: &[IpAddr], routers: &[IpAddr]) -> Option<DnsHijack> {
+    servers
+        .iter()
+        .find(|server| routers.contains(server))
+        .map(|server| DnsHijack::Router { server: *server })
+}
+
+// A server whose answers have nothing in common with the trusted ones is sending the Node
+// somewhere else
+pub fn spot_captive_portal(
+    answers: &[(IpAddr, Vec<IpAddr>)],
+    trusted_answers: &[IpAddr],
+) -> Option<DnsHijack> {
+    answers
+        .iter()
+        .find(|(_, answers)| !answers.iter().any(|ip| trusted_answers.contains(ip)))
+        .map(|(server, answers)| DnsHijack::CaptivePortal {
+            server: *server,
+            answers: answers.clone(),
+            trusted_answers: trusted_answers.to_vec(),
+        })
+}
+
+fn udp_lookup(server: IpAddr, name: &str) -> Result<Vec<IpAddr>, String> {
+    let local_address = match server {
+        IpAddr::V4(_) => "0.0.0.0:0",
+        IpAddr::V6(_) => "[::]:0",
+    };
+    let socket = UdpSocket::bind(local_address).map_err(|e| e.to_string())?;
+    socket
+        .set_read_timeout(Some(DNS_PROBE_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    socket
+        .send_to(&make_query(name)?, SocketAddr::new(server, 53))
+        .map_err(|e| e.to_string())?;
+    let mut buf = [0u8; 512];
+    let length = socket.recv(&mut buf).map_err(|e| e.to_string())?;
+    answers_from(&buf[..length])
+}
+
+// DNS-over-HTTPS goes around whatever the local network does to port 53
+fn doh_lookup(name: &str) -> Result<Vec<IpAddr>, String> {
+    let address = (DOH_SERVER_HOST, DOH_SERVER_PORT)
+        .to_socket_addrs()
+        .map_err(|e| e.to_string())?
+        .next()
+        .ok_or_else(|| format!("{} doesn't resolve", DOH_SERVER_HOST))?;
+    let stream =
+        TcpStream::connect_timeout(&address, DNS_PROBE_TIMEOUT).map_err(|e| e.to_string())?;
+    stream
+        .set_read_timeout(Some(DNS_PROBE_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    let connector = TlsConnector::new().map_err(|e| e.to_string())?;
+    let mut stream = connector
+        .connect(DOH_SERVER_HOST, stream)
+        .map_err(|e| e.to_string())?;
+    let query = make_query(name)?;
+    let header = format!(
+        "POST /dns-query HTTP/1.0\r\nHost: {}\r\nContent-Type: application/dns-message\r\nAccept: application/dns-message\r\nContent-Length: {}\r\n\r\n",
+        DOH_SERVER_HOST,
+        query.len()
+    );
+    stream
+        .write_all(header.as_bytes())
+        .and_then(|_| stream.write_all(&query))
+        .map_err(|e| e.to_string())?;
+    let mut response = vec![];
+    stream
+        .read_to_end(&mut response)
+        .map_err(|e| e.to_string())?;
+    answers_from(http_body(&response)?)
+}
+
+fn http_body(response: &[u8]) -> Result<&[u8], String> {
+    let header_end = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or_else(|| "Response has no end of header".to_string())?;
+    let status_line = String::from_utf8_lossy(&response[..header_end])
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    if status_line.split_whitespace().nth(1) != Some("200") {
+        return Err(format!("Unsuccessful response: {}", status_line));
+    }
+    Ok(&response[(header_end + 4)..])
+}
+
+fn make_query(name: &str) -> Result<Vec<u8>, String> {
+    let name = Name::from_ascii(name).map_err(|e| e.to_string())?;
+    let mut message = Message::new();
+    message
+        .set_id(rand::random())
+        .set_recursion_desired(true)
+        .add_query(Query::query(name, RecordType::A));
+    message.to_vec().map_err(|e| e.to_string())
+}
+
+fn answers_from(response: &[u8]) -> Result<Vec<IpAddr>, String> {
+    let message = Message::from_vec(response).map_err(|e| e.to_string())?;
+    Ok(message
+        .answers()
+        .iter()
+        .filter_map(|record| match record.rdata() {
+            RData::A(ip) => Some(IpAddr::V4(*ip)),
+            _ => None,
+        })
+        .collect())
+}
+
+fn join_addresses(addresses: &[IpAddr]) -> String {
+    addresses
+        .iter()
+        .map(|ip| ip.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::setup_reporter::setup_cluster_from;
+    use masq_lib::messages::UiSetupResponseValueStatus::Set;
+    use std::net::Ipv4Addr;
+    use trust_dns::rr::Record;
+
+    fn ip(s: &str) -> IpAddr {
+        IpAddr::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(KNOWN_GOOD_NAME, "one.one.one.one");
+        assert_eq!(DOH_SERVER_HOST, "cloudflare-dns.com");
+        assert_eq!(DOH_SERVER_PORT, 443);
+        assert_eq!(DNS_PROBE_TIMEOUT, Duration::from_secs(3));
+    }
+
+    #[test]
+    fn hijacks_render_properly() {
+        let router = DnsHijack::Router {
+            server: ip("192.168.0.1"),
+        };
+        let captive_portal = DnsHijack::CaptivePortal {
+            server: ip("10.0.0.1"),
+            answers: vec![ip("10.0.0.254")],
+            trusted_answers: vec![ip("1.1.1.1"), ip("1.0.0.1")],
+        };
+
+        assert_eq!(
+            router.to_string(),
+            "DNS server 192.168.0.1 is the router, which may rewrite or censor answers; choose \
+             public DNS servers instead"
+        );
+        assert_eq!(
+            captive_portal.to_string(),
+            "DNS server 10.0.0.1 says one.one.one.one is at [10.0.0.254], but DNS-over-HTTPS says \
+             [1.1.1.1, 1.0.0.1]; it may be a captive portal or a hijacking resolver, so choose \
+             public DNS servers instead"
+        );
+    }
+
+    #[test]
+    fn spot_router_finds_the_router_among_the_servers() {
+        let servers = vec![ip("8.8.8.8"), ip("192.168.0.1")];
+
+        let found = spot_router(&servers, &[ip("192.168.0.1")]);
+        let not_found = spot_router(&servers, &[ip("192.168.1.1")]);
+
+        assert_eq!(
+            found,
+            Some(DnsHijack::Router {
+                server: ip("192.168.0.1")
+            })
+        );
+        assert_eq!(not_found, None);
+    }
+
+    #[test]
+    fn spot_captive_portal_finds_the_server_whose_answers_are_all_wrong() {
+        let trusted_answers = vec![ip("1.1.1.1"), ip("1.0.0.1")];
+        let answers = vec![
+            (ip("8.8.8.8"), vec![ip("1.0.0.1")]),
+            (ip("10.0.0.1"), vec![ip("10.0.0.254")]),
+        ];
+
+        let found = spot_captive_portal(&answers, &trusted_answers);
+        let not_found = spot_captive_portal(&answers[..1], &trusted_answers);
+
+        assert_eq!(
+            found,
+            Some(DnsHijack::CaptivePortal {
+                server: ip("10.0.0.1"),
+                answers: vec![ip("10.0.0.254")],
+                trusted_answers,
+            })
+        );
+        assert_eq!(not_found, None);
+    }
+
+    #[test]
+    fn check_ignores_dns_servers_in_modes_that_do_not_use_them() {
+        let subject = DnsHijackDetectorReal::new();
+        let consume_only = setup_cluster_from(vec![
+            ("neighborhood-mode", "consume-only", Set),
+            ("dns-servers", "192.168.0.1", Set),
+        ]);
+        let blank = setup_cluster_from(vec![("dns-servers", "", Set)]);
+
+        assert_eq!(subject.check(&consume_only), None);
+        assert_eq!(subject.check(&blank), None);
+        assert!(subject.verdicts.borrow().is_empty());
+    }
+
+    #[test]
+    fn check_remembers_its_verdict_on_each_set_of_servers() {
+        let subject = DnsHijackDetectorReal::new();
+        let hijack = DnsHijack::Router {
+            server: ip("192.168.0.1"),
+        };
+        subject
+            .verdicts
+            .borrow_mut()
+            .insert("192.168.0.1".to_string(), Some(hijack.clone()));
+        let setup = setup_cluster_from(vec![
+            ("neighborhood-mode", "standard", Set),
+            ("dns-servers", "192.168.0.1", Set),
+        ]);
+
+        let result = subject.check(&setup);
+
+        assert_eq!(result, Some(hijack));
+    }
+
+    #[test]
+    fn answers_come_out_of_a_response_to_the_query() {
+        let query = make_query(KNOWN_GOOD_NAME).unwrap();
+        let mut response = Message::from_vec(&query).unwrap();
+        let name = Name::from_ascii(KNOWN_GOOD_NAME).unwrap();
+        response
+            .add_answer(Record::from_rdata(
+                name.clone(),
+                300,
+                RData::A(Ipv4Addr::new(1, 1, 1, 1)),
+            ))
+            .add_answer(Record::from_rdata(
+                name,
+                300,
+                RData::CNAME(Name::from_ascii("elsewhere.com").unwrap()),
+            ));
+
+        let result = answers_from(&response.to_vec().unwrap());
+
+        assert_eq!(result, Ok(vec![ip("1.1.1.1")]));
+    }
+
+    #[test]
+    fn http_body_insists_on_success() {
+        let success = b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\nbody";
+        let failure = b"HTTP/1.1 415 Unsupported Media Type\r\n\r\n";
+        let truncated = b"HTTP/1.1 200 OK\r\n";
+
+        assert_eq!(http_body(success), Ok(&b"body"[..]));
+        assert_eq!(
+            http_body(failure),
+            Err("Unsuccessful response: HTTP/1.1 415 Unsupported Media Type".to_string())
+        );
+        assert_eq!(
+            http_body(truncated),
+            Err("Response has no end of header".to_string())
+        );
+    }
+}
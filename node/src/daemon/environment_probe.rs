@@ -0,0 +1,275 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::sub_lib::accountant::{ScanIntervals, DEFAULT_SCAN_INTERVALS};
+use crate::sub_lib::neighborhood::{RatePack, DEFAULT_RATE_PACK};
+use crate::sub_lib::public_ip_discovery::is_public_ip;
+use automap_lib::control_layer::automap_control::{AutomapControl, AutomapControlReal};
+use masq_lib::logger::Logger;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use sysinfo::{DiskExt, System, SystemExt};
+
+pub const UPLINK_PROBE_SERVER: &str = "speedtest.tele2.net:80";
+pub const UPLINK_PROBE_PAYLOAD_SIZE: usize = 1_000_000;
+pub const UPLINK_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+// 10 Mbit/s: below this, routing for others will be felt by the user
+pub const THIN_UPLINK_BYTES_PER_SEC: u64 = 1_250_000;
+// A routing Node keeps ledgers of what it's owed and what it owes, and they grow with the traffic
+pub const LOW_DISK_SPACE_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnvironmentReport {
+    // The router answered PCP, PMP or IGDP, so it should be able to open the clandestine port
+    pub port_mapping_available: bool,
+    // The router's idea of our public address is one the rest of the Internet can't reach
+    pub behind_carrier_grade_nat: bool,
+    pub uplink_bytes_per_sec_opt: Option<u64>,
+    pub free_disk_bytes_opt: Option<u64>,
+}
+
+// Looks at the machine and the network around it before the Node has ever run there
+pub trait EnvironmentProbe {
+    fn probe(&self, data_directory: &Path) -> EnvironmentReport;
+}
+
+pub struct EnvironmentProbeReal {
+    logger: Logger,
+}
+
+impl Default for EnvironmentProbeReal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EnvironmentProbe for EnvironmentProbeReal {
+    fn probe(&self, data_directory: &Path) -> EnvironmentReport {
+        let (port_mapping_available, behind_carrier_grade_nat) = self.probe_router();
+        let report = EnvironmentReport {
+            port_mapping_available,
+            behind_carrier_grade_nat,
+            uplink_bytes_per_sec_opt: self.measure_uplink(),
+            free_disk_bytes_opt: free_disk_bytes(data_directory),
+        };
+        info!(self.logger, "First-run environment: {:?}", report);
+        report
+    }
+}
+
+impl EnvironmentProbeReal {
+    pub fn new() -> Self {
+        Self {
+            logger: Logger::new("EnvironmentProbe"),
+        }
+    }
+
+    fn probe_router(&self) -> (bool, bool) {
+        let mut automap_control = AutomapControlReal::new(None, Box::new(|_| ()));
+        match automap_control.get_public_ip() {
+            Ok(router_ip) => (true, !is_public_ip(router_ip)),
+            Err(e) => {
+                debug!(self.logger, "No router would talk to us: {:?}", e);
+                (false, false)
+            }
+        }
+    }
+
+    fn measure_uplink(&self) -> Option<u64> {
+        match Self::time_upload(UPLINK_PROBE_SERVER, UPLINK_PROBE_PAYLOAD_SIZE) {
+            Ok(elapsed) => {
+                Some((UPLINK_PROBE_PAYLOAD_SIZE as u128 * 1000 / elapsed.as_millis().max(1)) as u64)
+            }
+            Err(e) => {
+                debug!(self.logger, "Couldn't measure uplink bandwidth: {}", e);
+                None
+            }
+        }
+    }
+
+    // The clock stops when the server starts to answer, which it won't do until it has everything
+    fn time_upload(server: &str, payload_size: usize) -> Result<Duration, String> {
+        let address = server
+            .to_socket_addrs()
+            .map_err(|e| e.to_string())?
+            .next()
+            .ok_or_else(|| format!("{} doesn't resolve", server))?;
+        let mut stream = TcpStream::connect_timeout(&address, UPLINK_PROBE_TIMEOUT)
+            .map_err(|e| e.to_string())?;
+        stream
+            .set_write_timeout(Some(UPLINK_PROBE_TIMEOUT))
+            .map_err(|e| e.to_string())?;
+        stream
+            .set_read_timeout(Some(UPLINK_PROBE_TIMEOUT))
+            .map_err(|e| e.to_string())?;
+        let host = server.split(':').next().unwrap_or(server);
+        let header = format!(
+            "POST /upload.php HTTP/1.1\r\nHost: {}\r\nContent-Type: application/octet-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            host, payload_size
+        );
+        let started = Instant::now();
+        stream
+            .write_all(header.as_bytes())
+            .and_then(|_| stream.write_all(&vec![0u8; payload_size]))
+            .map_err(|e| e.to_string())?;
+        let mut first_byte = [0u8; 1];
+        match stream.read(&mut first_byte) {
+            Ok(1) => Ok(started.elapsed()),
+            Ok(_) => Err(format!("{} hung up without answering", server)),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+// The disk that holds the data directory is the one with the longest mount point above it
+pub fn free_disk_bytes(data_directory: &Path) -> Option<u64> {
+    let mut system = System::new();
+    system.refresh_disks_list();
+    system
+        .disks()
+        .iter()
+        .filter(|disk| data_directory.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+// A coherent set of values for a Node that has never run here before, neighborhood mode first,
+// since it decides whether the rate pack matters. A Node that can't be reached from outside can't
+// be a standard Node; a thin or unknown uplink is priced up so that it attracts less traffic, and
+// scanned less often.
+pub fn suggest_setup(report: &EnvironmentReport) -> Vec<(&'static str, String)> {
+    let disk_is_low =
+        matches!(report.free_disk_bytes_opt, Some(bytes) if bytes < LOW_DISK_SPACE_BYTES);
+    let uplink_is_thin =
+        !matches!(report.uplink_bytes_per_sec_opt, Some(rate) if rate >= THIN_UPLINK_BYTES_PER_SEC);
+    let neighborhood_mode = if report.port_mapping_available && !report.behind_carrier_grade_nat {
+        "standard"
+    } else if disk_is_low {
+        "consume-only"
+    } else {
+        "originate-only"
+    };
+    let scan_intervals = if uplink_is_thin {
+        let default = *DEFAULT_SCAN_INTERVALS;
+        ScanIntervals {
+            pending_payable_scan_interval: default.pending_payable_scan_interval * 3,
+            payable_scan_interval: default.payable_scan_interval * 3,
+            receivable_scan_interval: default.receivable_scan_interval * 3,
+        }
+    } else {
+        *DEFAULT_SCAN_INTERVALS
+    };
+    let rate_pack = if uplink_is_thin {
+        RatePack {
+            routing_byte_rate: DEFAULT_RATE_PACK.routing_byte_rate * 2,
+            routing_service_rate: DEFAULT_RATE_PACK.routing_service_rate * 2,
+            exit_byte_rate: DEFAULT_RATE_PACK.exit_byte_rate * 2,
+            exit_service_rate: DEFAULT_RATE_PACK.exit_service_rate * 2,
+        }
+    } else {
+        DEFAULT_RATE_PACK
+    };
+    vec![
+        ("neighborhood-mode", neighborhood_mode.to_string()),
+        ("scan-intervals", scan_intervals.to_string()),
+        ("rate-pack", rate_pack.to_string()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_report(
+        port_mapping_available: bool,
+        behind_carrier_grade_nat: bool,
+        uplink_bytes_per_sec_opt: Option<u64>,
+        free_disk_bytes_opt: Option<u64>,
+    ) -> EnvironmentReport {
+        EnvironmentReport {
+            port_mapping_available,
+            behind_carrier_grade_nat,
+            uplink_bytes_per_sec_opt,
+            free_disk_bytes_opt,
+        }
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(UPLINK_PROBE_SERVER, "speedtest.tele2.net:80");
+        assert_eq!(UPLINK_PROBE_PAYLOAD_SIZE, 1_000_000);
+        assert_eq!(UPLINK_PROBE_TIMEOUT, Duration::from_secs(10));
+        assert_eq!(THIN_UPLINK_BYTES_PER_SEC, 1_250_000);
+        assert_eq!(LOW_DISK_SPACE_BYTES, 2_147_483_648);
+    }
+
+    #[test]
+    fn reachable_node_with_a_fat_uplink_is_suggested_standard_mode_and_defaults() {
+        let report = make_report(true, false, Some(THIN_UPLINK_BYTES_PER_SEC), Some(u64::MAX));
+
+        let result = suggest_setup(&report);
+
+        assert_eq!(
+            result,
+            vec![
+                ("neighborhood-mode", "standard".to_string()),
+                ("scan-intervals", "600|600|600".to_string()),
+                (
+                    "rate-pack",
+                    "172300000|1723000000|344600000|3446000000".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn node_behind_carrier_grade_nat_with_an_unmeasured_uplink_is_suggested_originate_only() {
+        let report = make_report(true, true, None, None);
+
+        let result = suggest_setup(&report);
+
+        assert_eq!(
+            result,
+            vec![
+                ("neighborhood-mode", "originate-only".to_string()),
+                ("scan-intervals", "1800|1800|1800".to_string()),
+                (
+                    "rate-pack",
+                    "344600000|3446000000|689200000|6892000000".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn unmappable_node_short_of_disk_space_is_suggested_consume_only() {
+        let report = make_report(
+            false,
+            false,
+            Some(THIN_UPLINK_BYTES_PER_SEC - 1),
+            Some(LOW_DISK_SPACE_BYTES - 1),
+        );
+
+        let result = suggest_setup(&report);
+
+        assert_eq!(
+            result,
+            vec![
+                ("neighborhood-mode", "consume-only".to_string()),
+                ("scan-intervals", "1800|1800|1800".to_string()),
+                (
+                    "rate-pack",
+                    "344600000|3446000000|689200000|6892000000".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn free_disk_bytes_knows_nothing_of_a_directory_under_no_mount_point() {
+        let result = free_disk_bytes(Path::new("relative/data/directory"));
+
+        assert_eq!(result, None);
+    }
+}
@@ -2,10 +2,16 @@
 
 #![cfg(test)]
 
+use crate::daemon::dns_hijack_detector::{DnsHijack, DnsHijackDetector};
+use crate::daemon::environment_probe::{EnvironmentProbe, EnvironmentReport};
 use crate::daemon::launch_verifier::{
     ClientBuilderWrapper, ClientWrapper, LaunchVerification, LaunchVerifier, VerifierTools,
 };
+use crate::daemon::setup_reporter::{CryptDEProvider, SetupCluster};
+use crate::sub_lib::cryptde::CryptDE;
+use masq_lib::blockchains::chains::Chain;
 use std::cell::RefCell;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use websocket::client::ParseError;
 use websocket::{OwnedMessage, WebSocketError, WebSocketResult};
@@ -205,3 +211,99 @@ impl ClientBuilderWrapperMock {
         self
     }
 }
+
+pub struct EnvironmentProbeMock {
+    probe_params: Arc<Mutex<Vec<PathBuf>>>,
+    probe_results: RefCell<Vec<EnvironmentReport>>,
+}
+
+impl EnvironmentProbe for EnvironmentProbeMock {
+    fn probe(&self, data_directory: &Path) -> EnvironmentReport {
+        self.probe_params
+            .lock()
+            .unwrap()
+            .push(data_directory.to_path_buf());
+        self.probe_results.borrow_mut().remove(0)
+    }
+}
+
+impl EnvironmentProbeMock {
+    pub fn new() -> Self {
+        EnvironmentProbeMock {
+            probe_params: Arc::new(Mutex::new(vec![])),
+            probe_results: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn probe_params(mut self, params: &Arc<Mutex<Vec<PathBuf>>>) -> Self {
+        self.probe_params = params.clone();
+        self
+    }
+
+    pub fn probe_result(self, result: EnvironmentReport) -> Self {
+        self.probe_results.borrow_mut().push(result);
+        self
+    }
+}
+
+pub struct DnsHijackDetectorMock {
+    check_params: Arc<Mutex<Vec<SetupCluster>>>,
+    check_results: RefCell<Vec<Option<DnsHijack>>>,
+}
+
+impl DnsHijackDetector for DnsHijackDetectorMock {
+    fn check(&self, setup: &SetupCluster) -> Option<DnsHijack> {
+        self.check_params.lock().unwrap().push(setup.clone());
+        self.check_results.borrow_mut().remove(0)
+    }
+}
+
+impl DnsHijackDetectorMock {
+    pub fn new() -> Self {
+        DnsHijackDetectorMock {
+            check_params: Arc::new(Mutex::new(vec![])),
+            check_results: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn check_params(mut self, params: &Arc<Mutex<Vec<SetupCluster>>>) -> Self {
+        self.check_params = params.clone();
+        self
+    }
+
+    pub fn check_result(self, result: Option<DnsHijack>) -> Self {
+        self.check_results.borrow_mut().push(result);
+        self
+    }
+}
+
+pub struct CryptDEProviderMock {
+    cryptde_for_params: Arc<Mutex<Vec<Chain>>>,
+    cryptde_for_results: RefCell<Vec<Box<dyn CryptDE>>>,
+}
+
+impl CryptDEProvider for CryptDEProviderMock {
+    fn cryptde_for(&self, chain: Chain) -> Box<dyn CryptDE> {
+        self.cryptde_for_params.lock().unwrap().push(chain);
+        self.cryptde_for_results.borrow_mut().remove(0)
+    }
+}
+
+impl CryptDEProviderMock {
+    pub fn new() -> Self {
+        CryptDEProviderMock {
+            cryptde_for_params: Arc::new(Mutex::new(vec![])),
+            cryptde_for_results: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn cryptde_for_params(mut self, params: &Arc<Mutex<Vec<Chain>>>) -> Self {
+        self.cryptde_for_params = params.clone();
+        self
+    }
+
+    pub fn cryptde_for_result(self, result: Box<dyn CryptDE>) -> Self {
+        self.cryptde_for_results.borrow_mut().push(result);
+        self
+    }
+}
@@ -1,40 +1,66 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+pub mod broadcast_backlog;
+pub mod chain_consistency;
 pub mod crash_notification;
 pub mod daemon_initializer;
+pub mod dns_hijack_detector;
 pub mod dns_inspector;
+pub mod environment_probe;
 pub mod launch_verifier;
 mod launcher;
-mod setup_reporter;
+pub mod setup_profile;
+pub mod setup_reporter;
 
 #[cfg(test)]
 mod mocks;
 
+use crate::daemon::broadcast_backlog::BroadcastBacklog;
+use crate::daemon::chain_consistency::{ChainConsistencyChecker, ChainConsistencyCheckerReal};
 use crate::daemon::crash_notification::CrashNotification;
+use crate::daemon::dns_hijack_detector::DnsHijackDetector;
 use crate::daemon::launch_verifier::{VerifierTools, VerifierToolsReal};
-use crate::daemon::setup_reporter::{SetupCluster, SetupReporter, SetupReporterReal};
+use crate::daemon::setup_profile::SetupProfile;
+use crate::daemon::setup_reporter::{
+    make_command_line, SetupCluster, SetupReporter, SetupReporterReal,
+};
 use crate::node_configurator::DirsWrapperReal;
+use crate::sub_lib::data_directory_lock::{DataDirectoryLock, DataDirectoryLockError};
+use crate::sub_lib::node_pid_file::NodePidFile;
+use crate::sub_lib::shutdown_report::read_shutdown_report;
 use crate::sub_lib::utils::NODE_MAILBOX_CAPACITY;
 use actix::Recipient;
 use actix::{Actor, Context, Handler, Message};
 use crossbeam_channel::{Receiver, Sender};
 use itertools::Itertools;
 use lazy_static::lazy_static;
-use masq_lib::constants::{NODE_ALREADY_RUNNING_ERROR, NODE_LAUNCH_ERROR, NODE_NOT_RUNNING_ERROR};
+use masq_lib::blockchains::blockchain_records::CHAINS;
+use masq_lib::constants::{
+    DATA_DIRECTORY_CONFLICT_ERROR, DATA_DIRECTORY_LOCKED_ERROR, DNS_SERVERS_HIJACKED_ERROR,
+    NODE_ALREADY_RUNNING_ERROR, NODE_LAUNCH_ERROR, NODE_NOT_RUNNING_ERROR, PROFILE_ERROR,
+    SETUP_ERROR, SETUP_REVISION_CONFLICT_ERROR,
+};
 use masq_lib::logger::Logger;
-use masq_lib::messages::UiSetupResponseValueStatus::{Configured, Set};
+use masq_lib::messages::UiSetupResponseValueStatus::{Configured, Set, Suggested, Warning};
 use masq_lib::messages::{
-    FromMessageBody, ToMessageBody, UiNodeCrashedBroadcast, UiRedirect, UiSetupBroadcast,
-    UiSetupRequest, UiSetupResponse, UiSetupResponseValue, UiStartOrder, UiStartResponse,
-    UiUndeliveredFireAndForget,
+    FromMessageBody, OrphanedNodeAction, ToMessageBody, UiChainListRequest, UiChainListResponse,
+    UiChainMigrationRequest, UiChainMigrationResponse, UiChainRecord, UiLoadProfileRequest,
+    UiLoadProfileResponse, UiMissedEventsRequest, UiMissedEventsResponse, UiNodeCrashedBroadcast,
+    UiOrphanedNode, UiOrphanedNodeRequest, UiOrphanedNodeResponse, UiParameterDescription,
+    UiRedirect, UiSaveProfileRequest, UiSaveProfileResponse, UiSchemaDescriptionRequest,
+    UiSchemaDescriptionResponse, UiSetupBroadcast, UiSetupRequest, UiSetupRequestValue,
+    UiSetupResponse, UiSetupResponseValue, UiShutdownReportRequest, UiShutdownReportResponse,
+    UiStartOrder, UiStartResponse, UiUndeliveredFireAndForget,
 };
-use masq_lib::shared_schema::ConfiguratorError;
+use masq_lib::shared_schema::{parameter_descriptions, ConfiguratorError, ParamError};
 use masq_lib::ui_gateway::MessagePath::{Conversation, FireAndForget};
 use masq_lib::ui_gateway::MessageTarget::ClientId;
 use masq_lib::ui_gateway::{
     MessageBody, MessagePath, MessageTarget, NodeFromUiMessage, NodeToUiMessage,
 };
 use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 pub struct Recipients {
     ui_gateway_from_sub: Recipient<NodeFromUiMessage>,
@@ -99,12 +125,18 @@ pub struct DaemonBindMessage {
 pub struct Daemon {
     launcher: Box<dyn Launcher>,
     params: SetupCluster,
+    // The names of the parameters each revision of the setup changed, oldest first; the setup
+    // revision is the number of them
+    setup_changes: Vec<HashSet<String>>,
     ui_gateway_sub: Option<Recipient<NodeToUiMessage>>,
     crash_notification_sub: Option<Recipient<CrashNotification>>,
     node_process_id: Option<u32>,
     node_ui_port: Option<u16>,
     verifier_tools: Box<dyn VerifierTools>,
     setup_reporter: Box<dyn SetupReporter>,
+    chain_consistency_checker: Box<dyn ChainConsistencyChecker>,
+    dns_hijack_detector_opt: Option<Box<dyn DnsHijackDetector>>,
+    broadcast_backlog: BroadcastBacklog,
     logger: Logger,
 }
 
@@ -134,6 +166,22 @@ impl Handler<NodeFromUiMessage> for Daemon {
             self.handle_setup(client_id, context_id, setup_request);
         } else if let Ok((_, context_id)) = UiStartOrder::fmb(msg.body.clone()) {
             self.handle_start_order(client_id, context_id);
+        } else if let Ok((_, context_id)) = UiChainListRequest::fmb(msg.body.clone()) {
+            self.handle_chain_list(client_id, context_id);
+        } else if let Ok((_, context_id)) = UiSchemaDescriptionRequest::fmb(msg.body.clone()) {
+            self.handle_schema_description(client_id, context_id);
+        } else if let Ok((request, context_id)) = UiChainMigrationRequest::fmb(msg.body.clone()) {
+            self.handle_chain_migration(client_id, context_id, request);
+        } else if let Ok((request, context_id)) = UiMissedEventsRequest::fmb(msg.body.clone()) {
+            self.handle_missed_events(client_id, context_id, request);
+        } else if let Ok((request, context_id)) = UiOrphanedNodeRequest::fmb(msg.body.clone()) {
+            self.handle_orphaned_node(client_id, context_id, request);
+        } else if let Ok((_, context_id)) = UiShutdownReportRequest::fmb(msg.body.clone()) {
+            self.handle_shutdown_report(client_id, context_id);
+        } else if let Ok((request, context_id)) = UiSaveProfileRequest::fmb(msg.body.clone()) {
+            self.handle_save_profile(client_id, context_id, request);
+        } else if let Ok((request, context_id)) = UiLoadProfileRequest::fmb(msg.body.clone()) {
+            self.handle_load_profile(client_id, context_id, request);
         } else {
             self.handle_unexpected_message(client_id, msg.body);
         }
@@ -156,23 +204,44 @@ impl Daemon {
         Daemon {
             launcher,
             params: HashMap::new(),
+            setup_changes: vec![],
             ui_gateway_sub: None,
             crash_notification_sub: None,
             node_process_id: None,
             node_ui_port: None,
             verifier_tools: Box::new(VerifierToolsReal::new()),
             setup_reporter: Box::new(SetupReporterReal::new(Box::new(DirsWrapperReal::default()))),
+            chain_consistency_checker: Box::new(ChainConsistencyCheckerReal::default()),
+            dns_hijack_detector_opt: None,
+            broadcast_backlog: BroadcastBacklog::in_memory(),
             logger: Logger::new("Daemon"),
         }
     }
 
     fn handle_setup(&mut self, client_id: u64, context_id: u64, payload: UiSetupRequest) {
         if self.port_if_node_is_running().is_some() {
-            let body =
-                UiSetupResponse::new(true, self.censored_params(), ConfiguratorError::new(vec![]))
-                    .tmb(context_id);
+            let (params, errors) = self.reported_setup(ConfiguratorError::new(vec![]));
+            let body = UiSetupResponse::new(
+                true,
+                params,
+                errors,
+                self.command_line_preview(),
+                self.setup_revision(),
+            )
+            .tmb(context_id);
             let target = MessageTarget::ClientId(client_id);
             self.send_ui_message(body, target);
+        } else if let Some(conflicts) = self.revision_conflicts(&payload) {
+            let (params, errors) = self.reported_setup(conflicts);
+            let body = UiSetupResponse::new(
+                false,
+                params,
+                errors,
+                self.command_line_preview(),
+                self.setup_revision(),
+            )
+            .tmb(context_id);
+            self.send_ui_message(body, MessageTarget::ClientId(client_id));
         } else {
             let incoming_setup = payload.values;
             let existing_setup = self.params.clone();
@@ -206,39 +275,392 @@ impl Daemon {
                     )),
                 },
             ),
-            None => match self.launcher.launch(
-                self.params
-                    .iter()
-                    .filter(|(_, v)| v.status == Set || v.status == Configured)
-                    .map(|(k, v)| (k.to_string(), v.value.to_string()))
-                    .collect(),
-                self.crash_notification_sub.clone().expect("Daemon unbound"),
-            ) {
-                Ok(Some(success)) => {
-                    self.node_process_id = Some(success.new_process_id);
-                    self.node_ui_port = Some(success.redirect_ui_port);
-                    self.respond_to_ui(
-                        client_id,
-                        UiStartResponse {
-                            new_process_id: success.new_process_id,
-                            redirect_ui_port: success.redirect_ui_port,
-                        }
-                        .tmb(context_id),
-                    )
-                }
-                Ok(None) => (),
-                Err(s) => self.respond_to_ui(
+            None => match self.find_orphan() {
+                Some(orphan) => self.respond_to_ui(
                     client_id,
                     MessageBody {
                         opcode: "start".to_string(),
                         path: Conversation(context_id),
-                        payload: Err((NODE_LAUNCH_ERROR, format!("Could not launch Node: {}", s))),
+                        payload: Err((
+                            NODE_ALREADY_RUNNING_ERROR,
+                            format!(
+                                "Could not launch Node: orphaned Node (process {}, UI port {}) is \
+                                 already running against data directory {}; adopt or terminate it first",
+                                orphan.process_id, orphan.ui_port, orphan.data_directory
+                            ),
+                        )),
                     },
                 ),
+                None => match self.launch_unless_chain_conflict() {
+                    Ok(Some(success)) => {
+                        self.node_process_id = Some(success.new_process_id);
+                        self.node_ui_port = Some(success.redirect_ui_port);
+                        self.respond_to_ui(
+                            client_id,
+                            UiStartResponse {
+                                new_process_id: success.new_process_id,
+                                redirect_ui_port: success.redirect_ui_port,
+                            }
+                            .tmb(context_id),
+                        )
+                    }
+                    Ok(None) => (),
+                    Err(s) => self.respond_to_ui(
+                        client_id,
+                        MessageBody {
+                            opcode: "start".to_string(),
+                            path: Conversation(context_id),
+                            payload: Err((
+                                NODE_LAUNCH_ERROR,
+                                format!("Could not launch Node: {}", s),
+                            )),
+                        },
+                    ),
+                },
             },
         }
     }
 
+    fn launch_unless_chain_conflict(&self) -> Result<Option<LaunchSuccess>, String> {
+        if let Some(conflict) = self.chain_consistency_checker.check(&self.params) {
+            return Err(conflict.to_string());
+        }
+        if let Some(process_id) = self.data_directory_lock_holder() {
+            return Err(DataDirectoryLockError::HeldBy(process_id).to_string());
+        }
+        self.launcher.launch(
+            self.params
+                .iter()
+                .filter(|(_, v)| [Set, Configured, Suggested].contains(&v.status))
+                .map(|(k, v)| (k.to_string(), v.value.to_string()))
+                .collect(),
+            self.crash_notification_sub.clone().expect("Daemon unbound"),
+        )
+    }
+
+    fn handle_chain_list(&self, client_id: u64, context_id: u64) {
+        let chains = CHAINS.iter().map(UiChainRecord::from).collect();
+        self.respond_to_ui(client_id, UiChainListResponse { chains }.tmb(context_id));
+    }
+
+    fn handle_schema_description(&self, client_id: u64, context_id: u64) {
+        let parameters = parameter_descriptions()
+            .into_iter()
+            .map(|description| UiParameterDescription {
+                name: description.name,
+                group: description.group.name().to_string(),
+                display_order: description.display_order,
+                help: description.help,
+            })
+            .collect();
+        self.respond_to_ui(
+            client_id,
+            UiSchemaDescriptionResponse { parameters }.tmb(context_id),
+        );
+    }
+
+    fn handle_chain_migration(
+        &mut self,
+        client_id: u64,
+        context_id: u64,
+        request: UiChainMigrationRequest,
+    ) {
+        let error_body = |code: u64, message: String| MessageBody {
+            opcode: request.opcode().to_string(),
+            path: Conversation(context_id),
+            payload: Err((code, message)),
+        };
+        if self.port_if_node_is_running().is_some() {
+            return self.respond_to_ui(
+                client_id,
+                error_body(
+                    NODE_ALREADY_RUNNING_ERROR,
+                    "Cannot migrate data directory: Node is running".to_string(),
+                ),
+            );
+        }
+        let conflict = match self.chain_consistency_checker.check(&self.params) {
+            Some(conflict) => conflict,
+            None => {
+                return self.respond_to_ui(
+                    client_id,
+                    error_body(
+                        SETUP_ERROR,
+                        "Chain and data directory agree; there is nothing to migrate".to_string(),
+                    ),
+                )
+            }
+        };
+        let new_data_directory = match request
+            .data_directory_opt
+            .as_ref()
+            .map(PathBuf::from)
+            .or_else(|| conflict.suggested_data_directory())
+        {
+            Some(new_data_directory) => new_data_directory,
+            None => {
+                return self.respond_to_ui(
+                    client_id,
+                    error_body(
+                        SETUP_ERROR,
+                        format!(
+                            "{}; there is no obvious place for it, so please specify one",
+                            conflict
+                        ),
+                    ),
+                )
+            }
+        };
+        let config_copied = match self.chain_consistency_checker.prepare_data_directory(
+            conflict.data_directory(),
+            &new_data_directory,
+            request.copy_config,
+        ) {
+            Ok(config_copied) => config_copied,
+            Err(message) => return self.respond_to_ui(client_id, error_body(SETUP_ERROR, message)),
+        };
+        let incoming_setup = vec![UiSetupRequestValue::new(
+            "data-directory",
+            &new_data_directory.to_string_lossy(),
+        )];
+        let (setup, errors) = match self
+            .setup_reporter
+            .get_modified_setup(self.params.clone(), incoming_setup)
+        {
+            Ok(setup) => (setup, ConfiguratorError::new(vec![])),
+            Err((lame_cluster, errors)) => (lame_cluster, errors),
+        };
+        self.replace_setup(setup);
+        let data_directory = self
+            .params
+            .get("data-directory")
+            .map(|uisrv| uisrv.value.clone())
+            .unwrap_or_default();
+        info!(
+            &self.logger,
+            "Migrated setup from data directory {:?} to {}",
+            conflict.data_directory(),
+            data_directory
+        );
+        self.respond_to_ui(
+            client_id,
+            UiChainMigrationResponse {
+                data_directory,
+                config_copied,
+            }
+            .tmb(context_id),
+        );
+        let (params, errors) = self.reported_setup(errors);
+        self.send_ui_message(
+            UiSetupBroadcast::new(false, params, errors, self.setup_revision()).tmb(0),
+            MessageTarget::AllClients,
+        );
+    }
+
+    fn handle_missed_events(
+        &self,
+        client_id: u64,
+        context_id: u64,
+        request: UiMissedEventsRequest,
+    ) {
+        let response = UiMissedEventsResponse {
+            latest_sequence: self.broadcast_backlog.latest_sequence(),
+            events: self.broadcast_backlog.events_after(request.after_sequence),
+        };
+        self.respond_to_ui(client_id, response.tmb(context_id));
+    }
+
+    // The Node leaves its shutdown report behind in its data directory, where it outlives the Node
+    fn handle_shutdown_report(&self, client_id: u64, context_id: u64) {
+        let report_opt = self
+            .params
+            .get("data-directory")
+            .and_then(|data_directory| read_shutdown_report(Path::new(&data_directory.value)));
+        self.respond_to_ui(
+            client_id,
+            UiShutdownReportResponse { report_opt }.tmb(context_id),
+        );
+    }
+
+    // Saving only reads the setup, so it's allowed while the Node runs
+    fn handle_save_profile(&self, client_id: u64, context_id: u64, request: UiSaveProfileRequest) {
+        let profile = SetupProfile::from_setup(&self.params);
+        if let Err(message) = self
+            .profile_data_directory()
+            .and_then(|data_directory| profile.save(&data_directory, &request.name))
+        {
+            return self.respond_to_ui(
+                client_id,
+                Self::profile_error_body(request.opcode(), context_id, message),
+            );
+        }
+        info!(
+            &self.logger,
+            "Saved {} setup values to profile '{}'",
+            profile.values().len(),
+            request.name
+        );
+        self.respond_to_ui(
+            client_id,
+            UiSaveProfileResponse {
+                name: request.name,
+                saved_parameters: profile.names(),
+            }
+            .tmb(context_id),
+        );
+    }
+
+    fn handle_load_profile(
+        &mut self,
+        client_id: u64,
+        context_id: u64,
+        request: UiLoadProfileRequest,
+    ) {
+        if self.port_if_node_is_running().is_some() {
+            return self.respond_to_ui(
+                client_id,
+                MessageBody {
+                    opcode: request.opcode().to_string(),
+                    path: Conversation(context_id),
+                    payload: Err((
+                        NODE_ALREADY_RUNNING_ERROR,
+                        "Cannot load profile: Node is running".to_string(),
+                    )),
+                },
+            );
+        }
+        let profile = match self
+            .profile_data_directory()
+            .and_then(|data_directory| SetupProfile::load(&data_directory, &request.name))
+        {
+            Ok(profile) => profile,
+            Err(message) => {
+                return self.respond_to_ui(
+                    client_id,
+                    Self::profile_error_body(request.opcode(), context_id, message),
+                )
+            }
+        };
+        let loaded_parameters = profile.not_set_in(&self.params).names();
+        let (setup, errors) = match self
+            .setup_reporter
+            .get_profiled_setup(self.params.clone(), &profile)
+        {
+            Ok(setup) => (setup, ConfiguratorError::new(vec![])),
+            Err((lame_cluster, errors)) => (lame_cluster, errors),
+        };
+        self.replace_setup(setup);
+        info!(
+            &self.logger,
+            "Loaded {} setup values from profile '{}'",
+            loaded_parameters.len(),
+            request.name
+        );
+        self.respond_to_ui(
+            client_id,
+            UiLoadProfileResponse {
+                name: request.name,
+                loaded_parameters,
+            }
+            .tmb(context_id),
+        );
+        let (params, errors) = self.reported_setup(errors);
+        self.send_ui_message(
+            UiSetupBroadcast::new(false, params, errors, self.setup_revision()).tmb(0),
+            MessageTarget::AllClients,
+        );
+    }
+
+    fn profile_data_directory(&self) -> Result<PathBuf, String> {
+        self.params
+            .get("data-directory")
+            .map(|uisrv| PathBuf::from(&uisrv.value))
+            .ok_or_else(|| "The setup has no data directory to keep profiles in".to_string())
+    }
+
+    fn profile_error_body(opcode: &str, context_id: u64, message: String) -> MessageBody {
+        MessageBody {
+            opcode: opcode.to_string(),
+            path: Conversation(context_id),
+            payload: Err((PROFILE_ERROR, message)),
+        }
+    }
+
+    fn handle_orphaned_node(
+        &mut self,
+        client_id: u64,
+        context_id: u64,
+        request: UiOrphanedNodeRequest,
+    ) {
+        let orphan_opt = match self.port_if_node_is_running() {
+            Some(_) => None,
+            None => self.find_orphan(),
+        };
+        match (&orphan_opt, request.action) {
+            (_, OrphanedNodeAction::Report) => (),
+            (None, _) => {
+                return self.respond_to_ui(
+                    client_id,
+                    MessageBody {
+                        opcode: request.opcode().to_string(),
+                        path: Conversation(context_id),
+                        payload: Err((
+                            NODE_NOT_RUNNING_ERROR,
+                            "No orphaned Node is running against the data directory".to_string(),
+                        )),
+                    },
+                )
+            }
+            (Some(orphan), OrphanedNodeAction::Adopt) => {
+                info!(
+                    &self.logger,
+                    "Adopting orphaned Node: process {}, UI port {}",
+                    orphan.process_id,
+                    orphan.ui_port
+                );
+                self.node_process_id = Some(orphan.process_id);
+                self.node_ui_port = Some(orphan.ui_port);
+            }
+            (Some(orphan), OrphanedNodeAction::Terminate) => {
+                info!(
+                    &self.logger,
+                    "Terminating orphaned Node: process {}, UI port {}",
+                    orphan.process_id,
+                    orphan.ui_port
+                );
+                self.verifier_tools.kill_process(orphan.process_id);
+            }
+        }
+        self.respond_to_ui(
+            client_id,
+            UiOrphanedNodeResponse {
+                action: request.action,
+                orphan_opt,
+            }
+            .tmb(context_id),
+        );
+    }
+
+    // A Node the Daemon didn't launch is trusted only if its process is alive and its UI port
+    // answers: the pid file it left behind may be stale, and its process ID reused
+    fn find_orphan(&self) -> Option<UiOrphanedNode> {
+        let data_directory = &self.params.get("data-directory")?.value;
+        let pid_file = NodePidFile::read(Path::new(data_directory))?;
+        if self.verifier_tools.process_is_running(pid_file.process_id)
+            && self
+                .verifier_tools
+                .can_connect_to_ui_gateway(pid_file.ui_port)
+        {
+            Some(UiOrphanedNode {
+                process_id: pid_file.process_id,
+                ui_port: pid_file.ui_port,
+                data_directory: data_directory.clone(),
+            })
+        } else {
+            None
+        }
+    }
+
     fn handle_unexpected_message(&mut self, client_id: u64, body: MessageBody) {
         match self.port_if_node_is_running() {
             Some(port) => {
@@ -274,17 +696,21 @@ impl Daemon {
         if self.node_ui_port.is_some() || self.node_process_id.is_some() {
             self.node_process_id = None;
             self.node_ui_port = None;
-            self.send_ui_message(
+            self.broadcast_and_remember(
                 UiNodeCrashedBroadcast {
                     process_id: msg.process_id,
                     crash_reason: msg.analyze(),
                 }
                 .tmb(0),
-                MessageTarget::AllClients,
             );
         }
     }
 
+    fn broadcast_and_remember(&mut self, body: MessageBody) {
+        self.broadcast_backlog.record(&body, SystemTime::now());
+        self.send_ui_message(body, MessageTarget::AllClients);
+    }
+
     fn port_if_node_is_running(&mut self) -> Option<u16> {
         if let Some(process_id) = self.node_process_id {
             if self.verifier_tools.process_is_running(process_id) {
@@ -339,30 +765,152 @@ impl Daemon {
         let body_target_pairs = match Self::compare_setup_clusters(&self.params, &new_setup) {
             Err(_) => {
                 let originally_empty = self.params.is_empty();
-                self.params = new_setup;
+                self.replace_setup(new_setup);
+                let (params, errors) = self.reported_setup(errors);
                 let mut pairs = vec![(
-                    UiSetupResponse::new(false, self.censored_params(), errors.clone())
-                        .tmb(context_id),
+                    UiSetupResponse::new(
+                        false,
+                        params.clone(),
+                        errors.clone(),
+                        self.command_line_preview(),
+                        self.setup_revision(),
+                    )
+                    .tmb(context_id),
                     MessageTarget::ClientId(client_id),
                 )];
                 if !originally_empty {
                     pairs.push((
-                        UiSetupBroadcast::new(false, self.censored_params(), errors).tmb(0),
+                        UiSetupBroadcast::new(false, params, errors, self.setup_revision()).tmb(0),
                         MessageTarget::AllExcept(client_id),
                     ));
                 };
                 pairs
             }
-            Ok(_) => vec![(
-                UiSetupResponse::new(false, self.censored_params(), errors).tmb(context_id),
-                MessageTarget::ClientId(client_id),
-            )],
+            Ok(_) => {
+                let (params, errors) = self.reported_setup(errors);
+                vec![(
+                    UiSetupResponse::new(
+                        false,
+                        params,
+                        errors,
+                        self.command_line_preview(),
+                        self.setup_revision(),
+                    )
+                    .tmb(context_id),
+                    MessageTarget::ClientId(client_id),
+                )]
+            }
         };
         body_target_pairs
             .into_iter()
             .for_each(|(body, target)| self.send_ui_message(body, target));
     }
 
+    fn replace_setup(&mut self, new_setup: SetupCluster) {
+        let changed_names = self
+            .params
+            .keys()
+            .chain(new_setup.keys())
+            .filter(|name| {
+                self.params.get(*name).map(|uisrv| &uisrv.value)
+                    != new_setup.get(*name).map(|uisrv| &uisrv.value)
+            })
+            .cloned()
+            .collect::<HashSet<String>>();
+        if !changed_names.is_empty() {
+            self.setup_changes.push(changed_names);
+        }
+        self.params = new_setup;
+    }
+
+    fn setup_revision(&self) -> u64 {
+        self.setup_changes.len() as u64
+    }
+
+    // A request based on an old revision of the setup is refused if it would overwrite anything
+    // another UI has changed since; otherwise the two sets of changes merge on their own
+    fn revision_conflicts(&self, request: &UiSetupRequest) -> Option<ConfiguratorError> {
+        let revision = request.revision_opt?;
+        let changed_since = match self.setup_changes.get(revision as usize..) {
+            Some(changes) => changes.iter().flatten().collect::<HashSet<&String>>(),
+            // A revision from the future must predate a restart of the Daemon
+            None => request.values.iter().map(|value| &value.name).collect(),
+        };
+        let conflicts = request
+            .values
+            .iter()
+            .map(|value| &value.name)
+            .filter(|name| changed_since.contains(name))
+            .unique()
+            .map(|name| {
+                ParamError::new(
+                    name,
+                    &format!(
+                        "Changed by another UI since revision {}; the setup is now at revision {}",
+                        revision,
+                        self.setup_revision()
+                    ),
+                    SETUP_REVISION_CONFLICT_ERROR,
+                )
+            })
+            .collect::<Vec<ParamError>>();
+        if conflicts.is_empty() {
+            None
+        } else {
+            Some(ConfiguratorError::new(conflicts))
+        }
+    }
+
+    // The stored setup never carries a Warning: it's worked out afresh each time the setup is
+    // reported, so that it disappears as soon as the conflict does
+    fn reported_setup(&self, mut errors: ConfiguratorError) -> (SetupCluster, ConfiguratorError) {
+        let mut params = self.censored_params();
+        if let Some(conflict) = self.chain_consistency_checker.check(&self.params) {
+            if let Some(uisrv) = params.get_mut("data-directory") {
+                uisrv.status = Warning;
+            }
+            errors.param_errors.push(ParamError::new(
+                "data-directory",
+                &conflict.to_string(),
+                DATA_DIRECTORY_CONFLICT_ERROR,
+            ));
+        }
+        if let Some(process_id) = self.data_directory_lock_holder() {
+            if let Some(uisrv) = params.get_mut("data-directory") {
+                uisrv.status = Warning;
+            }
+            errors.param_errors.push(ParamError::new(
+                "data-directory",
+                &DataDirectoryLockError::HeldBy(process_id).to_string(),
+                DATA_DIRECTORY_LOCKED_ERROR,
+            ));
+        }
+        if let Some(hijack) = self
+            .dns_hijack_detector_opt
+            .as_ref()
+            .and_then(|detector| detector.check(&self.params))
+        {
+            if let Some(uisrv) = params.get_mut("dns-servers") {
+                uisrv.status = Warning;
+            }
+            errors.param_errors.push(ParamError::new(
+                "dns-servers",
+                &hijack.to_string(),
+                DNS_SERVERS_HIJACKED_ERROR,
+            ));
+        }
+        (params, errors)
+    }
+
+    // The Daemon's own Node holds the lock legitimately, so only some other Node counts
+    fn data_directory_lock_holder(&self) -> Option<u32> {
+        let data_directory = &self.params.get("data-directory")?.value;
+        DataDirectoryLock::holder(Path::new(data_directory), &|process_id| {
+            Some(process_id) != self.node_process_id
+                && self.verifier_tools.process_is_running(process_id)
+        })
+    }
+
     fn censored_params(&self) -> SetupCluster {
         self.params
             .clone()
@@ -377,6 +925,12 @@ impl Daemon {
             .collect()
     }
 
+    // Worked out from the stored setup rather than the reported one, so that a Warning doesn't
+    // hide a value the Node would still be given
+    fn command_line_preview(&self) -> Vec<String> {
+        make_command_line(&self.censored_params())
+    }
+
     fn send_ui_message(&self, body: MessageBody, target: MessageTarget) {
         self.ui_gateway_sub
             .as_ref()
@@ -429,23 +983,31 @@ impl Daemon {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::daemon::chain_consistency::ChainConflict;
     use crate::daemon::crash_notification::CrashNotification;
-    use crate::daemon::mocks::VerifierToolsMock;
+    use crate::daemon::dns_hijack_detector::DnsHijack;
+    use crate::daemon::mocks::{DnsHijackDetectorMock, VerifierToolsMock};
     use crate::daemon::setup_reporter::{setup_cluster_from, SetupCluster};
     use crate::daemon::LaunchSuccess;
+    use crate::sub_lib::data_directory_lock::DATA_DIRECTORY_LOCK_FILE_NAME;
+    use crate::sub_lib::shutdown_report::write_shutdown_report;
+    use crate::test_utils::assert_string_contains;
     use crate::test_utils::recorder::make_recorder;
     use crate::test_utils::unshared_test_utils::make_daemon_bind_message;
     use actix::System;
+    use masq_lib::blockchains::chains::Chain;
     use masq_lib::constants::{
-        NODE_ALREADY_RUNNING_ERROR, NODE_LAUNCH_ERROR, NODE_NOT_RUNNING_ERROR,
+        INVALID_PARAM_VALUE_ERROR, NODE_ALREADY_RUNNING_ERROR, NODE_LAUNCH_ERROR,
+        NODE_NOT_RUNNING_ERROR, SETUP_ERROR,
     };
     use masq_lib::messages::UiSetupResponseValueStatus::{Blank, Required, Set};
     use masq_lib::messages::{
-        CrashReason, UiFinancialsRequest, UiNodeCrashedBroadcast, UiRedirect, UiSetupBroadcast,
-        UiSetupRequest, UiSetupRequestValue, UiSetupResponse, UiSetupResponseValue,
-        UiSetupResponseValueStatus, UiShutdownRequest, UiStartOrder, UiStartResponse,
+        CrashReason, UiBanRisk, UiFinancialsRequest, UiNodeCrashedBroadcast, UiRedirect,
+        UiSetupBroadcast, UiSetupRequest, UiSetupRequestValue, UiSetupResponse,
+        UiSetupResponseValue, UiSetupResponseValueStatus, UiShutdownReport, UiShutdownRequest,
+        UiStartOrder, UiStartResponse,
     };
-    use masq_lib::shared_schema::ConfiguratorError;
+    use masq_lib::shared_schema::{ConfiguratorError, NEIGHBORHOOD_MODE_HELP};
     use masq_lib::test_utils::environment_guard::{ClapGuard, EnvironmentGuard};
     use masq_lib::test_utils::utils::{ensure_node_home_directory_exists, TEST_DEFAULT_CHAIN};
     use masq_lib::ui_gateway::MessageTarget::AllExcept;
@@ -453,6 +1015,9 @@ mod tests {
     use std::cell::RefCell;
     use std::collections::HashSet;
     use std::iter::FromIterator;
+    use std::net::IpAddr;
+    use std::path::Path;
+    use std::str::FromStr;
     use std::sync::{Arc, Mutex};
 
     #[test]
@@ -514,6 +1079,9 @@ mod tests {
         get_modified_setup_params: Arc<Mutex<Vec<(SetupCluster, Vec<UiSetupRequestValue>)>>>,
         get_modified_setup_results:
             RefCell<Vec<Result<SetupCluster, (SetupCluster, ConfiguratorError)>>>,
+        get_profiled_setup_params: Arc<Mutex<Vec<(SetupCluster, SetupProfile)>>>,
+        get_profiled_setup_results:
+            RefCell<Vec<Result<SetupCluster, (SetupCluster, ConfiguratorError)>>>,
     }
 
     impl SetupReporter for SetupReporterMock {
@@ -528,6 +1096,26 @@ mod tests {
                 .push((existing_setup, incoming_setup));
             self.get_modified_setup_results.borrow_mut().remove(0)
         }
+
+        fn validate_setup(
+            &self,
+            _existing_setup: SetupCluster,
+            _incoming_setup: Vec<UiSetupRequestValue>,
+        ) -> Result<SetupCluster, (SetupCluster, ConfiguratorError)> {
+            intentionally_blank!()
+        }
+
+        fn get_profiled_setup(
+            &self,
+            existing_setup: SetupCluster,
+            profile: &SetupProfile,
+        ) -> Result<SetupCluster, (SetupCluster, ConfiguratorError)> {
+            self.get_profiled_setup_params
+                .lock()
+                .unwrap()
+                .push((existing_setup, profile.clone()));
+            self.get_profiled_setup_results.borrow_mut().remove(0)
+        }
     }
 
     impl SetupReporterMock {
@@ -535,10 +1123,12 @@ mod tests {
             Self {
                 get_modified_setup_params: Arc::new(Mutex::new(vec![])),
                 get_modified_setup_results: RefCell::new(vec![]),
+                get_profiled_setup_params: Arc::new(Mutex::new(vec![])),
+                get_profiled_setup_results: RefCell::new(vec![]),
             }
         }
 
-        fn _get_modified_setup_params(
+        fn get_modified_setup_params(
             mut self,
             params: &Arc<Mutex<Vec<(SetupCluster, Vec<UiSetupRequestValue>)>>>,
         ) -> Self {
@@ -553,6 +1143,86 @@ mod tests {
             self.get_modified_setup_results.borrow_mut().push(result);
             self
         }
+
+        fn get_profiled_setup_params(
+            mut self,
+            params: &Arc<Mutex<Vec<(SetupCluster, SetupProfile)>>>,
+        ) -> Self {
+            self.get_profiled_setup_params = params.clone();
+            self
+        }
+
+        fn get_profiled_setup_result(
+            self,
+            result: Result<SetupCluster, (SetupCluster, ConfiguratorError)>,
+        ) -> Self {
+            self.get_profiled_setup_results.borrow_mut().push(result);
+            self
+        }
+    }
+
+    struct ChainConsistencyCheckerMock {
+        check_results: RefCell<Vec<Option<ChainConflict>>>,
+        prepare_data_directory_params: Arc<Mutex<Vec<(PathBuf, PathBuf, bool)>>>,
+        prepare_data_directory_results: RefCell<Vec<Result<bool, String>>>,
+    }
+
+    impl ChainConsistencyChecker for ChainConsistencyCheckerMock {
+        fn check(&self, _setup: &SetupCluster) -> Option<ChainConflict> {
+            self.check_results.borrow_mut().remove(0)
+        }
+
+        fn prepare_data_directory(
+            &self,
+            old_data_directory: &Path,
+            new_data_directory: &Path,
+            copy_config: bool,
+        ) -> Result<bool, String> {
+            self.prepare_data_directory_params.lock().unwrap().push((
+                old_data_directory.to_path_buf(),
+                new_data_directory.to_path_buf(),
+                copy_config,
+            ));
+            self.prepare_data_directory_results.borrow_mut().remove(0)
+        }
+    }
+
+    impl ChainConsistencyCheckerMock {
+        fn new() -> Self {
+            Self {
+                check_results: RefCell::new(vec![]),
+                prepare_data_directory_params: Arc::new(Mutex::new(vec![])),
+                prepare_data_directory_results: RefCell::new(vec![]),
+            }
+        }
+
+        fn check_result(self, result: Option<ChainConflict>) -> Self {
+            self.check_results.borrow_mut().push(result);
+            self
+        }
+
+        fn prepare_data_directory_params(
+            mut self,
+            params: &Arc<Mutex<Vec<(PathBuf, PathBuf, bool)>>>,
+        ) -> Self {
+            self.prepare_data_directory_params = params.clone();
+            self
+        }
+
+        fn prepare_data_directory_result(self, result: Result<bool, String>) -> Self {
+            self.prepare_data_directory_results
+                .borrow_mut()
+                .push(result);
+            self
+        }
+    }
+
+    fn make_chain_conflict() -> ChainConflict {
+        ChainConflict::Directory {
+            chain: "polygon-amoy".to_string(),
+            data_directory: PathBuf::from("/home/booga/MASQ/polygon-mainnet/polygon-amoy"),
+            directory_chain: "polygon-mainnet".to_string(),
+        }
     }
 
     fn make_setup_cluster(items: Vec<(&str, &str, UiSetupResponseValueStatus)>) -> SetupCluster {
@@ -567,6 +1237,14 @@ mod tests {
             .collect()
     }
 
+    fn make_orphan_setup(test_name: &str, process_id: u32, ui_port: u16) -> SetupCluster {
+        let data_dir = ensure_node_home_directory_exists("daemon", test_name);
+        NodePidFile::new(process_id, ui_port)
+            .write(&data_dir)
+            .unwrap();
+        make_setup_cluster(vec![("data-directory", &data_dir.to_string_lossy(), Set)])
+    }
+
     #[test]
     fn censorship_works() {
         let mut subject = Daemon::new(Box::new(LauncherMock::new()));
@@ -620,6 +1298,7 @@ mod tests {
                 client_id: 1234,
                 body: UiSetupRequest {
                     values: vec![UiSetupRequestValue::new("log-level", "trace")],
+                    revision_opt: None,
                 }
                 .tmb(4321),
             })
@@ -649,6 +1328,15 @@ mod tests {
                     UiSetupResponseValue::new("neighborhood-mode", "zero-hop", Set),
                 ],
                 errors: vec![],
+                command_line: vec![
+                    "--consuming-private-key".to_string(),
+                    "****************************************************************".to_string(),
+                    "--db-password".to_string(),
+                    "****************".to_string(),
+                    "--neighborhood-mode".to_string(),
+                    "zero-hop".to_string(),
+                ],
+                revision: 0,
             }
         );
     }
@@ -687,6 +1375,7 @@ mod tests {
                         UiSetupRequestValue::new("log-level", "trace"),
                         UiSetupRequestValue::new("consuming-private-key", "secret value"),
                     ],
+                    revision_opt: None,
                 }
                 .tmb(4321),
             })
@@ -759,6 +1448,7 @@ mod tests {
                         ),
                         UiSetupRequestValue::new("neighborhood-mode", "zero-hop"),
                     ],
+                    revision_opt: None,
                 }
                 .tmb(4321),
             })
@@ -819,6 +1509,7 @@ mod tests {
                     UiSetupRequestValue::new("chain", TEST_DEFAULT_CHAIN.rec().literal_identifier),
                     UiSetupRequestValue::new("neighborhood-mode", "zero-hop"),
                 ],
+                revision_opt: None,
             }
             .tmb(4321),
         };
@@ -834,22 +1525,23 @@ mod tests {
                 .get_record::<NodeToUiMessage>(idx)
                 .clone()
         };
-        let check_payload =
-            |running: bool, values: Vec<UiSetupResponseValue>, errors: Vec<(String, String)>| {
-                assert_eq!(running, false);
-                let actual_pairs: HashSet<(String, String)> = values
-                    .into_iter()
-                    .map(|value| (value.name, value.value))
-                    .collect();
-                assert_eq!(
-                    actual_pairs.contains(&(
-                        "chain".to_string(),
-                        TEST_DEFAULT_CHAIN.rec().literal_identifier.to_string()
-                    )),
-                    true
-                );
-                assert_eq!(errors, vec![]);
-            };
+        let check_payload = |running: bool,
+                             values: Vec<UiSetupResponseValue>,
+                             errors: Vec<(String, String, u64)>| {
+            assert_eq!(running, false);
+            let actual_pairs: HashSet<(String, String)> = values
+                .into_iter()
+                .map(|value| (value.name, value.value))
+                .collect();
+            assert_eq!(
+                actual_pairs.contains(&(
+                    "chain".to_string(),
+                    TEST_DEFAULT_CHAIN.rec().literal_identifier.to_string()
+                )),
+                true
+            );
+            assert_eq!(errors, vec![]);
+        };
         let record = get_record(0);
         assert_eq!(record.target, ClientId(1234));
         let (payload, context_id): (UiSetupResponse, u64) =
@@ -877,7 +1569,7 @@ mod tests {
         subject.setup_reporter =
             Box::new(SetupReporterMock::new().get_modified_setup_result(Err((
                 lame_setup,
-                ConfiguratorError::required("parameter", "message"),
+                ConfiguratorError::required("parameter", "message", INVALID_PARAM_VALUE_ERROR),
             ))));
         let system = System::new("test");
         subject.ui_gateway_sub = Some(ui_gateway.start().recipient());
@@ -895,7 +1587,13 @@ mod tests {
                 body: UiSetupResponse {
                     running: false,
                     values: vec![UiSetupResponseValue::new("name", "value", Configured)],
-                    errors: vec![("parameter".to_string(), "message".to_string())]
+                    errors: vec![(
+                        "parameter".to_string(),
+                        "message".to_string(),
+                        INVALID_PARAM_VALUE_ERROR
+                    )],
+                    command_line: vec!["--name".to_string(), "value".to_string()],
+                    revision: 1,
                 }
                 .tmb(74),
             }
@@ -939,7 +1637,9 @@ mod tests {
                         .map(|(_, v)| v)
                         .map(|v| v.clone())
                         .collect(),
-                    errors: vec![]
+                    errors: vec![],
+                    command_line: vec!["--additional-item".to_string(), "booga".to_string()],
+                    revision: 1,
                 }
                 .tmb(74),
             }
@@ -986,7 +1686,9 @@ mod tests {
                     ),]
                     .into_iter()
                     .collect(),
-                    errors: vec![]
+                    errors: vec![],
+                    command_line: vec!["--db-password".to_string(), "****************".to_string()],
+                    revision: 0,
                 }
                 .tmb(74),
             }
@@ -1034,7 +1736,14 @@ mod tests {
                         .map(|(_, v)| v.clone())
                         .sorted_by(|a, b| Ord::cmp(&a.name, &b.name))
                         .collect(),
-                    errors: vec![]
+                    errors: vec![],
+                    command_line: vec![
+                        "--additional-item".to_string(),
+                        "booga".to_string(),
+                        "--booga".to_string(),
+                        "agoob".to_string(),
+                    ],
+                    revision: 1,
                 }
                 .tmb(74),
             }
@@ -1051,30 +1760,170 @@ mod tests {
                         .map(|(_, v)| v)
                         .sorted_by(|a, b| Ord::cmp(&a.name, &b.name))
                         .collect(),
-                    errors: vec![]
+                    errors: vec![],
+                    revision: 1,
                 }
                 .tmb(0),
             }
         );
     }
 
+    fn make_stale_setup_request(revision: u64, values: Vec<(&str, &str)>) -> UiSetupRequest {
+        UiSetupRequest {
+            values: values
+                .into_iter()
+                .map(|(name, value)| UiSetupRequestValue::new(name, value))
+                .collect(),
+            revision_opt: Some(revision),
+        }
+    }
+
     #[test]
-    fn accepts_start_order_launches_and_replies_parent_success() {
+    fn handle_setup_refuses_stale_request_that_would_overwrite_changes_by_another_ui() {
         let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
-        let launch_params_arc = Arc::new(Mutex::new(vec![]));
-        let launcher = LauncherMock::new()
-            .launch_params(&launch_params_arc)
-            .launch_result(Ok(Some(LaunchSuccess {
-                new_process_id: 2345,
-                redirect_ui_port: 5432,
-            })));
-        let verifier_tools = VerifierToolsMock::new();
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        subject.setup_reporter = Box::new(SetupReporterMock::new()); // will panic if called
+        subject.params =
+            setup_cluster_from(vec![("ip", "1.2.3.4", Set), ("log-level", "debug", Set)]);
+        subject.setup_changes = vec![
+            HashSet::from(["ip".to_string()]),
+            HashSet::from(["log-level".to_string()]),
+        ];
         let system = System::new("test");
-        let mut subject = Daemon::new(Box::new(launcher));
-        subject.params.insert(
-            "db-password".to_string(),
-            UiSetupResponseValue::new("db-password", "goober", Set),
+        subject.ui_gateway_sub = Some(ui_gateway.start().recipient());
+
+        subject.handle_setup(
+            47,
+            74,
+            make_stale_setup_request(1, vec![("ip", "4.3.2.1"), ("log-level", "trace")]),
+        );
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let message: &NodeToUiMessage = ui_gateway_recording.get_record(0);
+        let (payload, _) = UiSetupResponse::fmb(message.body.clone()).unwrap();
+        assert_eq!(
+            payload.values,
+            vec![
+                UiSetupResponseValue::new("ip", "1.2.3.4", Set),
+                UiSetupResponseValue::new("log-level", "debug", Set),
+            ]
+        );
+        assert_eq!(
+            payload.errors,
+            vec![(
+                "log-level".to_string(),
+                "Changed by another UI since revision 1; the setup is now at revision 2"
+                    .to_string(),
+                SETUP_REVISION_CONFLICT_ERROR
+            )]
+        );
+        assert_eq!(payload.revision, 2);
+        assert_eq!(ui_gateway_recording.len(), 1);
+        assert_eq!(subject.params.get("log-level").unwrap().value, "debug");
+    }
+
+    #[test]
+    fn handle_setup_merges_stale_request_that_touches_nothing_changed_since() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let get_modified_setup_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        subject.params = setup_cluster_from(vec![("log-level", "debug", Set)]);
+        subject.setup_changes = vec![HashSet::from(["log-level".to_string()])];
+        let modified_setup =
+            setup_cluster_from(vec![("ip", "4.3.2.1", Set), ("log-level", "debug", Set)]);
+        subject.setup_reporter = Box::new(
+            SetupReporterMock::new()
+                .get_modified_setup_params(&get_modified_setup_params_arc)
+                .get_modified_setup_result(Ok(modified_setup.clone())),
+        );
+        let system = System::new("test");
+        subject.ui_gateway_sub = Some(ui_gateway.start().recipient());
+
+        subject.handle_setup(47, 74, make_stale_setup_request(0, vec![("ip", "4.3.2.1")]));
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let (response, _) = UiSetupResponse::fmb(
+            ui_gateway_recording
+                .get_record::<NodeToUiMessage>(0)
+                .body
+                .clone(),
+        )
+        .unwrap();
+        assert_eq!(response.errors, vec![]);
+        assert_eq!(response.revision, 2);
+        let (broadcast, _) = UiSetupBroadcast::fmb(
+            ui_gateway_recording
+                .get_record::<NodeToUiMessage>(1)
+                .body
+                .clone(),
+        )
+        .unwrap();
+        assert_eq!(broadcast.revision, 2);
+        assert_eq!(get_modified_setup_params_arc.lock().unwrap().len(), 1);
+        assert_eq!(subject.params, modified_setup);
+        assert_eq!(
+            subject.setup_changes,
+            vec![
+                HashSet::from(["log-level".to_string()]),
+                HashSet::from(["ip".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn handle_setup_refuses_request_based_on_a_revision_from_before_a_restart() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        subject.setup_reporter = Box::new(SetupReporterMock::new()); // will panic if called
+        let system = System::new("test");
+        subject.ui_gateway_sub = Some(ui_gateway.start().recipient());
+
+        subject.handle_setup(47, 74, make_stale_setup_request(5, vec![("ip", "4.3.2.1")]));
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let (response, _) = UiSetupResponse::fmb(
+            ui_gateway_recording
+                .get_record::<NodeToUiMessage>(0)
+                .body
+                .clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            response.errors,
+            vec![(
+                "ip".to_string(),
+                "Changed by another UI since revision 5; the setup is now at revision 0"
+                    .to_string(),
+                SETUP_REVISION_CONFLICT_ERROR
+            )]
         );
+        assert_eq!(response.revision, 0);
+    }
+
+    #[test]
+    fn accepts_start_order_launches_and_replies_parent_success() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let launch_params_arc = Arc::new(Mutex::new(vec![]));
+        let launcher = LauncherMock::new()
+            .launch_params(&launch_params_arc)
+            .launch_result(Ok(Some(LaunchSuccess {
+                new_process_id: 2345,
+                redirect_ui_port: 5432,
+            })));
+        let verifier_tools = VerifierToolsMock::new();
+        let system = System::new("test");
+        let mut subject = Daemon::new(Box::new(launcher));
+        subject.params = setup_cluster_from(vec![
+            ("db-password", "goober", Set),
+            ("neighborhood-mode", "originate-only", Suggested),
+            ("log-level", "warn", UiSetupResponseValueStatus::Default),
+        ]);
         subject.verifier_tools = Box::new(verifier_tools);
         let subject_addr = subject.start();
         subject_addr
@@ -1097,9 +1946,12 @@ mod tests {
                 .map(|x| &x.0)
                 .collect::<Vec<&HashMap<String, String>>>(),
             vec![&HashMap::from_iter(
-                vec![("db-password", "goober")]
-                    .into_iter()
-                    .map(|(n, v)| (n.to_string(), v.to_string()))
+                vec![
+                    ("db-password", "goober"),
+                    ("neighborhood-mode", "originate-only")
+                ]
+                .into_iter()
+                .map(|(n, v)| (n.to_string(), v.to_string()))
             )]
         );
         let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
@@ -1107,30 +1959,1015 @@ mod tests {
             .get_record::<NodeToUiMessage>(0)
             .clone();
         assert_eq!(record.target, ClientId(1234));
-        let (payload, context_id): (UiStartResponse, u64) =
-            UiStartResponse::fmb(record.body).unwrap();
+        let (payload, context_id): (UiStartResponse, u64) =
+            UiStartResponse::fmb(record.body).unwrap();
+        assert_eq!(context_id, 4321);
+        assert_eq!(
+            payload,
+            UiStartResponse {
+                new_process_id: 2345,
+                redirect_ui_port: 5432
+            }
+        );
+    }
+
+    #[test]
+    fn accepts_start_order_launches_and_replies_child_success() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let launcher = LauncherMock::new().launch_result(Ok(None));
+        let verifier_tools = VerifierToolsMock::new();
+        let system = System::new("test");
+        let mut subject = Daemon::new(Box::new(launcher));
+        subject.params.insert(
+            "db-password".to_string(),
+            UiSetupResponseValue::new("db-password", "goober", Set),
+        );
+        subject.verifier_tools = Box::new(verifier_tools);
+        let subject_addr = subject.start();
+        subject_addr
+            .try_send(make_daemon_bind_message(ui_gateway))
+            .unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiStartOrder {}.tmb(4321),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(ui_gateway_recording.len(), 0);
+    }
+
+    #[test]
+    fn maintains_setup_through_start_order() {
+        let _environment_guard = EnvironmentGuard::new();
+        let _clap_guard = ClapGuard::new();
+        let data_dir =
+            ensure_node_home_directory_exists("daemon", "maintains_setup_through_start_order");
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let launcher = LauncherMock::new().launch_result(Ok(Some(LaunchSuccess {
+            new_process_id: 2345,
+            redirect_ui_port: 5432,
+        })));
+        let verifier_tools = VerifierToolsMock::new()
+            .process_is_running_result(false)
+            .process_is_running_result(false)
+            .process_is_running_result(false);
+        let system = System::new("test");
+        let mut subject = Daemon::new(Box::new(launcher));
+        subject.params.insert(
+            "ip".to_string(),
+            UiSetupResponseValue::new("ip", "1.2.3.4", Set),
+        );
+        subject.params.insert(
+            "db-password".to_string(),
+            UiSetupResponseValue::new("db-password", "goober", Set),
+        );
+        subject.params.insert(
+            "neighborhood-mode".to_string(),
+            UiSetupResponseValue::new("neighborhood-mode", "zero-hop", Set),
+        );
+        subject.params.insert(
+            "data-directory".to_string(),
+            UiSetupResponseValue::new(
+                "data-directory",
+                data_dir.to_string_lossy().to_string().as_str(),
+                Set,
+            ),
+        );
+        subject.verifier_tools = Box::new(verifier_tools);
+        let subject_addr = subject.start();
+        subject_addr
+            .try_send(make_daemon_bind_message(ui_gateway))
+            .unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiSetupRequest::new(vec![]).tmb(4321),
+            })
+            .unwrap();
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiStartOrder {}.tmb(4321),
+            })
+            .unwrap();
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiSetupRequest::new(vec![]).tmb(4321),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        // ------
+        let record = ui_gateway_recording
+            .get_record::<NodeToUiMessage>(0)
+            .clone();
+        assert_eq!(record.target, ClientId(1234));
+        let (setup_response_before, _) = UiSetupResponse::fmb(record.body).unwrap();
+        let record = ui_gateway_recording
+            .get_record::<NodeToUiMessage>(1)
+            .clone();
+        assert_eq!(record.target, AllExcept(1234));
+        let (setup_broadcast_before, _) = UiSetupBroadcast::fmb(record.body).unwrap();
+        // skip start record (2)
+        let record = ui_gateway_recording
+            .get_record::<NodeToUiMessage>(3)
+            .clone();
+        let (setup_after, _) = UiSetupResponse::fmb(record.body).unwrap();
+        // ------
+        assert_eq!(setup_after.values, setup_response_before.values);
+        assert_eq!(setup_after.values, setup_broadcast_before.values);
+    }
+
+    #[test]
+    fn accepts_start_order_launches_and_replies_failure() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let launcher = LauncherMock::new().launch_result(Err("booga".to_string()));
+        let verifier_tools = VerifierToolsMock::new();
+        let system = System::new("test");
+        let mut subject = Daemon::new(Box::new(launcher));
+        subject.params.insert(
+            "db-password".to_string(),
+            UiSetupResponseValue::new("db-password", "goober", Set),
+        );
+        subject.verifier_tools = Box::new(verifier_tools);
+        let subject_addr = subject.start();
+        subject_addr
+            .try_send(make_daemon_bind_message(ui_gateway))
+            .unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiStartOrder {}.tmb(4321),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let record = ui_gateway_recording
+            .get_record::<NodeToUiMessage>(0)
+            .clone();
+        assert_eq!(record.target, ClientId(1234));
+        let (code, message) = record.body.payload.err().unwrap();
+        assert_eq!(code, NODE_LAUNCH_ERROR);
+        assert_eq!(message, "Could not launch Node: booga".to_string());
+    }
+
+    #[test]
+    fn rejects_start_order_when_node_is_already_running() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let launcher = LauncherMock::new().launch_result(Err("booga".to_string()));
+        let verifier_tools = VerifierToolsMock::new().process_is_running_result(true);
+        let system = System::new("test");
+        let mut subject = Daemon::new(Box::new(launcher));
+        subject.params.insert(
+            "db-password".to_string(),
+            UiSetupResponseValue::new("db-password", "goober", Set),
+        );
+        subject.node_ui_port = Some(1234);
+        subject.node_process_id = Some(3421);
+        subject.verifier_tools = Box::new(verifier_tools);
+        let subject_addr = subject.start();
+        subject_addr
+            .try_send(make_daemon_bind_message(ui_gateway))
+            .unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiStartOrder {}.tmb(4321),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let record = ui_gateway_recording
+            .get_record::<NodeToUiMessage>(0)
+            .clone();
+        assert_eq!(record.target, ClientId(1234));
+        assert_eq!(&record.body.opcode, "start");
+        let (code, message) = record.body.payload.err().unwrap();
+        assert_eq!(code, NODE_ALREADY_RUNNING_ERROR);
+        assert_eq!(
+            message,
+            "Could not launch Node: already running".to_string()
+        );
+    }
+
+    #[test]
+    fn rejects_start_order_when_orphaned_node_is_running_against_data_directory() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let launch_params_arc = Arc::new(Mutex::new(vec![]));
+        let launcher = LauncherMock::new().launch_params(&launch_params_arc);
+        let process_is_running_params_arc = Arc::new(Mutex::new(vec![]));
+        let can_connect_to_ui_gateway_params_arc = Arc::new(Mutex::new(vec![]));
+        let verifier_tools = VerifierToolsMock::new()
+            .process_is_running_params(&process_is_running_params_arc)
+            .process_is_running_result(true)
+            .can_connect_to_ui_gateway_params(&can_connect_to_ui_gateway_params_arc)
+            .can_connect_to_ui_gateway_result(true);
+        let system = System::new("test");
+        let mut subject = Daemon::new(Box::new(launcher));
+        subject.params = make_orphan_setup(
+            "rejects_start_order_when_orphaned_node_is_running_against_data_directory",
+            3456,
+            5432,
+        );
+        let data_directory = subject.params.get("data-directory").unwrap().value.clone();
+        subject.verifier_tools = Box::new(verifier_tools);
+        let subject_addr = subject.start();
+        subject_addr
+            .try_send(make_daemon_bind_message(ui_gateway))
+            .unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiStartOrder {}.tmb(4321),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let record = ui_gateway_recording
+            .get_record::<NodeToUiMessage>(0)
+            .clone();
+        assert_eq!(record.target, ClientId(1234));
+        assert_eq!(&record.body.opcode, "start");
+        let (code, message) = record.body.payload.err().unwrap();
+        assert_eq!(code, NODE_ALREADY_RUNNING_ERROR);
+        assert_eq!(
+            message,
+            format!(
+                "Could not launch Node: orphaned Node (process 3456, UI port 5432) is already \
+                 running against data directory {}; adopt or terminate it first",
+                data_directory
+            )
+        );
+        assert!(launch_params_arc.lock().unwrap().is_empty());
+        assert_eq!(*process_is_running_params_arc.lock().unwrap(), vec![3456]);
+        assert_eq!(
+            *can_connect_to_ui_gateway_params_arc.lock().unwrap(),
+            vec![5432]
+        );
+    }
+
+    #[test]
+    fn accepts_start_order_when_pid_file_in_data_directory_is_stale() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let launcher = LauncherMock::new().launch_result(Ok(Some(LaunchSuccess {
+            new_process_id: 2345,
+            redirect_ui_port: 5333,
+        })));
+        let verifier_tools = VerifierToolsMock::new().process_is_running_result(false);
+        let system = System::new("test");
+        let mut subject = Daemon::new(Box::new(launcher));
+        subject.params = make_orphan_setup(
+            "accepts_start_order_when_pid_file_in_data_directory_is_stale",
+            3456,
+            5432,
+        );
+        subject.verifier_tools = Box::new(verifier_tools);
+        let subject_addr = subject.start();
+        subject_addr
+            .try_send(make_daemon_bind_message(ui_gateway))
+            .unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiStartOrder {}.tmb(4321),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let record = ui_gateway_recording
+            .get_record::<NodeToUiMessage>(0)
+            .clone();
+        let (payload, context_id): (UiStartResponse, u64) =
+            UiStartResponse::fmb(record.body).unwrap();
+        assert_eq!(context_id, 4321);
+        assert_eq!(
+            payload,
+            UiStartResponse {
+                new_process_id: 2345,
+                redirect_ui_port: 5333,
+            }
+        );
+    }
+
+    #[test]
+    fn orphaned_node_request_reports_orphan_without_disturbing_it() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let kill_process_params_arc = Arc::new(Mutex::new(vec![]));
+        let verifier_tools = VerifierToolsMock::new()
+            .process_is_running_result(true)
+            .can_connect_to_ui_gateway_result(true)
+            .kill_process_params(&kill_process_params_arc);
+        let system = System::new("test");
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        subject.params = make_orphan_setup(
+            "orphaned_node_request_reports_orphan_without_disturbing_it",
+            3456,
+            5432,
+        );
+        let data_directory = subject.params.get("data-directory").unwrap().value.clone();
+        subject.verifier_tools = Box::new(verifier_tools);
+        let subject_addr = subject.start();
+        subject_addr
+            .try_send(make_daemon_bind_message(ui_gateway))
+            .unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiOrphanedNodeRequest {
+                    action: OrphanedNodeAction::Report,
+                }
+                .tmb(4321),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let record = ui_gateway_recording
+            .get_record::<NodeToUiMessage>(0)
+            .clone();
+        assert_eq!(record.target, ClientId(1234));
+        let (payload, context_id): (UiOrphanedNodeResponse, u64) =
+            UiOrphanedNodeResponse::fmb(record.body).unwrap();
+        assert_eq!(context_id, 4321);
+        assert_eq!(
+            payload,
+            UiOrphanedNodeResponse {
+                action: OrphanedNodeAction::Report,
+                orphan_opt: Some(UiOrphanedNode {
+                    process_id: 3456,
+                    ui_port: 5432,
+                    data_directory,
+                }),
+            }
+        );
+        assert!(kill_process_params_arc.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn orphaned_node_request_reports_no_orphan_when_ui_port_does_not_answer() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let verifier_tools = VerifierToolsMock::new()
+            .process_is_running_result(true)
+            .can_connect_to_ui_gateway_result(false);
+        let system = System::new("test");
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        subject.params = make_orphan_setup(
+            "orphaned_node_request_reports_no_orphan_when_ui_port_does_not_answer",
+            3456,
+            5432,
+        );
+        subject.verifier_tools = Box::new(verifier_tools);
+        let subject_addr = subject.start();
+        subject_addr
+            .try_send(make_daemon_bind_message(ui_gateway))
+            .unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiOrphanedNodeRequest {
+                    action: OrphanedNodeAction::Report,
+                }
+                .tmb(4321),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let record = ui_gateway_recording
+            .get_record::<NodeToUiMessage>(0)
+            .clone();
+        let (payload, _): (UiOrphanedNodeResponse, u64) =
+            UiOrphanedNodeResponse::fmb(record.body).unwrap();
+        assert_eq!(
+            payload,
+            UiOrphanedNodeResponse {
+                action: OrphanedNodeAction::Report,
+                orphan_opt: None,
+            }
+        );
+    }
+
+    #[test]
+    fn adopted_orphaned_node_receives_redirected_messages() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let process_is_running_params_arc = Arc::new(Mutex::new(vec![]));
+        let verifier_tools = VerifierToolsMock::new()
+            .process_is_running_params(&process_is_running_params_arc)
+            .process_is_running_result(true)
+            .can_connect_to_ui_gateway_result(true)
+            .process_is_running_result(true);
+        let system = System::new("test");
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        subject.params = make_orphan_setup(
+            "adopted_orphaned_node_receives_redirected_messages",
+            3456,
+            5432,
+        );
+        subject.verifier_tools = Box::new(verifier_tools);
+        let subject_addr = subject.start();
+        subject_addr
+            .try_send(make_daemon_bind_message(ui_gateway))
+            .unwrap();
+        let shutdown_body: MessageBody = UiShutdownRequest {}.tmb(4322);
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiOrphanedNodeRequest {
+                    action: OrphanedNodeAction::Adopt,
+                }
+                .tmb(4321),
+            })
+            .unwrap();
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: shutdown_body.clone(),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let adopt_record = ui_gateway_recording
+            .get_record::<NodeToUiMessage>(0)
+            .clone();
+        let (adopt_payload, _): (UiOrphanedNodeResponse, u64) =
+            UiOrphanedNodeResponse::fmb(adopt_record.body).unwrap();
+        assert_eq!(adopt_payload.action, OrphanedNodeAction::Adopt);
+        assert_eq!(adopt_payload.orphan_opt.unwrap().process_id, 3456);
+        let redirect_record = ui_gateway_recording
+            .get_record::<NodeToUiMessage>(1)
+            .clone();
+        let (redirect_payload, _): (UiRedirect, u64) =
+            UiRedirect::fmb(redirect_record.body).unwrap();
+        assert_eq!(
+            redirect_payload,
+            UiRedirect {
+                port: 5432,
+                opcode: shutdown_body.opcode,
+                context_id: Some(4322),
+                payload: shutdown_body.payload.unwrap(),
+            }
+        );
+        assert_eq!(
+            *process_is_running_params_arc.lock().unwrap(),
+            vec![3456, 3456]
+        );
+    }
+
+    #[test]
+    fn orphaned_node_can_be_terminated() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let kill_process_params_arc = Arc::new(Mutex::new(vec![]));
+        let verifier_tools = VerifierToolsMock::new()
+            .process_is_running_result(true)
+            .can_connect_to_ui_gateway_result(true)
+            .kill_process_params(&kill_process_params_arc);
+        let system = System::new("test");
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        subject.params = make_orphan_setup("orphaned_node_can_be_terminated", 3456, 5432);
+        subject.verifier_tools = Box::new(verifier_tools);
+        let subject_addr = subject.start();
+        subject_addr
+            .try_send(make_daemon_bind_message(ui_gateway))
+            .unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiOrphanedNodeRequest {
+                    action: OrphanedNodeAction::Terminate,
+                }
+                .tmb(4321),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let record = ui_gateway_recording
+            .get_record::<NodeToUiMessage>(0)
+            .clone();
+        let (payload, _): (UiOrphanedNodeResponse, u64) =
+            UiOrphanedNodeResponse::fmb(record.body).unwrap();
+        assert_eq!(payload.action, OrphanedNodeAction::Terminate);
+        assert_eq!(payload.orphan_opt.unwrap().ui_port, 5432);
+        assert_eq!(*kill_process_params_arc.lock().unwrap(), vec![3456]);
+    }
+
+    #[test]
+    fn orphaned_node_cannot_be_adopted_when_there_is_none() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let system = System::new("test");
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        subject.verifier_tools = Box::new(VerifierToolsMock::new());
+        let subject_addr = subject.start();
+        subject_addr
+            .try_send(make_daemon_bind_message(ui_gateway))
+            .unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiOrphanedNodeRequest {
+                    action: OrphanedNodeAction::Adopt,
+                }
+                .tmb(4321),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let record = ui_gateway_recording
+            .get_record::<NodeToUiMessage>(0)
+            .clone();
+        assert_eq!(record.target, ClientId(1234));
+        assert_eq!(&record.body.opcode, "orphanedNode");
+        assert_eq!(record.body.path, Conversation(4321));
+        assert_eq!(
+            record.body.payload,
+            Err((
+                NODE_NOT_RUNNING_ERROR,
+                "No orphaned Node is running against the data directory".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn rejects_start_order_when_chain_and_data_directory_disagree() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let launch_params_arc = Arc::new(Mutex::new(vec![]));
+        let launcher = LauncherMock::new().launch_params(&launch_params_arc);
+        let system = System::new("test");
+        let mut subject = Daemon::new(Box::new(launcher));
+        subject.verifier_tools = Box::new(VerifierToolsMock::new());
+        subject.chain_consistency_checker =
+            Box::new(ChainConsistencyCheckerMock::new().check_result(Some(make_chain_conflict())));
+        let subject_addr = subject.start();
+        subject_addr
+            .try_send(make_daemon_bind_message(ui_gateway))
+            .unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiStartOrder {}.tmb(4321),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let record = ui_gateway_recording
+            .get_record::<NodeToUiMessage>(0)
+            .clone();
+        assert_eq!(record.target, ClientId(1234));
+        let (code, message) = record.body.payload.err().unwrap();
+        assert_eq!(code, NODE_LAUNCH_ERROR);
+        assert_eq!(
+            message,
+            format!("Could not launch Node: {}", make_chain_conflict())
+        );
+        assert!(launch_params_arc.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn handle_setup_reports_chain_conflict_as_warning_on_data_directory() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        let modified_setup = setup_cluster_from(vec![
+            ("chain", "polygon-amoy", Set),
+            (
+                "data-directory",
+                "/home/booga/MASQ/polygon-mainnet/polygon-amoy",
+                Set,
+            ),
+        ]);
+        subject.setup_reporter = Box::new(
+            SetupReporterMock::new().get_modified_setup_result(Ok(modified_setup.clone())),
+        );
+        subject.chain_consistency_checker =
+            Box::new(ChainConsistencyCheckerMock::new().check_result(Some(make_chain_conflict())));
+        let system = System::new("test");
+        subject.ui_gateway_sub = Some(ui_gateway.start().recipient());
+
+        subject.handle_setup(47, 74, UiSetupRequest::new(vec![]));
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let message: &NodeToUiMessage = ui_gateway_recording.get_record(0);
+        let (payload, _) = UiSetupResponse::fmb(message.body.clone()).unwrap();
+        assert_eq!(
+            payload.values,
+            vec![
+                UiSetupResponseValue::new("chain", "polygon-amoy", Set),
+                UiSetupResponseValue::new(
+                    "data-directory",
+                    "/home/booga/MASQ/polygon-mainnet/polygon-amoy",
+                    UiSetupResponseValueStatus::Warning
+                ),
+            ]
+        );
+        assert_eq!(
+            payload.errors,
+            vec![(
+                "data-directory".to_string(),
+                make_chain_conflict().to_string(),
+                DATA_DIRECTORY_CONFLICT_ERROR
+            )]
+        );
+        assert_eq!(subject.params, modified_setup);
+    }
+
+    #[test]
+    fn handle_setup_reports_dns_hijack_as_warning_on_dns_servers() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let check_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        let modified_setup = setup_cluster_from(vec![
+            ("dns-servers", "192.168.0.1", Set),
+            ("neighborhood-mode", "standard", Set),
+        ]);
+        subject.setup_reporter = Box::new(
+            SetupReporterMock::new().get_modified_setup_result(Ok(modified_setup.clone())),
+        );
+        subject.chain_consistency_checker =
+            Box::new(ChainConsistencyCheckerMock::new().check_result(None));
+        let hijack = DnsHijack::Router {
+            server: IpAddr::from_str("192.168.0.1").unwrap(),
+        };
+        subject.dns_hijack_detector_opt = Some(Box::new(
+            DnsHijackDetectorMock::new()
+                .check_params(&check_params_arc)
+                .check_result(Some(hijack.clone())),
+        ));
+        let system = System::new("test");
+        subject.ui_gateway_sub = Some(ui_gateway.start().recipient());
+
+        subject.handle_setup(47, 74, UiSetupRequest::new(vec![]));
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let message: &NodeToUiMessage = ui_gateway_recording.get_record(0);
+        let (payload, _) = UiSetupResponse::fmb(message.body.clone()).unwrap();
+        assert_eq!(
+            payload.values,
+            vec![
+                UiSetupResponseValue::new(
+                    "dns-servers",
+                    "192.168.0.1",
+                    UiSetupResponseValueStatus::Warning
+                ),
+                UiSetupResponseValue::new("neighborhood-mode", "standard", Set),
+            ]
+        );
+        assert_eq!(
+            payload.errors,
+            vec![(
+                "dns-servers".to_string(),
+                hijack.to_string(),
+                DNS_SERVERS_HIJACKED_ERROR
+            )]
+        );
+        assert_eq!(
+            *check_params_arc.lock().unwrap(),
+            vec![modified_setup.clone()]
+        );
+        assert_eq!(subject.params, modified_setup);
+    }
+
+    fn make_locked_setup(test_name: &str, process_id: u32) -> SetupCluster {
+        let data_dir = ensure_node_home_directory_exists("daemon", test_name);
+        std::fs::write(
+            data_dir.join(DATA_DIRECTORY_LOCK_FILE_NAME),
+            format!("{}\n", process_id),
+        )
+        .unwrap();
+        setup_cluster_from(vec![("data-directory", &data_dir.to_string_lossy(), Set)])
+    }
+
+    #[test]
+    fn handle_setup_reports_data_directory_locked_by_another_node_as_warning() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let process_is_running_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        let modified_setup = make_locked_setup(
+            "handle_setup_reports_data_directory_locked_by_another_node_as_warning",
+            4567,
+        );
+        let data_directory = modified_setup.get("data-directory").unwrap().value.clone();
+        subject.setup_reporter = Box::new(
+            SetupReporterMock::new().get_modified_setup_result(Ok(modified_setup.clone())),
+        );
+        subject.chain_consistency_checker =
+            Box::new(ChainConsistencyCheckerMock::new().check_result(None));
+        subject.verifier_tools = Box::new(
+            VerifierToolsMock::new()
+                .process_is_running_params(&process_is_running_params_arc)
+                .process_is_running_result(true),
+        );
+        let system = System::new("test");
+        subject.ui_gateway_sub = Some(ui_gateway.start().recipient());
+
+        subject.handle_setup(47, 74, UiSetupRequest::new(vec![]));
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let message: &NodeToUiMessage = ui_gateway_recording.get_record(0);
+        let (payload, _) = UiSetupResponse::fmb(message.body.clone()).unwrap();
+        assert_eq!(
+            payload.values,
+            vec![UiSetupResponseValue::new(
+                "data-directory",
+                &data_directory,
+                UiSetupResponseValueStatus::Warning
+            )]
+        );
+        assert_eq!(
+            payload.errors,
+            vec![(
+                "data-directory".to_string(),
+                "Another Node (process 4567) is already running against this data directory"
+                    .to_string(),
+                DATA_DIRECTORY_LOCKED_ERROR
+            )]
+        );
+        assert_eq!(*process_is_running_params_arc.lock().unwrap(), vec![4567]);
+    }
+
+    #[test]
+    fn data_directory_locked_by_daemons_own_node_is_not_reported() {
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        subject.params = make_locked_setup(
+            "data_directory_locked_by_daemons_own_node_is_not_reported",
+            4567,
+        );
+        subject.node_process_id = Some(4567);
+        subject.chain_consistency_checker =
+            Box::new(ChainConsistencyCheckerMock::new().check_result(None));
+        subject.verifier_tools = Box::new(VerifierToolsMock::new());
+
+        let (_, errors) = subject.reported_setup(ConfiguratorError::new(vec![]));
+
+        assert_eq!(errors, ConfiguratorError::new(vec![]));
+    }
+
+    #[test]
+    fn rejects_start_order_when_data_directory_is_locked_by_another_node() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let launch_params_arc = Arc::new(Mutex::new(vec![]));
+        let launcher = LauncherMock::new().launch_params(&launch_params_arc);
+        let system = System::new("test");
+        let mut subject = Daemon::new(Box::new(launcher));
+        subject.params = make_locked_setup(
+            "rejects_start_order_when_data_directory_is_locked_by_another_node",
+            4567,
+        );
+        subject.verifier_tools = Box::new(VerifierToolsMock::new().process_is_running_result(true));
+        subject.chain_consistency_checker =
+            Box::new(ChainConsistencyCheckerMock::new().check_result(None));
+        let subject_addr = subject.start();
+        subject_addr
+            .try_send(make_daemon_bind_message(ui_gateway))
+            .unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiStartOrder {}.tmb(4321),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let record = ui_gateway_recording
+            .get_record::<NodeToUiMessage>(0)
+            .clone();
+        let (code, message) = record.body.payload.err().unwrap();
+        assert_eq!(code, NODE_LAUNCH_ERROR);
+        assert_eq!(
+            message,
+            "Could not launch Node: Another Node (process 4567) is already running against this data directory"
+        );
+        assert!(launch_params_arc.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn chain_migration_moves_setup_to_new_data_directory_and_broadcasts_it() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let prepare_data_directory_params_arc = Arc::new(Mutex::new(vec![]));
+        let get_modified_setup_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        subject.params = setup_cluster_from(vec![
+            ("chain", "polygon-amoy", Set),
+            (
+                "data-directory",
+                "/home/booga/MASQ/polygon-mainnet/polygon-amoy",
+                Set,
+            ),
+        ]);
+        let migrated_setup = setup_cluster_from(vec![
+            ("chain", "polygon-amoy", Set),
+            ("data-directory", "/home/booga/MASQ/polygon-amoy", Set),
+        ]);
+        subject.setup_reporter = Box::new(
+            SetupReporterMock::new()
+                .get_modified_setup_params(&get_modified_setup_params_arc)
+                .get_modified_setup_result(Ok(migrated_setup.clone())),
+        );
+        subject.chain_consistency_checker = Box::new(
+            ChainConsistencyCheckerMock::new()
+                .check_result(Some(make_chain_conflict()))
+                .check_result(None)
+                .prepare_data_directory_params(&prepare_data_directory_params_arc)
+                .prepare_data_directory_result(Ok(true)),
+        );
+        let system = System::new("test");
+        subject.ui_gateway_sub = Some(ui_gateway.start().recipient());
+
+        subject.handle_chain_migration(
+            47,
+            74,
+            UiChainMigrationRequest {
+                data_directory_opt: None,
+                copy_config: true,
+            },
+        );
+
+        System::current().stop();
+        system.run();
+        assert_eq!(
+            *prepare_data_directory_params_arc.lock().unwrap(),
+            vec![(
+                PathBuf::from("/home/booga/MASQ/polygon-mainnet/polygon-amoy"),
+                PathBuf::from("/home/booga/MASQ/polygon-amoy"),
+                true
+            )]
+        );
+        let get_modified_setup_params = get_modified_setup_params_arc.lock().unwrap();
+        assert_eq!(
+            get_modified_setup_params[0].1,
+            vec![UiSetupRequestValue::new(
+                "data-directory",
+                "/home/booga/MASQ/polygon-amoy"
+            )]
+        );
+        assert_eq!(subject.params, migrated_setup);
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            *ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            NodeToUiMessage {
+                target: ClientId(47),
+                body: UiChainMigrationResponse {
+                    data_directory: "/home/booga/MASQ/polygon-amoy".to_string(),
+                    config_copied: true,
+                }
+                .tmb(74),
+            }
+        );
+        assert_eq!(
+            *ui_gateway_recording.get_record::<NodeToUiMessage>(1),
+            NodeToUiMessage {
+                target: MessageTarget::AllClients,
+                body: UiSetupBroadcast::new(
+                    false,
+                    migrated_setup,
+                    ConfiguratorError::new(vec![]),
+                    1
+                )
+                .tmb(0),
+            }
+        );
+        assert_eq!(ui_gateway_recording.len(), 2);
+    }
+
+    #[test]
+    fn chain_migration_is_refused_when_there_is_no_conflict() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        subject.chain_consistency_checker =
+            Box::new(ChainConsistencyCheckerMock::new().check_result(None));
+        let system = System::new("test");
+        subject.ui_gateway_sub = Some(ui_gateway.start().recipient());
+
+        subject.handle_chain_migration(
+            47,
+            74,
+            UiChainMigrationRequest {
+                data_directory_opt: Some("/home/booga/elsewhere".to_string()),
+                copy_config: false,
+            },
+        );
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording
+                .get_record::<NodeToUiMessage>(0)
+                .body
+                .payload,
+            Err((
+                SETUP_ERROR,
+                "Chain and data directory agree; there is nothing to migrate".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn answers_shutdown_report_request_from_data_directory_after_node_is_gone() {
+        let data_dir = ensure_node_home_directory_exists(
+            "daemon",
+            "answers_shutdown_report_request_from_data_directory_after_node_is_gone",
+        );
+        let report = UiShutdownReport {
+            shutdown_timestamp: 1_700_000_000,
+            total_payable_gwei: 1_234_567,
+            total_receivable_gwei: 4_567,
+            unconfirmed_payment_count: 1,
+            ban_risks: vec![UiBanRisk {
+                wallet: "0x0000000000000000000000000000000000001234".to_string(),
+                balance_gwei: 1_000_000,
+                ban_timestamp: 1_700_086_400,
+            }],
+        };
+        write_shutdown_report(&data_dir, &report).unwrap();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let system = System::new("test");
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        subject.params =
+            make_setup_cluster(vec![("data-directory", &data_dir.to_string_lossy(), Set)]);
+        let subject_addr = subject.start();
+        subject_addr
+            .try_send(make_daemon_bind_message(ui_gateway))
+            .unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiShutdownReportRequest {}.tmb(4321),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let record = ui_gateway_recording
+            .get_record::<NodeToUiMessage>(0)
+            .clone();
+        assert_eq!(record.target, ClientId(1234));
+        let (response, context_id) = UiShutdownReportResponse::fmb(record.body).unwrap();
         assert_eq!(context_id, 4321);
         assert_eq!(
-            payload,
-            UiStartResponse {
-                new_process_id: 2345,
-                redirect_ui_port: 5432
+            response,
+            UiShutdownReportResponse {
+                report_opt: Some(report)
             }
         );
     }
 
     #[test]
-    fn accepts_start_order_launches_and_replies_child_success() {
+    fn answers_shutdown_report_request_without_report_when_node_never_left_one() {
+        let data_dir = ensure_node_home_directory_exists(
+            "daemon",
+            "answers_shutdown_report_request_without_report_when_node_never_left_one",
+        );
         let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
-        let launcher = LauncherMock::new().launch_result(Ok(None));
-        let verifier_tools = VerifierToolsMock::new();
         let system = System::new("test");
-        let mut subject = Daemon::new(Box::new(launcher));
-        subject.params.insert(
-            "db-password".to_string(),
-            UiSetupResponseValue::new("db-password", "goober", Set),
-        );
-        subject.verifier_tools = Box::new(verifier_tools);
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        subject.params =
+            make_setup_cluster(vec![("data-directory", &data_dir.to_string_lossy(), Set)]);
         let subject_addr = subject.start();
         subject_addr
             .try_send(make_daemon_bind_message(ui_gateway))
@@ -1139,54 +2976,39 @@ mod tests {
         subject_addr
             .try_send(NodeFromUiMessage {
                 client_id: 1234,
-                body: UiStartOrder {}.tmb(4321),
+                body: UiShutdownReportRequest {}.tmb(4321),
             })
             .unwrap();
 
         System::current().stop();
         system.run();
         let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
-        assert_eq!(ui_gateway_recording.len(), 0);
+        let record = ui_gateway_recording
+            .get_record::<NodeToUiMessage>(0)
+            .clone();
+        let (response, _) = UiShutdownReportResponse::fmb(record.body).unwrap();
+        assert_eq!(response, UiShutdownReportResponse { report_opt: None });
     }
 
     #[test]
-    fn maintains_setup_through_start_order() {
-        let _environment_guard = EnvironmentGuard::new();
-        let _clap_guard = ClapGuard::new();
-        let data_dir =
-            ensure_node_home_directory_exists("daemon", "maintains_setup_through_start_order");
+    fn save_profile_writes_chosen_setup_values_under_data_directory() {
+        let data_dir = ensure_node_home_directory_exists(
+            "daemon",
+            "save_profile_writes_chosen_setup_values_under_data_directory",
+        );
         let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
-        let launcher = LauncherMock::new().launch_result(Ok(Some(LaunchSuccess {
-            new_process_id: 2345,
-            redirect_ui_port: 5432,
-        })));
-        let verifier_tools = VerifierToolsMock::new()
-            .process_is_running_result(false)
-            .process_is_running_result(false)
-            .process_is_running_result(false);
         let system = System::new("test");
-        let mut subject = Daemon::new(Box::new(launcher));
-        subject.params.insert(
-            "ip".to_string(),
-            UiSetupResponseValue::new("ip", "1.2.3.4", Set),
-        );
-        subject.params.insert(
-            "db-password".to_string(),
-            UiSetupResponseValue::new("db-password", "goober", Set),
-        );
-        subject.params.insert(
-            "neighborhood-mode".to_string(),
-            UiSetupResponseValue::new("neighborhood-mode", "zero-hop", Set),
-        );
-        subject.params.insert(
-            "data-directory".to_string(),
-            UiSetupResponseValue::new(
-                "data-directory",
-                data_dir.to_string_lossy().to_string().as_str(),
-                Set,
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        subject.params = make_setup_cluster(vec![
+            ("data-directory", &data_dir.to_string_lossy(), Set),
+            ("db-password", "secret", Set),
+            ("log-level", "debug", Set),
+            (
+                "neighborhood-mode",
+                "standard",
+                UiSetupResponseValueStatus::Default,
             ),
-        );
-        subject.verifier_tools = Box::new(verifier_tools);
+        ]);
         let subject_addr = subject.start();
         subject_addr
             .try_send(make_daemon_bind_message(ui_gateway))
@@ -1195,58 +3017,186 @@ mod tests {
         subject_addr
             .try_send(NodeFromUiMessage {
                 client_id: 1234,
-                body: UiSetupRequest { values: vec![] }.tmb(4321),
-            })
-            .unwrap();
-        subject_addr
-            .try_send(NodeFromUiMessage {
-                client_id: 1234,
-                body: UiStartOrder {}.tmb(4321),
-            })
-            .unwrap();
-        subject_addr
-            .try_send(NodeFromUiMessage {
-                client_id: 1234,
-                body: UiSetupRequest { values: vec![] }.tmb(4321),
+                body: UiSaveProfileRequest {
+                    name: "home-wifi".to_string(),
+                }
+                .tmb(4321),
             })
             .unwrap();
 
         System::current().stop();
         system.run();
         let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
-        // ------
         let record = ui_gateway_recording
             .get_record::<NodeToUiMessage>(0)
             .clone();
         assert_eq!(record.target, ClientId(1234));
-        let (setup_response_before, _) = UiSetupResponse::fmb(record.body).unwrap();
-        let record = ui_gateway_recording
-            .get_record::<NodeToUiMessage>(1)
-            .clone();
-        assert_eq!(record.target, AllExcept(1234));
-        let (setup_broadcast_before, _) = UiSetupBroadcast::fmb(record.body).unwrap();
-        // skip start record (2)
-        let record = ui_gateway_recording
-            .get_record::<NodeToUiMessage>(3)
-            .clone();
-        let (setup_after, _) = UiSetupResponse::fmb(record.body).unwrap();
-        // ------
-        assert_eq!(setup_after.values, setup_response_before.values);
-        assert_eq!(setup_after.values, setup_broadcast_before.values);
+        let (response, context_id) = UiSaveProfileResponse::fmb(record.body).unwrap();
+        assert_eq!(context_id, 4321);
+        assert_eq!(
+            response,
+            UiSaveProfileResponse {
+                name: "home-wifi".to_string(),
+                saved_parameters: vec!["log-level".to_string()],
+            }
+        );
+        assert_eq!(
+            SetupProfile::load(&data_dir, "home-wifi").unwrap().names(),
+            vec!["log-level".to_string()]
+        );
     }
 
     #[test]
-    fn accepts_start_order_launches_and_replies_failure() {
+    fn load_profile_layers_profile_into_setup_and_broadcasts_it() {
+        let data_dir = ensure_node_home_directory_exists(
+            "daemon",
+            "load_profile_layers_profile_into_setup_and_broadcasts_it",
+        );
+        let profile = SetupProfile::from_setup(&setup_cluster_from(vec![
+            ("log-level", "debug", Set),
+            ("neighborhood-mode", "consume-only", Set),
+        ]));
+        profile.save(&data_dir, "datacenter").unwrap();
         let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
-        let launcher = LauncherMock::new().launch_result(Err("booga".to_string()));
-        let verifier_tools = VerifierToolsMock::new();
+        let get_profiled_setup_params_arc = Arc::new(Mutex::new(vec![]));
+        let existing_setup = setup_cluster_from(vec![
+            ("data-directory", &data_dir.to_string_lossy(), Set),
+            ("log-level", "trace", Set),
+        ]);
+        let profiled_setup = setup_cluster_from(vec![
+            ("data-directory", &data_dir.to_string_lossy(), Set),
+            ("log-level", "trace", Set),
+            ("neighborhood-mode", "consume-only", Configured),
+        ]);
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        subject.params = existing_setup.clone();
+        subject.setup_reporter = Box::new(
+            SetupReporterMock::new()
+                .get_profiled_setup_params(&get_profiled_setup_params_arc)
+                .get_profiled_setup_result(Ok(profiled_setup.clone())),
+        );
+        subject.chain_consistency_checker =
+            Box::new(ChainConsistencyCheckerMock::new().check_result(None));
         let system = System::new("test");
-        let mut subject = Daemon::new(Box::new(launcher));
-        subject.params.insert(
-            "db-password".to_string(),
-            UiSetupResponseValue::new("db-password", "goober", Set),
+        subject.ui_gateway_sub = Some(ui_gateway.start().recipient());
+
+        subject.handle_load_profile(
+            47,
+            74,
+            UiLoadProfileRequest {
+                name: "datacenter".to_string(),
+            },
         );
-        subject.verifier_tools = Box::new(verifier_tools);
+
+        System::current().stop();
+        system.run();
+        assert_eq!(
+            *get_profiled_setup_params_arc.lock().unwrap(),
+            vec![(existing_setup, profile)]
+        );
+        assert_eq!(subject.params, profiled_setup);
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            *ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            NodeToUiMessage {
+                target: ClientId(47),
+                body: UiLoadProfileResponse {
+                    name: "datacenter".to_string(),
+                    loaded_parameters: vec!["neighborhood-mode".to_string()],
+                }
+                .tmb(74),
+            }
+        );
+        assert_eq!(
+            *ui_gateway_recording.get_record::<NodeToUiMessage>(1),
+            NodeToUiMessage {
+                target: MessageTarget::AllClients,
+                body: UiSetupBroadcast::new(
+                    false,
+                    profiled_setup,
+                    ConfiguratorError::new(vec![]),
+                    1
+                )
+                .tmb(0),
+            }
+        );
+        assert_eq!(ui_gateway_recording.len(), 2);
+    }
+
+    #[test]
+    fn load_profile_complains_about_a_missing_profile() {
+        let data_dir = ensure_node_home_directory_exists(
+            "daemon",
+            "load_profile_complains_about_a_missing_profile",
+        );
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        subject.params =
+            setup_cluster_from(vec![("data-directory", &data_dir.to_string_lossy(), Set)]);
+        let system = System::new("test");
+        subject.ui_gateway_sub = Some(ui_gateway.start().recipient());
+
+        subject.handle_load_profile(
+            47,
+            74,
+            UiLoadProfileRequest {
+                name: "datacenter".to_string(),
+            },
+        );
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let body = &ui_gateway_recording.get_record::<NodeToUiMessage>(0).body;
+        assert_eq!(body.opcode, "loadProfile");
+        match &body.payload {
+            Err((code, message)) => {
+                assert_eq!(*code, PROFILE_ERROR);
+                assert_string_contains(message, "Can't read profile 'datacenter' from ");
+            }
+            x => panic!("Expected profile error, got {:?}", x),
+        }
+        assert_eq!(ui_gateway_recording.len(), 1);
+    }
+
+    #[test]
+    fn load_profile_is_refused_while_node_is_running() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        subject.node_ui_port = Some(1234);
+        subject.node_process_id = Some(4321);
+        subject.verifier_tools = Box::new(VerifierToolsMock::new().process_is_running_result(true));
+        let system = System::new("test");
+        subject.ui_gateway_sub = Some(ui_gateway.start().recipient());
+
+        subject.handle_load_profile(
+            47,
+            74,
+            UiLoadProfileRequest {
+                name: "datacenter".to_string(),
+            },
+        );
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording
+                .get_record::<NodeToUiMessage>(0)
+                .body
+                .payload,
+            Err((
+                NODE_ALREADY_RUNNING_ERROR,
+                "Cannot load profile: Node is running".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn answers_schema_description_request_whether_or_not_node_is_running() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let system = System::new("test");
+        let subject = Daemon::new(Box::new(LauncherMock::new()));
         let subject_addr = subject.start();
         subject_addr
             .try_send(make_daemon_bind_message(ui_gateway))
@@ -1255,7 +3205,7 @@ mod tests {
         subject_addr
             .try_send(NodeFromUiMessage {
                 client_id: 1234,
-                body: UiStartOrder {}.tmb(4321),
+                body: UiSchemaDescriptionRequest {}.tmb(4321),
             })
             .unwrap();
 
@@ -1266,22 +3216,26 @@ mod tests {
             .get_record::<NodeToUiMessage>(0)
             .clone();
         assert_eq!(record.target, ClientId(1234));
-        let (code, message) = record.body.payload.err().unwrap();
-        assert_eq!(code, NODE_LAUNCH_ERROR);
-        assert_eq!(message, "Could not launch Node: booga".to_string());
+        let (response, context_id) = UiSchemaDescriptionResponse::fmb(record.body).unwrap();
+        assert_eq!(context_id, 4321);
+        assert_eq!(response.parameters.len(), parameter_descriptions().len());
+        assert_eq!(
+            response.parameters[0],
+            UiParameterDescription {
+                name: "neighborhood-mode".to_string(),
+                group: "Networking".to_string(),
+                display_order: 1,
+                help: NEIGHBORHOOD_MODE_HELP.to_string(),
+            }
+        );
     }
 
     #[test]
-    fn rejects_start_order_when_node_is_already_running() {
+    fn answers_chain_list_request_whether_or_not_node_is_running() {
         let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
-        let launcher = LauncherMock::new().launch_result(Err("booga".to_string()));
         let verifier_tools = VerifierToolsMock::new().process_is_running_result(true);
         let system = System::new("test");
-        let mut subject = Daemon::new(Box::new(launcher));
-        subject.params.insert(
-            "db-password".to_string(),
-            UiSetupResponseValue::new("db-password", "goober", Set),
-        );
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
         subject.node_ui_port = Some(1234);
         subject.node_process_id = Some(3421);
         subject.verifier_tools = Box::new(verifier_tools);
@@ -1293,7 +3247,7 @@ mod tests {
         subject_addr
             .try_send(NodeFromUiMessage {
                 client_id: 1234,
-                body: UiStartOrder {}.tmb(4321),
+                body: UiChainListRequest {}.tmb(4321),
             })
             .unwrap();
 
@@ -1304,13 +3258,29 @@ mod tests {
             .get_record::<NodeToUiMessage>(0)
             .clone();
         assert_eq!(record.target, ClientId(1234));
-        assert_eq!(&record.body.opcode, "start");
-        let (code, message) = record.body.payload.err().unwrap();
-        assert_eq!(code, NODE_ALREADY_RUNNING_ERROR);
+        let (response, context_id) = UiChainListResponse::fmb(record.body).unwrap();
+        assert_eq!(context_id, 4321);
         assert_eq!(
-            message,
-            "Could not launch Node: already running".to_string()
+            response
+                .chains
+                .iter()
+                .map(|chain| chain.literal_identifier.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "polygon-mainnet",
+                "eth-mainnet",
+                "base-mainnet",
+                "base-sepolia",
+                "polygon-amoy",
+                "eth-ropsten",
+                "dev"
+            ]
         );
+        assert_eq!(
+            response.chains[0],
+            UiChainRecord::from(Chain::PolyMainnet.rec())
+        );
+        assert_eq!(ui_gateway_recording.len(), 1);
     }
 
     #[test]
@@ -1630,6 +3600,67 @@ mod tests {
         );
     }
 
+    #[test]
+    fn remembers_crash_broadcast_for_clients_that_ask_about_missed_events_later() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let system = System::new("test");
+        let mut subject = Daemon::new(Box::new(LauncherMock::new()));
+        subject.node_ui_port = Some(1234);
+        subject.node_process_id = Some(12345);
+        let subject_addr = subject.start();
+        subject_addr
+            .try_send(make_daemon_bind_message(ui_gateway))
+            .unwrap();
+        subject_addr
+            .try_send(CrashNotification {
+                process_id: 54321,
+                exit_code: Some(123),
+                stderr: Some("Standard error".to_string()),
+            })
+            .unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 7777,
+                body: UiMissedEventsRequest { after_sequence: 0 }.tmb(777),
+            })
+            .unwrap();
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 7777,
+                body: UiMissedEventsRequest { after_sequence: 1 }.tmb(778),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let crash_body = ui_gateway_recording
+            .get_record::<NodeToUiMessage>(0)
+            .body
+            .clone();
+        let record = ui_gateway_recording.get_record::<NodeToUiMessage>(1);
+        assert_eq!(record.target, ClientId(7777));
+        let (response, context_id) = UiMissedEventsResponse::fmb(record.body.clone()).unwrap();
+        assert_eq!(context_id, 777);
+        assert_eq!(response.latest_sequence, 1);
+        assert_eq!(response.events.len(), 1);
+        assert_eq!(response.events[0].sequence, 1);
+        assert_eq!(response.events[0].critical, true);
+        assert_eq!(response.events[0].opcode, "crashed".to_string());
+        assert_eq!(response.events[0].payload, crash_body.payload.unwrap());
+        let record = ui_gateway_recording.get_record::<NodeToUiMessage>(2);
+        let (response, context_id) = UiMissedEventsResponse::fmb(record.body.clone()).unwrap();
+        assert_eq!(context_id, 778);
+        assert_eq!(
+            response,
+            UiMissedEventsResponse {
+                latest_sequence: 1,
+                events: vec![]
+            }
+        );
+    }
+
     #[test]
     fn accepts_crash_notification_in_setup_mode_and_swallows() {
         let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
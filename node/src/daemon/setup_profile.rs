@@ -0,0 +1,230 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::daemon::setup_reporter::SetupCluster;
+use masq_lib::messages::UiSetupResponseValueStatus::{Configured, Set};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const PROFILES_DIRECTORY_NAME: &str = "profiles";
+
+// The profile lives in the data directory, and secrets don't belong in a plain file
+const UNSAVED_PARAMETERS: [&str; 4] = [
+    "config-file",
+    "consuming-private-key",
+    "data-directory",
+    "db-password",
+];
+
+// A named preset of setup values ("home-wifi", "datacenter") kept as a TOML file, in the same
+// format as config.toml, under the data directory. Only the values that were chosen, by a UI or
+// by configuration, are saved: everything else is worked out again when the profile is loaded.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct SetupProfile {
+    values: BTreeMap<String, String>,
+}
+
+impl SetupProfile {
+    pub fn from_setup(setup: &SetupCluster) -> Self {
+        let values = setup
+            .values()
+            .filter(|uisrv| [Set, Configured].contains(&uisrv.status))
+            .filter(|uisrv| !UNSAVED_PARAMETERS.contains(&uisrv.name.as_str()))
+            .map(|uisrv| (uisrv.name.clone(), uisrv.value.clone()))
+            .collect();
+        Self { values }
+    }
+
+    pub fn load(data_directory: &Path, name: &str) -> Result<Self, String> {
+        let path = Self::path(data_directory, name)?;
+        let toml_string = fs::read_to_string(&path)
+            .map_err(|e| format!("Can't read profile '{}' from {:?}: {}", name, path, e))?;
+        let values = toml::from_str::<BTreeMap<String, String>>(&toml_string)
+            .map_err(|e| format!("Profile '{}' in {:?} is garbled: {}", name, path, e))?;
+        Ok(Self { values })
+    }
+
+    pub fn save(&self, data_directory: &Path, name: &str) -> Result<(), String> {
+        let path = Self::path(data_directory, name)?;
+        let toml_string = toml::to_string(&self.values).expect("Profile won't serialize");
+        fs::create_dir_all(data_directory.join(PROFILES_DIRECTORY_NAME))
+            .and_then(|_| fs::write(&path, toml_string))
+            .map_err(|e| format!("Can't save profile '{}' to {:?}: {}", name, path, e))
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.values.keys().cloned().collect()
+    }
+
+    pub fn values(&self) -> &BTreeMap<String, String> {
+        &self.values
+    }
+
+    // A profile fills in underneath the values a UI has Set explicitly, never over them
+    pub fn not_set_in(&self, setup: &SetupCluster) -> Self {
+        let values = self
+            .values
+            .iter()
+            .filter(|(name, _)| setup.get(*name).map(|uisrv| uisrv.status) != Some(Set))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        Self { values }
+    }
+
+    // Profile names become file names, so nothing that could climb out of the profiles directory
+    fn path(data_directory: &Path, name: &str) -> Result<PathBuf, String> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "Profile name '{}' must be letters, digits, '-' and '_' only",
+                name
+            ));
+        }
+        Ok(data_directory
+            .join(PROFILES_DIRECTORY_NAME)
+            .join(format!("{}.toml", name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::setup_reporter::setup_cluster_from;
+    use masq_lib::messages::UiSetupResponseValueStatus::{Blank, Default};
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(PROFILES_DIRECTORY_NAME, "profiles");
+    }
+
+    #[test]
+    fn from_setup_keeps_only_chosen_values_that_are_not_secret_or_local() {
+        let setup = setup_cluster_from(vec![
+            ("blockchain-service-url", "https://booga.com", Configured),
+            ("chain", "polygon-mainnet", Default),
+            ("config-file", "config.toml", Set),
+            ("consuming-private-key", "0123456789abcdef", Set),
+            ("data-directory", "/home/booga/.local/share/MASQ", Set),
+            ("db-password", "password", Configured),
+            ("gas-price", "", Blank),
+            ("neighborhood-mode", "consume-only", Set),
+        ]);
+
+        let result = SetupProfile::from_setup(&setup);
+
+        assert_eq!(
+            result.names(),
+            vec![
+                "blockchain-service-url".to_string(),
+                "neighborhood-mode".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn profile_can_be_saved_and_loaded_back() {
+        let data_directory = ensure_node_home_directory_exists(
+            "setup_profile",
+            "profile_can_be_saved_and_loaded_back",
+        );
+        let subject = SetupProfile::from_setup(&setup_cluster_from(vec![
+            ("log-level", "debug", Set),
+            ("neighborhood-mode", "consume-only", Configured),
+        ]));
+
+        subject.save(&data_directory, "home-wifi").unwrap();
+        let result = SetupProfile::load(&data_directory, "home-wifi").unwrap();
+
+        assert_eq!(result, subject);
+        assert_eq!(
+            fs::read_to_string(data_directory.join("profiles").join("home-wifi.toml")).unwrap(),
+            "log-level = \"debug\"\nneighborhood-mode = \"consume-only\"\n"
+        );
+    }
+
+    #[test]
+    fn load_complains_about_a_missing_profile() {
+        let data_directory = ensure_node_home_directory_exists(
+            "setup_profile",
+            "load_complains_about_a_missing_profile",
+        );
+
+        let result = SetupProfile::load(&data_directory, "datacenter");
+
+        let message = result.err().unwrap();
+        assert!(
+            message.starts_with("Can't read profile 'datacenter' from "),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn load_complains_about_a_garbled_profile() {
+        let data_directory = ensure_node_home_directory_exists(
+            "setup_profile",
+            "load_complains_about_a_garbled_profile",
+        );
+        fs::create_dir_all(data_directory.join("profiles")).unwrap();
+        fs::write(
+            data_directory.join("profiles").join("datacenter.toml"),
+            "log-level = 4",
+        )
+        .unwrap();
+
+        let result = SetupProfile::load(&data_directory, "datacenter");
+
+        let message = result.err().unwrap();
+        assert!(
+            message.starts_with("Profile 'datacenter' in "),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn profile_names_cannot_leave_the_profiles_directory() {
+        let data_directory = ensure_node_home_directory_exists(
+            "setup_profile",
+            "profile_names_cannot_leave_the_profiles_directory",
+        );
+
+        let results = vec!["", "../config", "home wifi", "a/b"]
+            .into_iter()
+            .map(|name| SetupProfile::default().save(&data_directory, name))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            results[1],
+            Err("Profile name '../config' must be letters, digits, '-' and '_' only".to_string())
+        );
+        assert_eq!(results.iter().all(|result| result.is_err()), true);
+        assert_eq!(data_directory.join("profiles").exists(), false);
+    }
+
+    #[test]
+    fn not_set_in_leaves_out_values_the_ui_set() {
+        let subject = SetupProfile::from_setup(&setup_cluster_from(vec![
+            ("gas-price", "2", Set),
+            ("log-level", "debug", Set),
+            ("neighborhood-mode", "consume-only", Set),
+        ]));
+        let setup = setup_cluster_from(vec![
+            ("gas-price", "1", Default),
+            ("log-level", "trace", Set),
+            ("neighborhood-mode", "standard", Configured),
+        ]);
+
+        let result = subject.not_set_in(&setup);
+
+        assert_eq!(
+            result.names(),
+            vec!["gas-price".to_string(), "neighborhood-mode".to_string()]
+        );
+        assert_eq!(result.values().get("gas-price"), Some(&"2".to_string()));
+    }
+}
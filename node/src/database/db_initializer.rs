@@ -2,6 +2,8 @@
 use crate::database::rusqlite_wrappers::{ConnectionWrapper, ConnectionWrapperReal};
 
 use crate::database::db_migrations::db_migrator::{DbMigrator, DbMigratorReal};
+use crate::database::sqlite_pragmas::{SqlitePragmas, DEFAULT_SQLITE_PRAGMAS};
+use crate::database::traffic_database::attach_traffic_database;
 use crate::db_config::secure_config_layer::EXAMPLE_ENCRYPTED;
 use crate::neighborhood::DEFAULT_MIN_HOPS;
 use crate::sub_lib::accountant::{DEFAULT_PAYMENT_THRESHOLDS, DEFAULT_SCAN_INTERVALS};
@@ -21,6 +23,7 @@ use std::fmt::{Debug, Formatter};
 use std::io::ErrorKind;
 use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
 use std::path::Path;
+use std::str::FromStr;
 use std::{fs, vec};
 use tokio::net::TcpListener;
 
@@ -82,6 +85,7 @@ impl DbInitializer for DbInitializerReal {
             Ok(conn) => {
                 eprintln!("Opened existing database at {:?}", db_file_path);
                 Self::extra_configuration(&conn, &init_config)?;
+                Self::apply_sqlite_pragmas(&conn)?;
                 self.check_migrations_and_return_connection(
                     conn,
                     init_config,
@@ -97,6 +101,7 @@ impl DbInitializer for DbInitializerReal {
                 Ok(conn) => {
                     eprintln!("Created new database at {:?}", db_file_path);
                     Self::extra_configuration(&conn, &init_config)?;
+                    Self::apply_sqlite_pragmas(&conn)?;
                     self.create_database_tables(&conn, ExternalData::from(init_config));
                     Ok(Box::new(ConnectionWrapperReal::new(conn)))
                 }
@@ -138,6 +143,9 @@ impl DbInitializerReal {
         Self::create_pending_payable_table(conn);
         Self::create_receivable_table(conn);
         Self::create_banned_table(conn);
+        Self::create_balance_audit_log_table(conn);
+        Self::create_earnings_history_table(conn);
+        Self::create_address_book_table(conn);
     }
 
     pub fn create_config_table(conn: &Connection) {
@@ -174,6 +182,13 @@ impl DbInitializerReal {
             false,
             "clandestine port",
         );
+        Self::set_config_value(
+            conn,
+            "clandestine_port_rotates",
+            Some("false"),
+            false,
+            "whether a fresh clandestine port is chosen at every startup",
+        );
         Self::set_config_value(
             conn,
             "consuming_wallet_private_key",
@@ -256,6 +271,62 @@ impl DbInitializerReal {
             "scan intervals",
         );
         Self::set_config_value(conn, "max_block_count", None, false, "maximum block count");
+        Self::set_config_value(
+            conn,
+            "scanned_blocks",
+            None,
+            false,
+            "recently scanned block hashes",
+        );
+        Self::set_config_value(
+            conn,
+            "sqlite_pragmas",
+            Some(&DEFAULT_SQLITE_PRAGMAS.to_string()),
+            false,
+            "sqlite pragmas",
+        );
+        Self::set_config_value(
+            conn,
+            "token_contract_address",
+            None,
+            false,
+            "token contract address overriding the chain's own",
+        );
+        Self::set_config_value(
+            conn,
+            "neighbor_notes",
+            None,
+            false,
+            "signed operator notes that came with neighbors' descriptors",
+        );
+        Self::set_config_value(
+            conn,
+            "traffic_db_path",
+            None,
+            false,
+            "file chosen for the traffic tables",
+        );
+        Self::set_config_value(
+            conn,
+            "traffic_db_path_in_use",
+            None,
+            false,
+            "file the traffic tables are in",
+        );
+        Self::set_config_value(
+            conn,
+            "topology_snapshot",
+            None,
+            true,
+            "recent view of the neighborhood, for a fast restart",
+        );
+        Self::set_config_value(
+            conn,
+            "last_public_ip",
+            None,
+            false,
+            "public IP the router reported most recently",
+        );
     }
 
     pub fn create_pending_payable_table(conn: &Connection) {
@@ -314,6 +385,51 @@ impl DbInitializerReal {
         .expect("Can't create banned table");
     }
 
+    pub fn create_balance_audit_log_table(conn: &Connection) {
+        conn.execute(
+            "create table if not exists balance_audit_log (
+                    rowid integer primary key,
+                    timestamp integer not null,
+                    wallet_address text not null,
+                    account_type text not null,
+                    change_high_b integer not null,
+                    change_low_b integer not null,
+                    cause text not null,
+                    reference text null
+            ) strict",
+            [],
+        )
+        .expect("Can't create balance_audit_log table");
+    }
+
+    pub fn create_earnings_history_table(conn: &Connection) {
+        conn.execute(
+            "create table if not exists earnings_history (
+                    day_start integer primary key,
+                    bytes_routed integer not null,
+                    bytes_exited integer not null,
+                    earned_high_b integer not null,
+                    earned_low_b integer not null,
+                    spent_high_b integer not null,
+                    spent_low_b integer not null
+            ) strict",
+            [],
+        )
+        .expect("Can't create earnings_history table");
+    }
+
+    pub fn create_address_book_table(conn: &Connection) {
+        conn.execute(
+            "create table if not exists address_book (
+                    wallet_address text primary key,
+                    label text not null,
+                    descriptor text null
+            ) strict",
+            [],
+        )
+        .expect("Can't create address_book table");
+    }
+
     fn extra_configuration(
         conn: &Connection,
         init_config: &DbInitializationConfig,
@@ -332,6 +448,32 @@ impl DbInitializerReal {
         }
     }
 
+    fn apply_sqlite_pragmas(conn: &Connection) -> Result<(), InitializationError> {
+        Self::read_sqlite_pragmas(conn)
+            .apply(conn)
+            .map_err(InitializationError::SqliteError)
+    }
+
+    // A database that is brand new or not yet migrated to carry its own pragmas gets the defaults
+    fn read_sqlite_pragmas(conn: &Connection) -> SqlitePragmas {
+        match conn.query_row(
+            "select value from config where name = 'sqlite_pragmas'",
+            [],
+            |row| row.get::<usize, Option<String>>(0),
+        ) {
+            Ok(Some(value)) => SqlitePragmas::from_str(&value).unwrap_or_else(|e| {
+                warning!(
+                    Logger::new("DbInitializer"),
+                    "{}; falling back to {}",
+                    e,
+                    DEFAULT_SQLITE_PRAGMAS
+                );
+                DEFAULT_SQLITE_PRAGMAS
+            }),
+            _ => DEFAULT_SQLITE_PRAGMAS,
+        }
+    }
+
     fn check_migrations_and_return_connection(
         &self,
         conn: Connection,
@@ -342,7 +484,17 @@ impl DbInitializerReal {
     ) -> Result<Box<dyn ConnectionWrapper>, InitializationError> {
         let str_sv = Self::read_current_schema_version(&conn)?;
         match (Self::is_migration_required(&str_sv)?, init_config.mode) {
-            (None, _) => Ok(Box::new(ConnectionWrapperReal::new(conn))),
+            (None, mode) => {
+                // Only the Node itself may move the traffic tables; the Daemon just looks
+                let may_relocate = matches!(
+                    mode,
+                    InitializationMode::CreationAndMigration { .. }
+                        | InitializationMode::CreationBannedMigrationPanics
+                );
+                attach_traffic_database(&conn, may_relocate)
+                    .map_err(InitializationError::SqliteError)?;
+                Ok(Box::new(ConnectionWrapperReal::new(conn)))
+            }
             (Some(_), InitializationMode::CreationBannedMigrationPanics) => {
                 panic!("Broken code: Migrating database at inappropriate place")
             }
@@ -361,6 +513,7 @@ impl DbInitializerReal {
                 )
             }
             (Some(_), InitializationMode::CreationBannedMigrationSuppressed) => {
+                attach_traffic_database(&conn, false).map_err(InitializationError::SqliteError)?;
                 Ok(Box::new(ConnectionWrapperReal::new(conn)))
             }
             (Some(_), InitializationMode::CreationBannedMigrationRaisesErr) => {
@@ -403,6 +556,7 @@ impl DbInitializerReal {
             Logger::new("DbInitializer"),
             "Database is incompatible and its updating is necessary"
         );
+        // Migrations see only the main database; the traffic tables are attached afterwards
         let wrapped_connection = ConnectionWrapperReal::new(conn);
         match migrator.migrate_database(
             mismatched_version,
@@ -426,10 +580,15 @@ impl DbInitializerReal {
     ) -> Box<dyn ConnectionWrapper> {
         let conn = Connection::open_with_flags(db_file_path, opening_flags)
             .unwrap_or_else(|e| panic!("The database undoubtedly exists, but: {}", e));
+        Self::apply_sqlite_pragmas(&conn)
+            .unwrap_or_else(|e| panic!("Migrated database refused its pragmas: {:?}", e));
         let str_schema = Self::read_current_schema_version(&conn)
             .expect("Db migration failed; cannot find the row with the schema version");
         let numeric_schema = Self::validate_schema_version(&str_schema);
         if numeric_schema == target_version {
+            attach_traffic_database(&conn, true).unwrap_or_else(|e| {
+                panic!("Migrated database can't reach its traffic tables: {:?}", e)
+            });
             Box::new(ConnectionWrapperReal::new(conn))
         } else {
             panic!("DB migration failed, the resulting records are still incorrect; found schema {} but expecting {}", numeric_schema, target_version)
@@ -652,7 +811,7 @@ mod tests {
     #[test]
     fn constants_have_correct_values() {
         assert_eq!(DATABASE_FILE, "node-data.db");
-        assert_eq!(CURRENT_SCHEMA_VERSION, 10);
+        assert_eq!(CURRENT_SCHEMA_VERSION, 21);
     }
 
     #[test]
@@ -789,6 +948,108 @@ mod tests {
         assert_no_index_exists_for_table(conn.as_ref(), "banned")
     }
 
+    #[test]
+    fn db_initialize_creates_balance_audit_log_table() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "db_initializer",
+            "db_initialize_creates_balance_audit_log_table",
+        );
+        let subject = DbInitializerReal::default();
+
+        let conn = subject
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+
+        let mut stmt = conn
+            .prepare("select rowid, timestamp, wallet_address, account_type, change_high_b, change_low_b, cause, reference from balance_audit_log")
+            .unwrap();
+        let mut audit_log_contents = stmt.query_map([], |_| Ok(())).unwrap();
+        assert!(audit_log_contents.next().is_none());
+        assert_table_created_as_strict(&*conn, "balance_audit_log");
+        let expected_key_words: &[&[&str]] = &[
+            &["rowid", "integer", "primary", "key"],
+            &["timestamp", "integer", "not", "null"],
+            &["wallet_address", "text", "not", "null"],
+            &["account_type", "text", "not", "null"],
+            &["change_high_b", "integer", "not", "null"],
+            &["change_low_b", "integer", "not", "null"],
+            &["cause", "text", "not", "null"],
+            &["reference", "text", "null"],
+        ];
+        assert_create_table_stm_contains_all_parts(
+            conn.as_ref(),
+            "balance_audit_log",
+            expected_key_words,
+        );
+        assert_no_index_exists_for_table(conn.as_ref(), "balance_audit_log")
+    }
+
+    #[test]
+    fn db_initialize_creates_earnings_history_table() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "db_initializer",
+            "db_initialize_creates_earnings_history_table",
+        );
+        let subject = DbInitializerReal::default();
+
+        let conn = subject
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+
+        let mut stmt = conn
+            .prepare("select day_start, bytes_routed, bytes_exited, earned_high_b, earned_low_b, spent_high_b, spent_low_b from earnings_history")
+            .unwrap();
+        let mut earnings_history_contents = stmt.query_map([], |_| Ok(())).unwrap();
+        assert!(earnings_history_contents.next().is_none());
+        assert_table_created_as_strict(&*conn, "earnings_history");
+        let expected_key_words: &[&[&str]] = &[
+            &["day_start", "integer", "primary", "key"],
+            &["bytes_routed", "integer", "not", "null"],
+            &["bytes_exited", "integer", "not", "null"],
+            &["earned_high_b", "integer", "not", "null"],
+            &["earned_low_b", "integer", "not", "null"],
+            &["spent_high_b", "integer", "not", "null"],
+            &["spent_low_b", "integer", "not", "null"],
+        ];
+        assert_create_table_stm_contains_all_parts(
+            conn.as_ref(),
+            "earnings_history",
+            expected_key_words,
+        );
+        assert_no_index_exists_for_table(conn.as_ref(), "earnings_history")
+    }
+
+    #[test]
+    fn db_initialize_creates_address_book_table() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "db_initializer",
+            "db_initialize_creates_address_book_table",
+        );
+        let subject = DbInitializerReal::default();
+
+        let conn = subject
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+
+        let mut stmt = conn
+            .prepare("select wallet_address, label, descriptor from address_book")
+            .unwrap();
+        let mut address_book_contents = stmt.query_map([], |_| Ok(())).unwrap();
+        assert!(address_book_contents.next().is_none());
+        assert_table_created_as_strict(&*conn, "address_book");
+        let expected_key_words: &[&[&str]] = &[
+            &["wallet_address", "text", "primary", "key"],
+            &["label", "text", "not", "null"],
+            &["descriptor", "text", "null"],
+        ];
+        assert_create_table_stm_contains_all_parts(
+            conn.as_ref(),
+            "address_book",
+            expected_key_words,
+        );
+        assert_no_index_exists_for_table(conn.as_ref(), "address_book")
+    }
+
     #[test]
     #[should_panic(expected = "The database undoubtedly exists, but: unable to open database file")]
     fn double_check_the_result_of_db_migration_panics_if_cannot_reestablish_the_connection_to_the_database(
@@ -856,6 +1117,94 @@ mod tests {
         .collect::<HashMap<String, (Option<String>, bool)>>()
     }
 
+    #[test]
+    fn new_database_is_opened_with_default_pragmas() {
+        let home_dir = ensure_node_home_directory_exists(
+            "db_initializer",
+            "new_database_is_opened_with_default_pragmas",
+        );
+        let subject = DbInitializerReal::default();
+
+        let conn = subject
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+
+        assert_eq!(read_pragma(conn.as_ref(), "journal_mode"), "wal");
+        assert_eq!(read_pragma(conn.as_ref(), "synchronous"), "1");
+        assert_eq!(read_pragma(conn.as_ref(), "cache_size"), "-2000");
+        assert_eq!(read_pragma(conn.as_ref(), "busy_timeout"), "5000");
+    }
+
+    #[test]
+    fn existing_database_is_opened_with_the_pragmas_it_stores() {
+        let home_dir = ensure_node_home_directory_exists(
+            "db_initializer",
+            "existing_database_is_opened_with_the_pragmas_it_stores",
+        );
+        let subject = DbInitializerReal::default();
+        {
+            let conn = subject
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap();
+            conn.prepare(
+                "update config set value = 'TRUNCATE|FULL|-4000|1234' where name = 'sqlite_pragmas'",
+            )
+            .unwrap()
+            .execute([])
+            .unwrap();
+        }
+
+        let conn = subject
+            .initialize(&home_dir, DbInitializationConfig::panic_on_migration())
+            .unwrap();
+
+        assert_eq!(read_pragma(conn.as_ref(), "journal_mode"), "truncate");
+        assert_eq!(read_pragma(conn.as_ref(), "synchronous"), "2");
+        assert_eq!(read_pragma(conn.as_ref(), "cache_size"), "-4000");
+        assert_eq!(read_pragma(conn.as_ref(), "busy_timeout"), "1234");
+    }
+
+    #[test]
+    fn uninterpretable_stored_pragmas_are_replaced_by_defaults_with_a_warning() {
+        init_test_logging();
+        let home_dir = ensure_node_home_directory_exists(
+            "db_initializer",
+            "uninterpretable_stored_pragmas_are_replaced_by_defaults_with_a_warning",
+        );
+        let subject = DbInitializerReal::default();
+        {
+            let conn = subject
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap();
+            conn.prepare("update config set value = 'booga' where name = 'sqlite_pragmas'")
+                .unwrap()
+                .execute([])
+                .unwrap();
+        }
+
+        let conn = subject
+            .initialize(&home_dir, DbInitializationConfig::panic_on_migration())
+            .unwrap();
+
+        assert_eq!(read_pragma(conn.as_ref(), "journal_mode"), "wal");
+        assert_eq!(read_pragma(conn.as_ref(), "busy_timeout"), "5000");
+        TestLogHandler::new().exists_log_containing(
+            "WARN: DbInitializer: Bad db-pragmas value 'booga': expected <journal mode>|<synchronous level>|<cache size>|<busy timeout in ms>, like WAL|NORMAL|-2000|5000; falling back to WAL|NORMAL|-2000|5000",
+        );
+    }
+
+    fn read_pragma(conn: &dyn ConnectionWrapper, name: &str) -> String {
+        conn.prepare(&format!("pragma {}", name))
+            .unwrap()
+            .query_row([], |row| row.get::<usize, rusqlite::types::Value>(0))
+            .map(|value| match value {
+                rusqlite::types::Value::Integer(int) => int.to_string(),
+                rusqlite::types::Value::Text(text) => text,
+                x => panic!("Unexpected pragma value {:?}", x),
+            })
+            .unwrap()
+    }
+
     #[test]
     fn existing_database_with_correct_version_is_accepted_without_changes() {
         let home_dir = ensure_node_home_directory_exists(
@@ -917,6 +1266,12 @@ mod tests {
         let clandestine_port: u16 = clandestine_port_str_opt.unwrap().parse().unwrap();
         assert!(clandestine_port >= 1025);
         assert!(clandestine_port < 10000);
+        verify(
+            &mut config_vec,
+            "clandestine_port_rotates",
+            Some("false"),
+            false,
+        );
         verify(&mut config_vec, "consuming_wallet_private_key", None, true);
         verify(&mut config_vec, "earning_wallet_address", None, false);
         verify(&mut config_vec, EXAMPLE_ENCRYPTED, None, true);
@@ -926,9 +1281,11 @@ mod tests {
             Some(&DEFAULT_GAS_PRICE.to_string()),
             false,
         );
+        verify(&mut config_vec, "last_public_ip", None, false);
         verify(&mut config_vec, "mapping_protocol", None, false);
         verify(&mut config_vec, "max_block_count", None, false);
         verify(&mut config_vec, "min_hops", Some("3"), false);
+        verify(&mut config_vec, "neighbor_notes", None, false);
         verify(
             &mut config_vec,
             "neighborhood_mode",
@@ -955,16 +1312,91 @@ mod tests {
             Some(&DEFAULT_SCAN_INTERVALS.to_string()),
             false,
         );
+        verify(&mut config_vec, "scanned_blocks", None, false);
         verify(
             &mut config_vec,
             "schema_version",
             Some(&CURRENT_SCHEMA_VERSION.to_string()),
             false,
         );
+        verify(
+            &mut config_vec,
+            "sqlite_pragmas",
+            Some(&DEFAULT_SQLITE_PRAGMAS.to_string()),
+            false,
+        );
         verify(&mut config_vec, "start_block", None, false);
+        verify(&mut config_vec, "token_contract_address", None, false);
+        verify(&mut config_vec, "topology_snapshot", None, true);
+        verify(&mut config_vec, "traffic_db_path", None, false);
+        verify(&mut config_vec, "traffic_db_path_in_use", None, false);
         assert_eq!(config_vec, vec![]);
     }
 
+    #[test]
+    fn traffic_tables_are_moved_by_the_node_and_only_attached_for_the_daemon() {
+        let home_dir = ensure_node_home_directory_exists(
+            "db_initializer",
+            "traffic_tables_are_moved_by_the_node_and_only_attached_for_the_daemon",
+        );
+        let traffic_path = home_dir.join("traffic.db");
+        let subject = DbInitializerReal::default();
+        {
+            let conn = subject
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap();
+            conn.prepare(
+                "insert into receivable (wallet_address, balance_high_b, balance_low_b, \
+                 last_received_timestamp) values ('0xabc', 0, 1234, 5678)",
+            )
+            .unwrap()
+            .execute([])
+            .unwrap();
+            conn.prepare("update config set value = ? where name = 'traffic_db_path'")
+                .unwrap()
+                .execute([traffic_path.to_str().unwrap()])
+                .unwrap();
+        }
+        let count_main_tables = |conn: &dyn ConnectionWrapper| {
+            conn.prepare(
+                "select count(*) from main.sqlite_master where type = 'table' \
+                 and name = 'receivable'",
+            )
+            .unwrap()
+            .query_row([], |row| row.get::<usize, i64>(0))
+            .unwrap()
+        };
+        let read_wallet = |conn: &dyn ConnectionWrapper| {
+            conn.prepare("select wallet_address from receivable")
+                .unwrap()
+                .query_row([], |row| row.get::<usize, String>(0))
+                .unwrap()
+        };
+
+        let daemon_conn = subject
+            .initialize(&home_dir, DbInitializationConfig::migration_suppressed())
+            .unwrap();
+
+        assert_eq!(count_main_tables(daemon_conn.as_ref()), 1);
+        assert_eq!(traffic_path.exists(), false);
+        drop(daemon_conn);
+
+        let node_conn = subject
+            .initialize(&home_dir, DbInitializationConfig::panic_on_migration())
+            .unwrap();
+
+        assert_eq!(count_main_tables(node_conn.as_ref()), 0);
+        assert_eq!(read_wallet(node_conn.as_ref()), "0xabc".to_string());
+        assert_eq!(traffic_path.exists(), true);
+        drop(node_conn);
+
+        let daemon_conn = subject
+            .initialize(&home_dir, DbInitializationConfig::migration_suppressed())
+            .unwrap();
+
+        assert_eq!(read_wallet(daemon_conn.as_ref()), "0xabc".to_string());
+    }
+
     #[test]
     fn existing_database_with_no_version_is_rejected() {
         let home_dir = ensure_node_home_directory_exists(
@@ -2,7 +2,18 @@
 
 use crate::database::db_initializer::ExternalData;
 use crate::database::db_migrations::migrations::migration_0_to_1::Migrate_0_to_1;
+use crate::database::db_migrations::migrations::migration_10_to_11::Migrate_10_to_11;
+use crate::database::db_migrations::migrations::migration_11_to_12::Migrate_11_to_12;
+use crate::database::db_migrations::migrations::migration_12_to_13::Migrate_12_to_13;
+use crate::database::db_migrations::migrations::migration_13_to_14::Migrate_13_to_14;
+use crate::database::db_migrations::migrations::migration_14_to_15::Migrate_14_to_15;
+use crate::database::db_migrations::migrations::migration_15_to_16::Migrate_15_to_16;
+use crate::database::db_migrations::migrations::migration_16_to_17::Migrate_16_to_17;
+use crate::database::db_migrations::migrations::migration_17_to_18::Migrate_17_to_18;
+use crate::database::db_migrations::migrations::migration_18_to_19::Migrate_18_to_19;
+use crate::database::db_migrations::migrations::migration_19_to_20::Migrate_19_to_20;
 use crate::database::db_migrations::migrations::migration_1_to_2::Migrate_1_to_2;
+use crate::database::db_migrations::migrations::migration_20_to_21::Migrate_20_to_21;
 use crate::database::db_migrations::migrations::migration_2_to_3::Migrate_2_to_3;
 use crate::database::db_migrations::migrations::migration_3_to_4::Migrate_3_to_4;
 use crate::database::db_migrations::migrations::migration_4_to_5::Migrate_4_to_5;
@@ -17,6 +28,9 @@ use crate::database::db_migrations::migrator_utils::{
 use crate::database::rusqlite_wrappers::{ConnectionWrapper, TransactionSafeWrapper};
 use masq_lib::logger::Logger;
 
+// Above this many rows, the operator is warned that the Node may take a while to start
+pub const LARGE_MIGRATION_ROW_COUNT: u64 = 1_000_000;
+
 pub trait DbMigrator {
     fn migrate_database(
         &self,
@@ -38,6 +52,7 @@ impl DbMigrator for DbMigratorReal {
         target_version: usize,
         mut conn: Box<dyn ConnectionWrapper>,
     ) -> Result<(), String> {
+        self.log_estimate(obsolete_schema, target_version, &*conn);
         let migrator_config = DBMigratorInnerConfiguration::new();
         let migration_utils = match DBMigrationUtilitiesReal::new(&mut *conn, migrator_config) {
             Err(e) => return Err(e.to_string()),
@@ -80,6 +95,17 @@ impl DbMigratorReal {
             &Migrate_7_to_8,
             &Migrate_8_to_9,
             &Migrate_9_to_10,
+            &Migrate_10_to_11,
+            &Migrate_11_to_12,
+            &Migrate_12_to_13,
+            &Migrate_13_to_14,
+            &Migrate_14_to_15,
+            &Migrate_15_to_16,
+            &Migrate_16_to_17,
+            &Migrate_17_to_18,
+            &Migrate_18_to_19,
+            &Migrate_19_to_20,
+            &Migrate_20_to_21,
         ]
     }
 
@@ -96,8 +122,17 @@ impl DbMigratorReal {
             target_version,
             &*migration_utilities,
         );
-        for record in migrations_to_process {
+        let step_count = migrations_to_process.len();
+        for (index, record) in migrations_to_process.into_iter().enumerate() {
             let present_db_version = record.old_version();
+            info!(
+                &self.logger,
+                "Migrating from version {} to version {} (step {} of {})",
+                present_db_version,
+                present_db_version + 1,
+                index + 1,
+                step_count
+            );
             if let Err(e) = self.migrate_semi_automated(record, &*migration_utilities, &self.logger)
             {
                 return self.dispatch_bad_news(present_db_version, e);
@@ -113,12 +148,6 @@ impl DbMigratorReal {
         migration_utilities: &'a (dyn DBMigrationUtilities + 'a),
         logger: &Logger,
     ) -> rusqlite::Result<()> {
-        info!(
-            &self.logger,
-            "Migrating from version {} to version {}",
-            record.old_version(),
-            record.old_version() + 1
-        );
         record.migrate(migration_utilities.make_mig_declarator(&self.external, logger))?;
         let migrate_to = record.old_version() + 1;
         migration_utilities.update_schema_version(migrate_to)
@@ -154,6 +183,56 @@ impl DbMigratorReal {
             .collect::<Vec<&'a (dyn DatabaseMigration + 'a)>>()
     }
 
+    // Each step may rewrite any table, so the number of rows is the best guide to how long it'll take
+    fn log_estimate(
+        &self,
+        obsolete_schema: usize,
+        target_version: usize,
+        conn: &dyn ConnectionWrapper,
+    ) {
+        let (table_count, row_count) = match Self::count_rows(conn) {
+            Ok(counts) => counts,
+            Err(e) => {
+                warning!(
+                    self.logger,
+                    "Couldn't count rows before migrating the database: {:?}",
+                    e
+                );
+                return;
+            }
+        };
+        info!(
+            self.logger,
+            "Migrating database from version {} to {}: {} steps over {} rows in {} tables",
+            obsolete_schema,
+            target_version,
+            target_version.saturating_sub(obsolete_schema),
+            row_count,
+            table_count
+        );
+        if row_count > LARGE_MIGRATION_ROW_COUNT {
+            warning!(
+                self.logger,
+                "The database is large, so migrating it may take several minutes; the Node will start when it's done"
+            )
+        }
+    }
+
+    fn count_rows(conn: &dyn ConnectionWrapper) -> rusqlite::Result<(usize, u64)> {
+        let mut stmt = conn.prepare(
+            "select name from sqlite_master where type = 'table' and name not like 'sqlite_%'",
+        )?;
+        let table_names = stmt
+            .query_map([], |row| row.get::<usize, String>(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        let row_count = table_names.iter().try_fold(0_u64, |total, table_name| {
+            conn.prepare(&format!("select count(*) from \"{}\"", table_name))?
+                .query_row([], |row| row.get::<usize, i64>(0))
+                .map(|count| total + count as u64)
+        })?;
+        Ok((table_names.len(), row_count))
+    }
+
     fn dispatch_bad_news(
         &self,
         current_version: usize,
@@ -342,6 +421,7 @@ mod tests {
         let obsolete_schema = 0;
         let target_version = 5; //irrelevant
         let connection = ConnectionWrapperMock::default()
+            .prepare_result(Err(Error::InvalidQuery))
             .transaction_result(Err(Error::SqliteSingleThreadedMode)); //hard to find a real-like error for this
 
         let result =
@@ -671,6 +751,9 @@ mod tests {
         );
         let update_schema_version_params = update_schema_version_params_arc.lock().unwrap();
         assert_eq!(update_schema_version_params[0], 1);
+        TestLogHandler::new().exists_log_containing(
+            "INFO: DbMigrator: Migrating from version 0 to version 1 (step 1 of 1)",
+        );
         TestLogHandler::new().exists_log_containing(
             "INFO: DbMigrator: Database successfully migrated from version 0 to 1",
         );
@@ -685,6 +768,44 @@ mod tests {
         )
     }
 
+    #[test]
+    fn count_rows_counts_the_rows_in_every_table() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "create table one (a integer); insert into one values (1), (2), (3); \
+             create table two (b text); insert into two values ('x'), ('y'); \
+             create table three (c integer);",
+        )
+        .unwrap();
+        let conn = ConnectionWrapperReal::new(conn);
+
+        let result = DbMigratorReal::count_rows(&conn);
+
+        assert_eq!(result.unwrap(), (3, 5));
+    }
+
+    #[test]
+    fn log_estimate_reports_steps_rows_and_tables_before_migrating() {
+        init_test_logging();
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("create table one (a integer); insert into one values (1), (2);")
+            .unwrap();
+        let conn = ConnectionWrapperReal::new(conn);
+        let mut subject = DbMigratorReal::new(make_external_data());
+        subject.logger = Logger::new("log_estimate_reports_steps_rows_and_tables_before_migrating");
+
+        subject.log_estimate(4, 7, &conn);
+
+        let test_log_handler = TestLogHandler::new();
+        test_log_handler.exists_log_containing(
+            "INFO: log_estimate_reports_steps_rows_and_tables_before_migrating: Migrating database \
+             from version 4 to 7: 3 steps over 2 rows in 1 tables",
+        );
+        test_log_handler.exists_no_log_containing(
+            "WARN: log_estimate_reports_steps_rows_and_tables_before_migrating: The database is large",
+        );
+    }
+
     #[test]
     fn final_commit_of_the_root_transaction_sad_path() {
         let first_record_migration_p_arc = Arc::new(Mutex::new(vec![]));
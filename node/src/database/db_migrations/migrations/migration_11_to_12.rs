@@ -0,0 +1,83 @@
+use crate::database::db_migrations::db_migrator::DatabaseMigration;
+use crate::database::db_migrations::migrator_utils::DBMigDeclarator;
+
+#[allow(non_camel_case_types)]
+pub struct Migrate_11_to_12;
+
+impl DatabaseMigration for Migrate_11_to_12 {
+    fn migrate<'a>(
+        &self,
+        declaration_utils: Box<dyn DBMigDeclarator + 'a>,
+    ) -> rusqlite::Result<()> {
+        declaration_utils.execute_upon_transaction(&[
+            &"create table if not exists earnings_history (
+                    day_start integer primary key,
+                    bytes_routed integer not null,
+                    bytes_exited integer not null,
+                    earned_high_b integer not null,
+                    earned_low_b integer not null,
+                    spent_high_b integer not null,
+                    spent_low_b integer not null
+            ) strict",
+        ])
+    }
+
+    fn old_version(&self) -> usize {
+        11
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal, DATABASE_FILE,
+    };
+    use crate::test_utils::database_utils::{
+        assert_table_created_as_strict, bring_db_0_back_to_life_and_return_connection,
+        make_external_data, retrieve_config_row,
+    };
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use std::fs::create_dir_all;
+
+    #[test]
+    fn migration_from_11_to_12_is_properly_set() {
+        init_test_logging();
+        let dir_path = ensure_node_home_directory_exists(
+            "db_migrations",
+            "migration_from_11_to_12_is_properly_set",
+        );
+        create_dir_all(&dir_path).unwrap();
+        let db_path = dir_path.join(DATABASE_FILE);
+        let _ = bring_db_0_back_to_life_and_return_connection(&db_path);
+        let subject = DbInitializerReal::default();
+
+        let result = subject.initialize_to_version(
+            &dir_path,
+            11,
+            DbInitializationConfig::create_or_migrate(make_external_data()),
+        );
+
+        assert!(result.is_ok());
+
+        let result = subject.initialize_to_version(
+            &dir_path,
+            12,
+            DbInitializationConfig::create_or_migrate(make_external_data()),
+        );
+
+        let connection = result.unwrap();
+        let mut stmt = connection
+            .prepare("select day_start, bytes_routed, bytes_exited, earned_high_b, earned_low_b, spent_high_b, spent_low_b from earnings_history")
+            .unwrap();
+        let mut earnings_history_contents = stmt.query_map([], |_| Ok(())).unwrap();
+        assert!(earnings_history_contents.next().is_none());
+        assert_table_created_as_strict(&*connection, "earnings_history");
+        let (cs_value, cs_encrypted) = retrieve_config_row(connection.as_ref(), "schema_version");
+        assert_eq!(cs_value, Some(12.to_string()));
+        assert_eq!(cs_encrypted, false);
+        TestLogHandler::new().assert_logs_contain_in_order(vec![
+            "DbMigrator: Database successfully migrated from version 11 to 12",
+        ]);
+    }
+}
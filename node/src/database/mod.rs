@@ -4,4 +4,6 @@ pub mod config_dumper;
 pub mod db_initializer;
 pub mod db_migrations;
 pub mod rusqlite_wrappers;
+pub mod sqlite_pragmas;
 pub mod test_utils;
+pub mod traffic_database;
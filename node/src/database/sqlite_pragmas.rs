@@ -0,0 +1,264 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use rusqlite::Connection;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::Duration;
+
+// In the WAL journal mode readers don't block the writer and the writer doesn't block readers, so
+// the Accountant's scans no longer stall the Neighborhood while it persists its past neighbors
+pub const DEFAULT_SQLITE_PRAGMAS: SqlitePragmas = SqlitePragmas {
+    journal_mode: JournalMode::Wal,
+    synchronous: SynchronousLevel::Normal,
+    cache_size: -2000,
+    busy_timeout_ms: 5000,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum JournalMode {
+    Delete,
+    Truncate,
+    Persist,
+    Memory,
+    Wal,
+    Off,
+}
+
+impl JournalMode {
+    const ALL: [JournalMode; 6] = [
+        JournalMode::Delete,
+        JournalMode::Truncate,
+        JournalMode::Persist,
+        JournalMode::Memory,
+        JournalMode::Wal,
+        JournalMode::Off,
+    ];
+
+    fn keyword(&self) -> &'static str {
+        match self {
+            JournalMode::Delete => "DELETE",
+            JournalMode::Truncate => "TRUNCATE",
+            JournalMode::Persist => "PERSIST",
+            JournalMode::Memory => "MEMORY",
+            JournalMode::Wal => "WAL",
+            JournalMode::Off => "OFF",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SynchronousLevel {
+    Off,
+    Normal,
+    Full,
+    Extra,
+}
+
+impl SynchronousLevel {
+    const ALL: [SynchronousLevel; 4] = [
+        SynchronousLevel::Off,
+        SynchronousLevel::Normal,
+        SynchronousLevel::Full,
+        SynchronousLevel::Extra,
+    ];
+
+    fn keyword(&self) -> &'static str {
+        match self {
+            SynchronousLevel::Off => "OFF",
+            SynchronousLevel::Normal => "NORMAL",
+            SynchronousLevel::Full => "FULL",
+            SynchronousLevel::Extra => "EXTRA",
+        }
+    }
+}
+
+// cache_size follows SQLite's own convention: a positive number counts pages, a negative one
+// counts kibibytes
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SqlitePragmas {
+    pub journal_mode: JournalMode,
+    pub synchronous: SynchronousLevel,
+    pub cache_size: i64,
+    pub busy_timeout_ms: u32,
+}
+
+impl SqlitePragmas {
+    pub fn apply(&self, conn: &Connection) -> rusqlite::Result<()> {
+        conn.busy_timeout(Duration::from_millis(self.busy_timeout_ms as u64))?;
+        // Changing the journal mode answers with the mode in effect, which may differ from the
+        // one requested, e.g. for in-memory databases
+        let _: String = conn.pragma_update_and_check(
+            None,
+            "journal_mode",
+            self.journal_mode.keyword(),
+            |row| row.get(0),
+        )?;
+        conn.pragma_update(None, "synchronous", self.synchronous.keyword())?;
+        conn.pragma_update(None, "cache_size", self.cache_size)
+    }
+}
+
+impl Default for SqlitePragmas {
+    fn default() -> Self {
+        DEFAULT_SQLITE_PRAGMAS
+    }
+}
+
+impl Display for SqlitePragmas {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}|{}|{}|{}",
+            self.journal_mode.keyword(),
+            self.synchronous.keyword(),
+            self.cache_size,
+            self.busy_timeout_ms
+        )
+    }
+}
+
+impl FromStr for SqlitePragmas {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let segments = s.split('|').collect::<Vec<&str>>();
+        let parsed = match segments.as_slice() {
+            [journal_mode, synchronous, cache_size, busy_timeout_ms] => (
+                JournalMode::ALL
+                    .into_iter()
+                    .find(|mode| mode.keyword().eq_ignore_ascii_case(journal_mode)),
+                SynchronousLevel::ALL
+                    .into_iter()
+                    .find(|level| level.keyword().eq_ignore_ascii_case(synchronous)),
+                cache_size.parse::<i64>().ok(),
+                busy_timeout_ms.parse::<u32>().ok(),
+            ),
+            _ => (None, None, None, None),
+        };
+        match parsed {
+            (Some(journal_mode), Some(synchronous), Some(cache_size), Some(busy_timeout_ms)) => {
+                Ok(Self {
+                    journal_mode,
+                    synchronous,
+                    cache_size,
+                    busy_timeout_ms,
+                })
+            }
+            _ => Err(format!(
+                "Bad db-pragmas value '{}': expected <journal mode>|<synchronous level>|<cache size>|<busy timeout in ms>, like {}",
+                s, DEFAULT_SQLITE_PRAGMAS
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            DEFAULT_SQLITE_PRAGMAS,
+            SqlitePragmas {
+                journal_mode: JournalMode::Wal,
+                synchronous: SynchronousLevel::Normal,
+                cache_size: -2000,
+                busy_timeout_ms: 5000,
+            }
+        );
+        assert_eq!(SqlitePragmas::default(), DEFAULT_SQLITE_PRAGMAS);
+    }
+
+    #[test]
+    fn sqlite_pragmas_survive_a_round_trip_through_a_string() {
+        let subject = SqlitePragmas {
+            journal_mode: JournalMode::Truncate,
+            synchronous: SynchronousLevel::Full,
+            cache_size: 4096,
+            busy_timeout_ms: 250,
+        };
+
+        let string = subject.to_string();
+
+        assert_eq!(string, "TRUNCATE|FULL|4096|250");
+        assert_eq!(SqlitePragmas::from_str(&string), Ok(subject));
+        assert_eq!(DEFAULT_SQLITE_PRAGMAS.to_string(), "WAL|NORMAL|-2000|5000");
+    }
+
+    #[test]
+    fn sqlite_pragmas_parse_keywords_case_insensitively() {
+        let result = SqlitePragmas::from_str("wal|Extra|-8000|0");
+
+        assert_eq!(
+            result,
+            Ok(SqlitePragmas {
+                journal_mode: JournalMode::Wal,
+                synchronous: SynchronousLevel::Extra,
+                cache_size: -8000,
+                busy_timeout_ms: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn sqlite_pragmas_reject_malformed_values() {
+        [
+            "WAL|NORMAL|-2000",
+            "WAL|NORMAL|-2000|5000|1",
+            "JOURNAL|NORMAL|-2000|5000",
+            "WAL|SOMETIMES|-2000|5000",
+            "WAL|NORMAL|lots|5000",
+            "WAL|NORMAL|-2000|-5000",
+            "",
+        ]
+        .into_iter()
+        .for_each(|bad_value| {
+            assert_eq!(
+                SqlitePragmas::from_str(bad_value),
+                Err(format!(
+                    "Bad db-pragmas value '{}': expected <journal mode>|<synchronous level>|<cache size>|<busy timeout in ms>, like WAL|NORMAL|-2000|5000",
+                    bad_value
+                )),
+                "{}",
+                bad_value
+            )
+        });
+    }
+
+    #[test]
+    fn apply_sets_the_pragmas_on_the_connection() {
+        let home_dir = ensure_node_home_directory_exists(
+            "sqlite_pragmas",
+            "apply_sets_the_pragmas_on_the_connection",
+        );
+        let conn = Connection::open(home_dir.join("pragmas.db")).unwrap();
+        let subject = SqlitePragmas {
+            journal_mode: JournalMode::Wal,
+            synchronous: SynchronousLevel::Full,
+            cache_size: -4000,
+            busy_timeout_ms: 1234,
+        };
+
+        let result = subject.apply(&conn);
+
+        assert_eq!(result, Ok(()));
+        let pragma = |name: &str| -> String {
+            conn.query_row(&format!("pragma {}", name), [], |row| {
+                row.get::<usize, rusqlite::types::Value>(0)
+            })
+            .map(|value| match value {
+                rusqlite::types::Value::Integer(int) => int.to_string(),
+                rusqlite::types::Value::Text(text) => text,
+                x => panic!("Unexpected pragma value {:?}", x),
+            })
+            .unwrap()
+        };
+        assert_eq!(pragma("journal_mode"), "wal");
+        assert_eq!(pragma("synchronous"), "2");
+        assert_eq!(pragma("cache_size"), "-4000");
+        assert_eq!(pragma("busy_timeout"), "1234");
+    }
+}
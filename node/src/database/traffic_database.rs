@@ -0,0 +1,457 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::database::db_initializer::DbInitializerReal;
+use masq_lib::logger::Logger;
+use rusqlite::{Connection, Transaction, TransactionBehavior};
+use std::path::Path;
+
+pub const TRAFFIC_SCHEMA: &str = "traffic";
+const RETIRING_TRAFFIC_SCHEMA: &str = "retiring_traffic";
+const MAIN_SCHEMA: &str = "main";
+
+// The tables that change with nearly every CORES package; config, banned and the rest stay put
+pub const TRAFFIC_TABLES: &[(&str, fn(&Connection))] = &[
+    ("payable", DbInitializerReal::create_payable_table),
+    (
+        "pending_payable",
+        DbInitializerReal::create_pending_payable_table,
+    ),
+    ("receivable", DbInitializerReal::create_receivable_table),
+    (
+        "balance_audit_log",
+        DbInitializerReal::create_balance_audit_log_table,
+    ),
+    (
+        "earnings_history",
+        DbInitializerReal::create_earnings_history_table,
+    ),
+];
+
+// SQLite looks for an unqualified table name in the main database before the attached ones, so
+// once the traffic tables are gone from the main database the DAOs find them in the attached file
+// without knowing it's there. Only a connection that may write to the database moves tables;
+// the others just attach the file the tables are in now.
+pub fn attach_traffic_database(conn: &Connection, may_relocate: bool) -> rusqlite::Result<()> {
+    let chosen_opt = read_path(conn, "traffic_db_path");
+    let in_use_opt = read_path(conn, "traffic_db_path_in_use");
+    if may_relocate && chosen_opt != in_use_opt {
+        relocate(conn, in_use_opt, chosen_opt)
+    } else if let Some(in_use) = in_use_opt {
+        // A file on a RAM disk doesn't survive a reboot; the tables start over empty
+        prepare_file(&in_use)?;
+        attach(conn, &in_use, TRAFFIC_SCHEMA)
+    } else {
+        Ok(())
+    }
+}
+
+// Databases from before the traffic tables could move don't have these rows
+fn read_path(conn: &Connection, name: &str) -> Option<String> {
+    conn.query_row("select value from config where name = ?", [name], |row| {
+        row.get::<usize, Option<String>>(0)
+    })
+    .ok()
+    .flatten()
+}
+
+fn relocate(
+    conn: &Connection,
+    from_opt: Option<String>,
+    to_opt: Option<String>,
+) -> rusqlite::Result<()> {
+    let from_schema_opt = match &from_opt {
+        None => Some(MAIN_SCHEMA),
+        Some(from) if Path::new(from).exists() => {
+            attach(conn, from, RETIRING_TRAFFIC_SCHEMA)?;
+            Some(RETIRING_TRAFFIC_SCHEMA)
+        }
+        Some(_) => None,
+    };
+    let to_schema = match &to_opt {
+        None => MAIN_SCHEMA,
+        Some(to) => {
+            prepare_file(to)?;
+            attach(conn, to, TRAFFIC_SCHEMA)?;
+            TRAFFIC_SCHEMA
+        }
+    };
+    let result = move_tables(conn, from_schema_opt, to_schema, &to_opt);
+    if from_schema_opt == Some(RETIRING_TRAFFIC_SCHEMA) {
+        conn.execute("detach database ?", [RETIRING_TRAFFIC_SCHEMA])?;
+    }
+    if result.is_ok() {
+        info!(
+            Logger::new("DbInitializer"),
+            "Traffic tables moved from {} to {}",
+            describe(&from_opt),
+            describe(&to_opt)
+        );
+    }
+    result
+}
+
+fn move_tables(
+    conn: &Connection,
+    from_schema_opt: Option<&str>,
+    to_schema: &str,
+    to_opt: &Option<String>,
+) -> rusqlite::Result<()> {
+    let txn = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?;
+    // Another connection may have moved the tables while this one was waiting for the lock
+    if read_path(&txn, "traffic_db_path_in_use") == *to_opt {
+        return Ok(());
+    }
+    if to_schema == MAIN_SCHEMA {
+        create_missing_tables(&txn)?;
+    }
+    if let Some(from_schema) = from_schema_opt {
+        for (table, _) in TRAFFIC_TABLES {
+            if !table_exists(&txn, from_schema, table)? {
+                continue;
+            }
+            // Tables that migrations have altered may not list their columns in creation order
+            let columns = column_names(&txn, from_schema, table)?.join(", ");
+            txn.execute(&format!("delete from {}.{}", to_schema, table), [])?;
+            txn.execute(
+                &format!(
+                    "insert into {}.{} ({}) select {} from {}.{}",
+                    to_schema, table, columns, columns, from_schema, table
+                ),
+                [],
+            )?;
+            txn.execute(&format!("drop table {}.{}", from_schema, table), [])?;
+        }
+    }
+    txn.execute(
+        "update config set value = ? where name = 'traffic_db_path_in_use'",
+        [to_opt],
+    )?;
+    txn.commit()
+}
+
+fn prepare_file(path: &str) -> rusqlite::Result<()> {
+    let mut file_conn = Connection::open(path)?;
+    let txn = file_conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    create_missing_tables(&txn)?;
+    txn.commit()
+}
+
+fn create_missing_tables(conn: &Connection) -> rusqlite::Result<()> {
+    for (table, create) in TRAFFIC_TABLES {
+        if !table_exists(conn, MAIN_SCHEMA, table)? {
+            create(conn)
+        }
+    }
+    Ok(())
+}
+
+fn attach(conn: &Connection, path: &str, schema: &str) -> rusqlite::Result<()> {
+    conn.execute("attach database ? as ?", [path, schema])
+        .map(|_| ())
+}
+
+fn table_exists(conn: &Connection, schema: &str, table: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        &format!(
+            "select count(*) from {}.sqlite_master where type = 'table' and name = ?",
+            schema
+        ),
+        [table],
+        |row| row.get::<usize, i64>(0),
+    )
+    .map(|count| count > 0)
+}
+
+fn column_names(conn: &Connection, schema: &str, table: &str) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("pragma {}.table_info({})", schema, table))?;
+    let names = stmt
+        .query_map([], |row| row.get::<usize, String>(1))?
+        .collect::<rusqlite::Result<Vec<String>>>();
+    names
+}
+
+fn describe(path_opt: &Option<String>) -> String {
+    match path_opt {
+        None => "the main database".to_string(),
+        Some(path) => path.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::db_initializer::DATABASE_FILE;
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use std::path::PathBuf;
+
+    fn make_main_database(home_dir: &Path) -> Connection {
+        let conn = Connection::open(home_dir.join(DATABASE_FILE)).unwrap();
+        DbInitializerReal::create_config_table(&conn);
+        conn.execute(
+            "insert into config (name, value, encrypted) values ('traffic_db_path', null, 0), \
+             ('traffic_db_path_in_use', null, 0)",
+            [],
+        )
+        .unwrap();
+        DbInitializerReal::create_banned_table(&conn);
+        TRAFFIC_TABLES.iter().for_each(|(_, create)| create(&conn));
+        conn
+    }
+
+    fn set_path(conn: &Connection, name: &str, path_opt: Option<&PathBuf>) {
+        conn.execute(
+            "update config set value = ? where name = ?",
+            [
+                path_opt.map(|path| path.to_str().unwrap().to_string()),
+                Some(name.to_string()),
+            ],
+        )
+        .unwrap();
+    }
+
+    fn insert_receivable(conn: &Connection, wallet: &str) {
+        conn.execute(
+            "insert into receivable (wallet_address, balance_high_b, balance_low_b, \
+             last_received_timestamp) values (?, 0, 1234, 5678)",
+            [wallet],
+        )
+        .unwrap();
+    }
+
+    // Unqualified, the way the DAOs ask for them
+    fn receivable_wallets(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn
+            .prepare("select wallet_address from receivable order by wallet_address")
+            .unwrap();
+        let wallets = stmt
+            .query_map([], |row| row.get::<usize, String>(0))
+            .unwrap()
+            .map(|wallet| wallet.unwrap())
+            .collect();
+        wallets
+    }
+
+    fn tables_in(conn: &Connection, schema: &str) -> Vec<String> {
+        let mut stmt = conn
+            .prepare(&format!(
+                "select name from {}.sqlite_master where type = 'table' order by name",
+                schema
+            ))
+            .unwrap();
+        let names = stmt
+            .query_map([], |row| row.get::<usize, String>(0))
+            .unwrap()
+            .map(|name| name.unwrap())
+            .collect();
+        names
+    }
+
+    fn attached_schemas(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn.prepare("pragma database_list").unwrap();
+        let names = stmt
+            .query_map([], |row| row.get::<usize, String>(1))
+            .unwrap()
+            .map(|name| name.unwrap())
+            .collect();
+        names
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(TRAFFIC_SCHEMA, "traffic");
+        assert_eq!(
+            TRAFFIC_TABLES
+                .iter()
+                .map(|(table, _)| *table)
+                .collect::<Vec<&str>>(),
+            vec![
+                "payable",
+                "pending_payable",
+                "receivable",
+                "balance_audit_log",
+                "earnings_history"
+            ]
+        );
+    }
+
+    #[test]
+    fn nothing_happens_while_the_traffic_tables_stay_in_the_main_database() {
+        let home_dir = ensure_node_home_directory_exists(
+            "traffic_database",
+            "nothing_happens_while_the_traffic_tables_stay_in_the_main_database",
+        );
+        let conn = make_main_database(&home_dir);
+        insert_receivable(&conn, "0xabc");
+
+        let result = attach_traffic_database(&conn, true);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(attached_schemas(&conn), vec!["main".to_string()]);
+        assert_eq!(receivable_wallets(&conn), vec!["0xabc".to_string()]);
+    }
+
+    #[test]
+    fn traffic_tables_move_out_of_the_main_database_with_their_data() {
+        init_test_logging();
+        let home_dir = ensure_node_home_directory_exists(
+            "traffic_database",
+            "traffic_tables_move_out_of_the_main_database_with_their_data",
+        );
+        let traffic_path = home_dir.join("traffic.db");
+        let conn = make_main_database(&home_dir);
+        insert_receivable(&conn, "0xabc");
+        insert_receivable(&conn, "0xdef");
+        set_path(&conn, "traffic_db_path", Some(&traffic_path));
+
+        let result = attach_traffic_database(&conn, true);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            tables_in(&conn, "main"),
+            vec!["banned".to_string(), "config".to_string()]
+        );
+        assert_eq!(
+            tables_in(&conn, TRAFFIC_SCHEMA),
+            vec![
+                "balance_audit_log".to_string(),
+                "earnings_history".to_string(),
+                "payable".to_string(),
+                "pending_payable".to_string(),
+                "receivable".to_string()
+            ]
+        );
+        assert_eq!(
+            receivable_wallets(&conn),
+            vec!["0xabc".to_string(), "0xdef".to_string()]
+        );
+        assert_eq!(
+            read_path(&conn, "traffic_db_path_in_use"),
+            Some(traffic_path.to_str().unwrap().to_string())
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: DbInitializer: Traffic tables moved from the main database to {}",
+            traffic_path.to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn traffic_tables_move_from_one_file_to_another_and_back_home() {
+        let home_dir = ensure_node_home_directory_exists(
+            "traffic_database",
+            "traffic_tables_move_from_one_file_to_another_and_back_home",
+        );
+        let first_path = home_dir.join("first.db");
+        let second_path = home_dir.join("second.db");
+        {
+            let conn = make_main_database(&home_dir);
+            insert_receivable(&conn, "0xabc");
+            set_path(&conn, "traffic_db_path", Some(&first_path));
+            attach_traffic_database(&conn, true).unwrap();
+        }
+        {
+            let conn = Connection::open(home_dir.join(DATABASE_FILE)).unwrap();
+            set_path(&conn, "traffic_db_path", Some(&second_path));
+
+            let result = attach_traffic_database(&conn, true);
+
+            assert_eq!(result, Ok(()));
+            assert_eq!(
+                attached_schemas(&conn),
+                vec!["main".to_string(), TRAFFIC_SCHEMA.to_string()]
+            );
+            assert_eq!(receivable_wallets(&conn), vec!["0xabc".to_string()]);
+            let first_conn = Connection::open(&first_path).unwrap();
+            assert_eq!(tables_in(&first_conn, "main"), Vec::<String>::new());
+        }
+        let conn = Connection::open(home_dir.join(DATABASE_FILE)).unwrap();
+        set_path(&conn, "traffic_db_path", None);
+
+        let result = attach_traffic_database(&conn, true);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(attached_schemas(&conn), vec!["main".to_string()]);
+        assert_eq!(receivable_wallets(&conn), vec!["0xabc".to_string()]);
+        assert_eq!(read_path(&conn, "traffic_db_path_in_use"), None);
+    }
+
+    #[test]
+    fn connection_that_may_not_relocate_attaches_the_file_in_use() {
+        let home_dir = ensure_node_home_directory_exists(
+            "traffic_database",
+            "connection_that_may_not_relocate_attaches_the_file_in_use",
+        );
+        let first_path = home_dir.join("first.db");
+        let second_path = home_dir.join("second.db");
+        {
+            let conn = make_main_database(&home_dir);
+            insert_receivable(&conn, "0xabc");
+            set_path(&conn, "traffic_db_path", Some(&first_path));
+            attach_traffic_database(&conn, true).unwrap();
+        }
+        let conn = Connection::open(home_dir.join(DATABASE_FILE)).unwrap();
+        set_path(&conn, "traffic_db_path", Some(&second_path));
+
+        let result = attach_traffic_database(&conn, false);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(receivable_wallets(&conn), vec!["0xabc".to_string()]);
+        assert_eq!(second_path.exists(), false);
+        assert_eq!(
+            read_path(&conn, "traffic_db_path_in_use"),
+            Some(first_path.to_str().unwrap().to_string())
+        );
+    }
+
+    #[test]
+    fn traffic_tables_start_over_empty_if_their_file_has_disappeared() {
+        let home_dir = ensure_node_home_directory_exists(
+            "traffic_database",
+            "traffic_tables_start_over_empty_if_their_file_has_disappeared",
+        );
+        let traffic_path = home_dir.join("traffic.db");
+        {
+            let conn = make_main_database(&home_dir);
+            insert_receivable(&conn, "0xabc");
+            set_path(&conn, "traffic_db_path", Some(&traffic_path));
+            attach_traffic_database(&conn, true).unwrap();
+        }
+        std::fs::remove_file(&traffic_path).unwrap();
+        let conn = Connection::open(home_dir.join(DATABASE_FILE)).unwrap();
+
+        let result = attach_traffic_database(&conn, true);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(tables_in(&conn, TRAFFIC_SCHEMA).len(), TRAFFIC_TABLES.len());
+        assert_eq!(receivable_wallets(&conn), Vec::<String>::new());
+        insert_receivable(&conn, "0xdef");
+        assert_eq!(receivable_wallets(&conn), vec!["0xdef".to_string()]);
+    }
+
+    #[test]
+    fn move_is_skipped_if_another_connection_has_already_made_it() {
+        let home_dir = ensure_node_home_directory_exists(
+            "traffic_database",
+            "move_is_skipped_if_another_connection_has_already_made_it",
+        );
+        let traffic_path = home_dir.join("traffic.db");
+        let traffic_path_string = Some(traffic_path.to_str().unwrap().to_string());
+        let conn = make_main_database(&home_dir);
+        insert_receivable(&conn, "0xabc");
+        set_path(&conn, "traffic_db_path_in_use", Some(&traffic_path));
+
+        let result = move_tables(
+            &conn,
+            Some(MAIN_SCHEMA),
+            TRAFFIC_SCHEMA,
+            &traffic_path_string,
+        );
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(receivable_wallets(&conn), vec!["0xabc".to_string()]);
+        assert_eq!(
+            tables_in(&conn, "main").contains(&"receivable".to_string()),
+            true
+        );
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::database::db_initializer::DbInitializerReal;
 use crate::database::rusqlite_wrappers::TransactionSafeWrapper;
+use crate::database::sqlite_pragmas::DEFAULT_SQLITE_PRAGMAS;
 use crate::db_config::config_dao::{ConfigDao, ConfigDaoError, ConfigDaoRecord};
 use crate::neighborhood::DEFAULT_MIN_HOPS;
 use crate::sub_lib::accountant::{DEFAULT_PAYMENT_THRESHOLDS, DEFAULT_SCAN_INTERVALS};
@@ -142,6 +143,21 @@ impl Default for ConfigDaoNull {
             (Some(DEFAULT_SCAN_INTERVALS.to_string()), false),
         );
         data.insert("max_block_count".to_string(), (None, false));
+        data.insert("scanned_blocks".to_string(), (None, false));
+        data.insert(
+            "sqlite_pragmas".to_string(),
+            (Some(DEFAULT_SQLITE_PRAGMAS.to_string()), false),
+        );
+        data.insert("token_contract_address".to_string(), (None, false));
+        data.insert("neighbor_notes".to_string(), (None, false));
+        data.insert(
+            "clandestine_port_rotates".to_string(),
+            (Some("false".to_string()), false),
+        );
+        data.insert("traffic_db_path".to_string(), (None, false));
+        data.insert("traffic_db_path_in_use".to_string(), (None, false));
+        data.insert("topology_snapshot".to_string(), (None, true));
+        data.insert("last_public_ip".to_string(), (None, false));
         Self { data }
     }
 }
@@ -287,6 +303,15 @@ mod tests {
                 Some(format!("{}", CURRENT_SCHEMA_VERSION).as_str()),
             ),
             ("max_block_count", None),
+            ("scanned_blocks", None),
+            ("sqlite_pragmas", Some("WAL|NORMAL|-2000|5000")),
+            ("token_contract_address", None),
+            ("neighbor_notes", None),
+            ("clandestine_port_rotates", Some("false")),
+            ("traffic_db_path", None),
+            ("traffic_db_path_in_use", None),
+            ("topology_snapshot", None),
+            ("last_public_ip", None),
         ]
         .into_iter()
         .map(|(k, v_opt)| (k.to_string(), v_opt.map(|v| v.to_string())))
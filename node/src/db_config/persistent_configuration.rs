@@ -3,25 +3,32 @@
 use crate::arbitrary_id_stamp_in_trait;
 use crate::blockchain::bip32::Bip32EncryptionKeyProvider;
 use crate::blockchain::bip39::{Bip39, Bip39Error};
+use crate::blockchain::blockchain_interface::data_structures::ScannedBlock;
 use crate::database::rusqlite_wrappers::{ConnectionWrapper, TransactionSafeWrapper};
+use crate::database::sqlite_pragmas::SqlitePragmas;
 use crate::db_config::config_dao::{ConfigDao, ConfigDaoError, ConfigDaoReal, ConfigDaoRecord};
 use crate::db_config::secure_config_layer::{SecureConfigLayer, SecureConfigLayerError};
 use crate::db_config::typed_config_layer::{
     decode_bytes, decode_combined_params, decode_u64, encode_bytes, encode_u64,
     TypedConfigLayerError,
 };
+use crate::neighborhood::topology_snapshot::TopologySnapshot;
 use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals};
 use crate::sub_lib::cryptde::PlainData;
 use crate::sub_lib::neighborhood::{Hops, NodeDescriptor, RatePack};
+use crate::sub_lib::operator_notes::OperatorNotes;
 use crate::sub_lib::wallet::Wallet;
-use masq_lib::constants::{HIGHEST_USABLE_PORT, LOWEST_USABLE_INSECURE_PORT};
+use masq_lib::constants::{
+    DB_PASSWORD_ERROR, HIGHEST_USABLE_PORT, LOWEST_USABLE_INSECURE_PORT, PARAM_DATABASE_ERROR,
+};
 use masq_lib::shared_schema::{ConfiguratorError, ParamError};
 use masq_lib::utils::NeighborhoodModeLight;
 use masq_lib::utils::{to_string, AutomapProtocol};
 use rustc_hex::{FromHex, ToHex};
 use std::fmt::Display;
-use std::net::{Ipv4Addr, SocketAddrV4, TcpListener};
+use std::net::{IpAddr, Ipv4Addr, SocketAddrV4, TcpListener};
 use std::str::FromStr;
+use web3::types::Address;
 use websocket::url::Url;
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -75,8 +82,12 @@ impl From<ConfigDaoError> for PersistentConfigError {
 
 impl PersistentConfigError {
     pub fn into_configurator_error(self, parameter: &str) -> ConfiguratorError {
+        let code = match self {
+            PersistentConfigError::PasswordError => DB_PASSWORD_ERROR,
+            _ => PARAM_DATABASE_ERROR,
+        };
         ConfiguratorError {
-            param_errors: vec![ParamError::new(parameter, &format!("{:?}", self))],
+            param_errors: vec![ParamError::new(parameter, &format!("{:?}", self), code)],
         }
     }
 }
@@ -104,12 +115,20 @@ pub trait PersistentConfiguration {
     ) -> Result<Option<String>, PersistentConfigError>;
     fn clandestine_port(&self) -> Result<u16, PersistentConfigError>;
     fn set_clandestine_port(&mut self, port: u16) -> Result<(), PersistentConfigError>;
+    // True if the operator asked for a fresh random clandestine port every time the Node starts
+    fn clandestine_port_rotates(&self) -> Result<bool, PersistentConfigError>;
+    fn set_clandestine_port_rotates(&mut self, rotates: bool) -> Result<(), PersistentConfigError>;
     // WARNING: Actors should get earning-wallet information from their startup config, not from here
     fn earning_wallet(&self) -> Result<Option<Wallet>, PersistentConfigError>;
     // WARNING: Actors should get earning-wallet information from their startup config, not from here
     fn earning_wallet_address(&self) -> Result<Option<String>, PersistentConfigError>;
     fn gas_price(&self) -> Result<u64, PersistentConfigError>;
     fn set_gas_price(&mut self, gas_price: u64) -> Result<(), PersistentConfigError>;
+    fn last_public_ip(&self) -> Result<Option<IpAddr>, PersistentConfigError>;
+    fn set_last_public_ip(
+        &mut self,
+        value_opt: Option<IpAddr>,
+    ) -> Result<(), PersistentConfigError>;
     fn mapping_protocol(&self) -> Result<Option<AutomapProtocol>, PersistentConfigError>;
     fn set_mapping_protocol(
         &mut self,
@@ -131,6 +150,15 @@ pub trait PersistentConfiguration {
         node_descriptors_opt: Option<Vec<NodeDescriptor>>,
         db_password: &str,
     ) -> Result<(), PersistentConfigError>;
+    fn topology_snapshot(
+        &self,
+        db_password: &str,
+    ) -> Result<Option<TopologySnapshot>, PersistentConfigError>;
+    fn set_topology_snapshot(
+        &mut self,
+        snapshot_opt: Option<TopologySnapshot>,
+        db_password: &str,
+    ) -> Result<(), PersistentConfigError>;
     fn start_block(&self) -> Result<Option<u64>, PersistentConfigError>;
     fn set_start_block(&mut self, value_opt: Option<u64>) -> Result<(), PersistentConfigError>;
     fn max_block_count(&self) -> Result<Option<u64>, PersistentConfigError>;
@@ -140,6 +168,14 @@ pub trait PersistentConfiguration {
         value_opt: Option<u64>,
         transaction: &mut TransactionSafeWrapper,
     ) -> Result<(), PersistentConfigError>;
+    // Oldest first
+    fn scanned_blocks(&self) -> Result<Vec<ScannedBlock>, PersistentConfigError>;
+    fn set_scanned_blocks(&mut self, blocks: &[ScannedBlock]) -> Result<(), PersistentConfigError>;
+    fn set_scanned_blocks_from_txn(
+        &mut self,
+        blocks: &[ScannedBlock],
+        transaction: &mut TransactionSafeWrapper,
+    ) -> Result<(), PersistentConfigError>;
     fn set_wallet_info(
         &mut self,
         consuming_wallet_private_key: &str,
@@ -152,6 +188,25 @@ pub trait PersistentConfiguration {
     fn set_rate_pack(&mut self, rate_pack: String) -> Result<(), PersistentConfigError>;
     fn scan_intervals(&self) -> Result<ScanIntervals, PersistentConfigError>;
     fn set_scan_intervals(&mut self, intervals: String) -> Result<(), PersistentConfigError>;
+    // Applied by the DbInitializer to every connection it opens afterwards
+    fn sqlite_pragmas(&self) -> Result<SqlitePragmas, PersistentConfigError>;
+    fn set_sqlite_pragmas(&mut self, pragmas: SqlitePragmas) -> Result<(), PersistentConfigError>;
+    // Overrides the chain's own token contract; the database belongs to a single chain
+    fn token_contract_address(&self) -> Result<Option<Address>, PersistentConfigError>;
+    fn set_token_contract_address(&mut self, address: Address)
+        -> Result<(), PersistentConfigError>;
+    // Signed notes that came with neighbors' descriptors; nothing secret, so not encrypted
+    fn neighbor_notes(&self) -> Result<Vec<OperatorNotes>, PersistentConfigError>;
+    fn set_neighbor_notes(
+        &mut self,
+        notes: Vec<OperatorNotes>,
+    ) -> Result<(), PersistentConfigError>;
+    // Where the DbInitializer should keep the traffic tables; None for the main database
+    fn traffic_db_path(&self) -> Result<Option<String>, PersistentConfigError>;
+    fn set_traffic_db_path(
+        &mut self,
+        path_opt: Option<String>,
+    ) -> Result<(), PersistentConfigError>;
 
     arbitrary_id_stamp_in_trait!();
 }
@@ -292,6 +347,24 @@ impl PersistentConfiguration for PersistentConfigurationReal {
             .set("clandestine_port", encode_u64(Some(u64::from(port)))?)?)
     }
 
+    fn clandestine_port_rotates(&self) -> Result<bool, PersistentConfigError> {
+        match self.get("clandestine_port_rotates")? {
+            None => Self::missing_value_panic("clandestine_port_rotates"),
+            Some(rotates) => rotates.parse::<bool>().map_err(|_| {
+                PersistentConfigError::UninterpretableValue(format!(
+                    "Clandestine port rotation should be true or false, not '{}'",
+                    rotates
+                ))
+            }),
+        }
+    }
+
+    fn set_clandestine_port_rotates(&mut self, rotates: bool) -> Result<(), PersistentConfigError> {
+        Ok(self
+            .dao
+            .set("clandestine_port_rotates", Some(rotates.to_string()))?)
+    }
+
     fn earning_wallet(&self) -> Result<Option<Wallet>, PersistentConfigError> {
         match self.earning_wallet_address()? {
             None => Ok(None),
@@ -322,6 +395,26 @@ impl PersistentConfiguration for PersistentConfigurationReal {
         self.simple_set_method("gas_price", gas_price)
     }
 
+    fn last_public_ip(&self) -> Result<Option<IpAddr>, PersistentConfigError> {
+        match self.get("last_public_ip")? {
+            None => Ok(None),
+            Some(val) => match IpAddr::from_str(&val) {
+                Ok(ip) => Ok(Some(ip)),
+                Err(e) => Err(PersistentConfigError::DatabaseError(format!(
+                    "Illegal last_public_ip value '{}': {}",
+                    val, e
+                ))),
+            },
+        }
+    }
+
+    fn set_last_public_ip(
+        &mut self,
+        value_opt: Option<IpAddr>,
+    ) -> Result<(), PersistentConfigError> {
+        Ok(self.dao.set("last_public_ip", value_opt.map(to_string))?)
+    }
+
     fn mapping_protocol(&self) -> Result<Option<AutomapProtocol>, PersistentConfigError> {
         let result = self
             .get("mapping_protocol")?
@@ -406,6 +499,49 @@ impl PersistentConfiguration for PersistentConfigurationReal {
         )?)
     }
 
+    // Unlike past neighbors, a snapshot is only an optimization: one that can't be read is reported
+    // rather than fatal, and the Node simply starts from Gossip
+    fn topology_snapshot(
+        &self,
+        db_password: &str,
+    ) -> Result<Option<TopologySnapshot>, PersistentConfigError> {
+        let bytes_opt = decode_bytes(self.scl.decrypt(
+            self.get_record("topology_snapshot")?,
+            Some(db_password.to_string()),
+            &self.dao,
+        )?)?;
+        match bytes_opt {
+            None => Ok(None),
+            Some(bytes) => serde_cbor::de::from_slice::<TopologySnapshot>(bytes.as_slice())
+                .map(Some)
+                .map_err(|e| {
+                    PersistentConfigError::UninterpretableValue(format!(
+                        "Topology snapshot cannot be deserialized: {}",
+                        e
+                    ))
+                }),
+        }
+    }
+
+    fn set_topology_snapshot(
+        &mut self,
+        snapshot_opt: Option<TopologySnapshot>,
+        db_password: &str,
+    ) -> Result<(), PersistentConfigError> {
+        let plain_data_opt = snapshot_opt.map(|snapshot| {
+            PlainData::new(&serde_cbor::ser::to_vec(&snapshot).expect("Serialization failed"))
+        });
+        Ok(self.dao.set(
+            "topology_snapshot",
+            self.scl.encrypt(
+                "topology_snapshot",
+                encode_bytes(plain_data_opt)?,
+                Some(db_password.to_string()),
+                &self.dao,
+            )?,
+        )?)
+    }
+
     fn start_block(&self) -> Result<Option<u64>, PersistentConfigError> {
         Ok(decode_u64(self.get("start_block")?)?)
     }
@@ -430,6 +566,37 @@ impl PersistentConfiguration for PersistentConfigurationReal {
         self.simple_set_method_from_provided_txn("start_block", value_opt, transaction)
     }
 
+    fn scanned_blocks(&self) -> Result<Vec<ScannedBlock>, PersistentConfigError> {
+        match self.get("scanned_blocks")? {
+            None => Ok(vec![]),
+            Some(blocks) => blocks
+                .split(',')
+                .map(|block| {
+                    ScannedBlock::from_str(block)
+                        .map_err(PersistentConfigError::UninterpretableValue)
+                })
+                .collect(),
+        }
+    }
+
+    fn set_scanned_blocks(&mut self, blocks: &[ScannedBlock]) -> Result<(), PersistentConfigError> {
+        Ok(self
+            .dao
+            .set("scanned_blocks", Self::encode_scanned_blocks(blocks))?)
+    }
+
+    fn set_scanned_blocks_from_txn(
+        &mut self,
+        blocks: &[ScannedBlock],
+        transaction: &mut TransactionSafeWrapper,
+    ) -> Result<(), PersistentConfigError> {
+        Ok(self.dao.set_by_guest_transaction(
+            transaction,
+            "scanned_blocks",
+            Self::encode_scanned_blocks(blocks),
+        )?)
+    }
+
     fn set_wallet_info(
         &mut self,
         consuming_wallet_private_key: &str,
@@ -504,6 +671,72 @@ impl PersistentConfiguration for PersistentConfigurationReal {
     fn set_scan_intervals(&mut self, intervals: String) -> Result<(), PersistentConfigError> {
         self.simple_set_method("scan_intervals", intervals)
     }
+
+    fn sqlite_pragmas(&self) -> Result<SqlitePragmas, PersistentConfigError> {
+        let result = self
+            .get("sqlite_pragmas")?
+            .map(|val| SqlitePragmas::from_str(&val));
+        match result {
+            None => Self::missing_value_panic("sqlite_pragmas"),
+            Some(Ok(pragmas)) => Ok(pragmas),
+            Some(Err(msg)) => Err(PersistentConfigError::DatabaseError(msg)),
+        }
+    }
+
+    fn set_sqlite_pragmas(&mut self, pragmas: SqlitePragmas) -> Result<(), PersistentConfigError> {
+        Ok(self.dao.set("sqlite_pragmas", Some(pragmas.to_string()))?)
+    }
+
+    fn token_contract_address(&self) -> Result<Option<Address>, PersistentConfigError> {
+        match self.get("token_contract_address")? {
+            None => Ok(None),
+            Some(address) => match address.trim_start_matches("0x").parse::<Address>() {
+                Ok(address) => Ok(Some(address)),
+                Err(_) => Err(PersistentConfigError::BadAddressFormat(address)),
+            },
+        }
+    }
+
+    fn set_token_contract_address(
+        &mut self,
+        address: Address,
+    ) -> Result<(), PersistentConfigError> {
+        Ok(self
+            .dao
+            .set("token_contract_address", Some(format!("{:?}", address)))?)
+    }
+
+    fn neighbor_notes(&self) -> Result<Vec<OperatorNotes>, PersistentConfigError> {
+        match decode_bytes(self.get("neighbor_notes")?)? {
+            None => Ok(vec![]),
+            Some(bytes) => serde_cbor::de::from_slice::<Vec<OperatorNotes>>(bytes.as_slice())
+                .map_err(|e| {
+                    PersistentConfigError::UninterpretableValue(format!(
+                        "Neighbor notes are corrupt: {}",
+                        e
+                    ))
+                }),
+        }
+    }
+
+    fn set_neighbor_notes(
+        &mut self,
+        notes: Vec<OperatorNotes>,
+    ) -> Result<(), PersistentConfigError> {
+        let bytes = PlainData::new(&serde_cbor::ser::to_vec(&notes).expect("Serialization failed"));
+        Ok(self.dao.set("neighbor_notes", encode_bytes(Some(bytes))?)?)
+    }
+
+    fn traffic_db_path(&self) -> Result<Option<String>, PersistentConfigError> {
+        self.get("traffic_db_path")
+    }
+
+    fn set_traffic_db_path(
+        &mut self,
+        path_opt: Option<String>,
+    ) -> Result<(), PersistentConfigError> {
+        Ok(self.dao.set("traffic_db_path", path_opt)?)
+    }
 }
 
 impl From<Box<dyn ConnectionWrapper>> for PersistentConfigurationReal {
@@ -578,6 +811,20 @@ impl PersistentConfigurationReal {
         )?)
     }
 
+    fn encode_scanned_blocks(blocks: &[ScannedBlock]) -> Option<String> {
+        if blocks.is_empty() {
+            None
+        } else {
+            Some(
+                blocks
+                    .iter()
+                    .map(|block| block.to_string())
+                    .collect::<Vec<String>>()
+                    .join(","),
+            )
+        }
+    }
+
     fn combined_params_get_method<'a, T, C>(
         &'a self,
         values_parser: C,
@@ -607,11 +854,14 @@ mod tests {
     use crate::database::db_initializer::{
         DbInitializationConfig, DbInitializer, DbInitializerReal,
     };
+    use crate::database::sqlite_pragmas::{JournalMode, SynchronousLevel, DEFAULT_SQLITE_PRAGMAS};
     use crate::database::test_utils::transaction_wrapper_mock::TransactionInnerWrapperMockBuilder;
     use crate::db_config::config_dao::ConfigDaoRecord;
     use crate::db_config::mocks::ConfigDaoMock;
     use crate::db_config::secure_config_layer::EXAMPLE_ENCRYPTED;
+    use crate::neighborhood::gossip::GossipNodeRecord;
     use crate::test_utils::main_cryptde;
+    use crate::test_utils::neighborhood_test_utils::make_node_record;
     use crate::test_utils::unshared_test_utils::arbitrary_id_stamp::ArbitraryIdStamp;
     use bip39::{Language, MnemonicType};
     use lazy_static::lazy_static;
@@ -623,6 +873,7 @@ mod tests {
     use std::sync::{Arc, Mutex};
     use std::time::Duration;
     use tiny_hderive::bip32::ExtendedPrivKey;
+    use web3::types::H256;
 
     lazy_static! {
         static ref CONFIG_TABLE_PARAMETERS: Vec<String> = list_of_config_parameters();
@@ -988,6 +1239,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn clandestine_port_rotates_works() {
+        let get_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = Box::new(
+            ConfigDaoMock::new()
+                .get_params(&get_params_arc)
+                .get_result(Ok(ConfigDaoRecord::new(
+                    "clandestine_port_rotates",
+                    Some("true"),
+                    false,
+                ))),
+        );
+        let subject = PersistentConfigurationReal::new(config_dao);
+
+        let result = subject.clandestine_port_rotates();
+
+        assert_eq!(result, Ok(true));
+        let get_params = get_params_arc.lock().unwrap();
+        assert_eq!(*get_params, vec!["clandestine_port_rotates".to_string()]);
+    }
+
+    #[test]
+    fn clandestine_port_rotates_complains_about_uninterpretable_value() {
+        let config_dao = Box::new(ConfigDaoMock::new().get_result(Ok(ConfigDaoRecord::new(
+            "clandestine_port_rotates",
+            Some("sometimes"),
+            false,
+        ))));
+        let subject = PersistentConfigurationReal::new(config_dao);
+
+        let result = subject.clandestine_port_rotates();
+
+        assert_eq!(
+            result,
+            Err(PersistentConfigError::UninterpretableValue(
+                "Clandestine port rotation should be true or false, not 'sometimes'".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "ever-supplied value missing: clandestine_port_rotates; database is corrupt!"
+    )]
+    fn clandestine_port_rotates_panics_if_none_got_from_database() {
+        let config_dao = Box::new(ConfigDaoMock::new().get_result(Ok(ConfigDaoRecord::new(
+            "clandestine_port_rotates",
+            None,
+            false,
+        ))));
+        let subject = PersistentConfigurationReal::new(config_dao);
+
+        let _ = subject.clandestine_port_rotates();
+    }
+
+    #[test]
+    fn set_clandestine_port_rotates_works() {
+        let set_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = Box::new(
+            ConfigDaoMock::new()
+                .set_params(&set_params_arc)
+                .set_result(Ok(())),
+        );
+        let mut subject = PersistentConfigurationReal::new(config_dao);
+
+        let result = subject.set_clandestine_port_rotates(true);
+
+        assert_eq!(result, Ok(()));
+        let set_params = set_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_params,
+            vec![(
+                "clandestine_port_rotates".to_string(),
+                Some("true".to_string())
+            )]
+        );
+    }
+
     #[test]
     fn consuming_wallet_private_key_when_password_is_wrong() {
         let get_params_arc = Arc::new(Mutex::new(vec![]));
@@ -1555,6 +1884,123 @@ mod tests {
         )
     }
 
+    #[test]
+    fn scanned_blocks_are_read_oldest_first() {
+        let config_dao = Box::new(ConfigDaoMock::new().get_result(Ok(ConfigDaoRecord::new(
+            "scanned_blocks",
+            Some(
+                "1000:0x0000000000000000000000000000000000000000000000000000000000000001,\
+                1010:0x0000000000000000000000000000000000000000000000000000000000000002",
+            ),
+            false,
+        ))));
+        let subject = PersistentConfigurationReal::new(config_dao);
+
+        let result = subject.scanned_blocks();
+
+        assert_eq!(
+            result,
+            Ok(vec![
+                ScannedBlock {
+                    number: 1000,
+                    hash: H256::from_low_u64_be(1)
+                },
+                ScannedBlock {
+                    number: 1010,
+                    hash: H256::from_low_u64_be(2)
+                }
+            ])
+        );
+    }
+
+    #[test]
+    fn scanned_blocks_can_be_empty() {
+        let config_dao = Box::new(ConfigDaoMock::new().get_result(Ok(ConfigDaoRecord::new(
+            "scanned_blocks",
+            None,
+            false,
+        ))));
+        let subject = PersistentConfigurationReal::new(config_dao);
+
+        let result = subject.scanned_blocks();
+
+        assert_eq!(result, Ok(vec![]));
+    }
+
+    #[test]
+    fn scanned_blocks_complains_about_garbage() {
+        let config_dao = Box::new(ConfigDaoMock::new().get_result(Ok(ConfigDaoRecord::new(
+            "scanned_blocks",
+            Some("booga"),
+            false,
+        ))));
+        let subject = PersistentConfigurationReal::new(config_dao);
+
+        let result = subject.scanned_blocks();
+
+        assert_eq!(
+            result,
+            Err(PersistentConfigError::UninterpretableValue(
+                "Scanned block 'booga' is missing a colon".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn set_scanned_blocks_writes_none_for_an_empty_list() {
+        let set_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = Box::new(
+            ConfigDaoMock::new()
+                .set_params(&set_params_arc)
+                .set_result(Ok(())),
+        );
+        let mut subject = PersistentConfigurationReal::new(config_dao);
+
+        let result = subject.set_scanned_blocks(&[]);
+
+        assert_eq!(result, Ok(()));
+        let set_params = set_params_arc.lock().unwrap();
+        assert_eq!(*set_params, vec![("scanned_blocks".to_string(), None)])
+    }
+
+    #[test]
+    fn set_scanned_blocks_from_txn_success() {
+        let set_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = Box::new(
+            ConfigDaoMock::new()
+                .set_by_guest_transaction_params(&set_params_arc)
+                .set_by_guest_transaction_result(Ok(())),
+        );
+        let txn_id = ArbitraryIdStamp::new();
+        let txn_inner_builder =
+            TransactionInnerWrapperMockBuilder::default().set_arbitrary_id_stamp(txn_id);
+        let mut txn = TransactionSafeWrapper::new_with_builder(txn_inner_builder);
+        let mut subject = PersistentConfigurationReal::new(config_dao);
+        let blocks = vec![
+            ScannedBlock {
+                number: 1000,
+                hash: H256::from_low_u64_be(1),
+            },
+            ScannedBlock {
+                number: 1010,
+                hash: H256::from_low_u64_be(2),
+            },
+        ];
+
+        let result = subject.set_scanned_blocks_from_txn(&blocks, &mut txn);
+
+        assert_eq!(result, Ok(()));
+        let set_params = set_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_params,
+            vec![(
+                txn_id,
+                "scanned_blocks".to_string(),
+                Some(format!("{},{}", blocks[0], blocks[1]))
+            )]
+        )
+    }
+
     #[test]
     fn set_start_block_success_with_none() {
         let set_params_arc = Arc::new(Mutex::new(vec![]));
@@ -1711,16 +2157,198 @@ mod tests {
         assert_eq!(set_params.len(), 1);
     }
 
+    fn make_topology_snapshot() -> TopologySnapshot {
+        let node_record = make_node_record(2345, true);
+        TopologySnapshot {
+            taken_at: 1_700_000_000,
+            root_neighbors: vec![node_record.public_key().clone()],
+            node_records: vec![GossipNodeRecord::from(node_record)],
+        }
+    }
+
     #[test]
-    fn mapping_protocol_works() {
+    fn topology_snapshot_success() {
+        let example = "Aside from that, Mrs. Lincoln, how was the play?".as_bytes();
+        let example_encrypted = Bip39::encrypt_bytes(&example, "password").unwrap();
+        let snapshot = make_topology_snapshot();
+        let snapshot_bytes = PlainData::new(&serde_cbor::ser::to_vec(&snapshot).unwrap());
+        let snapshot_string = encode_bytes(Some(snapshot_bytes)).unwrap().unwrap();
+        let snapshot_enc = Bip39::encrypt_bytes(&snapshot_string.as_bytes(), "password").unwrap();
         let get_params_arc = Arc::new(Mutex::new(vec![]));
-        let config_dao = ConfigDaoMock::new()
-            .get_params(&get_params_arc)
-            .get_result(Ok(ConfigDaoRecord::new(
-                "mapping_protocol",
-                Some("PCP"),
-                false,
-            )));
+        let config_dao = Box::new(
+            ConfigDaoMock::new()
+                .get_params(&get_params_arc)
+                .get_result(Ok(ConfigDaoRecord::new(
+                    "topology_snapshot",
+                    Some(&snapshot_enc),
+                    true,
+                )))
+                .get_result(Ok(ConfigDaoRecord::new(
+                    EXAMPLE_ENCRYPTED,
+                    Some(&example_encrypted),
+                    true,
+                ))),
+        );
+        let subject = PersistentConfigurationReal::new(config_dao);
+
+        let result = subject.topology_snapshot("password").unwrap();
+
+        assert_eq!(result, Some(snapshot));
+        let get_params = get_params_arc.lock().unwrap();
+        assert_eq!(
+            *get_params,
+            vec![
+                "topology_snapshot".to_string(),
+                EXAMPLE_ENCRYPTED.to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn topology_snapshot_that_cannot_be_deserialized_is_reported() {
+        let example = "Aside from that, Mrs. Lincoln, how was the play?".as_bytes();
+        let example_encrypted = Bip39::encrypt_bytes(&example, "password").unwrap();
+        let garbage_string = encode_bytes(Some(PlainData::new(b"garbage")))
+            .unwrap()
+            .unwrap();
+        let garbage_enc = Bip39::encrypt_bytes(&garbage_string.as_bytes(), "password").unwrap();
+        let config_dao = Box::new(
+            ConfigDaoMock::new()
+                .get_result(Ok(ConfigDaoRecord::new(
+                    "topology_snapshot",
+                    Some(&garbage_enc),
+                    true,
+                )))
+                .get_result(Ok(ConfigDaoRecord::new(
+                    EXAMPLE_ENCRYPTED,
+                    Some(&example_encrypted),
+                    true,
+                ))),
+        );
+        let subject = PersistentConfigurationReal::new(config_dao);
+
+        let result = subject.topology_snapshot("password");
+
+        match result {
+            Err(PersistentConfigError::UninterpretableValue(msg)) => assert!(
+                msg.starts_with("Topology snapshot cannot be deserialized: "),
+                "{}",
+                msg
+            ),
+            x => panic!("Expected UninterpretableValue, got {:?}", x),
+        }
+    }
+
+    #[test]
+    fn set_topology_snapshot_success() {
+        let example = "Aside from that, Mrs. Lincoln, how was the play?".as_bytes();
+        let example_encrypted = Bip39::encrypt_bytes(&example, "password").unwrap();
+        let snapshot = make_topology_snapshot();
+        let set_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = Box::new(
+            ConfigDaoMock::new()
+                .get_result(Ok(ConfigDaoRecord::new(
+                    EXAMPLE_ENCRYPTED,
+                    Some(&example_encrypted),
+                    true,
+                )))
+                .get_result(Ok(ConfigDaoRecord::new(
+                    "topology_snapshot",
+                    Some("irrelevant"),
+                    true,
+                )))
+                .set_params(&set_params_arc)
+                .set_result(Ok(())),
+        );
+        let mut subject = PersistentConfigurationReal::new(config_dao);
+
+        subject
+            .set_topology_snapshot(Some(snapshot.clone()), "password")
+            .unwrap();
+
+        let set_params = set_params_arc.lock().unwrap();
+        assert_eq!(set_params.len(), 1);
+        assert_eq!(set_params[0].0, "topology_snapshot".to_string());
+        let encrypted_serialized_snapshot = set_params[0].1.clone().unwrap();
+        let encoded_serialized_snapshot =
+            Bip39::decrypt_bytes(&encrypted_serialized_snapshot, "password").unwrap();
+        let serialized_snapshot = decode_bytes(Some(
+            String::from_utf8(encoded_serialized_snapshot.into()).unwrap(),
+        ))
+        .unwrap()
+        .unwrap();
+        let actual_snapshot =
+            serde_cbor::de::from_slice::<TopologySnapshot>(&serialized_snapshot.as_slice())
+                .unwrap();
+        assert_eq!(actual_snapshot, snapshot);
+    }
+
+    #[test]
+    fn last_public_ip_works() {
+        let get_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = ConfigDaoMock::new()
+            .get_params(&get_params_arc)
+            .get_result(Ok(ConfigDaoRecord::new(
+                "last_public_ip",
+                Some("1.2.3.4"),
+                false,
+            )));
+        let subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.last_public_ip().unwrap();
+
+        assert_eq!(result, Some(IpAddr::from_str("1.2.3.4").unwrap()));
+        let get_params = get_params_arc.lock().unwrap();
+        assert_eq!(*get_params, vec!["last_public_ip".to_string()]);
+    }
+
+    #[test]
+    fn last_public_ip_complains_about_garbage() {
+        let config_dao = ConfigDaoMock::new().get_result(Ok(ConfigDaoRecord::new(
+            "last_public_ip",
+            Some("booga"),
+            false,
+        )));
+        let subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.last_public_ip();
+
+        assert_eq!(
+            result,
+            Err(PersistentConfigError::DatabaseError(
+                "Illegal last_public_ip value 'booga': invalid IP address syntax".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn set_last_public_ip_to_some() {
+        let set_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = ConfigDaoMock::new()
+            .set_params(&set_params_arc)
+            .set_result(Ok(()));
+        let mut subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.set_last_public_ip(Some(IpAddr::from_str("4.3.2.1").unwrap()));
+
+        assert!(result.is_ok());
+        let set_params = set_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_params,
+            vec![("last_public_ip".to_string(), Some("4.3.2.1".to_string()))]
+        );
+    }
+
+    #[test]
+    fn mapping_protocol_works() {
+        let get_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = ConfigDaoMock::new()
+            .get_params(&get_params_arc)
+            .get_result(Ok(ConfigDaoRecord::new(
+                "mapping_protocol",
+                Some("PCP"),
+                false,
+            )));
         let subject = PersistentConfigurationReal::new(Box::new(config_dao));
 
         let result = subject.mapping_protocol().unwrap();
@@ -1806,6 +2434,287 @@ mod tests {
         let _result = subject.min_hops();
     }
 
+    #[test]
+    fn sqlite_pragmas_works() {
+        let get_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = ConfigDaoMock::new()
+            .get_params(&get_params_arc)
+            .get_result(Ok(ConfigDaoRecord::new(
+                "sqlite_pragmas",
+                Some("TRUNCATE|FULL|4096|250"),
+                false,
+            )));
+        let subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.sqlite_pragmas();
+
+        assert_eq!(
+            result,
+            Ok(SqlitePragmas {
+                journal_mode: JournalMode::Truncate,
+                synchronous: SynchronousLevel::Full,
+                cache_size: 4096,
+                busy_timeout_ms: 250,
+            })
+        );
+        let get_params = get_params_arc.lock().unwrap();
+        assert_eq!(*get_params, vec!["sqlite_pragmas".to_string()]);
+    }
+
+    #[test]
+    fn sqlite_pragmas_complains_about_uninterpretable_value() {
+        let config_dao = ConfigDaoMock::new().get_result(Ok(ConfigDaoRecord::new(
+            "sqlite_pragmas",
+            Some("WAL|NORMAL"),
+            false,
+        )));
+        let subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.sqlite_pragmas();
+
+        assert_eq!(
+            result,
+            Err(PersistentConfigError::DatabaseError(
+                "Bad db-pragmas value 'WAL|NORMAL': expected <journal mode>|<synchronous level>|<cache size>|<busy timeout in ms>, like WAL|NORMAL|-2000|5000".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn set_sqlite_pragmas_works() {
+        let set_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = ConfigDaoMock::new()
+            .set_params(&set_params_arc)
+            .set_result(Ok(()));
+        let mut subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.set_sqlite_pragmas(DEFAULT_SQLITE_PRAGMAS);
+
+        assert_eq!(result, Ok(()));
+        let set_params = set_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_params,
+            vec![(
+                "sqlite_pragmas".to_string(),
+                Some("WAL|NORMAL|-2000|5000".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn token_contract_address_works() {
+        let get_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = ConfigDaoMock::new()
+            .get_params(&get_params_arc)
+            .get_result(Ok(ConfigDaoRecord::new(
+                "token_contract_address",
+                Some("0x0123456789abcdef0123456789abcdef01234567"),
+                false,
+            )));
+        let subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.token_contract_address();
+
+        assert_eq!(
+            result,
+            Ok(Some(
+                Address::from_str("0123456789abcdef0123456789abcdef01234567").unwrap()
+            ))
+        );
+        let get_params = get_params_arc.lock().unwrap();
+        assert_eq!(*get_params, vec!["token_contract_address".to_string()]);
+    }
+
+    #[test]
+    fn token_contract_address_allows_none_value() {
+        let config_dao = ConfigDaoMock::new().get_result(Ok(ConfigDaoRecord::new(
+            "token_contract_address",
+            None,
+            false,
+        )));
+        let subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.token_contract_address();
+
+        assert_eq!(result, Ok(None));
+    }
+
+    #[test]
+    fn token_contract_address_complains_about_uninterpretable_value() {
+        let config_dao = ConfigDaoMock::new().get_result(Ok(ConfigDaoRecord::new(
+            "token_contract_address",
+            Some("booga"),
+            false,
+        )));
+        let subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.token_contract_address();
+
+        assert_eq!(
+            result,
+            Err(PersistentConfigError::BadAddressFormat("booga".to_string()))
+        );
+    }
+
+    #[test]
+    fn set_token_contract_address_works() {
+        let set_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = ConfigDaoMock::new()
+            .set_params(&set_params_arc)
+            .set_result(Ok(()));
+        let mut subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.set_token_contract_address(
+            Address::from_str("0123456789ABCDEF0123456789ABCDEF01234567").unwrap(),
+        );
+
+        assert_eq!(result, Ok(()));
+        let set_params = set_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_params,
+            vec![(
+                "token_contract_address".to_string(),
+                Some("0x0123456789abcdef0123456789abcdef01234567".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn traffic_db_path_works() {
+        let get_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = ConfigDaoMock::new()
+            .get_params(&get_params_arc)
+            .get_result(Ok(ConfigDaoRecord::new(
+                "traffic_db_path",
+                Some("/mnt/ramdisk/traffic.db"),
+                false,
+            )))
+            .get_result(Ok(ConfigDaoRecord::new("traffic_db_path", None, false)));
+        let subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let file_result = subject.traffic_db_path();
+        let main_result = subject.traffic_db_path();
+
+        assert_eq!(file_result, Ok(Some("/mnt/ramdisk/traffic.db".to_string())));
+        assert_eq!(main_result, Ok(None));
+        let get_params = get_params_arc.lock().unwrap();
+        assert_eq!(
+            *get_params,
+            vec!["traffic_db_path".to_string(), "traffic_db_path".to_string()]
+        );
+    }
+
+    #[test]
+    fn set_traffic_db_path_works() {
+        let set_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = ConfigDaoMock::new()
+            .set_params(&set_params_arc)
+            .set_result(Ok(()))
+            .set_result(Ok(()));
+        let mut subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let file_result = subject.set_traffic_db_path(Some("/mnt/ramdisk/traffic.db".to_string()));
+        let main_result = subject.set_traffic_db_path(None);
+
+        assert_eq!(file_result, Ok(()));
+        assert_eq!(main_result, Ok(()));
+        let set_params = set_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_params,
+            vec![
+                (
+                    "traffic_db_path".to_string(),
+                    Some("/mnt/ramdisk/traffic.db".to_string())
+                ),
+                ("traffic_db_path".to_string(), None)
+            ]
+        );
+    }
+
+    #[test]
+    fn neighbor_notes_works() {
+        let notes = vec![
+            OperatorNotes::sign(main_cryptde(), "Run by alice"),
+            OperatorNotes::sign(main_cryptde(), "Run by bob"),
+        ];
+        let notes_string = encode_bytes(Some(PlainData::new(
+            &serde_cbor::ser::to_vec(&notes).unwrap(),
+        )))
+        .unwrap()
+        .unwrap();
+        let get_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = ConfigDaoMock::new()
+            .get_params(&get_params_arc)
+            .get_result(Ok(ConfigDaoRecord::new(
+                "neighbor_notes",
+                Some(&notes_string),
+                false,
+            )));
+        let subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.neighbor_notes();
+
+        assert_eq!(result, Ok(notes));
+        let get_params = get_params_arc.lock().unwrap();
+        assert_eq!(*get_params, vec!["neighbor_notes".to_string()]);
+    }
+
+    #[test]
+    fn neighbor_notes_allows_none_value() {
+        let config_dao = ConfigDaoMock::new().get_result(Ok(ConfigDaoRecord::new(
+            "neighbor_notes",
+            None,
+            false,
+        )));
+        let subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.neighbor_notes();
+
+        assert_eq!(result, Ok(vec![]));
+    }
+
+    #[test]
+    fn neighbor_notes_complains_about_uninterpretable_value() {
+        let config_dao = ConfigDaoMock::new().get_result(Ok(ConfigDaoRecord::new(
+            "neighbor_notes",
+            Some("0102"),
+            false,
+        )));
+        let subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.neighbor_notes();
+
+        match result {
+            Err(PersistentConfigError::UninterpretableValue(msg)) => {
+                assert!(msg.starts_with("Neighbor notes are corrupt: "), "{}", msg)
+            }
+            x => panic!("Expected UninterpretableValue, got {:?}", x),
+        }
+    }
+
+    #[test]
+    fn set_neighbor_notes_works() {
+        let notes = vec![OperatorNotes::sign(main_cryptde(), "Run by alice")];
+        let set_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = ConfigDaoMock::new()
+            .set_params(&set_params_arc)
+            .set_result(Ok(()));
+        let mut subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.set_neighbor_notes(notes.clone());
+
+        assert_eq!(result, Ok(()));
+        let set_params = set_params_arc.lock().unwrap();
+        let expected_value = encode_bytes(Some(PlainData::new(
+            &serde_cbor::ser::to_vec(&notes).unwrap(),
+        )))
+        .unwrap();
+        assert_eq!(
+            *set_params,
+            vec![("neighbor_notes".to_string(), expected_value)]
+        );
+    }
+
     #[test]
     fn neighborhood_mode_works() {
         let get_params_arc = Arc::new(Mutex::new(vec![]));
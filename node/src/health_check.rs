@@ -0,0 +1,445 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::database::rusqlite_wrappers::ConnectionWrapper;
+use crate::sub_lib::health_check::{GossipHeartbeat, HealthCheckSubs};
+use crate::sub_lib::peer_actors::BindMessage;
+use crate::sub_lib::utils::NODE_MAILBOX_CAPACITY;
+use actix::{Actor, Addr, AsyncContext, Context, Handler, Message};
+use masq_lib::logger::Logger;
+use masq_lib::utils::localhost;
+use serde_derive::Serialize;
+use std::io;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+pub const HEALTH_CHECK_TICK_INTERVAL: Duration = Duration::from_secs(5);
+// A Node whose HealthCheck actor hasn't been given a chance to tick for this long has an actor
+// system that isn't scheduling work anymore.
+pub const EVENT_LOOP_STALL_LIMIT: Duration = Duration::from_secs(30);
+const PROBE_READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HealthState {
+    pub actors_bound: bool,
+    pub database_open: bool,
+    pub last_tick_opt: Option<SystemTime>,
+    pub last_gossip_opt: Option<SystemTime>,
+}
+
+// The neighbor heartbeat age is reported, but it doesn't make the Node unhealthy: a Node with no
+// neighbors yet, or one in zero-hop mode, isn't broken, and restarting it wouldn't help.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct HealthReport {
+    pub ready: bool,
+    pub live: bool,
+    #[serde(rename = "actorsBound")]
+    pub actors_bound: bool,
+    #[serde(rename = "databaseOpen")]
+    pub database_open: bool,
+    #[serde(rename = "eventLoopLagSecs")]
+    pub event_loop_lag_secs_opt: Option<u64>,
+    #[serde(rename = "neighborHeartbeatAgeSecs")]
+    pub neighbor_heartbeat_age_secs_opt: Option<u64>,
+}
+
+impl HealthReport {
+    pub fn new(state: &HealthState, now: SystemTime) -> Self {
+        let age_secs = |then: SystemTime| {
+            now.duration_since(then)
+                .unwrap_or_else(|_| Duration::from_secs(0))
+                .as_secs()
+        };
+        let event_loop_lag_secs_opt = state.last_tick_opt.map(age_secs);
+        let live = matches!(
+            event_loop_lag_secs_opt,
+            Some(lag_secs) if lag_secs < EVENT_LOOP_STALL_LIMIT.as_secs()
+        );
+        HealthReport {
+            ready: state.actors_bound && state.database_open,
+            live,
+            actors_bound: state.actors_bound,
+            database_open: state.database_open,
+            event_loop_lag_secs_opt,
+            neighbor_heartbeat_age_secs_opt: state.last_gossip_opt.map(age_secs),
+        }
+    }
+}
+
+#[derive(Message, Debug, Clone, PartialEq, Eq)]
+pub struct HealthCheckTick {}
+
+pub struct HealthCheck {
+    state: Arc<Mutex<HealthState>>,
+    db_conn_opt: Option<Box<dyn ConnectionWrapper>>,
+    tick_interval: Duration,
+    logger: Logger,
+}
+
+impl Actor for HealthCheck {
+    type Context = Context<Self>;
+}
+
+impl Handler<BindMessage> for HealthCheck {
+    type Result = ();
+
+    // The ActorSystemFactory binds the HealthCheck after every other actor, so by the time this
+    // arrives, all of them have been started and handed their BindMessages.
+    fn handle(&mut self, _msg: BindMessage, ctx: &mut Self::Context) -> Self::Result {
+        ctx.set_mailbox_capacity(NODE_MAILBOX_CAPACITY);
+        self.lock_state().actors_bound = true;
+        ctx.notify(HealthCheckTick {});
+    }
+}
+
+impl Handler<HealthCheckTick> for HealthCheck {
+    type Result = ();
+
+    fn handle(&mut self, _msg: HealthCheckTick, ctx: &mut Self::Context) -> Self::Result {
+        let database_open = self.database_is_open();
+        {
+            let mut state = self.lock_state();
+            if state.database_open && !database_open {
+                error!(self.logger, "Database stopped answering queries");
+            }
+            state.database_open = database_open;
+            state.last_tick_opt = Some(SystemTime::now());
+        }
+        ctx.notify_later(HealthCheckTick {}, self.tick_interval);
+    }
+}
+
+impl Handler<GossipHeartbeat> for HealthCheck {
+    type Result = ();
+
+    fn handle(&mut self, _msg: GossipHeartbeat, _ctx: &mut Self::Context) -> Self::Result {
+        self.lock_state().last_gossip_opt = Some(SystemTime::now());
+    }
+}
+
+impl HealthCheck {
+    pub fn new(
+        state: Arc<Mutex<HealthState>>,
+        db_conn_opt: Option<Box<dyn ConnectionWrapper>>,
+    ) -> Self {
+        Self {
+            state,
+            db_conn_opt,
+            tick_interval: HEALTH_CHECK_TICK_INTERVAL,
+            logger: Logger::new("HealthCheck"),
+        }
+    }
+
+    pub fn make_subs_from(addr: &Addr<HealthCheck>) -> HealthCheckSubs {
+        HealthCheckSubs {
+            bind: recipient!(addr, BindMessage),
+            gossip_heartbeat: recipient!(addr, GossipHeartbeat),
+        }
+    }
+
+    fn database_is_open(&self) -> bool {
+        match self.db_conn_opt.as_ref() {
+            Some(conn) => conn
+                .prepare("select count(*) from config")
+                .and_then(|mut stmt| stmt.query_row([], |_| Ok(())))
+                .is_ok(),
+            None => false,
+        }
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<HealthState> {
+        self.state.lock().expect("HealthCheck state is poisoned")
+    }
+}
+
+// The probe server runs on a thread of its own rather than in the actor system, so that it can
+// still answer (with a 503) when the actor system has stalled.
+pub fn start_health_server(port: u16, state: Arc<Mutex<HealthState>>) -> io::Result<SocketAddr> {
+    let listener = TcpListener::bind(SocketAddr::new(localhost(), port))?;
+    let local_addr = listener.local_addr()?;
+    let logger = Logger::new("HealthCheck");
+    info!(
+        logger,
+        "Answering health probes at http://{}/health", local_addr
+    );
+    thread::spawn(move || {
+        listener
+            .incoming()
+            .for_each(|stream_result| match stream_result {
+                Ok(stream) => serve_probe(stream, &state, &logger),
+                Err(e) => warning!(logger, "Health probe connection failed: {}", e),
+            })
+    });
+    Ok(local_addr)
+}
+
+fn serve_probe(mut stream: TcpStream, state: &Arc<Mutex<HealthState>>, logger: &Logger) {
+    let request_line = match read_request_line(&stream) {
+        Ok(request_line) => request_line,
+        Err(e) => {
+            debug!(logger, "Couldn't read health probe: {}", e);
+            return;
+        }
+    };
+    let report = HealthReport::new(
+        &state.lock().expect("HealthCheck state is poisoned"),
+        SystemTime::now(),
+    );
+    let response = respond_to_probe(&request_line, &report);
+    if let Err(e) = stream.write_all(response.as_bytes()) {
+        debug!(logger, "Couldn't answer health probe: {}", e);
+    }
+}
+
+// Reads the whole request head, so that closing the connection afterward doesn't reset it under
+// a probe that hasn't read its response yet, and returns the first line.
+fn read_request_line(stream: &TcpStream) -> io::Result<String> {
+    stream.set_read_timeout(Some(PROBE_READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && !header.trim().is_empty() {
+        header.clear();
+    }
+    Ok(request_line)
+}
+
+fn respond_to_probe(request_line: &str, report: &HealthReport) -> String {
+    let mut words = request_line.split_whitespace();
+    let healthy = match (words.next(), words.next()) {
+        (Some("GET"), Some("/health")) => report.ready && report.live,
+        (Some("GET"), Some("/health/ready")) => report.ready,
+        (Some("GET"), Some("/health/live")) => report.live,
+        _ => return http_response("404 Not Found", "{}"),
+    };
+    let body = serde_json::to_string(report).expect("HealthReport serialization failed");
+    if healthy {
+        http_response("200 OK", &body)
+    } else {
+        http_response("503 Service Unavailable", &body)
+    }
+}
+
+fn http_response(status: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal,
+    };
+    use crate::test_utils::recorder::peer_actors_builder;
+    use actix::System;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use std::io::Read;
+
+    fn make_state(
+        actors_bound: bool,
+        database_open: bool,
+        tick_age_opt: Option<Duration>,
+        gossip_age_opt: Option<Duration>,
+        now: SystemTime,
+    ) -> HealthState {
+        HealthState {
+            actors_bound,
+            database_open,
+            last_tick_opt: tick_age_opt.map(|age| now - age),
+            last_gossip_opt: gossip_age_opt.map(|age| now - age),
+        }
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(HEALTH_CHECK_TICK_INTERVAL, Duration::from_secs(5));
+        assert_eq!(EVENT_LOOP_STALL_LIMIT, Duration::from_secs(30));
+        assert_eq!(PROBE_READ_TIMEOUT, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn healthy_node_is_reported_ready_and_live() {
+        let now = SystemTime::now();
+        let state = make_state(
+            true,
+            true,
+            Some(Duration::from_secs(3)),
+            Some(Duration::from_secs(75)),
+            now,
+        );
+
+        let result = HealthReport::new(&state, now);
+
+        assert_eq!(
+            result,
+            HealthReport {
+                ready: true,
+                live: true,
+                actors_bound: true,
+                database_open: true,
+                event_loop_lag_secs_opt: Some(3),
+                neighbor_heartbeat_age_secs_opt: Some(75),
+            }
+        );
+        assert_eq!(
+            serde_json::to_string(&result).unwrap(),
+            r#"{"ready":true,"live":true,"actorsBound":true,"databaseOpen":true,"eventLoopLagSecs":3,"neighborHeartbeatAgeSecs":75}"#
+        );
+    }
+
+    #[test]
+    fn node_is_not_ready_until_actors_are_bound_and_database_is_open() {
+        let now = SystemTime::now();
+        let tick_age_opt = Some(Duration::from_secs(1));
+
+        let unbound = HealthReport::new(&make_state(false, true, tick_age_opt, None, now), now);
+        let no_database = HealthReport::new(&make_state(true, false, tick_age_opt, None, now), now);
+
+        assert_eq!(unbound.ready, false);
+        assert_eq!(no_database.ready, false);
+        assert_eq!(unbound.live, true);
+        assert_eq!(no_database.live, true);
+    }
+
+    #[test]
+    fn node_is_not_live_if_its_event_loop_has_stalled_or_never_ticked() {
+        let now = SystemTime::now();
+
+        let stalled = HealthReport::new(
+            &make_state(true, true, Some(EVENT_LOOP_STALL_LIMIT), None, now),
+            now,
+        );
+        let never_ticked = HealthReport::new(&make_state(true, true, None, None, now), now);
+
+        assert_eq!(stalled.live, false);
+        assert_eq!(stalled.event_loop_lag_secs_opt, Some(30));
+        assert_eq!(never_ticked.live, false);
+        assert_eq!(never_ticked.event_loop_lag_secs_opt, None);
+    }
+
+    #[test]
+    fn missing_neighbor_heartbeat_does_not_make_node_unhealthy() {
+        let now = SystemTime::now();
+        let state = make_state(true, true, Some(Duration::from_secs(1)), None, now);
+
+        let result = HealthReport::new(&state, now);
+
+        assert_eq!(result.ready && result.live, true);
+        assert_eq!(result.neighbor_heartbeat_age_secs_opt, None);
+    }
+
+    #[test]
+    fn probes_are_answered_according_to_path_and_health() {
+        let now = SystemTime::now();
+        let report = HealthReport::new(
+            &make_state(true, false, Some(Duration::from_secs(1)), None, now),
+            now,
+        );
+        let body = serde_json::to_string(&report).unwrap();
+
+        let whole = respond_to_probe("GET /health HTTP/1.1\r\n", &report);
+        let ready = respond_to_probe("GET /health/ready HTTP/1.1\r\n", &report);
+        let live = respond_to_probe("GET /health/live HTTP/1.1\r\n", &report);
+        let unknown = respond_to_probe("GET /booga HTTP/1.1\r\n", &report);
+        let wrong_method = respond_to_probe("POST /health HTTP/1.1\r\n", &report);
+
+        assert_eq!(whole, http_response("503 Service Unavailable", &body));
+        assert_eq!(ready, http_response("503 Service Unavailable", &body));
+        assert_eq!(live, http_response("200 OK", &body));
+        assert_eq!(unknown, http_response("404 Not Found", "{}"));
+        assert_eq!(wrong_method, http_response("404 Not Found", "{}"));
+        assert_eq!(
+            http_response("200 OK", "{}"),
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}"
+        );
+    }
+
+    #[test]
+    fn health_server_answers_probes_over_http() {
+        let now = SystemTime::now();
+        let state_arc = Arc::new(Mutex::new(make_state(
+            true,
+            true,
+            Some(Duration::from_secs(0)),
+            None,
+            now,
+        )));
+        let local_addr = start_health_server(0, state_arc).unwrap();
+        let mut stream = TcpStream::connect(local_addr).unwrap();
+
+        stream
+            .write_all(b"GET /health/live HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_at(response.find("\r\n\r\n").unwrap() + 4);
+        assert!(head.starts_with("HTTP/1.1 200 OK\r\n"), "{}", head);
+        assert_eq!(
+            body,
+            r#"{"ready":true,"live":true,"actorsBound":true,"databaseOpen":true,"eventLoopLagSecs":0,"neighborHeartbeatAgeSecs":null}"#
+        );
+    }
+
+    #[test]
+    fn health_check_records_binding_ticks_database_and_gossip() {
+        let home_dir = ensure_node_home_directory_exists(
+            "health_check",
+            "health_check_records_binding_ticks_database_and_gossip",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let state_arc = Arc::new(Mutex::new(HealthState::default()));
+        let subject = HealthCheck::new(state_arc.clone(), Some(conn));
+        let system = System::new("health_check_records_binding_ticks_database_and_gossip");
+        let addr = subject.start();
+        let subs = HealthCheck::make_subs_from(&addr);
+        let before = SystemTime::now();
+
+        subs.bind
+            .try_send(BindMessage {
+                peer_actors: peer_actors_builder().build(),
+            })
+            .unwrap();
+        subs.gossip_heartbeat.try_send(GossipHeartbeat {}).unwrap();
+
+        System::current().stop();
+        system.run();
+        let after = SystemTime::now();
+        let state = state_arc.lock().unwrap();
+        assert_eq!(state.actors_bound, true);
+        assert_eq!(state.database_open, true);
+        let last_tick = state.last_tick_opt.unwrap();
+        assert!(before <= last_tick && last_tick <= after);
+        let last_gossip = state.last_gossip_opt.unwrap();
+        assert!(before <= last_gossip && last_gossip <= after);
+    }
+
+    #[test]
+    fn health_check_without_a_database_connection_reports_it_closed() {
+        let state_arc = Arc::new(Mutex::new(HealthState {
+            database_open: true,
+            ..HealthState::default()
+        }));
+        let subject = HealthCheck::new(state_arc.clone(), None);
+        let system = System::new("health_check_without_a_database_connection_reports_it_closed");
+        let addr = subject.start();
+
+        addr.try_send(HealthCheckTick {}).unwrap();
+
+        System::current().stop();
+        system.run();
+        let state = state_arc.lock().unwrap();
+        assert_eq!(state.database_open, false);
+        assert_eq!(state.last_tick_opt.is_some(), true);
+    }
+}
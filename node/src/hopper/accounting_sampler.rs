@@ -0,0 +1,102 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::sub_lib::wallet::Wallet;
+use std::collections::HashMap;
+
+// Counts every CORES package routed for each paying wallet, but lets only every Nth one through to
+// be charged, standing for all N. A stream's last package is let through early, standing for the
+// ones counted since the last sample, so that short streams aren't routed for free.
+pub struct AccountingSampler {
+    sample_interval: u32,
+    unsampled_counts: HashMap<Wallet, u32>,
+}
+
+impl AccountingSampler {
+    pub fn new(sample_interval: u32) -> Self {
+        Self {
+            sample_interval: sample_interval.max(1),
+            unsampled_counts: HashMap::new(),
+        }
+    }
+
+    pub fn is_sampling(&self) -> bool {
+        self.sample_interval > 1
+    }
+
+    // Returns the number of packages this one should be charged for, if it should be charged now
+    pub fn sample(&mut self, paying_wallet: &Wallet, last_data: bool) -> Option<u32> {
+        if !self.is_sampling() {
+            return Some(1);
+        }
+        let count = self
+            .unsampled_counts
+            .entry(paying_wallet.clone())
+            .or_insert(0);
+        *count += 1;
+        if *count >= self.sample_interval || last_data {
+            self.unsampled_counts.remove(paying_wallet)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::make_wallet;
+
+    #[test]
+    fn sampler_with_interval_of_one_charges_every_package() {
+        let wallet = make_wallet("booga");
+        let mut subject = AccountingSampler::new(1);
+
+        let results = (0..3)
+            .map(|_| subject.sample(&wallet, false))
+            .collect::<Vec<Option<u32>>>();
+
+        assert_eq!(subject.is_sampling(), false);
+        assert_eq!(results, vec![Some(1), Some(1), Some(1)]);
+    }
+
+    #[test]
+    fn sampler_charges_every_nth_package_for_n_packages_per_wallet() {
+        let wallet_a = make_wallet("wallet a");
+        let wallet_b = make_wallet("wallet b");
+        let mut subject = AccountingSampler::new(3);
+
+        let results = vec![
+            subject.sample(&wallet_a, false),
+            subject.sample(&wallet_b, false),
+            subject.sample(&wallet_a, false),
+            subject.sample(&wallet_a, false),
+            subject.sample(&wallet_b, false),
+            subject.sample(&wallet_a, false),
+        ];
+
+        assert_eq!(subject.is_sampling(), true);
+        assert_eq!(results, vec![None, None, None, Some(3), None, None]);
+    }
+
+    #[test]
+    fn sampler_charges_the_last_package_of_a_stream_for_the_ones_not_yet_charged() {
+        let wallet = make_wallet("booga");
+        let mut subject = AccountingSampler::new(16);
+
+        let results = vec![
+            subject.sample(&wallet, false),
+            subject.sample(&wallet, false),
+            subject.sample(&wallet, true),
+            subject.sample(&wallet, false),
+        ];
+
+        assert_eq!(results, vec![None, None, Some(3), None]);
+    }
+
+    #[test]
+    fn sampler_treats_an_interval_of_zero_as_one() {
+        let subject = AccountingSampler::new(0);
+
+        assert_eq!(subject.is_sampling(), false);
+    }
+}
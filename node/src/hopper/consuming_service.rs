@@ -1,21 +1,25 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use super::live_cores_package::LiveCoresPackage;
-use crate::sub_lib::cryptde::CryptData;
+use super::traffic_padding::TrafficPadder;
 use crate::sub_lib::cryptde::{encodex, CryptDE};
+use crate::sub_lib::cryptde::{CryptData, PublicKey};
 use crate::sub_lib::dispatcher::{Endpoint, InboundClientData};
 use crate::sub_lib::hopper::{IncipientCoresPackage, NoLookupIncipientCoresPackage};
 use crate::sub_lib::stream_handler_pool::TransmitDataMsg;
 use actix::Recipient;
 use masq_lib::logger::Logger;
 use std::borrow::Borrow;
+use std::cell::RefCell;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
-use std::time::SystemTime;
+use std::rc::Rc;
+use std::time::{Instant, SystemTime};
 
 pub struct ConsumingService {
     cryptde: &'static dyn CryptDE,
     to_dispatcher: Recipient<TransmitDataMsg>,
     to_hopper: Recipient<InboundClientData>,
+    traffic_padder_opt: Option<Rc<RefCell<TrafficPadder>>>,
     logger: Logger,
 }
 
@@ -24,11 +28,13 @@ impl ConsumingService {
         cryptde: &'static dyn CryptDE,
         to_dispatcher: Recipient<TransmitDataMsg>,
         to_hopper: Recipient<InboundClientData>,
+        traffic_padder_opt: Option<Rc<RefCell<TrafficPadder>>>,
     ) -> Self {
         Self {
             cryptde,
             to_dispatcher,
             to_hopper,
+            traffic_padder_opt,
             logger: Logger::new("ConsumingService"),
         }
     }
@@ -42,7 +48,8 @@ impl ConsumingService {
         let target_key = incipient_cores_package.public_key.clone();
         let target_node_addr = incipient_cores_package.node_addr.clone();
         match LiveCoresPackage::from_no_lookup_incipient(incipient_cores_package, self.cryptde) {
-            Ok((live_package, _)) => {
+            Ok((mut live_package, _)) => {
+                self.pad_for(&mut live_package, &target_key);
                 let encrypted_package = match encodex(self.cryptde, &target_key, &live_package) {
                     Ok(p) => p,
                     Err(e) => {
@@ -73,7 +80,11 @@ impl ConsumingService {
             incipient_cores_package.payload.len()
         );
         match LiveCoresPackage::from_incipient(incipient_cores_package, self.cryptde.borrow()) {
-            Ok((live_package, next_hop)) => {
+            Ok((mut live_package, next_hop)) => {
+                let is_zero_hop = &next_hop.public_key == self.cryptde.public_key();
+                if !is_zero_hop {
+                    self.pad_for(&mut live_package, &next_hop.public_key);
+                }
                 let encrypted_package =
                     match encodex(self.cryptde, &next_hop.public_key, &live_package) {
                         Ok(p) => p,
@@ -82,7 +93,7 @@ impl ConsumingService {
                             return;
                         }
                     };
-                if &next_hop.public_key == self.cryptde.public_key() {
+                if is_zero_hop {
                     self.zero_hop(encrypted_package);
                 } else {
                     self.launch_lcp(encrypted_package, Endpoint::Key(next_hop.public_key));
@@ -92,6 +103,48 @@ impl ConsumingService {
         };
     }
 
+    pub fn send_cover(&self, candidates: Vec<PublicKey>) {
+        let recipient_opt = self.traffic_padder_opt.as_ref().and_then(|traffic_padder| {
+            traffic_padder
+                .borrow()
+                .idle_recipient_opt(&candidates, Instant::now())
+        });
+        let recipient = match recipient_opt {
+            Some(recipient) => recipient,
+            None => {
+                trace!(
+                    self.logger,
+                    "None of {} candidates needs cover traffic",
+                    candidates.len()
+                );
+                return;
+            }
+        };
+        let mut live_package = match LiveCoresPackage::cover(&recipient, self.cryptde) {
+            Ok(p) => p,
+            Err(e) => {
+                error!(self.logger, "Couldn't make cover package: {}", e);
+                return;
+            }
+        };
+        self.pad_for(&mut live_package, &recipient);
+        match encodex(self.cryptde, &recipient, &live_package) {
+            Ok(encrypted_package) => {
+                debug!(self.logger, "Sending cover package to {}", recipient);
+                self.launch_lcp(encrypted_package, Endpoint::Key(recipient))
+            }
+            Err(e) => error!(self.logger, "Couldn't encode cover package: {:?}", e),
+        }
+    }
+
+    fn pad_for(&self, live_package: &mut LiveCoresPackage, recipient: &PublicKey) {
+        if let Some(traffic_padder) = self.traffic_padder_opt.as_ref() {
+            let mut traffic_padder = traffic_padder.borrow_mut();
+            traffic_padder.pad(live_package);
+            traffic_padder.record_transmission(recipient, Instant::now());
+        }
+    }
+
     fn zero_hop(&self, encrypted_package: CryptData) {
         let ibcd = InboundClientData {
             timestamp: SystemTime::now(),
@@ -135,6 +188,7 @@ mod tests {
     use crate::node_test_utils::check_timestamp;
     use crate::sub_lib::cryptde::PublicKey;
     use crate::sub_lib::dispatcher::{Component, InboundClientData};
+    use crate::sub_lib::hopper::TrafficPadding;
     use crate::sub_lib::node_addr::NodeAddr;
     use crate::sub_lib::route::Route;
     use crate::sub_lib::route::RouteSegment;
@@ -167,6 +221,7 @@ mod tests {
             main_cryptde(),
             peer_actors.dispatcher.from_dispatcher_client,
             peer_actors.hopper.from_dispatcher,
+            None,
         );
 
         subject.consume_no_lookup(package.clone());
@@ -203,6 +258,7 @@ mod tests {
             main_cryptde(),
             peer_actors.dispatcher.from_dispatcher_client,
             peer_actors.hopper.from_dispatcher,
+            None,
         );
 
         subject.consume_no_lookup(package);
@@ -237,6 +293,7 @@ mod tests {
             cryptde,
             peer_actors.dispatcher.from_dispatcher_client,
             peer_actors.hopper.from_dispatcher,
+            None,
         );
 
         subject.consume(incipient_cores_package.clone());
@@ -259,6 +316,148 @@ mod tests {
         );
     }
 
+    fn make_traffic_padder(cover_per_minute: u32) -> Rc<RefCell<TrafficPadder>> {
+        Rc::new(RefCell::new(TrafficPadder::new(TrafficPadding {
+            bucket_size: 256,
+            cover_per_minute,
+        })))
+    }
+
+    #[test]
+    fn consume_pads_live_package_when_padding_traffic() {
+        let cryptde = main_cryptde();
+        let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
+        let destination_key = PublicKey::new(&[65, 65, 65]);
+        let route = Route::one_way(
+            RouteSegment::new(
+                vec![cryptde.public_key(), &destination_key],
+                Component::Neighborhood,
+            ),
+            cryptde,
+            Some(make_paying_wallet(b"wallet")),
+            Some(TEST_DEFAULT_CHAIN.rec().contract),
+        )
+        .unwrap();
+        let incipient_cores_package = IncipientCoresPackage::new(
+            cryptde,
+            route,
+            make_meaningless_message_type(),
+            &destination_key,
+        )
+        .unwrap();
+        let system = System::new("consume_pads_live_package_when_padding_traffic");
+        let peer_actors = peer_actors_builder().dispatcher(dispatcher).build();
+        let subject = ConsumingService::new(
+            cryptde,
+            peer_actors.dispatcher.from_dispatcher_client,
+            peer_actors.hopper.from_dispatcher,
+            Some(make_traffic_padder(2)),
+        );
+
+        subject.consume(incipient_cores_package.clone());
+
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        let record = dispatcher_recording.get_record::<TransmitDataMsg>(0);
+        let (mut expected_lcp, _) =
+            LiveCoresPackage::from_incipient(incipient_cores_package, cryptde).unwrap();
+        expected_lcp.pad_to_bucket(256);
+        assert_eq!(
+            record.data,
+            Vec::from(encodex(cryptde, &destination_key, &expected_lcp).unwrap())
+        );
+        assert_eq!(
+            serde_cbor::ser::to_vec(&expected_lcp).unwrap().len() % 256,
+            0
+        );
+    }
+
+    #[test]
+    fn send_cover_sends_padded_cover_package_to_idle_neighbor() {
+        let cryptde = main_cryptde();
+        let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
+        let neighbor_key = PublicKey::new(&[65, 65, 65]);
+        let system = System::new("send_cover_sends_padded_cover_package_to_idle_neighbor");
+        let peer_actors = peer_actors_builder().dispatcher(dispatcher).build();
+        let subject = ConsumingService::new(
+            cryptde,
+            peer_actors.dispatcher.from_dispatcher_client,
+            peer_actors.hopper.from_dispatcher,
+            Some(make_traffic_padder(2)),
+        );
+
+        subject.send_cover(vec![neighbor_key.clone()]);
+
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        let record = dispatcher_recording.get_record::<TransmitDataMsg>(0);
+        let mut expected_lcp = LiveCoresPackage::cover(&neighbor_key, cryptde).unwrap();
+        expected_lcp.pad_to_bucket(256);
+        assert_eq!(
+            *record,
+            TransmitDataMsg {
+                endpoint: Endpoint::Key(neighbor_key.clone()),
+                last_data: false,
+                sequence_number: None,
+                data: encodex(cryptde, &neighbor_key, &expected_lcp)
+                    .unwrap()
+                    .into(),
+            }
+        );
+    }
+
+    #[test]
+    fn send_cover_leaves_busy_neighbors_alone() {
+        let cryptde = main_cryptde();
+        let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
+        let neighbor_key = PublicKey::new(&[65, 65, 65]);
+        let package = NoLookupIncipientCoresPackage::new(
+            cryptde,
+            &neighbor_key,
+            &NodeAddr::new(&IpAddr::from_str("1.2.1.2").unwrap(), &[1212]),
+            make_meaningless_message_type(),
+        )
+        .unwrap();
+        let system = System::new("send_cover_leaves_busy_neighbors_alone");
+        let peer_actors = peer_actors_builder().dispatcher(dispatcher).build();
+        let subject = ConsumingService::new(
+            cryptde,
+            peer_actors.dispatcher.from_dispatcher_client,
+            peer_actors.hopper.from_dispatcher,
+            Some(make_traffic_padder(2)),
+        );
+
+        subject.consume_no_lookup(package);
+        subject.send_cover(vec![neighbor_key]);
+
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        assert_eq!(dispatcher_recording.len(), 1);
+    }
+
+    #[test]
+    fn send_cover_does_nothing_when_not_padding_traffic() {
+        let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
+        let system = System::new("send_cover_does_nothing_when_not_padding_traffic");
+        let peer_actors = peer_actors_builder().dispatcher(dispatcher).build();
+        let subject = ConsumingService::new(
+            main_cryptde(),
+            peer_actors.dispatcher.from_dispatcher_client,
+            peer_actors.hopper.from_dispatcher,
+            None,
+        );
+
+        subject.send_cover(vec![PublicKey::new(&[65, 65, 65])]);
+
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        assert_eq!(dispatcher_recording.len(), 0);
+    }
+
     #[test]
     fn consume_sends_zero_hop_incipient_directly_to_hopper() {
         let cryptde = main_cryptde();
@@ -284,6 +483,7 @@ mod tests {
             cryptde,
             peer_actors.dispatcher.from_dispatcher_client,
             peer_actors.hopper.from_dispatcher,
+            None,
         );
         let before = SystemTime::now();
 
@@ -320,7 +520,7 @@ mod tests {
         let to_dispatcher = peer_actors.dispatcher.from_dispatcher_client;
         let to_hopper = peer_actors.hopper.from_dispatcher;
 
-        let subject = ConsumingService::new(main_cryptde(), to_dispatcher, to_hopper);
+        let subject = ConsumingService::new(main_cryptde(), to_dispatcher, to_hopper, None);
 
         subject.consume(
             IncipientCoresPackage::new(
@@ -11,11 +11,26 @@ use crate::sub_lib::route::Route;
 use serde_derive::{Deserialize, Serialize};
 use std::net::SocketAddr;
 
+// The serialized name of the padding field, with its one-byte CBOR header
+const PADDING_FIELD_NAME_LEN: usize = 8;
+// The field name, a one-byte array header and a single byte of padding
+const MIN_PADDING_OVERHEAD: usize = PADDING_FIELD_NAME_LEN + 2;
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LiveCoresPackage {
     pub version: DataVersion,
     pub route: Route,
     pub payload: CryptData,
+    // These are only serialized when in use, so that Nodes that don't pad traffic send the same
+    // packages they always have, and Nodes that don't know about them ignore them
+    #[serde(default, skip_serializing_if = "is_not_cover")]
+    pub cover: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub padding: Vec<u8>,
+}
+
+fn is_not_cover(cover: &bool) -> bool {
+    !*cover
 }
 
 impl LiveCoresPackage {
@@ -28,9 +43,55 @@ impl LiveCoresPackage {
             version: Self::version(),
             route,
             payload,
+            cover: false,
+            padding: vec![],
         }
     }
 
+    // A cover package looks like any other single-hop package, but its recipient drops it unread
+    pub fn cover(
+        recipient: &PublicKey,
+        cryptde: &dyn CryptDE, // must be the CryptDE of the Node the package is about to leave
+    ) -> Result<LiveCoresPackage, String> {
+        let mut route = match Route::single_hop(recipient, cryptde) {
+            Ok(r) => r,
+            Err(e) => return Err(format!("{:?}", e)),
+        };
+        route
+            .shift(cryptde)
+            .expect("CryptDE suddenly changed its keying");
+        Ok(Self {
+            cover: true,
+            ..LiveCoresPackage::new(route, CryptData::new(&[]))
+        })
+    }
+
+    pub fn is_padded(&self) -> bool {
+        !self.padding.is_empty()
+    }
+
+    // Encryption adds the same overhead to every package, so packages padded to the same number of
+    // buckets here are indistinguishable by size on the wire.
+    pub fn pad_to_bucket(&mut self, bucket_size: usize) {
+        self.padding.clear();
+        let unpadded_len = self.serialized_len();
+        let mut padded_len =
+            (unpadded_len + MIN_PADDING_OVERHEAD + bucket_size - 1) / bucket_size * bucket_size;
+        loop {
+            if let Some(padding_len) = padding_len_for(padded_len - unpadded_len) {
+                self.padding = vec![0; padding_len];
+                return;
+            }
+            padded_len += bucket_size;
+        }
+    }
+
+    fn serialized_len(&self) -> usize {
+        serde_cbor::ser::to_vec(self)
+            .expect("Serialization of LiveCoresPackage failed")
+            .len()
+    }
+
     pub fn into_next_live(
         mut self,
         cryptde: &dyn CryptDE, // must be the main CryptDE of the Node to which the top hop is encrypted
@@ -88,6 +149,25 @@ impl LiveCoresPackage {
     }
 }
 
+// How much padding adds exactly this many bytes to a serialized package, if any does: the array
+// header grows with the array, so some sizes can't be hit.
+fn padding_len_for(overhead: usize) -> Option<usize> {
+    let array_len = overhead.checked_sub(PADDING_FIELD_NAME_LEN)?;
+    [1, 2, 3, 5].into_iter().find_map(|header_len| {
+        let padding_len = array_len.checked_sub(header_len)?;
+        (padding_len > 0 && cbor_array_header_len(padding_len) == header_len).then_some(padding_len)
+    })
+}
+
+fn cbor_array_header_len(array_len: usize) -> usize {
+    match array_len {
+        n if n < 24 => 1,
+        n if n < 0x100 => 2,
+        n if n < 0x10000 => 3,
+        _ => 5,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -410,4 +490,155 @@ mod tests {
 
         assert_eq!(deserialized, original);
     }
+
+    #[test]
+    fn padding_fills_packages_out_to_whole_buckets() {
+        vec![0usize, 1, 20, 23, 24, 100, 255, 256, 1000, 1013, 5000]
+            .into_iter()
+            .for_each(|payload_len| {
+                vec![64usize, 100, 1024]
+                    .into_iter()
+                    .for_each(|bucket_size| {
+                        let mut subject = LiveCoresPackage::new(
+                            make_meaningless_route(),
+                            CryptData::new(&vec![1; payload_len]),
+                        );
+                        let unpadded_len = serde_cbor::ser::to_vec(&subject).unwrap().len();
+
+                        subject.pad_to_bucket(bucket_size);
+
+                        let padded = serde_cbor::ser::to_vec(&subject).unwrap();
+                        let case = format!("payload {}, bucket {}", payload_len, bucket_size);
+                        assert_eq!(subject.is_padded(), true, "{}", case);
+                        assert_eq!(padded.len() % bucket_size, 0, "{}", case);
+                        assert!(padded.len() > unpadded_len, "{}", case);
+                        assert!(
+                            padded.len() <= unpadded_len + MIN_PADDING_OVERHEAD + 2 * bucket_size,
+                            "{}",
+                            case
+                        );
+                        assert_eq!(
+                            serde_cbor::de::from_slice::<LiveCoresPackage>(&padded).unwrap(),
+                            subject,
+                            "{}",
+                            case
+                        );
+                    })
+            });
+    }
+
+    #[test]
+    fn padding_is_repadded_rather_than_accumulated() {
+        let mut subject =
+            LiveCoresPackage::new(make_meaningless_route(), CryptData::new(&[1, 2, 3, 4]));
+        subject.pad_to_bucket(1024);
+        let padding_len = subject.padding.len();
+
+        subject.pad_to_bucket(1024);
+
+        assert_eq!(subject.padding.len(), padding_len);
+    }
+
+    #[test]
+    fn nodes_that_dont_know_about_padding_or_cover_can_still_read_packages() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct OldLiveCoresPackage {
+            pub version: DataVersion,
+            pub route: Route,
+            pub payload: CryptData,
+        }
+        let mut subject =
+            LiveCoresPackage::new(make_meaningless_route(), CryptData::new(&[1, 2, 3, 4]));
+        let unpadded = serde_cbor::ser::to_vec(&subject).unwrap();
+        subject.pad_to_bucket(256);
+        subject.cover = true;
+        let padded = serde_cbor::ser::to_vec(&subject).unwrap();
+
+        let result = serde_cbor::de::from_slice::<OldLiveCoresPackage>(&padded).unwrap();
+
+        assert_eq!(
+            result,
+            OldLiveCoresPackage {
+                version: subject.version,
+                route: subject.route.clone(),
+                payload: subject.payload.clone(),
+            }
+        );
+        assert_eq!(
+            serde_cbor::de::from_slice::<OldLiveCoresPackage>(&unpadded).unwrap(),
+            result
+        );
+    }
+
+    #[test]
+    fn unpadded_packages_serialize_as_they_always_have() {
+        #[derive(Serialize)]
+        struct OldLiveCoresPackage {
+            pub version: DataVersion,
+            pub route: Route,
+            pub payload: CryptData,
+        }
+        let subject =
+            LiveCoresPackage::new(make_meaningless_route(), CryptData::new(&[1, 2, 3, 4]));
+        let old = OldLiveCoresPackage {
+            version: subject.version,
+            route: subject.route.clone(),
+            payload: subject.payload.clone(),
+        };
+
+        assert_eq!(
+            serde_cbor::ser::to_vec(&subject).unwrap(),
+            serde_cbor::ser::to_vec(&old).unwrap()
+        );
+    }
+
+    #[test]
+    fn into_next_live_drops_padding() {
+        let cryptde = main_cryptde();
+        let mut subject = LiveCoresPackage::new(
+            Route::one_way(
+                RouteSegment::new(
+                    vec![cryptde.public_key(), &PublicKey::new(&[3, 4])],
+                    Component::Neighborhood,
+                ),
+                cryptde,
+                Some(make_paying_wallet(b"wallet")),
+                Some(TEST_DEFAULT_CHAIN.rec().contract),
+            )
+            .unwrap(),
+            CryptData::new(&[5, 6]),
+        );
+        subject.pad_to_bucket(1024);
+
+        let (_, result) = subject.into_next_live(cryptde).unwrap();
+
+        assert_eq!(result.is_padded(), false);
+    }
+
+    #[test]
+    fn cover_package_is_single_hop_and_empty() {
+        let cryptde = main_cryptde();
+        let recipient = PublicKey::new(&[3, 4, 5, 6]);
+
+        let result = LiveCoresPackage::cover(&recipient, cryptde).unwrap();
+
+        let mut expected_route = Route::single_hop(&recipient, cryptde).unwrap();
+        expected_route.shift(cryptde).unwrap();
+        assert_eq!(result.cover, true);
+        assert_eq!(result.route, expected_route);
+        assert_eq!(result.payload, CryptData::new(&[]));
+        assert_eq!(result.is_padded(), false);
+    }
+
+    #[test]
+    fn padding_len_for_skips_sizes_the_array_header_makes_unreachable() {
+        assert_eq!(padding_len_for(9), None);
+        assert_eq!(padding_len_for(10), Some(1));
+        assert_eq!(padding_len_for(32), Some(23));
+        assert_eq!(padding_len_for(33), None);
+        assert_eq!(padding_len_for(34), Some(24));
+        assert_eq!(padding_len_for(265), Some(255));
+        assert_eq!(padding_len_for(266), None);
+        assert_eq!(padding_len_for(267), Some(256));
+    }
 }
@@ -1,15 +1,19 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+mod accounting_sampler;
 mod consuming_service;
 pub mod live_cores_package;
 mod routing_service;
+mod traffic_padding;
 
 use crate::bootstrapper::CryptDEPair;
 use crate::hopper::routing_service::RoutingServiceSubs;
 use crate::sub_lib::dispatcher::InboundClientData;
 use crate::sub_lib::hopper::HopperSubs;
 use crate::sub_lib::hopper::IncipientCoresPackage;
-use crate::sub_lib::hopper::{HopperConfig, NoLookupIncipientCoresPackage};
+use crate::sub_lib::hopper::{
+    HopperConfig, NoLookupIncipientCoresPackage, SendCoverTraffic, TrafficPadding,
+};
 use crate::sub_lib::peer_actors::BindMessage;
 use crate::sub_lib::utils::{handle_ui_crash_request, NODE_MAILBOX_CAPACITY};
 use actix::Actor;
@@ -20,6 +24,9 @@ use consuming_service::ConsumingService;
 use masq_lib::logger::Logger;
 use masq_lib::ui_gateway::NodeFromUiMessage;
 use routing_service::RoutingService;
+use std::cell::RefCell;
+use std::rc::Rc;
+use traffic_padding::TrafficPadder;
 
 pub const CRASH_KEY: &str = "HOPPER";
 
@@ -30,6 +37,8 @@ pub struct Hopper {
     per_routing_service: u64,
     per_routing_byte: u64,
     is_decentralized: bool,
+    accounting_sample_interval: u32,
+    traffic_padding_opt: Option<TrafficPadding>,
     logger: Logger,
     crashable: bool,
 }
@@ -43,10 +52,14 @@ impl Handler<BindMessage> for Hopper {
 
     fn handle(&mut self, msg: BindMessage, ctx: &mut Self::Context) -> Self::Result {
         ctx.set_mailbox_capacity(NODE_MAILBOX_CAPACITY);
+        let traffic_padder_opt = self
+            .traffic_padding_opt
+            .map(|padding| Rc::new(RefCell::new(TrafficPadder::new(padding))));
         self.consuming_service = Some(ConsumingService::new(
             self.cryptdes.main,
             msg.peer_actors.dispatcher.from_dispatcher_client.clone(),
             msg.peer_actors.hopper.from_dispatcher.clone(),
+            traffic_padder_opt.clone(),
         ));
         self.routing_service = Some(RoutingService::new(
             self.cryptdes,
@@ -61,6 +74,8 @@ impl Handler<BindMessage> for Hopper {
             self.per_routing_service,
             self.per_routing_byte,
             self.is_decentralized,
+            self.accounting_sample_interval,
+            traffic_padder_opt,
         ));
     }
 }
@@ -95,6 +110,17 @@ impl Handler<IncipientCoresPackage> for Hopper {
     }
 }
 
+impl Handler<SendCoverTraffic> for Hopper {
+    type Result = ();
+
+    fn handle(&mut self, msg: SendCoverTraffic, _ctx: &mut Self::Context) -> Self::Result {
+        self.consuming_service
+            .as_ref()
+            .expect("Hopper unbound: no ConsumingService")
+            .send_cover(msg.candidates);
+    }
+}
+
 impl Handler<InboundClientData> for Hopper {
     type Result = ();
 
@@ -124,6 +150,8 @@ impl Hopper {
             per_routing_service: config.per_routing_service,
             per_routing_byte: config.per_routing_byte,
             is_decentralized: config.is_decentralized,
+            accounting_sample_interval: config.accounting_sample_interval,
+            traffic_padding_opt: config.traffic_padding_opt,
             logger: Logger::new("Hopper"),
         }
     }
@@ -134,6 +162,7 @@ impl Hopper {
             from_hopper_client: recipient!(addr, IncipientCoresPackage),
             from_hopper_client_no_lookup: recipient!(addr, NoLookupIncipientCoresPackage),
             from_dispatcher: recipient!(addr, InboundClientData),
+            send_cover_traffic: recipient!(addr, SendCoverTraffic),
             node_from_ui: recipient!(addr, NodeFromUiMessage),
         }
     }
@@ -206,6 +235,8 @@ mod tests {
             per_routing_byte: 200,
             is_decentralized: false,
             crashable: false,
+            accounting_sample_interval: 1,
+            traffic_padding_opt: None,
         });
         let subject_addr = subject.start();
 
@@ -249,6 +280,8 @@ mod tests {
             per_routing_byte: 200,
             is_decentralized: false,
             crashable: false,
+            accounting_sample_interval: 1,
+            traffic_padding_opt: None,
         });
         let subject_addr = subject.start();
 
@@ -269,6 +302,8 @@ mod tests {
             per_routing_byte: 200,
             is_decentralized: false,
             crashable: true,
+            accounting_sample_interval: 1,
+            traffic_padding_opt: None,
         });
 
         prove_that_crash_request_handler_is_hooked_up(hopper, CRASH_KEY);
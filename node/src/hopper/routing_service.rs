@@ -1,14 +1,16 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use super::accounting_sampler::AccountingSampler;
 use super::live_cores_package::LiveCoresPackage;
+use super::traffic_padding::TrafficPadder;
 use crate::blockchain::payer::Payer;
 use crate::bootstrapper::CryptDEPair;
-use crate::neighborhood::gossip::Gossip_0v1;
-use crate::sub_lib::accountant::ReportRoutingServiceProvidedMessage;
+use crate::neighborhood::gossip::{Gossip_0v1, ReceivedGossip};
+use crate::sub_lib::accountant::{PaymentOverdue_0v1, ReportRoutingServiceProvidedMessage};
 use crate::sub_lib::cryptde::{decodex, encodex, CryptData, CryptdecError};
 use crate::sub_lib::dispatcher::{Component, Endpoint, InboundClientData};
 use crate::sub_lib::hop::LiveHop;
 use crate::sub_lib::hopper::{ExpiredCoresPackage, HopperSubs, MessageType};
-use crate::sub_lib::neighborhood::{GossipFailure_0v1, NeighborhoodSubs};
+use crate::sub_lib::neighborhood::{GossipFailure_0v1, NeighborhoodSubs, Rendezvous_0v1};
 use crate::sub_lib::proxy_client::{
     ClientResponsePayload_0v1, DnsResolveFailure_0v1, ProxyClientSubs,
 };
@@ -17,9 +19,11 @@ use crate::sub_lib::stream_handler_pool::TransmitDataMsg;
 use actix::Recipient;
 use masq_lib::logger::Logger;
 use std::borrow::Borrow;
+use std::cell::RefCell;
 use std::convert::TryFrom;
 use std::net::SocketAddr;
-use std::time::SystemTime;
+use std::rc::Rc;
+use std::time::{Instant, SystemTime};
 
 pub struct RoutingServiceSubs {
     pub proxy_client_subs_opt: Option<ProxyClientSubs>,
@@ -35,6 +39,8 @@ pub struct RoutingService {
     routing_service_subs: RoutingServiceSubs,
     per_routing_service: u64,
     per_routing_byte: u64,
+    accounting_sampler: RefCell<AccountingSampler>,
+    traffic_padder_opt: Option<Rc<RefCell<TrafficPadder>>>,
     logger: Logger,
     is_decentralized: bool,
 }
@@ -46,12 +52,16 @@ impl RoutingService {
         per_routing_service: u64,
         per_routing_byte: u64,
         is_decentralized: bool,
+        accounting_sample_interval: u32,
+        traffic_padder_opt: Option<Rc<RefCell<TrafficPadder>>>,
     ) -> RoutingService {
         RoutingService {
             cryptdes,
             routing_service_subs,
             per_routing_service,
             per_routing_byte,
+            accounting_sampler: RefCell::new(AccountingSampler::new(accounting_sample_interval)),
+            traffic_padder_opt,
             logger: Logger::new("RoutingService"),
             is_decentralized,
         }
@@ -86,6 +96,16 @@ impl RoutingService {
             }
         };
 
+        if live_package.cover {
+            trace!(
+                self.logger,
+                "Discarding {}-byte cover package from {}",
+                data_size,
+                peer_addr
+            );
+            return;
+        }
+
         let next_hop = match live_package.route.next_hop(self.cryptdes.main.borrow()) {
             Ok(hop) => hop,
             Err(e) => {
@@ -347,7 +367,28 @@ impl RoutingService {
                     ))
                     .expect("ProxyServer is dead")
             }
+            (Component::ProxyServer, MessageType::PaymentOverdue(vd)) => {
+                let notice = match PaymentOverdue_0v1::try_from(vd) {
+                    Ok(n) => n,
+                    Err(e) => {
+                        error!(self.logger, "Received unmigratable PaymentOverdue: {:?}", e);
+                        return;
+                    }
+                };
+                self.routing_service_subs
+                    .proxy_server_subs
+                    .payment_overdue_from_hopper
+                    .try_send(ExpiredCoresPackage::new(
+                        expired_package.immediate_neighbor,
+                        expired_package.paying_wallet,
+                        expired_package.remaining_route,
+                        notice,
+                        expired_package.payload_len,
+                    ))
+                    .expect("ProxyServer is dead")
+            }
             (Component::Neighborhood, MessageType::Gossip(vd)) => {
+                let data_version = vd.version();
                 let gossip = match Gossip_0v1::try_from(vd) {
                     Ok(g) => g,
                     Err(e) => {
@@ -362,7 +403,10 @@ impl RoutingService {
                         expired_package.immediate_neighbor,
                         expired_package.paying_wallet,
                         expired_package.remaining_route,
-                        gossip,
+                        ReceivedGossip {
+                            data_version,
+                            gossip,
+                        },
                         expired_package.payload_len,
                     ))
                     .expect("Neighborhood is dead")
@@ -387,6 +431,26 @@ impl RoutingService {
                     ))
                     .expect("Neighborhood is dead")
             }
+            (Component::Neighborhood, MessageType::Rendezvous(vd)) => {
+                let rendezvous = match Rendezvous_0v1::try_from(vd) {
+                    Ok(r) => r,
+                    Err(e) => {
+                        error!(self.logger, "Received unmigratable Rendezvous: {:?}", e);
+                        return;
+                    }
+                };
+                self.routing_service_subs
+                    .neighborhood_subs
+                    .rendezvous
+                    .try_send(ExpiredCoresPackage::new(
+                        expired_package.immediate_neighbor,
+                        expired_package.paying_wallet,
+                        expired_package.remaining_route,
+                        rendezvous,
+                        expired_package.payload_len,
+                    ))
+                    .expect("Neighborhood is dead")
+            }
             (destination, payload) => error!(
                 self.logger,
                 "Attempt to send invalid combination {:?} to {:?}", payload, destination
@@ -418,18 +482,24 @@ impl RoutingService {
                     );
                     return;
                 }
-                match self.routing_service_subs.to_accountant_routing.try_send(
-                    ReportRoutingServiceProvidedMessage {
-                        timestamp: SystemTime::now(),
-                        paying_wallet: payer.wallet,
-                        payload_size,
-                        service_rate: self.per_routing_service,
-                        byte_rate: self.per_routing_byte,
-                    },
-                ) {
-                    Ok(_) => (),
-                    Err(e) => {
-                        fatal!(self.logger, "Accountant is dead: {:?}", e);
+                let mut accounting_sampler = self.accounting_sampler.borrow_mut();
+                if let Some(package_count) = accounting_sampler.sample(&payer.wallet, last_data) {
+                    match self.routing_service_subs.to_accountant_routing.try_send(
+                        ReportRoutingServiceProvidedMessage {
+                            timestamp: SystemTime::now(),
+                            paying_wallet: payer.wallet,
+                            payload_size,
+                            service_rate: self.per_routing_service,
+                            byte_rate: self.per_routing_byte,
+                            sample_size_opt: accounting_sampler
+                                .is_sampling()
+                                .then_some(package_count),
+                        },
+                    ) {
+                        Ok(_) => (),
+                        Err(e) => {
+                            fatal!(self.logger, "Accountant is dead: {:?}", e);
+                        }
                     }
                 }
             }
@@ -467,7 +537,8 @@ impl RoutingService {
         live_package: LiveCoresPackage,
         last_data: bool,
     ) -> Result<TransmitDataMsg, CryptdecError> {
-        let (next_hop, next_live_package) =
+        let was_padded = live_package.is_padded();
+        let (next_hop, mut next_live_package) =
             match live_package.into_next_live(self.cryptdes.main.borrow()) {
                 Err(e) => {
                     let msg = format!(
@@ -479,6 +550,15 @@ impl RoutingService {
                 }
                 Ok(p) => p,
             };
+        if let Some(traffic_padder) = self.traffic_padder_opt.as_ref() {
+            let mut traffic_padder = traffic_padder.borrow_mut();
+            // Padding is negotiated route by route: the originator pads, and Nodes along the route
+            // that pad too keep it padded. We don't start padding a package that arrived bare.
+            if was_padded {
+                traffic_padder.pad(&mut next_live_package);
+            }
+            traffic_padder.record_transmission(&next_hop.public_key, Instant::now());
+        }
         let next_live_package_enc =
             match encodex(self.cryptdes.main, &next_hop.public_key, &next_live_package) {
                 Ok(nlpe) => nlpe,
@@ -502,14 +582,16 @@ mod tests {
     use super::*;
     use crate::accountant::db_access_objects::banned_dao::BAN_CACHE;
     use crate::bootstrapper::Bootstrapper;
-    use crate::neighborhood::gossip::{GossipBuilder, Gossip_0v1};
+    use crate::neighborhood::gossip::GossipBuilder;
     use crate::node_test_utils::check_timestamp;
     use crate::sub_lib::accountant::ReportRoutingServiceProvidedMessage;
     use crate::sub_lib::cryptde::{encodex, CryptDE, PlainData, PublicKey};
     use crate::sub_lib::cryptde_null::CryptDENull;
     use crate::sub_lib::cryptde_real::CryptDEReal;
-    use crate::sub_lib::hopper::{IncipientCoresPackage, MessageType, MessageType::ClientRequest};
-    use crate::sub_lib::neighborhood::GossipFailure_0v1;
+    use crate::sub_lib::hopper::{
+        IncipientCoresPackage, MessageType, MessageType::ClientRequest, TrafficPadding,
+    };
+    use crate::sub_lib::neighborhood::{GossipFailure_0v1, RendezvousKind, Rendezvous_0v1};
     use crate::sub_lib::peer_actors::PeerActors;
     use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, DnsResolveFailure_0v1};
     use crate::sub_lib::proxy_server::{ClientRequestPayload_0v1, ProxyProtocol};
@@ -578,6 +660,8 @@ mod tests {
             100,
             200,
             false,
+            1,
+            None,
         );
 
         subject.route(inbound_client_data);
@@ -623,6 +707,8 @@ mod tests {
             100,
             200,
             false,
+            1,
+            None,
         );
 
         subject.route(inbound_client_data);
@@ -673,6 +759,8 @@ mod tests {
             100,
             200,
             false,
+            1,
+            None,
         );
 
         subject.route(inbound_client_data);
@@ -725,6 +813,8 @@ mod tests {
             100,
             200,
             false,
+            1,
+            None,
         );
         subject.route(inbound_client_data);
         TestLogHandler::new().exists_log_matching("Attempt to send invalid combination .* to .*");
@@ -781,6 +871,8 @@ mod tests {
             0,
             0,
             false,
+            1,
+            None,
         );
 
         subject.route(inbound_client_data);
@@ -854,6 +946,8 @@ mod tests {
             0,
             0,
             false,
+            1,
+            None,
         );
 
         subject.route(inbound_client_data);
@@ -913,6 +1007,8 @@ mod tests {
             0,
             0,
             false,
+            1,
+            None,
         );
 
         subject.route(inbound_client_data);
@@ -994,6 +1090,8 @@ mod tests {
             0,
             0,
             false,
+            1,
+            None,
         );
 
         subject.route(inbound_client_data);
@@ -1001,7 +1099,7 @@ mod tests {
         System::current().stop();
         system.run();
         let component_recording = component_recording_arc.lock().unwrap();
-        let record = component_recording.get_record::<ExpiredCoresPackage<Gossip_0v1>>(0);
+        let record = component_recording.get_record::<ExpiredCoresPackage<ReceivedGossip>>(0);
         let expected_ecp = lcp_a
             .to_expired(
                 SocketAddr::from_str("1.3.2.4:5678").unwrap(),
@@ -1012,7 +1110,13 @@ mod tests {
         assert_eq!(record.immediate_neighbor, expected_ecp.immediate_neighbor);
         assert_eq!(record.paying_wallet, expected_ecp.paying_wallet);
         assert_eq!(record.remaining_route, expected_ecp.remaining_route);
-        assert_eq!(record.payload, payload);
+        assert_eq!(
+            record.payload,
+            ReceivedGossip {
+                data_version: dv!(0, 1),
+                gossip: payload
+            }
+        );
         assert_eq!(record.payload_len, expected_ecp.payload_len);
     }
 
@@ -1071,6 +1175,8 @@ mod tests {
             0,
             0,
             true,
+            1,
+            None,
         );
 
         subject.route(inbound_client_data);
@@ -1093,6 +1199,86 @@ mod tests {
         assert_eq!(record.payload_len, expected_ecp.payload_len);
     }
 
+    #[test]
+    fn converts_live_rendezvous_message_to_expired_for_neighborhood() {
+        let _eg = EnvironmentGuard::new();
+        BAN_CACHE.clear();
+        let cryptde = main_cryptde();
+        let (component, _, component_recording_arc) = make_recorder();
+        let mut route = Route::one_way(
+            RouteSegment::new(
+                vec![&cryptde.public_key(), &cryptde.public_key()],
+                Component::Neighborhood,
+            ),
+            cryptde,
+            None,
+            None,
+        )
+        .unwrap();
+        route.shift(cryptde).unwrap();
+        let rendezvous =
+            Rendezvous_0v1::new(RendezvousKind::Request, PublicKey::new(&[1, 2, 3, 4]), None);
+        let payload = MessageType::Rendezvous(VersionedData::new(
+            &crate::sub_lib::migrations::rendezvous::MIGRATIONS,
+            &rendezvous,
+        ));
+        let lcp = LiveCoresPackage::new(
+            route,
+            encodex::<MessageType>(cryptde, &cryptde.public_key(), &payload).unwrap(),
+        );
+        let data_enc = encodex(cryptde, &cryptde.public_key(), &lcp).unwrap();
+        let inbound_client_data = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: SocketAddr::from_str("1.3.2.4:5678").unwrap(),
+            reception_port: None,
+            last_data: false,
+            is_clandestine: true,
+            sequence_number: None,
+            data: data_enc.into(),
+        };
+
+        let system = System::new("converts_live_rendezvous_message_to_expired_for_neighborhood");
+        let peer_actors = peer_actors_builder().neighborhood(component).build();
+        let subject = RoutingService::new(
+            CryptDEPair {
+                main: cryptde,
+                alias: alias_cryptde(),
+            },
+            RoutingServiceSubs {
+                proxy_client_subs_opt: peer_actors.proxy_client_opt,
+                proxy_server_subs: peer_actors.proxy_server,
+                neighborhood_subs: peer_actors.neighborhood,
+                hopper_subs: peer_actors.hopper,
+                to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
+                to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+            },
+            0,
+            0,
+            true,
+            1,
+            None,
+        );
+
+        subject.route(inbound_client_data);
+
+        System::current().stop();
+        system.run();
+        let component_recording = component_recording_arc.lock().unwrap();
+        let record = component_recording.get_record::<ExpiredCoresPackage<Rendezvous_0v1>>(0);
+        let expected_ecp = lcp
+            .to_expired(
+                SocketAddr::from_str("1.3.2.4:5678").unwrap(),
+                cryptde,
+                cryptde,
+            )
+            .unwrap();
+        assert_eq!(record.immediate_neighbor, expected_ecp.immediate_neighbor);
+        assert_eq!(record.paying_wallet, expected_ecp.paying_wallet);
+        assert_eq!(record.remaining_route, expected_ecp.remaining_route);
+        assert_eq!(record.payload, rendezvous);
+        assert_eq!(record.payload_len, expected_ecp.payload_len);
+    }
+
     #[test]
     fn passes_on_inbound_client_data_not_meant_for_this_node() {
         let _eg = EnvironmentGuard::new();
@@ -1153,6 +1339,8 @@ mod tests {
             rate_pack_routing(103),
             rate_pack_routing_byte(103),
             false,
+            1,
+            None,
         );
         let before = SystemTime::now();
 
@@ -1187,10 +1375,221 @@ mod tests {
                 payload_size: lcp.payload.len(),
                 service_rate: rate_pack_routing(103),
                 byte_rate: rate_pack_routing_byte(103),
+                sample_size_opt: None,
             }
         )
     }
 
+    fn relay_through_padding_node(lcp: LiveCoresPackage, next_key: &PublicKey) -> LiveCoresPackage {
+        let _eg = EnvironmentGuard::new();
+        BAN_CACHE.clear();
+        let main_cryptde = main_cryptde();
+        let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
+        let inbound_client_data = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: SocketAddr::from_str("1.2.3.4:5678").unwrap(),
+            reception_port: None,
+            last_data: false,
+            is_clandestine: true,
+            sequence_number: None,
+            data: encodex(main_cryptde, main_cryptde.public_key(), &lcp)
+                .unwrap()
+                .into(),
+        };
+        let system = System::new("relay_through_padding_node");
+        let peer_actors = peer_actors_builder().dispatcher(dispatcher).build();
+        let subject = RoutingService::new(
+            make_cryptde_pair(),
+            make_routing_service_subs(peer_actors),
+            rate_pack_routing(103),
+            rate_pack_routing_byte(103),
+            false,
+            1,
+            Some(Rc::new(RefCell::new(TrafficPadder::new(TrafficPadding {
+                bucket_size: 256,
+                cover_per_minute: 2,
+            })))),
+        );
+
+        subject.route(inbound_client_data);
+
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
+        let record = dispatcher_recording.get_record::<TransmitDataMsg>(0);
+        assert_eq!(record.endpoint, Endpoint::Key(next_key.clone()));
+        decodex::<LiveCoresPackage>(
+            &CryptDENull::from(next_key, TEST_DEFAULT_CHAIN),
+            &CryptData::new(&record.data),
+        )
+        .unwrap()
+    }
+
+    fn make_relayable_lcp(next_key: &PublicKey) -> LiveCoresPackage {
+        let main_cryptde = main_cryptde();
+        let route = Route::one_way(
+            RouteSegment::new(
+                vec![main_cryptde.public_key(), next_key],
+                Component::Neighborhood,
+            ),
+            main_cryptde,
+            Some(make_paying_wallet(b"wallet")),
+            Some(TEST_DEFAULT_CHAIN.rec().contract),
+        )
+        .unwrap();
+        LiveCoresPackage::new(
+            route,
+            main_cryptde
+                .encode(next_key, &PlainData::new(&b"abcd"[..]))
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn padding_node_keeps_padded_routes_padded() {
+        let next_key = PublicKey::new(&[65, 65, 65]);
+        let mut lcp = make_relayable_lcp(&next_key);
+        lcp.pad_to_bucket(1024);
+
+        let result = relay_through_padding_node(lcp.clone(), &next_key);
+
+        let mut expected_lcp = lcp.into_next_live(main_cryptde()).unwrap().1;
+        expected_lcp.pad_to_bucket(256);
+        assert_eq!(result, expected_lcp);
+        assert_eq!(result.is_padded(), true);
+    }
+
+    #[test]
+    fn padding_node_leaves_bare_routes_bare() {
+        let next_key = PublicKey::new(&[65, 65, 65]);
+        let lcp = make_relayable_lcp(&next_key);
+
+        let result = relay_through_padding_node(lcp.clone(), &next_key);
+
+        assert_eq!(result, lcp.into_next_live(main_cryptde()).unwrap().1);
+        assert_eq!(result.is_padded(), false);
+    }
+
+    #[test]
+    fn cover_packages_are_discarded_unread() {
+        let main_cryptde = main_cryptde();
+        let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
+        let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
+        let mut lcp = LiveCoresPackage::cover(main_cryptde.public_key(), main_cryptde).unwrap();
+        lcp.pad_to_bucket(256);
+        let inbound_client_data = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: SocketAddr::from_str("1.2.3.4:5678").unwrap(),
+            reception_port: None,
+            last_data: false,
+            is_clandestine: true,
+            sequence_number: None,
+            data: encodex(main_cryptde, main_cryptde.public_key(), &lcp)
+                .unwrap()
+                .into(),
+        };
+        let system = System::new("cover_packages_are_discarded_unread");
+        let peer_actors = peer_actors_builder()
+            .dispatcher(dispatcher)
+            .neighborhood(neighborhood)
+            .build();
+        let subject = RoutingService::new(
+            make_cryptde_pair(),
+            make_routing_service_subs(peer_actors),
+            rate_pack_routing(103),
+            rate_pack_routing_byte(103),
+            false,
+            1,
+            None,
+        );
+
+        subject.route(inbound_client_data);
+
+        System::current().stop();
+        system.run();
+        assert_eq!(dispatcher_recording_arc.lock().unwrap().len(), 0);
+        assert_eq!(neighborhood_recording_arc.lock().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn sampling_routing_service_reports_only_every_nth_package_and_the_last_one() {
+        let _eg = EnvironmentGuard::new();
+        BAN_CACHE.clear();
+        let main_cryptde = main_cryptde();
+        let alias_cryptde = alias_cryptde();
+        let paying_wallet = make_paying_wallet(b"wallet");
+        let address_paying_wallet = Wallet::from(paying_wallet.address());
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let next_key = PublicKey::new(&[65, 65, 65]);
+        let contract_address = TEST_DEFAULT_CHAIN.rec().contract;
+        let route = Route::one_way(
+            RouteSegment::new(
+                vec![&main_cryptde.public_key(), &next_key],
+                Component::Neighborhood,
+            ),
+            main_cryptde,
+            Some(paying_wallet.clone()),
+            Some(contract_address.clone()),
+        )
+        .unwrap();
+        let payload = PlainData::new(&b"abcd"[..]);
+        let lcp = LiveCoresPackage::new(route, main_cryptde.encode(&next_key, &payload).unwrap());
+        let data_ser = PlainData::new(&serde_cbor::ser::to_vec(&lcp).unwrap()[..]);
+        let data_enc = main_cryptde
+            .encode(&main_cryptde.public_key(), &data_ser)
+            .unwrap();
+        let make_inbound_client_data = |last_data: bool| InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: SocketAddr::from_str("1.2.3.4:5678").unwrap(),
+            reception_port: None,
+            last_data,
+            is_clandestine: true,
+            sequence_number: None,
+            data: data_enc.clone().into(),
+        };
+        let system =
+            System::new("sampling_routing_service_reports_only_every_nth_package_and_the_last_one");
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        let subject = RoutingService::new(
+            CryptDEPair {
+                main: main_cryptde,
+                alias: alias_cryptde,
+            },
+            RoutingServiceSubs {
+                proxy_client_subs_opt: peer_actors.proxy_client_opt,
+                proxy_server_subs: peer_actors.proxy_server,
+                neighborhood_subs: peer_actors.neighborhood,
+                hopper_subs: peer_actors.hopper,
+                to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
+                to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+            },
+            rate_pack_routing(103),
+            rate_pack_routing_byte(103),
+            false,
+            3,
+            None,
+        );
+
+        vec![false, false, false, false, true]
+            .into_iter()
+            .for_each(|last_data| subject.route(make_inbound_client_data(last_data)));
+
+        System::current().stop();
+        system.run();
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(accountant_recording.len(), 2);
+        let sample_sizes = (0..2)
+            .map(|idx| {
+                let message =
+                    accountant_recording.get_record::<ReportRoutingServiceProvidedMessage>(idx);
+                assert_eq!(message.paying_wallet, address_paying_wallet);
+                assert_eq!(message.payload_size, lcp.payload.len());
+                message.sample_size_opt
+            })
+            .collect::<Vec<Option<u32>>>();
+        assert_eq!(sample_sizes, vec![Some(3), Some(2)]);
+    }
+
     #[test]
     fn reprocesses_inbound_client_data_meant_for_this_node_and_destined_for_hopper() {
         let _eg = EnvironmentGuard::new();
@@ -1248,6 +1647,8 @@ mod tests {
             rate_pack_routing(103),
             rate_pack_routing_byte(103),
             false,
+            1,
+            None,
         );
         let before = SystemTime::now();
 
@@ -1344,6 +1745,8 @@ mod tests {
             100,
             200,
             true,
+            1,
+            None,
         );
 
         subject.route(inbound_client_data);
@@ -1445,6 +1848,8 @@ mod tests {
             100,
             200,
             true,
+            1,
+            None,
         );
 
         subject.route(inbound_client_data);
@@ -1543,6 +1948,8 @@ mod tests {
             100,
             200,
             true,
+            1,
+            None,
         );
 
         subject.route_data_externally(
@@ -1619,6 +2026,8 @@ mod tests {
             rate_pack_routing(103),
             rate_pack_routing_byte(103),
             false,
+            1,
+            None,
         );
 
         subject.route(inbound_client_data);
@@ -1693,6 +2102,8 @@ mod tests {
             rate_pack_routing(103),
             rate_pack_routing_byte(103),
             false,
+            1,
+            None,
         );
 
         subject.route(inbound_client_data);
@@ -1743,6 +2154,8 @@ mod tests {
             100,
             200,
             false,
+            1,
+            None,
         );
 
         subject.route(inbound_client_data);
@@ -1804,6 +2217,8 @@ mod tests {
             100,
             200,
             false,
+            1,
+            None,
         );
 
         subject.route(inbound_client_data);
@@ -1836,6 +2251,8 @@ mod tests {
             100,
             200,
             false,
+            1,
+            None,
         );
         let lcp = LiveCoresPackage::new(Route { hops: vec![] }, CryptData::new(&[]));
         let ibcd = InboundClientData {
@@ -1879,6 +2296,8 @@ mod tests {
             100,
             200,
             true,
+            1,
+            None,
         );
         let route = Route::single_hop(&PublicKey::new(b"1234"), subject.cryptdes.main).unwrap();
         let payload = payload_factory(&subject.cryptdes);
@@ -1928,9 +2347,7 @@ mod tests {
                 cryptdes.alias.public_key(),
                 &MessageType::DnsResolveFailed(VersionedData::new(
                     &crate::sub_lib::migrations::dns_resolve_failure::MIGRATIONS,
-                    &DnsResolveFailure_0v1 {
-                        stream_key: StreamKey::make_meaningless_stream_key(),
-                    },
+                    &DnsResolveFailure_0v1::new(StreamKey::make_meaningless_stream_key()),
                 )),
             )
             .unwrap()
@@ -2000,6 +2417,8 @@ mod tests {
             100,
             200,
             false,
+            1,
+            None,
         );
         let expired_package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -2039,6 +2458,8 @@ mod tests {
             100,
             200,
             false,
+            1,
+            None,
         );
         let expired_package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -2078,6 +2499,8 @@ mod tests {
             100,
             200,
             false,
+            1,
+            None,
         );
         let expired_package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -2117,6 +2540,8 @@ mod tests {
             100,
             200,
             false,
+            1,
+            None,
         );
         let expired_package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -2156,6 +2581,8 @@ mod tests {
             100,
             200,
             false,
+            1,
+            None,
         );
         let expired_package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -2176,4 +2603,136 @@ mod tests {
             "ERROR: RoutingService: Received unmigratable GossipFailure: MigrationNotFound(DataVersion { major: 0, minor: 0 }, DataVersion { major: 0, minor: 1 })",
         );
     }
+
+    #[test]
+    fn route_expired_package_sends_payment_overdue_to_proxy_server() {
+        let (proxy_server, _, proxy_server_recording_arc) = make_recorder();
+        let peer_actors = peer_actors_builder().proxy_server(proxy_server).build();
+        let subject = RoutingService::new(
+            make_cryptde_pair(),
+            RoutingServiceSubs {
+                proxy_client_subs_opt: peer_actors.proxy_client_opt,
+                proxy_server_subs: peer_actors.proxy_server,
+                neighborhood_subs: peer_actors.neighborhood,
+                hopper_subs: peer_actors.hopper,
+                to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
+                to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+            },
+            100,
+            200,
+            false,
+            1,
+            None,
+        );
+        let notice = PaymentOverdue_0v1 {
+            balance_gwei: 1_234_567,
+            deadline: 1_700_000_000,
+        };
+        let expired_package = ExpiredCoresPackage::new(
+            SocketAddr::from_str("1.2.3.4:1234").unwrap(),
+            Some(make_paying_wallet(b"wallet")),
+            Route { hops: vec![] },
+            MessageType::from(notice.clone()),
+            56,
+        );
+        let system = System::new("route_expired_package_sends_payment_overdue_to_proxy_server");
+
+        subject.route_expired_package(Component::ProxyServer, expired_package, true);
+
+        System::current().stop_with_code(0);
+        system.run();
+        let proxy_server_recording = proxy_server_recording_arc.lock().unwrap();
+        assert_eq!(
+            proxy_server_recording.get_record::<ExpiredCoresPackage<PaymentOverdue_0v1>>(0),
+            &ExpiredCoresPackage::new(
+                SocketAddr::from_str("1.2.3.4:1234").unwrap(),
+                Some(make_paying_wallet(b"wallet")),
+                Route { hops: vec![] },
+                notice,
+                56,
+            )
+        );
+    }
+
+    #[test]
+    fn route_expired_package_handles_unmigratable_payment_overdue() {
+        init_test_logging();
+        let (proxy_server, _, proxy_server_recording_arc) = make_recorder();
+        let peer_actors = peer_actors_builder().proxy_server(proxy_server).build();
+        let subject = RoutingService::new(
+            make_cryptde_pair(),
+            RoutingServiceSubs {
+                proxy_client_subs_opt: peer_actors.proxy_client_opt,
+                proxy_server_subs: peer_actors.proxy_server,
+                neighborhood_subs: peer_actors.neighborhood,
+                hopper_subs: peer_actors.hopper,
+                to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
+                to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+            },
+            100,
+            200,
+            false,
+            1,
+            None,
+        );
+        let expired_package = ExpiredCoresPackage::new(
+            SocketAddr::from_str("1.2.3.4:1234").unwrap(),
+            None,
+            Route { hops: vec![] },
+            MessageType::PaymentOverdue(VersionedData::test_new(dv!(0, 0), vec![])),
+            0,
+        );
+        let system = System::new("route_expired_package_handles_unmigratable_payment_overdue");
+
+        subject.route_expired_package(Component::ProxyServer, expired_package, true);
+
+        System::current().stop_with_code(0);
+        system.run();
+        let proxy_server_recording = proxy_server_recording_arc.lock().unwrap();
+        assert_eq!(proxy_server_recording.len(), 0);
+        TestLogHandler::new().exists_log_containing(
+            "ERROR: RoutingService: Received unmigratable PaymentOverdue: MigrationNotFound(DataVersion { major: 0, minor: 0 }, DataVersion { major: 0, minor: 1 })",
+        );
+    }
+
+    #[test]
+    fn route_expired_package_handles_unmigratable_rendezvous() {
+        init_test_logging();
+        let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
+        let peer_actors = peer_actors_builder().neighborhood(neighborhood).build();
+        let subject = RoutingService::new(
+            make_cryptde_pair(),
+            RoutingServiceSubs {
+                proxy_client_subs_opt: peer_actors.proxy_client_opt,
+                proxy_server_subs: peer_actors.proxy_server,
+                neighborhood_subs: peer_actors.neighborhood,
+                hopper_subs: peer_actors.hopper,
+                to_dispatcher: peer_actors.dispatcher.from_dispatcher_client,
+                to_accountant_routing: peer_actors.accountant.report_routing_service_provided,
+            },
+            100,
+            200,
+            false,
+            1,
+            None,
+        );
+        let expired_package = ExpiredCoresPackage::new(
+            SocketAddr::from_str("1.2.3.4:1234").unwrap(),
+            None,
+            Route { hops: vec![] },
+            MessageType::Rendezvous(VersionedData::test_new(dv!(0, 0), vec![])),
+            0,
+        );
+        let system = System::new("route_expired_package_handles_unmigratable_rendezvous");
+
+        subject.route_expired_package(Component::Neighborhood, expired_package, true);
+
+        System::current().stop_with_code(0);
+        system.run();
+        let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(neighborhood_recording.len(), 0);
+        TestLogHandler::new().exists_log_containing(
+            "ERROR: RoutingService: Received unmigratable Rendezvous: MigrationNotFound(DataVersion { major: 0, minor: 0 }, DataVersion { major: 0, minor: 1 })",
+        );
+    }
 }
@@ -0,0 +1,125 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use super::live_cores_package::LiveCoresPackage;
+use crate::sub_lib::cryptde::PublicKey;
+use crate::sub_lib::hopper::TrafficPadding;
+use std::collections::HashMap;
+use std::time::Instant;
+
+// Shared by the ConsumingService and the RoutingService, so that a neighbor we've just relayed
+// something to doesn't count as idle when it's time for cover traffic.
+pub struct TrafficPadder {
+    padding: TrafficPadding,
+    last_transmissions: HashMap<PublicKey, Instant>,
+}
+
+impl TrafficPadder {
+    pub fn new(padding: TrafficPadding) -> Self {
+        Self {
+            padding,
+            last_transmissions: HashMap::new(),
+        }
+    }
+
+    pub fn pad(&self, live_package: &mut LiveCoresPackage) {
+        live_package.pad_to_bucket(self.padding.bucket_size)
+    }
+
+    pub fn record_transmission(&mut self, recipient: &PublicKey, now: Instant) {
+        self.last_transmissions.insert(recipient.clone(), now);
+    }
+
+    // Of the candidates we haven't sent anything to for at least a cover interval, the one we've
+    // neglected longest
+    pub fn idle_recipient_opt(&self, candidates: &[PublicKey], now: Instant) -> Option<PublicKey> {
+        let cover_interval = self.padding.cover_interval_opt()?;
+        candidates
+            .iter()
+            .map(|candidate| (candidate, self.last_transmissions.get(candidate)))
+            .filter(|(_, last_transmission_opt)| match last_transmission_opt {
+                Some(last_transmission) => {
+                    now.duration_since(**last_transmission) >= cover_interval
+                }
+                None => true,
+            })
+            .min_by_key(|(_, last_transmission_opt)| last_transmission_opt.copied())
+            .map(|(candidate, _)| candidate.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sub_lib::cryptde::CryptData;
+    use crate::test_utils::make_meaningless_route;
+    use std::time::Duration;
+
+    fn make_subject(cover_per_minute: u32) -> TrafficPadder {
+        TrafficPadder::new(TrafficPadding {
+            bucket_size: 256,
+            cover_per_minute,
+        })
+    }
+
+    #[test]
+    fn pads_to_the_configured_bucket_size() {
+        let subject = make_subject(2);
+        let mut live_package =
+            LiveCoresPackage::new(make_meaningless_route(), CryptData::new(&[1, 2, 3, 4]));
+
+        subject.pad(&mut live_package);
+
+        assert_eq!(
+            serde_cbor::ser::to_vec(&live_package).unwrap().len() % 256,
+            0
+        );
+    }
+
+    #[test]
+    fn prefers_neighbors_never_sent_to_then_the_longest_neglected() {
+        let mut subject = make_subject(2);
+        let recent = PublicKey::new(&[1]);
+        let older = PublicKey::new(&[2]);
+        let oldest = PublicKey::new(&[3]);
+        let never = PublicKey::new(&[4]);
+        let then = Instant::now();
+        let now = then + Duration::from_secs(120);
+        subject.record_transmission(&oldest, then);
+        subject.record_transmission(&older, then + Duration::from_secs(30));
+        subject.record_transmission(&recent, now - Duration::from_secs(29));
+
+        let candidates = vec![recent.clone(), older.clone(), oldest.clone(), never.clone()];
+        assert_eq!(subject.idle_recipient_opt(&candidates, now), Some(never));
+        let candidates = vec![recent.clone(), older.clone(), oldest.clone()];
+        assert_eq!(subject.idle_recipient_opt(&candidates, now), Some(oldest));
+        let candidates = vec![recent.clone(), older.clone()];
+        assert_eq!(subject.idle_recipient_opt(&candidates, now), Some(older));
+    }
+
+    #[test]
+    fn neighbors_sent_to_within_the_cover_interval_are_not_idle() {
+        let mut subject = make_subject(2);
+        let neighbor = PublicKey::new(&[1]);
+        let then = Instant::now();
+        subject.record_transmission(&neighbor, then);
+        let candidates = vec![neighbor.clone()];
+
+        assert_eq!(
+            subject.idle_recipient_opt(&candidates, then + Duration::from_secs(29)),
+            None
+        );
+        assert_eq!(
+            subject.idle_recipient_opt(&candidates, then + Duration::from_secs(30)),
+            Some(neighbor)
+        );
+    }
+
+    #[test]
+    fn nobody_is_idle_without_cover_traffic() {
+        let subject = make_subject(0);
+
+        let result = subject.idle_recipient_opt(&[PublicKey::new(&[1])], Instant::now());
+
+        assert_eq!(result, None);
+    }
+}
@@ -22,6 +22,7 @@ pub mod db_config;
 pub mod discriminator;
 pub mod dispatcher;
 pub mod entry_dns;
+pub mod health_check;
 pub mod hopper;
 pub mod http_request_start_finder;
 pub mod json_discriminator_factory;
@@ -31,10 +32,12 @@ mod listener_handler;
 pub mod masquerader;
 pub mod neighborhood;
 pub mod node_configurator;
+pub mod node_handle;
 mod null_masquerader;
 pub mod privilege_drop;
 pub mod proxy_client;
 pub mod proxy_server;
+pub mod replication;
 pub mod run_modes;
 pub mod run_modes_factories;
 pub mod server_initializer;
@@ -1,8 +1,8 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 use crate::bootstrapper::PortConfiguration;
 use crate::stream_messages::AddStreamMsg;
-use crate::sub_lib::stream_connector::StreamConnector;
 use crate::sub_lib::stream_connector::StreamConnectorReal;
+use crate::sub_lib::stream_connector::{SocketTuning, StreamConnector};
 use crate::sub_lib::tokio_wrappers::TokioListenerWrapper;
 use crate::sub_lib::tokio_wrappers::TokioListenerWrapperReal;
 use actix::Recipient;
@@ -25,7 +25,7 @@ pub trait ListenerHandler: Send + Future {
 }
 
 pub trait ListenerHandlerFactory: Send {
-    fn make(&self) -> Box<dyn ListenerHandler<Item = (), Error = ()>>;
+    fn make(&self, socket_tuning: SocketTuning) -> Box<dyn ListenerHandler<Item = (), Error = ()>>;
 }
 
 pub struct ListenerHandlerReal {
@@ -106,13 +106,13 @@ impl Future for ListenerHandlerReal {
 }
 
 impl ListenerHandlerReal {
-    fn new() -> ListenerHandlerReal {
+    fn new(socket_tuning: SocketTuning) -> ListenerHandlerReal {
         ListenerHandlerReal {
             port: None,
             port_configuration: None,
             listener: Box::new(TokioListenerWrapperReal::new()),
             add_stream_sub: None,
-            stream_connector: Box::new(StreamConnectorReal {}),
+            stream_connector: Box::new(StreamConnectorReal::new(socket_tuning)),
             logger: Logger::new("Uninitialized Listener"),
         }
     }
@@ -121,8 +121,8 @@ impl ListenerHandlerReal {
 pub struct ListenerHandlerFactoryReal {}
 
 impl ListenerHandlerFactory for ListenerHandlerFactoryReal {
-    fn make(&self) -> Box<dyn ListenerHandler<Item = (), Error = ()>> {
-        Box::new(ListenerHandlerReal::new())
+    fn make(&self, socket_tuning: SocketTuning) -> Box<dyn ListenerHandler<Item = (), Error = ()>> {
+        Box::new(ListenerHandlerReal::new(socket_tuning))
     }
 }
 
@@ -209,7 +209,7 @@ mod tests {
     #[test]
     #[should_panic(expected = "TcpListener not initialized - bind to a SocketAddr")]
     fn panics_if_tried_to_run_without_initializing() {
-        let subject = ListenerHandlerReal::new();
+        let subject = ListenerHandlerReal::new(SocketTuning::default());
         let _result = subject.wait();
     }
 
@@ -218,7 +218,7 @@ mod tests {
         let listener = TokioListenerWrapperMock::new()
             .bind_result(Err(Error::from(ErrorKind::AddrNotAvailable)));
         let discriminator_factory = NullDiscriminatorFactory::new();
-        let mut subject = ListenerHandlerReal::new();
+        let mut subject = ListenerHandlerReal::new(SocketTuning::default());
         subject.listener = Box::new(listener);
 
         let result = subject.bind_port_and_configuration(
@@ -235,7 +235,7 @@ mod tests {
         let listener_log = listener.log.clone();
         let discriminator_factory =
             NullDiscriminatorFactory::new().discriminator_nature(vec![b"booga".to_vec()]);
-        let mut subject = ListenerHandlerReal::new();
+        let mut subject = ListenerHandlerReal::new(SocketTuning::default());
         subject.listener = Box::new(listener);
 
         let result = subject.bind_port_and_configuration(
@@ -260,7 +260,7 @@ mod tests {
         let listener_log = listener.log.clone();
         let discriminator_factory =
             NullDiscriminatorFactory::new().discriminator_nature(vec![b"booga".to_vec()]);
-        let mut subject = ListenerHandlerReal::new();
+        let mut subject = ListenerHandlerReal::new(SocketTuning::default());
         subject.listener = Box::new(listener);
 
         let result = subject.bind_port_and_configuration(
@@ -303,7 +303,7 @@ mod tests {
                     Err(Error::from(ErrorKind::AddrNotAvailable)),
                     Ok(Async::NotReady),
                 ]);
-            let mut subject = ListenerHandlerReal::new();
+            let mut subject = ListenerHandlerReal::new(SocketTuning::default());
             subject.listener = Box::new(tokio_listener_wrapper);
             subject.bind_subs(add_stream_sub);
             subject
@@ -345,7 +345,7 @@ mod tests {
                     SocketAddr::from_str("1.2.3.4:5").unwrap(),
                 )))]);
             let stream_connector = StreamConnectorMock::new().split_stream_result(None);
-            let mut subject = ListenerHandlerReal::new();
+            let mut subject = ListenerHandlerReal::new(SocketTuning::default());
             subject.listener = Box::new(tokio_listener_wrapper);
             subject.stream_connector = Box::new(stream_connector);
             subject.bind_subs(add_stream_sub);
@@ -382,7 +382,7 @@ mod tests {
         let port = find_free_port();
         thread::spawn(move || {
             let add_stream_sub = rx.recv().unwrap();
-            let mut subject = ListenerHandlerReal::new();
+            let mut subject = ListenerHandlerReal::new(SocketTuning::default());
             subject.bind_subs(add_stream_sub);
             subject
                 .bind_port_and_configuration(port, PortConfiguration::new(vec![], false))
@@ -10,6 +10,7 @@ use crate::sub_lib::cryptde::{CryptDE, CryptData, PlainData, PublicKey};
 use crate::sub_lib::hopper::MessageType;
 use crate::sub_lib::node_addr::NodeAddr;
 use crate::sub_lib::versioned_data::StepError;
+use masq_lib::data_version::DataVersion;
 use pretty_hex::PrettyHex;
 use serde_cbor::Value;
 use serde_derive::{Deserialize, Serialize};
@@ -161,6 +162,13 @@ impl From<Gossip_0v1> for MessageType {
     }
 }
 
+// Gossip as it arrived from a neighbor, along with the data version the neighbor spoke
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ReceivedGossip {
+    pub data_version: DataVersion,
+    pub gossip: Gossip_0v1,
+}
+
 impl TryInto<Vec<AccessibleGossipRecord>> for Gossip_0v1 {
     type Error = String;
 
@@ -145,7 +145,13 @@ impl GossipHandler for DebutHandler {
             .as_ref()
             .expect("Source Node NodeAddr disappeared")
             .clone();
-        if let Some(preferred_key) = self.find_more_appropriate_neighbor(database, &source_agr) {
+        // A pinned Node's operator wants it as our neighbor, not passed along to someone else
+        let preferred_key_opt = if database.is_pinned(&source_key) {
+            None
+        } else {
+            self.find_more_appropriate_neighbor(database, &source_agr)
+        };
+        if let Some(preferred_key) = preferred_key_opt {
             let preferred_ip = database
                 .node_by_key(preferred_key)
                 .expect("Preferred Node disappeared")
@@ -267,7 +273,9 @@ impl DebutHandler {
         debuting_agr: &AccessibleGossipRecord,
         gossip_source: SocketAddr,
     ) -> Result<GossipAcceptanceResult, ()> {
-        if database.gossip_target_degree(database.root().public_key()) >= MAX_DEGREE {
+        if !database.is_pinned(&debuting_agr.inner.public_key)
+            && database.gossip_target_degree(database.root().public_key()) >= MAX_DEGREE
+        {
             debug!(self.logger, "Neighbor count already at maximum");
             return Err(());
         }
@@ -629,11 +637,14 @@ impl GossipHandler for IntroductionHandler {
         gossip_source: SocketAddr,
         neighborhood_metadata: NeighborhoodMetadata,
     ) -> GossipAcceptanceResult {
-        if database.root().full_neighbor_keys(database).len() >= MAX_DEGREE {
+        let (introducer, introducee) = Self::identify_players(agrs, gossip_source)
+            .expect("Introduction not properly qualified");
+        let involves_pinned_node = database.is_pinned(&introducer.inner.public_key)
+            || database.is_pinned(&introducee.inner.public_key);
+        let at_max_degree = database.root().full_neighbor_keys(database).len() >= MAX_DEGREE;
+        if at_max_degree && !involves_pinned_node {
             GossipAcceptanceResult::Ignored
         } else {
-            let (introducer, introducee) = Self::identify_players(agrs, gossip_source)
-                .expect("Introduction not properly qualified");
             let introducer_key = introducer.inner.public_key.clone();
             let introducer_ip_addr = introducer
                 .node_addr_opt
@@ -1119,7 +1130,9 @@ impl StandardGossipHandler {
             Some(node) => node,
         };
         let gossip_node_key = gossip_node.public_key().clone();
-        if database.root().full_neighbor_keys(database).len() >= MAX_DEGREE {
+        if !database.is_pinned(&gossip_node_key)
+            && database.root().full_neighbor_keys(database).len() >= MAX_DEGREE
+        {
             false
         } else {
             match database.add_half_neighbor(&gossip_node_key) {
@@ -1520,6 +1533,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn proper_debut_of_pinned_node_is_accepted_even_when_neighbor_count_is_at_maximum() {
+        let src_root = make_node_record(1234, true);
+        let mut src_db = db_from_node(&src_root);
+        let cryptde = CryptDENull::from(src_db.root().public_key(), TEST_DEFAULT_CHAIN);
+        let dest_root = make_node_record(2345, true);
+        let mut dest_db = db_from_node(&dest_root);
+        (0..MAX_DEGREE as u16).into_iter().for_each(|index| {
+            let common_neighbor = make_node_record(3456 + index, true);
+            src_db.add_node(common_neighbor.clone()).unwrap();
+            src_db.add_arbitrary_full_neighbor(src_root.public_key(), common_neighbor.public_key());
+            dest_db.add_node(common_neighbor.clone()).unwrap();
+            dest_db
+                .add_arbitrary_full_neighbor(dest_root.public_key(), common_neighbor.public_key());
+        });
+        dest_db.pin(src_root.public_key());
+        let gossip = GossipBuilder::new(&src_db)
+            .node(src_db.root().public_key(), true)
+            .build();
+        let agrs_vec: Vec<AccessibleGossipRecord> = gossip.try_into().unwrap();
+        let subject = DebutHandler::new(Logger::new("test"));
+
+        let result = subject.handle(
+            &cryptde,
+            &mut dest_db,
+            agrs_vec,
+            src_root.node_addr_opt().unwrap().into(),
+            make_default_neighborhood_metadata(),
+        );
+
+        assert_eq!(result, GossipAcceptanceResult::Accepted);
+        assert!(dest_db.has_half_neighbor(dest_root.public_key(), src_root.public_key()));
+    }
+
     #[test]
     fn debut_with_node_addr_not_accepting_connections_is_rejected() {
         let (mut gossip, _j, gossip_source) = make_debut(2345, Mode::OriginateOnly);
@@ -1995,6 +2042,41 @@ mod tests {
         assert_eq!(handle_result, GossipAcceptanceResult::Ignored);
     }
 
+    #[test]
+    fn introduction_of_pinned_node_is_processed_even_when_target_is_already_max_degree() {
+        let (gossip, gossip_source) = make_introduction(2345, 3456);
+        let dest_root = make_node_record(7878, true);
+        let mut dest_db = db_from_node(&dest_root);
+        for i in 0..MAX_DEGREE as u16 {
+            let key = dest_db.add_node(make_node_record(i, true)).unwrap();
+            dest_db.add_arbitrary_full_neighbor(dest_root.public_key(), &key);
+        }
+        let cryptde = CryptDENull::from(dest_db.root().public_key(), TEST_DEFAULT_CHAIN);
+        let subject = IntroductionHandler::new(Logger::new("test"));
+        let agrs: Vec<AccessibleGossipRecord> = gossip.try_into().unwrap();
+        dest_db.pin(&agrs[1].inner.public_key);
+
+        let handle_result = subject.handle(
+            &cryptde,
+            &mut dest_db,
+            agrs.clone(),
+            gossip_source,
+            make_default_neighborhood_metadata(),
+        );
+
+        let debut = GossipBuilder::new(&dest_db)
+            .node(dest_db.root().public_key(), true)
+            .build();
+        assert_eq!(
+            GossipAcceptanceResult::Reply(
+                debut,
+                agrs[1].inner.public_key.clone(),
+                agrs[1].node_addr_opt.clone().unwrap(),
+            ),
+            handle_result
+        );
+    }
+
     #[test]
     fn introduction_with_no_problems_is_processed_correctly_when_introducer_is_in_database_and_obsolete(
     ) {
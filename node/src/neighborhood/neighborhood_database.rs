@@ -13,20 +13,82 @@ use crate::sub_lib::wallet::Wallet;
 use itertools::Itertools;
 use masq_lib::logger::Logger;
 use masq_lib::utils::ExpectValue;
+use std::cmp::Reverse;
 use std::collections::HashSet;
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::fmt;
 use std::fmt::Debug;
+use std::fmt::Display;
 use std::fmt::Error;
 use std::fmt::Formatter;
 use std::net::IpAddr;
+use std::str::FromStr;
 
 pub const ISOLATED_NODE_GRACE_PERIOD_SECS: u32 = 30;
+pub const DEFAULT_MAX_NODE_DISTANCE: usize = 10;
+pub const DEFAULT_MAX_NODE_RECORDS: usize = 2_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NeighborhoodLimits {
+    // Hops from the root, following the neighborships we know about. A Node further away than this
+    // (or not reachable at all) can't appear in any route we'd build.
+    pub max_distance: usize,
+    pub max_node_records: usize,
+}
+
+impl Default for NeighborhoodLimits {
+    fn default() -> Self {
+        Self {
+            max_distance: DEFAULT_MAX_NODE_DISTANCE,
+            max_node_records: DEFAULT_MAX_NODE_RECORDS,
+        }
+    }
+}
+
+impl Display for NeighborhoodLimits {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}|{}", self.max_distance, self.max_node_records)
+    }
+}
+
+impl FromStr for NeighborhoodLimits {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = s
+            .split('|')
+            .map(|segment| segment.parse::<usize>())
+            .collect::<Result<Vec<usize>, _>>()
+            .map_err(|e| format!("Bad neighborhood-limits value '{}': {}", s, e))?;
+        match values.as_slice() {
+            [max_distance, max_node_records] if *max_distance > 0 && *max_node_records > 0 => {
+                Ok(Self {
+                    max_distance: *max_distance,
+                    max_node_records: *max_node_records,
+                })
+            }
+            _ => Err(format!(
+                "Bad neighborhood-limits value '{}': expected <max distance>|<max Node records>",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CompactionReport {
+    pub distant_nodes_dropped: usize,
+    pub excess_nodes_dropped: usize,
+    pub nodes_remaining: usize,
+}
 
 #[derive(Clone)]
 pub struct NeighborhoodDatabase {
     this_node: PublicKey,
     by_public_key: HashMap<PublicKey, NodeRecord>,
     by_ip_addr: HashMap<IpAddr, PublicKey>,
+    // Nodes the operator has vouched for; compaction and culling leave them alone
+    pinned_keys: BTreeSet<PublicKey>,
     logger: Logger,
 }
 
@@ -47,6 +109,7 @@ impl NeighborhoodDatabase {
             this_node: public_key.clone(),
             by_public_key: HashMap::new(),
             by_ip_addr: HashMap::new(),
+            pinned_keys: BTreeSet::new(),
             logger: Logger::new("NeighborhoodDatabase"),
         };
 
@@ -64,6 +127,7 @@ impl NeighborhoodDatabase {
                 .set_node_addr(&node_addr)
                 .expect("NodeAddr suddenly appeared out of nowhere");
         }
+        node_record.inner.exits_data = neighborhood_mode.exits_data();
         node_record.regenerate_signed_gossip(cryptde);
         result.add_arbitrary_node(node_record);
         result
@@ -108,6 +172,22 @@ impl NeighborhoodDatabase {
         self.has_half_neighbor(from, to) && self.has_half_neighbor(to, from)
     }
 
+    pub fn pin(&mut self, public_key: &PublicKey) {
+        self.pinned_keys.insert(public_key.clone());
+    }
+
+    pub fn unpin(&mut self, public_key: &PublicKey) {
+        self.pinned_keys.remove(public_key);
+    }
+
+    pub fn is_pinned(&self, public_key: &PublicKey) -> bool {
+        self.pinned_keys.contains(public_key)
+    }
+
+    pub fn pinned_keys(&self) -> &BTreeSet<PublicKey> {
+        &self.pinned_keys
+    }
+
     pub fn gossip_target_degree(&self, target: &PublicKey) -> usize {
         let target_node = match self.node_by_key(target) {
             None => return 0,
@@ -217,7 +297,7 @@ impl NeighborhoodDatabase {
         let to_cull: BTreeSet<PublicKey> = self
             .keys()
             .into_iter()
-            .filter(|k| !referenced_node_keys.contains(*k))
+            .filter(|k| !referenced_node_keys.contains(*k) && !self.is_pinned(k))
             .cloned()
             .filter(|k| {
                 self.node_by_key(k)
@@ -238,6 +318,42 @@ impl NeighborhoodDatabase {
         }
     }
 
+    // The root, its own neighbors and pinned Nodes are never dropped, even if that leaves more
+    // records than max_node_records.
+    pub fn compact(&mut self, limits: &NeighborhoodLimits) -> CompactionReport {
+        let distances = self.distances_from_root();
+        let distant_nodes: Vec<PublicKey> = self
+            .keys()
+            .into_iter()
+            .filter(|k| !self.is_pinned(k))
+            .filter(|k| match distances.get(*k) {
+                Some(distance) => *distance > limits.max_distance,
+                None => true,
+            })
+            .cloned()
+            .collect();
+        distant_nodes.iter().for_each(|k| self.remove_node(k));
+        let excess = self
+            .by_public_key
+            .len()
+            .saturating_sub(limits.max_node_records);
+        let excess_nodes: Vec<PublicKey> = self
+            .by_public_key
+            .values()
+            .filter(|node| !self.is_pinned(node.public_key()))
+            .filter(|node| distances[node.public_key()] > 1)
+            .sorted_by_key(|node| (Reverse(distances[node.public_key()]), node.last_updated()))
+            .take(excess)
+            .map(|node| node.public_key().clone())
+            .collect();
+        excess_nodes.iter().for_each(|k| self.remove_node(k));
+        CompactionReport {
+            distant_nodes_dropped: distant_nodes.len(),
+            excess_nodes_dropped: excess_nodes.len(),
+            nodes_remaining: self.by_public_key.len(),
+        }
+    }
+
     pub fn to_dot_graph(&self) -> String {
         let renderables = self.to_dot_renderables();
         render_dot_graph(renderables)
@@ -266,6 +382,38 @@ impl NeighborhoodDatabase {
         self.by_ip_addr.insert(public_ip, public_key);
     }
 
+    // Distances count a neighborship in either direction, so that a Node we haven't yet decided
+    // to neighbor back isn't mistaken for an unreachable one.
+    fn distances_from_root(&self) -> HashMap<PublicKey, usize> {
+        let mut adjacent: HashMap<&PublicKey, Vec<&PublicKey>> = HashMap::new();
+        self.by_public_key.values().for_each(|node| {
+            node.half_neighbor_keys()
+                .into_iter()
+                .filter(|k| self.by_public_key.contains_key(*k))
+                .for_each(|k| {
+                    adjacent.entry(node.public_key()).or_default().push(k);
+                    adjacent.entry(k).or_default().push(node.public_key());
+                })
+        });
+        let mut distances: HashMap<PublicKey, usize> = HashMap::new();
+        let mut to_visit: VecDeque<(&PublicKey, usize)> = VecDeque::new();
+        distances.insert(self.this_node.clone(), 0);
+        to_visit.push_back((&self.this_node, 0));
+        while let Some((key, distance)) = to_visit.pop_front() {
+            adjacent
+                .get(key)
+                .into_iter()
+                .flatten()
+                .for_each(|neighbor_key| {
+                    if !distances.contains_key(*neighbor_key) {
+                        distances.insert((*neighbor_key).clone(), distance + 1);
+                        to_visit.push_back((*neighbor_key, distance + 1));
+                    }
+                });
+        }
+        distances
+    }
+
     fn to_dot_renderables(&self) -> Vec<Box<dyn DotRenderable>> {
         let mut mentioned: HashSet<PublicKey> = HashSet::new();
         let mut present: HashSet<PublicKey> = HashSet::new();
@@ -362,6 +510,7 @@ pub enum NeighborhoodDatabaseError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::neighborhood::node_record::NodeRecordInner_0v1;
     use crate::sub_lib::cryptde_null::CryptDENull;
     use crate::sub_lib::utils::time_t_timestamp;
     use crate::test_utils::assert_string_contains;
@@ -374,6 +523,8 @@ mod tests {
     #[test]
     fn constants_have_correct_values() {
         assert_eq!(ISOLATED_NODE_GRACE_PERIOD_SECS, 30);
+        assert_eq!(DEFAULT_MAX_NODE_DISTANCE, 10);
+        assert_eq!(DEFAULT_MAX_NODE_RECORDS, 2_000);
     }
 
     #[test]
@@ -404,6 +555,31 @@ mod tests {
         assert_eq!(*root, this_node);
     }
 
+    #[test]
+    fn a_relay_only_database_advertises_that_its_root_does_not_exit() {
+        let this_node = make_node_record(1234, true);
+        let cryptde = CryptDENull::from(this_node.public_key(), TEST_DEFAULT_CHAIN);
+
+        let subject = NeighborhoodDatabase::new(
+            this_node.public_key(),
+            NeighborhoodMode::RelayOnly(
+                this_node.node_addr_opt().unwrap(),
+                vec![],
+                *this_node.rate_pack(),
+            ),
+            this_node.earning_wallet(),
+            &cryptde,
+        );
+
+        let root = subject.root();
+        assert_eq!(root.accepts_connections(), true);
+        assert_eq!(root.routes_data(), true);
+        assert_eq!(root.exits_data(), false);
+        let gossiped_inner: NodeRecordInner_0v1 =
+            serde_cbor::de::from_slice(root.signed_gossip().as_slice()).unwrap();
+        assert_eq!(gossiped_inner.exits_data, false);
+    }
+
     #[test]
     fn can_get_mutable_root() {
         let this_node = make_node_record(1234, true);
@@ -939,4 +1115,218 @@ mod tests {
         assert!(subject.node_by_key(referenced_by_dead).is_some());
         assert!(subject.node_by_key(dead).is_none());
     }
+
+    #[test]
+    fn cull_dead_nodes_spares_pinned_nodes() {
+        let root_node = make_node_record(1234, true);
+        let mut subject: NeighborhoodDatabase = db_from_node(&root_node);
+        let pinned = &subject.add_node(make_node_record(2345, true)).unwrap();
+        let unpinned = &subject.add_node(make_node_record(3456, true)).unwrap();
+        vec![pinned, unpinned].into_iter().for_each(|key| {
+            subject
+                .node_by_key_mut(key)
+                .unwrap()
+                .set_last_updated(time_t_timestamp() - ISOLATED_NODE_GRACE_PERIOD_SECS - 2)
+        });
+        subject.pin(pinned);
+
+        subject.cull_dead_nodes();
+
+        assert!(subject.node_by_key(pinned).is_some());
+        assert!(subject.node_by_key(unpinned).is_none());
+    }
+
+    #[test]
+    fn pinned_keys_can_be_pinned_and_unpinned() {
+        let mut subject = db_from_node(&make_node_record(1234, true));
+        let first = PublicKey::new(b"first");
+        let second = PublicKey::new(b"second");
+
+        subject.pin(&second);
+        subject.pin(&first);
+        subject.pin(&second);
+        let pinned_before = subject.pinned_keys().clone();
+        subject.unpin(&second);
+
+        assert_eq!(
+            pinned_before.into_iter().collect::<Vec<PublicKey>>(),
+            vec![first.clone(), second.clone()]
+        );
+        assert!(subject.is_pinned(&first));
+        assert!(!subject.is_pinned(&second));
+    }
+
+    #[test]
+    fn neighborhood_limits_round_trip_through_strings() {
+        let subject = NeighborhoodLimits::from_str("6|500").unwrap();
+
+        assert_eq!(
+            subject,
+            NeighborhoodLimits {
+                max_distance: 6,
+                max_node_records: 500,
+            }
+        );
+        assert_eq!(subject.to_string(), "6|500".to_string());
+        assert_eq!(
+            NeighborhoodLimits::default().to_string(),
+            "10|2000".to_string()
+        );
+    }
+
+    #[test]
+    fn neighborhood_limits_reject_bad_strings() {
+        assert_eq!(
+            NeighborhoodLimits::from_str("6|0"),
+            Err(
+                "Bad neighborhood-limits value '6|0': expected <max distance>|<max Node records>"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            NeighborhoodLimits::from_str("6"),
+            Err(
+                "Bad neighborhood-limits value '6': expected <max distance>|<max Node records>"
+                    .to_string()
+            )
+        );
+        assert!(NeighborhoodLimits::from_str("six|500").is_err());
+    }
+
+    #[test]
+    fn compact_drops_nodes_that_are_too_distant_or_unreachable() {
+        let root_node = make_node_record(1000, true);
+        let mut subject: NeighborhoodDatabase = db_from_node(&root_node);
+        let one_hop = subject.add_node(make_node_record(1001, true)).unwrap();
+        let two_hops = subject.add_node(make_node_record(1002, true)).unwrap();
+        let three_hops = subject.add_node(make_node_record(1003, true)).unwrap();
+        let referencing_root = subject.add_node(make_node_record(1004, true)).unwrap();
+        let unreachable = subject.add_node(make_node_record(1005, true)).unwrap();
+        subject.add_arbitrary_half_neighbor(root_node.public_key(), &one_hop);
+        subject.add_arbitrary_half_neighbor(&one_hop, &two_hops);
+        subject.add_arbitrary_half_neighbor(&three_hops, &two_hops);
+        subject.add_arbitrary_half_neighbor(&referencing_root, root_node.public_key());
+        let limits = NeighborhoodLimits {
+            max_distance: 2,
+            max_node_records: 100,
+        };
+
+        let result = subject.compact(&limits);
+
+        assert_eq!(
+            result,
+            CompactionReport {
+                distant_nodes_dropped: 2,
+                excess_nodes_dropped: 0,
+                nodes_remaining: 4,
+            }
+        );
+        assert_eq!(
+            subject.keys(),
+            vec![
+                root_node.public_key(),
+                &one_hop,
+                &two_hops,
+                &referencing_root
+            ]
+            .into_iter()
+            .collect::<HashSet<&PublicKey>>()
+        );
+        assert!(subject.node_by_key(&three_hops).is_none());
+        assert!(subject.node_by_key(&unreachable).is_none());
+    }
+
+    #[test]
+    fn compact_drops_the_most_distant_and_stalest_nodes_when_there_are_too_many() {
+        let root_node = make_node_record(1000, true);
+        let mut subject: NeighborhoodDatabase = db_from_node(&root_node);
+        let neighbor = subject.add_node(make_node_record(1001, true)).unwrap();
+        let fresh = subject.add_node(make_node_record(1002, true)).unwrap();
+        let stale = subject.add_node(make_node_record(1003, true)).unwrap();
+        let distant = subject.add_node(make_node_record(1004, true)).unwrap();
+        subject
+            .node_by_key_mut(&stale)
+            .unwrap()
+            .set_last_updated(time_t_timestamp() - 1_000);
+        subject.add_arbitrary_half_neighbor(root_node.public_key(), &neighbor);
+        subject.add_arbitrary_half_neighbor(&neighbor, &fresh);
+        subject.add_arbitrary_half_neighbor(&neighbor, &stale);
+        subject.add_arbitrary_half_neighbor(&fresh, &distant);
+        let limits = NeighborhoodLimits {
+            max_distance: 10,
+            max_node_records: 3,
+        };
+
+        let result = subject.compact(&limits);
+
+        assert_eq!(
+            result,
+            CompactionReport {
+                distant_nodes_dropped: 0,
+                excess_nodes_dropped: 2,
+                nodes_remaining: 3,
+            }
+        );
+        assert!(subject.node_by_key(&distant).is_none());
+        assert!(subject.node_by_key(&stale).is_none());
+        assert!(subject.node_by_key(&fresh).is_some());
+    }
+
+    #[test]
+    fn compact_never_drops_the_root_or_its_neighbors() {
+        let root_node = make_node_record(1000, true);
+        let mut subject: NeighborhoodDatabase = db_from_node(&root_node);
+        let neighbor_1 = subject.add_node(make_node_record(1001, true)).unwrap();
+        let neighbor_2 = subject.add_node(make_node_record(1002, true)).unwrap();
+        subject.add_arbitrary_half_neighbor(root_node.public_key(), &neighbor_1);
+        subject.add_arbitrary_half_neighbor(root_node.public_key(), &neighbor_2);
+        let limits = NeighborhoodLimits {
+            max_distance: 1,
+            max_node_records: 1,
+        };
+
+        let result = subject.compact(&limits);
+
+        assert_eq!(
+            result,
+            CompactionReport {
+                distant_nodes_dropped: 0,
+                excess_nodes_dropped: 0,
+                nodes_remaining: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn compact_never_drops_pinned_nodes() {
+        let root_node = make_node_record(1000, true);
+        let mut subject: NeighborhoodDatabase = db_from_node(&root_node);
+        let neighbor = subject.add_node(make_node_record(1001, true)).unwrap();
+        let pinned_distant = subject.add_node(make_node_record(1002, true)).unwrap();
+        let pinned_unreachable = subject.add_node(make_node_record(1003, true)).unwrap();
+        let unpinned_distant = subject.add_node(make_node_record(1004, true)).unwrap();
+        subject.add_arbitrary_half_neighbor(root_node.public_key(), &neighbor);
+        subject.add_arbitrary_half_neighbor(&neighbor, &pinned_distant);
+        subject.add_arbitrary_half_neighbor(&neighbor, &unpinned_distant);
+        subject.pin(&pinned_distant);
+        subject.pin(&pinned_unreachable);
+        let limits = NeighborhoodLimits {
+            max_distance: 2,
+            max_node_records: 2,
+        };
+
+        let result = subject.compact(&limits);
+
+        assert_eq!(
+            result,
+            CompactionReport {
+                distant_nodes_dropped: 0,
+                excess_nodes_dropped: 1,
+                nodes_remaining: 4,
+            }
+        );
+        assert!(subject.node_by_key(&pinned_distant).is_some());
+        assert!(subject.node_by_key(&pinned_unreachable).is_some());
+        assert!(subject.node_by_key(&unpinned_distant).is_none());
+    }
 }
@@ -23,9 +23,32 @@ pub struct NodeRecordInner_0v1 {
     pub neighbors: BTreeSet<PublicKey>,
     pub accepts_connections: bool,
     pub routes_data: bool,
+    // Only serialized when false, so that Gossip from Nodes that can exit looks the same as it
+    // always has to Nodes that don't know about this field
+    #[serde(
+        default = "exits_data_default",
+        skip_serializing_if = "exits_data_is_default"
+    )]
+    pub exits_data: bool,
+    // Only serialized when true, for the same reason: Nodes that don't pad traffic can't tell cover
+    // packages from real ones, so they mustn't be sent any
+    #[serde(default, skip_serializing_if = "pads_traffic_is_default")]
+    pub pads_traffic: bool,
     pub version: u32,
 }
 
+fn exits_data_default() -> bool {
+    true
+}
+
+fn exits_data_is_default(exits_data: &bool) -> bool {
+    *exits_data
+}
+
+fn pads_traffic_is_default(pads_traffic: &bool) -> bool {
+    !*pads_traffic
+}
+
 impl TryFrom<GossipNodeRecord> for NodeRecordInner_0v1 {
     type Error = String;
 
@@ -76,6 +99,8 @@ impl NodeRecord {
                 rate_pack,
                 accepts_connections,
                 routes_data,
+                exits_data: true,
+                pads_traffic: false,
                 neighbors: BTreeSet::new(),
                 version,
             },
@@ -206,6 +231,14 @@ impl NodeRecord {
         self.inner.routes_data
     }
 
+    pub fn exits_data(&self) -> bool {
+        self.inner.exits_data
+    }
+
+    pub fn pads_traffic(&self) -> bool {
+        self.inner.pads_traffic
+    }
+
     pub fn version(&self) -> u32 {
         self.inner.version
     }
@@ -949,4 +982,49 @@ mod tests {
         let final_serialized = serde_cbor::ser::to_vec(&subject.inner).unwrap();
         assert_eq!(&final_serialized[..], final_signed_gossip.as_slice());
     }
+
+    #[test]
+    fn exits_data_is_serialized_only_when_it_is_false() {
+        let mut subject = make_node_record(1234, true);
+        let exiting_serialized = serde_cbor::ser::to_vec(&subject.inner).unwrap();
+        subject.inner.exits_data = false;
+
+        let non_exiting_serialized = serde_cbor::ser::to_vec(&subject.inner).unwrap();
+
+        let contains_field_name = |serialized: &[u8]| {
+            serialized
+                .windows("exits_data".len())
+                .any(|window| window == b"exits_data")
+        };
+        assert_eq!(contains_field_name(&exiting_serialized), false);
+        assert_eq!(contains_field_name(&non_exiting_serialized), true);
+        let exiting_inner: NodeRecordInner_0v1 =
+            serde_cbor::de::from_slice(&exiting_serialized).unwrap();
+        let non_exiting_inner: NodeRecordInner_0v1 =
+            serde_cbor::de::from_slice(&non_exiting_serialized).unwrap();
+        assert_eq!(exiting_inner.exits_data, true);
+        assert_eq!(non_exiting_inner, subject.inner);
+    }
+    #[test]
+    fn pads_traffic_is_serialized_only_when_it_is_true() {
+        let mut subject = make_node_record(1234, true);
+        let bare_serialized = serde_cbor::ser::to_vec(&subject.inner).unwrap();
+        subject.inner.pads_traffic = true;
+
+        let padding_serialized = serde_cbor::ser::to_vec(&subject.inner).unwrap();
+
+        let contains_field_name = |serialized: &[u8]| {
+            serialized
+                .windows("pads_traffic".len())
+                .any(|window| window == b"pads_traffic")
+        };
+        assert_eq!(contains_field_name(&bare_serialized), false);
+        assert_eq!(contains_field_name(&padding_serialized), true);
+        let bare_inner: NodeRecordInner_0v1 = serde_cbor::de::from_slice(&bare_serialized).unwrap();
+        let padding_inner: NodeRecordInner_0v1 =
+            serde_cbor::de::from_slice(&padding_serialized).unwrap();
+        assert_eq!(bare_inner.pads_traffic, false);
+        assert_eq!(padding_inner, subject.inner);
+        assert_eq!(subject.pads_traffic(), true);
+    }
 }
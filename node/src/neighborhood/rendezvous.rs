@@ -0,0 +1,290 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::sub_lib::cryptde::PublicKey;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+pub const RENDEZVOUS_WINDOW: Duration = Duration::from_secs(60);
+pub const RENDEZVOUS_RETRY_INTERVAL: Duration = Duration::from_secs(300);
+pub const MAX_RENDEZVOUS_REQUESTS_PER_GOSSIP: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RendezvousBrokerLimits {
+    pub per_node_per_minute: u32,
+    pub total_per_minute: u32,
+}
+
+impl Display for RendezvousBrokerLimits {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}|{}", self.per_node_per_minute, self.total_per_minute)
+    }
+}
+
+impl FromStr for RendezvousBrokerLimits {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = s
+            .split('|')
+            .map(|segment| segment.parse::<u32>())
+            .collect::<Result<Vec<u32>, _>>()
+            .map_err(|e| format!("Bad rendezvous-broker value '{}': {}", s, e))?;
+        match values.as_slice() {
+            [per_node_per_minute, total_per_minute]
+                if *per_node_per_minute > 0 && *total_per_minute > 0 =>
+            {
+                Ok(Self {
+                    per_node_per_minute: *per_node_per_minute,
+                    total_per_minute: *total_per_minute,
+                })
+            }
+            _ => Err(format!(
+                "Bad rendezvous-broker value '{}': expected <per Node per minute>|<total per minute>",
+                s
+            )),
+        }
+    }
+}
+
+// Serving as a broker costs a publicly-reachable Node very little, but an unlimited broker could be
+// used to make two Nodes hammer each other with connection attempts, so introductions are metered
+// over a sliding one-minute window, both for each requester and altogether.
+pub struct RendezvousBroker {
+    limits: RendezvousBrokerLimits,
+    admissions: VecDeque<(PublicKey, Instant)>,
+}
+
+impl RendezvousBroker {
+    pub fn new(limits: RendezvousBrokerLimits) -> Self {
+        Self {
+            limits,
+            admissions: VecDeque::new(),
+        }
+    }
+
+    pub fn admit(&mut self, requester: &PublicKey, now: Instant) -> bool {
+        while let Some((_, timestamp)) = self.admissions.front() {
+            if now.duration_since(*timestamp) < RENDEZVOUS_WINDOW {
+                break;
+            }
+            self.admissions.pop_front();
+        }
+        let requester_admissions = self
+            .admissions
+            .iter()
+            .filter(|(key, _)| key == requester)
+            .count();
+        if self.admissions.len() >= self.limits.total_per_minute as usize
+            || requester_admissions >= self.limits.per_node_per_minute as usize
+        {
+            return false;
+        }
+        self.admissions.push_back((requester.clone(), now));
+        true
+    }
+}
+
+// Keeps a Node that can't accept connections from pestering its brokers: each peer is asked for at
+// most once per retry interval, and a broker that has said it won't broker is never asked again.
+#[derive(Default)]
+pub struct RendezvousRequester {
+    last_requests: HashMap<PublicKey, Instant>,
+    disabled_brokers: HashSet<PublicKey>,
+}
+
+impl RendezvousRequester {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn peers_to_request(
+        &mut self,
+        broker: &PublicKey,
+        candidates: Vec<PublicKey>,
+        now: Instant,
+    ) -> Vec<PublicKey> {
+        if self.disabled_brokers.contains(broker) {
+            return vec![];
+        }
+        self.last_requests
+            .retain(|_, timestamp| now.duration_since(*timestamp) < RENDEZVOUS_RETRY_INTERVAL);
+        let peers = candidates
+            .into_iter()
+            .filter(|peer| !self.last_requests.contains_key(peer))
+            .take(MAX_RENDEZVOUS_REQUESTS_PER_GOSSIP)
+            .collect::<Vec<PublicKey>>();
+        peers.iter().for_each(|peer| {
+            self.last_requests.insert(peer.clone(), now);
+        });
+        peers
+    }
+
+    pub fn broker_disabled(&mut self, broker: &PublicKey) {
+        self.disabled_brokers.insert(broker.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(RENDEZVOUS_WINDOW, Duration::from_secs(60));
+        assert_eq!(RENDEZVOUS_RETRY_INTERVAL, Duration::from_secs(300));
+        assert_eq!(MAX_RENDEZVOUS_REQUESTS_PER_GOSSIP, 3);
+    }
+
+    #[test]
+    fn rendezvous_broker_limits_round_trip_through_strings() {
+        let subject = RendezvousBrokerLimits::from_str("2|30").unwrap();
+
+        assert_eq!(
+            subject,
+            RendezvousBrokerLimits {
+                per_node_per_minute: 2,
+                total_per_minute: 30,
+            }
+        );
+        assert_eq!(subject.to_string(), "2|30");
+    }
+
+    #[test]
+    fn rendezvous_broker_limits_reject_bad_values() {
+        assert_eq!(
+            RendezvousBrokerLimits::from_str("0|30"),
+            Err(
+                "Bad rendezvous-broker value '0|30': expected <per Node per minute>|<total per minute>"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            RendezvousBrokerLimits::from_str("2|30|4"),
+            Err(
+                "Bad rendezvous-broker value '2|30|4': expected <per Node per minute>|<total per minute>"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            RendezvousBrokerLimits::from_str("two|30"),
+            Err("Bad rendezvous-broker value 'two|30': invalid digit found in string".to_string())
+        );
+    }
+
+    #[test]
+    fn broker_limits_each_requester() {
+        let mut subject = RendezvousBroker::new(RendezvousBrokerLimits {
+            per_node_per_minute: 2,
+            total_per_minute: 30,
+        });
+        let requester = PublicKey::new(&[1, 2, 3, 4]);
+        let another_requester = PublicKey::new(&[2, 3, 4, 5]);
+        let now = Instant::now();
+
+        assert_eq!(subject.admit(&requester, now), true);
+        assert_eq!(subject.admit(&requester, now), true);
+        assert_eq!(subject.admit(&requester, now), false);
+        assert_eq!(subject.admit(&another_requester, now), true);
+    }
+
+    #[test]
+    fn broker_limits_total_introductions() {
+        let mut subject = RendezvousBroker::new(RendezvousBrokerLimits {
+            per_node_per_minute: 2,
+            total_per_minute: 3,
+        });
+        let now = Instant::now();
+
+        assert_eq!(subject.admit(&PublicKey::new(&[1]), now), true);
+        assert_eq!(subject.admit(&PublicKey::new(&[2]), now), true);
+        assert_eq!(subject.admit(&PublicKey::new(&[3]), now), true);
+        assert_eq!(subject.admit(&PublicKey::new(&[4]), now), false);
+    }
+
+    #[test]
+    fn broker_forgets_admissions_older_than_the_window() {
+        let mut subject = RendezvousBroker::new(RendezvousBrokerLimits {
+            per_node_per_minute: 1,
+            total_per_minute: 30,
+        });
+        let requester = PublicKey::new(&[1, 2, 3, 4]);
+        let then = Instant::now();
+
+        assert_eq!(subject.admit(&requester, then), true);
+        assert_eq!(
+            subject.admit(
+                &requester,
+                then + RENDEZVOUS_WINDOW - Duration::from_secs(1)
+            ),
+            false
+        );
+        assert_eq!(subject.admit(&requester, then + RENDEZVOUS_WINDOW), true);
+    }
+
+    #[test]
+    fn requester_asks_for_each_peer_once_per_retry_interval() {
+        let mut subject = RendezvousRequester::new();
+        let broker = PublicKey::new(&[9, 9, 9, 9]);
+        let peer = PublicKey::new(&[1, 2, 3, 4]);
+        let then = Instant::now();
+
+        assert_eq!(
+            subject.peers_to_request(&broker, vec![peer.clone()], then),
+            vec![peer.clone()]
+        );
+        assert_eq!(
+            subject.peers_to_request(
+                &broker,
+                vec![peer.clone()],
+                then + RENDEZVOUS_RETRY_INTERVAL - Duration::from_secs(1)
+            ),
+            vec![]
+        );
+        assert_eq!(
+            subject.peers_to_request(
+                &broker,
+                vec![peer.clone()],
+                then + RENDEZVOUS_RETRY_INTERVAL
+            ),
+            vec![peer]
+        );
+    }
+
+    #[test]
+    fn requester_asks_for_only_a_few_peers_at_once() {
+        let mut subject = RendezvousRequester::new();
+        let broker = PublicKey::new(&[9, 9, 9, 9]);
+        let candidates = (1..=5u8)
+            .map(|n| PublicKey::new(&[n]))
+            .collect::<Vec<PublicKey>>();
+
+        let result = subject.peers_to_request(&broker, candidates.clone(), Instant::now());
+
+        assert_eq!(
+            result,
+            candidates[0..MAX_RENDEZVOUS_REQUESTS_PER_GOSSIP].to_vec()
+        );
+    }
+
+    #[test]
+    fn requester_stops_asking_a_disabled_broker() {
+        let mut subject = RendezvousRequester::new();
+        let broker = PublicKey::new(&[9, 9, 9, 9]);
+        let another_broker = PublicKey::new(&[8, 8, 8, 8]);
+        let peer = PublicKey::new(&[1, 2, 3, 4]);
+
+        subject.broker_disabled(&broker);
+
+        assert_eq!(
+            subject.peers_to_request(&broker, vec![peer.clone()], Instant::now()),
+            vec![]
+        );
+        assert_eq!(
+            subject.peers_to_request(&another_broker, vec![peer.clone()], Instant::now()),
+            vec![peer]
+        );
+    }
+}
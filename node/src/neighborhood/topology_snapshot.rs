@@ -0,0 +1,289 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::neighborhood::gossip::GossipNodeRecord;
+use crate::neighborhood::neighborhood_database::NeighborhoodDatabase;
+use crate::neighborhood::node_record::NodeRecord;
+use crate::neighborhood::AccessibleGossipRecord;
+use crate::sub_lib::cryptde::{CryptDE, PublicKey};
+use itertools::Itertools;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::convert::TryFrom;
+
+// Older than this, a snapshot says more about the neighborhood as it was than as it is
+pub const TOPOLOGY_SNAPSHOT_MAX_AGE_SECS: u32 = 900;
+// Restored Node records that no incoming Gossip has vouched for by then are dropped
+pub const TOPOLOGY_SNAPSHOT_CONFIRMATION_SECS: u32 = 180;
+
+// The neighborhood as this Node last saw it: every record but its own, still signed by its
+// owner, and which of those Nodes were its own neighbors
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TopologySnapshot {
+    pub taken_at: u32,
+    pub root_neighbors: Vec<PublicKey>,
+    pub node_records: Vec<GossipNodeRecord>,
+}
+
+impl TopologySnapshot {
+    pub fn take(database: &NeighborhoodDatabase, now: u32) -> Self {
+        let root_key = database.root().public_key();
+        TopologySnapshot {
+            taken_at: now,
+            root_neighbors: database
+                .root()
+                .half_neighbor_keys()
+                .into_iter()
+                .cloned()
+                .sorted()
+                .collect(),
+            node_records: database
+                .keys()
+                .into_iter()
+                .filter(|key| *key != root_key)
+                .sorted()
+                .map(|key| GossipNodeRecord::from((database, key, true)))
+                .collect(),
+        }
+    }
+
+    // Puts back every record whose owner's signature still holds and that the database doesn't
+    // already know, and returns the keys of the Nodes it put back
+    pub fn restore(
+        self,
+        database: &mut NeighborhoodDatabase,
+        cryptde: &dyn CryptDE,
+        now: u32,
+    ) -> Result<HashSet<PublicKey>, String> {
+        let age = now.saturating_sub(self.taken_at);
+        if age > TOPOLOGY_SNAPSHOT_MAX_AGE_SECS {
+            return Err(format!(
+                "it is {} seconds old; the limit is {}",
+                age, TOPOLOGY_SNAPSHOT_MAX_AGE_SECS
+            ));
+        }
+        let restored_keys = self
+            .node_records
+            .into_iter()
+            .flat_map(AccessibleGossipRecord::try_from)
+            .filter(|agr| {
+                cryptde.verify_signature(&agr.signed_gossip, &agr.signature, &agr.inner.public_key)
+            })
+            .flat_map(|agr| database.add_node(NodeRecord::from(agr)))
+            .collect::<HashSet<PublicKey>>();
+        let restored_neighbors = self
+            .root_neighbors
+            .iter()
+            .filter(|key| restored_keys.contains(*key))
+            .filter(|key| matches!(database.add_half_neighbor(key), Ok(true)))
+            .count();
+        if restored_neighbors > 0 {
+            let root = database.root_mut();
+            root.increment_version();
+            root.regenerate_signed_gossip(cryptde);
+        }
+        Ok(restored_keys)
+    }
+}
+
+// Restored Node records waiting for live Gossip to show that they still describe the neighborhood
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RestoredTopology {
+    pub restored_at: u32,
+    pub unconfirmed_keys: HashSet<PublicKey>,
+}
+
+impl RestoredTopology {
+    pub fn new(restored_at: u32, unconfirmed_keys: HashSet<PublicKey>) -> Self {
+        Self {
+            restored_at,
+            unconfirmed_keys,
+        }
+    }
+
+    pub fn confirm<'a>(&mut self, keys: impl Iterator<Item = &'a PublicKey>) {
+        keys.for_each(|key| {
+            self.unconfirmed_keys.remove(key);
+        })
+    }
+
+    pub fn is_due(&self, now: u32) -> bool {
+        now.saturating_sub(self.restored_at) >= TOPOLOGY_SNAPSHOT_CONFIRMATION_SECS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sub_lib::cryptde::CryptData;
+    use crate::sub_lib::cryptde_null::CryptDENull;
+    use crate::test_utils::neighborhood_test_utils::{db_from_node, make_node_record};
+    use masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN;
+
+    const NOW: u32 = 1_700_000_000;
+
+    fn make_database() -> NeighborhoodDatabase {
+        let root = make_node_record(1234, true);
+        let one_neighbor = make_node_record(2345, true);
+        let another_neighbor = make_node_record(3456, true);
+        let distant_node = make_node_record(4567, false);
+        let mut database = db_from_node(&root);
+        database.add_node(one_neighbor.clone()).unwrap();
+        database.add_node(another_neighbor.clone()).unwrap();
+        database.add_node(distant_node.clone()).unwrap();
+        database.add_arbitrary_full_neighbor(root.public_key(), one_neighbor.public_key());
+        database.add_arbitrary_full_neighbor(root.public_key(), another_neighbor.public_key());
+        database.add_arbitrary_full_neighbor(one_neighbor.public_key(), distant_node.public_key());
+        database
+    }
+
+    fn root_cryptde(database: &NeighborhoodDatabase) -> CryptDENull {
+        CryptDENull::from(database.root().public_key(), TEST_DEFAULT_CHAIN)
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(TOPOLOGY_SNAPSHOT_MAX_AGE_SECS, 900);
+        assert_eq!(TOPOLOGY_SNAPSHOT_CONFIRMATION_SECS, 180);
+    }
+
+    #[test]
+    fn take_records_everyone_but_the_root_and_the_roots_neighbors() {
+        let database = make_database();
+
+        let result = TopologySnapshot::take(&database, NOW);
+
+        assert_eq!(result.taken_at, NOW);
+        assert_eq!(
+            result.root_neighbors,
+            vec![
+                make_node_record(2345, true).public_key().clone(),
+                make_node_record(3456, true).public_key().clone()
+            ]
+        );
+        let keys = result
+            .node_records
+            .into_iter()
+            .map(|gnr| AccessibleGossipRecord::try_from(gnr).unwrap())
+            .map(|agr| (agr.inner.public_key, agr.node_addr_opt.is_some()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![
+                (make_node_record(2345, true).public_key().clone(), true),
+                (make_node_record(3456, true).public_key().clone(), true),
+                (make_node_record(4567, false).public_key().clone(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn restore_rebuilds_the_neighborhood_and_the_roots_neighbors() {
+        let original = make_database();
+        let snapshot = TopologySnapshot::take(&original, NOW - TOPOLOGY_SNAPSHOT_MAX_AGE_SECS);
+        let mut subject = db_from_node(&make_node_record(1234, true));
+        let cryptde = root_cryptde(&subject);
+        let root_version_before = subject.root().version();
+
+        let result = snapshot.restore(&mut subject, &cryptde, NOW);
+
+        assert_eq!(
+            result,
+            Ok(original
+                .keys()
+                .into_iter()
+                .filter(|key| *key != original.root().public_key())
+                .cloned()
+                .collect::<HashSet<PublicKey>>())
+        );
+        assert_eq!(
+            subject.root().half_neighbor_keys(),
+            original.root().half_neighbor_keys()
+        );
+        assert_eq!(subject.root().version(), root_version_before + 1);
+        assert!(cryptde.verify_signature(
+            subject.root().signed_gossip(),
+            subject.root().signature(),
+            subject.root().public_key()
+        ));
+        let distant_key = make_node_record(4567, false).public_key().clone();
+        assert_eq!(
+            subject.node_by_key(&distant_key).unwrap().inner,
+            original.node_by_key(&distant_key).unwrap().inner
+        );
+    }
+
+    #[test]
+    fn restore_refuses_a_stale_snapshot() {
+        let original = make_database();
+        let snapshot = TopologySnapshot::take(&original, NOW - TOPOLOGY_SNAPSHOT_MAX_AGE_SECS - 1);
+        let mut subject = db_from_node(&make_node_record(1234, true));
+        let cryptde = root_cryptde(&subject);
+
+        let result = snapshot.restore(&mut subject, &cryptde, NOW);
+
+        assert_eq!(
+            result,
+            Err("it is 901 seconds old; the limit is 900".to_string())
+        );
+        assert_eq!(subject.keys().len(), 1);
+    }
+
+    #[test]
+    fn restore_skips_forged_records_and_nodes_already_known() {
+        let original = make_database();
+        let mut snapshot = TopologySnapshot::take(&original, NOW);
+        snapshot.node_records[1].signature = CryptData::new(b"forged");
+        let mut subject = db_from_node(&make_node_record(1234, true));
+        let mut known_node = make_node_record(4567, false);
+        known_node.increment_version();
+        subject.add_node(known_node.clone()).unwrap();
+        let cryptde = root_cryptde(&subject);
+
+        let result = snapshot.restore(&mut subject, &cryptde, NOW);
+
+        let one_neighbor_key = make_node_record(2345, true).public_key().clone();
+        assert_eq!(
+            result,
+            Ok(vec![one_neighbor_key.clone()]
+                .into_iter()
+                .collect::<HashSet<PublicKey>>())
+        );
+        assert_eq!(
+            subject.root().half_neighbor_keys(),
+            vec![&one_neighbor_key]
+                .into_iter()
+                .collect::<HashSet<&PublicKey>>()
+        );
+        assert_eq!(subject.keys().len(), 3);
+        assert_eq!(
+            subject
+                .node_by_key(known_node.public_key())
+                .unwrap()
+                .version(),
+            known_node.version()
+        );
+    }
+
+    #[test]
+    fn restored_topology_is_due_after_the_confirmation_period_and_forgets_confirmed_keys() {
+        let one_key = PublicKey::new(&[1, 2, 3, 4]);
+        let another_key = PublicKey::new(&[2, 3, 4, 5]);
+        let mut subject = RestoredTopology::new(
+            NOW,
+            vec![one_key.clone(), another_key.clone()]
+                .into_iter()
+                .collect(),
+        );
+
+        subject.confirm(vec![one_key.clone(), PublicKey::new(&[9, 9, 9, 9])].iter());
+
+        assert_eq!(
+            subject.unconfirmed_keys,
+            vec![another_key]
+                .into_iter()
+                .collect::<HashSet<PublicKey>>()
+        );
+        assert!(!subject.is_due(NOW + TOPOLOGY_SNAPSHOT_CONFIRMATION_SECS - 1));
+        assert!(subject.is_due(NOW + TOPOLOGY_SNAPSHOT_CONFIRMATION_SECS));
+    }
+}
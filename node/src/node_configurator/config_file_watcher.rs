@@ -0,0 +1,137 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use masq_lib::logger::Logger;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+pub const CONFIG_FILE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+// Notices when the config file the Node was started with is edited, so that the Configurator can
+// apply the runtime-adjustable parameters in it without a restart or a SIGHUP. Like the
+// InterfaceWatcher, it polls, which works the same on every platform and needs no extra crate.
+pub struct ConfigFileWatcher {
+    config_file: PathBuf,
+    fingerprint_opt: Option<(SystemTime, u64)>,
+    logger: Logger,
+}
+
+impl ConfigFileWatcher {
+    pub fn new(config_file: PathBuf) -> Self {
+        let fingerprint_opt = Self::fingerprint(&config_file);
+        Self {
+            config_file,
+            fingerprint_opt,
+            logger: Logger::new("ConfigFileWatcher"),
+        }
+    }
+
+    // Returns true if the config file has changed since it was seen last. A missing file isn't a
+    // change to act on: editors often replace a file by removing it first, and the Configurator
+    // would only complain that it couldn't read it.
+    pub fn check(&mut self) -> bool {
+        let fingerprint = match Self::fingerprint(&self.config_file) {
+            Some(fingerprint) => fingerprint,
+            None => return false,
+        };
+        if self.fingerprint_opt.replace(fingerprint) == Some(fingerprint) {
+            return false;
+        }
+        info!(
+            self.logger,
+            "Config file {} has changed",
+            self.config_file.display()
+        );
+        true
+    }
+
+    // Modification times can be coarse; the length catches most edits made within the same tick
+    fn fingerprint(config_file: &Path) -> Option<(SystemTime, u64)> {
+        let metadata = fs::metadata(config_file).ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(CONFIG_FILE_CHECK_INTERVAL, Duration::from_secs(5));
+    }
+
+    #[test]
+    fn check_reports_nothing_while_the_config_file_stays_the_same() {
+        let home_dir = ensure_node_home_directory_exists(
+            "config_file_watcher",
+            "check_reports_nothing_while_the_config_file_stays_the_same",
+        );
+        let config_file = home_dir.join("config.toml");
+        fs::write(&config_file, "log-level = \"info\"\n").unwrap();
+        let mut subject = ConfigFileWatcher::new(config_file);
+
+        let result = subject.check();
+
+        assert_eq!(result, false);
+    }
+
+    #[test]
+    fn check_reports_an_edited_config_file_once() {
+        init_test_logging();
+        let home_dir = ensure_node_home_directory_exists(
+            "config_file_watcher",
+            "check_reports_an_edited_config_file_once",
+        );
+        let config_file = home_dir.join("config.toml");
+        fs::write(&config_file, "log-level = \"info\"\n").unwrap();
+        let mut subject = ConfigFileWatcher::new(config_file.clone());
+        fs::write(&config_file, "log-level = \"debug\"\n").unwrap();
+
+        let first_result = subject.check();
+        let second_result = subject.check();
+
+        assert_eq!(first_result, true);
+        assert_eq!(second_result, false);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: ConfigFileWatcher: Config file {} has changed",
+            config_file.display()
+        ));
+    }
+
+    #[test]
+    fn check_waits_out_a_missing_config_file_and_reports_the_one_that_returns() {
+        let home_dir = ensure_node_home_directory_exists(
+            "config_file_watcher",
+            "check_waits_out_a_missing_config_file_and_reports_the_one_that_returns",
+        );
+        let config_file = home_dir.join("config.toml");
+        fs::write(&config_file, "log-level = \"info\"\n").unwrap();
+        let mut subject = ConfigFileWatcher::new(config_file.clone());
+        fs::remove_file(&config_file).unwrap();
+
+        let missing_result = subject.check();
+        fs::write(&config_file, "log-level = \"trace\"\n").unwrap();
+        let returned_result = subject.check();
+
+        assert_eq!(missing_result, false);
+        assert_eq!(returned_result, true);
+    }
+
+    #[test]
+    fn check_reports_a_config_file_that_appears_after_startup() {
+        let home_dir = ensure_node_home_directory_exists(
+            "config_file_watcher",
+            "check_reports_a_config_file_that_appears_after_startup",
+        );
+        let config_file = home_dir.join("config.toml");
+        let mut subject = ConfigFileWatcher::new(config_file.clone());
+        fs::write(&config_file, "log-level = \"info\"\n").unwrap();
+
+        let result = subject.check();
+
+        assert_eq!(result, true);
+    }
+}
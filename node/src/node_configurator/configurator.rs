@@ -1,34 +1,44 @@
 // Copyright (c) 2019-2021, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+use std::convert::TryFrom;
 use std::path::PathBuf;
 use std::str::FromStr;
 
 use actix::{Actor, Context, Handler, Recipient};
 
 use masq_lib::messages::{
-    FromMessageBody, ToMessageBody, UiChangePasswordRequest, UiChangePasswordResponse,
-    UiCheckPasswordRequest, UiCheckPasswordResponse, UiConfigurationRequest,
-    UiConfigurationResponse, UiGenerateSeedSpec, UiGenerateWalletsRequest,
-    UiGenerateWalletsResponse, UiNewPasswordBroadcast, UiPaymentThresholds, UiRatePack,
-    UiRecoverWalletsRequest, UiRecoverWalletsResponse, UiScanIntervals, UiSetConfigurationRequest,
-    UiSetConfigurationResponse, UiWalletAddressesRequest, UiWalletAddressesResponse,
+    FromMessageBody, ToMessageBody, UiChangeLogLevelRequest, UiChangeLogLevelResponse,
+    UiChangePasswordRequest, UiChangePasswordResponse, UiCheckPasswordRequest,
+    UiCheckPasswordResponse, UiConfigurationRequest, UiConfigurationResponse, UiGenerateSeedSpec,
+    UiGenerateWalletsRequest, UiGenerateWalletsResponse, UiNewPasswordBroadcast,
+    UiPaymentThresholds, UiRatePack, UiRecoverWalletsRequest, UiRecoverWalletsResponse,
+    UiScanIntervals, UiSetConfigurationRequest, UiSetConfigurationResponse,
+    UiWalletAddressesRequest, UiWalletAddressesResponse,
 };
 use masq_lib::ui_gateway::MessageTarget::ClientId;
 use masq_lib::ui_gateway::{
     MessageBody, MessagePath, MessageTarget, NodeFromUiMessage, NodeToUiMessage,
 };
 
+use crate::apps::app_node;
 use crate::blockchain::bip32::Bip32EncryptionKeyProvider;
 use crate::blockchain::bip39::Bip39;
 use crate::database::db_initializer::DbInitializationConfig;
 use crate::database::db_initializer::{DbInitializer, DbInitializerReal};
+use crate::database::sqlite_pragmas::SqlitePragmas;
 use crate::db_config::config_dao::ConfigDaoReal;
 use crate::db_config::persistent_configuration::{
     PersistentConfigError, PersistentConfiguration, PersistentConfigurationReal,
 };
+use crate::node_configurator::parameter_consistency::check_payment_thresholds;
+use crate::server_initializer::{LogLevelChanger, LogLevelChangerReal};
+use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals};
+use crate::sub_lib::configurator::ReloadConfigFile;
 use crate::sub_lib::neighborhood::{ConfigChange, ConfigChangeMsg, Hops, WalletPair};
 use crate::sub_lib::peer_actors::{BindMessage, ConfigChangeSubs};
-use crate::sub_lib::utils::{db_connection_launch_panic, handle_ui_crash_request};
+use crate::sub_lib::utils::{
+    db_connection_launch_panic, handle_ui_crash_request, make_new_multi_config,
+};
 use crate::sub_lib::wallet::Wallet;
 use crate::test_utils::main_cryptde;
 use bip39::{Language, Mnemonic, MnemonicType, Seed};
@@ -37,7 +47,8 @@ use masq_lib::constants::{
     ILLEGAL_MNEMONIC_WORD_COUNT_ERROR, MISSING_DATA, MNEMONIC_PHRASE_ERROR, NON_PARSABLE_VALUE,
     UNKNOWN_ERROR, UNRECOGNIZED_MNEMONIC_LANGUAGE_ERROR, UNRECOGNIZED_PARAMETER,
 };
-use masq_lib::logger::Logger;
+use masq_lib::logger::{LogLevelOverrides, LogLevelSpec, Logger};
+use masq_lib::multi_config::ConfigFileVcl;
 use masq_lib::utils::{derivation_path, to_string};
 use rustc_hex::{FromHex, ToHex};
 use tiny_hderive::bip32::ExtendedPrivKey;
@@ -48,6 +59,8 @@ pub struct Configurator {
     persistent_config: Box<dyn PersistentConfiguration>,
     node_to_ui_sub_opt: Option<Recipient<NodeToUiMessage>>,
     config_change_subs_opt: Option<ConfigChangeSubs>,
+    log_level_changer: Box<dyn LogLevelChanger>,
+    config_file_opt: Option<PathBuf>,
     crashable: bool,
     logger: Logger,
 }
@@ -69,7 +82,9 @@ impl Handler<NodeFromUiMessage> for Configurator {
     type Result = ();
 
     fn handle(&mut self, msg: NodeFromUiMessage, _ctx: &mut Self::Context) -> Self::Result {
-        if let Ok((body, context_id)) = UiChangePasswordRequest::fmb(msg.body.clone()) {
+        if let Ok((body, context_id)) = UiChangeLogLevelRequest::fmb(msg.body.clone()) {
+            self.call_handler(msg, |c| c.handle_change_log_level(body, context_id));
+        } else if let Ok((body, context_id)) = UiChangePasswordRequest::fmb(msg.body.clone()) {
             let client_id = msg.client_id;
             self.call_handler(msg, |c| {
                 c.handle_change_password(body, client_id, context_id)
@@ -92,10 +107,21 @@ impl Handler<NodeFromUiMessage> for Configurator {
     }
 }
 
+impl Handler<ReloadConfigFile> for Configurator {
+    type Result = ();
+
+    fn handle(&mut self, _msg: ReloadConfigFile, _ctx: &mut Self::Context) -> Self::Result {
+        self.handle_reload_config_file()
+    }
+}
+
 type MessageError = (u64, String);
 
+// Parameters that can be changed in the config file and picked up without a restart, by name
+const RELOADABLE_PARAMETERS: [&str; 3] = ["gas-price", "payment-thresholds", "scan-intervals"];
+
 impl Configurator {
-    pub fn new(data_directory: PathBuf, crashable: bool) -> Self {
+    pub fn new(data_directory: PathBuf, config_file_opt: Option<PathBuf>, crashable: bool) -> Self {
         let initializer = DbInitializerReal::default();
         let conn = initializer
             .initialize(
@@ -110,11 +136,97 @@ impl Configurator {
             persistent_config,
             node_to_ui_sub_opt: None,
             config_change_subs_opt: None,
+            log_level_changer: Box::new(LogLevelChangerReal),
+            config_file_opt,
             crashable,
             logger: Logger::new("Configurator"),
         }
     }
 
+    fn handle_change_log_level(
+        &mut self,
+        msg: UiChangeLogLevelRequest,
+        context_id: u64,
+    ) -> MessageBody {
+        match LogLevelSpec::from_str(&msg.log_level) {
+            Ok(log_level) => {
+                self.change_log_level(log_level);
+                UiChangeLogLevelResponse {}.tmb(context_id)
+            }
+            Err(e) => MessageBody {
+                opcode: msg.opcode().to_string(),
+                path: MessagePath::Conversation(context_id),
+                payload: Err((NON_PARSABLE_VALUE, e)),
+            },
+        }
+    }
+
+    fn change_log_level(&mut self, log_level: LogLevelSpec) {
+        self.log_level_changer.change(log_level.clone());
+        info!(self.logger, "Log level changed to {}", log_level);
+    }
+
+    fn handle_reload_config_file(&mut self) {
+        let config_file = match self.config_file_opt.clone() {
+            Some(config_file) => config_file,
+            None => {
+                info!(
+                    self.logger,
+                    "Asked to reload the config file, but this Node has none"
+                );
+                return;
+            }
+        };
+        let multi_config = match ConfigFileVcl::new(&config_file, true)
+            .map_err(|e| e.to_string())
+            .and_then(|vcl| {
+                make_new_multi_config(&app_node(), vec![Box::new(vcl)])
+                    .map_err(|e| format!("{:?}", e))
+            }) {
+            Ok(multi_config) => multi_config,
+            Err(e) => {
+                warning!(
+                    self.logger,
+                    "Could not reload config file {}: {}",
+                    config_file.display(),
+                    e
+                );
+                return;
+            }
+        };
+        info!(
+            self.logger,
+            "Reloading runtime-adjustable parameters from {}",
+            config_file.display()
+        );
+        let log_level_opt = value_m!(multi_config, "log-level", LogLevelSpec);
+        let overrides_opt = value_m!(multi_config, "log-level-overrides", LogLevelOverrides);
+        if log_level_opt.is_some() || overrides_opt.is_some() {
+            self.change_log_level(
+                log_level_opt
+                    .unwrap_or_default()
+                    .overridden_by(&overrides_opt.unwrap_or_default()),
+            );
+        }
+        RELOADABLE_PARAMETERS.iter().for_each(|name| {
+            if let Some(value) = value_m!(multi_config, *name, String) {
+                let msg = UiSetConfigurationRequest {
+                    name: name.to_string(),
+                    value: value.clone(),
+                };
+                match self.unfriendly_handle_set_configuration(msg, 0) {
+                    Ok(_) => info!(self.logger, "{} changed to {}", name, value),
+                    Err((_, e)) => warning!(
+                        self.logger,
+                        "Could not apply {} from config file: {}",
+                        name,
+                        e
+                    ),
+                }
+            }
+        });
+    }
+
     fn handle_check_password(
         &mut self,
         msg: UiCheckPasswordRequest,
@@ -737,6 +849,9 @@ impl Configurator {
                 "gas-price" => self.set_gas_price(msg.value)?,
                 "min-hops" => self.set_min_hops(msg.value)?,
                 "start-block" => self.set_start_block(msg.value)?,
+                "db-pragmas" => self.set_db_pragmas(msg.value)?,
+                "scan-intervals" => self.set_scan_intervals(msg.value)?,
+                "payment-thresholds" => self.set_payment_thresholds(msg.value)?,
                 _ => {
                     return Err((
                         UNRECOGNIZED_PARAMETER,
@@ -786,6 +901,58 @@ impl Configurator {
         }
     }
 
+    fn set_scan_intervals(&mut self, string_intervals: String) -> Result<(), (u64, String)> {
+        let scan_intervals = match ScanIntervals::try_from(string_intervals.as_str()) {
+            Ok(scan_intervals) => scan_intervals,
+            Err(e) => return Err((NON_PARSABLE_VALUE, format!("scan intervals: {}", e))),
+        };
+        match self
+            .persistent_config
+            .set_scan_intervals(scan_intervals.to_string())
+        {
+            Ok(_) => {
+                self.send_config_change_msg(ConfigChangeMsg {
+                    change: ConfigChange::UpdateScanIntervals(scan_intervals),
+                });
+                Ok(())
+            }
+            Err(e) => Err((CONFIGURATOR_WRITE_ERROR, format!("scan intervals: {:?}", e))),
+        }
+    }
+
+    fn set_payment_thresholds(&mut self, string_thresholds: String) -> Result<(), (u64, String)> {
+        let payment_thresholds = match PaymentThresholds::try_from(string_thresholds.as_str()) {
+            Ok(payment_thresholds) => payment_thresholds,
+            Err(e) => return Err((NON_PARSABLE_VALUE, format!("payment thresholds: {}", e))),
+        };
+        if let Err(ce) = check_payment_thresholds(&payment_thresholds) {
+            let reasons = ce
+                .param_errors
+                .into_iter()
+                .map(|pe| pe.reason)
+                .collect::<Vec<_>>();
+            return Err((
+                NON_PARSABLE_VALUE,
+                format!("payment thresholds: {}", reasons.join(". ")),
+            ));
+        }
+        match self
+            .persistent_config
+            .set_payment_thresholds(payment_thresholds.to_string())
+        {
+            Ok(_) => {
+                self.send_config_change_msg(ConfigChangeMsg {
+                    change: ConfigChange::UpdatePaymentThresholds(payment_thresholds),
+                });
+                Ok(())
+            }
+            Err(e) => Err((
+                CONFIGURATOR_WRITE_ERROR,
+                format!("payment thresholds: {:?}", e),
+            )),
+        }
+    }
+
     fn set_start_block(&mut self, string_number: String) -> Result<(), (u64, String)> {
         let block_number_opt = if "none".eq_ignore_ascii_case(&string_number) {
             None
@@ -801,6 +968,24 @@ impl Configurator {
         }
     }
 
+    fn set_db_pragmas(&mut self, string_pragmas: String) -> Result<(), (u64, String)> {
+        let pragmas = match SqlitePragmas::from_str(&string_pragmas) {
+            Ok(pragmas) => pragmas,
+            Err(e) => return Err((NON_PARSABLE_VALUE, format!("db pragmas: {}", e))),
+        };
+        match self.persistent_config.set_sqlite_pragmas(pragmas) {
+            Ok(_) => {
+                info!(
+                    self.logger,
+                    "Database pragmas changed to {}; they will apply to database connections opened from now on, fully after a restart",
+                    pragmas
+                );
+                Ok(())
+            }
+            Err(e) => Err((CONFIGURATOR_WRITE_ERROR, format!("db pragmas: {:?}", e))),
+        }
+    }
+
     fn send_to_ui_gateway(&self, target: MessageTarget, body: MessageBody) {
         let msg = NodeToUiMessage { target, body };
         self.node_to_ui_sub_opt
@@ -881,6 +1066,8 @@ mod tests {
         UiScanIntervals, UiStartOrder, UiWalletAddressesRequest, UiWalletAddressesResponse,
     };
     use masq_lib::ui_gateway::{MessagePath, MessageTarget};
+    use std::fs::File;
+    use std::io::Write;
     use std::path::Path;
     use std::str::FromStr;
     use std::sync::{Arc, Mutex};
@@ -889,6 +1076,7 @@ mod tests {
     use crate::db_config::persistent_configuration::{
         PersistentConfigError, PersistentConfigurationReal,
     };
+    use crate::server_initializer::test_utils::LogLevelChangerMock;
     use crate::test_utils::persistent_configuration_mock::PersistentConfigurationMock;
     use crate::test_utils::recorder::{make_recorder, peer_actors_builder};
     use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
@@ -898,6 +1086,7 @@ mod tests {
     use crate::blockchain::bip39::Bip39;
     use crate::blockchain::test_utils::make_meaningless_phrase_words;
     use crate::database::db_initializer::{DbInitializer, DbInitializerReal};
+    use crate::database::sqlite_pragmas::{JournalMode, SynchronousLevel};
     use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals};
     use crate::sub_lib::cryptde::PublicKey as PK;
     use crate::sub_lib::cryptde::{CryptDE, PlainData};
@@ -932,7 +1121,7 @@ mod tests {
                 .unwrap(),
         )));
         let peer_actors = peer_actors_builder().build();
-        let mut subject = Configurator::new(data_dir, false);
+        let mut subject = Configurator::new(data_dir, None, false);
         subject.config_change_subs_opt = Some(peer_actors.config_change_subs());
         subject.node_to_ui_sub_opt = Some(peer_actors.ui_gateway.node_to_ui_message_sub);
 
@@ -959,7 +1148,7 @@ mod tests {
         );
 
         let act = |data_dir: &Path| {
-            Configurator::new(data_dir.to_path_buf(), false);
+            Configurator::new(data_dir.to_path_buf(), None, false);
         };
 
         assert_on_initialization_with_panic_on_migration(&data_dir, &act);
@@ -987,6 +1176,246 @@ mod tests {
         assert_eq!(recording.len(), 0);
     }
 
+    #[test]
+    fn change_log_level_works() {
+        init_test_logging();
+        let system = System::new("change_log_level_works");
+        let change_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = make_subject(None);
+        subject.log_level_changer =
+            Box::new(LogLevelChangerMock::default().change_params(&change_params_arc));
+        subject.logger = Logger::new("change_log_level_works");
+        let subject_addr = subject.start();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiChangeLogLevelRequest {
+                    log_level: "error,Neighborhood=debug".to_string(),
+                }
+                .tmb(4321),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let change_params = change_params_arc.lock().unwrap();
+        assert_eq!(
+            *change_params,
+            vec![LogLevelSpec::from_str("error,neighborhood=debug").unwrap()]
+        );
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            &NodeToUiMessage {
+                target: MessageTarget::ClientId(1234),
+                body: UiChangeLogLevelResponse {}.tmb(4321)
+            }
+        );
+        assert_eq!(ui_gateway_recording.len(), 1);
+        TestLogHandler::new().exists_log_containing(
+            "INFO: change_log_level_works: Log level changed to error,neighborhood=debug",
+        );
+    }
+
+    #[test]
+    fn change_log_level_rejects_unparsable_log_level() {
+        let change_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = make_subject(None);
+        subject.log_level_changer =
+            Box::new(LogLevelChangerMock::default().change_params(&change_params_arc));
+        let msg = UiChangeLogLevelRequest {
+            log_level: "warn,neighborhood".to_string(),
+        };
+
+        let result = subject.handle_change_log_level(msg, 4321);
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "changeLogLevel".to_string(),
+                path: MessagePath::Conversation(4321),
+                payload: Err((
+                    NON_PARSABLE_VALUE,
+                    LogLevelSpec::from_str("warn,neighborhood").unwrap_err()
+                ))
+            }
+        );
+        assert_eq!(change_params_arc.lock().unwrap().is_empty(), true);
+    }
+
+    #[test]
+    fn reload_config_file_applies_runtime_adjustable_parameters() {
+        init_test_logging();
+        let test_name = "reload_config_file_applies_runtime_adjustable_parameters";
+        let home_dir = ensure_node_home_directory_exists("configurator", test_name);
+        let config_file_path = home_dir.join("config.toml");
+        {
+            let mut config_file = File::create(&config_file_path).unwrap();
+            config_file
+                .write_all(
+                    b"log-level = \"debug\"\nlog-level-overrides = \"neighborhood=trace\"\n\
+                    gas-price = 77\nscan-intervals = \"60|600|1200\"\n\
+                    payment-thresholds = \"2000000000|2000|3000|400000000|5000|300000000\"\n\
+                    ip = \"1.2.3.4\"\n",
+                )
+                .unwrap();
+        }
+        let change_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_gas_price_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_scan_intervals_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_payment_thresholds_params_arc = Arc::new(Mutex::new(vec![]));
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_gas_price_params(&set_gas_price_params_arc)
+            .set_gas_price_result(Ok(()))
+            .set_payment_thresholds_params(&set_payment_thresholds_params_arc)
+            .set_payment_thresholds_result(Ok(()))
+            .set_scan_intervals_params(&set_scan_intervals_params_arc)
+            .set_scan_intervals_result(Ok(()));
+        let system = System::new(test_name);
+        let mut subject = make_subject(Some(persistent_config));
+        subject.log_level_changer =
+            Box::new(LogLevelChangerMock::default().change_params(&change_params_arc));
+        subject.config_file_opt = Some(config_file_path.clone());
+        subject.logger = Logger::new(test_name);
+        let subject_addr = subject.start();
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let peer_actors = peer_actors_builder()
+            .accountant(accountant)
+            .ui_gateway(ui_gateway)
+            .build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr.try_send(ReloadConfigFile {}).unwrap();
+
+        System::current().stop();
+        system.run();
+        let change_params = change_params_arc.lock().unwrap();
+        assert_eq!(
+            *change_params,
+            vec![LogLevelSpec::from_str("debug,neighborhood=trace").unwrap()]
+        );
+        let set_gas_price_params = set_gas_price_params_arc.lock().unwrap();
+        assert_eq!(*set_gas_price_params, vec![77]);
+        let set_scan_intervals_params = set_scan_intervals_params_arc.lock().unwrap();
+        assert_eq!(*set_scan_intervals_params, vec!["60|600|1200".to_string()]);
+        let set_payment_thresholds_params = set_payment_thresholds_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_payment_thresholds_params,
+            vec!["2000000000|2000|3000|400000000|5000|300000000".to_string()]
+        );
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(
+            accountant_recording.get_record::<ConfigChangeMsg>(0),
+            &ConfigChangeMsg {
+                change: ConfigChange::UpdatePaymentThresholds(PaymentThresholds {
+                    debt_threshold_gwei: 2_000_000_000,
+                    maturity_threshold_sec: 2_000,
+                    payment_grace_period_sec: 3_000,
+                    permanent_debt_allowed_gwei: 400_000_000,
+                    threshold_interval_sec: 5_000,
+                    unban_below_gwei: 300_000_000,
+                })
+            }
+        );
+        assert_eq!(
+            accountant_recording.get_record::<ConfigChangeMsg>(1),
+            &ConfigChangeMsg {
+                change: ConfigChange::UpdateScanIntervals(ScanIntervals {
+                    pending_payable_scan_interval: Duration::from_secs(60),
+                    payable_scan_interval: Duration::from_secs(600),
+                    receivable_scan_interval: Duration::from_secs(1200),
+                })
+            }
+        );
+        assert_eq!(ui_gateway_recording_arc.lock().unwrap().len(), 0);
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_containing(&format!(
+            "INFO: {}: Reloading runtime-adjustable parameters from {}",
+            test_name,
+            config_file_path.display()
+        ));
+        tlh.exists_log_containing(&format!(
+            "INFO: {}: Log level changed to debug,neighborhood=trace",
+            test_name
+        ));
+        tlh.exists_log_containing(&format!("INFO: {}: gas-price changed to 77", test_name));
+        tlh.exists_log_containing(&format!(
+            "INFO: {}: payment-thresholds changed to 2000000000|2000|3000|400000000|5000|300000000",
+            test_name
+        ));
+        tlh.exists_log_containing(&format!(
+            "INFO: {}: scan-intervals changed to 60|600|1200",
+            test_name
+        ));
+    }
+
+    #[test]
+    fn reload_config_file_complains_about_values_it_cannot_apply() {
+        init_test_logging();
+        let test_name = "reload_config_file_complains_about_values_it_cannot_apply";
+        let home_dir = ensure_node_home_directory_exists("configurator", test_name);
+        let config_file_path = home_dir.join("config.toml");
+        {
+            let mut config_file = File::create(&config_file_path).unwrap();
+            config_file.write_all(b"gas-price = 77\n").unwrap();
+        }
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_gas_price_result(Err(PersistentConfigError::TransactionError));
+        let mut subject = make_subject(Some(persistent_config));
+        subject.config_file_opt = Some(config_file_path);
+        subject.logger = Logger::new(test_name);
+
+        subject.handle_reload_config_file();
+
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {}: Could not apply gas-price from config file: gas price: TransactionError",
+            test_name
+        ));
+    }
+
+    #[test]
+    fn reload_config_file_complains_about_a_missing_config_file() {
+        init_test_logging();
+        let test_name = "reload_config_file_complains_about_a_missing_config_file";
+        let home_dir = ensure_node_home_directory_exists("configurator", test_name);
+        let config_file_path = home_dir.join("nonexistent.toml");
+        let mut subject = make_subject(None);
+        subject.config_file_opt = Some(config_file_path.clone());
+        subject.logger = Logger::new(test_name);
+
+        subject.handle_reload_config_file();
+
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {}: Could not reload config file {}: ",
+            test_name,
+            config_file_path.display()
+        ));
+    }
+
+    #[test]
+    fn reload_config_file_does_nothing_without_a_config_file() {
+        init_test_logging();
+        let test_name = "reload_config_file_does_nothing_without_a_config_file";
+        let change_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = make_subject(None);
+        subject.log_level_changer =
+            Box::new(LogLevelChangerMock::default().change_params(&change_params_arc));
+        subject.logger = Logger::new(test_name);
+
+        subject.handle_reload_config_file();
+
+        assert_eq!(change_params_arc.lock().unwrap().is_empty(), true);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {}: Asked to reload the config file, but this Node has none",
+            test_name
+        ));
+    }
+
     #[test]
     fn check_password_works() {
         let system = System::new("test");
@@ -2432,6 +2861,308 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn handle_set_configuration_works_for_db_pragmas() {
+        init_test_logging();
+        let test_name = "handle_set_configuration_works_for_db_pragmas";
+        let set_sqlite_pragmas_params_arc = Arc::new(Mutex::new(vec![]));
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_sqlite_pragmas_params(&set_sqlite_pragmas_params_arc)
+            .set_sqlite_pragmas_result(Ok(()));
+        let mut subject = make_subject(Some(persistent_config));
+        subject.logger = Logger::new(test_name);
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "db-pragmas".to_string(),
+                value: "delete|full|-4000|10000".to_string(),
+            },
+            4000,
+        );
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setConfiguration".to_string(),
+                path: MessagePath::Conversation(4000),
+                payload: Ok(r#"{}"#.to_string())
+            }
+        );
+        let set_sqlite_pragmas_params = set_sqlite_pragmas_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_sqlite_pragmas_params,
+            vec![SqlitePragmas {
+                journal_mode: JournalMode::Delete,
+                synchronous: SynchronousLevel::Full,
+                cache_size: -4000,
+                busy_timeout_ms: 10000,
+            }]
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {}: Database pragmas changed to DELETE|FULL|-4000|10000",
+            test_name
+        ));
+    }
+
+    #[test]
+    fn handle_set_configuration_complains_about_non_parsable_db_pragmas() {
+        let persistent_config = PersistentConfigurationMock::new();
+        let mut subject = make_subject(Some(persistent_config));
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "db-pragmas".to_string(),
+                value: "WAL|NORMAL".to_string(),
+            },
+            4000,
+        );
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setConfiguration".to_string(),
+                path: MessagePath::Conversation(4000),
+                payload: Err((
+                    NON_PARSABLE_VALUE,
+                    "db pragmas: Bad db-pragmas value 'WAL|NORMAL': expected <journal mode>|<synchronous level>|<cache size>|<busy timeout in ms>, like WAL|NORMAL|-2000|5000".to_string()
+                ))
+            }
+        );
+    }
+
+    #[test]
+    fn handle_set_configuration_handles_failure_on_db_pragmas_database_issue() {
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_sqlite_pragmas_result(Err(PersistentConfigError::TransactionError));
+        let mut subject = make_subject(Some(persistent_config));
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "db-pragmas".to_string(),
+                value: "WAL|NORMAL|-2000|5000".to_string(),
+            },
+            4000,
+        );
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setConfiguration".to_string(),
+                path: MessagePath::Conversation(4000),
+                payload: Err((
+                    CONFIGURATOR_WRITE_ERROR,
+                    "db pragmas: TransactionError".to_string()
+                ))
+            }
+        );
+    }
+
+    #[test]
+    fn handle_set_configuration_works_for_scan_intervals() {
+        let set_scan_intervals_params_arc = Arc::new(Mutex::new(vec![]));
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_scan_intervals_params(&set_scan_intervals_params_arc)
+            .set_scan_intervals_result(Ok(()));
+        let system = System::new("handle_set_configuration_works_for_scan_intervals");
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        let mut subject = make_subject(Some(persistent_config));
+        subject.config_change_subs_opt = Some(peer_actors.config_change_subs());
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "scan-intervals".to_string(),
+                value: "60|600|1200".to_string(),
+            },
+            4000,
+        );
+
+        System::current().stop();
+        system.run();
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setConfiguration".to_string(),
+                path: MessagePath::Conversation(4000),
+                payload: Ok(r#"{}"#.to_string())
+            }
+        );
+        let set_scan_intervals_params = set_scan_intervals_params_arc.lock().unwrap();
+        assert_eq!(*set_scan_intervals_params, vec!["60|600|1200".to_string()]);
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(
+            accountant_recording.get_record::<ConfigChangeMsg>(0),
+            &ConfigChangeMsg {
+                change: ConfigChange::UpdateScanIntervals(ScanIntervals {
+                    pending_payable_scan_interval: Duration::from_secs(60),
+                    payable_scan_interval: Duration::from_secs(600),
+                    receivable_scan_interval: Duration::from_secs(1200),
+                })
+            }
+        );
+    }
+
+    #[test]
+    fn handle_set_configuration_complains_about_non_parsable_scan_intervals() {
+        let persistent_config = PersistentConfigurationMock::new();
+        let mut subject = make_subject(Some(persistent_config));
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "scan-intervals".to_string(),
+                value: "60|600".to_string(),
+            },
+            4000,
+        );
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setConfiguration".to_string(),
+                path: MessagePath::Conversation(4000),
+                payload: Err((
+                    NON_PARSABLE_VALUE,
+                    "scan intervals: Wrong number of values: expected 3 but 2 supplied".to_string()
+                ))
+            }
+        );
+    }
+
+    #[test]
+    fn handle_set_configuration_handles_failure_on_scan_intervals_database_issue() {
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_scan_intervals_result(Err(PersistentConfigError::TransactionError));
+        let mut subject = make_subject(Some(persistent_config));
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "scan-intervals".to_string(),
+                value: "60|600|1200".to_string(),
+            },
+            4000,
+        );
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setConfiguration".to_string(),
+                path: MessagePath::Conversation(4000),
+                payload: Err((
+                    CONFIGURATOR_WRITE_ERROR,
+                    "scan intervals: TransactionError".to_string()
+                ))
+            }
+        );
+    }
+
+    #[test]
+    fn handle_set_configuration_works_for_payment_thresholds() {
+        let set_payment_thresholds_params_arc = Arc::new(Mutex::new(vec![]));
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_payment_thresholds_params(&set_payment_thresholds_params_arc)
+            .set_payment_thresholds_result(Ok(()));
+        let system = System::new("handle_set_configuration_works_for_payment_thresholds");
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        let mut subject = make_subject(Some(persistent_config));
+        subject.config_change_subs_opt = Some(peer_actors.config_change_subs());
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "payment-thresholds".to_string(),
+                value: "2000000000|2000|3000|400000000|5000|300000000".to_string(),
+            },
+            4000,
+        );
+
+        System::current().stop();
+        system.run();
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setConfiguration".to_string(),
+                path: MessagePath::Conversation(4000),
+                payload: Ok(r#"{}"#.to_string())
+            }
+        );
+        let set_payment_thresholds_params = set_payment_thresholds_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_payment_thresholds_params,
+            vec!["2000000000|2000|3000|400000000|5000|300000000".to_string()]
+        );
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(
+            accountant_recording.get_record::<ConfigChangeMsg>(0),
+            &ConfigChangeMsg {
+                change: ConfigChange::UpdatePaymentThresholds(PaymentThresholds {
+                    debt_threshold_gwei: 2_000_000_000,
+                    maturity_threshold_sec: 2_000,
+                    payment_grace_period_sec: 3_000,
+                    permanent_debt_allowed_gwei: 400_000_000,
+                    threshold_interval_sec: 5_000,
+                    unban_below_gwei: 300_000_000,
+                })
+            }
+        );
+    }
+
+    #[test]
+    fn handle_set_configuration_complains_about_non_parsable_payment_thresholds() {
+        let persistent_config = PersistentConfigurationMock::new();
+        let mut subject = make_subject(Some(persistent_config));
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "payment-thresholds".to_string(),
+                value: "2000000000|2000".to_string(),
+            },
+            4000,
+        );
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setConfiguration".to_string(),
+                path: MessagePath::Conversation(4000),
+                payload: Err((
+                    NON_PARSABLE_VALUE,
+                    "payment thresholds: Wrong number of values: expected 6 but 2 supplied"
+                        .to_string()
+                ))
+            }
+        );
+    }
+
+    #[test]
+    fn handle_set_configuration_complains_about_inconsistent_payment_thresholds() {
+        let persistent_config = PersistentConfigurationMock::new();
+        let mut subject = make_subject(Some(persistent_config));
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "payment-thresholds".to_string(),
+                value: "2000000000|2000|3000|400000000|5000|500000000".to_string(),
+            },
+            4000,
+        );
+
+        // No set_payment_thresholds result prepared: the test would panic if it were saved
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setConfiguration".to_string(),
+                path: MessagePath::Conversation(4000),
+                payload: Err((
+                    NON_PARSABLE_VALUE,
+                    "payment thresholds: Value of UnbanBelowGwei (500000000) must not be bigger \
+                     than PermanentDebtAllowedGwei (400000000); otherwise a Node could be unbanned \
+                     while it still owes enough to be banned again"
+                        .to_string()
+                ))
+            }
+        );
+    }
+
     #[test]
     fn handle_set_configuration_complains_about_unexpected_parameter() {
         let persistent_config = PersistentConfigurationMock::new();
@@ -2996,6 +3727,8 @@ mod tests {
                 persistent_config,
                 node_to_ui_sub_opt: None,
                 config_change_subs_opt: None,
+                log_level_changer: Box::new(LogLevelChangerMock::default()),
+                config_file_opt: None,
                 crashable: false,
                 logger: Logger::new("Configurator"),
             }
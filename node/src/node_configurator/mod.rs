@@ -1,8 +1,10 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+pub mod config_file_watcher;
 pub mod configurator;
 pub mod node_configurator_initialization;
 pub mod node_configurator_standard;
+pub mod parameter_consistency;
 pub mod unprivileged_parse_args_configuration;
 
 use crate::bootstrapper::RealUser;
@@ -1,10 +1,11 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+use crate::actor_system_factory::{available_cores, ActorThreads};
 use crate::bootstrapper::BootstrapperConfig;
 use crate::node_configurator::{initialize_database, DirsWrapper, FieldPair, NodeConfigurator};
 use crate::node_configurator::{ConfigInitializationData, DirsWrapperReal};
 use masq_lib::crash_point::CrashPoint;
-use masq_lib::logger::Logger;
+use masq_lib::logger::{LogFormat, LogLevelOverrides, LogLevelSpec, Logger};
 use masq_lib::multi_config::{MultiConfig, VirtualCommandLine};
 use masq_lib::shared_schema::ConfiguratorError;
 use masq_lib::utils::NeighborhoodModeLight;
@@ -12,13 +13,14 @@ use std::net::SocketAddr;
 use std::net::{IpAddr, Ipv4Addr};
 
 use clap::value_t;
-use log::LevelFilter;
 
 use crate::apps::app_node;
 use crate::bootstrapper::PortConfiguration;
-use crate::database::db_initializer::{DbInitializationConfig, ExternalData};
+use crate::database::db_initializer::{DbInitializationConfig, DbInitializerReal, ExternalData};
 use crate::db_config::persistent_configuration::PersistentConfiguration;
 use crate::http_request_start_finder::HttpRequestDiscriminatorFactory;
+use crate::neighborhood::neighborhood_database::NeighborhoodLimits;
+use crate::neighborhood::rendezvous::RendezvousBrokerLimits;
 use crate::node_configurator::unprivileged_parse_args_configuration::{
     UnprivilegedParseArgsConfiguration, UnprivilegedParseArgsConfigurationDaoReal,
 };
@@ -26,12 +28,22 @@ use crate::node_configurator::{
     data_directory_from_context, determine_user_specific_data,
     real_user_data_directory_path_and_chain,
 };
+use crate::proxy_server::route_pool::RoutePoolConfig;
 use crate::sub_lib::cryptde::PublicKey;
 use crate::sub_lib::cryptde_null::CryptDENull;
+use crate::sub_lib::hopper::{PerformancePreset, TrafficPadding};
+use crate::sub_lib::neighborhood::{PinnedNeighbors, VersionQuarantinePolicy};
+use crate::sub_lib::operator_notes::OperatorNotes;
+use crate::sub_lib::proxy_client::ExitHeaderPolicy;
+use crate::sub_lib::stream_connector::SocketTuning;
 use crate::sub_lib::utils::make_new_multi_config;
 use crate::tls_discriminator_factory::TlsDiscriminatorFactory;
-use masq_lib::constants::{DEFAULT_UI_PORT, HTTP_PORT, TLS_PORT};
+use masq_lib::constants::{
+    CONFIG_FILE_ERROR, DEFAULT_UI_PORT, HTTP_PORT, INVALID_PARAM_VALUE_ERROR, TLS_PORT,
+    TRAFFIC_DB_IN_MAIN_DATABASE,
+};
 use masq_lib::multi_config::{CommandLineVcl, ConfigFileVcl, EnvironmentVcl};
+use std::path::PathBuf;
 use std::str::FromStr;
 
 pub struct NodeConfiguratorStandardPrivileged {
@@ -208,7 +220,13 @@ pub fn server_initializer_collected_params<'a>(
         initialization_data.config_file.user_specified,
     ) {
         Ok(cfv) => cfv,
-        Err(e) => return Err(ConfiguratorError::required("config-file", &e.to_string())),
+        Err(e) => {
+            return Err(ConfiguratorError::required(
+                "config-file",
+                &e.to_string(),
+                CONFIG_FILE_ERROR,
+            ))
+        }
     };
 
     let environment_vcl = EnvironmentVcl::new(&app);
@@ -246,16 +264,20 @@ pub fn establish_port_configurations(config: &mut BootstrapperConfig) {
             false,
         ),
     );
-    config.port_configurations.insert(
-        TLS_PORT,
-        PortConfiguration::new(
-            vec![
-                Box::new(TlsDiscriminatorFactory::new()),
-                Box::new(HttpRequestDiscriminatorFactory::new()),
-            ],
-            false,
-        ),
-    );
+    config
+        .port_configurations
+        .insert(TLS_PORT, tunneling_port_configuration());
+}
+
+// A CONNECT opens the tunnel and is read by the HTTP discriminator; everything after it is TLS
+fn tunneling_port_configuration() -> PortConfiguration {
+    PortConfiguration::new(
+        vec![
+            Box::new(TlsDiscriminatorFactory::new()),
+            Box::new(HttpRequestDiscriminatorFactory::new()),
+        ],
+        false,
+    )
 }
 
 // All initialization that doesn't specifically require lack of privilege should be done here.
@@ -272,6 +294,7 @@ pub fn privileged_parse_args(
     };
     privileged_config.real_user = real_user;
     privileged_config.data_directory = directory;
+    privileged_config.config_file_opt = value_m!(multi_config, "config-file", PathBuf);
     privileged_config.blockchain_bridge_config.chain = chain;
 
     let joined_dns_servers_opt = value_m!(multi_config, "dns-servers", String);
@@ -288,8 +311,13 @@ pub fn privileged_parse_args(
         None => vec![SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)), 53)],
     };
 
-    privileged_config.log_level =
-        value_m!(multi_config, "log-level", LevelFilter).unwrap_or(LevelFilter::Warn);
+    privileged_config.log_level = value_m!(multi_config, "log-level", LogLevelSpec)
+        .unwrap_or_default()
+        .overridden_by(
+            &value_m!(multi_config, "log-level-overrides", LogLevelOverrides).unwrap_or_default(),
+        );
+    privileged_config.log_format =
+        value_m!(multi_config, "log-format", LogFormat).unwrap_or_default();
 
     privileged_config.ui_gateway_config.ui_port =
         value_m!(multi_config, "ui-port", u16).unwrap_or(DEFAULT_UI_PORT);
@@ -297,6 +325,52 @@ pub fn privileged_parse_args(
     privileged_config.crash_point =
         value_m!(multi_config, "crash-point", CrashPoint).unwrap_or(CrashPoint::None);
 
+    privileged_config.route_pool_config =
+        value_m!(multi_config, "route-pool", RoutePoolConfig).unwrap_or_default();
+
+    privileged_config.neighborhood_limits =
+        value_m!(multi_config, "neighborhood-limits", NeighborhoodLimits).unwrap_or_default();
+
+    privileged_config.performance_preset =
+        value_m!(multi_config, "performance-preset", PerformancePreset).unwrap_or_default();
+
+    privileged_config.version_quarantine_opt =
+        value_m!(multi_config, "version-quarantine", VersionQuarantinePolicy);
+
+    privileged_config.pinned_neighbors =
+        value_m!(multi_config, "pinned-neighbors", PinnedNeighbors).unwrap_or_default();
+
+    privileged_config.health_check_port_opt = value_m!(multi_config, "health-check-port", u16);
+
+    if let Some(port) = value_m!(multi_config, "connect-proxy-port", u16) {
+        privileged_config
+            .port_configurations
+            .insert(port, tunneling_port_configuration());
+    }
+
+    privileged_config.price_feed_url_opt = value_m!(multi_config, "price-feed-url", String);
+
+    privileged_config.public_ip_server_opt = value_m!(multi_config, "public-ip-server", String);
+
+    privileged_config.operator_notes_opt = value_m!(multi_config, "operator-notes", String);
+
+    privileged_config.rendezvous_broker_opt =
+        value_m!(multi_config, "rendezvous-broker", RendezvousBrokerLimits);
+
+    privileged_config.socket_tuning =
+        value_m!(multi_config, "socket-tuning", SocketTuning).unwrap_or_default();
+
+    privileged_config.exit_header_policy =
+        value_m!(multi_config, "exit-header-policy", ExitHeaderPolicy).unwrap_or_default();
+
+    privileged_config.traffic_padding_opt =
+        value_m!(multi_config, "traffic-padding", TrafficPadding);
+
+    privileged_config.actor_threads_opt = value_m!(multi_config, "actor-threads", ActorThreads)
+        .map(|actor_threads| actor_threads.check_against_cores(available_cores()))
+        .transpose()
+        .map_err(|e| ConfiguratorError::required("actor-threads", &e, INVALID_PARAM_VALUE_ERROR))?;
+
     if let Some(public_key_str) = value_m!(multi_config, "fake-public-key", String) {
         let (main_public_key, alias_public_key) = match base64::decode(&public_key_str) {
             Ok(mut key) => {
@@ -327,7 +401,25 @@ fn configure_database(
 ) -> Result<(), ConfiguratorError> {
     // We don't want to panic in case clandestine_port or blockchain_service_url is not configured
     // inside the bootstrap config
-    if let Some(port) = config.clandestine_port_opt {
+    if let Some(rotates) = config.clandestine_port_rotates_opt {
+        if let Err(pce) = persistent_config.set_clandestine_port_rotates(rotates) {
+            return Err(pce.into_configurator_error("clandestine-port"));
+        }
+    }
+    let clandestine_port_opt = match config.clandestine_port_opt {
+        Some(port) => Some(port),
+        None => {
+            let rotates = match config.clandestine_port_rotates_opt {
+                Some(rotates) => rotates,
+                None => match persistent_config.clandestine_port_rotates() {
+                    Ok(rotates) => rotates,
+                    Err(pce) => return Err(pce.into_configurator_error("clandestine-port")),
+                },
+            };
+            rotates.then(DbInitializerReal::choose_clandestine_port)
+        }
+    };
+    if let Some(port) = clandestine_port_opt {
         if let Err(pce) = persistent_config.set_clandestine_port(port) {
             return Err(pce.into_configurator_error("clandestine-port"));
         }
@@ -348,12 +440,46 @@ fn configure_database(
             return Err(pce.into_configurator_error("blockchain-service-url"));
         }
     }
+    if let Some(address) = config.blockchain_bridge_config.token_contract_address_opt {
+        if let Err(pce) = persistent_config.set_token_contract_address(address) {
+            return Err(pce.into_configurator_error("token-contract-address"));
+        }
+    }
+    if !config.neighbor_notes.is_empty() {
+        remember_neighbor_notes(&config.neighbor_notes, persistent_config)?;
+    }
+    // The DbInitializer moves the traffic tables the next time it opens the database
+    if let Some(path) = config.traffic_db_path_opt.as_ref() {
+        let path_opt = (path != TRAFFIC_DB_IN_MAIN_DATABASE).then(|| path.clone());
+        if let Err(pce) = persistent_config.set_traffic_db_path(path_opt) {
+            return Err(pce.into_configurator_error("traffic-db-path"));
+        }
+    }
     if let Err(pce) = persistent_config.set_gas_price(config.blockchain_bridge_config.gas_price) {
         return Err(pce.into_configurator_error("gas-price"));
     }
     Ok(())
 }
 
+// New notes for a neighbor replace whatever was remembered for it before
+fn remember_neighbor_notes(
+    neighbor_notes: &[OperatorNotes],
+    persistent_config: &mut dyn PersistentConfiguration,
+) -> Result<(), ConfiguratorError> {
+    let mut remembered = persistent_config
+        .neighbor_notes()
+        .map_err(|pce| pce.into_configurator_error("neighbors"))?;
+    remembered.retain(|old| {
+        neighbor_notes
+            .iter()
+            .all(|new| new.public_key != old.public_key)
+    });
+    remembered.extend(neighbor_notes.iter().cloned());
+    persistent_config
+        .set_neighbor_notes(remembered)
+        .map_err(|pce| pce.into_configurator_error("neighbors"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -377,7 +503,9 @@ mod tests {
     };
     use crate::test_utils::{assert_string_contains, main_cryptde, ArgsBuilder};
     use masq_lib::blockchains::chains::Chain;
-    use masq_lib::constants::DEFAULT_CHAIN;
+    use masq_lib::constants::{
+        DEFAULT_CHAIN, HIGHEST_RANDOM_CLANDESTINE_PORT, LOWEST_USABLE_INSECURE_PORT,
+    };
     use masq_lib::multi_config::VirtualCommandLine;
     use masq_lib::shared_schema::ParamError;
     use masq_lib::test_utils::environment_guard::{ClapGuard, EnvironmentGuard};
@@ -390,7 +518,9 @@ mod tests {
     use std::io::Write;
     use std::path::{Path, PathBuf};
     use std::sync::{Arc, Mutex};
+    use std::time::Duration;
     use std::vec;
+    use web3::types::Address;
 
     #[test]
     fn node_configurator_standard_unprivileged_uses_parse_args_configurator_dao_real() {
@@ -463,6 +593,7 @@ mod tests {
         let mut config = BootstrapperConfig::new();
         config.clandestine_port_opt = None;
         let mut persistent_config = PersistentConfigurationMock::new()
+            .clandestine_port_rotates_result(Ok(false))
             .set_neighborhood_mode_result(Ok(()))
             .set_min_hops_result(Ok(()))
             .set_gas_price_result(Err(PersistentConfigError::TransactionError));
@@ -481,6 +612,7 @@ mod tests {
         config.blockchain_bridge_config.blockchain_service_url_opt =
             Some("https://infura.io/ID".to_string());
         let mut persistent_config = PersistentConfigurationMock::new()
+            .clandestine_port_rotates_result(Ok(false))
             .set_neighborhood_mode_result(Ok(()))
             .set_min_hops_result(Ok(()))
             .set_blockchain_service_url_result(Err(PersistentConfigError::TransactionError));
@@ -494,11 +626,149 @@ mod tests {
         )
     }
 
+    #[test]
+    fn configure_database_remembers_token_contract_address() {
+        let set_token_contract_address_params_arc = Arc::new(Mutex::new(vec![]));
+        let address = Address::from_low_u64_be(0x1234);
+        let mut config = BootstrapperConfig::new();
+        config.blockchain_bridge_config.token_contract_address_opt = Some(address);
+        let mut persistent_config = PersistentConfigurationMock::new()
+            .clandestine_port_rotates_result(Ok(false))
+            .set_neighborhood_mode_result(Ok(()))
+            .set_min_hops_result(Ok(()))
+            .set_token_contract_address_params(&set_token_contract_address_params_arc)
+            .set_token_contract_address_result(Ok(()))
+            .set_gas_price_result(Ok(()));
+
+        let result = configure_database(&config, &mut persistent_config);
+
+        assert_eq!(result, Ok(()));
+        let set_token_contract_address_params =
+            set_token_contract_address_params_arc.lock().unwrap();
+        assert_eq!(*set_token_contract_address_params, vec![address]);
+    }
+
+    #[test]
+    fn configure_database_handles_error_during_setting_token_contract_address() {
+        let mut config = BootstrapperConfig::new();
+        config.blockchain_bridge_config.token_contract_address_opt =
+            Some(Address::from_low_u64_be(0x1234));
+        let mut persistent_config = PersistentConfigurationMock::new()
+            .clandestine_port_rotates_result(Ok(false))
+            .set_neighborhood_mode_result(Ok(()))
+            .set_min_hops_result(Ok(()))
+            .set_token_contract_address_result(Err(PersistentConfigError::TransactionError));
+
+        let result = configure_database(&config, &mut persistent_config);
+
+        assert_eq!(
+            result,
+            Err(PersistentConfigError::TransactionError
+                .into_configurator_error("token-contract-address"))
+        )
+    }
+
+    #[test]
+    fn configure_database_remembers_neighbor_notes() {
+        let set_neighbor_notes_params_arc = Arc::new(Mutex::new(vec![]));
+        let alice_old = OperatorNotes::sign(main_cryptde(), "Run by alice");
+        let alice_new =
+            OperatorNotes::sign(main_cryptde(), "Run by alice, now at alice.example.com");
+        let bob_cryptde = CryptDENull::from(&PublicKey::new(b"bob"), TEST_DEFAULT_CHAIN);
+        let bob = OperatorNotes::sign(&bob_cryptde, "Run by bob");
+        let mut config = BootstrapperConfig::new();
+        config.neighbor_notes = vec![alice_new.clone()];
+        let mut persistent_config = PersistentConfigurationMock::new()
+            .clandestine_port_rotates_result(Ok(false))
+            .set_neighborhood_mode_result(Ok(()))
+            .set_min_hops_result(Ok(()))
+            .neighbor_notes_result(Ok(vec![alice_old, bob.clone()]))
+            .set_neighbor_notes_params(&set_neighbor_notes_params_arc)
+            .set_neighbor_notes_result(Ok(()))
+            .set_gas_price_result(Ok(()));
+
+        let result = configure_database(&config, &mut persistent_config);
+
+        assert_eq!(result, Ok(()));
+        let set_neighbor_notes_params = set_neighbor_notes_params_arc.lock().unwrap();
+        assert_eq!(*set_neighbor_notes_params, vec![vec![bob, alice_new]]);
+    }
+
+    #[test]
+    fn configure_database_handles_error_during_setting_neighbor_notes() {
+        let mut config = BootstrapperConfig::new();
+        config.neighbor_notes = vec![OperatorNotes::sign(main_cryptde(), "Run by alice")];
+        let mut persistent_config = PersistentConfigurationMock::new()
+            .clandestine_port_rotates_result(Ok(false))
+            .set_neighborhood_mode_result(Ok(()))
+            .set_min_hops_result(Ok(()))
+            .neighbor_notes_result(Ok(vec![]))
+            .set_neighbor_notes_result(Err(PersistentConfigError::TransactionError));
+
+        let result = configure_database(&config, &mut persistent_config);
+
+        assert_eq!(
+            result,
+            Err(PersistentConfigError::TransactionError.into_configurator_error("neighbors"))
+        )
+    }
+
+    #[test]
+    fn configure_database_remembers_traffic_db_path() {
+        vec![
+            ("/mnt/ramdisk/traffic.db", Some("/mnt/ramdisk/traffic.db")),
+            ("main", None),
+        ]
+        .into_iter()
+        .for_each(|(specified, expected)| {
+            let set_traffic_db_path_params_arc = Arc::new(Mutex::new(vec![]));
+            let mut config = BootstrapperConfig::new();
+            config.traffic_db_path_opt = Some(specified.to_string());
+            let mut persistent_config = PersistentConfigurationMock::new()
+                .clandestine_port_rotates_result(Ok(false))
+                .set_neighborhood_mode_result(Ok(()))
+                .set_min_hops_result(Ok(()))
+                .set_traffic_db_path_params(&set_traffic_db_path_params_arc)
+                .set_traffic_db_path_result(Ok(()))
+                .set_gas_price_result(Ok(()));
+
+            let result = configure_database(&config, &mut persistent_config);
+
+            assert_eq!(result, Ok(()));
+            let set_traffic_db_path_params = set_traffic_db_path_params_arc.lock().unwrap();
+            assert_eq!(
+                *set_traffic_db_path_params,
+                vec![expected.map(|path| path.to_string())],
+                "{}",
+                specified
+            );
+        });
+    }
+
+    #[test]
+    fn configure_database_handles_error_during_setting_traffic_db_path() {
+        let mut config = BootstrapperConfig::new();
+        config.traffic_db_path_opt = Some("main".to_string());
+        let mut persistent_config = PersistentConfigurationMock::new()
+            .clandestine_port_rotates_result(Ok(false))
+            .set_neighborhood_mode_result(Ok(()))
+            .set_min_hops_result(Ok(()))
+            .set_traffic_db_path_result(Err(PersistentConfigError::TransactionError));
+
+        let result = configure_database(&config, &mut persistent_config);
+
+        assert_eq!(
+            result,
+            Err(PersistentConfigError::TransactionError.into_configurator_error("traffic-db-path"))
+        )
+    }
+
     #[test]
     fn configure_database_handles_error_during_setting_neighborhood_mode() {
         let mut config = BootstrapperConfig::new();
         config.neighborhood_config.mode = ZeroHop;
         let mut persistent_config = PersistentConfigurationMock::new()
+            .clandestine_port_rotates_result(Ok(false))
             .set_neighborhood_mode_result(Err(PersistentConfigError::TransactionError));
 
         let result = configure_database(&config, &mut persistent_config);
@@ -515,6 +785,7 @@ mod tests {
         let mut config = BootstrapperConfig::new();
         config.neighborhood_config.min_hops = Hops::FourHops;
         let mut persistent_config = PersistentConfigurationMock::new()
+            .clandestine_port_rotates_result(Ok(false))
             .set_neighborhood_mode_result(Ok(()))
             .set_min_hops_result(Err(PersistentConfigError::TransactionError));
 
@@ -695,6 +966,10 @@ mod tests {
             None,
         );
         assert_eq!(config.data_directory, home_dir);
+        assert_eq!(
+            config.config_file_opt,
+            Some(PathBuf::from("specified_config.toml"))
+        );
         assert_eq!(
             config.main_cryptde_null_opt.unwrap().public_key(),
             &PublicKey::new(&[1, 2, 3, 4]),
@@ -723,6 +998,22 @@ mod tests {
         );
         assert_eq!(config.crash_point, CrashPoint::None);
         assert_eq!(config.ui_gateway_config.ui_port, DEFAULT_UI_PORT);
+        assert_eq!(config.route_pool_config, RoutePoolConfig::default());
+        assert_eq!(config.neighborhood_limits, NeighborhoodLimits::default());
+        assert_eq!(config.performance_preset, PerformancePreset::Standard);
+        assert_eq!(config.version_quarantine_opt, None);
+        assert_eq!(config.pinned_neighbors, PinnedNeighbors::default());
+        assert_eq!(config.health_check_port_opt, None);
+        assert_eq!(config.price_feed_url_opt, None);
+        assert_eq!(config.public_ip_server_opt, None);
+        assert_eq!(config.operator_notes_opt, None);
+        assert_eq!(config.rendezvous_broker_opt, None);
+        assert_eq!(config.socket_tuning, SocketTuning::default());
+        assert_eq!(config.exit_header_policy, ExitHeaderPolicy::Strip);
+        assert_eq!(config.log_format, LogFormat::Text);
+        assert_eq!(config.traffic_padding_opt, None);
+        assert_eq!(config.actor_threads_opt, None);
+        assert_eq!(config.config_file_opt, None);
         assert!(config.main_cryptde_null_opt.is_none());
         assert_eq!(
             config.real_user,
@@ -810,6 +1101,342 @@ mod tests {
         assert_eq!(config.crash_point, CrashPoint::Panic);
     }
 
+    #[test]
+    fn with_parameters_produces_configuration_for_route_pool() {
+        running_test();
+        let args = make_default_cli_params().param("--route-pool", "5|20");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.route_pool_config,
+            RoutePoolConfig {
+                size: 5,
+                route_lifetime: Duration::from_secs(20)
+            }
+        );
+    }
+
+    #[test]
+    fn with_parameters_produces_configuration_for_neighborhood_limits() {
+        running_test();
+        let args = make_default_cli_params().param("--neighborhood-limits", "6|500");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.neighborhood_limits,
+            NeighborhoodLimits {
+                max_distance: 6,
+                max_node_records: 500,
+            }
+        );
+    }
+
+    #[test]
+    fn with_parameters_produces_configuration_for_performance_preset() {
+        running_test();
+        let args = make_default_cli_params().param("--performance-preset", "low-power");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(config.performance_preset, PerformancePreset::LowPower);
+    }
+
+    #[test]
+    fn with_parameters_produces_configuration_for_version_quarantine() {
+        running_test();
+        let args = make_default_cli_params().param("--version-quarantine", "0.1|0.3,0.4");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.version_quarantine_opt,
+            Some(VersionQuarantinePolicy {
+                minimum_version: dv!(0, 1),
+                denied_versions: vec![dv!(0, 3), dv!(0, 4)],
+            })
+        );
+    }
+
+    #[test]
+    fn with_parameters_produces_configuration_for_pinned_neighbors() {
+        running_test();
+        let args =
+            make_default_cli_params().param("--pinned-neighbors", "QmlsbHlCb2JKb2U,U2FsbHlTdWU");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.pinned_neighbors,
+            PinnedNeighbors {
+                keys: vec![PublicKey::new(b"BillyBobJoe"), PublicKey::new(b"SallySue")],
+            }
+        );
+    }
+
+    #[test]
+    fn with_parameters_produces_configuration_for_subsystem_log_levels() {
+        running_test();
+        let args = make_default_cli_params().param("--log-level", "info,neighborhood=trace");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.log_level,
+            LogLevelSpec::from_str("info,neighborhood=trace").unwrap()
+        );
+    }
+
+    #[test]
+    fn with_parameters_produces_configuration_for_log_level_overrides() {
+        running_test();
+        let args = make_default_cli_params()
+            .param("--log-level", "info,neighborhood=trace,hopper=error")
+            .param(
+                "--log-level-overrides",
+                "neighborhood=debug,accountant=trace",
+            );
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.log_level,
+            LogLevelSpec::from_str("info,hopper=error,neighborhood=debug,accountant=trace")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn with_parameters_produces_configuration_for_health_check_port() {
+        running_test();
+        let args = make_default_cli_params().param("--health-check-port", "8081");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(config.health_check_port_opt, Some(8081));
+    }
+
+    #[test]
+    fn with_parameters_produces_configuration_for_price_feed_url() {
+        running_test();
+        let args =
+            make_default_cli_params().param("--price-feed-url", "http://localhost:8080/masq-price");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.price_feed_url_opt,
+            Some("http://localhost:8080/masq-price".to_string())
+        );
+    }
+
+    #[test]
+    fn with_parameters_produces_configuration_for_connect_proxy_port() {
+        running_test();
+        let args = make_default_cli_params().param("--connect-proxy-port", "8443");
+        let mut config = BootstrapperConfig::new();
+        establish_port_configurations(&mut config);
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        let mut ports = config
+            .port_configurations
+            .keys()
+            .cloned()
+            .collect::<Vec<u16>>();
+        ports.sort();
+        assert_eq!(ports, vec![HTTP_PORT, TLS_PORT, 8443]);
+        assert_eq!(
+            format!("{:?}", config.port_configurations.get(&8443).unwrap()),
+            format!("{:?}", config.port_configurations.get(&TLS_PORT).unwrap())
+        );
+    }
+
+    #[test]
+    fn with_parameters_produces_configuration_for_public_ip_server() {
+        running_test();
+        let args = make_default_cli_params().param("--public-ip-server", "stun.example.com:3478");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.public_ip_server_opt,
+            Some("stun.example.com:3478".to_string())
+        );
+    }
+
+    #[test]
+    fn with_parameters_produces_configuration_for_operator_notes() {
+        running_test();
+        let args = make_default_cli_params().param("--operator-notes", "Run by alice");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(config.operator_notes_opt, Some("Run by alice".to_string()));
+    }
+
+    #[test]
+    fn with_parameters_produces_configuration_for_rendezvous_broker() {
+        running_test();
+        let args = make_default_cli_params().param("--rendezvous-broker", "2|30");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.rendezvous_broker_opt,
+            Some(RendezvousBrokerLimits {
+                per_node_per_minute: 2,
+                total_per_minute: 30,
+            })
+        );
+    }
+
+    #[test]
+    fn with_parameters_produces_configuration_for_exit_header_policy() {
+        running_test();
+        let args = make_default_cli_params().param("--exit-header-policy", "normalize");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(config.exit_header_policy, ExitHeaderPolicy::Normalize);
+    }
+
+    #[test]
+    fn with_parameters_produces_configuration_for_log_format() {
+        running_test();
+        let args = make_default_cli_params().param("--log-format", "JSON");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(config.log_format, LogFormat::Json);
+    }
+
+    #[test]
+    fn with_parameters_produces_configuration_for_socket_tuning() {
+        running_test();
+        let args = make_default_cli_params().param("--socket-tuning", "1048576|65536|on");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.socket_tuning,
+            SocketTuning {
+                recv_buffer_size: 1048576,
+                send_buffer_size: 65536,
+                nodelay: true,
+            }
+        );
+    }
+
+    #[test]
+    fn with_parameters_produces_configuration_for_traffic_padding() {
+        running_test();
+        let args = make_default_cli_params().param("--traffic-padding", "1024|2");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.traffic_padding_opt,
+            Some(TrafficPadding {
+                bucket_size: 1024,
+                cover_per_minute: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn with_parameters_produces_configuration_for_actor_threads() {
+        running_test();
+        let args = make_default_cli_params().param("--actor-threads", "1|2");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.actor_threads_opt,
+            Some(ActorThreads {
+                worker_threads: 1,
+                blocking_threads: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn privileged_parse_args_rejects_more_worker_threads_than_cores() {
+        running_test();
+        let args = make_default_cli_params().param("--actor-threads", "65535|1");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        let result = privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config);
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "actor-threads",
+                &format!(
+                    "65535 worker threads are more than the {} CPU cores on this machine",
+                    available_cores()
+                ),
+                INVALID_PARAM_VALUE_ERROR
+            ))
+        );
+    }
+
     fn fill_up_config_file(mut config_file: File) {
         {
             config_file
@@ -837,7 +1464,7 @@ mod tests {
                 .write_all(b"neighborhood-mode = \"zero-hop\"\n")
                 .unwrap();
             config_file
-                .write_all(b"payment-thresholds = \"3333|55|33|646|999|999\"\n")
+                .write_all(b"payment-thresholds = \"3333|55|33|646|999|646\"\n")
                 .unwrap();
             config_file.write_all(b"rate-pack = \"2|2|2|2\"\n").unwrap();
             config_file
@@ -950,8 +1577,11 @@ mod tests {
                 .to_str()
                 .unwrap()
         );
-        let expected =
-            ConfiguratorError::new(vec![ParamError::new("config-file", result_path.as_str())]);
+        let expected = ConfiguratorError::new(vec![ParamError::new(
+            "config-file",
+            result_path.as_str(),
+            CONFIG_FILE_ERROR,
+        )]);
 
         assert_eq!(result, expected);
     }
@@ -1419,7 +2049,7 @@ mod tests {
     }
 
     #[should_panic(
-        expected = "expected MultiConfig: ConfiguratorError { param_errors: [ParamError { parameter: \"gas-price\", reason: \"Invalid value: unleaded\" }] }"
+        expected = "expected MultiConfig: ConfiguratorError { param_errors: [ParamError { parameter: \"gas-price\", reason: \"Invalid value: unleaded\", code: 562949953421313 }] }"
     )]
     #[test]
     fn server_initializer_collected_params_rejects_invalid_gas_price() {
@@ -1436,7 +2066,11 @@ mod tests {
 
         assert_eq!(
             result,
-            ConfiguratorError::required("gas-price", "Invalid value: unleaded")
+            ConfiguratorError::required(
+                "gas-price",
+                "Invalid value: unleaded",
+                INVALID_PARAM_VALUE_ERROR
+            )
         )
     }
 
@@ -1506,6 +2140,7 @@ mod tests {
         let set_clandestine_port_params_arc = Arc::new(Mutex::new(vec![]));
         let set_neighborhood_mode_params_arc = Arc::new(Mutex::new(vec![]));
         let mut persistent_config = PersistentConfigurationMock::new()
+            .clandestine_port_rotates_result(Ok(false))
             .set_clandestine_port_params(&set_clandestine_port_params_arc)
             .set_blockchain_service_url_params(&set_blockchain_service_params_arc)
             .set_neighborhood_mode_params(&set_neighborhood_mode_params_arc)
@@ -1529,6 +2164,96 @@ mod tests {
         )
     }
 
+    #[test]
+    fn configure_database_remembers_auto_rotate_and_chooses_a_fresh_port() {
+        let mut config = BootstrapperConfig::new();
+        config.clandestine_port_rotates_opt = Some(true);
+        let set_clandestine_port_rotates_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_clandestine_port_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut persistent_config = PersistentConfigurationMock::new()
+            .set_clandestine_port_rotates_params(&set_clandestine_port_rotates_params_arc)
+            .set_clandestine_port_rotates_result(Ok(()))
+            .set_clandestine_port_params(&set_clandestine_port_params_arc)
+            .set_clandestine_port_result(Ok(()))
+            .set_neighborhood_mode_result(Ok(()))
+            .set_min_hops_result(Ok(()))
+            .set_gas_price_result(Ok(()));
+
+        let result = configure_database(&config, &mut persistent_config);
+
+        assert_eq!(result, Ok(()));
+        let set_clandestine_port_rotates_params =
+            set_clandestine_port_rotates_params_arc.lock().unwrap();
+        assert_eq!(*set_clandestine_port_rotates_params, vec![true]);
+        let set_clandestine_port_params = set_clandestine_port_params_arc.lock().unwrap();
+        assert_eq!(set_clandestine_port_params.len(), 1);
+        assert!(set_clandestine_port_params[0] >= LOWEST_USABLE_INSECURE_PORT);
+        assert!(set_clandestine_port_params[0] < HIGHEST_RANDOM_CLANDESTINE_PORT);
+    }
+
+    #[test]
+    fn configure_database_chooses_a_fresh_port_when_rotation_was_chosen_before() {
+        let config = BootstrapperConfig::new();
+        let set_clandestine_port_rotates_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_clandestine_port_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut persistent_config = PersistentConfigurationMock::new()
+            .clandestine_port_rotates_result(Ok(true))
+            .set_clandestine_port_rotates_params(&set_clandestine_port_rotates_params_arc)
+            .set_clandestine_port_params(&set_clandestine_port_params_arc)
+            .set_clandestine_port_result(Ok(()))
+            .set_neighborhood_mode_result(Ok(()))
+            .set_min_hops_result(Ok(()))
+            .set_gas_price_result(Ok(()));
+
+        let result = configure_database(&config, &mut persistent_config);
+
+        assert_eq!(result, Ok(()));
+        let set_clandestine_port_rotates_params =
+            set_clandestine_port_rotates_params_arc.lock().unwrap();
+        assert!(set_clandestine_port_rotates_params.is_empty());
+        let set_clandestine_port_params = set_clandestine_port_params_arc.lock().unwrap();
+        assert_eq!(set_clandestine_port_params.len(), 1);
+    }
+
+    #[test]
+    fn configure_database_remembers_auto_stable_and_keeps_the_port() {
+        let mut config = BootstrapperConfig::new();
+        config.clandestine_port_rotates_opt = Some(false);
+        let set_clandestine_port_rotates_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_clandestine_port_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut persistent_config = PersistentConfigurationMock::new()
+            .set_clandestine_port_rotates_params(&set_clandestine_port_rotates_params_arc)
+            .set_clandestine_port_rotates_result(Ok(()))
+            .set_clandestine_port_params(&set_clandestine_port_params_arc)
+            .set_neighborhood_mode_result(Ok(()))
+            .set_min_hops_result(Ok(()))
+            .set_gas_price_result(Ok(()));
+
+        let result = configure_database(&config, &mut persistent_config);
+
+        assert_eq!(result, Ok(()));
+        let set_clandestine_port_rotates_params =
+            set_clandestine_port_rotates_params_arc.lock().unwrap();
+        assert_eq!(*set_clandestine_port_rotates_params, vec![false]);
+        let set_clandestine_port_params = set_clandestine_port_params_arc.lock().unwrap();
+        assert!(set_clandestine_port_params.is_empty());
+    }
+
+    #[test]
+    fn configure_database_handles_error_during_setting_clandestine_port_rotation() {
+        let mut config = BootstrapperConfig::new();
+        config.clandestine_port_rotates_opt = Some(true);
+        let mut persistent_config = PersistentConfigurationMock::new()
+            .set_clandestine_port_rotates_result(Err(PersistentConfigError::TransactionError));
+
+        let result = configure_database(&config, &mut persistent_config);
+
+        assert_eq!(
+            result,
+            Err(PersistentConfigError::TransactionError.into_configurator_error("clandestine-port"))
+        )
+    }
+
     #[test]
     fn external_data_is_properly_created_when_password_is_provided() {
         let mut configurator_standard =
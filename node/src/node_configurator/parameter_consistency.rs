@@ -0,0 +1,240 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::sub_lib::accountant::PaymentThresholds;
+use crate::sub_lib::neighborhood::RatePack;
+use masq_lib::constants::INVALID_PARAM_VALUE_ERROR;
+use masq_lib::shared_schema::{ConfiguratorError, ParamError};
+
+// Each of the combined parameters parses fine on its own terms, value by value; these checks catch
+// the combinations that parse but leave the Accountant or the Neighborhood doing something nobody
+// would have asked for. The Daemon's setup runs the same parsing, so a UI hears about them too.
+pub fn check_payment_thresholds(
+    payment_thresholds: &PaymentThresholds,
+) -> Result<(), ConfiguratorError> {
+    if payment_thresholds.debt_threshold_gwei <= payment_thresholds.permanent_debt_allowed_gwei {
+        let msg = format!(
+            "Value of DebtThresholdGwei ({}) must be bigger than PermanentDebtAllowedGwei ({})",
+            payment_thresholds.debt_threshold_gwei, payment_thresholds.permanent_debt_allowed_gwei
+        );
+        return Err(ConfiguratorError::required(
+            "payment-thresholds",
+            &msg,
+            INVALID_PARAM_VALUE_ERROR,
+        ));
+    }
+    if payment_thresholds.threshold_interval_sec > 10_u64.pow(9) {
+        return Err(ConfiguratorError::required(
+            "payment-thresholds",
+            "Value of ThresholdIntervalSec must not exceed 1,000,000,000 s",
+            INVALID_PARAM_VALUE_ERROR,
+        ));
+    }
+    let mut reasons = vec![];
+    if payment_thresholds.unban_below_gwei > payment_thresholds.permanent_debt_allowed_gwei {
+        reasons.push(format!(
+            "Value of UnbanBelowGwei ({}) must not be bigger than PermanentDebtAllowedGwei ({}); \
+             otherwise a Node could be unbanned while it still owes enough to be banned again",
+            payment_thresholds.unban_below_gwei, payment_thresholds.permanent_debt_allowed_gwei
+        ));
+    }
+    if payment_thresholds.threshold_interval_sec <= payment_thresholds.maturity_threshold_sec {
+        reasons.push(format!(
+            "Value of ThresholdIntervalSec ({}) must be bigger than MaturityThresholdSec ({}); \
+             otherwise the debt allowed to stay unpaid drops faster than debts mature",
+            payment_thresholds.threshold_interval_sec, payment_thresholds.maturity_threshold_sec
+        ));
+    }
+    to_result("payment-thresholds", reasons)
+}
+
+pub fn check_rate_pack(rate_pack: &RatePack) -> Result<(), ConfiguratorError> {
+    let mut reasons = vec![];
+    if rate_pack.exit_byte_rate < rate_pack.routing_byte_rate {
+        reasons.push(format!(
+            "Exit Byte Rate ({}) must not be lower than Routing Byte Rate ({}); an exit Node \
+             does all the work a relay Node does, and more",
+            rate_pack.exit_byte_rate, rate_pack.routing_byte_rate
+        ));
+    }
+    if rate_pack.exit_service_rate < rate_pack.routing_service_rate {
+        reasons.push(format!(
+            "Exit Service Rate ({}) must not be lower than Routing Service Rate ({}); an exit \
+             Node does all the work a relay Node does, and more",
+            rate_pack.exit_service_rate, rate_pack.routing_service_rate
+        ));
+    }
+    to_result("rate-pack", reasons)
+}
+
+fn to_result(parameter: &str, reasons: Vec<String>) -> Result<(), ConfiguratorError> {
+    if reasons.is_empty() {
+        Ok(())
+    } else {
+        Err(ConfiguratorError::new(
+            reasons
+                .iter()
+                .map(|reason| ParamError::new(parameter, reason, INVALID_PARAM_VALUE_ERROR))
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accountant::db_access_objects::utils::ThresholdUtils;
+    use crate::sub_lib::accountant::DEFAULT_PAYMENT_THRESHOLDS;
+    use crate::sub_lib::neighborhood::DEFAULT_RATE_PACK;
+
+    #[test]
+    fn default_values_are_consistent() {
+        assert_eq!(
+            check_payment_thresholds(&DEFAULT_PAYMENT_THRESHOLDS),
+            Ok(())
+        );
+        assert_eq!(check_rate_pack(&DEFAULT_RATE_PACK), Ok(()));
+    }
+
+    #[test]
+    fn check_payment_thresholds_works_for_equal_debt_parameters() {
+        let mut payment_thresholds = *DEFAULT_PAYMENT_THRESHOLDS;
+        payment_thresholds.permanent_debt_allowed_gwei = 10000;
+        payment_thresholds.debt_threshold_gwei = 10000;
+
+        let result = check_payment_thresholds(&payment_thresholds);
+
+        let expected_msg = "Value of DebtThresholdGwei (10000) must be bigger than PermanentDebtAllowedGwei (10000)";
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "payment-thresholds",
+                expected_msg,
+                INVALID_PARAM_VALUE_ERROR
+            ))
+        )
+    }
+
+    #[test]
+    fn check_payment_thresholds_works_for_too_small_debt_threshold() {
+        let mut payment_thresholds = *DEFAULT_PAYMENT_THRESHOLDS;
+        payment_thresholds.permanent_debt_allowed_gwei = 10000;
+        payment_thresholds.debt_threshold_gwei = 9999;
+
+        let result = check_payment_thresholds(&payment_thresholds);
+
+        let expected_msg = "Value of DebtThresholdGwei (9999) must be bigger than PermanentDebtAllowedGwei (10000)";
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "payment-thresholds",
+                expected_msg,
+                INVALID_PARAM_VALUE_ERROR
+            ))
+        )
+    }
+
+    #[test]
+    fn check_payment_thresholds_does_not_permit_threshold_interval_longer_than_1_000_000_000_s() {
+        //this goes to the furthest extreme where the delta of debt limits is just 1 gwei, which,
+        //if divided by the slope interval equal or longer 10^9 and rounded, gives 0
+        let mut payment_thresholds = *DEFAULT_PAYMENT_THRESHOLDS;
+        payment_thresholds.permanent_debt_allowed_gwei = 100;
+        payment_thresholds.debt_threshold_gwei = 101;
+        payment_thresholds.threshold_interval_sec = 1_000_000_001;
+
+        let result = check_payment_thresholds(&payment_thresholds);
+
+        let expected_msg = "Value of ThresholdIntervalSec must not exceed 1,000,000,000 s";
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "payment-thresholds",
+                expected_msg,
+                INVALID_PARAM_VALUE_ERROR
+            ))
+        );
+        payment_thresholds.threshold_interval_sec -= 1;
+        let last_value_possible = ThresholdUtils::slope(&payment_thresholds);
+        assert_eq!(last_value_possible, -1)
+    }
+
+    #[test]
+    fn check_payment_thresholds_permits_unban_below_equal_to_permanent_debt_allowed() {
+        let mut payment_thresholds = *DEFAULT_PAYMENT_THRESHOLDS;
+        payment_thresholds.permanent_debt_allowed_gwei = 10000;
+        payment_thresholds.unban_below_gwei = 10000;
+
+        let result = check_payment_thresholds(&payment_thresholds);
+
+        assert_eq!(result, Ok(()))
+    }
+
+    #[test]
+    fn check_payment_thresholds_reports_every_inconsistency_it_finds() {
+        let mut payment_thresholds = *DEFAULT_PAYMENT_THRESHOLDS;
+        payment_thresholds.permanent_debt_allowed_gwei = 10000;
+        payment_thresholds.unban_below_gwei = 10001;
+        payment_thresholds.maturity_threshold_sec = 3600;
+        payment_thresholds.threshold_interval_sec = 3600;
+
+        let result = check_payment_thresholds(&payment_thresholds);
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "payment-thresholds",
+                "Value of UnbanBelowGwei (10001) must not be bigger than PermanentDebtAllowedGwei \
+                 (10000); otherwise a Node could be unbanned while it still owes enough to be banned again",
+                INVALID_PARAM_VALUE_ERROR
+            )
+            .another_required(
+                "payment-thresholds",
+                "Value of ThresholdIntervalSec (3600) must be bigger than MaturityThresholdSec \
+                 (3600); otherwise the debt allowed to stay unpaid drops faster than debts mature",
+                INVALID_PARAM_VALUE_ERROR
+            ))
+        )
+    }
+
+    #[test]
+    fn check_rate_pack_permits_exit_rates_equal_to_routing_rates() {
+        let rate_pack = RatePack {
+            routing_byte_rate: 100,
+            routing_service_rate: 200,
+            exit_byte_rate: 100,
+            exit_service_rate: 200,
+        };
+
+        let result = check_rate_pack(&rate_pack);
+
+        assert_eq!(result, Ok(()))
+    }
+
+    #[test]
+    fn check_rate_pack_reports_exit_rates_lower_than_routing_rates() {
+        let rate_pack = RatePack {
+            routing_byte_rate: 100,
+            routing_service_rate: 200,
+            exit_byte_rate: 99,
+            exit_service_rate: 199,
+        };
+
+        let result = check_rate_pack(&rate_pack);
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "rate-pack",
+                "Exit Byte Rate (99) must not be lower than Routing Byte Rate (100); an exit Node \
+                 does all the work a relay Node does, and more",
+                INVALID_PARAM_VALUE_ERROR
+            )
+            .another_required(
+                "rate-pack",
+                "Exit Service Rate (199) must not be lower than Routing Service Rate (200); an exit \
+                 Node does all the work a relay Node does, and more",
+                INVALID_PARAM_VALUE_ERROR
+            ))
+        )
+    }
+}
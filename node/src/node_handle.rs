@@ -0,0 +1,521 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::run_modes_factories::{ServerInitializerFactory, ServerInitializerFactoryReal};
+use actix::{Actor, Addr, Context, Handler, Message, Recipient, System};
+use futures::Future;
+use lazy_static::lazy_static;
+use masq_lib::command::StdStreams;
+use masq_lib::messages::{ToMessageBody, UiSetConfigurationRequest};
+use masq_lib::shared_schema::ConfiguratorError;
+use masq_lib::ui_gateway::{MessageBody, MessageTarget, NodeFromUiMessage, NodeToUiMessage};
+use std::cell::Cell;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::thread::JoinHandle;
+
+// The WebSocketSupervisor numbers its clients upward from zero; it will never get this far.
+pub const EMBEDDER_CLIENT_ID: u64 = u64::MAX;
+
+lazy_static! {
+    static ref EMBEDDER_LINK_OPT: Mutex<Option<EmbedderLink>> = Mutex::new(None);
+}
+
+// The logger and the CryptDEs are process-wide and can be initialized only once.
+static NODE_STARTED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeStatus {
+    Starting,
+    Running,
+    Stopped { exit_code: i32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeEvent {
+    StatusChanged(NodeStatus),
+    // Responses to requests sent through the NodeHandle, and broadcasts to all UIs
+    UiMessage(MessageBody),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeHandleError {
+    AlreadyStarted,
+    Configuration(ConfiguratorError),
+    StartupFailed,
+    NotReady,
+    Stopped,
+}
+
+/// Runs a MASQ Node inside the current process, on a thread of its own, and lets the embedding
+/// application control it with the same UI messages a `masq` client would send over the
+/// WebSocket, minus the WebSocket.
+///
+/// Start it with the command-line arguments you'd give the `MASQNode` binary. Be aware that the
+/// Node behaves exactly as the binary does: in particular, if the process has privilege, the Node
+/// will drop it to that of `--real-user` once it has opened its privileged ports. Only one Node
+/// can ever be started in a process.
+///
+/// ```ignore
+/// let mut node = NodeHandle::start(&["--chain", "polygon-mainnet", "--neighborhood-mode", "zero-hop"])?;
+/// let events = node.take_events().unwrap();
+/// node.change_configuration("gas-price", "50")?;
+/// node.stop();
+/// ```
+pub struct NodeHandle {
+    status_arc: Arc<Mutex<NodeStatus>>,
+    link: EmbedderLink,
+    events_opt: Option<Receiver<NodeEvent>>,
+    stopper_opt: Option<Addr<NodeStopper>>,
+    join_handle_opt: Option<JoinHandle<()>>,
+    next_context_id: Cell<u64>,
+}
+
+impl NodeHandle {
+    /// Configures the Node from the arguments and starts it. Returns only once the Node has either
+    /// been configured and started up or refused its configuration.
+    pub fn start(args: &[&str]) -> Result<NodeHandle, NodeHandleError> {
+        if NODE_STARTED.swap(true, Ordering::SeqCst) {
+            return Err(NodeHandleError::AlreadyStarted);
+        }
+        Self::start_with(args, Box::new(ServerInitializerFactoryReal))
+    }
+
+    pub fn status(&self) -> NodeStatus {
+        self.status_arc
+            .lock()
+            .expect("Node status is poisoned")
+            .clone()
+    }
+
+    /// The stream of status changes and UI messages from the Node. It can be taken only once.
+    pub fn take_events(&mut self) -> Option<Receiver<NodeEvent>> {
+        self.events_opt.take()
+    }
+
+    /// Sends a UI request to the Node and returns its context ID, which the response arriving in
+    /// the event stream will carry.
+    pub fn send_request<T: ToMessageBody>(&self, request: T) -> Result<u64, NodeHandleError> {
+        if self.stopper_opt.is_none() {
+            return Err(NodeHandleError::Stopped);
+        }
+        let ui_sub_opt = self
+            .link
+            .ui_sub_arc
+            .lock()
+            .expect("UiGateway link is poisoned")
+            .clone();
+        let ui_sub = ui_sub_opt.ok_or(NodeHandleError::NotReady)?;
+        let context_id = self.next_context_id.get();
+        self.next_context_id.set(context_id + 1);
+        ui_sub
+            .try_send(NodeFromUiMessage {
+                client_id: EMBEDDER_CLIENT_ID,
+                body: request.tmb(context_id),
+            })
+            .map_err(|_| NodeHandleError::Stopped)?;
+        Ok(context_id)
+    }
+
+    /// Equivalent to the `setConfiguration` UI request.
+    pub fn change_configuration(&self, name: &str, value: &str) -> Result<u64, NodeHandleError> {
+        self.send_request(UiSetConfigurationRequest {
+            name: name.to_string(),
+            value: value.to_string(),
+        })
+    }
+
+    /// Stops the Node, waits for it to finish, and returns its final status.
+    pub fn stop(&mut self) -> NodeStatus {
+        if let Some(stopper) = self.stopper_opt.take() {
+            // If the Node has already stopped by itself, there's nobody to tell
+            let _ = stopper.try_send(StopNode {});
+        }
+        if let Some(join_handle) = self.join_handle_opt.take() {
+            let _ = join_handle.join();
+        }
+        self.status()
+    }
+
+    fn start_with(
+        args: &[&str],
+        server_initializer_factory: Box<dyn ServerInitializerFactory + Send>,
+    ) -> Result<NodeHandle, NodeHandleError> {
+        let mut full_args = vec!["MASQNode".to_string()];
+        full_args.extend(args.iter().map(|arg| arg.to_string()));
+        let (event_tx, event_rx) = channel();
+        let link = EmbedderLink {
+            ui_sub_arc: Arc::new(Mutex::new(None)),
+            event_tx: event_tx.clone(),
+        };
+        {
+            let mut link_opt = EMBEDDER_LINK_OPT.lock().expect("Embedder link is poisoned");
+            if link_opt.is_some() {
+                return Err(NodeHandleError::AlreadyStarted);
+            }
+            *link_opt = Some(link.clone());
+        }
+        let status_arc = Arc::new(Mutex::new(NodeStatus::Starting));
+        let (startup_tx, startup_rx) = channel();
+        let runner = NodeRunner {
+            server_initializer_factory,
+            args: full_args,
+            status_arc: status_arc.clone(),
+            event_tx,
+            startup_tx,
+        };
+        let join_handle = thread::Builder::new()
+            .name("MASQNode".to_string())
+            .spawn(move || runner.run())
+            .expect("Couldn't start the Node's thread");
+        match startup_rx.recv() {
+            Ok(Ok(stopper)) => Ok(NodeHandle {
+                status_arc,
+                link,
+                events_opt: Some(event_rx),
+                stopper_opt: Some(stopper),
+                join_handle_opt: Some(join_handle),
+                next_context_id: Cell::new(0),
+            }),
+            Ok(Err(e)) => {
+                let _ = join_handle.join();
+                Err(NodeHandleError::Configuration(e))
+            }
+            Err(_) => {
+                let _ = join_handle.join();
+                Err(NodeHandleError::StartupFailed)
+            }
+        }
+    }
+}
+
+impl Drop for NodeHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+// The UiGateway picks this up when it's created, and uses it to accept requests from the
+// embedding application and to pass the Node's responses and broadcasts back to it.
+#[derive(Clone)]
+pub struct EmbedderLink {
+    ui_sub_arc: Arc<Mutex<Option<Recipient<NodeFromUiMessage>>>>,
+    event_tx: Sender<NodeEvent>,
+}
+
+impl EmbedderLink {
+    pub fn bind(&self, ui_sub: Recipient<NodeFromUiMessage>) {
+        self.ui_sub_arc
+            .lock()
+            .expect("UiGateway link is poisoned")
+            .replace(ui_sub);
+    }
+
+    pub fn deliver(&self, msg: &NodeToUiMessage) {
+        let wanted = match msg.target {
+            MessageTarget::ClientId(client_id) => client_id == EMBEDDER_CLIENT_ID,
+            MessageTarget::AllExcept(client_id) => client_id != EMBEDDER_CLIENT_ID,
+            MessageTarget::AllClients => true,
+        };
+        if wanted {
+            // The embedding application may have stopped listening; that's its business
+            let _ = self.event_tx.send(NodeEvent::UiMessage(msg.body.clone()));
+        }
+    }
+}
+
+#[cfg(test)]
+impl EmbedderLink {
+    pub fn make_for_test() -> (EmbedderLink, Receiver<NodeEvent>) {
+        let (event_tx, event_rx) = channel();
+        let link = EmbedderLink {
+            ui_sub_arc: Arc::new(Mutex::new(None)),
+            event_tx,
+        };
+        (link, event_rx)
+    }
+
+    pub fn is_bound(&self) -> bool {
+        self.ui_sub_arc
+            .lock()
+            .expect("UiGateway link is poisoned")
+            .is_some()
+    }
+}
+
+pub fn embedder_link_opt() -> Option<EmbedderLink> {
+    EMBEDDER_LINK_OPT
+        .lock()
+        .expect("Embedder link is poisoned")
+        .clone()
+}
+
+struct NodeRunner {
+    server_initializer_factory: Box<dyn ServerInitializerFactory + Send>,
+    args: Vec<String>,
+    status_arc: Arc<Mutex<NodeStatus>>,
+    event_tx: Sender<NodeEvent>,
+    startup_tx: Sender<Result<Addr<NodeStopper>, ConfiguratorError>>,
+}
+
+impl NodeRunner {
+    fn run(self) {
+        let _link_releaser = EmbedderLinkReleaser {};
+        let system = System::new("main");
+        let mut server_initializer = self.server_initializer_factory.make();
+        let mut streams: StdStreams<'_> = StdStreams {
+            stdin: &mut io::stdin(),
+            stdout: &mut io::stdout(),
+            stderr: &mut io::stderr(),
+        };
+        if let Err(e) = server_initializer.go(&mut streams, &self.args) {
+            self.set_status(NodeStatus::Stopped { exit_code: 1 });
+            let _ = self.startup_tx.send(Err(e));
+            return;
+        }
+        actix::spawn(server_initializer.map_err(|_| {
+            System::current().stop_with_code(1);
+        }));
+        let stopper = NodeStopper {}.start();
+        self.set_status(NodeStatus::Running);
+        let _ = self.startup_tx.send(Ok(stopper));
+        let exit_code = system.run();
+        self.set_status(NodeStatus::Stopped { exit_code });
+    }
+
+    fn set_status(&self, status: NodeStatus) {
+        *self.status_arc.lock().expect("Node status is poisoned") = status.clone();
+        let _ = self.event_tx.send(NodeEvent::StatusChanged(status));
+    }
+}
+
+// Frees the way for another NodeHandle however the Node's thread ends, panics included
+struct EmbedderLinkReleaser {}
+
+impl Drop for EmbedderLinkReleaser {
+    fn drop(&mut self) {
+        if let Ok(mut link_opt) = EMBEDDER_LINK_OPT.lock() {
+            *link_opt = None;
+        }
+    }
+}
+
+#[derive(Message)]
+struct StopNode {}
+
+struct NodeStopper {}
+
+impl Actor for NodeStopper {
+    type Context = Context<Self>;
+}
+
+impl Handler<StopNode> for NodeStopper {
+    type Result = ();
+
+    fn handle(&mut self, _msg: StopNode, _ctx: &mut Self::Context) -> Self::Result {
+        System::current().stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run_modes_factories::mocks::{ServerInitializerFactoryMock, ServerInitializerMock};
+    use crate::test_utils::recorder::make_recorder;
+    use futures::Async;
+    use masq_lib::constants::INVALID_PARAM_VALUE_ERROR;
+    use masq_lib::messages::{FromMessageBody, UiDescriptorRequest};
+    use masq_lib::shared_schema::ParamError;
+    use masq_lib::ui_gateway::MessagePath::FireAndForget;
+    use masq_lib::ui_gateway::MessageTarget::{AllClients, AllExcept, ClientId};
+
+    lazy_static! {
+        static ref EMBEDDER_LINK_LOCK: Mutex<()> = Mutex::new(());
+    }
+
+    fn factory_for(server_initializer: ServerInitializerMock) -> Box<ServerInitializerFactoryMock> {
+        Box::new(ServerInitializerFactoryMock::default().make_result(Box::new(server_initializer)))
+    }
+
+    fn make_body(opcode: &str) -> MessageBody {
+        MessageBody {
+            opcode: opcode.to_string(),
+            path: FireAndForget,
+            payload: Ok("{}".to_string()),
+        }
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(EMBEDDER_CLIENT_ID, u64::MAX);
+    }
+
+    #[test]
+    fn start_refuses_a_bad_configuration_and_frees_the_way_for_another_node() {
+        let _lock = EMBEDDER_LINK_LOCK.lock();
+        let go_params_arc = Arc::new(Mutex::new(vec![]));
+        let error = ConfiguratorError::new(vec![ParamError::new(
+            "ip",
+            "Bad IP",
+            INVALID_PARAM_VALUE_ERROR,
+        )]);
+        let server_initializer = ServerInitializerMock::default()
+            .go_params(&go_params_arc)
+            .go_result(Err(error.clone()));
+
+        let result = NodeHandle::start_with(&["--ip", "booga"], factory_for(server_initializer));
+
+        assert_eq!(result.err(), Some(NodeHandleError::Configuration(error)));
+        assert_eq!(
+            *go_params_arc.lock().unwrap(),
+            vec![vec![
+                "MASQNode".to_string(),
+                "--ip".to_string(),
+                "booga".to_string()
+            ]]
+        );
+        assert_eq!(embedder_link_opt().is_none(), true);
+    }
+
+    #[test]
+    fn node_runs_until_it_is_stopped() {
+        let _lock = EMBEDDER_LINK_LOCK.lock();
+        let server_initializer = ServerInitializerMock::default()
+            .go_result(Ok(()))
+            .poll_result(Ok(Async::Ready(())));
+        let mut subject = NodeHandle::start_with(&[], factory_for(server_initializer)).unwrap();
+        let events = subject.take_events().unwrap();
+        assert_eq!(subject.status(), NodeStatus::Running);
+        assert_eq!(embedder_link_opt().is_some(), true);
+
+        let result = subject.stop();
+
+        assert_eq!(result, NodeStatus::Stopped { exit_code: 0 });
+        assert_eq!(subject.status(), NodeStatus::Stopped { exit_code: 0 });
+        assert_eq!(
+            events.try_iter().collect::<Vec<NodeEvent>>(),
+            vec![
+                NodeEvent::StatusChanged(NodeStatus::Running),
+                NodeEvent::StatusChanged(NodeStatus::Stopped { exit_code: 0 })
+            ]
+        );
+        assert_eq!(subject.take_events().is_none(), true);
+        assert_eq!(embedder_link_opt().is_none(), true);
+        assert_eq!(
+            subject.change_configuration("gas-price", "50"),
+            Err(NodeHandleError::Stopped)
+        );
+    }
+
+    #[test]
+    fn node_that_fails_while_running_reports_its_exit_code() {
+        let _lock = EMBEDDER_LINK_LOCK.lock();
+        let server_initializer = ServerInitializerMock::default()
+            .go_result(Ok(()))
+            .poll_result(Err(()));
+        let mut subject = NodeHandle::start_with(&[], factory_for(server_initializer)).unwrap();
+        let events = subject.take_events().unwrap();
+
+        let stopped_event = events
+            .iter()
+            .find(|event| matches!(event, NodeEvent::StatusChanged(NodeStatus::Stopped { .. })));
+
+        assert_eq!(
+            stopped_event,
+            Some(NodeEvent::StatusChanged(NodeStatus::Stopped {
+                exit_code: 1
+            }))
+        );
+        assert_eq!(subject.stop(), NodeStatus::Stopped { exit_code: 1 });
+    }
+
+    #[test]
+    fn a_second_node_cannot_start_while_the_first_is_running() {
+        let _lock = EMBEDDER_LINK_LOCK.lock();
+        let server_initializer = ServerInitializerMock::default()
+            .go_result(Ok(()))
+            .poll_result(Ok(Async::Ready(())));
+        let mut first = NodeHandle::start_with(&[], factory_for(server_initializer)).unwrap();
+
+        let result = NodeHandle::start_with(&[], factory_for(ServerInitializerMock::default()));
+
+        assert_eq!(result.err(), Some(NodeHandleError::AlreadyStarted));
+        first.stop();
+    }
+
+    #[test]
+    fn requests_go_to_the_ui_gateway_once_it_has_bound() {
+        let _lock = EMBEDDER_LINK_LOCK.lock();
+        let server_initializer = ServerInitializerMock::default()
+            .go_result(Ok(()))
+            .poll_result(Ok(Async::Ready(())));
+        let mut subject = NodeHandle::start_with(&[], factory_for(server_initializer)).unwrap();
+        let not_ready_result = subject.send_request(UiDescriptorRequest {});
+        let system = System::new("requests_go_to_the_ui_gateway_once_it_has_bound");
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let ui_gateway_addr = ui_gateway.start();
+        embedder_link_opt()
+            .unwrap()
+            .bind(ui_gateway_addr.recipient());
+
+        let first_result = subject.send_request(UiDescriptorRequest {});
+        let second_result = subject.change_configuration("gas-price", "50");
+
+        System::current().stop();
+        system.run();
+        subject.stop();
+        assert_eq!(not_ready_result, Err(NodeHandleError::NotReady));
+        assert_eq!(first_result, Ok(0));
+        assert_eq!(second_result, Ok(1));
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let first_msg = ui_gateway_recording.get_record::<NodeFromUiMessage>(0);
+        assert_eq!(first_msg.client_id, EMBEDDER_CLIENT_ID);
+        assert_eq!(
+            UiDescriptorRequest::fmb(first_msg.body.clone()).unwrap(),
+            (UiDescriptorRequest {}, 0)
+        );
+        let second_msg = ui_gateway_recording.get_record::<NodeFromUiMessage>(1);
+        assert_eq!(second_msg.client_id, EMBEDDER_CLIENT_ID);
+        assert_eq!(
+            UiSetConfigurationRequest::fmb(second_msg.body.clone()).unwrap(),
+            (
+                UiSetConfigurationRequest {
+                    name: "gas-price".to_string(),
+                    value: "50".to_string()
+                },
+                1
+            )
+        );
+    }
+
+    #[test]
+    fn link_delivers_only_messages_meant_for_the_embedder() {
+        let (subject, event_rx) = EmbedderLink::make_for_test();
+
+        vec![
+            (ClientId(EMBEDDER_CLIENT_ID), "forMe"),
+            (ClientId(1), "forSomebodyElse"),
+            (AllClients, "forEverybody"),
+            (AllExcept(1), "forEverybodyButSomebodyElse"),
+            (AllExcept(EMBEDDER_CLIENT_ID), "forEverybodyButMe"),
+        ]
+        .into_iter()
+        .for_each(|(target, opcode)| {
+            subject.deliver(&NodeToUiMessage {
+                target,
+                body: make_body(opcode),
+            })
+        });
+
+        assert_eq!(
+            event_rx.try_iter().collect::<Vec<NodeEvent>>(),
+            vec![
+                NodeEvent::UiMessage(make_body("forMe")),
+                NodeEvent::UiMessage(make_body("forEverybody")),
+                NodeEvent::UiMessage(make_body("forEverybodyButSomebodyElse")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,204 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::sub_lib::proxy_client::ExitHeaderPolicy;
+use masq_lib::utils::index_of;
+
+// Headers that proxies add to describe where a request came from and what it passed through
+const FORWARDING_HEADERS: &[&str] = &[
+    "forwarded",
+    "via",
+    "x-client-ip",
+    "x-forwarded-for",
+    "x-forwarded-host",
+    "x-forwarded-port",
+    "x-forwarded-proto",
+    "x-real-ip",
+    "true-client-ip",
+];
+
+// Client hints that predate the Sec-CH- prefix
+const LEGACY_CLIENT_HINTS: &[&str] = &[
+    "device-memory",
+    "downlink",
+    "dpr",
+    "ect",
+    "rtt",
+    "viewport-width",
+    "width",
+];
+
+// Browsers send these to every site whether asked or not, so they say little about the device
+const LOW_ENTROPY_CLIENT_HINTS: &[&str] = &["sec-ch-ua", "sec-ch-ua-mobile", "sec-ch-ua-platform"];
+
+// Returns None if the data isn't the head of an HTTP request or the policy leaves it as it is.
+// A request head split across packets is passed through untouched, as is everything after the head.
+pub fn sanitize_request_head(data: &[u8], policy: ExitHeaderPolicy) -> Option<Vec<u8>> {
+    if policy == ExitHeaderPolicy::Off {
+        return None;
+    }
+    let request_line_end = index_of(data, b"\r\n")?;
+    if !is_request_line(&data[..request_line_end]) {
+        return None;
+    }
+    let head_end = index_of(data, b"\r\n\r\n")?;
+    let header_lines = data[request_line_end + 2..head_end + 2]
+        .split_inclusive(|byte| *byte == b'\n')
+        .collect::<Vec<&[u8]>>();
+    let mut kept_lines = Vec::with_capacity(header_lines.len());
+    let mut keeping = true;
+    for line in &header_lines {
+        // A line that starts with whitespace continues the header before it
+        if !line.starts_with(b" ") && !line.starts_with(b"\t") {
+            keeping = passes(&header_name(line), policy);
+        }
+        if keeping {
+            kept_lines.push(*line);
+        }
+    }
+    if kept_lines.len() == header_lines.len() {
+        return None;
+    }
+    let mut sanitized = Vec::with_capacity(data.len());
+    sanitized.extend_from_slice(&data[..request_line_end + 2]);
+    kept_lines
+        .into_iter()
+        .for_each(|line| sanitized.extend_from_slice(line));
+    sanitized.extend_from_slice(&data[head_end + 2..]);
+    Some(sanitized)
+}
+
+fn is_request_line(line: &[u8]) -> bool {
+    let words = line.split(|byte| *byte == b' ').collect::<Vec<&[u8]>>();
+    match words.as_slice() {
+        [method, _, version] => {
+            http::Method::from_bytes(method).is_ok() && version.starts_with(b"HTTP/")
+        }
+        _ => false,
+    }
+}
+
+fn header_name(line: &[u8]) -> String {
+    let end = index_of(line, b":").unwrap_or(line.len());
+    String::from_utf8_lossy(&line[..end]).trim().to_lowercase()
+}
+
+fn passes(name: &str, policy: ExitHeaderPolicy) -> bool {
+    if FORWARDING_HEADERS.contains(&name) {
+        return false;
+    }
+    let is_client_hint = name.starts_with("sec-ch-") || LEGACY_CLIENT_HINTS.contains(&name);
+    match policy {
+        ExitHeaderPolicy::Strip => !is_client_hint,
+        ExitHeaderPolicy::Normalize => !is_client_hint || LOW_ENTROPY_CLIENT_HINTS.contains(&name),
+        ExitHeaderPolicy::Off => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REQUEST: &[u8] = b"GET /index.html HTTP/1.1\r\n\
+        Host: www.example.com\r\n\
+        X-Forwarded-For: 192.168.0.10, 10.0.0.1\r\n\
+        User-Agent: Mozilla/5.0\r\n\
+        Via: 1.1 proxy.lan\r\n\
+        forwarded: for=192.168.0.10\r\n\
+        X-Real-IP: 192.168.0.10\r\n\
+        Sec-CH-UA: \"Chromium\";v=\"118\"\r\n\
+        Sec-CH-UA-Mobile: ?0\r\n\
+        Sec-CH-UA-Platform: \"Linux\"\r\n\
+        Sec-CH-UA-Platform-Version: \"6.5.0\"\r\n\
+        Sec-CH-UA-Model: \"\"\r\n\
+        Device-Memory: 8\r\n\
+        Accept: */*\r\n\
+        \r\n\
+        body";
+
+    #[test]
+    fn strip_removes_forwarding_headers_and_all_client_hints() {
+        let result = sanitize_request_head(REQUEST, ExitHeaderPolicy::Strip);
+
+        assert_eq!(
+            String::from_utf8(result.unwrap()).unwrap(),
+            "GET /index.html HTTP/1.1\r\n\
+             Host: www.example.com\r\n\
+             User-Agent: Mozilla/5.0\r\n\
+             Accept: */*\r\n\
+             \r\n\
+             body"
+        );
+    }
+
+    #[test]
+    fn normalize_keeps_only_low_entropy_client_hints() {
+        let result = sanitize_request_head(REQUEST, ExitHeaderPolicy::Normalize);
+
+        assert_eq!(
+            String::from_utf8(result.unwrap()).unwrap(),
+            "GET /index.html HTTP/1.1\r\n\
+             Host: www.example.com\r\n\
+             User-Agent: Mozilla/5.0\r\n\
+             Sec-CH-UA: \"Chromium\";v=\"118\"\r\n\
+             Sec-CH-UA-Mobile: ?0\r\n\
+             Sec-CH-UA-Platform: \"Linux\"\r\n\
+             Accept: */*\r\n\
+             \r\n\
+             body"
+        );
+    }
+
+    #[test]
+    fn off_leaves_request_alone() {
+        let result = sanitize_request_head(REQUEST, ExitHeaderPolicy::Off);
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn continuation_lines_go_with_their_header() {
+        let data = b"POST /form HTTP/1.1\r\n\
+            Host: www.example.com\r\n\
+            X-Forwarded-For: 192.168.0.10,\r\n\
+            \t10.0.0.1\r\n\
+            Accept: */*\r\n\
+            \r\n";
+
+        let result = sanitize_request_head(data, ExitHeaderPolicy::Strip);
+
+        assert_eq!(
+            String::from_utf8(result.unwrap()).unwrap(),
+            "POST /form HTTP/1.1\r\n\
+             Host: www.example.com\r\n\
+             Accept: */*\r\n\
+             \r\n"
+        );
+    }
+
+    #[test]
+    fn clean_request_is_left_alone() {
+        let data = b"GET / HTTP/1.1\r\nHost: www.example.com\r\n\r\n";
+
+        let result = sanitize_request_head(data, ExitHeaderPolicy::Strip);
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn data_that_is_not_a_whole_request_head_is_left_alone() {
+        vec![
+            &b"\x16\x03\x01\x02\x00\x01\x00\x01\xfc\x03\x03"[..],
+            &b"GET / HTTP/1.1\r\nHost: www.example.com\r\nVia: 1.1 proxy.lan\r\n"[..],
+            &b"some body text\r\nVia: 1.1 proxy.lan\r\n\r\n"[..],
+            &b"Via: 1.1 proxy.lan\r\n\r\n"[..],
+        ]
+        .into_iter()
+        .for_each(|data| {
+            assert_eq!(
+                sanitize_request_head(data, ExitHeaderPolicy::Strip),
+                None,
+                "{:?}",
+                data
+            )
+        });
+    }
+}
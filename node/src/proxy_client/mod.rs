@@ -1,5 +1,6 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+mod header_sanitizer;
 #[cfg(test)]
 mod local_test_utils;
 mod resolver_wrapper;
@@ -8,11 +9,14 @@ mod stream_handler_pool;
 mod stream_reader;
 mod stream_writer;
 
+use crate::accountant::db_access_objects::utils::to_time_t;
+use crate::proxy_client::header_sanitizer::sanitize_request_head;
 use crate::proxy_client::resolver_wrapper::ResolverWrapperFactory;
 use crate::proxy_client::resolver_wrapper::ResolverWrapperFactoryReal;
 use crate::proxy_client::stream_handler_pool::StreamHandlerPool;
 use crate::proxy_client::stream_handler_pool::StreamHandlerPoolFactory;
 use crate::proxy_client::stream_handler_pool::StreamHandlerPoolFactoryReal;
+use crate::sub_lib::accountant::PaymentOverdue_0v1;
 use crate::sub_lib::accountant::ReportExitServiceProvidedMessage;
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::cryptde::PublicKey;
@@ -20,23 +24,28 @@ use crate::sub_lib::hopper::MessageType;
 use crate::sub_lib::hopper::{ExpiredCoresPackage, IncipientCoresPackage};
 use crate::sub_lib::peer_actors::BindMessage;
 use crate::sub_lib::proxy_client::InboundServerData;
-use crate::sub_lib::proxy_client::ProxyClientConfig;
+use crate::sub_lib::proxy_client::PaymentOverdueNoticeMessage;
 use crate::sub_lib::proxy_client::ProxyClientSubs;
 use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, DnsResolveFailure_0v1};
-use crate::sub_lib::proxy_server::ClientRequestPayload_0v1;
+use crate::sub_lib::proxy_client::{ExitHeaderPolicy, ProxyClientConfig};
+use crate::sub_lib::proxy_server::{ClientRequestPayload_0v1, ProxyProtocol};
 use crate::sub_lib::route::Route;
 use crate::sub_lib::sequence_buffer::SequencedPacket;
+use crate::sub_lib::stream_connector::SocketTuning;
 use crate::sub_lib::stream_key::StreamKey;
+use crate::sub_lib::supervision::{run_isolated, RestartReporter};
 use crate::sub_lib::utils::{handle_ui_crash_request, NODE_MAILBOX_CAPACITY};
 use crate::sub_lib::versioned_data::VersionedData;
 use crate::sub_lib::wallet::Wallet;
 use actix::Actor;
+use actix::ActorContext;
 use actix::Addr;
 use actix::Context;
 use actix::Handler;
 use actix::Recipient;
+use actix::Supervised;
 use masq_lib::logger::Logger;
-use masq_lib::ui_gateway::NodeFromUiMessage;
+use masq_lib::ui_gateway::{NodeFromUiMessage, NodeToUiMessage};
 use pretty_hex::PrettyHex;
 use std::collections::HashMap;
 use std::net::SocketAddr;
@@ -55,12 +64,18 @@ pub struct ProxyClient {
     cryptde: &'static dyn CryptDE,
     to_hopper: Option<Recipient<IncipientCoresPackage>>,
     to_accountant: Option<Recipient<ReportExitServiceProvidedMessage>>,
+    to_ui_gateway: Option<Recipient<NodeToUiMessage>>,
+    self_subs: Option<ProxyClientSubs>,
     pool: Option<Box<dyn StreamHandlerPool>>,
     stream_contexts: HashMap<StreamKey, StreamContext>,
+    overdue_notices: HashMap<Wallet, PaymentOverdue_0v1>,
     exit_service_rate: u64,
     exit_byte_rate: u64,
     is_decentralized: bool,
     crashable: bool,
+    socket_tuning: SocketTuning,
+    exit_header_policy: ExitHeaderPolicy,
+    restart_reporter: RestartReporter,
     logger: Logger,
 }
 
@@ -68,6 +83,19 @@ impl Actor for ProxyClient {
     type Context = Context<Self>;
 }
 
+// A panic while serving an exit request shouldn't cost the Node every other stream it's carrying,
+// so the ProxyClient is started under a Supervisor. It stops itself when one of its handlers panics,
+// and comes back here with the streams it knew about forgotten and a brand-new StreamHandlerPool.
+impl Supervised for ProxyClient {
+    fn restarting(&mut self, ctx: &mut Self::Context) {
+        ctx.set_mailbox_capacity(NODE_MAILBOX_CAPACITY);
+        self.stream_contexts.clear();
+        if self.self_subs.is_some() {
+            self.pool = Some(self.make_pool());
+        }
+    }
+}
+
 impl Handler<BindMessage> for ProxyClient {
     type Result = ();
 
@@ -76,6 +104,104 @@ impl Handler<BindMessage> for ProxyClient {
         ctx.set_mailbox_capacity(NODE_MAILBOX_CAPACITY);
         self.to_hopper = Some(msg.peer_actors.hopper.from_hopper_client);
         self.to_accountant = Some(msg.peer_actors.accountant.report_exit_service_provided);
+        self.to_ui_gateway = Some(msg.peer_actors.ui_gateway.node_to_ui_message_sub);
+        self.self_subs = msg.peer_actors.proxy_client_opt;
+        self.pool = Some(self.make_pool());
+    }
+}
+
+impl Handler<ExpiredCoresPackage<ClientRequestPayload_0v1>> for ProxyClient {
+    type Result = ();
+
+    fn handle(
+        &mut self,
+        msg: ExpiredCoresPackage<ClientRequestPayload_0v1>,
+        ctx: &mut Self::Context,
+    ) -> Self::Result {
+        let result = run_isolated(|| self.handle_client_request(msg));
+        self.restart_on_panic(result, ctx)
+    }
+}
+
+impl Handler<InboundServerData> for ProxyClient {
+    type Result = ();
+
+    fn handle(&mut self, msg: InboundServerData, ctx: &mut Self::Context) -> Self::Result {
+        let result = run_isolated(|| self.handle_inbound_server_data(msg));
+        self.restart_on_panic(result, ctx)
+    }
+}
+
+impl Handler<DnsResolveFailure_0v1> for ProxyClient {
+    type Result = ();
+
+    fn handle(&mut self, msg: DnsResolveFailure_0v1, ctx: &mut Self::Context) -> Self::Result {
+        let result = run_isolated(|| self.handle_dns_resolve_failure(msg));
+        self.restart_on_panic(result, ctx)
+    }
+}
+
+impl Handler<PaymentOverdueNoticeMessage> for ProxyClient {
+    type Result = ();
+
+    fn handle(
+        &mut self,
+        msg: PaymentOverdueNoticeMessage,
+        ctx: &mut Self::Context,
+    ) -> Self::Result {
+        let result = run_isolated(|| self.handle_payment_overdue_notice(msg));
+        self.restart_on_panic(result, ctx)
+    }
+}
+
+impl Handler<NodeFromUiMessage> for ProxyClient {
+    type Result = ();
+
+    fn handle(&mut self, msg: NodeFromUiMessage, _ctx: &mut Self::Context) -> Self::Result {
+        handle_ui_crash_request(msg, &self.logger, self.crashable, CRASH_KEY)
+    }
+}
+
+impl ProxyClient {
+    pub fn new(config: ProxyClientConfig) -> ProxyClient {
+        if config.dns_servers.is_empty() {
+            panic!("ProxyClient requires at least one DNS server IP address after the --dns-servers parameter")
+        }
+        ProxyClient {
+            dns_servers: config.dns_servers,
+            resolver_wrapper_factory: Box::new(ResolverWrapperFactoryReal {}),
+            stream_handler_pool_factory: Box::new(StreamHandlerPoolFactoryReal {}),
+            cryptde: config.cryptde,
+            to_hopper: None,
+            to_accountant: None,
+            to_ui_gateway: None,
+            self_subs: None,
+            pool: None,
+            stream_contexts: HashMap::new(),
+            overdue_notices: HashMap::new(),
+            exit_service_rate: config.exit_service_rate,
+            exit_byte_rate: config.exit_byte_rate,
+            is_decentralized: config.is_decentralized,
+            crashable: config.crashable,
+            socket_tuning: config.socket_tuning,
+            exit_header_policy: config.exit_header_policy,
+            restart_reporter: RestartReporter::new("ProxyClient"),
+            logger: Logger::new("ProxyClient"),
+        }
+    }
+
+    pub fn make_subs_from(addr: &Addr<ProxyClient>) -> ProxyClientSubs {
+        ProxyClientSubs {
+            bind: recipient!(addr, BindMessage),
+            from_hopper: recipient!(addr, ExpiredCoresPackage<ClientRequestPayload_0v1>),
+            inbound_server_data: recipient!(addr, InboundServerData),
+            dns_resolve_failed: recipient!(addr, DnsResolveFailure_0v1),
+            node_from_ui: recipient!(addr, NodeFromUiMessage),
+            payment_overdue_notice: recipient!(addr, PaymentOverdueNoticeMessage),
+        }
+    }
+
+    fn make_pool(&self) -> Box<dyn StreamHandlerPool> {
         let mut config = ResolverConfig::new();
         for dns_server_ref in &self.dns_servers {
             info!(self.logger, "Adding DNS server: {}", dns_server_ref.ip());
@@ -87,29 +213,29 @@ impl Handler<BindMessage> for ProxyClient {
         }
         let opts = ResolverOpts::default();
         let resolver = self.resolver_wrapper_factory.make(config, opts);
-        self.pool = Some(self.stream_handler_pool_factory.make(
+        self.stream_handler_pool_factory.make(
             resolver,
             self.cryptde,
             self.to_accountant.clone().expect("Accountant is unbound"),
-            msg.peer_actors.proxy_client_opt.unwrap(),
+            self.self_subs.clone().expect("ProxyClient is unbound"),
             self.exit_service_rate,
             self.exit_byte_rate,
-        ));
+            self.socket_tuning,
+        )
     }
-}
 
-impl Handler<ExpiredCoresPackage<ClientRequestPayload_0v1>> for ProxyClient {
-    type Result = ();
+    fn restart_on_panic(&mut self, result: Result<(), String>, ctx: &mut Context<Self>) {
+        if let Err(panic_message) = result {
+            self.restart_reporter
+                .report(panic_message, self.to_ui_gateway.as_ref());
+            ctx.stop();
+        }
+    }
 
-    fn handle(
-        &mut self,
-        msg: ExpiredCoresPackage<ClientRequestPayload_0v1>,
-        _ctx: &mut Self::Context,
-    ) -> Self::Result {
-        let payload = msg.payload;
+    fn handle_client_request(&mut self, msg: ExpiredCoresPackage<ClientRequestPayload_0v1>) {
+        let mut payload = msg.payload;
         let paying_wallet = msg.paying_wallet;
         if paying_wallet.is_some() || !self.is_decentralized {
-            let pool = self.pool.as_mut().expect("StreamHandlerPool unbound");
             let return_route = msg.remaining_route;
             let latest_stream_context = StreamContext {
                 return_route,
@@ -123,19 +249,34 @@ impl Handler<ExpiredCoresPackage<ClientRequestPayload_0v1>> for ProxyClient {
                 payload.sequenced_packet.sequence_number,
                 payload.sequenced_packet.data.len()
             );
+            if let Some(debtor_wallet) = paying_wallet.as_ref() {
+                if let Some(notice) = self.overdue_notices.remove(debtor_wallet) {
+                    self.send_payment_overdue_notice(notice, &latest_stream_context);
+                }
+            }
             self.stream_contexts
                 .insert(payload.stream_key, latest_stream_context);
+            if payload.protocol == ProxyProtocol::HTTP {
+                if let Some(data) =
+                    sanitize_request_head(&payload.sequenced_packet.data, self.exit_header_policy)
+                {
+                    debug!(
+                        self.logger,
+                        "Removed {} bytes of identifying headers from request on stream {}",
+                        payload.sequenced_packet.data.len() - data.len(),
+                        payload.stream_key
+                    );
+                    payload.sequenced_packet.data = data;
+                }
+            }
+            let pool = self.pool.as_mut().expect("StreamHandlerPool unbound");
             pool.process_package(payload, paying_wallet);
         } else {
             warning!(self.logger, "Refusing to provide exit services for CORES package with {}-byte payload without paying wallet", payload.sequenced_packet.data.len());
         }
     }
-}
 
-impl Handler<InboundServerData> for ProxyClient {
-    type Result = ();
-
-    fn handle(&mut self, msg: InboundServerData, _ctx: &mut Self::Context) -> Self::Result {
+    fn handle_inbound_server_data(&mut self, msg: InboundServerData) {
         let msg_data_len = msg.data.len();
         let msg_source = msg.source;
         let msg_sequence_number = msg.sequence_number;
@@ -169,12 +310,8 @@ impl Handler<InboundServerData> for ProxyClient {
             self.stream_contexts.remove(&msg_stream_key);
         }
     }
-}
 
-impl Handler<DnsResolveFailure_0v1> for ProxyClient {
-    type Result = ();
-
-    fn handle(&mut self, msg: DnsResolveFailure_0v1, _ctx: &mut Self::Context) -> Self::Result {
+    fn handle_dns_resolve_failure(&mut self, msg: DnsResolveFailure_0v1) {
         let stream_key = msg.stream_key;
         let stream_context_opt = self.stream_contexts.get(&stream_key);
         match stream_context_opt {
@@ -206,46 +343,50 @@ impl Handler<DnsResolveFailure_0v1> for ProxyClient {
             ),
         }
     }
-}
-
-impl Handler<NodeFromUiMessage> for ProxyClient {
-    type Result = ();
-
-    fn handle(&mut self, msg: NodeFromUiMessage, _ctx: &mut Self::Context) -> Self::Result {
-        handle_ui_crash_request(msg, &self.logger, self.crashable, CRASH_KEY)
-    }
-}
 
-impl ProxyClient {
-    pub fn new(config: ProxyClientConfig) -> ProxyClient {
-        if config.dns_servers.is_empty() {
-            panic!("ProxyClient requires at least one DNS server IP address after the --dns-servers parameter")
-        }
-        ProxyClient {
-            dns_servers: config.dns_servers,
-            resolver_wrapper_factory: Box::new(ResolverWrapperFactoryReal {}),
-            stream_handler_pool_factory: Box::new(StreamHandlerPoolFactoryReal {}),
-            cryptde: config.cryptde,
-            to_hopper: None,
-            to_accountant: None,
-            pool: None,
-            stream_contexts: HashMap::new(),
-            exit_service_rate: config.exit_service_rate,
-            exit_byte_rate: config.exit_byte_rate,
-            is_decentralized: config.is_decentralized,
-            crashable: config.crashable,
-            logger: Logger::new("ProxyClient"),
+    // A debtor can only be reached back along a route it has sent us a request over, so if none of
+    // its streams is open, the notice waits for its next request
+    fn handle_payment_overdue_notice(&mut self, msg: PaymentOverdueNoticeMessage) {
+        let stream_context_opt = self.stream_contexts.values().find(|stream_context| {
+            stream_context.paying_wallet.as_ref() == Some(&msg.debtor_wallet)
+        });
+        match stream_context_opt {
+            Some(stream_context) => self.send_payment_overdue_notice(msg.notice, stream_context),
+            None => {
+                debug!(
+                    self.logger,
+                    "No open stream for debtor {}; holding its payment overdue notice for its next request",
+                    msg.debtor_wallet
+                );
+                self.overdue_notices.insert(msg.debtor_wallet, msg.notice);
+            }
         }
     }
 
-    pub fn make_subs_from(addr: &Addr<ProxyClient>) -> ProxyClientSubs {
-        ProxyClientSubs {
-            bind: recipient!(addr, BindMessage),
-            from_hopper: recipient!(addr, ExpiredCoresPackage<ClientRequestPayload_0v1>),
-            inbound_server_data: recipient!(addr, InboundServerData),
-            dns_resolve_failed: recipient!(addr, DnsResolveFailure_0v1),
-            node_from_ui: recipient!(addr, NodeFromUiMessage),
+    fn send_payment_overdue_notice(
+        &self,
+        notice: PaymentOverdue_0v1,
+        stream_context: &StreamContext,
+    ) {
+        if notice.deadline <= to_time_t(SystemTime::now()) as u64 {
+            debug!(
+                self.logger,
+                "Dropping payment overdue notice whose deadline has already passed"
+            );
+            return;
         }
+        let package = IncipientCoresPackage::new(
+            self.cryptde,
+            stream_context.return_route.clone(),
+            MessageType::from(notice),
+            &stream_context.payload_destination_key,
+        )
+        .expect("Failed to create IncipientCoresPackage");
+        self.to_hopper
+            .as_ref()
+            .expect("Hopper is unbound")
+            .try_send(package)
+            .expect("Hopper is dead");
     }
 
     fn send_response_to_hopper(
@@ -355,9 +496,12 @@ mod tests {
     use crate::test_utils::recorder::Recorder;
     use crate::test_utils::unshared_test_utils::prove_that_crash_request_handler_is_hooked_up;
     use crate::test_utils::*;
+    use actix::Supervisor;
     use actix::System;
     use masq_lib::blockchains::chains::Chain;
+    use masq_lib::messages::{FromMessageBody, UiActorRestartedBroadcast};
     use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use masq_lib::ui_gateway::MessageTarget;
     use std::cell::RefCell;
     use std::net::SocketAddr;
     use std::net::{IpAddr, SocketAddrV4};
@@ -419,6 +563,7 @@ mod tests {
                     ProxyClientSubs,
                     u64,
                     u64,
+                    SocketTuning,
                 )>,
             >,
         >,
@@ -434,6 +579,7 @@ mod tests {
             proxy_client_subs: ProxyClientSubs,
             exit_service_rate: u64,
             exit_byte_rate: u64,
+            socket_tuning: SocketTuning,
         ) -> Box<dyn StreamHandlerPool> {
             self.make_parameters.lock().unwrap().push((
                 resolver,
@@ -442,6 +588,7 @@ mod tests {
                 proxy_client_subs,
                 exit_service_rate,
                 exit_byte_rate,
+                socket_tuning,
             ));
             self.make_results.borrow_mut().remove(0)
         }
@@ -466,6 +613,7 @@ mod tests {
                         ProxyClientSubs,
                         u64,
                         u64,
+                        SocketTuning,
                     )>,
                 >,
             >,
@@ -494,6 +642,8 @@ mod tests {
             exit_byte_rate: 200,
             is_decentralized,
             crashable: false,
+            socket_tuning: SocketTuning::default(),
+            exit_header_policy: ExitHeaderPolicy::default(),
         };
 
         let zero_hop = ProxyClient::new(config_factory(false));
@@ -517,6 +667,8 @@ mod tests {
             exit_byte_rate: 200,
             is_decentralized: true,
             crashable: true,
+            socket_tuning: SocketTuning::default(),
+            exit_header_policy: ExitHeaderPolicy::default(),
         });
 
         prove_that_crash_request_handler_is_hooked_up(proxy_client, CRASH_KEY);
@@ -534,6 +686,8 @@ mod tests {
             exit_byte_rate: 200,
             is_decentralized: true,
             crashable: false,
+            socket_tuning: SocketTuning::default(),
+            exit_header_policy: ExitHeaderPolicy::default(),
         });
     }
 
@@ -563,6 +717,12 @@ mod tests {
             exit_byte_rate: 200,
             is_decentralized: true,
             crashable: false,
+            socket_tuning: SocketTuning {
+                recv_buffer_size: 1048576,
+                send_buffer_size: 65536,
+                nodelay: true,
+            },
+            exit_header_policy: ExitHeaderPolicy::default(),
         });
         subject.resolver_wrapper_factory = Box::new(resolver_wrapper_factory);
         subject.stream_handler_pool_factory = Box::new(pool_factory);
@@ -595,11 +755,24 @@ mod tests {
         );
         assert_eq!(opts, ResolverOpts::default());
         assert_eq!(resolver_wrapper_new_parameters.is_empty(), true);
+        let pool_factory_make_parameters = pool_factory_make_parameters.lock().unwrap();
+        let (_, _, _, _, exit_service_rate, exit_byte_rate, socket_tuning) =
+            &pool_factory_make_parameters[0];
+        assert_eq!(*exit_service_rate, 100);
+        assert_eq!(*exit_byte_rate, 200);
+        assert_eq!(
+            *socket_tuning,
+            SocketTuning {
+                recv_buffer_size: 1048576,
+                send_buffer_size: 65536,
+                nodelay: true,
+            }
+        );
     }
 
     #[test]
-    #[should_panic(expected = "StreamHandlerPool unbound")]
-    fn panics_if_unbound() {
+    fn logs_and_stops_instead_of_panicking_if_unbound() {
+        init_test_logging();
         let request = ClientRequestPayload_0v1 {
             stream_key: StreamKey::make_meaningless_stream_key(),
             sequenced_packet: SequencedPacket {
@@ -620,7 +793,7 @@ mod tests {
             request,
             0,
         );
-        let system = System::new("panics_if_hopper_is_unbound");
+        let system = System::new("logs_and_stops_instead_of_panicking_if_unbound");
         let subject = ProxyClient::new(ProxyClientConfig {
             cryptde,
             dns_servers: dnss(),
@@ -628,6 +801,8 @@ mod tests {
             exit_byte_rate: 200,
             is_decentralized: true,
             crashable: false,
+            socket_tuning: SocketTuning::default(),
+            exit_header_policy: ExitHeaderPolicy::default(),
         });
         let subject_addr: Addr<ProxyClient> = subject.start();
 
@@ -635,6 +810,105 @@ mod tests {
 
         System::current().stop_with_code(0);
         system.run();
+        TestLogHandler::new().exists_log_containing(
+            "ERROR: ProxyClient: Restarting with clean state (restart #1) after panic: \
+             StreamHandlerPool unbound",
+        );
+    }
+
+    struct PanickingStreamHandlerPool {}
+
+    impl StreamHandlerPool for PanickingStreamHandlerPool {
+        fn process_package(
+            &self,
+            _payload: ClientRequestPayload_0v1,
+            _paying_wallet: Option<Wallet>,
+        ) {
+            panic!("Malformed response choked the pool")
+        }
+    }
+
+    #[test]
+    fn panic_in_handler_restarts_proxy_client_with_fresh_pool_and_broadcasts_it() {
+        let cryptde = main_cryptde();
+        let make_package = |sequence_number: u64| {
+            ExpiredCoresPackage::new(
+                SocketAddr::from_str("1.2.3.4:1234").unwrap(),
+                Some(make_wallet("consuming")),
+                make_one_way_route_to_proxy_client(vec![&make_meaningless_public_key()]),
+                ClientRequestPayload_0v1 {
+                    stream_key: StreamKey::make_meaningless_stream_key(),
+                    sequenced_packet: SequencedPacket {
+                        data: b"inbound data".to_vec(),
+                        sequence_number,
+                        last_data: false,
+                    },
+                    target_hostname: None,
+                    target_port: 0,
+                    protocol: ProxyProtocol::HTTP,
+                    originator_public_key: PublicKey::new(&b"originator"[..]),
+                },
+                0,
+            )
+        };
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let system =
+            System::new("panic_in_handler_restarts_proxy_client_with_fresh_pool_and_broadcasts_it");
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        let mut process_package_parameters = Arc::new(Mutex::new(vec![]));
+        let fresh_pool = StreamHandlerPoolMock::new()
+            .process_package_parameters(&mut process_package_parameters);
+        let mut pool_factory_make_parameters = Arc::new(Mutex::new(vec![]));
+        let pool_factory = StreamHandlerPoolFactoryMock::new()
+            .make_parameters(&mut pool_factory_make_parameters)
+            .make_result(Box::new(PanickingStreamHandlerPool {}))
+            .make_result(Box::new(fresh_pool));
+        let resolver_factory = ResolverWrapperFactoryMock::new()
+            .new_result(Box::new(ResolverWrapperMock::new()))
+            .new_result(Box::new(ResolverWrapperMock::new()));
+        let mut subject = ProxyClient::new(ProxyClientConfig {
+            cryptde,
+            dns_servers: dnss(),
+            exit_service_rate: 100,
+            exit_byte_rate: 200,
+            is_decentralized: true,
+            crashable: false,
+            socket_tuning: SocketTuning::default(),
+            exit_header_policy: ExitHeaderPolicy::default(),
+        });
+        subject.resolver_wrapper_factory = Box::new(resolver_factory);
+        subject.stream_handler_pool_factory = Box::new(pool_factory);
+        let subject_addr: Addr<ProxyClient> = Supervisor::start(|_| subject);
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr.try_send(make_package(0)).unwrap();
+        subject_addr.try_send(make_package(1)).unwrap();
+
+        System::current().stop_with_code(0);
+        system.run();
+        assert_eq!(pool_factory_make_parameters.lock().unwrap().len(), 2);
+        let process_package_parameters = process_package_parameters.lock().unwrap();
+        assert_eq!(process_package_parameters.len(), 1);
+        assert_eq!(
+            process_package_parameters[0]
+                .0
+                .sequenced_packet
+                .sequence_number,
+            1
+        );
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let message = ui_gateway_recording.get_record::<NodeToUiMessage>(0);
+        assert_eq!(message.target, MessageTarget::AllClients);
+        assert_eq!(
+            UiActorRestartedBroadcast::fmb(message.body.clone())
+                .unwrap()
+                .0,
+            UiActorRestartedBroadcast {
+                actor: "ProxyClient".to_string(),
+                panic_message: "Malformed response choked the pool".to_string(),
+                restart_count: 1,
+            }
+        );
     }
 
     #[test]
@@ -652,6 +926,8 @@ mod tests {
                 exit_byte_rate: 0,
                 is_decentralized: true,
                 crashable: false,
+                socket_tuning: SocketTuning::default(),
+                exit_header_policy: ExitHeaderPolicy::default(),
             });
             let subject_addr = subject.start();
             let subject_subs = ProxyClient::make_subs_from(&subject_addr);
@@ -693,6 +969,8 @@ mod tests {
                 exit_byte_rate: 0,
                 is_decentralized: true,
                 crashable: false,
+                socket_tuning: SocketTuning::default(),
+                exit_header_policy: ExitHeaderPolicy::default(),
             });
             subject.stream_contexts.insert(
                 stream_key_inner,
@@ -785,6 +1063,8 @@ mod tests {
             exit_byte_rate: 200,
             is_decentralized: true,
             crashable: false,
+            socket_tuning: SocketTuning::default(),
+            exit_header_policy: ExitHeaderPolicy::default(),
         });
         subject.resolver_wrapper_factory = Box::new(resolver_factory);
         subject.stream_handler_pool_factory = Box::new(pool_factory);
@@ -799,6 +1079,76 @@ mod tests {
         assert_eq!(parameter, (request, Some(make_wallet("consuming")),));
     }
 
+    #[test]
+    fn identifying_headers_are_removed_before_http_data_reaches_stream_handler_pool() {
+        let cryptde = main_cryptde();
+        let make_request = |data: &[u8], protocol: ProxyProtocol| ClientRequestPayload_0v1 {
+            stream_key: StreamKey::make_meaningless_stream_key(),
+            sequenced_packet: SequencedPacket {
+                data: data.to_vec(),
+                sequence_number: 0,
+                last_data: false,
+            },
+            target_hostname: Some("www.example.com".to_string()),
+            target_port: 80,
+            protocol,
+            originator_public_key: PublicKey::new(&b"originator"[..]),
+        };
+        let request_head =
+            b"GET / HTTP/1.1\r\nHost: www.example.com\r\nX-Forwarded-For: 192.168.0.10\r\n\r\n";
+        let http_request = make_request(request_head, ProxyProtocol::HTTP);
+        let tls_request = make_request(request_head, ProxyProtocol::TLS);
+        let make_package = |request: &ClientRequestPayload_0v1| {
+            ExpiredCoresPackage::new(
+                SocketAddr::from_str("1.2.3.4:1234").unwrap(),
+                Some(make_wallet("consuming")),
+                make_meaningless_route(),
+                request.clone(),
+                0,
+            )
+        };
+        let system = System::new(
+            "identifying_headers_are_removed_before_http_data_reaches_stream_handler_pool",
+        );
+        let peer_actors = peer_actors_builder().build();
+        let mut process_package_parameters = Arc::new(Mutex::new(vec![]));
+        let pool = Box::new(
+            StreamHandlerPoolMock::new()
+                .process_package_parameters(&mut process_package_parameters),
+        );
+        let pool_factory = StreamHandlerPoolFactoryMock::new().make_result(pool);
+        let resolver = ResolverWrapperMock::new();
+        let resolver_factory = ResolverWrapperFactoryMock::new().new_result(Box::new(resolver));
+        let mut subject = ProxyClient::new(ProxyClientConfig {
+            cryptde,
+            dns_servers: dnss(),
+            exit_service_rate: 100,
+            exit_byte_rate: 200,
+            is_decentralized: true,
+            crashable: false,
+            socket_tuning: SocketTuning::default(),
+            exit_header_policy: ExitHeaderPolicy::Strip,
+        });
+        subject.resolver_wrapper_factory = Box::new(resolver_factory);
+        subject.stream_handler_pool_factory = Box::new(pool_factory);
+        let subject_addr: Addr<ProxyClient> = subject.start();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr.try_send(make_package(&http_request)).unwrap();
+        subject_addr.try_send(make_package(&tls_request)).unwrap();
+
+        System::current().stop_with_code(0);
+        system.run();
+        let mut process_package_parameters = process_package_parameters.lock().unwrap();
+        let (http_parameter, _) = process_package_parameters.remove(0);
+        assert_eq!(
+            http_parameter.sequenced_packet.data,
+            b"GET / HTTP/1.1\r\nHost: www.example.com\r\n\r\n".to_vec()
+        );
+        let (tls_parameter, _) = process_package_parameters.remove(0);
+        assert_eq!(tls_parameter, tls_request);
+    }
+
     #[test]
     fn refuse_to_provide_exit_services_with_no_paying_wallet() {
         init_test_logging();
@@ -842,6 +1192,8 @@ mod tests {
             exit_byte_rate: rate_pack_exit_byte(100),
             is_decentralized: true,
             crashable: false,
+            socket_tuning: SocketTuning::default(),
+            exit_header_policy: ExitHeaderPolicy::default(),
         });
         subject.resolver_wrapper_factory = Box::new(resolver_factory);
         subject.stream_handler_pool_factory = Box::new(pool_factory);
@@ -909,6 +1261,8 @@ mod tests {
             exit_byte_rate: rate_pack_exit_byte(100),
             is_decentralized: false,
             crashable: false,
+            socket_tuning: SocketTuning::default(),
+            exit_header_policy: ExitHeaderPolicy::default(),
         });
         subject.resolver_wrapper_factory = Box::new(resolver_factory);
         subject.stream_handler_pool_factory = Box::new(pool_factory);
@@ -940,6 +1294,8 @@ mod tests {
             exit_byte_rate: 200,
             is_decentralized: true,
             crashable: false,
+            socket_tuning: SocketTuning::default(),
+            exit_header_policy: ExitHeaderPolicy::default(),
         });
         subject.stream_contexts.insert(
             stream_key.clone(),
@@ -1089,6 +1445,8 @@ mod tests {
             exit_byte_rate: 200,
             is_decentralized: true,
             crashable: false,
+            socket_tuning: SocketTuning::default(),
+            exit_header_policy: ExitHeaderPolicy::default(),
         });
         subject.stream_contexts.insert(
             stream_key.clone(),
@@ -1141,6 +1499,8 @@ mod tests {
             exit_byte_rate: 200,
             is_decentralized: true,
             crashable: false,
+            socket_tuning: SocketTuning::default(),
+            exit_header_policy: ExitHeaderPolicy::default(),
         });
         subject.stream_contexts.insert(
             stream_key.clone(),
@@ -1191,6 +1551,8 @@ mod tests {
             exit_byte_rate: 200,
             is_decentralized: true,
             crashable: false,
+            socket_tuning: SocketTuning::default(),
+            exit_header_policy: ExitHeaderPolicy::default(),
         });
         let mut process_package_params_arc = Arc::new(Mutex::new(vec![]));
         let pool = StreamHandlerPoolMock::new()
@@ -1294,4 +1656,161 @@ mod tests {
             }
         )
     }
+
+    fn far_future_deadline() -> u64 {
+        to_time_t(SystemTime::now()) as u64 + 3600
+    }
+
+    #[test]
+    fn payment_overdue_notice_goes_back_over_an_open_stream_of_the_debtor() {
+        let (hopper, _, hopper_recording_arc) = make_recorder();
+        let system =
+            System::new("payment_overdue_notice_goes_back_over_an_open_stream_of_the_debtor");
+        let mut subject = ProxyClient::new(ProxyClientConfig {
+            cryptde: main_cryptde(),
+            dns_servers: vec![SocketAddr::from_str("8.7.6.5:4321").unwrap()],
+            exit_service_rate: 100,
+            exit_byte_rate: 200,
+            is_decentralized: true,
+            crashable: false,
+            socket_tuning: SocketTuning::default(),
+            exit_header_policy: ExitHeaderPolicy::default(),
+        });
+        let route = make_meaningless_route();
+        subject.stream_contexts.insert(
+            StreamKey::make_meaningful_stream_key("other"),
+            StreamContext {
+                return_route: Route { hops: vec![] },
+                payload_destination_key: PublicKey::new(&b"other"[..]),
+                paying_wallet: Some(make_wallet("other")),
+            },
+        );
+        subject.stream_contexts.insert(
+            StreamKey::make_meaningful_stream_key("debtor"),
+            StreamContext {
+                return_route: route.clone(),
+                payload_destination_key: PublicKey::new(&b"abcd"[..]),
+                paying_wallet: Some(make_wallet("debtor")),
+            },
+        );
+        let subject_addr: Addr<ProxyClient> = subject.start();
+        let peer_actors = peer_actors_builder().hopper(hopper).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let notice = PaymentOverdue_0v1 {
+            balance_gwei: 1_234_567,
+            deadline: far_future_deadline(),
+        };
+
+        subject_addr
+            .try_send(PaymentOverdueNoticeMessage {
+                debtor_wallet: make_wallet("debtor"),
+                notice: notice.clone(),
+            })
+            .unwrap();
+
+        System::current().stop_with_code(0);
+        system.run();
+        let hopper_recording = hopper_recording_arc.lock().unwrap();
+        assert_eq!(
+            hopper_recording.get_record::<IncipientCoresPackage>(0),
+            &IncipientCoresPackage::new(
+                main_cryptde(),
+                route,
+                MessageType::from(notice),
+                &PublicKey::new(&b"abcd"[..]),
+            )
+            .unwrap()
+        );
+        assert_eq!(hopper_recording.len(), 1);
+    }
+
+    #[test]
+    fn payment_overdue_notice_waits_for_the_debtors_next_request_unless_its_deadline_passes() {
+        let (hopper, _, hopper_recording_arc) = make_recorder();
+        let system = System::new("payment_overdue_notice_waits_for_the_debtors_next_request");
+        let mut subject = ProxyClient::new(ProxyClientConfig {
+            cryptde: main_cryptde(),
+            dns_servers: vec![SocketAddr::from_str("8.7.6.5:4321").unwrap()],
+            exit_service_rate: 100,
+            exit_byte_rate: 200,
+            is_decentralized: true,
+            crashable: false,
+            socket_tuning: SocketTuning::default(),
+            exit_header_policy: ExitHeaderPolicy::default(),
+        });
+        let pool_factory =
+            StreamHandlerPoolFactoryMock::new().make_result(Box::new(StreamHandlerPoolMock::new()));
+        subject.stream_handler_pool_factory = Box::new(pool_factory);
+        let subject_addr: Addr<ProxyClient> = subject.start();
+        let peer_actors = peer_actors_builder().hopper(hopper).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let notice = PaymentOverdue_0v1 {
+            balance_gwei: 1_234_567,
+            deadline: far_future_deadline(),
+        };
+        let expired_notice = PaymentOverdue_0v1 {
+            balance_gwei: 7_654_321,
+            deadline: 1_000,
+        };
+        subject_addr
+            .try_send(PaymentOverdueNoticeMessage {
+                debtor_wallet: make_wallet("debtor"),
+                notice: notice.clone(),
+            })
+            .unwrap();
+        subject_addr
+            .try_send(PaymentOverdueNoticeMessage {
+                debtor_wallet: make_wallet("late debtor"),
+                notice: expired_notice,
+            })
+            .unwrap();
+        let originator_public_key = PublicKey::new(&[4, 3, 2, 1]);
+        let return_route = make_meaningless_route();
+        let make_request = |name: &str, wallet: &str| {
+            ExpiredCoresPackage::new(
+                SocketAddr::from_str("2.3.4.5:1235").unwrap(),
+                Some(make_wallet(wallet)),
+                return_route.clone(),
+                ClientRequestPayload_0v1 {
+                    stream_key: StreamKey::make_meaningful_stream_key(name),
+                    sequenced_packet: SequencedPacket {
+                        data: vec![],
+                        sequence_number: 0,
+                        last_data: false,
+                    },
+                    target_hostname: None,
+                    target_port: 0,
+                    protocol: ProxyProtocol::TLS,
+                    originator_public_key: originator_public_key.clone(),
+                }
+                .into(),
+                0,
+            )
+        };
+
+        subject_addr
+            .try_send(make_request("first", "debtor"))
+            .unwrap();
+        subject_addr
+            .try_send(make_request("second", "debtor"))
+            .unwrap();
+        subject_addr
+            .try_send(make_request("third", "late debtor"))
+            .unwrap();
+
+        System::current().stop_with_code(0);
+        system.run();
+        let hopper_recording = hopper_recording_arc.lock().unwrap();
+        assert_eq!(
+            hopper_recording.get_record::<IncipientCoresPackage>(0),
+            &IncipientCoresPackage::new(
+                main_cryptde(),
+                return_route,
+                MessageType::from(notice),
+                &originator_public_key,
+            )
+            .unwrap()
+        );
+        assert_eq!(hopper_recording.len(), 1);
+    }
 }
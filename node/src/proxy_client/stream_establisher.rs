@@ -10,8 +10,8 @@ use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::proxy_client::{InboundServerData, ProxyClientSubs};
 use crate::sub_lib::proxy_server::ClientRequestPayload_0v1;
 use crate::sub_lib::sequence_buffer::SequencedPacket;
-use crate::sub_lib::stream_connector::StreamConnector;
 use crate::sub_lib::stream_connector::StreamConnectorReal;
+use crate::sub_lib::stream_connector::{SocketTuning, StreamConnector};
 use crate::sub_lib::stream_key::StreamKey;
 use crate::sub_lib::tokio_wrappers::ReadHalfWrapper;
 use actix::Recipient;
@@ -37,7 +37,7 @@ impl Clone for StreamEstablisher {
             cryptde: self.cryptde,
             stream_adder_tx: self.stream_adder_tx.clone(),
             stream_killer_tx: self.stream_killer_tx.clone(),
-            stream_connector: Box::new(StreamConnectorReal {}),
+            stream_connector: Box::new(StreamConnectorReal::default()),
             proxy_client_sub: self.proxy_client_sub.clone(),
             logger: self.logger.clone(),
             channel_factory: Box::new(FuturesChannelFactoryReal {}),
@@ -116,6 +116,7 @@ pub struct StreamEstablisherFactoryReal {
     pub stream_adder_tx: Sender<(StreamKey, StreamSenders)>,
     pub stream_killer_tx: Sender<(StreamKey, u64)>,
     pub proxy_client_subs: ProxyClientSubs,
+    pub socket_tuning: SocketTuning,
     pub logger: Logger,
 }
 
@@ -125,7 +126,7 @@ impl StreamEstablisherFactory for StreamEstablisherFactoryReal {
             cryptde: self.cryptde,
             stream_adder_tx: self.stream_adder_tx.clone(),
             stream_killer_tx: self.stream_killer_tx.clone(),
-            stream_connector: Box::new(StreamConnectorReal {}),
+            stream_connector: Box::new(StreamConnectorReal::new(self.socket_tuning)),
             proxy_client_sub: self.proxy_client_subs.inbound_server_data.clone(),
             logger: self.logger.clone(),
             channel_factory: Box::new(FuturesChannelFactoryReal {}),
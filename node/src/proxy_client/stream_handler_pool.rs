@@ -8,9 +8,10 @@ use crate::sub_lib::accountant::ReportExitServiceProvidedMessage;
 use crate::sub_lib::channel_wrappers::SenderWrapper;
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::proxy_client::{error_socket_addr, ProxyClientSubs};
-use crate::sub_lib::proxy_client::{DnsResolveFailure_0v1, InboundServerData};
+use crate::sub_lib::proxy_client::{DnsResolveFailure_0v1, ExitFailureKind, InboundServerData};
 use crate::sub_lib::proxy_server::ClientRequestPayload_0v1;
 use crate::sub_lib::sequence_buffer::SequencedPacket;
+use crate::sub_lib::stream_connector::SocketTuning;
 use crate::sub_lib::stream_key::StreamKey;
 use crate::sub_lib::wallet::Wallet;
 use actix::Recipient;
@@ -80,6 +81,7 @@ impl StreamHandlerPoolReal {
         proxy_client_subs: ProxyClientSubs,
         exit_service_rate: u64,
         exit_byte_rate: u64,
+        socket_tuning: SocketTuning,
     ) -> StreamHandlerPoolReal {
         let (stream_killer_tx, stream_killer_rx) = unbounded();
         let (stream_adder_tx, stream_adder_rx) = unbounded();
@@ -90,6 +92,7 @@ impl StreamHandlerPoolReal {
                     stream_adder_tx,
                     stream_killer_tx,
                     proxy_client_subs: proxy_client_subs.clone(),
+                    socket_tuning,
                     logger: Logger::new("ProxyClient"),
                 }),
                 accountant_sub,
@@ -328,12 +331,27 @@ impl StreamHandlerPoolReal {
         inner_arc: Arc<Mutex<StreamHandlerPoolRealInner>>,
         target_hostname: String,
     ) -> StreamEstablisherResult {
+        let dns_resolve_failed_sub = inner_arc
+            .lock()
+            .expect("Stream handler pool is poisoned")
+            .proxy_client_subs
+            .dns_resolve_failed
+            .clone();
+        let stream_key = payload.stream_key;
         let mut stream_establisher = StreamHandlerPoolReal::make_establisher(inner_arc);
         Box::new(
             future::lazy(move || {
                 stream_establisher.establish_stream(&payload, vec![ip_addr], target_hostname)
             })
-            .map_err(|io_error| format!("Could not establish stream: {:?}", io_error)),
+            .map_err(move |io_error| {
+                dns_resolve_failed_sub
+                    .try_send(DnsResolveFailure_0v1::new_with_kind(
+                        stream_key,
+                        ExitFailureKind::from_connection_error(&io_error),
+                    ))
+                    .expect("ProxyClient is poisoned");
+                format!("Could not establish stream: {:?}", io_error)
+            }),
         )
     }
 
@@ -367,13 +385,16 @@ impl StreamHandlerPoolReal {
                         &mut establisher,
                     )
                 })
-                .map_err(move |io_error| {
+                .map_err(move |(failure_kind, io_error)| {
                     // We are sending this message;
                     // 1. DNS fails to resolve an IP
                     // 2. DNS resolves a wildcard IP E.G. [0.0.0.0]
                     // 3. An exit nodes fails to establish a stream
                     dns_resolve_failed_sub
-                        .try_send(DnsResolveFailure_0v1::new(stream_key))
+                        .try_send(DnsResolveFailure_0v1::new_with_kind(
+                            stream_key,
+                            failure_kind,
+                        ))
                         .expect("ProxyClient is poisoned");
                     format!("Could not establish stream: {:?}", io_error)
                 }),
@@ -393,14 +414,14 @@ impl StreamHandlerPoolReal {
         lookup_result: Result<LookupIp, ResolveError>,
         logger: Logger,
         establisher: &mut StreamEstablisher,
-    ) -> io::Result<Box<dyn SenderWrapper<SequencedPacket>>> {
+    ) -> Result<Box<dyn SenderWrapper<SequencedPacket>>, (ExitFailureKind, io::Error)> {
         let ip_addrs: Vec<IpAddr> = match lookup_result {
             Err(e) => {
                 error!(
                     logger,
                     "Could not find IP address for host {}: {}", target_hostname, e
                 );
-                return Err(io::Error::from(e));
+                return Err((ExitFailureKind::DnsResolution, io::Error::from(e)));
             }
             Ok(lookup_ip) => lookup_ip.iter().collect(),
         };
@@ -412,14 +433,19 @@ impl StreamHandlerPoolReal {
                 logger,
                 "Unable to find valid IP addresses for host {}: {:?}", target_hostname, &ip_addrs
             );
-            return Err(io::Error::from(io::ErrorKind::NotFound));
+            return Err((
+                ExitFailureKind::DnsResolution,
+                io::Error::from(io::ErrorKind::NotFound),
+            ));
         }
 
         debug!(
             logger,
             "Found IP addresses for {}: {:?}", target_hostname, &filtered_ip_addrs
         );
-        establisher.establish_stream(payload, filtered_ip_addrs, target_hostname)
+        establisher
+            .establish_stream(payload, filtered_ip_addrs, target_hostname)
+            .map_err(|e| (ExitFailureKind::from_connection_error(&e), e))
     }
 
     fn make_fqdn(target_hostname: &str) -> String {
@@ -540,6 +566,7 @@ pub trait StreamHandlerPoolFactory {
         proxy_client_subs: ProxyClientSubs,
         exit_service_rate: u64,
         exit_byte_rate: u64,
+        socket_tuning: SocketTuning,
     ) -> Box<dyn StreamHandlerPool>;
 }
 
@@ -554,6 +581,7 @@ impl StreamHandlerPoolFactory for StreamHandlerPoolFactoryReal {
         proxy_client_subs: ProxyClientSubs,
         exit_service_rate: u64,
         exit_byte_rate: u64,
+        socket_tuning: SocketTuning,
     ) -> Box<dyn StreamHandlerPool> {
         Box::new(StreamHandlerPoolReal::new(
             resolver,
@@ -562,6 +590,7 @@ impl StreamHandlerPoolFactory for StreamHandlerPoolFactoryReal {
             proxy_client_subs,
             exit_service_rate,
             exit_byte_rate,
+            socket_tuning,
         ))
     }
 }
@@ -736,6 +765,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                SocketTuning::default(),
             );
             subject.inner.lock().unwrap().stream_writer_channels.insert(
                 stream_key,
@@ -798,6 +828,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                SocketTuning::default(),
             );
             {
                 let mut inner = subject.inner.lock().unwrap();
@@ -883,6 +914,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                SocketTuning::default(),
             );
             let (stream_killer_tx, stream_killer_rx) = unbounded();
             subject.stream_killer_rx = stream_killer_rx;
@@ -965,6 +997,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                SocketTuning::default(),
             );
             {
                 let mut inner = subject.inner.lock().unwrap();
@@ -1026,6 +1059,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                SocketTuning::default(),
             );
             {
                 let mut inner = subject.inner.lock().unwrap();
@@ -1108,6 +1142,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                SocketTuning::default(),
             );
             let (stream_killer_tx, stream_killer_rx) = unbounded();
             subject.stream_killer_rx = stream_killer_rx;
@@ -1198,6 +1233,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                SocketTuning::default(),
             );
 
             run_process_package_in_actix(subject, package);
@@ -1287,6 +1323,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                SocketTuning::default(),
             );
             let (stream_killer_tx, stream_killer_rx) = unbounded();
             subject.stream_killer_rx = stream_killer_rx;
@@ -1389,6 +1426,7 @@ mod tests {
                 peer_actors.proxy_client_opt.clone().unwrap(),
                 100,
                 200,
+                SocketTuning::default(),
             );
             let (stream_killer_tx, stream_killer_rx) = unbounded();
             subject.stream_killer_rx = stream_killer_rx;
@@ -1418,7 +1456,7 @@ mod tests {
         let proxy_client_recording = proxy_client_recording_arc.lock().unwrap();
         assert_eq!(
             proxy_client_recording.get_record::<DnsResolveFailure_0v1>(0),
-            &DnsResolveFailure_0v1 { stream_key }
+            &DnsResolveFailure_0v1::new_with_kind(stream_key, ExitFailureKind::ConnectionFailed)
         );
         assert_eq!(
             proxy_client_recording.get_record::<InboundServerData>(1),
@@ -1508,6 +1546,7 @@ mod tests {
                 peer_actors.proxy_client_opt.clone().unwrap(),
                 100,
                 200,
+                SocketTuning::default(),
             );
             let (stream_killer_tx, stream_killer_rx) = unbounded();
             subject.stream_killer_rx = stream_killer_rx;
@@ -1540,7 +1579,7 @@ mod tests {
         let proxy_client_recording = proxy_client_recording_arc.lock().unwrap();
         assert_eq!(
             proxy_client_recording.get_record::<DnsResolveFailure_0v1>(0),
-            &DnsResolveFailure_0v1 { stream_key }
+            &DnsResolveFailure_0v1::new(stream_key)
         );
         assert_eq!(
             proxy_client_recording.get_record::<InboundServerData>(1),
@@ -1618,6 +1657,7 @@ mod tests {
                 peer_actors.proxy_client_opt.clone().unwrap(),
                 100,
                 200,
+                SocketTuning::default(),
             );
 
             let peer_addr = SocketAddr::from_str("3.4.5.6:80").unwrap();
@@ -1715,6 +1755,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                SocketTuning::default(),
             );
             subject.inner.lock().unwrap().logger =
                 Logger::new("bad_dns_lookup_produces_log_and_sends_error_response");
@@ -1784,6 +1825,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                SocketTuning::default(),
             );
             subject.inner.lock().unwrap().stream_writer_channels.insert(
                 stream_key,
@@ -1844,6 +1886,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                SocketTuning::default(),
             );
 
             subject.inner.lock().unwrap().establisher_factory =
@@ -1880,6 +1923,7 @@ mod tests {
             peer_actors.proxy_client_opt.unwrap(),
             0,
             0,
+            SocketTuning::default(),
         );
         let (stream_killer_tx, stream_killer_rx) = unbounded();
         subject.stream_killer_rx = stream_killer_rx;
@@ -1931,6 +1975,7 @@ mod tests {
             peer_actors.proxy_client_opt.unwrap(),
             0,
             0,
+            SocketTuning::default(),
         );
         let (stream_killer_tx, stream_killer_rx) = unbounded();
         subject.stream_killer_rx = stream_killer_rx;
@@ -1973,6 +2018,7 @@ mod tests {
             peer_actors.proxy_client_opt.unwrap(),
             0,
             0,
+            SocketTuning::default(),
         );
         let (stream_killer_tx, stream_killer_rx) = unbounded();
         subject.stream_killer_rx = stream_killer_rx;
@@ -2000,6 +2046,7 @@ mod tests {
             peer_actors.proxy_client_opt.unwrap(),
             0,
             0,
+            SocketTuning::default(),
         );
         subject.stream_adder_rx = stream_adder_rx;
         {
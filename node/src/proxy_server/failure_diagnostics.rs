@@ -0,0 +1,191 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::accountant::db_access_objects::utils::to_time_t;
+use crate::sub_lib::neighborhood::ExpectedService;
+use crate::sub_lib::stream_key::StreamKey;
+use masq_lib::messages::{UiRequestFailure, UiRouteHop};
+use std::collections::VecDeque;
+use std::time::SystemTime;
+
+// The oldest failure is forgotten to make room for a new one beyond this count
+pub const RECENT_FAILURES_CAPACITY: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FailureStage {
+    // The Neighborhood couldn't supply a route, or the request couldn't be sent down it
+    RouteQuery,
+    // The exit Node couldn't reach the server
+    Exit,
+}
+
+impl FailureStage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FailureStage::RouteQuery => "routeQuery",
+            FailureStage::Exit => "exit",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestFailure {
+    pub failed_at: SystemTime,
+    pub stream_key: StreamKey,
+    pub hostname_opt: Option<String>,
+    pub route: Vec<ExpectedService>,
+    pub stage: FailureStage,
+    pub error_class: String,
+    pub retry_count: usize,
+}
+
+pub struct RecentFailures {
+    capacity: usize,
+    failures: VecDeque<RequestFailure>,
+}
+
+impl Default for RecentFailures {
+    fn default() -> Self {
+        Self::new(RECENT_FAILURES_CAPACITY)
+    }
+}
+
+impl RecentFailures {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            failures: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn record(&mut self, failure: RequestFailure) {
+        if self.failures.len() >= self.capacity {
+            self.failures.pop_front();
+        }
+        self.failures.push_back(failure)
+    }
+
+    // Oldest first
+    pub fn records(&self) -> Vec<UiRequestFailure> {
+        self.failures
+            .iter()
+            .map(|failure| UiRequestFailure {
+                failed_at: to_time_t(failure.failed_at),
+                stream_key: failure.stream_key.to_string(),
+                hostname_opt: failure.hostname_opt.clone(),
+                route: failure
+                    .route
+                    .iter()
+                    .filter_map(|service| match service {
+                        ExpectedService::Routing(public_key, _, _) => Some(UiRouteHop {
+                            role: "routing".to_string(),
+                            public_key: public_key.to_string(),
+                        }),
+                        ExpectedService::Exit(public_key, _, _) => Some(UiRouteHop {
+                            role: "exit".to_string(),
+                            public_key: public_key.to_string(),
+                        }),
+                        ExpectedService::Nothing => None,
+                    })
+                    .collect(),
+                stage: failure.stage.name().to_string(),
+                error_class: failure.error_class.clone(),
+                retry_count: failure.retry_count as u32,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sub_lib::cryptde::PublicKey;
+    use crate::sub_lib::neighborhood::DEFAULT_RATE_PACK;
+    use crate::test_utils::make_wallet;
+    use std::time::Duration;
+
+    fn make_failure(hostname: &str, stage: FailureStage) -> RequestFailure {
+        RequestFailure {
+            failed_at: SystemTime::UNIX_EPOCH + Duration::from_secs(1_000),
+            stream_key: StreamKey::make_meaningful_stream_key(hostname),
+            hostname_opt: Some(hostname.to_string()),
+            route: vec![],
+            stage,
+            error_class: "NoRoute".to_string(),
+            retry_count: 0,
+        }
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(RECENT_FAILURES_CAPACITY, 100);
+    }
+
+    #[test]
+    fn recent_failures_describe_the_route_and_the_failure() {
+        let routing_key = PublicKey::new(&[1, 2, 3, 4]);
+        let exit_key = PublicKey::new(&[2, 3, 4, 5]);
+        let stream_key = StreamKey::make_meaningful_stream_key("booga");
+        let mut subject = RecentFailures::default();
+
+        subject.record(RequestFailure {
+            failed_at: SystemTime::UNIX_EPOCH + Duration::from_secs(1_000),
+            stream_key,
+            hostname_opt: Some("booga.com".to_string()),
+            route: vec![
+                ExpectedService::Nothing,
+                ExpectedService::Routing(
+                    routing_key.clone(),
+                    make_wallet("routing"),
+                    DEFAULT_RATE_PACK,
+                ),
+                ExpectedService::Exit(exit_key.clone(), make_wallet("exit"), DEFAULT_RATE_PACK),
+            ],
+            stage: FailureStage::Exit,
+            error_class: "ConnectionRefused".to_string(),
+            retry_count: 2,
+        });
+
+        assert_eq!(
+            subject.records(),
+            vec![UiRequestFailure {
+                failed_at: 1_000,
+                stream_key: stream_key.to_string(),
+                hostname_opt: Some("booga.com".to_string()),
+                route: vec![
+                    UiRouteHop {
+                        role: "routing".to_string(),
+                        public_key: routing_key.to_string(),
+                    },
+                    UiRouteHop {
+                        role: "exit".to_string(),
+                        public_key: exit_key.to_string(),
+                    },
+                ],
+                stage: "exit".to_string(),
+                error_class: "ConnectionRefused".to_string(),
+                retry_count: 2,
+            }]
+        );
+    }
+
+    #[test]
+    fn recent_failures_forget_the_oldest_failures_beyond_their_capacity() {
+        let mut subject = RecentFailures::new(2);
+
+        subject.record(make_failure("one.com", FailureStage::RouteQuery));
+        subject.record(make_failure("two.com", FailureStage::Exit));
+        subject.record(make_failure("three.com", FailureStage::RouteQuery));
+
+        assert_eq!(
+            subject
+                .records()
+                .into_iter()
+                .map(|record| (record.hostname_opt.unwrap(), record.stage))
+                .collect::<Vec<(String, String)>>(),
+            vec![
+                ("two.com".to_string(), "exit".to_string()),
+                ("three.com".to_string(), "routeQuery".to_string()),
+            ]
+        );
+    }
+}
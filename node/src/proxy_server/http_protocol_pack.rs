@@ -78,17 +78,24 @@ impl HttpProtocolPack {
     }
 
     pub fn is_connect(data: &[u8]) -> bool {
+        matches!(Self::method(data), Ok(http::Method::CONNECT))
+    }
+
+    pub fn is_idempotent(data: &[u8]) -> bool {
+        match Self::method(data) {
+            Ok(method) => method.is_idempotent(),
+            Err(_) => false,
+        }
+    }
+
+    fn method(data: &[u8]) -> Result<http::Method, http::method::InvalidMethod> {
         let method_bytes: Vec<u8> = data
             .iter()
             .take(8)
             .take_while(|c| c != &&b' ')
             .cloned()
             .collect();
-
-        matches!(
-            http::Method::from_bytes(method_bytes.as_slice()),
-            Ok(http::Method::CONNECT)
-        )
+        http::Method::from_bytes(method_bytes.as_slice())
     }
 
     fn port_from_string(port_str: String) -> Option<u16> {
@@ -352,4 +359,33 @@ mod tests {
         let data = b"CONNECTX";
         assert!(!HttpProtocolPack::is_connect(data));
     }
+
+    #[test]
+    fn is_idempotent_true_for_idempotent_methods() {
+        vec!["GET", "HEAD", "OPTIONS", "TRACE", "PUT", "DELETE"]
+            .into_iter()
+            .for_each(|method| {
+                let data = format!("{} /index.html HTTP/1.1\r\nHost: booga.com\r\n\r\n", method);
+                assert!(
+                    HttpProtocolPack::is_idempotent(data.as_bytes()),
+                    "{}",
+                    method
+                );
+            });
+    }
+
+    #[test]
+    fn is_idempotent_false_for_other_methods_and_garbage() {
+        vec!["POST", "PATCH", "CONNECT", "GETX"]
+            .into_iter()
+            .for_each(|method| {
+                let data = format!("{} /index.html HTTP/1.1\r\nHost: booga.com\r\n\r\n", method);
+                assert!(
+                    !HttpProtocolPack::is_idempotent(data.as_bytes()),
+                    "{}",
+                    method
+                );
+            });
+        assert!(!HttpProtocolPack::is_idempotent(b""));
+    }
 }
@@ -1,22 +1,33 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 pub mod client_request_payload_factory;
+pub mod failure_diagnostics;
 pub mod http_protocol_pack;
 pub mod protocol_pack;
+pub mod route_pool;
 pub mod server_impersonator_http;
 pub mod server_impersonator_tls;
+pub mod stream_key_sweep;
 pub mod tls_protocol_pack;
 
 use crate::proxy_server::client_request_payload_factory::{
     ClientRequestPayloadFactory, ClientRequestPayloadFactoryReal,
 };
+use crate::proxy_server::failure_diagnostics::{FailureStage, RecentFailures, RequestFailure};
 use crate::proxy_server::http_protocol_pack::HttpProtocolPack;
 use crate::proxy_server::protocol_pack::{from_ibcd, from_protocol, ProtocolPack};
+use crate::proxy_server::route_pool::{
+    AddPooledRouteMessage, RefillRoutePool, RoutePool, RoutePoolConfig,
+};
+use crate::proxy_server::stream_key_sweep::{
+    StreamKeySweepStats, SweepStreamKeys, STREAM_KEY_IDLE_LIMIT, STREAM_KEY_SWEEP_INTERVAL,
+};
 use crate::proxy_server::ExitServiceSearch::{Definite, ZeroHop};
 use crate::stream_messages::NonClandestineAttributes;
 use crate::stream_messages::RemovedStreamType;
 use crate::sub_lib::accountant::RoutingServiceConsumed;
 use crate::sub_lib::accountant::{ExitServiceConsumed, ReportServicesConsumedMessage};
+use crate::sub_lib::accountant::{PaymentOverdue_0v1, ReportPaymentOverdueMessage};
 use crate::sub_lib::bidi_hashmap::BidiHashMap;
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::cryptde::PublicKey;
@@ -28,7 +39,9 @@ use crate::sub_lib::neighborhood::{ExpectedService, UpdateNodeRecordMetadataMess
 use crate::sub_lib::neighborhood::{ExpectedServices, RatePack};
 use crate::sub_lib::neighborhood::{NRMetadataChange, RouteQueryMessage};
 use crate::sub_lib::peer_actors::BindMessage;
-use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, DnsResolveFailure_0v1};
+use crate::sub_lib::proxy_client::{
+    ClientResponsePayload_0v1, DnsResolveFailure_0v1, ExitFailureKind,
+};
 use crate::sub_lib::proxy_server::ProxyServerSubs;
 use crate::sub_lib::proxy_server::{AddReturnRouteMessage, StreamKeyPurge};
 use crate::sub_lib::proxy_server::{
@@ -46,10 +59,13 @@ use actix::Recipient;
 use actix::{Actor, MailboxError};
 use actix::{Addr, AsyncContext};
 use masq_lib::logger::Logger;
-use masq_lib::ui_gateway::NodeFromUiMessage;
+use masq_lib::messages::{
+    FromMessageBody, ToMessageBody, UiRecentFailuresRequest, UiRecentFailuresResponse,
+};
+use masq_lib::ui_gateway::{MessageTarget, NodeFromUiMessage, NodeToUiMessage};
 use masq_lib::utils::MutabilityConflictHelper;
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
 use std::rc::Rc;
 use std::time::{Duration, SystemTime};
@@ -59,6 +75,8 @@ pub const CRASH_KEY: &str = "PROXYSERVER";
 pub const RETURN_ROUTE_TTL: Duration = Duration::from_secs(120);
 
 pub const STREAM_KEY_PURGE_DELAY: Duration = Duration::from_secs(30);
+// How many more routes a decentralized Node tries when an exit can't reach the server
+pub const DNS_FAILURE_RETRIES: usize = 3;
 
 struct ProxyServerOutSubs {
     dispatcher: Recipient<TransmitDataMsg>,
@@ -70,6 +88,8 @@ struct ProxyServerOutSubs {
     stream_shutdown_sub: Recipient<StreamShutdownMsg>,
     route_result_sub: Recipient<AddRouteResultMessage>,
     schedule_stream_key_purge: Recipient<MessageScheduler<StreamKeyPurge>>,
+    node_to_ui: Recipient<NodeToUiMessage>,
+    payment_overdue: Recipient<ReportPaymentOverdueMessage>,
 }
 
 pub struct ProxyServer {
@@ -81,6 +101,7 @@ pub struct ProxyServer {
     dns_failure_retries: HashMap<StreamKey, DNSFailureRetry>,
     stream_key_routes: HashMap<StreamKey, RouteQueryResponse>,
     stream_key_ttl: HashMap<StreamKey, SystemTime>,
+    stream_key_activity: HashMap<StreamKey, SystemTime>,
     is_decentralized: bool,
     consuming_wallet_balance: Option<i64>,
     main_cryptde: &'static dyn CryptDE,
@@ -91,6 +112,10 @@ pub struct ProxyServer {
     browser_proxy_sequence_offset: bool,
     inbound_client_data_helper_opt: Option<Box<dyn IBCDHelper>>,
     stream_key_purge_delay: Duration,
+    stream_key_idle_limit: Duration,
+    stream_key_sweep_stats: StreamKeySweepStats,
+    route_pool: RoutePool,
+    recent_failures: RecentFailures,
 }
 
 impl Actor for ProxyServer {
@@ -112,15 +137,21 @@ impl Handler<BindMessage> for ProxyServer {
             stream_shutdown_sub: msg.peer_actors.proxy_server.stream_shutdown_sub,
             route_result_sub: msg.peer_actors.proxy_server.route_result_sub,
             schedule_stream_key_purge: msg.peer_actors.proxy_server.schedule_stream_key_purge,
+            node_to_ui: msg.peer_actors.ui_gateway.node_to_ui_message_sub,
+            payment_overdue: msg.peer_actors.accountant.report_payment_overdue,
         };
         self.subs = Some(subs);
+        if self.route_pool_is_useful() {
+            ctx.notify(RefillRoutePool {});
+        }
+        ctx.notify_later(SweepStreamKeys {}, STREAM_KEY_SWEEP_INTERVAL);
     }
 }
 
 impl Handler<InboundClientData> for ProxyServer {
     type Result = ();
 
-    fn handle(&mut self, msg: InboundClientData, _ctx: &mut Self::Context) -> Self::Result {
+    fn handle(&mut self, msg: InboundClientData, ctx: &mut Self::Context) -> Self::Result {
         if msg.is_connect() {
             self.tls_connect(&msg);
             self.browser_proxy_sequence_offset = true;
@@ -129,6 +160,48 @@ impl Handler<InboundClientData> for ProxyServer {
         {
             error!(self.logger, "{}", e)
         }
+        self.refill_route_pool(ctx);
+    }
+}
+
+impl Handler<RefillRoutePool> for ProxyServer {
+    type Result = ();
+
+    fn handle(&mut self, _msg: RefillRoutePool, ctx: &mut Self::Context) -> Self::Result {
+        self.refill_route_pool(ctx);
+        debug!(
+            self.logger,
+            "Route pool holds {} of {} routes; {}",
+            self.route_pool.len(),
+            self.route_pool.config().size,
+            self.route_pool.stats()
+        );
+        ctx.notify_later(RefillRoutePool {}, self.route_pool.config().route_lifetime);
+    }
+}
+
+impl Handler<SweepStreamKeys> for ProxyServer {
+    type Result = ();
+
+    fn handle(&mut self, _msg: SweepStreamKeys, ctx: &mut Self::Context) -> Self::Result {
+        self.sweep_stream_keys(SystemTime::now());
+        ctx.notify_later(SweepStreamKeys {}, STREAM_KEY_SWEEP_INTERVAL);
+    }
+}
+
+impl Handler<AddPooledRouteMessage> for ProxyServer {
+    type Result = ();
+
+    fn handle(&mut self, msg: AddPooledRouteMessage, _ctx: &mut Self::Context) -> Self::Result {
+        match msg.route_opt {
+            Some(route_query_response) => {
+                self.route_pool.add(route_query_response, SystemTime::now())
+            }
+            None => {
+                debug!(self.logger, "Neighborhood has no route for the route pool");
+                self.route_pool.request_failed()
+            }
+        }
     }
 }
 
@@ -181,6 +254,16 @@ impl Handler<AddRouteResultMessage> for ProxyServer {
             }
             Err(e) => {
                 warning!(self.logger, "No route found for hostname: {:?} - stream key {} - retries left: {} - AddRouteResultMessage Error: {}",dns_failure.unsuccessful_request.target_hostname, msg.stream_key, dns_failure.retries_left, e);
+                let failure = RequestFailure {
+                    failed_at: SystemTime::now(),
+                    stream_key: msg.stream_key,
+                    hostname_opt: dns_failure.unsuccessful_request.target_hostname.clone(),
+                    route: vec![],
+                    stage: FailureStage::RouteQuery,
+                    error_class: "NoRoute".to_string(),
+                    retry_count: self.retries_used(dns_failure.retries_left),
+                };
+                self.recent_failures.record(failure);
             }
         }
     }
@@ -198,6 +281,18 @@ impl Handler<ExpiredCoresPackage<DnsResolveFailure_0v1>> for ProxyServer {
     }
 }
 
+impl Handler<ExpiredCoresPackage<PaymentOverdue_0v1>> for ProxyServer {
+    type Result = ();
+
+    fn handle(
+        &mut self,
+        msg: ExpiredCoresPackage<PaymentOverdue_0v1>,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        self.handle_payment_overdue(&msg)
+    }
+}
+
 impl Handler<ExpiredCoresPackage<ClientResponsePayload_0v1>> for ProxyServer {
     type Result = ();
 
@@ -222,7 +317,11 @@ impl Handler<NodeFromUiMessage> for ProxyServer {
     type Result = ();
 
     fn handle(&mut self, msg: NodeFromUiMessage, _ctx: &mut Self::Context) -> Self::Result {
-        handle_ui_crash_request(msg, &self.logger, self.crashable, CRASH_KEY)
+        if let Ok((_, context_id)) = UiRecentFailuresRequest::fmb(msg.body.clone()) {
+            self.handle_recent_failures_request(msg.client_id, context_id)
+        } else {
+            handle_ui_crash_request(msg, &self.logger, self.crashable, CRASH_KEY)
+        }
     }
 }
 
@@ -262,6 +361,7 @@ impl ProxyServer {
             dns_failure_retries: HashMap::new(),
             stream_key_routes: HashMap::new(),
             stream_key_ttl: HashMap::new(),
+            stream_key_activity: HashMap::new(),
             is_decentralized,
             consuming_wallet_balance,
             main_cryptde,
@@ -272,9 +372,18 @@ impl ProxyServer {
             browser_proxy_sequence_offset: false,
             inbound_client_data_helper_opt: Some(Box::new(IBCDHelperReal::new())),
             stream_key_purge_delay: STREAM_KEY_PURGE_DELAY,
+            stream_key_idle_limit: STREAM_KEY_IDLE_LIMIT,
+            stream_key_sweep_stats: StreamKeySweepStats::default(),
+            route_pool: RoutePool::new(RoutePoolConfig::disabled()),
+            recent_failures: RecentFailures::default(),
         }
     }
 
+    pub fn with_route_pool(mut self, config: RoutePoolConfig) -> Self {
+        self.route_pool = RoutePool::new(config);
+        self
+    }
+
     pub fn make_subs_from(addr: &Addr<ProxyServer>) -> ProxyServerSubs {
         ProxyServerSubs {
             bind: recipient!(addr, BindMessage),
@@ -286,7 +395,49 @@ impl ProxyServer {
             node_from_ui: recipient!(addr, NodeFromUiMessage),
             route_result_sub: recipient!(addr, AddRouteResultMessage),
             schedule_stream_key_purge: recipient!(addr, MessageScheduler<StreamKeyPurge>),
+            payment_overdue_from_hopper: recipient!(addr, ExpiredCoresPackage<PaymentOverdue_0v1>),
+        }
+    }
+
+    fn route_pool_is_useful(&self) -> bool {
+        self.is_decentralized
+            && self.consuming_wallet_balance.is_some()
+            && self.route_pool.is_enabled()
+    }
+
+    fn take_pooled_route(&mut self) -> Option<RouteQueryResponse> {
+        if self.route_pool_is_useful() {
+            self.route_pool.take(SystemTime::now())
+        } else {
+            None
+        }
+    }
+
+    fn refill_route_pool(&mut self, ctx: &mut Context<Self>) {
+        if !self.route_pool_is_useful() {
+            return;
+        }
+        let wanted = self.route_pool.claim_refill(SystemTime::now());
+        if wanted == 0 {
+            return;
         }
+        let route_source = self.out_subs("Neighborhood").route_source.clone();
+        let pool_sub = ctx.address().recipient::<AddPooledRouteMessage>();
+        (0..wanted).for_each(|_| {
+            let pool_sub = pool_sub.clone();
+            tokio::spawn(
+                route_source
+                    .send(RouteQueryMessage::data_indefinite_route_request(None, 0))
+                    .then(move |route_result| {
+                        pool_sub
+                            .try_send(AddPooledRouteMessage {
+                                route_opt: route_result.unwrap_or(None),
+                            })
+                            .expect("ProxyServer is dead");
+                        Ok(())
+                    }),
+            );
+        });
     }
 
     fn remove_dns_failure_retry(
@@ -385,7 +536,7 @@ impl ProxyServer {
                         .expect("Neighborhood unbound in ProxyServer")
                         .update_node_record_metadata
                         .try_send(UpdateNodeRecordMetadataMessage {
-                            public_key: exit_public_key,
+                            public_key: exit_public_key.clone(),
                             metadata_change: NRMetadataChange::AddUnreachableHost {
                                 hostname: server_name,
                             },
@@ -409,12 +560,41 @@ impl ProxyServer {
                         return;
                     }
                 };
-                if retry.retries_left > 0 {
+                let failure_kind = response.failure_kind();
+                if retry.retries_left > 0
+                    && Self::is_safe_to_retry(failure_kind, &retry.unsuccessful_request)
+                {
+                    debug!(
+                        self.logger,
+                        "Exit {} reported {:?} for stream key {}; retrying over another route",
+                        exit_public_key,
+                        failure_kind,
+                        response.stream_key
+                    );
                     let mut returned_retry = self.retry_dns_resolution(retry, client_addr);
                     returned_retry.retries_left -= 1;
                     self.dns_failure_retries
                         .insert(response.stream_key, returned_retry);
                 } else {
+                    if retry.retries_left > 0 {
+                        debug!(
+                            self.logger,
+                            "Exit {} reported {:?} for stream key {}; request isn't idempotent, so not retrying",
+                            exit_public_key,
+                            failure_kind,
+                            response.stream_key
+                        );
+                    }
+                    let failure = RequestFailure {
+                        failed_at: SystemTime::now(),
+                        stream_key: response.stream_key,
+                        hostname_opt: hostname_opt.clone(),
+                        route: return_route_info.expected_services.clone(),
+                        stage: FailureStage::Exit,
+                        error_class: format!("{:?}", failure_kind),
+                        retry_count: self.retries_used(retry.retries_left),
+                    };
+                    self.recent_failures.record(failure);
                     self.retire_stream_key(&response.stream_key);
                     self.send_dns_failure_response_to_the_browser(
                         client_addr,
@@ -433,6 +613,19 @@ impl ProxyServer {
         }
     }
 
+    // A DNS failure means the exit never tried to reach the server, so anything can be retried.
+    // A failed connection might still have been half-made, so an HTTP request goes out again only
+    // if sending it twice can't hurt; a TLS stream at this point carries nothing but a handshake.
+    fn is_safe_to_retry(failure_kind: ExitFailureKind, request: &ClientRequestPayload_0v1) -> bool {
+        match (failure_kind, &request.protocol) {
+            (ExitFailureKind::DnsResolution, _) => true,
+            (_, ProxyProtocol::TLS) => true,
+            (_, ProxyProtocol::HTTP) => {
+                HttpProtocolPack::is_idempotent(&request.sequenced_packet.data)
+            }
+        }
+    }
+
     fn schedule_stream_key_purge(&mut self, stream_key: StreamKey) {
         let host_info = match self.tunneled_hosts.get(&stream_key) {
             None => String::from(""),
@@ -503,6 +696,9 @@ impl ProxyServer {
             payload_data_len,
         );
         let stream_key = response.stream_key;
+        if let Some(last_activity) = self.stream_key_activity.get_mut(&stream_key) {
+            *last_activity = SystemTime::now();
+        }
         match self.remove_dns_failure_retry(&stream_key) {
             Ok(_) => {
                 debug!(self.logger, "Successful attempt of DNS resolution, removing DNS retry entry for stream key: {}", &response.stream_key)
@@ -591,6 +787,67 @@ impl ProxyServer {
         }
     }
 
+    fn retries_allowed(&self) -> usize {
+        if self.is_decentralized {
+            DNS_FAILURE_RETRIES
+        } else {
+            0
+        }
+    }
+
+    fn retries_used(&self, retries_left: usize) -> usize {
+        self.retries_allowed().saturating_sub(retries_left)
+    }
+
+    fn handle_recent_failures_request(&self, client_id: u64, context_id: u64) {
+        let response = UiRecentFailuresResponse {
+            failures: self.recent_failures.records(),
+        };
+        self.out_subs("UiGateway")
+            .node_to_ui
+            .try_send(NodeToUiMessage {
+                target: MessageTarget::ClientId(client_id),
+                body: response.tmb(context_id),
+            })
+            .expect("UiGateway is dead");
+    }
+
+    // The notice can only have come from the exit Node of the route it came back over, so that
+    // Node's wallet is the creditor's, and the Accountant decides whether it's worth a word
+    fn handle_payment_overdue(&self, msg: &ExpiredCoresPackage<PaymentOverdue_0v1>) {
+        let return_route_info =
+            match self.get_return_route_info(&msg.remaining_route, "payment overdue notice") {
+                Some(rri) => rri,
+                None => return,
+            };
+        let creditor_wallet = match return_route_info
+            .expected_services
+            .iter()
+            .find_map(|service| match service {
+                ExpectedService::Exit(_, wallet, _) => Some(wallet.clone()),
+                _ => None,
+            }) {
+            Some(wallet) => wallet,
+            None => {
+                warning!(
+                    self.logger,
+                    "Ignoring payment overdue notice that came back over route {}, which has no exit Node",
+                    return_route_info.return_route_id
+                );
+                return;
+            }
+        };
+        self.report_response_services_consumed(&return_route_info, 0, msg.payload_len);
+        self.out_subs("Accountant")
+            .payment_overdue
+            .try_send(ReportPaymentOverdueMessage {
+                timestamp: SystemTime::now(),
+                creditor_wallet,
+                notice: msg.payload.clone(),
+            })
+            .expect("Accountant is dead");
+    }
+
     fn out_subs(&self, actor_name: &str) -> &ProxyServerOutSubs {
         self.subs
             .as_ref()
@@ -639,7 +896,7 @@ impl ProxyServer {
     }
 
     fn find_or_generate_stream_key(&mut self, ibcd: &InboundClientData) -> StreamKey {
-        match self.keys_and_addrs.b_to_a(&ibcd.peer_addr) {
+        let stream_key = match self.keys_and_addrs.b_to_a(&ibcd.peer_addr) {
             Some(stream_key) => {
                 debug!(
                     self.logger,
@@ -658,7 +915,10 @@ impl ProxyServer {
                 );
                 stream_key
             }
-        }
+        };
+        self.stream_key_activity
+            .insert(stream_key, SystemTime::now());
+        stream_key
     }
 
     fn purge_stream_key(&mut self, stream_key: &StreamKey, reason: &str) {
@@ -672,6 +932,78 @@ impl ProxyServer {
         let _ = self.stream_key_ttl.remove(stream_key);
     }
 
+    // The activity record outlives purge_stream_key() on purpose: a DNS retry may still be waiting
+    // for its route, so whatever's left for a purged stream key is only dropped once it's gone idle
+    fn sweep_stream_keys(&mut self, now: SystemTime) {
+        let orphaned_keys = self
+            .stream_key_activity
+            .keys()
+            .chain(self.stream_key_routes.keys())
+            .chain(self.tunneled_hosts.keys())
+            .chain(self.dns_failure_retries.keys())
+            .chain(self.stream_key_ttl.keys())
+            .copied()
+            .filter(|stream_key| self.stream_key_is_orphaned(stream_key, now))
+            .collect::<HashSet<StreamKey>>();
+        let mut stream_keys_reclaimed = 0;
+        let mut entries_reclaimed = 0;
+        for stream_key in orphaned_keys {
+            if self.keys_and_addrs.remove_a(&stream_key).is_some() {
+                stream_keys_reclaimed += 1;
+            }
+            entries_reclaimed += [
+                self.stream_key_routes.remove(&stream_key).is_some(),
+                self.tunneled_hosts.remove(&stream_key).is_some(),
+                self.dns_failure_retries.remove(&stream_key).is_some(),
+                self.stream_key_ttl.remove(&stream_key).is_some(),
+            ]
+            .iter()
+            .filter(|removed| **removed)
+            .count();
+            self.stream_key_activity.remove(&stream_key);
+        }
+        self.stream_key_sweep_stats
+            .record_sweep(stream_keys_reclaimed, entries_reclaimed);
+        if stream_keys_reclaimed + entries_reclaimed > 0 {
+            info!(
+                self.logger,
+                "Swept {} orphaned stream key(s) and {} leftover entries; {}",
+                stream_keys_reclaimed,
+                entries_reclaimed,
+                self.stream_key_sweep_stats
+            );
+        } else {
+            debug!(
+                self.logger,
+                "Stream key sweep found nothing to reclaim; {}", self.stream_key_sweep_stats
+            );
+        }
+    }
+
+    fn stream_key_is_orphaned(&self, stream_key: &StreamKey, now: SystemTime) -> bool {
+        match self.stream_key_activity.get(stream_key) {
+            Some(last_activity) => now
+                .duration_since(*last_activity)
+                .map(|idle| idle > self.stream_key_idle_limit)
+                .unwrap_or(false),
+            None => true,
+        }
+    }
+
+    // Whatever flows through a tunnel a CONNECT opened is TLS, whichever port it came in on
+    fn protocol_pack_for(&self, ibcd: &InboundClientData) -> Result<Box<dyn ProtocolPack>, String> {
+        let is_tunneled = self
+            .keys_and_addrs
+            .b_to_a(&ibcd.peer_addr)
+            .map(|stream_key| self.tunneled_hosts.contains_key(&stream_key))
+            .unwrap_or(false);
+        if is_tunneled {
+            Ok(from_protocol(ProxyProtocol::TLS))
+        } else {
+            from_ibcd(ibcd)
+        }
+    }
+
     fn make_payload(
         &mut self,
         ibcd: InboundClientData,
@@ -1107,7 +1439,7 @@ impl IBCDHelper for IBCDHelperReal {
     ) -> Result<(), String> {
         let source_addr = msg.peer_addr;
         if proxy.consuming_wallet_balance.is_none() && proxy.is_decentralized {
-            let protocol_pack = match from_ibcd(&msg) {
+            let protocol_pack = match proxy.protocol_pack_for(&msg) {
                 Err(e) => return Err(e),
                 Ok(pp) => pp,
             };
@@ -1137,7 +1469,7 @@ impl IBCDHelper for IBCDHelperReal {
         if proxy.dns_failure_retries.get(&stream_key).is_none() {
             let dns_failure_retry = DNSFailureRetry {
                 unsuccessful_request: payload.clone(),
-                retries_left: if proxy.is_decentralized { 3 } else { 0 },
+                retries_left: proxy.retries_allowed(),
             };
             proxy
                 .dns_failure_retries
@@ -1156,6 +1488,15 @@ impl IBCDHelper for IBCDHelperReal {
             );
             let route_query_response = route_query_response.clone();
             ProxyServer::try_transmit_to_hopper(tth_args, route_query_response)
+        } else if let Some(route_query_response) = proxy.take_pooled_route() {
+            debug!(
+                proxy.logger,
+                "Opening new stream with key {} over a pooled route", pld.stream_key
+            );
+            proxy
+                .stream_key_routes
+                .insert(pld.stream_key, route_query_response.clone());
+            ProxyServer::try_transmit_to_hopper(tth_args, route_query_response)
         } else {
             let route_source = proxy.out_subs("Neighborhood").route_source.clone();
             let proxy_server_sub = proxy.out_subs("ProxyServer").route_result_sub.clone();
@@ -1297,8 +1638,10 @@ impl Hostname {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::accountant::db_access_objects::utils::to_time_t;
     use crate::match_every_type_id;
     use crate::proxy_server::protocol_pack::ServerImpersonator;
+    use crate::proxy_server::route_pool::RoutePoolStats;
     use crate::proxy_server::server_impersonator_http::ServerImpersonatorHttp;
     use crate::proxy_server::server_impersonator_tls::ServerImpersonatorTls;
     use crate::stream_messages::{NonClandestineAttributes, RemovedStreamType};
@@ -1335,6 +1678,7 @@ mod tests {
     use actix::System;
     use crossbeam_channel::unbounded;
     use masq_lib::constants::{HTTP_PORT, TLS_PORT};
+    use masq_lib::messages::{UiRequestFailure, UiRouteHop};
     use masq_lib::test_utils::logging::init_test_logging;
     use masq_lib::test_utils::logging::TestLogHandler;
     use masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN;
@@ -1445,6 +1789,8 @@ mod tests {
             stream_shutdown_sub: recipient!(addr, StreamShutdownMsg),
             route_result_sub: recipient!(addr, AddRouteResultMessage),
             schedule_stream_key_purge: recipient!(addr, MessageScheduler<StreamKeyPurge>),
+            node_to_ui: recipient!(addr, NodeToUiMessage),
+            payment_overdue: recipient!(addr, ReportPaymentOverdueMessage),
         }
     }
 
@@ -1657,6 +2003,168 @@ mod tests {
         );
     }
 
+    #[test]
+    fn proxy_server_opens_new_stream_over_pooled_route_and_replaces_it() {
+        let main_cryptde = main_cryptde();
+        let alias_cryptde = alias_cryptde();
+        let http_request = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n";
+        let (hopper_mock, hopper_awaiter, hopper_log_arc) = make_recorder();
+        let (neighborhood_mock, neighborhood_awaiter, neighborhood_recording_arc) = make_recorder();
+        let destination_key = PublicKey::from(&b"our destination"[..]);
+        let pooled_route = RouteQueryResponse {
+            route: Route { hops: vec![] },
+            expected_services: ExpectedServices::RoundTrip(
+                vec![make_exit_service_from_key(destination_key.clone())],
+                vec![],
+                1234,
+            ),
+        };
+        let neighborhood_mock = neighborhood_mock.route_query_response(Some(pooled_route.clone()));
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let msg_from_dispatcher = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: socket_addr.clone(),
+            reception_port: Some(HTTP_PORT),
+            sequence_number: Some(0),
+            last_data: true,
+            is_clandestine: false,
+            data: http_request.to_vec(),
+        };
+        let expected_payload = ClientRequestPayload_0v1 {
+            stream_key: stream_key.clone(),
+            sequenced_packet: SequencedPacket {
+                data: http_request.to_vec(),
+                sequence_number: 0,
+                last_data: true,
+            },
+            target_hostname: Some(String::from("nowhere.com")),
+            target_port: HTTP_PORT,
+            protocol: ProxyProtocol::HTTP,
+            originator_public_key: alias_cryptde.public_key().clone(),
+        };
+        let expected_pkg = IncipientCoresPackage::new(
+            main_cryptde,
+            Route { hops: vec![] },
+            expected_payload.into(),
+            &destination_key,
+        )
+        .unwrap();
+        thread::spawn(move || {
+            let system =
+                System::new("proxy_server_opens_new_stream_over_pooled_route_and_replaces_it");
+            let mut subject = ProxyServer::new(
+                main_cryptde,
+                alias_cryptde,
+                true,
+                Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                false,
+            )
+            .with_route_pool(RoutePoolConfig {
+                size: 1,
+                route_lifetime: Duration::from_secs(60),
+            });
+            subject.route_pool.add(pooled_route, SystemTime::now());
+            subject.stream_key_factory =
+                Box::new(StreamKeyFactoryMock::new().make_result(stream_key));
+            let subject_addr: Addr<ProxyServer> = subject.start();
+            let mut peer_actors = peer_actors_builder()
+                .hopper(hopper_mock)
+                .neighborhood(neighborhood_mock)
+                .build();
+            peer_actors.proxy_server = ProxyServer::make_subs_from(&subject_addr);
+            subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+            subject_addr.try_send(msg_from_dispatcher).unwrap();
+
+            system.run();
+        });
+
+        hopper_awaiter.await_message_count(1);
+        let recording = hopper_log_arc.lock().unwrap();
+        assert_eq!(
+            recording.get_record::<IncipientCoresPackage>(0),
+            &expected_pkg
+        );
+        neighborhood_awaiter.await_message_count(1);
+        let recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(
+            recording.get_record::<RouteQueryMessage>(0),
+            &RouteQueryMessage::data_indefinite_route_request(None, 0)
+        );
+        assert_eq!(recording.len(), 1);
+    }
+
+    #[test]
+    fn proxy_server_fills_route_pool_when_bound() {
+        let (neighborhood_mock, neighborhood_awaiter, neighborhood_recording_arc) = make_recorder();
+        let neighborhood_mock = neighborhood_mock.route_query_response(None);
+        thread::spawn(move || {
+            let system = System::new("proxy_server_fills_route_pool_when_bound");
+            let subject = ProxyServer::new(
+                main_cryptde(),
+                alias_cryptde(),
+                true,
+                Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                false,
+            )
+            .with_route_pool(RoutePoolConfig {
+                size: 2,
+                route_lifetime: Duration::from_secs(60),
+            });
+            let subject_addr: Addr<ProxyServer> = subject.start();
+            let peer_actors = peer_actors_builder()
+                .neighborhood(neighborhood_mock)
+                .build();
+
+            subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+            system.run();
+        });
+
+        neighborhood_awaiter.await_message_count(2);
+        let recording = neighborhood_recording_arc.lock().unwrap();
+        (0..2).for_each(|idx| {
+            assert_eq!(
+                recording.get_record::<RouteQueryMessage>(idx),
+                &RouteQueryMessage::data_indefinite_route_request(None, 0)
+            )
+        });
+    }
+
+    #[test]
+    fn route_pool_stays_idle_in_zero_hop_mode() {
+        let system = System::new("route_pool_stays_idle_in_zero_hop_mode");
+        let subject = ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            false,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            false,
+        )
+        .with_route_pool(RoutePoolConfig::default());
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let (neighborhood_mock, _, neighborhood_recording_arc) = make_recorder();
+        let peer_actors = peer_actors_builder()
+            .neighborhood(neighborhood_mock)
+            .build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(AssertionsMessage {
+                assertions: Box::new(|proxy_server: &mut ProxyServer| {
+                    assert_eq!(proxy_server.take_pooled_route(), None);
+                    assert_eq!(proxy_server.route_pool.stats(), RoutePoolStats::default());
+                }),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(recording.len(), 0);
+    }
+
     #[test]
     fn proxy_server_receives_connect_responds_with_ok_and_stores_stream_key_and_hostname() {
         let main_cryptde = main_cryptde();
@@ -2110,6 +2618,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn proxy_server_sends_impersonated_tls_response_through_connect_proxy_tunnel_with_no_consuming_wallet(
+    ) {
+        let cryptde = main_cryptde();
+        let (hopper, _, hopper_log_arc) = make_recorder();
+        let (dispatcher, _, dispatcher_log_arc) = make_recorder();
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let connect_msg = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: socket_addr.clone(),
+            reception_port: Some(8443),
+            sequence_number: Some(0),
+            last_data: false,
+            is_clandestine: false,
+            data: b"CONNECT realdomain.nu:443 HTTP/1.1\r\nHost: realdomain.nu:443\r\n\r\n".to_vec(),
+        };
+        let tunneled_msg = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: socket_addr.clone(),
+            reception_port: Some(8443),
+            sequence_number: Some(0),
+            last_data: false,
+            is_clandestine: false,
+            data: b"client hello".to_vec(),
+        };
+        let system = System::new("proxy_server_sends_impersonated_tls_response_through_connect_proxy_tunnel_with_no_consuming_wallet");
+        let mut subject = ProxyServer::new(cryptde, alias_cryptde(), true, None, false);
+        subject.stream_key_factory = Box::new(StreamKeyFactoryMock::new().make_result(stream_key));
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder()
+            .dispatcher(dispatcher)
+            .hopper(hopper)
+            .build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr.try_send(connect_msg).unwrap();
+        subject_addr.try_send(tunneled_msg).unwrap();
+
+        System::current().stop();
+        system.run();
+        let hopper_recording = hopper_log_arc.lock().unwrap();
+        assert!(hopper_recording.is_empty());
+        let dispatcher_recording = dispatcher_log_arc.lock().unwrap();
+        assert_eq!(
+            dispatcher_recording.get_record::<TransmitDataMsg>(0).data,
+            b"HTTP/1.1 200 OK\r\n\r\n".to_vec()
+        );
+        assert_eq!(
+            dispatcher_recording.get_record::<TransmitDataMsg>(1),
+            &TransmitDataMsg {
+                endpoint: Endpoint::Socket(socket_addr),
+                last_data: true,
+                sequence_number: Some(0),
+                data: ServerImpersonatorTls {}.consuming_wallet_absent(),
+            }
+        );
+    }
+
     #[test]
     fn proxy_server_receives_http_request_with_no_consuming_wallet_in_zero_hop_mode_and_handles_normally(
     ) {
@@ -2955,17 +3522,81 @@ mod tests {
             false,
         );
         let subject_addr: Addr<ProxyServer> = subject.start();
-        let peer_actors = peer_actors_builder().build();
+        let peer_actors = peer_actors_builder().build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(AddRouteResultMessage {
+                stream_key: StreamKey::make_meaningless_stream_key(),
+                result: Err("Some Error".to_string()),
+            })
+            .unwrap();
+
+        system.run();
+    }
+
+    #[test]
+    fn route_result_failure_is_reported_among_recent_failures() {
+        let system = System::new("route_result_failure_is_reported_among_recent_failures");
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let cryptde = main_cryptde();
+        let mut subject = ProxyServer::new(
+            cryptde,
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            false,
+        );
+        let stream_key = StreamKey::make_meaningful_stream_key("route result failure");
+        subject.dns_failure_retries.insert(
+            stream_key,
+            DNSFailureRetry {
+                unsuccessful_request: make_request_payload(0, cryptde),
+                retries_left: 2,
+            },
+        );
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
         subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let before = to_time_t(SystemTime::now());
 
         subject_addr
             .try_send(AddRouteResultMessage {
-                stream_key: StreamKey::make_meaningless_stream_key(),
-                result: Err("Some Error".to_string()),
+                stream_key,
+                result: Err("Failed to find route to example.com".to_string()),
+            })
+            .unwrap();
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiRecentFailuresRequest {}.tmb(4321),
             })
             .unwrap();
 
+        System::current().stop();
         system.run();
+        let after = to_time_t(SystemTime::now());
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let message = ui_gateway_recording.get_record::<NodeToUiMessage>(0);
+        assert_eq!(message.target, MessageTarget::ClientId(1234));
+        let (mut response, context_id) =
+            UiRecentFailuresResponse::fmb(message.body.clone()).unwrap();
+        assert_eq!(context_id, 4321);
+        let failure = response.failures.remove(0);
+        assert!(before <= failure.failed_at && failure.failed_at <= after);
+        assert_eq!(
+            failure,
+            UiRequestFailure {
+                failed_at: failure.failed_at,
+                stream_key: stream_key.to_string(),
+                hostname_opt: Some("example.com".to_string()),
+                route: vec![],
+                stage: "routeQuery".to_string(),
+                error_class: "NoRoute".to_string(),
+                retry_count: 1,
+            }
+        );
+        assert_eq!(response.failures, vec![]);
     }
 
     #[test]
@@ -4453,6 +5084,215 @@ mod tests {
         );
     }
 
+    #[test]
+    fn handle_dns_resolve_failure_is_reported_among_recent_failures_once_retries_run_out() {
+        let system = System::new(
+            "handle_dns_resolve_failure_is_reported_among_recent_failures_once_retries_run_out",
+        );
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let cryptde = main_cryptde();
+        let mut subject = ProxyServer::new(
+            cryptde,
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            false,
+        );
+        let stream_key = StreamKey::make_meaningful_stream_key("dns resolve failure");
+        subject.dns_failure_retries.insert(
+            stream_key,
+            DNSFailureRetry {
+                unsuccessful_request: make_request_payload(0, cryptde),
+                retries_left: 0,
+            },
+        );
+        subject
+            .keys_and_addrs
+            .insert(stream_key, SocketAddr::from_str("1.2.3.4:5678").unwrap());
+        let routing_public_key = PublicKey::from(&b"routing_key"[..]);
+        let exit_public_key = PublicKey::from(&b"exit_key"[..]);
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        subject_addr
+            .try_send(AddReturnRouteMessage {
+                return_route_id: 1234,
+                expected_services: vec![
+                    ExpectedService::Nothing,
+                    ExpectedService::Routing(
+                        routing_public_key.clone(),
+                        make_wallet("routing wallet"),
+                        rate_pack(9),
+                    ),
+                    ExpectedService::Exit(
+                        exit_public_key.clone(),
+                        make_wallet("exit wallet"),
+                        rate_pack(10),
+                    ),
+                ],
+                protocol: ProxyProtocol::HTTP,
+                hostname_opt: Some("server.com".to_string()),
+            })
+            .unwrap();
+        let dns_resolve_failure = DnsResolveFailure_0v1::new(stream_key);
+        subject_addr
+            .try_send(ExpiredCoresPackage::new(
+                SocketAddr::from_str("1.2.3.4:1234").unwrap(),
+                Some(make_wallet("irrelevant")),
+                return_route_with_id(cryptde, 1234),
+                dns_resolve_failure.into(),
+                0,
+            ))
+            .unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiRecentFailuresRequest {}.tmb(4321),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let message = ui_gateway_recording.get_record::<NodeToUiMessage>(0);
+        let (response, _) = UiRecentFailuresResponse::fmb(message.body.clone()).unwrap();
+        let failure = &response.failures[0];
+        assert_eq!(
+            failure,
+            &UiRequestFailure {
+                failed_at: failure.failed_at,
+                stream_key: stream_key.to_string(),
+                hostname_opt: Some("server.com".to_string()),
+                route: vec![
+                    UiRouteHop {
+                        role: "routing".to_string(),
+                        public_key: routing_public_key.to_string(),
+                    },
+                    UiRouteHop {
+                        role: "exit".to_string(),
+                        public_key: exit_public_key.to_string(),
+                    },
+                ],
+                stage: "exit".to_string(),
+                error_class: "DnsResolution".to_string(),
+                retry_count: 3,
+            }
+        );
+        assert_eq!(response.failures.len(), 1);
+    }
+
+    #[test]
+    fn connection_failure_for_non_idempotent_request_is_reported_to_browser_without_retry() {
+        let system = System::new("test");
+        let (dispatcher_mock, _, dispatcher_log_arc) = make_recorder();
+        let cryptde = main_cryptde();
+        let mut subject = ProxyServer::new(
+            cryptde,
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            false,
+        );
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let mut client_payload = make_request_payload(0, cryptde);
+        client_payload.sequenced_packet.data =
+            b"POST /form HTTP/1.1\r\nHost: server.com\r\n\r\n".to_vec();
+        subject.dns_failure_retries.insert(
+            stream_key,
+            DNSFailureRetry {
+                unsuccessful_request: client_payload,
+                retries_left: 3,
+            },
+        );
+        subject
+            .keys_and_addrs
+            .insert(stream_key.clone(), socket_addr.clone());
+        let exit_public_key = PublicKey::from(&b"exit_key"[..]);
+        let exit_wallet = make_wallet("exit wallet");
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let dns_resolve_failure =
+            DnsResolveFailure_0v1::new_with_kind(stream_key, ExitFailureKind::ConnectionRefused);
+        let expired_cores_package: ExpiredCoresPackage<DnsResolveFailure_0v1> =
+            ExpiredCoresPackage::new(
+                SocketAddr::from_str("1.2.3.4:1234").unwrap(),
+                Some(make_wallet("irrelevant")),
+                return_route_with_id(cryptde, 1234),
+                dns_resolve_failure.into(),
+                0,
+            );
+        let peer_actors = peer_actors_builder().dispatcher(dispatcher_mock).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        subject_addr
+            .try_send(AddReturnRouteMessage {
+                return_route_id: 1234,
+                expected_services: vec![ExpectedService::Exit(
+                    exit_public_key.clone(),
+                    exit_wallet,
+                    rate_pack(10),
+                )],
+                protocol: ProxyProtocol::HTTP,
+                hostname_opt: Some("server.com".to_string()),
+            })
+            .unwrap();
+
+        subject_addr.try_send(expired_cores_package).unwrap();
+
+        subject_addr
+            .try_send(AssertionsMessage {
+                assertions: Box::new(move |proxy_server: &mut ProxyServer| {
+                    assert!(proxy_server.dns_failure_retries.get(&stream_key).is_none());
+                }),
+            })
+            .unwrap();
+        System::current().stop();
+        system.run();
+        let dispatcher_recording = dispatcher_log_arc.lock().unwrap();
+        let record = dispatcher_recording.get_record::<TransmitDataMsg>(0);
+        assert_eq!(
+            record.data,
+            ServerImpersonatorHttp {}
+                .dns_resolution_failure_response(Some("server.com".to_string()))
+        );
+    }
+
+    #[test]
+    fn only_requests_that_cannot_have_reached_the_server_twice_are_retried() {
+        let cryptde = main_cryptde();
+        let make_http_request = |method: &str| {
+            let mut payload = make_request_payload(0, cryptde);
+            payload.sequenced_packet.data =
+                format!("{} / HTTP/1.1\r\nHost: server.com\r\n\r\n", method).into_bytes();
+            payload
+        };
+        let mut tls_request = make_request_payload(10, cryptde);
+        tls_request.protocol = ProxyProtocol::TLS;
+        let get = make_http_request("GET");
+        let post = make_http_request("POST");
+
+        assert!(ProxyServer::is_safe_to_retry(
+            ExitFailureKind::DnsResolution,
+            &post
+        ));
+        assert!(ProxyServer::is_safe_to_retry(
+            ExitFailureKind::ConnectionRefused,
+            &get
+        ));
+        assert!(!ProxyServer::is_safe_to_retry(
+            ExitFailureKind::ConnectionRefused,
+            &post
+        ));
+        assert!(!ProxyServer::is_safe_to_retry(
+            ExitFailureKind::ConnectionFailed,
+            &post
+        ));
+        assert!(ProxyServer::is_safe_to_retry(
+            ExitFailureKind::ConnectionFailed,
+            &tls_request
+        ));
+    }
+
     #[test]
     fn handle_dns_resolve_failure_reports_services_consumed() {
         let system = System::new("proxy_server_records_accounting");
@@ -4568,6 +5408,90 @@ mod tests {
         assert_eq!(accountant_recording.len(), 1);
     }
 
+    #[test]
+    fn handle_payment_overdue_reports_services_consumed_and_tells_accountant_who_the_creditor_is() {
+        let system = System::new("handle_payment_overdue_reports_services_consumed");
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let cryptde = main_cryptde();
+        let mut subject = ProxyServer::new(
+            cryptde,
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            false,
+        );
+        let irrelevant_public_key = PublicKey::from(&b"irrelevant"[..]);
+        let exit_wallet = make_wallet("exit wallet");
+        let routing_wallet = make_wallet("routing wallet");
+        subject.route_ids_to_return_routes.insert(
+            1234,
+            AddReturnRouteMessage {
+                return_route_id: 1234,
+                expected_services: vec![
+                    ExpectedService::Exit(
+                        irrelevant_public_key.clone(),
+                        exit_wallet.clone(),
+                        rate_pack(101),
+                    ),
+                    ExpectedService::Routing(
+                        irrelevant_public_key.clone(),
+                        routing_wallet.clone(),
+                        rate_pack(102),
+                    ),
+                    ExpectedService::Nothing,
+                ],
+                protocol: ProxyProtocol::TLS,
+                hostname_opt: Some("server.com".to_string()),
+            },
+        );
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let notice = PaymentOverdue_0v1 {
+            balance_gwei: 1_234_567,
+            deadline: 1_700_000_000,
+        };
+        let expired_cores_package: ExpiredCoresPackage<PaymentOverdue_0v1> =
+            ExpiredCoresPackage::new(
+                SocketAddr::from_str("1.2.3.4:1234").unwrap(),
+                Some(make_wallet("irrelevant")),
+                return_route_with_id(cryptde, 1234),
+                notice.clone(),
+                0,
+            );
+        let routing_size = expired_cores_package.payload_len;
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let before = SystemTime::now();
+
+        subject_addr.try_send(expired_cores_package).unwrap();
+
+        System::current().stop();
+        system.run();
+        let after = SystemTime::now();
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        let services_consumed_message =
+            accountant_recording.get_record::<ReportServicesConsumedMessage>(0);
+        assert_eq!(services_consumed_message.exit.earning_wallet, exit_wallet);
+        assert_eq!(services_consumed_message.exit.payload_size, 0);
+        assert_eq!(services_consumed_message.routing_payload_size, routing_size);
+        assert_eq!(
+            services_consumed_message.routing[0].earning_wallet,
+            routing_wallet
+        );
+        let payment_overdue_message =
+            accountant_recording.get_record::<ReportPaymentOverdueMessage>(1);
+        let timestamp = payment_overdue_message.timestamp;
+        assert_eq!(
+            payment_overdue_message,
+            &ReportPaymentOverdueMessage {
+                timestamp,
+                creditor_wallet: exit_wallet,
+                notice,
+            }
+        );
+        assert!(before <= timestamp && timestamp <= after);
+        assert_eq!(accountant_recording.len(), 2);
+    }
+
     #[test]
     fn handle_dns_resolve_failure_sends_message_to_neighborhood() {
         init_test_logging();
@@ -5837,6 +6761,101 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn sweep_stream_keys_reclaims_idle_and_leftover_state_but_leaves_live_streams_alone() {
+        init_test_logging();
+        let test_name =
+            "sweep_stream_keys_reclaims_idle_and_leftover_state_but_leaves_live_streams_alone";
+        let mut subject = ProxyServer::new(main_cryptde(), alias_cryptde(), true, Some(0), false);
+        subject.logger = Logger::new(test_name);
+        let now = SystemTime::now();
+        let dead_stream_key = StreamKey::make_meaningful_stream_key("dead");
+        let live_stream_key = StreamKey::make_meaningful_stream_key("live");
+        let leftover_stream_key = StreamKey::make_meaningful_stream_key("leftover");
+        let dead_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let live_addr = SocketAddr::from_str("2.3.4.5:6789").unwrap();
+        subject.keys_and_addrs.insert(dead_stream_key, dead_addr);
+        subject
+            .tunneled_hosts
+            .insert(dead_stream_key, "dead.com".to_string());
+        subject
+            .stream_key_ttl
+            .insert(dead_stream_key, now - Duration::from_secs(3600));
+        subject.stream_key_activity.insert(
+            dead_stream_key,
+            now - STREAM_KEY_IDLE_LIMIT - Duration::from_secs(1),
+        );
+        subject.keys_and_addrs.insert(live_stream_key, live_addr);
+        subject
+            .tunneled_hosts
+            .insert(live_stream_key, "live.com".to_string());
+        subject
+            .stream_key_activity
+            .insert(live_stream_key, now - Duration::from_secs(60));
+        subject
+            .tunneled_hosts
+            .insert(leftover_stream_key, "leftover.com".to_string());
+
+        subject.sweep_stream_keys(now);
+
+        assert_eq!(subject.keys_and_addrs.a_to_b(&dead_stream_key), None);
+        assert_eq!(subject.tunneled_hosts.get(&dead_stream_key), None);
+        assert_eq!(subject.stream_key_ttl.get(&dead_stream_key), None);
+        assert_eq!(subject.stream_key_activity.get(&dead_stream_key), None);
+        assert_eq!(subject.tunneled_hosts.get(&leftover_stream_key), None);
+        assert_eq!(
+            subject.keys_and_addrs.a_to_b(&live_stream_key),
+            Some(live_addr)
+        );
+        assert_eq!(
+            subject.tunneled_hosts.get(&live_stream_key),
+            Some(&"live.com".to_string())
+        );
+        assert_eq!(
+            subject.stream_key_sweep_stats,
+            StreamKeySweepStats {
+                sweeps: 1,
+                stream_keys_reclaimed: 1,
+                entries_reclaimed: 3,
+            }
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {}: Swept 1 orphaned stream key(s) and 3 leftover entries; 1 stream keys and 3 \
+            entries reclaimed in 1 sweeps",
+            test_name
+        ));
+    }
+
+    #[test]
+    fn stream_key_activity_is_recorded_for_inbound_data_and_outlives_the_purge_until_swept() {
+        let mut subject = ProxyServer::new(main_cryptde(), alias_cryptde(), true, Some(0), false);
+        let stream_key = StreamKey::make_meaningful_stream_key("active");
+        subject.stream_key_factory = Box::new(StreamKeyFactoryMock::new().make_result(stream_key));
+        let ibcd = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: SocketAddr::from_str("1.2.3.4:5678").unwrap(),
+            reception_port: Some(HTTP_PORT),
+            last_data: false,
+            is_clandestine: false,
+            sequence_number: Some(0),
+            data: vec![],
+        };
+        let before = SystemTime::now();
+
+        let result = subject.find_or_generate_stream_key(&ibcd);
+
+        assert_eq!(result, stream_key);
+        let last_activity = *subject.stream_key_activity.get(&stream_key).unwrap();
+        assert!(last_activity >= before);
+        subject.purge_stream_key(&stream_key, "test");
+        assert_eq!(
+            subject.stream_key_activity.get(&stream_key),
+            Some(&last_activity)
+        );
+        subject.sweep_stream_keys(last_activity + STREAM_KEY_IDLE_LIMIT + Duration::from_secs(1));
+        assert_eq!(subject.stream_key_activity.get(&stream_key), None);
+    }
+
     #[test]
     fn handle_stream_shutdown_msg_logs_errors_from_handling_normal_client_data() {
         init_test_logging();
@@ -0,0 +1,339 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::sub_lib::neighborhood::RouteQueryResponse;
+use actix::Message;
+use std::collections::VecDeque;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+pub const DEFAULT_ROUTE_POOL_SIZE: usize = 2;
+pub const DEFAULT_ROUTE_POOL_LIFETIME: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RoutePoolConfig {
+    pub size: usize,
+    // A pooled route older than this is dropped rather than used; the pool is also checked for
+    // stale routes this often.
+    pub route_lifetime: Duration,
+}
+
+impl Default for RoutePoolConfig {
+    fn default() -> Self {
+        Self {
+            size: DEFAULT_ROUTE_POOL_SIZE,
+            route_lifetime: DEFAULT_ROUTE_POOL_LIFETIME,
+        }
+    }
+}
+
+impl RoutePoolConfig {
+    pub fn disabled() -> Self {
+        Self {
+            size: 0,
+            ..Self::default()
+        }
+    }
+}
+
+impl Display for RoutePoolConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}|{}", self.size, self.route_lifetime.as_secs())
+    }
+}
+
+impl FromStr for RoutePoolConfig {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = s
+            .split('|')
+            .map(|segment| segment.parse::<u64>())
+            .collect::<Result<Vec<u64>, _>>()
+            .map_err(|e| format!("Bad route-pool value '{}': {}", s, e))?;
+        match values.as_slice() {
+            [size, lifetime_secs] if *lifetime_secs > 0 => Ok(Self {
+                size: *size as usize,
+                route_lifetime: Duration::from_secs(*lifetime_secs),
+            }),
+            _ => Err(format!(
+                "Bad route-pool value '{}': expected <pool size>|<route lifetime in seconds>",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RoutePoolStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl RoutePoolStats {
+    pub fn hit_rate_percent(&self) -> Option<u64> {
+        match self.hits + self.misses {
+            0 => None,
+            total => Some(self.hits * 100 / total),
+        }
+    }
+}
+
+impl Display for RoutePoolStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.hit_rate_percent() {
+            None => write!(f, "no requests yet"),
+            Some(rate) => write!(
+                f,
+                "{}% hit rate ({} hits, {} misses)",
+                rate, self.hits, self.misses
+            ),
+        }
+    }
+}
+
+#[derive(Message, Debug, PartialEq, Eq)]
+pub struct RefillRoutePool {}
+
+#[derive(Message, Debug, PartialEq, Eq)]
+pub struct AddPooledRouteMessage {
+    pub route_opt: Option<RouteQueryResponse>,
+}
+
+// Routes in the pool are requested without a hostname, so the Neighborhood can't steer them away
+// from exits known to be unable to reach a particular host; a DNS failure over a pooled route
+// falls back to the ordinary retry, which asks for a fresh route that knows the hostname.
+pub struct RoutePool {
+    config: RoutePoolConfig,
+    routes: VecDeque<(RouteQueryResponse, SystemTime)>,
+    requests_outstanding: usize,
+    stats: RoutePoolStats,
+}
+
+impl RoutePool {
+    pub fn new(config: RoutePoolConfig) -> Self {
+        Self {
+            config,
+            routes: VecDeque::new(),
+            requests_outstanding: 0,
+            stats: RoutePoolStats::default(),
+        }
+    }
+
+    pub fn config(&self) -> RoutePoolConfig {
+        self.config
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.size > 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    pub fn stats(&self) -> RoutePoolStats {
+        self.stats
+    }
+
+    pub fn take(&mut self, now: SystemTime) -> Option<RouteQueryResponse> {
+        if !self.is_enabled() {
+            return None;
+        }
+        self.evict_stale(now);
+        match self.routes.pop_front() {
+            Some((route, _)) => {
+                self.stats.hits += 1;
+                Some(route)
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    // Returns how many new routes should be requested to fill the pool, and counts them as
+    // outstanding until each one is answered with add() or request_failed().
+    pub fn claim_refill(&mut self, now: SystemTime) -> usize {
+        self.evict_stale(now);
+        let wanted = self
+            .config
+            .size
+            .saturating_sub(self.routes.len() + self.requests_outstanding);
+        self.requests_outstanding += wanted;
+        wanted
+    }
+
+    pub fn add(&mut self, route: RouteQueryResponse, now: SystemTime) {
+        self.requests_outstanding = self.requests_outstanding.saturating_sub(1);
+        if self.routes.len() < self.config.size {
+            self.routes.push_back((route, now));
+        }
+    }
+
+    pub fn request_failed(&mut self) {
+        self.requests_outstanding = self.requests_outstanding.saturating_sub(1);
+    }
+
+    fn evict_stale(&mut self, now: SystemTime) {
+        let lifetime = self.config.route_lifetime;
+        self.routes
+            .retain(|(_, added)| match now.duration_since(*added) {
+                Ok(age) => age < lifetime,
+                Err(_) => true,
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sub_lib::neighborhood::ExpectedServices;
+    use crate::sub_lib::route::Route;
+
+    fn make_route(return_route_id: u32) -> RouteQueryResponse {
+        RouteQueryResponse {
+            route: Route { hops: vec![] },
+            expected_services: ExpectedServices::RoundTrip(vec![], vec![], return_route_id),
+        }
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(DEFAULT_ROUTE_POOL_SIZE, 2);
+        assert_eq!(DEFAULT_ROUTE_POOL_LIFETIME, Duration::from_secs(60));
+    }
+
+    #[test]
+    fn route_pool_config_round_trips_through_strings() {
+        let subject = RoutePoolConfig::from_str("4|30").unwrap();
+
+        assert_eq!(
+            subject,
+            RoutePoolConfig {
+                size: 4,
+                route_lifetime: Duration::from_secs(30)
+            }
+        );
+        assert_eq!(subject.to_string(), "4|30");
+        assert_eq!(RoutePoolConfig::default().to_string(), "2|60");
+        assert_eq!(RoutePoolConfig::disabled().to_string(), "0|60");
+    }
+
+    #[test]
+    fn route_pool_config_rejects_bad_strings() {
+        assert_eq!(
+            RoutePoolConfig::from_str("4"),
+            Err(
+                "Bad route-pool value '4': expected <pool size>|<route lifetime in seconds>"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            RoutePoolConfig::from_str("4|0"),
+            Err(
+                "Bad route-pool value '4|0': expected <pool size>|<route lifetime in seconds>"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            RoutePoolConfig::from_str("four|30"),
+            Err("Bad route-pool value 'four|30': invalid digit found in string".to_string())
+        );
+    }
+
+    #[test]
+    fn claim_refill_asks_only_for_what_is_neither_pooled_nor_outstanding() {
+        let now = SystemTime::now();
+        let mut subject = RoutePool::new(RoutePoolConfig {
+            size: 3,
+            route_lifetime: Duration::from_secs(60),
+        });
+
+        let first_claim = subject.claim_refill(now);
+        let second_claim = subject.claim_refill(now);
+        subject.add(make_route(1), now);
+        subject.request_failed();
+        let third_claim = subject.claim_refill(now);
+
+        assert_eq!(first_claim, 3);
+        assert_eq!(second_claim, 0);
+        assert_eq!(third_claim, 1);
+        assert_eq!(subject.len(), 1);
+    }
+
+    #[test]
+    fn take_hands_out_routes_in_order_and_counts_hits_and_misses() {
+        let now = SystemTime::now();
+        let mut subject = RoutePool::new(RoutePoolConfig {
+            size: 2,
+            route_lifetime: Duration::from_secs(60),
+        });
+        subject.claim_refill(now);
+        subject.add(make_route(1), now);
+        subject.add(make_route(2), now);
+
+        let results = (0..3).map(|_| subject.take(now)).collect::<Vec<_>>();
+
+        assert_eq!(
+            results,
+            vec![Some(make_route(1)), Some(make_route(2)), None]
+        );
+        assert_eq!(subject.stats(), RoutePoolStats { hits: 2, misses: 1 });
+        assert_eq!(subject.stats().hit_rate_percent(), Some(66));
+        assert_eq!(
+            subject.stats().to_string(),
+            "66% hit rate (2 hits, 1 misses)"
+        );
+    }
+
+    #[test]
+    fn stale_routes_are_dropped_instead_of_used() {
+        let then = SystemTime::now();
+        let now = then + Duration::from_secs(60);
+        let mut subject = RoutePool::new(RoutePoolConfig {
+            size: 2,
+            route_lifetime: Duration::from_secs(60),
+        });
+        subject.claim_refill(then);
+        subject.add(make_route(1), then);
+        subject.add(make_route(2), now);
+
+        let result = subject.take(now);
+
+        assert_eq!(result, Some(make_route(2)));
+        assert!(subject.is_empty());
+    }
+
+    #[test]
+    fn a_full_pool_discards_surplus_routes() {
+        let now = SystemTime::now();
+        let mut subject = RoutePool::new(RoutePoolConfig {
+            size: 1,
+            route_lifetime: Duration::from_secs(60),
+        });
+
+        subject.add(make_route(1), now);
+        subject.add(make_route(2), now);
+
+        assert_eq!(subject.len(), 1);
+        assert_eq!(subject.take(now), Some(make_route(1)));
+    }
+
+    #[test]
+    fn a_disabled_pool_neither_hands_out_nor_requests_routes() {
+        let now = SystemTime::now();
+        let mut subject = RoutePool::new(RoutePoolConfig::disabled());
+
+        assert_eq!(subject.claim_refill(now), 0);
+        assert_eq!(subject.take(now), None);
+        assert_eq!(subject.stats(), RoutePoolStats::default());
+        assert_eq!(subject.stats().to_string(), "no requests yet");
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use actix::Message;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+pub const STREAM_KEY_SWEEP_INTERVAL: Duration = Duration::from_secs(300);
+// A stream key that has seen no traffic in either direction for this long belongs to a socket
+// that died without a StreamShutdownMsg, so everything kept for it can go.
+pub const STREAM_KEY_IDLE_LIMIT: Duration = Duration::from_secs(1800);
+
+#[derive(Message, Debug, PartialEq, Eq)]
+pub struct SweepStreamKeys {}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StreamKeySweepStats {
+    pub sweeps: u64,
+    pub stream_keys_reclaimed: u64,
+    pub entries_reclaimed: u64,
+}
+
+impl StreamKeySweepStats {
+    pub fn record_sweep(&mut self, stream_keys_reclaimed: usize, entries_reclaimed: usize) {
+        self.sweeps += 1;
+        self.stream_keys_reclaimed += stream_keys_reclaimed as u64;
+        self.entries_reclaimed += entries_reclaimed as u64;
+    }
+}
+
+impl Display for StreamKeySweepStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} stream keys and {} entries reclaimed in {} sweeps",
+            self.stream_keys_reclaimed, self.entries_reclaimed, self.sweeps
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(STREAM_KEY_SWEEP_INTERVAL, Duration::from_secs(300));
+        assert_eq!(STREAM_KEY_IDLE_LIMIT, Duration::from_secs(1800));
+    }
+
+    #[test]
+    fn stats_accumulate_over_sweeps() {
+        let mut subject = StreamKeySweepStats::default();
+
+        subject.record_sweep(2, 7);
+        subject.record_sweep(0, 0);
+        subject.record_sweep(1, 3);
+
+        assert_eq!(
+            subject,
+            StreamKeySweepStats {
+                sweeps: 3,
+                stream_keys_reclaimed: 3,
+                entries_reclaimed: 10,
+            }
+        );
+        assert_eq!(
+            subject.to_string(),
+            "3 stream keys and 10 entries reclaimed in 3 sweeps"
+        );
+    }
+}
@@ -0,0 +1,280 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::replication::{ReplicationError, ReplicationMessage};
+use serde_derive::{Deserialize, Serialize};
+use sodiumoxide::crypto::auth;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+
+pub const MAX_REPLICATION_FRAME_SIZE: usize = 16 * 1024 * 1024;
+const LENGTH_PREFIX_SIZE: usize = 4;
+
+// Shared secret both ends of the link must be configured with; anyone without it can neither
+// inject frames into the standby nor have the primary's frames accepted elsewhere.
+#[derive(Clone)]
+pub struct ReplicationKey {
+    key: auth::Key,
+}
+
+impl ReplicationKey {
+    pub fn generate() -> Self {
+        Self {
+            key: auth::gen_key(),
+        }
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        auth::Key::from_slice(bytes).map(|key| Self { key })
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.key.0
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+struct ReplicationFrame {
+    sequence: u64,
+    message: ReplicationMessage,
+}
+
+// A frame on the wire is a four-byte big-endian length, the HMAC tag, and then that many bytes of
+// JSON. The tag covers the sequence number, so a recorded frame can't be replayed later.
+pub struct ReplicationSender<W: Write> {
+    writer: W,
+    key: ReplicationKey,
+    next_sequence: u64,
+}
+
+impl<W: Write> ReplicationSender<W> {
+    pub fn new(writer: W, key: ReplicationKey) -> Self {
+        Self {
+            writer,
+            key,
+            next_sequence: 1,
+        }
+    }
+
+    pub fn send(&mut self, message: ReplicationMessage) -> Result<(), ReplicationError> {
+        let frame = ReplicationFrame {
+            sequence: self.next_sequence,
+            message,
+        };
+        let payload = serde_json::to_vec(&frame)
+            .unwrap_or_else(|e| panic!("Couldn't serialize replication frame: {:?}", e));
+        if payload.len() > MAX_REPLICATION_FRAME_SIZE {
+            return Err(ReplicationError::FrameTooLarge(payload.len()));
+        }
+        let tag = auth::authenticate(&payload, &self.key.key);
+        let mut bytes = Vec::with_capacity(LENGTH_PREFIX_SIZE + auth::TAGBYTES + payload.len());
+        bytes.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(&tag.0);
+        bytes.extend_from_slice(&payload);
+        self.writer
+            .write_all(&bytes)
+            .and_then(|_| self.writer.flush())
+            .map_err(|e| ReplicationError::Io(e.to_string()))?;
+        self.next_sequence += 1;
+        Ok(())
+    }
+}
+
+pub struct ReplicationReceiver<R: Read> {
+    reader: R,
+    key: ReplicationKey,
+    last_sequence: u64,
+}
+
+impl<R: Read> ReplicationReceiver<R> {
+    pub fn new(reader: R, key: ReplicationKey) -> Self {
+        Self {
+            reader,
+            key,
+            last_sequence: 0,
+        }
+    }
+
+    // Ok(None) means the primary closed the link cleanly between frames
+    pub fn receive(&mut self) -> Result<Option<ReplicationMessage>, ReplicationError> {
+        let mut length_bytes = [0u8; LENGTH_PREFIX_SIZE];
+        match self.reader.read_exact(&mut length_bytes) {
+            Ok(()) => (),
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(ReplicationError::Io(e.to_string())),
+        }
+        let length = u32::from_be_bytes(length_bytes) as usize;
+        if length > MAX_REPLICATION_FRAME_SIZE {
+            return Err(ReplicationError::FrameTooLarge(length));
+        }
+        let mut tag_bytes = [0u8; auth::TAGBYTES];
+        let mut payload = vec![0u8; length];
+        self.reader
+            .read_exact(&mut tag_bytes)
+            .and_then(|_| self.reader.read_exact(&mut payload))
+            .map_err(|e| ReplicationError::Io(e.to_string()))?;
+        if !auth::verify(&auth::Tag(tag_bytes), &payload, &self.key.key) {
+            return Err(ReplicationError::BadTag);
+        }
+        let frame: ReplicationFrame = serde_json::from_slice(&payload)
+            .map_err(|e| ReplicationError::Malformed(e.to_string()))?;
+        if frame.sequence <= self.last_sequence {
+            return Err(ReplicationError::Replayed {
+                last_sequence: self.last_sequence,
+                received: frame.sequence,
+            });
+        }
+        self.last_sequence = frame.sequence;
+        Ok(Some(frame.message))
+    }
+}
+
+// The link is meant for two Nodes on the same machine or the same trusted LAN segment; the
+// standby refuses to hand replication state to anything that didn't come in over loopback.
+pub fn accept_local(listener: &TcpListener) -> Result<(TcpStream, SocketAddr), ReplicationError> {
+    let (stream, peer_addr) = listener
+        .accept()
+        .map_err(|e| ReplicationError::Io(e.to_string()))?;
+    if !peer_addr.ip().is_loopback() {
+        return Err(ReplicationError::NotLocal(peer_addr));
+    }
+    Ok((stream, peer_addr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accountant::db_access_objects::audit_log_dao::{
+        AuditedAccount, BalanceAuditRecord, BalanceChange, BalanceChangeCause,
+    };
+    use crate::test_utils::make_wallet;
+    use masq_lib::test_utils::fake_stream_holder::{ByteArrayReader, ByteArrayWriter};
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::{Duration, SystemTime};
+
+    fn make_snapshot(descriptor: &str) -> ReplicationMessage {
+        ReplicationMessage::NeighborhoodSnapshot {
+            neighbor_descriptors: vec![descriptor.to_string()],
+        }
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(MAX_REPLICATION_FRAME_SIZE, 16 * 1024 * 1024);
+    }
+
+    #[test]
+    fn frames_survive_the_round_trip() {
+        let key = ReplicationKey::generate();
+        let writer = ByteArrayWriter::new();
+        let mut sender = ReplicationSender::new(writer, key.clone());
+        sender.send(make_snapshot("booga")).unwrap();
+        sender.send(make_snapshot("agoob")).unwrap();
+        let bytes = sender.writer.get_bytes();
+        let mut subject = ReplicationReceiver::new(ByteArrayReader::new(&bytes), key);
+
+        let first = subject.receive();
+        let second = subject.receive();
+        let end = subject.receive();
+
+        assert_eq!(first, Ok(Some(make_snapshot("booga"))));
+        assert_eq!(second, Ok(Some(make_snapshot("agoob"))));
+        assert_eq!(end, Ok(None));
+    }
+
+    #[test]
+    fn balance_changes_survive_the_round_trip() {
+        let key = ReplicationKey::generate();
+        let message = ReplicationMessage::BalanceChanges {
+            records: vec![BalanceAuditRecord {
+                rowid: 42,
+                change: BalanceChange {
+                    timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(1_234_567),
+                    wallet: make_wallet("booga"),
+                    account: AuditedAccount::Receivable,
+                    change_wei: -(u64::MAX as i128) * 1000,
+                    cause: BalanceChangeCause::PaymentReceived { block_number: 555 },
+                },
+            }],
+        };
+        let mut sender = ReplicationSender::new(ByteArrayWriter::new(), key.clone());
+        sender.send(message.clone()).unwrap();
+        let bytes = sender.writer.get_bytes();
+        let mut subject = ReplicationReceiver::new(ByteArrayReader::new(&bytes), key);
+
+        let result = subject.receive();
+
+        assert_eq!(result, Ok(Some(message)));
+    }
+
+    #[test]
+    fn frames_from_a_sender_with_a_different_key_are_rejected() {
+        let mut sender = ReplicationSender::new(ByteArrayWriter::new(), ReplicationKey::generate());
+        sender.send(make_snapshot("booga")).unwrap();
+        let bytes = sender.writer.get_bytes();
+        let mut subject =
+            ReplicationReceiver::new(ByteArrayReader::new(&bytes), ReplicationKey::generate());
+
+        let result = subject.receive();
+
+        assert_eq!(result, Err(ReplicationError::BadTag));
+    }
+
+    #[test]
+    fn replayed_frames_are_rejected() {
+        let key = ReplicationKey::generate();
+        let mut sender = ReplicationSender::new(ByteArrayWriter::new(), key.clone());
+        sender.send(make_snapshot("booga")).unwrap();
+        let frame = sender.writer.get_bytes();
+        let replayed = [frame.clone(), frame].concat();
+        let mut subject = ReplicationReceiver::new(ByteArrayReader::new(&replayed), key);
+
+        let first = subject.receive();
+        let second = subject.receive();
+
+        assert_eq!(first, Ok(Some(make_snapshot("booga"))));
+        assert_eq!(
+            second,
+            Err(ReplicationError::Replayed {
+                last_sequence: 1,
+                received: 1
+            })
+        );
+    }
+
+    #[test]
+    fn oversized_frames_are_rejected_before_being_read() {
+        let bytes = ((MAX_REPLICATION_FRAME_SIZE + 1) as u32).to_be_bytes();
+        let mut subject =
+            ReplicationReceiver::new(ByteArrayReader::new(&bytes), ReplicationKey::generate());
+
+        let result = subject.receive();
+
+        assert_eq!(
+            result,
+            Err(ReplicationError::FrameTooLarge(
+                MAX_REPLICATION_FRAME_SIZE + 1
+            ))
+        );
+    }
+
+    #[test]
+    fn key_can_be_rebuilt_from_its_bytes() {
+        let key = ReplicationKey::generate();
+
+        let result = ReplicationKey::from_bytes(key.as_bytes()).unwrap();
+
+        assert_eq!(result.as_bytes(), key.as_bytes());
+        assert!(ReplicationKey::from_bytes(&[1, 2, 3]).is_none());
+    }
+
+    #[test]
+    fn accept_local_takes_connections_over_loopback() {
+        let listener =
+            TcpListener::bind(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0)).unwrap();
+        let _client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+
+        let (_, peer_addr) = accept_local(&listener).unwrap();
+
+        assert!(peer_addr.ip().is_loopback());
+    }
+}
@@ -0,0 +1,306 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+// Experimental warm standby. A primary Node streams the public descriptors of its neighbors and
+// its balance audit log to a standby over an authenticated local link; the standby replays the
+// balance changes into its own payable and receivable tables. On failover the standby keeps the
+// same earning wallet but comes up with its own, fresh keypair, using the replicated neighbors to
+// rejoin the network quickly. Nothing here is wired into the actor system yet.
+
+pub mod link;
+
+use crate::accountant::db_access_objects::audit_log_dao::{
+    AuditLogDao, AuditedAccount, BalanceAuditRecord,
+};
+use crate::accountant::db_access_objects::payable_dao::PayableDao;
+use crate::accountant::db_access_objects::receivable_dao::ReceivableDao;
+use masq_lib::logger::Logger;
+use serde_derive::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+pub const REPLICATION_BATCH_SIZE: u16 = 500;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum ReplicationMessage {
+    NeighborhoodSnapshot { neighbor_descriptors: Vec<String> },
+    BalanceChanges { records: Vec<BalanceAuditRecord> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplicationError {
+    Io(String),
+    NotLocal(SocketAddr),
+    FrameTooLarge(usize),
+    BadTag,
+    Malformed(String),
+    Replayed { last_sequence: u64, received: u64 },
+    Gap { expected: u64, received: u64 },
+    Dao(String),
+}
+
+pub struct ReplicationSource {
+    audit_log_dao: Box<dyn AuditLogDao>,
+}
+
+impl ReplicationSource {
+    pub fn new(audit_log_dao: Box<dyn AuditLogDao>) -> Self {
+        Self { audit_log_dao }
+    }
+
+    // The audit log is append-only and its rowids start at 1, so a standby that has applied
+    // 'cursor' records needs the ones from offset 'cursor' on.
+    pub fn balance_changes_after(&self, cursor: u64) -> Option<ReplicationMessage> {
+        let records = self.audit_log_dao.records(cursor, REPLICATION_BATCH_SIZE);
+        if records.is_empty() {
+            None
+        } else {
+            Some(ReplicationMessage::BalanceChanges { records })
+        }
+    }
+}
+
+pub struct ReplicationSink {
+    payable_dao: Box<dyn PayableDao>,
+    receivable_dao: Box<dyn ReceivableDao>,
+    audit_log_dao: Box<dyn AuditLogDao>,
+    neighbor_descriptors: Vec<String>,
+    logger: Logger,
+}
+
+impl ReplicationSink {
+    pub fn new(
+        payable_dao: Box<dyn PayableDao>,
+        receivable_dao: Box<dyn ReceivableDao>,
+        audit_log_dao: Box<dyn AuditLogDao>,
+    ) -> Self {
+        Self {
+            payable_dao,
+            receivable_dao,
+            audit_log_dao,
+            neighbor_descriptors: vec![],
+            logger: Logger::new("ReplicationSink"),
+        }
+    }
+
+    // A standby does no business of its own, so every row in its audit log is a replayed one
+    // and their count tells how far into the primary's log it has got.
+    pub fn cursor(&self) -> u64 {
+        self.audit_log_dao.count()
+    }
+
+    pub fn neighbor_descriptors(&self) -> &[String] {
+        &self.neighbor_descriptors
+    }
+
+    pub fn apply(&mut self, message: ReplicationMessage) -> Result<(), ReplicationError> {
+        match message {
+            ReplicationMessage::NeighborhoodSnapshot {
+                neighbor_descriptors,
+            } => {
+                debug!(
+                    self.logger,
+                    "Replicated {} neighbor descriptors",
+                    neighbor_descriptors.len()
+                );
+                self.neighbor_descriptors = neighbor_descriptors;
+                Ok(())
+            }
+            ReplicationMessage::BalanceChanges { records } => self.apply_balance_changes(records),
+        }
+    }
+
+    fn apply_balance_changes(
+        &mut self,
+        records: Vec<BalanceAuditRecord>,
+    ) -> Result<(), ReplicationError> {
+        let mut cursor = self.cursor();
+        for record in records {
+            if record.rowid <= cursor {
+                debug!(
+                    self.logger,
+                    "Skipping balance change {} already replicated", record.rowid
+                );
+                continue;
+            }
+            if record.rowid > cursor + 1 {
+                return Err(ReplicationError::Gap {
+                    expected: cursor + 1,
+                    received: record.rowid,
+                });
+            }
+            match record.change.account {
+                AuditedAccount::Payable => self
+                    .payable_dao
+                    .apply_replicated_change(&record.change)
+                    .map_err(|e| ReplicationError::Dao(format!("{:?}", e)))?,
+                AuditedAccount::Receivable => self
+                    .receivable_dao
+                    .apply_replicated_change(&record.change)
+                    .map_err(|e| ReplicationError::Dao(format!("{:?}", e)))?,
+            }
+            cursor = record.rowid;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accountant::db_access_objects::audit_log_dao::{BalanceChange, BalanceChangeCause};
+    use crate::accountant::db_access_objects::payable_dao::PayableDaoError;
+    use crate::accountant::test_utils::{AuditLogDaoMock, PayableDaoMock, ReceivableDaoMock};
+    use crate::test_utils::make_wallet;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, SystemTime};
+
+    fn make_record(rowid: u64, account: AuditedAccount) -> BalanceAuditRecord {
+        BalanceAuditRecord {
+            rowid,
+            change: BalanceChange {
+                timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(rowid),
+                wallet: make_wallet("booga"),
+                account,
+                change_wei: rowid as i128 * 1000,
+                cause: BalanceChangeCause::ServicesConsumed,
+            },
+        }
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(REPLICATION_BATCH_SIZE, 500);
+    }
+
+    #[test]
+    fn source_reads_the_audit_log_from_the_cursor_on() {
+        let records_params_arc = Arc::new(Mutex::new(vec![]));
+        let audit_log_dao = AuditLogDaoMock::new()
+            .records_params(&records_params_arc)
+            .records_result(vec![make_record(8, AuditedAccount::Payable)])
+            .records_result(vec![]);
+        let subject = ReplicationSource::new(Box::new(audit_log_dao));
+
+        let first = subject.balance_changes_after(7);
+        let second = subject.balance_changes_after(8);
+
+        assert_eq!(
+            first,
+            Some(ReplicationMessage::BalanceChanges {
+                records: vec![make_record(8, AuditedAccount::Payable)]
+            })
+        );
+        assert_eq!(second, None);
+        assert_eq!(
+            *records_params_arc.lock().unwrap(),
+            vec![(7, REPLICATION_BATCH_SIZE), (8, REPLICATION_BATCH_SIZE)]
+        );
+    }
+
+    #[test]
+    fn sink_skips_records_already_applied_and_routes_the_rest_by_account() {
+        let payable_params_arc = Arc::new(Mutex::new(vec![]));
+        let receivable_params_arc = Arc::new(Mutex::new(vec![]));
+        let payable_dao = PayableDaoMock::new()
+            .apply_replicated_change_params(&payable_params_arc)
+            .apply_replicated_change_result(Ok(()));
+        let receivable_dao = ReceivableDaoMock::new()
+            .apply_replicated_change_params(&receivable_params_arc)
+            .apply_replicated_change_result(Ok(()));
+        let audit_log_dao = AuditLogDaoMock::new().count_result(2);
+        let mut subject = ReplicationSink::new(
+            Box::new(payable_dao),
+            Box::new(receivable_dao),
+            Box::new(audit_log_dao),
+        );
+
+        let result = subject.apply(ReplicationMessage::BalanceChanges {
+            records: vec![
+                make_record(1, AuditedAccount::Payable),
+                make_record(2, AuditedAccount::Receivable),
+                make_record(3, AuditedAccount::Payable),
+                make_record(4, AuditedAccount::Receivable),
+            ],
+        });
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            *payable_params_arc.lock().unwrap(),
+            vec![make_record(3, AuditedAccount::Payable).change]
+        );
+        assert_eq!(
+            *receivable_params_arc.lock().unwrap(),
+            vec![make_record(4, AuditedAccount::Receivable).change]
+        );
+    }
+
+    #[test]
+    fn sink_refuses_to_skip_over_missing_records() {
+        let audit_log_dao = AuditLogDaoMock::new().count_result(2);
+        let mut subject = ReplicationSink::new(
+            Box::new(PayableDaoMock::new()),
+            Box::new(ReceivableDaoMock::new()),
+            Box::new(audit_log_dao),
+        );
+
+        let result = subject.apply(ReplicationMessage::BalanceChanges {
+            records: vec![make_record(4, AuditedAccount::Payable)],
+        });
+
+        assert_eq!(
+            result,
+            Err(ReplicationError::Gap {
+                expected: 3,
+                received: 4
+            })
+        );
+    }
+
+    #[test]
+    fn sink_reports_dao_failure() {
+        let payable_dao = PayableDaoMock::new().apply_replicated_change_result(Err(
+            PayableDaoError::RusqliteError("booga".to_string()),
+        ));
+        let audit_log_dao = AuditLogDaoMock::new().count_result(0);
+        let mut subject = ReplicationSink::new(
+            Box::new(payable_dao),
+            Box::new(ReceivableDaoMock::new()),
+            Box::new(audit_log_dao),
+        );
+
+        let result = subject.apply(ReplicationMessage::BalanceChanges {
+            records: vec![make_record(1, AuditedAccount::Payable)],
+        });
+
+        assert_eq!(
+            result,
+            Err(ReplicationError::Dao(
+                "RusqliteError(\"booga\")".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn sink_keeps_the_latest_neighborhood_snapshot() {
+        let mut subject = ReplicationSink::new(
+            Box::new(PayableDaoMock::new()),
+            Box::new(ReceivableDaoMock::new()),
+            Box::new(AuditLogDaoMock::new()),
+        );
+
+        subject
+            .apply(ReplicationMessage::NeighborhoodSnapshot {
+                neighbor_descriptors: vec!["booga".to_string()],
+            })
+            .unwrap();
+        subject
+            .apply(ReplicationMessage::NeighborhoodSnapshot {
+                neighbor_descriptors: vec!["agoob".to_string(), "gooba".to_string()],
+            })
+            .unwrap();
+
+        assert_eq!(
+            subject.neighbor_descriptors(),
+            &["agoob".to_string(), "gooba".to_string()]
+        );
+    }
+}
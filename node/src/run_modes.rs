@@ -162,9 +162,10 @@ impl RunModes {
         error.into_iter().for_each(|err_case| {
             short_writeln!(
                 streams.stderr,
-                "{} - {}",
+                "{} - {} (code {:#018x})",
                 err_case.parameter,
-                err_case.reason
+                err_case.reason,
+                err_case.code
             )
         })
     }
@@ -288,6 +289,9 @@ mod tests {
         DumpConfigRunnerMock, ServerInitializerFactoryMock, ServerInitializerMock,
     };
     use crate::server_initializer::test_utils::PrivilegeDropperMock;
+    use masq_lib::constants::{
+        INVALID_PARAM_VALUE_ERROR, MISSING_PARAM_ERROR, UNFAMILIAR_PARAM_ERROR,
+    };
     use masq_lib::test_utils::fake_stream_holder::FakeStreamHolder;
     use masq_lib::utils::slice_of_strs_to_vec_of_strings;
     use regex::Regex;
@@ -462,11 +466,12 @@ mod tests {
     #[test]
     fn go_accepts_requireds_errors_and_renders_them() {
         let mut subject = RunModes::new();
-        subject.runner = Box::new(RunnerMock::new().dump_config_result(Err(
-            RunnerError::Configurator(
-                ConfiguratorError::required("parm1", "msg1").another_required("parm2", "msg2"),
-            ),
-        )));
+        subject.runner = Box::new(
+            RunnerMock::new().dump_config_result(Err(RunnerError::Configurator(
+                ConfiguratorError::required("parm1", "msg1", INVALID_PARAM_VALUE_ERROR)
+                    .another_required("parm2", "msg2", MISSING_PARAM_ERROR),
+            ))),
+        );
         subject.privilege_dropper =
             Box::new(PrivilegeDropperMock::new().expect_privilege_result(true));
         let mut holder = FakeStreamHolder::new();
@@ -477,8 +482,8 @@ mod tests {
         assert_eq!(
             &holder.stderr.get_string(),
             "Configuration error\n\
-parm1 - msg1\n\
-parm2 - msg2\n"
+parm1 - msg1 (code 0x0002000000000001)\n\
+parm2 - msg2 (code 0x0002000000000002)\n"
         )
     }
 
@@ -493,6 +498,7 @@ parm2 - msg2\n"
                     .go_result(Err(ConfiguratorError::required(
                         "some-parameter",
                         "too-low-value",
+                        INVALID_PARAM_VALUE_ERROR,
                     )))
                     .go_params(&go_params_arc),
             )),
@@ -512,7 +518,8 @@ parm2 - msg2\n"
             configurator_error.param_errors[0],
             ParamError {
                 parameter: "some-parameter".to_string(),
-                reason: "too-low-value".to_string()
+                reason: "too-low-value".to_string(),
+                code: INVALID_PARAM_VALUE_ERROR
             }
         );
         assert_eq!(&holder.stdout.get_string(), "");
@@ -562,6 +569,7 @@ parm2 - msg2\n"
                 .make_result(Err(ConfiguratorError::required(
                     "<unknown>",
                     "Unfamiliar message: error: Found argument \'--halabala\'",
+                    UNFAMILIAR_PARAM_ERROR,
                 ))),
         );
         subject.runner = Box::new(runner);
@@ -579,7 +587,8 @@ parm2 - msg2\n"
             Err(RunnerError::Configurator(ConfiguratorError::new(vec![
                 ParamError {
                     parameter: "<unknown>".to_string(),
-                    reason: "Unfamiliar message: error: Found argument \'--halabala\'".to_string()
+                    reason: "Unfamiliar message: error: Found argument \'--halabala\'".to_string(),
+                    code: UNFAMILIAR_PARAM_ERROR
                 }
             ])))
         )
@@ -594,7 +603,11 @@ parm2 - msg2\n"
             DaemonInitializerFactoryMock::default().make_result(Ok(Box::new(
                 DaemonInitializerMock::default()
                     .go_params(&go_params_arc)
-                    .go_results(Err(ConfiguratorError::required("parameter", "too-bad"))),
+                    .go_results(Err(ConfiguratorError::required(
+                        "parameter",
+                        "too-bad",
+                        INVALID_PARAM_VALUE_ERROR,
+                    ))),
             ))),
         );
         subject.runner = Box::new(runner);
@@ -613,7 +626,8 @@ parm2 - msg2\n"
             Err(RunnerError::Configurator(ConfiguratorError::new(vec![
                 ParamError {
                     parameter: "parameter".to_string(),
-                    reason: "too-bad".to_string()
+                    reason: "too-bad".to_string(),
+                    code: INVALID_PARAM_VALUE_ERROR
                 }
             ])))
         )
@@ -630,6 +644,7 @@ parm2 - msg2\n"
                     .dump_config_result(Err(ConfiguratorError::required(
                         "parameter",
                         "deep-reason",
+                        INVALID_PARAM_VALUE_ERROR,
                     )))
                     .dump_config_params(&dump_config_params_arc),
             )),
@@ -649,7 +664,8 @@ parm2 - msg2\n"
             configurator_error.param_errors[0],
             ParamError {
                 parameter: "parameter".to_string(),
-                reason: "deep-reason".to_string()
+                reason: "deep-reason".to_string(),
+                code: INVALID_PARAM_VALUE_ERROR
             }
         );
         assert_eq!(&holder.stdout.get_string(), "");
@@ -141,6 +141,7 @@ mod tests {
         ServerInitializerFactoryReal,
     };
     use crate::server_initializer::ServerInitializerReal;
+    use masq_lib::constants::INVALID_PARAM_VALUE_ERROR;
     use masq_lib::shared_schema::ConfiguratorError;
     use masq_lib::utils::slice_of_strs_to_vec_of_strings;
     use std::cell::RefCell;
@@ -211,7 +212,11 @@ mod tests {
             Box::new(
                 NodeConfiguratorInitializationMock::default()
                     .configure_params(&configure_params_arc)
-                    .configure_result(Err(ConfiguratorError::required("parameter", "too bad")))
+                    .configure_result(Err(ConfiguratorError::required(
+                        "parameter",
+                        "too bad",
+                        INVALID_PARAM_VALUE_ERROR,
+                    )))
                     .demanded_values_from_multi_config(vec!["ui-port".to_string()]),
             ),
             daemon_clustered_params,
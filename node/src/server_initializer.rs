@@ -7,17 +7,19 @@ use crate::node_configurator::node_configurator_standard::server_initializer_col
 use crate::node_configurator::{DirsWrapper, DirsWrapperReal};
 use crate::run_modes_factories::{RunModeResult, ServerInitializer};
 use crate::sub_lib::socket_server::ConfiguredByPrivilege;
+use crate::sub_lib::supervision::panic_message;
 use backtrace::Backtrace;
 use clap::value_t;
 use flexi_logger::{
-    Cleanup, Criterion, DeferredNow, Duplicate, LevelFilter, LogSpecBuilder, Logger, Naming, Record,
+    Cleanup, Criterion, DeferredNow, Duplicate, LevelFilter, LogSpecBuilder, LogSpecification,
+    Logger, Naming, ReconfigurationHandle, Record,
 };
 use futures::try_ready;
 use lazy_static::lazy_static;
 use log::{log, Level};
 use masq_lib::command::StdStreams;
 use masq_lib::logger;
-use masq_lib::logger::{real_format_function, POINTER_TO_FORMAT_FUNCTION};
+use masq_lib::logger::{LogFormat, LogLevelSpec, POINTER_TO_FORMAT_FUNCTION};
 use masq_lib::shared_schema::ConfiguratorError;
 use std::any::Any;
 use std::io;
@@ -124,6 +126,7 @@ impl ResultsCombiner for RunModeResult {
 
 lazy_static! {
     pub static ref LOGFILE_NAME: Mutex<PathBuf> = Mutex::new(PathBuf::from("uninitialized"));
+    static ref RECONFIGURATION_HANDLE_OPT: Mutex<Option<ReconfigurationHandle>> = Mutex::new(None);
 }
 
 pub trait LoggerInitializerWrapper {
@@ -131,11 +134,41 @@ pub trait LoggerInitializerWrapper {
         &mut self,
         file_path: PathBuf,
         real_user: &RealUser,
-        log_level: LevelFilter,
+        log_level: &LogLevelSpec,
+        log_format: LogFormat,
         discriminant_opt: Option<&str>,
     );
 }
 
+pub trait LogLevelChanger: Send {
+    fn change(&self, log_level: LogLevelSpec);
+}
+
+pub struct LogLevelChangerReal;
+
+impl LogLevelChanger for LogLevelChangerReal {
+    fn change(&self, log_level: LogLevelSpec) {
+        if let Some(handle) = RECONFIGURATION_HANDLE_OPT
+            .lock()
+            .expect("Logger reconfiguration handle poisoned")
+            .as_mut()
+        {
+            handle.set_new_spec(log_specification(&log_level));
+        }
+        logger::set_log_level_spec(log_level);
+    }
+}
+
+// The levels for particular subsystems are applied by the masq_lib Logger, which matches them
+// against its own names; all flexi_logger has to do is let the most verbose of them through.
+fn log_specification(log_level: &LogLevelSpec) -> LogSpecification {
+    LogSpecBuilder::new()
+        .default(log_level.max_level())
+        .module("tokio", LevelFilter::Off)
+        .module("mio", LevelFilter::Off)
+        .build()
+}
+
 pub struct LoggerInitializerWrapperReal;
 
 impl LoggerInitializerWrapper for LoggerInitializerWrapperReal {
@@ -143,31 +176,30 @@ impl LoggerInitializerWrapper for LoggerInitializerWrapperReal {
         &mut self,
         file_path: PathBuf,
         real_user: &RealUser,
-        log_level: LevelFilter,
+        log_level: &LogLevelSpec,
+        log_format: LogFormat,
         discriminant_opt: Option<&str>,
     ) {
-        let mut logger = Logger::with(
-            LogSpecBuilder::new()
-                .default(log_level)
-                .module("tokio", LevelFilter::Off)
-                .module("mio", LevelFilter::Off)
-                .build(),
-        )
-        .log_to_file()
-        .directory(file_path.clone())
-        .print_message()
-        .duplicate_to_stderr(Duplicate::Info)
-        .suppress_timestamp()
-        .format(format_function)
-        .rotate(
-            Criterion::Size(100_000_000),
-            Naming::Numbers,
-            Cleanup::KeepZipFiles(50),
-        );
+        logger::set_log_level_spec(log_level.clone());
+        let mut logger = Logger::with(log_specification(log_level))
+            .log_to_file()
+            .directory(file_path.clone())
+            .print_message()
+            .duplicate_to_stderr(Duplicate::Info)
+            .suppress_timestamp()
+            .format(format_function)
+            .rotate(
+                Criterion::Size(100_000_000),
+                Naming::Numbers,
+                Cleanup::KeepZipFiles(50),
+            );
         if let Some(discriminant) = discriminant_opt {
             logger = logger.discriminant(discriminant);
         }
-        logger.start().expect("Logging subsystem failed to start");
+        let handle = logger.start().expect("Logging subsystem failed to start");
+        *RECONFIGURATION_HANDLE_OPT
+            .lock()
+            .expect("Logger reconfiguration handle poisoned") = Some(handle);
         let privilege_dropper = PrivilegeDropperReal::new();
         let logfile_name = file_path.join(format!(
             "MASQNode_{}rCURRENT.log",
@@ -182,12 +214,19 @@ impl LoggerInitializerWrapper for LoggerInitializerWrapperReal {
             panic_hook(AltPanicInfo::from(panic_info))
         }));
 
+        if log_format == LogFormat::Json {
+            // Even the heading has to be a JSON object for a log aggregation system to take it
+            unsafe {
+                POINTER_TO_FORMAT_FUNCTION = log_format.format_function();
+            }
+        }
+
         // Info level is not shown within the log
         log!(Level::Info, "{}", logger::Logger::log_file_heading());
 
         unsafe {
             // This resets the format function after specialized formatting for the log heading is used.
-            POINTER_TO_FORMAT_FUNCTION = real_format_function;
+            POINTER_TO_FORMAT_FUNCTION = log_format.format_function();
         }
     }
 }
@@ -248,13 +287,7 @@ fn panic_hook(panic_info: AltPanicInfo) {
         None => "<unknown location>".to_string(),
         Some(location) => format!("{}:{}:{}", location.file, location.line, location.col),
     };
-    let message = if let Some(s) = panic_info.payload.downcast_ref::<&str>() {
-        (*s).to_string()
-    } else if let Some(s) = panic_info.payload.downcast_ref::<String>() {
-        s.clone()
-    } else {
-        "<message indecipherable>".to_string()
-    };
+    let message = panic_message(panic_info.payload);
     let logger = masq_lib::logger::Logger::new("PanicHandler");
     error!(logger, "{} - {}", location, message);
     let backtrace = Backtrace::new();
@@ -275,8 +308,8 @@ fn format_function(
 pub mod test_utils {
     use crate::bootstrapper::RealUser;
     use crate::privilege_drop::PrivilegeDropper;
-    use crate::server_initializer::LoggerInitializerWrapper;
-    use log::LevelFilter;
+    use crate::server_initializer::{LogLevelChanger, LoggerInitializerWrapper};
+    use masq_lib::logger::{LogFormat, LogLevelSpec};
     use std::cell::RefCell;
     use std::path::{Path, PathBuf};
     use std::sync::{Arc, Mutex};
@@ -343,8 +376,10 @@ pub mod test_utils {
         }
     }
 
+    pub type LoggerInitializerParams = (PathBuf, RealUser, LogLevelSpec, LogFormat, Option<String>);
+
     pub struct LoggerInitializerWrapperMock {
-        init_parameters: Arc<Mutex<Vec<(PathBuf, RealUser, LevelFilter, Option<String>)>>>,
+        init_parameters: Arc<Mutex<Vec<LoggerInitializerParams>>>,
     }
 
     impl LoggerInitializerWrapper for LoggerInitializerWrapperMock {
@@ -352,13 +387,15 @@ pub mod test_utils {
             &mut self,
             file_path: PathBuf,
             real_user: &RealUser,
-            log_level: LevelFilter,
+            log_level: &LogLevelSpec,
+            log_format: LogFormat,
             name_segment: Option<&str>,
         ) {
             self.init_parameters.lock().unwrap().push((
                 file_path,
                 real_user.clone(),
-                log_level,
+                log_level.clone(),
+                log_format,
                 match name_segment {
                     Some(s) => Some(s.to_string()),
                     None => None,
@@ -376,12 +413,30 @@ pub mod test_utils {
 
         pub fn init_parameters(
             mut self,
-            parameters: &Arc<Mutex<Vec<(PathBuf, RealUser, LevelFilter, Option<String>)>>>,
+            parameters: &Arc<Mutex<Vec<LoggerInitializerParams>>>,
         ) -> Self {
             self.init_parameters = parameters.clone();
             self
         }
     }
+
+    #[derive(Default)]
+    pub struct LogLevelChangerMock {
+        change_params: Arc<Mutex<Vec<LogLevelSpec>>>,
+    }
+
+    impl LogLevelChanger for LogLevelChangerMock {
+        fn change(&self, log_level: LogLevelSpec) {
+            self.change_params.lock().unwrap().push(log_level);
+        }
+    }
+
+    impl LogLevelChangerMock {
+        pub fn change_params(mut self, params: &Arc<Mutex<Vec<LogLevelSpec>>>) -> Self {
+            self.change_params = params.clone();
+            self
+        }
+    }
 }
 
 #[cfg(test)]
@@ -393,7 +448,7 @@ pub mod tests {
     use crate::server_initializer::test_utils::PrivilegeDropperMock;
     use crate::test_utils::logfile_name_guard::LogfileNameGuard;
     use crate::test_utils::unshared_test_utils::make_pre_populated_mocked_directory_wrapper;
-    use masq_lib::constants::DEFAULT_CHAIN;
+    use masq_lib::constants::{DEFAULT_CHAIN, INVALID_PARAM_VALUE_ERROR};
     use masq_lib::crash_point::CrashPoint;
     use masq_lib::multi_config::MultiConfig;
     use masq_lib::shared_schema::{ConfiguratorError, ParamError};
@@ -567,8 +622,8 @@ pub mod tests {
     fn combine_results_combines_success_and_failure() {
         let initial_success: RunModeResult = Ok(());
         let additional_failure: RunModeResult = Err(ConfiguratorError::new(vec![
-            ParamError::new("param-one", "Reason One"),
-            ParamError::new("param-two", "Reason Two"),
+            ParamError::new("param-one", "Reason One", INVALID_PARAM_VALUE_ERROR),
+            ParamError::new("param-two", "Reason Two", INVALID_PARAM_VALUE_ERROR),
         ]));
 
         let result = initial_success.combine_results(additional_failure);
@@ -576,8 +631,8 @@ pub mod tests {
         assert_eq!(
             result,
             Err(ConfiguratorError::new(vec![
-                ParamError::new("param-one", "Reason One"),
-                ParamError::new("param-two", "Reason Two"),
+                ParamError::new("param-one", "Reason One", INVALID_PARAM_VALUE_ERROR),
+                ParamError::new("param-two", "Reason Two", INVALID_PARAM_VALUE_ERROR),
             ]))
         );
     }
@@ -585,8 +640,8 @@ pub mod tests {
     #[test]
     fn combine_results_combines_failure_and_success() {
         let initial_failure: RunModeResult = Err(ConfiguratorError::new(vec![
-            ParamError::new("param-one", "Reason One"),
-            ParamError::new("param-two", "Reason Two"),
+            ParamError::new("param-one", "Reason One", INVALID_PARAM_VALUE_ERROR),
+            ParamError::new("param-two", "Reason Two", INVALID_PARAM_VALUE_ERROR),
         ]));
         let additional_success: RunModeResult = Ok(());
 
@@ -595,8 +650,8 @@ pub mod tests {
         assert_eq!(
             result,
             Err(ConfiguratorError::new(vec![
-                ParamError::new("param-one", "Reason One"),
-                ParamError::new("param-two", "Reason Two"),
+                ParamError::new("param-one", "Reason One", INVALID_PARAM_VALUE_ERROR),
+                ParamError::new("param-two", "Reason Two", INVALID_PARAM_VALUE_ERROR),
             ]))
         );
     }
@@ -604,12 +659,12 @@ pub mod tests {
     #[test]
     fn combine_results_combines_failure_and_failure() {
         let initial_failure: RunModeResult = Err(ConfiguratorError::new(vec![
-            ParamError::new("param-one", "Reason One"),
-            ParamError::new("param-two", "Reason Two"),
+            ParamError::new("param-one", "Reason One", INVALID_PARAM_VALUE_ERROR),
+            ParamError::new("param-two", "Reason Two", INVALID_PARAM_VALUE_ERROR),
         ]));
         let additional_failure: RunModeResult = Err(ConfiguratorError::new(vec![
-            ParamError::new("param-two", "Reason Three"),
-            ParamError::new("param-three", "Reason Four"),
+            ParamError::new("param-two", "Reason Three", INVALID_PARAM_VALUE_ERROR),
+            ParamError::new("param-three", "Reason Four", INVALID_PARAM_VALUE_ERROR),
         ]));
 
         let result = initial_failure.combine_results(additional_failure);
@@ -617,10 +672,10 @@ pub mod tests {
         assert_eq!(
             result,
             Err(ConfiguratorError::new(vec![
-                ParamError::new("param-one", "Reason One"),
-                ParamError::new("param-two", "Reason Two"),
-                ParamError::new("param-two", "Reason Three"),
-                ParamError::new("param-three", "Reason Four"),
+                ParamError::new("param-one", "Reason One", INVALID_PARAM_VALUE_ERROR),
+                ParamError::new("param-two", "Reason Two", INVALID_PARAM_VALUE_ERROR),
+                ParamError::new("param-two", "Reason Three", INVALID_PARAM_VALUE_ERROR),
+                ParamError::new("param-three", "Reason Four", INVALID_PARAM_VALUE_ERROR),
             ]))
         );
     }
@@ -864,19 +919,23 @@ pub mod tests {
             .initialize_as_privileged_result(Err(ConfiguratorError::required(
                 "dns-iap",
                 "dns-iap-reason",
+                INVALID_PARAM_VALUE_ERROR,
             )))
             .initialize_as_unprivileged_result(Err(ConfiguratorError::required(
                 "dns-iau",
                 "dns-iau-reason",
+                INVALID_PARAM_VALUE_ERROR,
             )));
         let bootstrapper = ConfiguredByPrivilegeMock::default()
             .initialize_as_privileged_result(Err(ConfiguratorError::required(
                 "boot-iap",
                 "boot-iap-reason",
+                INVALID_PARAM_VALUE_ERROR,
             )))
             .initialize_as_unprivileged_result(Err(ConfiguratorError::required(
                 "boot-iau",
                 "boot-iau-reason",
+                INVALID_PARAM_VALUE_ERROR,
             )));
         let privilege_dropper = PrivilegeDropperMock::new();
         let mut subject = ServerInitializerReal {
@@ -897,10 +956,10 @@ pub mod tests {
         assert_eq!(
             result,
             Err(ConfiguratorError::new(vec![
-                // ParamError::new("dns-iap", "dns-iap-reason"),
-                ParamError::new("boot-iap", "boot-iap-reason"),
-                // ParamError::new("dns-iau", "dns-iau-reason"),
-                ParamError::new("boot-iau", "boot-iau-reason")
+                // ParamError::new("dns-iap", "dns-iap-reason", INVALID_PARAM_VALUE_ERROR),
+                ParamError::new("boot-iap", "boot-iap-reason", INVALID_PARAM_VALUE_ERROR),
+                // ParamError::new("dns-iau", "dns-iau-reason", INVALID_PARAM_VALUE_ERROR),
+                ParamError::new("boot-iau", "boot-iau-reason", INVALID_PARAM_VALUE_ERROR)
             ]))
         );
     }
@@ -25,7 +25,7 @@ use crate::sub_lib::node_addr::NodeAddr;
 use crate::sub_lib::sequence_buffer::SequencedPacket;
 use crate::sub_lib::stream_connector::ConnectionInfo;
 use crate::sub_lib::stream_connector::StreamConnector;
-use crate::sub_lib::stream_connector::StreamConnectorReal;
+use crate::sub_lib::stream_connector::{SocketTuning, StreamConnectorReal};
 use crate::sub_lib::stream_handler_pool::DispatcherNodeQueryResponse;
 use crate::sub_lib::stream_handler_pool::TransmitDataMsg;
 use crate::sub_lib::tokio_wrappers::ReadHalfWrapper;
@@ -194,6 +194,7 @@ impl StreamHandlerPool {
     pub fn new(
         clandestine_discriminator_factories: Vec<Box<dyn DiscriminatorFactory>>,
         crashable: bool,
+        socket_tuning: SocketTuning,
     ) -> StreamHandlerPool {
         StreamHandlerPool {
             stream_writers: HashMap::new(),
@@ -204,7 +205,7 @@ impl StreamHandlerPool {
             connection_progress_sub_opt: None,
             logger: Logger::new("Dispatcher"),
             crashable,
-            stream_connector: Box::new(StreamConnectorReal {}),
+            stream_connector: Box::new(StreamConnectorReal::new(socket_tuning)),
             channel_factory: Box::new(FuturesChannelFactoryReal {}),
             clandestine_discriminator_factories,
             traffic_analyzer: Box::new(TrafficAnalyzerReal {}),
@@ -861,7 +862,7 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(vec![], false, SocketTuning::default());
             subject.stream_connector = Box::new(StreamConnectorMock::new());
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -982,7 +983,7 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let subject = StreamHandlerPool::new(vec![], false);
+            let subject = StreamHandlerPool::new(vec![], false, SocketTuning::default());
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -1046,7 +1047,7 @@ mod tests {
         thread::spawn(move || {
             let system = System::new("test");
 
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(vec![], false, SocketTuning::default());
             subject.stream_connector = Box::new(
                 StreamConnectorMock::new()
                     .connect_pair_result(Err(Error::from(ErrorKind::ConnectionRefused))),
@@ -1150,8 +1151,11 @@ mod tests {
         thread::spawn(move || {
             let system = System::new("test");
 
-            let mut subject =
-                StreamHandlerPool::new(vec![Box::new(JsonDiscriminatorFactory {})], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![Box::new(JsonDiscriminatorFactory {})],
+                false,
+                SocketTuning::default(),
+            );
             subject.stream_connector = Box::new(StreamConnectorMock::new().connection(
                 local_addr,
                 peer_addr,
@@ -1223,7 +1227,7 @@ mod tests {
         let (recorder, _, recording_arc) = make_recorder();
         let system = System::new("test");
         let sub = recorder.start().recipient::<StreamShutdownMsg>();
-        let mut subject = StreamHandlerPool::new(vec![], false);
+        let mut subject = StreamHandlerPool::new(vec![], false, SocketTuning::default());
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
         let local_addr = SocketAddr::from_str("127.0.0.1:0").unwrap();
         let sw_key = StreamWriterKey::from(peer_addr);
@@ -1259,7 +1263,7 @@ mod tests {
         let (recorder, _, recording_arc) = make_recorder();
         let system = System::new("test");
         let sub = recorder.start().recipient::<StreamShutdownMsg>();
-        let mut subject = StreamHandlerPool::new(vec![], false);
+        let mut subject = StreamHandlerPool::new(vec![], false, SocketTuning::default());
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
         let local_addr = SocketAddr::from_str("127.0.0.1:0").unwrap();
         let sw_key = StreamWriterKey::from(peer_addr);
@@ -1299,7 +1303,7 @@ mod tests {
         let (recorder, _, recording_arc) = make_recorder();
         let system = System::new(test_name);
         let sub = recorder.start().recipient::<StreamShutdownMsg>();
-        let mut subject = StreamHandlerPool::new(vec![], false);
+        let mut subject = StreamHandlerPool::new(vec![], false, SocketTuning::default());
         subject.logger = Logger::new(test_name);
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
         let local_addr = SocketAddr::from_str("127.0.0.1:0").unwrap();
@@ -1344,7 +1348,7 @@ mod tests {
         let (neighborhood, neighborhood_awaiter, neighborhood_recording_arc) = make_recorder();
         thread::spawn(move || {
             let system = System::new("when_stream_handler_pool_fails_to_create_nonexistent_stream_for_write_then_it_logs_and_notifies_neighborhood");
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(vec![], false, SocketTuning::default());
             subject.stream_connector = Box::new(
                 StreamConnectorMock::new()
                     .connect_pair_result(Err(Error::from(ErrorKind::Other)))
@@ -1419,7 +1423,11 @@ mod tests {
                 "stream_handler_pool_creates_nonexistent_stream_for_reading_and_writing",
             );
             let discriminator_factory = JsonDiscriminatorFactory::new();
-            let mut subject = StreamHandlerPool::new(vec![Box::new(discriminator_factory)], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![Box::new(discriminator_factory)],
+                false,
+                SocketTuning::default(),
+            );
             subject.stream_connector = Box::new(
                 StreamConnectorMock::new().connect_pair_result(Ok(ConnectionInfo {
                     reader: Box::new(
@@ -1539,7 +1547,7 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let subject = StreamHandlerPool::new(vec![], false);
+            let subject = StreamHandlerPool::new(vec![], false, SocketTuning::default());
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -1620,7 +1628,7 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let subject = StreamHandlerPool::new(vec![], false);
+            let subject = StreamHandlerPool::new(vec![], false, SocketTuning::default());
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -1668,7 +1676,7 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let subject = StreamHandlerPool::new(vec![], false);
+            let subject = StreamHandlerPool::new(vec![], false, SocketTuning::default());
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -1732,7 +1740,7 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(vec![], false, SocketTuning::default());
             subject
                 .stream_writers
                 .insert(StreamWriterKey::from(peer_addr), None);
@@ -1822,7 +1830,7 @@ mod tests {
         let sender_wrapper = SenderWrapperMock::new(peer_addr)
             .unbounded_send_params(&sender_wrapper_unbounded_send_params_arc)
             .unbounded_send_result(Err(send_error));
-        let mut subject = StreamHandlerPool::new(vec![], false);
+        let mut subject = StreamHandlerPool::new(vec![], false, SocketTuning::default());
         subject
             .stream_writers
             .insert(sw_key, Some(Box::new(sender_wrapper)));
@@ -1895,7 +1903,7 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(vec![], false, SocketTuning::default());
             subject.stream_connector = Box::new(
                 StreamConnectorMock::new()
                     .connect_pair_result(Err(Error::from(ErrorKind::Other)))
@@ -1975,7 +1983,7 @@ mod tests {
         let sender_wrapper = SenderWrapperMock::new(peer_addr)
             .unbounded_send_params(&sender_wrapper_unbounded_send_params_arc)
             .unbounded_send_result(Ok(()));
-        let mut subject = StreamHandlerPool::new(vec![], false);
+        let mut subject = StreamHandlerPool::new(vec![], false, SocketTuning::default());
         subject.stream_writers.insert(
             StreamWriterKey::from(peer_addr),
             Some(Box::new(sender_wrapper)),
@@ -2035,7 +2043,7 @@ mod tests {
         };
 
         let system = System::new("test");
-        let subject = StreamHandlerPool::new(vec![], false);
+        let subject = StreamHandlerPool::new(vec![], false, SocketTuning::default());
         let subject_addr: Addr<StreamHandlerPool> = subject.start();
         let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
         let peer_actors = peer_actors_builder().build();
@@ -2083,7 +2091,7 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let subject = StreamHandlerPool::new(vec![], false);
+            let subject = StreamHandlerPool::new(vec![], false, SocketTuning::default());
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -2156,7 +2164,7 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(vec![], false, SocketTuning::default());
             subject.traffic_analyzer = Box::new(TrafficAnalyzerMock {});
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
@@ -2217,7 +2225,11 @@ mod tests {
                 "stream_handler_pool_creates_nonexistent_stream_for_reading_and_writing",
             );
             let discriminator_factory = JsonDiscriminatorFactory::new();
-            let mut subject = StreamHandlerPool::new(vec![Box::new(discriminator_factory)], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![Box::new(discriminator_factory)],
+                false,
+                SocketTuning::default(),
+            );
             subject.stream_connector = Box::new(StreamConnectorMock::new()); // this will panic if a connection is attempted
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -2264,7 +2276,7 @@ mod tests {
         expected = "panic message (processed with: node_lib::sub_lib::utils::crash_request_analyzer)"
     )]
     fn stream_handler_can_be_crashed_properly_but_not_improperly() {
-        let stream_handler_pool = StreamHandlerPool::new(vec![], true);
+        let stream_handler_pool = StreamHandlerPool::new(vec![], true, SocketTuning::default());
 
         prove_that_crash_request_handler_is_hooked_up(stream_handler_pool, CRASH_KEY);
     }
@@ -1,24 +1,33 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::accountant::db_access_objects::address_book_dao::AddressBookDaoFactory;
+use crate::accountant::db_access_objects::audit_log_dao::AuditLogDaoFactory;
 use crate::accountant::db_access_objects::banned_dao::BannedDaoFactory;
+use crate::accountant::db_access_objects::earnings_history_dao::EarningsHistoryDaoFactory;
+use crate::accountant::db_access_objects::maintenance_dao::MaintenanceDaoFactory;
 use crate::accountant::db_access_objects::payable_dao::PayableDaoFactory;
 use crate::accountant::db_access_objects::pending_payable_dao::PendingPayableDaoFactory;
 use crate::accountant::db_access_objects::receivable_dao::ReceivableDaoFactory;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::BlockchainAgentWithContextMessage;
 use crate::accountant::{
-    checked_conversion, Accountant, ReceivedPayments, ReportTransactionReceipts, ScanError,
-    SentPayables,
+    checked_conversion, Accountant, ReceivedPayments, ReportPaymentReceipt,
+    ReportTransactionReceipts, ScanError, SentPayables, DEFAULT_MAX_PENDING_ATTEMPTS,
+    DEFAULT_PENDING_TOO_LONG_SEC,
 };
 use crate::actor_system_factory::SubsFactory;
 use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
 use crate::db_config::config_dao::ConfigDaoFactory;
+use crate::sub_lib::hopper::MessageType;
 use crate::sub_lib::neighborhood::ConfigChangeMsg;
 use crate::sub_lib::peer_actors::{BindMessage, StartMessage};
+use crate::sub_lib::versioned_data::VersionedData;
 use crate::sub_lib::wallet::Wallet;
 use actix::Recipient;
 use actix::{Addr, Message};
 use lazy_static::lazy_static;
 use masq_lib::ui_gateway::NodeFromUiMessage;
-use std::fmt::{Debug, Formatter};
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::time::{Duration, SystemTime};
@@ -70,6 +79,10 @@ impl PaymentThresholds {
 }
 
 pub struct DaoFactories {
+    pub address_book_dao_factory: Box<dyn AddressBookDaoFactory>,
+    pub audit_log_dao_factory: Box<dyn AuditLogDaoFactory>,
+    pub earnings_history_dao_factory: Box<dyn EarningsHistoryDaoFactory>,
+    pub maintenance_dao_factory: Box<dyn MaintenanceDaoFactory>,
     pub payable_dao_factory: Box<dyn PayableDaoFactory>,
     pub pending_payable_dao_factory: Box<dyn PendingPayableDaoFactory>,
     pub receivable_dao_factory: Box<dyn ReceivableDaoFactory>,
@@ -90,6 +103,99 @@ impl Default for ScanIntervals {
     }
 }
 
+// A pending payable whose transaction has not been seen on the blockchain after this many
+// receipt checks, or for this long, is given up as failed
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct PendingPayableLimits {
+    pub max_attempts: u16,
+    pub max_age_sec: u64,
+}
+
+impl Default for PendingPayableLimits {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_PENDING_ATTEMPTS,
+            max_age_sec: DEFAULT_PENDING_TOO_LONG_SEC,
+        }
+    }
+}
+
+impl Display for PendingPayableLimits {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}|{}", self.max_attempts, self.max_age_sec)
+    }
+}
+
+impl FromStr for PendingPayableLimits {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut segments = s.split('|');
+        match (segments.next(), segments.next(), segments.next()) {
+            (Some(max_attempts), Some(max_age_sec), None) => {
+                match (max_attempts.parse::<u16>(), max_age_sec.parse::<u64>()) {
+                    (Ok(max_attempts), Ok(max_age_sec)) if max_attempts > 0 && max_age_sec > 0 => {
+                        Ok(Self {
+                            max_attempts,
+                            max_age_sec,
+                        })
+                    }
+                    _ => Err(format!(
+                        "Bad pending-payable-limits value '{}': both limits must be positive integers",
+                        s
+                    )),
+                }
+            }
+            _ => Err(format!(
+                "Bad pending-payable-limits value '{}': expected <max attempts>|<max age in seconds>",
+                s
+            )),
+        }
+    }
+}
+
+// Any receivable at least this old and this large, or payables adding up to more than this, make
+// the Accountant raise an alert
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct AlertThresholds {
+    pub receivable_age_sec: u64,
+    pub receivable_balance_gwei: u64,
+    pub total_payables_gwei: u64,
+}
+
+impl Display for AlertThresholds {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}|{}|{}",
+            self.receivable_age_sec, self.receivable_balance_gwei, self.total_payables_gwei
+        )
+    }
+}
+
+impl FromStr for AlertThresholds {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = s
+            .split('|')
+            .map(|segment| segment.parse::<u64>())
+            .collect::<Result<Vec<u64>, _>>();
+        match values.as_deref() {
+            Ok([age, balance, total]) if *age > 0 && *balance > 0 && *total > 0 => Ok(Self {
+                receivable_age_sec: *age,
+                receivable_balance_gwei: *balance,
+                total_payables_gwei: *total,
+            }),
+            _ => Err(format!(
+                "Bad alert-thresholds value '{}': expected three positive integers, \
+                 <receivable age in seconds>|<receivable balance in gwei>|<total payables in gwei>",
+                s
+            )),
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub struct AccountantSubs {
     pub bind: Recipient<BindMessage>,
@@ -102,9 +208,13 @@ pub struct AccountantSubs {
     pub report_inbound_payments: Recipient<ReceivedPayments>,
     pub init_pending_payable_fingerprints: Recipient<PendingPayableFingerprintSeeds>,
     pub report_transaction_receipts: Recipient<ReportTransactionReceipts>,
+    pub report_payment_receipt: Recipient<ReportPaymentReceipt>,
     pub report_sent_payments: Recipient<SentPayables>,
     pub scan_errors: Recipient<ScanError>,
     pub ui_message_sub: Recipient<NodeFromUiMessage>,
+    pub creditors_to_avoid_query: Recipient<CreditorsToAvoidQuery>,
+    pub shutdown_report_query: Recipient<ShutdownReportQuery>,
+    pub report_payment_overdue: Recipient<ReportPaymentOverdueMessage>,
 }
 
 impl Debug for AccountantSubs {
@@ -129,6 +239,9 @@ pub struct ReportRoutingServiceProvidedMessage {
     pub payload_size: usize,
     pub service_rate: u64,
     pub byte_rate: u64,
+    // Set when routing is accounted for by sampling: this message stands for that many CORES
+    // packages, each assumed to be as large as this one
+    pub sample_size_opt: Option<u32>,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Message)]
@@ -163,6 +276,55 @@ pub struct ExitServiceConsumed {
     pub byte_rate: u64,
 }
 
+// Asks which creditors this Node has owed so much, or for so long, that they have probably banned
+// it; the answer goes to the recipient
+#[derive(Clone, PartialEq, Eq, Message)]
+pub struct CreditorsToAvoidQuery {
+    pub recipient: Recipient<CreditorsToAvoid>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Message)]
+pub struct CreditorsToAvoid {
+    pub wallets: Vec<Wallet>,
+}
+
+// Asks for a final financial summary to be logged and left in the data directory, because the Node
+// is about to shut down; the recipient hears when it's done
+#[derive(Clone, PartialEq, Eq, Message)]
+pub struct ShutdownReportQuery {
+    pub recipient: Recipient<ShutdownReportDone>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Message)]
+pub struct ShutdownReportDone {}
+
+// What a creditor tells a debtor before banning it: how much is overdue, and when (in seconds
+// since the epoch) the ban will come if it still isn't paid
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[allow(non_camel_case_types)]
+pub struct PaymentOverdue_0v1 {
+    pub balance_gwei: u64,
+    pub deadline: u64,
+}
+
+// A PaymentOverdue notice that came back over one of this Node's own routes. The creditor is the
+// exit Node of that route, whatever else the notice might suggest.
+#[derive(Clone, PartialEq, Eq, Debug, Message)]
+pub struct ReportPaymentOverdueMessage {
+    pub timestamp: SystemTime,
+    pub creditor_wallet: Wallet,
+    pub notice: PaymentOverdue_0v1,
+}
+
+impl From<PaymentOverdue_0v1> for MessageType {
+    fn from(data: PaymentOverdue_0v1) -> Self {
+        MessageType::PaymentOverdue(VersionedData::new(
+            &crate::sub_lib::migrations::payment_overdue::MIGRATIONS,
+            &data,
+        ))
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, Default)]
 pub struct FinancialStatistics {
     pub total_paid_payable_wei: u128,
@@ -196,9 +358,10 @@ mod tests {
     use crate::accountant::test_utils::AccountantBuilder;
     use crate::accountant::{checked_conversion, Accountant};
     use crate::sub_lib::accountant::{
-        AccountantSubsFactoryReal, MessageIdGenerator, MessageIdGeneratorReal, PaymentThresholds,
-        ScanIntervals, SubsFactory, DEFAULT_EARNING_WALLET, DEFAULT_PAYMENT_THRESHOLDS,
-        DEFAULT_SCAN_INTERVALS, MSG_ID_INCREMENTER, TEMPORARY_CONSUMING_WALLET,
+        AccountantSubsFactoryReal, AlertThresholds, MessageIdGenerator, MessageIdGeneratorReal,
+        PaymentThresholds, PendingPayableLimits, ScanIntervals, SubsFactory,
+        DEFAULT_EARNING_WALLET, DEFAULT_PAYMENT_THRESHOLDS, DEFAULT_SCAN_INTERVALS,
+        MSG_ID_INCREMENTER, TEMPORARY_CONSUMING_WALLET,
     };
     use crate::sub_lib::wallet::Wallet;
     use crate::test_utils::recorder::{make_accountant_subs_from_recorder, Recorder};
@@ -244,6 +407,105 @@ mod tests {
         )
     }
 
+    #[test]
+    fn pending_payable_limits_default_to_the_accountant_constants() {
+        let result = PendingPayableLimits::default();
+
+        assert_eq!(
+            result,
+            PendingPayableLimits {
+                max_attempts: 50,
+                max_age_sec: 21_600,
+            }
+        );
+        assert_eq!(result.to_string(), "50|21600");
+    }
+
+    #[test]
+    fn pending_payable_limits_can_be_parsed_from_their_string_form() {
+        let result = PendingPayableLimits::from_str("12|3600");
+
+        assert_eq!(
+            result,
+            Ok(PendingPayableLimits {
+                max_attempts: 12,
+                max_age_sec: 3600,
+            })
+        );
+    }
+
+    #[test]
+    fn pending_payable_limits_reject_malformed_strings() {
+        vec!["12", "12|3600|1", "12,3600", ""]
+            .into_iter()
+            .for_each(|value| {
+                assert_eq!(
+                    PendingPayableLimits::from_str(value),
+                    Err(format!(
+                        "Bad pending-payable-limits value '{}': expected <max attempts>|<max age in seconds>",
+                        value
+                    )),
+                    "{}",
+                    value
+                )
+            });
+        vec!["0|3600", "12|0", "twelve|3600", "70000|3600", "12|-1"]
+            .into_iter()
+            .for_each(|value| {
+                assert_eq!(
+                    PendingPayableLimits::from_str(value),
+                    Err(format!(
+                        "Bad pending-payable-limits value '{}': both limits must be positive integers",
+                        value
+                    )),
+                    "{}",
+                    value
+                )
+            });
+    }
+
+    #[test]
+    fn alert_thresholds_can_be_parsed_from_their_string_form() {
+        let result = AlertThresholds::from_str("86400|1000000000|5000000000");
+
+        assert_eq!(
+            result,
+            Ok(AlertThresholds {
+                receivable_age_sec: 86400,
+                receivable_balance_gwei: 1_000_000_000,
+                total_payables_gwei: 5_000_000_000,
+            })
+        );
+        assert_eq!(result.unwrap().to_string(), "86400|1000000000|5000000000");
+    }
+
+    #[test]
+    fn alert_thresholds_reject_malformed_strings() {
+        vec![
+            "86400|1000",
+            "86400|1000|5000|1",
+            "0|1000|5000",
+            "86400|0|5000",
+            "86400|1000|0",
+            "a day|1000|5000",
+            "86400,1000,5000",
+            "",
+        ]
+        .into_iter()
+        .for_each(|value| {
+            assert_eq!(
+                AlertThresholds::from_str(value),
+                Err(format!(
+                    "Bad alert-thresholds value '{}': expected three positive integers, \
+                     <receivable age in seconds>|<receivable balance in gwei>|<total payables in gwei>",
+                    value
+                )),
+                "{}",
+                value
+            )
+        });
+    }
+
     #[test]
     fn accountant_subs_debug() {
         let addr = Recorder::new().start();
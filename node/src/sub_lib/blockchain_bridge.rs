@@ -12,15 +12,96 @@ use masq_lib::blockchains::chains::Chain;
 use masq_lib::ui_gateway::NodeFromUiMessage;
 use std::fmt;
 use std::fmt::{Debug, Formatter};
-use web3::types::U256;
+use std::str::FromStr;
+use web3::types::{Address, U256};
 
 #[derive(Clone, PartialEq, Eq, Debug, Default)]
 pub struct BlockchainBridgeConfig {
     pub blockchain_service_url_opt: Option<String>,
     pub chain: Chain,
+    // Replaces the chain's own token contract, for private forks and new deployments
+    pub token_contract_address_opt: Option<Address>,
     // TODO: totally ignored during the setup of the BlockchainBridge actor!
     // Use it in the body or delete this field
     pub gas_price: u64,
+    pub failover_opt: Option<BlockchainServiceFailover>,
+}
+
+// The blockchain service counts as failing once failure_threshold requests in a row have failed
+// within failure_window_minutes; the BlockchainBridge then moves on to the next alternate URL
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BlockchainServiceFailover {
+    pub failure_threshold: usize,
+    pub failure_window_minutes: u64,
+    pub pause_payables: bool,
+    pub alternate_urls: Vec<String>,
+}
+
+impl fmt::Display for BlockchainServiceFailover {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}|{}|{}",
+            self.failure_threshold,
+            self.failure_window_minutes,
+            if self.pause_payables {
+                "pause"
+            } else {
+                "continue"
+            }
+        )?;
+        if !self.alternate_urls.is_empty() {
+            write!(f, "|{}", self.alternate_urls.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for BlockchainServiceFailover {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let segments = s.split('|').collect::<Vec<&str>>();
+        let (threshold_str, window_str, payables_str, urls_opt) = match segments.as_slice() {
+            [threshold, window, payables] => (*threshold, *window, *payables, None),
+            [threshold, window, payables, urls] => (*threshold, *window, *payables, Some(*urls)),
+            _ => return Err(Self::complaint(s)),
+        };
+        let pause_payables = match payables_str {
+            "pause" => true,
+            "continue" => false,
+            _ => return Err(Self::complaint(s)),
+        };
+        let alternate_urls = match urls_opt {
+            Some(urls) => urls.split(',').map(|url| url.trim().to_string()).collect(),
+            None => vec![],
+        };
+        match (threshold_str.parse::<usize>(), window_str.parse::<u64>()) {
+            (Ok(failure_threshold), Ok(failure_window_minutes))
+                if failure_threshold > 0
+                    && failure_window_minutes > 0
+                    && alternate_urls.iter().all(|url: &String| !url.is_empty()) =>
+            {
+                Ok(Self {
+                    failure_threshold,
+                    failure_window_minutes,
+                    pause_payables,
+                    alternate_urls,
+                })
+            }
+            _ => Err(Self::complaint(s)),
+        }
+    }
+}
+
+impl BlockchainServiceFailover {
+    fn complaint(s: &str) -> String {
+        format!(
+            "Bad blockchain-service-failover value '{}': expected \
+             <failures>|<minutes>|<pause or continue>[|<alternate URL>,<alternate URL>,...]",
+            s
+        )
+    }
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -86,9 +167,79 @@ mod tests {
     use crate::actor_system_factory::SubsFactory;
     use crate::blockchain::blockchain_bridge::{BlockchainBridge, BlockchainBridgeSubsFactoryReal};
     use crate::blockchain::test_utils::BlockchainInterfaceMock;
+    use crate::sub_lib::blockchain_bridge::BlockchainServiceFailover;
     use crate::test_utils::persistent_configuration_mock::PersistentConfigurationMock;
     use crate::test_utils::recorder::{make_blockchain_bridge_subs_from_recorder, Recorder};
     use actix::Actor;
+    use std::str::FromStr;
+
+    #[test]
+    fn blockchain_service_failover_can_be_parsed_from_its_string_form() {
+        let result = BlockchainServiceFailover::from_str(
+            "5|10|pause|https://polygon-rpc.com,http://localhost:8545",
+        );
+
+        assert_eq!(
+            result,
+            Ok(BlockchainServiceFailover {
+                failure_threshold: 5,
+                failure_window_minutes: 10,
+                pause_payables: true,
+                alternate_urls: vec![
+                    "https://polygon-rpc.com".to_string(),
+                    "http://localhost:8545".to_string()
+                ],
+            })
+        );
+        assert_eq!(
+            result.unwrap().to_string(),
+            "5|10|pause|https://polygon-rpc.com,http://localhost:8545"
+        );
+    }
+
+    #[test]
+    fn blockchain_service_failover_needs_no_alternate_urls() {
+        let result = BlockchainServiceFailover::from_str("3|60|continue");
+
+        assert_eq!(
+            result,
+            Ok(BlockchainServiceFailover {
+                failure_threshold: 3,
+                failure_window_minutes: 60,
+                pause_payables: false,
+                alternate_urls: vec![],
+            })
+        );
+        assert_eq!(result.unwrap().to_string(), "3|60|continue");
+    }
+
+    #[test]
+    fn blockchain_service_failover_rejects_malformed_strings() {
+        vec![
+            "5|10",
+            "0|10|pause",
+            "5|0|pause",
+            "five|10|pause",
+            "5|10|stop",
+            "5|10|pause|",
+            "5|10|pause|https://polygon-rpc.com,",
+            "5|10|pause|https://polygon-rpc.com|http://localhost:8545",
+            "",
+        ]
+        .into_iter()
+        .for_each(|value| {
+            assert_eq!(
+                BlockchainServiceFailover::from_str(value),
+                Err(format!(
+                    "Bad blockchain-service-failover value '{}': expected \
+                     <failures>|<minutes>|<pause or continue>[|<alternate URL>,<alternate URL>,...]",
+                    value
+                )),
+                "{}",
+                value
+            )
+        });
+    }
 
     #[test]
     fn blockchain_bridge_subs_debug() {
@@ -1,6 +1,7 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use crate::sub_lib::peer_actors::BindMessage;
+use actix::Message;
 use actix::Recipient;
 use masq_lib::ui_gateway::NodeFromUiMessage;
 use std::fmt;
@@ -10,8 +11,13 @@ use std::fmt::{Debug, Formatter};
 pub struct ConfiguratorSubs {
     pub bind: Recipient<BindMessage>,
     pub node_from_ui_sub: Recipient<NodeFromUiMessage>,
+    pub reload_config_file: Recipient<ReloadConfigFile>,
 }
 
+// Asks the Configurator to re-read the config file and apply whatever can change while running
+#[derive(Clone, Debug, Message, PartialEq, Eq)]
+pub struct ReloadConfigFile {}
+
 impl Debug for ConfiguratorSubs {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "ConfiguratorSubs")
@@ -31,6 +37,7 @@ mod tests {
         let subject = ConfiguratorSubs {
             bind: recipient!(recorder, BindMessage),
             node_from_ui_sub: recipient!(recorder, NodeFromUiMessage),
+            reload_config_file: recipient!(recorder, ReloadConfigFile),
         };
 
         assert_eq!(format!("{:?}", subject), "ConfiguratorSubs");
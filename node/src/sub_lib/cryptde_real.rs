@@ -33,6 +33,9 @@ pub struct CryptDEReal {
 }
 
 impl CryptDE for CryptDEReal {
+    // A sealed box encrypts under a fresh ephemeral key every time, so no key is ever shared by
+    // more than one CORES package: routes carry no long-lived session key that could need
+    // periodic re-keying.
     fn encode(&self, key: &PublicKey, data: &PlainData) -> Result<CryptData, CryptdecError> {
         if key.len() != cxsp::PUBLICKEYBYTES + signing::PUBLICKEYBYTES {
             return Err(CryptdecError::InvalidKey(format!("{:?}", key.as_slice())));
@@ -193,6 +196,30 @@ impl CryptDEReal {
     pub fn new(chain: Chain) -> Self {
         let (e_public, e_secret) = encryption::gen_keypair();
         let (s_public, s_secret) = signing::gen_keypair();
+        Self::from_key_pairs((e_public, e_secret), (s_public, s_secret), chain)
+    }
+
+    // The same seed always produces the same keys, so that tests that need real cryptography can
+    // still be rerun with the same public keys, descriptors and routes. Never for production use.
+    #[cfg(any(test, feature = "expose_test_privates"))]
+    pub fn from_seed(seed: &[u8], chain: Chain) -> Self {
+        let derive = |purpose: &[u8]| {
+            let digest = hash::hash(&[seed, purpose].concat());
+            let mut seed_bytes = [0u8; 32];
+            seed_bytes.copy_from_slice(&digest.0[..32]);
+            seed_bytes
+        };
+        let encryption_pair =
+            encryption::keypair_from_seed(&encryption::Seed(derive(b"encryption")));
+        let signing_pair = signing::keypair_from_seed(&signing::Seed(derive(b"signing")));
+        Self::from_key_pairs(encryption_pair, signing_pair, chain)
+    }
+
+    fn from_key_pairs(
+        (e_public, e_secret): (encryption::PublicKey, encryption::SecretKey),
+        (s_public, s_secret): (signing::PublicKey, signing::SecretKey),
+        chain: Chain,
+    ) -> Self {
         let public_key = Self::local_public_key_from(&e_public, &s_public);
         let digest = cryptde::create_digest(&public_key, &chain.rec().contract);
         let pre_shared_data = chain.rec().contract.0;
@@ -252,6 +279,30 @@ mod tests {
         assert_ne!(first_subject.public_key(), second_subject.public_key());
     }
 
+    #[test]
+    fn construction_from_the_same_seed_generates_the_same_keys() {
+        let first_subject = CryptDEReal::from_seed(b"booga", TEST_DEFAULT_CHAIN);
+        let second_subject = CryptDEReal::from_seed(b"booga", TEST_DEFAULT_CHAIN);
+        let other_subject = CryptDEReal::from_seed(b"agoob", TEST_DEFAULT_CHAIN);
+
+        assert_eq!(first_subject.public_key(), second_subject.public_key());
+        assert_eq!(first_subject.digest(), second_subject.digest());
+        assert_ne!(first_subject.public_key(), other_subject.public_key());
+    }
+
+    #[test]
+    fn construction_from_a_seed_produces_working_keys() {
+        let subject = CryptDEReal::from_seed(b"booga", TEST_DEFAULT_CHAIN);
+        let plain_data = PlainData::new(b"Fourscore and seven years ago");
+
+        let encrypted = subject.encode(subject.public_key(), &plain_data).unwrap();
+        let decrypted = subject.decode(&encrypted).unwrap();
+        let signature = subject.sign(&plain_data).unwrap();
+
+        assert_eq!(decrypted, plain_data);
+        assert!(subject.verify_signature(&plain_data, &signature, subject.public_key()));
+    }
+
     #[test]
     fn dup_produces_identical_keys() {
         let subject = CryptDEReal::default();
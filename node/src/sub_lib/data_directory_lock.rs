@@ -0,0 +1,203 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process;
+
+pub const DATA_DIRECTORY_LOCK_FILE_NAME: &str = "node.lock";
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum DataDirectoryLockError {
+    HeldBy(u32),
+    Io(String),
+}
+
+impl Display for DataDirectoryLockError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataDirectoryLockError::HeldBy(process_id) => write!(
+                f,
+                "Another Node (process {}) is already running against this data directory",
+                process_id
+            ),
+            DataDirectoryLockError::Io(msg) => {
+                write!(f, "Couldn't lock the data directory: {}", msg)
+            }
+        }
+    }
+}
+
+// Two Nodes sharing a data directory would silently corrupt each other's database, so a Node
+// holds this lock for as long as it runs. A Node that dies without releasing it leaves the lock
+// behind; the lock is considered stale, and is taken over, once its process is gone.
+#[derive(Debug)]
+pub struct DataDirectoryLock {
+    path: PathBuf,
+}
+
+impl Drop for DataDirectoryLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+impl DataDirectoryLock {
+    pub fn acquire(
+        data_directory: &Path,
+        process_is_running: &dyn Fn(u32) -> bool,
+    ) -> Result<Self, DataDirectoryLockError> {
+        fs::create_dir_all(data_directory)
+            .map_err(|e| DataDirectoryLockError::Io(e.to_string()))?;
+        let path = data_directory.join(DATA_DIRECTORY_LOCK_FILE_NAME);
+        match Self::create(&path) {
+            Ok(()) => return Ok(Self { path }),
+            Err(e) if e.kind() != io::ErrorKind::AlreadyExists => {
+                return Err(DataDirectoryLockError::Io(e.to_string()))
+            }
+            Err(_) => (),
+        }
+        if let Some(process_id) = Self::holder(data_directory, process_is_running) {
+            if process_id != process::id() {
+                return Err(DataDirectoryLockError::HeldBy(process_id));
+            }
+        }
+        fs::remove_file(&path).map_err(|e| DataDirectoryLockError::Io(e.to_string()))?;
+        Self::create(&path).map_err(|e| match e.kind() {
+            io::ErrorKind::AlreadyExists => {
+                DataDirectoryLockError::HeldBy(Self::read_process_id(&path).unwrap_or_default())
+            }
+            _ => DataDirectoryLockError::Io(e.to_string()),
+        })?;
+        Ok(Self { path })
+    }
+
+    // The process ID of the live Node holding the lock, if there is one
+    pub fn holder(data_directory: &Path, process_is_running: &dyn Fn(u32) -> bool) -> Option<u32> {
+        Self::read_process_id(&data_directory.join(DATA_DIRECTORY_LOCK_FILE_NAME))
+            .filter(|process_id| process_is_running(*process_id))
+    }
+
+    fn create(path: &Path) -> io::Result<()> {
+        let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+        writeln!(file, "{}", process::id())
+    }
+
+    fn read_process_id(path: &Path) -> Option<u32> {
+        fs::read_to_string(path).ok()?.trim().parse::<u32>().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(DATA_DIRECTORY_LOCK_FILE_NAME, "node.lock");
+    }
+
+    #[test]
+    fn lock_is_acquired_in_empty_data_directory_and_released_when_dropped() {
+        let data_dir = ensure_node_home_directory_exists(
+            "data_directory_lock",
+            "lock_is_acquired_in_empty_data_directory_and_released_when_dropped",
+        );
+        let lock_path = data_dir.join(DATA_DIRECTORY_LOCK_FILE_NAME);
+
+        let subject =
+            DataDirectoryLock::acquire(&data_dir, &|_| panic!("Shouldn't be asked")).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&lock_path).unwrap(),
+            format!("{}\n", process::id())
+        );
+        drop(subject);
+        assert_eq!(lock_path.exists(), false);
+    }
+
+    #[test]
+    fn lock_creates_missing_data_directory() {
+        let home_dir = ensure_node_home_directory_exists(
+            "data_directory_lock",
+            "lock_creates_missing_data_directory",
+        );
+        let data_dir = home_dir.join("not").join("yet");
+
+        let _lock = DataDirectoryLock::acquire(&data_dir, &|_| true).unwrap();
+
+        assert_eq!(data_dir.join(DATA_DIRECTORY_LOCK_FILE_NAME).exists(), true);
+    }
+
+    #[test]
+    fn lock_held_by_live_process_is_not_acquired() {
+        let data_dir = ensure_node_home_directory_exists(
+            "data_directory_lock",
+            "lock_held_by_live_process_is_not_acquired",
+        );
+        fs::write(data_dir.join(DATA_DIRECTORY_LOCK_FILE_NAME), "4321\n").unwrap();
+
+        let result = DataDirectoryLock::acquire(&data_dir, &|process_id| process_id == 4321);
+
+        assert_eq!(result.err(), Some(DataDirectoryLockError::HeldBy(4321)));
+        assert_eq!(
+            fs::read_to_string(data_dir.join(DATA_DIRECTORY_LOCK_FILE_NAME)).unwrap(),
+            "4321\n"
+        );
+    }
+
+    #[test]
+    fn stale_lock_is_taken_over() {
+        let data_dir =
+            ensure_node_home_directory_exists("data_directory_lock", "stale_lock_is_taken_over");
+        fs::write(data_dir.join(DATA_DIRECTORY_LOCK_FILE_NAME), "4321\n").unwrap();
+
+        let result = DataDirectoryLock::acquire(&data_dir, &|_| false);
+
+        let _lock = result.unwrap();
+        assert_eq!(
+            fs::read_to_string(data_dir.join(DATA_DIRECTORY_LOCK_FILE_NAME)).unwrap(),
+            format!("{}\n", process::id())
+        );
+    }
+
+    #[test]
+    fn garbled_lock_is_taken_over() {
+        let data_dir =
+            ensure_node_home_directory_exists("data_directory_lock", "garbled_lock_is_taken_over");
+        fs::write(data_dir.join(DATA_DIRECTORY_LOCK_FILE_NAME), "booga").unwrap();
+
+        let result = DataDirectoryLock::acquire(&data_dir, &|_| true);
+
+        assert_eq!(result.is_ok(), true);
+    }
+
+    #[test]
+    fn holder_reports_only_live_process() {
+        let data_dir = ensure_node_home_directory_exists(
+            "data_directory_lock",
+            "holder_reports_only_live_process",
+        );
+        assert_eq!(DataDirectoryLock::holder(&data_dir, &|_| true), None);
+        fs::write(data_dir.join(DATA_DIRECTORY_LOCK_FILE_NAME), "4321\n").unwrap();
+
+        assert_eq!(DataDirectoryLock::holder(&data_dir, &|_| true), Some(4321));
+        assert_eq!(DataDirectoryLock::holder(&data_dir, &|_| false), None);
+    }
+
+    #[test]
+    fn lock_errors_are_displayed_sensibly() {
+        assert_eq!(
+            DataDirectoryLockError::HeldBy(4321).to_string(),
+            "Another Node (process 4321) is already running against this data directory"
+        );
+        assert_eq!(
+            DataDirectoryLockError::Io("booga".to_string()).to_string(),
+            "Couldn't lock the data directory: booga"
+        );
+    }
+}
@@ -0,0 +1,43 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::sub_lib::peer_actors::BindMessage;
+use actix::Message;
+use actix::Recipient;
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+
+// Sent by the Neighborhood each time it receives well-formed, properly-signed Gossip from a
+// neighbor, so that health probes can report how long the Node has gone without hearing from one.
+#[derive(Message, Clone, Debug, PartialEq, Eq)]
+pub struct GossipHeartbeat {}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct HealthCheckSubs {
+    pub bind: Recipient<BindMessage>,
+    pub gossip_heartbeat: Recipient<GossipHeartbeat>,
+}
+
+impl Debug for HealthCheckSubs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "HealthCheckSubs")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::recorder::Recorder;
+    use actix::Actor;
+
+    #[test]
+    fn health_check_subs_debug() {
+        let recorder = Recorder::new().start();
+
+        let subject = HealthCheckSubs {
+            bind: recipient!(recorder, BindMessage),
+            gossip_heartbeat: recipient!(recorder, GossipHeartbeat),
+        };
+
+        assert_eq!(format!("{:?}", subject), "HealthCheckSubs");
+    }
+}
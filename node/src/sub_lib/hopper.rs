@@ -1,12 +1,13 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 use crate::bootstrapper::CryptDEPair;
 use crate::neighborhood::gossip::Gossip_0v1;
+use crate::sub_lib::accountant::PaymentOverdue_0v1;
 use crate::sub_lib::cryptde::encodex;
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::cryptde::CryptData;
 use crate::sub_lib::cryptde::PublicKey;
 use crate::sub_lib::dispatcher::InboundClientData;
-use crate::sub_lib::neighborhood::GossipFailure_0v1;
+use crate::sub_lib::neighborhood::{GossipFailure_0v1, Rendezvous_0v1};
 use crate::sub_lib::node_addr::NodeAddr;
 use crate::sub_lib::peer_actors::BindMessage;
 use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, DnsResolveFailure_0v1};
@@ -18,8 +19,15 @@ use actix::Message;
 use actix::Recipient;
 use masq_lib::ui_gateway::NodeFromUiMessage;
 use serde_derive::{Deserialize, Serialize};
-use std::fmt::Debug;
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
 use std::net::SocketAddr;
+use std::str::FromStr;
+use std::time::Duration;
+
+pub const LOW_POWER_ACCOUNTING_SAMPLE_INTERVAL: u32 = 16;
+pub const MIN_TRAFFIC_PADDING_BUCKET_SIZE: usize = 64;
+pub const MAX_TRAFFIC_PADDING_BUCKET_SIZE: usize = 65536;
 
 /// Special-case hack to avoid extending a Card From Hell. I'm not sure what the right way to do
 /// this is, but this doesn't feel like it. The intent here is to provide a way to send a CORES
@@ -72,6 +80,8 @@ pub enum MessageType {
     Gossip(VersionedData<Gossip_0v1>),
     GossipFailure(VersionedData<GossipFailure_0v1>),
     DnsResolveFailed(VersionedData<DnsResolveFailure_0v1>),
+    Rendezvous(VersionedData<Rendezvous_0v1>),
+    PaymentOverdue(VersionedData<PaymentOverdue_0v1>),
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -81,6 +91,8 @@ pub enum MessageTypeLite {
     Gossip,
     GossipFailure,
     DnsResolveFailed,
+    Rendezvous,
+    PaymentOverdue,
 }
 
 #[allow(clippy::from_over_into)]
@@ -92,6 +104,8 @@ impl Into<MessageTypeLite> for MessageType {
             MessageType::Gossip(_) => MessageTypeLite::Gossip,
             MessageType::GossipFailure(_) => MessageTypeLite::GossipFailure,
             MessageType::DnsResolveFailed(_) => MessageTypeLite::DnsResolveFailed,
+            MessageType::Rendezvous(_) => MessageTypeLite::Rendezvous,
+            MessageType::PaymentOverdue(_) => MessageTypeLite::PaymentOverdue,
         }
     }
 }
@@ -150,6 +164,106 @@ pub struct HopperConfig {
     pub per_routing_byte: u64,
     pub is_decentralized: bool,
     pub crashable: bool,
+    pub accounting_sample_interval: u32,
+    pub traffic_padding_opt: Option<TrafficPadding>,
+}
+
+// Trades some precision for less work on every packet, for devices that can't spare the CPU
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum PerformancePreset {
+    #[default]
+    Standard,
+    LowPower,
+}
+
+impl PerformancePreset {
+    // Routing is charged for one CORES package in this many, extrapolated to stand for them all
+    pub fn accounting_sample_interval(&self) -> u32 {
+        match self {
+            PerformancePreset::Standard => 1,
+            PerformancePreset::LowPower => LOW_POWER_ACCOUNTING_SAMPLE_INTERVAL,
+        }
+    }
+}
+
+impl Display for PerformancePreset {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PerformancePreset::Standard => write!(f, "standard"),
+            PerformancePreset::LowPower => write!(f, "low-power"),
+        }
+    }
+}
+
+impl FromStr for PerformancePreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "standard" => Ok(PerformancePreset::Standard),
+            "low-power" => Ok(PerformancePreset::LowPower),
+            _ => Err(format!(
+                "Bad performance-preset value '{}': expected standard or low-power",
+                s
+            )),
+        }
+    }
+}
+
+// Hides the sizes of CORES packages by padding them up to a multiple of the bucket size, and the
+// silences between them by trickling cover packages to idle neighbors that can recognize them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TrafficPadding {
+    pub bucket_size: usize,
+    pub cover_per_minute: u32,
+}
+
+impl TrafficPadding {
+    pub fn cover_interval_opt(&self) -> Option<Duration> {
+        match self.cover_per_minute {
+            0 => None,
+            n => Some(Duration::from_secs(60) / n),
+        }
+    }
+}
+
+impl Display for TrafficPadding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}|{}", self.bucket_size, self.cover_per_minute)
+    }
+}
+
+impl FromStr for TrafficPadding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = s
+            .split('|')
+            .map(|segment| segment.parse::<u32>())
+            .collect::<Result<Vec<u32>, _>>()
+            .map_err(|e| format!("Bad traffic-padding value '{}': {}", s, e))?;
+        match values.as_slice() {
+            [bucket_size, cover_per_minute]
+                if (MIN_TRAFFIC_PADDING_BUCKET_SIZE..=MAX_TRAFFIC_PADDING_BUCKET_SIZE)
+                    .contains(&(*bucket_size as usize)) =>
+            {
+                Ok(Self {
+                    bucket_size: *bucket_size as usize,
+                    cover_per_minute: *cover_per_minute,
+                })
+            }
+            _ => Err(format!(
+                "Bad traffic-padding value '{}': expected <bucket size {}-{}>|<cover packages per minute>",
+                s, MIN_TRAFFIC_PADDING_BUCKET_SIZE, MAX_TRAFFIC_PADDING_BUCKET_SIZE
+            )),
+        }
+    }
+}
+
+// Asks the Hopper to send a cover package to whichever of these neighbors has been idle longest
+#[derive(Clone, Debug, PartialEq, Eq, Message)]
+pub struct SendCoverTraffic {
+    pub candidates: Vec<PublicKey>,
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -158,6 +272,7 @@ pub struct HopperSubs {
     pub from_hopper_client: Recipient<IncipientCoresPackage>,
     pub from_hopper_client_no_lookup: Recipient<NoLookupIncipientCoresPackage>,
     pub from_dispatcher: Recipient<InboundClientData>,
+    pub send_cover_traffic: Recipient<SendCoverTraffic>,
     pub node_from_ui: Recipient<NodeFromUiMessage>,
 }
 
@@ -180,6 +295,93 @@ mod tests {
     use std::net::IpAddr;
     use std::str::FromStr;
 
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(LOW_POWER_ACCOUNTING_SAMPLE_INTERVAL, 16);
+        assert_eq!(MIN_TRAFFIC_PADDING_BUCKET_SIZE, 64);
+        assert_eq!(MAX_TRAFFIC_PADDING_BUCKET_SIZE, 65536);
+    }
+
+    #[test]
+    fn traffic_padding_survives_a_round_trip_through_a_string() {
+        let subject = TrafficPadding::from_str("1024|2").unwrap();
+
+        assert_eq!(
+            subject,
+            TrafficPadding {
+                bucket_size: 1024,
+                cover_per_minute: 2,
+            }
+        );
+        assert_eq!(subject.to_string(), "1024|2");
+    }
+
+    #[test]
+    fn traffic_padding_rejects_bad_values() {
+        vec!["32|2", "65537|2", "1024", "1024|2|3"]
+            .into_iter()
+            .for_each(|value| {
+                assert_eq!(
+                    TrafficPadding::from_str(value),
+                    Err(format!(
+                        "Bad traffic-padding value '{}': expected <bucket size 64-65536>|<cover packages per minute>",
+                        value
+                    )),
+                    "{}",
+                    value
+                )
+            });
+        assert_eq!(
+            TrafficPadding::from_str("big|2"),
+            Err("Bad traffic-padding value 'big|2': invalid digit found in string".to_string())
+        );
+    }
+
+    #[test]
+    fn traffic_padding_spreads_cover_over_the_minute() {
+        let subject = |cover_per_minute| TrafficPadding {
+            bucket_size: 1024,
+            cover_per_minute,
+        };
+
+        assert_eq!(subject(0).cover_interval_opt(), None);
+        assert_eq!(
+            subject(1).cover_interval_opt(),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            subject(4).cover_interval_opt(),
+            Some(Duration::from_secs(15))
+        );
+    }
+
+    #[test]
+    fn performance_presets_choose_accounting_sample_intervals() {
+        assert_eq!(PerformancePreset::default(), PerformancePreset::Standard);
+        assert_eq!(PerformancePreset::Standard.accounting_sample_interval(), 1);
+        assert_eq!(
+            PerformancePreset::LowPower.accounting_sample_interval(),
+            LOW_POWER_ACCOUNTING_SAMPLE_INTERVAL
+        );
+    }
+
+    #[test]
+    fn performance_presets_survive_a_round_trip_through_a_string() {
+        [PerformancePreset::Standard, PerformancePreset::LowPower]
+            .into_iter()
+            .for_each(|preset| {
+                assert_eq!(PerformancePreset::from_str(&preset.to_string()), Ok(preset))
+            });
+        assert_eq!(
+            PerformancePreset::from_str("Low-Power"),
+            Ok(PerformancePreset::LowPower)
+        );
+        assert_eq!(
+            PerformancePreset::from_str("turbo"),
+            Err("Bad performance-preset value 'turbo': expected standard or low-power".to_string())
+        );
+    }
+
     #[test]
     fn hopper_subs_debug() {
         let recorder = Recorder::new().start();
@@ -189,6 +391,7 @@ mod tests {
             from_hopper_client: recipient!(recorder, IncipientCoresPackage),
             from_hopper_client_no_lookup: recipient!(recorder, NoLookupIncipientCoresPackage),
             from_dispatcher: recipient!(recorder, InboundClientData),
+            send_cover_traffic: recipient!(recorder, SendCoverTraffic),
             node_from_ui: recipient!(recorder, NodeFromUiMessage),
         };
 
@@ -325,17 +528,24 @@ mod tests {
         let client_request = MessageType::ClientRequest(VersionedData::test_new(dv!(0, 0), vec![]));
         let gossip_failure = MessageType::GossipFailure(VersionedData::test_new(dv!(0, 0), vec![]));
         let gossip = MessageType::Gossip(VersionedData::test_new(dv!(0, 0), vec![]));
+        let rendezvous = MessageType::Rendezvous(VersionedData::test_new(dv!(0, 0), vec![]));
+        let payment_overdue =
+            MessageType::PaymentOverdue(VersionedData::test_new(dv!(0, 0), vec![]));
 
         let dns_resolve_failed_result: MessageTypeLite = dns_resolve_failed.into();
         let client_response_result: MessageTypeLite = client_response.into();
         let client_request_result: MessageTypeLite = client_request.into();
         let gossip_failure_result: MessageTypeLite = gossip_failure.into();
         let gossip_result: MessageTypeLite = gossip.into();
+        let rendezvous_result: MessageTypeLite = rendezvous.into();
+        let payment_overdue_result: MessageTypeLite = payment_overdue.into();
 
         assert_eq!(dns_resolve_failed_result, MessageTypeLite::DnsResolveFailed);
         assert_eq!(client_response_result, MessageTypeLite::ClientResponse);
         assert_eq!(client_request_result, MessageTypeLite::ClientRequest);
         assert_eq!(gossip_failure_result, MessageTypeLite::GossipFailure);
         assert_eq!(gossip_result, MessageTypeLite::Gossip);
+        assert_eq!(rendezvous_result, MessageTypeLite::Rendezvous);
+        assert_eq!(payment_overdue_result, MessageTypeLite::PaymentOverdue);
     }
 }
@@ -0,0 +1,140 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use automap_lib::comm_layer::LocalIpFinder;
+use masq_lib::logger::Logger;
+use std::net::IpAddr;
+use std::time::Duration;
+
+pub const INTERFACE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+// Notices when this machine's traffic starts leaving through a different network interface (Wi-Fi
+// to Ethernet, a VPN coming up or going down) by watching the local address of the default route.
+// Portable polling, rather than netlink or OS events, keeps it the same on every platform.
+pub struct InterfaceWatcher {
+    local_ip_finder: Box<dyn LocalIpFinder>,
+    local_ip_opt: Option<IpAddr>,
+    logger: Logger,
+}
+
+impl InterfaceWatcher {
+    pub fn new(local_ip_finder: Box<dyn LocalIpFinder>) -> Self {
+        let local_ip_opt = local_ip_finder.find().ok();
+        Self {
+            local_ip_finder,
+            local_ip_opt,
+            logger: Logger::new("InterfaceWatcher"),
+        }
+    }
+
+    pub fn local_ip_opt(&self) -> Option<IpAddr> {
+        self.local_ip_opt
+    }
+
+    // Returns the new local IP if it differs from the one seen last. Losing the local IP altogether
+    // isn't a change to act on: nothing can be mapped until an interface comes up again.
+    pub fn check(&mut self) -> Option<IpAddr> {
+        let local_ip = match self.local_ip_finder.find() {
+            Ok(local_ip) => local_ip,
+            Err(e) => {
+                if self.local_ip_opt.is_some() {
+                    warning!(
+                        self.logger,
+                        "Lost the local IP address ({:?}); waiting for a network interface",
+                        e
+                    );
+                }
+                return None;
+            }
+        };
+        if self.local_ip_opt.replace(local_ip) == Some(local_ip) {
+            return None;
+        }
+        info!(
+            self.logger,
+            "Network traffic now leaves through the interface at {}", local_ip
+        );
+        Some(local_ip)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use automap_lib::comm_layer::AutomapError;
+    use automap_lib::mocks::LocalIpFinderMock;
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use std::str::FromStr;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(INTERFACE_CHECK_INTERVAL, Duration::from_secs(10));
+    }
+
+    #[test]
+    fn check_reports_nothing_while_the_local_ip_stays_the_same() {
+        let local_ip_finder = LocalIpFinderMock::new()
+            .find_result(Ok(IpAddr::from_str("192.168.0.5").unwrap()))
+            .find_result(Ok(IpAddr::from_str("192.168.0.5").unwrap()));
+        let mut subject = InterfaceWatcher::new(Box::new(local_ip_finder));
+
+        let result = subject.check();
+
+        assert_eq!(result, None);
+        assert_eq!(
+            subject.local_ip_opt(),
+            Some(IpAddr::from_str("192.168.0.5").unwrap())
+        );
+    }
+
+    #[test]
+    fn check_reports_a_new_local_ip() {
+        init_test_logging();
+        let local_ip_finder = LocalIpFinderMock::new()
+            .find_result(Ok(IpAddr::from_str("192.168.0.5").unwrap()))
+            .find_result(Ok(IpAddr::from_str("10.8.0.2").unwrap()))
+            .find_result(Ok(IpAddr::from_str("10.8.0.2").unwrap()));
+        let mut subject = InterfaceWatcher::new(Box::new(local_ip_finder));
+
+        let first_result = subject.check();
+        let second_result = subject.check();
+
+        assert_eq!(first_result, Some(IpAddr::from_str("10.8.0.2").unwrap()));
+        assert_eq!(second_result, None);
+        TestLogHandler::new().exists_log_containing(
+            "INFO: InterfaceWatcher: Network traffic now leaves through the interface at 10.8.0.2",
+        );
+    }
+
+    #[test]
+    fn check_waits_out_a_lost_local_ip_and_reports_the_one_that_returns() {
+        init_test_logging();
+        let local_ip_finder = LocalIpFinderMock::new()
+            .find_result(Ok(IpAddr::from_str("192.168.0.5").unwrap()))
+            .find_result(Err(AutomapError::NoLocalIpAddress))
+            .find_result(Err(AutomapError::NoLocalIpAddress))
+            .find_result(Ok(IpAddr::from_str("192.168.1.7").unwrap()));
+        let mut subject = InterfaceWatcher::new(Box::new(local_ip_finder));
+
+        let results = (0..3).map(|_| subject.check()).collect::<Vec<_>>();
+
+        assert_eq!(
+            results,
+            vec![None, None, Some(IpAddr::from_str("192.168.1.7").unwrap())]
+        );
+        TestLogHandler::new().exists_log_containing(
+            "WARN: InterfaceWatcher: Lost the local IP address (NoLocalIpAddress); waiting for a network interface",
+        );
+    }
+
+    #[test]
+    fn check_reports_the_first_local_ip_if_there_was_none_at_startup() {
+        let local_ip_finder = LocalIpFinderMock::new()
+            .find_result(Err(AutomapError::NoLocalIpAddress))
+            .find_result(Ok(IpAddr::from_str("192.168.0.5").unwrap()));
+        let mut subject = InterfaceWatcher::new(Box::new(local_ip_finder));
+
+        let result = subject.check();
+
+        assert_eq!(result, Some(IpAddr::from_str("192.168.0.5").unwrap()));
+    }
+}
@@ -1,7 +1,7 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use crate::sub_lib::migrations::utils::value_to_type;
-use crate::sub_lib::proxy_client::DnsResolveFailure_0v1;
+use crate::sub_lib::proxy_client::{DnsResolveFailure_0v1, ExitFailureKind};
 use crate::sub_lib::stream_key::StreamKey;
 use crate::sub_lib::versioned_data::Migrations;
 use crate::sub_lib::versioned_data::{MigrationError, StepError, VersionedData};
@@ -46,11 +46,16 @@ impl TryFrom<&Value> for DnsResolveFailure_0v1 {
         match value {
             Value::Map(map) => {
                 let mut stream_key_opt: Option<StreamKey> = None;
+                let mut failure_kind_opt: Option<ExitFailureKind> = None;
                 map.keys().for_each(|k| {
                     let v = map.get(k).expect("Disappeared");
                     if let Value::Text(field_name) = k {
-                        if field_name.as_str() == "stream_key" {
-                            stream_key_opt = value_to_type::<StreamKey>(v)
+                        match field_name.as_str() {
+                            "stream_key" => stream_key_opt = value_to_type::<StreamKey>(v),
+                            "failure_kind_opt" => {
+                                failure_kind_opt = value_to_type::<ExitFailureKind>(v)
+                            }
+                            _ => (),
                         }
                     }
                 });
@@ -70,6 +75,7 @@ impl TryFrom<&Value> for DnsResolveFailure_0v1 {
                 }
                 Ok(DnsResolveFailure_0v1 {
                     stream_key: stream_key_opt.expect("stream_key disappeared"),
+                    failure_kind_opt,
                 })
             }
             _ => Err(StepError::SemanticError(format!(
@@ -96,6 +102,7 @@ mod tests {
         }
         let expected_crp = DnsResolveFailure_0v1 {
             stream_key: StreamKey::make_meaningful_stream_key("All Things Must Pass"),
+            failure_kind_opt: None,
         };
         let future_crp = ExampleFutureDRF {
             stream_key: expected_crp.stream_key.clone(),
@@ -114,6 +121,61 @@ mod tests {
         assert_eq!(actual_crp, expected_crp);
     }
 
+    #[test]
+    fn can_migrate_failure_kind_from_the_future() {
+        #[derive(Serialize, Deserialize)]
+        struct ExampleFutureDRF {
+            pub stream_key: StreamKey,
+            pub failure_kind_opt: Option<ExitFailureKind>,
+            pub another_field: String,
+        }
+        let expected_crp = DnsResolveFailure_0v1::new_with_kind(
+            StreamKey::make_meaningful_stream_key("All Things Must Pass"),
+            ExitFailureKind::ConnectionRefused,
+        );
+        let future_crp = ExampleFutureDRF {
+            stream_key: expected_crp.stream_key.clone(),
+            failure_kind_opt: expected_crp.failure_kind_opt,
+            another_field: "These are the times that try men's souls".to_string(),
+        };
+        let future_migrations = Migrations::new(DataVersion::new(4095, 4095));
+        let serialized =
+            serde_cbor::ser::to_vec(&VersionedData::new(&future_migrations, &future_crp)).unwrap();
+        let future_vd =
+            serde_cbor::de::from_slice::<VersionedData<DnsResolveFailure_0v1>>(&serialized)
+                .unwrap();
+
+        let actual_crp = DnsResolveFailure_0v1::try_from(future_vd).unwrap();
+
+        assert_eq!(actual_crp, expected_crp);
+    }
+
+    #[test]
+    fn can_read_current_version_from_an_exit_that_does_not_classify_failures() {
+        #[derive(Serialize, Deserialize)]
+        struct UnclassifiedDRF {
+            pub stream_key: StreamKey,
+        }
+        let stream_key = StreamKey::make_meaningful_stream_key("All Things Must Pass");
+        let serialized = serde_cbor::ser::to_vec(&VersionedData::new(
+            &MIGRATIONS,
+            &UnclassifiedDRF { stream_key },
+        ))
+        .unwrap();
+        let vd = serde_cbor::de::from_slice::<VersionedData<DnsResolveFailure_0v1>>(&serialized)
+            .unwrap();
+
+        let actual_crp = DnsResolveFailure_0v1::try_from(vd).unwrap();
+
+        assert_eq!(
+            actual_crp,
+            DnsResolveFailure_0v1 {
+                stream_key,
+                failure_kind_opt: None
+            }
+        );
+    }
+
     #[test]
     fn cannot_migrate_from_value_other_than_map() {
         let value = Value::Bool(true);
@@ -6,4 +6,6 @@ pub mod dns_resolve_failure;
 pub mod gossip;
 pub mod gossip_failure;
 pub mod node_record_inner;
+pub mod payment_overdue;
+pub mod rendezvous;
 pub mod utils;
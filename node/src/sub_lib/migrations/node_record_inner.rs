@@ -54,6 +54,8 @@ impl TryFrom<&Value> for NodeRecordInner_0v1 {
                 let mut neighbors_opt: Option<BTreeSet<PublicKey>> = None;
                 let mut accepts_connections_opt: Option<bool> = None;
                 let mut routes_data_opt: Option<bool> = None;
+                let mut exits_data_opt: Option<bool> = None;
+                let mut pads_traffic_opt: Option<bool> = None;
                 let mut version_opt: Option<u32> = None;
                 map.keys().for_each(|k| {
                     let v = map.get(k).expect("Disappeared");
@@ -83,6 +85,8 @@ impl TryFrom<&Value> for NodeRecordInner_0v1 {
                                     accepts_connections_opt = Some(*field_value)
                                 }
                                 "routes_data" => routes_data_opt = Some(*field_value),
+                                "exits_data" => exits_data_opt = Some(*field_value),
+                                "pads_traffic" => pads_traffic_opt = Some(*field_value),
                                 _ => (),
                             }
                         }
@@ -130,6 +134,8 @@ impl TryFrom<&Value> for NodeRecordInner_0v1 {
                     neighbors: neighbors_opt.expect("public_key disappeared"),
                     accepts_connections: accepts_connections_opt.expect("public_key disappeared"),
                     routes_data: routes_data_opt.expect("public_key disappeared"),
+                    exits_data: exits_data_opt.unwrap_or(true),
+                    pads_traffic: pads_traffic_opt.unwrap_or(false),
                     version: version_opt.expect("public_key disappeared"),
                 })
             }
@@ -185,6 +191,8 @@ mod tests {
             ),
             accepts_connections: false,
             routes_data: true,
+            exits_data: true,
+            pads_traffic: false,
             version: 42,
         };
         let future_nri = ExampleFutureNRI {
@@ -209,6 +217,55 @@ mod tests {
         assert_eq!(actual_nri, expected_nri);
     }
 
+    #[test]
+    fn migration_from_the_future_preserves_exits_data_and_pads_traffic_when_present() {
+        #[derive(Serialize, Deserialize)]
+        struct ExampleFutureNRI {
+            pub public_key: PublicKey,
+            pub earning_wallet: Wallet,
+            pub rate_pack: RatePack,
+            pub neighbors: BTreeSet<PublicKey>,
+            pub accepts_connections: bool,
+            pub routes_data: bool,
+            pub exits_data: bool,
+            pub pads_traffic: bool,
+            pub version: u32,
+            pub another_field: String,
+        }
+        let expected_nri = NodeRecordInner_0v1 {
+            public_key: PublicKey::new(&[1, 2, 3, 4]),
+            earning_wallet: Wallet::new("0x0123456789012345678901234567890123456789"),
+            rate_pack: DEFAULT_RATE_PACK,
+            neighbors: BTreeSet::new(),
+            accepts_connections: true,
+            routes_data: true,
+            exits_data: false,
+            pads_traffic: true,
+            version: 42,
+        };
+        let future_nri = ExampleFutureNRI {
+            public_key: expected_nri.public_key.clone(),
+            earning_wallet: expected_nri.earning_wallet.clone(),
+            rate_pack: expected_nri.rate_pack.clone(),
+            neighbors: expected_nri.neighbors.clone(),
+            accepts_connections: expected_nri.accepts_connections,
+            routes_data: expected_nri.routes_data,
+            exits_data: expected_nri.exits_data,
+            pads_traffic: expected_nri.pads_traffic,
+            version: expected_nri.version,
+            another_field: "These are the times that try men's souls".to_string(),
+        };
+        let future_migrations = Migrations::new(DataVersion::new(4095, 4095));
+        let serialized =
+            serde_cbor::ser::to_vec(&VersionedData::new(&future_migrations, &future_nri)).unwrap();
+        let future_vd =
+            serde_cbor::de::from_slice::<VersionedData<NodeRecordInner_0v1>>(&serialized).unwrap();
+
+        let actual_nri = NodeRecordInner_0v1::try_from(future_vd).unwrap();
+
+        assert_eq!(actual_nri, expected_nri);
+    }
+
     #[test]
     fn cannot_migrate_from_value_other_than_map() {
         let value = Value::Bool(true);
@@ -0,0 +1,146 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::sub_lib::accountant::PaymentOverdue_0v1;
+use crate::sub_lib::migrations::utils::value_to_type;
+use crate::sub_lib::versioned_data::Migrations;
+use crate::sub_lib::versioned_data::{MigrationError, StepError, VersionedData};
+use lazy_static::lazy_static;
+use serde_cbor::Value;
+use std::convert::TryFrom;
+
+lazy_static! {
+    pub static ref MIGRATIONS: Migrations = {
+        let current_version = masq_lib::constants::PAYMENT_OVERDUE_CURRENT_VERSION;
+        let mut migrations = Migrations::new(current_version);
+
+        migrate_value!(dv!(0, 1), PaymentOverdue_0v1, PaymentOverdueMF_0v1, {|value: serde_cbor::Value| {
+            PaymentOverdue_0v1::try_from (&value)
+        }});
+        migrations.add_step (masq_lib::data_version::FUTURE_VERSION, dv!(0, 1), Box::new (PaymentOverdueMF_0v1{}));
+
+        // add more steps here
+
+        migrations
+    };
+}
+
+impl From<PaymentOverdue_0v1> for VersionedData<PaymentOverdue_0v1> {
+    fn from(data: PaymentOverdue_0v1) -> Self {
+        VersionedData::new(&MIGRATIONS, &data)
+    }
+}
+
+impl TryFrom<VersionedData<PaymentOverdue_0v1>> for PaymentOverdue_0v1 {
+    type Error = MigrationError;
+
+    fn try_from(vd: VersionedData<PaymentOverdue_0v1>) -> Result<Self, Self::Error> {
+        vd.extract(&MIGRATIONS)
+    }
+}
+
+impl TryFrom<&Value> for PaymentOverdue_0v1 {
+    type Error = StepError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Map(map) => {
+                let mut balance_gwei_opt: Option<u64> = None;
+                let mut deadline_opt: Option<u64> = None;
+                map.keys().for_each(|k| {
+                    let v = map.get(k).expect("Disappeared");
+                    if let Value::Text(field_name) = k {
+                        match field_name.as_str() {
+                            "balance_gwei" => balance_gwei_opt = value_to_type::<u64>(v),
+                            "deadline" => deadline_opt = value_to_type::<u64>(v),
+                            _ => (),
+                        }
+                    }
+                });
+                match (balance_gwei_opt, deadline_opt) {
+                    (Some(balance_gwei), Some(deadline)) => Ok(PaymentOverdue_0v1 {
+                        balance_gwei,
+                        deadline,
+                    }),
+                    _ => Err(StepError::SemanticError(
+                        "PaymentOverdue is missing its balance or its deadline".to_string(),
+                    )),
+                }
+            }
+            _ => Err(StepError::SemanticError(format!(
+                "Expected Value::Map; found {:?}",
+                value
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::data_version::DataVersion;
+    use serde_derive::{Deserialize, Serialize};
+
+    #[test]
+    fn can_migrate_from_the_future() {
+        #[derive(Serialize, Deserialize)]
+        struct ExampleFuturePaymentOverdue {
+            pub balance_gwei: u64,
+            pub deadline: u64,
+            pub another_field: String,
+        }
+        let future = ExampleFuturePaymentOverdue {
+            balance_gwei: 1_234_567,
+            deadline: 1_700_000_000,
+            another_field: "booga".to_string(),
+        };
+        let future_migrations = Migrations::new(DataVersion::new(4095, 4095));
+        let serialized =
+            serde_cbor::ser::to_vec(&VersionedData::new(&future_migrations, &future)).unwrap();
+        let future_vd =
+            serde_cbor::de::from_slice::<VersionedData<PaymentOverdue_0v1>>(&serialized).unwrap();
+
+        let actual = PaymentOverdue_0v1::try_from(future_vd).unwrap();
+
+        assert_eq!(
+            actual,
+            PaymentOverdue_0v1 {
+                balance_gwei: 1_234_567,
+                deadline: 1_700_000_000,
+            }
+        );
+    }
+
+    #[test]
+    fn cannot_migrate_without_a_deadline() {
+        #[derive(Serialize, Deserialize)]
+        struct ExampleFuturePaymentOverdue {
+            pub balance_gwei: u64,
+        }
+        let future = ExampleFuturePaymentOverdue {
+            balance_gwei: 1_234_567,
+        };
+        let future_migrations = Migrations::new(DataVersion::new(4095, 4095));
+        let serialized =
+            serde_cbor::ser::to_vec(&VersionedData::new(&future_migrations, &future)).unwrap();
+        let future_vd =
+            serde_cbor::de::from_slice::<VersionedData<PaymentOverdue_0v1>>(&serialized).unwrap();
+
+        let result = PaymentOverdue_0v1::try_from(future_vd);
+
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn cannot_migrate_from_value_other_than_map() {
+        let value = Value::Bool(true);
+
+        let result = PaymentOverdue_0v1::try_from(&value);
+
+        assert_eq!(
+            result,
+            Err(StepError::SemanticError(
+                "Expected Value::Map; found Bool(true)".to_string()
+            ))
+        )
+    }
+}
@@ -0,0 +1,165 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::sub_lib::cryptde::PublicKey;
+use crate::sub_lib::migrations::utils::value_to_type;
+use crate::sub_lib::neighborhood::{RendezvousKind, Rendezvous_0v1};
+use crate::sub_lib::node_addr::NodeAddr;
+use crate::sub_lib::versioned_data::Migrations;
+use crate::sub_lib::versioned_data::{MigrationError, StepError, VersionedData};
+use lazy_static::lazy_static;
+use serde_cbor::Value;
+use std::convert::TryFrom;
+
+lazy_static! {
+    pub static ref MIGRATIONS: Migrations = {
+        let current_version = masq_lib::constants::RENDEZVOUS_CURRENT_VERSION;
+        let mut migrations = Migrations::new(current_version);
+
+        migrate_value!(dv!(0, 1), Rendezvous_0v1, RendezvousMF_0v1, {|value: serde_cbor::Value| {
+            Rendezvous_0v1::try_from (&value)
+        }});
+        migrations.add_step (masq_lib::data_version::FUTURE_VERSION, dv!(0, 1), Box::new (RendezvousMF_0v1{}));
+
+        // add more steps here
+
+        migrations
+    };
+}
+
+impl From<Rendezvous_0v1> for VersionedData<Rendezvous_0v1> {
+    fn from(data: Rendezvous_0v1) -> Self {
+        VersionedData::new(&MIGRATIONS, &data)
+    }
+}
+
+impl TryFrom<VersionedData<Rendezvous_0v1>> for Rendezvous_0v1 {
+    type Error = MigrationError;
+
+    fn try_from(vd: VersionedData<Rendezvous_0v1>) -> Result<Self, Self::Error> {
+        vd.extract(&MIGRATIONS)
+    }
+}
+
+impl TryFrom<&Value> for Rendezvous_0v1 {
+    type Error = StepError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Map(map) => {
+                let mut kind_opt: Option<RendezvousKind> = None;
+                let mut peer_opt: Option<PublicKey> = None;
+                let mut peer_addr_opt: Option<NodeAddr> = None;
+                map.keys().for_each(|k| {
+                    let v = map.get(k).expect("Disappeared");
+                    if let Value::Text(field_name) = k {
+                        match field_name.as_str() {
+                            "kind" => kind_opt = value_to_type::<RendezvousKind>(v),
+                            "peer" => peer_opt = value_to_type::<PublicKey>(v),
+                            "peer_addr_opt" => peer_addr_opt = value_to_type::<NodeAddr>(v),
+                            _ => (),
+                        }
+                    }
+                });
+                match peer_opt {
+                    // A kind we don't know about yet is one we can't act on
+                    Some(peer) => Ok(Rendezvous_0v1 {
+                        kind: kind_opt.unwrap_or(RendezvousKind::Unknown),
+                        peer,
+                        peer_addr_opt,
+                    }),
+                    None => Err(StepError::SemanticError(
+                        "Rendezvous is missing its peer".to_string(),
+                    )),
+                }
+            }
+            _ => Err(StepError::SemanticError(format!(
+                "Expected Value::Map; found {:?}",
+                value
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::data_version::DataVersion;
+    use serde_derive::{Deserialize, Serialize};
+    use std::net::IpAddr;
+    use std::str::FromStr;
+
+    #[test]
+    fn can_migrate_from_the_future() {
+        #[derive(Serialize, Deserialize)]
+        enum ExampleFutureKind {
+            Offer,
+            AnotherKind,
+        }
+        #[derive(Serialize, Deserialize)]
+        struct ExampleFutureRendezvous {
+            pub kind: ExampleFutureKind,
+            pub peer: PublicKey,
+            pub peer_addr_opt: Option<NodeAddr>,
+            pub another_field: String,
+        }
+        let peer = PublicKey::new(&[1, 2, 3, 4]);
+        let peer_addr = NodeAddr::new(&IpAddr::from_str("1.2.3.4").unwrap(), &[1234]);
+        let check_migration = |kind: ExampleFutureKind, expected_kind: RendezvousKind| {
+            let future = ExampleFutureRendezvous {
+                kind,
+                peer: peer.clone(),
+                peer_addr_opt: Some(peer_addr.clone()),
+                another_field: "booga".to_string(),
+            };
+            let future_migrations = Migrations::new(DataVersion::new(4095, 4095));
+            let serialized =
+                serde_cbor::ser::to_vec(&VersionedData::new(&future_migrations, &future)).unwrap();
+            let future_vd =
+                serde_cbor::de::from_slice::<VersionedData<Rendezvous_0v1>>(&serialized).unwrap();
+
+            let actual = Rendezvous_0v1::try_from(future_vd).unwrap();
+
+            assert_eq!(
+                actual,
+                Rendezvous_0v1::new(expected_kind, peer.clone(), Some(peer_addr.clone()))
+            );
+        };
+
+        check_migration(ExampleFutureKind::Offer, RendezvousKind::Offer);
+        check_migration(ExampleFutureKind::AnotherKind, RendezvousKind::Unknown);
+    }
+
+    #[test]
+    fn cannot_migrate_without_a_peer() {
+        #[derive(Serialize, Deserialize)]
+        struct ExampleFutureRendezvous {
+            pub kind: RendezvousKind,
+        }
+        let future = ExampleFutureRendezvous {
+            kind: RendezvousKind::Request,
+        };
+        let future_migrations = Migrations::new(DataVersion::new(4095, 4095));
+        let serialized =
+            serde_cbor::ser::to_vec(&VersionedData::new(&future_migrations, &future)).unwrap();
+        let future_vd =
+            serde_cbor::de::from_slice::<VersionedData<Rendezvous_0v1>>(&serialized).unwrap();
+
+        let result = Rendezvous_0v1::try_from(future_vd);
+
+        assert_eq!(result.is_err(), true);
+    }
+
+    #[test]
+    fn cannot_migrate_from_value_other_than_map() {
+        let value = Value::Bool(true);
+
+        let result = Rendezvous_0v1::try_from(&value);
+
+        assert_eq!(
+            result,
+            Err(StepError::SemanticError(
+                "Expected Value::Map; found Bool(true)".to_string()
+            ))
+        )
+    }
+}
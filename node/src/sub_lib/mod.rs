@@ -15,12 +15,15 @@ pub mod configurator;
 pub mod cryptde;
 pub mod cryptde_null;
 pub mod cryptde_real;
+pub mod data_directory_lock;
 pub mod data_version;
 pub mod dispatcher;
 pub mod framer;
 pub mod framer_utils;
+pub mod health_check;
 pub mod hop;
 pub mod hopper;
+pub mod interface_watcher;
 pub mod http_packet_framer;
 pub mod http_response_start_finder;
 pub mod limiter;
@@ -28,16 +31,24 @@ pub mod main_tools;
 pub mod migrations;
 pub mod neighborhood;
 pub mod node_addr;
+pub mod node_pid_file;
+pub mod operator_notes;
 pub mod peer_actors;
 pub mod proxy_client;
 pub mod proxy_server;
+pub mod public_ip_discovery;
+pub mod public_ip_tracker;
 pub mod route;
 pub mod sequence_buffer;
 pub mod sequencer;
+pub mod shutdown_report;
+#[cfg(not(target_os = "windows"))]
+pub mod sighup;
 pub mod socket_server;
 pub mod stream_connector;
 pub mod stream_handler_pool;
 pub mod stream_key;
+pub mod supervision;
 pub mod tcp_wrappers;
 pub mod tls_framer;
 pub mod tokio_wrappers;
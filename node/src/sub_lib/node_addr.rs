@@ -7,7 +7,7 @@ use std::fmt::Debug;
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::net::SocketAddr;
-use std::net::{IpAddr, Ipv4Addr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::str::FromStr;
 
 #[derive(PartialEq, Eq, Hash, Deserialize, Serialize)]
@@ -37,6 +37,29 @@ impl NodeAddr {
     pub fn ports(&self) -> Vec<u16> {
         self.ports.clone()
     }
+
+    // IPv6 addresses are full of colons, so they go in square brackets, as in URLs
+    fn ip_addr_string(&self) -> String {
+        match self.ip_addr {
+            IpAddr::V4(ip) => ip.to_string(),
+            IpAddr::V6(ip) => format!("[{}]", ip),
+        }
+    }
+
+    fn split_ip_addr_and_ports(input: &str) -> Option<(&str, &str)> {
+        match input.strip_prefix('[') {
+            Some(bracketed) => {
+                let (ip_addr_str, rest) = bracketed.split_once(']')?;
+                Some((ip_addr_str, rest.strip_prefix(':')?))
+            }
+            None => match input.split(':').collect::<Vec<&str>>().as_slice() {
+                [ip_addr_str, ports_str] if !ip_addr_str.is_empty() => {
+                    Some((*ip_addr_str, *ports_str))
+                }
+                _ => None,
+            },
+        }
+    }
 }
 
 impl Default for NodeAddr {
@@ -76,7 +99,7 @@ impl Clone for NodeAddr {
 
 impl Debug for NodeAddr {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}:{:?}", self.ip_addr(), self.ports())
+        write!(f, "{}:{:?}", self.ip_addr_string(), self.ports())
     }
 }
 
@@ -90,7 +113,7 @@ impl Display for NodeAddr {
         write!(
             f,
             "{}:{}",
-            self.ip_addr(),
+            self.ip_addr_string(),
             port_list.join(Self::PORTS_SEPARATOR)
         )
     }
@@ -100,23 +123,30 @@ impl FromStr for NodeAddr {
     type Err = String;
 
     fn from_str(input: &str) -> Result<NodeAddr, String> {
-        let pieces: Vec<&str> = input.split(':').collect();
-        if pieces.len() != 2 || pieces[0].is_empty() {
-            return Err(format!(
-                "NodeAddr should be expressed as '<IP address>:<port>/<port>/...', not '{}'",
-                input
-            ));
-        }
-        let ip_addr = match IpAddr::from_str(pieces[0]) {
+        let (ip_addr_str, ports_str) = match Self::split_ip_addr_and_ports(input) {
+            Some(pieces) => pieces,
+            None => {
+                return Err(format!(
+                    "NodeAddr should be expressed as '<IP address>:<port>/<port>/...', not '{}'",
+                    input
+                ))
+            }
+        };
+        let ip_addr_result = if input.starts_with('[') {
+            Ipv6Addr::from_str(ip_addr_str).map(IpAddr::V6)
+        } else {
+            Ipv4Addr::from_str(ip_addr_str).map(IpAddr::V4)
+        };
+        let ip_addr = match ip_addr_result {
             Err(_) => {
                 return Err(format!(
                     "NodeAddr must have a valid IP address, not '{}'",
-                    pieces[0]
+                    ip_addr_str
                 ));
             }
             Ok(ip_addr) => ip_addr,
         };
-        let ports: Vec<u16> = match pieces[1]
+        let ports: Vec<u16> = match ports_str
             .split(Self::PORTS_SEPARATOR)
             .map(|s| match s.parse::<u16>() {
                 Err(_) => Err(format!(
@@ -229,6 +259,18 @@ mod tests {
         assert_eq!(result, "2.5.8.1:6/9");
     }
 
+    #[test]
+    fn node_addrs_put_ipv6_addresses_in_brackets() {
+        let ip_addr = IpAddr::from_str("2001:db8::1").unwrap();
+        let subject = NodeAddr::new(&ip_addr, &[9, 6]);
+
+        let display = format!("{}", subject);
+        let debug = format!("{:?}", subject);
+
+        assert_eq!(display, "[2001:db8::1]:6/9");
+        assert_eq!(debug, "[2001:db8::1]:[6, 9]");
+    }
+
     #[test]
     fn node_addrs_from_str_needs_two_pieces() {
         let result = NodeAddr::from_str("Booga");
@@ -290,6 +332,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn node_addrs_from_str_insists_on_brackets_around_ipv6_addresses() {
+        let unbracketed = NodeAddr::from_str("2001:db8::1:1234");
+        let bracketed_ipv4 = NodeAddr::from_str("[1.2.3.4]:1234");
+        let unclosed = NodeAddr::from_str("[2001:db8::1:1234");
+
+        assert_eq!(
+            unbracketed,
+            Err(String::from(
+                "NodeAddr should be expressed as '<IP address>:<port>/<port>/...', not '2001:db8::1:1234'"
+            ))
+        );
+        assert_eq!(
+            bracketed_ipv4,
+            Err(String::from(
+                "NodeAddr must have a valid IP address, not '1.2.3.4'"
+            ))
+        );
+        assert_eq!(
+            unclosed,
+            Err(String::from(
+                "NodeAddr should be expressed as '<IP address>:<port>/<port>/...', not '[2001:db8::1:1234'"
+            ))
+        );
+    }
+
+    #[test]
+    fn node_addrs_from_str_handles_ipv6_addresses() {
+        let result = NodeAddr::from_str("[2001:db8::1]:1234/2345");
+
+        assert_eq!(
+            result,
+            Ok(NodeAddr::new(
+                &IpAddr::from_str("2001:db8::1").unwrap(),
+                &[1234, 2345]
+            ))
+        );
+        assert_eq!(result.unwrap().to_string(), "[2001:db8::1]:1234/2345");
+    }
+
     #[test]
     fn node_addrs_from_str_follows_the_happy_path() {
         let result = NodeAddr::from_str("1.2.3.4:1234/2345/3456");
@@ -0,0 +1,87 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub const NODE_PID_FILE_NAME: &str = "node.pid";
+
+// Left in the data directory by a running Node, so that a Daemon that has lost track of it (say,
+// because the Daemon was restarted) can find it again. The file may be stale: a reader must check
+// that the process is still running and that its UI port still answers before trusting it.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct NodePidFile {
+    pub process_id: u32,
+    pub ui_port: u16,
+}
+
+impl NodePidFile {
+    pub fn new(process_id: u32, ui_port: u16) -> Self {
+        Self {
+            process_id,
+            ui_port,
+        }
+    }
+
+    pub fn write(&self, data_directory: &Path) -> io::Result<()> {
+        fs::write(
+            data_directory.join(NODE_PID_FILE_NAME),
+            format!("{}\n{}\n", self.process_id, self.ui_port),
+        )
+    }
+
+    pub fn read(data_directory: &Path) -> Option<Self> {
+        let contents = fs::read_to_string(data_directory.join(NODE_PID_FILE_NAME)).ok()?;
+        let mut lines = contents.lines();
+        let process_id = lines.next()?.trim().parse::<u32>().ok()?;
+        let ui_port = lines.next()?.trim().parse::<u16>().ok()?;
+        Some(Self::new(process_id, ui_port))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(NODE_PID_FILE_NAME, "node.pid");
+    }
+
+    #[test]
+    fn pid_file_can_be_written_and_read_back() {
+        let data_dir = ensure_node_home_directory_exists(
+            "node_pid_file",
+            "pid_file_can_be_written_and_read_back",
+        );
+        let subject = NodePidFile::new(4321, 5333);
+
+        subject.write(&data_dir).unwrap();
+
+        let contents = fs::read_to_string(data_dir.join(NODE_PID_FILE_NAME)).unwrap();
+        assert_eq!(contents, "4321\n5333\n");
+        assert_eq!(NodePidFile::read(&data_dir), Some(subject));
+    }
+
+    #[test]
+    fn missing_pid_file_reads_as_none() {
+        let data_dir =
+            ensure_node_home_directory_exists("node_pid_file", "missing_pid_file_reads_as_none");
+
+        let result = NodePidFile::read(&data_dir);
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn garbled_pid_file_reads_as_none() {
+        let data_dir =
+            ensure_node_home_directory_exists("node_pid_file", "garbled_pid_file_reads_as_none");
+        fs::write(data_dir.join(NODE_PID_FILE_NAME), "4321\nbooga\n").unwrap();
+
+        let result = NodePidFile::read(&data_dir);
+
+        assert_eq!(result, None);
+    }
+}
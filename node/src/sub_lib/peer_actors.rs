@@ -3,6 +3,7 @@ use crate::sub_lib::accountant::AccountantSubs;
 use crate::sub_lib::blockchain_bridge::BlockchainBridgeSubs;
 use crate::sub_lib::configurator::ConfiguratorSubs;
 use crate::sub_lib::dispatcher::DispatcherSubs;
+use crate::sub_lib::health_check::HealthCheckSubs;
 use crate::sub_lib::hopper::HopperSubs;
 use crate::sub_lib::neighborhood::{ConfigChangeMsg, NeighborhoodSubs};
 use crate::sub_lib::proxy_client::ProxyClientSubs;
@@ -25,6 +26,7 @@ pub struct PeerActors {
     pub ui_gateway: UiGatewaySubs,
     pub blockchain_bridge: BlockchainBridgeSubs,
     pub configurator: ConfiguratorSubs,
+    pub health_check_opt: Option<HealthCheckSubs>,
 }
 
 impl Debug for PeerActors {
@@ -1,19 +1,25 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::sub_lib::accountant::PaymentOverdue_0v1;
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::hopper::{ExpiredCoresPackage, MessageType};
 use crate::sub_lib::peer_actors::BindMessage;
 use crate::sub_lib::proxy_server::ClientRequestPayload_0v1;
 use crate::sub_lib::sequence_buffer::SequencedPacket;
+use crate::sub_lib::stream_connector::SocketTuning;
 use crate::sub_lib::stream_key::StreamKey;
 use crate::sub_lib::versioned_data::VersionedData;
+use crate::sub_lib::wallet::Wallet;
 use actix::Message;
 use actix::Recipient;
 use masq_lib::ui_gateway::NodeFromUiMessage;
 use serde_derive::{Deserialize, Serialize};
-use std::fmt::{Debug, Formatter};
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
+use std::io;
 use std::net::Ipv4Addr;
 use std::net::SocketAddr;
 use std::net::SocketAddrV4;
+use std::str::FromStr;
 
 pub fn error_socket_addr() -> SocketAddr {
     SocketAddr::from(SocketAddrV4::new(Ipv4Addr::new(0, 0, 0, 0), 0))
@@ -27,6 +33,44 @@ pub struct ProxyClientConfig {
     pub exit_byte_rate: u64,
     pub is_decentralized: bool,
     pub crashable: bool,
+    pub socket_tuning: SocketTuning,
+    pub exit_header_policy: ExitHeaderPolicy,
+}
+
+// What an exit does to headers in HTTP requests that could tell the server about the originator's
+// device or about the route the request took to get there
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ExitHeaderPolicy {
+    #[default]
+    Strip,
+    Normalize,
+    Off,
+}
+
+impl Display for ExitHeaderPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ExitHeaderPolicy::Strip => write!(f, "strip"),
+            ExitHeaderPolicy::Normalize => write!(f, "normalize"),
+            ExitHeaderPolicy::Off => write!(f, "off"),
+        }
+    }
+}
+
+impl FromStr for ExitHeaderPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "strip" => Ok(ExitHeaderPolicy::Strip),
+            "normalize" => Ok(ExitHeaderPolicy::Normalize),
+            "off" => Ok(ExitHeaderPolicy::Off),
+            _ => Err(format!(
+                "Bad exit-header-policy value '{}': expected strip, normalize, or off",
+                s
+            )),
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
@@ -40,11 +84,44 @@ pub struct ClientResponsePayload_0v1 {
 #[allow(non_camel_case_types)]
 pub struct DnsResolveFailure_0v1 {
     pub stream_key: StreamKey,
+    // Exits that predate failure classification don't send this; treat None as DnsResolution
+    #[serde(default)]
+    pub failure_kind_opt: Option<ExitFailureKind>,
 }
 
 impl DnsResolveFailure_0v1 {
     pub fn new(stream_key: StreamKey) -> Self {
-        Self { stream_key }
+        Self::new_with_kind(stream_key, ExitFailureKind::DnsResolution)
+    }
+
+    pub fn new_with_kind(stream_key: StreamKey, failure_kind: ExitFailureKind) -> Self {
+        Self {
+            stream_key,
+            failure_kind_opt: Some(failure_kind),
+        }
+    }
+
+    pub fn failure_kind(&self) -> ExitFailureKind {
+        self.failure_kind_opt
+            .unwrap_or(ExitFailureKind::DnsResolution)
+    }
+}
+
+// Why the exit couldn't get a request to its server. In every case nothing reached the server,
+// but only a DNS failure proves that no connection was even attempted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ExitFailureKind {
+    DnsResolution,
+    ConnectionRefused,
+    ConnectionFailed,
+}
+
+impl ExitFailureKind {
+    pub fn from_connection_error(error: &io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::ConnectionRefused => ExitFailureKind::ConnectionRefused,
+            _ => ExitFailureKind::ConnectionFailed,
+        }
     }
 }
 
@@ -66,6 +143,14 @@ impl From<DnsResolveFailure_0v1> for MessageType {
     }
 }
 
+// Asks for a PaymentOverdue notice to be delivered to the Node that pays with debtor_wallet. Only
+// a Node that has sent this one a request can be reached, over that request's return route.
+#[derive(Message, Clone, Debug, PartialEq, Eq)]
+pub struct PaymentOverdueNoticeMessage {
+    pub debtor_wallet: Wallet,
+    pub notice: PaymentOverdue_0v1,
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub struct ProxyClientSubs {
     pub bind: Recipient<BindMessage>,
@@ -73,6 +158,7 @@ pub struct ProxyClientSubs {
     pub inbound_server_data: Recipient<InboundServerData>,
     pub dns_resolve_failed: Recipient<DnsResolveFailure_0v1>,
     pub node_from_ui: Recipient<NodeFromUiMessage>,
+    pub payment_overdue_notice: Recipient<PaymentOverdueNoticeMessage>,
 }
 
 impl Debug for ProxyClientSubs {
@@ -129,6 +215,63 @@ mod tests {
         )
     }
 
+    #[test]
+    fn exit_header_policy_round_trips_through_strings() {
+        assert_eq!(ExitHeaderPolicy::default(), ExitHeaderPolicy::Strip);
+        [
+            ExitHeaderPolicy::Strip,
+            ExitHeaderPolicy::Normalize,
+            ExitHeaderPolicy::Off,
+        ]
+        .into_iter()
+        .for_each(|policy| assert_eq!(ExitHeaderPolicy::from_str(&policy.to_string()), Ok(policy)));
+        assert_eq!(
+            ExitHeaderPolicy::from_str("Normalize"),
+            Ok(ExitHeaderPolicy::Normalize)
+        );
+        assert_eq!(
+            ExitHeaderPolicy::from_str("scrub"),
+            Err(
+                "Bad exit-header-policy value 'scrub': expected strip, normalize, or off"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn exit_failure_is_classified_from_connection_error() {
+        assert_eq!(
+            ExitFailureKind::from_connection_error(&io::Error::from(
+                io::ErrorKind::ConnectionRefused
+            )),
+            ExitFailureKind::ConnectionRefused
+        );
+        assert_eq!(
+            ExitFailureKind::from_connection_error(&io::Error::from(io::ErrorKind::TimedOut)),
+            ExitFailureKind::ConnectionFailed
+        );
+    }
+
+    #[test]
+    fn dns_resolve_failure_without_a_kind_is_a_dns_failure() {
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let subject = DnsResolveFailure_0v1 {
+            stream_key,
+            failure_kind_opt: None,
+        };
+
+        assert_eq!(subject.failure_kind(), ExitFailureKind::DnsResolution);
+        assert_eq!(
+            DnsResolveFailure_0v1::new(stream_key).failure_kind(),
+            ExitFailureKind::DnsResolution
+        );
+        assert_eq!(
+            DnsResolveFailure_0v1::new_with_kind(stream_key, ExitFailureKind::ConnectionRefused)
+                .failure_kind(),
+            ExitFailureKind::ConnectionRefused
+        );
+    }
+
     #[test]
     fn proxy_client_subs_debug() {
         let recorder = Recorder::new().start();
@@ -139,6 +282,7 @@ mod tests {
             inbound_server_data: recipient!(recorder, InboundServerData),
             dns_resolve_failed: recipient!(recorder, DnsResolveFailure_0v1),
             node_from_ui: recipient!(recorder, NodeFromUiMessage),
+            payment_overdue_notice: recipient!(recorder, PaymentOverdueNoticeMessage),
         };
 
         assert_eq!(format!("{:?}", subject), "ProxyClientSubs");
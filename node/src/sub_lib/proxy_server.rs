@@ -1,4 +1,5 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::sub_lib::accountant::PaymentOverdue_0v1;
 use crate::sub_lib::cryptde::PublicKey;
 use crate::sub_lib::data_version::DataVersion;
 use crate::sub_lib::dispatcher::InboundClientData;
@@ -86,6 +87,7 @@ pub struct ProxyServerSubs {
     pub node_from_ui: Recipient<NodeFromUiMessage>,
     pub route_result_sub: Recipient<AddRouteResultMessage>,
     pub schedule_stream_key_purge: Recipient<MessageScheduler<StreamKeyPurge>>,
+    pub payment_overdue_from_hopper: Recipient<ExpiredCoresPackage<PaymentOverdue_0v1>>,
 }
 
 impl Debug for ProxyServerSubs {
@@ -118,6 +120,10 @@ mod tests {
             node_from_ui: recipient!(recorder, NodeFromUiMessage),
             route_result_sub: recipient!(recorder, AddRouteResultMessage),
             schedule_stream_key_purge: recipient!(recorder, MessageScheduler<StreamKeyPurge>),
+            payment_overdue_from_hopper: recipient!(
+                recorder,
+                ExpiredCoresPackage<PaymentOverdue_0v1>
+            ),
         };
 
         assert_eq!(format!("{:?}", subject), "ProxyServerSubs");
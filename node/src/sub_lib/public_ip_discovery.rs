@@ -0,0 +1,675 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use automap_lib::control_layer::automap_control::AutomapControl;
+use masq_lib::logger::Logger;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+pub const STUN_ANSWER_TIMEOUT: Duration = Duration::from_secs(3);
+pub const STUN_ATTEMPTS: usize = 3;
+
+const STUN_BINDING_REQUEST: u16 = 0x0001;
+const STUN_BINDING_SUCCESS: u16 = 0x0101;
+const STUN_MAGIC_COOKIE: u32 = 0x2112_A442;
+const STUN_HEADER_LENGTH: usize = 20;
+const STUN_MAPPED_ADDRESS: u16 = 0x0001;
+const STUN_XOR_MAPPED_ADDRESS: u16 = 0x0020;
+const STUN_FAMILY_IPV4: u8 = 0x01;
+const STUN_FAMILY_IPV6: u8 = 0x02;
+
+// One way of finding out the address at which the rest of the Internet sees this Node
+pub trait PublicIpDiscovery {
+    fn method(&self) -> String;
+    fn discover_public_ip(&mut self) -> Result<IpAddr, String>;
+}
+
+pub trait StunDiscoveryFactory {
+    fn make(&self, server: &str) -> Box<dyn PublicIpDiscovery>;
+}
+
+#[derive(Default)]
+pub struct StunDiscoveryFactoryReal {}
+
+impl StunDiscoveryFactory for StunDiscoveryFactoryReal {
+    fn make(&self, server: &str) -> Box<dyn PublicIpDiscovery> {
+        Box::new(StunDiscovery::new(server, STUN_ANSWER_TIMEOUT))
+    }
+}
+
+impl StunDiscoveryFactoryReal {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+// Tries each method in turn until one of them comes up with a public address. If none does, the
+// error explains what went wrong with each and what the user can do about it.
+pub fn discover_public_ip(
+    methods: Vec<&mut dyn PublicIpDiscovery>,
+    logger: &Logger,
+) -> Result<IpAddr, String> {
+    let mut failures = vec![];
+    for method in methods {
+        match method.discover_public_ip() {
+            Ok(public_ip) => {
+                if !failures.is_empty() {
+                    info!(
+                        logger,
+                        "Learned public IP {} from {}",
+                        public_ip,
+                        method.method()
+                    );
+                }
+                return Ok(public_ip);
+            }
+            Err(e) => {
+                warning!(
+                    logger,
+                    "Couldn't learn public IP from {}: {}",
+                    method.method(),
+                    e
+                );
+                failures.push(format!("{}: {}", method.method(), e));
+            }
+        }
+    }
+    Err(format!(
+        "Can't determine this Node's public IP address ({}). Supply it yourself with --ip, or \
+         name a STUN server your Node can reach with --public-ip-server.",
+        failures.join("; ")
+    ))
+}
+
+// Whether Nodes out on the Internet could reach this address at all
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let octets = ip.octets();
+            // 100.64.0.0/10 is the shared address space of carrier-grade NAT
+            let carrier_grade_nat = octets[0] == 100 && (octets[1] & 0xC0) == 64;
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || carrier_grade_nat)
+        }
+        IpAddr::V6(ip) => {
+            let first_segment = ip.segments()[0];
+            let unique_local = (first_segment & 0xFE00) == 0xFC00;
+            let link_local = (first_segment & 0xFFC0) == 0xFE80;
+            !(ip.is_loopback() || ip.is_unspecified() || unique_local || link_local)
+        }
+    }
+}
+
+// Asks the router through Automap. Remembers what the router said even if it wasn't a public
+// address, because the router can still map ports when it answers at all.
+pub struct AutomapDiscovery<'a> {
+    automap_control: &'a mut dyn AutomapControl,
+    router_ip_opt: Option<IpAddr>,
+}
+
+impl PublicIpDiscovery for AutomapDiscovery<'_> {
+    fn method(&self) -> String {
+        "the router".to_string()
+    }
+
+    fn discover_public_ip(&mut self) -> Result<IpAddr, String> {
+        let router_ip = self
+            .automap_control
+            .get_public_ip()
+            .map_err(|e| format!("{:?}", e))?;
+        self.router_ip_opt = Some(router_ip);
+        if is_public_ip(router_ip) {
+            Ok(router_ip)
+        } else {
+            Err(format!(
+                "reported {}, which isn't a public address",
+                router_ip
+            ))
+        }
+    }
+}
+
+impl<'a> AutomapDiscovery<'a> {
+    pub fn new(automap_control: &'a mut dyn AutomapControl) -> Self {
+        Self {
+            automap_control,
+            router_ip_opt: None,
+        }
+    }
+
+    pub fn router_ip_opt(&self) -> Option<IpAddr> {
+        self.router_ip_opt
+    }
+}
+
+// Sends a STUN Binding request (RFC 5389) and reads the address the server saw it come from
+pub struct StunDiscovery {
+    server: String,
+    answer_timeout: Duration,
+}
+
+impl PublicIpDiscovery for StunDiscovery {
+    fn method(&self) -> String {
+        format!("STUN server {}", self.server)
+    }
+
+    fn discover_public_ip(&mut self) -> Result<IpAddr, String> {
+        let server_addr = self
+            .server
+            .to_socket_addrs()
+            .map_err(|e| format!("can't resolve the server's address: {}", e))?
+            .next()
+            .ok_or_else(|| "the server's name resolves to no address".to_string())?;
+        let local_ip = match server_addr {
+            SocketAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            SocketAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        };
+        let socket = UdpSocket::bind(SocketAddr::new(local_ip, 0))
+            .map_err(|e| format!("can't open a UDP socket: {}", e))?;
+        socket
+            .set_read_timeout(Some(self.answer_timeout))
+            .map_err(|e| format!("can't set a UDP timeout: {}", e))?;
+        let transaction_id: [u8; 12] = rand::random();
+        let request = make_binding_request(&transaction_id);
+        let mut buffer = [0u8; 1024];
+        for _ in 0..STUN_ATTEMPTS {
+            socket
+                .send_to(&request, server_addr)
+                .map_err(|e| format!("can't send to the server: {}", e))?;
+            if let Ok((length, from)) = socket.recv_from(&mut buffer) {
+                if from == server_addr {
+                    let public_ip = parse_binding_response(&buffer[..length], &transaction_id)?;
+                    return if is_public_ip(public_ip) {
+                        Ok(public_ip)
+                    } else {
+                        Err(format!(
+                            "reported {}, which isn't a public address",
+                            public_ip
+                        ))
+                    };
+                }
+            }
+        }
+        Err(format!("no answer after {} attempts", STUN_ATTEMPTS))
+    }
+}
+
+impl StunDiscovery {
+    pub fn new(server: &str, answer_timeout: Duration) -> Self {
+        Self {
+            server: server.to_string(),
+            answer_timeout,
+        }
+    }
+}
+
+fn make_binding_request(transaction_id: &[u8; 12]) -> Vec<u8> {
+    let mut request = Vec::with_capacity(STUN_HEADER_LENGTH);
+    request.extend_from_slice(&STUN_BINDING_REQUEST.to_be_bytes());
+    request.extend_from_slice(&0u16.to_be_bytes());
+    request.extend_from_slice(&STUN_MAGIC_COOKIE.to_be_bytes());
+    request.extend_from_slice(transaction_id);
+    request
+}
+
+fn parse_binding_response(response: &[u8], transaction_id: &[u8; 12]) -> Result<IpAddr, String> {
+    let read_u16 =
+        |bytes: &[u8], offset: usize| u16::from_be_bytes([bytes[offset], bytes[offset + 1]]);
+    if response.len() < STUN_HEADER_LENGTH
+        || read_u16(response, 0) != STUN_BINDING_SUCCESS
+        || response[4..8] != STUN_MAGIC_COOKIE.to_be_bytes()
+        || &response[8..STUN_HEADER_LENGTH] != transaction_id
+    {
+        return Err("answered with something other than a Binding success".to_string());
+    }
+    let body_length = read_u16(response, 2) as usize;
+    let attributes = response
+        .get(STUN_HEADER_LENGTH..STUN_HEADER_LENGTH + body_length)
+        .ok_or_else(|| "answered with a truncated message".to_string())?;
+    let mut mapped_address_opt = None;
+    let mut offset = 0;
+    while offset + 4 <= attributes.len() {
+        let attribute_type = read_u16(attributes, offset);
+        let value_length = read_u16(attributes, offset + 2) as usize;
+        let value = attributes
+            .get(offset + 4..offset + 4 + value_length)
+            .ok_or_else(|| "answered with a truncated attribute".to_string())?;
+        match attribute_type {
+            STUN_XOR_MAPPED_ADDRESS => return decode_address(value, Some(transaction_id)),
+            STUN_MAPPED_ADDRESS => mapped_address_opt = Some(decode_address(value, None)),
+            _ => (),
+        }
+        // Attribute values are padded to a multiple of four bytes
+        offset += 4 + ((value_length + 3) & !3);
+    }
+    mapped_address_opt.unwrap_or_else(|| Err("answered without an address".to_string()))
+}
+
+// XOR-MAPPED-ADDRESS obscures the address with the magic cookie followed by the transaction ID
+fn decode_address(
+    value: &[u8],
+    xor_transaction_id_opt: Option<&[u8; 12]>,
+) -> Result<IpAddr, String> {
+    let mask = match xor_transaction_id_opt {
+        Some(transaction_id) => {
+            [&STUN_MAGIC_COOKIE.to_be_bytes()[..], &transaction_id[..]].concat()
+        }
+        None => vec![0u8; 16],
+    };
+    let unmask = |address: &[u8]| -> Vec<u8> {
+        address
+            .iter()
+            .zip(mask.iter())
+            .map(|(byte, mask_byte)| byte ^ mask_byte)
+            .collect()
+    };
+    match (value.get(1).copied(), value.len()) {
+        (Some(STUN_FAMILY_IPV4), 8) => {
+            let octets: [u8; 4] = unmask(&value[4..8])
+                .try_into()
+                .expect("Four bytes aren't four");
+            Ok(IpAddr::V4(Ipv4Addr::from(octets)))
+        }
+        (Some(STUN_FAMILY_IPV6), 20) => {
+            let octets: [u8; 16] = unmask(&value[4..20])
+                .try_into()
+                .expect("Sixteen bytes aren't sixteen");
+            Ok(IpAddr::V6(Ipv6Addr::from(octets)))
+        }
+        _ => Err("answered with a malformed address".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::automap_mocks::{AutomapControlMock, PublicIpDiscoveryMock};
+    use automap_lib::comm_layer::AutomapError;
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use std::str::FromStr;
+    use std::thread;
+
+    fn make_binding_response(transaction_id: &[u8; 12], attributes: &[(u16, Vec<u8>)]) -> Vec<u8> {
+        let body = attributes
+            .iter()
+            .flat_map(|(attribute_type, value)| {
+                let mut attribute = attribute_type.to_be_bytes().to_vec();
+                attribute.extend_from_slice(&(value.len() as u16).to_be_bytes());
+                attribute.extend_from_slice(value);
+                attribute.resize(4 + ((value.len() + 3) & !3), 0);
+                attribute
+            })
+            .collect::<Vec<u8>>();
+        let mut response = STUN_BINDING_SUCCESS.to_be_bytes().to_vec();
+        response.extend_from_slice(&(body.len() as u16).to_be_bytes());
+        response.extend_from_slice(&STUN_MAGIC_COOKIE.to_be_bytes());
+        response.extend_from_slice(transaction_id);
+        response.extend(body);
+        response
+    }
+
+    fn xor_mapped_ipv4(ip: Ipv4Addr) -> Vec<u8> {
+        let cookie = STUN_MAGIC_COOKIE.to_be_bytes();
+        let mut value = vec![0, STUN_FAMILY_IPV4, 0x12, 0x34];
+        value.extend(ip.octets().iter().zip(cookie.iter()).map(|(a, b)| a ^ b));
+        value
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(STUN_ANSWER_TIMEOUT, Duration::from_secs(3));
+        assert_eq!(STUN_ATTEMPTS, 3);
+        assert_eq!(STUN_BINDING_REQUEST, 0x0001);
+        assert_eq!(STUN_BINDING_SUCCESS, 0x0101);
+        assert_eq!(STUN_MAGIC_COOKIE, 0x2112_A442);
+        assert_eq!(STUN_HEADER_LENGTH, 20);
+        assert_eq!(STUN_MAPPED_ADDRESS, 0x0001);
+        assert_eq!(STUN_XOR_MAPPED_ADDRESS, 0x0020);
+        assert_eq!(STUN_FAMILY_IPV4, 0x01);
+        assert_eq!(STUN_FAMILY_IPV6, 0x02);
+    }
+
+    #[test]
+    fn public_and_non_public_addresses_are_told_apart() {
+        vec!["1.2.3.4", "100.128.0.1", "8.8.8.8", "2001:4860:4860::8888"]
+            .into_iter()
+            .for_each(|ip| assert!(is_public_ip(IpAddr::from_str(ip).unwrap()), "{}", ip));
+        vec![
+            "0.0.0.0",
+            "10.1.2.3",
+            "100.64.0.1",
+            "100.127.255.254",
+            "127.0.0.1",
+            "169.254.1.1",
+            "172.16.0.1",
+            "192.168.1.1",
+            "192.0.2.1",
+            "255.255.255.255",
+            "::",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+        ]
+        .into_iter()
+        .for_each(|ip| assert!(!is_public_ip(IpAddr::from_str(ip).unwrap()), "{}", ip));
+    }
+
+    #[test]
+    fn discovery_stops_at_the_first_method_that_finds_a_public_ip() {
+        let mut first = PublicIpDiscoveryMock::new("first")
+            .discover_public_ip_result(Ok(IpAddr::from_str("1.2.3.4").unwrap()));
+        let mut second = PublicIpDiscoveryMock::new("second");
+
+        let result = discover_public_ip(vec![&mut first, &mut second], &Logger::new("test"));
+
+        assert_eq!(result, Ok(IpAddr::from_str("1.2.3.4").unwrap()));
+    }
+
+    #[test]
+    fn discovery_falls_back_on_later_methods() {
+        let test_name = "discovery_falls_back_on_later_methods";
+        init_test_logging();
+        let mut first =
+            PublicIpDiscoveryMock::new("first").discover_public_ip_result(Err("booga".to_string()));
+        let mut second = PublicIpDiscoveryMock::new("second")
+            .discover_public_ip_result(Ok(IpAddr::from_str("5.6.7.8").unwrap()));
+
+        let result = discover_public_ip(vec![&mut first, &mut second], &Logger::new(test_name));
+
+        assert_eq!(result, Ok(IpAddr::from_str("5.6.7.8").unwrap()));
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_containing(&format!(
+            "WARN: {test_name}: Couldn't learn public IP from first: booga"
+        ));
+        tlh.exists_log_containing(&format!(
+            "INFO: {test_name}: Learned public IP 5.6.7.8 from second"
+        ));
+    }
+
+    #[test]
+    fn discovery_explains_itself_when_every_method_fails() {
+        let mut first =
+            PublicIpDiscoveryMock::new("first").discover_public_ip_result(Err("booga".to_string()));
+        let mut second = PublicIpDiscoveryMock::new("second")
+            .discover_public_ip_result(Err("wooga".to_string()));
+
+        let result = discover_public_ip(vec![&mut first, &mut second], &Logger::new("test"));
+
+        assert_eq!(
+            result,
+            Err(
+                "Can't determine this Node's public IP address (first: booga; second: wooga). \
+                 Supply it yourself with --ip, or name a STUN server your Node can reach with \
+                 --public-ip-server."
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn automap_discovery_accepts_a_public_address_from_the_router() {
+        let mut automap_control = AutomapControlMock::new()
+            .get_public_ip_result(Ok(IpAddr::from_str("1.2.3.4").unwrap()));
+        let mut subject = AutomapDiscovery::new(&mut automap_control);
+
+        let result = subject.discover_public_ip();
+
+        assert_eq!(result, Ok(IpAddr::from_str("1.2.3.4").unwrap()));
+        assert_eq!(
+            subject.router_ip_opt(),
+            Some(IpAddr::from_str("1.2.3.4").unwrap())
+        );
+        assert_eq!(subject.method(), "the router".to_string());
+    }
+
+    #[test]
+    fn automap_discovery_rejects_but_remembers_a_private_address_from_the_router() {
+        let mut automap_control = AutomapControlMock::new()
+            .get_public_ip_result(Ok(IpAddr::from_str("192.168.0.1").unwrap()));
+        let mut subject = AutomapDiscovery::new(&mut automap_control);
+
+        let result = subject.discover_public_ip();
+
+        assert_eq!(
+            result,
+            Err("reported 192.168.0.1, which isn't a public address".to_string())
+        );
+        assert_eq!(
+            subject.router_ip_opt(),
+            Some(IpAddr::from_str("192.168.0.1").unwrap())
+        );
+    }
+
+    #[test]
+    fn automap_discovery_passes_along_automap_errors() {
+        let mut automap_control = AutomapControlMock::new()
+            .get_public_ip_result(Err(AutomapError::AllProtocolsFailed(vec![])));
+        let mut subject = AutomapDiscovery::new(&mut automap_control);
+
+        let result = subject.discover_public_ip();
+
+        assert_eq!(result, Err("AllProtocolsFailed([])".to_string()));
+        assert_eq!(subject.router_ip_opt(), None);
+    }
+
+    #[test]
+    fn binding_request_is_well_formed() {
+        let transaction_id = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+
+        let result = make_binding_request(&transaction_id);
+
+        assert_eq!(
+            result,
+            vec![
+                0x00, 0x01, 0x00, 0x00, 0x21, 0x12, 0xA4, 0x42, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11,
+                12
+            ]
+        );
+    }
+
+    #[test]
+    fn binding_response_xor_mapped_address_is_preferred() {
+        let transaction_id = [12; 12];
+        let response = make_binding_response(
+            &transaction_id,
+            &[
+                (
+                    STUN_MAPPED_ADDRESS,
+                    vec![0, STUN_FAMILY_IPV4, 0x12, 0x34, 10, 0, 0, 1],
+                ),
+                (0x8022, b"some software".to_vec()),
+                (
+                    STUN_XOR_MAPPED_ADDRESS,
+                    xor_mapped_ipv4(Ipv4Addr::new(5, 6, 7, 8)),
+                ),
+            ],
+        );
+
+        let result = parse_binding_response(&response, &transaction_id);
+
+        assert_eq!(result, Ok(IpAddr::from_str("5.6.7.8").unwrap()));
+    }
+
+    #[test]
+    fn binding_response_plain_mapped_address_is_accepted_from_old_servers() {
+        let transaction_id = [12; 12];
+        let response = make_binding_response(
+            &transaction_id,
+            &[(
+                STUN_MAPPED_ADDRESS,
+                vec![0, STUN_FAMILY_IPV4, 0x12, 0x34, 5, 6, 7, 8],
+            )],
+        );
+
+        let result = parse_binding_response(&response, &transaction_id);
+
+        assert_eq!(result, Ok(IpAddr::from_str("5.6.7.8").unwrap()));
+    }
+
+    #[test]
+    fn binding_response_xor_mapped_ipv6_address_is_decoded() {
+        let transaction_id = [7; 12];
+        let ip = Ipv6Addr::from_str("2001:db8::1234").unwrap();
+        let mask = [&STUN_MAGIC_COOKIE.to_be_bytes()[..], &transaction_id[..]].concat();
+        let mut value = vec![0, STUN_FAMILY_IPV6, 0x12, 0x34];
+        value.extend(ip.octets().iter().zip(mask.iter()).map(|(a, b)| a ^ b));
+        let response = make_binding_response(&transaction_id, &[(STUN_XOR_MAPPED_ADDRESS, value)]);
+
+        let result = parse_binding_response(&response, &transaction_id);
+
+        assert_eq!(result, Ok(IpAddr::V6(ip)));
+    }
+
+    #[test]
+    fn binding_response_problems_are_reported() {
+        let transaction_id = [12; 12];
+        let good_response = make_binding_response(
+            &transaction_id,
+            &[(
+                STUN_XOR_MAPPED_ADDRESS,
+                xor_mapped_ipv4(Ipv4Addr::new(5, 6, 7, 8)),
+            )],
+        );
+        let mut wrong_type = good_response.clone();
+        wrong_type[1] = 0x11;
+        let mut truncated = good_response.clone();
+        truncated.pop();
+        let mut bad_length = good_response.clone();
+        bad_length[23] = 200;
+        let mut bad_family = good_response.clone();
+        bad_family[25] = 0x03;
+
+        vec![
+            (
+                good_response.clone(),
+                [13; 12],
+                "answered with something other than a Binding success",
+            ),
+            (
+                wrong_type,
+                transaction_id,
+                "answered with something other than a Binding success",
+            ),
+            (
+                vec![1, 1, 0],
+                transaction_id,
+                "answered with something other than a Binding success",
+            ),
+            (
+                truncated,
+                transaction_id,
+                "answered with a truncated message",
+            ),
+            (
+                bad_length,
+                transaction_id,
+                "answered with a truncated attribute",
+            ),
+            (
+                bad_family,
+                transaction_id,
+                "answered with a malformed address",
+            ),
+            (
+                make_binding_response(&transaction_id, &[]),
+                transaction_id,
+                "answered without an address",
+            ),
+        ]
+        .into_iter()
+        .for_each(|(response, transaction_id, expected_error)| {
+            assert_eq!(
+                parse_binding_response(&response, &transaction_id),
+                Err(expected_error.to_string()),
+                "{:?}",
+                response
+            )
+        });
+    }
+
+    #[test]
+    fn stun_discovery_learns_the_address_the_server_reports() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let server_addr = server.local_addr().unwrap();
+        let server_thread = thread::spawn(move || {
+            let mut buffer = [0u8; 100];
+            let (length, from) = server.recv_from(&mut buffer).unwrap();
+            let transaction_id: [u8; 12] = buffer[8..20].try_into().unwrap();
+            let response = make_binding_response(
+                &transaction_id,
+                &[(
+                    STUN_XOR_MAPPED_ADDRESS,
+                    xor_mapped_ipv4(Ipv4Addr::new(5, 6, 7, 8)),
+                )],
+            );
+            server.send_to(&response, from).unwrap();
+            buffer[..length].to_vec()
+        });
+        let mut subject = StunDiscovery::new(&server_addr.to_string(), Duration::from_secs(1));
+
+        let result = subject.discover_public_ip();
+
+        assert_eq!(result, Ok(IpAddr::from_str("5.6.7.8").unwrap()));
+        let request = server_thread.join().unwrap();
+        assert_eq!(request.len(), STUN_HEADER_LENGTH);
+        assert_eq!(
+            request[0..8],
+            [0x00, 0x01, 0x00, 0x00, 0x21, 0x12, 0xA4, 0x42]
+        );
+        assert_eq!(subject.method(), format!("STUN server {}", server_addr));
+    }
+
+    #[test]
+    fn stun_discovery_rejects_a_non_public_address() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let server_addr = server.local_addr().unwrap();
+        thread::spawn(move || {
+            let mut buffer = [0u8; 100];
+            let (_, from) = server.recv_from(&mut buffer).unwrap();
+            let transaction_id: [u8; 12] = buffer[8..20].try_into().unwrap();
+            let response = make_binding_response(
+                &transaction_id,
+                &[(
+                    STUN_XOR_MAPPED_ADDRESS,
+                    xor_mapped_ipv4(Ipv4Addr::new(127, 0, 0, 1)),
+                )],
+            );
+            server.send_to(&response, from).unwrap();
+        });
+        let mut subject = StunDiscovery::new(&server_addr.to_string(), Duration::from_secs(1));
+
+        let result = subject.discover_public_ip();
+
+        assert_eq!(
+            result,
+            Err("reported 127.0.0.1, which isn't a public address".to_string())
+        );
+    }
+
+    #[test]
+    fn stun_discovery_gives_up_on_a_silent_server() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let server_addr = server.local_addr().unwrap();
+        let mut subject = StunDiscovery::new(&server_addr.to_string(), Duration::from_millis(50));
+
+        let result = subject.discover_public_ip();
+
+        assert_eq!(result, Err("no answer after 3 attempts".to_string()));
+    }
+
+    #[test]
+    fn stun_discovery_factory_makes_stun_discovery() {
+        let subject = StunDiscoveryFactoryReal::new();
+
+        let result = subject.make("stun.example.com:3478");
+
+        assert_eq!(
+            result.method(),
+            "STUN server stun.example.com:3478".to_string()
+        );
+    }
+}
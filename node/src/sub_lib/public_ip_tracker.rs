@@ -0,0 +1,172 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::db_config::persistent_configuration::PersistentConfiguration;
+use masq_lib::logger::Logger;
+use std::net::IpAddr;
+
+// Routers may announce the same public IP more than once, and the IGDP housekeeping thread polls
+// for it; every NewPublicIp that reaches the Neighborhood makes it re-publish this Node's
+// descriptor. The tracker remembers the last public IP, across restarts too, so that only real
+// changes are passed on.
+pub struct PublicIpTracker {
+    last_public_ip_opt: Option<IpAddr>,
+    logger: Logger,
+}
+
+impl PublicIpTracker {
+    pub fn new(persistent_config: &dyn PersistentConfiguration) -> Self {
+        let logger = Logger::new("PublicIpTracker");
+        let last_public_ip_opt = match persistent_config.last_public_ip() {
+            Ok(ip_opt) => ip_opt,
+            Err(e) => {
+                warning!(
+                    logger,
+                    "Can't read the last public IP from the database: {:?}",
+                    e
+                );
+                None
+            }
+        };
+        Self {
+            last_public_ip_opt,
+            logger,
+        }
+    }
+
+    pub fn last_public_ip_opt(&self) -> Option<IpAddr> {
+        self.last_public_ip_opt
+    }
+
+    pub fn differs(&self, public_ip: IpAddr) -> bool {
+        self.last_public_ip_opt != Some(public_ip)
+    }
+
+    // Returns true if the public IP is a change, which has then been remembered and saved
+    pub fn record(
+        &mut self,
+        public_ip: IpAddr,
+        persistent_config: &mut dyn PersistentConfiguration,
+    ) -> bool {
+        if !self.differs(public_ip) {
+            debug!(
+                self.logger,
+                "Public IP is still {}; nothing to announce", public_ip
+            );
+            return false;
+        }
+        match self.last_public_ip_opt.replace(public_ip) {
+            Some(old_public_ip) => info!(
+                self.logger,
+                "Public IP changed from {} to {}", old_public_ip, public_ip
+            ),
+            None => info!(self.logger, "Public IP is {}", public_ip),
+        }
+        if let Err(e) = persistent_config.set_last_public_ip(Some(public_ip)) {
+            warning!(
+                self.logger,
+                "Can't save public IP {} to the database: {:?}",
+                public_ip,
+                e
+            );
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db_config::persistent_configuration::PersistentConfigError;
+    use crate::test_utils::persistent_configuration_mock::PersistentConfigurationMock;
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use std::str::FromStr;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn new_loads_the_last_public_ip_from_the_database() {
+        let persistent_config = PersistentConfigurationMock::new()
+            .last_public_ip_result(Ok(Some(IpAddr::from_str("1.2.3.4").unwrap())));
+
+        let subject = PublicIpTracker::new(&persistent_config);
+
+        assert_eq!(
+            subject.last_public_ip_opt(),
+            Some(IpAddr::from_str("1.2.3.4").unwrap())
+        );
+        assert_eq!(subject.differs(IpAddr::from_str("1.2.3.4").unwrap()), false);
+        assert_eq!(subject.differs(IpAddr::from_str("4.3.2.1").unwrap()), true);
+    }
+
+    #[test]
+    fn new_starts_from_nothing_if_the_database_complains() {
+        init_test_logging();
+        let persistent_config = PersistentConfigurationMock::new().last_public_ip_result(Err(
+            PersistentConfigError::DatabaseError("booga".to_string()),
+        ));
+
+        let subject = PublicIpTracker::new(&persistent_config);
+
+        assert_eq!(subject.last_public_ip_opt(), None);
+        TestLogHandler::new().exists_log_containing(
+            "WARN: PublicIpTracker: Can't read the last public IP from the database: DatabaseError(\"booga\")",
+        );
+    }
+
+    #[test]
+    fn record_saves_and_reports_a_changed_public_ip() {
+        init_test_logging();
+        let set_last_public_ip_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut persistent_config = PersistentConfigurationMock::new()
+            .last_public_ip_result(Ok(Some(IpAddr::from_str("1.2.3.4").unwrap())))
+            .set_last_public_ip_params(&set_last_public_ip_params_arc)
+            .set_last_public_ip_result(Ok(()));
+        let mut subject = PublicIpTracker::new(&persistent_config);
+
+        let result = subject.record(IpAddr::from_str("4.3.2.1").unwrap(), &mut persistent_config);
+
+        assert_eq!(result, true);
+        assert_eq!(
+            subject.last_public_ip_opt(),
+            Some(IpAddr::from_str("4.3.2.1").unwrap())
+        );
+        assert_eq!(
+            *set_last_public_ip_params_arc.lock().unwrap(),
+            vec![Some(IpAddr::from_str("4.3.2.1").unwrap())]
+        );
+        TestLogHandler::new().exists_log_containing(
+            "INFO: PublicIpTracker: Public IP changed from 1.2.3.4 to 4.3.2.1",
+        );
+    }
+
+    #[test]
+    fn record_ignores_an_unchanged_public_ip() {
+        let mut persistent_config = PersistentConfigurationMock::new()
+            .last_public_ip_result(Ok(Some(IpAddr::from_str("1.2.3.4").unwrap())));
+        let mut subject = PublicIpTracker::new(&persistent_config);
+
+        let result = subject.record(IpAddr::from_str("1.2.3.4").unwrap(), &mut persistent_config);
+
+        // No set_last_public_ip result prepared: the test would panic if it were saved
+        assert_eq!(result, false);
+    }
+
+    #[test]
+    fn record_still_reports_a_change_it_cannot_save() {
+        init_test_logging();
+        let mut persistent_config = PersistentConfigurationMock::new()
+            .last_public_ip_result(Ok(None))
+            .set_last_public_ip_result(Err(PersistentConfigError::NotPresent));
+        let mut subject = PublicIpTracker::new(&persistent_config);
+
+        let result = subject.record(IpAddr::from_str("4.3.2.1").unwrap(), &mut persistent_config);
+
+        assert_eq!(result, true);
+        assert_eq!(
+            subject.last_public_ip_opt(),
+            Some(IpAddr::from_str("4.3.2.1").unwrap())
+        );
+        TestLogHandler::new().exists_log_containing(
+            "WARN: PublicIpTracker: Can't save public IP 4.3.2.1 to the database: NotPresent",
+        );
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use masq_lib::messages::UiShutdownReport;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub const SHUTDOWN_REPORT_FILE_NAME: &str = "shutdown_report.json";
+
+// Written into the data directory by the Node's Accountant as the Node shuts down, and read by the
+// Daemon, which outlives it. Each shutdown replaces the previous report.
+pub fn write_shutdown_report(data_directory: &Path, report: &UiShutdownReport) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(report).expect("Shutdown report won't serialize");
+    fs::write(data_directory.join(SHUTDOWN_REPORT_FILE_NAME), json)
+}
+
+pub fn read_shutdown_report(data_directory: &Path) -> Option<UiShutdownReport> {
+    let json = fs::read_to_string(data_directory.join(SHUTDOWN_REPORT_FILE_NAME)).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::messages::UiBanRisk;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(SHUTDOWN_REPORT_FILE_NAME, "shutdown_report.json");
+    }
+
+    #[test]
+    fn shutdown_report_can_be_written_and_read_back() {
+        let data_dir = ensure_node_home_directory_exists(
+            "shutdown_report",
+            "shutdown_report_can_be_written_and_read_back",
+        );
+        let report = UiShutdownReport {
+            shutdown_timestamp: 1_700_000_000,
+            total_payable_gwei: 1_234_567,
+            total_receivable_gwei: -4_567,
+            unconfirmed_payment_count: 2,
+            ban_risks: vec![UiBanRisk {
+                wallet: "0x0000000000000000000000000000000000001234".to_string(),
+                balance_gwei: 1_000_000,
+                ban_timestamp: 1_700_086_400,
+            }],
+        };
+
+        write_shutdown_report(&data_dir, &report).unwrap();
+
+        assert_eq!(read_shutdown_report(&data_dir), Some(report));
+    }
+
+    #[test]
+    fn missing_shutdown_report_reads_as_none() {
+        let data_dir = ensure_node_home_directory_exists(
+            "shutdown_report",
+            "missing_shutdown_report_reads_as_none",
+        );
+
+        let result = read_shutdown_report(&data_dir);
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn garbled_shutdown_report_reads_as_none() {
+        let data_dir = ensure_node_home_directory_exists(
+            "shutdown_report",
+            "garbled_shutdown_report_reads_as_none",
+        );
+        fs::write(data_dir.join(SHUTDOWN_REPORT_FILE_NAME), "{\"booga\": 12}").unwrap();
+
+        let result = read_shutdown_report(&data_dir);
+
+        assert_eq!(result, None);
+    }
+}
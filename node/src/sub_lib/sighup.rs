@@ -0,0 +1,81 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use lazy_static::lazy_static;
+use nix::errno::Errno;
+use nix::libc::c_int;
+use nix::sys::signal::{sigaction, SaFlags, SigAction, SigHandler, SigSet, Signal};
+use nix::unistd::{pipe, read, write};
+use std::os::unix::io::RawFd;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Mutex, Once};
+use std::thread;
+
+static SIGHUP_PIPE_WRITE_FD: AtomicI32 = AtomicI32::new(-1);
+static INSTALL_SIGHUP_HANDLER: Once = Once::new();
+
+lazy_static! {
+    static ref SIGHUP_CALLBACKS: Mutex<Vec<Box<dyn Fn() + Send>>> = Mutex::new(vec![]);
+}
+
+// A signal handler may do almost nothing safely, so this one only pokes a pipe; the callbacks
+// run on an ordinary thread waiting at the other end.
+extern "C" fn handle_sighup(_signal: c_int) {
+    let write_fd = SIGHUP_PIPE_WRITE_FD.load(Ordering::Relaxed);
+    if write_fd >= 0 {
+        let _ = write(write_fd, &[0u8]);
+    }
+}
+
+pub fn on_sighup(callback: Box<dyn Fn() + Send>) {
+    SIGHUP_CALLBACKS
+        .lock()
+        .expect("SIGHUP callbacks poisoned")
+        .push(callback);
+    INSTALL_SIGHUP_HANDLER.call_once(|| {
+        let (read_fd, write_fd) = pipe().expect("Could not create pipe for SIGHUP");
+        SIGHUP_PIPE_WRITE_FD.store(write_fd, Ordering::Relaxed);
+        thread::spawn(move || wait_for_sighups(read_fd));
+        let action = SigAction::new(
+            SigHandler::Handler(handle_sighup),
+            SaFlags::SA_RESTART,
+            SigSet::empty(),
+        );
+        unsafe { sigaction(Signal::SIGHUP, &action) }.expect("Could not install SIGHUP handler");
+    });
+}
+
+fn wait_for_sighups(read_fd: RawFd) {
+    let mut buffer = [0u8; 16];
+    loop {
+        match read(read_fd, &mut buffer) {
+            Ok(0) => break,
+            Ok(_) => SIGHUP_CALLBACKS
+                .lock()
+                .expect("SIGHUP callbacks poisoned")
+                .iter()
+                .for_each(|callback| callback()),
+            Err(Errno::EINTR) => continue,
+            Err(_) => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam_channel::unbounded;
+    use nix::sys::signal::raise;
+    use std::time::Duration;
+
+    #[test]
+    fn sighup_runs_callback() {
+        let (tx, rx) = unbounded();
+        on_sighup(Box::new(move || {
+            let _ = tx.send(());
+        }));
+
+        raise(Signal::SIGHUP).unwrap();
+
+        rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    }
+}
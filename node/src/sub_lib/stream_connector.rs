@@ -4,10 +4,13 @@ use crate::sub_lib::tokio_wrappers::ReadHalfWrapperReal;
 use crate::sub_lib::tokio_wrappers::WriteHalfWrapper;
 use crate::sub_lib::tokio_wrappers::WriteHalfWrapperReal;
 use masq_lib::logger::Logger;
+use std::fmt;
+use std::fmt::{Display, Formatter};
 use std::io::ErrorKind;
 use std::net::IpAddr;
 use std::net::SocketAddr;
 use std::net::TcpStream as StdTcpStream;
+use std::str::FromStr;
 use std::time::Duration;
 use tokio::io;
 use tokio::io::AsyncRead;
@@ -38,16 +41,102 @@ pub trait StreamConnector: Send {
     fn split_stream(&self, stream: TcpStream, logger: &Logger) -> Option<ConnectionInfo>;
 }
 
-#[derive(Clone)]
-pub struct StreamConnectorReal {}
+// Buffer sizes of zero leave the system defaults alone
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SocketTuning {
+    pub recv_buffer_size: usize,
+    pub send_buffer_size: usize,
+    pub nodelay: bool,
+}
+
+impl SocketTuning {
+    pub fn apply(&self, stream: &TcpStream, logger: &Logger) {
+        if self.recv_buffer_size > 0 {
+            if let Err(e) = stream.set_recv_buffer_size(self.recv_buffer_size) {
+                warning!(
+                    logger,
+                    "Could not set receive buffer size to {} bytes: {}",
+                    self.recv_buffer_size,
+                    e
+                );
+            }
+        }
+        if self.send_buffer_size > 0 {
+            if let Err(e) = stream.set_send_buffer_size(self.send_buffer_size) {
+                warning!(
+                    logger,
+                    "Could not set send buffer size to {} bytes: {}",
+                    self.send_buffer_size,
+                    e
+                );
+            }
+        }
+        if self.nodelay {
+            if let Err(e) = stream.set_nodelay(true) {
+                warning!(logger, "Could not set TCP_NODELAY: {}", e);
+            }
+        }
+    }
+}
+
+impl Display for SocketTuning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}|{}|{}",
+            self.recv_buffer_size,
+            self.send_buffer_size,
+            if self.nodelay { "on" } else { "off" }
+        )
+    }
+}
+
+impl FromStr for SocketTuning {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad_value = || {
+            format!(
+                "Bad socket-tuning value '{}': expected <receive buffer bytes>|<send buffer bytes>|<on or off>",
+                s
+            )
+        };
+        let segments = s.split('|').collect::<Vec<&str>>();
+        match segments.as_slice() {
+            [recv_buffer_size, send_buffer_size, nodelay] => Ok(SocketTuning {
+                recv_buffer_size: recv_buffer_size.parse::<usize>().map_err(|_| bad_value())?,
+                send_buffer_size: send_buffer_size.parse::<usize>().map_err(|_| bad_value())?,
+                nodelay: match *nodelay {
+                    "on" => true,
+                    "off" => false,
+                    _ => return Err(bad_value()),
+                },
+            }),
+            _ => Err(bad_value()),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct StreamConnectorReal {
+    pub socket_tuning: SocketTuning,
+}
+
+impl StreamConnectorReal {
+    pub fn new(socket_tuning: SocketTuning) -> Self {
+        Self { socket_tuning }
+    }
+}
 
 impl StreamConnector for StreamConnectorReal {
     fn connect(&self, socket_addr: SocketAddr, logger: &Logger) -> ConnectionInfoFuture {
         let future_logger = logger.clone();
+        let socket_tuning = self.socket_tuning;
         Box::new(
             Timeout::new(
                 TcpStream::connect(&socket_addr).then(move |result| match result {
                     Ok(stream) => {
+                        socket_tuning.apply(&stream, &future_logger);
                         let local_addr = stream.local_addr().unwrap_or_else(|_| {
                             panic!(
                                 "Newly-connected stream to {} has no local_addr",
@@ -130,6 +219,7 @@ impl StreamConnector for StreamConnectorReal {
     }
 
     fn split_stream(&self, stream: TcpStream, logger: &Logger) -> Option<ConnectionInfo> {
+        self.socket_tuning.apply(&stream, logger);
         let local_addr = stream
             .local_addr()
             .expect("Stream has no local_addr before splitting");
@@ -174,13 +264,79 @@ mod tests {
         assert_eq!(CONNECT_TIMEOUT_MS, 5000);
     }
 
+    #[test]
+    fn socket_tuning_survives_a_round_trip_through_a_string() {
+        let subject = SocketTuning::from_str("1048576|65536|on").unwrap();
+
+        assert_eq!(
+            subject,
+            SocketTuning {
+                recv_buffer_size: 1048576,
+                send_buffer_size: 65536,
+                nodelay: true,
+            }
+        );
+        assert_eq!(subject.to_string(), "1048576|65536|on");
+        assert_eq!(SocketTuning::default().to_string(), "0|0|off");
+    }
+
+    #[test]
+    fn socket_tuning_rejects_bad_values() {
+        vec!["1048576|65536", "1048576|65536|yes", "big|65536|on", "1048576|65536|on|off"]
+            .into_iter()
+            .for_each(|value| {
+                assert_eq!(
+                    SocketTuning::from_str(value),
+                    Err(format!(
+                        "Bad socket-tuning value '{}': expected <receive buffer bytes>|<send buffer bytes>|<on or off>",
+                        value
+                    ))
+                )
+            });
+    }
+
+    #[test]
+    fn socket_tuning_is_applied_to_stream() {
+        let server = LittleTcpServer::start();
+        let std_stream = StdTcpStream::connect(server.socket_addr()).unwrap();
+        let stream = TcpStream::from_std(std_stream, &Handle::default()).unwrap();
+        let subject = SocketTuning {
+            recv_buffer_size: 262144,
+            send_buffer_size: 131072,
+            nodelay: true,
+        };
+
+        subject.apply(&stream, &Logger::new("test"));
+
+        // Some platforms round the requested sizes up, or double them for bookkeeping
+        assert!(stream.recv_buffer_size().unwrap() >= 262144);
+        assert!(stream.send_buffer_size().unwrap() >= 131072);
+        assert_eq!(stream.nodelay().unwrap(), true);
+    }
+
+    #[test]
+    fn default_socket_tuning_leaves_stream_alone() {
+        let server = LittleTcpServer::start();
+        let std_stream = StdTcpStream::connect(server.socket_addr()).unwrap();
+        let stream = TcpStream::from_std(std_stream, &Handle::default()).unwrap();
+        let recv_buffer_size = stream.recv_buffer_size().unwrap();
+        let send_buffer_size = stream.send_buffer_size().unwrap();
+        let nodelay = stream.nodelay().unwrap();
+
+        SocketTuning::default().apply(&stream, &Logger::new("test"));
+
+        assert_eq!(stream.recv_buffer_size().unwrap(), recv_buffer_size);
+        assert_eq!(stream.send_buffer_size().unwrap(), send_buffer_size);
+        assert_eq!(stream.nodelay().unwrap(), nodelay);
+    }
+
     #[test]
     fn stream_connector_can_fail_to_connect() {
         init_test_logging();
         let dead_port = find_free_port();
         let socket_addr = SocketAddr::new(localhost(), dead_port);
         let logger = Logger::new("test");
-        let subject = StreamConnectorReal {};
+        let subject = StreamConnectorReal::default();
 
         let future = subject.connect(socket_addr, &logger);
 
@@ -205,7 +361,7 @@ mod tests {
     fn stream_connector_can_succeed_to_connect() {
         let server = LittleTcpServer::start();
         let logger = Logger::new("test");
-        let subject = StreamConnectorReal {};
+        let subject = StreamConnectorReal::default();
 
         let future = subject.connect(server.socket_addr(), &logger);
 
@@ -227,7 +383,7 @@ mod tests {
         let bogus_ip = IpAddr::from_str("255.255.255.255").unwrap();
         let good_ip = socket_addr.ip();
 
-        let subject = StreamConnectorReal {};
+        let subject = StreamConnectorReal::default();
         let ip_addrs = vec![bogus_ip, good_ip];
 
         let (tx, rx) = unbounded();
@@ -263,7 +419,7 @@ mod tests {
 
         let ip_addr = socket_addr.ip();
 
-        let subject = StreamConnectorReal {};
+        let subject = StreamConnectorReal::default();
         let ip_addrs = vec![ip_addr, ip_addr];
 
         let (connection_info_tx, connection_info_rx) = unbounded();
@@ -299,7 +455,7 @@ mod tests {
 
         let bogus_ip = IpAddr::from_str("255.255.255.255").unwrap();
 
-        let subject = StreamConnectorReal {};
+        let subject = StreamConnectorReal::default();
         let ip_addrs = vec![bogus_ip];
 
         let (tx, rx) = unbounded();
@@ -331,7 +487,7 @@ mod tests {
         thread::sleep(Duration::from_millis(100)); // Shutdown apparently needs time to propagate
         let stream = TcpStream::from_std(std_stream, &Handle::default()).unwrap();
         let logger = Logger::new("either/or");
-        let subject = StreamConnectorReal {};
+        let subject = StreamConnectorReal::default();
 
         let result = subject.split_stream(stream, &logger);
 
@@ -0,0 +1,148 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use actix::Recipient;
+use masq_lib::logger::Logger;
+use masq_lib::messages::{ToMessageBody, UiActorRestartedBroadcast};
+use masq_lib::ui_gateway::{MessageTarget, NodeToUiMessage};
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+// A non-critical actor can run its handlers through this function so that a panic in one of them
+// doesn't unwind through its Arbiter, which would take the whole Node down with it. The panic hook
+// will already have logged the panic and its backtrace by the time this function returns.
+pub fn run_isolated<F>(f: F) -> Result<(), String>
+where
+    F: FnOnce(),
+{
+    catch_unwind(AssertUnwindSafe(f)).map_err(|payload| panic_message(payload.as_ref()))
+}
+
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        (*s).to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "<message indecipherable>".to_string()
+    }
+}
+
+// Counts the restarts of one supervised actor and tells the UIs about each of them
+pub struct RestartReporter {
+    actor: &'static str,
+    restart_count: u32,
+    logger: Logger,
+}
+
+impl RestartReporter {
+    pub fn new(actor: &'static str) -> Self {
+        Self {
+            actor,
+            restart_count: 0,
+            logger: Logger::new(actor),
+        }
+    }
+
+    pub fn restart_count(&self) -> u32 {
+        self.restart_count
+    }
+
+    pub fn report(
+        &mut self,
+        panic_message: String,
+        ui_gateway_opt: Option<&Recipient<NodeToUiMessage>>,
+    ) {
+        self.restart_count += 1;
+        error!(
+            self.logger,
+            "Restarting with clean state (restart #{}) after panic: {}",
+            self.restart_count,
+            panic_message
+        );
+        if let Some(ui_gateway) = ui_gateway_opt {
+            let broadcast = UiActorRestartedBroadcast {
+                actor: self.actor.to_string(),
+                panic_message,
+                restart_count: self.restart_count,
+            };
+            ui_gateway
+                .try_send(NodeToUiMessage {
+                    target: MessageTarget::AllClients,
+                    body: broadcast.tmb(0),
+                })
+                .expect("UiGateway is dead");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::recorder::make_recorder;
+    use actix::{Actor, System};
+    use masq_lib::messages::FromMessageBody;
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+
+    #[test]
+    fn run_isolated_returns_ok_when_nothing_panics() {
+        let mut ran = false;
+
+        let result = run_isolated(|| ran = true);
+
+        assert_eq!(result, Ok(()));
+        assert!(ran);
+    }
+
+    #[test]
+    fn run_isolated_catches_panics_with_str_and_string_payloads() {
+        let str_result = run_isolated(|| panic!("Static message"));
+        let detail = "message";
+        let string_result = run_isolated(|| panic!("Formatted {}", detail));
+
+        assert_eq!(str_result, Err("Static message".to_string()));
+        assert_eq!(string_result, Err("Formatted message".to_string()));
+    }
+
+    #[test]
+    fn panic_message_handles_unprintable_payload() {
+        let result = panic_message(&42_u32);
+
+        assert_eq!(result, "<message indecipherable>".to_string());
+    }
+
+    #[test]
+    fn restart_reporter_counts_restarts_and_broadcasts_them() {
+        init_test_logging();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let system = System::new("restart_reporter_counts_restarts_and_broadcasts_them");
+        let ui_gateway_sub = ui_gateway.start().recipient::<NodeToUiMessage>();
+        let mut subject = RestartReporter::new("SupervisedActor");
+
+        subject.report("First panic".to_string(), Some(&ui_gateway_sub));
+        subject.report("Second panic".to_string(), None);
+
+        System::current().stop();
+        system.run();
+        assert_eq!(subject.restart_count(), 2);
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(ui_gateway_recording.len(), 1);
+        let message = ui_gateway_recording.get_record::<NodeToUiMessage>(0);
+        assert_eq!(message.target, MessageTarget::AllClients);
+        let (broadcast, _) = UiActorRestartedBroadcast::fmb(message.body.clone()).unwrap();
+        assert_eq!(
+            broadcast,
+            UiActorRestartedBroadcast {
+                actor: "SupervisedActor".to_string(),
+                panic_message: "First panic".to_string(),
+                restart_count: 1,
+            }
+        );
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_containing(
+            "ERROR: SupervisedActor: Restarting with clean state (restart #1) after panic: First panic",
+        );
+        tlh.exists_log_containing(
+            "ERROR: SupervisedActor: Restarting with clean state (restart #2) after panic: Second panic",
+        );
+    }
+}
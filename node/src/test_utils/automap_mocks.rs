@@ -1,8 +1,11 @@
 // Copyright (c) 2019-2021, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use crate::actor_system_factory::AutomapControlFactory;
+use crate::sub_lib::public_ip_discovery::{PublicIpDiscovery, StunDiscoveryFactory};
 use automap_lib::comm_layer::AutomapError;
-use automap_lib::control_layer::automap_control::{AutomapControl, ChangeHandler};
+use automap_lib::control_layer::automap_control::{
+    AutomapControl, ChangeHandler, EstablishedMapping,
+};
 use masq_lib::utils::AutomapProtocol;
 use std::cell::RefCell;
 use std::net::IpAddr;
@@ -61,6 +64,8 @@ pub struct AutomapControlMock {
     get_public_ip_results: RefCell<Vec<Result<IpAddr, AutomapError>>>,
     add_mapping_params: Arc<Mutex<Vec<u16>>>,
     add_mapping_results: RefCell<Vec<Result<(), AutomapError>>>,
+    establish_mapping_with_fallback_params: Arc<Mutex<Vec<Vec<u16>>>>,
+    establish_mapping_with_fallback_results: RefCell<Vec<Result<EstablishedMapping, AutomapError>>>,
     delete_mappings_results: RefCell<Vec<Result<(), AutomapError>>>,
     get_mapping_protocol_results: RefCell<Vec<Option<AutomapProtocol>>>,
 }
@@ -75,6 +80,19 @@ impl AutomapControl for AutomapControlMock {
         self.add_mapping_results.borrow_mut().remove(0)
     }
 
+    fn establish_mapping_with_fallback(
+        &mut self,
+        hole_ports: &[u16],
+    ) -> Result<EstablishedMapping, AutomapError> {
+        self.establish_mapping_with_fallback_params
+            .lock()
+            .unwrap()
+            .push(hole_ports.to_vec());
+        self.establish_mapping_with_fallback_results
+            .borrow_mut()
+            .remove(0)
+    }
+
     fn delete_mappings(&mut self) -> Result<(), AutomapError> {
         self.delete_mappings_results.borrow_mut().remove(0)
     }
@@ -96,6 +114,8 @@ impl AutomapControlMock {
             get_public_ip_results: RefCell::new(vec![]),
             add_mapping_params: Arc::new(Mutex::new(vec![])),
             add_mapping_results: RefCell::new(vec![]),
+            establish_mapping_with_fallback_params: Arc::new(Mutex::new(vec![])),
+            establish_mapping_with_fallback_results: RefCell::new(vec![]),
             delete_mappings_results: RefCell::new(vec![]),
             get_mapping_protocol_results: RefCell::new(vec![]),
         }
@@ -116,6 +136,24 @@ impl AutomapControlMock {
         self
     }
 
+    pub fn establish_mapping_with_fallback_params(
+        mut self,
+        params: &Arc<Mutex<Vec<Vec<u16>>>>,
+    ) -> Self {
+        self.establish_mapping_with_fallback_params = params.clone();
+        self
+    }
+
+    pub fn establish_mapping_with_fallback_result(
+        self,
+        result: Result<EstablishedMapping, AutomapError>,
+    ) -> Self {
+        self.establish_mapping_with_fallback_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
+
     pub fn delete_mappings_result(self, result: Result<(), AutomapError>) -> Self {
         self.delete_mappings_results.borrow_mut().push(result);
         self
@@ -126,3 +164,61 @@ impl AutomapControlMock {
         self
     }
 }
+
+pub struct PublicIpDiscoveryMock {
+    method: String,
+    discover_public_ip_results: RefCell<Vec<Result<IpAddr, String>>>,
+}
+
+impl PublicIpDiscovery for PublicIpDiscoveryMock {
+    fn method(&self) -> String {
+        self.method.clone()
+    }
+
+    fn discover_public_ip(&mut self) -> Result<IpAddr, String> {
+        self.discover_public_ip_results.borrow_mut().remove(0)
+    }
+}
+
+impl PublicIpDiscoveryMock {
+    pub fn new(method: &str) -> Self {
+        Self {
+            method: method.to_string(),
+            discover_public_ip_results: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn discover_public_ip_result(self, result: Result<IpAddr, String>) -> Self {
+        self.discover_public_ip_results.borrow_mut().push(result);
+        self
+    }
+}
+
+#[derive(Default)]
+pub struct StunDiscoveryFactoryMock {
+    make_params: Arc<Mutex<Vec<String>>>,
+    make_results: RefCell<Vec<Box<dyn PublicIpDiscovery>>>,
+}
+
+impl StunDiscoveryFactory for StunDiscoveryFactoryMock {
+    fn make(&self, server: &str) -> Box<dyn PublicIpDiscovery> {
+        self.make_params.lock().unwrap().push(server.to_string());
+        self.make_results.borrow_mut().remove(0)
+    }
+}
+
+impl StunDiscoveryFactoryMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn make_params(mut self, params: &Arc<Mutex<Vec<String>>>) -> Self {
+        self.make_params = params.clone();
+        self
+    }
+
+    pub fn make_result(self, result: Box<dyn PublicIpDiscovery>) -> Self {
+        self.make_results.borrow_mut().push(result);
+        self
+    }
+}
@@ -541,7 +541,6 @@ macro_rules! arbitrary_id_stamp_in_trait {
 
 #[cfg(test)]
 pub mod unshared_test_utils {
-    use crate::accountant::DEFAULT_PENDING_TOO_LONG_SEC;
     use crate::apps::app_node;
     use crate::bootstrapper::BootstrapperConfig;
     use crate::daemon::{ChannelFactory, DaemonBindMessage};
@@ -549,11 +548,17 @@ pub mod unshared_test_utils {
     use crate::db_config::config_dao_null::ConfigDaoNull;
     use crate::db_config::persistent_configuration::PersistentConfigurationReal;
     use crate::node_test_utils::DirsWrapperMock;
-    use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals};
-    use crate::sub_lib::neighborhood::{ConnectionProgressMessage, DEFAULT_RATE_PACK};
+    use crate::sub_lib::accountant::{
+        AlertThresholds, PaymentThresholds, PendingPayableLimits, ScanIntervals,
+    };
+    use crate::sub_lib::neighborhood::{
+        ConnectionProgressMessage, Hops, NeighborhoodConfig, NeighborhoodMode, NodeDescriptor,
+        DEFAULT_RATE_PACK,
+    };
     use crate::sub_lib::utils::{
         NLSpawnHandleHolder, NLSpawnHandleHolderReal, NotifyHandle, NotifyLaterHandle,
     };
+    use crate::sub_lib::wallet::Wallet;
     use crate::test_utils::database_utils::bring_db_0_back_to_life_and_return_connection;
     use crate::test_utils::neighborhood_test_utils::MIN_HOPS_FOR_TEST;
     use crate::test_utils::persistent_configuration_mock::PersistentConfigurationMock;
@@ -565,6 +570,7 @@ pub mod unshared_test_utils {
     use crossbeam_channel::{unbounded, Receiver, Sender};
     use itertools::Either;
     use lazy_static::lazy_static;
+    use masq_lib::blockchains::chains::Chain;
     use masq_lib::messages::{ToMessageBody, UiCrashRequest};
     use masq_lib::multi_config::MultiConfig;
     #[cfg(not(feature = "no_test_share"))]
@@ -698,12 +704,121 @@ pub mod unshared_test_utils {
     }
 
     pub fn make_bc_with_defaults() -> BootstrapperConfig {
-        let mut config = BootstrapperConfig::new();
-        config.scan_intervals_opt = Some(ScanIntervals::default());
-        config.suppress_initial_scans = false;
-        config.when_pending_too_long_sec = DEFAULT_PENDING_TOO_LONG_SEC;
-        config.payment_thresholds_opt = Some(PaymentThresholds::default());
-        config
+        BootstrapperConfigBuilder::new().build()
+    }
+
+    // Starts from a config the Accountant can be built from; anything not covered here can still
+    // be set on the result of build()
+    pub struct BootstrapperConfigBuilder {
+        config: BootstrapperConfig,
+    }
+
+    impl Default for BootstrapperConfigBuilder {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl BootstrapperConfigBuilder {
+        pub fn new() -> Self {
+            let mut config = BootstrapperConfig::new();
+            config.scan_intervals_opt = Some(ScanIntervals::default());
+            config.suppress_initial_scans = false;
+            config.pending_payable_limits = PendingPayableLimits::default();
+            config.payment_thresholds_opt = Some(PaymentThresholds::default());
+            Self { config }
+        }
+
+        pub fn neighborhood_config(mut self, neighborhood_config: NeighborhoodConfig) -> Self {
+            self.config.neighborhood_config = neighborhood_config;
+            self
+        }
+
+        pub fn neighborhood_mode(mut self, mode: NeighborhoodMode) -> Self {
+            self.config.neighborhood_config.mode = mode;
+            self
+        }
+
+        pub fn min_hops(mut self, min_hops: Hops) -> Self {
+            self.config.neighborhood_config.min_hops = min_hops;
+            self
+        }
+
+        pub fn node_descriptor(mut self, node_descriptor: NodeDescriptor) -> Self {
+            self.config.node_descriptor = node_descriptor;
+            self
+        }
+
+        pub fn earning_wallet(mut self, earning_wallet: Wallet) -> Self {
+            self.config.earning_wallet = earning_wallet;
+            self
+        }
+
+        pub fn consuming_wallet(mut self, consuming_wallet: Wallet) -> Self {
+            self.config.consuming_wallet_opt = Some(consuming_wallet);
+            self
+        }
+
+        pub fn consuming_wallet_opt(mut self, consuming_wallet_opt: Option<Wallet>) -> Self {
+            self.config.consuming_wallet_opt = consuming_wallet_opt;
+            self
+        }
+
+        pub fn scan_intervals(mut self, scan_intervals: ScanIntervals) -> Self {
+            self.config.scan_intervals_opt = Some(scan_intervals);
+            self
+        }
+
+        pub fn payment_thresholds(mut self, payment_thresholds: PaymentThresholds) -> Self {
+            self.config.payment_thresholds_opt = Some(payment_thresholds);
+            self
+        }
+
+        pub fn suppress_initial_scans(mut self, suppress_initial_scans: bool) -> Self {
+            self.config.suppress_initial_scans = suppress_initial_scans;
+            self
+        }
+
+        pub fn pending_payable_limits(mut self, limits: PendingPayableLimits) -> Self {
+            self.config.pending_payable_limits = limits;
+            self
+        }
+
+        pub fn alert_thresholds(mut self, alert_thresholds: AlertThresholds) -> Self {
+            self.config.alert_thresholds_opt = Some(alert_thresholds);
+            self
+        }
+
+        pub fn chain(mut self, chain: Chain) -> Self {
+            self.config.blockchain_bridge_config.chain = chain;
+            self
+        }
+
+        pub fn blockchain_service_url(mut self, url: &str) -> Self {
+            self.config
+                .blockchain_bridge_config
+                .blockchain_service_url_opt = Some(url.to_string());
+            self
+        }
+
+        pub fn gas_price(mut self, gas_price: u64) -> Self {
+            self.config.blockchain_bridge_config.gas_price = gas_price;
+            self
+        }
+
+        pub fn data_directory(mut self, data_directory: &Path) -> Self {
+            self.config.data_directory = data_directory.to_path_buf();
+            self
+        }
+
+        pub fn ui_port(mut self, ui_port: u16) -> Self {
+            self.config.ui_gateway_config.ui_port = ui_port;
+            self
+        }
+
+        pub fn build(self) -> BootstrapperConfig {
+            self.config
+        }
     }
 
     pub fn make_recipient_and_recording_arc<M: 'static>(
@@ -1223,14 +1338,21 @@ pub mod unshared_test_utils {
 
 #[cfg(test)]
 mod tests {
+    use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals};
+    use crate::sub_lib::blockchain_bridge::BlockchainBridgeConfig;
     use crate::sub_lib::cryptde::CryptData;
     use crate::sub_lib::hop::LiveHop;
-    use crate::sub_lib::neighborhood::ExpectedService;
+    use crate::sub_lib::neighborhood::{
+        ExpectedService, Hops, NeighborhoodConfig, NeighborhoodMode,
+    };
     use crate::test_utils::unshared_test_utils::arbitrary_id_stamp::{
         ArbitraryIdStamp, FirstTraitMock, SecondTraitMock, TestSubject,
     };
+    use crate::test_utils::unshared_test_utils::BootstrapperConfigBuilder;
+    use masq_lib::blockchains::chains::Chain;
     use std::borrow::BorrowMut;
     use std::iter;
+    use std::path::{Path, PathBuf};
     use std::sync::{Arc, Mutex};
     use std::thread;
     use std::time::Duration;
@@ -1401,4 +1523,58 @@ mod tests {
         // exactly the same object that went into the black box at some other point, when the object
         // itself does not otherwise provide enough identifying information to make the assertion.
     }
+
+    #[test]
+    fn bootstrapper_config_builder_starts_from_accountant_ready_defaults_and_applies_overrides() {
+        let defaults = BootstrapperConfigBuilder::new().build();
+        let nc = NeighborhoodConfig {
+            mode: NeighborhoodMode::ConsumeOnly(vec![]),
+            min_hops: Hops::TwoHops,
+        };
+        let scan_intervals = ScanIntervals {
+            pending_payable_scan_interval: Duration::from_secs(1),
+            ..ScanIntervals::default()
+        };
+
+        let result = BootstrapperConfigBuilder::new()
+            .neighborhood_config(nc.clone())
+            .earning_wallet(make_wallet("earning"))
+            .consuming_wallet(make_wallet("consuming"))
+            .scan_intervals(scan_intervals)
+            .suppress_initial_scans(true)
+            .chain(Chain::PolyMainnet)
+            .blockchain_service_url("https://booga.com")
+            .gas_price(123)
+            .data_directory(Path::new("/home/booga"))
+            .ui_port(4321)
+            .build();
+
+        assert_eq!(defaults.scan_intervals_opt, Some(ScanIntervals::default()));
+        assert_eq!(
+            defaults.payment_thresholds_opt,
+            Some(PaymentThresholds::default())
+        );
+        assert_eq!(defaults.suppress_initial_scans, false);
+        assert_eq!(result.neighborhood_config, nc);
+        assert_eq!(result.earning_wallet, make_wallet("earning"));
+        assert_eq!(result.consuming_wallet_opt, Some(make_wallet("consuming")));
+        assert_eq!(result.scan_intervals_opt, Some(scan_intervals));
+        assert_eq!(result.suppress_initial_scans, true);
+        assert_eq!(
+            result.blockchain_bridge_config,
+            BlockchainBridgeConfig {
+                blockchain_service_url_opt: Some("https://booga.com".to_string()),
+                chain: Chain::PolyMainnet,
+                token_contract_address_opt: None,
+                gas_price: 123,
+                failover_opt: None,
+            }
+        );
+        assert_eq!(result.data_directory, PathBuf::from("/home/booga"));
+        assert_eq!(result.ui_gateway_config.ui_port, 4321);
+        assert_eq!(
+            result.payment_thresholds_opt,
+            Some(PaymentThresholds::default())
+        );
+    }
 }
@@ -118,15 +118,19 @@ impl From<&NodeRecord> for NeighborhoodMode {
             node.node_addr_opt(),
             node.accepts_connections(),
             node.routes_data(),
+            node.exits_data(),
         ) {
-            (Some(node_addr), true, true) => {
+            (Some(node_addr), true, true, true) => {
                 NeighborhoodMode::Standard(node_addr, vec![], *node.rate_pack())
             }
-            (_, false, true) => NeighborhoodMode::OriginateOnly(vec![], *node.rate_pack()),
-            (_, false, false) => NeighborhoodMode::ConsumeOnly(vec![]),
-            (node_addr_opt, accepts_connections, routes_data) => panic!(
-                "Cannot determine NeighborhoodMode from triple: ({:?}, {}, {})",
-                node_addr_opt, accepts_connections, routes_data
+            (Some(node_addr), true, true, false) => {
+                NeighborhoodMode::RelayOnly(node_addr, vec![], *node.rate_pack())
+            }
+            (_, false, true, _) => NeighborhoodMode::OriginateOnly(vec![], *node.rate_pack()),
+            (_, false, false, _) => NeighborhoodMode::ConsumeOnly(vec![]),
+            (node_addr_opt, accepts_connections, routes_data, exits_data) => panic!(
+                "Cannot determine NeighborhoodMode from quadruple: ({:?}, {}, {}, {})",
+                node_addr_opt, accepts_connections, routes_data, exits_data
             ),
         }
     }
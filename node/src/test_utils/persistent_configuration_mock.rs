@@ -2,18 +2,24 @@
 
 #![cfg(test)]
 
+use crate::blockchain::blockchain_interface::data_structures::ScannedBlock;
 use crate::database::rusqlite_wrappers::TransactionSafeWrapper;
+use crate::database::sqlite_pragmas::SqlitePragmas;
 use crate::db_config::persistent_configuration::{PersistentConfigError, PersistentConfiguration};
+use crate::neighborhood::topology_snapshot::TopologySnapshot;
 use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals};
 use crate::sub_lib::neighborhood::{Hops, NodeDescriptor, RatePack};
+use crate::sub_lib::operator_notes::OperatorNotes;
 use crate::sub_lib::wallet::Wallet;
 use crate::test_utils::unshared_test_utils::arbitrary_id_stamp::ArbitraryIdStamp;
 use crate::{arbitrary_id_stamp_in_trait_impl, set_arbitrary_id_stamp_in_mock_impl};
 use masq_lib::utils::AutomapProtocol;
 use masq_lib::utils::NeighborhoodModeLight;
 use std::cell::RefCell;
+use std::net::IpAddr;
 use std::sync::{Arc, Mutex};
 use std::u64;
+use web3::types::Address;
 
 #[allow(clippy::type_complexity)]
 #[derive(Clone, Default)]
@@ -31,6 +37,9 @@ pub struct PersistentConfigurationMock {
     clandestine_port_results: RefCell<Vec<Result<u16, PersistentConfigError>>>,
     set_clandestine_port_params: Arc<Mutex<Vec<u16>>>,
     set_clandestine_port_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
+    clandestine_port_rotates_results: RefCell<Vec<Result<bool, PersistentConfigError>>>,
+    set_clandestine_port_rotates_params: Arc<Mutex<Vec<bool>>>,
+    set_clandestine_port_rotates_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
     gas_price_results: RefCell<Vec<Result<u64, PersistentConfigError>>>,
     set_gas_price_params: Arc<Mutex<Vec<u64>>>,
     set_gas_price_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
@@ -43,6 +52,9 @@ pub struct PersistentConfigurationMock {
     earning_wallet_address_results: RefCell<Vec<Result<Option<String>, PersistentConfigError>>>,
     set_wallet_info_params: Arc<Mutex<Vec<(String, String, String)>>>,
     set_wallet_info_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
+    last_public_ip_results: RefCell<Vec<Result<Option<IpAddr>, PersistentConfigError>>>,
+    set_last_public_ip_params: Arc<Mutex<Vec<Option<IpAddr>>>>,
+    set_last_public_ip_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
     mapping_protocol_results: RefCell<Vec<Result<Option<AutomapProtocol>, PersistentConfigError>>>,
     set_mapping_protocol_params: Arc<Mutex<Vec<Option<AutomapProtocol>>>>,
     set_mapping_protocol_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
@@ -67,6 +79,11 @@ pub struct PersistentConfigurationMock {
     set_max_block_count_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
     set_start_block_from_txn_params: Arc<Mutex<Vec<(Option<u64>, ArbitraryIdStamp)>>>,
     set_start_block_from_txn_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
+    scanned_blocks_results: RefCell<Vec<Result<Vec<ScannedBlock>, PersistentConfigError>>>,
+    set_scanned_blocks_params: Arc<Mutex<Vec<Vec<ScannedBlock>>>>,
+    set_scanned_blocks_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
+    set_scanned_blocks_from_txn_params: Arc<Mutex<Vec<(Vec<ScannedBlock>, ArbitraryIdStamp)>>>,
+    set_scanned_blocks_from_txn_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
     payment_thresholds_results: RefCell<Vec<Result<PaymentThresholds, PersistentConfigError>>>,
     set_payment_thresholds_params: Arc<Mutex<Vec<String>>>,
     set_payment_thresholds_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
@@ -76,6 +93,23 @@ pub struct PersistentConfigurationMock {
     scan_intervals_results: RefCell<Vec<Result<ScanIntervals, PersistentConfigError>>>,
     set_scan_intervals_params: Arc<Mutex<Vec<String>>>,
     set_scan_intervals_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
+    sqlite_pragmas_results: RefCell<Vec<Result<SqlitePragmas, PersistentConfigError>>>,
+    set_sqlite_pragmas_params: Arc<Mutex<Vec<SqlitePragmas>>>,
+    set_sqlite_pragmas_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
+    token_contract_address_results: RefCell<Vec<Result<Option<Address>, PersistentConfigError>>>,
+    set_token_contract_address_params: Arc<Mutex<Vec<Address>>>,
+    set_token_contract_address_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
+    neighbor_notes_results: RefCell<Vec<Result<Vec<OperatorNotes>, PersistentConfigError>>>,
+    set_neighbor_notes_params: Arc<Mutex<Vec<Vec<OperatorNotes>>>>,
+    set_neighbor_notes_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
+    traffic_db_path_results: RefCell<Vec<Result<Option<String>, PersistentConfigError>>>,
+    set_traffic_db_path_params: Arc<Mutex<Vec<Option<String>>>>,
+    set_traffic_db_path_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
+    topology_snapshot_params: Arc<Mutex<Vec<String>>>,
+    topology_snapshot_results:
+        RefCell<Vec<Result<Option<TopologySnapshot>, PersistentConfigError>>>,
+    set_topology_snapshot_params: Arc<Mutex<Vec<(Option<TopologySnapshot>, String)>>>,
+    set_topology_snapshot_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
     arbitrary_id_stamp_opt: Option<ArbitraryIdStamp>,
 }
 
@@ -154,6 +188,20 @@ impl PersistentConfiguration for PersistentConfigurationMock {
         self.set_clandestine_port_results.borrow_mut().remove(0)
     }
 
+    fn clandestine_port_rotates(&self) -> Result<bool, PersistentConfigError> {
+        self.clandestine_port_rotates_results.borrow_mut().remove(0)
+    }
+
+    fn set_clandestine_port_rotates(&mut self, rotates: bool) -> Result<(), PersistentConfigError> {
+        self.set_clandestine_port_rotates_params
+            .lock()
+            .unwrap()
+            .push(rotates);
+        self.set_clandestine_port_rotates_results
+            .borrow_mut()
+            .remove(0)
+    }
+
     fn earning_wallet(&self) -> Result<Option<Wallet>, PersistentConfigError> {
         Self::result_from(&self.earning_wallet_results)
     }
@@ -171,6 +219,15 @@ impl PersistentConfiguration for PersistentConfigurationMock {
         self.set_gas_price_results.borrow_mut().remove(0)
     }
 
+    fn last_public_ip(&self) -> Result<Option<IpAddr>, PersistentConfigError> {
+        self.last_public_ip_results.borrow_mut().remove(0)
+    }
+
+    fn set_last_public_ip(&mut self, value: Option<IpAddr>) -> Result<(), PersistentConfigError> {
+        self.set_last_public_ip_params.lock().unwrap().push(value);
+        self.set_last_public_ip_results.borrow_mut().remove(0)
+    }
+
     fn mapping_protocol(&self) -> Result<Option<AutomapProtocol>, PersistentConfigError> {
         self.mapping_protocol_results.borrow_mut().remove(0)
     }
@@ -261,6 +318,30 @@ impl PersistentConfiguration for PersistentConfigurationMock {
             .push((value, transaction.arbitrary_id_stamp()));
         Self::result_from(&self.set_start_block_from_txn_results)
     }
+
+    fn scanned_blocks(&self) -> Result<Vec<ScannedBlock>, PersistentConfigError> {
+        Self::result_from(&self.scanned_blocks_results)
+    }
+
+    fn set_scanned_blocks(&mut self, blocks: &[ScannedBlock]) -> Result<(), PersistentConfigError> {
+        self.set_scanned_blocks_params
+            .lock()
+            .unwrap()
+            .push(blocks.to_vec());
+        Self::result_from(&self.set_scanned_blocks_results)
+    }
+
+    fn set_scanned_blocks_from_txn(
+        &mut self,
+        blocks: &[ScannedBlock],
+        transaction: &mut TransactionSafeWrapper,
+    ) -> Result<(), PersistentConfigError> {
+        self.set_scanned_blocks_from_txn_params
+            .lock()
+            .unwrap()
+            .push((blocks.to_vec(), transaction.arbitrary_id_stamp()));
+        Self::result_from(&self.set_scanned_blocks_from_txn_results)
+    }
     fn set_wallet_info(
         &mut self,
         consuming_wallet_private_key: &str,
@@ -308,6 +389,82 @@ impl PersistentConfiguration for PersistentConfigurationMock {
         self.set_scan_intervals_results.borrow_mut().remove(0)
     }
 
+    fn sqlite_pragmas(&self) -> Result<SqlitePragmas, PersistentConfigError> {
+        self.sqlite_pragmas_results.borrow_mut().remove(0)
+    }
+
+    fn set_sqlite_pragmas(&mut self, pragmas: SqlitePragmas) -> Result<(), PersistentConfigError> {
+        self.set_sqlite_pragmas_params.lock().unwrap().push(pragmas);
+        self.set_sqlite_pragmas_results.borrow_mut().remove(0)
+    }
+
+    fn token_contract_address(&self) -> Result<Option<Address>, PersistentConfigError> {
+        self.token_contract_address_results.borrow_mut().remove(0)
+    }
+
+    fn set_token_contract_address(
+        &mut self,
+        address: Address,
+    ) -> Result<(), PersistentConfigError> {
+        self.set_token_contract_address_params
+            .lock()
+            .unwrap()
+            .push(address);
+        self.set_token_contract_address_results
+            .borrow_mut()
+            .remove(0)
+    }
+
+    fn neighbor_notes(&self) -> Result<Vec<OperatorNotes>, PersistentConfigError> {
+        self.neighbor_notes_results.borrow_mut().remove(0)
+    }
+
+    fn set_neighbor_notes(
+        &mut self,
+        notes: Vec<OperatorNotes>,
+    ) -> Result<(), PersistentConfigError> {
+        self.set_neighbor_notes_params.lock().unwrap().push(notes);
+        self.set_neighbor_notes_results.borrow_mut().remove(0)
+    }
+
+    fn traffic_db_path(&self) -> Result<Option<String>, PersistentConfigError> {
+        self.traffic_db_path_results.borrow_mut().remove(0)
+    }
+
+    fn set_traffic_db_path(
+        &mut self,
+        path_opt: Option<String>,
+    ) -> Result<(), PersistentConfigError> {
+        self.set_traffic_db_path_params
+            .lock()
+            .unwrap()
+            .push(path_opt);
+        self.set_traffic_db_path_results.borrow_mut().remove(0)
+    }
+
+    fn topology_snapshot(
+        &self,
+        db_password: &str,
+    ) -> Result<Option<TopologySnapshot>, PersistentConfigError> {
+        self.topology_snapshot_params
+            .lock()
+            .unwrap()
+            .push(db_password.to_string());
+        self.topology_snapshot_results.borrow_mut().remove(0)
+    }
+
+    fn set_topology_snapshot(
+        &mut self,
+        snapshot_opt: Option<TopologySnapshot>,
+        db_password: &str,
+    ) -> Result<(), PersistentConfigError> {
+        self.set_topology_snapshot_params
+            .lock()
+            .unwrap()
+            .push((snapshot_opt, db_password.to_string()));
+        self.set_topology_snapshot_results.borrow_mut().remove(0)
+    }
+
     arbitrary_id_stamp_in_trait_impl!();
 }
 
@@ -397,6 +554,31 @@ impl PersistentConfigurationMock {
         self
     }
 
+    pub fn clandestine_port_rotates_result(
+        self,
+        result: Result<bool, PersistentConfigError>,
+    ) -> Self {
+        self.clandestine_port_rotates_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
+
+    pub fn set_clandestine_port_rotates_params(mut self, params: &Arc<Mutex<Vec<bool>>>) -> Self {
+        self.set_clandestine_port_rotates_params = params.clone();
+        self
+    }
+
+    pub fn set_clandestine_port_rotates_result(
+        self,
+        result: Result<(), PersistentConfigError>,
+    ) -> Self {
+        self.set_clandestine_port_rotates_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
+
     pub fn min_hops_result(self, result: Result<Hops, PersistentConfigError>) -> Self {
         self.min_hops_results.borrow_mut().push(result);
         self
@@ -602,6 +784,45 @@ impl PersistentConfigurationMock {
         self
     }
 
+    pub fn scanned_blocks_result(
+        self,
+        result: Result<Vec<ScannedBlock>, PersistentConfigError>,
+    ) -> Self {
+        self.scanned_blocks_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn set_scanned_blocks_params(
+        mut self,
+        params: &Arc<Mutex<Vec<Vec<ScannedBlock>>>>,
+    ) -> Self {
+        self.set_scanned_blocks_params = params.clone();
+        self
+    }
+
+    pub fn set_scanned_blocks_result(self, result: Result<(), PersistentConfigError>) -> Self {
+        self.set_scanned_blocks_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn set_scanned_blocks_from_txn_params(
+        mut self,
+        params: &Arc<Mutex<Vec<(Vec<ScannedBlock>, ArbitraryIdStamp)>>>,
+    ) -> Self {
+        self.set_scanned_blocks_from_txn_params = params.clone();
+        self
+    }
+
+    pub fn set_scanned_blocks_from_txn_result(
+        self,
+        result: Result<(), PersistentConfigError>,
+    ) -> Self {
+        self.set_scanned_blocks_from_txn_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
+
     pub fn payment_thresholds_result(
         self,
         result: Result<PaymentThresholds, PersistentConfigError>,
@@ -655,6 +876,133 @@ impl PersistentConfigurationMock {
         self
     }
 
+    pub fn sqlite_pragmas_result(
+        self,
+        result: Result<SqlitePragmas, PersistentConfigError>,
+    ) -> Self {
+        self.sqlite_pragmas_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn set_sqlite_pragmas_params(mut self, params: &Arc<Mutex<Vec<SqlitePragmas>>>) -> Self {
+        self.set_sqlite_pragmas_params = params.clone();
+        self
+    }
+
+    pub fn set_sqlite_pragmas_result(self, result: Result<(), PersistentConfigError>) -> Self {
+        self.set_sqlite_pragmas_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn token_contract_address_result(
+        self,
+        result: Result<Option<Address>, PersistentConfigError>,
+    ) -> Self {
+        self.token_contract_address_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
+
+    pub fn set_token_contract_address_params(mut self, params: &Arc<Mutex<Vec<Address>>>) -> Self {
+        self.set_token_contract_address_params = params.clone();
+        self
+    }
+
+    pub fn set_token_contract_address_result(
+        self,
+        result: Result<(), PersistentConfigError>,
+    ) -> Self {
+        self.set_token_contract_address_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
+
+    pub fn neighbor_notes_result(
+        self,
+        result: Result<Vec<OperatorNotes>, PersistentConfigError>,
+    ) -> Self {
+        self.neighbor_notes_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn set_neighbor_notes_params(
+        mut self,
+        params: &Arc<Mutex<Vec<Vec<OperatorNotes>>>>,
+    ) -> Self {
+        self.set_neighbor_notes_params = params.clone();
+        self
+    }
+
+    pub fn set_neighbor_notes_result(self, result: Result<(), PersistentConfigError>) -> Self {
+        self.set_neighbor_notes_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn traffic_db_path_result(
+        self,
+        result: Result<Option<String>, PersistentConfigError>,
+    ) -> Self {
+        self.traffic_db_path_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn set_traffic_db_path_params(mut self, params: &Arc<Mutex<Vec<Option<String>>>>) -> Self {
+        self.set_traffic_db_path_params = params.clone();
+        self
+    }
+
+    pub fn set_traffic_db_path_result(self, result: Result<(), PersistentConfigError>) -> Self {
+        self.set_traffic_db_path_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn topology_snapshot_params(mut self, params: &Arc<Mutex<Vec<String>>>) -> Self {
+        self.topology_snapshot_params = params.clone();
+        self
+    }
+
+    pub fn topology_snapshot_result(
+        self,
+        result: Result<Option<TopologySnapshot>, PersistentConfigError>,
+    ) -> Self {
+        self.topology_snapshot_results.borrow_mut().push(result);
+        self
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn set_topology_snapshot_params(
+        mut self,
+        params: &Arc<Mutex<Vec<(Option<TopologySnapshot>, String)>>>,
+    ) -> Self {
+        self.set_topology_snapshot_params = params.clone();
+        self
+    }
+
+    pub fn set_topology_snapshot_result(self, result: Result<(), PersistentConfigError>) -> Self {
+        self.set_topology_snapshot_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn last_public_ip_result(
+        self,
+        result: Result<Option<IpAddr>, PersistentConfigError>,
+    ) -> Self {
+        self.last_public_ip_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn set_last_public_ip_params(mut self, params: &Arc<Mutex<Vec<Option<IpAddr>>>>) -> Self {
+        self.set_last_public_ip_params = params.clone();
+        self
+    }
+
+    pub fn set_last_public_ip_result(self, result: Result<(), PersistentConfigError>) -> Self {
+        self.set_last_public_ip_results.borrow_mut().push(result);
+        self
+    }
+
     pub fn mapping_protocol_result(
         self,
         result: Result<Option<AutomapProtocol>, PersistentConfigError>,
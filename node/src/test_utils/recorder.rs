@@ -3,40 +3,45 @@
 
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::BlockchainAgentWithContextMessage;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::QualifiedPayablesMessage;
-use crate::accountant::ReportTransactionReceipts;
 use crate::accountant::{
     ReceivedPayments, RequestTransactionReceipts, ScanError, ScanForPayables,
     ScanForPendingPayables, ScanForReceivables, SentPayables,
 };
+use crate::accountant::{ReportPaymentReceipt, ReportTransactionReceipts};
 use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
 use crate::blockchain::blockchain_bridge::RetrieveTransactions;
 use crate::daemon::crash_notification::CrashNotification;
 use crate::daemon::DaemonBindMessage;
-use crate::neighborhood::gossip::Gossip_0v1;
+use crate::neighborhood::gossip::ReceivedGossip;
 use crate::stream_messages::{AddStreamMsg, PoolBindMessage, RemoveStreamMsg};
 use crate::sub_lib::accountant::AccountantSubs;
 use crate::sub_lib::accountant::ReportExitServiceProvidedMessage;
 use crate::sub_lib::accountant::ReportRoutingServiceProvidedMessage;
 use crate::sub_lib::accountant::ReportServicesConsumedMessage;
+use crate::sub_lib::accountant::{CreditorsToAvoid, CreditorsToAvoidQuery};
+use crate::sub_lib::accountant::{PaymentOverdue_0v1, ReportPaymentOverdueMessage};
+use crate::sub_lib::accountant::{ShutdownReportDone, ShutdownReportQuery};
 use crate::sub_lib::blockchain_bridge::BlockchainBridgeSubs;
 use crate::sub_lib::blockchain_bridge::OutboundPaymentsInstructions;
 use crate::sub_lib::dispatcher::InboundClientData;
 use crate::sub_lib::dispatcher::{DispatcherSubs, StreamShutdownMsg};
+use crate::sub_lib::health_check::{GossipHeartbeat, HealthCheckSubs};
 use crate::sub_lib::hopper::IncipientCoresPackage;
 use crate::sub_lib::hopper::{ExpiredCoresPackage, NoLookupIncipientCoresPackage};
-use crate::sub_lib::hopper::{HopperSubs, MessageType};
+use crate::sub_lib::hopper::{HopperSubs, MessageType, SendCoverTraffic};
 use crate::sub_lib::neighborhood::NeighborhoodSubs;
 use crate::sub_lib::neighborhood::{ConfigChangeMsg, ConnectionProgressMessage};
 
-use crate::sub_lib::configurator::ConfiguratorSubs;
+use crate::sub_lib::configurator::{ConfiguratorSubs, ReloadConfigFile};
 use crate::sub_lib::neighborhood::NodeQueryResponseMetadata;
 use crate::sub_lib::neighborhood::RemoveNeighborMessage;
 use crate::sub_lib::neighborhood::RouteQueryMessage;
 use crate::sub_lib::neighborhood::RouteQueryResponse;
 use crate::sub_lib::neighborhood::UpdateNodeRecordMetadataMessage;
-use crate::sub_lib::neighborhood::{DispatcherNodeQueryMessage, GossipFailure_0v1};
+use crate::sub_lib::neighborhood::{DispatcherNodeQueryMessage, GossipFailure_0v1, Rendezvous_0v1};
 use crate::sub_lib::peer_actors::PeerActors;
 use crate::sub_lib::peer_actors::{BindMessage, NewPublicIp, StartMessage};
+use crate::sub_lib::proxy_client::PaymentOverdueNoticeMessage;
 use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, InboundServerData};
 use crate::sub_lib::proxy_client::{DnsResolveFailure_0v1, ProxyClientSubs};
 use crate::sub_lib::proxy_server::{
@@ -131,6 +136,8 @@ recorder_message_handler_t_p!(BlockchainAgentWithContextMessage);
 recorder_message_handler_t_m_p!(ConfigChangeMsg);
 recorder_message_handler_t_m_p!(ConnectionProgressMessage);
 recorder_message_handler_t_m_p!(CrashNotification);
+recorder_message_handler_t_m_p!(CreditorsToAvoid);
+recorder_message_handler_t_m_p!(CreditorsToAvoidQuery);
 recorder_message_handler_t_m_p!(DaemonBindMessage);
 recorder_message_handler_t_m_p!(DispatcherNodeQueryMessage);
 recorder_message_handler_t_m_p!(DispatcherNodeQueryResponse);
@@ -138,9 +145,12 @@ recorder_message_handler_t_m_p!(DnsResolveFailure_0v1);
 recorder_message_handler_t_m_p!(ExpiredCoresPackage<ClientRequestPayload_0v1>);
 recorder_message_handler_t_m_p!(ExpiredCoresPackage<ClientResponsePayload_0v1>);
 recorder_message_handler_t_m_p!(ExpiredCoresPackage<DnsResolveFailure_0v1>);
-recorder_message_handler_t_m_p!(ExpiredCoresPackage<Gossip_0v1>);
+recorder_message_handler_t_m_p!(ExpiredCoresPackage<ReceivedGossip>);
 recorder_message_handler_t_m_p!(ExpiredCoresPackage<GossipFailure_0v1>);
+recorder_message_handler_t_m_p!(ExpiredCoresPackage<Rendezvous_0v1>);
 recorder_message_handler_t_m_p!(ExpiredCoresPackage<MessageType>);
+recorder_message_handler_t_m_p!(ExpiredCoresPackage<PaymentOverdue_0v1>);
+recorder_message_handler_t_m_p!(GossipHeartbeat);
 recorder_message_handler_t_m_p!(InboundClientData);
 recorder_message_handler_t_m_p!(InboundServerData);
 recorder_message_handler_t_m_p!(IncipientCoresPackage);
@@ -149,13 +159,17 @@ recorder_message_handler_t_m_p!(NodeFromUiMessage);
 recorder_message_handler_t_m_p!(NodeToUiMessage);
 recorder_message_handler_t_m_p!(NoLookupIncipientCoresPackage);
 recorder_message_handler_t_p!(OutboundPaymentsInstructions);
+recorder_message_handler_t_m_p!(PaymentOverdueNoticeMessage);
 recorder_message_handler_t_m_p!(PendingPayableFingerprintSeeds);
 recorder_message_handler_t_m_p!(PoolBindMessage);
 recorder_message_handler_t_m_p!(QualifiedPayablesMessage);
 recorder_message_handler_t_m_p!(ReceivedPayments);
+recorder_message_handler_t_m_p!(ReloadConfigFile);
 recorder_message_handler_t_m_p!(RemoveNeighborMessage);
 recorder_message_handler_t_m_p!(RemoveStreamMsg);
 recorder_message_handler_t_m_p!(ReportExitServiceProvidedMessage);
+recorder_message_handler_t_m_p!(ReportPaymentOverdueMessage);
+recorder_message_handler_t_m_p!(ReportPaymentReceipt);
 recorder_message_handler_t_m_p!(ReportRoutingServiceProvidedMessage);
 recorder_message_handler_t_m_p!(ReportServicesConsumedMessage);
 recorder_message_handler_t_m_p!(ReportTransactionReceipts);
@@ -165,7 +179,10 @@ recorder_message_handler_t_m_p!(ScanError);
 recorder_message_handler_t_m_p!(ScanForPayables);
 recorder_message_handler_t_m_p!(ScanForPendingPayables);
 recorder_message_handler_t_m_p!(ScanForReceivables);
+recorder_message_handler_t_m_p!(SendCoverTraffic);
 recorder_message_handler_t_m_p!(SentPayables);
+recorder_message_handler_t_m_p!(ShutdownReportDone);
+recorder_message_handler_t_m_p!(ShutdownReportQuery);
 recorder_message_handler_t_m_p!(StartMessage);
 recorder_message_handler_t_m_p!(StreamShutdownMsg);
 recorder_message_handler_t_m_p!(TransmitDataMsg);
@@ -401,6 +418,7 @@ pub fn make_proxy_server_subs_from_recorder(addr: &Addr<Recorder>) -> ProxyServe
         node_from_ui: recipient!(addr, NodeFromUiMessage),
         route_result_sub: recipient!(addr, AddRouteResultMessage),
         schedule_stream_key_purge: recipient!(addr, MessageScheduler<StreamKeyPurge>),
+        payment_overdue_from_hopper: recipient!(addr, ExpiredCoresPackage<PaymentOverdue_0v1>),
     }
 }
 
@@ -421,6 +439,7 @@ pub fn make_hopper_subs_from_recorder(addr: &Addr<Recorder>) -> HopperSubs {
         from_hopper_client: recipient!(addr, IncipientCoresPackage),
         from_hopper_client_no_lookup: recipient!(addr, NoLookupIncipientCoresPackage),
         from_dispatcher: recipient!(addr, InboundClientData),
+        send_cover_traffic: recipient!(addr, SendCoverTraffic),
         node_from_ui: recipient!(addr, NodeFromUiMessage),
     }
 }
@@ -432,6 +451,7 @@ pub fn make_proxy_client_subs_from_recorder(addr: &Addr<Recorder>) -> ProxyClien
         inbound_server_data: recipient!(addr, InboundServerData),
         dns_resolve_failed: recipient!(addr, DnsResolveFailure_0v1),
         node_from_ui: recipient!(addr, NodeFromUiMessage),
+        payment_overdue_notice: recipient!(addr, PaymentOverdueNoticeMessage),
     }
 }
 
@@ -442,8 +462,9 @@ pub fn make_neighborhood_subs_from_recorder(addr: &Addr<Recorder>) -> Neighborho
         new_public_ip: recipient!(addr, NewPublicIp),
         route_query: recipient!(addr, RouteQueryMessage),
         update_node_record_metadata: recipient!(addr, UpdateNodeRecordMetadataMessage),
-        from_hopper: recipient!(addr, ExpiredCoresPackage<Gossip_0v1>),
+        from_hopper: recipient!(addr, ExpiredCoresPackage<ReceivedGossip>),
         gossip_failure: recipient!(addr, ExpiredCoresPackage<GossipFailure_0v1>),
+        rendezvous: recipient!(addr, ExpiredCoresPackage<Rendezvous_0v1>),
         dispatcher_node_query: recipient!(addr, DispatcherNodeQueryMessage),
         remove_neighbor: recipient!(addr, RemoveNeighborMessage),
         config_change_msg_sub: recipient!(addr, ConfigChangeMsg),
@@ -465,9 +486,13 @@ pub fn make_accountant_subs_from_recorder(addr: &Addr<Recorder>) -> AccountantSu
         report_inbound_payments: recipient!(addr, ReceivedPayments),
         init_pending_payable_fingerprints: recipient!(addr, PendingPayableFingerprintSeeds),
         report_transaction_receipts: recipient!(addr, ReportTransactionReceipts),
+        report_payment_receipt: recipient!(addr, ReportPaymentReceipt),
         report_sent_payments: recipient!(addr, SentPayables),
         scan_errors: recipient!(addr, ScanError),
         ui_message_sub: recipient!(addr, NodeFromUiMessage),
+        creditors_to_avoid_query: recipient!(addr, CreditorsToAvoidQuery),
+        shutdown_report_query: recipient!(addr, ShutdownReportQuery),
+        report_payment_overdue: recipient!(addr, ReportPaymentOverdueMessage),
     }
 }
 
@@ -494,6 +519,14 @@ pub fn make_configurator_subs_from_recorder(addr: &Addr<Recorder>) -> Configurat
     ConfiguratorSubs {
         bind: recipient!(addr, BindMessage),
         node_from_ui_sub: recipient!(addr, NodeFromUiMessage),
+        reload_config_file: recipient!(addr, ReloadConfigFile),
+    }
+}
+
+pub fn make_health_check_subs_from_recorder(addr: &Addr<Recorder>) -> HealthCheckSubs {
+    HealthCheckSubs {
+        bind: recipient!(addr, BindMessage),
+        gossip_heartbeat: recipient!(addr, GossipHeartbeat),
     }
 }
 
@@ -512,6 +545,7 @@ pub struct PeerActorsBuilder {
     ui_gateway: Recorder,
     blockchain_bridge: Recorder,
     configurator: Recorder,
+    health_check: Recorder,
 }
 
 impl PeerActorsBuilder {
@@ -526,6 +560,7 @@ impl PeerActorsBuilder {
             ui_gateway: Recorder::new(),
             blockchain_bridge: Recorder::new(),
             configurator: Recorder::new(),
+            health_check: Recorder::new(),
         }
     }
 
@@ -574,6 +609,11 @@ impl PeerActorsBuilder {
         self
     }
 
+    pub fn health_check(mut self, recorder: Recorder) -> PeerActorsBuilder {
+        self.health_check = recorder;
+        self
+    }
+
     // This must be called after System.new and before System.run
     pub fn build(self) -> PeerActors {
         let proxy_server_addr = self.proxy_server.start();
@@ -585,6 +625,7 @@ impl PeerActorsBuilder {
         let ui_gateway_addr = self.ui_gateway.start();
         let blockchain_bridge_addr = self.blockchain_bridge.start();
         let configurator_addr = self.configurator.start();
+        let health_check_addr = self.health_check.start();
 
         PeerActors {
             proxy_server: make_proxy_server_subs_from_recorder(&proxy_server_addr),
@@ -596,6 +637,7 @@ impl PeerActorsBuilder {
             ui_gateway: make_ui_gateway_subs_from_recorder(&ui_gateway_addr),
             blockchain_bridge: make_blockchain_bridge_subs_from_recorder(&blockchain_bridge_addr),
             configurator: make_configurator_subs_from_recorder(&configurator_addr),
+            health_check_opt: Some(make_health_check_subs_from_recorder(&health_check_addr)),
         }
     }
 }
@@ -0,0 +1,202 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::accountant::db_access_objects::utils::to_time_t;
+use masq_lib::messages::{UiConnectionRecord, UiRequestCount};
+use std::collections::{BTreeMap, VecDeque};
+use std::net::SocketAddr;
+use std::time::SystemTime;
+
+// The oldest record is dropped, connected or not, to make room for a new one beyond this count
+pub const CONNECTION_AUDIT_CAPACITY: usize = 100;
+
+struct ConnectionEntry {
+    client_id: u64,
+    socket_addr: SocketAddr,
+    user_agent_opt: Option<String>,
+    locale_opt: Option<String>,
+    protocol_version: u32,
+    connected_at: SystemTime,
+    requests: BTreeMap<String, u64>,
+    disconnection_opt: Option<(SystemTime, String)>,
+}
+
+pub struct ConnectionAudit {
+    capacity: usize,
+    entries: VecDeque<ConnectionEntry>,
+}
+
+impl Default for ConnectionAudit {
+    fn default() -> Self {
+        Self::new(CONNECTION_AUDIT_CAPACITY)
+    }
+}
+
+impl ConnectionAudit {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn connected(
+        &mut self,
+        client_id: u64,
+        socket_addr: SocketAddr,
+        user_agent_opt: Option<String>,
+        locale_opt: Option<String>,
+        protocol_version: u32,
+        timestamp: SystemTime,
+    ) {
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(ConnectionEntry {
+            client_id,
+            socket_addr,
+            user_agent_opt,
+            locale_opt,
+            protocol_version,
+            connected_at: timestamp,
+            requests: BTreeMap::new(),
+            disconnection_opt: None,
+        })
+    }
+
+    pub fn request(&mut self, client_id: u64, opcode: &str) {
+        if let Some(entry) = self.live_entry(client_id) {
+            *entry.requests.entry(opcode.to_string()).or_insert(0) += 1
+        }
+    }
+
+    // Only the first reason counts: a client dropped for a send error may still produce a close
+    // message or a protocol violation on its way out
+    pub fn disconnected(&mut self, client_id: u64, reason: &str, timestamp: SystemTime) {
+        if let Some(entry) = self.live_entry(client_id) {
+            entry.disconnection_opt = Some((timestamp, reason.to_string()))
+        }
+    }
+
+    pub fn records(&self) -> Vec<UiConnectionRecord> {
+        self.entries
+            .iter()
+            .map(|entry| UiConnectionRecord {
+                client_id: entry.client_id,
+                address: entry.socket_addr.to_string(),
+                user_agent_opt: entry.user_agent_opt.clone(),
+                locale_opt: entry.locale_opt.clone(),
+                protocol_version: entry.protocol_version,
+                connected_at: to_time_t(entry.connected_at),
+                requests: entry
+                    .requests
+                    .iter()
+                    .map(|(opcode, count)| UiRequestCount {
+                        opcode: opcode.clone(),
+                        count: *count,
+                    })
+                    .collect(),
+                disconnected_at_opt: entry
+                    .disconnection_opt
+                    .as_ref()
+                    .map(|(timestamp, _)| to_time_t(*timestamp)),
+                disconnect_reason_opt: entry
+                    .disconnection_opt
+                    .as_ref()
+                    .map(|(_, reason)| reason.clone()),
+            })
+            .collect()
+    }
+
+    fn live_entry(&mut self, client_id: u64) -> Option<&mut ConnectionEntry> {
+        self.entries
+            .iter_mut()
+            .rev()
+            .find(|entry| entry.client_id == client_id && entry.disconnection_opt.is_none())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(CONNECTION_AUDIT_CAPACITY, 100);
+    }
+
+    #[test]
+    fn connection_audit_records_the_life_of_a_connection() {
+        let connected_at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let disconnected_at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_060);
+        let socket_addr = SocketAddr::from_str("127.0.0.1:4567").unwrap();
+        let mut subject = ConnectionAudit::default();
+
+        subject.connected(
+            3,
+            socket_addr,
+            Some("masq/0.8.2".to_string()),
+            Some("fr-ca".to_string()),
+            2,
+            connected_at,
+        );
+        subject.request(3, "setup");
+        subject.request(3, "financials");
+        subject.request(3, "setup");
+        subject.disconnected(3, "closed by UI", disconnected_at);
+        subject.request(3, "shutdown");
+        subject.disconnected(3, "send error", disconnected_at + Duration::from_secs(1));
+
+        assert_eq!(
+            subject.records(),
+            vec![UiConnectionRecord {
+                client_id: 3,
+                address: "127.0.0.1:4567".to_string(),
+                user_agent_opt: Some("masq/0.8.2".to_string()),
+                locale_opt: Some("fr-ca".to_string()),
+                protocol_version: 2,
+                connected_at: 1_000,
+                requests: vec![
+                    UiRequestCount {
+                        opcode: "financials".to_string(),
+                        count: 1
+                    },
+                    UiRequestCount {
+                        opcode: "setup".to_string(),
+                        count: 2
+                    },
+                ],
+                disconnected_at_opt: Some(1_060),
+                disconnect_reason_opt: Some("closed by UI".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn connection_audit_forgets_the_oldest_connections_beyond_its_capacity() {
+        let timestamp = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let socket_addr = SocketAddr::from_str("127.0.0.1:4567").unwrap();
+        let mut subject = ConnectionAudit::new(2);
+
+        (0..3).for_each(|client_id| {
+            subject.connected(client_id, socket_addr, None, None, 2, timestamp)
+        });
+        subject.request(0, "setup");
+
+        let records = subject.records();
+        assert_eq!(
+            records
+                .iter()
+                .map(|record| record.client_id)
+                .collect::<Vec<u64>>(),
+            vec![1, 2]
+        );
+        assert_eq!(
+            records
+                .iter()
+                .all(|record| record.requests.is_empty() && record.disconnected_at_opt.is_none()),
+            true
+        );
+    }
+}
@@ -0,0 +1,266 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use masq_lib::messages::{
+    idempotency_key_opt, UiChangePasswordRequest, UiGenerateWalletsRequest,
+    UiRecoverWalletsRequest, UiScanRequest, UiSetConfigurationRequest, UiShutdownRequest,
+    UiStartOrder,
+};
+use masq_lib::ui_gateway::MessagePath::Conversation;
+use masq_lib::ui_gateway::MessageTarget::ClientId;
+use masq_lib::ui_gateway::{MessageBody, NodeFromUiMessage, NodeToUiMessage};
+use std::collections::{HashMap, VecDeque};
+
+pub const MAX_REMEMBERED_IDEMPOTENCY_KEYS: usize = 256;
+
+// Only requests that change something are worth protecting; the rest can simply be sent again
+fn idempotent_opcodes() -> Vec<&'static str> {
+    vec![
+        UiStartOrder::type_opcode(),
+        UiShutdownRequest::type_opcode(),
+        UiGenerateWalletsRequest::type_opcode(),
+        UiRecoverWalletsRequest::type_opcode(),
+        UiScanRequest::type_opcode(),
+        UiSetConfigurationRequest::type_opcode(),
+        UiChangePasswordRequest::type_opcode(),
+    ]
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Admission {
+    // First time this key has been seen: pass the request on to be handled
+    Forward,
+    // The request with this key is still being handled; its answer will go to this client too
+    Hold,
+    // The request with this key has already been answered; here's the answer again
+    Replay(NodeToUiMessage),
+}
+
+enum KeyState {
+    InProgress {
+        originator: (u64, u64),
+        retriers: Vec<(u64, u64)>,
+    },
+    Answered(MessageBody),
+}
+
+// (opcode, idempotency key) -> what has become of the request, for the most recent keys only
+pub struct IdempotencyCache {
+    states: HashMap<(String, String), KeyState>,
+    order: VecDeque<(String, String)>,
+    capacity: usize,
+}
+
+impl Default for IdempotencyCache {
+    fn default() -> Self {
+        Self::new(MAX_REMEMBERED_IDEMPOTENCY_KEYS)
+    }
+}
+
+impl IdempotencyCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            states: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    pub fn admit(&mut self, msg: &NodeFromUiMessage) -> Admission {
+        let context_id = match msg.body.path {
+            Conversation(context_id) => context_id,
+            _ => return Admission::Forward,
+        };
+        if !idempotent_opcodes().contains(&msg.body.opcode.as_str()) {
+            return Admission::Forward;
+        }
+        let key = match idempotency_key_opt(&msg.body) {
+            Some(key) => (msg.body.opcode.clone(), key),
+            None => return Admission::Forward,
+        };
+        match self.states.get_mut(&key) {
+            None => {
+                self.remember(
+                    key,
+                    KeyState::InProgress {
+                        originator: (msg.client_id, context_id),
+                        retriers: vec![],
+                    },
+                );
+                Admission::Forward
+            }
+            Some(KeyState::InProgress { retriers, .. }) => {
+                retriers.push((msg.client_id, context_id));
+                Admission::Hold
+            }
+            Some(KeyState::Answered(body)) => Admission::Replay(NodeToUiMessage {
+                target: ClientId(msg.client_id),
+                body: MessageBody {
+                    path: Conversation(context_id),
+                    ..body.clone()
+                },
+            }),
+        }
+    }
+
+    // Returns copies of the answer for any clients that retried while the request was in progress
+    pub fn record_answer(&mut self, msg: &NodeToUiMessage) -> Vec<NodeToUiMessage> {
+        let originator = match (&msg.target, msg.body.path) {
+            (ClientId(client_id), Conversation(context_id)) => (*client_id, context_id),
+            _ => return vec![],
+        };
+        let state = match self.states.iter_mut().find(|((opcode, _), state)| {
+            opcode == &msg.body.opcode
+                && matches!(state, KeyState::InProgress { originator: o, .. } if *o == originator)
+        }) {
+            Some((_, state)) => state,
+            None => return vec![],
+        };
+        let retriers = match std::mem::replace(state, KeyState::Answered(msg.body.clone())) {
+            KeyState::InProgress { retriers, .. } => retriers,
+            KeyState::Answered(_) => vec![],
+        };
+        retriers
+            .into_iter()
+            .map(|(client_id, context_id)| NodeToUiMessage {
+                target: ClientId(client_id),
+                body: MessageBody {
+                    path: Conversation(context_id),
+                    ..msg.body.clone()
+                },
+            })
+            .collect()
+    }
+
+    fn remember(&mut self, key: (String, String), state: KeyState) {
+        if self.order.len() >= self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.states.remove(&oldest);
+            }
+        }
+        self.order.push_back(key.clone());
+        self.states.insert(key, state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::messages::{with_idempotency_key, ToMessageBody, UiShutdownResponse};
+    use masq_lib::ui_gateway::MessagePath::FireAndForget;
+
+    fn shutdown_request(client_id: u64, context_id: u64, key: &str) -> NodeFromUiMessage {
+        NodeFromUiMessage {
+            client_id,
+            body: with_idempotency_key(UiShutdownRequest {}.tmb(context_id), key),
+        }
+    }
+
+    fn shutdown_response(client_id: u64, context_id: u64) -> NodeToUiMessage {
+        NodeToUiMessage {
+            target: ClientId(client_id),
+            body: UiShutdownResponse {}.tmb(context_id),
+        }
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(MAX_REMEMBERED_IDEMPOTENCY_KEYS, 256);
+        assert_eq!(
+            idempotent_opcodes(),
+            vec![
+                "start",
+                "shutdown",
+                "generateWallets",
+                "recoverWallets",
+                "scan",
+                "setConfiguration",
+                "changePassword"
+            ]
+        );
+    }
+
+    #[test]
+    fn requests_without_keys_or_outside_the_idempotent_set_are_always_forwarded() {
+        let mut subject = IdempotencyCache::default();
+        let unkeyed = NodeFromUiMessage {
+            client_id: 1234,
+            body: UiShutdownRequest {}.tmb(1),
+        };
+        let unprotected = NodeFromUiMessage {
+            client_id: 1234,
+            body: MessageBody {
+                opcode: "financials".to_string(),
+                path: Conversation(2),
+                payload: Ok(r#"{"idempotencyKey":"key"}"#.to_string()),
+            },
+        };
+        let fire_and_forget = NodeFromUiMessage {
+            client_id: 1234,
+            body: MessageBody {
+                path: FireAndForget,
+                ..shutdown_request(1234, 3, "key").body
+            },
+        };
+
+        vec![unkeyed, unprotected, fire_and_forget]
+            .into_iter()
+            .for_each(|msg| {
+                assert_eq!(subject.admit(&msg), Admission::Forward);
+                assert_eq!(subject.admit(&msg), Admission::Forward);
+            });
+    }
+
+    #[test]
+    fn retry_after_answer_gets_the_answer_again_under_its_own_context() {
+        let mut subject = IdempotencyCache::default();
+
+        let first_admission = subject.admit(&shutdown_request(1234, 1, "key"));
+        let copies = subject.record_answer(&shutdown_response(1234, 1));
+        let retry_admission = subject.admit(&shutdown_request(4321, 7, "key"));
+
+        assert_eq!(first_admission, Admission::Forward);
+        assert_eq!(copies, vec![]);
+        assert_eq!(
+            retry_admission,
+            Admission::Replay(shutdown_response(4321, 7))
+        );
+    }
+
+    #[test]
+    fn retry_while_in_progress_is_held_and_answered_with_the_original() {
+        let mut subject = IdempotencyCache::default();
+        subject.admit(&shutdown_request(1234, 1, "key"));
+
+        let retry_admission = subject.admit(&shutdown_request(4321, 7, "key"));
+        let unrelated_copies = subject.record_answer(&shutdown_response(1234, 2));
+        let copies = subject.record_answer(&shutdown_response(1234, 1));
+
+        assert_eq!(retry_admission, Admission::Hold);
+        assert_eq!(unrelated_copies, vec![]);
+        assert_eq!(copies, vec![shutdown_response(4321, 7)]);
+    }
+
+    #[test]
+    fn different_keys_are_handled_separately() {
+        let mut subject = IdempotencyCache::default();
+
+        let first = subject.admit(&shutdown_request(1234, 1, "key-1"));
+        let second = subject.admit(&shutdown_request(1234, 2, "key-2"));
+
+        assert_eq!(first, Admission::Forward);
+        assert_eq!(second, Admission::Forward);
+    }
+
+    #[test]
+    fn oldest_key_is_forgotten_when_the_cache_is_full() {
+        let mut subject = IdempotencyCache::new(2);
+        subject.admit(&shutdown_request(1234, 1, "key-1"));
+        subject.record_answer(&shutdown_response(1234, 1));
+        subject.admit(&shutdown_request(1234, 2, "key-2"));
+        subject.admit(&shutdown_request(1234, 3, "key-3"));
+
+        let oldest_admission = subject.admit(&shutdown_request(1234, 4, "key-1"));
+
+        assert_eq!(oldest_admission, Admission::Forward);
+    }
+}
@@ -1,15 +1,20 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+mod connection_audit;
+mod idempotency;
+mod protocol_compatibility;
 mod websocket_supervisor;
 
 #[cfg(test)]
 pub mod websocket_supervisor_mocks;
 
 use crate::daemon::DaemonBindMessage;
+use crate::node_handle::{EmbedderLink, EMBEDDER_CLIENT_ID};
 use crate::sub_lib::peer_actors::BindMessage;
 use crate::sub_lib::ui_gateway::UiGatewayConfig;
 use crate::sub_lib::ui_gateway::UiGatewaySubs;
 use crate::sub_lib::utils::NODE_MAILBOX_CAPACITY;
+use crate::ui_gateway::idempotency::{Admission, IdempotencyCache};
 use crate::ui_gateway::websocket_supervisor::{
     WebSocketSupervisor, WebSocketSupervisorFactory, WebsocketSupervisorFactoryReal,
 };
@@ -20,9 +25,17 @@ use actix::Handler;
 use actix::Recipient;
 use itertools::Either;
 use masq_lib::logger::Logger;
-use masq_lib::messages::UiCrashRequest;
+use masq_lib::message_catalog::{supported_locale, CATALOG, DEFAULT_LOCALE};
+use masq_lib::messages::{
+    FromMessageBody, ToMessageBody, UiCancelRequest, UiCatalogEntry, UiConnectionAuditRequest,
+    UiConnectionAuditResponse, UiCrashRequest, UiMessageCatalogRequest, UiMessageCatalogResponse,
+    UiProgressBroadcast,
+};
+use masq_lib::ui_gateway::MessagePath::{Conversation, FireAndForget};
+use masq_lib::ui_gateway::MessageTarget::ClientId;
 use masq_lib::ui_gateway::{MessageBody, NodeFromUiMessage, NodeToUiMessage};
 use masq_lib::utils::ExpectValue;
+use std::collections::HashMap;
 use std::mem::replace;
 
 pub const CRASH_KEY: &str = "UIGATEWAY";
@@ -31,6 +44,11 @@ pub struct UiGateway {
     port: u16,
     websocket_supervisor: Either<Box<dyn WebSocketSupervisorFactory>, Box<dyn WebSocketSupervisor>>,
     incoming_message_recipients: Vec<Recipient<NodeFromUiMessage>>,
+    embedder_link_opt: Option<EmbedderLink>,
+    // (client ID, context ID) -> opcode of the requests that have reported progress but haven't
+    // been answered yet; these are the only ones a UI can cancel
+    requests_in_progress: HashMap<(u64, u64), String>,
+    idempotency_cache: IdempotencyCache,
     crashable: bool,
     logger: Logger,
 }
@@ -41,12 +59,21 @@ impl UiGateway {
             port: config.ui_port,
             websocket_supervisor: Either::Left(Box::new(WebsocketSupervisorFactoryReal)),
             incoming_message_recipients: vec![],
+            embedder_link_opt: None,
+            requests_in_progress: HashMap::new(),
+            idempotency_cache: IdempotencyCache::default(),
             crashable,
 
             logger: Logger::new("UiGateway"),
         }
     }
 
+    // Present only when the Node is running inside an application through a NodeHandle
+    pub fn with_embedder_link_opt(mut self, embedder_link_opt: Option<EmbedderLink>) -> Self {
+        self.embedder_link_opt = embedder_link_opt;
+        self
+    }
+
     pub fn make_subs_from(addr: &Addr<UiGateway>) -> UiGatewaySubs {
         UiGatewaySubs {
             bind: recipient!(addr, BindMessage),
@@ -95,6 +122,108 @@ impl UiGateway {
         }
     }
 
+    fn track_request_in_progress(&mut self, msg: &NodeToUiMessage) {
+        let client_id = match msg.target {
+            ClientId(client_id) => client_id,
+            _ => return,
+        };
+        match msg.body.path {
+            Conversation(context_id) => {
+                self.requests_in_progress.remove(&(client_id, context_id));
+            }
+            FireAndForget => {
+                if let Ok((progress, _)) = UiProgressBroadcast::fmb(msg.body.clone()) {
+                    self.requests_in_progress
+                        .insert((client_id, progress.context_id), progress.opcode);
+                }
+            }
+        }
+    }
+
+    fn is_cancellable(&self, client_id: u64, cancel_request: &UiCancelRequest) -> bool {
+        match self
+            .requests_in_progress
+            .get(&(client_id, cancel_request.context_id))
+        {
+            Some(opcode) => {
+                debug!(
+                    self.logger,
+                    "Client {} cancels its '{}' request {}",
+                    client_id,
+                    opcode,
+                    cancel_request.context_id
+                );
+                true
+            }
+            None => {
+                debug!(
+                    self.logger,
+                    "Client {} tried to cancel request {}, which is not in progress; ignoring",
+                    client_id,
+                    cancel_request.context_id
+                );
+                false
+            }
+        }
+    }
+
+    fn handle_connection_audit(&self, client_id: u64, context_id: u64) {
+        let connections = self.websocket_supervisor().connection_audit();
+        self.send_to_ui(NodeToUiMessage {
+            target: ClientId(client_id),
+            body: UiConnectionAuditResponse { connections }.tmb(context_id),
+        })
+    }
+
+    fn handle_message_catalog(&self, client_id: u64, context_id: u64, locale_opt: Option<String>) {
+        let requested = locale_opt
+            .or_else(|| {
+                self.websocket_supervisor()
+                    .connection_audit()
+                    .into_iter()
+                    .rev()
+                    .find(|record| {
+                        record.client_id == client_id && record.disconnected_at_opt.is_none()
+                    })
+                    .and_then(|record| record.locale_opt)
+            })
+            .unwrap_or_else(|| DEFAULT_LOCALE.to_string());
+        let entries = CATALOG
+            .iter()
+            .map(|entry| UiCatalogEntry {
+                id: entry.id.to_string(),
+                code_opt: entry.code_opt,
+                text: entry.english.to_string(),
+            })
+            .collect();
+        self.send_to_ui(NodeToUiMessage {
+            target: ClientId(client_id),
+            body: UiMessageCatalogResponse {
+                locale: supported_locale(&requested).to_string(),
+                entries,
+            }
+            .tmb(context_id),
+        })
+    }
+
+    fn send_to_ui(&self, msg: NodeToUiMessage) {
+        if let Some(embedder_link) = self.embedder_link_opt.as_ref() {
+            embedder_link.deliver(&msg);
+            if msg.target == ClientId(EMBEDDER_CLIENT_ID) {
+                return;
+            }
+        }
+        self.websocket_supervisor().send_msg(msg)
+    }
+
+    fn websocket_supervisor(&self) -> &dyn WebSocketSupervisor {
+        self.websocket_supervisor
+            .as_ref()
+            .right()
+            .expect("WebSocketSupervisor is uninitialized")
+            .as_ref()
+    }
+
     fn initiate_websocket_supervisor(&mut self, recipient: Recipient<NodeFromUiMessage>) {
         let ws = match self
             .websocket_supervisor
@@ -126,7 +255,11 @@ impl Handler<BindMessage> for UiGateway {
             msg.peer_actors.blockchain_bridge.ui_sub.clone(),
             msg.peer_actors.dispatcher.ui_sub.clone(),
             msg.peer_actors.configurator.node_from_ui_sub.clone(),
+            msg.peer_actors.proxy_server.node_from_ui.clone(),
         ];
+        if let Some(embedder_link) = self.embedder_link_opt.as_ref() {
+            embedder_link.bind(msg.peer_actors.ui_gateway.node_from_ui_message_sub.clone());
+        }
         self.initiate_websocket_supervisor(msg.peer_actors.ui_gateway.node_from_ui_message_sub);
         debug!(self.logger, "UIGateway bound");
     }
@@ -147,12 +280,12 @@ impl Handler<NodeToUiMessage> for UiGateway {
     type Result = ();
 
     fn handle(&mut self, msg: NodeToUiMessage, _ctx: &mut Self::Context) -> Self::Result {
-        self.websocket_supervisor
-            .as_ref()
-            .right()
-            .as_ref()
-            .expect("WebSocketSupervisor is uninitialized")
-            .send_msg(msg)
+        self.track_request_in_progress(&msg);
+        let copies_for_retriers = self.idempotency_cache.record_answer(&msg);
+        self.send_to_ui(msg);
+        copies_for_retriers
+            .into_iter()
+            .for_each(|copy| self.send_to_ui(copy))
     }
 }
 
@@ -175,6 +308,41 @@ impl Handler<NodeFromUiMessage> for UiGateway {
             );
             return;
         };
+        if let Ok((cancel_request, _)) = UiCancelRequest::fmb(msg.body.clone()) {
+            if !self.is_cancellable(msg.client_id, &cancel_request) {
+                return;
+            }
+        }
+        if let Ok((_, context_id)) = UiConnectionAuditRequest::fmb(msg.body.clone()) {
+            self.handle_connection_audit(msg.client_id, context_id);
+            return;
+        }
+        if let Ok((request, context_id)) = UiMessageCatalogRequest::fmb(msg.body.clone()) {
+            self.handle_message_catalog(msg.client_id, context_id, request.locale_opt);
+            return;
+        }
+        match self.idempotency_cache.admit(&msg) {
+            Admission::Forward => (),
+            Admission::Hold => {
+                debug!(
+                    self.logger,
+                    "Client {} retried a '{}' request that is still in progress; it will get the original's answer",
+                    msg.client_id,
+                    msg.body.opcode
+                );
+                return;
+            }
+            Admission::Replay(answer) => {
+                debug!(
+                    self.logger,
+                    "Client {} retried a '{}' request that has already been answered; sending the answer again",
+                    msg.client_id,
+                    msg.body.opcode
+                );
+                self.send_to_ui(answer);
+                return;
+            }
+        }
         let len = self.incoming_message_recipients.len();
         (0..len).for_each(|idx| {
             let recipient = &self.incoming_message_recipients[idx];
@@ -189,6 +357,7 @@ impl Handler<NodeFromUiMessage> for UiGateway {
 mod tests {
     use super::*;
     use crate::dispatcher;
+    use crate::node_handle::NodeEvent;
     use crate::test_utils::recorder::peer_actors_builder;
     use crate::test_utils::recorder::{make_recorder, Recording};
     use crate::test_utils::unshared_test_utils::make_daemon_bind_message;
@@ -200,7 +369,11 @@ mod tests {
     use actix::Message;
     use actix::System;
     use crossbeam_channel::{unbounded, Sender};
-    use masq_lib::messages::{ToMessageBody, UiChangePasswordRequest};
+    use masq_lib::constants::SETUP_REVISION_CONFLICT_ERROR;
+    use masq_lib::messages::{
+        with_idempotency_key, UiChangePasswordRequest, UiConnectionRecord, UiRequestCount,
+        UiShutdownRequest, UiShutdownResponse,
+    };
     use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
     use masq_lib::ui_gateway::MessagePath::FireAndForget;
     use masq_lib::ui_gateway::{MessageBody, MessagePath, MessageTarget};
@@ -281,10 +454,10 @@ mod tests {
         let (blockchain, _, blockchain_recording_arc) = make_recorder();
         let (dispatcher, _, dispatcher_recording_arc) = make_recorder();
         let (configurator, _, configurator_recording_arc) = make_recorder();
+        let (proxy_server, _, proxy_server_recording_arc) = make_recorder();
         // These actors should not receive NodeFromUiMessages
         let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
         let (proxy_client, _, proxy_client_recording_arc) = make_recorder();
-        let (proxy_server, _, proxy_server_recording_arc) = make_recorder();
         let (hopper, _, hopper_recording_arc) = make_recorder();
         let subject = UiGateway::new(
             &UiGatewayConfig {
@@ -334,9 +507,9 @@ mod tests {
         did_receive(blockchain_recording_arc);
         did_receive(dispatcher_recording_arc);
         did_receive(configurator_recording_arc);
+        did_receive(proxy_server_recording_arc);
         did_not_receive(ui_gateway_recording_arc);
         did_not_receive(proxy_client_recording_arc);
-        did_not_receive(proxy_server_recording_arc);
         did_not_receive(hopper_recording_arc);
     }
 
@@ -376,6 +549,323 @@ mod tests {
         assert_eq!(send_parameters[0], msg);
     }
 
+    #[test]
+    fn cancel_request_is_passed_on_only_while_its_request_is_in_progress() {
+        init_test_logging();
+        let test_name = "cancel_request_is_passed_on_only_while_its_request_is_in_progress";
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let websocket_supervisor_factory = WebsocketSupervisorFactoryMock::default()
+            .make_result(Ok(Box::new(WebSocketSupervisorMock::new())));
+        let mut subject = UiGateway::new(
+            &UiGatewayConfig {
+                ui_port: find_free_port(),
+            },
+            false,
+        );
+        subject.websocket_supervisor = Either::Left(
+            Box::new(websocket_supervisor_factory) as Box<dyn WebSocketSupervisorFactory>
+        );
+        subject.logger = Logger::new(test_name);
+        let system = System::new(test_name);
+        let subject_addr: Addr<UiGateway> = subject.start();
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let cancel = |client_id: u64, context_id: u64| NodeFromUiMessage {
+            client_id,
+            body: UiCancelRequest { context_id }.tmb(0),
+        };
+        subject_addr
+            .try_send(NodeToUiMessage {
+                target: MessageTarget::ClientId(1234),
+                body: UiProgressBroadcast {
+                    context_id: 2222,
+                    opcode: "databaseMaintenance".to_string(),
+                    stage: "vacuuming".to_string(),
+                    completed: 1,
+                    total_opt: Some(3),
+                }
+                .tmb(0),
+            })
+            .unwrap();
+
+        subject_addr.try_send(cancel(4321, 2222)).unwrap();
+        subject_addr.try_send(cancel(1234, 2222)).unwrap();
+        subject_addr
+            .try_send(NodeToUiMessage {
+                target: MessageTarget::ClientId(1234),
+                body: MessageBody {
+                    opcode: "databaseMaintenance".to_string(),
+                    path: Conversation(2222),
+                    payload: Ok("{}".to_string()),
+                },
+            })
+            .unwrap();
+        subject_addr.try_send(cancel(1234, 2222)).unwrap();
+
+        System::current().stop();
+        system.run();
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(accountant_recording.len(), 1);
+        assert_eq!(
+            accountant_recording.get_record::<NodeFromUiMessage>(0),
+            &cancel(1234, 2222)
+        );
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_containing(&format!(
+            "DEBUG: {test_name}: Client 1234 cancels its 'databaseMaintenance' request 2222"
+        ));
+        tlh.exists_log_containing(&format!(
+            "DEBUG: {test_name}: Client 4321 tried to cancel request 2222, which is not in \
+             progress; ignoring"
+        ));
+        tlh.exists_log_containing(&format!(
+            "DEBUG: {test_name}: Client 1234 tried to cancel request 2222, which is not in \
+             progress; ignoring"
+        ));
+    }
+
+    #[test]
+    fn connection_audit_request_is_answered_by_the_ui_gateway_itself() {
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let send_msg_params_arc = Arc::new(Mutex::new(vec![]));
+        let connections = vec![UiConnectionRecord {
+            client_id: 1234,
+            address: "127.0.0.1:5678".to_string(),
+            user_agent_opt: Some("masq/0.8.2".to_string()),
+            locale_opt: None,
+            protocol_version: 2,
+            connected_at: 1_700_000_000,
+            requests: vec![UiRequestCount {
+                opcode: "connectionAudit".to_string(),
+                count: 1,
+            }],
+            disconnected_at_opt: None,
+            disconnect_reason_opt: None,
+        }];
+        let websocket_supervisor = WebSocketSupervisorMock::new()
+            .send_msg_params(&send_msg_params_arc)
+            .connection_audit_result(connections.clone());
+        let websocket_supervisor_factory = WebsocketSupervisorFactoryMock::default()
+            .make_result(Ok(Box::new(websocket_supervisor)));
+        let mut subject = UiGateway::new(
+            &UiGatewayConfig {
+                ui_port: find_free_port(),
+            },
+            false,
+        );
+        subject.websocket_supervisor = Either::Left(
+            Box::new(websocket_supervisor_factory) as Box<dyn WebSocketSupervisorFactory>
+        );
+        let system = System::new("test");
+        let subject_addr: Addr<UiGateway> = subject.start();
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiConnectionAuditRequest {}.tmb(4321),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(accountant_recording.len(), 0);
+        let send_msg_params = send_msg_params_arc.lock().unwrap();
+        assert_eq!(
+            *send_msg_params,
+            vec![NodeToUiMessage {
+                target: MessageTarget::ClientId(1234),
+                body: UiConnectionAuditResponse { connections }.tmb(4321),
+            }]
+        );
+    }
+
+    #[test]
+    fn message_catalog_request_is_answered_in_the_locale_from_the_handshake() {
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let send_msg_params_arc = Arc::new(Mutex::new(vec![]));
+        let connections = vec![UiConnectionRecord {
+            client_id: 1234,
+            address: "127.0.0.1:5678".to_string(),
+            user_agent_opt: None,
+            locale_opt: Some("fr-ca".to_string()),
+            protocol_version: 2,
+            connected_at: 1_700_000_000,
+            requests: vec![],
+            disconnected_at_opt: None,
+            disconnect_reason_opt: None,
+        }];
+        let websocket_supervisor = WebSocketSupervisorMock::new()
+            .send_msg_params(&send_msg_params_arc)
+            .connection_audit_result(connections);
+        let websocket_supervisor_factory = WebsocketSupervisorFactoryMock::default()
+            .make_result(Ok(Box::new(websocket_supervisor)));
+        let mut subject = UiGateway::new(
+            &UiGatewayConfig {
+                ui_port: find_free_port(),
+            },
+            false,
+        );
+        subject.websocket_supervisor = Either::Left(
+            Box::new(websocket_supervisor_factory) as Box<dyn WebSocketSupervisorFactory>
+        );
+        let system = System::new("test");
+        let subject_addr: Addr<UiGateway> = subject.start();
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiMessageCatalogRequest { locale_opt: None }.tmb(4321),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(accountant_recording.len(), 0);
+        let send_msg_params = send_msg_params_arc.lock().unwrap();
+        assert_eq!(send_msg_params.len(), 1);
+        assert_eq!(send_msg_params[0].target, MessageTarget::ClientId(1234));
+        let (response, context_id) =
+            UiMessageCatalogResponse::fmb(send_msg_params[0].body.clone()).unwrap();
+        assert_eq!(context_id, 4321);
+        // No French texts ship yet, so the GUI is told it's getting English
+        assert_eq!(response.locale, "en".to_string());
+        assert_eq!(response.entries.len(), CATALOG.len());
+        assert_eq!(
+            response
+                .entries
+                .iter()
+                .find(|entry| entry.code_opt == Some(SETUP_REVISION_CONFLICT_ERROR)),
+            Some(&UiCatalogEntry {
+                id: "error.setupRevisionConflict".to_string(),
+                code_opt: Some(SETUP_REVISION_CONFLICT_ERROR),
+                text: "Another UI changed the setup first".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn retried_request_with_idempotency_key_is_answered_without_being_handled_again() {
+        init_test_logging();
+        let test_name =
+            "retried_request_with_idempotency_key_is_answered_without_being_handled_again";
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let send_msg_params_arc = Arc::new(Mutex::new(vec![]));
+        let websocket_supervisor =
+            WebSocketSupervisorMock::new().send_msg_params(&send_msg_params_arc);
+        let websocket_supervisor_factory = WebsocketSupervisorFactoryMock::default()
+            .make_result(Ok(Box::new(websocket_supervisor)));
+        let mut subject = UiGateway::new(
+            &UiGatewayConfig {
+                ui_port: find_free_port(),
+            },
+            false,
+        );
+        subject.websocket_supervisor = Either::Left(
+            Box::new(websocket_supervisor_factory) as Box<dyn WebSocketSupervisorFactory>
+        );
+        subject.logger = Logger::new(test_name);
+        let system = System::new(test_name);
+        let subject_addr: Addr<UiGateway> = subject.start();
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let request = |client_id: u64, context_id: u64| NodeFromUiMessage {
+            client_id,
+            body: with_idempotency_key(UiShutdownRequest {}.tmb(context_id), "shutdown-1"),
+        };
+        let response = |client_id: u64, context_id: u64| NodeToUiMessage {
+            target: MessageTarget::ClientId(client_id),
+            body: UiShutdownResponse {}.tmb(context_id),
+        };
+
+        subject_addr.try_send(request(1234, 1)).unwrap();
+        subject_addr.try_send(request(2345, 2)).unwrap();
+        subject_addr.try_send(response(1234, 1)).unwrap();
+        subject_addr.try_send(request(3456, 3)).unwrap();
+
+        System::current().stop();
+        system.run();
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(accountant_recording.len(), 1);
+        assert_eq!(
+            accountant_recording.get_record::<NodeFromUiMessage>(0),
+            &request(1234, 1)
+        );
+        let send_msg_params = send_msg_params_arc.lock().unwrap();
+        assert_eq!(
+            *send_msg_params,
+            vec![response(1234, 1), response(2345, 2), response(3456, 3)]
+        );
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_containing(&format!(
+            "DEBUG: {test_name}: Client 2345 retried a 'shutdown' request that is still in \
+             progress; it will get the original's answer"
+        ));
+        tlh.exists_log_containing(&format!(
+            "DEBUG: {test_name}: Client 3456 retried a 'shutdown' request that has already been \
+             answered; sending the answer again"
+        ));
+    }
+
+    #[test]
+    fn embedder_link_is_bound_and_gets_its_own_messages_instead_of_the_websocket_supervisor() {
+        let send_msg_params_arc = Arc::new(Mutex::new(vec![]));
+        let websocket_supervisor =
+            WebSocketSupervisorMock::new().send_msg_params(&send_msg_params_arc);
+        let websocket_supervisor_factory = WebsocketSupervisorFactoryMock::default()
+            .make_result(Ok(Box::new(websocket_supervisor)));
+        let (embedder_link, event_rx) = EmbedderLink::make_for_test();
+        let mut subject = UiGateway::new(
+            &UiGatewayConfig {
+                ui_port: find_free_port(),
+            },
+            false,
+        )
+        .with_embedder_link_opt(Some(embedder_link.clone()));
+        subject.websocket_supervisor = Either::Left(
+            Box::new(websocket_supervisor_factory) as Box<dyn WebSocketSupervisorFactory>
+        );
+        let system = System::new("test");
+        let subject_addr: Addr<UiGateway> = subject.start();
+        let peer_actors = peer_actors_builder().build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let make_msg = |target: MessageTarget, opcode: &str| NodeToUiMessage {
+            target,
+            body: MessageBody {
+                opcode: opcode.to_string(),
+                path: FireAndForget,
+                payload: Ok("{}".to_string()),
+            },
+        };
+        let for_embedder = make_msg(MessageTarget::ClientId(EMBEDDER_CLIENT_ID), "forEmbedder");
+        let for_client = make_msg(MessageTarget::ClientId(1234), "forClient");
+        let for_everybody = make_msg(MessageTarget::AllClients, "forEverybody");
+
+        subject_addr.try_send(for_embedder.clone()).unwrap();
+        subject_addr.try_send(for_client.clone()).unwrap();
+        subject_addr.try_send(for_everybody.clone()).unwrap();
+
+        System::current().stop();
+        system.run();
+        assert_eq!(embedder_link.is_bound(), true);
+        assert_eq!(
+            event_rx.try_iter().collect::<Vec<NodeEvent>>(),
+            vec![
+                NodeEvent::UiMessage(for_embedder.body),
+                NodeEvent::UiMessage(for_everybody.body.clone()),
+            ]
+        );
+        assert_eq!(
+            *send_msg_params_arc.lock().unwrap(),
+            vec![for_client, for_everybody]
+        );
+    }
+
     #[test]
     fn syntactically_bad_json_is_caught_and_a_truncated_example_is_provided() {
         init_test_logging();
@@ -0,0 +1,179 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use masq_lib::messages::{node_ui_protocol_version, NODE_UI_PROTOCOL_VERSION};
+use masq_lib::ui_gateway::MessageBody;
+
+// Translates between an older version of the UI protocol and the current one, so that a UI
+// that hasn't caught up with a protocol change can keep talking to the Node for a while.
+pub trait LegacyUiProtocol: Send {
+    fn version(&self) -> u32;
+    fn upgrade_from_ui(&self, body: MessageBody) -> MessageBody;
+    fn downgrade_to_ui(&self, body: MessageBody) -> MessageBody;
+}
+
+// When NODE_UI_PROTOCOL_VERSION is bumped, put an adapter for the previous version here; remove
+// it once its deprecation window has closed.
+fn legacy_ui_protocols() -> Vec<Box<dyn LegacyUiProtocol>> {
+    vec![]
+}
+
+pub struct UiProtocolCompatibility {
+    legacy_protocols: Vec<Box<dyn LegacyUiProtocol>>,
+}
+
+impl Default for UiProtocolCompatibility {
+    fn default() -> Self {
+        Self::new(legacy_ui_protocols())
+    }
+}
+
+impl UiProtocolCompatibility {
+    pub fn new(legacy_protocols: Vec<Box<dyn LegacyUiProtocol>>) -> Self {
+        Self { legacy_protocols }
+    }
+
+    pub fn negotiate(&self, offered_protocols: &[String]) -> Option<u32> {
+        offered_protocols
+            .iter()
+            .filter_map(|protocol| node_ui_protocol_version(protocol))
+            .filter(|version| self.supports(*version))
+            .max()
+    }
+
+    pub fn is_deprecated(&self, version: u32) -> bool {
+        version != NODE_UI_PROTOCOL_VERSION
+    }
+
+    pub fn upgrade_from_ui(&self, version: u32, body: MessageBody) -> MessageBody {
+        match self.legacy_protocol(version) {
+            Some(legacy_protocol) => legacy_protocol.upgrade_from_ui(body),
+            None => body,
+        }
+    }
+
+    pub fn downgrade_to_ui(&self, version: u32, body: MessageBody) -> MessageBody {
+        match self.legacy_protocol(version) {
+            Some(legacy_protocol) => legacy_protocol.downgrade_to_ui(body),
+            None => body,
+        }
+    }
+
+    fn supports(&self, version: u32) -> bool {
+        version == NODE_UI_PROTOCOL_VERSION || self.legacy_protocol(version).is_some()
+    }
+
+    fn legacy_protocol(&self, version: u32) -> Option<&dyn LegacyUiProtocol> {
+        self.legacy_protocols
+            .iter()
+            .find(|legacy_protocol| legacy_protocol.version() == version)
+            .map(|legacy_protocol| legacy_protocol.as_ref())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use masq_lib::messages::node_ui_protocol_name;
+    use masq_lib::ui_gateway::MessagePath::FireAndForget;
+
+    // Stands in for an adapter to the protocol version before the current one: it renames the
+    // "booga" opcode, which that version called "oldbooga".
+    pub struct OldBoogaProtocol;
+
+    impl LegacyUiProtocol for OldBoogaProtocol {
+        fn version(&self) -> u32 {
+            NODE_UI_PROTOCOL_VERSION - 1
+        }
+
+        fn upgrade_from_ui(&self, body: MessageBody) -> MessageBody {
+            match body.opcode.as_str() {
+                "oldbooga" => MessageBody {
+                    opcode: "booga".to_string(),
+                    ..body
+                },
+                _ => body,
+            }
+        }
+
+        fn downgrade_to_ui(&self, body: MessageBody) -> MessageBody {
+            match body.opcode.as_str() {
+                "booga" => MessageBody {
+                    opcode: "oldbooga".to_string(),
+                    ..body
+                },
+                _ => body,
+            }
+        }
+    }
+
+    fn make_body(opcode: &str) -> MessageBody {
+        MessageBody {
+            opcode: opcode.to_string(),
+            path: FireAndForget,
+            payload: Ok("{}".to_string()),
+        }
+    }
+
+    #[test]
+    fn negotiate_picks_the_newest_supported_version_offered() {
+        let subject = UiProtocolCompatibility::new(vec![Box::new(OldBoogaProtocol)]);
+        let offered = vec![
+            node_ui_protocol_name(NODE_UI_PROTOCOL_VERSION - 1),
+            node_ui_protocol_name(NODE_UI_PROTOCOL_VERSION + 1),
+            node_ui_protocol_name(NODE_UI_PROTOCOL_VERSION),
+            "chat".to_string(),
+        ];
+
+        let result = subject.negotiate(&offered);
+
+        assert_eq!(result, Some(NODE_UI_PROTOCOL_VERSION));
+    }
+
+    #[test]
+    fn negotiate_falls_back_to_a_legacy_version() {
+        let subject = UiProtocolCompatibility::new(vec![Box::new(OldBoogaProtocol)]);
+        let offered = vec![node_ui_protocol_name(NODE_UI_PROTOCOL_VERSION - 1)];
+
+        let result = subject.negotiate(&offered);
+
+        assert_eq!(result, Some(NODE_UI_PROTOCOL_VERSION - 1));
+        assert!(subject.is_deprecated(NODE_UI_PROTOCOL_VERSION - 1));
+        assert!(!subject.is_deprecated(NODE_UI_PROTOCOL_VERSION));
+    }
+
+    #[test]
+    fn negotiate_finds_nothing_when_no_supported_version_is_offered() {
+        let subject = UiProtocolCompatibility::default();
+        let offered = vec![
+            node_ui_protocol_name(NODE_UI_PROTOCOL_VERSION - 1),
+            "chat".to_string(),
+        ];
+
+        let result = subject.negotiate(&offered);
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn legacy_versions_are_translated_in_both_directions() {
+        let subject = UiProtocolCompatibility::new(vec![Box::new(OldBoogaProtocol)]);
+        let legacy_version = NODE_UI_PROTOCOL_VERSION - 1;
+
+        let upgraded = subject.upgrade_from_ui(legacy_version, make_body("oldbooga"));
+        let downgraded = subject.downgrade_to_ui(legacy_version, make_body("booga"));
+
+        assert_eq!(upgraded, make_body("booga"));
+        assert_eq!(downgraded, make_body("oldbooga"));
+    }
+
+    #[test]
+    fn current_version_passes_through_untouched() {
+        let subject = UiProtocolCompatibility::new(vec![Box::new(OldBoogaProtocol)]);
+
+        let upgraded = subject.upgrade_from_ui(NODE_UI_PROTOCOL_VERSION, make_body("oldbooga"));
+        let downgraded = subject.downgrade_to_ui(NODE_UI_PROTOCOL_VERSION, make_body("booga"));
+
+        assert_eq!(upgraded, make_body("oldbooga"));
+        assert_eq!(downgraded, make_body("booga"));
+    }
+}
@@ -1,4 +1,6 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::ui_gateway::connection_audit::ConnectionAudit;
+use crate::ui_gateway::protocol_compatibility::UiProtocolCompatibility;
 use actix::Recipient;
 use bytes::BytesMut;
 use futures::future::FutureResult;
@@ -10,7 +12,11 @@ use futures::Sink;
 use futures::Stream;
 use masq_lib::constants::UNMARSHAL_ERROR;
 use masq_lib::logger::Logger;
-use masq_lib::messages::{ToMessageBody, UiUnmarshalError, NODE_UI_PROTOCOL};
+use masq_lib::message_catalog::locale_from_accept_language;
+use masq_lib::messages::{
+    node_ui_protocol_name, ToMessageBody, UiConnectionRecord, UiUnmarshalError, NODE_UI_PROTOCOL,
+    NODE_UI_PROTOCOL_VERSION,
+};
 use masq_lib::ui_gateway::MessagePath::Conversation;
 use masq_lib::ui_gateway::MessageTarget::ClientId;
 use masq_lib::ui_gateway::{MessageBody, MessageTarget, NodeFromUiMessage, NodeToUiMessage};
@@ -18,11 +24,12 @@ use masq_lib::ui_traffic_converter::UiTrafficConverter;
 use masq_lib::ui_traffic_converter::UnmarshalError::{Critical, NonCritical};
 use masq_lib::utils::{localhost, ExpectValue};
 use std::any::Any;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::io::ErrorKind;
 use std::net::SocketAddr;
 use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::SystemTime;
 use tokio::reactor::Handle;
 use websocket::client::r#async::Framed;
 use websocket::r#async::MessageCodec;
@@ -58,6 +65,7 @@ impl ClientWrapper for ClientWrapperReal {
 
 pub trait WebSocketSupervisor: Send {
     fn send_msg(&self, msg: NodeToUiMessage);
+    fn connection_audit(&self) -> Vec<UiConnectionRecord>;
 }
 
 pub struct WebSocketSupervisorReal {
@@ -72,12 +80,23 @@ struct WebSocketSupervisorInner {
     client_id_by_socket_addr: HashMap<SocketAddr, u64>,
     socket_addr_by_client_id: HashMap<u64, SocketAddr>,
     client_by_id: HashMap<u64, Box<dyn ClientWrapper>>,
+    compatibility: UiProtocolCompatibility,
+    protocol_version_by_client_id: HashMap<u64, u32>,
+    connection_audit: ConnectionAudit,
 }
 
 impl WebSocketSupervisor for WebSocketSupervisorReal {
     fn send_msg(&self, msg: NodeToUiMessage) {
         Self::send_msg(&self.inner, msg);
     }
+
+    fn connection_audit(&self) -> Vec<UiConnectionRecord> {
+        self.inner
+            .lock()
+            .expect("WebSocketSupervisor is poisoned")
+            .connection_audit
+            .records()
+    }
 }
 
 impl WebSocketSupervisorReal {
@@ -92,6 +111,9 @@ impl WebSocketSupervisorReal {
             client_id_by_socket_addr: HashMap::new(),
             socket_addr_by_client_id: HashMap::new(),
             client_by_id: HashMap::new(),
+            compatibility: UiProtocolCompatibility::default(),
+            protocol_version_by_client_id: HashMap::new(),
+            connection_audit: ConnectionAudit::default(),
         }));
         let logger = Logger::new("WebSocketSupervisor");
         let logger_1 = logger.clone();
@@ -137,6 +159,8 @@ impl WebSocketSupervisorReal {
 
     fn send_msg(inner_arc: &Arc<Mutex<WebSocketSupervisorInner>>, msg: NodeToUiMessage) {
         let mut locked_inner = inner_arc.lock().expect("WebSocketSupervisor is poisoned");
+        let json_by_version = Self::marshal_for_connected_versions(&locked_inner, msg.body);
+        let version_by_client_id = locked_inner.protocol_version_by_client_id.clone();
         let clients = match msg.target {
             MessageTarget::ClientId(n) => {
                 let clients = Self::filter_clients(&mut locked_inner, |(id, _)| **id == n);
@@ -152,13 +176,44 @@ impl WebSocketSupervisorReal {
             }
             MessageTarget::AllClients => Self::filter_clients(&mut locked_inner, |_| true),
         };
-        let json = UiTrafficConverter::new_marshal(msg.body);
-        if let Some(errors) = Self::send_to_clients(clients, json) {
+        let json_for_client = |client_id: u64| {
+            let version = version_by_client_id
+                .get(&client_id)
+                .copied()
+                .unwrap_or(NODE_UI_PROTOCOL_VERSION);
+            json_by_version
+                .get(&version)
+                .expectv("marshalled message")
+                .clone()
+        };
+        if let Some(errors) = Self::send_to_clients(clients, json_for_client) {
             drop(locked_inner);
             Self::handle_sink_errs(errors, inner_arc)
         }
     }
 
+    // Each message is marshalled once per protocol version that connected UIs speak, rather than
+    // once per client.
+    fn marshal_for_connected_versions(
+        locked_inner: &MutexGuard<WebSocketSupervisorInner>,
+        body: MessageBody,
+    ) -> HashMap<u32, String> {
+        locked_inner
+            .protocol_version_by_client_id
+            .values()
+            .copied()
+            .chain(std::iter::once(NODE_UI_PROTOCOL_VERSION))
+            .collect::<HashSet<u32>>()
+            .into_iter()
+            .map(|version| {
+                let versioned_body = locked_inner
+                    .compatibility
+                    .downgrade_to_ui(version, body.clone());
+                (version, UiTrafficConverter::new_marshal(versioned_body))
+            })
+            .collect()
+    }
+
     fn handle_sink_errs(
         errors: Vec<SendToClientWebsocketError>,
         inner_arc: &Arc<Mutex<WebSocketSupervisorInner>>,
@@ -200,31 +255,62 @@ impl WebSocketSupervisorReal {
         inner: Arc<Mutex<WebSocketSupervisorInner>>,
         logger: &Logger,
     ) {
-        if upgrade
-            .protocols()
-            .contains(&String::from(NODE_UI_PROTOCOL))
-        {
-            Self::accept_upgrade_request(upgrade, socket_addr, inner, logger);
-        } else {
-            Self::reject_upgrade_request(upgrade, logger);
+        let version_opt = inner
+            .lock()
+            .expect("WebSocketSupervisor is poisoned")
+            .compatibility
+            .negotiate(upgrade.protocols());
+        match version_opt {
+            Some(version) => {
+                Self::accept_upgrade_request(upgrade, socket_addr, version, inner, logger)
+            }
+            None => Self::reject_upgrade_request(upgrade, logger),
         }
     }
 
     fn accept_upgrade_request(
         upgrade: WsUpgrade<TcpStream, BytesMut>,
         socket_addr: SocketAddr,
+        version: u32,
         inner: Arc<Mutex<WebSocketSupervisorInner>>,
         logger: &Logger,
     ) {
         let logger_clone = logger.clone();
-        info!(logger_clone, "UI connected at {}", socket_addr);
-        let upgrade_future =
-            upgrade
-                .use_protocol(NODE_UI_PROTOCOL)
-                .accept()
-                .map(move |(client, _)| {
-                    Self::handle_connection(client, &inner, &logger_clone, socket_addr);
-                });
+        let protocol = node_ui_protocol_name(version);
+        let user_agent_opt = Self::user_agent_opt(&upgrade);
+        let locale_opt = Self::locale_opt(&upgrade);
+        info!(
+            logger_clone,
+            "UI connected at {} using {}", socket_addr, protocol
+        );
+        if inner
+            .lock()
+            .expect("WebSocketSupervisor is poisoned")
+            .compatibility
+            .is_deprecated(version)
+        {
+            warning!(
+                logger_clone,
+                "UI at {} uses deprecated protocol {}, which will stop being supported; it should move to {}",
+                socket_addr,
+                protocol,
+                NODE_UI_PROTOCOL
+            );
+        }
+        let upgrade_future = upgrade
+            .use_protocol(protocol)
+            .accept()
+            .map(move |(client, _)| {
+                Self::handle_connection(
+                    client,
+                    &inner,
+                    &logger_clone,
+                    socket_addr,
+                    version,
+                    user_agent_opt,
+                    locale_opt,
+                );
+            });
         tokio::spawn(upgrade_future.then(|result| {
             match result {
                 Ok(_) => ok::<(), ()>(()),
@@ -233,6 +319,25 @@ impl WebSocketSupervisorReal {
         }));
     }
 
+    fn user_agent_opt(upgrade: &WsUpgrade<TcpStream, BytesMut>) -> Option<String> {
+        Self::header_opt(upgrade, "User-Agent")
+    }
+
+    // The language the UI wants user-facing text in, if it said
+    fn locale_opt(upgrade: &WsUpgrade<TcpStream, BytesMut>) -> Option<String> {
+        Self::header_opt(upgrade, "Accept-Language")
+            .and_then(|header| locale_from_accept_language(&header))
+    }
+
+    fn header_opt(upgrade: &WsUpgrade<TcpStream, BytesMut>, name: &str) -> Option<String> {
+        upgrade
+            .request
+            .headers
+            .get_raw(name)
+            .and_then(|values| values.first())
+            .map(|value| String::from_utf8_lossy(value).to_string())
+    }
+
     fn reject_upgrade_request(upgrade: WsUpgrade<TcpStream, BytesMut>, logger: &Logger) {
         info!(
             logger,
@@ -248,10 +353,14 @@ impl WebSocketSupervisorReal {
         inner: &Arc<Mutex<WebSocketSupervisorInner>>,
         logger: &Logger,
         socket_addr: SocketAddr,
+        version: u32,
+        user_agent_opt: Option<String>,
+        locale_opt: Option<String>,
     ) {
         let logger_1 = logger.clone();
         let logger_2 = logger.clone();
         let inner_1 = inner.clone();
+        let inner_2 = inner.clone();
         let (outgoing, incoming) = client.split();
         // "Going synchronous" here to avoid calling .send() on an async Sink, which consumes it
         let sync_outgoing: Wait<SplitSink<_>> = outgoing.wait();
@@ -268,8 +377,21 @@ impl WebSocketSupervisorReal {
             .socket_addr_by_client_id
             .insert(client_id, socket_addr);
         locked_inner.client_by_id.insert(client_id, client_wrapper);
+        locked_inner
+            .protocol_version_by_client_id
+            .insert(client_id, version);
+        locked_inner.connection_audit.connected(
+            client_id,
+            socket_addr,
+            user_agent_opt,
+            locale_opt,
+            version,
+            SystemTime::now(),
+        );
         let incoming_future = incoming
-            .then(move |result| Self::handle_websocket_errors(result, &logger_2, socket_addr))
+            .then(move |result| {
+                Self::handle_websocket_errors(result, &inner_2, &logger_2, socket_addr)
+            })
             .map(move |owned_message| match owned_message {
                 OwnedMessage::Text(message) => {
                     Self::handle_text_message(&inner_1, &logger_1, socket_addr, &message)
@@ -303,7 +425,7 @@ impl WebSocketSupervisorReal {
         socket_addr: SocketAddr,
         message: &str,
     ) -> FutureResult<(), ()> {
-        let locked_inner = inner_arc.lock().expect("WebSocketSupervisor is poisoned");
+        let mut locked_inner = inner_arc.lock().expect("WebSocketSupervisor is poisoned");
         let client_id = match locked_inner.client_id_by_socket_addr.get(&socket_addr) {
             Some(client_id_ref) => *client_id_ref,
             None => {
@@ -316,6 +438,20 @@ impl WebSocketSupervisorReal {
         };
         match UiTrafficConverter::new_unmarshal_from_ui(message, client_id) {
             Ok(from_ui_message) => {
+                locked_inner
+                    .connection_audit
+                    .request(client_id, &from_ui_message.body.opcode);
+                let version = locked_inner
+                    .protocol_version_by_client_id
+                    .get(&client_id)
+                    .copied()
+                    .unwrap_or(NODE_UI_PROTOCOL_VERSION);
+                let from_ui_message = NodeFromUiMessage {
+                    client_id,
+                    body: locked_inner
+                        .compatibility
+                        .upgrade_from_ui(version, from_ui_message.body),
+                };
                 locked_inner
                     .from_ui_message_sub
                     .try_send(from_ui_message)
@@ -408,6 +544,9 @@ impl WebSocketSupervisorReal {
             client_id,
             locked_inner.port
         );
+        locked_inner
+            .connection_audit
+            .disconnected(client_id, "closed by UI", SystemTime::now());
         Self::close_connection(&mut locked_inner, client_id, socket_addr, logger);
 
         err::<(), ()>(()) // end the stream
@@ -425,21 +564,24 @@ impl WebSocketSupervisorReal {
         ok::<(), ()>(())
     }
 
-    fn send_to_clients(
+    fn send_to_clients<F>(
         clients: Vec<(u64, &mut dyn ClientWrapper)>,
-        json: String,
-    ) -> Option<Vec<SendToClientWebsocketError>> {
+        json_for_client: F,
+    ) -> Option<Vec<SendToClientWebsocketError>>
+    where
+        F: Fn(u64) -> String,
+    {
         let errors: Vec<SendToClientWebsocketError> = clients
             .into_iter()
-            .flat_map(
-                |(client_id, client)| match client.send(OwnedMessage::Text(json.clone())) {
+            .flat_map(|(client_id, client)| {
+                match client.send(OwnedMessage::Text(json_for_client(client_id))) {
                     Ok(_) => match client.flush() {
                         Ok(_) => None,
                         Err(e) => Some(SendToClientWebsocketError::FlushError((client_id, e))),
                     },
                     Err(e) => Some(SendToClientWebsocketError::SendError((client_id, e))),
-                },
-            )
+                }
+            })
             .collect();
         if errors.is_empty() {
             None
@@ -457,7 +599,11 @@ impl WebSocketSupervisorReal {
             WebSocketError::IoError(e)
                 if e.kind() == ErrorKind::BrokenPipe || e.kind() == ErrorKind::ConnectionReset =>
             {
-                Self::emergency_client_removal(client_id, inner_arc);
+                Self::emergency_client_removal(
+                    client_id,
+                    inner_arc,
+                    &format!("fatal flush error: {:?}", e.kind()),
+                );
                 warning!(
                     Logger::new("WebSocketSupervisor"),
                     "Client {} hit a fatal flush error: {:?}, dropping the client",
@@ -479,19 +625,29 @@ impl WebSocketSupervisorReal {
         inner_arc: &Arc<Mutex<WebSocketSupervisorInner>>,
         client_id: u64,
     ) {
-        Self::emergency_client_removal(client_id, inner_arc);
+        Self::emergency_client_removal(client_id, inner_arc, &format!("send error: {:?}", error));
         error!(
             Logger::new("WebSocketSupervisor"),
             "Error sending to client {}: {:?}, dropping the client", client_id, error
         );
     }
 
-    fn emergency_client_removal(client_id: u64, inner_arc: &Arc<Mutex<WebSocketSupervisorInner>>) {
+    fn emergency_client_removal(
+        client_id: u64,
+        inner_arc: &Arc<Mutex<WebSocketSupervisorInner>>,
+        reason: &str,
+    ) {
         let mut locked_inner = inner_arc.lock().expect("WebSocketSupervisor is poisoned");
+        locked_inner
+            .connection_audit
+            .disconnected(client_id, reason, SystemTime::now());
         locked_inner
             .client_by_id
             .remove(&client_id)
             .expectv("client");
+        let _ = locked_inner
+            .protocol_version_by_client_id
+            .remove(&client_id);
         let socket_addr = locked_inner
             .socket_addr_by_client_id
             .remove(&client_id)
@@ -504,6 +660,7 @@ impl WebSocketSupervisorReal {
 
     fn handle_websocket_errors<I>(
         result: Result<I, WebSocketError>,
+        inner_arc: &Arc<Mutex<WebSocketSupervisorInner>>,
         logger: &Logger,
         socket_addr: SocketAddr,
     ) -> FutureResult<I, ()> {
@@ -515,6 +672,15 @@ impl WebSocketSupervisorReal {
                     socket_addr,
                     e
                 );
+                let mut locked_inner = inner_arc.lock().expect("WebSocketSupervisor is poisoned");
+                if let Some(client_id) = locked_inner.client_id_by_socket_addr.get(&socket_addr) {
+                    let client_id = *client_id;
+                    locked_inner.connection_audit.disconnected(
+                        client_id,
+                        &format!("protocol violation: {:?}", e),
+                        SystemTime::now(),
+                    );
+                }
                 err::<I, ()>(())
             }
             Ok(msg) => ok::<I, ()>(msg),
@@ -528,6 +694,9 @@ impl WebSocketSupervisorReal {
         logger: &Logger,
     ) {
         let _ = locked_inner.socket_addr_by_client_id.remove(&client_id);
+        let _ = locked_inner
+            .protocol_version_by_client_id
+            .remove(&client_id);
         let mut client = match locked_inner.client_by_id.remove(&client_id) {
             Some(client) => client,
             None => panic!("WebSocketSupervisor got a disconnect from a client that has disappeared from the stable!"),
@@ -591,14 +760,15 @@ mod tests {
     use super::*;
     use crate::test_utils::recorder::{make_recorder, Recorder};
     use crate::test_utils::{assert_contains, await_value, wait_for};
+    use crate::ui_gateway::protocol_compatibility::tests::OldBoogaProtocol;
     use actix::System;
     use actix::{Actor, Addr};
     use crossbeam_channel::bounded;
     use futures::lazy;
     use masq_lib::constants::UNMARSHAL_ERROR;
     use masq_lib::messages::{
-        FromMessageBody, UiDescriptorResponse, UiShutdownRequest, UiStartOrder, UiUnmarshalError,
-        NODE_UI_PROTOCOL,
+        FromMessageBody, UiDescriptorResponse, UiRequestCount, UiShutdownRequest, UiStartOrder,
+        UiUnmarshalError, NODE_UI_PROTOCOL,
     };
     use masq_lib::test_utils::logging::init_test_logging;
     use masq_lib::test_utils::logging::TestLogHandler;
@@ -615,6 +785,7 @@ mod tests {
     use std::time::Duration;
     use tokio::runtime::Runtime;
     use websocket::client::sync::Client;
+    use websocket::header::Headers;
     use websocket::r#async::TcpStream as TcpStreamAsync;
     use websocket::stream::sync::TcpStream;
     use websocket::ClientBuilder;
@@ -794,6 +965,8 @@ mod tests {
             socket_addr: SocketAddr,
             logger: Logger,
         ) -> impl Future<Item = (), Error = WebSocketError> {
+            let user_agent_opt = WebSocketSupervisorReal::user_agent_opt(&upgradable);
+            let locale_opt = WebSocketSupervisorReal::locale_opt(&upgradable);
             upgradable.accept().and_then(move |(client, _)| {
                 let logger = logger;
                 //this is the function being under assertions in this test
@@ -803,6 +976,9 @@ mod tests {
                     &inner_arc,
                     &logger,
                     socket_addr,
+                    NODE_UI_PROTOCOL_VERSION,
+                    user_agent_opt,
+                    locale_opt,
                 );
                 //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
                 Ok(())
@@ -814,8 +990,12 @@ mod tests {
         let upgrade = WebSocketSupervisorReal::remove_failures(ws_server.incoming(), &logger);
         //thread causing an initiation of a new connection to the server
         let join_handle = thread::spawn(move || {
+            let mut headers = Headers::new();
+            headers.set_raw("User-Agent", vec![b"test-ui/1.0".to_vec()]);
+            headers.set_raw("Accept-Language", vec![b"pt-BR, en;q=0.5".to_vec()]);
             ClientBuilder::new(format!("ws://127.0.0.1:{}", port).as_str())
                 .unwrap()
+                .custom_headers(&headers)
                 .connect_insecure()
         });
         let future_result = upgrade
@@ -841,6 +1021,18 @@ mod tests {
                             &0
                         );
                         assert!(inner_accessible.client_by_id.get(&0).is_some());
+                        assert_eq!(
+                            inner_accessible.protocol_version_by_client_id.get(&0),
+                            Some(&NODE_UI_PROTOCOL_VERSION)
+                        );
+                        let audit = inner_accessible.connection_audit.records();
+                        assert_eq!(audit.len(), 1);
+                        assert_eq!(audit[0].client_id, 0);
+                        assert_eq!(audit[0].address, "1.2.3.4:1234".to_string());
+                        assert_eq!(audit[0].user_agent_opt, Some("test-ui/1.0".to_string()));
+                        assert_eq!(audit[0].locale_opt, Some("pt-br".to_string()));
+                        assert_eq!(audit[0].protocol_version, NODE_UI_PROTOCOL_VERSION);
+                        assert_eq!(audit[0].disconnected_at_opt, None);
                         ok::<(), ()>(())
                     });
                 match future.wait() {
@@ -1080,6 +1272,9 @@ mod tests {
             client_id_by_socket_addr: Default::default(),
             socket_addr_by_client_id: Default::default(),
             client_by_id: Default::default(),
+            compatibility: UiProtocolCompatibility::default(),
+            protocol_version_by_client_id: Default::default(),
+            connection_audit: ConnectionAudit::default(),
         }
     }
 
@@ -1238,11 +1433,15 @@ mod tests {
             client_id_by_socket_addr,
             socket_addr_by_client_id,
             client_by_id,
+            compatibility: UiProtocolCompatibility::default(),
+            protocol_version_by_client_id: HashMap::new(),
+            connection_audit: ConnectionAudit::default(),
         }));
         let msg = NodeToUiMessage {
             target: ClientId(123),
             body: UiDescriptorResponse {
                 node_descriptor_opt: None,
+                operator_notes_token_opt: None,
             }
             .tmb(111),
         };
@@ -1340,7 +1539,60 @@ mod tests {
         let inner_clone = mail.lock().unwrap();
         assert!(inner_clone.client_by_id.is_empty());
         assert!(inner_clone.client_id_by_socket_addr.is_empty());
-        assert!(inner_clone.socket_addr_by_client_id.is_empty())
+        assert!(inner_clone.socket_addr_by_client_id.is_empty());
+        let audit = inner_clone.connection_audit.records();
+        assert_eq!(audit.len(), 1);
+        assert_eq!(
+            audit[0].requests,
+            vec![UiRequestCount {
+                opcode: "shutdown".to_string(),
+                count: 1
+            }]
+        );
+        assert_eq!(audit[0].disconnected_at_opt.is_some(), true);
+        assert_eq!(
+            audit[0].disconnect_reason_opt,
+            Some("closed by UI".to_string())
+        );
+    }
+
+    #[test]
+    fn connection_audit_records_why_a_client_was_dropped_after_a_send_failure() {
+        let mut inner = make_ordinary_inner();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::from([1, 2, 4, 5])), 4455);
+        let client = ClientWrapperMock::new().send_result(Err(WebSocketError::NoDataAvailable));
+        inner.client_by_id.insert(123, Box::new(client));
+        inner.client_id_by_socket_addr.insert(socket_addr, 123);
+        inner.socket_addr_by_client_id.insert(123, socket_addr);
+        inner.connection_audit.connected(
+            123,
+            socket_addr,
+            None,
+            None,
+            NODE_UI_PROTOCOL_VERSION,
+            SystemTime::now(),
+        );
+        let inner_arc = Arc::new(Mutex::new(inner));
+        let msg = NodeToUiMessage {
+            target: ClientId(123),
+            body: UiDescriptorResponse {
+                node_descriptor_opt: None,
+                operator_notes_token_opt: None,
+            }
+            .tmb(111),
+        };
+
+        WebSocketSupervisorReal::send_msg(&inner_arc, msg);
+
+        let subject = WebSocketSupervisorReal { inner: inner_arc };
+        let audit = subject.connection_audit();
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].client_id, 123);
+        assert_eq!(audit[0].disconnected_at_opt.is_some(), true);
+        assert_eq!(
+            audit[0].disconnect_reason_opt,
+            Some("send error: NoDataAvailable".to_string())
+        );
     }
 
     #[test]
@@ -1355,6 +1607,9 @@ mod tests {
         let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), 44444);
         inner.client_by_id.insert(1, Box::new(mock_client));
         inner.socket_addr_by_client_id.insert(1, socket_addr);
+        inner
+            .protocol_version_by_client_id
+            .insert(1, NODE_UI_PROTOCOL_VERSION);
         let inner_arc = Arc::new(Mutex::new(inner));
         let mut locked_inner = inner_arc.lock().unwrap();
 
@@ -1370,7 +1625,8 @@ mod tests {
          flush transmission to UI at 1.2.3.4:44444, client dumped anyway",
         );
         assert!(locked_inner.socket_addr_by_client_id.is_empty());
-        assert!(locked_inner.client_by_id.is_empty())
+        assert!(locked_inner.client_by_id.is_empty());
+        assert!(locked_inner.protocol_version_by_client_id.is_empty())
         //the third hashmap is supposed to be cleared a step before this fn call
     }
 
@@ -1409,6 +1665,101 @@ mod tests {
         assert_eq!(ui_gateway_recording.len(), 1);
     }
 
+    #[test]
+    fn send_msg_speaks_to_each_client_in_its_own_protocol_version() {
+        let legacy_version = NODE_UI_PROTOCOL_VERSION - 1;
+        let mut inner = make_ordinary_inner();
+        inner.compatibility = UiProtocolCompatibility::new(vec![Box::new(OldBoogaProtocol)]);
+        let legacy_send_params_arc = Arc::new(Mutex::new(vec![]));
+        let current_send_params_arc = Arc::new(Mutex::new(vec![]));
+        let legacy_client = ClientWrapperMock::new()
+            .send_params(&legacy_send_params_arc)
+            .send_result(Ok(()))
+            .flush_result(Ok(()));
+        let current_client = ClientWrapperMock::new()
+            .send_params(&current_send_params_arc)
+            .send_result(Ok(()))
+            .flush_result(Ok(()));
+        inner.client_by_id.insert(0, Box::new(legacy_client));
+        inner.client_by_id.insert(1, Box::new(current_client));
+        inner
+            .protocol_version_by_client_id
+            .insert(0, legacy_version);
+        inner
+            .protocol_version_by_client_id
+            .insert(1, NODE_UI_PROTOCOL_VERSION);
+        let inner_arc = Arc::new(Mutex::new(inner));
+        let body = MessageBody {
+            opcode: "booga".to_string(),
+            path: FireAndForget,
+            payload: Ok("{}".to_string()),
+        };
+
+        WebSocketSupervisorReal::send_msg(
+            &inner_arc,
+            NodeToUiMessage {
+                target: MessageTarget::AllClients,
+                body: body.clone(),
+            },
+        );
+
+        let opcode_sent = |send_params_arc: &Arc<Mutex<Vec<OwnedMessage>>>| match send_params_arc
+            .lock()
+            .unwrap()
+            .remove(0)
+        {
+            OwnedMessage::Text(json) => {
+                UiTrafficConverter::new_unmarshal_to_ui(&json, ClientId(0))
+                    .unwrap()
+                    .body
+                    .opcode
+            }
+            x => panic!("Expected OwnedMessage::Text, got {:?}", x),
+        };
+        assert_eq!(opcode_sent(&legacy_send_params_arc), "oldbooga".to_string());
+        assert_eq!(opcode_sent(&current_send_params_arc), "booga".to_string());
+    }
+
+    #[test]
+    fn messages_from_a_client_on_a_legacy_protocol_are_upgraded_before_forwarding() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let system = System::new(
+            "messages_from_a_client_on_a_legacy_protocol_are_upgraded_before_forwarding",
+        );
+        let socket_addr = SocketAddr::from_str("1.2.3.4:1234").unwrap();
+        let mut inner = make_ordinary_inner();
+        inner.from_ui_message_sub = subs(ui_gateway);
+        inner.compatibility = UiProtocolCompatibility::new(vec![Box::new(OldBoogaProtocol)]);
+        inner.client_id_by_socket_addr.insert(socket_addr, 7);
+        inner
+            .protocol_version_by_client_id
+            .insert(7, NODE_UI_PROTOCOL_VERSION - 1);
+        let inner_arc = Arc::new(Mutex::new(inner));
+
+        let _ = WebSocketSupervisorReal::handle_text_message(
+            &inner_arc,
+            &Logger::new("test"),
+            socket_addr,
+            r#"{"opcode": "oldbooga", "payload": {}}"#,
+        )
+        .wait();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeFromUiMessage>(0),
+            &NodeFromUiMessage {
+                client_id: 7,
+                body: MessageBody {
+                    opcode: "booga".to_string(),
+                    path: FireAndForget,
+                    payload: Ok("{}".to_string()),
+                }
+            }
+        );
+    }
+
     #[test]
     fn send_msg_with_a_client_id_sends_a_message_to_the_client() {
         let port = find_free_port();
@@ -3,6 +3,7 @@
 
 use crate::ui_gateway::websocket_supervisor::{WebSocketSupervisor, WebSocketSupervisorFactory};
 use actix::Recipient;
+use masq_lib::messages::UiConnectionRecord;
 use masq_lib::ui_gateway::{NodeFromUiMessage, NodeToUiMessage};
 use std::cell::RefCell;
 use std::sync::{Arc, Mutex};
@@ -10,18 +11,24 @@ use std::sync::{Arc, Mutex};
 #[derive(Default)]
 pub struct WebSocketSupervisorMock {
     send_msg_parameters: Arc<Mutex<Vec<NodeToUiMessage>>>,
+    connection_audit_results: RefCell<Vec<Vec<UiConnectionRecord>>>,
 }
 
 impl WebSocketSupervisor for WebSocketSupervisorMock {
     fn send_msg(&self, msg: NodeToUiMessage) {
         self.send_msg_parameters.lock().unwrap().push(msg);
     }
+
+    fn connection_audit(&self) -> Vec<UiConnectionRecord> {
+        self.connection_audit_results.borrow_mut().remove(0)
+    }
 }
 
 impl WebSocketSupervisorMock {
     pub fn new() -> WebSocketSupervisorMock {
         WebSocketSupervisorMock {
             send_msg_parameters: Arc::new(Mutex::new(vec![])),
+            connection_audit_results: RefCell::new(vec![]),
         }
     }
 
@@ -32,6 +39,11 @@ impl WebSocketSupervisorMock {
         self.send_msg_parameters = parameters.clone();
         self
     }
+
+    pub fn connection_audit_result(self, result: Vec<UiConnectionRecord>) -> Self {
+        self.connection_audit_results.borrow_mut().push(result);
+        self
+    }
 }
 
 #[derive(Default)]
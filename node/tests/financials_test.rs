@@ -67,6 +67,8 @@ fn financials_command_retrieves_payable_and_receivable_records() {
         top_records_opt: Some(TopRecordsConfig {
             count: 10,
             ordered_by: TopRecordsOrdering::Balance,
+            offset: 0,
+            ascending: false,
         }),
         custom_queries_opt: None,
     };
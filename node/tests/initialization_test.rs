@@ -294,7 +294,8 @@ fn node_creates_log_file_with_heading_integration() {
     /__/ /    /__/ /__/ /__/ /________/ /_____   / /           dns_resolve_failure::MIGRATIONS \(\d+\.\d+\)
     |__|/     |__|/|__|/|__|/|________|/|____/__/ /            gossip::MIGRATIONS \(\d+\.\d+\)
                                              |__|/             gossip_failure::MIGRATIONS \(\d+\.\d+\)
-                                                               node_record_inner::MIGRATIONS \(\d+\.\d+\)\n
+                                                               node_record_inner::MIGRATIONS \(\d+\.\d+\)
+                                                               rendezvous::MIGRATIONS \(\d+\.\d+\)\n
 \d+\-\d+\-\d+ \d+:\d+:\d+\.\d+ Thd\d+:"#,
         //The last line represents the first log with its timestamp.
     );